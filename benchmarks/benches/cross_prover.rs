@@ -0,0 +1,38 @@
+//! Run this bench using `cargo bench -p benchmarks --bench cross_prover`
+
+use benchmarks::{
+    kimchi::PoseidonChain, msm::Multiplication, o1vm::ScratchTrace, saffron::StorageWorkload,
+    Report, Workload,
+};
+use criterion::{criterion_group, criterion_main, Criterion, SamplingMode, Throughput};
+use std::time::Duration;
+
+fn bench_workload(c: &mut Criterion, workload: &dyn Workload) {
+    let mut group = c.benchmark_group("cross_prover");
+    group.sampling_mode(SamplingMode::Flat); // for slow benchmarks
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(60));
+    group.throughput(Throughput::Elements(workload.rows()));
+    group.bench_function(workload.name(), |b| b.iter(|| workload.prove()));
+    group.finish();
+
+    println!("{}", Report::measure(workload));
+}
+
+// The workloads run by increasing memory usage, so that the peak resident set
+// size reported after each of them is the one of that workload.
+pub fn bench_cross_prover(c: &mut Criterion) {
+    for domain_size in [1 << 12, 1 << 15] {
+        bench_workload(c, &Multiplication::new(domain_size));
+    }
+    for hashes in [100, 1000] {
+        bench_workload(c, &PoseidonChain::new(hashes));
+    }
+    for domain_size in [1 << 10, 1 << 12] {
+        bench_workload(c, &ScratchTrace::new(domain_size));
+    }
+    bench_workload(c, &StorageWorkload::new(1 << 20));
+}
+
+criterion_group!(benches, bench_cross_prover);
+criterion_main!(benches);
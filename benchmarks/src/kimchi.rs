@@ -0,0 +1,112 @@
+//! Kimchi workload: a chain of Poseidon permutations, where each permutation
+//! takes the output of the previous one as input
+
+use crate::{Workload, FIELD_BYTES};
+use ark_ff::Zero;
+use groupmap::GroupMap;
+use kimchi::{
+    bench::{BaseSpongeVesta, ScalarSpongeVesta},
+    circuits::{
+        gate::CircuitGate,
+        polynomials::{self, poseidon::ROUNDS_PER_ROW},
+        wires::{Wire, COLUMNS},
+    },
+    curve::KimchiCurve,
+    proof::ProverProof,
+    prover_index::{testing::new_index_for_test, ProverIndex},
+    verifier::verify,
+};
+use mina_curves::pasta::{Fp, Vesta};
+use mina_poseidon::constants::{PlonkSpongeConstantsKimchi, SpongeConstants};
+use poly_commitment::{commitment::CommitmentCurve, ipa::OpeningProof};
+use std::array;
+
+const ROWS_PER_HASH: usize = PlonkSpongeConstantsKimchi::PERM_ROUNDS_FULL / ROUNDS_PER_ROW + 1;
+
+/// A chain of Poseidon permutations proven with kimchi over Vesta
+pub struct PoseidonChain {
+    hashes: usize,
+    group_map: <Vesta as CommitmentCurve>::Map,
+    index: ProverIndex<Vesta, OpeningProof<Vesta>>,
+    witness: [Vec<Fp>; COLUMNS],
+}
+
+impl PoseidonChain {
+    /// Creates a chain of `hashes` permutations
+    pub fn new(hashes: usize) -> Self {
+        let round_constants = &*Vesta::sponge_params().round_constants;
+
+        let mut gates = Vec::with_capacity(hashes * ROWS_PER_HASH);
+        let mut row = 0;
+        for _ in 0..hashes {
+            let wires = [Wire::for_row(row), Wire::for_row(row + ROWS_PER_HASH - 1)];
+            // the returned row is the one of the output, not the next free row
+            let (poseidon, _) =
+                CircuitGate::<Fp>::create_poseidon_gadget(row, wires, round_constants);
+            gates.extend(poseidon);
+            row += ROWS_PER_HASH;
+        }
+
+        let mut witness: [Vec<Fp>; COLUMNS] =
+            array::from_fn(|_| vec![Fp::zero(); hashes * ROWS_PER_HASH]);
+        let mut input = [Fp::from(1u32), Fp::from(2u32), Fp::from(3u32)];
+        for h in 0..hashes {
+            let first_row = h * ROWS_PER_HASH;
+            polynomials::poseidon::generate_witness(
+                first_row,
+                Vesta::sponge_params(),
+                &mut witness,
+                input,
+            );
+            let last_row = first_row + ROWS_PER_HASH - 1;
+            input = array::from_fn(|i| witness[i][last_row]);
+        }
+
+        PoseidonChain {
+            hashes,
+            group_map: <Vesta as CommitmentCurve>::Map::setup(),
+            index: new_index_for_test(gates, 0),
+            witness,
+        }
+    }
+
+    fn create_proof(&self) -> ProverProof<Vesta, OpeningProof<Vesta>> {
+        ProverProof::create::<BaseSpongeVesta, ScalarSpongeVesta, _>(
+            &self.group_map,
+            self.witness.clone(),
+            &[],
+            &self.index,
+            &mut rand::rngs::OsRng,
+        )
+        .unwrap()
+    }
+}
+
+impl Workload for PoseidonChain {
+    fn name(&self) -> String {
+        format!("kimchi/poseidon-chain/{}", self.hashes)
+    }
+
+    fn rows(&self) -> u64 {
+        self.witness[0].len() as u64
+    }
+
+    fn bytes(&self) -> u64 {
+        self.rows() * COLUMNS as u64 * FIELD_BYTES
+    }
+
+    fn prove(&self) {
+        self.create_proof();
+    }
+
+    fn prove_and_verify(&self) -> bool {
+        let proof = self.create_proof();
+        verify::<Vesta, BaseSpongeVesta, ScalarSpongeVesta, OpeningProof<Vesta>>(
+            &self.group_map,
+            &self.index.verifier_index(),
+            &proof,
+            &[],
+        )
+        .is_ok()
+    }
+}
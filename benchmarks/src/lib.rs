@@ -0,0 +1,86 @@
+//! Standardized workloads to benchmark the provers of the workspace with the
+//! same tool.
+//!
+//! Each [Workload] prepares its inputs once (circuit, index, SRS and witness),
+//! and then only creates proofs, so that the throughput of the provers can be
+//! compared in rows and bytes of witness per second. The criterion harness is
+//! in `benches/cross_prover.rs`, and can be run with:
+//!
+//! ```bash
+//! cargo bench -p benchmarks
+//! ```
+//!
+//! Along with the criterion statistics, it prints a [Report] per workload with
+//! the rows per second, the bytes per second and the peak resident set size of
+//! the process.
+
+use std::{fmt, time::Instant};
+
+pub mod kimchi;
+pub mod msm;
+pub mod o1vm;
+pub mod rss;
+pub mod saffron;
+
+/// A proving workload of one of the provers of the workspace
+pub trait Workload {
+    /// Name of the workload, used as the benchmark id
+    fn name(&self) -> String;
+
+    /// Number of rows of the witness of one proof
+    fn rows(&self) -> u64;
+
+    /// Number of bytes of witness or data of one proof
+    fn bytes(&self) -> u64;
+
+    /// Creates one proof of the workload
+    fn prove(&self);
+
+    /// Creates one proof of the workload and returns whether it verifies
+    fn prove_and_verify(&self) -> bool;
+}
+
+/// The throughput and memory usage of one proof of a workload
+#[derive(Clone, Debug)]
+pub struct Report {
+    pub name: String,
+    pub rows_per_sec: f64,
+    pub bytes_per_sec: f64,
+    /// Peak resident set size of the process so far, in bytes, if available
+    pub peak_rss: Option<u64>,
+}
+
+impl Report {
+    /// Times one proof of the workload.
+    /// Note that the peak resident set size is the one of the whole process,
+    /// so it is only meaningful for the first workloads of a run, or when the
+    /// workloads run by increasing memory usage.
+    pub fn measure<W: Workload + ?Sized>(workload: &W) -> Self {
+        let start = Instant::now();
+        workload.prove();
+        let secs = start.elapsed().as_secs_f64();
+        Report {
+            name: workload.name(),
+            rows_per_sec: workload.rows() as f64 / secs,
+            bytes_per_sec: workload.bytes() as f64 / secs,
+            peak_rss: rss::peak_rss(),
+        }
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {:.0} rows/s, {:.0} bytes/s, peak RSS ",
+            self.name, self.rows_per_sec, self.bytes_per_sec
+        )?;
+        match self.peak_rss {
+            Some(bytes) => write!(f, "{} MiB", bytes >> 20),
+            None => write!(f, "unavailable"),
+        }
+    }
+}
+
+/// Number of bytes of a field element in a witness
+pub(crate) const FIELD_BYTES: u64 = 32;
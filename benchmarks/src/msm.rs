@@ -0,0 +1,106 @@
+//! kimchi-msm workload: a degree-two relation over random columns, proven with
+//! KZG over BN254
+
+use crate::{Workload, FIELD_BYTES};
+use ark_ff::UniformRand;
+use kimchi::circuits::domains::EvaluationDomains;
+use kimchi_msm::{
+    columns::Column,
+    expr::{curr_cell, E},
+    lookups::LookupTableIDs,
+    precomputed_srs::get_bn254_srs,
+    proof::{Proof, ProofInputs},
+    prover::prove,
+    verifier::verify,
+    witness::Witness,
+    BN254G1Affine, BaseSponge, Fp, OpeningProof, ScalarSponge, BN254,
+};
+use poly_commitment::kzg::PairingSRS;
+
+/// Number of columns of the relation
+const N: usize = 3;
+
+/// The relation `X_0 * X_1 - X_2` proven with kimchi-msm
+pub struct Multiplication {
+    domain: EvaluationDomains<Fp>,
+    srs: PairingSRS<BN254>,
+    constraints: Vec<E<Fp>>,
+    witness: Witness<N, Vec<Fp>>,
+}
+
+impl Multiplication {
+    /// Creates the relation over a domain of `domain_size` rows
+    pub fn new(domain_size: usize) -> Self {
+        let domain = EvaluationDomains::<Fp>::create(domain_size).unwrap();
+        let constraints = {
+            let x0 = curr_cell::<Fp>(Column::Relation(0));
+            let x1 = curr_cell::<Fp>(Column::Relation(1));
+            let x2 = curr_cell::<Fp>(Column::Relation(2));
+            vec![x0 * x1 - x2]
+        };
+
+        let mut rng = rand::thread_rng();
+        let x0s: Vec<Fp> = (0..domain_size).map(|_| Fp::rand(&mut rng)).collect();
+        let x1s: Vec<Fp> = (0..domain_size).map(|_| Fp::rand(&mut rng)).collect();
+        let x2s = x0s
+            .iter()
+            .zip(x1s.iter())
+            .map(|(x0, x1)| *x0 * x1)
+            .collect();
+
+        Multiplication {
+            domain,
+            srs: get_bn254_srs(domain),
+            constraints,
+            witness: Witness {
+                cols: Box::new([x0s, x1s, x2s]),
+            },
+        }
+    }
+
+    fn create_proof(&self) -> Proof<N, N, 0, 0, BN254G1Affine, OpeningProof, LookupTableIDs> {
+        let inputs = ProofInputs {
+            evaluations: self.witness.clone(),
+            logups: Default::default(),
+        };
+        prove::<_, OpeningProof, BaseSponge, ScalarSponge, _, N, N, 0, 0, _>(
+            self.domain,
+            &self.srs,
+            &self.constraints,
+            Box::new([]),
+            inputs,
+            &mut rand::rngs::OsRng,
+        )
+        .unwrap()
+    }
+}
+
+impl Workload for Multiplication {
+    fn name(&self) -> String {
+        format!("msm/multiplication/{}", self.domain.d1.size)
+    }
+
+    fn rows(&self) -> u64 {
+        self.domain.d1.size
+    }
+
+    fn bytes(&self) -> u64 {
+        self.rows() * N as u64 * FIELD_BYTES
+    }
+
+    fn prove(&self) {
+        self.create_proof();
+    }
+
+    fn prove_and_verify(&self) -> bool {
+        let proof = self.create_proof();
+        verify::<_, OpeningProof, BaseSponge, ScalarSponge, N, N, 0, 0, 0, _>(
+            self.domain,
+            &self.srs,
+            &self.constraints,
+            Box::new([]),
+            &proof,
+            Witness::zero_vec(self.domain.d1.size as usize),
+        )
+    }
+}
@@ -0,0 +1,137 @@
+//! o1vm workload: a trace filling all the scratch columns of the MIPS
+//! interpreter, constrained by their sum, proven with the pickles flavor
+
+use crate::{Workload, FIELD_BYTES};
+use ark_ff::{Field, UniformRand, Zero};
+use kimchi::circuits::{domains::EvaluationDomains, expr::Expr, gate::CurrOrNext};
+use kimchi_msm::columns::Column;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use o1vm::{
+    interpreters::mips::column::{SelectorLayout, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE},
+    pickles::{
+        column_env::RelationColumnType,
+        proof::{Proof, ProofInputs, WitnessColumns},
+        prover::prove,
+        verifier::verify,
+    },
+    E,
+};
+use poly_commitment::{ipa::SRS, SRS as _};
+
+type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+/// Number of columns of the trace
+const N: usize = SCRATCH_SIZE + SCRATCH_SIZE_INVERSE + 3;
+
+/// A trace of random scratch values, which is the widest witness of the o1vm
+pub struct ScratchTrace {
+    domain: EvaluationDomains<Fp>,
+    srs: SRS<Vesta>,
+    constraints: Vec<E<Fp>>,
+    witness: WitnessColumns<Vec<Fp>, Vec<Fp>>,
+}
+
+impl ScratchTrace {
+    /// Creates a trace of `domain_size` rows
+    pub fn new(domain_size: usize) -> Self {
+        let domain = EvaluationDomains::<Fp>::create(domain_size).unwrap();
+        let srs = SRS::create(domain_size);
+        srs.get_lagrange_basis(domain.d1);
+
+        let cell = |col| Expr::cell(Column::Relation(col), CurrOrNext::Curr);
+        let mut sum = Expr::zero();
+        for i in 0..SCRATCH_SIZE {
+            sum += cell(RelationColumnType::Scratch(i));
+        }
+        for i in 0..SCRATCH_SIZE_INVERSE {
+            sum += cell(RelationColumnType::ScratchInverse(i));
+        }
+        sum += cell(RelationColumnType::InstructionCounter);
+        sum += cell(RelationColumnType::Error);
+
+        let mut rng = rand::thread_rng();
+        let mut random_column =
+            || -> Vec<Fp> { (0..domain_size).map(|_| Fp::rand(&mut rng)).collect() };
+        let scratch: [Vec<Fp>; SCRATCH_SIZE] = std::array::from_fn(|_| random_column());
+        let scratch_inverse: [Vec<Fp>; SCRATCH_SIZE_INVERSE] =
+            std::array::from_fn(|_| random_column());
+        let instruction_counter = (0..domain_size as u64).map(Fp::from).collect::<Vec<_>>();
+        // the error column cancels the sum of the other columns, where the
+        // prover inverts the values of the scratch inverse columns
+        let error = (0..domain_size)
+            .map(|row| {
+                let sum: Fp = scratch.iter().map(|col| col[row]).sum::<Fp>()
+                    + scratch_inverse
+                        .iter()
+                        .map(|col| col[row].inverse().unwrap())
+                        .sum::<Fp>();
+                -(sum + instruction_counter[row])
+            })
+            .collect();
+        let selector = vec![Fp::zero(); domain_size];
+
+        ScratchTrace {
+            domain,
+            srs,
+            constraints: vec![sum],
+            witness: WitnessColumns {
+                scratch,
+                scratch_inverse,
+                lookup_state: vec![],
+                instruction_counter,
+                error,
+                selector,
+            },
+        }
+    }
+
+    fn create_proof(&self) -> Proof<Vesta> {
+        let inputs = ProofInputs {
+            evaluations: WitnessColumns {
+                scratch: self.witness.scratch.clone(),
+                scratch_inverse: self.witness.scratch_inverse.clone(),
+                lookup_state: self.witness.lookup_state.clone(),
+                instruction_counter: self.witness.instruction_counter.clone(),
+                error: self.witness.error.clone(),
+                selector: self.witness.selector.clone(),
+            },
+            selector_layout: SelectorLayout::default(),
+        };
+        prove::<Vesta, BaseSponge, ScalarSponge, _>(
+            self.domain,
+            &self.srs,
+            inputs,
+            &self.constraints,
+            &mut rand::rngs::OsRng,
+        )
+        .unwrap()
+    }
+}
+
+impl Workload for ScratchTrace {
+    fn name(&self) -> String {
+        format!("o1vm/scratch-trace/{}", self.domain.d1.size)
+    }
+
+    fn rows(&self) -> u64 {
+        self.domain.d1.size
+    }
+
+    fn bytes(&self) -> u64 {
+        self.rows() * N as u64 * FIELD_BYTES
+    }
+
+    fn prove(&self) {
+        self.create_proof();
+    }
+
+    fn prove_and_verify(&self) -> bool {
+        let proof = self.create_proof();
+        verify::<Vesta, BaseSponge, ScalarSponge>(self.domain, &self.srs, &self.constraints, &proof)
+    }
+}
@@ -0,0 +1,28 @@
+//! Peak resident set size of the process
+
+/// Returns the peak resident set size of the process in bytes, or `None` if it
+/// cannot be read on this platform
+#[cfg(unix)]
+pub fn peak_rss() -> Option<u64> {
+    let mut usage = std::mem::MaybeUninit::<libc::rusage>::uninit();
+    // SAFETY: getrusage only writes to the provided struct
+    let res = unsafe { libc::getrusage(libc::RUSAGE_SELF, usage.as_mut_ptr()) };
+    if res != 0 {
+        return None;
+    }
+    // SAFETY: the struct is initialized when getrusage succeeds
+    let max_rss = unsafe { usage.assume_init() }.ru_maxrss as u64;
+    // the peak is in bytes on macOS, and in kilobytes elsewhere
+    if cfg!(target_os = "macos") {
+        Some(max_rss)
+    } else {
+        Some(max_rss * 1024)
+    }
+}
+
+/// Returns the peak resident set size of the process in bytes, or `None` if it
+/// cannot be read on this platform
+#[cfg(not(unix))]
+pub fn peak_rss() -> Option<u64> {
+    None
+}
@@ -0,0 +1,87 @@
+//! saffron workload: a storage proof of a blob of random bytes
+
+use crate::Workload;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use groupmap::GroupMap;
+use kimchi::curve::KimchiCurve;
+use mina_poseidon::FqSponge;
+use poly_commitment::{commitment::CommitmentCurve, ipa::SRS, SRS as _};
+use rand::{rngs::OsRng, Rng};
+use saffron::{
+    blob::FieldBlob,
+    commitment::combine_commitments,
+    storage_proof::{prove, verify_wrt_combined_data_commitment, StorageProof},
+    Curve, CurveSponge, ScalarField,
+};
+
+/// A storage proof of a blob, over the SRS of saffron
+pub struct StorageWorkload {
+    size: usize,
+    srs: SRS<Curve>,
+    group_map: <Curve as CommitmentCurve>::Map,
+    blob: FieldBlob,
+    combined_commitment: Curve,
+    challenge: ScalarField,
+}
+
+impl StorageWorkload {
+    /// Creates a blob of `size` random bytes
+    pub fn new(size: usize) -> Self {
+        let srs = poly_commitment::precomputed_srs::get_srs_test();
+        let domain = Radix2EvaluationDomain::new(srs.size()).unwrap();
+        let mut rng = OsRng;
+        let bytes: Vec<u8> = (0..size).map(|_| rng.gen()).collect();
+        let blob = FieldBlob::from_bytes::<_>(&srs, domain, &bytes);
+
+        let mut sponge = CurveSponge::new(Curve::other_curve_sponge_params());
+        let (combined_commitment, challenge) = combine_commitments(&mut sponge, &blob.commitments);
+
+        StorageWorkload {
+            size,
+            srs,
+            group_map: <Curve as CommitmentCurve>::Map::setup(),
+            blob,
+            combined_commitment,
+            challenge,
+        }
+    }
+
+    fn create_proof(&self) -> StorageProof {
+        prove(
+            &self.srs,
+            &self.group_map,
+            self.blob.clone(),
+            self.challenge,
+            &mut OsRng,
+        )
+    }
+}
+
+impl Workload for StorageWorkload {
+    fn name(&self) -> String {
+        format!("saffron/storage-proof/{}", self.size)
+    }
+
+    fn rows(&self) -> u64 {
+        self.blob.data.len() as u64
+    }
+
+    fn bytes(&self) -> u64 {
+        self.size as u64
+    }
+
+    fn prove(&self) {
+        self.create_proof();
+    }
+
+    fn prove_and_verify(&self) -> bool {
+        let proof = self.create_proof();
+        verify_wrt_combined_data_commitment(
+            &self.srs,
+            &self.group_map,
+            self.combined_commitment,
+            &proof,
+            &mut OsRng,
+        )
+    }
+}
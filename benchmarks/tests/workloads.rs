@@ -0,0 +1,55 @@
+use benchmarks::{
+    kimchi::PoseidonChain, msm::Multiplication, o1vm::ScratchTrace, rss::peak_rss,
+    saffron::StorageWorkload, Report, Workload,
+};
+
+fn check_workload(workload: &dyn Workload) {
+    assert!(
+        workload.prove_and_verify(),
+        "{} must verify",
+        workload.name()
+    );
+
+    let report = Report::measure(workload);
+    assert_eq!(report.name, workload.name());
+    assert!(report.rows_per_sec > 0.0 && report.bytes_per_sec > 0.0);
+}
+
+#[test]
+fn test_kimchi_workload() {
+    let workload = PoseidonChain::new(4);
+    assert_eq!(workload.rows(), 4 * 12);
+    check_workload(&workload);
+}
+
+#[test]
+fn test_msm_workload() {
+    check_workload(&Multiplication::new(1 << 8));
+}
+
+#[test]
+fn test_o1vm_workload() {
+    // The combination of the constraints of the trace recurses deeply
+    std::thread::Builder::new()
+        .stack_size(32 << 20)
+        .spawn(|| check_workload(&ScratchTrace::new(1 << 6)))
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn test_saffron_workload() {
+    let workload = StorageWorkload::new(1000);
+    assert_eq!(workload.bytes(), 1000);
+    check_workload(&workload);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_peak_rss() {
+    let before = peak_rss().unwrap();
+    let buffer = vec![1u8; 64 << 20];
+    assert!(peak_rss().unwrap() >= before);
+    assert!(peak_rss().unwrap() >= buffer.len() as u64);
+}
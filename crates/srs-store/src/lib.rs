@@ -0,0 +1,150 @@
+//! Management of the structured reference strings (SRS) used by the provers.
+//!
+//! Generating an SRS is expensive, so the provers load it from disk whenever
+//! possible. This crate gathers the ways to obtain one:
+//! - [load] and [save] read and write an SRS file. By convention, a file whose
+//!   name starts with `test_` holds a [TestSRS], whose points are not checked
+//!   when deserialized, which is much faster;
+//! - [verify] checks the integrity of an SRS by deriving again some of its
+//!   generators;
+//! - [trim] extracts the sub-SRS of a given size from a larger SRS;
+//! - [SrsStore] is an on-disk cache of SRS, returning an SRS of the requested
+//!   size from the precomputed SRS of this repository or from the cache, and
+//!   generating and caching it otherwise.
+
+use poly_commitment::{
+    commitment::CommitmentCurve, hash_map_cache::HashMapCache, ipa::SRS, precomputed_srs::TestSRS,
+    PolyComm,
+};
+use rand::Rng;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+mod store;
+
+pub use store::SrsStore;
+
+/// The number of random generators checked by [verify], in addition to the
+/// first and last ones
+pub const VERIFIED_GENERATORS: usize = 16;
+
+#[derive(Debug, Error)]
+pub enum SrsError {
+    #[error("cannot access the SRS file {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("cannot decode the SRS file {}: {source}", path.display())]
+    Decode {
+        path: PathBuf,
+        source: rmp_serde::decode::Error,
+    },
+    #[error("cannot encode the SRS: {0}")]
+    Encode(#[from] rmp_serde::encode::Error),
+    #[error("the SRS does not contain the expected generators")]
+    Integrity,
+}
+
+/// Whether the SRS file at `path` holds a [TestSRS]
+fn is_test_srs(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with("test_"))
+}
+
+/// Read the SRS stored at `path`
+pub fn load<G: CommitmentCurve>(path: &Path) -> Result<SRS<G>, SrsError> {
+    let file = File::open(path).map_err(|source| SrsError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let reader = BufReader::new(file);
+    let decode_error = |source| SrsError::Decode {
+        path: path.to_path_buf(),
+        source,
+    };
+    if is_test_srs(path) {
+        let test_srs: TestSRS<G> = rmp_serde::from_read(reader).map_err(decode_error)?;
+        Ok(test_srs.into())
+    } else {
+        rmp_serde::from_read(reader).map_err(decode_error)
+    }
+}
+
+/// Write the SRS at `path`, with the lagrange bases it has computed if it is a
+/// [TestSRS].
+/// The SRS is first written to a temporary file, which is then moved to
+/// `path`, so that concurrent readers never see a partially written SRS.
+pub fn save<G: CommitmentCurve>(path: &Path, srs: &SRS<G>) -> Result<(), SrsError> {
+    let io_error = |source| SrsError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let bytes = if is_test_srs(path) {
+        rmp_serde::to_vec(&TestSRS::from(srs.clone()))?
+    } else {
+        rmp_serde::to_vec(srs)?
+    };
+    let tmp_path = path.with_extension(format!("tmp{}", rand::thread_rng().gen::<u64>()));
+    std::fs::write(&tmp_path, bytes).map_err(io_error)?;
+    std::fs::rename(&tmp_path, path).map_err(io_error)
+}
+
+/// Check that the first and last generators of the SRS, a few random ones and
+/// its blinding generator are the ones derived when creating an SRS.
+/// It detects corrupted or tampered files, without the cost of generating the
+/// SRS again.
+pub fn verify<G: CommitmentCurve>(srs: &SRS<G>) -> Result<(), SrsError> {
+    let mut indices = vec![];
+    if !srs.g.is_empty() {
+        let mut rng = rand::thread_rng();
+        indices.push(0);
+        indices.push(srs.g.len() - 1);
+        indices.extend((0..VERIFIED_GENERATORS).map(|_| rng.gen_range(0..srs.g.len())));
+    }
+    if srs.verify_generators(&indices) {
+        Ok(())
+    } else {
+        Err(SrsError::Integrity)
+    }
+}
+
+/// Extract the SRS of `size` generators from a larger SRS. The lagrange bases
+/// of the domains of size at most `size` are kept, as they only depend on the
+/// first generators.
+///
+/// # Panics
+///
+/// Will panic if the SRS has less than `size` generators.
+pub fn trim<G: Clone>(srs: SRS<G>, size: usize) -> SRS<G> {
+    assert!(
+        size <= srs.g.len(),
+        "cannot extract an SRS of size {size} from an SRS of size {}",
+        srs.g.len()
+    );
+    let SRS {
+        mut g,
+        h,
+        lagrange_bases,
+        backend,
+    } = srs;
+    let lagrange_bases: HashMap<usize, Vec<PolyComm<G>>> = lagrange_bases.into();
+    g.truncate(size);
+    SRS {
+        g,
+        h,
+        lagrange_bases: HashMapCache::new_from_hashmap(
+            lagrange_bases
+                .into_iter()
+                .filter(|(domain_size, _)| *domain_size <= size)
+                .collect(),
+        ),
+        backend,
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{load, save, trim, verify, SrsError};
+use mina_curves::named::NamedCurve;
+use poly_commitment::{
+    commitment::CommitmentCurve,
+    ipa::SRS,
+    precomputed_srs::{get_srs_path, StoredSRSType, SERIALIZED_SRS_SIZE},
+    SRS as _,
+};
+use std::path::{Path, PathBuf};
+
+/// The environment variable setting the directory of [SrsStore::from_env]
+pub const SRS_CACHE_DIR: &str = "SRS_CACHE_DIR";
+
+/// An on-disk cache of SRS.
+///
+/// An SRS of a given size is obtained, by order of preference:
+/// 1. by trimming the precomputed test SRS of this repository, if it is large
+///    enough;
+/// 2. by trimming the smallest SRS of the cache that is large enough. A cached
+///    SRS that cannot be read or fails the integrity check of [verify] is
+///    removed;
+/// 3. by generating it, in which case it is added to the cache.
+pub struct SrsStore {
+    dir: PathBuf,
+}
+
+impl SrsStore {
+    /// A store caching the SRS in `dir`, created if needed
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// A store caching the SRS in the directory given by the environment
+    /// variable `SRS_CACHE_DIR`, or in a directory of the temporary directory
+    /// of the system otherwise
+    pub fn from_env() -> Self {
+        match std::env::var_os(SRS_CACHE_DIR) {
+            Some(dir) => Self::new(dir),
+            None => Self::new(std::env::temp_dir().join("proof-systems-srs")),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The path of the cached SRS of `size` generators. The SRS are cached as
+    /// test SRS, which are faster to read, as their integrity is checked with
+    /// [verify] instead.
+    fn path<G: NamedCurve>(&self, size: usize) -> PathBuf {
+        self.dir.join(format!("test_{}_{size}.srs", G::NAME))
+    }
+
+    /// The sizes of the SRS of the cache
+    fn cached_sizes<G: NamedCurve>(&self) -> Vec<usize> {
+        let prefix = format!("test_{}_", G::NAME);
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return vec![];
+        };
+        entries
+            .filter_map(|entry| {
+                let name = entry.ok()?.file_name().into_string().ok()?;
+                name.strip_prefix(&prefix)?
+                    .strip_suffix(".srs")?
+                    .parse()
+                    .ok()
+            })
+            .collect()
+    }
+
+    /// Return the SRS of `size` generators
+    pub fn get<G>(&self, size: usize) -> Result<SRS<G>, SrsError>
+    where
+        G: NamedCurve + CommitmentCurve,
+    {
+        let precomputed_path = get_srs_path::<G>(StoredSRSType::Test);
+        if size <= 1 << SERIALIZED_SRS_SIZE && precomputed_path.exists() {
+            let srs = load(&precomputed_path)?;
+            if srs.g.len() >= size {
+                return Ok(trim(srs, size));
+            }
+        }
+
+        let mut cached_sizes = self.cached_sizes::<G>();
+        cached_sizes.sort_unstable();
+        for cached_size in cached_sizes.into_iter().filter(|s| *s >= size) {
+            let path = self.path::<G>(cached_size);
+            if let Ok(srs) = load(&path).and_then(|srs| verify(&srs).map(|_| srs)) {
+                return Ok(trim(srs, size));
+            }
+            // the error is ignored, as another process may have removed the
+            // corrupted SRS already
+            let _ = std::fs::remove_file(&path);
+        }
+
+        let srs = SRS::<G>::create(size);
+        self.insert(&srs)?;
+        Ok(srs)
+    }
+
+    /// Add the SRS to the cache, with the lagrange bases it has computed
+    pub fn insert<G>(&self, srs: &SRS<G>) -> Result<(), SrsError>
+    where
+        G: NamedCurve + CommitmentCurve,
+    {
+        std::fs::create_dir_all(&self.dir).map_err(|source| SrsError::Io {
+            path: self.dir.clone(),
+            source,
+        })?;
+        save(&self.path::<G>(srs.size()), srs)
+    }
+}
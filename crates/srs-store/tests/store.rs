@@ -0,0 +1,98 @@
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use mina_curves::pasta::{Fp, Vesta};
+use poly_commitment::{
+    ipa::SRS,
+    precomputed_srs::{get_srs_test, SERIALIZED_SRS_SIZE},
+    SRS as _,
+};
+use rand::Rng;
+use srs_store::{load, save, trim, verify, SrsError, SrsStore};
+use std::path::PathBuf;
+
+fn tmp_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "srs-store-test-{}",
+        rand::thread_rng().gen::<u64>()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_trim() {
+    let srs = SRS::<Vesta>::create(16);
+    srs.get_lagrange_basis(Radix2EvaluationDomain::<Fp>::new(4).unwrap());
+    srs.get_lagrange_basis(Radix2EvaluationDomain::<Fp>::new(16).unwrap());
+
+    let trimmed = trim(srs.clone(), 8);
+    assert_eq!(trimmed, SRS::<Vesta>::create(8));
+    assert!(trimmed.lagrange_bases.contains_key(&4));
+    assert!(!trimmed.lagrange_bases.contains_key(&16));
+    assert_eq!(
+        trimmed.get_lagrange_basis_from_domain_size(4),
+        srs.get_lagrange_basis_from_domain_size(4)
+    );
+}
+
+#[test]
+fn test_save_and_load() {
+    let dir = tmp_dir();
+    let srs = SRS::<Vesta>::create(8);
+    srs.get_lagrange_basis(Radix2EvaluationDomain::<Fp>::new(8).unwrap());
+
+    for name in ["vesta.srs", "test_vesta.srs"] {
+        let path = dir.join(name);
+        save(&path, &srs).unwrap();
+        let loaded: SRS<Vesta> = load(&path).unwrap();
+        assert_eq!(loaded, srs);
+        // only test SRS keep their lagrange bases
+        assert_eq!(
+            loaded.lagrange_bases.contains_key(&8),
+            name == "test_vesta.srs"
+        );
+    }
+
+    let missing = load::<Vesta>(&dir.join("missing.srs"));
+    assert!(matches!(missing, Err(SrsError::Io { .. })));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_verify() {
+    let mut srs = SRS::<Vesta>::create(32);
+    assert!(verify(&srs).is_ok());
+    assert!(verify(&get_srs_test::<Vesta>()).is_ok());
+
+    srs.g.swap(0, 1);
+    assert!(matches!(verify(&srs), Err(SrsError::Integrity)));
+}
+
+#[test]
+fn test_store() {
+    let dir = tmp_dir();
+    let store = SrsStore::new(&dir);
+
+    // small SRS come from the precomputed one, without being cached
+    let srs = store.get::<Vesta>(1 << 4).unwrap();
+    assert_eq!(srs, SRS::<Vesta>::create(1 << 4));
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+    // larger SRS are generated and cached
+    let size = (1 << SERIALIZED_SRS_SIZE) + 2;
+    let srs = store.get::<Vesta>(size).unwrap();
+    assert_eq!(srs.size(), size);
+    let cached = dir.join(format!("test_vesta_{size}.srs"));
+    assert!(cached.exists());
+
+    // and extracted from the cache when possible
+    let smaller = store.get::<Vesta>(size - 1).unwrap();
+    assert_eq!(smaller, trim(srs.clone(), size - 1));
+
+    // a corrupted SRS of the cache is generated again
+    std::fs::write(&cached, b"corrupted").unwrap();
+    assert_eq!(store.get::<Vesta>(size - 1).unwrap(), smaller);
+    assert!(dir.join(format!("test_vesta_{}.srs", size - 1)).exists());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
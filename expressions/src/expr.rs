@@ -0,0 +1,112 @@
+//! [Expression] for the expressions of kimchi, which are also the ones of
+//! kimchi-msm and o1vm.
+//!
+//! Cached sub-expressions are rebuilt as the expressions they cache. The
+//! special constants (MDS matrix and endomorphism coefficient), the feature
+//! flags and the polynomials that depend on the evaluation domain are specific
+//! to kimchi and cannot be rebuilt in other expression types.
+
+use crate::{Expression, ExpressionError, Variable};
+use ark_ff::Field;
+use kimchi::circuits::expr::{
+    AlphaChallengeTerm, ConstantExpr, ConstantExprInner, ConstantTerm, Expr, ExprInner, Operations,
+};
+
+impl<F, Column, ChallengeTerm> Expression for Expr<ConstantExpr<F, ChallengeTerm>, Column>
+where
+    F: Field,
+    Column: Copy + PartialEq,
+    ChallengeTerm: for<'a> AlphaChallengeTerm<'a>,
+{
+    type F = F;
+    type Column = Column;
+    type Challenge = ChallengeTerm;
+
+    fn from_constant(c: F) -> Self {
+        Expr::constant(ConstantTerm::Literal(c).into())
+    }
+
+    fn from_variable(var: Variable<Column>) -> Self {
+        Expr::cell(var.col, var.row)
+    }
+
+    fn from_challenge(chal: ChallengeTerm) -> Self {
+        Expr::constant(chal.into())
+    }
+
+    fn double(self) -> Self {
+        Expr::double(self)
+    }
+
+    fn square(self) -> Self {
+        Expr::square(self)
+    }
+
+    fn pow(self, p: u64) -> Self {
+        Operations::pow(self, p)
+    }
+
+    fn build<E>(&self) -> Result<E, ExpressionError>
+    where
+        E: Expression<F = F>,
+        E::Column: From<Column>,
+        E::Challenge: From<ChallengeTerm>,
+    {
+        match self {
+            Operations::Atom(inner) => match inner {
+                ExprInner::Constant(c) => build_constant(c),
+                ExprInner::Cell(var) => Ok(E::from_variable(Variable {
+                    col: var.col.into(),
+                    row: var.row,
+                })),
+                ExprInner::VanishesOnZeroKnowledgeAndPreviousRows => Err(
+                    ExpressionError::Unsupported("VanishesOnZeroKnowledgeAndPreviousRows"),
+                ),
+                ExprInner::UnnormalizedLagrangeBasis(_) => {
+                    Err(ExpressionError::Unsupported("UnnormalizedLagrangeBasis"))
+                }
+            },
+            Operations::Pow(x, p) => Ok(x.build::<E>()?.pow(*p)),
+            Operations::Add(x, y) => Ok(x.build::<E>()? + y.build()?),
+            Operations::Mul(x, y) => Ok(x.build::<E>()? * y.build()?),
+            Operations::Sub(x, y) => Ok(x.build::<E>()? - y.build()?),
+            Operations::Double(x) => Ok(x.build::<E>()?.double()),
+            Operations::Square(x) => Ok(x.build::<E>()?.square()),
+            Operations::Cache(_, x) => x.build(),
+            Operations::IfFeature(_, _, _) => Err(ExpressionError::Unsupported("IfFeature")),
+        }
+    }
+}
+
+/// Rebuild a constant expression of kimchi, which can contain challenges, in
+/// the expression type `E`
+fn build_constant<E, F, ChallengeTerm>(
+    c: &ConstantExpr<F, ChallengeTerm>,
+) -> Result<E, ExpressionError>
+where
+    E: Expression<F = F>,
+    E::Challenge: From<ChallengeTerm>,
+    F: Field,
+    ChallengeTerm: Copy,
+{
+    match c {
+        Operations::Atom(inner) => match inner {
+            ConstantExprInner::Challenge(chal) => Ok(E::from_challenge((*chal).into())),
+            ConstantExprInner::Constant(ConstantTerm::Literal(f)) => Ok(E::from_constant(*f)),
+            ConstantExprInner::Constant(ConstantTerm::EndoCoefficient) => {
+                Err(ExpressionError::Unsupported("EndoCoefficient"))
+            }
+            ConstantExprInner::Constant(ConstantTerm::Mds { .. }) => {
+                Err(ExpressionError::Unsupported("Mds"))
+            }
+        },
+        Operations::Pow(x, p) => Ok(build_constant::<E, _, _>(x)?.pow(*p)),
+        Operations::Add(x, y) => Ok(build_constant::<E, _, _>(x)? + build_constant(y)?),
+        Operations::Mul(x, y) => Ok(build_constant::<E, _, _>(x)? * build_constant(y)?),
+        Operations::Sub(x, y) => Ok(build_constant::<E, _, _>(x)? - build_constant(y)?),
+        Operations::Double(x) => Ok(build_constant::<E, _, _>(x)?.double()),
+        Operations::Square(x) => Ok(build_constant::<E, _, _>(x)?.square()),
+        Operations::Cache(_, x) => build_constant(x),
+        Operations::IfFeature(_, _, _) => Err(ExpressionError::Unsupported("IfFeature")),
+    }
+}
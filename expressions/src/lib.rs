@@ -0,0 +1,100 @@
+//! A common interface over the expression types used by the provers of this
+//! repository.
+//!
+//! kimchi, kimchi-msm and o1vm describe their constraints with the expressions
+//! of [kimchi::circuits::expr], while folding uses its own
+//! `FoldingCompatibleExpr`. All of them are multivariate polynomials whose
+//! variables are [Variable]s over a column type, with constants in a field and
+//! challenges coined by the verifier. The [Expression] trait captures this
+//! common structure:
+//! - constraints can be written once, generically over [Expression], and
+//!   built directly in the expression type of any prover, without going
+//!   through a conversion;
+//! - any expression can be rebuilt in another expression type sharing the same
+//!   field with [Expression::build], as long as the columns and challenges can
+//!   be converted.
+//!
+//! The column, row and challenge abstractions are the ones of kimchi, and are
+//! re-exported from here.
+//!
+//! The trait is implemented for the expressions of kimchi (see [expr]) and for
+//! `FoldingCompatibleExpr`, but the provers do not use it yet. To finish the
+//! unification:
+//! - the constraints of kimchi, kimchi-msm and o1vm, which are written
+//!   directly with [kimchi::circuits::expr::Expr], must be written generically
+//!   over [Expression];
+//! - folding must build its expressions with [Expression::build] instead of
+//!   converting kimchi expressions with `From`, and its conversion code must be
+//!   removed.
+
+use ark_ff::Field;
+use core::ops::{Add, Mul, Sub};
+use thiserror::Error;
+
+pub use kimchi::circuits::{
+    expr::{AlphaChallengeTerm, Variable},
+    gate::CurrOrNext,
+};
+
+pub mod expr;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ExpressionError {
+    #[error("the node {0} cannot be represented in the target expression type")]
+    Unsupported(&'static str),
+}
+
+/// A multivariate polynomial over [Variable]s of type [Self::Column], with
+/// coefficients in [Self::F] and challenges of type [Self::Challenge].
+pub trait Expression:
+    Sized + Clone + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    type F: Field;
+
+    /// The columns the variables of the expression are defined over
+    type Column: Copy;
+
+    /// The challenges the expression can use
+    type Challenge: Copy;
+
+    /// A constant field element
+    fn from_constant(c: Self::F) -> Self;
+
+    /// A variable, i.e. a column at the current or next row
+    fn from_variable(var: Variable<Self::Column>) -> Self;
+
+    /// A challenge, coined by the verifier
+    fn from_challenge(chal: Self::Challenge) -> Self;
+
+    fn double(self) -> Self;
+
+    fn square(self) -> Self;
+
+    fn pow(self, p: u64) -> Self;
+
+    /// The column `col` at the current row
+    fn curr(col: Self::Column) -> Self {
+        Self::from_variable(Variable {
+            col,
+            row: CurrOrNext::Curr,
+        })
+    }
+
+    /// The column `col` at the next row
+    fn next(col: Self::Column) -> Self {
+        Self::from_variable(Variable {
+            col,
+            row: CurrOrNext::Next,
+        })
+    }
+
+    /// Rebuild the expression in the expression type `E`.
+    /// An error is returned if the expression contains a node that is specific
+    /// to its type, like the Lagrange basis polynomials of kimchi or the
+    /// extensions of folding.
+    fn build<E>(&self) -> Result<E, ExpressionError>
+    where
+        E: Expression<F = Self::F>,
+        E::Column: From<Self::Column>,
+        E::Challenge: From<Self::Challenge>;
+}
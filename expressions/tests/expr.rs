@@ -0,0 +1,67 @@
+use o1_expressions::{CurrOrNext, Expression, ExpressionError};
+use kimchi::circuits::{
+    berkeley_columns::{BerkeleyChallengeTerm, Column, E},
+    expr::{Cache, ConstantTerm, Expr, ExprInner, RowOffset},
+};
+use mina_curves::pasta::Fp;
+
+/// `(w_0 + w_1) * w_2' - beta * w_0`, written for any expression type
+fn constraint<Ex>() -> Ex
+where
+    Ex: Expression<F = Fp, Column = Column, Challenge = BerkeleyChallengeTerm>,
+{
+    let w = |i| Ex::curr(Column::Witness(i));
+    let beta = Ex::from_challenge(BerkeleyChallengeTerm::Beta);
+    (w(0) + w(1)) * Ex::next(Column::Witness(2)) - beta * w(0)
+}
+
+#[test]
+fn test_generic_constraint_as_kimchi_expr() {
+    let w = |i| E::<Fp>::cell(Column::Witness(i), CurrOrNext::Curr);
+    let beta = E::<Fp>::from(BerkeleyChallengeTerm::Beta);
+    let expected =
+        (w(0) + w(1)) * E::<Fp>::cell(Column::Witness(2), CurrOrNext::Next) - beta * w(0);
+
+    assert_eq!(constraint::<E<Fp>>(), expected);
+}
+
+#[test]
+fn test_build_kimchi_expr() {
+    let expr = constraint::<E<Fp>>();
+    let rebuilt: E<Fp> = expr.build().unwrap();
+    assert_eq!(rebuilt, expr);
+
+    let expr = E::<Fp>::from_constant(Fp::from(3u64)) * expr.square().pow(3);
+    let rebuilt: E<Fp> = expr.build().unwrap();
+    assert_eq!(rebuilt, expr);
+}
+
+#[test]
+fn test_build_cached_kimchi_expr() {
+    let mut cache = Cache::default();
+    let expr = constraint::<E<Fp>>();
+    let cached = cache.cache(expr.clone()) + E::<Fp>::curr(Column::Witness(3));
+
+    let rebuilt: E<Fp> = cached.build().unwrap();
+    assert_eq!(rebuilt, expr + E::<Fp>::curr(Column::Witness(3)));
+}
+
+#[test]
+fn test_build_unsupported_kimchi_expr() {
+    let lagrange = E::<Fp>::Atom(ExprInner::UnnormalizedLagrangeBasis(RowOffset {
+        zk_rows: false,
+        offset: 0,
+    }));
+    let expr = constraint::<E<Fp>>() * lagrange;
+    assert_eq!(
+        expr.build::<E<Fp>>(),
+        Err(ExpressionError::Unsupported("UnnormalizedLagrangeBasis"))
+    );
+
+    let endo = Expr::from(ConstantTerm::EndoCoefficient);
+    let expr = constraint::<E<Fp>>() * endo;
+    assert_eq!(
+        expr.build::<E<Fp>>(),
+        Err(ExpressionError::Unsupported("EndoCoefficient"))
+    );
+}
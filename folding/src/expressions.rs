@@ -277,12 +277,13 @@ use crate::{
     FoldingConfig, ScalarField,
 };
 use ark_ec::AffineRepr;
-use ark_ff::{One, Zero};
+use ark_ff::{Field, One, Zero};
 use core::{
     fmt,
     fmt::{Display, Formatter},
 };
 use derivative::Derivative;
+use o1_expressions::{Expression, ExpressionError};
 use itertools::Itertools;
 use kimchi::circuits::{
     berkeley_columns::BerkeleyChallengeTerm,
@@ -379,7 +380,9 @@ pub enum FoldingCompatibleExprInner<C: FoldingConfig> {
 
 /// Compatible folding expressions that can be used with folding schemes.
 /// An expression from [kimchi::circuits::expr::Expr] can be converted into a
-/// [FoldingCompatibleExpr] using the trait [From].
+/// [FoldingCompatibleExpr] using the trait [From]. Constraints written
+/// generically over [Expression] can also be built directly as a
+/// [FoldingCompatibleExpr].
 /// From there, an expression of type [IntegratedFoldingExpr] can be created
 /// using the function [folding_expression].
 #[derive(Derivative)]
@@ -1105,53 +1108,74 @@ impl<F, Col, Config: FoldingConfig<Column = Col>>
 where
     Config::Curve: AffineRepr<ScalarField = F>,
     Config::Challenge: From<BerkeleyChallengeTerm>,
+    F: Field,
+    Col: Copy + PartialEq,
 {
+    // The constant expressions nodes are considered as top level expressions in
+    // folding
     fn from(
         expr: Operations<ExprInner<Operations<ConstantExprInner<F, BerkeleyChallengeTerm>>, Col>>,
     ) -> Self {
-        match expr {
-            Operations::Atom(inner) => match inner {
-                ExprInner::Constant(op) => match op {
-                    // The constant expressions nodes are considered as top level
-                    // expressions in folding
-                    Operations::Atom(inner) => FoldingCompatibleExpr::Atom(inner.into()),
-                    Operations::Add(x, y) => {
-                        FoldingCompatibleExpr::Add(Box::new((*x).into()), Box::new((*y).into()))
-                    }
-                    Operations::Mul(x, y) => {
-                        FoldingCompatibleExpr::Mul(Box::new((*x).into()), Box::new((*y).into()))
-                    }
-                    Operations::Sub(x, y) => {
-                        FoldingCompatibleExpr::Sub(Box::new((*x).into()), Box::new((*y).into()))
-                    }
-                    Operations::Double(x) => FoldingCompatibleExpr::Double(Box::new((*x).into())),
-                    Operations::Square(x) => FoldingCompatibleExpr::Square(Box::new((*x).into())),
-                    Operations::Pow(e, p) => FoldingCompatibleExpr::Pow(Box::new((*e).into()), p),
-                    _ => panic!("Operation not supported in folding expressions"),
-                },
-                ExprInner::Cell(col) => {
-                    FoldingCompatibleExpr::Atom(FoldingCompatibleExprInner::Cell(col))
-                }
-                ExprInner::UnnormalizedLagrangeBasis(_) => {
-                    panic!("UnnormalizedLagrangeBasis should not be used in folding expressions")
+        expr.build()
+            .unwrap_or_else(|e| panic!("Expression not supported by folding: {e}"))
+    }
+}
+
+impl<C: FoldingConfig> Expression for FoldingCompatibleExpr<C> {
+    type F = <C::Curve as AffineRepr>::ScalarField;
+    type Column = C::Column;
+    type Challenge = C::Challenge;
+
+    fn from_constant(c: Self::F) -> Self {
+        FoldingCompatibleExpr::Atom(FoldingCompatibleExprInner::Constant(c))
+    }
+
+    fn from_variable(var: Variable<C::Column>) -> Self {
+        FoldingCompatibleExpr::Atom(FoldingCompatibleExprInner::Cell(var))
+    }
+
+    fn from_challenge(chal: C::Challenge) -> Self {
+        FoldingCompatibleExpr::Atom(FoldingCompatibleExprInner::Challenge(chal))
+    }
+
+    fn double(self) -> Self {
+        FoldingCompatibleExpr::Double(Box::new(self))
+    }
+
+    fn square(self) -> Self {
+        FoldingCompatibleExpr::Square(Box::new(self))
+    }
+
+    fn pow(self, p: u64) -> Self {
+        FoldingCompatibleExpr::Pow(Box::new(self), p)
+    }
+
+    fn build<E>(&self) -> Result<E, ExpressionError>
+    where
+        E: Expression<F = Self::F>,
+        E::Column: From<C::Column>,
+        E::Challenge: From<C::Challenge>,
+    {
+        match self {
+            FoldingCompatibleExpr::Atom(inner) => match inner {
+                FoldingCompatibleExprInner::Constant(c) => Ok(E::from_constant(*c)),
+                FoldingCompatibleExprInner::Challenge(chal) => {
+                    Ok(E::from_challenge((*chal).into()))
                 }
-                ExprInner::VanishesOnZeroKnowledgeAndPreviousRows => {
-                    panic!("VanishesOnZeroKnowledgeAndPreviousRows should not be used in folding expressions")
+                FoldingCompatibleExprInner::Cell(var) => Ok(E::from_variable(Variable {
+                    col: var.col.into(),
+                    row: var.row,
+                })),
+                FoldingCompatibleExprInner::Extensions(_) => {
+                    Err(ExpressionError::Unsupported("Extensions"))
                 }
             },
-            Operations::Add(x, y) => {
-                FoldingCompatibleExpr::Add(Box::new((*x).into()), Box::new((*y).into()))
-            }
-            Operations::Mul(x, y) => {
-                FoldingCompatibleExpr::Mul(Box::new((*x).into()), Box::new((*y).into()))
-            }
-            Operations::Sub(x, y) => {
-                FoldingCompatibleExpr::Sub(Box::new((*x).into()), Box::new((*y).into()))
-            }
-            Operations::Double(x) => FoldingCompatibleExpr::Double(Box::new((*x).into())),
-            Operations::Square(x) => FoldingCompatibleExpr::Square(Box::new((*x).into())),
-            Operations::Pow(e, p) => FoldingCompatibleExpr::Pow(Box::new((*e).into()), p),
-            _ => panic!("Operation not supported in folding expressions"),
+            FoldingCompatibleExpr::Pow(x, p) => Ok(x.build::<E>()?.pow(*p)),
+            FoldingCompatibleExpr::Add(x, y) => Ok(x.build::<E>()? + y.build()?),
+            FoldingCompatibleExpr::Sub(x, y) => Ok(x.build::<E>()? - y.build()?),
+            FoldingCompatibleExpr::Mul(x, y) => Ok(x.build::<E>()? * y.build()?),
+            FoldingCompatibleExpr::Double(x) => Ok(x.build::<E>()?.double()),
+            FoldingCompatibleExpr::Square(x) => Ok(x.build::<E>()?.square()),
         }
     }
 }
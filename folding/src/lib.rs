@@ -32,6 +32,7 @@ use std::{
     rc::Rc,
     sync::atomic::{AtomicUsize, Ordering},
 };
+use tracing::{debug_span, instrument};
 
 // Make available outside the crate to avoid code duplication
 pub use error_term::Side;
@@ -180,6 +181,16 @@ impl<'a, CF: FoldingConfig> FoldingScheme<'a, CF> {
     /// - The error terms are computed and committed.
     /// - The sponge absorbs the commitments and challenges.
     #[allow(clippy::type_complexity)]
+    #[instrument(
+        name = "fold",
+        skip_all,
+        level = "debug",
+        fields(
+            prover = "folding",
+            domain_size = self.domain.size,
+            additional_columns = self.get_number_of_additional_columns(),
+        )
+    )]
     pub fn fold_instance_witness_pair<A, B, Sponge>(
         &self,
         a: A,
@@ -208,14 +219,19 @@ impl<'a, CF: FoldingConfig> FoldingScheme<'a, CF> {
         // Computing the additional columns, resulting of the quadritization
         // process.
         // Side-effect: commitments are added in both relaxed (extended) instance.
+        let phase = debug_span!("quadraticization").entered();
         let env: ExtendedEnv<CF> =
             env.compute_extension(&self.extended_witness_generator, self.srs);
+        drop(phase);
 
         // Computing the error terms
+        let phase = debug_span!("error_terms").entered();
         let error: [Vec<ScalarField<CF>>; 2] = compute_error(&self.expression, &env, u);
         let error_evals = error.map(|e| Evaluations::from_vec_and_domain(e, self.domain));
+        drop(phase);
 
         // Committing to the cross terms
+        let phase = debug_span!("commit_error_terms").entered();
         // Default blinder for committing to the cross terms
         let blinders = PolyComm::new(vec![ScalarField::<CF>::one()]);
         let error_commitments = error_evals
@@ -236,6 +252,9 @@ impl<'a, CF: FoldingConfig> FoldingScheme<'a, CF> {
         assert_eq!(error_commitments[0].len(), 1);
         assert_eq!(error_commitments[1].len(), 1);
 
+        drop(phase);
+
+        let _phase = debug_span!("combine").entered();
         let t_0 = &error_commitments[0].get_first_chunk();
         let t_1 = &error_commitments[1].get_first_chunk();
 
@@ -23,6 +23,7 @@ use kimchi::{
     curve::KimchiCurve,
 };
 use mina_poseidon::{constants::PlonkSpongeConstantsKimchi, sponge::DefaultFqSponge, FqSponge};
+use o1_expressions::Expression;
 use poly_commitment::{ipa::SRS, SRS as _};
 use rand::thread_rng;
 use std::println as debug;
@@ -189,6 +190,20 @@ fn constraints() -> Vec<FoldingCompatibleExpr<TestFoldingConfig>> {
     vec![add, mul]
 }
 
+/// The same constraints as [constraints], written once for any expression type
+fn generic_constraints<E: Expression<Column = Column>>() -> Vec<E> {
+    let a = E::curr(Column::X(0));
+    let b = E::curr(Column::X(1));
+    let c = E::curr(Column::X(2));
+    let s_add = E::curr(Column::Selector(0));
+    let s_mul = E::curr(Column::Selector(1));
+
+    let add = (a.clone() + b.clone() - c.clone()) * s_add;
+    let mul = (a * b - c) * s_mul;
+
+    vec![add, mul]
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 struct TestFoldingConfig;
 
@@ -508,3 +523,15 @@ fn test_folding_instance() {
         checker.check(&final_constraint, domain);
     }
 }
+
+#[test]
+fn test_generic_constraints() {
+    let built = generic_constraints::<FoldingCompatibleExpr<TestFoldingConfig>>();
+    assert_eq!(built, constraints());
+
+    // building a folding expression into itself gives back the same expression
+    for constraint in built {
+        let rebuilt: FoldingCompatibleExpr<TestFoldingConfig> = constraint.build().unwrap();
+        assert_eq!(rebuilt, constraint);
+    }
+}
@@ -26,6 +26,10 @@ pub trait GroupMap<F> {
     fn setup() -> Self;
     fn to_group(&self, u: F) -> (F, F);
     fn batch_to_group_x(&self, ts: Vec<F>) -> Vec<[F; 3]>;
+    /// Same as calling [GroupMap::to_group] on each element of `us`, but
+    /// shares a single field inversion across all of them using Montgomery's
+    /// batch inversion trick.
+    fn to_group_batch(&self, us: &[F]) -> Vec<(F, F)>;
 }
 
 #[derive(Clone, Copy)]
@@ -111,12 +115,10 @@ pub fn get_y<G: SWCurveConfig>(x: G::BaseField) -> Option<G::BaseField> {
     fx.sqrt()
 }
 
-fn get_xy<G: SWCurveConfig>(
-    params: &BWParameters<G>,
-    t: G::BaseField,
-) -> (G::BaseField, G::BaseField) {
-    let xvec = potential_xs(params, t);
-    for x in &xvec {
+/// returns the first of the candidate x-coordinates that is on the curve, with
+/// its y-coordinate
+fn first_xy<G: SWCurveConfig>(xs: &[G::BaseField; 3]) -> (G::BaseField, G::BaseField) {
+    for x in xs {
         if let Some(y) = get_y::<G>(*x) {
             return (*x, y);
         }
@@ -124,6 +126,40 @@ fn get_xy<G: SWCurveConfig>(
     panic!("get_xy")
 }
 
+fn get_xy<G: SWCurveConfig>(
+    params: &BWParameters<G>,
+    t: G::BaseField,
+) -> (G::BaseField, G::BaseField) {
+    first_xy::<G>(&potential_xs(params, t))
+}
+
+/// batched version of [potential_xs], sharing the inversion of all the alphas
+fn batch_potential_xs<G: SWCurveConfig>(
+    params: &BWParameters<G>,
+    ts: &[G::BaseField],
+) -> Vec<[G::BaseField; 3]> {
+    let t2_alpha_invs: Vec<_> = ts
+        .iter()
+        .map(|t| {
+            let t2 = t.square();
+            let mut alpha_inv = t2;
+            alpha_inv += &params.fu;
+            alpha_inv *= &t2;
+            (t2, alpha_inv)
+        })
+        .collect();
+
+    // zeros are left untouched, which matches the behaviour of [potential_xs]
+    let mut alphas: Vec<G::BaseField> = t2_alpha_invs.iter().map(|(_, a)| *a).collect();
+    ark_ff::batch_inversion::<G::BaseField>(&mut alphas);
+
+    t2_alpha_invs
+        .iter()
+        .zip(alphas)
+        .map(|((t2, _), alpha)| potential_xs_helper(params, *t2, alpha))
+        .collect()
+}
+
 impl<G: SWCurveConfig> GroupMap<G::BaseField> for BWParameters<G> {
     fn setup() -> Self {
         assert!(G::COEFF_A.is_zero());
@@ -157,28 +193,17 @@ impl<G: SWCurveConfig> GroupMap<G::BaseField> for BWParameters<G> {
     }
 
     fn batch_to_group_x(&self, ts: Vec<G::BaseField>) -> Vec<[G::BaseField; 3]> {
-        let t2_alpha_invs: Vec<_> = ts
-            .iter()
-            .map(|t| {
-                let t2 = t.square();
-                let mut alpha_inv = t2;
-                alpha_inv += &self.fu;
-                alpha_inv *= &t2;
-                (t2, alpha_inv)
-            })
-            .collect();
-
-        let mut alphas: Vec<G::BaseField> = t2_alpha_invs.iter().map(|(_, a)| *a).collect();
-        ark_ff::batch_inversion::<G::BaseField>(&mut alphas);
-
-        let potential_xs = t2_alpha_invs
-            .iter()
-            .zip(alphas)
-            .map(|((t2, _), alpha)| potential_xs_helper(self, *t2, alpha));
-        potential_xs.collect()
+        batch_potential_xs(self, &ts)
     }
 
     fn to_group(&self, t: G::BaseField) -> (G::BaseField, G::BaseField) {
         get_xy(self, t)
     }
+
+    fn to_group_batch(&self, ts: &[G::BaseField]) -> Vec<(G::BaseField, G::BaseField)> {
+        batch_potential_xs(self, ts)
+            .iter()
+            .map(|xs| first_xy::<G>(xs))
+            .collect()
+    }
 }
@@ -31,3 +31,17 @@ fn test_batch_group_map_on_curve() {
         assert!(g.is_on_curve());
     }
 }
+
+#[test]
+fn test_batch_group_map_matches_to_group() {
+    let params = BWParameters::<G>::setup();
+    let mut ts: Vec<Fq> = (0..1000).map(|_| rand::random()).collect();
+    // zero is handled specially, since it has no inverse
+    ts.push(Fq::from(0u64));
+    let points = params.to_group_batch(&ts);
+    assert_eq!(points.len(), ts.len());
+    for (t, (x, y)) in ts.iter().zip(points) {
+        assert_eq!(params.to_group(*t), (x, y));
+        assert!(Vesta::new(x, y).is_on_curve());
+    }
+}
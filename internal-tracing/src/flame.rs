@@ -0,0 +1,199 @@
+//! A [`tracing_subscriber::Layer`] writing the time spent in each span in the
+//! folded stack format, which can be rendered with `inferno-flamegraph` or
+//! `flamegraph.pl`.
+//!
+//! The provers of this repository follow the same naming convention for their
+//! spans, so that the flamegraphs of different provers can be compared:
+//! - the root span of a proof is named `prove` (`fold` for a folding step) and
+//!   records the fields `prover`, `domain_size` and `columns`;
+//! - the phases of a proof are child spans named `commit_witness`,
+//!   `permutation`, `quotient`, `evaluations` and `opening`, and the phases of
+//!   a folding step are named `quadraticization`, `error_terms`,
+//!   `commit_error_terms` and `combine`.
+//!
+//! In the folded stacks, a span recording a `prover` field is written as
+//! `name[prover]`, e.g. `prove[kimchi];quotient 1234`. The weights are the self
+//! time of the stacks, in microseconds.
+//!
+//! ```no_run
+//! use internal_tracing::flame::FlameLayer;
+//! use tracing_subscriber::prelude::*;
+//!
+//! let file = std::fs::File::create("prover.folded").unwrap();
+//! tracing_subscriber::registry()
+//!     .with(FlameLayer::new(std::io::BufWriter::new(file)))
+//!     .init();
+//! ```
+
+use std::{
+    fmt::Debug,
+    io::Write,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id},
+    Subscriber,
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+
+/// The frame of a span in the folded stacks
+struct Frame(String);
+
+/// The timing of a span, reset each time the span is entered
+struct Timing {
+    entered: Instant,
+    children: Duration,
+}
+
+/// Looks for the `prover` field of a span
+struct ProverVisitor(Option<String>);
+
+impl Visit for ProverVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "prover" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "prover" {
+            self.0 = Some(format!("{value:?}"));
+        }
+    }
+}
+
+/// Writes one folded stack line each time a span is exited
+pub struct FlameLayer<W> {
+    out: Mutex<W>,
+}
+
+impl<W: Write> FlameLayer<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out: Mutex::new(out),
+        }
+    }
+}
+
+impl<S, W> Layer<S> for FlameLayer<W>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: Write + 'static,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let span = ctx.span(id).expect("span must exist");
+        let mut visitor = ProverVisitor(None);
+        attrs.record(&mut visitor);
+        let frame = match visitor.0 {
+            Some(prover) => format!("{}[{prover}]", span.name()),
+            None => span.name().to_string(),
+        };
+        span.extensions_mut().insert(Frame(frame));
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        let span = ctx.span(id).expect("span must exist");
+        let timing = Timing {
+            entered: Instant::now(),
+            children: Duration::ZERO,
+        };
+        span.extensions_mut().replace(timing);
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        let span = ctx.span(id).expect("span must exist");
+        let Some(timing) = span.extensions_mut().remove::<Timing>() else {
+            return;
+        };
+        let elapsed = timing.entered.elapsed();
+
+        if let Some(parent) = span.parent() {
+            if let Some(parent_timing) = parent.extensions_mut().get_mut::<Timing>() {
+                parent_timing.children += elapsed;
+            }
+        }
+
+        let stack = span
+            .scope()
+            .from_root()
+            .map(|span| match span.extensions().get::<Frame>() {
+                Some(Frame(frame)) => frame.clone(),
+                None => span.name().to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(";");
+        // the children of a span can run in parallel, and take longer than it
+        let self_time = elapsed.saturating_sub(timing.children);
+
+        if let Ok(mut out) = self.out.lock() {
+            let _ = writeln!(out, "{stack} {}", self_time.as_micros());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tracing::{debug_span, instrument};
+    use tracing_subscriber::prelude::*;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[instrument(
+        name = "prove",
+        skip_all,
+        level = "debug",
+        fields(prover = "test", domain_size = 8, columns = 2)
+    )]
+    fn prove() {
+        let phase = debug_span!("commit_witness").entered();
+        std::thread::sleep(Duration::from_millis(2));
+        drop(phase);
+
+        let _phase = debug_span!("opening").entered();
+        std::thread::sleep(Duration::from_millis(2));
+    }
+
+    #[test]
+    fn test_folded_stacks() {
+        let buffer = Buffer::default();
+        let subscriber = tracing_subscriber::registry().with(FlameLayer::new(buffer.clone()));
+        tracing::subscriber::with_default(subscriber, prove);
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<(&str, u128)> = output
+            .lines()
+            .map(|line| {
+                let (stack, micros) = line.rsplit_once(' ').unwrap();
+                (stack, micros.parse().unwrap())
+            })
+            .collect();
+
+        let stacks: Vec<_> = lines.iter().map(|(stack, _)| *stack).collect();
+        assert_eq!(
+            stacks,
+            [
+                "prove[test];commit_witness",
+                "prove[test];opening",
+                "prove[test]"
+            ]
+        );
+        // the phases slept, while the root span only accounts for its self time
+        assert!(lines[0].1 >= 2000 && lines[1].1 >= 2000);
+        assert!(lines[2].1 < lines[0].1 + lines[1].1);
+    }
+}
@@ -1,5 +1,8 @@
 use std::time::SystemTime;
 
+#[cfg(feature = "flame")]
+pub mod flame;
+
 #[cfg(feature = "enabled")]
 pub use serde_json::{json, to_writer as json_to_writer, Value as JsonValue};
 
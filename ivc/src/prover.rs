@@ -28,7 +28,7 @@ use kimchi::{
 };
 use kimchi_msm::{columns::Column as GenericColumn, witness::Witness};
 use mina_poseidon::{sponge::ScalarChallenge, FqSponge};
-use o1_utils::ExtendedDensePolynomial;
+use o1_utils::{pointwise_evaluations, ExtendedDensePolynomial};
 use poly_commitment::{
     commitment::{absorb_commitment, CommitmentCurve, PolyComm},
     kzg::{KZGProof, PairingSRS},
@@ -263,16 +263,9 @@ where
     let quotient_poly = {
         let evaluation_domain = domain.d4;
 
-        let enlarge_to_domain_generic =
-            |evaluations: &Evaluations<Fp, R2D<Fp>>, new_domain: R2D<Fp>| {
-                assert!(evaluations.domain() == domain.d1);
-                evaluations
-                    .interpolate_by_ref()
-                    .evaluate_over_domain_by_ref(new_domain)
-            };
-
         let enlarge_to_domain = |evaluations: &Evaluations<Fp, R2D<Fp>>| {
-            enlarge_to_domain_generic(evaluations, evaluation_domain)
+            assert!(evaluations.domain() == domain.d1);
+            pointwise_evaluations::resize(evaluations, evaluation_domain)
         };
 
         let simple_eval_env: SimpleEvalEnv<G, N_WIT, N_FSEL> = {
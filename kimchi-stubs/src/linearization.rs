@@ -30,10 +30,14 @@ where
                     lookup: false,
                     range_check: false,
                     foreign_field_mul: false,
+                    vector_lookup: false,
                 },
                 joint_lookup_used: false,
                 uses_runtime_tables: false,
+                log_up: false,
             },
+            cairo: false,
+            poseidon2: false,
         })
     };
     let evaluated_cols = linearization_columns::<F>(features.as_ref());
@@ -17,11 +17,13 @@ use kimchi::{
 };
 use mina_curves::pasta::{Fp, Pallas, Vesta, VestaParameters};
 use mina_poseidon::{constants::PlonkSpongeConstantsKimchi, sponge::DefaultFqSponge};
+use o1_utils::lazy_cache::LazyCache;
 use poly_commitment::{ipa::OpeningProof, SRS as _};
 use serde::{Deserialize, Serialize};
 use std::{
     fs::{File, OpenOptions},
     io::{BufReader, BufWriter, Seek, SeekFrom::Start},
+    sync::Arc,
 };
 
 /// Boxed so that we don't store large proving indexes in the OCaml heap.
@@ -168,9 +170,10 @@ pub fn caml_pasta_fp_plonk_index_read(
     )?;
     t.srs = srs.clone();
 
-    let (linearization, powers_of_alpha) = expr_linearization(Some(&t.cs.feature_flags), true);
-    t.linearization = linearization;
-    t.powers_of_alpha = powers_of_alpha;
+    t.linearization = Arc::new(LazyCache::preinit(expr_linearization(
+        Some(&t.cs.feature_flags),
+        true,
+    )));
 
     Ok(CamlPastaFpPlonkIndex(Box::new(t)))
 }
@@ -1058,6 +1058,10 @@ pub fn caml_pasta_fp_plonk_proof_dummy() -> CamlProofWithPublic<CamlGVesta, Caml
         lookup_gate_lookup_selector: None,
         range_check_lookup_selector: None,
         foreign_field_mul_lookup_selector: None,
+        cairo_selectors: None,
+        vector_lookup_selector: None,
+        lookup_multiplicities: None,
+        poseidon2_selectors: None,
     };
 
     let public = vec![Fp::one(), Fp::one()];
@@ -11,7 +11,7 @@ use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as Domain};
 use core::convert::TryInto;
 use kimchi::{
     circuits::{
-        constraints::FeatureFlags,
+        constraints::{FeatureFlags, ZkLevel},
         lookup::lookups::{LookupFeatures, LookupPatterns},
         polynomials::permutation::{permutation_vanishing_polynomial, zk_w, Shifts},
         wires::{COLUMNS, PERMUTS},
@@ -108,12 +108,16 @@ impl From<CamlPastaFpPlonkVerifierIndex> for VerifierIndex<Vesta, OpeningProof<V
                             lookup: false,
                             range_check: false,
                             foreign_field_mul: false,
+                            vector_lookup: false,
                         },
                         joint_lookup_used: false,
                         uses_runtime_tables: false,
+                        log_up: false,
                     }
                 }
             },
+            cairo: false,
+            poseidon2: false,
         };
 
         // TODO dummy_lookup_value ?
@@ -146,6 +150,8 @@ impl From<CamlPastaFpPlonkVerifierIndex> for VerifierIndex<Vesta, OpeningProof<V
             foreign_field_add_comm: evals.foreign_field_add_comm.map(Into::into),
             foreign_field_mul_comm: evals.foreign_field_mul_comm.map(Into::into),
             rot_comm: evals.rot_comm.map(Into::into),
+            cairo_comms: None,
+            poseidon2_comms: None,
 
             shift,
             permutation_vanishing_polynomial_m: {
@@ -166,6 +172,7 @@ impl From<CamlPastaFpPlonkVerifierIndex> for VerifierIndex<Vesta, OpeningProof<V
 
             lookup_index: index.lookup_index.map(Into::into),
             linearization,
+            zk_level: ZkLevel::Full,
         }
     }
 }
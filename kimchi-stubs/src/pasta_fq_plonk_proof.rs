@@ -229,6 +229,10 @@ pub fn caml_pasta_fq_plonk_proof_dummy() -> CamlProofWithPublic<CamlGPallas, Cam
         lookup_gate_lookup_selector: None,
         range_check_lookup_selector: None,
         foreign_field_mul_lookup_selector: None,
+        cairo_selectors: None,
+        vector_lookup_selector: None,
+        lookup_multiplicities: None,
+        poseidon2_selectors: None,
     };
 
     let public = vec![Fq::one(), Fq::one()];
@@ -57,6 +57,7 @@ where
             lookup,
             range_check,
             ffmul,
+            vector_lookup: _,
         } = val;
         CamlLookupSelectors {
             lookup: lookup.map(From::from),
@@ -84,6 +85,7 @@ where
             xor: xor.map(From::from),
             range_check: range_check.map(From::from),
             ffmul: ffmul.map(From::from),
+            vector_lookup: None,
         }
     }
 }
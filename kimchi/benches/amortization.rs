@@ -29,5 +29,31 @@ pub fn amortization(c: &mut Criterion) {
     }
 }
 
-criterion_group!(benches, amortization);
+pub fn amortization_same_index(c: &mut Criterion) {
+    let mut group = c.benchmark_group("amortization_same_index");
+
+    let ctx = BenchmarkCtx::new(16);
+    let proof_and_public = ctx.create_proof();
+    let proofs: Vec<_> = core::iter::repeat(proof_and_public)
+        .take(1 << PROOFS)
+        .collect();
+
+    group.sample_size(10);
+    for size in 0..=PROOFS {
+        group.throughput(criterion::Throughput::Elements(1 << size));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("2^{size}")),
+            &(),
+            |b, _| {
+                b.iter_batched(
+                    || &proofs[0..(1 << size)],
+                    |input| ctx.batch_verification_same_index(black_box(input)),
+                    BatchSize::SmallInput,
+                );
+            },
+        );
+    }
+}
+
+criterion_group!(benches, amortization, amortization_same_index);
 criterion_main!(benches);
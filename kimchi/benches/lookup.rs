@@ -0,0 +1,66 @@
+#![allow(clippy::unit_arg)]
+use criterion::{black_box, criterion_group, criterion_main, Criterion, SamplingMode};
+use kimchi::bench::BenchmarkCtx;
+
+const ARGUMENTS: [(&str, bool); 2] = [("plookup", false), ("logUp", true)];
+
+pub fn bench_lookup_proof_creation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lookup_proof_creation");
+    group.sampling_mode(SamplingMode::Flat); // for slow benchmarks
+    group.sample_size(10);
+
+    for size in [10, 14] {
+        for (name, log_up) in ARGUMENTS {
+            let ctx = BenchmarkCtx::new_lookup(size, log_up);
+
+            // the proof size does not depend on the witness
+            let (proof, _) = ctx.create_proof();
+            println!(
+                "{name}: proof size {} bytes (SRS size 2^{{{}}}, {} lookup gates)",
+                rmp_serde::to_vec(&proof).unwrap().len(),
+                ctx.srs_size(),
+                ctx.num_gates
+            );
+
+            group.bench_function(
+                format!(
+                    "{name} proof creation (SRS size 2^{{{}}}, {} lookup gates)",
+                    ctx.srs_size(),
+                    ctx.num_gates
+                ),
+                |b| b.iter(|| black_box(ctx.create_proof())),
+            );
+        }
+    }
+
+    group.finish()
+}
+
+pub fn bench_lookup_proof_verification(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lookup_proof_verification");
+
+    for size in [10, 14] {
+        for (name, log_up) in ARGUMENTS {
+            let ctx = BenchmarkCtx::new_lookup(size, log_up);
+            let proof = ctx.create_proof();
+
+            group.bench_function(
+                format!(
+                    "{name} proof verification (SRS size 2^{{{}}}, {} lookup gates)",
+                    ctx.srs_size(),
+                    ctx.num_gates
+                ),
+                |b| b.iter(|| black_box(ctx.batch_verification(std::slice::from_ref(&proof)))),
+            );
+        }
+    }
+
+    group.finish()
+}
+
+criterion_group!(
+    benches,
+    bench_lookup_proof_creation,
+    bench_lookup_proof_verification
+);
+criterion_main!(benches);
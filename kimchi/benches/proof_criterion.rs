@@ -23,6 +23,24 @@ pub fn bench_proof_creation(c: &mut Criterion) {
     group.finish()
 }
 
+pub fn bench_proof_creation_xor(c: &mut Criterion) {
+    let mut group = c.benchmark_group("proof_creation_xor");
+    group.sampling_mode(SamplingMode::Flat); // for slow benchmarks
+    group.sample_size(10);
+
+    let ctx = BenchmarkCtx::new_xor(16);
+    group.bench_function(
+        format!(
+            "proof creation (SRS size 2^{{{}}}, {} xor gates)",
+            ctx.srs_size(),
+            ctx.num_gates
+        ),
+        |b| b.iter(|| black_box(ctx.create_proof())),
+    );
+
+    group.finish()
+}
+
 pub fn bench_proof_verification(c: &mut Criterion) {
     let mut group = c.benchmark_group("proof_verification");
 
@@ -61,5 +79,10 @@ pub fn bench_proof_verification(c: &mut Criterion) {
     group.finish()
 }
 
-criterion_group!(benches, bench_proof_creation, bench_proof_verification);
+criterion_group!(
+    benches,
+    bench_proof_creation,
+    bench_proof_creation_xor,
+    bench_proof_verification
+);
 criterion_main!(benches);
@@ -0,0 +1,147 @@
+//! This module implements the accumulation of the deferred part of the
+//! verification of kimchi proofs.
+//!
+//! With the IPA commitment scheme, verifying a proof has two parts:
+//!
+//! * a succinct part, made of the Fiat-Shamir argument, of the check of the
+//!   evaluations of the proof, and of the check of its opening proof up to its
+//!   final commitment `sg`,
+//! * the check that `sg` is the commitment to the polynomial of the
+//!   challenges of the opening proof, which is a multi-scalar multiplication
+//!   of the size of the SRS.
+//!
+//! The second part can be deferred: the challenges and `sg` of a proof form an
+//! accumulator, a [RecursionChallenge], that a later proof takes as one of its
+//! previous challenges and opens. Verifying this later proof checks all the
+//! accumulators it opens in its own opening proof.
+//!
+//! [accumulate] checks the succinct part of K proofs, which may be proofs of
+//! different verifier indexes, and returns their K accumulators.
+//! [prove_with_accumulators] then creates a proof of a circuit taking them as
+//! previous challenges. Verifying this proof with
+//! [verify](crate::verifier::verify) checks the K deferred parts at once.
+//!
+//! This is not an aggregation of the K proofs: their succinct part is only
+//! checked natively by [accumulate], and is not proven by the proof taking
+//! their accumulators. A verifier given this proof alone learns that the
+//! accumulators are valid, not that the K proofs are. Proving the succinct
+//! part requires a circuit verifying it, which is not implemented.
+
+use crate::{
+    circuits::wires::COLUMNS,
+    curve::KimchiCurve,
+    error::{AccumulationError, VerifyError},
+    plonk_sponge::FrSponge,
+    proof::{ProverProof, RecursionChallenge},
+    prover_index::ProverIndex,
+    verifier::{to_batch, Context},
+};
+use ark_ff::PrimeField;
+use mina_poseidon::FqSponge;
+use poly_commitment::{commitment::PolyComm, ipa::OpeningProof, SRS as _};
+use rand::{thread_rng, CryptoRng, RngCore};
+
+/// Checks the succinct part of the proofs `proofs`, see [self], and returns
+/// the accumulator of each of them, in the same order.
+///
+/// # Errors
+///
+/// Will give error if the proofs do not use the same SRS, or if the succinct
+/// part of one of them is not valid.
+pub fn accumulate<G, EFqSponge, EFrSponge>(
+    group_map: &G::Map,
+    proofs: &[Context<G, OpeningProof<G>>],
+) -> Result<Vec<RecursionChallenge<G>>, VerifyError>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+{
+    let Some(first) = proofs.first() else {
+        return Ok(vec![]);
+    };
+    let srs = first.verifier_index.srs();
+    for &Context { verifier_index, .. } in proofs {
+        if verifier_index.srs().max_poly_size() != srs.max_poly_size() {
+            return Err(VerifyError::DifferentSRS);
+        }
+    }
+
+    let mut batch = vec![];
+    for &Context {
+        verifier_index,
+        proof,
+        public_input,
+    } in proofs
+    {
+        batch.push(to_batch::<G, EFqSponge, EFrSponge, OpeningProof<G>>(
+            verifier_index,
+            verifier_index.digest::<EFqSponge>(),
+            proof,
+            public_input,
+        )?);
+    }
+
+    let challenges = srs
+        .verify_deferred(group_map, &mut batch, &mut thread_rng())
+        .ok_or(VerifyError::OpenProof)?;
+    Ok(proofs
+        .iter()
+        .zip(challenges)
+        .map(|(context, chals)| {
+            RecursionChallenge::new(chals, PolyComm::new(vec![context.proof.proof.sg]))
+        })
+        .collect())
+}
+
+/// Checks the succinct part of the proofs `proofs` with [accumulate], and
+/// creates a proof of the circuit of index `index`, from the witness
+/// `witness`, taking their accumulators as previous challenges, see [self].
+///
+/// The circuit must take as many previous challenges as there are proofs, and
+/// use the same SRS as them.
+///
+/// # Errors
+///
+/// Will give error if the number of proofs is not the one of the circuit, if
+/// the proofs do not use the SRS of the circuit, if the succinct part of one
+/// of them is not valid, or if the proof fails to be created.
+pub fn prove_with_accumulators<G, EFqSponge, EFrSponge, RNG>(
+    group_map: &G::Map,
+    proofs: &[Context<G, OpeningProof<G>>],
+    index: &ProverIndex<G, OpeningProof<G>>,
+    witness: [Vec<G::ScalarField>; COLUMNS],
+    rng: &mut RNG,
+) -> Result<ProverProof<G, OpeningProof<G>>, AccumulationError>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+    RNG: RngCore + CryptoRng,
+{
+    if proofs.len() != index.cs.prev_challenges {
+        return Err(AccumulationError::WrongNumberOfProofs(
+            index.cs.prev_challenges,
+            proofs.len(),
+        ));
+    }
+    if proofs
+        .iter()
+        .any(|context| context.verifier_index.srs().g.len() != index.srs.g.len())
+    {
+        return Err(AccumulationError::DifferentSRS);
+    }
+
+    let accumulators = accumulate::<G, EFqSponge, EFrSponge>(group_map, proofs)?;
+    Ok(ProverProof::create_recursive::<EFqSponge, EFrSponge, _>(
+        group_map,
+        witness,
+        &[],
+        index,
+        accumulators,
+        None,
+        rng,
+    )?)
+}
@@ -19,16 +19,20 @@ use std::{array, path::PathBuf};
 
 use crate::{
     circuits::{
-        constraints::ConstraintSystem,
+        constraints::{ConstraintSystem, ZK_ROWS_BY_DEFAULT},
         gate::CircuitGate,
-        lookup::runtime_tables::RuntimeTable,
-        polynomials::generic::GenericGateSpec,
+        gate::GateType,
+        lookup::{runtime_tables::RuntimeTable, tables::LookupTable},
+        polynomials::{generic::GenericGateSpec, xor},
         wires::{Wire, COLUMNS},
     },
     curve::KimchiCurve,
     proof::{ProverProof, RecursionChallenge},
-    prover_index::{testing::new_index_for_test, ProverIndex},
-    verifier::{batch_verify, Context},
+    prover_index::{
+        testing::{new_index_for_test, new_index_for_test_with_lookups},
+        ProverIndex,
+    },
+    verifier::{batch_verify, batch_verify_same_index, Context},
 };
 
 pub type BaseSpongeVesta = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
@@ -40,6 +44,7 @@ pub struct BenchmarkCtx {
     pub num_gates: usize,
     group_map: BWParameters<VestaParameters>,
     index: ProverIndex<Vesta, OpeningProof<Vesta>>,
+    witness: [Vec<Fp>; COLUMNS],
 }
 
 impl BenchmarkCtx {
@@ -67,11 +72,109 @@ impl BenchmarkCtx {
             ));
         }
 
-        // group map
-        let group_map = <Vesta as CommitmentCurve>::Map::setup();
+        // create the index
+        let index = new_index_for_test(gates, 0);
+
+        // create witness
+        let witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![1u32.into(); num_gates]);
+
+        Self::from_index(srs_size_log2, num_gates, index, witness)
+    }
+
+    /// This will create a context that allows for benchmarks of the lookup
+    /// argument, with `num_gates` lookup gates of 3 lookups each into a table
+    /// of half the size of the SRS, checked with logUp if `log_up` is `true`
+    /// and with plookup otherwise.
+    pub fn new_lookup(srs_size_log2: u32, log_up: bool) -> Self {
+        // there's some overhead that we need to remove (e.g. zk rows)
+        let num_gates = ((1 << srs_size_log2) - 10) as usize;
+        let table_len = 1u64 << (srs_size_log2 - 1);
+
+        // the table of the squares, whose first row is the dummy row of zeroes
+        let table = LookupTable {
+            id: 0,
+            data: vec![
+                (0..table_len).map(Into::into).collect(),
+                (0..table_len).map(|i| (i * i).into()).collect(),
+            ],
+        };
+
+        // create the circuit
+        let gates = (0..num_gates)
+            .map(|row| CircuitGate::new(GateType::Lookup, Wire::for_row(row), vec![]))
+            .collect();
 
         // create the index
-        let mut index = new_index_for_test(gates, 0);
+        let index = new_index_for_test_with_lookups(
+            gates,
+            0,
+            0,
+            vec![table],
+            None,
+            false,
+            None,
+            false,
+            log_up,
+        );
+
+        // create witness: the table ID, then 3 pairs of an index and its square
+        let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::from(0u32); num_gates]);
+        for row in 0..num_gates {
+            for j in 0..3 {
+                let index = (3 * row as u64 + j as u64) % table_len;
+                witness[2 * j + 1][row] = index.into();
+                witness[2 * j + 2][row] = (index * index).into();
+            }
+        }
+
+        Self::from_index(srs_size_log2, num_gates, index, witness)
+    }
+
+    /// This will create a context that allows for benchmarks of a circuit of
+    /// 64-bit XOR gadgets filling the domain of size `2^srs_size_log2`,
+    /// after 5 public inputs, as in the test of the lazy mode.
+    pub fn new_xor(srs_size_log2: u32) -> Self {
+        let circuit_size = 1 << srs_size_log2;
+        let bits = 64;
+        let rng = &mut rand::thread_rng();
+
+        let public = [Fp::from(1u8); 5];
+        let mut gates = vec![];
+        let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+        for (row, p) in public.iter().enumerate() {
+            gates.push(CircuitGate::create_generic_gadget(
+                Wire::for_row(row),
+                GenericGateSpec::Pub,
+                None,
+            ));
+            for (col, w) in witness.iter_mut().enumerate() {
+                w.push(if col == 0 { *p } else { Fp::from(0u8) });
+            }
+        }
+
+        // each gadget uses 5 rows, and the zero-knowledge rows must still fit
+        while gates.len() + 5 + ZK_ROWS_BY_DEFAULT as usize <= circuit_size {
+            CircuitGate::<Fp>::extend_xor_gadget(&mut gates, bits);
+            let input1 = Fp::from(rng.gen_range(0u64..1 << (bits - 1)));
+            let input2 = Fp::from(rng.gen_range(0u64..1 << (bits - 1)));
+            xor::extend_xor_witness(&mut witness, input1, input2, bits);
+        }
+        let num_gates = gates.len();
+
+        // create the index
+        let index = new_index_for_test(gates, public.len());
+
+        Self::from_index(srs_size_log2, num_gates, index, witness)
+    }
+
+    fn from_index(
+        srs_size_log2: u32,
+        num_gates: usize,
+        mut index: ProverIndex<Vesta, OpeningProof<Vesta>>,
+        witness: [Vec<Fp>; COLUMNS],
+    ) -> Self {
+        // group map
+        let group_map = <Vesta as CommitmentCurve>::Map::setup();
 
         assert_eq!(index.cs.domain.d1.log_size_of_group, srs_size_log2, "the test wanted to use an SRS of size {srs_size_log2} but the domain size ended up being {}", index.cs.domain.d1.log_size_of_group);
 
@@ -85,21 +188,19 @@ impl BenchmarkCtx {
             num_gates,
             group_map,
             index,
+            witness,
         }
     }
 
     /// Produces a proof
     pub fn create_proof(&self) -> (ProverProof<Vesta, OpeningProof<Vesta>>, Vec<Fp>) {
-        // create witness
-        let witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![1u32.into(); self.num_gates]);
-
-        let public_input = witness[0][0..self.index.cs.public].to_vec();
+        let public_input = self.witness[0][0..self.index.cs.public].to_vec();
 
         // add the proof to the batch
         (
             ProverProof::create::<BaseSpongeVesta, ScalarSpongeVesta, _>(
                 &self.group_map,
-                witness,
+                self.witness.clone(),
                 &[],
                 &self.index,
                 &mut rand::rngs::OsRng,
@@ -126,6 +227,24 @@ impl BenchmarkCtx {
         )
         .unwrap();
     }
+
+    /// Verifies a batch of proofs of the circuit with [batch_verify_same_index]
+    #[allow(clippy::type_complexity)]
+    pub fn batch_verification_same_index(
+        &self,
+        batch: &[(ProverProof<Vesta, OpeningProof<Vesta>>, Vec<Fp>)],
+    ) {
+        let batch: Vec<_> = batch
+            .iter()
+            .map(|(proof, public)| (proof, public.as_slice()))
+            .collect();
+        batch_verify_same_index::<Vesta, BaseSpongeVesta, ScalarSpongeVesta, OpeningProof<Vesta>>(
+            &self.group_map,
+            self.index.verifier_index.as_ref().unwrap(),
+            &batch,
+        )
+        .unwrap();
+    }
 }
 
 /// This function can be called before any call to a kimchi verfier,
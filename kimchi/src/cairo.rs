@@ -0,0 +1,129 @@
+//! This module implements the proof of executions of Cairo programs run by
+//! turshi: the statement of an execution, the constraint system that its
+//! witness satisfies, and the prover and verifier entry points.
+//!
+//! The statement of an execution is made of its claimed initial and final pc
+//! and ap, and it is the public input of the circuit created by
+//! [CircuitGate::create_cairo_circuit].
+//!
+//! Note that the program and its outputs are not part of the statement, as
+//! the circuit does not bind the memory accesses of the trace to them: a proof
+//! attests a valid execution between the claimed pointers, but not which
+//! program ran nor what it output.
+
+use crate::{
+    circuits::{
+        constraints::ConstraintSystem,
+        gate::CircuitGate,
+        polynomials::turshi::{witness::cairo_witness_with_public_input, CLAIM_PUBLIC_INPUTS},
+    },
+    curve::KimchiCurve,
+    error::{ProverError, SetupError, VerifyError},
+    plonk_sponge::FrSponge,
+    proof::ProverProof,
+    prover_index::ProverIndex,
+    verifier::verify,
+    verifier_index::VerifierIndex,
+};
+use ark_ff::PrimeField;
+use mina_poseidon::FqSponge;
+use poly_commitment::OpenProof;
+use rand_core::{CryptoRng, RngCore};
+use turshi::{runner::Pointers, CairoProgram};
+
+/// The public statement of an execution of a Cairo program
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CairoStatement<F> {
+    /// Claimed initial pc and ap, and final pc and ap
+    pub pointers: [F; CLAIM_PUBLIC_INPUTS],
+}
+
+impl<F: PrimeField> CairoStatement<F> {
+    /// Creates the statement of an execution of a Cairo program
+    pub fn new(prog: &CairoProgram<F>) -> Self {
+        CairoStatement {
+            pointers: [
+                prog.ini().pc(),
+                prog.ini().ap(),
+                prog.fin().pc(),
+                prog.fin().ap(),
+            ],
+        }
+    }
+
+    /// Returns the public input of the circuit for this statement, in the
+    /// format expected by [CircuitGate::create_cairo_public_input]
+    pub fn public_input(&self) -> Vec<F> {
+        self.pointers.to_vec()
+    }
+}
+
+/// Creates the constraint system of the executions of `steps` instructions
+///
+/// # Errors
+///
+/// Will give error if the constraint system cannot be built.
+pub fn cairo_constraint_system<F: PrimeField>(
+    steps: usize,
+) -> Result<ConstraintSystem<F>, SetupError> {
+    let (_, public) = CircuitGate::<F>::create_cairo_public_input();
+    ConstraintSystem::create(CircuitGate::create_cairo_circuit(steps))
+        .public(public)
+        .build()
+}
+
+/// Proves an execution of a Cairo program, and returns the proof along with
+/// the statement it attests
+///
+/// # Errors
+///
+/// Will give error if the proof cannot be created, e.g. if the execution does
+/// not match the shape of the index.
+#[allow(clippy::type_complexity)]
+pub fn prove_cairo<G, EFqSponge, EFrSponge, OpeningProof, RNG>(
+    group_map: &G::Map,
+    index: &ProverIndex<G, OpeningProof>,
+    prog: &CairoProgram<G::ScalarField>,
+    rng: &mut RNG,
+) -> Result<(ProverProof<G, OpeningProof>, CairoStatement<G::ScalarField>), ProverError>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+    OpeningProof: OpenProof<G>,
+    RNG: RngCore + CryptoRng,
+    VerifierIndex<G, OpeningProof>: Clone,
+{
+    let statement = CairoStatement::new(prog);
+    let witness = cairo_witness_with_public_input(prog);
+    let proof =
+        ProverProof::create::<EFqSponge, EFrSponge, _>(group_map, witness, &[], index, rng)?;
+    Ok((proof, statement))
+}
+
+/// Verifies the proof of an execution of a Cairo program against its statement
+///
+/// # Errors
+///
+/// Will give error if the proof is not valid for the statement.
+pub fn verify_cairo<G, EFqSponge, EFrSponge, OpeningProof>(
+    group_map: &G::Map,
+    verifier_index: &VerifierIndex<G, OpeningProof>,
+    statement: &CairoStatement<G::ScalarField>,
+    proof: &ProverProof<G, OpeningProof>,
+) -> Result<(), VerifyError>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+    OpeningProof: OpenProof<G>,
+{
+    verify::<G, EFqSponge, EFrSponge, OpeningProof>(
+        group_map,
+        verifier_index,
+        proof,
+        &statement.public_input(),
+    )
+}
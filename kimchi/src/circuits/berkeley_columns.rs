@@ -111,6 +111,7 @@ pub enum Column {
     LookupKindIndex(LookupPattern),
     LookupRuntimeSelector,
     LookupRuntimeTable,
+    LookupMultiplicities,
     Index(GateType),
     Coefficient(usize),
     Permutation(usize),
@@ -137,6 +138,7 @@ impl FormattedOutput for Column {
             Column::LookupKindIndex(i) => format!("k_{{{i:?}}}"),
             Column::LookupRuntimeSelector => "rts".to_string(),
             Column::LookupRuntimeTable => "rt".to_string(),
+            Column::LookupMultiplicities => "m".to_string(),
             Column::Index(gate) => {
                 format!("{gate:?}")
             }
@@ -155,6 +157,7 @@ impl FormattedOutput for Column {
             Column::LookupKindIndex(i) => format!("k[{i:?}]"),
             Column::LookupRuntimeSelector => "rts".to_string(),
             Column::LookupRuntimeTable => "rt".to_string(),
+            Column::LookupMultiplicities => "m".to_string(),
             Column::Index(gate) => {
                 format!("{gate:?}")
             }
@@ -181,6 +184,9 @@ impl<F: Copy> ColumnEvaluations<F> for ProofEvaluations<PointEvaluations<F>> {
             LookupRuntimeTable => self
                 .runtime_lookup_table
                 .ok_or(ExprError::MissingIndexEvaluation(col)),
+            LookupMultiplicities => self
+                .lookup_multiplicities
+                .ok_or(ExprError::MissingIndexEvaluation(col)),
             Index(GateType::Poseidon) => Ok(self.poseidon_selector),
             Index(GateType::Generic) => Ok(self.generic_selector),
             Index(GateType::CompleteAdd) => Ok(self.complete_add_selector),
@@ -205,6 +211,17 @@ impl<F: Copy> ColumnEvaluations<F> for ProofEvaluations<PointEvaluations<F>> {
             Index(GateType::Rot64) => self
                 .rot_selector
                 .ok_or(ExprError::MissingIndexEvaluation(col)),
+            Index(
+                GateType::CairoClaim
+                | GateType::CairoInstruction
+                | GateType::CairoFlags
+                | GateType::CairoTransition
+                | GateType::Poseidon2External
+                | GateType::Poseidon2Internal,
+            ) => self
+                .get_column(col)
+                .copied()
+                .ok_or(ExprError::MissingIndexEvaluation(col)),
             Permutation(i) => Ok(self.s[i]),
             Coefficient(i) => Ok(self.coefficients[i]),
             LookupKindIndex(LookupPattern::Xor) => self
@@ -219,6 +236,9 @@ impl<F: Copy> ColumnEvaluations<F> for ProofEvaluations<PointEvaluations<F>> {
             LookupKindIndex(LookupPattern::ForeignFieldMul) => self
                 .foreign_field_mul_lookup_selector
                 .ok_or(ExprError::MissingIndexEvaluation(col)),
+            LookupKindIndex(LookupPattern::VectorLookup) => self
+                .vector_lookup_selector
+                .ok_or(ExprError::MissingIndexEvaluation(col)),
             LookupRuntimeSelector => self
                 .runtime_lookup_table_selector
                 .ok_or(ExprError::MissingIndexEvaluation(col)),
@@ -245,6 +265,7 @@ impl<'a, F: FftField> ColumnEnvironment<'a, F, BerkeleyChallengeTerm, BerkeleyCh
             LookupTable => lookup.map(|l| l.table),
             LookupRuntimeSelector => lookup.and_then(|l| l.runtime_selector),
             LookupRuntimeTable => lookup.and_then(|l| l.runtime_table),
+            LookupMultiplicities => lookup.and_then(|l| l.multiplicities),
             Index(t) => match self.index.get(t) {
                 None => None,
                 Some(e) => Some(e),
@@ -303,6 +324,9 @@ pub struct LookupEnvironment<'a, F: FftField> {
     pub runtime_selector: Option<&'a Evaluations<F, D<F>>>,
     /// The evaluations of the optional runtime table.
     pub runtime_table: Option<&'a Evaluations<F, D<F>>>,
+    /// The evaluations of the multiplicities of the table entries, only used
+    /// by the logUp argument.
+    pub multiplicities: Option<&'a Evaluations<F, D<F>>>,
 }
 
 /// The collection of polynomials (all in evaluation form) and constants
@@ -0,0 +1,290 @@
+//! Read-write and read-only memories of a [CircuitBuilder]
+//!
+//! A memory is an array of `size` cells, allocated by [CircuitBuilder::memory]
+//! with all its cells equal to zero, or by [CircuitBuilder::rom] with the
+//! values of its cells, which cannot be written. Each read and write appends
+//! an access `(address, time, value, write)` to the log of the memory, whose
+//! time is the index of the access, so that the log is ordered by time. The
+//! value of a read is computed by the witness as the value of the last write
+//! to its address, and is only constrained when the circuit is built.
+//!
+//! The log is checked by the permutation argument of memory checking: the
+//! prover provides the accesses sorted by address, and then by time, and the
+//! gates check that
+//!
+//! * the sorted log is ordered, the differences of the consecutive addresses
+//!   and of the consecutive times of an address being range-checked,
+//! * the addresses are smaller than the size of the memory,
+//! * each read of the sorted log returns the value of the previous access if
+//!   it has the same address, and zero otherwise,
+//! * the sorted log is a permutation of the log, as
+//!
+//! ```text
+//! ∏ (γ - f(access)) = ∏ (γ - f(sorted access))
+//! f(a, t, v, w) = a + α * t + α^2 * v + α^3 * w
+//! ```
+//!
+//! where the challenges `α` and `γ` are squeezed from a Poseidon sponge
+//! absorbing both logs, so that the prover commits to the logs before they are
+//! drawn.
+//!
+//! The check of a memory takes about 58 rows per access, most of them for the
+//! 3 Poseidon permutations absorbing it.
+//!
+//! ```
+//! use kimchi::{circuits::builder::CircuitBuilder, curve::KimchiCurve};
+//! use mina_curves::pasta::{Fp, Vesta};
+//!
+//! let mut builder = CircuitBuilder::<Fp>::new();
+//! let address = builder.private_input();
+//! let value = builder.private_input();
+//! let memory = builder.memory(Vesta::sponge_params(), 4);
+//! builder.write(memory, address, value);
+//! let read = builder.read(memory, address);
+//! builder.assert_equal(read, value);
+//!
+//! let (gates, _, witness) = builder.build();
+//! let witness = witness(&[], &[Fp::from(3u64), Fp::from(42u64)]);
+//! assert_eq!(witness[0].len(), gates.len());
+//! ```
+
+use super::{CircuitBuilder, Var};
+use ark_ff::PrimeField;
+use core::{array, cell::RefCell};
+use mina_poseidon::poseidon::ArithmeticSpongeParams;
+use o1_utils::FieldHelpers;
+use std::rc::Rc;
+
+/// A memory of a [CircuitBuilder], see [self]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Memory(usize);
+
+/// An access to a memory
+#[derive(Clone, Copy, Debug)]
+struct Access {
+    address: Var,
+    value: Var,
+    write: bool,
+}
+
+/// The log of the accesses to a memory, in the order of their times
+pub(super) struct MemoryLog<F: PrimeField> {
+    params: &'static ArithmeticSpongeParams<F>,
+    size: usize,
+    read_only: bool,
+    /// Shared with the witness of the reads, which look for the last write
+    accesses: Rc<RefCell<Vec<Access>>>,
+}
+
+impl<F: PrimeField> CircuitBuilder<F> {
+    /// Allocates a memory of `size` cells equal to zero, whose log is
+    /// absorbed by a Poseidon sponge of parameters `params`
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `size` is zero.
+    pub fn memory(&mut self, params: &'static ArithmeticSpongeParams<F>, size: usize) -> Memory {
+        assert!(size > 0, "a memory must have at least one cell");
+        self.memories.push(MemoryLog {
+            params,
+            size,
+            read_only: false,
+            accesses: Rc::new(RefCell::new(vec![])),
+        });
+        Memory(self.memories.len() - 1)
+    }
+
+    /// Allocates a read-only memory holding `values`, whose log is absorbed by
+    /// a Poseidon sponge of parameters `params`. The values are written by the
+    /// first accesses of the log.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `values` is empty.
+    pub fn rom(&mut self, params: &'static ArithmeticSpongeParams<F>, values: &[Var]) -> Memory {
+        let memory = self.memory(params, values.len());
+        for (address, value) in values.iter().enumerate() {
+            let address = self.constant(F::from(address as u64));
+            self.access(memory, address, *value, true);
+        }
+        self.memories[memory.0].read_only = true;
+        memory
+    }
+
+    /// Reads the cell of `memory` at `address`, and returns a new variable
+    /// constrained to be its value when the circuit is built
+    pub fn read(&mut self, memory: Memory, address: Var) -> Var {
+        let value = self.var();
+        let accesses = self.memories[memory.0].accesses.clone();
+        let time = accesses.borrow().len();
+        self.compute(value, move |values| {
+            accesses.borrow()[..time]
+                .iter()
+                .rev()
+                .find(|access| access.write && values[access.address.0] == values[address.0])
+                .map_or(F::zero(), |access| values[access.value.0])
+        });
+        self.access(memory, address, value, false);
+        value
+    }
+
+    /// Writes `value` to the cell of `memory` at `address`
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the memory is read-only.
+    pub fn write(&mut self, memory: Memory, address: Var, value: Var) {
+        assert!(
+            !self.memories[memory.0].read_only,
+            "cannot write to a read-only memory"
+        );
+        self.access(memory, address, value, true);
+    }
+
+    /// Lays out the checks of the logs of all the memories, see [self]
+    pub(super) fn check_memories(&mut self) {
+        for log in core::mem::take(&mut self.memories) {
+            self.check_memory(log);
+        }
+    }
+
+    fn access(&mut self, memory: Memory, address: Var, value: Var, write: bool) {
+        self.memories[memory.0].accesses.borrow_mut().push(Access {
+            address,
+            value,
+            write,
+        });
+    }
+
+    fn check_memory(&mut self, log: MemoryLog<F>) {
+        let accesses = log.accesses.borrow().clone();
+        let n = accesses.len();
+        if n == 0 {
+            return;
+        }
+        // The differences of the addresses and of the times are smaller than
+        // the size of the memory and the number of accesses
+        let bits = (usize::BITS - (log.size.max(n) - 1).leading_zeros()).max(1) as usize;
+
+        // The sorted log, as (address, time, value, write), and whether each
+        // access has the same address as the previous one
+        let sorted: Vec<[Var; 4]> = (0..n).map(|_| array::from_fn(|_| self.var())).collect();
+        let same: Vec<Var> = (1..n).map(|_| self.var()).collect();
+        {
+            let (accesses, sorted, same) = (accesses.clone(), sorted.clone(), same.clone());
+            self.fillers.push(Box::new(move |values, _| {
+                let mut order: Vec<usize> = (0..n).collect();
+                order.sort_by_cached_key(|&time| {
+                    (values[accesses[time].address.0].to_biguint(), time)
+                });
+                for (entry, time) in sorted.iter().zip(order) {
+                    let access = accesses[time];
+                    values[entry[0].0] = values[access.address.0];
+                    values[entry[1].0] = F::from(time as u64);
+                    values[entry[2].0] = values[access.value.0];
+                    values[entry[3].0] = F::from(access.write);
+                }
+                for (flag, pair) in same.iter().zip(sorted.windows(2)) {
+                    values[flag.0] = F::from(values[pair[0][0].0] == values[pair[1][0].0]);
+                }
+            }));
+        }
+
+        // The first read of an address returns zero: value * (1 - write) = 0
+        let [first_address, _, first_value, first_write] = sorted[0];
+        self.generic(
+            [F::one(), F::zero(), F::zero(), -F::one(), F::zero()],
+            [Some(first_value), Some(first_write), None],
+        );
+        self.range_check(first_address, bits);
+        for (pair, &same) in sorted.windows(2).zip(&same) {
+            let [address, time, value, _] = pair[0];
+            let [next_address, next_time, next_value, next_write] = pair[1];
+            // same is a boolean, and is set if and only if the addresses are
+            // equal, the next address being larger otherwise
+            self.generic(
+                [-F::one(), F::zero(), F::zero(), F::one(), F::zero()],
+                [Some(same), Some(same), None],
+            );
+            let address_diff = self.sub(next_address, address);
+            self.generic(
+                [F::zero(), F::zero(), F::zero(), F::one(), F::zero()],
+                [Some(same), Some(address_diff), None],
+            );
+            // same * (time_diff - 1) + (1 - same) * (address_diff - 1)
+            let time_diff = self.sub(next_time, time);
+            let same_time_diff = self.mul(same, time_diff);
+            let delta = self.var();
+            self.compute(delta, move |values| {
+                values[same_time_diff.0] + values[address_diff.0] - F::one()
+            });
+            self.generic(
+                [F::one(), F::one(), -F::one(), F::zero(), -F::one()],
+                [Some(same_time_diff), Some(address_diff), Some(delta)],
+            );
+            self.range_check(delta, bits);
+            // A read returns the value of the previous access to its address,
+            // or zero: (next_value - same * value) * (1 - next_write) = 0
+            let same_value = self.mul(same, value);
+            let value_diff = self.sub(next_value, same_value);
+            self.generic(
+                [F::one(), F::zero(), F::zero(), -F::one(), F::zero()],
+                [Some(value_diff), Some(next_write), None],
+            );
+        }
+        let max_address = self.constant(F::from(log.size as u64 - 1));
+        let margin = self.sub(max_address, sorted[n - 1][0]);
+        self.range_check(margin, bits);
+
+        // The challenges, squeezed after absorbing the addresses and values of
+        // the log, whose times and kinds are constants, and the sorted log
+        let zero = self.constant(F::zero());
+        let mut state = [zero; 3];
+        let elements: Vec<Var> = accesses
+            .iter()
+            .flat_map(|access| [access.address, access.value])
+            .chain(sorted.iter().flatten().copied())
+            .collect();
+        for pair in elements.chunks(2) {
+            state[0] = self.add(state[0], pair[0]);
+            state[1] = self.add(state[1], pair[1]);
+            state = self.poseidon(log.params, state);
+        }
+        let [alpha, gamma, _] = state;
+
+        let mut products = vec![];
+        for entries in [
+            accesses
+                .iter()
+                .enumerate()
+                .map(|(time, access)| {
+                    let time = self.constant(F::from(time as u64));
+                    let write = self.constant(F::from(access.write));
+                    [access.address, time, access.value, write]
+                })
+                .collect::<Vec<_>>(),
+            sorted,
+        ] {
+            let mut product = None;
+            for entry in entries {
+                let fingerprint = self.fingerprint(alpha, entry);
+                let term = self.sub(gamma, fingerprint);
+                product = Some(match product {
+                    None => term,
+                    Some(product) => self.mul(product, term),
+                });
+            }
+            products.push(product.unwrap());
+        }
+        self.assert_equal(products[0], products[1]);
+    }
+
+    /// Constrains a new variable to be `a + α * t + α^2 * v + α^3 * w` for the
+    /// entry `[a, t, v, w]`
+    fn fingerprint(&mut self, alpha: Var, entry: [Var; 4]) -> Var {
+        entry[..3].iter().rev().fold(entry[3], |acc, term| {
+            let acc = self.mul(alpha, acc);
+            self.add(acc, *term)
+        })
+    }
+}
@@ -0,0 +1,378 @@
+//! A builder of circuits generating the gates and the witness together
+//!
+//! Instead of writing the gates of a circuit and the columns of its witness
+//! side by side, a [CircuitBuilder] declares variables and the operations on
+//! them, and lays out the gates of the operations one after the other:
+//!
+//! * the public inputs, one row each, which must be declared first,
+//! * additions, subtractions, multiplications, constants, splits into low
+//!   and high bits, and equality assertions, in generic gates holding two of
+//!   them per row,
+//! * XORs, range-checks and Poseidon permutations, in the gates of their
+//!   gadgets,
+//! * reads and writes of [memories](memory), whose accesses are checked when
+//!   the circuit is built.
+//!
+//! Each variable is copied to all the cells using it by the permutation.
+//! [CircuitBuilder::build] returns the gates and a closure filling their
+//! witness from the values of the public and private inputs, which computes
+//! the values of the other variables as the operations are laid out.
+//!
+//! ```
+//! use kimchi::circuits::builder::CircuitBuilder;
+//! use mina_curves::pasta::Fp;
+//!
+//! let mut builder = CircuitBuilder::<Fp>::new();
+//! let x = builder.public_input();
+//! let y = builder.private_input();
+//! let x_times_y = builder.mul(x, y);
+//! let z = builder.constant(Fp::from(42u64));
+//! builder.assert_equal(x_times_y, z);
+//!
+//! let (gates, public, witness) = builder.build();
+//! let witness = witness(&[Fp::from(6u64)], &[Fp::from(7u64)]);
+//! assert_eq!(public, 1);
+//! assert_eq!(witness[0].len(), gates.len());
+//! ```
+
+pub mod memory;
+
+use crate::circuits::{
+    gate::{CircuitGate, Connect, GateType},
+    polynomial::COLUMNS,
+    polynomials::{
+        generic::GENERIC_COEFFS,
+        poseidon::{self, POS_ROWS_PER_HASH, SPONGE_WIDTH},
+        range_check, xor,
+    },
+    wires::Wire,
+};
+use ark_ff::PrimeField;
+use core::array;
+use memory::MemoryLog;
+use mina_poseidon::poseidon::ArithmeticSpongeParams;
+use num_bigint::BigUint;
+use o1_utils::{BigUintFieldHelpers, BitwiseOps, FieldHelpers};
+
+/// A cell of the circuit, as `(row, column)`
+type Cell = (usize, usize);
+
+/// Fills the witness of an operation from the values of the variables, and
+/// computes the values of its results
+type Filler<F> = Box<dyn Fn(&mut [F], &mut [Vec<F>; COLUMNS])>;
+
+/// A variable of a [CircuitBuilder]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Var(usize);
+
+/// Builds the gates of a circuit together with its witness, see [self]
+pub struct CircuitBuilder<F: PrimeField> {
+    gates: Vec<CircuitGate<F>>,
+    /// The cells of each variable
+    cells: Vec<Vec<Cell>>,
+    public_inputs: Vec<Var>,
+    private_inputs: Vec<Var>,
+    /// The row of the generic gate whose second half is free
+    free_generic: Option<usize>,
+    fillers: Vec<Filler<F>>,
+    /// The accesses to each memory
+    memories: Vec<MemoryLog<F>>,
+}
+
+impl<F: PrimeField> Default for CircuitBuilder<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: PrimeField> CircuitBuilder<F> {
+    /// Creates an empty builder
+    pub fn new() -> Self {
+        Self {
+            gates: vec![],
+            cells: vec![],
+            public_inputs: vec![],
+            private_inputs: vec![],
+            free_generic: None,
+            fillers: vec![],
+            memories: vec![],
+        }
+    }
+
+    /// Declares a public input, in a new row
+    ///
+    /// # Panics
+    ///
+    /// Will panic if an operation has already been laid out, as the public
+    /// inputs are in the first rows of the circuit.
+    pub fn public_input(&mut self) -> Var {
+        let row = self.gates.len();
+        assert_eq!(
+            row,
+            self.public_inputs.len(),
+            "public inputs must be declared before any operation"
+        );
+        let mut coeffs = [F::zero(); GENERIC_COEFFS * 2];
+        coeffs[0] = F::one();
+        self.gates
+            .push(CircuitGate::create_generic(Wire::for_row(row), coeffs));
+
+        let var = self.var();
+        self.public_inputs.push(var);
+        self.place(var, (row, 0));
+        var
+    }
+
+    /// Declares a private input, whose value is given to the witness closure
+    pub fn private_input(&mut self) -> Var {
+        let var = self.var();
+        self.private_inputs.push(var);
+        var
+    }
+
+    /// Constrains a new variable to be the constant `value`
+    pub fn constant(&mut self, value: F) -> Var {
+        let var = self.var();
+        self.compute(var, move |_| value);
+        self.generic(
+            [F::one(), F::zero(), F::zero(), F::zero(), -value],
+            [Some(var), None, None],
+        );
+        var
+    }
+
+    /// Constrains a new variable to be `left + right`
+    pub fn add(&mut self, left: Var, right: Var) -> Var {
+        let var = self.var();
+        self.compute(var, move |values| values[left.0] + values[right.0]);
+        self.generic(
+            [F::one(), F::one(), -F::one(), F::zero(), F::zero()],
+            [Some(left), Some(right), Some(var)],
+        );
+        var
+    }
+
+    /// Constrains a new variable to be `left - right`
+    pub fn sub(&mut self, left: Var, right: Var) -> Var {
+        let var = self.var();
+        self.compute(var, move |values| values[left.0] - values[right.0]);
+        self.generic(
+            [F::one(), -F::one(), -F::one(), F::zero(), F::zero()],
+            [Some(left), Some(right), Some(var)],
+        );
+        var
+    }
+
+    /// Constrains a new variable to be `left * right`
+    pub fn mul(&mut self, left: Var, right: Var) -> Var {
+        let var = self.var();
+        self.compute(var, move |values| values[left.0] * values[right.0]);
+        self.generic(
+            [F::zero(), F::zero(), -F::one(), F::one(), F::zero()],
+            [Some(left), Some(right), Some(var)],
+        );
+        var
+    }
+
+    /// Constrains `left` and `right` to be equal
+    pub fn assert_equal(&mut self, left: Var, right: Var) {
+        self.generic(
+            [F::one(), -F::one(), F::zero(), F::zero(), F::zero()],
+            [Some(left), Some(right), None],
+        );
+    }
+
+    /// Constrains a new variable to be the XOR of `left` and `right`, which
+    /// are constrained to have at most `bits` bits, in the gates of
+    /// [CircuitGate::extend_xor_gadget]
+    pub fn xor(&mut self, left: Var, right: Var, bits: usize) -> Var {
+        let row = self.gates.len();
+        CircuitGate::extend_xor_gadget(&mut self.gates, bits);
+
+        let var = self.var();
+        self.compute(var, move |values| {
+            BigUint::bitwise_xor(&values[left.0].to_biguint(), &values[right.0].to_biguint())
+                .to_field()
+                .expect("failed to convert to field element")
+        });
+        self.fillers.push(Box::new(move |values, witness| {
+            let rows = xor::create_xor_witness(values[left.0], values[right.0], bits);
+            copy_rows(witness, row, &rows);
+        }));
+        self.cells[left.0].push((row, 0));
+        self.cells[right.0].push((row, 1));
+        self.cells[var.0].push((row, 2));
+        var
+    }
+
+    /// Constrains `var` to have at most `bits` bits, in the gates of
+    /// [CircuitGate::create_range_check_bits]
+    pub fn range_check(&mut self, var: Var, bits: usize) {
+        let mut row = self.gates.len();
+        let start_row = row;
+        CircuitGate::extend_range_check_bits(&mut self.gates, &mut row, bits);
+
+        self.fillers.push(Box::new(move |values, witness| {
+            let rows = range_check::witness::create_bits(values[var.0], bits);
+            copy_rows(witness, start_row, &rows);
+        }));
+        self.cells[var.0].push((start_row, 0));
+    }
+
+    /// Constrains new variables `low` and `high` to be such that
+    /// `var = low + 2^bits * high`, with `low` computed as the `bits` low bits
+    /// of `var`. Their sizes are not constrained, see [Self::range_check].
+    pub fn split(&mut self, var: Var, bits: usize) -> (Var, Var) {
+        let low = self.var();
+        let high = self.var();
+        self.compute(low, move |values| {
+            let value = values[var.0].to_biguint();
+            (value % (BigUint::from(1u8) << bits))
+                .to_field()
+                .expect("failed to convert to field element")
+        });
+        self.compute(high, move |values| {
+            (values[var.0].to_biguint() >> bits)
+                .to_field()
+                .expect("failed to convert to field element")
+        });
+        let two_to_bits = F::from(2u64).pow([bits as u64]);
+        self.generic(
+            [F::one(), -F::one(), -two_to_bits, F::zero(), F::zero()],
+            [Some(var), Some(low), Some(high)],
+        );
+        (low, high)
+    }
+
+    /// Constrains new variables to be the Poseidon permutation of `input`
+    /// with the parameters `params`, in the gates of
+    /// [CircuitGate::create_poseidon_gadget]
+    pub fn poseidon(
+        &mut self,
+        params: &'static ArithmeticSpongeParams<F>,
+        input: [Var; SPONGE_WIDTH],
+    ) -> [Var; SPONGE_WIDTH] {
+        let row = self.gates.len();
+        let last_row = row + POS_ROWS_PER_HASH;
+        let (gates, _) = CircuitGate::create_poseidon_gadget(
+            row,
+            [Wire::for_row(row), Wire::for_row(last_row)],
+            &params.round_constants,
+        );
+        self.gates.extend(gates);
+
+        let output = array::from_fn(|_| self.var());
+        self.fillers.push(Box::new(move |values, witness| {
+            let input = input.map(|var| values[var.0]);
+            poseidon::generate_witness(row, params, witness, input);
+            for (col, var) in output.iter().enumerate() {
+                values[var.0] = witness[col][last_row];
+            }
+        }));
+        for (col, (input, output)) in input.iter().zip(&output).enumerate() {
+            self.cells[input.0].push((row, col));
+            self.cells[output.0].push((last_row, col));
+        }
+        output
+    }
+
+    /// Returns the gates of the circuit, its number of public inputs, and a
+    /// closure computing its witness from the values of its public and
+    /// private inputs, in the order of their declaration. The accesses to the
+    /// memories are checked in the last gates.
+    pub fn build(
+        mut self,
+    ) -> (
+        Vec<CircuitGate<F>>,
+        usize,
+        impl Fn(&[F], &[F]) -> [Vec<F>; COLUMNS],
+    ) {
+        self.check_memories();
+        for cells in &self.cells {
+            for cell in cells.iter().skip(1) {
+                self.gates.connect_cell_pair(cells[0], *cell);
+            }
+        }
+
+        let rows = self.gates.len();
+        let vars = self.cells.len();
+        let public = self.public_inputs.len();
+        let public_inputs = self.public_inputs;
+        let private_inputs = self.private_inputs;
+        let fillers = self.fillers;
+        let witness = move |public: &[F], private: &[F]| {
+            assert_eq!(public.len(), public_inputs.len());
+            assert_eq!(private.len(), private_inputs.len());
+            let mut values = vec![F::zero(); vars];
+            for (var, value) in public_inputs.iter().zip(public) {
+                values[var.0] = *value;
+            }
+            for (var, value) in private_inputs.iter().zip(private) {
+                values[var.0] = *value;
+            }
+
+            let mut witness: [Vec<F>; COLUMNS] = array::from_fn(|_| vec![F::zero(); rows]);
+            for filler in &fillers {
+                filler(&mut values, &mut witness);
+            }
+            witness
+        };
+
+        (self.gates, public, witness)
+    }
+
+    /// Declares a new variable, without any cell
+    fn var(&mut self) -> Var {
+        self.cells.push(vec![]);
+        Var(self.cells.len() - 1)
+    }
+
+    /// Computes the value of `var` from the values of the previous variables
+    fn compute(&mut self, var: Var, f: impl Fn(&[F]) -> F + 'static) {
+        self.fillers.push(Box::new(move |values, _| {
+            values[var.0] = f(values);
+        }));
+    }
+
+    /// Puts `var` in the cell `cell`
+    fn place(&mut self, var: Var, cell: Cell) {
+        self.cells[var.0].push(cell);
+        self.fillers.push(Box::new(move |values, witness| {
+            witness[cell.1][cell.0] = values[var.0];
+        }));
+    }
+
+    /// Lays out a generic gate of coefficients `coeffs` on the variables
+    /// `vars` (left, right and output), in the free half of the last generic
+    /// row if there is one
+    fn generic(&mut self, coeffs: [F; GENERIC_COEFFS], vars: [Option<Var>; 3]) {
+        let (row, offset) = match self.free_generic.take() {
+            Some(row) => (row, GENERIC_COEFFS),
+            None => {
+                let row = self.gates.len();
+                self.gates.push(CircuitGate::create_generic(
+                    Wire::for_row(row),
+                    [F::zero(); GENERIC_COEFFS * 2],
+                ));
+                self.free_generic = Some(row);
+                (row, 0)
+            }
+        };
+        debug_assert_eq!(self.gates[row].typ, GateType::Generic);
+        self.gates[row].coeffs[offset..offset + GENERIC_COEFFS].copy_from_slice(&coeffs);
+
+        let first_col = if offset == 0 { 0 } else { 3 };
+        for (col, var) in vars.into_iter().enumerate() {
+            if let Some(var) = var {
+                self.place(var, (row, first_col + col));
+            }
+        }
+    }
+}
+
+/// Copies the rows `rows` of a gadget into `witness`, from the row `row`
+fn copy_rows<F: PrimeField>(witness: &mut [Vec<F>; COLUMNS], row: usize, rows: &[Vec<F>; COLUMNS]) {
+    for (col, values) in witness.iter_mut().zip(rows) {
+        col[row..row + values.len()].copy_from_slice(values);
+    }
+}
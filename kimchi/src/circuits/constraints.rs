@@ -4,14 +4,14 @@ use crate::{
     circuits::{
         domain_constant_evaluation::DomainConstantEvaluations,
         domains::EvaluationDomains,
-        gate::{CircuitGate, GateType},
+        gate::{CircuitGate, CircuitGateError, GateType},
         lookup::{
             index::{LookupConstraintSystem, LookupError},
             lookups::{LookupFeatures, LookupPatterns},
             tables::{GateLookupTables, LookupTable},
         },
         polynomial::{WitnessEvals, WitnessOverDomains, WitnessShifts},
-        polynomials::permutation::Shifts,
+        polynomials::{permutation::Shifts, poseidon2, turshi},
         wires::*,
     },
     curve::KimchiCurve,
@@ -31,6 +31,7 @@ use rayon::prelude::*;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::serde_as;
 use std::sync::Arc;
+use thiserror::Error;
 
 //
 // ConstraintSystem
@@ -58,6 +59,12 @@ pub struct FeatureFlags {
     pub rot: bool,
     /// Lookup features
     pub lookup_features: LookupFeatures,
+    /// Cairo gates
+    #[serde(default)]
+    pub cairo: bool,
+    /// Poseidon2 gates
+    #[serde(default)]
+    pub poseidon2: bool,
 }
 
 impl Default for FeatureFlags {
@@ -72,14 +79,18 @@ impl Default for FeatureFlags {
                     lookup: false,
                     range_check: false,
                     foreign_field_mul: false,
+                    vector_lookup: false,
                 },
                 joint_lookup_used: false,
                 uses_runtime_tables: false,
+                log_up: false,
             },
             foreign_field_add: false,
             foreign_field_mul: false,
             xor: false,
             rot: false,
+            cairo: false,
+            poseidon2: false,
         }
     }
 }
@@ -165,6 +176,17 @@ pub struct ColumnEvaluations<F: PrimeField> {
     /// Rot gate selector over domain d8
     #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
     pub rot_selector8: Option<E<F, D<F>>>,
+
+    /// Cairo gate selectors over domain d8, in the order claim, instruction,
+    /// flags and transition
+    #[serde_as(as = "Option<[o1_utils::serialization::SerdeAs; 4]>")]
+    pub cairo_selectors8: Option<[E<F, D<F>>; 4]>,
+
+    /// Poseidon2 gate selectors over domain d8, in the order external and
+    /// internal rounds
+    #[serde(default)]
+    #[serde_as(as = "Option<[o1_utils::serialization::SerdeAs; 2]>")]
+    pub poseidon2_selectors8: Option<[E<F, D<F>>; 2]>,
 }
 
 #[serde_as]
@@ -207,6 +229,9 @@ pub struct ConstraintSystem<F: PrimeField> {
 
     /// Disable gates checks (for testing; only enables with development builds)
     pub disable_gates_checks: bool,
+
+    /// The zero-knowledge level of the proofs, see [ZkLevel]
+    pub zk_level: ZkLevel,
 }
 
 pub(crate) type LookupConstraintSystemCache<F> =
@@ -243,6 +268,8 @@ where
             #[serde(bound = "LookupConstraintSystem<F>: Serialize + DeserializeOwned")]
             lookup_constraint_system: Arc<LookupConstraintSystemCache<F>>,
             disable_gates_checks: bool,
+            #[serde(default)]
+            zk_level: ZkLevel,
         }
 
         // This is to avoid implementing a default value for LazyCache
@@ -266,20 +293,59 @@ where
             endo: cs.endo,
             lookup_constraint_system: cs.lookup_constraint_system,
             disable_gates_checks: cs.disable_gates_checks,
+            zk_level: cs.zk_level,
             precomputations,
         })
     }
 }
 
+/// A constraint of a gate that is not satisfied by the witness
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintFailure<F> {
+    /// The row of the gate
+    pub row: usize,
+    /// The type of the gate
+    pub typ: GateType,
+    /// The index of the constraint within the gate, starting from 1 as in
+    /// [CircuitGateError::Constraint]
+    pub constraint: usize,
+    /// The left and right values of the constraint, which should be equal,
+    /// when the check knows them
+    pub values: Option<(F, F)>,
+}
+
+impl<F: core::fmt::Display> core::fmt::Display for ConstraintFailure<F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "row {}: constraint {} of the {:?} gate is not satisfied",
+            self.row, self.constraint, self.typ
+        )?;
+        if let Some((left, right)) = &self.values {
+            write!(f, " (left: {left}, right: {right})")?;
+        }
+        Ok(())
+    }
+}
+
 /// Represents an error found when verifying a witness with a gate
-#[derive(Debug)]
-pub enum GateError {
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum GateError<F: core::fmt::Display> {
     /// Some connected wires have different values
+    #[error("row {}: cell ({}, {}) is wired to cell ({}, {}) which has a different value", .0.row, .0.row, .0.col, .1.row, .1.col)]
     DisconnectedWires(Wire, Wire),
     /// A public gate was incorrectly connected
+    #[error("row {0}: the public input gate is incorrectly configured")]
     IncorrectPublic(usize),
-    /// A specific gate did not verify correctly
-    Custom { row: usize, err: String },
+    /// A wire is connected to a column that is not part of the permutation
+    #[error("row {row}: a wire can only be connected to the first {PERMUTS} columns, not to column {col}")]
+    WireColumn { row: usize, col: usize },
+    /// A constraint of a gate is not satisfied
+    #[error("{0}")]
+    Constraint(ConstraintFailure<F>),
+    /// A gate could not be checked
+    #[error("row {row}: {err}")]
+    Gate { row: usize, err: CircuitGateError },
 }
 
 pub struct Builder<F: PrimeField> {
@@ -292,6 +358,8 @@ pub struct Builder<F: PrimeField> {
     disable_gates_checks: bool,
     max_poly_size: Option<usize>,
     lazy_mode: bool,
+    log_up: bool,
+    zk_level: ZkLevel,
 }
 
 /// Create selector polynomial for a circuit gate
@@ -336,10 +404,11 @@ impl<F: PrimeField> ConstraintSystem<F> {
     /// - `precomputations: None`,
     /// - `disable_gates_checks: false`,
     /// - `lazy_mode: false`,
+    /// - `log_up: false`,
     ///
     /// How to use it:
     /// 1. Create your instance of your builder for the constraint system using `crate(gates, sponge params)`
-    /// 2. Iterativelly invoke any desired number of steps: `public(), lookup(), runtime(), precomputations(), lazy_mode()`
+    /// 2. Iterativelly invoke any desired number of steps: `public(), lookup(), runtime(), precomputations(), lazy_mode(), log_up()`
     /// 3. Finally call the `build()` method and unwrap the `Result` to obtain your `ConstraintSystem`
     pub fn create(gates: Vec<CircuitGate<F>>) -> Builder<F> {
         Builder {
@@ -352,6 +421,8 @@ impl<F: PrimeField> ConstraintSystem<F> {
             disable_gates_checks: false,
             max_poly_size: None,
             lazy_mode: false,
+            log_up: false,
+            zk_level: ZkLevel::Full,
         }
     }
 
@@ -359,6 +430,14 @@ impl<F: PrimeField> ConstraintSystem<F> {
         self.precomputations.get().clone()
     }
 
+    /// Returns the precomputations if they have already been computed, see
+    /// [Self::precomputations]
+    pub(crate) fn cached_precomputations(&self) -> Option<Arc<DomainConstantEvaluations<F>>> {
+        self.precomputations
+            .is_initialized()
+            .then(|| self.precomputations())
+    }
+
     /// test helpers
     pub fn for_testing(gates: Vec<CircuitGate<F>>) -> Self {
         let public = 0;
@@ -381,7 +460,7 @@ impl<F: PrimeField, G: KimchiCurve<ScalarField = F>, OpeningProof: OpenProof<G>>
     /// assignments (witness) against the constraints
     ///     witness: wire assignment witness
     ///     RETURN: verification status
-    pub fn verify(&self, witness: &[Vec<F>; COLUMNS], public: &[F]) -> Result<(), GateError> {
+    pub fn verify(&self, witness: &[Vec<F>; COLUMNS], public: &[F]) -> Result<(), GateError<F>> {
         // pad the witness
         let pad = vec![F::zero(); self.cs.domain.d1.size() - witness[0].len()];
         let witness: [Vec<F>; COLUMNS] = array::from_fn(|i| {
@@ -397,10 +476,7 @@ impl<F: PrimeField, G: KimchiCurve<ScalarField = F>, OpeningProof: OpenProof<G>>
                 let wire = gate.wires[col];
 
                 if wire.col >= PERMUTS {
-                    return Err(GateError::Custom {
-                        row,
-                        err: format!("a wire can only be connected to the first {PERMUTS} columns"),
-                    });
+                    return Err(GateError::WireColumn { row, col: wire.col });
                 }
 
                 if witness[col][row] != witness[wire.col][wire.row] {
@@ -420,8 +496,7 @@ impl<F: PrimeField, G: KimchiCurve<ScalarField = F>, OpeningProof: OpenProof<G>>
             }
 
             // check the gate's satisfiability
-            gate.verify(row, &witness, self, public)
-                .map_err(|err| GateError::Custom { row, err })?;
+            gate.verify(row, &witness, self, public)?;
         }
 
         // all good!
@@ -430,19 +505,24 @@ impl<F: PrimeField, G: KimchiCurve<ScalarField = F>, OpeningProof: OpenProof<G>>
 }
 
 impl<F: PrimeField> ConstraintSystem<F> {
+    /// Evaluates the witness polynomials and z over d8, in parallel
+    fn evaluate_d8(&self, w: &[DP<F>; COLUMNS], z: &DP<F>) -> WitnessEvals<F> {
+        let mut res = w
+            .par_iter()
+            .chain(rayon::iter::once(z))
+            .map(|elem| elem.evaluate_over_domain_by_ref(self.domain.d8))
+            .collect::<Vec<_>>();
+        let z = res.pop().unwrap();
+        WitnessEvals {
+            w: res.try_into().unwrap(),
+            z,
+        }
+    }
+
     /// evaluate witness polynomials over domains
     pub fn evaluate(&self, w: &[DP<F>; COLUMNS], z: &DP<F>) -> WitnessOverDomains<F> {
         // compute shifted witness polynomials and z8, all in parallel
-        let (w8, z8): ([E<F, D<F>>; COLUMNS], _) = {
-            let mut res = w
-                .par_iter()
-                .chain(rayon::iter::once(z))
-                .map(|elem| elem.evaluate_over_domain_by_ref(self.domain.d8))
-                .collect::<Vec<_>>();
-            let z8 = res[COLUMNS].clone();
-            res.truncate(COLUMNS);
-            (res.try_into().unwrap(), z8)
-        };
+        let WitnessEvals { w: w8, z: z8 } = self.evaluate_d8(w, z);
 
         let w4: [E<F, D<F>>; COLUMNS] = (0..COLUMNS)
             .into_par_iter()
@@ -497,6 +577,36 @@ impl<F: PrimeField> ConstraintSystem<F> {
         }
     }
 
+    /// Evaluates the witness polynomials over the domains read by the
+    /// quotient of the prover in [crate::prover_index::ProverMemoryMode::Low]:
+    /// the witness and z over d8, and z shifted to the next rows. The
+    /// evaluations over d4, and of the witness shifted to the next rows, are
+    /// dummy evaluations.
+    pub fn evaluate_low_memory(&self, w: &[DP<F>; COLUMNS], z: &DP<F>) -> WitnessOverDomains<F> {
+        let this = self.evaluate_d8(w, z);
+        let z8_shift8 = this.z.shift(8);
+
+        let dummy = || DP::<F>::zero().evaluate_over_domain_by_ref(D::<F>::new(1).unwrap());
+        let dummy_evals = || WitnessEvals {
+            w: array::from_fn(|_| dummy()),
+            z: dummy(),
+        };
+
+        WitnessOverDomains {
+            d4: WitnessShifts {
+                next: dummy_evals(),
+                this: dummy_evals(),
+            },
+            d8: WitnessShifts {
+                next: WitnessEvals {
+                    w: array::from_fn(|_| dummy()),
+                    z: z8_shift8,
+                },
+                this,
+            },
+        }
+    }
+
     pub(crate) fn evaluated_column_coefficients(&self) -> EvaluatedColumnCoefficients<F> {
         // compute permutation polynomials
         let shifts = Shifts::new(&self.domain.d1);
@@ -715,6 +825,38 @@ impl<F: PrimeField> ConstraintSystem<F> {
             }
         };
 
+        let cairo_selectors8 = {
+            if !self.feature_flags.cairo {
+                None
+            } else {
+                Some(turshi::circuit_gates().map(|gate_type| {
+                    selector_polynomial(
+                        gate_type,
+                        &self.gates,
+                        &self.domain,
+                        &self.domain.d8,
+                        self.disable_gates_checks,
+                    )
+                }))
+            }
+        };
+
+        let poseidon2_selectors8 = {
+            if !self.feature_flags.poseidon2 {
+                None
+            } else {
+                Some(poseidon2::circuit_gates().map(|gate_type| {
+                    selector_polynomial(
+                        gate_type,
+                        &self.gates,
+                        &self.domain,
+                        &self.domain.d8,
+                        self.disable_gates_checks,
+                    )
+                }))
+            }
+        };
+
         // TODO: This doesn't need to be degree 8 but that would require some changes in expr
         let coefficients8 = array::from_fn(|i| {
             evaluated_column_coefficients.coefficients[i]
@@ -736,6 +878,8 @@ impl<F: PrimeField> ConstraintSystem<F> {
             foreign_field_mul_selector8,
             xor_selector8,
             rot_selector8,
+            cairo_selectors8,
+            poseidon2_selectors8,
         }
     }
 }
@@ -743,9 +887,50 @@ impl<F: PrimeField> ConstraintSystem<F> {
 /// The default number of chunks in a circuit is one (< 2^16 rows)
 pub const NUM_CHUNKS_BY_DEFAULT: usize = 1;
 
+/// The number of chunks of the polynomials of a circuit over a domain of
+/// `domain_size` rows, committed with an SRS of `max_poly_size` points: the
+/// commitments of polynomials larger than the SRS are split in as many chunks
+pub fn num_chunks(domain_size: usize, max_poly_size: usize) -> usize {
+    if domain_size < max_poly_size {
+        NUM_CHUNKS_BY_DEFAULT
+    } else {
+        domain_size / max_poly_size
+    }
+}
+
 /// The number of rows required for zero knowledge in circuits with one single chunk
 pub const ZK_ROWS_BY_DEFAULT: u64 = 3;
 
+/// The number of rows reserved at the end of the domain of circuits whose
+/// proofs are not zero-knowledge, see [ZkLevel::Disabled]. The permutation
+/// argument ends one row before the end of the domain.
+pub const ZK_ROWS_DISABLED: u64 = 1;
+
+/// The zero-knowledge level of the proofs of a circuit, set with
+/// [Builder::zk_level] and recorded in its verifier index
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ZkLevel {
+    /// The proofs hide the witness, with the minimal number of
+    /// zero-knowledge rows for the number of chunks of the circuit
+    #[default]
+    Full,
+    /// The proofs hide the witness, with the given number of zero-knowledge
+    /// rows, which must be above [zk_rows_strict_lower_bound]
+    Rows(u64),
+    /// The proofs only prove the integrity of the computation and do not hide
+    /// the witness: the witness, the permutation aggregation and the quotient
+    /// are committed without blinding, and only [ZK_ROWS_DISABLED] rows are
+    /// reserved at the end of the domain
+    Disabled,
+}
+
+impl ZkLevel {
+    /// Whether the proofs hide the witness
+    pub fn is_hiding(&self) -> bool {
+        !matches!(self, ZkLevel::Disabled)
+    }
+}
+
 /// This function computes a strict lower bound in the number of rows required
 /// for zero knowledge in circuits with `num_chunks` chunks. This means that at
 /// least one needs 1 more row than the result of this function to achieve zero
@@ -773,6 +958,8 @@ impl FeatureFlags {
             foreign_field_mul: false,
             xor: false,
             rot: false,
+            cairo: false,
+            poseidon2: false,
         };
 
         for gate in gates {
@@ -783,6 +970,13 @@ impl FeatureFlags {
                 GateType::ForeignFieldMul => feature_flags.foreign_field_mul = true,
                 GateType::Xor16 => feature_flags.xor = true,
                 GateType::Rot64 => feature_flags.rot = true,
+                GateType::CairoClaim
+                | GateType::CairoInstruction
+                | GateType::CairoFlags
+                | GateType::CairoTransition => feature_flags.cairo = true,
+                GateType::Poseidon2External | GateType::Poseidon2Internal => {
+                    feature_flags.poseidon2 = true
+                }
                 _ => (),
             }
         }
@@ -829,6 +1023,16 @@ impl<F: PrimeField> Builder<F> {
         self
     }
 
+    /// Adds the fixed lookup table `lookup_table`, for instance built with
+    /// [LookupTable::new] or read with [LookupTable::from_file], to the ones
+    /// of the circuit. Its commitment is part of the verifier index.
+    ///
+    /// The same warning as for [Builder::lookup] applies to its ID.
+    pub fn lookup_table(mut self, lookup_table: LookupTable<F>) -> Self {
+        self.lookup_tables.push(lookup_table);
+        self
+    }
+
     /// Set up the runtime tables.
     /// If not invoked, it is `None` by default.
     ///
@@ -867,6 +1071,29 @@ impl<F: PrimeField> Builder<F> {
         self
     }
 
+    /// Set up the zero-knowledge level of the proofs, see [ZkLevel].
+    /// If not invoked, it is [ZkLevel::Full] by default.
+    ///
+    /// [ZkLevel::Disabled] saves the blinding of the commitments and the
+    /// zero-knowledge rows, which may give a smaller domain, for circuits that
+    /// only need succinctness.
+    pub fn zk_level(mut self, zk_level: ZkLevel) -> Self {
+        self.zk_level = zk_level;
+        self
+    }
+
+    /// Set up the lookup argument: the logarithmic-derivative argument
+    /// (logUp) if `true`, or plookup.
+    /// If not invoked, it is `false` by default.
+    ///
+    /// logUp commits to a single column of multiplicities instead of the
+    /// `max_per_row + 1` sorted columns of plookup, see
+    /// [crate::circuits::lookup::constraints].
+    pub fn log_up(mut self, log_up: bool) -> Self {
+        self.log_up = log_up;
+        self
+    }
+
     /// Build the [ConstraintSystem] from a [Builder].
     pub fn build(self) -> Result<ConstraintSystem<F>, SetupError> {
         let mut gates = self.gates;
@@ -877,7 +1104,8 @@ impl<F: PrimeField> Builder<F> {
         // for some reason we need more than 1 gate for the circuit to work, see TODO below
         assert!(gates.len() > 1);
 
-        let feature_flags = FeatureFlags::from_gates(&gates, runtime_tables.is_some());
+        let mut feature_flags = FeatureFlags::from_gates(&gates, runtime_tables.is_some());
+        feature_flags.lookup_features.log_up = self.log_up;
 
         let lookup_domain_size = {
             // First we sum over the lookup table size
@@ -957,31 +1185,58 @@ impl<F: PrimeField> Builder<F> {
             // degree n + 1 where n is the order of the subgroup H).
             let circuit_lower_bound = core::cmp::max(gates.len(), lookup_domain_size + 1);
             let get_domain_size_lower_bound = |zk_rows: u64| circuit_lower_bound + zk_rows as usize;
+            // the size of the domain and the number of chunks of a circuit
+            // of `domain_size_lower_bound` rows
+            let get_domain_size_and_chunks = |domain_size_lower_bound: usize| {
+                let domain_size = D::<F>::compute_size_of_domain(domain_size_lower_bound).ok_or(
+                    SetupError::DomainCreation(DomainCreationError::DomainSizeFailed(
+                        domain_size_lower_bound,
+                    )),
+                )?;
+                let num_chunks = match self.max_poly_size {
+                    Some(max_poly_size) => num_chunks(domain_size, max_poly_size),
+                    None => NUM_CHUNKS_BY_DEFAULT,
+                };
+                Ok::<_, SetupError>((domain_size, num_chunks))
+            };
 
-            let mut zk_rows = 3;
-            let mut domain_size_lower_bound = get_domain_size_lower_bound(zk_rows);
-            if let Some(max_poly_size) = self.max_poly_size {
-                // Iterate to find a fixed-point where zk_rows is sufficient for the number of
-                // chunks that we use, and also does not cause us to overflow the domain size.
-                // NB: We use iteration here rather than hard-coding an assumption about
-                // `compute_size_of_domain`s internals. In practice, this will never be executed
-                // more than once.
-                while {
-                    let domain_size = D::<F>::compute_size_of_domain(domain_size_lower_bound)
-                        .ok_or(SetupError::DomainCreation(
-                            DomainCreationError::DomainSizeFailed(domain_size_lower_bound),
-                        ))?;
-                    let num_chunks = if domain_size < max_poly_size {
-                        1
-                    } else {
-                        domain_size / max_poly_size
-                    };
-                    zk_rows = (zk_rows_strict_lower_bound(num_chunks) + 1) as u64;
-                    domain_size_lower_bound = get_domain_size_lower_bound(zk_rows);
-                    domain_size < domain_size_lower_bound
-                } {}
+            match self.zk_level {
+                ZkLevel::Full => {
+                    let mut zk_rows = ZK_ROWS_BY_DEFAULT;
+                    let mut domain_size_lower_bound = get_domain_size_lower_bound(zk_rows);
+                    if self.max_poly_size.is_some() {
+                        // Iterate to find a fixed-point where zk_rows is sufficient for the number of
+                        // chunks that we use, and also does not cause us to overflow the domain size.
+                        // NB: We use iteration here rather than hard-coding an assumption about
+                        // `compute_size_of_domain`s internals. In practice, this will never be executed
+                        // more than once.
+                        while {
+                            let (domain_size, num_chunks) =
+                                get_domain_size_and_chunks(domain_size_lower_bound)?;
+                            zk_rows = (zk_rows_strict_lower_bound(num_chunks) + 1) as u64;
+                            domain_size_lower_bound = get_domain_size_lower_bound(zk_rows);
+                            domain_size < domain_size_lower_bound
+                        } {}
+                    }
+                    (zk_rows, domain_size_lower_bound)
+                }
+                ZkLevel::Rows(zk_rows) => {
+                    let domain_size_lower_bound = get_domain_size_lower_bound(zk_rows);
+                    let (_, num_chunks) = get_domain_size_and_chunks(domain_size_lower_bound)?;
+                    let zero_knowledge_limit = zk_rows_strict_lower_bound(num_chunks);
+                    if zk_rows as usize <= zero_knowledge_limit {
+                        return Err(SetupError::ConstraintSystem(format!(
+                            "{zk_rows} zero-knowledge rows are not enough for {num_chunks} chunks (expected at least {})",
+                            zero_knowledge_limit + 1
+                        )));
+                    }
+                    (zk_rows, domain_size_lower_bound)
+                }
+                ZkLevel::Disabled => (
+                    ZK_ROWS_DISABLED,
+                    get_domain_size_lower_bound(ZK_ROWS_DISABLED),
+                ),
             }
-            (zk_rows, domain_size_lower_bound)
         };
 
         //~ 1. Create a domain for the circuit. That is,
@@ -1019,6 +1274,7 @@ impl<F: PrimeField> Builder<F> {
                 self.runtime_tables,
                 &domain,
                 zk_rows as usize,
+                self.log_up,
             )
         });
         if !self.lazy_mode {
@@ -1060,6 +1316,7 @@ impl<F: PrimeField> Builder<F> {
             feature_flags,
             precomputations: Arc::new(precomputations),
             disable_gates_checks: self.disable_gates_checks,
+            zk_level: self.zk_level,
         };
 
         Ok(constraints)
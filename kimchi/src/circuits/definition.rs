@@ -0,0 +1,162 @@
+//! This module implements a versioned format for the definition of a circuit.
+//!
+//! A [CircuitDefinition] holds all that is needed to build the
+//! [ConstraintSystem] of a circuit: its gates with their coefficients and
+//! wiring, its number of public inputs and of previous challenges, the lookup
+//! tables and runtime tables it registers, and its lookup argument. It can be
+//! written in two forms:
+//!
+//! * a binary form, made of the magic bytes [CIRCUIT_MAGIC], the version of
+//!   the format [CIRCUIT_FORMAT_VERSION] as a little-endian `u32`, and the
+//!   MessagePack encoding of the definition,
+//! * a JSON form for debugging and diffing, an object holding the `version`
+//!   of the format and the `circuit`, whose field elements are written in
+//!   hexadecimal.
+//!
+//! Both forms are read back only if their version is the current one.
+
+use crate::circuits::{
+    constraints::{Builder, ConstraintSystem},
+    gate::CircuitGate,
+    lookup::{runtime_tables::RuntimeTableCfg, tables::LookupTable},
+};
+use ark_ff::PrimeField;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use thiserror::Error;
+
+/// The magic bytes starting the binary form of a [CircuitDefinition]
+pub const CIRCUIT_MAGIC: [u8; 4] = *b"KCRC";
+
+/// The version of the format of a [CircuitDefinition], increased on each
+/// change of its fields
+pub const CIRCUIT_FORMAT_VERSION: u32 = 1;
+
+/// Errors that can arise when reading or writing a [CircuitDefinition]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CircuitFormatError {
+    #[error("the data does not start with the magic bytes of a circuit")]
+    InvalidMagic,
+
+    #[error("the format version {0} is not supported (expected: {CIRCUIT_FORMAT_VERSION})")]
+    UnsupportedVersion(u32),
+
+    #[error("the circuit could not be encoded: {0}")]
+    Encoding(String),
+
+    #[error("the circuit could not be decoded: {0}")]
+    Decoding(String),
+}
+
+/// The definition of a circuit, see [self]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct CircuitDefinition<F: PrimeField> {
+    /// The number of public inputs, in the first rows of the circuit
+    pub public_input_size: usize,
+    /// The number of previous challenges of the recursion
+    pub prev_challenges: usize,
+    /// The gates of the circuit
+    pub gates: Vec<CircuitGate<F>>,
+    /// The lookup tables registered with [Builder::lookup]
+    pub lookup_tables: Vec<LookupTable<F>>,
+    /// The runtime tables registered with [Builder::runtime]
+    pub runtime_tables: Option<Vec<RuntimeTableCfg<F>>>,
+    /// Whether the lookup argument is logUp, see [Builder::log_up]
+    pub log_up: bool,
+}
+
+/// The JSON form of a [CircuitDefinition]
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "C: Serialize", deserialize = "C: DeserializeOwned"))]
+struct VersionedCircuit<C> {
+    version: u32,
+    circuit: C,
+}
+
+impl<F: PrimeField> CircuitDefinition<F> {
+    /// Creates the definition of a circuit of gates `gates`, with
+    /// `public_input_size` public inputs, and without lookup table
+    pub fn new(public_input_size: usize, gates: Vec<CircuitGate<F>>) -> Self {
+        Self {
+            public_input_size,
+            prev_challenges: 0,
+            gates,
+            lookup_tables: vec![],
+            runtime_tables: None,
+            log_up: false,
+        }
+    }
+
+    /// Returns a [Builder] of the constraint system of the circuit
+    pub fn constraint_system(&self) -> Builder<F> {
+        ConstraintSystem::create(self.gates.clone())
+            .public(self.public_input_size)
+            .prev_challenges(self.prev_challenges)
+            .lookup(self.lookup_tables.clone())
+            .runtime(self.runtime_tables.clone())
+            .log_up(self.log_up)
+    }
+
+    /// Writes the binary form of the definition
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the definition fails to be encoded.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, CircuitFormatError> {
+        let mut bytes = CIRCUIT_MAGIC.to_vec();
+        bytes.extend(CIRCUIT_FORMAT_VERSION.to_le_bytes());
+        rmp_serde::encode::write(&mut bytes, self)
+            .map_err(|e| CircuitFormatError::Encoding(e.to_string()))?;
+        Ok(bytes)
+    }
+
+    /// Reads a definition from its binary form
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `bytes` does not start with [CIRCUIT_MAGIC], if its
+    /// version is not [CIRCUIT_FORMAT_VERSION], or if the definition fails to
+    /// be decoded.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CircuitFormatError> {
+        let bytes = bytes
+            .strip_prefix(&CIRCUIT_MAGIC)
+            .ok_or(CircuitFormatError::InvalidMagic)?;
+        let (version, bytes) = bytes
+            .split_first_chunk::<4>()
+            .ok_or_else(|| CircuitFormatError::Decoding("missing version".to_string()))?;
+        let version = u32::from_le_bytes(*version);
+        if version != CIRCUIT_FORMAT_VERSION {
+            return Err(CircuitFormatError::UnsupportedVersion(version));
+        }
+        rmp_serde::from_slice(bytes).map_err(|e| CircuitFormatError::Decoding(e.to_string()))
+    }
+
+    /// Writes the JSON form of the definition
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the definition fails to be encoded.
+    pub fn to_json(&self) -> Result<String, CircuitFormatError> {
+        serde_json::to_string_pretty(&VersionedCircuit {
+            version: CIRCUIT_FORMAT_VERSION,
+            circuit: self,
+        })
+        .map_err(|e| CircuitFormatError::Encoding(e.to_string()))
+    }
+
+    /// Reads a definition from its JSON form
+    ///
+    /// # Errors
+    ///
+    /// Will give error if its version is not [CIRCUIT_FORMAT_VERSION], or if
+    /// the definition fails to be decoded.
+    pub fn from_json(json: &str) -> Result<Self, CircuitFormatError> {
+        let versioned: VersionedCircuit<serde_json::Value> =
+            serde_json::from_str(json).map_err(|e| CircuitFormatError::Decoding(e.to_string()))?;
+        if versioned.version != CIRCUIT_FORMAT_VERSION {
+            return Err(CircuitFormatError::UnsupportedVersion(versioned.version));
+        }
+        serde_json::from_value(versioned.circuit)
+            .map_err(|e| CircuitFormatError::Decoding(e.to_string()))
+    }
+}
@@ -4,10 +4,10 @@ use crate::{
     circuits::{
         argument::{Argument, ArgumentEnv},
         berkeley_columns::BerkeleyChallenges,
-        constraints::ConstraintSystem,
+        constraints::{ConstraintFailure, ConstraintSystem, GateError},
         polynomials::{
             complete_add, endomul_scalar, endosclmul, foreign_field_add, foreign_field_mul,
-            poseidon, range_check, rot, turshi, varbasemul, xor,
+            poseidon, poseidon2, range_check, rot, turshi, varbasemul, xor,
         },
         wires::*,
     },
@@ -96,6 +96,11 @@ pub enum GateType {
     // Gates for Keccak
     Xor16,
     Rot64,
+    /// Lookup of vectors of up to 4 values
+    VectorLookup,
+    /// Poseidon2 permutation gates
+    Poseidon2External,
+    Poseidon2Internal,
 }
 
 /// Gate error
@@ -125,7 +130,7 @@ pub enum CircuitGateError {
 pub type CircuitGateResult<T> = core::result::Result<T, CircuitGateError>;
 
 #[serde_as]
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 /// A single gate in a circuit.
 pub struct CircuitGate<F: PrimeField> {
     /// type of the gate
@@ -159,44 +164,39 @@ impl<F: PrimeField> CircuitGate<F> {
     ///
     /// # Errors
     ///
-    /// Will give error if verify process returns error.
+    /// Will give error pinpointing the first constraint of the gate that the
+    /// witness does not satisfy.
     pub fn verify<G: KimchiCurve<ScalarField = F>, OpeningProof: OpenProof<G>>(
         &self,
         row: usize,
         witness: &[Vec<F>; COLUMNS],
         index: &ProverIndex<G, OpeningProof>,
         public: &[F],
-    ) -> Result<(), String> {
+    ) -> Result<(), GateError<F>> {
         use GateType::*;
         match self.typ {
             Zero => Ok(()),
             Generic => self.verify_generic(row, witness, public),
-            Poseidon => self.verify_poseidon::<G>(row, witness),
-            CompleteAdd => self.verify_complete_add(row, witness),
-            VarBaseMul => self.verify_vbmul(row, witness),
-            EndoMul => self.verify_endomul::<G>(row, witness, &index.cs),
-            EndoMulScalar => self.verify_endomul_scalar::<G>(row, witness, &index.cs),
             // TODO: implement the verification for the lookup gate
             // See https://github.com/MinaProtocol/mina/issues/14011
-            Lookup => Ok(()),
-            CairoClaim | CairoInstruction | CairoFlags | CairoTransition => {
-                self.verify_cairo_gate::<G>(row, witness, &index.cs)
+            Lookup | VectorLookup => Ok(()),
+            Poseidon | CompleteAdd | VarBaseMul | EndoMul | EndoMulScalar | CairoClaim
+            | CairoInstruction | CairoFlags | CairoTransition | RangeCheck0 | RangeCheck1
+            | ForeignFieldAdd | ForeignFieldMul | Xor16 | Rot64 | Poseidon2External
+            | Poseidon2Internal => {
+                let results = self
+                    .constraint_evaluations::<G>(row, witness, &index.cs)
+                    .map_err(|err| GateError::Gate { row, err })?;
+                match results.into_iter().enumerate().find(|(_, r)| !r.is_zero()) {
+                    Some((i, result)) => Err(GateError::Constraint(ConstraintFailure {
+                        row,
+                        typ: self.typ,
+                        constraint: i + 1,
+                        values: Some((result, F::zero())),
+                    })),
+                    None => Ok(()),
+                }
             }
-            RangeCheck0 | RangeCheck1 => self
-                .verify_witness::<G>(row, witness, &index.cs, public)
-                .map_err(|e| e.to_string()),
-            ForeignFieldAdd => self
-                .verify_witness::<G>(row, witness, &index.cs, public)
-                .map_err(|e| e.to_string()),
-            ForeignFieldMul => self
-                .verify_witness::<G>(row, witness, &index.cs, public)
-                .map_err(|e| e.to_string()),
-            Xor16 => self
-                .verify_witness::<G>(row, witness, &index.cs, public)
-                .map_err(|e| e.to_string()),
-            Rot64 => self
-                .verify_witness::<G>(row, witness, &index.cs, public)
-                .map_err(|e| e.to_string()),
         }
     }
 
@@ -208,6 +208,52 @@ impl<F: PrimeField> CircuitGate<F> {
         cs: &ConstraintSystem<F>,
         _public: &[F],
     ) -> CircuitGateResult<()> {
+        // Check the wiring (i.e. copy constraints) for this gate
+        // Note: Gates can operated on row Curr or Curr and Next.
+        //       It could be nice for gates to know this and then
+        //       this code could be adapted to check Curr or Curr
+        //       and Next depending on the gate definition
+        for col in 0..PERMUTS {
+            let wire = self.wires[col];
+
+            if wire.col >= PERMUTS {
+                return Err(CircuitGateError::WireColumn(self.typ, col));
+            }
+
+            if witness[col][row] != witness[wire.col][wire.row] {
+                // Pinpoint failed copy constraint
+                return Err(CircuitGateError::CopyConstraint {
+                    typ: self.typ,
+                    src: Wire { row, col },
+                    dst: wire,
+                });
+            }
+        }
+
+        // Perform witness verification on each constraint for this gate
+        let results = self.constraint_evaluations::<G>(row, witness, cs)?;
+
+        // Check for failed constraints
+        for (i, result) in results.iter().enumerate() {
+            if !result.is_zero() {
+                // Pinpoint failed constraint
+                return Err(CircuitGateError::Constraint(self.typ, i + 1));
+            }
+        }
+
+        // TODO: implement generic plookup witness verification
+
+        Ok(())
+    }
+
+    /// Evaluates each constraint of this gate on the witness at the given
+    /// row, which are all zero when the witness satisfies the gate
+    pub(crate) fn constraint_evaluations<G: KimchiCurve<ScalarField = F>>(
+        &self,
+        row: usize,
+        witness: &[Vec<F>; COLUMNS],
+        cs: &ConstraintSystem<F>,
+    ) -> CircuitGateResult<Vec<F>> {
         // Grab the relevant part of the witness
         let argument_witness = self.argument_witness(row, witness)?;
         // Set up the constants.  Note that alpha, beta, gamma and joint_combiner
@@ -232,31 +278,8 @@ impl<F: PrimeField> CircuitGate<F> {
             challenges,
         );
 
-        // Check the wiring (i.e. copy constraints) for this gate
-        // Note: Gates can operated on row Curr or Curr and Next.
-        //       It could be nice for gates to know this and then
-        //       this code could be adapted to check Curr or Curr
-        //       and Next depending on the gate definition
-        for col in 0..PERMUTS {
-            let wire = self.wires[col];
-
-            if wire.col >= PERMUTS {
-                return Err(CircuitGateError::WireColumn(self.typ, col));
-            }
-
-            if witness[col][row] != witness[wire.col][wire.row] {
-                // Pinpoint failed copy constraint
-                return Err(CircuitGateError::CopyConstraint {
-                    typ: self.typ,
-                    src: Wire { row, col },
-                    dst: wire,
-                });
-            }
-        }
-
         let mut cache = expr::Cache::default();
 
-        // Perform witness verification on each constraint for this gate
         let results = match self.typ {
             GateType::Zero => {
                 vec![]
@@ -272,7 +295,7 @@ impl<F: PrimeField> CircuitGate<F> {
             GateType::EndoMulScalar => {
                 endomul_scalar::EndomulScalar::constraint_checks(&env, &mut cache)
             }
-            GateType::Lookup => {
+            GateType::Lookup | GateType::VectorLookup => {
                 // TODO: implement the verification for the lookup gate
                 // See https://github.com/MinaProtocol/mina/issues/14011
                 vec![]
@@ -299,19 +322,15 @@ impl<F: PrimeField> CircuitGate<F> {
             }
             GateType::Xor16 => xor::Xor16::constraint_checks(&env, &mut cache),
             GateType::Rot64 => rot::Rot64::constraint_checks(&env, &mut cache),
-        };
-
-        // Check for failed constraints
-        for (i, result) in results.iter().enumerate() {
-            if !result.is_zero() {
-                // Pinpoint failed constraint
-                return Err(CircuitGateError::Constraint(self.typ, i + 1));
+            GateType::Poseidon2External => {
+                poseidon2::Poseidon2External::constraint_checks(&env, &mut cache)
             }
-        }
-
-        // TODO: implement generic plookup witness verification
+            GateType::Poseidon2Internal => {
+                poseidon2::Poseidon2Internal::constraint_checks(&env, &mut cache)
+            }
+        };
 
-        Ok(())
+        Ok(results)
     }
 
     // Return the part of the witness relevant to this gate at the given row offset
@@ -333,6 +333,176 @@ where
     Ok(res)
 }
 
+/// Computes the multiplicities required by the logUp argument, that is the
+/// number of times each entry of the table is looked up, the dummy lookups
+/// padding the rows to `max_lookups_per_row` included.
+/// The multiplicity of an entry is held in the first row where the entry
+/// appears in the table, and the other rows are zero.
+///
+/// # Errors
+///
+/// Will give error if a lookup, or the dummy lookup, is missing from the
+/// `table`.
+#[allow(clippy::too_many_arguments)]
+pub fn multiplicities<F: PrimeField>(
+    dummy_lookup_value: F,
+    joint_lookup_table_d8: &Evaluations<F, D<F>>,
+    d1: D<F>,
+    gates: &[CircuitGate<F>],
+    witness: &[Vec<F>; COLUMNS],
+    joint_combiner: F,
+    table_id_combiner: F,
+    lookup_info: &LookupInfo,
+    zk_rows: usize,
+) -> Result<Vec<F>, ProverError> {
+    let n = d1.size();
+    let lookup_rows = n - zk_rows - 1;
+    let max_lookups_per_row = lookup_info.max_per_row;
+
+    // The first row of each entry of the table
+    let mut first_rows: HashMap<F, usize> = HashMap::new();
+    for (i, t) in joint_lookup_table_d8
+        .evals
+        .iter()
+        .step_by(8)
+        .take(lookup_rows)
+        .enumerate()
+    {
+        first_rows.entry(*t).or_insert(i);
+    }
+
+    let mut counts = vec![0u64; lookup_rows];
+    for (i, spec) in lookup_info
+        .by_row(gates)
+        .iter()
+        .enumerate()
+        .take(lookup_rows)
+    {
+        let eval = |pos: LocalPosition| -> F {
+            let row = match pos.row {
+                Curr => i,
+                Next => i + 1,
+            };
+            witness[pos.column][row]
+        };
+        for joint_lookup in spec.iter() {
+            let joint_lookup_evaluation =
+                joint_lookup.evaluate(&joint_combiner, &table_id_combiner, &eval);
+            match first_rows.get(&joint_lookup_evaluation) {
+                None => return Err(ProverError::ValueNotInTable(i)),
+                Some(row) => counts[*row] += 1,
+            }
+        }
+
+        let padding = max_lookups_per_row - spec.len();
+        if padding > 0 {
+            match first_rows.get(&dummy_lookup_value) {
+                None => return Err(ProverError::ValueNotInTable(i)),
+                Some(row) => counts[*row] += padding as u64,
+            }
+        }
+    }
+
+    Ok(counts.into_iter().map(F::from).collect())
+}
+
+/// Computes the running sum of the logUp argument for maximum n lookups per
+/// row, whose kth entry is the sum of the terms
+///
+///  \sum_{0 <= j < n} 1 / (gamma + f_{i,j}) - m_i / (gamma + t_i)
+///
+/// for i < k.
+///
+/// t_i is the ith entry in the table, m_i its multiplicity, and f_{i, j} is the
+/// jth lookup in the ith row of the witness, padded with dummy lookups.
+///
+/// The sum of the first terms over all the rows is the sum of
+/// `c / (gamma + v)` over the values `v` looked up `c` times, which the
+/// second terms cancel when the multiplicities count the lookups. Because of
+/// the random choice of gamma, there is a negligible probability that the
+/// terms cancel if a value that is not in the table is looked up, so that the
+/// final entry is 0.
+///
+/// # Panics
+///
+/// Will panic if final evaluation is not 0.
+#[allow(clippy::too_many_arguments)]
+pub fn log_up_aggregation<R, F>(
+    dummy_lookup_value: F,
+    joint_lookup_table_d8: &Evaluations<F, D<F>>,
+    d1: D<F>,
+    gates: &[CircuitGate<F>],
+    witness: &[Vec<F>; COLUMNS],
+    joint_combiner: &F,
+    table_id_combiner: &F,
+    gamma: F,
+    multiplicities: &Evaluations<F, D<F>>,
+    rng: &mut R,
+    lookup_info: &LookupInfo,
+    zk_rows: usize,
+) -> Result<Evaluations<F, D<F>>, ProverError>
+where
+    R: Rng + ?Sized,
+    F: PrimeField,
+{
+    let n = d1.size();
+    let lookup_rows = n - zk_rows - 1;
+    let max_lookups_per_row = lookup_info.max_per_row;
+    let by_row = lookup_info.by_row(gates);
+
+    // The terms to invert: the dummy lookup first, then for each row the
+    // table entry followed by the lookups of the row
+    let mut inverses = vec![gamma + dummy_lookup_value];
+    joint_lookup_table_d8
+        .evals
+        .iter()
+        .step_by(8)
+        .take(lookup_rows)
+        .zip(by_row.iter())
+        .enumerate()
+        .for_each(|(i, (t, spec))| {
+            let eval = |pos: LocalPosition| -> F {
+                let row = match pos.row {
+                    Curr => i,
+                    Next => i + 1,
+                };
+                witness[pos.column][row]
+            };
+            inverses.push(gamma + t);
+            inverses.extend(
+                spec.iter()
+                    .map(|j| gamma + j.evaluate(joint_combiner, table_id_combiner, &eval)),
+            );
+        });
+    ark_ff::fields::batch_inversion::<F>(&mut inverses);
+
+    let dummy_inverse = inverses[0];
+    let mut inverses = inverses[1..].iter();
+    let mut running_sum = vec![F::zero()];
+    for (i, spec) in by_row.iter().take(lookup_rows).enumerate() {
+        let table_inverse = *inverses.next().unwrap();
+        let padding = F::from((max_lookups_per_row - spec.len()) as u64);
+        let lookups = inverses
+            .by_ref()
+            .take(spec.len())
+            .fold(padding * dummy_inverse, |acc, x| acc + x);
+        let prev = running_sum[i];
+        running_sum.push(prev + lookups - multiplicities.evals[i] * table_inverse);
+    }
+
+    let res = zk_patch(running_sum, d1, zk_rows, rng);
+
+    // check that the final evaluation is equal to 0
+    if cfg!(debug_assertions) {
+        let final_val = res.evals[d1.size() - (zk_rows + 1)];
+        if !final_val.is_zero() {
+            panic!("logUp running sum incorrect: {final_val}");
+        }
+    }
+
+    Ok(res)
+}
+
 /// Configuration for the lookup constraint.
 /// These values are independent of the choice of lookup values.
 // TODO: move to lookup::index
@@ -392,6 +562,117 @@ pub fn constraints<F: FftField>(
 
     let column = |col: Column| E::cell(col, Curr);
 
+    // to toggle dummy queries when we do not have any lookups in a row
+    // (1 minus the sum of the lookup selectors)
+    let non_lookup_indicator = {
+        let lookup_indicator = lookup_info
+            .features
+            .patterns
+            .into_iter()
+            .map(|spec| {
+                let mut term = column(Column::LookupKindIndex(spec));
+                if generate_feature_flags {
+                    term = E::IfFeature(
+                        FeatureFlag::LookupPattern(spec),
+                        Box::new(term),
+                        Box::new(E::zero()),
+                    )
+                }
+                term
+            })
+            .fold(E::zero(), |acc: E<F>, x| acc + x);
+
+        E::one() - lookup_indicator
+    };
+
+    let joint_combiner = E::from(BerkeleyChallengeTerm::JointCombiner);
+    let table_id_combiner =
+        // Compute `joint_combiner.pow(lookup_info.max_joint_size)`, injecting feature flags if
+        // needed.
+        (1..lookup_info.max_joint_size).fold(joint_combiner.clone(), |acc, i| {
+            let mut new_term = joint_combiner.clone();
+            if generate_feature_flags {
+                new_term = E::IfFeature(
+                    FeatureFlag::TableWidth((i + 1) as isize),
+                    Box::new(new_term),
+                    Box::new(E::one()),
+                );
+            }
+            acc * new_term
+        });
+
+    // combine the columns of the dummy lookup row
+    let dummy_lookup = {
+        let expr_dummy: JointLookupValue<E<F>> = JointLookup {
+            entry: configuration
+                .dummy_lookup
+                .entry
+                .iter()
+                .map(|x| ConstantTerm::Literal(*x).into())
+                .collect(),
+            table_id: ConstantTerm::Literal(configuration.dummy_lookup.table_id).into(),
+        };
+        expr_dummy.evaluate(&joint_combiner, &table_id_combiner)
+    };
+
+    let mut res = if lookup_info.features.log_up {
+        log_up_constraints(
+            lookup_info,
+            non_lookup_indicator,
+            &joint_combiner,
+            &table_id_combiner,
+            dummy_lookup,
+            generate_feature_flags,
+        )
+    } else {
+        plookup_constraints(
+            lookup_info,
+            non_lookup_indicator,
+            &joint_combiner,
+            &table_id_combiner,
+            dummy_lookup,
+            generate_feature_flags,
+        )
+    };
+
+    // Padding to make sure that the position of the runtime tables constraints is always
+    // consistent.
+    res.resize(CONSTRAINTS as usize, E::zero());
+
+    // if we are using runtime tables, we add:
+    // $RT(x) (1 - \text{selector}_{RT}(x)) = 0$
+    if configuration.lookup_info.features.uses_runtime_tables {
+        let mut rt_constraints = runtime_tables::constraints();
+        if generate_feature_flags {
+            for term in rt_constraints.iter_mut() {
+                // Dummy value, to appease the borrow checker.
+                let mut boxed_term = Box::new(constant(F::zero()));
+                core::mem::swap(term, &mut *boxed_term);
+                *term = E::IfFeature(
+                    FeatureFlag::RuntimeLookupTables,
+                    boxed_term,
+                    Box::new(E::zero()),
+                )
+            }
+        }
+        res.extend(rt_constraints);
+    }
+
+    res
+}
+
+// The constraints of the plookup argument, over the sorted columns and the
+// aggregation of their product.
+fn plookup_constraints<F: FftField>(
+    lookup_info: &LookupInfo,
+    non_lookup_indicator: E<F>,
+    joint_combiner: &E<F>,
+    table_id_combiner: &E<F>,
+    dummy_lookup: E<F>,
+    generate_feature_flags: bool,
+) -> Vec<E<F>> {
+    let column = |col: Column| E::cell(col, Curr);
+
     // gamma * (beta + 1)
     let gammabeta1 = E::<F>::from(
         ConstantExpr::from(BerkeleyChallengeTerm::Gamma)
@@ -400,59 +681,6 @@ pub fn constraints<F: FftField>(
 
     // the numerator part in the multiset check of plookup
     let numerator = {
-        // to toggle dummy queries when we do not have any lookups in a row
-        // (1 minus the sum of the lookup selectors)
-        let non_lookup_indicator = {
-            let lookup_indicator = lookup_info
-                .features
-                .patterns
-                .into_iter()
-                .map(|spec| {
-                    let mut term = column(Column::LookupKindIndex(spec));
-                    if generate_feature_flags {
-                        term = E::IfFeature(
-                            FeatureFlag::LookupPattern(spec),
-                            Box::new(term),
-                            Box::new(E::zero()),
-                        )
-                    }
-                    term
-                })
-                .fold(E::zero(), |acc: E<F>, x| acc + x);
-
-            E::one() - lookup_indicator
-        };
-
-        let joint_combiner = E::from(BerkeleyChallengeTerm::JointCombiner);
-        let table_id_combiner =
-            // Compute `joint_combiner.pow(lookup_info.max_joint_size)`, injecting feature flags if
-            // needed.
-            (1..lookup_info.max_joint_size).fold(joint_combiner.clone(), |acc, i| {
-                let mut new_term = joint_combiner.clone();
-                if generate_feature_flags {
-                    new_term = E::IfFeature(
-                        FeatureFlag::TableWidth((i + 1) as isize),
-                        Box::new(new_term),
-                        Box::new(E::one()),
-                    );
-                }
-                acc * new_term
-            });
-
-        // combine the columns of the dummy lookup row
-        let dummy_lookup = {
-            let expr_dummy: JointLookupValue<E<F>> = JointLookup {
-                entry: configuration
-                    .dummy_lookup
-                    .entry
-                    .iter()
-                    .map(|x| ConstantTerm::Literal(*x).into())
-                    .collect(),
-                table_id: ConstantTerm::Literal(configuration.dummy_lookup.table_id).into(),
-            };
-            expr_dummy.evaluate(&joint_combiner, &table_id_combiner)
-        };
-
         // (1 + beta)^max_per_row
         let beta1_per_row: E<F> = {
             let beta1 = E::from(ConstantExpr::one() + BerkeleyChallengeTerm::Beta.into());
@@ -511,7 +739,7 @@ pub fn constraints<F: FftField>(
             spec.iter()
                 .map(|j| {
                     E::from(BerkeleyChallengeTerm::Gamma)
-                        + j.evaluate(&joint_combiner, &table_id_combiner, &eval)
+                        + j.evaluate(joint_combiner, table_id_combiner, &eval)
                 })
                 .fold(padding, |acc: E<F>, x: E<F>| acc * x)
         };
@@ -651,30 +879,112 @@ pub fn constraints<F: FftField>(
         .collect();
     res.extend(compatibility_checks);
 
-    // Padding to make sure that the position of the runtime tables constraints is always
-    // consistent.
-    res.extend((lookup_info.max_per_row..4).map(|_| E::zero()));
+    res
+}
 
-    // if we are using runtime tables, we add:
-    // $RT(x) (1 - \text{selector}_{RT}(x)) = 0$
-    if configuration.lookup_info.features.uses_runtime_tables {
-        let mut rt_constraints = runtime_tables::constraints();
-        if generate_feature_flags {
-            for term in rt_constraints.iter_mut() {
-                // Dummy value, to appease the borrow checker.
-                let mut boxed_term = Box::new(constant(F::zero()));
-                core::mem::swap(term, &mut *boxed_term);
-                *term = E::IfFeature(
-                    FeatureFlag::RuntimeLookupTables,
-                    boxed_term,
-                    Box::new(E::zero()),
+// The constraints of the logUp argument, over the multiplicities and the
+// running sum of the logarithmic derivatives.
+fn log_up_constraints<F: FftField>(
+    lookup_info: &LookupInfo,
+    non_lookup_indicator: E<F>,
+    joint_combiner: &E<F>,
+    table_id_combiner: &E<F>,
+    dummy_lookup: E<F>,
+    generate_feature_flags: bool,
+) -> Vec<E<F>> {
+    let column = |col: Column| E::cell(col, Curr);
+    let gamma = E::<F>::from(BerkeleyChallengeTerm::Gamma);
+
+    // Adds `1 / term` to the fraction `numerator / denominator`, where the
+    // denominator is the product of the terms added so far, and the
+    // numerator is the sum of the products of all of them but one.
+    let add_term = |(denominator, numerator): (E<F>, E<F>), term: E<F>| {
+        (
+            denominator.clone() * term.clone(),
+            numerator * term + denominator,
+        )
+    };
+
+    // This is set up so that on rows that have lookups, the fraction is the
+    // sum over all lookups `f` in that row of `1 / (gamma + f)`, padded with
+    // dummy lookups to `max_per_row` lookups.
+    let fraction = |spec: &Vec<JointLookupSpec<_>>| {
+        assert!(spec.len() <= lookup_info.max_per_row);
+
+        let eval = |pos: LocalPosition| witness(pos.column, pos.row);
+        let fraction = spec.iter().fold((E::one(), E::zero()), |acc, j| {
+            add_term(
+                acc,
+                gamma.clone() + j.evaluate(joint_combiner, table_id_combiner, &eval),
+            )
+        });
+
+        (spec.len()..lookup_info.max_per_row).fold(fraction, |acc, i| {
+            let (denominator, numerator) =
+                add_term(acc.clone(), gamma.clone() + dummy_lookup.clone());
+            if generate_feature_flags {
+                let flag = FeatureFlag::LookupsPerRow((i + 1) as isize);
+                (
+                    E::IfFeature(flag, Box::new(denominator), Box::new(acc.0)),
+                    E::IfFeature(flag, Box::new(numerator), Box::new(acc.1)),
                 )
+            } else {
+                (denominator, numerator)
             }
-        }
-        res.extend(rt_constraints);
-    }
+        })
+    };
 
-    res
+    let (denominator, numerator) = {
+        let (dummy_denominator, dummy_numerator) = fraction(&vec![]);
+        let dummy_rows = (
+            non_lookup_indicator.clone() * dummy_denominator,
+            non_lookup_indicator * dummy_numerator,
+        );
+
+        lookup_info
+            .features
+            .patterns
+            .into_iter()
+            .map(|spec| {
+                let selector = column(Column::LookupKindIndex(spec));
+                let (denominator, numerator) = fraction(&spec.lookups::<F>());
+                let mut terms = (selector.clone() * denominator, selector * numerator);
+                if generate_feature_flags {
+                    let flag = FeatureFlag::LookupPattern(spec);
+                    terms = (
+                        E::IfFeature(flag, Box::new(terms.0), Box::new(E::zero())),
+                        E::IfFeature(flag, Box::new(terms.1), Box::new(E::zero())),
+                    );
+                }
+                terms
+            })
+            .fold(dummy_rows, |acc, x| (acc.0 + x.0, acc.1 + x.1))
+    };
+
+    // phi(x w) - phi(x) = numerator / denominator - m(x) / (gamma + t(x))
+    let table = gamma + column(Column::LookupTable);
+    let running_sum_equation = ((E::cell(Column::LookupAggreg, Next)
+        - column(Column::LookupAggreg))
+        * denominator.clone()
+        - numerator)
+        * table
+        + column(Column::LookupMultiplicities) * denominator;
+
+    vec![
+        // the running sum except for the last zk_rows+1 rows
+        // (contains the zk-rows and the last value of the running sum)
+        E::Atom(ExprInner::VanishesOnZeroKnowledgeAndPreviousRows) * running_sum_equation,
+        // the initial value of the running sum
+        E::Atom(ExprInner::UnnormalizedLagrangeBasis(RowOffset {
+            zk_rows: false,
+            offset: 0,
+        })) * column(Column::LookupAggreg),
+        // Check that the final value of the running sum is 0
+        E::Atom(ExprInner::UnnormalizedLagrangeBasis(RowOffset {
+            zk_rows: true,
+            offset: -1,
+        })) * column(Column::LookupAggreg),
+    ]
 }
 
 /// Checks that all the lookup constraints are satisfied.
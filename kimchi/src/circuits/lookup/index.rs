@@ -34,6 +34,10 @@ pub enum LookupError {
     TableIDZeroMustHaveZeroEntry,
     #[error("Cannot create a combined table since ids for sub-tables are colliding. The collision type is: {collision_type}")]
     LookupTableIdCollision { collision_type: String },
+    #[error("One of the lookup tables has no column or no entry")]
+    EmptyTable,
+    #[error("Cannot read the lookup table at line {line}: {reason}")]
+    InvalidTableFile { line: usize, reason: String },
 }
 
 /// Lookup selectors
@@ -47,6 +51,8 @@ pub struct LookupSelectors<T> {
     pub range_check: Option<T>,
     /// Foreign field multiplication pattern lookup selector
     pub ffmul: Option<T>,
+    /// Vector lookup pattern lookup selector
+    pub vector_lookup: Option<T>,
 }
 
 #[serde_as]
@@ -60,6 +66,8 @@ struct LookupSelectorsSerdeAs<F: FftField> {
     pub range_check: Option<E<F, D<F>>>,
     #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
     pub ffmul: Option<E<F, D<F>>>,
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    pub vector_lookup: Option<E<F, D<F>>>,
 }
 
 impl<F: FftField> serde_with::SerializeAs<LookupSelectors<E<F, D<F>>>>
@@ -74,6 +82,7 @@ impl<F: FftField> serde_with::SerializeAs<LookupSelectors<E<F, D<F>>>>
             lookup: val.lookup.clone(),
             range_check: val.range_check.clone(),
             ffmul: val.ffmul.clone(),
+            vector_lookup: val.vector_lookup.clone(),
         };
         repr.serialize(serializer)
     }
@@ -91,12 +100,14 @@ impl<'de, F: FftField> serde_with::DeserializeAs<'de, LookupSelectors<E<F, D<F>>
             lookup,
             range_check,
             ffmul,
+            vector_lookup,
         } = LookupSelectorsSerdeAs::deserialize(deserializer)?;
         Ok(LookupSelectors {
             xor,
             lookup,
             range_check,
             ffmul,
+            vector_lookup,
         })
     }
 }
@@ -110,6 +121,7 @@ impl<T> core::ops::Index<LookupPattern> for LookupSelectors<T> {
             LookupPattern::Lookup => &self.lookup,
             LookupPattern::RangeCheck => &self.range_check,
             LookupPattern::ForeignFieldMul => &self.ffmul,
+            LookupPattern::VectorLookup => &self.vector_lookup,
         }
     }
 }
@@ -121,6 +133,7 @@ impl<T> core::ops::IndexMut<LookupPattern> for LookupSelectors<T> {
             LookupPattern::Lookup => &mut self.lookup,
             LookupPattern::RangeCheck => &mut self.range_check,
             LookupPattern::ForeignFieldMul => &mut self.ffmul,
+            LookupPattern::VectorLookup => &mut self.vector_lookup,
         }
     }
 }
@@ -132,6 +145,7 @@ impl<T> LookupSelectors<T> {
             lookup,
             range_check,
             ffmul,
+            vector_lookup,
         } = self;
         // This closure isn't really redundant -- it shields the parameter from a copy -- but
         // clippy isn't smart enough to figure that out..
@@ -142,6 +156,7 @@ impl<T> LookupSelectors<T> {
             lookup: lookup.map(f),
             range_check: range_check.map(f),
             ffmul: ffmul.map(f),
+            vector_lookup: vector_lookup.map(f),
         }
     }
 
@@ -151,6 +166,7 @@ impl<T> LookupSelectors<T> {
             lookup: self.lookup.as_ref(),
             range_check: self.range_check.as_ref(),
             ffmul: self.ffmul.as_ref(),
+            vector_lookup: self.vector_lookup.as_ref(),
         }
     }
 }
@@ -206,11 +222,14 @@ impl<F: PrimeField> LookupConstraintSystem<F> {
         runtime_tables: Option<Vec<RuntimeTableCfg<F>>>,
         domain: &EvaluationDomains<F>,
         zk_rows: usize,
+        log_up: bool,
     ) -> Result<Option<Self>, LookupError> {
         //~ 1. If no lookup is used in the circuit, do not create a lookup index
         match LookupInfo::create_from_gates(gates, runtime_tables.is_some()) {
             None => Ok(None),
-            Some(lookup_info) => {
+            Some(mut lookup_info) => {
+                lookup_info.features.log_up = log_up;
+
                 let d1_size = domain.d1.size();
 
                 // The maximum number of entries that can be provided across all tables.
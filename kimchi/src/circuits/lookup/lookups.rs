@@ -52,6 +52,8 @@ pub struct LookupPatterns {
     pub lookup: bool,
     pub range_check: bool,
     pub foreign_field_mul: bool,
+    #[serde(default)]
+    pub vector_lookup: bool,
 }
 
 impl IntoIterator for LookupPatterns {
@@ -65,6 +67,7 @@ impl IntoIterator for LookupPatterns {
             lookup,
             range_check,
             foreign_field_mul,
+            vector_lookup,
         } = self;
 
         let mut patterns = Vec::with_capacity(5);
@@ -81,6 +84,9 @@ impl IntoIterator for LookupPatterns {
         if foreign_field_mul {
             patterns.push(LookupPattern::ForeignFieldMul)
         }
+        if vector_lookup {
+            patterns.push(LookupPattern::VectorLookup)
+        }
         patterns.into_iter()
     }
 }
@@ -94,6 +100,7 @@ impl core::ops::Index<LookupPattern> for LookupPatterns {
             LookupPattern::Lookup => &self.lookup,
             LookupPattern::RangeCheck => &self.range_check,
             LookupPattern::ForeignFieldMul => &self.foreign_field_mul,
+            LookupPattern::VectorLookup => &self.vector_lookup,
         }
     }
 }
@@ -105,6 +112,7 @@ impl core::ops::IndexMut<LookupPattern> for LookupPatterns {
             LookupPattern::Lookup => &mut self.lookup,
             LookupPattern::RangeCheck => &mut self.range_check,
             LookupPattern::ForeignFieldMul => &mut self.foreign_field_mul,
+            LookupPattern::VectorLookup => &mut self.vector_lookup,
         }
     }
 }
@@ -146,6 +154,11 @@ pub struct LookupFeatures {
     pub joint_lookup_used: bool,
     /// True if runtime lookup tables are used.
     pub uses_runtime_tables: bool,
+    /// True if the lookups are checked with the logarithmic-derivative
+    /// argument (logUp) instead of plookup, see
+    /// [lookup::constraints](crate::circuits::lookup::constraints).
+    #[serde(default)]
+    pub log_up: bool,
 }
 
 impl LookupFeatures {
@@ -158,6 +171,7 @@ impl LookupFeatures {
             patterns,
             uses_runtime_tables,
             joint_lookup_used,
+            log_up: false,
         }
     }
 }
@@ -202,6 +216,16 @@ impl LookupInfo {
         }
     }
 
+    /// The number of sorted columns of the lookup argument: `max_per_row + 1`
+    /// with plookup, and none with logUp.
+    pub fn sorted_columns(&self) -> usize {
+        if self.features.log_up {
+            0
+        } else {
+            self.max_per_row + 1
+        }
+    }
+
     /// Each entry in `kinds` has a corresponding selector polynomial that controls whether that
     /// lookup kind should be enforced at a given row. This computes those selector polynomials.
     pub fn selector_polynomials_and_tables<F: PrimeField>(
@@ -392,6 +416,7 @@ pub enum LookupPattern {
     Lookup,
     RangeCheck,
     ForeignFieldMul,
+    VectorLookup,
 }
 
 impl LookupPattern {
@@ -399,7 +424,7 @@ impl LookupPattern {
     pub fn max_lookups_per_row(&self) -> usize {
         match self {
             LookupPattern::Xor | LookupPattern::RangeCheck | LookupPattern::ForeignFieldMul => 4,
-            LookupPattern::Lookup => 3,
+            LookupPattern::Lookup | LookupPattern::VectorLookup => 3,
         }
     }
 
@@ -407,6 +432,7 @@ impl LookupPattern {
     pub fn max_joint_size(&self) -> u32 {
         match self {
             LookupPattern::Xor => 3,
+            LookupPattern::VectorLookup => 4,
             LookupPattern::Lookup => 2,
             LookupPattern::ForeignFieldMul | LookupPattern::RangeCheck => 1,
         }
@@ -496,6 +522,24 @@ impl LookupPattern {
                     })
                     .collect()
             }
+            LookupPattern::VectorLookup => {
+                (0..3)
+                    .map(|i| {
+                        // 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14
+                        // - v v v v - - - - - -  -  -  -  -
+                        // - - - - - v v v v - -  -  -  -  -
+                        // - - - - - - - - - v v  v  v  -  -
+                        JointLookup {
+                            table_id: LookupTableID::WitnessColumn(0),
+                            entry: (4 * i + 1..4 * i + 5)
+                                .map(|column| SingleLookup {
+                                    value: vec![(F::one(), curr_row(column))],
+                                })
+                                .collect(),
+                        }
+                    })
+                    .collect()
+            }
         }
     }
 
@@ -503,7 +547,7 @@ impl LookupPattern {
     pub fn table(&self) -> Option<GateLookupTable> {
         match self {
             LookupPattern::Xor => Some(GateLookupTable::Xor),
-            LookupPattern::Lookup => None,
+            LookupPattern::Lookup | LookupPattern::VectorLookup => None,
             LookupPattern::RangeCheck => Some(GateLookupTable::RangeCheck),
             LookupPattern::ForeignFieldMul => Some(GateLookupTable::RangeCheck),
         }
@@ -520,6 +564,7 @@ impl LookupPattern {
             }
             (ForeignFieldMul, Curr | Next) => Some(LookupPattern::ForeignFieldMul),
             (Xor16, Curr) => Some(LookupPattern::Xor),
+            (VectorLookup, Curr) => Some(LookupPattern::VectorLookup),
             _ => None,
         }
     }
@@ -533,6 +578,7 @@ impl GateType {
             LookupPattern::Lookup,
             LookupPattern::RangeCheck,
             LookupPattern::ForeignFieldMul,
+            LookupPattern::VectorLookup,
         ]
     }
 }
@@ -566,12 +612,14 @@ pub mod wasm {
             lookup: bool,
             range_check: bool,
             foreign_field_mul: bool,
+            vector_lookup: bool,
         ) -> LookupPatterns {
             LookupPatterns {
                 xor,
                 lookup,
                 range_check,
                 foreign_field_mul,
+                vector_lookup,
             }
         }
     }
@@ -583,11 +631,13 @@ pub mod wasm {
             patterns: LookupPatterns,
             joint_lookup_used: bool,
             uses_runtime_tables: bool,
+            log_up: bool,
         ) -> LookupFeatures {
             LookupFeatures {
                 patterns,
                 joint_lookup_used,
                 uses_runtime_tables,
+                log_up,
             }
         }
     }
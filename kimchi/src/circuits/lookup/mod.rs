@@ -1,5 +1,9 @@
 //! This module implement the lookup argument.
 //! See <https://eprint.iacr.org/2020/315.pdf>
+//!
+//! The lookups can also be checked with the logarithmic-derivative argument
+//! (logUp), which replaces the sorted columns by a single column of
+//! multiplicities, see <https://eprint.iacr.org/2022/1530.pdf>.
 
 pub mod constraints;
 pub mod index;
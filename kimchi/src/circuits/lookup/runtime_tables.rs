@@ -1,13 +1,20 @@
 //! Runtime tables are tables (or arrays) that can be produced during proof creation.
 //! The setup has to prepare for their presence using [`RuntimeTableCfg`].
 //! At proving time, the prover can use [`RuntimeTable`] to specify the actual tables.
+//!
+//! A table can also be declared by its ID and capacity only, with
+//! [`RuntimeTableCfg::with_capacity`], its first column being the indices of its
+//! entries. It is then filled during witness generation from application data,
+//! such as a read-only memory image, starting from [`RuntimeTable::new`].
 
 // TODO: write cargo specifications
 
 use crate::circuits::{berkeley_columns::Column, expr::prologue::*, gate::CurrOrNext};
 
 use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 
 /// The specification of a runtime table.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,11 +28,14 @@ pub struct RuntimeTableSpec {
 /// Use this type at setup time, to list all the runtime tables.
 ///
 /// Note: care must be taken as table IDs can collide with IDs of other types of lookup tables.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "F: CanonicalSerialize + CanonicalDeserialize")]
 pub struct RuntimeTableCfg<F> {
     /// The table ID.
     pub id: i32,
     /// The content of the first column of the runtime table.
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
     pub first_column: Vec<F>,
 }
 
@@ -46,6 +56,17 @@ impl<F> RuntimeTableCfg<F> {
     }
 }
 
+impl<F: Field> RuntimeTableCfg<F> {
+    /// Returns the configuration of a runtime table of `capacity` entries,
+    /// indexed by `0..capacity`.
+    pub fn with_capacity(id: i32, capacity: usize) -> Self {
+        Self {
+            id,
+            first_column: (0..capacity as u64).map(F::from).collect(),
+        }
+    }
+}
+
 impl<F> From<RuntimeTableCfg<F>> for RuntimeTableSpec {
     fn from(rt_cfg: RuntimeTableCfg<F>) -> Self {
         Self {
@@ -65,6 +86,56 @@ pub struct RuntimeTable<F> {
     pub data: Vec<F>,
 }
 
+impl<F: Field> RuntimeTable<F> {
+    /// Returns a runtime table matching `spec`, whose entries are all zero
+    /// until written.
+    pub fn new(spec: &RuntimeTableSpec) -> Self {
+        Self {
+            id: spec.id,
+            data: vec![F::zero(); spec.len],
+        }
+    }
+
+    /// Returns the number of entries of the runtime table.
+    pub fn capacity(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the value of the entry at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `index` is not smaller than the capacity of the table.
+    pub fn read(&self, index: usize) -> F {
+        self.data[index]
+    }
+
+    /// Writes `value` to the entry at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `index` is not smaller than the capacity of the table.
+    pub fn write(&mut self, index: usize, value: F) {
+        self.data[index] = value;
+    }
+
+    /// Writes `values` to the entries starting at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the values do not fit in the capacity of the table.
+    pub fn write_all(&mut self, index: usize, values: &[F]) {
+        assert!(
+            index + values.len() <= self.capacity(),
+            "runtime table {} of capacity {} cannot hold {} values at index {index}",
+            self.id,
+            self.capacity(),
+            values.len()
+        );
+        self.data[index..index + values.len()].copy_from_slice(values);
+    }
+}
+
 /// Returns the constraints related to the runtime tables.
 pub fn constraints<F>() -> Vec<E<F>>
 where
@@ -1,6 +1,14 @@
-use ark_ff::{FftField, One, Zero};
+use crate::circuits::lookup::index::LookupError;
+use ark_ff::{FftField, One, PrimeField, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use num_bigint::BigUint;
 use poly_commitment::PolyComm;
 use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use std::{
+    io::{BufRead, BufReader, Read},
+    path::Path,
+};
 
 pub mod range_check;
 pub mod xor;
@@ -71,9 +79,12 @@ impl IntoIterator for GateLookupTables {
 }
 
 /// A table of values that can be used for a lookup, along with the ID for the table.
-#[derive(Debug, Clone)]
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "F: CanonicalSerialize + CanonicalDeserialize")]
 pub struct LookupTable<F> {
     pub id: i32,
+    #[serde_as(as = "Vec<Vec<o1_utils::serialization::SerdeAs>>")]
     pub data: Vec<Vec<F>>,
 }
 
@@ -81,6 +92,30 @@ impl<F> LookupTable<F>
 where
     F: FftField,
 {
+    /// Creates the table of ID `id` from its columns `data`, to be registered
+    /// with [Builder::lookup](crate::circuits::constraints::Builder::lookup)
+    /// or [Builder::lookup_table](crate::circuits::constraints::Builder::lookup_table).
+    /// Its ID must not be one of the tables used by the gates of the circuit.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the table has no column or no entry, if its columns
+    /// have different lengths, or if its ID is 0 and it has no entry of all
+    /// zeros.
+    pub fn new(id: i32, data: Vec<Vec<F>>) -> Result<Self, LookupError> {
+        if data.is_empty() || data[0].is_empty() {
+            return Err(LookupError::EmptyTable);
+        }
+        if data.iter().any(|column| column.len() != data[0].len()) {
+            return Err(LookupError::InconsistentTableLength);
+        }
+        let table = LookupTable { id, data };
+        if id == 0 && !table.has_zero_entry() {
+            return Err(LookupError::TableIDZeroMustHaveZeroEntry);
+        }
+        Ok(table)
+    }
+
     /// Return true if the table has an entry (row) containing all zeros.
     pub fn has_zero_entry(&self) -> bool {
         // reminder: a table is written as a list of columns,
@@ -111,6 +146,147 @@ where
     }
 }
 
+impl<F: PrimeField> LookupTable<F> {
+    /// Reads the table of ID `id` from `reader`, in the format described in
+    /// [LookupTable::from_file].
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `reader` cannot be read, if it is not in the expected
+    /// format, or in the cases described in [LookupTable::new].
+    pub fn from_reader(id: i32, reader: impl Read) -> Result<Self, LookupError> {
+        let modulus: BigUint = F::MODULUS.into();
+        let mut data: Vec<Vec<F>> = vec![];
+        for (i, line) in BufReader::new(reader).lines().enumerate() {
+            let line_number = i + 1;
+            let invalid = |reason: String| LookupError::InvalidTableFile {
+                line: line_number,
+                reason,
+            };
+            let line = line.map_err(|e| invalid(e.to_string()))?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let entry = line
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|value| !value.is_empty())
+                .map(|value| {
+                    let parsed = match value.strip_prefix("0x") {
+                        Some(hex) => BigUint::parse_bytes(hex.as_bytes(), 16),
+                        None => BigUint::parse_bytes(value.as_bytes(), 10),
+                    };
+                    match parsed {
+                        Some(n) if n < modulus => Ok(F::from(n)),
+                        Some(_) => Err(invalid(format!("{value} is not below the field modulus"))),
+                        None => Err(invalid(format!("{value} is not a number"))),
+                    }
+                })
+                .collect::<Result<Vec<F>, _>>()?;
+
+            if data.is_empty() {
+                data = vec![vec![]; entry.len()];
+            } else if entry.len() != data.len() {
+                return Err(invalid(format!(
+                    "the entry has {} values instead of {}",
+                    entry.len(),
+                    data.len()
+                )));
+            }
+            for (column, value) in data.iter_mut().zip(entry) {
+                column.push(value);
+            }
+        }
+        Self::new(id, data)
+    }
+
+    /// Reads the table of ID `id` from the file at `path`.
+    ///
+    /// The file has one entry of the table per line, whose values are
+    /// separated by commas or whitespaces, and written in decimal or in
+    /// hexadecimal with a `0x` prefix. Empty lines and lines starting with `#`
+    /// are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the file cannot be read, or in the cases described
+    /// in [LookupTable::from_reader].
+    pub fn from_file(id: i32, path: impl AsRef<Path>) -> Result<Self, LookupError> {
+        let file = std::fs::File::open(path).map_err(|e| LookupError::InvalidTableFile {
+            line: 0,
+            reason: e.to_string(),
+        })?;
+        Self::from_reader(id, file)
+    }
+}
+
+/// A builder of a [LookupTable] from its entries, each of them being a tuple of
+/// `width` values, such as `(input, output, flags)` for a substitution box.
+///
+/// Tables of width up to the maximum joint size of the lookup patterns in use
+/// can be looked up, see
+/// [LookupPattern::VectorLookup](crate::circuits::lookup::lookups::LookupPattern::VectorLookup)
+/// for tuples of up to 4 values.
+#[derive(Debug, Clone)]
+pub struct LookupTableBuilder<F> {
+    id: i32,
+    data: Vec<Vec<F>>,
+}
+
+impl<F: FftField> LookupTableBuilder<F> {
+    /// Creates the builder of an empty table of ID `id` whose entries have
+    /// `width` values.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `width` is zero.
+    pub fn new(id: i32, width: usize) -> Self {
+        assert!(width > 0, "a lookup table must have at least one column");
+        LookupTableBuilder {
+            id,
+            data: vec![vec![]; width],
+        }
+    }
+
+    /// Adds the entry `entry` to the table.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `entry` does not have the width of the table.
+    pub fn entry(mut self, entry: &[F]) -> Self {
+        assert_eq!(entry.len(), self.data.len(), "wrong width of lookup entry");
+        for (column, value) in self.data.iter_mut().zip(entry) {
+            column.push(*value);
+        }
+        self
+    }
+
+    /// Adds all the entries of `entries` to the table.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if one of the entries does not have the width of the table.
+    pub fn entries<E: AsRef<[F]>>(self, entries: impl IntoIterator<Item = E>) -> Self {
+        entries
+            .into_iter()
+            .fold(self, |builder, entry| builder.entry(entry.as_ref()))
+    }
+
+    /// Returns the table, stored as a list of columns.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the table has no entry.
+    pub fn build(self) -> LookupTable<F> {
+        assert!(!self.data[0].is_empty(), "a lookup table must have entries");
+        LookupTable {
+            id: self.id,
+            data: self.data,
+        }
+    }
+}
+
 /// Returns the lookup table associated to a [`GateLookupTable`].
 pub fn get_table<F: FftField>(table_name: GateLookupTable) -> LookupTable<F> {
     match table_name {
@@ -3,15 +3,20 @@ pub mod macros;
 
 pub mod argument;
 pub mod berkeley_columns;
+pub mod builder;
 pub mod constraints;
+pub mod definition;
 pub mod domain_constant_evaluation;
 pub mod domains;
 pub mod expr;
 pub mod gate;
 pub mod lookup;
+pub mod optimizer;
 pub mod polynomial;
 pub mod polynomials;
+pub mod recursion;
 pub mod scalars;
 mod serialization_helper;
+pub mod stats;
 pub mod wires;
 pub mod witness;
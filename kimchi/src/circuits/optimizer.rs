@@ -0,0 +1,315 @@
+//! An optimizer of circuits, reducing the number of rows of a gate list
+//!
+//! Circuits written by hand often leave rows half empty: a generic gate only
+//! using its first half, or a generic gate whose coefficients are all zero
+//! and which only serves to copy a value around. [optimize] runs over a built
+//! gate list and
+//!
+//! * removes the dead cells of the generic gates, whose registers are not
+//!   read by any coefficient, from the copy constraints of the permutation,
+//! * removes the generic gates whose coefficients are all zero,
+//! * merges pairs of generic gates only using their first half into double
+//!   generic gates, the second one moving to the second half of the first,
+//! * and rewires the permutation of the remaining cells.
+//!
+//! The public input rows are left untouched, and so are the rows read by the
+//! gate of the row above them, so that the rows of multi-row gadgets stay
+//! together and their cells stay wired.
+//!
+//! The witness of the original circuit is mapped to the optimized one with
+//! [OptimizedCircuit::witness].
+//!
+//! ```
+//! use kimchi::circuits::{
+//!     gate::CircuitGate,
+//!     optimizer::optimize,
+//!     polynomials::generic::GenericGateSpec,
+//!     wires::Wire,
+//! };
+//! use mina_curves::pasta::Fp;
+//!
+//! let gates: Vec<CircuitGate<Fp>> = (0..4)
+//!     .map(|row| {
+//!         CircuitGate::create_generic_gadget(
+//!             Wire::for_row(row),
+//!             GenericGateSpec::Const(Fp::from(row as u64)),
+//!             None,
+//!         )
+//!     })
+//!     .collect();
+//!
+//! let optimized = optimize(&gates, 0);
+//! assert_eq!(optimized.gates.len(), 2);
+//! assert_eq!(optimized.report.rows_saved(), 2);
+//! ```
+
+use crate::circuits::{
+    gate::{CircuitGate, GateType},
+    polynomial::COLUMNS,
+    polynomials::generic::{GENERIC_COEFFS, GENERIC_REGISTERS},
+    wires::{Wire, PERMUTS},
+};
+use ark_ff::PrimeField;
+use core::{array, fmt};
+
+/// A cell of the circuit, as `(row, column)`
+type Cell = (usize, usize);
+
+/// The rows saved by [optimize]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OptimizationReport {
+    /// The number of rows of the original circuit
+    pub rows_before: usize,
+    /// The number of rows of the optimized circuit
+    pub rows_after: usize,
+    /// The number of generic gates moved to the second half of another row
+    pub merged_gates: usize,
+    /// The number of generic gates removed as their coefficients are all zero
+    pub dead_gates: usize,
+    /// The number of cells removed from the copy constraints
+    pub unwired_cells: usize,
+}
+
+impl OptimizationReport {
+    /// The number of rows removed from the circuit
+    pub fn rows_saved(&self) -> usize {
+        self.rows_before - self.rows_after
+    }
+}
+
+impl fmt::Display for OptimizationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let percent = if self.rows_before == 0 {
+            0.0
+        } else {
+            100.0 * self.rows_saved() as f64 / self.rows_before as f64
+        };
+        write!(
+            f,
+            "{} rows -> {} rows ({} saved, {percent:.1}%): {} generic gates merged, {} dead gates removed, {} cells unwired",
+            self.rows_before,
+            self.rows_after,
+            self.rows_saved(),
+            self.merged_gates,
+            self.dead_gates,
+            self.unwired_cells,
+        )
+    }
+}
+
+/// A circuit optimized by [optimize]
+#[derive(Clone, Debug)]
+pub struct OptimizedCircuit<F: PrimeField> {
+    /// The gates of the optimized circuit
+    pub gates: Vec<CircuitGate<F>>,
+    /// The rows saved by the optimization
+    pub report: OptimizationReport,
+    /// The cell of the optimized circuit of each cell of the original
+    /// circuit, if it is still read by a gate
+    cells: Vec<[Option<Cell>; COLUMNS]>,
+}
+
+impl<F: PrimeField> OptimizedCircuit<F> {
+    /// Maps a witness of the original circuit to the optimized circuit
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the witness has fewer rows than the original circuit.
+    pub fn witness(&self, witness: &[Vec<F>; COLUMNS]) -> [Vec<F>; COLUMNS] {
+        let mut optimized = array::from_fn(|_| vec![F::zero(); self.gates.len()]);
+        for (row, cells) in self.cells.iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                if let Some((new_row, new_col)) = *cell {
+                    optimized[new_col][new_row] = witness[col][row];
+                }
+            }
+        }
+        optimized
+    }
+}
+
+/// Whether the constraints and lookups of a gate only read its own row, so
+/// that the row below it can be moved. Unlisted gates are assumed to read
+/// their next row.
+fn reads_only_curr_row(typ: GateType) -> bool {
+    matches!(
+        typ,
+        GateType::Zero
+            | GateType::Generic
+            | GateType::CompleteAdd
+            | GateType::EndoMulScalar
+            | GateType::Lookup
+            | GateType::VectorLookup
+    )
+}
+
+/// The coefficient `index` of a generic gate, zero if it is not stored
+fn coeff<F: PrimeField>(gate: &CircuitGate<F>, index: usize) -> F {
+    gate.coeffs.get(index).copied().unwrap_or_else(F::zero)
+}
+
+/// Whether the row is read by the gate of the row above it
+fn is_read_by_prev<F: PrimeField>(gates: &[CircuitGate<F>], row: usize) -> bool {
+    row > 0 && !reads_only_curr_row(gates[row - 1].typ)
+}
+
+/// Whether the cell `col` of the gate of `row` is read by a constraint
+fn is_live<F: PrimeField>(gates: &[CircuitGate<F>], row: usize, col: usize, public: usize) -> bool {
+    let gate = &gates[row];
+    if gate.typ != GateType::Generic || is_read_by_prev(gates, row) {
+        return true;
+    }
+    if col >= 2 * GENERIC_REGISTERS {
+        return false;
+    }
+    let offset = (col / GENERIC_REGISTERS) * GENERIC_COEFFS;
+    let mul = !coeff(gate, offset + 3).is_zero();
+    match col % GENERIC_REGISTERS {
+        0 => !coeff(gate, offset).is_zero() || mul || (col == 0 && row < public),
+        1 => !coeff(gate, offset + 1).is_zero() || mul,
+        _ => !coeff(gate, offset + 2).is_zero(),
+    }
+}
+
+/// Whether the gate is a generic gate only using its first half
+fn is_single_generic<F: PrimeField>(gate: &CircuitGate<F>) -> bool {
+    gate.typ == GateType::Generic
+        && (GENERIC_COEFFS..2 * GENERIC_COEFFS).all(|i| coeff(gate, i).is_zero())
+}
+
+/// Whether the gate is a generic gate whose coefficients are all zero
+fn is_dead_generic<F: PrimeField>(gate: &CircuitGate<F>) -> bool {
+    is_single_generic(gate) && (0..GENERIC_COEFFS).all(|i| coeff(gate, i).is_zero())
+}
+
+/// Where the gate of a row of the original circuit ends up
+#[derive(Clone, Copy)]
+enum Placement {
+    /// In its own row
+    Kept,
+    /// In the second half of the generic gate of the row
+    Merged(usize),
+    /// Nowhere
+    Removed,
+}
+
+/// Optimizes the gates of a circuit whose `public` first rows are the public
+/// inputs, see [self]
+pub fn optimize<F: PrimeField>(gates: &[CircuitGate<F>], public: usize) -> OptimizedCircuit<F> {
+    let mut report = OptimizationReport {
+        rows_before: gates.len(),
+        ..Default::default()
+    };
+
+    // Place the gates, merging each single generic gate with the last single
+    // generic gate that was not merged yet
+    let mut placements = vec![Placement::Kept; gates.len()];
+    let mut host = None;
+    for row in public..gates.len() {
+        if is_read_by_prev(gates, row) {
+            continue;
+        }
+        if is_dead_generic(&gates[row]) {
+            placements[row] = Placement::Removed;
+            report.dead_gates += 1;
+        } else if is_single_generic(&gates[row]) {
+            match host {
+                Some(host_row) => {
+                    placements[row] = Placement::Merged(host_row);
+                    report.merged_gates += 1;
+                    host = None;
+                }
+                None => host = Some(row),
+            }
+        }
+    }
+
+    // Number the remaining rows
+    let mut new_rows = vec![0; gates.len()];
+    let mut rows_after = 0;
+    for (row, placement) in placements.iter().enumerate() {
+        if matches!(placement, Placement::Kept) {
+            new_rows[row] = rows_after;
+            rows_after += 1;
+        }
+    }
+    report.rows_after = rows_after;
+
+    // Map the cells still read by a gate
+    let cells: Vec<[Option<Cell>; COLUMNS]> = (0..gates.len())
+        .map(|row| {
+            array::from_fn(|col| {
+                if !is_live(gates, row, col, public) {
+                    return None;
+                }
+                match placements[row] {
+                    Placement::Kept => Some((new_rows[row], col)),
+                    Placement::Merged(host_row) => {
+                        Some((new_rows[host_row], GENERIC_REGISTERS + col))
+                    }
+                    Placement::Removed => None,
+                }
+            })
+        })
+        .collect();
+
+    // Lay out the gates, with the coefficients of the merged gates in the
+    // second half of their host
+    let mut optimized: Vec<CircuitGate<F>> = gates
+        .iter()
+        .zip(&placements)
+        .filter(|(_, placement)| matches!(placement, Placement::Kept))
+        .enumerate()
+        .map(|(new_row, (gate, _))| CircuitGate {
+            wires: Wire::for_row(new_row),
+            ..gate.clone()
+        })
+        .collect();
+    for (row, placement) in placements.iter().enumerate() {
+        if let Placement::Merged(host_row) = *placement {
+            let host = &mut optimized[new_rows[host_row]];
+            host.coeffs = (0..GENERIC_COEFFS)
+                .map(|i| coeff(host, i))
+                .chain((0..GENERIC_COEFFS).map(|i| coeff(&gates[row], i)))
+                .collect();
+        }
+    }
+
+    // Rewire the permutation, following each cycle of the original circuit
+    // and linking its remaining cells
+    let mut visited = vec![[false; PERMUTS]; gates.len()];
+    for row in 0..gates.len() {
+        for col in 0..PERMUTS {
+            if visited[row][col] {
+                continue;
+            }
+            let mut cycle = vec![];
+            let (mut r, mut c) = (row, col);
+            while !visited[r][c] {
+                visited[r][c] = true;
+                cycle.push((r, c));
+                let Wire {
+                    row: next_row,
+                    col: next_col,
+                } = gates[r].wires[c];
+                (r, c) = (next_row, next_col);
+            }
+            if cycle.len() == 1 {
+                continue;
+            }
+
+            let remaining: Vec<Cell> = cycle.iter().filter_map(|&(r, c)| cells[r][c]).collect();
+            report.unwired_cells += cycle.len() - remaining.len();
+            for (i, &(r, c)) in remaining.iter().enumerate() {
+                let (next_row, next_col) = remaining[(i + 1) % remaining.len()];
+                optimized[r].wires[c] = Wire::new(next_row, next_col);
+            }
+        }
+    }
+
+    OptimizedCircuit {
+        gates: optimized,
+        report,
+        cells,
+    }
+}
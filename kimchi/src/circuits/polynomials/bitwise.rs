@@ -0,0 +1,284 @@
+//! This module includes the word-level bitwise gadgets AND, OR, NAND and NOT,
+//! and the witness creation code. They reuse the XOR gadget and the Generic
+//! gate, and need no new gate type.
+//!
+//! The XOR gadget decomposes its words into 4-bit nybbles checked with the
+//! XOR lookup table, so that the other bitwise operations can be derived from
+//! the XOR of the words with a single double generic gate instead of a full
+//! bit decomposition. As for the [AND gadget](super::and), bitwise addition
+//! satisfies
+//!
+//! ```text
+//! a + b = (a xor b) + 2 * (a and b)
+//! ```
+//!
+//! so that, with `sum = a + b`, `xor = a xor b` and `1* = 2^bits - 1` the
+//! all-one word:
+//!
+//! ```text
+//! 2 * (a and b)  = sum - xor
+//! 2 * (a or b)   = sum + xor
+//! 2 * (a nand b) = 2 * 1* - sum + xor
+//! ```
+//!
+//! A binary operation over words of `bits` bits is laid out as follows:
+//!
+//! | Row         | `CircuitGate` | Purpose                                         |
+//! | ----------- | ------------- | ----------------------------------------------- |
+//! | i...i+n-1   | `Xor16`       | `xor = a xor b`, 16 bits per row                |
+//! | i+n         | `Generic`     | Zero values of the end of the XOR chain         |
+//! | i+n+1       | `Generic`     | `a + b = sum`, and the operation on `sum, xor`  |
+//!
+//! The negation `not a = a xor 1*` is the XOR of the word with the all-one
+//! word, which is held as a constant in the second half of the generic gate
+//! of the zero values, for `n + 1` rows.
+//!
+//! The words must have a multiple of 16 bits, which the XOR gadget checks to
+//! be the length of the inputs, so that 32- and 64-bit words take 4 and 6 rows
+//! for a binary operation, and 3 and 5 rows for a negation.
+
+use super::{
+    generic::GenericGateSpec,
+    xor::{init_xor, num_xors},
+};
+use crate::circuits::{
+    gate::{CircuitGate, Connect},
+    polynomial::COLUMNS,
+    wires::Wire,
+};
+use ark_ff::PrimeField;
+use core::array;
+use num_bigint::BigUint;
+use o1_utils::{BigUintFieldHelpers, BigUintHelpers, BitwiseOps, FieldHelpers, Two};
+
+/// A binary bitwise operation, computed from the sum and the XOR of its
+/// operands, see [self]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitwiseOp {
+    /// The conjunction `a and b`
+    And,
+    /// The disjunction `a or b`
+    Or,
+    /// The negated conjunction `not (a and b)`
+    Nand,
+}
+
+impl BitwiseOp {
+    /// Computes the operation on the words `input1` and `input2` of `bits`
+    /// bits
+    pub fn compute(self, input1: &BigUint, input2: &BigUint, bits: usize) -> BigUint {
+        let and = BigUint::bitwise_and(input1, input2, bits / 8);
+        match self {
+            BitwiseOp::And => and,
+            BitwiseOp::Or => input1 + input2 - and,
+            BitwiseOp::Nand => BigUint::bitwise_not(&and, Some(bits)),
+        }
+    }
+
+    // The coefficients of the second half of the generic gate, on the sum,
+    // the XOR and the output
+    fn coeffs<F: PrimeField>(self, bits: usize) -> [F; 5] {
+        let (xor, out, constant) = match self {
+            BitwiseOp::And => (-F::one(), -F::two(), F::zero()),
+            BitwiseOp::Or => (F::one(), -F::two(), F::zero()),
+            BitwiseOp::Nand => (-F::one(), F::two(), -F::two() * all_ones::<F>(bits)),
+        };
+        [F::one(), xor, out, F::zero(), constant]
+    }
+}
+
+// The all-one word `2^bits - 1`
+fn all_ones<F: PrimeField>(bits: usize) -> F {
+    F::from(2u8).pow([bits as u64]) - F::one()
+}
+
+// Checks that the words of `bits` bits are supported
+fn check_bits<F: PrimeField>(bits: usize) {
+    assert!(
+        bits > 0 && bits % 16 == 0,
+        "Bits must be a positive multiple of 16"
+    );
+    assert!(
+        bits < F::MODULUS_BIT_SIZE as usize - 1,
+        "Bits must be smaller than the native field"
+    );
+}
+
+impl<F: PrimeField> CircuitGate<F> {
+    /// Extends a bitwise operation gadget on words of `bits` bits, see
+    /// [self] for the layout.
+    /// The full operation being performed is the following, with
+    /// `1* = 2^bits - 1`:
+    /// - `a AND b = 1/2 * (a + b - (a XOR b))`
+    /// - `a OR b = 1/2 * (a + b + (a XOR b))`
+    /// - `a NAND b = 1* - 1/2 * (a + b - (a XOR b))`
+    ///
+    /// Includes:
+    /// - num_xors Xor16 gates to perform `xor = a XOR b`
+    /// - 1 Generic gate to constrain the final row to be zero with itself
+    /// - 1 double Generic gate to perform the operation as `a + b = sum` and
+    ///   the equation of the operation on `sum` and `xor`
+    ///
+    /// Input:
+    /// - gates    : vector of circuit gates comprising the full circuit
+    /// - op       : the bitwise operation
+    /// - bits     : number of bits of the words, a multiple of 16
+    ///
+    /// Output:
+    /// - next_row  : next row after this gate, the inputs being in the
+    ///   columns 0 and 1 of its first row and the output in the column 5 of
+    ///   its last row
+    ///
+    /// Warning:
+    /// - if there's any public input for the operation, don't forget to wire
+    ///   it
+    pub fn extend_bitwise(gates: &mut Vec<Self>, op: BitwiseOp, bits: usize) -> usize {
+        check_bits::<F>(bits);
+        let xor_row = gates.len();
+        let op_row = Self::extend_xor_gadget(gates, bits);
+        // a + b = sum, and the operation on sum and xor
+        let mut coeffs = [F::zero(); 10];
+        coeffs[..5].copy_from_slice(&[F::one(), F::one(), -F::one(), F::zero(), F::zero()]);
+        coeffs[5..].copy_from_slice(&op.coeffs(bits));
+        gates.push(CircuitGate::create_generic(Wire::for_row(op_row), coeffs));
+
+        // connect the XOR inputs to the inputs of the first generic gate
+        gates.connect_cell_pair((xor_row, 0), (op_row, 0));
+        gates.connect_cell_pair((xor_row, 1), (op_row, 1));
+        // connect the sum output of the first generic gate to the left input of the second generic gate
+        gates.connect_cell_pair((op_row, 2), (op_row, 3));
+        // connect the XOR output to the right input of the second generic gate
+        gates.connect_cell_pair((xor_row, 2), (op_row, 4));
+
+        gates.len()
+    }
+
+    /// Extends a NOT gadget on words of `bits` bits, as the XOR of the word
+    /// with the all-one word `2^bits - 1`, held as a constant
+    ///
+    /// Includes:
+    /// - num_xors Xor16 gates
+    /// - 1 double Generic gate to constrain the final row to be zero with
+    ///   itself, and holding the all-one word wired to the second input of the
+    ///   XOR
+    ///
+    /// Input:
+    /// - gates    : vector of circuit gates comprising the full circuit
+    /// - bits     : number of bits of the word, a multiple of 16
+    ///
+    /// Output:
+    /// - next_row  : next row after this gate, the input being in the column
+    ///   0 and the output in the column 2 of its first row
+    pub fn extend_not(gates: &mut Vec<Self>, bits: usize) -> usize {
+        check_bits::<F>(bits);
+        let xor_row = gates.len();
+        let (zero_row, mut xor_gates) = Self::create_xor_gadget(xor_row, bits);
+        let zero_row = zero_row - 1;
+        *xor_gates.last_mut().unwrap() = CircuitGate::create_generic_gadget(
+            Wire::for_row(zero_row),
+            GenericGateSpec::Const(F::zero()),
+            Some(GenericGateSpec::Const(all_ones(bits))),
+        );
+        gates.append(&mut xor_gates);
+
+        // check fin_in1, fin_in2, fin_out are zero
+        gates.connect_cell_pair((zero_row, 0), (zero_row, 1));
+        gates.connect_cell_pair((zero_row, 0), (zero_row, 2));
+        // the second input of the XOR is the all-one word
+        gates.connect_cell_pair((zero_row, 3), (xor_row, 1));
+
+        gates.len()
+    }
+}
+
+/// Create a bitwise operation witness for inputs as field elements starting
+/// at row 0
+/// Input: the operation, first input, second input, and number of bits
+/// Panics if the inputs are too large for the chosen number of bits
+pub fn create_bitwise_witness<F: PrimeField>(
+    op: BitwiseOp,
+    input1: F,
+    input2: F,
+    bits: usize,
+) -> [Vec<F>; COLUMNS] {
+    check_bits::<F>(bits);
+    let input1_big = input1.to_biguint();
+    let input2_big = input2.to_biguint();
+    if bits < input1_big.bitlen() || bits < input2_big.bitlen() {
+        panic!("Bits must be greater or equal than the inputs length");
+    }
+
+    let xor = BigUint::bitwise_xor(&input1_big, &input2_big)
+        .to_field()
+        .unwrap();
+    let output = op
+        .compute(&input1_big, &input2_big, bits)
+        .to_field()
+        .unwrap();
+    let sum = input1 + input2;
+
+    let op_row = num_xors(bits) + 1;
+    let mut witness: [Vec<F>; COLUMNS] = array::from_fn(|_| vec![F::zero(); op_row + 1]);
+
+    init_xor(&mut witness, 0, bits, (input1, input2, xor));
+    // Fill in double generic witness
+    for (col, value) in [input1, input2, sum, sum, xor, output]
+        .into_iter()
+        .enumerate()
+    {
+        witness[col][op_row] = value;
+    }
+
+    witness
+}
+
+/// Extends a bitwise operation witness to the whole witness
+/// Input: the operation, first input, second input, and number of bits
+/// Panics if the inputs are too large for the chosen number of bits
+pub fn extend_bitwise_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    op: BitwiseOp,
+    input1: F,
+    input2: F,
+    bits: usize,
+) {
+    let op_witness = create_bitwise_witness(op, input1, input2, bits);
+    for col in 0..COLUMNS {
+        witness[col].extend(op_witness[col].iter());
+    }
+}
+
+/// Create a NOT witness for an input as a field element starting at row 0,
+/// laid out as [CircuitGate::extend_not]
+/// Input: the input, and number of bits
+/// Panics if the input is too large for the chosen number of bits
+pub fn create_not_witness<F: PrimeField>(input: F, bits: usize) -> [Vec<F>; COLUMNS] {
+    check_bits::<F>(bits);
+    let input_big = input.to_biguint();
+    if bits < input_big.bitlen() {
+        panic!("Bits must be greater or equal than the input length");
+    }
+    let output = BigUint::bitwise_not(&input_big, Some(bits));
+
+    let zero_row = num_xors(bits);
+    let mut witness: [Vec<F>; COLUMNS] = array::from_fn(|_| vec![F::zero(); zero_row + 1]);
+    init_xor(
+        &mut witness,
+        0,
+        bits,
+        (input, all_ones(bits), output.to_field().unwrap()),
+    );
+    witness[3][zero_row] = all_ones(bits);
+
+    witness
+}
+
+/// Extends a NOT witness to the whole witness
+/// Input: the input, and number of bits
+/// Panics if the input is too large for the chosen number of bits
+pub fn extend_not_witness<F: PrimeField>(witness: &mut [Vec<F>; COLUMNS], input: F, bits: usize) {
+    let not_witness = create_not_witness(input, bits);
+    for col in 0..COLUMNS {
+        witness[col].extend(not_witness[col].iter());
+    }
+}
@@ -0,0 +1,150 @@
+//! This module implements the sub-circuits of the Cairo builtins executed by
+//! the turshi runner, and the code to create their witness.
+//! Like the AND gadget, they do not need any new gate type.
+//!
+//! The instances of a builtin segment are read from the memory of the
+//! execution with [turshi::builtins::BuiltinSegment::instances], and each of
+//! them is constrained by its own copy of the sub-circuit.
+//!
+//! Note that the sub-circuits are not wired to the Cairo circuit, which has no
+//! memory argument: they prove that their cells hold valid instances, not that
+//! these are the instances used by the execution.
+
+use super::{and, generic::GenericGateSpec, range_check, xor::num_xors};
+use crate::circuits::{
+    gate::{CircuitGate, Connect},
+    polynomial::COLUMNS,
+    wires::Wire,
+};
+use ark_ff::PrimeField;
+use o1_utils::{FieldHelpers, Two};
+
+//~ ##### Range check builtin
+//~
+//~ The range check builtin asserts that a value fits in 128 bits. The value is
+//~ split into two 64-bit limbs $value = lo + 2^{64} \cdot hi$ with a double
+//~ generic gate, whose first gate constrains a cell to be zero, and each limb is
+//~ constrained by a `RangeCheck0` gate whose two most significant 12-bit limbs
+//~ are wired to that zero cell.
+//~
+//~ | Row | Gate          | Columns                      |
+//~ | --- | ------------- | ---------------------------- |
+//~ | 0   | `Generic`     | `0`, -, -, `lo`, `hi`, `value` |
+//~ | 1   | `RangeCheck0` | `lo`                         |
+//~ | 2   | `RangeCheck0` | `hi`                         |
+//~
+//~ ##### Bitwise builtin
+//~
+//~ The bitwise builtin computes the AND, XOR and OR of two values. It reuses
+//~ the AND gadget, which already outputs both the XOR and the AND of its inputs,
+//~ followed by one generic gate for $or = xor + and$, as the bits set in the XOR
+//~ and in the AND are disjoint.
+
+impl<F: PrimeField> CircuitGate<F> {
+    /// Extends the circuit with the sub-circuit of one instance of the range
+    /// check builtin, constraining a value to 128 bits.
+    ///
+    /// Output:
+    /// - next_row  : next row after this gadget
+    ///
+    /// Warning:
+    /// - the value is in column 5 of the first row, don't forget to wire it
+    pub fn extend_cairo_range_check(gates: &mut Vec<Self>) -> usize {
+        let gen_row = gates.len();
+        // 0 = 0 and lo + 2^64 * hi = value
+        let zero = GenericGateSpec::Const(F::zero());
+        let split = GenericGateSpec::Add {
+            left_coeff: None,
+            right_coeff: Some(F::two_pow(64)),
+            output_coeff: None,
+        };
+        gates.push(Self::create_generic_gadget(
+            Wire::for_row(gen_row),
+            zero,
+            Some(split),
+        ));
+        let mut next_row = gen_row + 1;
+        Self::extend_range_check(gates, &mut next_row);
+        Self::extend_range_check(gates, &mut next_row);
+
+        // both limbs are 64 bits
+        gates.connect_64bit(gen_row, gen_row + 1);
+        gates.connect_64bit(gen_row, gen_row + 2);
+        // connect the limbs to the generic gate
+        gates.connect_cell_pair((gen_row, 3), (gen_row + 1, 0));
+        gates.connect_cell_pair((gen_row, 4), (gen_row + 2, 0));
+
+        gates.len()
+    }
+
+    /// Extends the circuit with the sub-circuit of one instance of the bitwise
+    /// builtin for inputs of `bytes` length.
+    ///
+    /// Output:
+    /// - next_row  : next row after this gadget
+    ///
+    /// Warning:
+    /// - the inputs are in columns 0 and 1 of the first row, and the AND, XOR
+    ///   and OR in columns 1, 0 and 2 of the last row, don't forget to wire them
+    pub fn extend_cairo_bitwise(gates: &mut Vec<Self>, bytes: usize) -> usize {
+        let and_row = Self::extend_and(gates, bytes) - 1;
+        let or_row = gates.len();
+        // xor + and = or
+        let or = GenericGateSpec::Add {
+            left_coeff: None,
+            right_coeff: None,
+            output_coeff: None,
+        };
+        gates.push(Self::create_generic_gadget(Wire::for_row(or_row), or, None));
+
+        gates.connect_cell_pair((and_row, 4), (or_row, 0));
+        gates.connect_cell_pair((and_row, 5), (or_row, 1));
+
+        gates.len()
+    }
+}
+
+/// Extends the witness with one instance of the range check builtin
+/// Panics if the value does not fit in 128 bits
+pub fn extend_cairo_range_check_witness<F: PrimeField>(witness: &mut [Vec<F>; COLUMNS], value: F) {
+    let bytes = value.to_bytes();
+    assert!(
+        bytes[16..].iter().all(|byte| *byte == 0),
+        "Value must fit in 128 bits"
+    );
+    let lo = F::from(u64::from_le_bytes(bytes[..8].try_into().unwrap()));
+    let hi = F::from(u64::from_le_bytes(bytes[8..16].try_into().unwrap()));
+
+    let mut row = [F::zero(); COLUMNS];
+    row[3] = lo;
+    row[4] = hi;
+    row[5] = value;
+    for col in 0..COLUMNS {
+        witness[col].push(row[col]);
+    }
+    range_check::witness::extend_single(witness, lo);
+    range_check::witness::extend_single(witness, hi);
+}
+
+/// Extends the witness with one instance of the bitwise builtin for inputs of
+/// `bytes` length
+/// Panics if the inputs are too large for the chosen number of bytes
+pub fn extend_cairo_bitwise_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    input1: F,
+    input2: F,
+    bytes: usize,
+) {
+    let and_row = witness[0].len() + num_xors(bytes * 8) + 1;
+    and::extend_and_witness(witness, input1, input2, bytes);
+    let xor = witness[4][and_row];
+    let and = witness[5][and_row];
+
+    let mut row = [F::zero(); COLUMNS];
+    row[0] = xor;
+    row[1] = and;
+    row[2] = xor + and;
+    for col in 0..COLUMNS {
+        witness[col].push(row[col]);
+    }
+}
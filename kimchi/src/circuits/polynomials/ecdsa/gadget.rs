@@ -0,0 +1,168 @@
+//! This module obtains the gates of the ECDSA verification gadget.
+
+use super::{
+    add, negate, offset_point, offset_sum, secp256k1_generator, secp256k1_modulus, secp256k1_order,
+    SCALAR_LIMB_BITS,
+};
+use crate::circuits::{
+    gate::CircuitGate,
+    polynomials::{
+        foreign_field_add::witness::FFOps, foreign_field_builder::gadget::ForeignFieldCircuit,
+    },
+};
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+
+pub use crate::circuits::polynomials::foreign_field_builder::gadget::{
+    Cell, ElementCells, PointCells,
+};
+
+/// The cells of the inputs of the ECDSA verification gadget, see
+/// [CircuitGate::extend_ecdsa_verify]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EcdsaCells {
+    /// The hash `z` of the message, as an integer of at most 256 bits
+    pub hash: ElementCells,
+    /// The first half `r` of the signature
+    pub r: ElementCells,
+    /// The second half `s` of the signature
+    pub s: ElementCells,
+    /// The public key `Q`
+    pub public_key: PointCells,
+}
+
+impl<F: PrimeField> CircuitGate<F> {
+    /// Extends the circuit with the verification of an ECDSA signature over
+    /// secp256k1, see [super] for the algorithm.
+    ///
+    /// Includes:
+    /// - Generic gates holding the constants, the bits of the scalars and the
+    ///   selection of the points added to the accumulator
+    /// - `ForeignFieldMul` and `ForeignFieldAdd` gates for the arithmetic
+    /// - the multi-range-checks of the limbs of every foreign field element
+    ///   and of their bounds
+    ///
+    /// Output:
+    /// - the cells of the hash, of the signature and of the public key, which
+    ///   the rest of the circuit must copy
+    ///
+    /// Warning:
+    /// - the results of the multiplications are only checked to have a high
+    ///   limb at most the one of the modulus, not to be fully reduced
+    /// - the additions of points are incomplete: a witness adding a point to
+    ///   itself or to its opposite, which happens with negligible probability
+    ///   for a valid signature, cannot be proven
+    pub fn extend_ecdsa_verify(gates: &mut Vec<Self>) -> EcdsaCells {
+        let (p, n) = (secp256k1_modulus(), secp256k1_order());
+        let offset = offset_point();
+        let generator_offset = add(&secp256k1_generator(), &offset);
+
+        let mut builder = ForeignFieldCircuit::new(gates);
+
+        // Inputs
+        let hash = builder.element(&n);
+        let r = builder.element(&n);
+        let s = builder.element(&n);
+        let public_key = (builder.element(&p), builder.element(&p));
+        assert_on_curve(&mut builder, &public_key);
+
+        // Scalars
+        let w = builder.element(&n);
+        let one = builder.one();
+        let s_w = builder.mul(&s, &w, &n);
+        builder.assert_equal(&s_w, &one);
+        let u1 = builder.mul(&hash, &w, &n);
+        let u2 = builder.mul(&r, &w, &n);
+        let (u1_limbs, u1_bits) = builder.bits(SCALAR_LIMB_BITS);
+        builder.assert_equal(&u1_limbs, &u1);
+        let (u2_limbs, u2_bits) = builder.bits(SCALAR_LIMB_BITS);
+        builder.assert_equal(&u2_limbs, &u2);
+
+        // Shamir's trick
+        let offset_cells = builder.constant_point(&offset);
+        let generator_offset_cells = builder.constant_point(&generator_offset);
+        let key_offset = add_points(&mut builder, &public_key, &offset_cells);
+        let key_generator_offset = add_points(&mut builder, &public_key, &generator_offset_cells);
+        let key_diff = builder.difference(&key_offset, &key_generator_offset);
+        let mut acc = offset_cells;
+        for (bit1, bit2) in u1_bits.into_iter().zip(u2_bits) {
+            acc = double(&mut builder, &acc);
+            let without_key = builder.select_constant(bit1, &offset, &generator_offset);
+            let with_key = builder.select(bit1, &key_offset, &key_diff);
+            let diff = builder.difference(&without_key, &with_key);
+            let summand = builder.select(bit2, &without_key, &diff);
+            acc = add_points(&mut builder, &acc, &summand);
+        }
+        let offset_sum = builder.constant_point(&negate(&offset_sum()));
+        let point = add_points(&mut builder, &acc, &offset_sum);
+
+        // Final check
+        let x = builder.mul(&point.0, &one, &n);
+        builder.assert_equal(&x, &r);
+        builder.finish();
+
+        EcdsaCells {
+            hash,
+            r,
+            s,
+            public_key,
+        }
+    }
+}
+
+// Extends the circuit with the check that `point` is on the curve
+// `y^2 = x^3 + 7`
+fn assert_on_curve<F: PrimeField>(builder: &mut ForeignFieldCircuit<F>, point: &PointCells) {
+    let p = secp256k1_modulus();
+    let seven = builder.constant(&BigUint::from(7u32));
+    let y2 = builder.mul(&point.1, &point.1, &p);
+    let x2 = builder.mul(&point.0, &point.0, &p);
+    let x3 = builder.mul(&x2, &point.0, &p);
+    let rhs = builder.add_chain(&[x3, seven], &[FFOps::Add], &p);
+    builder.assert_equal(&y2, &rhs);
+}
+
+// Extends the circuit with the addition of the points `left` and `right`, of
+// slope `λ * (right.x - left.x) = right.y - left.y`
+fn add_points<F: PrimeField>(
+    builder: &mut ForeignFieldCircuit<F>,
+    left: &PointCells,
+    right: &PointCells,
+) -> PointCells {
+    let p = secp256k1_modulus();
+    let num = builder.add_chain(&[right.1, left.1], &[FFOps::Sub], &p);
+    let den = builder.add_chain(&[right.0, left.0], &[FFOps::Sub], &p);
+    complete(builder, left, right, &num, &den)
+}
+
+// Extends the circuit with the doubling of `point`, of slope
+// `λ * 2 * point.y = 3 * point.x^2`
+fn double<F: PrimeField>(builder: &mut ForeignFieldCircuit<F>, point: &PointCells) -> PointCells {
+    let p = secp256k1_modulus();
+    let x2 = builder.mul(&point.0, &point.0, &p);
+    let num = builder.add_chain(&[x2, x2, x2], &[FFOps::Add, FFOps::Add], &p);
+    let den = builder.add_chain(&[point.1, point.1], &[FFOps::Add], &p);
+    complete(builder, point, point, &num, &den)
+}
+
+// Extends the circuit with the sum of `left` and `right`, given the numerator
+// and the denominator of the slope `λ` of the line through them:
+// `x = λ^2 - left.x - right.x` and `y = λ * (left.x - x) - left.y`
+fn complete<F: PrimeField>(
+    builder: &mut ForeignFieldCircuit<F>,
+    left: &PointCells,
+    right: &PointCells,
+    num: &ElementCells,
+    den: &ElementCells,
+) -> PointCells {
+    let p = secp256k1_modulus();
+    let lambda = builder.element(&p);
+    let prod = builder.mul(&lambda, den, &p);
+    builder.assert_equal(&prod, num);
+    let lambda2 = builder.mul(&lambda, &lambda, &p);
+    let x = builder.add_chain(&[lambda2, left.0, right.0], &[FFOps::Sub, FFOps::Sub], &p);
+    let dx = builder.add_chain(&[left.0, x], &[FFOps::Sub], &p);
+    let prod = builder.mul(&lambda, &dx, &p);
+    let y = builder.add_chain(&[prod, left.1], &[FFOps::Sub], &p);
+    (x, y)
+}
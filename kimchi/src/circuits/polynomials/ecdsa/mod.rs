@@ -0,0 +1,136 @@
+//! ECDSA signature verification over secp256k1
+//!
+//! The gadget verifies that `(r, s)` is a signature of the hash `z` under the
+//! public key `Q`, that is that the `x`-coordinate of
+//!
+//! ```text
+//! R = u1 * G + u2 * Q    with    u1 = z / s mod n,  u2 = r / s mod n
+//! ```
+//!
+//! is `r` modulo the order `n` of the curve. It is built from the
+//! [foreign field multiplication](super::foreign_field_mul) and
+//! [foreign field addition](super::foreign_field_add) gates, the elements of
+//! the base field and of the scalar field of secp256k1 being held in three
+//! 88-bit limbs:
+//!
+//! * `w = 1 / s` is computed by the prover and constrained by `s * w = 1` over
+//!   the scalar field, then `u1 = z * w` and `u2 = r * w`,
+//! * `u1` and `u2` are decomposed into bits, which are constrained to be
+//!   booleans and to recompose the limbs of `u1` and `u2`,
+//! * `R` is computed with Shamir's trick, doubling an accumulator and adding
+//!   one of `H`, `G + H`, `Q + H` and `G + Q + H` for each pair of bits. The
+//!   offset point `H` avoids adding the point at infinity, and the sum of its
+//!   multiples `(2^257 - 1) * H` is subtracted at the end,
+//! * the final check reduces the `x`-coordinate of `R` modulo `n` with a
+//!   multiplication by `1`, and copies the result to `r`.
+//!
+//! The points are added and doubled with the affine formulas, whose slope
+//! `λ` is computed by the prover and constrained by a multiplication. Each
+//! foreign field element is range-checked when it is created, by the
+//! multi-range-check of the remainder of its multiplication or of the result of
+//! its addition, and the whole verification takes 65916 rows.
+//!
+//! The gates are created by [CircuitGate::extend_ecdsa_verify](crate::circuits::gate::CircuitGate::extend_ecdsa_verify)
+//! and the witness by [witness::extend_ecdsa_verify_witness], which lay out
+//! the rows in the same order.
+
+pub mod gadget;
+pub mod witness;
+
+pub use super::foreign_field_builder::{inverse, sub, Point};
+
+use ark_ff::{One, Zero};
+use num_bigint::BigUint;
+use num_integer::Integer;
+use o1_utils::BigUintHelpers;
+
+/// The number of bits of the scalars `u1` and `u2`
+pub const SCALAR_BITS: usize = 256;
+
+/// The number of bits of the limbs of a scalar, from the lowest to the highest
+pub const SCALAR_LIMB_BITS: [usize; 3] = [88, 88, 80];
+
+/// The modulus of the base field of secp256k1
+pub fn secp256k1_modulus() -> BigUint {
+    BigUint::from_hex("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f")
+}
+
+/// The order of the group of points of secp256k1
+pub fn secp256k1_order() -> BigUint {
+    BigUint::from_hex("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141")
+}
+
+/// The generator of secp256k1
+pub fn secp256k1_generator() -> Point {
+    (
+        BigUint::from_hex("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+        BigUint::from_hex("483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8"),
+    )
+}
+
+/// The point `H` added to the accumulator of the scalar multiplication, whose
+/// `x`-coordinate is the first one after the ASCII encoding of
+/// `"kimchi ecdsa offset"` to be on the curve, and whose `y`-coordinate is even
+pub fn offset_point() -> Point {
+    let p = secp256k1_modulus();
+    let mut x = BigUint::from_bytes_be(b"kimchi ecdsa offset");
+    loop {
+        let y2 = (x.modpow(&BigUint::from(3u32), &p) + BigUint::from(7u32)) % &p;
+        // p = 3 mod 4, so that a square root is a power (p + 1) / 4
+        let y = y2.modpow(&((&p + BigUint::one()) >> 2), &p);
+        if (&y * &y) % &p == y2 {
+            let y = if y.is_even() { y } else { &p - y };
+            return (x, y);
+        }
+        x += BigUint::one();
+    }
+}
+
+/// The sum `(2^257 - 1) * H` of the multiples of the offset point added by the
+/// scalar multiplication of the gadget
+pub fn offset_sum() -> Point {
+    let h = offset_point();
+    (0..SCALAR_BITS).fold(h.clone(), |acc, _| add(&double(&acc), &h))
+}
+
+/// The slope of the line through the distinct points `left` and `right`
+pub fn add_slope(left: &Point, right: &Point) -> BigUint {
+    let p = secp256k1_modulus();
+    let num = sub(&right.1, &left.1, &p);
+    let den = sub(&right.0, &left.0, &p);
+    (num * inverse(&den, &p)) % &p
+}
+
+/// The slope of the tangent to the curve at `point`
+pub fn double_slope(point: &Point) -> BigUint {
+    let p = secp256k1_modulus();
+    let num = BigUint::from(3u32) * &point.0 * &point.0;
+    let den = BigUint::from(2u32) * &point.1;
+    (num * inverse(&(den % &p), &p)) % &p
+}
+
+/// The sum of `left` and `right`, given the slope `lambda` of the line through
+/// them
+fn complete(left: &Point, right: &Point, lambda: &BigUint) -> Point {
+    let p = secp256k1_modulus();
+    let x = sub(&sub(&(lambda * lambda), &left.0, &p), &right.0, &p);
+    let y = sub(&(lambda * sub(&left.0, &x, &p)), &left.1, &p);
+    (x, y)
+}
+
+/// Adds the points `left` and `right`, which must be distinct and not
+/// opposite
+pub fn add(left: &Point, right: &Point) -> Point {
+    complete(left, right, &add_slope(left, right))
+}
+
+/// Doubles `point`, which must not be of order 2
+pub fn double(point: &Point) -> Point {
+    complete(point, point, &double_slope(point))
+}
+
+/// Negates `point`
+pub fn negate(point: &Point) -> Point {
+    let p = secp256k1_modulus();
+    (point.0.clone(), sub(&BigUint::zero(), &point.1, &p))
+}
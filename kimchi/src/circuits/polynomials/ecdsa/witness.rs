@@ -0,0 +1,130 @@
+//! This module computes the witness of the ECDSA verification gadget.
+
+use super::{
+    add, add_slope, double_slope, inverse, negate, offset_point, offset_sum, secp256k1_generator,
+    secp256k1_modulus, secp256k1_order, Point, SCALAR_LIMB_BITS,
+};
+use crate::circuits::{
+    polynomial::COLUMNS,
+    polynomials::{
+        foreign_field_add::witness::FFOps, foreign_field_builder::witness::ForeignFieldWitness,
+    },
+};
+use ark_ff::{One, PrimeField};
+use num_bigint::BigUint;
+
+/// Extends the witness with the verification of the ECDSA signature `(r, s)`
+/// of `hash` under `public_key`, laid out as the gates of
+/// [CircuitGate::extend_ecdsa_verify](crate::circuits::gate::CircuitGate::extend_ecdsa_verify).
+///
+/// Returns whether the signature is valid, the final copy constraint not
+/// being satisfied otherwise.
+pub fn extend_ecdsa_verify_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    hash: &BigUint,
+    r: &BigUint,
+    s: &BigUint,
+    public_key: &Point,
+) -> bool {
+    let (p, n) = (secp256k1_modulus(), secp256k1_order());
+    let offset = offset_point();
+    let generator_offset = add(&secp256k1_generator(), &offset);
+
+    let mut builder = ForeignFieldWitness::new(witness);
+
+    // Inputs
+    builder.element(hash, &n);
+    builder.element(r, &n);
+    builder.element(s, &n);
+    builder.element(&public_key.0, &p);
+    builder.element(&public_key.1, &p);
+    extend_on_curve_witness(&mut builder, public_key);
+
+    // Scalars
+    let w = inverse(&(s % &n), &n);
+    builder.element(&w, &n);
+    builder.mul(s, &w, &n);
+    let u1 = builder.mul(hash, &w, &n);
+    let u2 = builder.mul(r, &w, &n);
+    let u1_bits = builder.bits(&u1, SCALAR_LIMB_BITS);
+    let u2_bits = builder.bits(&u2, SCALAR_LIMB_BITS);
+
+    // Shamir's trick
+    builder.constant_point(&offset);
+    builder.constant_point(&generator_offset);
+    let key_offset = extend_add_witness(&mut builder, public_key, &offset);
+    let key_generator_offset = extend_add_witness(&mut builder, public_key, &generator_offset);
+    builder.difference(&key_offset, &key_generator_offset);
+    let mut acc = offset.clone();
+    for (bit1, bit2) in u1_bits.into_iter().zip(u2_bits) {
+        acc = extend_double_witness(&mut builder, &acc);
+        let without_key = builder.select_constant(bit1, &offset, &generator_offset);
+        let with_key = builder.select(bit1, &key_offset, &key_generator_offset);
+        builder.difference(&without_key, &with_key);
+        let summand = builder.select(bit2, &without_key, &with_key);
+        acc = extend_add_witness(&mut builder, &acc, &summand);
+    }
+    let offset_sum = negate(&offset_sum());
+    builder.constant_point(&offset_sum);
+    let point = extend_add_witness(&mut builder, &acc, &offset_sum);
+
+    // Final check
+    let x = builder.mul(&point.0, &BigUint::one(), &n);
+    builder.finish();
+
+    x == *r
+}
+
+fn extend_on_curve_witness<F: PrimeField>(builder: &mut ForeignFieldWitness<F>, point: &Point) {
+    let p = secp256k1_modulus();
+    let seven = BigUint::from(7u32);
+    builder.constant(&seven);
+    builder.mul(&point.1, &point.1, &p);
+    let x2 = builder.mul(&point.0, &point.0, &p);
+    let x3 = builder.mul(&x2, &point.0, &p);
+    builder.add_chain(&[x3, seven], &[FFOps::Add], &p);
+}
+
+fn extend_add_witness<F: PrimeField>(
+    builder: &mut ForeignFieldWitness<F>,
+    left: &Point,
+    right: &Point,
+) -> Point {
+    let p = secp256k1_modulus();
+    builder.add_chain(&[right.1.clone(), left.1.clone()], &[FFOps::Sub], &p);
+    let den = builder.add_chain(&[right.0.clone(), left.0.clone()], &[FFOps::Sub], &p);
+    extend_complete_witness(builder, left, right, &add_slope(left, right), &den)
+}
+
+fn extend_double_witness<F: PrimeField>(
+    builder: &mut ForeignFieldWitness<F>,
+    point: &Point,
+) -> Point {
+    let p = secp256k1_modulus();
+    let x2 = builder.mul(&point.0, &point.0, &p);
+    builder.add_chain(&[x2.clone(), x2.clone(), x2], &[FFOps::Add, FFOps::Add], &p);
+    let den = builder.add_chain(&[point.1.clone(), point.1.clone()], &[FFOps::Add], &p);
+    extend_complete_witness(builder, point, point, &double_slope(point), &den)
+}
+
+fn extend_complete_witness<F: PrimeField>(
+    builder: &mut ForeignFieldWitness<F>,
+    left: &Point,
+    right: &Point,
+    lambda: &BigUint,
+    den: &BigUint,
+) -> Point {
+    let p = secp256k1_modulus();
+    builder.element(lambda, &p);
+    builder.mul(lambda, den, &p);
+    let lambda2 = builder.mul(lambda, lambda, &p);
+    let x = builder.add_chain(
+        &[lambda2, left.0.clone(), right.0.clone()],
+        &[FFOps::Sub, FFOps::Sub],
+        &p,
+    );
+    let dx = builder.add_chain(&[left.0.clone(), x.clone()], &[FFOps::Sub], &p);
+    let prod = builder.mul(lambda, &dx, &p);
+    let y = builder.add_chain(&[prod, left.1.clone()], &[FFOps::Sub], &p);
+    (x, y)
+}
@@ -0,0 +1,216 @@
+//! This module obtains the gates of the EdDSA verification gadget.
+
+use super::{
+    ed25519_d, ed25519_generator, ed25519_modulus, ed25519_order, identity, DIGEST_LIMBS,
+    SCALAR_LIMB_BITS,
+};
+use crate::circuits::{
+    gate::CircuitGate,
+    polynomials::{
+        foreign_field_add::witness::FFOps,
+        foreign_field_builder::gadget::{Cell, ElementCells, ForeignFieldCircuit, PointCells},
+    },
+};
+use ark_ff::{One, PrimeField};
+use num_bigint::BigUint;
+
+/// The cells of the inputs of the EdDSA verification gadget, see
+/// [CircuitGate::extend_eddsa_verify]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EddsaCells {
+    /// The 88-bit limbs of the digest `H(R || A || M)`, from the lowest to the
+    /// highest
+    pub digest: [Cell; DIGEST_LIMBS],
+    /// The first half `R` of the signature
+    pub r: PointCells,
+    /// The second half `S` of the signature
+    pub s: ElementCells,
+    /// The public key `A`
+    pub public_key: PointCells,
+}
+
+// The cells of the constants of the curve
+struct Curve {
+    // The parameter `d`
+    d: ElementCells,
+    // The element `2`
+    two: ElementCells,
+}
+
+impl<F: PrimeField> CircuitGate<F> {
+    /// Extends the circuit with the verification of an EdDSA signature over
+    /// Ed25519, see [super] for the algorithm.
+    ///
+    /// Includes:
+    /// - Generic gates holding the constants, the bits of the scalars and the
+    ///   selection of the points added to the accumulator
+    /// - `ForeignFieldMul` and `ForeignFieldAdd` gates for the arithmetic
+    /// - the multi-range-checks of the limbs of the digest, of every foreign
+    ///   field element and of their bounds
+    ///
+    /// Output:
+    /// - the cells of the digest, of the signature and of the public key,
+    ///   which the rest of the circuit must copy
+    ///
+    /// Warning:
+    /// - the gadget does not hash: the rest of the circuit must bind the digest
+    ///   to the encodings of `R` and `A` and to the message, or the verifier
+    ///   must check it
+    /// - the verification is cofactorless and does not check the order of `A`
+    pub fn extend_eddsa_verify(gates: &mut Vec<Self>) -> EddsaCells {
+        let (p, l) = (ed25519_modulus(), ed25519_order());
+        let (generator, identity) = (ed25519_generator(), identity());
+
+        let mut builder = ForeignFieldCircuit::new(gates);
+        let zero = [builder.zero(); 3];
+
+        // Inputs
+        let digest_lo = builder.limbs();
+        let digest_hi = builder.limbs();
+        let r = (builder.element(&p), builder.element(&p));
+        let s = builder.element(&l);
+        let public_key = (builder.element(&p), builder.element(&p));
+        let curve = Curve {
+            d: builder.constant(&ed25519_d()),
+            two: builder.constant(&BigUint::from(2u32)),
+        };
+        assert_on_curve(&mut builder, &curve, &public_key);
+
+        // Scalars
+        let s_reduced = builder.add_chain(&[s, zero], &[FFOps::Add], &l);
+        builder.assert_equal(&s_reduced, &s);
+        let two_to_176 = builder.constant(&(BigUint::one() << 176));
+        let two_to_352 = builder.constant(&((BigUint::one() << 352) % &l));
+        let digest_0 = [digest_lo[0], digest_lo[1], zero[0]];
+        let digest_1 = [digest_lo[2], digest_hi[0], zero[0]];
+        let digest_2 = [digest_hi[1], digest_hi[2], zero[0]];
+        let digest_1 = builder.mul(&digest_1, &two_to_176, &l);
+        let digest_2 = builder.mul(&digest_2, &two_to_352, &l);
+        let k = builder.add_chain(
+            &[digest_0, digest_1, digest_2],
+            &[FFOps::Add, FFOps::Add],
+            &l,
+        );
+        let (s_limbs, s_bits) = builder.bits(SCALAR_LIMB_BITS);
+        builder.assert_equal(&s_limbs, &s);
+        let (k_limbs, k_bits) = builder.bits(SCALAR_LIMB_BITS);
+        builder.assert_equal(&k_limbs, &k);
+
+        // Shamir's trick
+        let generator_cells = builder.constant_point(&generator);
+        let negated_key = (
+            builder.add_chain(&[zero, public_key.0], &[FFOps::Sub], &p),
+            public_key.1,
+        );
+        let generator_negated_key = add(&mut builder, &curve, &generator_cells, &negated_key);
+        let key_diff = builder.difference(&negated_key, &generator_negated_key);
+        let mut acc = (zero, builder.one());
+        for (bit1, bit2) in s_bits.into_iter().zip(k_bits) {
+            acc = double(&mut builder, &curve, &acc);
+            let without_key = builder.select_constant(bit1, &identity, &generator);
+            let with_key = builder.select(bit1, &negated_key, &key_diff);
+            let diff = builder.difference(&without_key, &with_key);
+            let summand = builder.select(bit2, &without_key, &diff);
+            acc = add(&mut builder, &curve, &acc, &summand);
+        }
+
+        // Final check
+        builder.assert_equal(&acc.0, &r.0);
+        builder.assert_equal(&acc.1, &r.1);
+        builder.finish();
+
+        EddsaCells {
+            digest: [
+                digest_lo[0],
+                digest_lo[1],
+                digest_lo[2],
+                digest_hi[0],
+                digest_hi[1],
+                digest_hi[2],
+            ],
+            r,
+            s,
+            public_key,
+        }
+    }
+}
+
+// Extends the circuit with the check that `point` is on the curve
+// `-x^2 + y^2 = 1 + d * x^2 * y^2`
+fn assert_on_curve<F: PrimeField>(
+    builder: &mut ForeignFieldCircuit<F>,
+    curve: &Curve,
+    point: &PointCells,
+) {
+    let p = ed25519_modulus();
+    let one = builder.one();
+    let x2 = builder.mul(&point.0, &point.0, &p);
+    let y2 = builder.mul(&point.1, &point.1, &p);
+    let t = builder.mul(&x2, &y2, &p);
+    let dt = builder.mul(&t, &curve.d, &p);
+    let lhs = builder.add_chain(&[y2, x2], &[FFOps::Sub], &p);
+    let rhs = builder.add_chain(&[one, dt], &[FFOps::Add], &p);
+    builder.assert_equal(&lhs, &rhs);
+}
+
+// Extends the circuit with the addition of the points `left` and `right`:
+// `x * (1 + d * t) = x1 * y2 + y1 * x2` and `y * (1 - d * t) = y1 * y2 + x1 * x2`
+// with `t = x1 * x2 * y1 * y2`
+fn add<F: PrimeField>(
+    builder: &mut ForeignFieldCircuit<F>,
+    curve: &Curve,
+    left: &PointCells,
+    right: &PointCells,
+) -> PointCells {
+    let p = ed25519_modulus();
+    let one = builder.one();
+    let x1y2 = builder.mul(&left.0, &right.1, &p);
+    let y1x2 = builder.mul(&left.1, &right.0, &p);
+    let y1y2 = builder.mul(&left.1, &right.1, &p);
+    let x1x2 = builder.mul(&left.0, &right.0, &p);
+    let t = builder.mul(&x1x2, &y1y2, &p);
+    let dt = builder.mul(&t, &curve.d, &p);
+    let x_num = builder.add_chain(&[x1y2, y1x2], &[FFOps::Add], &p);
+    let x_den = builder.add_chain(&[one, dt], &[FFOps::Add], &p);
+    let y_num = builder.add_chain(&[y1y2, x1x2], &[FFOps::Add], &p);
+    let y_den = builder.add_chain(&[one, dt], &[FFOps::Sub], &p);
+    complete(builder, &x_num, &x_den, &y_num, &y_den)
+}
+
+// Extends the circuit with the doubling of `point`, whose denominators are
+// simplified with the equation of the curve:
+// `x * (y1^2 - x1^2) = 2 * x1 * y1` and `y * (2 - y1^2 + x1^2) = y1^2 + x1^2`
+fn double<F: PrimeField>(
+    builder: &mut ForeignFieldCircuit<F>,
+    curve: &Curve,
+    point: &PointCells,
+) -> PointCells {
+    let p = ed25519_modulus();
+    let xy = builder.mul(&point.0, &point.1, &p);
+    let x2 = builder.mul(&point.0, &point.0, &p);
+    let y2 = builder.mul(&point.1, &point.1, &p);
+    let x_num = builder.add_chain(&[xy, xy], &[FFOps::Add], &p);
+    let x_den = builder.add_chain(&[y2, x2], &[FFOps::Sub], &p);
+    let y_num = builder.add_chain(&[y2, x2], &[FFOps::Add], &p);
+    let y_den = builder.add_chain(&[curve.two, y2, x2], &[FFOps::Sub, FFOps::Add], &p);
+    complete(builder, &x_num, &x_den, &y_num, &y_den)
+}
+
+// Extends the circuit with the coordinates of a sum computed by the prover,
+// given the numerators and the denominators of their formulas
+fn complete<F: PrimeField>(
+    builder: &mut ForeignFieldCircuit<F>,
+    x_num: &ElementCells,
+    x_den: &ElementCells,
+    y_num: &ElementCells,
+    y_den: &ElementCells,
+) -> PointCells {
+    let p = ed25519_modulus();
+    let x = builder.element(&p);
+    let prod = builder.mul(&x, x_den, &p);
+    builder.assert_equal(&prod, x_num);
+    let y = builder.element(&p);
+    let prod = builder.mul(&y, y_den, &p);
+    builder.assert_equal(&prod, y_num);
+    (x, y)
+}
@@ -0,0 +1,191 @@
+//! EdDSA signature verification over Ed25519
+//!
+//! The gadget verifies that `(R, S)` is a signature of a message under the
+//! public key `A`, that is that
+//!
+//! ```text
+//! S * B = R + k * A    with    k = H(R || A || M) mod ℓ
+//! ```
+//!
+//! where `B` is the base point of Ed25519, `ℓ` is the order of its subgroup
+//! and `H` is SHA-512 for Ed25519, or any hash of at most 528 bits given by
+//! [challenge]. It is built from the
+//! [foreign field builders](super::foreign_field_builder), the elements of the
+//! base field and of the scalar field of Ed25519 being held in three 88-bit
+//! limbs:
+//!
+//! * the digest `H(R || A || M)` is given in six 88-bit limbs and reduced
+//!   modulo `ℓ` with two multiplications by the constants `2^176` and
+//!   `2^352 mod ℓ`,
+//! * `S` is checked to be smaller than `ℓ` by copying it to the result of its
+//!   addition to `0` modulo `ℓ`, which rejects the malleable signatures,
+//! * `A` is checked to be on the curve,
+//! * `S` and `k` are decomposed into 253 bits, which are constrained to be
+//!   booleans and to recompose their limbs,
+//! * `S * B - k * A` is computed with Shamir's trick from the identity,
+//!   doubling an accumulator and adding one of `O`, `B`, `-A` and `B - A` for
+//!   each pair of bits, and is copied to `R`.
+//!
+//! Ed25519 is the twisted Edwards curve `-x^2 + y^2 = 1 + d * x^2 * y^2`,
+//! whose addition formula is complete: the gadget does not need an offset
+//! point, and has no exceptional case. The coordinates of a sum are computed
+//! by the prover and constrained by a multiplication with the denominator of
+//! the formula, and the whole verification takes 85946 rows.
+//!
+//! The gates are created by [CircuitGate::extend_eddsa_verify](crate::circuits::gate::CircuitGate::extend_eddsa_verify)
+//! and the witness by [witness::extend_eddsa_verify_witness], which lay out
+//! the rows in the same order.
+
+pub mod gadget;
+pub mod witness;
+
+pub use super::foreign_field_builder::Point;
+
+use super::foreign_field_builder::{inverse, sub};
+use ark_ff::{One, Zero};
+use blake2::Digest;
+use num_bigint::BigUint;
+use num_integer::Integer;
+use o1_utils::BigUintHelpers;
+
+/// The number of bits of the scalars `S` and `k`
+pub const SCALAR_BITS: usize = 253;
+
+/// The number of bits of the limbs of a scalar, from the lowest to the highest
+pub const SCALAR_LIMB_BITS: [usize; 3] = [88, 88, 77];
+
+/// The number of 88-bit limbs of the digest of the challenge
+pub const DIGEST_LIMBS: usize = 6;
+
+/// The modulus `2^255 - 19` of the base field of Ed25519
+pub fn ed25519_modulus() -> BigUint {
+    (BigUint::one() << 255) - BigUint::from(19u32)
+}
+
+/// The order `ℓ` of the subgroup of Ed25519 generated by its base point
+pub fn ed25519_order() -> BigUint {
+    BigUint::from_hex("1000000000000000000000000000000014def9dea2f79cd65812631a5cf5d3ed")
+}
+
+/// The parameter `d = -121665 / 121666` of Ed25519
+pub fn ed25519_d() -> BigUint {
+    let p = ed25519_modulus();
+    let d = BigUint::from(121665u32) * inverse(&BigUint::from(121666u32), &p);
+    sub(&BigUint::zero(), &d, &p)
+}
+
+/// The base point `B` of Ed25519, of `y`-coordinate `4 / 5` and even
+/// `x`-coordinate
+pub fn ed25519_generator() -> Point {
+    let p = ed25519_modulus();
+    let y = (BigUint::from(4u32) * inverse(&BigUint::from(5u32), &p)) % &p;
+    (recover_x(&y, false).unwrap(), y)
+}
+
+/// The identity `(0, 1)` of Ed25519
+pub fn identity() -> Point {
+    (BigUint::zero(), BigUint::one())
+}
+
+/// Adds the points `left` and `right` with the complete formula
+///
+/// ```text
+/// x = (x1 * y2 + y1 * x2) / (1 + d * x1 * x2 * y1 * y2)
+/// y = (y1 * y2 + x1 * x2) / (1 - d * x1 * x2 * y1 * y2)
+/// ```
+pub fn add(left: &Point, right: &Point) -> Point {
+    let p = ed25519_modulus();
+    let dt = (ed25519_d() * &left.0 * &right.0 % &p) * &left.1 * &right.1 % &p;
+    let x = (&left.0 * &right.1 + &left.1 * &right.0) % &p;
+    let y = (&left.1 * &right.1 + &left.0 * &right.0) % &p;
+    (
+        x * inverse(&((BigUint::one() + &dt) % &p), &p) % &p,
+        y * inverse(&sub(&BigUint::one(), &dt, &p), &p) % &p,
+    )
+}
+
+/// Negates `point`
+pub fn negate(point: &Point) -> Point {
+    let p = ed25519_modulus();
+    (sub(&BigUint::zero(), &point.0, &p), point.1.clone())
+}
+
+/// Multiplies `point` by `scalar`
+pub fn scalar_mul(point: &Point, scalar: &BigUint) -> Point {
+    (0..scalar.bits()).rev().fold(identity(), |acc, i| {
+        let acc = add(&acc, &acc);
+        if scalar.bit(i) {
+            add(&acc, point)
+        } else {
+            acc
+        }
+    })
+}
+
+/// Whether `point` is on Ed25519
+pub fn is_on_curve(point: &Point) -> bool {
+    let p = ed25519_modulus();
+    let (x2, y2) = (&point.0 * &point.0 % &p, &point.1 * &point.1 % &p);
+    let rhs = (BigUint::one() + ed25519_d() * &x2 % &p * &y2) % &p;
+    sub(&y2, &x2, &p) == rhs
+}
+
+/// The `x`-coordinate of the point of `y`-coordinate `y` whose parity is
+/// `odd`, if any
+fn recover_x(y: &BigUint, odd: bool) -> Option<BigUint> {
+    let p = ed25519_modulus();
+    // x^2 = (y^2 - 1) / (d * y^2 + 1)
+    let y2 = y * y % &p;
+    let u = sub(&y2, &BigUint::one(), &p);
+    let v = (ed25519_d() * &y2 + BigUint::one()) % &p;
+    let x2 = u * inverse(&v, &p) % &p;
+    // p = 5 mod 8, so that a square root is a power (p + 3) / 8, possibly
+    // multiplied by a square root of -1
+    let mut x = x2.modpow(&((&p + BigUint::from(3u32)) >> 3), &p);
+    if &x * &x % &p != x2 {
+        let sqrt_minus_one = BigUint::from(2u32).modpow(&((&p - BigUint::one()) >> 2), &p);
+        x = x * sqrt_minus_one % &p;
+    }
+    if &x * &x % &p != x2 || (x.is_zero() && odd) {
+        return None;
+    }
+    Some(if x.is_odd() == odd { x } else { &p - x })
+}
+
+/// The encoding of `point` in 32 bytes: its `y`-coordinate in little-endian,
+/// whose most significant bit is the parity of its `x`-coordinate
+pub fn encode(point: &Point) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    let y = point.1.to_bytes_le();
+    bytes[..y.len()].copy_from_slice(&y);
+    if point.0.is_odd() {
+        bytes[31] |= 0x80;
+    }
+    bytes
+}
+
+/// The point of encoding `bytes`, if it is a canonical encoding of a point of
+/// Ed25519
+pub fn decode(bytes: &[u8; 32]) -> Option<Point> {
+    let mut y = *bytes;
+    y[31] &= 0x7f;
+    let y = BigUint::from_bytes_le(&y);
+    if y >= ed25519_modulus() {
+        return None;
+    }
+    let x = recover_x(&y, bytes[31] >> 7 == 1)?;
+    Some((x, y))
+}
+
+/// The digest `H(R || A || M)` of the signature `R` of `message` under
+/// `public_key`, as a little-endian integer to be reduced by the gadget. The
+/// output of the hash `D`, SHA-512 for Ed25519, must have at most 66 bytes.
+pub fn challenge<D: Digest>(r: &Point, public_key: &Point, message: &[u8]) -> BigUint {
+    let digest = D::new()
+        .chain_update(encode(r))
+        .chain_update(encode(public_key))
+        .chain_update(message)
+        .finalize();
+    assert!(digest.len() * 8 <= DIGEST_LIMBS * 88);
+    BigUint::from_bytes_le(&digest)
+}
@@ -0,0 +1,153 @@
+//! This module computes the witness of the EdDSA verification gadget.
+
+use super::{
+    add, ed25519_d, ed25519_generator, ed25519_modulus, ed25519_order, identity, Point,
+    DIGEST_LIMBS, SCALAR_LIMB_BITS,
+};
+use crate::circuits::{
+    polynomial::COLUMNS,
+    polynomials::{
+        foreign_field_add::witness::FFOps, foreign_field_builder::witness::ForeignFieldWitness,
+        foreign_field_common::BigUintForeignFieldHelpers,
+    },
+};
+use ark_ff::{One, PrimeField, Zero};
+use num_bigint::BigUint;
+
+/// Extends the witness with the verification of the EdDSA signature `(r, s)`
+/// under `public_key`, whose challenge has the digest `digest` given by
+/// [super::challenge], laid out as the gates of
+/// [CircuitGate::extend_eddsa_verify](crate::circuits::gate::CircuitGate::extend_eddsa_verify).
+///
+/// Returns whether the signature is valid, the final copy constraints not
+/// being satisfied otherwise.
+pub fn extend_eddsa_verify_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    digest: &BigUint,
+    r: &Point,
+    s: &BigUint,
+    public_key: &Point,
+) -> bool {
+    assert!(digest.bits() as usize <= DIGEST_LIMBS * 88);
+    let (p, l) = (ed25519_modulus(), ed25519_order());
+    let (generator, identity) = (ed25519_generator(), identity());
+
+    let mut builder = ForeignFieldWitness::new(witness);
+    let zero = BigUint::zero();
+
+    // Inputs
+    let digest_lo = digest % BigUint::binary_modulus();
+    let digest_hi = digest >> 264;
+    builder.limbs(&digest_lo);
+    builder.limbs(&digest_hi);
+    builder.element(&r.0, &p);
+    builder.element(&r.1, &p);
+    builder.element(s, &l);
+    builder.element(&public_key.0, &p);
+    builder.element(&public_key.1, &p);
+    builder.constant(&ed25519_d());
+    builder.constant(&BigUint::from(2u32));
+    extend_on_curve_witness(&mut builder, public_key);
+
+    // Scalars
+    builder.add_chain(&[s.clone(), zero.clone()], &[FFOps::Add], &l);
+    let two_to_176 = BigUint::one() << 176;
+    let two_to_352 = (BigUint::one() << 352) % &l;
+    builder.constant(&two_to_176);
+    builder.constant(&two_to_352);
+    let digest_0 = digest % (BigUint::one() << 176);
+    let digest_1 = builder.mul(&((digest >> 176) % &two_to_176), &two_to_176, &l);
+    let digest_2 = builder.mul(&(digest >> 352), &two_to_352, &l);
+    let k = builder.add_chain(
+        &[digest_0, digest_1, digest_2],
+        &[FFOps::Add, FFOps::Add],
+        &l,
+    );
+    let s_bits = builder.bits(s, SCALAR_LIMB_BITS);
+    let k_bits = builder.bits(&k, SCALAR_LIMB_BITS);
+
+    // Shamir's trick
+    builder.constant_point(&generator);
+    let negated_key = (
+        builder.add_chain(&[zero, public_key.0.clone()], &[FFOps::Sub], &p),
+        public_key.1.clone(),
+    );
+    let generator_negated_key = extend_add_witness(&mut builder, &generator, &negated_key);
+    builder.difference(&negated_key, &generator_negated_key);
+    let mut acc = identity.clone();
+    for (bit1, bit2) in s_bits.into_iter().zip(k_bits) {
+        acc = extend_double_witness(&mut builder, &acc);
+        let without_key = builder.select_constant(bit1, &identity, &generator);
+        let with_key = builder.select(bit1, &negated_key, &generator_negated_key);
+        builder.difference(&without_key, &with_key);
+        let summand = builder.select(bit2, &without_key, &with_key);
+        acc = extend_add_witness(&mut builder, &acc, &summand);
+    }
+
+    // Final check
+    builder.finish();
+
+    acc == *r
+}
+
+fn extend_on_curve_witness<F: PrimeField>(builder: &mut ForeignFieldWitness<F>, point: &Point) {
+    let p = ed25519_modulus();
+    let x2 = builder.mul(&point.0, &point.0, &p);
+    let y2 = builder.mul(&point.1, &point.1, &p);
+    let t = builder.mul(&x2, &y2, &p);
+    let dt = builder.mul(&t, &ed25519_d(), &p);
+    builder.add_chain(&[y2, x2], &[FFOps::Sub], &p);
+    builder.add_chain(&[BigUint::one(), dt], &[FFOps::Add], &p);
+}
+
+fn extend_add_witness<F: PrimeField>(
+    builder: &mut ForeignFieldWitness<F>,
+    left: &Point,
+    right: &Point,
+) -> Point {
+    let p = ed25519_modulus();
+    let x1y2 = builder.mul(&left.0, &right.1, &p);
+    let y1x2 = builder.mul(&left.1, &right.0, &p);
+    let y1y2 = builder.mul(&left.1, &right.1, &p);
+    let x1x2 = builder.mul(&left.0, &right.0, &p);
+    let t = builder.mul(&x1x2, &y1y2, &p);
+    let dt = builder.mul(&t, &ed25519_d(), &p);
+    builder.add_chain(&[x1y2, y1x2], &[FFOps::Add], &p);
+    let x_den = builder.add_chain(&[BigUint::one(), dt.clone()], &[FFOps::Add], &p);
+    builder.add_chain(&[y1y2, x1x2], &[FFOps::Add], &p);
+    let y_den = builder.add_chain(&[BigUint::one(), dt], &[FFOps::Sub], &p);
+    extend_complete_witness(builder, &add(left, right), &x_den, &y_den)
+}
+
+fn extend_double_witness<F: PrimeField>(
+    builder: &mut ForeignFieldWitness<F>,
+    point: &Point,
+) -> Point {
+    let p = ed25519_modulus();
+    let xy = builder.mul(&point.0, &point.1, &p);
+    let x2 = builder.mul(&point.0, &point.0, &p);
+    let y2 = builder.mul(&point.1, &point.1, &p);
+    builder.add_chain(&[xy.clone(), xy], &[FFOps::Add], &p);
+    let x_den = builder.add_chain(&[y2.clone(), x2.clone()], &[FFOps::Sub], &p);
+    builder.add_chain(&[y2.clone(), x2.clone()], &[FFOps::Add], &p);
+    let y_den = builder.add_chain(
+        &[BigUint::from(2u32), y2, x2],
+        &[FFOps::Sub, FFOps::Add],
+        &p,
+    );
+    extend_complete_witness(builder, &add(point, point), &x_den, &y_den)
+}
+
+fn extend_complete_witness<F: PrimeField>(
+    builder: &mut ForeignFieldWitness<F>,
+    point: &Point,
+    x_den: &BigUint,
+    y_den: &BigUint,
+) -> Point {
+    let p = ed25519_modulus();
+    builder.element(&point.0, &p);
+    builder.mul(&point.0, x_den, &p);
+    builder.element(&point.1, &p);
+    builder.mul(&point.1, y_den, &p);
+    point.clone()
+}
@@ -0,0 +1,365 @@
+//! This module obtains the gates of the foreign curve gadgets.
+
+use super::{scalar_limb_bits, window_sizes, ForeignCurve};
+use crate::circuits::{
+    gate::CircuitGate,
+    polynomials::{
+        foreign_field_add::witness::FFOps, foreign_field_builder::gadget::ForeignFieldCircuit,
+    },
+};
+use ark_ff::{PrimeField, Zero};
+
+pub use crate::circuits::polynomials::foreign_field_builder::gadget::{
+    Cell, ElementCells, PointCells,
+};
+
+/// The cells of the complete addition gadget, see
+/// [CircuitGate::extend_foreign_curve_add]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForeignCurveAddCells {
+    /// The left summand
+    pub left: PointCells,
+    /// The right summand
+    pub right: PointCells,
+    /// The sum, whose coordinates are meaningless if it is the point at
+    /// infinity
+    pub sum: PointCells,
+    /// The boolean flagging a sum equal to the point at infinity
+    pub infinity: Cell,
+}
+
+/// The cells of the scalar multiplication gadget, see
+/// [CircuitGate::extend_foreign_curve_scalar_mul]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForeignCurveScalarMulCells {
+    /// The point `P`
+    pub point: PointCells,
+    /// The limbs of the scalar
+    pub scalar: ElementCells,
+    /// The product, whose coordinates are meaningless if it is the point at
+    /// infinity
+    pub product: PointCells,
+    /// The boolean flagging a product equal to the point at infinity
+    pub infinity: Cell,
+}
+
+impl<F: PrimeField> CircuitGate<F> {
+    /// Extends the circuit with the complete addition of two points of
+    /// `curve`, see [super] for the algorithm.
+    ///
+    /// Output:
+    /// - the cells of the summands, which are checked to be on the curve, and
+    ///   of the sum and of its flag, which the rest of the circuit must copy
+    ///
+    /// Warning:
+    /// - the summands must not be the point at infinity, and a witness
+    ///   doubling a point of order 2 cannot be proven
+    pub fn extend_foreign_curve_add(
+        gates: &mut Vec<Self>,
+        curve: &ForeignCurve,
+    ) -> ForeignCurveAddCells {
+        let mut builder = ForeignFieldCircuit::new(gates);
+        let circuit = ForeignCurveCircuit::new(&mut builder, curve);
+        let left = circuit.point(&mut builder);
+        let right = circuit.point(&mut builder);
+        let (sum, infinity) = circuit.add(&mut builder, &left, &right);
+        builder.finish();
+
+        ForeignCurveAddCells {
+            left,
+            right,
+            sum,
+            infinity,
+        }
+    }
+
+    /// Extends the circuit with the multiplication of a point of `curve` by a
+    /// scalar of `scalar_bits` bits, with windows of `window` bits, see
+    /// [super] for the algorithm.
+    ///
+    /// Output:
+    /// - the cells of the point, which is checked to be on the curve, of the
+    ///   limbs of the scalar, and of the product and of its flag, which the
+    ///   rest of the circuit must copy
+    ///
+    /// Warning:
+    /// - the additions of the accumulator are incomplete: a point whose small
+    ///   multiples meet the offset point, which happens with negligible
+    ///   probability, cannot be proven
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the scalar has no bits or more than
+    /// [MAX_SCALAR_BITS](super::MAX_SCALAR_BITS), or if the window is empty.
+    pub fn extend_foreign_curve_scalar_mul(
+        gates: &mut Vec<Self>,
+        curve: &ForeignCurve,
+        scalar_bits: usize,
+        window: usize,
+    ) -> ForeignCurveScalarMulCells {
+        let mut builder = ForeignFieldCircuit::new(gates);
+        let circuit = ForeignCurveCircuit::new(&mut builder, curve);
+        let point = circuit.point(&mut builder);
+        let (scalar, product, infinity) =
+            circuit.scalar_mul(&mut builder, &point, scalar_bits, window);
+        builder.finish();
+
+        ForeignCurveScalarMulCells {
+            point,
+            scalar,
+            product,
+            infinity,
+        }
+    }
+}
+
+/// Extends a [ForeignFieldCircuit] with the arithmetic of the points of a
+/// curve, see [super]
+#[derive(Clone, Debug)]
+pub struct ForeignCurveCircuit {
+    curve: ForeignCurve,
+    /// The cells of the parameter `a`, if it is not `0`
+    a: Option<ElementCells>,
+    /// The cells of the parameter `b`, if it is not `0`
+    b: Option<ElementCells>,
+}
+
+impl ForeignCurveCircuit {
+    /// Creates the arithmetic of `curve`, extending the circuit with up to 4
+    /// generic gates holding its parameters
+    pub fn new<F: PrimeField>(builder: &mut ForeignFieldCircuit<F>, curve: &ForeignCurve) -> Self {
+        let a = (!curve.a.is_zero()).then(|| builder.constant(&curve.a));
+        let b = (!curve.b.is_zero()).then(|| builder.constant(&curve.b));
+        Self {
+            curve: curve.clone(),
+            a,
+            b,
+        }
+    }
+
+    /// Extends the circuit with a point computed by the prover, and the check
+    /// that it is on the curve
+    pub fn point<F: PrimeField>(&self, builder: &mut ForeignFieldCircuit<F>) -> PointCells {
+        let p = &self.curve.modulus;
+        let point = (builder.element(p), builder.element(p));
+        self.assert_on_curve(builder, &point);
+        point
+    }
+
+    /// Extends the circuit with the check that `point` is on the curve
+    pub fn assert_on_curve<F: PrimeField>(
+        &self,
+        builder: &mut ForeignFieldCircuit<F>,
+        point: &PointCells,
+    ) {
+        let p = &self.curve.modulus;
+        let y2 = builder.mul(&point.1, &point.1, p);
+        let x2 = builder.mul(&point.0, &point.0, p);
+        let mut terms = vec![builder.mul(&x2, &point.0, p)];
+        if let Some(a) = &self.a {
+            terms.push(builder.mul(a, &point.0, p));
+        }
+        terms.extend(self.b);
+        let rhs = if terms.len() == 1 {
+            terms[0]
+        } else {
+            builder.add_chain(&terms, &vec![FFOps::Add; terms.len() - 1], p)
+        };
+        builder.assert_equal(&y2, &rhs);
+    }
+
+    /// Extends the circuit with the complete addition of `left` and `right`,
+    /// and returns the cells of the sum and of the boolean flagging the point
+    /// at infinity
+    pub fn add<F: PrimeField>(
+        &self,
+        builder: &mut ForeignFieldCircuit<F>,
+        left: &PointCells,
+        right: &PointCells,
+    ) -> (PointCells, Cell) {
+        let p = &self.curve.modulus;
+        let zero = builder.zero();
+        let dy = builder.add_chain(&[right.1, left.1], &[FFOps::Sub], p);
+        let dx = builder.add_chain(&[right.0, left.0], &[FFOps::Sub], p);
+
+        // same_x = 1 if and only if dx = 0: dx * same_x = 0, and
+        // dx * inv = 1 - same_x
+        let (same_x, not_same_x) = flag(builder);
+        let inv = builder.element(p);
+        let prod = builder.mul(&dx, &inv, p);
+        builder.assert_equal(&prod, &[not_same_x, zero, zero]);
+        let prod = builder.mul(&dx, &[same_x, zero, zero], p);
+        builder.assert_equal(&prod, &[zero; 3]);
+
+        // The slope of the tangent if the x-coordinates are equal, of the line
+        // through the points otherwise
+        let tangent = self.tangent(builder, left);
+        let diff = builder.difference(&(dy, dx), &tangent);
+        let (num, den) = builder.select(same_x, &(dy, dx), &diff);
+        let sum = self.complete(builder, left, right, &num, &den);
+
+        // infinity = 1 if and only if same_x = 1 and dy != 0:
+        // dy * inf_z = infinity, and dy * same_x = dy * infinity
+        let (infinity, _) = flag(builder);
+        let inf_z = builder.element(p);
+        let prod = builder.mul(&dy, &inf_z, p);
+        builder.assert_equal(&prod, &[infinity, zero, zero]);
+        let prod_same_x = builder.mul(&dy, &[same_x, zero, zero], p);
+        let prod_infinity = builder.mul(&dy, &[infinity, zero, zero], p);
+        builder.assert_equal(&prod_same_x, &prod_infinity);
+
+        (sum, infinity)
+    }
+
+    /// Extends the circuit with the doubling of `point`
+    pub fn double<F: PrimeField>(
+        &self,
+        builder: &mut ForeignFieldCircuit<F>,
+        point: &PointCells,
+    ) -> PointCells {
+        let (num, den) = self.tangent(builder, point);
+        self.complete(builder, point, point, &num, &den)
+    }
+
+    /// Extends the circuit with the multiplication of `point` by a scalar of
+    /// `scalar_bits` bits, with windows of `window` bits, and returns the
+    /// cells of the limbs of the scalar, of the product and of the boolean
+    /// flagging the point at infinity
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the scalar has no bits or more than
+    /// [MAX_SCALAR_BITS](super::MAX_SCALAR_BITS), or if the window is empty.
+    pub fn scalar_mul<F: PrimeField>(
+        &self,
+        builder: &mut ForeignFieldCircuit<F>,
+        point: &PointCells,
+        scalar_bits: usize,
+        window: usize,
+    ) -> (ElementCells, PointCells, Cell) {
+        let sizes = window_sizes(scalar_bits, window);
+        let (scalar, bits) = builder.bits(scalar_limb_bits(scalar_bits));
+
+        // The table of H + d * P
+        let table_size = 1 << sizes.iter().max().unwrap();
+        let mut table = vec![builder.constant_point(&self.curve.offset_point())];
+        for d in 1..table_size {
+            let entry = self.add_distinct(builder, &table[d - 1], point);
+            table.push(entry);
+        }
+
+        let mut bits = bits.into_iter();
+        let mut acc: Option<PointCells> = None;
+        for size in sizes {
+            let window_bits: Vec<Cell> = bits.by_ref().take(size).collect();
+            let summand = lookup(builder, &table[..1 << size], &window_bits);
+            acc = Some(match acc {
+                None => summand,
+                Some(mut acc) => {
+                    for _ in 0..size {
+                        acc = self.double(builder, &acc);
+                    }
+                    self.add_distinct(builder, &acc, &summand)
+                }
+            });
+        }
+
+        let offset_sum = self
+            .curve
+            .negate(&self.curve.offset_sum(scalar_bits, window));
+        let offset_sum = builder.constant_point(&offset_sum);
+        let (product, infinity) = self.add(builder, &acc.unwrap(), &offset_sum);
+        (scalar, product, infinity)
+    }
+
+    // Extends the circuit with the addition of the points `left` and `right`,
+    // of slope `λ * (right.x - left.x) = right.y - left.y`, and the check that
+    // `right.x - left.x` is invertible
+    fn add_distinct<F: PrimeField>(
+        &self,
+        builder: &mut ForeignFieldCircuit<F>,
+        left: &PointCells,
+        right: &PointCells,
+    ) -> PointCells {
+        let p = &self.curve.modulus;
+        let num = builder.add_chain(&[right.1, left.1], &[FFOps::Sub], p);
+        let den = builder.add_chain(&[right.0, left.0], &[FFOps::Sub], p);
+        let inv = builder.element(p);
+        let prod = builder.mul(&den, &inv, p);
+        let one = builder.one();
+        builder.assert_equal(&prod, &one);
+        self.complete(builder, left, right, &num, &den)
+    }
+
+    // Extends the circuit with the numerator `3 * point.x^2 + a` and the
+    // denominator `2 * point.y` of the slope of the tangent at `point`
+    fn tangent<F: PrimeField>(
+        &self,
+        builder: &mut ForeignFieldCircuit<F>,
+        point: &PointCells,
+    ) -> (ElementCells, ElementCells) {
+        let p = &self.curve.modulus;
+        let x2 = builder.mul(&point.0, &point.0, p);
+        let mut terms = vec![x2, x2, x2];
+        terms.extend(self.a);
+        let num = builder.add_chain(&terms, &vec![FFOps::Add; terms.len() - 1], p);
+        let den = builder.add_chain(&[point.1, point.1], &[FFOps::Add], p);
+        (num, den)
+    }
+
+    // Extends the circuit with the sum of `left` and `right`, given the
+    // numerator and the denominator of the slope `λ` of the line through them:
+    // `x = λ^2 - left.x - right.x` and `y = λ * (left.x - x) - left.y`
+    fn complete<F: PrimeField>(
+        &self,
+        builder: &mut ForeignFieldCircuit<F>,
+        left: &PointCells,
+        right: &PointCells,
+        num: &ElementCells,
+        den: &ElementCells,
+    ) -> PointCells {
+        let p = &self.curve.modulus;
+        let lambda = builder.element(p);
+        let prod = builder.mul(&lambda, den, p);
+        builder.assert_equal(&prod, num);
+        let lambda2 = builder.mul(&lambda, &lambda, p);
+        let x = builder.add_chain(&[lambda2, left.0, right.0], &[FFOps::Sub, FFOps::Sub], p);
+        let dx = builder.add_chain(&[left.0, x], &[FFOps::Sub], p);
+        let prod = builder.mul(&lambda, &dx, p);
+        let y = builder.add_chain(&[prod, left.1], &[FFOps::Sub], p);
+        (x, y)
+    }
+}
+
+// Extends the circuit with a boolean computed by the prover and its
+// complement, in a double generic gate: `bit * bit = bit` and
+// `bit + not_bit = 1`
+fn flag<F: PrimeField>(builder: &mut ForeignFieldCircuit<F>) -> (Cell, Cell) {
+    let row = builder.generic(
+        [-F::one(), F::zero(), F::zero(), F::one(), F::zero()],
+        [F::one(), F::zero(), F::one(), F::zero(), -F::one()],
+    );
+    builder.connect(&[(row, 0), (row, 0)], &[(row, 1), (row, 3)]);
+    ((row, 0), (row, 5))
+}
+
+// Extends the circuit with the selection of the entry of `table` indexed by
+// `bits`, from the most significant one, with a tree of selections starting
+// from the least significant bit
+fn lookup<F: PrimeField>(
+    builder: &mut ForeignFieldCircuit<F>,
+    table: &[PointCells],
+    bits: &[Cell],
+) -> PointCells {
+    let mut entries = table.to_vec();
+    for bit in bits.iter().rev() {
+        entries = entries
+            .chunks(2)
+            .map(|pair| {
+                let diff = builder.difference(&pair[0], &pair[1]);
+                builder.select(*bit, &pair[0], &diff)
+            })
+            .collect();
+    }
+    entries[0]
+}
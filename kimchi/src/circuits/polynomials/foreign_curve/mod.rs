@@ -0,0 +1,256 @@
+//! Elliptic curve arithmetic over a foreign field
+//!
+//! The gadgets of this module add, double and multiply the points of any
+//! short Weierstrass curve `y^2 = x^3 + a * x + b` over a foreign field of at
+//! most 259 bits, given by a [ForeignCurve]. They are built from the
+//! [foreign field builders](super::foreign_field_builder), the coordinates
+//! being held in three 88-bit limbs, and chain within a foreign field circuit
+//! through [gadget::ForeignCurveCircuit] and [witness::ForeignCurveWitness],
+//! whose methods are called in the same order:
+//!
+//! * the points are added and doubled with the affine formulas, whose slope
+//!   `λ` is computed by the prover and constrained by a multiplication,
+//! * the complete addition of two points also handles the addition of a
+//!   point to itself, and of a point to its opposite, whose result is the
+//!   point at infinity. As the [CompleteAdd](super::complete_add) gate, it
+//!   selects the slope of the tangent when the `x`-coordinates are equal, and
+//!   returns a boolean flagging the point at infinity, constrained by the
+//!   inverses of the differences of the coordinates,
+//! * the scalar multiplication decomposes the scalar in bits and processes
+//!   them in windows of `w` bits, from the most significant one. It doubles
+//!   an accumulator `w` times and adds `H + d * P` to it, where `d` is the
+//!   value of the window and `H` is an offset point, the table of the `2^w`
+//!   points `H + d * P` being computed once and read with a tree of
+//!   selections. The sum of the multiples of `H` is removed with a complete
+//!   addition at the end, so that a product equal to the point at infinity
+//!   is flagged.
+//!
+//! The additions of the scalar multiplication are incomplete, and checked to
+//! add points of distinct `x`-coordinates: a witness adding a point to itself
+//! or to its opposite, which happens with negligible probability for a point
+//! `P` independent from the offset point, cannot be proven. Neither can the
+//! doubling of a point of order 2.
+//!
+//! Over secp256k1, the complete addition gadget takes 397 rows, including the
+//! checks that its summands are on the curve, and the multiplication by a
+//! 256-bit scalar with windows of 4 bits takes 51242 rows.
+//!
+//! The gates are created by
+//! [CircuitGate::extend_foreign_curve_add](crate::circuits::gate::CircuitGate::extend_foreign_curve_add)
+//! and
+//! [CircuitGate::extend_foreign_curve_scalar_mul](crate::circuits::gate::CircuitGate::extend_foreign_curve_scalar_mul),
+//! and the witness by [witness::extend_foreign_curve_add_witness] and
+//! [witness::extend_foreign_curve_scalar_mul_witness], which lay out the rows
+//! in the same order.
+
+pub mod gadget;
+pub mod witness;
+
+pub use super::foreign_field_builder::Point;
+
+use super::{
+    foreign_field_builder::{inverse, sqrt, sub},
+    foreign_field_common::{BINARY_MODULUS_EXP, LIMB_BITS},
+};
+use ark_ff::{One, Zero};
+use num_bigint::BigUint;
+use num_integer::Integer;
+use o1_utils::BigUintHelpers;
+
+/// The largest number of bits of the modulus of the base field, supported by
+/// the foreign field multiplication
+pub const MAX_MODULUS_BITS: u64 = 259;
+
+/// The largest number of bits of a scalar, held in three 88-bit limbs
+pub const MAX_SCALAR_BITS: usize = BINARY_MODULUS_EXP;
+
+/// A short Weierstrass curve `y^2 = x^3 + a * x + b` over the foreign field of
+/// prime `modulus`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForeignCurve {
+    /// The parameter `a`
+    pub a: BigUint,
+    /// The parameter `b`
+    pub b: BigUint,
+    /// The modulus of the base field
+    pub modulus: BigUint,
+}
+
+impl ForeignCurve {
+    /// Creates the curve `y^2 = x^3 + a * x + b` over the field of prime
+    /// `modulus`
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the modulus is larger than the moduli supported by the
+    /// foreign field multiplication, or if the curve is singular.
+    pub fn new(a: BigUint, b: BigUint, modulus: BigUint) -> Self {
+        assert!(
+            modulus.bits() <= MAX_MODULUS_BITS,
+            "the modulus is too large for the foreign field multiplication"
+        );
+        let (a, b) = (a % &modulus, b % &modulus);
+        // 4 * a^3 + 27 * b^2 != 0
+        let discriminant = (BigUint::from(4u32) * a.modpow(&BigUint::from(3u32), &modulus)
+            + BigUint::from(27u32) * &b * &b)
+            % &modulus;
+        assert!(!discriminant.is_zero(), "the curve is singular");
+        Self { a, b, modulus }
+    }
+
+    /// The curve secp256k1, `y^2 = x^3 + 7`
+    pub fn secp256k1() -> Self {
+        Self::new(
+            BigUint::zero(),
+            BigUint::from(7u32),
+            BigUint::from_hex("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f"),
+        )
+    }
+
+    /// The curve secp256r1, also known as P-256, `y^2 = x^3 - 3 * x + b`
+    pub fn secp256r1() -> Self {
+        let modulus =
+            BigUint::from_hex("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff");
+        Self::new(
+            &modulus - BigUint::from(3u32),
+            BigUint::from_hex("5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b"),
+            modulus,
+        )
+    }
+
+    /// Whether `point` is on the curve
+    pub fn is_on_curve(&self, point: &Point) -> bool {
+        let p = &self.modulus;
+        let (x, y) = point;
+        x < p && y < p && y * y % p == self.rhs(x)
+    }
+
+    // The right-hand side `x^3 + a * x + b` of the equation of the curve
+    fn rhs(&self, x: &BigUint) -> BigUint {
+        let p = &self.modulus;
+        (x * x % p * x + &self.a * x + &self.b) % p
+    }
+
+    /// The slope of the line through the points `left` and `right` of distinct
+    /// `x`-coordinates
+    pub fn add_slope(&self, left: &Point, right: &Point) -> BigUint {
+        let p = &self.modulus;
+        let num = sub(&right.1, &left.1, p);
+        let den = sub(&right.0, &left.0, p);
+        num * inverse(&den, p) % p
+    }
+
+    /// The slope of the tangent to the curve at `point`, or `0` if `point` is
+    /// of order 2
+    pub fn double_slope(&self, point: &Point) -> BigUint {
+        let p = &self.modulus;
+        let num = (BigUint::from(3u32) * &point.0 * &point.0 + &self.a) % p;
+        let den = BigUint::from(2u32) * &point.1 % p;
+        num * inverse(&den, p) % p
+    }
+
+    /// The sum of `left` and `right`, given the slope `lambda` of the line
+    /// through them
+    fn sum(&self, left: &Point, right: &Point, lambda: &BigUint) -> Point {
+        let p = &self.modulus;
+        let x = sub(&sub(&(lambda * lambda), &left.0, p), &right.0, p);
+        let y = sub(&(lambda * sub(&left.0, &x, p)), &left.1, p);
+        (x, y)
+    }
+
+    /// Adds the points `left` and `right`, and returns `None` if the sum is
+    /// the point at infinity
+    pub fn add(&self, left: &Point, right: &Point) -> Option<Point> {
+        if left.0 != right.0 {
+            Some(self.sum(left, right, &self.add_slope(left, right)))
+        } else if left.1 == right.1 && !left.1.is_zero() {
+            Some(self.double(left))
+        } else {
+            None
+        }
+    }
+
+    /// Doubles `point`, which must not be of order 2
+    pub fn double(&self, point: &Point) -> Point {
+        self.sum(point, point, &self.double_slope(point))
+    }
+
+    /// Negates `point`
+    pub fn negate(&self, point: &Point) -> Point {
+        (
+            point.0.clone(),
+            sub(&BigUint::zero(), &point.1, &self.modulus),
+        )
+    }
+
+    /// Multiplies `point` by `scalar`, and returns `None` if the product is
+    /// the point at infinity
+    pub fn scalar_mul(&self, point: &Point, scalar: &BigUint) -> Option<Point> {
+        (0..scalar.bits()).rev().fold(None, |acc, i| {
+            let acc = acc.and_then(|acc| self.add(&acc, &acc));
+            match (acc, scalar.bit(i)) {
+                (Some(acc), true) => self.add(&acc, point),
+                (None, true) => Some(point.clone()),
+                (acc, false) => acc,
+            }
+        })
+    }
+
+    /// The point `H` added to the accumulator of the scalar multiplication,
+    /// whose `x`-coordinate is the first one after the ASCII encoding of
+    /// `"kimchi foreign curve offset"` to be on the curve, and whose
+    /// `y`-coordinate is even
+    pub fn offset_point(&self) -> Point {
+        let p = &self.modulus;
+        let mut x = BigUint::from_bytes_be(b"kimchi foreign curve offset") % p;
+        loop {
+            if let Some(y) = sqrt(&self.rhs(&x), p) {
+                let y = if y.is_even() { y } else { p - y };
+                return (x, y);
+            }
+            x = (x + BigUint::one()) % p;
+        }
+    }
+
+    /// The sum of the multiples of the offset point added by a scalar
+    /// multiplication by a scalar of `scalar_bits` bits with windows of
+    /// `window` bits, see [self]
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the sum is the point at infinity.
+    pub fn offset_sum(&self, scalar_bits: usize, window: usize) -> Point {
+        // The multiple of H is doubled by the size of each window after the
+        // first one, and incremented
+        let multiple = window_sizes(scalar_bits, window)[1..]
+            .iter()
+            .fold(BigUint::one(), |acc, size| (acc << size) + BigUint::one());
+        self.scalar_mul(&self.offset_point(), &multiple)
+            .expect("the offset sum is not the point at infinity")
+    }
+}
+
+/// The sizes of the windows of a scalar of `scalar_bits` bits, from the most
+/// significant one, all of `window` bits but the first one
+///
+/// # Panics
+///
+/// Will panic if the scalar has no bits or more than [MAX_SCALAR_BITS], or if
+/// the window is empty.
+fn window_sizes(scalar_bits: usize, window: usize) -> Vec<usize> {
+    assert!(
+        (1..=MAX_SCALAR_BITS).contains(&scalar_bits),
+        "the scalar must have between 1 and {MAX_SCALAR_BITS} bits"
+    );
+    assert!(window > 0, "the windows must not be empty");
+    let windows = scalar_bits.div_ceil(window);
+    let mut sizes = vec![window; windows];
+    sizes[0] = scalar_bits - (windows - 1) * window;
+    sizes
+}
+
+/// The number of bits of the limbs of a scalar of `scalar_bits` bits, from the
+/// lowest to the highest
+fn scalar_limb_bits(scalar_bits: usize) -> [usize; 3] {
+    core::array::from_fn(|i| scalar_bits.saturating_sub(i * LIMB_BITS).min(LIMB_BITS))
+}
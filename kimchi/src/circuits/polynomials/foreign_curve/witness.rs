@@ -0,0 +1,291 @@
+//! This module computes the witness of the foreign curve gadgets.
+
+use super::{inverse, scalar_limb_bits, window_sizes, ForeignCurve, Point};
+use crate::circuits::{
+    polynomial::COLUMNS,
+    polynomials::{
+        foreign_field_add::witness::FFOps, foreign_field_builder::witness::ForeignFieldWitness,
+    },
+};
+use ark_ff::{PrimeField, Zero};
+use num_bigint::BigUint;
+
+/// Extends the witness with the complete addition of the points `left` and
+/// `right` of `curve`, laid out as the gates of
+/// [CircuitGate::extend_foreign_curve_add](crate::circuits::gate::CircuitGate::extend_foreign_curve_add),
+/// and returns the sum, or `None` if it is the point at infinity.
+pub fn extend_foreign_curve_add_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    curve: &ForeignCurve,
+    left: &Point,
+    right: &Point,
+) -> Option<Point> {
+    let mut builder = ForeignFieldWitness::new(witness);
+    let circuit = ForeignCurveWitness::new(&mut builder, curve);
+    circuit.point(&mut builder, left);
+    circuit.point(&mut builder, right);
+    let (sum, infinity) = circuit.add(&mut builder, left, right);
+    builder.finish();
+    (!infinity).then_some(sum)
+}
+
+/// Extends the witness with the multiplication of `point` of `curve` by
+/// `scalar` of at most `scalar_bits` bits, with windows of `window` bits,
+/// laid out as the gates of
+/// [CircuitGate::extend_foreign_curve_scalar_mul](crate::circuits::gate::CircuitGate::extend_foreign_curve_scalar_mul),
+/// and returns the product, or `None` if it is the point at infinity.
+///
+/// # Panics
+///
+/// Will panic if the scalar has more than `scalar_bits` bits.
+pub fn extend_foreign_curve_scalar_mul_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    curve: &ForeignCurve,
+    point: &Point,
+    scalar: &BigUint,
+    scalar_bits: usize,
+    window: usize,
+) -> Option<Point> {
+    let mut builder = ForeignFieldWitness::new(witness);
+    let circuit = ForeignCurveWitness::new(&mut builder, curve);
+    circuit.point(&mut builder, point);
+    let (product, infinity) = circuit.scalar_mul(&mut builder, point, scalar, scalar_bits, window);
+    builder.finish();
+    (!infinity).then_some(product)
+}
+
+/// Extends a [ForeignFieldWitness] with the arithmetic of the points of a
+/// curve, in the order of the gates of
+/// [super::gadget::ForeignCurveCircuit]
+#[derive(Clone, Debug)]
+pub struct ForeignCurveWitness {
+    curve: ForeignCurve,
+}
+
+impl ForeignCurveWitness {
+    /// Creates the arithmetic of `curve`, extending the witness with the rows
+    /// of its parameters
+    pub fn new<F: PrimeField>(builder: &mut ForeignFieldWitness<F>, curve: &ForeignCurve) -> Self {
+        for param in [&curve.a, &curve.b] {
+            if !param.is_zero() {
+                builder.constant(param);
+            }
+        }
+        Self {
+            curve: curve.clone(),
+        }
+    }
+
+    /// Extends the witness with `point`, and the check that it is on the curve
+    pub fn point<F: PrimeField>(&self, builder: &mut ForeignFieldWitness<F>, point: &Point) {
+        let p = &self.curve.modulus;
+        builder.element(&point.0, p);
+        builder.element(&point.1, p);
+        self.assert_on_curve(builder, point);
+    }
+
+    /// Extends the witness with the check that `point` is on the curve
+    pub fn assert_on_curve<F: PrimeField>(
+        &self,
+        builder: &mut ForeignFieldWitness<F>,
+        point: &Point,
+    ) {
+        let p = &self.curve.modulus;
+        builder.mul(&point.1, &point.1, p);
+        let x2 = builder.mul(&point.0, &point.0, p);
+        let mut terms = vec![builder.mul(&x2, &point.0, p)];
+        if !self.curve.a.is_zero() {
+            terms.push(builder.mul(&self.curve.a, &point.0, p));
+        }
+        if !self.curve.b.is_zero() {
+            terms.push(self.curve.b.clone());
+        }
+        if terms.len() > 1 {
+            builder.add_chain(&terms, &vec![FFOps::Add; terms.len() - 1], p);
+        }
+    }
+
+    /// Extends the witness with the complete addition of `left` and `right`,
+    /// and returns the sum and whether it is the point at infinity, the
+    /// coordinates of the sum being those computed by the gadget
+    pub fn add<F: PrimeField>(
+        &self,
+        builder: &mut ForeignFieldWitness<F>,
+        left: &Point,
+        right: &Point,
+    ) -> (Point, bool) {
+        let p = &self.curve.modulus;
+        let dy = builder.add_chain(&[right.1.clone(), left.1.clone()], &[FFOps::Sub], p);
+        let dx = builder.add_chain(&[right.0.clone(), left.0.clone()], &[FFOps::Sub], p);
+
+        let same_x = dx.is_zero();
+        flag(builder, same_x);
+        let inv = inverse(&dx, p);
+        builder.element(&inv, p);
+        builder.mul(&dx, &inv, p);
+        builder.mul(&dx, &BigUint::from(same_x), p);
+
+        let line = (dy.clone(), dx);
+        let tangent = self.tangent(builder, left);
+        builder.difference(&line, &tangent);
+        let (_, den) = builder.select(same_x, &line, &tangent);
+        let lambda = if same_x {
+            self.curve.double_slope(left)
+        } else {
+            self.curve.add_slope(left, right)
+        };
+        let sum = self.complete(builder, left, right, &lambda, &den);
+
+        let infinity = same_x && !dy.is_zero();
+        flag(builder, infinity);
+        let inf_z = if infinity {
+            inverse(&dy, p)
+        } else {
+            BigUint::zero()
+        };
+        builder.element(&inf_z, p);
+        builder.mul(&dy, &inf_z, p);
+        builder.mul(&dy, &BigUint::from(same_x), p);
+        builder.mul(&dy, &BigUint::from(infinity), p);
+
+        (sum, infinity)
+    }
+
+    /// Extends the witness with the doubling of `point`, and returns it
+    pub fn double<F: PrimeField>(
+        &self,
+        builder: &mut ForeignFieldWitness<F>,
+        point: &Point,
+    ) -> Point {
+        let (_, den) = self.tangent(builder, point);
+        self.complete(builder, point, point, &self.curve.double_slope(point), &den)
+    }
+
+    /// Extends the witness with the multiplication of `point` by `scalar` of
+    /// at most `scalar_bits` bits, with windows of `window` bits, and returns
+    /// the product and whether it is the point at infinity
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the scalar has more than `scalar_bits` bits.
+    pub fn scalar_mul<F: PrimeField>(
+        &self,
+        builder: &mut ForeignFieldWitness<F>,
+        point: &Point,
+        scalar: &BigUint,
+        scalar_bits: usize,
+        window: usize,
+    ) -> (Point, bool) {
+        let sizes = window_sizes(scalar_bits, window);
+        assert!(
+            scalar.bits() <= scalar_bits as u64,
+            "the scalar has more than {scalar_bits} bits"
+        );
+        let bits = builder.bits(scalar, scalar_limb_bits(scalar_bits));
+
+        let offset = self.curve.offset_point();
+        builder.constant_point(&offset);
+        let table_size = 1 << sizes.iter().max().unwrap();
+        let mut table = vec![offset];
+        for d in 1..table_size {
+            let entry = self.add_distinct(builder, &table[d - 1], point);
+            table.push(entry);
+        }
+
+        let mut bits = bits.into_iter();
+        let mut acc: Option<Point> = None;
+        for size in sizes {
+            let window_bits: Vec<bool> = bits.by_ref().take(size).collect();
+            let summand = lookup(builder, &table[..1 << size], &window_bits);
+            acc = Some(match acc {
+                None => summand,
+                Some(mut acc) => {
+                    for _ in 0..size {
+                        acc = self.double(builder, &acc);
+                    }
+                    self.add_distinct(builder, &acc, &summand)
+                }
+            });
+        }
+
+        let offset_sum = self
+            .curve
+            .negate(&self.curve.offset_sum(scalar_bits, window));
+        builder.constant_point(&offset_sum);
+        self.add(builder, &acc.unwrap(), &offset_sum)
+    }
+
+    fn add_distinct<F: PrimeField>(
+        &self,
+        builder: &mut ForeignFieldWitness<F>,
+        left: &Point,
+        right: &Point,
+    ) -> Point {
+        let p = &self.curve.modulus;
+        builder.add_chain(&[right.1.clone(), left.1.clone()], &[FFOps::Sub], p);
+        let den = builder.add_chain(&[right.0.clone(), left.0.clone()], &[FFOps::Sub], p);
+        let inv = inverse(&den, p);
+        builder.element(&inv, p);
+        builder.mul(&den, &inv, p);
+        let lambda = self.curve.add_slope(left, right);
+        self.complete(builder, left, right, &lambda, &den)
+    }
+
+    fn tangent<F: PrimeField>(&self, builder: &mut ForeignFieldWitness<F>, point: &Point) -> Point {
+        let p = &self.curve.modulus;
+        let x2 = builder.mul(&point.0, &point.0, p);
+        let mut terms = vec![x2.clone(), x2.clone(), x2];
+        if !self.curve.a.is_zero() {
+            terms.push(self.curve.a.clone());
+        }
+        let num = builder.add_chain(&terms, &vec![FFOps::Add; terms.len() - 1], p);
+        let den = builder.add_chain(&[point.1.clone(), point.1.clone()], &[FFOps::Add], p);
+        (num, den)
+    }
+
+    fn complete<F: PrimeField>(
+        &self,
+        builder: &mut ForeignFieldWitness<F>,
+        left: &Point,
+        right: &Point,
+        lambda: &BigUint,
+        den: &BigUint,
+    ) -> Point {
+        let p = &self.curve.modulus;
+        builder.element(lambda, p);
+        builder.mul(lambda, den, p);
+        let lambda2 = builder.mul(lambda, lambda, p);
+        let x = builder.add_chain(
+            &[lambda2, left.0.clone(), right.0.clone()],
+            &[FFOps::Sub, FFOps::Sub],
+            p,
+        );
+        let dx = builder.add_chain(&[left.0.clone(), x.clone()], &[FFOps::Sub], p);
+        let prod = builder.mul(lambda, &dx, p);
+        let y = builder.add_chain(&[prod, left.1.clone()], &[FFOps::Sub], p);
+        (x, y)
+    }
+}
+
+fn flag<F: PrimeField>(builder: &mut ForeignFieldWitness<F>, bit: bool) {
+    let value = F::from(bit);
+    builder.row(&[value, value, F::zero(), value, F::zero(), F::one() - value]);
+}
+
+fn lookup<F: PrimeField>(
+    builder: &mut ForeignFieldWitness<F>,
+    table: &[Point],
+    bits: &[bool],
+) -> Point {
+    let mut entries = table.to_vec();
+    for bit in bits.iter().rev() {
+        entries = entries
+            .chunks(2)
+            .map(|pair| {
+                builder.difference(&pair[0], &pair[1]);
+                builder.select(*bit, &pair[0], &pair[1])
+            })
+            .collect();
+    }
+    entries.swap_remove(0)
+}
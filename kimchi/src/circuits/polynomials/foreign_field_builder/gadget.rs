@@ -0,0 +1,400 @@
+//! This module obtains the gates of a circuit over foreign field elements.
+
+use super::Point;
+use crate::circuits::{
+    gate::{CircuitGate, Connect},
+    polynomials::{
+        foreign_field_add::witness::FFOps,
+        foreign_field_common::{BigUintForeignFieldHelpers, KimchiForeignElement},
+        generic::GenericGateSpec,
+    },
+    wires::Wire,
+};
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use o1_utils::foreign_field::ForeignFieldHelpers;
+
+/// A cell of the circuit, as `(row, column)`
+pub type Cell = (usize, usize);
+
+/// The cells of the three 88-bit limbs of a foreign field element, from the
+/// lowest to the highest
+pub type ElementCells = [Cell; 3];
+
+/// The cells of the coordinates of a point
+pub type PointCells = (ElementCells, ElementCells);
+
+/// The cells holding the constants used by the builder
+#[derive(Clone, Copy, Debug)]
+struct Constants {
+    /// The cell holding `0`
+    zero: Cell,
+    /// The cell holding `1`
+    one: Cell,
+    /// The cell holding `2^88`
+    two_to_limb: Cell,
+}
+
+/// Extends the gates of a circuit over foreign field elements, pooling the
+/// bound checks of the high limbs, see [super]
+pub struct ForeignFieldCircuit<'a, F: PrimeField> {
+    gates: &'a mut Vec<CircuitGate<F>>,
+    constants: Constants,
+    /// The high limb waiting for its bound to be computed, with its modulus
+    high_limb: Option<(Cell, BigUint)>,
+    /// The bounds waiting to be range-checked
+    limbs: Vec<Cell>,
+}
+
+impl<'a, F: PrimeField> ForeignFieldCircuit<'a, F> {
+    /// Creates a builder extending `gates`, starting with 2 generic gates
+    /// holding the constants `0`, `1` and `2^88`
+    pub fn new(gates: &'a mut Vec<CircuitGate<F>>) -> Self {
+        let row = gates.len();
+        gates.push(CircuitGate::create_generic_gadget(
+            Wire::for_row(row),
+            GenericGateSpec::Const(F::zero()),
+            Some(GenericGateSpec::Const(F::one())),
+        ));
+        gates.push(CircuitGate::create_generic_gadget(
+            Wire::for_row(row + 1),
+            GenericGateSpec::Const(KimchiForeignElement::<F>::two_to_limb()),
+            None,
+        ));
+        Self {
+            gates,
+            constants: Constants {
+                zero: (row, 0),
+                one: (row, 3),
+                two_to_limb: (row + 1, 0),
+            },
+            high_limb: None,
+            limbs: vec![],
+        }
+    }
+
+    /// The cell holding `0`
+    pub fn zero(&self) -> Cell {
+        self.constants.zero
+    }
+
+    /// The cells of the element `1`, whose limbs are `1`, `0` and `0`
+    pub fn one(&self) -> ElementCells {
+        [self.constants.one, self.constants.zero, self.constants.zero]
+    }
+
+    /// Extends the circuit with a double generic gate of coefficients `left`
+    /// and `right`, and returns its row
+    pub fn generic(&mut self, left: [F; 5], right: [F; 5]) -> usize {
+        let row = self.gates.len();
+        let mut coeffs = [F::zero(); 10];
+        coeffs[..5].copy_from_slice(&left);
+        coeffs[5..].copy_from_slice(&right);
+        self.gates
+            .push(CircuitGate::create_generic(Wire::for_row(row), coeffs));
+        row
+    }
+
+    /// Connects the cells `left` and `right` pairwise
+    pub fn connect(&mut self, left: &[Cell], right: &[Cell]) {
+        for (left, right) in left.iter().zip(right) {
+            self.gates.connect_cell_pair(*left, *right);
+        }
+    }
+
+    /// Constrains the elements `left` and `right` to have the same limbs
+    pub fn assert_equal(&mut self, left: &ElementCells, right: &ElementCells) {
+        self.connect(left, right);
+    }
+
+    /// Extends the circuit with 2 generic gates holding the limbs of `value`
+    pub fn constant(&mut self, value: &BigUint) -> ElementCells {
+        let limbs = value.to_field_limbs::<F>();
+        let row = self.gates.len();
+        for (r, gate1, gate2) in [
+            (row, limbs[0], Some(GenericGateSpec::Const(limbs[1]))),
+            (row + 1, limbs[2], None),
+        ] {
+            self.gates.push(CircuitGate::create_generic_gadget(
+                Wire::for_row(r),
+                GenericGateSpec::Const(gate1),
+                gate2,
+            ));
+        }
+        [(row, 0), (row, 3), (row + 1, 0)]
+    }
+
+    /// Extends the circuit with 4 generic gates holding the coordinates of
+    /// `point`
+    pub fn constant_point(&mut self, point: &Point) -> PointCells {
+        (self.constant(&point.0), self.constant(&point.1))
+    }
+
+    /// Extends the circuit with a multi-range-check of the limbs of an element
+    /// computed by the prover, and the bound of its high limb
+    pub fn element(&mut self, modulus: &BigUint) -> ElementCells {
+        let row = self.gates.len();
+        let mut next_row = row;
+        CircuitGate::extend_multi_range_check(self.gates, &mut next_row);
+        self.high_limb((row + 2, 0), modulus);
+        [(row, 0), (row + 1, 0), (row + 2, 0)]
+    }
+
+    /// Extends the circuit with a multi-range-check of three 88-bit limbs
+    /// computed by the prover, which are not bound to a modulus
+    pub fn limbs(&mut self) -> ElementCells {
+        let row = self.gates.len();
+        let mut next_row = row;
+        CircuitGate::extend_multi_range_check(self.gates, &mut next_row);
+        [(row, 0), (row + 1, 0), (row + 2, 0)]
+    }
+
+    // Computes the bound of a high limb, two at a time
+    fn high_limb(&mut self, limb: Cell, modulus: &BigUint) {
+        match self.high_limb.take() {
+            Some((pending, pending_modulus)) if pending_modulus == *modulus => {
+                self.high_bounds(&[pending, limb], modulus)
+            }
+            Some((pending, pending_modulus)) => {
+                self.high_bounds(&[pending], &pending_modulus);
+                self.high_limb = Some((limb, modulus.clone()));
+            }
+            None => self.high_limb = Some((limb, modulus.clone())),
+        }
+    }
+
+    fn high_bounds(&mut self, limbs: &[Cell], modulus: &BigUint) {
+        let row = self.gates.len();
+        let mut next_row = row;
+        CircuitGate::extend_high_bounds(self.gates, &mut next_row, modulus);
+        for (i, limb) in limbs.iter().enumerate() {
+            self.gates.connect_cell_pair(*limb, (row, 3 * i));
+            self.range_check((row, 3 * i + 2));
+        }
+    }
+
+    /// Range-checks the 88-bit `limb`, pooling three limbs per
+    /// multi-range-check
+    pub fn range_check(&mut self, limb: Cell) {
+        self.limbs.push(limb);
+        if self.limbs.len() == 3 {
+            self.limb_checks();
+        }
+    }
+
+    fn limb_checks(&mut self) {
+        let row = self.gates.len();
+        let mut next_row = row;
+        CircuitGate::extend_multi_range_check(self.gates, &mut next_row);
+        for (i, limb) in core::mem::take(&mut self.limbs).into_iter().enumerate() {
+            self.gates.connect_cell_pair(limb, (row + i, 0));
+        }
+    }
+
+    /// Extends the circuit with the checks still pooled
+    pub fn finish(&mut self) {
+        if let Some((limb, modulus)) = self.high_limb.take() {
+            self.high_bounds(&[limb], &modulus);
+        }
+        if !self.limbs.is_empty() {
+            self.limb_checks();
+        }
+    }
+
+    /// Extends the circuit with a foreign field multiplication, the
+    /// multi-range-checks of its remainder, quotient and intermediate
+    /// products, and returns the cells of the remainder
+    pub fn mul(
+        &mut self,
+        left: &ElementCells,
+        right: &ElementCells,
+        modulus: &BigUint,
+    ) -> ElementCells {
+        let row = self.gates.len();
+        let mut next_row = row;
+        CircuitGate::extend_foreign_field_mul(self.gates, &mut next_row, modulus);
+        self.connect(left, &[(row, 0), (row, 1), (row, 2)]);
+        self.connect(right, &[(row, 3), (row, 4), (row, 5)]);
+
+        // Remainder
+        let remainder_row = next_row;
+        CircuitGate::extend_compact_multi_range_check(self.gates, &mut next_row);
+        self.gates
+            .connect_cell_pair((row + 1, 0), (remainder_row + 2, 1));
+        self.gates
+            .connect_cell_pair((row + 1, 1), (remainder_row, 0));
+
+        // Quotient
+        let quotient_row = next_row;
+        CircuitGate::extend_multi_range_check(self.gates, &mut next_row);
+        self.connect(
+            &[(row + 1, 2), (row + 1, 3), (row + 1, 4)],
+            &[
+                (quotient_row, 0),
+                (quotient_row + 1, 0),
+                (quotient_row + 2, 0),
+            ],
+        );
+
+        // Quotient bound and intermediate products
+        let products_row = next_row;
+        CircuitGate::extend_multi_range_check(self.gates, &mut next_row);
+        self.connect(
+            &[(row + 1, 5), (row, 6), (row + 1, 6)],
+            &[
+                (products_row, 0),
+                (products_row + 1, 0),
+                (products_row + 2, 0),
+            ],
+        );
+
+        self.high_limb((row + 1, 1), modulus);
+        [
+            (remainder_row + 1, 0),
+            (remainder_row + 2, 0),
+            (remainder_row, 0),
+        ]
+    }
+
+    /// Extends the circuit with a chain of foreign field additions, its final
+    /// bound check, the multi-range-checks of the result and of its bound, and
+    /// returns the cells of the result
+    pub fn add_chain(
+        &mut self,
+        inputs: &[ElementCells],
+        opcodes: &[FFOps],
+        modulus: &BigUint,
+    ) -> ElementCells {
+        let row = self.gates.len();
+        let (mut next_row, add_gates) = CircuitGate::create_chain_ffadd(row, opcodes, modulus);
+        self.gates.extend(add_gates);
+        self.connect(&inputs[0], &[(row, 0), (row, 1), (row, 2)]);
+        for (i, input) in inputs[1..].iter().enumerate() {
+            self.connect(input, &[(row + i, 3), (row + i, 4), (row + i, 5)]);
+        }
+
+        // The right input 2^264 and the overflow 1 of the final bound check
+        let bound_row = row + opcodes.len();
+        let Constants {
+            zero,
+            one,
+            two_to_limb,
+        } = self.constants;
+        self.connect(
+            &[zero, zero, two_to_limb, one],
+            &[
+                (bound_row, 3),
+                (bound_row, 4),
+                (bound_row, 5),
+                (bound_row, 6),
+            ],
+        );
+
+        let result = [(bound_row, 0), (bound_row, 1), (bound_row, 2)];
+        for limbs_row in [bound_row, bound_row + 1] {
+            let check_row = next_row;
+            CircuitGate::extend_multi_range_check(self.gates, &mut next_row);
+            self.connect(
+                &[(limbs_row, 0), (limbs_row, 1), (limbs_row, 2)],
+                &[(check_row, 0), (check_row + 1, 0), (check_row + 2, 0)],
+            );
+        }
+        result
+    }
+
+    /// Extends the circuit with the decomposition of a scalar in bits, one
+    /// double generic gate per bit checking that it is a boolean and adding it
+    /// to its limb, the limbs having `limb_bits` bits. Returns the cells of
+    /// the limbs and of the bits, from the most significant one.
+    pub fn bits(&mut self, limb_bits: [usize; 3]) -> (ElementCells, Vec<Cell>) {
+        let mut limbs = [self.constants.zero; 3];
+        let mut bits = vec![];
+        for (limb, limb_bits) in limbs.iter_mut().zip(limb_bits).rev() {
+            let mut acc = self.constants.zero;
+            for _ in 0..limb_bits {
+                // 2 * acc + bit = next_acc, bit * bit = bit
+                let row = self.generic(
+                    [F::from(2u32), F::one(), -F::one(), F::zero(), F::zero()],
+                    [-F::one(), F::zero(), F::zero(), F::one(), F::zero()],
+                );
+                self.gates.connect_cell_pair(acc, (row, 0));
+                self.gates.connect_cell_pair((row, 1), (row, 3));
+                self.gates.connect_cell_pair((row, 1), (row, 4));
+                bits.push((row, 1));
+                acc = (row, 2);
+            }
+            *limb = acc;
+        }
+        (limbs, bits)
+    }
+
+    /// Extends the circuit with the selection of the constant point `right` if
+    /// `bit` is 1 or of `left` otherwise, with one generic gate per limb:
+    /// `left + bit * (right - left) = out`
+    pub fn select_constant(&mut self, bit: Cell, left: &Point, right: &Point) -> PointCells {
+        let left = [left.0.to_field_limbs::<F>(), left.1.to_field_limbs()].concat();
+        let right = [right.0.to_field_limbs::<F>(), right.1.to_field_limbs()].concat();
+        let mut out = vec![];
+        for i in (0..6).step_by(2) {
+            let row = self.generic(
+                [right[i] - left[i], F::zero(), -F::one(), F::zero(), left[i]],
+                [
+                    right[i + 1] - left[i + 1],
+                    F::zero(),
+                    -F::one(),
+                    F::zero(),
+                    left[i + 1],
+                ],
+            );
+            self.connect(&[bit, bit], &[(row, 0), (row, 3)]);
+            out.extend([(row, 2), (row, 5)]);
+        }
+        point_cells(&out)
+    }
+
+    /// Extends the circuit with the differences of the limbs of `right` and
+    /// `left`, with one generic gate per limb: `right - left = out`
+    pub fn difference(&mut self, left: &PointCells, right: &PointCells) -> PointCells {
+        let left = [left.0, left.1].concat();
+        let right = [right.0, right.1].concat();
+        let coeffs = [F::one(), -F::one(), -F::one(), F::zero(), F::zero()];
+        let mut out = vec![];
+        for i in (0..6).step_by(2) {
+            let row = self.generic(coeffs, coeffs);
+            self.connect(
+                &[right[i], left[i], right[i + 1], left[i + 1]],
+                &[(row, 0), (row, 1), (row, 3), (row, 4)],
+            );
+            out.extend([(row, 2), (row, 5)]);
+        }
+        point_cells(&out)
+    }
+
+    /// Extends the circuit with the selection of `left + diff` if `bit` is 1
+    /// or of `left` otherwise, with two generic gates per limb:
+    /// `bit * diff = prod` and `left + prod = out`
+    pub fn select(&mut self, bit: Cell, left: &PointCells, diff: &PointCells) -> PointCells {
+        let left = [left.0, left.1].concat();
+        let diff = [diff.0, diff.1].concat();
+        let mut out = vec![];
+        for i in 0..6 {
+            let row = self.generic(
+                [F::zero(), F::zero(), -F::one(), F::one(), F::zero()],
+                [F::one(), F::one(), -F::one(), F::zero(), F::zero()],
+            );
+            self.connect(
+                &[bit, diff[i], left[i], (row, 2)],
+                &[(row, 0), (row, 1), (row, 3), (row, 4)],
+            );
+            out.push((row, 5));
+        }
+        point_cells(&out)
+    }
+}
+
+fn point_cells(limbs: &[Cell]) -> PointCells {
+    (
+        [limbs[0], limbs[1], limbs[2]],
+        [limbs[3], limbs[4], limbs[5]],
+    )
+}
@@ -0,0 +1,89 @@
+//! Builders of circuits over foreign field elements
+//!
+//! Gadgets such as the [ECDSA](super::ecdsa) and [EdDSA](super::eddsa)
+//! verifications chain many foreign field multiplications and additions,
+//! together with the range-checks making them sound. The builders of this
+//! module lay them out one after the other and wire them with copy
+//! constraints: [gadget::ForeignFieldCircuit] extends the gates and returns
+//! the cells of the results, while [witness::ForeignFieldWitness] extends the
+//! witness with the same rows and returns the values of the results. A gadget
+//! calls the same methods of both builders in the same order.
+//!
+//! A foreign field element is held in three 88-bit limbs. Each element is
+//! range-checked when it is created:
+//!
+//! * a multiplication range-checks its remainder, its quotient and its
+//!   intermediate products, and checks that the high limb of the remainder is
+//!   at most the one of the modulus,
+//! * a chain of additions range-checks its result and the bound proving that
+//!   the result is smaller than the modulus,
+//! * an element computed by the prover is range-checked with the same bound
+//!   on its high limb as the remainders.
+//!
+//! The bounds of the high limbs are computed two at a time in a generic gate
+//! and range-checked three at a time, so that
+//! [gadget::ForeignFieldCircuit::finish] and
+//! [witness::ForeignFieldWitness::finish] must be called at the end of the
+//! gadget.
+
+pub mod gadget;
+pub mod witness;
+
+use ark_ff::{One, Zero};
+use num_bigint::BigUint;
+
+/// A point in affine coordinates
+pub type Point = (BigUint, BigUint);
+
+/// The inverse of `x` modulo the prime `modulus`, or `0` if `x` is `0`
+pub fn inverse(x: &BigUint, modulus: &BigUint) -> BigUint {
+    x.modpow(&(modulus - BigUint::from(2u32)), modulus)
+}
+
+/// Subtracts `right` from `left` modulo `modulus`
+pub fn sub(left: &BigUint, right: &BigUint, modulus: &BigUint) -> BigUint {
+    (left + modulus - right % modulus) % modulus
+}
+
+/// A square root of `x` modulo the odd prime `modulus`, if `x` is a square,
+/// computed with the Tonelli-Shanks algorithm
+pub fn sqrt(x: &BigUint, modulus: &BigUint) -> Option<BigUint> {
+    let x = x % modulus;
+    if x.is_zero() {
+        return Some(x);
+    }
+    let one = BigUint::one();
+    let half = (modulus - &one) >> 1;
+    // Euler's criterion
+    if x.modpow(&half, modulus) != one {
+        return None;
+    }
+
+    // modulus - 1 = q * 2^s with q odd, and z a non-square
+    let s = (modulus - &one).trailing_zeros().unwrap();
+    let q = (modulus - &one) >> s;
+    let mut z = BigUint::from(2u32);
+    while z.modpow(&half, modulus) == one {
+        z += 1u32;
+    }
+
+    let mut m = s;
+    let mut c = z.modpow(&q, modulus);
+    let mut t = x.modpow(&q, modulus);
+    let mut root = x.modpow(&((&q + &one) >> 1), modulus);
+    while t != one {
+        // The least i such that t^(2^i) = 1
+        let mut i = 0;
+        let mut t2 = t.clone();
+        while t2 != one {
+            t2 = &t2 * &t2 % modulus;
+            i += 1;
+        }
+        let b = c.modpow(&(BigUint::one() << (m - i - 1)), modulus);
+        m = i;
+        c = &b * &b % modulus;
+        t = t * &c % modulus;
+        root = root * b % modulus;
+    }
+    Some(root)
+}
@@ -0,0 +1,248 @@
+//! This module computes the witness of a circuit over foreign field elements.
+
+use super::Point;
+use crate::circuits::{
+    polynomial::COLUMNS,
+    polynomials::{
+        foreign_field_add::{self, witness::FFOps},
+        foreign_field_common::{BigUintForeignFieldHelpers, KimchiForeignElement},
+        foreign_field_mul::{self, witness::ExternalChecks},
+        range_check,
+    },
+};
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use o1_utils::foreign_field::ForeignFieldHelpers;
+
+/// Extends the witness of a circuit over foreign field elements in the order
+/// of the gates of [super::gadget::ForeignFieldCircuit], pooling the bound
+/// checks of the high limbs in the same way
+pub struct ForeignFieldWitness<'a, F: PrimeField> {
+    witness: &'a mut [Vec<F>; COLUMNS],
+    /// The high limb waiting for its bound to be computed, with its modulus
+    high_limb: Option<(F, BigUint)>,
+    /// The bounds waiting to be range-checked
+    limbs: Vec<F>,
+}
+
+impl<'a, F: PrimeField> ForeignFieldWitness<'a, F> {
+    /// Creates a builder extending `witness`, starting with the 2 rows of the
+    /// constants
+    pub fn new(witness: &'a mut [Vec<F>; COLUMNS]) -> Self {
+        let mut builder = Self {
+            witness,
+            high_limb: None,
+            limbs: vec![],
+        };
+        builder.row(&[F::zero(), F::zero(), F::zero(), F::one()]);
+        builder.row(&[KimchiForeignElement::<F>::two_to_limb()]);
+        builder
+    }
+
+    /// Extends the witness with a row starting with `values`
+    pub fn row(&mut self, values: &[F]) {
+        for (col, column) in self.witness.iter_mut().enumerate() {
+            column.push(values.get(col).copied().unwrap_or_else(F::zero));
+        }
+    }
+
+    fn rows(&mut self, rows: [Vec<F>; COLUMNS]) {
+        for (column, rows) in self.witness.iter_mut().zip(rows) {
+            column.extend(rows);
+        }
+    }
+
+    /// Extends the witness with the limbs of the constant `value`
+    pub fn constant(&mut self, value: &BigUint) {
+        let limbs = value.to_field_limbs::<F>();
+        self.row(&[limbs[0], F::zero(), F::zero(), limbs[1]]);
+        self.row(&[limbs[2]]);
+    }
+
+    /// Extends the witness with the coordinates of the constant `point`
+    pub fn constant_point(&mut self, point: &Point) {
+        self.constant(&point.0);
+        self.constant(&point.1);
+    }
+
+    /// Extends the witness with the element `value` computed by the prover
+    pub fn element(&mut self, value: &BigUint, modulus: &BigUint) {
+        let limbs = value.to_field_limbs::<F>();
+        range_check::witness::extend_multi(self.witness, limbs[0], limbs[1], limbs[2]);
+        self.high_limb(limbs[2], modulus);
+    }
+
+    /// Extends the witness with the three 88-bit limbs of `value`, which is
+    /// smaller than `2^264`
+    pub fn limbs(&mut self, value: &BigUint) {
+        let limbs = value.to_field_limbs::<F>();
+        range_check::witness::extend_multi(self.witness, limbs[0], limbs[1], limbs[2]);
+    }
+
+    fn high_limb(&mut self, limb: F, modulus: &BigUint) {
+        match self.high_limb.take() {
+            Some((pending, pending_modulus)) if pending_modulus == *modulus => {
+                self.high_bounds(&[pending, limb], modulus)
+            }
+            Some((pending, pending_modulus)) => {
+                self.high_bounds(&[pending], &pending_modulus);
+                self.high_limb = Some((limb, modulus.clone()));
+            }
+            None => self.high_limb = Some((limb, modulus.clone())),
+        }
+    }
+
+    fn high_bounds(&mut self, limbs: &[F], modulus: &BigUint) {
+        let mut checks = ExternalChecks::<F>::default();
+        for limb in limbs {
+            checks.add_high_bound_computation(limb);
+        }
+        checks.extend_witness_high_bounds_computation(self.witness, modulus);
+        let hi_limb =
+            KimchiForeignElement::<F>::two_to_limb() - modulus.to_field_limbs::<F>()[2] - F::one();
+        for limb in limbs {
+            self.range_check(*limb + hi_limb);
+        }
+    }
+
+    /// Extends the witness with the range-check of `limb`, pooled in the same
+    /// way
+    pub fn range_check(&mut self, limb: F) {
+        self.limbs.push(limb);
+        if self.limbs.len() == 3 {
+            self.limb_checks();
+        }
+    }
+
+    fn limb_checks(&mut self) {
+        let mut checks = ExternalChecks::<F>::default();
+        for limb in core::mem::take(&mut self.limbs) {
+            checks.add_limb_check(&limb);
+        }
+        checks.extend_witness_limb_checks(self.witness);
+    }
+
+    /// Extends the witness with the checks still pooled
+    pub fn finish(&mut self) {
+        if let Some((limb, modulus)) = self.high_limb.take() {
+            self.high_bounds(&[limb], &modulus);
+        }
+        if !self.limbs.is_empty() {
+            self.limb_checks();
+        }
+    }
+
+    /// Extends the witness with the multiplication of `left` and `right`, and
+    /// returns the remainder
+    pub fn mul(&mut self, left: &BigUint, right: &BigUint, modulus: &BigUint) -> BigUint {
+        let (rows, mut checks) = foreign_field_mul::witness::create::<F>(left, right, modulus);
+        self.rows(rows);
+        // The remainder, then the quotient and the intermediate products
+        checks.extend_witness_compact_multi_range_checks(self.witness);
+        checks.extend_witness_multi_range_checks(self.witness);
+        let remainder = (left * right) % modulus;
+        self.high_limb(remainder.to_field_limbs()[2], modulus);
+        remainder
+    }
+
+    /// Extends the witness with a chain of additions of `inputs`, and returns
+    /// the result
+    pub fn add_chain(
+        &mut self,
+        inputs: &[BigUint],
+        opcodes: &[FFOps],
+        modulus: &BigUint,
+    ) -> BigUint {
+        let rows = foreign_field_add::witness::create_chain::<F>(inputs, opcodes, modulus.clone());
+        self.rows(rows);
+        let result =
+            inputs[1..]
+                .iter()
+                .zip(opcodes)
+                .fold(inputs[0].clone(), |acc, (input, opcode)| match opcode {
+                    FFOps::Add => (acc + input) % modulus,
+                    FFOps::Sub => (acc + modulus - input % modulus) % modulus,
+                });
+        let bound = &result + BigUint::binary_modulus() - modulus;
+        for value in [&result, &bound] {
+            let limbs = value.to_field_limbs::<F>();
+            range_check::witness::extend_multi(self.witness, limbs[0], limbs[1], limbs[2]);
+        }
+        result
+    }
+
+    /// Extends the witness with the decomposition of `scalar` in bits, the
+    /// limbs having `limb_bits` bits, and returns the bits from the most
+    /// significant one
+    pub fn bits(&mut self, scalar: &BigUint, limb_bits: [usize; 3]) -> Vec<bool> {
+        let limbs = scalar.to_limbs();
+        let mut bits = vec![];
+        for (limb, limb_bits) in limbs.iter().zip(limb_bits).rev() {
+            let mut acc = F::zero();
+            for i in (0..limb_bits).rev() {
+                let bit = limb.bit(i as u64);
+                let value = F::from(bit);
+                let next_acc = acc.double() + value;
+                self.row(&[acc, value, next_acc, value, value]);
+                bits.push(bit);
+                acc = next_acc;
+            }
+        }
+        bits
+    }
+
+    /// Extends the witness with the selection of `right` if `bit` is set or
+    /// of `left` otherwise, and returns it
+    pub fn select_constant(&mut self, bit: bool, left: &Point, right: &Point) -> Point {
+        let out = if bit { right } else { left };
+        let limbs = point_limbs::<F>(out);
+        let bit = F::from(bit);
+        for i in (0..6).step_by(2) {
+            self.row(&[bit, F::zero(), limbs[i], bit, F::zero(), limbs[i + 1]]);
+        }
+        out.clone()
+    }
+
+    /// Extends the witness with the differences of the limbs of `right` and
+    /// `left`
+    pub fn difference(&mut self, left: &Point, right: &Point) {
+        let left = point_limbs::<F>(left);
+        let right = point_limbs::<F>(right);
+        for i in (0..6).step_by(2) {
+            self.row(&[
+                right[i],
+                left[i],
+                right[i] - left[i],
+                right[i + 1],
+                left[i + 1],
+                right[i + 1] - left[i + 1],
+            ]);
+        }
+    }
+
+    /// Extends the witness with the selection of `right` if `bit` is set or
+    /// of `left` otherwise, given the differences of their limbs, and returns
+    /// it
+    pub fn select(&mut self, bit: bool, left: &Point, right: &Point) -> Point {
+        let left_limbs = point_limbs::<F>(left);
+        let right_limbs = point_limbs::<F>(right);
+        let value = F::from(bit);
+        for (left, right) in left_limbs.into_iter().zip(right_limbs) {
+            let diff = right - left;
+            let prod = value * diff;
+            self.row(&[value, diff, prod, left, prod, left + prod]);
+        }
+        if bit {
+            right.clone()
+        } else {
+            left.clone()
+        }
+    }
+}
+
+// The limbs of the coordinates of `point`
+fn point_limbs<F: PrimeField>(point: &Point) -> [F; 6] {
+    let x = point.0.to_field_limbs::<F>();
+    let y = point.1.to_field_limbs::<F>();
+    [x[0], x[1], x[2], y[0], y[1], y[2]]
+}
@@ -0,0 +1,188 @@
+//! This module obtains the gates of the wide foreign field multiplication
+//! gadget.
+
+use crate::circuits::{
+    gate::CircuitGate,
+    polynomials::{
+        foreign_field_builder::gadget::{Cell, ForeignFieldCircuit},
+        modexp::{
+            gadget::{accumulate, assert_zero, big_int},
+            limb_count, to_limbs,
+        },
+    },
+};
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+
+/// The cells of the 88-bit limbs of an element modulo a wide modulus, from
+/// the lowest to the highest
+pub type WideElementCells = Vec<Cell>;
+
+/// The cells of the inputs and of the output of the wide foreign field
+/// multiplication gadget, see [CircuitGate::extend_wide_foreign_field_mul]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WideForeignFieldMulCells {
+    /// The left input
+    pub left: WideElementCells,
+    /// The right input
+    pub right: WideElementCells,
+    /// The result `left * right mod modulus`
+    pub result: WideElementCells,
+}
+
+impl<F: PrimeField> CircuitGate<F> {
+    /// Extends the circuit with the multiplication of two elements modulo the
+    /// foreign `modulus`, see [super] for the layout.
+    ///
+    /// Includes:
+    /// - the multi-range-checks of the limbs of the inputs, and the checks
+    ///   that they are smaller than the modulus
+    /// - the generic gates checking `left * right = q * modulus + r`, and the
+    ///   multi-range-checks of the limbs of `q` and of the carries
+    /// - the check that the result `r` is smaller than the modulus
+    ///
+    /// Output:
+    /// - the cells of the inputs and of the result, which the rest of the
+    ///   circuit must copy
+    pub fn extend_wide_foreign_field_mul(
+        gates: &mut Vec<Self>,
+        modulus: &BigUint,
+    ) -> WideForeignFieldMulCells {
+        let mut builder = ForeignFieldCircuit::new(gates);
+        let left = wide_element(&mut builder, modulus);
+        let right = wide_element(&mut builder, modulus);
+        let result = wide_mul(&mut builder, &left, &right, modulus);
+        builder.finish();
+
+        WideForeignFieldMulCells {
+            left,
+            right,
+            result,
+        }
+    }
+}
+
+/// Extends the circuit with the multi-range-checks of the limbs of an element
+/// computed by the prover, and the check that it is smaller than `modulus`
+pub fn wide_element<F: PrimeField>(
+    builder: &mut ForeignFieldCircuit<F>,
+    modulus: &BigUint,
+) -> WideElementCells {
+    let limbs = big_int(builder, limb_count(modulus.bits() as usize));
+    bound_check(builder, &limbs, modulus);
+    limbs
+}
+
+/// Extends the circuit with the multiplication `left * right = q * modulus +
+/// r` of two elements smaller than `modulus`, and returns the cells of the
+/// remainder `r`, checked to be smaller than `modulus`
+///
+/// # Panics
+///
+/// Will panic if the elements do not have the limbs of the modulus.
+pub fn wide_mul<F: PrimeField>(
+    builder: &mut ForeignFieldCircuit<F>,
+    left: &WideElementCells,
+    right: &WideElementCells,
+    modulus: &BigUint,
+) -> WideElementCells {
+    let count = limb_count(modulus.bits() as usize);
+    assert_eq!(left.len(), count);
+    assert_eq!(right.len(), count);
+    let modulus_limbs: Vec<F> = to_limbs(modulus, count).into_iter().map(F::from).collect();
+
+    let square = left == right;
+    let quotient = big_int(builder, count);
+    let remainder = big_int(builder, count);
+    let zero = builder.zero();
+    let columns = (0..2 * count - 1)
+        .map(|i| {
+            let mut acc = zero;
+            // The limbs of weight 2^(88 * i) are the pairs (j, i - j)
+            let terms = i.saturating_sub(count - 1)..=i.min(count - 1);
+            for j in terms.clone() {
+                let l = i - j;
+                if !square || j <= l {
+                    let coeff = if square && j < l {
+                        F::from(2u32)
+                    } else {
+                        F::one()
+                    };
+                    acc = accumulate(builder, acc, left[j], right[l], coeff);
+                }
+            }
+            // The limbs of the modulus are constants, multiplying the limbs
+            // of the quotient two at a time, followed by the remainder
+            let mut linear_terms: Vec<_> = terms
+                .map(|j| (quotient[j], -modulus_limbs[i - j]))
+                .collect();
+            if i < count {
+                linear_terms.push((remainder[i], -F::one()));
+            }
+            for pair in linear_terms.chunks(2) {
+                acc = linear(builder, acc, pair);
+            }
+            acc
+        })
+        .collect();
+    assert_zero(builder, columns);
+    bound_check(builder, &remainder, modulus);
+    remainder
+}
+
+// Extends the circuit with a generic gate computing `acc + c1 * x1 + c2 * x2`
+// for the one or two `terms` (x, c)
+fn linear<F: PrimeField>(
+    builder: &mut ForeignFieldCircuit<F>,
+    acc: Cell,
+    terms: &[(Cell, F)],
+) -> Cell {
+    let (x1, c1) = terms[0];
+    let (x2, c2) = terms.get(1).copied().unwrap_or((builder.zero(), F::zero()));
+    // acc + c1 * x1 = t, t + c2 * x2 = out
+    let row = builder.generic(
+        [F::one(), c1, -F::one(), F::zero(), F::zero()],
+        [F::one(), c2, -F::one(), F::zero(), F::zero()],
+    );
+    builder.connect(
+        &[acc, x1, (row, 2), x2],
+        &[(row, 0), (row, 1), (row, 3), (row, 4)],
+    );
+    (row, 5)
+}
+
+// Extends the circuit with the check that the element of limbs `limbs` is
+// smaller than `modulus`: the limbs of `d = modulus - 1 - x` are
+// range-checked, and `(n_i - [i = 0]) - x_i - d_i` are the columns of zero,
+// two per generic gate
+fn bound_check<F: PrimeField>(
+    builder: &mut ForeignFieldCircuit<F>,
+    limbs: &WideElementCells,
+    modulus: &BigUint,
+) {
+    let count = limbs.len();
+    let modulus_limbs = to_limbs(modulus, count);
+    let diff = big_int(builder, count);
+    let half = |k: usize| {
+        let mut constant = F::from(modulus_limbs[k].clone());
+        if k == 0 {
+            constant -= F::one();
+        }
+        [-F::one(), -F::one(), -F::one(), F::zero(), constant]
+    };
+    let mut columns = vec![];
+    for k in (0..count).step_by(2) {
+        let pair = (k..count).take(2);
+        let right = if pair.len() == 2 {
+            half(k + 1)
+        } else {
+            [F::zero(); 5]
+        };
+        let row = builder.generic(half(k), right);
+        for (h, k) in pair.enumerate() {
+            builder.connect(&[limbs[k], diff[k]], &[(row, 3 * h), (row, 3 * h + 1)]);
+            columns.push((row, 3 * h + 2));
+        }
+    }
+    assert_zero(builder, columns);
+}
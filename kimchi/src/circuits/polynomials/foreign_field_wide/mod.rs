@@ -0,0 +1,32 @@
+//! Foreign field multiplication for moduli wider than the foreign field gates
+//!
+//! The [foreign field multiplication](super::foreign_field_mul) gate holds
+//! its elements in three 88-bit limbs, and handles moduli of at most 259
+//! bits. This gadget multiplies elements modulo a fixed foreign modulus `n`
+//! of any width, such as the 512- and 1024-bit moduli of RSA or of some
+//! pairing-friendly curves, with elements held in `k` limbs of 88 bits, with
+//! `88 * k` at least the number of bits of the modulus:
+//!
+//! * the limbs of the elements are range-checked, and each element is
+//!   checked to be smaller than the modulus by the range-checks of the limbs
+//!   of `n - 1 - x`,
+//! * the product `a * b = q * n + r` is checked with generic gates, over the
+//!   columns of the limbs of weight `2^(88 * i)` and the carries from one
+//!   column to the next, as in the [modular exponentiation](super::modexp),
+//!   the products by the limbs of the modulus being constant coefficients,
+//! * the limbs of the quotient `q` are range-checked, and the remainder `r`
+//!   is checked to be smaller than the modulus.
+//!
+//! A multiplication takes 236 rows for a 512-bit modulus, and 579 rows for a
+//! 1024-bit modulus, including the range-checks of its inputs.
+//!
+//! The gates are created by
+//! [CircuitGate::extend_wide_foreign_field_mul](crate::circuits::gate::CircuitGate::extend_wide_foreign_field_mul)
+//! and the witness by [witness::extend_wide_foreign_field_mul_witness], which
+//! lay out the rows in the same order. The multiplications can be chained
+//! within a [foreign field circuit](super::foreign_field_builder) with
+//! [gadget::wide_element] and [gadget::wide_mul], and their witness with
+//! [witness::wide_element] and [witness::wide_mul].
+
+pub mod gadget;
+pub mod witness;
@@ -0,0 +1,164 @@
+//! This module computes the witness of the wide foreign field multiplication
+//! gadget.
+
+use crate::circuits::{
+    polynomial::COLUMNS,
+    polynomials::{
+        foreign_field_builder::witness::ForeignFieldWitness,
+        modexp::{
+            limb_count, to_limbs,
+            witness::{accumulate, assert_zero, big_int, field},
+        },
+    },
+};
+use ark_ff::PrimeField;
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+
+/// Extends the witness with the multiplication of `left` and `right` modulo
+/// the foreign `modulus`, laid out as the gates of
+/// [CircuitGate::extend_wide_foreign_field_mul](crate::circuits::gate::CircuitGate::extend_wide_foreign_field_mul),
+/// and returns the result.
+pub fn extend_wide_foreign_field_mul_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    left: &BigUint,
+    right: &BigUint,
+    modulus: &BigUint,
+) -> BigUint {
+    let mut builder = ForeignFieldWitness::new(witness);
+    wide_element(&mut builder, left, modulus);
+    wide_element(&mut builder, right, modulus);
+    let result = wide_mul(&mut builder, left, right, modulus);
+    builder.finish();
+    result
+}
+
+/// Extends the witness with the element `value` smaller than `modulus`, laid
+/// out as [super::gadget::wide_element]
+///
+/// # Panics
+///
+/// Will panic if `value` is not smaller than `modulus`.
+pub fn wide_element<F: PrimeField>(
+    builder: &mut ForeignFieldWitness<F>,
+    value: &BigUint,
+    modulus: &BigUint,
+) {
+    big_int(builder, value, limb_count(modulus.bits() as usize));
+    bound_check(builder, value, modulus);
+}
+
+/// Extends the witness with the multiplication of `left` and `right` modulo
+/// `modulus`, laid out as [super::gadget::wide_mul], and returns the result
+///
+/// # Panics
+///
+/// Will panic if the elements are not smaller than `modulus`.
+pub fn wide_mul<F: PrimeField>(
+    builder: &mut ForeignFieldWitness<F>,
+    left: &BigUint,
+    right: &BigUint,
+    modulus: &BigUint,
+) -> BigUint {
+    assert!(left < modulus && right < modulus);
+    let count = limb_count(modulus.bits() as usize);
+    let square = left == right;
+    let (quotient, remainder) = (left * right).div_rem(modulus);
+    big_int(builder, &quotient, count);
+    big_int(builder, &remainder, count);
+
+    let left = to_limbs(left, count);
+    let right = to_limbs(right, count);
+    let quotient_limbs = to_limbs(&quotient, count);
+    let modulus_limbs = to_limbs(modulus, count);
+    let remainder_limbs = to_limbs(&remainder, count);
+    let columns = (0..2 * count - 1)
+        .map(|i| {
+            let mut acc = BigInt::from(0);
+            let terms = i.saturating_sub(count - 1)..=i.min(count - 1);
+            for j in terms.clone() {
+                let l = i - j;
+                if !square || j <= l {
+                    let coeff = if square && j < l { 2 } else { 1 };
+                    acc = accumulate(builder, acc, &left[j], &right[l], coeff);
+                }
+            }
+            let mut linear_terms: Vec<_> = terms
+                .map(|j| {
+                    (
+                        &quotient_limbs[j],
+                        BigInt::from(modulus_limbs[i - j].clone()),
+                    )
+                })
+                .collect();
+            if i < count {
+                linear_terms.push((&remainder_limbs[i], BigInt::from(1)));
+            }
+            for pair in linear_terms.chunks(2) {
+                acc = linear(builder, acc, pair);
+            }
+            acc
+        })
+        .collect();
+    assert_zero(builder, columns);
+    bound_check(builder, &remainder, modulus);
+    remainder
+}
+
+// Extends the witness with the generic gate computing `acc - c1 * x1 - c2 *
+// x2` for the one or two `terms` (x, c), and returns the result
+fn linear<F: PrimeField>(
+    builder: &mut ForeignFieldWitness<F>,
+    acc: BigInt,
+    terms: &[(&BigUint, BigInt)],
+) -> BigInt {
+    let zero = BigUint::from(0u32);
+    let (x1, c1) = &terms[0];
+    let (x2, c2) = terms
+        .get(1)
+        .map_or((&zero, BigInt::from(0)), |(x, c)| (*x, c.clone()));
+    let t = &acc - c1 * BigInt::from((*x1).clone());
+    let out = &t - c2 * BigInt::from(x2.clone());
+    builder.row(&[
+        field(&acc),
+        F::from((*x1).clone()),
+        field(&t),
+        field(&t),
+        F::from(x2.clone()),
+        field(&out),
+    ]);
+    out
+}
+
+// Extends the witness with the check that `value` is smaller than `modulus`,
+// laid out as the gates of the bound check
+fn bound_check<F: PrimeField>(
+    builder: &mut ForeignFieldWitness<F>,
+    value: &BigUint,
+    modulus: &BigUint,
+) {
+    let count = limb_count(modulus.bits() as usize);
+    let diff = modulus - 1u32 - value;
+    big_int(builder, &diff, count);
+    let (modulus_limbs, value_limbs) = (to_limbs(modulus, count), to_limbs(value, count));
+    let diff_limbs = to_limbs(&diff, count);
+    let mut columns = vec![];
+    for k in (0..count).step_by(2) {
+        let mut row = vec![];
+        for k in (k..count).take(2) {
+            let constant = if k == 0 { 1u32 } else { 0u32 };
+            let column = BigInt::from(modulus_limbs[k].clone())
+                - constant
+                - BigInt::from(value_limbs[k].clone())
+                - BigInt::from(diff_limbs[k].clone());
+            row.extend([
+                F::from(value_limbs[k].clone()),
+                F::from(diff_limbs[k].clone()),
+                field(&column),
+            ]);
+            columns.push(column);
+        }
+        builder.row(&row);
+    }
+    assert_zero(builder, columns);
+}
@@ -255,7 +255,11 @@ impl<F: PrimeField> CircuitGate<F> {
 
 pub mod testing {
     use super::*;
-    use crate::circuits::wires::Wire;
+    use crate::circuits::{
+        constraints::{ConstraintFailure, GateError},
+        gate::CircuitGateError,
+        wires::Wire,
+    };
     use itertools::iterate;
 
     impl<F: PrimeField> CircuitGate<F> {
@@ -263,23 +267,26 @@ pub mod testing {
         ///
         /// # Errors
         ///
-        /// Will give error if `self.typ` is not `GateType::Generic`.
+        /// Will give error if `self.typ` is not `GateType::Generic`, or
+        /// pinpointing the generic gate of the row that is not satisfied.
         pub fn verify_generic(
             &self,
             row: usize,
             witness: &[Vec<F>; COLUMNS],
             public: &[F],
-        ) -> Result<(), String> {
+        ) -> Result<(), GateError<F>> {
             // assignments
             let this: [F; COLUMNS] = array::from_fn(|i| witness[i][row]);
 
-            // constants
-            let zero = F::zero();
-
             // check if it's the correct gate
-            ensure_eq!(self.typ, GateType::Generic, "generic: incorrect gate");
+            if self.typ != GateType::Generic {
+                return Err(GateError::Gate {
+                    row,
+                    err: CircuitGateError::InvalidConstraint(self.typ),
+                });
+            }
 
-            let check_single = |coeffs_offset, register_offset| {
+            let check_single = |constraint, coeffs_offset, register_offset| {
                 let get = |offset| {
                     self.coeffs
                         .get(offset)
@@ -301,16 +308,20 @@ pub mod testing {
                 } else {
                     F::zero()
                 };
-                ensure_eq!(
-                    zero,
-                    sum + mul + c_coeff - public,
-                    "generic: incorrect gate"
-                );
+                let left = sum + mul + c_coeff;
+                if left != public {
+                    return Err(GateError::Constraint(ConstraintFailure {
+                        row,
+                        typ: GateType::Generic,
+                        constraint,
+                        values: Some((left, public)),
+                    }));
+                }
                 Ok(())
             };
 
-            check_single(0, 0)?;
-            check_single(GENERIC_COEFFS, GENERIC_REGISTERS)
+            check_single(1, 0, 0)?;
+            check_single(2, GENERIC_COEFFS, GENERIC_REGISTERS)
         }
     }
 
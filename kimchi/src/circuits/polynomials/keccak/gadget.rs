@@ -0,0 +1,402 @@
+//! Keccak gadget for ordinary kimchi circuits
+//!
+//! The layout of [super::witness] holds a whole round in one row of 1965
+//! cells, which only fits the wide traces of the zkVM. Here the rounds are
+//! composed of the 64-bit gadgets of kimchi instead, operating on the words of
+//! the state held in single cells, so that a Keccak permutation fits the 15
+//! columns of a kimchi row:
+//!
+//! * `XOR` uses the [Xor16](super::super::xor) chain and its XOR lookup table,
+//!   which also constrains the inputs and the output to be 64-bit words,
+//! * the rotations use [Rot64](super::super::rot) and the range-check lookup
+//!   table,
+//! * `NOT` is a subtraction from the all-one word in a double generic gate,
+//!   the inputs being known to be 64-bit words,
+//! * `AND` uses [the AND gadget](super::super::and).
+//!
+//! The words of the state are linked from one operation to the next with copy
+//! constraints, so that the `π` step is only a permutation of the cells.
+//!
+//! A round takes the following rows, for 631 rows in total:
+//!
+//! | Step  | Rows                                        |
+//! | ----- | ------------------------------------------- |
+//! | `ι`   | 1 generic gate holding the round constant   |
+//! | `θ`   | 50 XORs and 5 rotations                     |
+//! | `ρ`   | 24 rotations, the first word is not rotated |
+//! | `χ`   | 13 NOT rows, 25 ANDs and 25 XORs            |
+//! | `ι`   | 1 XOR with the round constant               |
+//!
+//! A permutation starts with 2 rows holding the constants `0` and `2^64 - 1`,
+//! the former for the bounds of the rotations, the latter for the negations.
+//!
+//! The gates are created by [CircuitGate::extend_keccak_permutation] and
+//! [CircuitGate::extend_keccak256], and the witness by
+//! [extend_keccak_permutation_witness] and [extend_keccak256_witness], which
+//! lay out the rows in the same order.
+
+use super::{
+    constants::{DIM, RATE_IN_BYTES, ROUNDS},
+    Keccak, OFF, RC,
+};
+use crate::circuits::{
+    gate::{CircuitGate, Connect},
+    lookup::{
+        self,
+        tables::{GateLookupTable, LookupTable},
+    },
+    polynomial::COLUMNS,
+    polynomials::{
+        and::extend_and_witness,
+        generic::GenericGateSpec,
+        not::extend_not_witness_unchecked_length,
+        rot::{self, RotMode},
+        xor::extend_xor_witness,
+    },
+    wires::Wire,
+};
+use ark_ff::PrimeField;
+use core::array;
+
+/// The number of 64-bit words of the Keccak state
+pub const STATE_WORDS: usize = DIM * DIM;
+
+/// The number of 64-bit words of the state absorbing a block of the message
+pub const RATE_WORDS: usize = RATE_IN_BYTES / 8;
+
+/// The number of 64-bit words of the Keccak-256 digest
+pub const DIGEST_WORDS: usize = 4;
+
+/// The number of rows of a round of the gadget
+pub const ROUND_ROWS: usize = 631;
+
+/// A cell of the circuit, as `(row, column)`
+pub type Cell = (usize, usize);
+
+/// The cells of the message and of the digest of the Keccak-256 gadget, see
+/// [CircuitGate::extend_keccak256]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Keccak256Cells {
+    /// The cells of the 64-bit words of the padded message, in little-endian
+    /// order, [RATE_WORDS] for each block
+    pub message: Vec<Cell>,
+    /// The cells of the 64-bit words of the digest, in little-endian order
+    pub digest: [Cell; DIGEST_WORDS],
+}
+
+/// The rows holding the constants used by the rounds
+#[derive(Clone, Copy, Debug)]
+struct Constants {
+    /// The row whose first cell is `0`
+    zero_row: usize,
+    /// The row whose first cell is `2^64 - 1`
+    ones_row: usize,
+}
+
+impl<F: PrimeField> CircuitGate<F> {
+    /// Extends the circuit with a Keccak permutation of the state held in the
+    /// cells `state`, with the word `x + 5y` of the state in `state[x + 5y]`.
+    ///
+    /// Includes:
+    /// - 2 Generic gates holding the constants of the rounds
+    /// - 24 rounds of [ROUND_ROWS] rows
+    ///
+    /// Output:
+    /// - the cells holding the words of the permuted state
+    ///
+    /// Warning:
+    /// - the cells of `state` must hold 64-bit words, as the outputs of other
+    ///   64-bit gadgets do
+    pub fn extend_keccak_permutation(
+        gates: &mut Vec<Self>,
+        state: &[Cell; STATE_WORDS],
+    ) -> [Cell; STATE_WORDS] {
+        let constants = extend_constants(gates);
+        extend_permutation(gates, state, constants)
+    }
+
+    /// Extends the circuit with a Keccak-256 hash of a message of `blocks`
+    /// blocks of [RATE_IN_BYTES] bytes once padded, see [Keccak::num_blocks].
+    ///
+    /// Includes:
+    /// - 2 Generic gates holding the constants of the rounds
+    /// - for each block, [RATE_WORDS] XORs absorbing it, followed by a
+    ///   permutation of 24 rounds
+    ///
+    /// Output:
+    /// - the cells of the words of the padded message and of the digest
+    ///
+    /// Warning:
+    /// - the padding of the message is not constrained, the cells of the
+    ///   message must be wired to the words of a message padded as in
+    ///   [Keccak::pad]
+    pub fn extend_keccak256(gates: &mut Vec<Self>, blocks: usize) -> Keccak256Cells {
+        assert!(blocks > 0, "A padded message has at least one block");
+        let constants = extend_constants(gates);
+        let mut state = [(constants.zero_row, 0); STATE_WORDS];
+        let mut message = vec![];
+        for _ in 0..blocks {
+            for word in state.iter_mut().take(RATE_WORDS) {
+                let row = gates.len();
+                Self::extend_xor_gadget(gates, 64);
+                gates.connect_cell_pair(*word, (row, 0));
+                message.push((row, 1));
+                *word = (row, 2);
+            }
+            state = extend_permutation(gates, &state, constants);
+        }
+        Keccak256Cells {
+            message,
+            digest: array::from_fn(|i| state[i]),
+        }
+    }
+}
+
+/// Get the lookup tables used by the Keccak gadget
+pub fn lookup_tables<F: PrimeField>() -> Vec<LookupTable<F>> {
+    vec![
+        lookup::tables::get_table::<F>(GateLookupTable::Xor),
+        lookup::tables::get_table::<F>(GateLookupTable::RangeCheck),
+    ]
+}
+
+fn extend_constants<F: PrimeField>(gates: &mut Vec<CircuitGate<F>>) -> Constants {
+    let zero_row = extend_constant(gates, 0);
+    let ones_row = extend_constant(gates, u64::MAX);
+    Constants { zero_row, ones_row }
+}
+
+// Extends the circuit with a generic gate constraining its first cell to be
+// `value`, and returns its row
+fn extend_constant<F: PrimeField>(gates: &mut Vec<CircuitGate<F>>, value: u64) -> usize {
+    let row = gates.len();
+    gates.push(CircuitGate::create_generic_gadget(
+        Wire::for_row(row),
+        GenericGateSpec::Const(F::from(value)),
+        None,
+    ));
+    row
+}
+
+fn extend_permutation<F: PrimeField>(
+    gates: &mut Vec<CircuitGate<F>>,
+    state: &[Cell; STATE_WORDS],
+    constants: Constants,
+) -> [Cell; STATE_WORDS] {
+    let mut state = *state;
+    for round in 0..ROUNDS {
+        state = extend_round(gates, &state, constants, round);
+    }
+    state
+}
+
+fn extend_round<F: PrimeField>(
+    gates: &mut Vec<CircuitGate<F>>,
+    state: &[Cell; STATE_WORDS],
+    constants: Constants,
+    round: usize,
+) -> [Cell; STATE_WORDS] {
+    let rc_row = extend_constant(gates, RC[round]);
+
+    // θ
+    let c: [Cell; DIM] = array::from_fn(|x| {
+        (1..DIM).fold(state[x], |c, y| extend_xor(gates, c, state[x + DIM * y]))
+    });
+    let d: [Cell; DIM] = array::from_fn(|x| {
+        let rot_c = extend_rot(gates, c[(x + 1) % DIM], 1, constants);
+        extend_xor(gates, c[(x + DIM - 1) % DIM], rot_c)
+    });
+    let e: [Cell; STATE_WORDS] = array::from_fn(|i| extend_xor(gates, state[i], d[i % DIM]));
+
+    // ρ and π
+    let mut b = [(0, 0); STATE_WORDS];
+    for (i, word) in e.iter().enumerate() {
+        let (x, y) = (i % DIM, i / DIM);
+        b[y + DIM * ((2 * x + 3 * y) % DIM)] = match OFF[y][x] {
+            0 => *word,
+            offset => extend_rot(gates, *word, offset as u32, constants),
+        };
+    }
+
+    // χ
+    let not_b = extend_not(gates, &b, constants);
+    let mut f: [Cell; STATE_WORDS] = array::from_fn(|i| {
+        let (x, y) = (i % DIM, i / DIM);
+        let and = extend_and(
+            gates,
+            not_b[(x + 1) % DIM + DIM * y],
+            b[(x + 2) % DIM + DIM * y],
+        );
+        extend_xor(gates, b[i], and)
+    });
+
+    // ι
+    f[0] = extend_xor(gates, f[0], (rc_row, 0));
+    f
+}
+
+fn extend_xor<F: PrimeField>(gates: &mut Vec<CircuitGate<F>>, left: Cell, right: Cell) -> Cell {
+    let row = gates.len();
+    CircuitGate::extend_xor_gadget(gates, 64);
+    gates.connect_cell_pair(left, (row, 0));
+    gates.connect_cell_pair(right, (row, 1));
+    (row, 2)
+}
+
+fn extend_rot<F: PrimeField>(
+    gates: &mut Vec<CircuitGate<F>>,
+    word: Cell,
+    offset: u32,
+    constants: Constants,
+) -> Cell {
+    let row = gates.len();
+    CircuitGate::extend_rot(gates, offset, RotMode::Left, constants.zero_row);
+    gates.connect_cell_pair(word, (row, 0));
+    (row, 1)
+}
+
+fn extend_and<F: PrimeField>(gates: &mut Vec<CircuitGate<F>>, left: Cell, right: Cell) -> Cell {
+    let row = gates.len();
+    let next_row = CircuitGate::extend_and(gates, 8);
+    gates.connect_cell_pair(left, (row, 0));
+    gates.connect_cell_pair(right, (row, 1));
+    (next_row - 1, 5)
+}
+
+fn extend_not<F: PrimeField>(
+    gates: &mut Vec<CircuitGate<F>>,
+    words: &[Cell; STATE_WORDS],
+    constants: Constants,
+) -> [Cell; STATE_WORDS] {
+    let row = gates.len();
+    CircuitGate::extend_not_gadget_unchecked_length(gates, STATE_WORDS, constants.ones_row);
+    array::from_fn(|i| {
+        // Each row negates two words, in the columns 1 and 4
+        let (row, col) = (row + i / 2, 3 * (i % 2));
+        gates.connect_cell_pair(words[i], (row, col + 1));
+        (row, col + 2)
+    })
+}
+
+/// Extends the witness with the rows of a Keccak permutation of `state`, laid
+/// out as in [CircuitGate::extend_keccak_permutation], and returns the
+/// permuted state
+pub fn extend_keccak_permutation_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    state: [u64; STATE_WORDS],
+) -> [u64; STATE_WORDS] {
+    extend_constants_witness(witness);
+    extend_permutation_witness(witness, state)
+}
+
+/// Extends the witness with the rows of the Keccak-256 hash of `message`,
+/// laid out as in [CircuitGate::extend_keccak256] with
+/// `Keccak::num_blocks(message.len())` blocks, and returns the digest
+pub fn extend_keccak256_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    message: &[u8],
+) -> [u8; 32] {
+    extend_constants_witness(witness);
+    let mut state = [0; STATE_WORDS];
+    for block in Keccak::pad(message).chunks(RATE_IN_BYTES) {
+        for (word, bytes) in state.iter_mut().zip(block.chunks(8)) {
+            *word = extend_xor_witness_u64(
+                witness,
+                *word,
+                u64::from_le_bytes(bytes.try_into().unwrap()),
+            );
+        }
+        state = extend_permutation_witness(witness, state);
+    }
+    let mut digest = [0; 32];
+    for (bytes, word) in digest.chunks_mut(8).zip(state) {
+        bytes.copy_from_slice(&word.to_le_bytes());
+    }
+    digest
+}
+
+fn extend_constants_witness<F: PrimeField>(witness: &mut [Vec<F>; COLUMNS]) {
+    extend_constant_witness(witness, 0);
+    extend_constant_witness(witness, u64::MAX);
+}
+
+fn extend_constant_witness<F: PrimeField>(witness: &mut [Vec<F>; COLUMNS], value: u64) {
+    witness[0].push(F::from(value));
+    for col in witness.iter_mut().skip(1) {
+        col.push(F::zero());
+    }
+}
+
+fn extend_permutation_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    mut state: [u64; STATE_WORDS],
+) -> [u64; STATE_WORDS] {
+    for round in 0..ROUNDS {
+        state = extend_round_witness(witness, state, round);
+    }
+    state
+}
+
+fn extend_round_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    state: [u64; STATE_WORDS],
+    round: usize,
+) -> [u64; STATE_WORDS] {
+    extend_constant_witness(witness, RC[round]);
+
+    // θ
+    let c: [u64; DIM] = array::from_fn(|x| {
+        (1..DIM).fold(state[x], |c, y| {
+            extend_xor_witness_u64(witness, c, state[x + DIM * y])
+        })
+    });
+    let d: [u64; DIM] = array::from_fn(|x| {
+        let rot_c = extend_rot_witness(witness, c[(x + 1) % DIM], 1);
+        extend_xor_witness_u64(witness, c[(x + DIM - 1) % DIM], rot_c)
+    });
+    let e: [u64; STATE_WORDS] =
+        array::from_fn(|i| extend_xor_witness_u64(witness, state[i], d[i % DIM]));
+
+    // ρ and π
+    let mut b = [0; STATE_WORDS];
+    for (i, word) in e.iter().enumerate() {
+        let (x, y) = (i % DIM, i / DIM);
+        b[y + DIM * ((2 * x + 3 * y) % DIM)] = match OFF[y][x] {
+            0 => *word,
+            offset => extend_rot_witness(witness, *word, offset as u32),
+        };
+    }
+
+    // χ
+    extend_not_witness_unchecked_length(witness, &b.map(F::from), 64)
+        .expect("the words of the state are 64-bit words");
+    let mut f: [u64; STATE_WORDS] = array::from_fn(|i| {
+        let (x, y) = (i % DIM, i / DIM);
+        let not_b = !b[(x + 1) % DIM + DIM * y];
+        let and = b[(x + 2) % DIM + DIM * y];
+        extend_and_witness(witness, F::from(not_b), F::from(and), 8);
+        extend_xor_witness_u64(witness, b[i], not_b & and)
+    });
+
+    // ι
+    f[0] = extend_xor_witness_u64(witness, f[0], RC[round]);
+    f
+}
+
+fn extend_xor_witness_u64<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    left: u64,
+    right: u64,
+) -> u64 {
+    extend_xor_witness(witness, F::from(left), F::from(right), 64);
+    left ^ right
+}
+
+fn extend_rot_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    word: u64,
+    offset: u32,
+) -> u64 {
+    rot::extend_rot(witness, word, offset, RotMode::Left);
+    word.rotate_left(offset)
+}
@@ -1,5 +1,6 @@
 //! Keccak hash module
 pub mod constants;
+pub mod gadget;
 pub mod witness;
 
 use crate::circuits::expr::constraints::ExprOps;
@@ -1,15 +1,24 @@
 pub mod and;
+pub mod bitwise;
+pub mod cairo_builtins;
 pub mod complete_add;
+pub mod ecdsa;
+pub mod eddsa;
 pub mod endomul_scalar;
 pub mod endosclmul;
+pub mod foreign_curve;
 pub mod foreign_field_add;
+pub mod foreign_field_builder;
 pub mod foreign_field_common;
 pub mod foreign_field_mul;
+pub mod foreign_field_wide;
 pub mod generic;
 pub mod keccak;
+pub mod modexp;
 pub mod not;
 pub mod permutation;
 pub mod poseidon;
+pub mod poseidon2;
 pub mod range_check;
 pub mod rot;
 pub mod turshi;
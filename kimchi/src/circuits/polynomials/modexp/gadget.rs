@@ -0,0 +1,210 @@
+//! This module obtains the gates of the modular exponentiation gadget.
+
+use super::{digits, limb_count, CARRY_OFFSET_BITS, CARRY_SHIFT_BITS};
+use crate::circuits::{
+    gate::CircuitGate,
+    polynomials::{
+        foreign_field_builder::gadget::{Cell, ForeignFieldCircuit},
+        foreign_field_common::KimchiForeignElement,
+    },
+};
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use o1_utils::foreign_field::ForeignFieldHelpers;
+
+/// The cells of the 88-bit limbs of a big integer, from the lowest to the
+/// highest
+pub type BigIntCells = Vec<Cell>;
+
+/// The cells of the inputs and of the output of the modular exponentiation
+/// gadget, see [CircuitGate::extend_modexp]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModExpCells {
+    /// The base
+    pub base: BigIntCells,
+    /// The modulus
+    pub modulus: BigIntCells,
+    /// The result `base^e mod modulus`
+    pub result: BigIntCells,
+}
+
+impl<F: PrimeField> CircuitGate<F> {
+    /// Extends the circuit with the modular exponentiation of a base by the
+    /// fixed `exponent`, modulo a modulus of `bits` bits, with a window of
+    /// `window` bits, see [super] for the algorithm.
+    ///
+    /// Includes:
+    /// - Generic gates accumulating the products of the limbs and checking the
+    ///   carries from one limb to the next
+    /// - the multi-range-checks of the limbs of the integers and of the
+    ///   carries
+    ///
+    /// Output:
+    /// - the cells of the base, of the modulus and of the result, which the
+    ///   rest of the circuit must copy
+    ///
+    /// Panics if `exponent` is smaller than `2`.
+    pub fn extend_modexp(
+        gates: &mut Vec<Self>,
+        bits: usize,
+        exponent: &BigUint,
+        window: usize,
+    ) -> ModExpCells {
+        assert!(*exponent >= BigUint::from(2u32));
+        let count = limb_count(bits);
+        let digits = digits(exponent, window);
+
+        let mut builder = ForeignFieldCircuit::new(gates);
+
+        // Inputs
+        let base = big_int(&mut builder, count);
+        let modulus = big_int(&mut builder, count);
+
+        // Table of the powers of the base
+        let mut table = vec![base.clone()];
+        for _ in 1..*digits.iter().max().unwrap() {
+            let power = mul(&mut builder, table.last().unwrap(), &base, &modulus);
+            table.push(power);
+        }
+
+        // Windows
+        let mut result = table[digits[0] - 1].clone();
+        for digit in &digits[1..] {
+            for _ in 0..window {
+                result = mul(&mut builder, &result, &result, &modulus);
+            }
+            if *digit != 0 {
+                result = mul(&mut builder, &result, &table[digit - 1], &modulus);
+            }
+        }
+
+        // Final check of n - 1 - r
+        let diff = big_int(&mut builder, count);
+        let columns = (0..count)
+            .map(|i| {
+                let constant = if i == 0 { -F::one() } else { F::zero() };
+                let row = builder.generic(
+                    [F::one(), -F::one(), -F::one(), F::zero(), constant],
+                    [F::one(), -F::one(), -F::one(), F::zero(), F::zero()],
+                );
+                builder.connect(
+                    &[modulus[i], result[i], (row, 2), diff[i]],
+                    &[(row, 0), (row, 1), (row, 3), (row, 4)],
+                );
+                (row, 5)
+            })
+            .collect();
+        assert_zero(&mut builder, columns);
+        builder.finish();
+
+        ModExpCells {
+            base,
+            modulus,
+            result,
+        }
+    }
+}
+
+/// Extends the circuit with the multi-range-checks of `count` limbs computed
+/// by the prover
+pub(crate) fn big_int<F: PrimeField>(
+    builder: &mut ForeignFieldCircuit<F>,
+    count: usize,
+) -> BigIntCells {
+    let mut limbs = vec![];
+    while limbs.len() < count {
+        limbs.extend(builder.limbs());
+    }
+    limbs.truncate(count);
+    limbs
+}
+
+// Extends the circuit with the multiplication `left * right = q * n + r`, and
+// returns the cells of the remainder `r`
+fn mul<F: PrimeField>(
+    builder: &mut ForeignFieldCircuit<F>,
+    left: &BigIntCells,
+    right: &BigIntCells,
+    modulus: &BigIntCells,
+) -> BigIntCells {
+    let count = modulus.len();
+    let square = left == right;
+    let quotient = big_int(builder, count);
+    let remainder = big_int(builder, count);
+    let zero = builder.zero();
+    let columns = (0..2 * count - 1)
+        .map(|i| {
+            let mut acc = zero;
+            // The limbs of weight 2^(88 * i) are the pairs (j, i - j)
+            let terms = i.saturating_sub(count - 1)..=i.min(count - 1);
+            for j in terms.clone() {
+                let l = i - j;
+                if !square || j <= l {
+                    let coeff = if square && j < l {
+                        F::from(2u32)
+                    } else {
+                        F::one()
+                    };
+                    acc = accumulate(builder, acc, left[j], right[l], coeff);
+                }
+            }
+            for j in terms {
+                acc = accumulate(builder, acc, quotient[j], modulus[i - j], -F::one());
+            }
+            if i < count {
+                let row = builder.generic(
+                    [F::one(), -F::one(), -F::one(), F::zero(), F::zero()],
+                    [F::zero(); 5],
+                );
+                builder.connect(&[acc, remainder[i]], &[(row, 0), (row, 1)]);
+                acc = (row, 2);
+            }
+            acc
+        })
+        .collect();
+    assert_zero(builder, columns);
+    remainder
+}
+
+/// Extends the circuit with a generic gate computing `acc + coeff * x * y`
+pub(crate) fn accumulate<F: PrimeField>(
+    builder: &mut ForeignFieldCircuit<F>,
+    acc: Cell,
+    x: Cell,
+    y: Cell,
+    coeff: F,
+) -> Cell {
+    // x * y = prod, acc + coeff * prod = out
+    let row = builder.generic(
+        [F::zero(), F::zero(), -F::one(), F::one(), F::zero()],
+        [F::one(), coeff, -F::one(), F::zero(), F::zero()],
+    );
+    builder.connect(
+        &[x, y, acc, (row, 2)],
+        &[(row, 0), (row, 1), (row, 3), (row, 4)],
+    );
+    (row, 5)
+}
+
+/// Extends the circuit with the check that the integer of limbs `columns` is
+/// zero, with one generic gate per column computing the carry to the next one:
+/// `column + carry_in = 2^88 * carry_out` and
+/// `carry_out + 2^96 = lo + 2^10 * hi`, where `lo` and `hi` are range-checked
+pub(crate) fn assert_zero<F: PrimeField>(builder: &mut ForeignFieldCircuit<F>, columns: Vec<Cell>) {
+    let two_to_limb = KimchiForeignElement::<F>::two_to_limb();
+    let offset = F::from(2u64).pow([CARRY_OFFSET_BITS as u64]);
+    let shift = F::from(2u64).pow([CARRY_SHIFT_BITS as u64]);
+    let mut carry = builder.zero();
+    for column in columns {
+        let row = builder.generic(
+            [F::one(), F::one(), -two_to_limb, F::zero(), F::zero()],
+            [F::one(), shift, -F::one(), F::zero(), -offset],
+        );
+        builder.connect(&[column, carry, (row, 2)], &[(row, 0), (row, 1), (row, 5)]);
+        builder.range_check((row, 3));
+        builder.range_check((row, 4));
+        carry = (row, 2);
+    }
+    let zero = builder.zero();
+    builder.connect(&[carry], &[zero]);
+}
@@ -0,0 +1,77 @@
+//! Modular exponentiation of big integers
+//!
+//! The gadget computes `base^e mod n` for a fixed public exponent `e`, such as
+//! the exponent [RSA_EXPONENT] of RSA signatures, and a modulus `n` of 2048 or
+//! 4096 bits given as an input. The integers are held in `k` limbs of 88 bits,
+//! with `88 * k` at least the number of bits of the modulus, each of them being
+//! range-checked by the [range check](super::range_check) gates.
+//!
+//! The foreign field gates only handle moduli of at most 259 bits, so that the
+//! product `a * b = q * n + r` of two integers is checked with generic gates,
+//! one row per product of limbs:
+//!
+//! * the column `c_i = Σ a_j * b_(i - j) - Σ q_j * n_(i - j) - r_i` of the
+//!   limbs of weight `2^(88 * i)` is accumulated over the native field, its
+//!   absolute value being smaller than `2^183`,
+//! * the carry `c_i + carry_(i - 1) = carry_i * 2^88` from one column to the
+//!   next is computed by the prover, and range-checked to be between `-2^96`
+//!   and `2^98`, so that no equation overflows the native field,
+//! * the last carry is `0`, so that `Σ c_i * 2^(88 * i) = 0` over the
+//!   integers.
+//!
+//! A squaring only accumulates the products `a_j * a_l` with `j <= l`,
+//! doubling the ones with `j < l`. The exponentiation uses a fixed window of
+//! `w` bits: the table of `base^d` for the digits `d` of `e` in base `2^w` is
+//! computed first, then each digit squares the result `w` times and multiplies
+//! it by an entry of the table. The exponent `65537` is best computed with a
+//! window of 1 bit, with 16 squarings and 1 multiplication. The result is
+//! finally checked to be smaller than the modulus, with the same carries over
+//! the limbs of `n - 1 - r`. The exponentiation by `65537` takes 19805 rows
+//! for a 2048-bit modulus, and 66998 rows for a 4096-bit modulus.
+//!
+//! The gates are created by [CircuitGate::extend_modexp](crate::circuits::gate::CircuitGate::extend_modexp)
+//! and the witness by [witness::extend_modexp_witness], which lay out the rows
+//! in the same order.
+
+pub mod gadget;
+pub mod witness;
+
+use super::foreign_field_common::LIMB_BITS;
+use num_bigint::BigUint;
+
+/// The public exponent `2^16 + 1` of RSA signatures
+pub const RSA_EXPONENT: u32 = 65537;
+
+/// The number of bits of the offset of the carries
+pub const CARRY_OFFSET_BITS: usize = 96;
+
+/// The number of bits of the shift of the high limb of the carries
+pub const CARRY_SHIFT_BITS: usize = 10;
+
+/// The number of 88-bit limbs of the integers modulo a modulus of `bits` bits
+pub fn limb_count(bits: usize) -> usize {
+    bits.div_ceil(LIMB_BITS)
+}
+
+/// The digits of `exponent` in base `2^window`, from the most significant one
+pub fn digits(exponent: &BigUint, window: usize) -> Vec<usize> {
+    assert!((1..usize::BITS as usize).contains(&window));
+    let count = (exponent.bits() as usize).div_ceil(window);
+    (0..count)
+        .rev()
+        .map(|i| {
+            (0..window).fold(0, |digit, j| {
+                digit | (usize::from(exponent.bit((i * window + j) as u64)) << j)
+            })
+        })
+        .collect()
+}
+
+/// The `count` limbs of 88 bits of `value`, from the lowest to the highest
+pub fn to_limbs(value: &BigUint, count: usize) -> Vec<BigUint> {
+    assert!(value.bits() as usize <= count * LIMB_BITS);
+    let mask = (BigUint::from(1u32) << LIMB_BITS) - 1u32;
+    (0..count)
+        .map(|i| (value >> (i * LIMB_BITS)) & &mask)
+        .collect()
+}
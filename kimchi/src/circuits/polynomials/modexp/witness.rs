@@ -0,0 +1,222 @@
+//! This module computes the witness of the modular exponentiation gadget.
+
+use super::{digits, limb_count, to_limbs, CARRY_OFFSET_BITS, CARRY_SHIFT_BITS};
+use crate::circuits::{
+    polynomial::COLUMNS,
+    polynomials::{
+        foreign_field_builder::witness::ForeignFieldWitness, foreign_field_common::LIMB_BITS,
+    },
+};
+use ark_ff::PrimeField;
+use num_bigint::{BigInt, BigUint, Sign};
+use num_integer::Integer;
+
+/// Extends the witness with the modular exponentiation of `base` by the fixed
+/// `exponent` modulo `modulus`, laid out as the gates of
+/// [CircuitGate::extend_modexp](crate::circuits::gate::CircuitGate::extend_modexp)
+/// for the same `bits` and `window`, and returns the result.
+pub fn extend_modexp_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    bits: usize,
+    exponent: &BigUint,
+    window: usize,
+    base: &BigUint,
+    modulus: &BigUint,
+) -> BigUint {
+    let count = limb_count(bits);
+    let digits = digits(exponent, window);
+
+    let mut builder = ForeignFieldWitness::new(witness);
+
+    // Inputs
+    big_int(&mut builder, base, count);
+    big_int(&mut builder, modulus, count);
+
+    // Table of the powers of the base
+    let mut table = vec![base.clone()];
+    for _ in 1..*digits.iter().max().unwrap() {
+        // The first power squares the base
+        let square = table.len() == 1;
+        let power = extend_mul_witness(
+            &mut builder,
+            table.last().unwrap(),
+            base,
+            modulus,
+            count,
+            square,
+        );
+        table.push(power);
+    }
+
+    // Windows
+    let mut result = table[digits[0] - 1].clone();
+    for digit in &digits[1..] {
+        for _ in 0..window {
+            result = extend_mul_witness(&mut builder, &result, &result, modulus, count, true);
+        }
+        if *digit != 0 {
+            result = extend_mul_witness(
+                &mut builder,
+                &result,
+                &table[digit - 1],
+                modulus,
+                count,
+                false,
+            );
+        }
+    }
+
+    // Final check of n - 1 - r
+    let diff = modulus - 1u32 - &result;
+    big_int(&mut builder, &diff, count);
+    let (modulus_limbs, result_limbs) = (to_limbs(modulus, count), to_limbs(&result, count));
+    let diff_limbs = to_limbs(&diff, count);
+    let columns = (0..count)
+        .map(|i| {
+            let constant = if i == 0 { 1u32 } else { 0u32 };
+            let t = BigInt::from(modulus_limbs[i].clone())
+                - BigInt::from(result_limbs[i].clone())
+                - constant;
+            let column = &t - BigInt::from(diff_limbs[i].clone());
+            builder.row(&[
+                F::from(modulus_limbs[i].clone()),
+                F::from(result_limbs[i].clone()),
+                field(&t),
+                field(&t),
+                F::from(diff_limbs[i].clone()),
+                field(&column),
+            ]);
+            column
+        })
+        .collect();
+    assert_zero(&mut builder, columns);
+    builder.finish();
+
+    result
+}
+
+/// Converts a signed integer to the native field
+pub(crate) fn field<F: PrimeField>(value: &BigInt) -> F {
+    let abs = F::from(value.magnitude().clone());
+    if value.sign() == Sign::Minus {
+        -abs
+    } else {
+        abs
+    }
+}
+
+/// Extends the witness with the multi-range-checks of the `count` limbs of
+/// `value`, laid out as [super::gadget::big_int]
+pub(crate) fn big_int<F: PrimeField>(
+    builder: &mut ForeignFieldWitness<F>,
+    value: &BigUint,
+    count: usize,
+) {
+    let limbs = to_limbs(value, count.next_multiple_of(3));
+    for chunk in limbs.chunks(3) {
+        let limb = chunk
+            .iter()
+            .rev()
+            .fold(BigUint::from(0u32), |acc, limb| (acc << LIMB_BITS) + limb);
+        builder.limbs(&limb);
+    }
+}
+
+fn extend_mul_witness<F: PrimeField>(
+    builder: &mut ForeignFieldWitness<F>,
+    left: &BigUint,
+    right: &BigUint,
+    modulus: &BigUint,
+    count: usize,
+    square: bool,
+) -> BigUint {
+    let (quotient, remainder) = (left * right).div_rem(modulus);
+    big_int(builder, &quotient, count);
+    big_int(builder, &remainder, count);
+    let left = to_limbs(left, count);
+    let right = to_limbs(right, count);
+    let quotient = to_limbs(&quotient, count);
+    let modulus = to_limbs(modulus, count);
+    let remainder = to_limbs(&remainder, count);
+    let columns = (0..2 * count - 1)
+        .map(|i| {
+            let mut acc = BigInt::from(0);
+            let terms = i.saturating_sub(count - 1)..=i.min(count - 1);
+            for j in terms.clone() {
+                let l = i - j;
+                if !square || j <= l {
+                    let coeff = if square && j < l { 2 } else { 1 };
+                    acc = accumulate(builder, acc, &left[j], &right[l], coeff);
+                }
+            }
+            for j in terms {
+                acc = accumulate(builder, acc, &quotient[j], &modulus[i - j], -1);
+            }
+            if i < count {
+                let out = &acc - BigInt::from(remainder[i].clone());
+                builder.row(&[field(&acc), F::from(remainder[i].clone()), field(&out)]);
+                acc = out;
+            }
+            acc
+        })
+        .collect();
+    assert_zero(builder, columns);
+    remainder
+        .iter()
+        .enumerate()
+        .fold(BigUint::from(0u32), |acc, (i, limb)| {
+            acc + (limb << (i * LIMB_BITS))
+        })
+}
+
+/// Extends the witness with the generic gate computing `acc + coeff * x * y`,
+/// laid out as [super::gadget::accumulate], and returns the result
+pub(crate) fn accumulate<F: PrimeField>(
+    builder: &mut ForeignFieldWitness<F>,
+    acc: BigInt,
+    x: &BigUint,
+    y: &BigUint,
+    coeff: i32,
+) -> BigInt {
+    let prod = BigInt::from(x * y);
+    let out = &acc + coeff * &prod;
+    builder.row(&[
+        F::from(x.clone()),
+        F::from(y.clone()),
+        field(&prod),
+        field(&acc),
+        field(&prod),
+        field(&out),
+    ]);
+    out
+}
+
+/// Extends the witness with the carries of the check that the integer of
+/// limbs `columns` is zero, laid out as [super::gadget::assert_zero]
+pub(crate) fn assert_zero<F: PrimeField>(
+    builder: &mut ForeignFieldWitness<F>,
+    columns: Vec<BigInt>,
+) {
+    let mut carry = BigInt::from(0);
+    for column in columns {
+        let (next, rest) = (&column + &carry).div_rem(&(BigInt::from(1) << LIMB_BITS));
+        assert_eq!(rest, BigInt::from(0));
+        let shifted = (&next + (BigInt::from(1) << CARRY_OFFSET_BITS))
+            .to_biguint()
+            .unwrap();
+        let lo = &shifted % (BigUint::from(1u32) << LIMB_BITS);
+        let hi = (&shifted - &lo) >> CARRY_SHIFT_BITS;
+        builder.row(&[
+            field(&column),
+            field(&carry),
+            field(&next),
+            F::from(lo.clone()),
+            F::from(hi.clone()),
+            field(&next),
+        ]);
+        builder.range_check(F::from(lo));
+        builder.range_check(F::from(hi));
+        carry = next;
+    }
+    assert_eq!(carry, BigInt::from(0));
+}
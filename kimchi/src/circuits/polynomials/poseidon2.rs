@@ -0,0 +1,344 @@
+//! This module implements the Poseidon2 gates, an alternative to the
+//! [Poseidon](super::poseidon) gate for the permutation of
+//! [mina_poseidon::poseidon2], which needs about half of its rows.
+//!
+//! A permutation is laid out on 7 rows: one `Poseidon2External` row for the
+//! first 4 full rounds, 4 `Poseidon2Internal` rows of 12 partial rounds each,
+//! one `Poseidon2External` row for the last 4 full rounds, and a row (usually a
+//! `Zero` gate) holding the output in its first 3 registers. Each row takes its
+//! input state in its first 3 registers, and writes its output state in the
+//! first 3 registers of the next row.
+//!
+//! A `Poseidon2External` row stores the states `s1` to `s3` after each of its
+//! first 3 rounds, and the input `s0'` of its first round. This input is the
+//! input state `s0` multiplied by the external matrix for the first row of the
+//! permutation, flagged by its coefficient 12, and `s0` otherwise.
+//!
+//! |  0 |  1 |  2 |  3 |  4 |  5 |  6 |  7 |  8 |  9 | 10 | 11 |  12 |  13 |  14 |
+//! |:--:|:--:|:--:|:--:|:--:|:--:|:--:|:--:|:--:|:--:|:--:|:--:|:---:|:---:|:---:|
+//! | s0 | s0 | s0 | s1 | s1 | s1 | s2 | s2 | s2 | s3 | s3 | s3 | s0' | s0' | s0' |
+//!
+//! Its first 12 coefficients are the round constants of its 4 rounds.
+//!
+//! A `Poseidon2Internal` row stores the outputs `x0` to `x11` of the S-boxes
+//! of its 12 rounds. The states between its rounds are linear combinations of
+//! these outputs and of the input state, which are not stored.
+//!
+//! |  0 |  1 |  2 |  3 |  4 | ... | 14  |
+//! |:--:|:--:|:--:|:--:|:--:|:---:|:---:|
+//! | s0 | s0 | s0 | x0 | x1 | ... | x11 |
+//!
+//! Its first 12 coefficients are the round constants of its 12 rounds.
+
+use crate::{
+    alphas::Alphas,
+    circuits::{
+        argument::{Argument, ArgumentEnv, ArgumentType},
+        berkeley_columns::{BerkeleyChallengeTerm, E},
+        expr::{constraints::ExprOps, Cache},
+        gate::{CircuitGate, GateType},
+        polynomial::COLUMNS,
+        wires::{GateWires, Wire},
+    },
+};
+use ark_ff::{Field, PrimeField};
+use core::{array, marker::PhantomData};
+use mina_poseidon::poseidon2::{
+    self, Poseidon2Params, HALF_FULL_ROUNDS, INTERNAL_DIAGONAL, PARTIAL_ROUNDS, SBOX, WIDTH,
+};
+
+/// Number of partial rounds of a `Poseidon2Internal` row
+pub const PARTIAL_ROUNDS_PER_ROW: usize = COLUMNS - WIDTH;
+
+/// Number of `Poseidon2Internal` rows of a permutation
+pub const INTERNAL_ROWS: usize = PARTIAL_ROUNDS / PARTIAL_ROUNDS_PER_ROW;
+
+/// Number of rows of the gates of a permutation, without its output row
+pub const ROWS_PER_HASH: usize = INTERNAL_ROWS + 2;
+
+/// Index of the coefficient flagging the initial external matrix
+const INITIAL_MATRIX_COEFF: usize = HALF_FULL_ROUNDS * WIDTH;
+
+/// Number of Poseidon2 gate types
+pub const CIRCUIT_GATE_COUNT: usize = 2;
+
+/// Get vector of Poseidon2 circuit gate types
+pub fn circuit_gates() -> [GateType; CIRCUIT_GATE_COUNT] {
+    [GateType::Poseidon2External, GateType::Poseidon2Internal]
+}
+
+/// Get combined constraints for a given Poseidon2 circuit gate
+pub fn circuit_gate_combined_constraints<F: PrimeField>(
+    typ: GateType,
+    alphas: &Alphas<F>,
+    cache: &mut Cache,
+) -> E<F> {
+    match typ {
+        GateType::Poseidon2External => Poseidon2External::combined_constraints(alphas, cache),
+        GateType::Poseidon2Internal => Poseidon2Internal::combined_constraints(alphas, cache),
+        GateType::Zero => E::literal(F::zero()),
+        _ => panic!("invalid gate type"),
+    }
+}
+
+/// Columns of the state after round `round` of a `Poseidon2External` row,
+/// the last one being in the next row.
+const fn external_state_cols(round: usize) -> [usize; WIDTH] {
+    match round {
+        0 => [12, 13, 14],
+        1 => [3, 4, 5],
+        2 => [6, 7, 8],
+        3 => [9, 10, 11],
+        _ => [0, 1, 2],
+    }
+}
+
+impl<F: PrimeField> CircuitGate<F> {
+    /// Creates a `Poseidon2External` gate for the full rounds of round
+    /// constants `round_constants`, applying the external matrix to its input
+    /// first if `initial_matrix` is set.
+    pub fn create_poseidon2_external(
+        wires: GateWires,
+        round_constants: &[[F; WIDTH]],
+        initial_matrix: bool,
+    ) -> Self {
+        assert_eq!(round_constants.len(), HALF_FULL_ROUNDS);
+        let mut coeffs: Vec<F> = round_constants.iter().flatten().copied().collect();
+        coeffs.push(F::from(initial_matrix));
+        CircuitGate::new(GateType::Poseidon2External, wires, coeffs)
+    }
+
+    /// Creates a `Poseidon2Internal` gate for the partial rounds of round
+    /// constants `round_constants`.
+    pub fn create_poseidon2_internal(wires: GateWires, round_constants: &[F]) -> Self {
+        assert_eq!(round_constants.len(), PARTIAL_ROUNDS_PER_ROW);
+        CircuitGate::new(GateType::Poseidon2Internal, wires, round_constants.to_vec())
+    }
+
+    /// `create_poseidon2_gadget(row, first_and_last_row, params)` creates the
+    /// gates of a Poseidon2 permutation of round constants `params`, starting
+    /// at the absolute row `row`, and wired to other gates of the circuit by
+    /// the wires of its first row (holding the input) and of its last row
+    /// (holding the output).
+    ///
+    /// The function returns the gates, and the row of the output of the
+    /// permutation, which is the last of the gates.
+    pub fn create_poseidon2_gadget(
+        row: usize,
+        first_and_last_row: [GateWires; 2],
+        params: &Poseidon2Params<F>,
+    ) -> (Vec<Self>, usize) {
+        let (first_rounds, last_rounds) =
+            params.external_round_constants.split_at(HALF_FULL_ROUNDS);
+
+        let mut gates = vec![CircuitGate::create_poseidon2_external(
+            first_and_last_row[0],
+            first_rounds,
+            true,
+        )];
+        for (i, round_constants) in params
+            .internal_round_constants
+            .chunks(PARTIAL_ROUNDS_PER_ROW)
+            .enumerate()
+        {
+            gates.push(CircuitGate::create_poseidon2_internal(
+                Wire::for_row(row + 1 + i),
+                round_constants,
+            ));
+        }
+        gates.push(CircuitGate::create_poseidon2_external(
+            Wire::for_row(row + ROWS_PER_HASH - 1),
+            last_rounds,
+            false,
+        ));
+
+        // final (zero) gate that contains the output of the permutation
+        gates.push(CircuitGate::zero(first_and_last_row[1]));
+
+        (gates, row + ROWS_PER_HASH)
+    }
+}
+
+/// `generate_witness(row, params, witness_cols, input)` writes the witness of
+/// the Poseidon2 permutation of `input` with the round constants `params`, for
+/// the gates of [CircuitGate::create_poseidon2_gadget] starting at row `row`.
+pub fn generate_witness<F: Field>(
+    row: usize,
+    params: &Poseidon2Params<F>,
+    witness_cols: &mut [Vec<F>; COLUMNS],
+    input: [F; WIDTH],
+) {
+    let (first_rounds, last_rounds) = params.external_round_constants.split_at(HALF_FULL_ROUNDS);
+    let mut state = input;
+
+    for (col, s) in input.iter().enumerate() {
+        witness_cols[col][row] = *s;
+    }
+    poseidon2::apply_external_matrix(&mut state);
+    external_row_witness(witness_cols, row, &mut state, first_rounds);
+
+    for (i, round_constants) in params
+        .internal_round_constants
+        .chunks(PARTIAL_ROUNDS_PER_ROW)
+        .enumerate()
+    {
+        let row = row + 1 + i;
+        for (col, s) in state.iter().enumerate() {
+            witness_cols[col][row] = *s;
+        }
+        for (k, round_constant) in round_constants.iter().enumerate() {
+            state[0] = poseidon2::sbox(state[0] + round_constant);
+            witness_cols[WIDTH + k][row] = state[0];
+            poseidon2::apply_internal_matrix(&mut state);
+        }
+    }
+
+    let row = row + ROWS_PER_HASH - 1;
+    for (col, s) in state.iter().enumerate() {
+        witness_cols[col][row] = *s;
+    }
+    external_row_witness(witness_cols, row, &mut state, last_rounds);
+
+    for (col, s) in state.iter().enumerate() {
+        witness_cols[col][row + 1] = *s;
+    }
+}
+
+/// Writes the states of the full rounds of round constants `round_constants`
+/// applied to `state` in the `Poseidon2External` row `row`, but the last one.
+fn external_row_witness<F: Field>(
+    witness_cols: &mut [Vec<F>; COLUMNS],
+    row: usize,
+    state: &mut [F; WIDTH],
+    round_constants: &[[F; WIDTH]],
+) {
+    for (round, round_constants) in round_constants.iter().enumerate() {
+        for (col, s) in external_state_cols(round).into_iter().zip(state.iter()) {
+            witness_cols[col][row] = *s;
+        }
+        poseidon2::full_round(state, round_constants);
+    }
+}
+
+/// Implementation of the `Poseidon2External` gate, for 4 full rounds
+/// `s <- M_E (s + c)^7` where `M_E = circ(2, 1, 1)`. Conjunction of:
+///
+/// ```ignore
+/// s0'[i] = s0[i] + coeff[12] * (s0[0] + s0[1] + s0[2])
+/// s1 = round(s0', coeff[0..3])
+/// s2 = round(s1, coeff[3..6])
+/// s3 = round(s2, coeff[6..9])
+/// next[0..3] = round(s3, coeff[9..12])
+/// ```
+#[derive(Default)]
+pub struct Poseidon2External<F>(PhantomData<F>);
+
+impl<F> Argument<F> for Poseidon2External<F>
+where
+    F: PrimeField,
+{
+    const ARGUMENT_TYPE: ArgumentType = ArgumentType::Gate(GateType::Poseidon2External);
+    const CONSTRAINTS: u32 = 15;
+
+    fn constraint_checks<T: ExprOps<F, BerkeleyChallengeTerm>>(
+        env: &ArgumentEnv<F, T>,
+        cache: &mut Cache,
+    ) -> Vec<T> {
+        let mut constraints = vec![];
+
+        // The optional initial external matrix, which is linear in the input
+        let input: [T; WIDTH] = array::from_fn(|i| env.witness_curr(i));
+        let input_sum = input.iter().fold(T::zero(), |acc, s| acc + s.clone());
+        for (i, col) in external_state_cols(0).into_iter().enumerate() {
+            constraints.push(
+                env.witness_curr(col)
+                    - (input[i].clone() + env.coeff(INITIAL_MATRIX_COEFF) * input_sum.clone()),
+            );
+        }
+
+        for round in 0..HALF_FULL_ROUNDS {
+            let sboxed: [T; WIDTH] = array::from_fn(|i| {
+                let s = env.witness_curr(external_state_cols(round)[i]);
+                cache.cache((s + env.coeff(round * WIDTH + i)).pow(SBOX))
+            });
+            let sum = sboxed.iter().fold(T::zero(), |acc, x| acc + x.clone());
+            for (i, col) in external_state_cols(round + 1).into_iter().enumerate() {
+                let output = if round + 1 == HALF_FULL_ROUNDS {
+                    env.witness_next(col)
+                } else {
+                    env.witness_curr(col)
+                };
+                constraints.push(output - (sboxed[i].clone() + sum.clone()));
+            }
+        }
+
+        constraints
+    }
+}
+
+/// Implementation of the `Poseidon2Internal` gate, for 12 partial rounds
+/// `s[0] <- (s[0] + c)^7; s <- M_I s` where `M_I = J + diag(1, 1, 2)`.
+///
+/// The state before each round is tracked as a linear combination, with small
+/// integer coefficients, of the input state and of the outputs of the previous
+/// S-boxes. Conjunction of:
+///
+/// ```ignore
+/// x_k = (state_k[0] + coeff[k])^7, for k in 0..12
+/// next[0..3] = state_12
+/// ```
+#[derive(Default)]
+pub struct Poseidon2Internal<F>(PhantomData<F>);
+
+impl<F> Argument<F> for Poseidon2Internal<F>
+where
+    F: PrimeField,
+{
+    const ARGUMENT_TYPE: ArgumentType = ArgumentType::Gate(GateType::Poseidon2Internal);
+    const CONSTRAINTS: u32 = 15;
+
+    fn constraint_checks<T: ExprOps<F, BerkeleyChallengeTerm>>(
+        env: &ArgumentEnv<F, T>,
+        _cache: &mut Cache,
+    ) -> Vec<T> {
+        let linear_combination = |coeffs: &[u64; COLUMNS]| {
+            coeffs
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| **c != 0)
+                .fold(T::zero(), |acc, (col, c)| {
+                    acc + T::from(*c) * env.witness_curr(col)
+                })
+        };
+
+        // The coefficients of the state over the registers of the row
+        let mut state: [[u64; COLUMNS]; WIDTH] = array::from_fn(|i| {
+            let mut coeffs = [0; COLUMNS];
+            coeffs[i] = 1;
+            coeffs
+        });
+
+        let mut constraints = vec![];
+        for k in 0..PARTIAL_ROUNDS_PER_ROW {
+            let sbox_col = WIDTH + k;
+            constraints.push(
+                env.witness_curr(sbox_col)
+                    - (linear_combination(&state[0]) + env.coeff(k)).pow(SBOX),
+            );
+
+            state[0] = [0; COLUMNS];
+            state[0][sbox_col] = 1;
+            let sum: [u64; COLUMNS] =
+                array::from_fn(|col| state.iter().map(|s| s[col]).sum::<u64>());
+            for (s, d) in state.iter_mut().zip(INTERNAL_DIAGONAL) {
+                *s = array::from_fn(|col| sum[col] + d * s[col]);
+            }
+        }
+
+        for (i, coeffs) in state.iter().enumerate() {
+            constraints.push(env.witness_next(i) - linear_combination(coeffs));
+        }
+
+        constraints
+    }
+}
@@ -13,6 +13,7 @@ use crate::{
             self,
             tables::{GateLookupTable, LookupTable},
         },
+        polynomials::{foreign_field_common::LIMB_BITS, generic::GENERIC_COEFFS},
         wires::Wire,
     },
 };
@@ -75,6 +76,117 @@ impl<F: PrimeField> CircuitGate<F> {
         gates.extend_from_slice(&circuit_gates);
     }
 
+    /// Create range check gadget for constraining a value to `bits` bits, for any
+    /// `bits` smaller than the size of the field.
+    ///     Inputs the starting row and the number of bits
+    ///     Outputs tuple (`next_row`, `circuit_gates`) where
+    ///       `next_row`      - next row after this gadget
+    ///       `circuit_gates` - vector of circuit gates comprising this gadget
+    ///
+    /// The value is in the first cell of the starting row. It is split into
+    /// `k = ceil(bits / 88)` limbs of 88 bits by generic gates, and the highest
+    /// limb `vh` is bounded to its remaining `bits - 88 * (k - 1)` bits by the
+    /// range check of `2^(88 * k - bits) * vh`. The limbs and the scaled limb are
+    /// constrained by multi-range-checks, that is by 12-bit lookups and 2-bit
+    /// crumbs.
+    ///
+    /// | `k` | Rows | Gates                                             |
+    /// | --- | ---- | ------------------------------------------------- |
+    /// |   1 |  4-5 | `Generic` (if `bits < 88`), multi-range-check     |
+    /// |   2 |    5 | `Generic`, multi-range-check                      |
+    /// |   3 |   10 | `Generic`, `Generic`, multi-range-check (x2)      |
+    ///
+    /// Panics if `bits` is zero or not smaller than the size of the field.
+    pub fn create_range_check_bits(start_row: usize, bits: usize) -> (usize, Vec<Self>) {
+        assert!(bits > 0 && bits < F::MODULUS_BIT_SIZE as usize);
+        let limbs = bits.div_ceil(LIMB_BITS);
+        let shift = F::from(2u64).pow([(limbs * LIMB_BITS - bits) as u64]);
+        let two_to_limb = F::from(2u64).pow([LIMB_BITS as u64]);
+        let generic = |coeffs: &[F]| {
+            let mut c = [F::zero(); GENERIC_COEFFS * 2];
+            c[..coeffs.len()].copy_from_slice(coeffs);
+            c
+        };
+
+        // Generic gates splitting the value into limbs and scaling the highest limb
+        //   k = 1: 2^s * v - vs = 0
+        //   k = 2: v - 2^88 * v1 - v0 = 0,  2^s * v1 - vs = 0
+        //   k = 3: v - 2^176 * v2 - v01 = 0, v0 + 2^88 * v1 - v01 = 0
+        //          2^s * v2 - vs = 0
+        let (coeffs, checked) = match limbs {
+            1 if bits == LIMB_BITS => (vec![], vec![]),
+            1 => (
+                vec![generic(&[shift, F::zero(), -F::one()])],
+                vec![(0, 0), (0, 2)],
+            ),
+            2 => (
+                vec![generic(&[
+                    F::one(),
+                    -two_to_limb,
+                    -F::one(),
+                    F::zero(),
+                    F::zero(),
+                    shift,
+                    F::zero(),
+                    -F::one(),
+                ])],
+                vec![(0, 2), (0, 1), (0, 5)],
+            ),
+            _ => (
+                vec![
+                    generic(&[
+                        F::one(),
+                        -two_to_limb.square(),
+                        -F::one(),
+                        F::zero(),
+                        F::zero(),
+                        F::one(),
+                        two_to_limb,
+                        -F::one(),
+                    ]),
+                    generic(&[shift, F::zero(), -F::one()]),
+                ],
+                vec![(0, 3), (0, 4), (0, 1), (1, 2)],
+            ),
+        };
+        let mut circuit_gates: Vec<Self> = coeffs
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| CircuitGate::create_generic(Wire::for_row(start_row + i), c))
+            .collect();
+        match limbs {
+            2 => circuit_gates.connect_cell_pair((0, 1), (0, 3)),
+            3 => {
+                circuit_gates.connect_cell_pair((0, 2), (0, 5));
+                circuit_gates.connect_cell_pair((0, 1), (1, 0));
+            }
+            _ => (),
+        }
+
+        // Multi-range-checks of the limbs and of the scaled limb, the unused
+        // values being free
+        let checks = checked.len().max(1).div_ceil(3);
+        for _ in 0..checks {
+            let row = circuit_gates.len();
+            circuit_gates.extend(Self::create_multi_range_check(start_row + row).1);
+        }
+        let first_check = circuit_gates.len() - 4 * checks;
+        for (i, cell) in checked.into_iter().enumerate() {
+            let row = first_check + 4 * (i / 3) + i % 3;
+            circuit_gates.connect_cell_pair(cell, (row, 0));
+        }
+
+        (start_row + circuit_gates.len(), circuit_gates)
+    }
+
+    /// Create range check gadget for constraining a value to `bits` bits by
+    /// extending the existing gates, see [Self::create_range_check_bits]
+    pub fn extend_range_check_bits(gates: &mut Vec<Self>, curr_row: &mut usize, bits: usize) {
+        let (next_row, circuit_gates) = Self::create_range_check_bits(*curr_row, bits);
+        *curr_row = next_row;
+        gates.extend_from_slice(&circuit_gates);
+    }
+
     // Create range check gate for constraining three 88-bit values.
     //     Inputs the starting row and whether the limbs are in compact format
     //     Outputs tuple (`next_row`, `circuit_gates`) where
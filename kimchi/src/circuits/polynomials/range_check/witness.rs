@@ -175,6 +175,93 @@ pub fn create<F: PrimeField>(v0: F) -> [Vec<F>; COLUMNS] {
     witness
 }
 
+/// Create a range check witness for a value of `bits` bits, laid out as the gates of
+/// [CircuitGate::create_range_check_bits](crate::circuits::gate::CircuitGate::create_range_check_bits)
+pub fn create_bits<F: PrimeField>(value: F, bits: usize) -> [Vec<F>; COLUMNS] {
+    assert!(bits > 0 && bits < F::MODULUS_BIT_SIZE as usize);
+    let limbs = bits.div_ceil(LIMB_BITS);
+    let shift: F = (BigUint::from(1u32) << (limbs * LIMB_BITS - bits))
+        .to_field()
+        .expect("failed to convert to field element");
+    let value = value.to_biguint();
+    // The highest limb keeps the remaining bits, so that the witness of a value out
+    // of range does not satisfy the constraints
+    let limb = |i: usize| -> F {
+        let limb = &value >> (i * LIMB_BITS);
+        let limb = if i + 1 < limbs {
+            limb % BigUint::two_to_limb()
+        } else {
+            limb
+        };
+        limb.to_field().expect("failed to convert to field element")
+    };
+    let scaled = |i: usize| -> F { limb(i) * shift };
+    let two_to_limb = F::from(2u64).pow([LIMB_BITS as u64]);
+    let zero = F::zero();
+
+    // Generic rows and values checked by the multi-range-checks
+    let (rows, checked) = match limbs {
+        1 if bits == LIMB_BITS => (vec![], vec![limb(0)]),
+        1 => (
+            vec![vec![limb(0), zero, scaled(0)]],
+            vec![limb(0), scaled(0)],
+        ),
+        2 => (
+            vec![vec![
+                limb(0) + limb(1) * two_to_limb,
+                limb(1),
+                limb(0),
+                limb(1),
+                zero,
+                scaled(1),
+            ]],
+            vec![limb(0), limb(1), scaled(1)],
+        ),
+        _ => {
+            let low = limb(0) + limb(1) * two_to_limb;
+            (
+                vec![
+                    vec![
+                        low + limb(2) * two_to_limb.square(),
+                        limb(2),
+                        low,
+                        limb(0),
+                        limb(1),
+                        low,
+                    ],
+                    vec![limb(2), zero, scaled(2)],
+                ],
+                vec![limb(0), limb(1), limb(2), scaled(2)],
+            )
+        }
+    };
+
+    let mut witness: [Vec<F>; COLUMNS] = array::from_fn(|_| vec![F::zero(); rows.len()]);
+    for (row, cells) in rows.into_iter().enumerate() {
+        for (col, cell) in cells.into_iter().enumerate() {
+            witness[col][row] = cell;
+        }
+    }
+    for values in checked.chunks(3) {
+        extend_multi(
+            &mut witness,
+            values[0],
+            values.get(1).copied().unwrap_or(zero),
+            values.get(2).copied().unwrap_or(zero),
+        );
+    }
+
+    witness
+}
+
+/// Extend an existing witness with a range check witness for a value of `bits` bits
+pub fn extend_bits<F: PrimeField>(witness: &mut [Vec<F>; COLUMNS], value: F, bits: usize) {
+    let bits_witness = create_bits(value, bits);
+    for col in 0..COLUMNS {
+        witness[col].extend(bits_witness[col].iter())
+    }
+}
+
 /// Extend an existing witness with a multi-range-check gadget for three 88-bit values: v0, v1 and v2
 pub fn extend_multi<F: PrimeField>(witness: &mut [Vec<F>; COLUMNS], v0: F, v1: F, v2: F) {
     let limbs_witness = create_multi(v0, v1, v2);
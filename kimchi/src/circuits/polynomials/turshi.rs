@@ -83,18 +83,17 @@ use crate::{
     alphas::Alphas,
     circuits::{
         argument::{Argument, ArgumentEnv, ArgumentType},
-        berkeley_columns::{BerkeleyChallengeTerm, BerkeleyChallenges, Column, E},
+        berkeley_columns::{BerkeleyChallengeTerm, E},
         constraints::ConstraintSystem,
-        expr::{self, constraints::ExprOps, Cache},
-        gate::{CircuitGate, GateType},
+        expr::{constraints::ExprOps, Cache},
+        gate::{CircuitGate, Connect, GateType},
+        polynomials::generic::GenericGateSpec,
         wires::{GateWires, Wire, COLUMNS},
     },
     curve::KimchiCurve,
-    proof::ProofEvaluations,
 };
 use ark_ff::{FftField, Field, PrimeField};
 use core::{array, marker::PhantomData};
-use log::error;
 use turshi::{
     runner::{CairoInstruction, CairoProgram, Pointers},
     word::{FlagBits, Offsets},
@@ -102,9 +101,21 @@ use turshi::{
 
 const NUM_FLAGS: usize = 16;
 pub const CIRCUIT_GATE_COUNT: usize = 4;
+/// Number of public inputs holding the claimed initial and final pc and ap
+pub const CLAIM_PUBLIC_INPUTS: usize = 4;
 
 // GATE-RELATED
 
+/// Get vector of Cairo circuit gate types
+pub fn circuit_gates() -> [GateType; CIRCUIT_GATE_COUNT] {
+    [
+        GateType::CairoClaim,
+        GateType::CairoInstruction,
+        GateType::CairoFlags,
+        GateType::CairoTransition,
+    ]
+}
+
 impl<F: PrimeField> CircuitGate<F> {
     /// This function creates a `CairoClaim` gate
     pub fn create_cairo_claim(wires: GateWires) -> Self {
@@ -168,94 +179,81 @@ impl<F: PrimeField> CircuitGate<F> {
         (gates, next)
     }
 
+    /// Gadget generator of the public input of a Cairo execution, made of the
+    /// claimed initial pc and ap, and final pc and ap.
+    /// Returns a vector of public gates, and the next available row after the gadget
+    pub fn create_cairo_public_input() -> (Vec<Self>, usize) {
+        let rows = CLAIM_PUBLIC_INPUTS;
+        let gates = (0..rows)
+            .map(|row| {
+                CircuitGate::create_generic_gadget(Wire::for_row(row), GenericGateSpec::Pub, None)
+            })
+            .collect();
+        (gates, rows)
+    }
+
+    /// Generator of the whole Cairo circuit of an execution of `num` instructions.
+    /// The public input comes first, and its pointers are wired to the ones of the
+    /// `CairoClaim` gate, so that the proof is only valid for the claimed pointers.
+    ///
+    /// Note that the program and its outputs are not part of the public input:
+    /// the code and output cells read and written by the instructions depend on
+    /// the execution, and no memory argument binds them to fixed rows that the
+    /// public input could be wired to.
+    pub fn create_cairo_circuit(num: usize) -> Vec<Self> {
+        let (mut gates, claim_row) = Self::create_cairo_public_input();
+        let (mut cairo_gates, _) = Self::create_cairo_gadget(claim_row, num);
+        gates.append(&mut cairo_gates);
+        for col in 0..CLAIM_PUBLIC_INPUTS {
+            gates.connect_cell_pair((col, 0), (claim_row, col));
+        }
+        gates
+    }
+
     /// verifies that the Cairo gate constraints are solved by the witness depending on its type
     ///
     /// # Errors
     ///
-    /// Will give error if `constraint evaluation` is invalid.
-    ///
-    /// # Panics
-    ///
-    /// Will panic if `constraint linearization` fails.
+    /// Will give error if the wiring or the constraints of the gate are not satisfied.
     pub fn verify_cairo_gate<G: KimchiCurve<ScalarField = F>>(
         &self,
         row: usize,
         witness: &[Vec<F>; COLUMNS],
         cs: &ConstraintSystem<F>,
     ) -> Result<(), String> {
-        // assignments
-        let curr: [F; COLUMNS] = array::from_fn(|i| witness[i][row]);
-        let mut next: [F; COLUMNS] = array::from_fn(|_| F::zero());
-        if self.typ != GateType::Zero {
-            next = array::from_fn(|i| witness[i][row + 1]);
-        }
-
-        // column polynomials
-        let polys = {
-            let mut h = std::collections::HashSet::new();
-            for i in 0..COLUMNS {
-                h.insert(Column::Witness(i)); // column witness polynomials
-            }
-            // gate selector polynomials
-            h.insert(Column::Index(GateType::CairoClaim));
-            h.insert(Column::Index(GateType::CairoInstruction));
-            h.insert(Column::Index(GateType::CairoFlags));
-            h.insert(Column::Index(GateType::CairoTransition));
-            h
-        };
-
-        // assign powers of alpha to these gates
-        let mut alphas = Alphas::<F>::default();
-        alphas.register(ArgumentType::Gate(self.typ), Instruction::<F>::CONSTRAINTS);
-
-        // Get constraints for this circuit gate
-        let constraints =
-            circuit_gate_combined_constraints(self.typ, &alphas, &mut Cache::default());
-
-        // Linearize
-        let linearized = constraints.linearize(polys).unwrap();
+        self.verify_witness::<G>(row, witness, cs, &[])
+            .map_err(|e| e.to_string())
+    }
+}
 
-        // Setup proof evaluations
-        let rng = &mut o1_utils::tests::make_test_rng(None);
-        let evals = ProofEvaluations::dummy_with_witness_evaluations(curr, next);
+pub mod witness {
+    use super::*;
 
-        // Setup circuit constants
-        let constants = expr::Constants {
-            endo_coefficient: cs.endo,
-            mds: &G::sponge_params().mds,
-            zk_rows: 3,
-        };
-        let challenges = BerkeleyChallenges {
-            alpha: F::rand(rng),
-            beta: F::rand(rng),
-            gamma: F::rand(rng),
-            joint_combiner: F::zero(),
-        };
+    /// Returns the public input of an execution of a Cairo program, in the
+    /// format expected by [CircuitGate::create_cairo_public_input]
+    pub fn cairo_public_input<F: Field>(prog: &CairoProgram<F>) -> Vec<F> {
+        vec![
+            prog.ini().pc(),
+            prog.ini().ap(),
+            prog.fin().pc(),
+            prog.fin().ap(),
+        ]
+    }
 
-        let pt = F::rand(rng);
-
-        // Evaluate constraints
-        match linearized
-            .constant_term
-            .evaluate_(cs.domain.d1, pt, &evals, &constants, &challenges)
-        {
-            Ok(x) => {
-                if x == F::zero() {
-                    Ok(())
-                } else {
-                    Err(format!("Invalid {:?} constraint", self.typ))
-                }
-            }
-            Err(x) => {
-                error!("{x:?}");
-                Err(format!("Failed to evaluate {:?} constraint", self.typ))
+    /// Returns the witness of an execution of a Cairo program for the circuit
+    /// created by [CircuitGate::create_cairo_circuit]
+    pub fn cairo_witness_with_public_input<F: Field>(prog: &CairoProgram<F>) -> [Vec<F>; COLUMNS] {
+        let public = cairo_public_input(prog);
+        let mut witness = cairo_witness(prog);
+        for (col, column) in witness.iter_mut().enumerate() {
+            let mut public_rows = vec![F::zero(); public.len()];
+            if col == 0 {
+                public_rows.copy_from_slice(&public);
             }
+            column.splice(0..0, public_rows);
         }
+        witness
     }
-}
-
-pub mod witness {
-    use super::*;
 
     /// Returns the witness of an execution of a Cairo program in `CircuitGate` format
     pub fn cairo_witness<F: Field>(prog: &CairoProgram<F>) -> [Vec<F>; COLUMNS] {
@@ -757,6 +755,7 @@ pub fn circuit_gate_combined_constraints<F: PrimeField>(
     }
 }
 
+#[derive(Default)]
 pub struct Claim<F>(PhantomData<F>);
 
 impl<F> Argument<F> for Claim<F>
@@ -797,6 +796,7 @@ where
     }
 }
 
+#[derive(Default)]
 pub struct Instruction<F>(PhantomData<F>);
 
 impl<F> Argument<F> for Instruction<F>
@@ -946,6 +946,7 @@ where
     }
 }
 
+#[derive(Default)]
 pub struct Flags<F>(PhantomData<F>);
 
 impl<F> Argument<F> for Flags<F>
@@ -1016,6 +1017,7 @@ where
     }
 }
 
+#[derive(Default)]
 pub struct Transition<F>(PhantomData<F>);
 
 impl<F> Argument<F> for Transition<F>
@@ -1,4 +1,5 @@
 //! This module includes the definition of the XOR gadget for 64, 32, and 16 bits,
+//! and of its chaining for words wider than the native field,
 //! the definition of the constraints of the `Xor16` circuit gate,
 //! and the code for witness generation for the XOR gadget.
 use crate::{
@@ -85,6 +86,57 @@ impl<F: PrimeField> CircuitGate<F> {
 
         (new_row + xor_gates.len(), xor_gates)
     }
+
+    /// Extends a XOR gadget for words of any `bits` length to a circuit,
+    /// including words wider than the native field. The words are split into
+    /// limbs of [XOR_LIMB_BITS] bits, from the least significant one, and the
+    /// limbs are XORed by consecutive XOR gadgets.
+    ///
+    /// Includes, for each limb:
+    /// - num_xors Xor16 gates
+    /// - 1 Generic gate to constrain the final row to be zero with itself
+    ///
+    /// Input:
+    /// - gates     : vector of circuit gates
+    /// - bits      : length of the words
+    ///
+    /// Output:
+    /// - new row index
+    ///
+    /// The limbs of the inputs and of the output are in the columns 0, 1 and 2
+    /// of the rows given by [wide_xor_rows].
+    pub fn extend_wide_xor_gadget(gates: &mut Vec<Self>, bits: usize) -> usize {
+        for limb_bits in xor_limb_bits(bits) {
+            Self::extend_xor_gadget(gates, limb_bits);
+        }
+        gates.len()
+    }
+}
+
+/// The number of bits of the limbs of the wide XOR gadget, see
+/// [CircuitGate::extend_wide_xor_gadget]
+pub const XOR_LIMB_BITS: usize = 128;
+
+/// Returns the number of bits of each limb of words of `bits` length, from
+/// the least significant one
+fn xor_limb_bits(bits: usize) -> Vec<usize> {
+    assert!(bits > 0, "Bits must be positive");
+    (0..bits.div_ceil(XOR_LIMB_BITS))
+        .map(|i| (bits - i * XOR_LIMB_BITS).min(XOR_LIMB_BITS))
+        .collect()
+}
+
+/// Returns the rows of the limbs of the wide XOR gadget for words of `bits`
+/// length, relative to its first row, from the least significant limb
+pub fn wide_xor_rows(bits: usize) -> Vec<usize> {
+    xor_limb_bits(bits)
+        .into_iter()
+        .scan(0, |row, limb_bits| {
+            let limb_row = *row;
+            *row += num_xors(limb_bits) + 1;
+            Some(limb_row)
+        })
+        .collect()
 }
 
 /// Get the xor lookup table
@@ -284,6 +336,42 @@ pub fn create_xor_witness<F: PrimeField>(input1: F, input2: F, bits: usize) -> [
     xor_witness
 }
 
+/// Create a wide Xor for words of any `bits` length starting at row 0, laid
+/// out as [CircuitGate::extend_wide_xor_gadget]
+/// Input: first input and second input, bits length
+/// Panics if the desired bits is smaller than the inputs length
+pub fn create_wide_xor_witness<F: PrimeField>(
+    input1: &BigUint,
+    input2: &BigUint,
+    bits: usize,
+) -> [Vec<F>; COLUMNS] {
+    if bits < input1.bitlen() || bits < input2.bitlen() {
+        panic!("Bits must be greater or equal than the inputs length");
+    }
+    let mask = (BigUint::from(1u8) << XOR_LIMB_BITS) - BigUint::from(1u8);
+    let mut xor_witness: [Vec<F>; COLUMNS] = array::from_fn(|_| vec![]);
+    for (i, limb_bits) in xor_limb_bits(bits).into_iter().enumerate() {
+        let limb = |word: &BigUint| ((word >> (i * XOR_LIMB_BITS)) & &mask).to_field().unwrap();
+        extend_xor_witness(&mut xor_witness, limb(input1), limb(input2), limb_bits);
+    }
+
+    xor_witness
+}
+
+/// Extends the wide Xor rows to the full witness
+/// Panics if the words are larger than the desired bits
+pub fn extend_wide_xor_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    input1: &BigUint,
+    input2: &BigUint,
+    bits: usize,
+) {
+    let xor_witness = create_wide_xor_witness(input1, input2, bits);
+    for col in 0..COLUMNS {
+        witness[col].extend(xor_witness[col].iter());
+    }
+}
+
 /// Returns the number of XOR rows needed for inputs of usize bits
 pub fn num_xors(bits: usize) -> usize {
     (bits as f64 / 16.0).ceil() as usize
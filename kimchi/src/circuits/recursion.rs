@@ -0,0 +1,531 @@
+//! In-circuit replay of the Fq-sponge transcript of kimchi proofs, towards
+//! recursion
+//!
+//! A kimchi proof over a curve `G` is verified with computations in two
+//! fields: the Fq-sponge absorbs the commitments of the proof, which are
+//! points with coordinates in the base field of `G`, while the evaluation
+//! checks of the proof are computations in its scalar field. A circuit over
+//! the base field of `G`, that is a proof over the other Pasta curve, can
+//! perform the first part natively, and defers the second part:
+//!
+//! * [replay_transcript] replays the Fq-sponge of the verifier, with the
+//!   [SpongeGadget], on the commitments of a proof given as
+//!   [ProofVars]. It derives the challenges of the proof, and the challenges
+//!   of its opening proof.
+//! * The values crossing the two fields are the [DeferredValues] of the
+//!   proof: the challenges derived by the transcript, which are 128-bit
+//!   values in both fields, the digest of the Fq-sponge given to the
+//!   Fr-sponge, and the combined inner product absorbed by the Fq-sponge
+//!   before the opening proof. They are the public inputs shared with the
+//!   circuit over the scalar field checking the evaluations.
+//! * The check that the final commitment `sg` of the opening proof commits
+//!   to the polynomial of its challenges is deferred to the next proof, as
+//!   one of its previous challenges, see
+//!   [accumulation](crate::accumulation).
+//!
+//! The public input of a proof is absorbed as its commitment, see
+//! [public_commitment], so that a proof with a large public input can be
+//! replayed from the commitment alone, with
+//! [ProofVars::values_with_public_commitment] and
+//! [DeferredValues::compute_with_public_commitment].
+//!
+//! Only proofs without lookups are supported.
+//!
+//! This module does not verify a proof in-circuit: it only derives its
+//! challenges. The group operations of the opening proof (the combination of
+//! the commitments, and the folding of the `L` and `R` points down to `sg`),
+//! the evaluation checks over the scalar field and the Fr-sponge are not
+//! implemented, and the [DeferredValues] are only computed natively.
+
+use crate::{
+    circuits::{
+        builder::{CircuitBuilder, Var},
+        polynomials::poseidon::SPONGE_WIDTH,
+        wires::COLUMNS,
+    },
+    curve::KimchiCurve,
+    error::VerifyError,
+    plonk_sponge::FrSponge,
+    proof::ProverProof,
+    verifier::public_commitment,
+    verifier_index::VerifierIndex,
+};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use mina_poseidon::{
+    constants::{PlonkSpongeConstantsKimchi, SpongeConstants},
+    poseidon::{ArithmeticSpongeParams, SpongeState},
+    sponge::CHALLENGE_LENGTH_IN_LIMBS,
+    FqSponge,
+};
+use num_bigint::BigUint;
+use poly_commitment::{
+    commitment::{shift_scalar, PolyComm},
+    ipa::OpeningProof,
+};
+
+/// The number of bits of the challenges squeezed by the Fq-sponge
+pub const CHALLENGE_BITS: usize = 64 * CHALLENGE_LENGTH_IN_LIMBS;
+
+/// The number of bits to which the high part of a squeezed value is
+/// constrained, so that its split into a challenge and a high part is unique
+const HIGH_BITS: usize = 126;
+
+/// A point given to the circuit as its coordinates, `(0, 0)` for the point at
+/// infinity
+pub type PointVar = [Var; 2];
+
+/// An in-circuit sponge behaving as
+/// [DefaultFqSponge](mina_poseidon::sponge::DefaultFqSponge) with the
+/// constants [PlonkSpongeConstantsKimchi], on the field of the circuit.
+///
+/// The state machine of the sponge is tracked when building the circuit, so
+/// that its permutations are laid out exactly when the native sponge applies
+/// them.
+#[derive(Clone)]
+pub struct SpongeGadget<F: PrimeField> {
+    params: &'static ArithmeticSpongeParams<F>,
+    state: [Var; SPONGE_WIDTH],
+    sponge_state: SpongeState,
+}
+
+impl<F: PrimeField> SpongeGadget<F> {
+    /// Creates a sponge of parameters `params`, with an all-zero state
+    pub fn new(
+        builder: &mut CircuitBuilder<F>,
+        params: &'static ArithmeticSpongeParams<F>,
+    ) -> Self {
+        let zero = builder.constant(F::zero());
+        Self {
+            params,
+            state: [zero; SPONGE_WIDTH],
+            sponge_state: SpongeState::Absorbed(0),
+        }
+    }
+
+    /// Absorbs the field element `x`
+    pub fn absorb(&mut self, builder: &mut CircuitBuilder<F>, x: Var) {
+        let rate = PlonkSpongeConstantsKimchi::SPONGE_RATE;
+        let i = match self.sponge_state {
+            SpongeState::Absorbed(n) if n == rate => {
+                self.permute(builder);
+                0
+            }
+            SpongeState::Absorbed(n) => n,
+            SpongeState::Squeezed(_) => 0,
+        };
+        self.sponge_state = SpongeState::Absorbed(i + 1);
+        self.state[i] = builder.add(self.state[i], x);
+    }
+
+    /// Absorbs the coordinates of the point `point`
+    pub fn absorb_point(&mut self, builder: &mut CircuitBuilder<F>, point: PointVar) {
+        for x in point {
+            self.absorb(builder, x);
+        }
+    }
+
+    /// Squeezes a field element, as `challenge_fq`
+    pub fn squeeze(&mut self, builder: &mut CircuitBuilder<F>) -> Var {
+        let rate = PlonkSpongeConstantsKimchi::SPONGE_RATE;
+        let i = match self.sponge_state {
+            SpongeState::Squeezed(n) if n < rate => n,
+            _ => {
+                self.permute(builder);
+                0
+            }
+        };
+        self.sponge_state = SpongeState::Squeezed(i + 1);
+        self.state[i]
+    }
+
+    /// Squeezes a challenge, made of the [CHALLENGE_BITS] low bits of a
+    /// squeezed field element, as `challenge`.
+    ///
+    /// The high bits of the squeezed element are constrained to be fewer
+    /// than 126, so the circuit cannot be satisfied for the elements larger
+    /// than `2^254`, which happens with a negligible probability in the Pasta
+    /// fields.
+    pub fn challenge(&mut self, builder: &mut CircuitBuilder<F>) -> Var {
+        let squeezed = self.squeeze(builder);
+        let (challenge, high) = builder.split(squeezed, CHALLENGE_BITS);
+        builder.range_check(challenge, CHALLENGE_BITS);
+        builder.range_check(high, HIGH_BITS);
+        challenge
+    }
+
+    fn permute(&mut self, builder: &mut CircuitBuilder<F>) {
+        self.state = builder.poseidon(self.params, self.state);
+    }
+}
+
+/// The number of commitments and chunks of the proofs of a verifier index,
+/// which fixes the inputs of [replay_transcript]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProofShape {
+    /// The number of previous challenges, whose commitments have one chunk
+    pub prev_challenges: usize,
+    /// The number of chunks of the commitments of the columns
+    pub chunks: usize,
+    /// The number of rounds of the opening proof
+    pub ipa_rounds: usize,
+}
+
+impl ProofShape {
+    /// The shape of the proofs of `verifier_index`
+    pub fn new<G: KimchiCurve>(verifier_index: &VerifierIndex<G, OpeningProof<G>>) -> Self
+    where
+        G::BaseField: PrimeField,
+    {
+        Self {
+            prev_challenges: verifier_index.prev_challenges,
+            chunks: verifier_index.num_chunks(),
+            ipa_rounds: verifier_index.srs().g.len().ilog2() as usize,
+        }
+    }
+}
+
+/// The commitments of a proof absorbed by the Fq-sponge, as private inputs
+/// of a circuit
+pub struct ProofVars {
+    /// The commitments of the previous challenges
+    pub prev_challenges: Vec<PointVar>,
+    /// The commitment to the negated public input polynomial
+    pub public_comm: Vec<PointVar>,
+    /// The commitments to the witness columns
+    pub w_comm: [Vec<PointVar>; COLUMNS],
+    /// The commitment to the permutation polynomial
+    pub z_comm: Vec<PointVar>,
+    /// The commitment to the quotient polynomial
+    pub t_comm: Vec<PointVar>,
+    /// The commitments of the rounds of the opening proof
+    pub lr: Vec<(PointVar, PointVar)>,
+    /// The blinding commitment of the opening proof
+    pub delta: PointVar,
+}
+
+impl ProofVars {
+    /// Declares the private inputs of the commitments of a proof of shape
+    /// `shape`, whose values are given by [Self::values], in the same order
+    pub fn private_inputs<F: PrimeField>(
+        builder: &mut CircuitBuilder<F>,
+        shape: &ProofShape,
+    ) -> Self {
+        let mut point = || [builder.private_input(), builder.private_input()];
+        let mut points = |n: usize| (0..n).map(|_| point()).collect::<Vec<_>>();
+        let prev_challenges = points(shape.prev_challenges);
+        let public_comm = points(shape.chunks);
+        let w_comm = core::array::from_fn(|_| points(shape.chunks));
+        let z_comm = points(shape.chunks);
+        let t_comm = points(7 * shape.chunks);
+        let lr = (0..shape.ipa_rounds)
+            .map(|_| {
+                let l = [builder.private_input(), builder.private_input()];
+                let r = [builder.private_input(), builder.private_input()];
+                (l, r)
+            })
+            .collect();
+        let delta = [builder.private_input(), builder.private_input()];
+        Self {
+            prev_challenges,
+            public_comm,
+            w_comm,
+            z_comm,
+            t_comm,
+            lr,
+            delta,
+        }
+    }
+
+    /// The values of the private inputs declared by [Self::private_inputs]
+    /// for the proof `proof` of `verifier_index` and public input
+    /// `public_input`
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the public input does not have the size of the
+    /// circuit.
+    pub fn values<G: KimchiCurve>(
+        verifier_index: &VerifierIndex<G, OpeningProof<G>>,
+        proof: &ProverProof<G, OpeningProof<G>>,
+        public_input: &[G::ScalarField],
+    ) -> Result<Vec<G::BaseField>, VerifyError>
+    where
+        G::BaseField: PrimeField,
+    {
+        let public_comm = public_commitment(verifier_index, public_input)?;
+        Ok(Self::values_with_public_commitment(proof, &public_comm))
+    }
+
+    /// The values of the private inputs declared by [Self::private_inputs]
+    /// for the proof `proof`, whose public input is given as the commitment
+    /// `public_comm` computed by [public_commitment]. The commitment is
+    /// absorbed by [replay_transcript], which binds the challenges of the
+    /// proof to it.
+    pub fn values_with_public_commitment<G: KimchiCurve>(
+        proof: &ProverProof<G, OpeningProof<G>>,
+        public_comm: &PolyComm<G>,
+    ) -> Vec<G::BaseField>
+    where
+        G::BaseField: PrimeField,
+    {
+        let comms = proof
+            .prev_challenges
+            .iter()
+            .flat_map(|prev| &prev.comm.chunks)
+            .chain(&public_comm.chunks)
+            .chain(proof.commitments.w_comm.iter().flat_map(|c| &c.chunks))
+            .chain(&proof.commitments.z_comm.chunks)
+            .chain(&proof.commitments.t_comm.chunks)
+            .chain(proof.proof.lr.iter().flat_map(|(l, r)| [l, r]))
+            .chain([&proof.proof.delta]);
+        comms
+            .flat_map(|point| {
+                let (x, y) = point
+                    .to_coordinates()
+                    .unwrap_or((G::BaseField::zero(), G::BaseField::zero()));
+                [x, y]
+            })
+            .collect()
+    }
+}
+
+/// The challenges of a proof derived in the circuit by [replay_transcript]
+pub struct ChallengeVars {
+    /// The first permutation challenge
+    pub beta: Var,
+    /// The second permutation challenge
+    pub gamma: Var,
+    /// The scalar challenge of the quotient
+    pub alpha_chal: Var,
+    /// The scalar challenge of the evaluation point
+    pub zeta_chal: Var,
+    /// The digest of the Fq-sponge absorbed by the Fr-sponge
+    pub sponge_digest: Var,
+    /// The element mapped to the point `U` of the opening proof
+    pub u: Var,
+    /// The scalar challenges of the rounds of the opening proof
+    pub ipa_challenges: Vec<Var>,
+    /// The scalar challenge combining the opening proof
+    pub c: Var,
+}
+
+/// Replays in the circuit the Fq-sponge of the verifier of a proof without
+/// lookups, whose commitments are `proof` and whose verifier index has the
+/// digest `verifier_index_digest`.
+///
+/// `combined_inner_product` is the deferred combined inner product of the
+/// proof, given as the elements absorbed by the Fq-sponge, see
+/// [DeferredValues::combined_inner_product_absorbed].
+///
+/// Only the challenges are constrained: the commitments are not checked to
+/// form a valid proof, see [self].
+pub fn replay_transcript<F: PrimeField>(
+    builder: &mut CircuitBuilder<F>,
+    params: &'static ArithmeticSpongeParams<F>,
+    verifier_index_digest: Var,
+    proof: &ProofVars,
+    combined_inner_product: &[Var],
+) -> ChallengeVars {
+    let mut sponge = SpongeGadget::new(builder, params);
+    let absorb_points = |sponge: &mut SpongeGadget<F>, builder: &mut _, points: &[PointVar]| {
+        for point in points {
+            sponge.absorb_point(builder, *point);
+        }
+    };
+
+    sponge.absorb(builder, verifier_index_digest);
+    absorb_points(&mut sponge, builder, &proof.prev_challenges);
+    absorb_points(&mut sponge, builder, &proof.public_comm);
+    for comm in &proof.w_comm {
+        absorb_points(&mut sponge, builder, comm);
+    }
+    let beta = sponge.challenge(builder);
+    let gamma = sponge.challenge(builder);
+    absorb_points(&mut sponge, builder, &proof.z_comm);
+    let alpha_chal = sponge.challenge(builder);
+    absorb_points(&mut sponge, builder, &proof.t_comm);
+    let zeta_chal = sponge.challenge(builder);
+    let sponge_digest = sponge.clone().squeeze(builder);
+
+    // the opening proof
+    for x in combined_inner_product {
+        sponge.absorb(builder, *x);
+    }
+    let u = sponge.squeeze(builder);
+    let ipa_challenges = proof
+        .lr
+        .iter()
+        .map(|(l, r)| {
+            sponge.absorb_point(builder, *l);
+            sponge.absorb_point(builder, *r);
+            sponge.challenge(builder)
+        })
+        .collect();
+    sponge.absorb_point(builder, proof.delta);
+    let c = sponge.challenge(builder);
+
+    ChallengeVars {
+        beta,
+        gamma,
+        alpha_chal,
+        zeta_chal,
+        sponge_digest,
+        u,
+        ipa_challenges,
+        c,
+    }
+}
+
+/// The values of a proof deferred from the circuit over the base field to the
+/// circuit over the scalar field, or to the next proof, see [self]
+#[derive(Clone, Debug)]
+pub struct DeferredValues<G: KimchiCurve> {
+    /// The first permutation challenge
+    pub beta: G::ScalarField,
+    /// The second permutation challenge
+    pub gamma: G::ScalarField,
+    /// The scalar challenge of the quotient
+    pub alpha_chal: G::ScalarField,
+    /// The scalar challenge of the evaluation point
+    pub zeta_chal: G::ScalarField,
+    /// The digest of the Fq-sponge absorbed by the Fr-sponge
+    pub sponge_digest: G::BaseField,
+    /// The combined inner product of the evaluations of the proof
+    pub combined_inner_product: G::ScalarField,
+    /// The element mapped to the point `U` of the opening proof
+    pub u: G::BaseField,
+    /// The scalar challenges of the rounds of the opening proof
+    pub ipa_challenges: Vec<G::ScalarField>,
+    /// The scalar challenge combining the opening proof
+    pub c: G::ScalarField,
+    /// The final commitment of the opening proof, whose check is deferred
+    /// to the next proof
+    pub sg: G,
+}
+
+impl<G: KimchiCurve> DeferredValues<G>
+where
+    G::BaseField: PrimeField,
+{
+    /// Computes the deferred values of the proof `proof` of `verifier_index`
+    /// and public input `public_input`, as the verifier does
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the proof uses lookups, or if its Fiat-Shamir
+    /// argument fails.
+    pub fn compute<EFqSponge, EFrSponge>(
+        verifier_index: &VerifierIndex<G, OpeningProof<G>>,
+        proof: &ProverProof<G, OpeningProof<G>>,
+        public_input: &[G::ScalarField],
+    ) -> Result<Self, VerifyError>
+    where
+        EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+        EFrSponge: FrSponge<G::ScalarField>,
+    {
+        let public_comm = public_commitment(verifier_index, public_input)?;
+        Self::compute_with_public_commitment::<EFqSponge, EFrSponge>(
+            verifier_index,
+            proof,
+            &public_comm,
+        )
+    }
+
+    /// Computes the deferred values of the proof `proof` of `verifier_index`,
+    /// whose public input is given as the commitment `public_comm` computed
+    /// by [public_commitment]
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the proof uses lookups, if it does not hold the
+    /// evaluations of its public input polynomial, or if its Fiat-Shamir
+    /// argument fails.
+    pub fn compute_with_public_commitment<EFqSponge, EFrSponge>(
+        verifier_index: &VerifierIndex<G, OpeningProof<G>>,
+        proof: &ProverProof<G, OpeningProof<G>>,
+        public_comm: &PolyComm<G>,
+    ) -> Result<Self, VerifyError>
+    where
+        EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+        EFrSponge: FrSponge<G::ScalarField>,
+    {
+        if verifier_index.lookup_index.is_some() {
+            return Err(VerifyError::LookupCommitmentMissing);
+        }
+        let oracles_result =
+            proof.oracles::<EFqSponge, EFrSponge>(verifier_index, public_comm, None)?;
+        let mut sponge = oracles_result.fq_sponge;
+        let sponge_digest = sponge.clone().challenge_fq();
+
+        let combined_inner_product = oracles_result.combined_inner_product;
+        sponge.absorb_fr(&[shift_scalar::<G>(combined_inner_product)]);
+        let u = sponge.challenge_fq();
+        let ipa_challenges = proof
+            .proof
+            .lr
+            .iter()
+            .map(|(l, r)| {
+                sponge.absorb_g(&[*l]);
+                sponge.absorb_g(&[*r]);
+                sponge.challenge()
+            })
+            .collect();
+        sponge.absorb_g(&[proof.proof.delta]);
+        let c = sponge.challenge();
+
+        let oracles = oracles_result.oracles;
+        Ok(Self {
+            beta: oracles.beta,
+            gamma: oracles.gamma,
+            alpha_chal: oracles.alpha_chal.0,
+            zeta_chal: oracles.zeta_chal.0,
+            sponge_digest,
+            combined_inner_product,
+            u,
+            ipa_challenges,
+            c,
+            sg: proof.proof.sg,
+        })
+    }
+
+    /// The challenges of the proof, as elements of the base field, in the
+    /// order of [replay_transcript]: `beta`, `gamma`, `alpha_chal`,
+    /// `zeta_chal`, the challenges of the opening proof and `c`
+    pub fn challenges_in_base_field(&self) -> Vec<G::BaseField> {
+        [self.beta, self.gamma, self.alpha_chal, self.zeta_chal]
+            .iter()
+            .chain(&self.ipa_challenges)
+            .chain([&self.c])
+            .map(|x| to_base_field::<G>(*x))
+            .collect()
+    }
+
+    /// The elements of the base field absorbed by the Fq-sponge for the
+    /// combined inner product
+    pub fn combined_inner_product_absorbed(&self) -> Vec<G::BaseField> {
+        let bits = shift_scalar::<G>(self.combined_inner_product)
+            .into_bigint()
+            .to_bits_le();
+        let scalar_modulus: BigUint = G::ScalarField::MODULUS.into();
+        let base_modulus: BigUint = G::BaseField::MODULUS.into();
+        if scalar_modulus < base_modulus {
+            vec![G::BaseField::from_bigint(BigInteger::from_bits_le(&bits))
+                .expect("the scalar field is smaller than the base field")]
+        } else {
+            vec![
+                G::BaseField::from_bigint(BigInteger::from_bits_le(&bits[1..]))
+                    .expect("the high bits fit in the base field"),
+                G::BaseField::from(u64::from(bits[0])),
+            ]
+        }
+    }
+}
+
+/// Converts a scalar smaller than the base field modulus to the base field
+fn to_base_field<G: KimchiCurve>(x: G::ScalarField) -> G::BaseField
+where
+    G::BaseField: PrimeField,
+{
+    G::BaseField::from_bigint(BigInteger::from_bits_le(&x.into_bigint().to_bits_le()))
+        .expect("the scalar fits in the base field")
+}
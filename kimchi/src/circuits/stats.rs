@@ -0,0 +1,279 @@
+//! Statistics of a circuit and an estimate of the cost of proving it
+//!
+//! [ConstraintSystem::stats] counts the gates of a circuit, its lookups and
+//! the degree of its constraints, and estimates the work of the prover from
+//! the size of the domain: the commitments it computes, as multi-scalar
+//! multiplications (MSMs), and the FFTs it runs. This allows comparing the
+//! implementations of a gadget without running the prover.
+//!
+//! ```
+//! use kimchi::circuits::{
+//!     constraints::ConstraintSystem,
+//!     gate::{CircuitGate, GateType},
+//!     polynomials::generic::GenericGateSpec,
+//!     wires::Wire,
+//! };
+//! use mina_curves::pasta::Fp;
+//!
+//! let gates: Vec<CircuitGate<Fp>> = (0..2)
+//!     .map(|row| {
+//!         CircuitGate::create_generic_gadget(
+//!             Wire::for_row(row),
+//!             GenericGateSpec::Const(Fp::from(42u64)),
+//!             None,
+//!         )
+//!     })
+//!     .collect();
+//! let cs = ConstraintSystem::create(gates).build().unwrap();
+//!
+//! let stats = cs.stats();
+//! assert_eq!(stats.gates[&GateType::Generic], 2);
+//! assert_eq!(stats.max_degree, 3);
+//! println!("{stats}");
+//! ```
+
+use crate::circuits::{
+    argument::DynArgument,
+    berkeley_columns::E,
+    constraints::ConstraintSystem,
+    expr::Cache,
+    gate::{CircuitGate, CurrOrNext, GateType},
+    lookup::lookups::{LookupInfo, LookupPattern},
+    polynomial::COLUMNS,
+    polynomials::{
+        complete_add::CompleteAdd,
+        endomul_scalar::EndomulScalar,
+        endosclmul::EndosclMul,
+        foreign_field_add::circuitgates::ForeignFieldAdd,
+        foreign_field_mul::circuitgates::ForeignFieldMul,
+        generic::Generic,
+        poseidon::Poseidon,
+        poseidon2::{Poseidon2External, Poseidon2Internal},
+        range_check::circuitgates::{RangeCheck0, RangeCheck1},
+        rot::Rot64,
+        turshi::{Claim, Flags, Instruction, Transition},
+        varbasemul::VarbaseMul,
+        xor::Xor16,
+    },
+    wires::{Wire, PERMUTS},
+};
+use ark_ff::PrimeField;
+use core::fmt;
+use std::collections::BTreeMap;
+
+/// An estimate of the work of the prover on a circuit, counting the main
+/// operations of a proof
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProverCost {
+    /// The number of points of the MSMs committing to the polynomials
+    pub msm_size: usize,
+    /// The number of polynomials committed to, each with an MSM of
+    /// `msm_size` points
+    pub msms: usize,
+    /// The number of rounds of the opening proof, each with two MSMs of
+    /// halving sizes, for about `2 * msm_size` points in total
+    pub opening_rounds: usize,
+    /// The number of FFTs, inverse or not, by size
+    pub ffts: BTreeMap<usize, usize>,
+}
+
+impl ProverCost {
+    /// The size of the largest FFTs, over the domain of the quotient
+    pub fn max_fft_size(&self) -> usize {
+        self.ffts.keys().next_back().copied().unwrap_or(0)
+    }
+}
+
+/// Statistics of a circuit, see [self]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CircuitStats {
+    /// The number of gates, one per row, without the zero gates padding the
+    /// circuit to the size of the domain
+    pub rows: usize,
+    /// The number of public inputs
+    pub public: usize,
+    /// The size of the domain of the circuit
+    pub domain_size: usize,
+    /// The number of rows reserved for zero-knowledge
+    pub zk_rows: u64,
+    /// The number of gates of each type, without the padding
+    pub gates: BTreeMap<GateType, usize>,
+    /// The number of lookups of each pattern, whose lookup table is given by
+    /// [LookupPattern::table] for the gates
+    pub lookups: BTreeMap<LookupPattern, usize>,
+    /// Whether the lookups use runtime tables
+    pub runtime_tables: bool,
+    /// Whether the lookups are checked with the logUp argument
+    pub log_up: bool,
+    /// The highest degree of the constraints of the gates, in the witness
+    /// and coefficient columns and without the selector of the gate
+    pub max_degree: u64,
+    /// The estimated cost of proving the circuit
+    pub cost: ProverCost,
+}
+
+/// The constraints of a type of gate
+fn gate_constraints<F: PrimeField>(typ: GateType) -> Vec<E<F>> {
+    let argument: &dyn DynArgument<F> = match typ {
+        GateType::Zero | GateType::Lookup | GateType::VectorLookup => return vec![],
+        GateType::Generic => &Generic::default(),
+        GateType::Poseidon => &Poseidon::default(),
+        GateType::CompleteAdd => &CompleteAdd::default(),
+        GateType::VarBaseMul => &VarbaseMul::default(),
+        GateType::EndoMul => &EndosclMul::default(),
+        GateType::EndoMulScalar => &EndomulScalar::default(),
+        GateType::CairoClaim => &Claim::default(),
+        GateType::CairoInstruction => &Instruction::default(),
+        GateType::CairoFlags => &Flags::default(),
+        GateType::CairoTransition => &Transition::default(),
+        GateType::RangeCheck0 => &RangeCheck0::default(),
+        GateType::RangeCheck1 => &RangeCheck1::default(),
+        GateType::ForeignFieldAdd => &ForeignFieldAdd::default(),
+        GateType::ForeignFieldMul => &ForeignFieldMul::default(),
+        GateType::Xor16 => &Xor16::default(),
+        GateType::Rot64 => &Rot64::default(),
+        GateType::Poseidon2External => &Poseidon2External::default(),
+        GateType::Poseidon2Internal => &Poseidon2Internal::default(),
+    };
+    argument.constraints(&mut Cache::default())
+}
+
+/// The number of lookups of each pattern of the gates, applied on the rows
+/// given by [LookupPattern::from_gate]
+fn count_lookups<F: PrimeField>(gates: &[CircuitGate<F>]) -> BTreeMap<LookupPattern, usize> {
+    let mut lookups = BTreeMap::new();
+    for gate in gates {
+        for row in [CurrOrNext::Curr, CurrOrNext::Next] {
+            if let Some(pattern) = LookupPattern::from_gate(gate.typ, row) {
+                *lookups.entry(pattern).or_insert(0) += pattern.lookups::<F>().len();
+            }
+        }
+    }
+    lookups
+}
+
+impl<F: PrimeField> ConstraintSystem<F> {
+    /// Returns the statistics of the circuit, see [CircuitStats]
+    ///
+    /// The cost of the prover is estimated for an SRS at least as large as
+    /// the domain, with polynomials committed in a single chunk.
+    pub fn stats(&self) -> CircuitStats {
+        // The padding is made of the trailing zero gates whose cells are not
+        // wired
+        let rows = self
+            .gates
+            .iter()
+            .enumerate()
+            .rposition(|(row, gate)| {
+                gate.typ != GateType::Zero || gate.wires != Wire::for_row(row)
+            })
+            .map_or(0, |row| row + 1);
+        let circuit = &self.gates[..rows];
+
+        let mut gates = BTreeMap::new();
+        for gate in circuit {
+            *gates.entry(gate.typ).or_insert(0) += 1;
+        }
+
+        let max_degree = gates
+            .keys()
+            .flat_map(|typ| gate_constraints::<F>(*typ))
+            .map(|constraint| constraint.degree(1, self.zk_rows))
+            .max()
+            .unwrap_or(0);
+
+        let lookups = count_lookups(circuit);
+        let lookup_features = self.feature_flags.lookup_features;
+        let n = self.domain.d1.size as usize;
+
+        // The public input, the witness columns, the permutation aggregation
+        // and the chunks of the quotient
+        let mut msms = 1 + COLUMNS + 1 + PERMUTS;
+        // Interpolating the public input, the witness columns and the
+        // permutation aggregation, evaluating the public input over d4 and
+        // the others over d8, and interpolating the two parts of the quotient
+        let mut ffts = BTreeMap::from([(n, 1 + COLUMNS + 1), (4 * n, 2), (8 * n, COLUMNS + 2)]);
+        if !lookups.is_empty() || lookup_features.uses_runtime_tables {
+            // The sorted polynomials of plookup, or the multiplicities of
+            // logUp, the lookup aggregation, and the runtime table
+            let sorted = if lookup_features.log_up {
+                1
+            } else {
+                LookupInfo::create(lookup_features).max_per_row + 1
+            };
+            let polys = sorted + 1 + usize::from(lookup_features.uses_runtime_tables);
+            msms += polys;
+            // Each polynomial is interpolated and evaluated over d8, and so
+            // is the joint lookup table
+            *ffts.entry(n).or_insert(0) += polys;
+            *ffts.entry(8 * n).or_insert(0) += polys + 1;
+        }
+
+        CircuitStats {
+            rows,
+            public: self.public,
+            domain_size: n,
+            zk_rows: self.zk_rows,
+            gates,
+            lookups,
+            runtime_tables: lookup_features.uses_runtime_tables,
+            log_up: lookup_features.log_up,
+            max_degree,
+            cost: ProverCost {
+                msm_size: n,
+                msms,
+                opening_rounds: n.trailing_zeros() as usize,
+                ffts,
+            },
+        }
+    }
+}
+
+impl fmt::Display for CircuitStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} rows ({} public), domain of {} rows with {} zk rows",
+            self.rows, self.public, self.domain_size, self.zk_rows
+        )?;
+        let gates: Vec<_> = self
+            .gates
+            .iter()
+            .map(|(typ, count)| format!("{typ:?}: {count}"))
+            .collect();
+        writeln!(f, "gates: {}", gates.join(", "))?;
+        if !self.lookups.is_empty() {
+            let lookups: Vec<_> = self
+                .lookups
+                .iter()
+                .map(|(pattern, count)| format!("{pattern:?}: {count}"))
+                .collect();
+            writeln!(
+                f,
+                "lookups ({}{}): {}",
+                if self.log_up { "logUp" } else { "plookup" },
+                if self.runtime_tables {
+                    ", runtime tables"
+                } else {
+                    ""
+                },
+                lookups.join(", ")
+            )?;
+        }
+        writeln!(f, "max constraint degree: {}", self.max_degree)?;
+        let ffts: Vec<_> = self
+            .cost
+            .ffts
+            .iter()
+            .map(|(size, count)| format!("{count} of size {size}"))
+            .collect();
+        write!(
+            f,
+            "prover: {} MSMs of size {}, {} opening rounds, FFTs: {}",
+            self.cost.msms,
+            self.cost.msm_size,
+            self.cost.opening_rounds,
+            ffts.join(", ")
+        )
+    }
+}
@@ -5,6 +5,7 @@ mod copy_bits_cell;
 mod copy_cell;
 mod copy_shift_cell;
 mod index_cell;
+pub mod solver;
 mod variable_bits_cell;
 mod variable_cell;
 mod variables;
@@ -0,0 +1,270 @@
+//! A solver deriving the witness of a circuit from the values of some of its
+//! cells
+//!
+//! The [WitnessSolver] starts from the public inputs and the cells assigned
+//! by the caller, and repeats the following until no new cell is known:
+//!
+//! * the value of a cell is copied to the other cells of its permutation
+//!   cycle, if it is one of the first [PERMUTS](crate::circuits::wires::PERMUTS) registers,
+//! * the rule of each gate derives the unknown cells of its row from the
+//!   known ones.
+//!
+//! The rules are the following:
+//!
+//! | Gate       | Rule                                                         |
+//! | ---------- | ------------------------------------------------------------ |
+//! | `Generic`  | each half gate solves its only unknown variable, if linear   |
+//! | `Poseidon` | the rounds are applied to the state of the first 3 registers |
+//! | `Zero`     | none, its cells are only copied                              |
+//!
+//! The rows of the other gates must be assigned by the caller. The cells left
+//! unknown are set to zero, unless they are used by their gate or copied to
+//! other cells, in which case solving fails.
+
+use crate::{
+    circuits::{
+        gate::{CircuitGate, GateType},
+        polynomial::COLUMNS,
+        polynomials::{
+            generic::GENERIC_COEFFS,
+            poseidon::{round_to_cols, ROUNDS_PER_ROW, SPONGE_WIDTH},
+        },
+    },
+    curve::KimchiCurve,
+};
+use ark_ff::PrimeField;
+use core::array;
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    poseidon::{sbox, ArithmeticSpongeParams},
+};
+use thiserror::Error;
+
+/// Errors that can arise when solving a witness
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SolverError {
+    #[error("the cell ({0}, {1}) has two different values")]
+    Inconsistent(usize, usize),
+
+    #[error("the values of the generic gate of row {0} do not satisfy it")]
+    Unsatisfied(usize),
+
+    #[error("the cell ({0}, {1}) could not be solved")]
+    Unsolved(usize, usize),
+
+    #[error("the row {0} of the gate {1:?} must be assigned")]
+    UnsupportedGate(usize, GateType),
+}
+
+/// Derives the witness of a circuit from the values of some of its cells, see
+/// [self]
+pub struct WitnessSolver<'a, F: PrimeField> {
+    gates: &'a [CircuitGate<F>],
+    values: [Vec<Option<F>>; COLUMNS],
+    /// The number of public inputs, whose rows have no rule
+    public: usize,
+    /// The first error met while assigning cells
+    error: Option<SolverError>,
+}
+
+impl<'a, F: PrimeField> WitnessSolver<'a, F> {
+    /// Creates a solver for the circuit `gates`, whose first rows hold the
+    /// public inputs `public`
+    pub fn new(gates: &'a [CircuitGate<F>], public: &[F]) -> Self {
+        let mut solver = Self {
+            gates,
+            values: array::from_fn(|_| vec![None; gates.len()]),
+            public: public.len(),
+            error: None,
+        };
+        for (row, value) in public.iter().enumerate() {
+            solver.assign((row, 0), *value);
+        }
+        solver
+    }
+
+    /// Assigns `value` to the cell `cell`, as `(row, column)`, and to the
+    /// other cells of its permutation cycle
+    pub fn assign(&mut self, cell: (usize, usize), value: F) -> &mut Self {
+        let (mut row, mut col) = cell;
+        loop {
+            match self.values[col][row] {
+                Some(known) if known != value => {
+                    self.error
+                        .get_or_insert(SolverError::Inconsistent(row, col));
+                    break;
+                }
+                Some(_) => (),
+                None => self.values[col][row] = Some(value),
+            }
+            let Some(wire) = self.gates[row].wires.get(col) else {
+                break;
+            };
+            (row, col) = (wire.row, wire.col);
+            if (row, col) == cell {
+                break;
+            }
+        }
+        self
+    }
+
+    /// Assigns `values` to the first cells of the row `row`
+    pub fn assign_row(&mut self, row: usize, values: &[F]) -> &mut Self {
+        for (col, value) in values.iter().enumerate() {
+            self.assign((row, col), *value);
+        }
+        self
+    }
+
+    /// Solves the cells left unknown, with the Poseidon parameters of `G`
+    ///
+    /// # Errors
+    ///
+    /// Will give error if two values are given to the same cell, if the values
+    /// of a generic gate do not satisfy it, if a cell
+    /// used by its gate cannot be solved, or if the row of a gate without
+    /// rule is not assigned.
+    pub fn solve<G: KimchiCurve<ScalarField = F>>(
+        mut self,
+    ) -> Result<[Vec<F>; COLUMNS], SolverError> {
+        let params = G::sponge_params();
+        loop {
+            let mut progress = false;
+            for row in self.public..self.gates.len() {
+                progress |= match self.gates[row].typ {
+                    GateType::Generic => self.solve_generic(row),
+                    GateType::Poseidon => self.solve_poseidon(row, params),
+                    _ => false,
+                };
+            }
+            if let Some(error) = self.error.take() {
+                return Err(error);
+            }
+            if !progress {
+                break;
+            }
+        }
+
+        for (row, gate) in self.gates.iter().enumerate() {
+            for col in 0..COLUMNS {
+                if self.values[col][row].is_some() {
+                    continue;
+                }
+                let used = match gate.typ {
+                    GateType::Generic => generic_uses(gate, col),
+                    GateType::Poseidon => true,
+                    GateType::Zero => false,
+                    typ => return Err(SolverError::UnsupportedGate(row, typ)),
+                };
+                let copied = gate
+                    .wires
+                    .get(col)
+                    .is_some_and(|wire| (wire.row, wire.col) != (row, col));
+                if used || copied {
+                    return Err(SolverError::Unsolved(row, col));
+                }
+            }
+        }
+
+        Ok(self
+            .values
+            .map(|col| col.into_iter().map(Option::unwrap_or_default).collect()))
+    }
+
+    /// Solves the only unknown variable of each half of the generic gate of
+    /// row `row`, and returns whether a cell was solved
+    fn solve_generic(&mut self, row: usize) -> bool {
+        let mut progress = false;
+        for half in 0..2 {
+            let coeffs = &self.gates[row].coeffs[half * GENERIC_COEFFS..];
+            let [cl, cr, co, cm, cc] =
+                array::from_fn(|i| coeffs.get(i).copied().unwrap_or_default());
+            let cols = [3 * half, 3 * half + 1, 3 * half + 2];
+            let used = cols.map(|col| generic_uses(&self.gates[row], col));
+            let known = cols.map(|col| self.values[col][row]);
+            let unknown: Vec<usize> = (0..3).filter(|i| used[*i] && known[*i].is_none()).collect();
+            let [l, r, o] = known.map(Option::unwrap_or_default);
+
+            let solved = match unknown[..] {
+                [0] => (cl + cm * r)
+                    .inverse()
+                    .map(|inv| (cols[0], -(cr * r + co * o + cc) * inv)),
+                [1] => (cr + cm * l)
+                    .inverse()
+                    .map(|inv| (cols[1], -(cl * l + co * o + cc) * inv)),
+                [2] => co
+                    .inverse()
+                    .map(|inv| (cols[2], -(cl * l + cr * r + cm * l * r + cc) * inv)),
+                [] => {
+                    if !(cl * l + cr * r + co * o + cm * l * r + cc).is_zero() {
+                        self.error.get_or_insert(SolverError::Unsatisfied(row));
+                    }
+                    None
+                }
+                _ => None,
+            };
+            if let Some((col, value)) = solved {
+                self.assign((row, col), value);
+                progress = true;
+            }
+        }
+        progress
+    }
+
+    /// Applies the rounds of the Poseidon gate of row `row` to the state of
+    /// its first 3 registers, and returns whether a cell was solved
+    fn solve_poseidon(&mut self, row: usize, params: &ArithmeticSpongeParams<F>) -> bool {
+        let Some(mut state) = (0..SPONGE_WIDTH)
+            .map(|col| self.values[col][row])
+            .collect::<Option<Vec<F>>>()
+        else {
+            return false;
+        };
+        let next_row_known = (0..SPONGE_WIDTH).all(|col| self.values[col][row + 1].is_some());
+        if next_row_known && self.values.iter().all(|col| col[row].is_some()) {
+            return false;
+        }
+
+        let round_constants = self.gates[row].rc();
+        for (round, round_constants) in round_constants.iter().enumerate() {
+            let state_sbox: Vec<F> = state
+                .iter()
+                .map(|s| sbox::<F, PlonkSpongeConstantsKimchi>(*s))
+                .collect();
+            state = params
+                .mds
+                .iter()
+                .zip(round_constants)
+                .map(|(mds_row, c)| {
+                    mds_row
+                        .iter()
+                        .zip(&state_sbox)
+                        .fold(*c, |acc, (m, s)| acc + *m * s)
+                })
+                .collect();
+
+            // the last state is stored in the first registers of the next row
+            let (row, cols) = if round == ROUNDS_PER_ROW - 1 {
+                (row + 1, round_to_cols(0))
+            } else {
+                (row, round_to_cols(round + 1))
+            };
+            for (col, s) in cols.zip(&state) {
+                self.assign((row, col), *s);
+            }
+        }
+        true
+    }
+}
+
+/// Whether the generic gate `gate` uses its register `col`
+fn generic_uses<F: PrimeField>(gate: &CircuitGate<F>, col: usize) -> bool {
+    let coeff = |i: usize| !gate.coeffs.get(i).copied().unwrap_or_default().is_zero();
+    let half = (col / 3) * GENERIC_COEFFS;
+    match col {
+        0 | 3 => coeff(half) || coeff(half + 3),
+        1 | 4 => coeff(half + 1) || coeff(half + 3),
+        2 | 5 => coeff(half + 2),
+        _ => false,
+    }
+}
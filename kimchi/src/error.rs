@@ -77,6 +77,9 @@ pub enum VerifyError {
     #[error("runtime tables are used, but missing from the proof")]
     IncorrectRuntimeProof,
 
+    #[error("the commitment to the runtime tables does not match the expected tables")]
+    IncorrectRuntimeTables,
+
     #[error("the evaluation for {0:?} is missing")]
     MissingEvaluation(crate::circuits::berkeley_columns::Column),
 
@@ -120,6 +123,63 @@ pub enum VerifierIndexError {
     SRSHasBeenSet,
 }
 
+/// Errors that can arise when writing or loading a prover index
+#[derive(Error, Debug, Clone)]
+pub enum ProverIndexCacheError {
+    #[error("the prover index could not be read or written: {0}")]
+    Io(String),
+
+    #[error("the data does not start with the magic bytes of a prover index")]
+    InvalidMagic,
+
+    #[error("the prover index is not the one of the expected circuit")]
+    CircuitMismatch,
+
+    #[error("the hash of the prover index does not match its content")]
+    Corrupted,
+
+    #[error("the prover index could not be encoded or decoded: {0}")]
+    Serialization(String),
+
+    #[error("the SRS has a maximal polynomial size of {1}, but the prover index expects {0}")]
+    SrsMismatch(usize, usize),
+}
+
+/// Errors that can arise when writing or loading a compact verifier index
+#[derive(Error, Debug, Clone)]
+pub enum VerifierKeyError {
+    #[error("the data does not start with the magic bytes of a verifier key")]
+    InvalidMagic,
+
+    #[error("the verifier key format version {0} is not supported")]
+    UnsupportedVersion(u32),
+
+    #[error("the verifier key could not be encoded or decoded: {0}")]
+    Serialization(String),
+
+    #[error("the domain size {0} is not a supported power of two")]
+    InvalidDomain(u64),
+
+    #[error("the SRS has a maximal polynomial size of {1}, but the verifier key expects {0}")]
+    SrsMismatch(usize, usize),
+}
+
+/// Errors that can arise when proving with the accumulators of proofs
+#[derive(Error, Debug, Clone)]
+pub enum AccumulationError {
+    #[error("the circuit takes {0} previous challenges, but {1} proofs were given")]
+    WrongNumberOfProofs(usize, usize),
+
+    #[error("the proofs and the circuit do not use the same SRS")]
+    DifferentSRS,
+
+    #[error("a proof failed to verify: {0}")]
+    Verify(#[from] VerifyError),
+
+    #[error("the proof taking the accumulators could not be created: {0}")]
+    Prover(#[from] ProverError),
+}
+
 // Handling of lookup errors happening inside creation of LookupConstraintSystem
 impl From<LazyCacheErrorOr<LookupError>> for SetupError {
     fn from(e: LazyCacheErrorOr<LookupError>) -> Self {
@@ -7,8 +7,10 @@ pub use o1_utils;
 pub use poly_commitment;
 pub use turshi;
 
+pub mod accumulation;
 pub mod alphas;
 pub mod bench;
+pub mod cairo;
 pub mod circuits;
 pub mod curve;
 pub mod error;
@@ -17,6 +19,7 @@ pub mod linearization;
 pub mod oracles;
 pub mod plonk_sponge;
 pub mod proof;
+pub mod proof_format;
 pub mod prover;
 pub mod prover_index;
 pub mod verifier;
@@ -18,8 +18,9 @@ use crate::{
             foreign_field_mul::circuitgates::ForeignFieldMul,
             generic, permutation,
             poseidon::Poseidon,
+            poseidon2,
             range_check::circuitgates::{RangeCheck0, RangeCheck1},
-            rot,
+            rot, turshi,
             varbasemul::VarbaseMul,
             xor,
         },
@@ -50,11 +51,23 @@ pub fn constraints_expr<F: PrimeField>(
     // register powers of alpha so that we don't reuse them across mutually inclusive constraints
     let mut powers_of_alpha = Alphas::<F>::default();
 
+    // The Cairo gates are not part of the feature-flagged expression, and only
+    // extend the powers of alpha of the gates when they are used, so that the
+    // powers of alpha of the other circuits are left unchanged.
+    let cairo = feature_flags.is_some_and(|feature_flags| feature_flags.cairo);
+    // The Poseidon2 gates are not part of the feature-flagged expression
+    // either, as their constraints fit in the powers of alpha of the gates.
+    let poseidon2 = feature_flags.is_some_and(|feature_flags| feature_flags.poseidon2);
+
     // Set up powers of alpha. Only the max number of constraints matters.
     // The gate type argument can just be the zero gate.
     powers_of_alpha.register(
         ArgumentType::Gate(GateType::Zero),
-        VarbaseMul::<F>::CONSTRAINTS,
+        if cairo {
+            turshi::Instruction::<F>::CONSTRAINTS
+        } else {
+            VarbaseMul::<F>::CONSTRAINTS
+        },
     );
 
     let mut cache = expr::Cache::default();
@@ -161,6 +174,23 @@ pub fn constraints_expr<F: PrimeField>(
         }
     }
 
+    if cairo {
+        for gate_type in turshi::circuit_gates() {
+            expr +=
+                turshi::circuit_gate_combined_constraints(gate_type, &powers_of_alpha, &mut cache);
+        }
+    }
+
+    if poseidon2 {
+        for gate_type in poseidon2::circuit_gates() {
+            expr += poseidon2::circuit_gate_combined_constraints(
+                gate_type,
+                &powers_of_alpha,
+                &mut cache,
+            );
+        }
+    }
+
     if generic {
         expr += generic::Generic::combined_constraints(&powers_of_alpha, &mut cache);
     }
@@ -194,9 +224,11 @@ pub fn constraints_expr<F: PrimeField>(
                 lookup: true,
                 range_check: true,
                 foreign_field_mul: true,
+                vector_lookup: true,
             },
             uses_runtime_tables: true,
             joint_lookup_used: true,
+            log_up: false,
         };
         let lookup_configuration = LookupConfiguration::new(LookupInfo::create(all_features));
         let constraints = lookup::constraints::constraints(&lookup_configuration, true);
@@ -229,7 +261,9 @@ pub fn constraints_expr<F: PrimeField>(
     // Check that the feature flags correctly turn on or off the constraints generated by the given
     // flags.
     if cfg!(feature = "check_feature_flags") {
-        if let Some(feature_flags) = feature_flags {
+        if let Some(feature_flags) =
+            feature_flags.filter(|feature_flags| !feature_flags.cairo && !feature_flags.poseidon2)
+        {
             let (feature_flagged_expr, _) = constraints_expr(None, generic);
             let feature_flagged_expr = feature_flagged_expr.apply_feature_flags(feature_flags);
             assert_eq!(expr, feature_flagged_expr);
@@ -266,10 +300,14 @@ pub fn linearization_columns<F: FftField>(
                         lookup: true,
                         range_check: true,
                         foreign_field_mul: true,
+                        vector_lookup: true,
                     },
                     joint_lookup_used: true,
                     uses_runtime_tables: true,
+                    log_up: false,
                 },
+                cairo: false,
+                poseidon2: false,
             }
         }
     };
@@ -292,9 +330,12 @@ pub fn linearization_columns<F: FftField>(
 
     // the lookup polynomials
     if let Some(lookup_info) = lookup_info {
-        for i in 0..=lookup_info.max_per_row {
+        for i in 0..lookup_info.sorted_columns() {
             h.insert(LookupSorted(i));
         }
+        if lookup_info.features.log_up {
+            h.insert(LookupMultiplicities);
+        }
         h.insert(LookupAggreg);
         h.insert(LookupTable);
 
@@ -325,6 +366,12 @@ pub fn linearization_columns<F: FftField>(
     h.insert(Index(GateType::ForeignFieldMul));
     h.insert(Index(GateType::Xor16));
     h.insert(Index(GateType::Rot64));
+    for gate_type in turshi::circuit_gates() {
+        h.insert(Index(gate_type));
+    }
+    for gate_type in poseidon2::circuit_gates() {
+        h.insert(Index(gate_type));
+    }
 
     // lookup selectors
     h.insert(LookupRuntimeSelector);
@@ -332,6 +379,7 @@ pub fn linearization_columns<F: FftField>(
     h.insert(LookupKindIndex(LookupPattern::Lookup));
     h.insert(LookupKindIndex(LookupPattern::RangeCheck));
     h.insert(LookupKindIndex(LookupPattern::ForeignFieldMul));
+    h.insert(LookupKindIndex(LookupPattern::VectorLookup));
 
     h
 }
@@ -90,6 +90,10 @@ impl<Fr: PrimeField> FrSponge<Fr> for DefaultFrSponge<Fr, SC> {
             lookup_gate_lookup_selector,
             range_check_lookup_selector,
             foreign_field_mul_lookup_selector,
+            cairo_selectors,
+            vector_lookup_selector,
+            lookup_multiplicities,
+            poseidon2_selectors,
         } = e;
 
         let mut points = vec![
@@ -155,6 +159,18 @@ impl<Fr: PrimeField> FrSponge<Fr> for DefaultFrSponge<Fr, SC> {
         {
             points.push(foreign_field_mul_lookup_selector)
         }
+        if let Some(cairo_selectors) = cairo_selectors.as_ref() {
+            points.extend(cairo_selectors)
+        }
+        if let Some(vector_lookup_selector) = vector_lookup_selector.as_ref() {
+            points.push(vector_lookup_selector)
+        }
+        if let Some(lookup_multiplicities) = lookup_multiplicities.as_ref() {
+            points.push(lookup_multiplicities)
+        }
+        if let Some(poseidon2_selectors) = poseidon2_selectors.as_ref() {
+            points.extend(poseidon2_selectors)
+        }
 
         points.into_iter().for_each(|p| {
             self.sponge.absorb(&p.zeta);
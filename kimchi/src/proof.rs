@@ -108,6 +108,18 @@ pub struct ProofEvaluations<Evals> {
     /// evaluation of the ForeignFieldMul range check pattern selector
     /// polynomial
     pub foreign_field_mul_lookup_selector: Option<Evals>,
+
+    /// evaluations of the Cairo selector polynomials, in the order claim,
+    /// instruction, flags and transition
+    pub cairo_selectors: Option<[Evals; 4]>,
+    /// evaluation of the VectorLookup pattern selector polynomial
+    pub vector_lookup_selector: Option<Evals>,
+    /// evaluation of the lookup multiplicities polynomial of the logUp
+    /// argument
+    pub lookup_multiplicities: Option<Evals>,
+    /// evaluations of the Poseidon2 selector polynomials, in the order
+    /// external and internal rounds
+    pub poseidon2_selectors: Option<[Evals; 2]>,
 }
 
 /// Commitments linked to the lookup feature
@@ -121,6 +133,9 @@ pub struct LookupCommitments<G: AffineRepr> {
     pub aggreg: PolyComm<G>,
     /// Optional commitment to concatenated runtime tables
     pub runtime: Option<PolyComm<G>>,
+    /// Commitment to the multiplicities of the table entries, in place of
+    /// the sorted polynomials when the lookups use the logUp argument
+    pub multiplicities: Option<PolyComm<G>>,
 }
 
 /// All the commitments that the prover creates as part of the proof.
@@ -230,6 +245,10 @@ impl<Eval> ProofEvaluations<Eval> {
             lookup_gate_lookup_selector,
             range_check_lookup_selector,
             foreign_field_mul_lookup_selector,
+            cairo_selectors,
+            vector_lookup_selector,
+            lookup_multiplicities,
+            poseidon2_selectors,
         } = self;
         ProofEvaluations {
             public: public.map(f),
@@ -258,6 +277,10 @@ impl<Eval> ProofEvaluations<Eval> {
             lookup_gate_lookup_selector: lookup_gate_lookup_selector.map(f),
             range_check_lookup_selector: range_check_lookup_selector.map(f),
             foreign_field_mul_lookup_selector: foreign_field_mul_lookup_selector.map(f),
+            cairo_selectors: cairo_selectors.map(|x| x.map(f)),
+            vector_lookup_selector: vector_lookup_selector.map(f),
+            lookup_multiplicities: lookup_multiplicities.map(f),
+            poseidon2_selectors: poseidon2_selectors.map(|x| x.map(f)),
         }
     }
 
@@ -289,6 +312,10 @@ impl<Eval> ProofEvaluations<Eval> {
             lookup_gate_lookup_selector,
             range_check_lookup_selector,
             foreign_field_mul_lookup_selector,
+            cairo_selectors,
+            vector_lookup_selector,
+            lookup_multiplicities,
+            poseidon2_selectors,
         } = self;
         ProofEvaluations {
             public: public.as_ref().map(f),
@@ -349,6 +376,10 @@ impl<Eval> ProofEvaluations<Eval> {
             lookup_gate_lookup_selector: lookup_gate_lookup_selector.as_ref().map(f),
             range_check_lookup_selector: range_check_lookup_selector.as_ref().map(f),
             foreign_field_mul_lookup_selector: foreign_field_mul_lookup_selector.as_ref().map(f),
+            cairo_selectors: cairo_selectors.as_ref().map(|x| x.each_ref().map(f)),
+            vector_lookup_selector: vector_lookup_selector.as_ref().map(f),
+            lookup_multiplicities: lookup_multiplicities.as_ref().map(f),
+            poseidon2_selectors: poseidon2_selectors.as_ref().map(|x| x.each_ref().map(f)),
         }
     }
 }
@@ -436,6 +467,10 @@ impl<F: Zero + Copy> ProofEvaluations<PointEvaluations<F>> {
             lookup_gate_lookup_selector: None,
             range_check_lookup_selector: None,
             foreign_field_mul_lookup_selector: None,
+            cairo_selectors: None,
+            vector_lookup_selector: None,
+            lookup_multiplicities: None,
+            poseidon2_selectors: None,
         }
     }
 }
@@ -467,8 +502,12 @@ impl<F> ProofEvaluations<F> {
             Column::LookupKindIndex(LookupPattern::ForeignFieldMul) => {
                 self.foreign_field_mul_lookup_selector.as_ref()
             }
+            Column::LookupKindIndex(LookupPattern::VectorLookup) => {
+                self.vector_lookup_selector.as_ref()
+            }
             Column::LookupRuntimeSelector => self.runtime_lookup_table_selector.as_ref(),
             Column::LookupRuntimeTable => self.runtime_lookup_table.as_ref(),
+            Column::LookupMultiplicities => self.lookup_multiplicities.as_ref(),
             Column::Index(GateType::Generic) => Some(&self.generic_selector),
             Column::Index(GateType::Poseidon) => Some(&self.poseidon_selector),
             Column::Index(GateType::CompleteAdd) => Some(&self.complete_add_selector),
@@ -481,6 +520,20 @@ impl<F> ProofEvaluations<F> {
             Column::Index(GateType::ForeignFieldMul) => self.foreign_field_mul_selector.as_ref(),
             Column::Index(GateType::Xor16) => self.xor_selector.as_ref(),
             Column::Index(GateType::Rot64) => self.rot_selector.as_ref(),
+            Column::Index(GateType::CairoClaim) => self.cairo_selectors.as_ref().map(|x| &x[0]),
+            Column::Index(GateType::CairoInstruction) => {
+                self.cairo_selectors.as_ref().map(|x| &x[1])
+            }
+            Column::Index(GateType::CairoFlags) => self.cairo_selectors.as_ref().map(|x| &x[2]),
+            Column::Index(GateType::CairoTransition) => {
+                self.cairo_selectors.as_ref().map(|x| &x[3])
+            }
+            Column::Index(GateType::Poseidon2External) => {
+                self.poseidon2_selectors.as_ref().map(|x| &x[0])
+            }
+            Column::Index(GateType::Poseidon2Internal) => {
+                self.poseidon2_selectors.as_ref().map(|x| &x[1])
+            }
             Column::Index(_) => None,
             Column::Coefficient(i) => Some(&self.coefficients[i]),
             Column::Permutation(i) => Some(&self.s[i]),
@@ -993,6 +1046,10 @@ pub mod caml {
                 foreign_field_mul_lookup_selector: cpe
                     .foreign_field_mul_lookup_selector
                     .map(|x| x.map(&|x| x.iter().map(|x| x.clone().into()).collect())),
+                cairo_selectors: None,
+                vector_lookup_selector: None,
+                lookup_multiplicities: None,
+                poseidon2_selectors: None,
             }
         }
     }
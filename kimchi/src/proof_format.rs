@@ -0,0 +1,68 @@
+//! This module implements the common proof interface of
+//! [o1_utils::proof] for kimchi proofs over the Pasta curves with the IPA
+//! commitment scheme, so that they can be verified through a
+//! [o1_utils::proof::ProofRegistry].
+
+use crate::{
+    curve::KimchiCurve, proof::ProverProof, verifier::verify, verifier_index::VerifierIndex,
+};
+use ark_ff::PrimeField;
+use mina_curves::pasta::{Fp, Fq, Pallas, PallasParameters, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use o1_utils::proof::{Proof, ProofError, ProofFormat};
+use poly_commitment::ipa::OpeningProof;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+/// The statement of a kimchi proof, which is the public input of the circuit
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicInput<F: PrimeField>(
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")] pub Vec<F>,
+);
+
+/// The data needed to verify kimchi proofs of a circuit
+pub struct KimchiVerifier<G: KimchiCurve>
+where
+    G::BaseField: PrimeField,
+{
+    pub group_map: G::Map,
+    pub verifier_index: VerifierIndex<G, OpeningProof<G>>,
+}
+
+macro_rules! impl_kimchi_proof {
+    ($curve:ty, $params:ty, $scalar:ty, $name:literal) => {
+        impl Proof for ProverProof<$curve, OpeningProof<$curve>> {
+            const FORMAT: ProofFormat = ProofFormat::new($name, 2);
+
+            type Statement = PublicInput<$scalar>;
+
+            type Verifier = KimchiVerifier<$curve>;
+
+            fn verify(
+                &self,
+                verifier: &Self::Verifier,
+                statement: &Self::Statement,
+            ) -> Result<(), ProofError> {
+                verify::<
+                    $curve,
+                    DefaultFqSponge<$params, PlonkSpongeConstantsKimchi>,
+                    DefaultFrSponge<$scalar, PlonkSpongeConstantsKimchi>,
+                    OpeningProof<$curve>,
+                >(
+                    &verifier.group_map,
+                    &verifier.verifier_index,
+                    self,
+                    &statement.0,
+                )
+                .map_err(|e| ProofError::Invalid(e.to_string()))
+            }
+        }
+    };
+}
+
+impl_kimchi_proof!(Vesta, VestaParameters, Fp, "kimchi-vesta-ipa");
+impl_kimchi_proof!(Pallas, PallasParameters, Fq, "kimchi-pallas-ipa");
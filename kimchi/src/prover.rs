@@ -16,8 +16,10 @@ use crate::{
             foreign_field_mul::{self, circuitgates::ForeignFieldMul},
             generic, permutation,
             poseidon::Poseidon,
+            poseidon2,
             range_check::circuitgates::{RangeCheck0, RangeCheck1},
             rot::Rot64,
+            turshi,
             varbasemul::VarbaseMul,
             xor::Xor16,
         },
@@ -31,7 +33,7 @@ use crate::{
         LookupCommitments, PointEvaluations, ProofEvaluations, ProverCommitments, ProverProof,
         RecursionChallenge,
     },
-    prover_index::ProverIndex,
+    prover_index::{ProverIndex, ProverMemoryMode},
     verifier_index::VerifierIndex,
 };
 use ark_ff::{FftField, Field, One, PrimeField, UniformRand, Zero};
@@ -53,6 +55,7 @@ use poly_commitment::{
 use rand_core::{CryptoRng, RngCore};
 use rayon::prelude::*;
 use std::collections::HashMap;
+use tracing::{debug_span, instrument};
 
 /// The result of a proof creation or verification.
 type Result<T> = core::result::Result<T, ProverError>;
@@ -74,6 +77,35 @@ macro_rules! check_constraint {
     }};
 }
 
+/// Samples the blinders of a commitment in `num_chunks` chunks: random
+/// blinders if `hiding`, and zero blinders otherwise. They are sampled ahead of
+/// the commitment, which can then run alongside other computations.
+fn sample_blinders<F: Field>(
+    num_chunks: usize,
+    hiding: bool,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> PolyComm<F> {
+    PolyComm::new(
+        (0..num_chunks)
+            .map(|_| if hiding { F::rand(rng) } else { F::zero() })
+            .collect(),
+    )
+}
+
+/// Commits to `plnm` with the SRS of `index`, masked with the blinders
+/// `blinders`, one per chunk
+fn commit<G: KimchiCurve, OpeningProof: OpenProof<G>>(
+    index: &ProverIndex<G, OpeningProof>,
+    plnm: &DensePolynomial<G::ScalarField>,
+    blinders: &PolyComm<G::ScalarField>,
+) -> Result<BlindedCommitment<G>> {
+    let commitment = index.srs.commit_non_hiding(plnm, blinders.len());
+    index
+        .srs
+        .mask_custom(commitment, blinders)
+        .map_err(ProverError::WrongBlinders)
+}
+
 /// Contains variables needed for lookup in the prover algorithm.
 #[derive(Default)]
 struct LookupContext<G, F>
@@ -106,6 +138,12 @@ where
     aggreg_comm: Option<BlindedCommitment<G>>,
     aggreg8: Option<Evaluations<F, D<F>>>,
 
+    /// The multiplicities polynomial of the logUp argument in different forms
+    multiplicities: Option<Evaluations<F, D<F>>>,
+    multiplicities_coeffs: Option<DensePolynomial<F>>,
+    multiplicities_comm: Option<BlindedCommitment<G>>,
+    multiplicities8: Option<Evaluations<F, D<F>>>,
+
     // lookup-related evaluations
     /// evaluation of lookup aggregation polynomial
     pub lookup_aggregation_eval: Option<PointEvaluations<Vec<F>>>,
@@ -115,6 +153,8 @@ where
     pub lookup_sorted_eval: [Option<PointEvaluations<Vec<F>>>; 5],
     /// evaluation of runtime lookup table polynomial
     pub runtime_lookup_table_eval: Option<PointEvaluations<Vec<F>>>,
+    /// evaluation of lookup multiplicities polynomial
+    pub lookup_multiplicities_eval: Option<PointEvaluations<Vec<F>>>,
 
     /// Runtime table
     runtime_table: Option<DensePolynomial<F>>,
@@ -123,6 +163,21 @@ where
     runtime_second_col_d8: Option<Evaluations<F, D<F>>>,
 }
 
+impl<G: CommitmentCurve, F: FftField> LookupContext<G, F> {
+    /// Drops the evaluations over d8 of the lookup polynomials, and the
+    /// evaluations over d1 of the polynomials kept in coefficient form
+    fn drop_evaluations(&mut self) {
+        self.joint_lookup_table_d8 = None;
+        self.sorted = None;
+        self.sorted8 = None;
+        self.aggreg8 = None;
+        self.multiplicities = None;
+        self.multiplicities8 = None;
+        self.runtime_table_d8 = None;
+        self.runtime_second_col_d8 = None;
+    }
+}
+
 impl<G: KimchiCurve, OpeningProof: OpenProof<G>> ProverProof<G, OpeningProof>
 where
     G::BaseField: PrimeField,
@@ -168,6 +223,12 @@ where
     /// # Panics
     ///
     /// Will panic if `lookup_context.joint_lookup_table_d8` is None.
+    #[instrument(
+        name = "prove",
+        skip_all,
+        level = "debug",
+        fields(prover = "kimchi", domain_size = index.cs.domain.d1.size, columns = COLUMNS)
+    )]
     pub fn create_recursive<
         EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
         EFrSponge: FrSponge<G::ScalarField>,
@@ -185,21 +246,29 @@ where
         VerifierIndex<G, OpeningProof>: Clone,
     {
         internal_tracing::checkpoint!(internal_traces; create_recursive);
+
+        // in low memory mode, the column evaluations are computed for this
+        // proof and dropped with it, see [ProverMemoryMode]
+        let index_for_proof = index.for_proof();
+        let index = &*index_for_proof;
+
         let d1_size = index.cs.domain.d1.size();
 
         let (_, endo_r) = G::endos();
 
-        let num_chunks = if d1_size < index.max_poly_size {
-            1
-        } else {
-            d1_size / index.max_poly_size
-        };
+        // the backend computing the FFTs of the prover, see
+        // [poly_commitment::backend]
+        let fft = index.srs.fft_backend();
+
+        let num_chunks = index.num_chunks();
 
         // Verify the circuit satisfiability by the computed witness (baring plookup constraints)
         // Catch mistakes before proof generation.
         if cfg!(debug_assertions) && !index.cs.disable_gates_checks {
             let public = witness[0][0..index.cs.public].to_vec();
-            index.verify(&witness, &public).expect("incorrect witness");
+            index
+                .verify(&witness, &public)
+                .unwrap_or_else(|e| panic!("incorrect witness: {e}"));
         }
 
         //~ 1. Ensure we have room in the witness for the zero-knowledge rows.
@@ -221,7 +290,8 @@ where
         //   for 1 chunk, `zero_knowledge_limit` is 2, and we need at least 3,
         //   thus the error should be raised and the message should say that the
         //   expected number of zero knowledge rows is 3 (hence the + 1).
-        if (index.cs.zk_rows as usize) <= zero_knowledge_limit {
+        let hiding = index.cs.zk_level.is_hiding();
+        if hiding && (index.cs.zk_rows as usize) <= zero_knowledge_limit {
             return Err(ProverError::NotZeroKnowledge(
                 zero_knowledge_limit + 1,
                 index.cs.zk_rows as usize,
@@ -243,9 +313,11 @@ where
             // padding
             w.extend(core::iter::repeat(G::ScalarField::zero()).take(length_padding));
 
-            // zk-rows
-            for row in w.iter_mut().rev().take(index.cs.zk_rows as usize) {
-                *row = <G::ScalarField as UniformRand>::rand(rng);
+            // zk-rows, left to zero if the proof does not hide the witness
+            if hiding {
+                for row in w.iter_mut().rev().take(index.cs.zk_rows as usize) {
+                    *row = <G::ScalarField as UniformRand>::rand(rng);
+                }
             }
         }
 
@@ -266,11 +338,12 @@ where
         //~    the polynomial that evaluates to $-p_i$ for the first `public_input_size` values of the domain,
         //~    and $0$ for the rest.
         let public = witness[0][0..index.cs.public].to_vec();
-        let public_poly = -Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(
-            public,
-            index.cs.domain.d1,
-        )
-        .interpolate();
+        let public_poly = -fft.interpolate(
+            Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(
+                public,
+                index.cs.domain.d1,
+            ),
+        );
 
         //~ 1. Commit (non-hiding) to the negated public input polynomial.
         let public_comm = index.srs.commit_non_hiding(&public_poly, num_chunks);
@@ -297,42 +370,68 @@ where
         //~    Note: since the witness is in evaluation form,
         //~    we can use the `commit_evaluation` optimization.
         internal_tracing::checkpoint!(internal_traces; commit_to_witness_columns);
-        // generate blinders if not given externally
+        let phase = debug_span!("commit_witness").entered();
+        // generate blinders if not given externally, or zero blinders if the
+        // proof does not hide the witness
+        let mut new_blinder = || {
+            let blinder = if hiding {
+                UniformRand::rand(rng)
+            } else {
+                G::ScalarField::zero()
+            };
+            PolyComm::new(vec![blinder; num_chunks])
+        };
         let blinders_final: Vec<PolyComm<G::ScalarField>> = match blinders {
-            None => (0..COLUMNS)
-                .map(|_| PolyComm::new(vec![UniformRand::rand(rng); num_chunks]))
-                .collect(),
+            None => (0..COLUMNS).map(|_| new_blinder()).collect(),
             Some(blinders_arr) => blinders_arr
                 .into_iter()
                 .map(|blinder_el| match blinder_el {
-                    None => PolyComm::new(vec![UniformRand::rand(rng); num_chunks]),
+                    None => new_blinder(),
                     Some(blinder_el_some) => blinder_el_some,
                 })
                 .collect(),
         };
-        let w_comm_opt_res: Vec<Result<_>> = witness
-            .clone()
-            .into_par_iter()
-            .zip(blinders_final.into_par_iter())
-            .map(|(witness, blinder)| {
-                let witness_eval =
-                    Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(
-                        witness,
-                        index.cs.domain.d1,
-                    );
-
-                // TODO: make this a function rather no? mask_with_custom()
-                let witness_com = index
-                    .srs
-                    .commit_evaluations_non_hiding(index.cs.domain.d1, &witness_eval);
-                let com = index
-                    .srs
-                    .mask_custom(witness_com, &blinder)
-                    .map_err(ProverError::WrongBlinders)?;
-
-                Ok(com)
-            })
-            .collect();
+        // The witness polynomials are interpolated alongside the commitments,
+        // on the cores left idle by their multi-scalar multiplications.
+        let (w_comm_opt_res, witness_poly): (Vec<Result<_>>, Vec<_>) = rayon::join(
+            || {
+                witness
+                    .par_iter()
+                    .zip(blinders_final.into_par_iter())
+                    .map(|(witness, blinder)| {
+                        let witness_eval =
+                            Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(
+                                witness.clone(),
+                                index.cs.domain.d1,
+                            );
+
+                        // TODO: make this a function rather no? mask_with_custom()
+                        let witness_com = index
+                            .srs
+                            .commit_evaluations_non_hiding(index.cs.domain.d1, &witness_eval);
+                        let com = index
+                            .srs
+                            .mask_custom(witness_com, &blinder)
+                            .map_err(ProverError::WrongBlinders)?;
+
+                        Ok(com)
+                    })
+                    .collect()
+            },
+            || {
+                witness
+                    .par_iter()
+                    .map(|w| {
+                        fft.interpolate(
+                            Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(
+                                w.clone(),
+                                index.cs.domain.d1,
+                            ),
+                        )
+                    })
+                    .collect()
+            },
+        );
 
         let w_comm_res: Result<Vec<BlindedCommitment<G>>> = w_comm_opt_res.into_iter().collect();
 
@@ -351,18 +450,9 @@ where
         //~    As mentioned above, we commit using the evaluations form rather than the coefficients
         //~    form so we can take advantage of the sparsity of the evaluations (i.e., there are many
         //~    0 entries and entries that have less-than-full-size field elemnts.)
-        let witness_poly: [DensePolynomial<G::ScalarField>; COLUMNS] = (0..COLUMNS)
-            .into_par_iter()
-            .map(|i| {
-                Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(
-                    witness[i].clone(),
-                    index.cs.domain.d1,
-                )
-                .interpolate()
-            })
-            .collect::<Vec<_>>()
+        let witness_poly: [DensePolynomial<G::ScalarField>; COLUMNS] = witness_poly
             .try_into()
-            .unwrap();
+            .expect("previous loop is of the correct length");
 
         let mut lookup_context = LookupContext::default();
 
@@ -378,38 +468,19 @@ where
                 "uses_runtime_tables": lcs.runtime_tables.is_some(),
             });
             //~~ * if using runtime table:
-            if let Some(cfg_runtime_tables) = &lcs.runtime_tables {
+            if lcs.runtime_tables.is_some() {
                 //~~~ * check that all the provided runtime tables have length and IDs that match the runtime table configuration of the index
                 //~~~   we expect the given runtime tables to be sorted as configured, this makes it easier afterwards
-                let expected_runtime: Vec<_> = cfg_runtime_tables
-                    .iter()
-                    .map(|rt| (rt.id, rt.len))
-                    .collect();
-                let runtime: Vec<_> = runtime_tables
-                    .iter()
-                    .map(|rt| (rt.id, rt.data.len()))
-                    .collect();
-                if expected_runtime != runtime {
-                    return Err(ProverError::RuntimeTablesInconsistent);
-                }
-
                 //~~~ * calculate the contribution to the second column of the lookup table
                 //~~~   (the runtime vector)
                 let (runtime_table_contribution, runtime_table_contribution_d8) = {
-                    let mut offset = lcs
-                        .runtime_table_offset
-                        .expect("runtime configuration missing offset");
-
-                    let mut evals = vec![G::ScalarField::zero(); d1_size];
-                    for rt in runtime_tables {
-                        let range = offset..(offset + rt.data.len());
-                        evals[range].copy_from_slice(&rt.data);
-                        offset += rt.data.len();
-                    }
+                    let mut evals = index.runtime_table_evaluations(runtime_tables)?;
 
-                    // zero-knowledge
-                    for e in evals.iter_mut().rev().take(index.cs.zk_rows as usize) {
-                        *e = <G::ScalarField as UniformRand>::rand(rng);
+                    // zero-knowledge, unless the runtime tables are public
+                    if !index.public_runtime_tables {
+                        for e in evals.iter_mut().rev().take(index.cs.zk_rows as usize) {
+                            *e = <G::ScalarField as UniformRand>::rand(rng);
+                        }
                     }
 
                     // get coeff and evaluation form
@@ -424,10 +495,20 @@ where
 
                 // commit the runtime polynomial
                 // (and save it to the proof)
-                let runtime_table_comm =
+                let runtime_table_comm = if index.public_runtime_tables {
+                    let commitment = index
+                        .srs
+                        .commit_non_hiding(&runtime_table_contribution, num_chunks);
+                    let blinders = commitment.map(|_| G::ScalarField::zero());
+                    BlindedCommitment {
+                        commitment,
+                        blinders,
+                    }
+                } else {
                     index
                         .srs
-                        .commit(&runtime_table_contribution, num_chunks, rng);
+                        .commit(&runtime_table_contribution, num_chunks, rng)
+                };
 
                 // absorb the commitment
                 absorb_commitment(&mut fq_sponge, &runtime_table_comm.commitment);
@@ -532,21 +613,27 @@ where
             // TODO: This interpolation is avoidable.
             let joint_lookup_table = joint_lookup_table_d8.interpolate_by_ref();
 
-            //~~ * Compute the sorted evaluations.
+            let log_up = lcs.configuration.lookup_info.features.log_up;
+
+            //~~ * Compute the sorted evaluations, unless the lookups use the logUp argument.
             // TODO: Once we switch to committing using lagrange commitments,
             // `witness` will be consumed when we interpolate, so interpolation will
             // have to moved below this.
-            let sorted: Vec<_> = lookup::constraints::sorted(
-                dummy_lookup_value,
-                &joint_lookup_table_d8,
-                index.cs.domain.d1,
-                &index.cs.gates,
-                &witness,
-                joint_combiner,
-                table_id_combiner,
-                &lcs.configuration.lookup_info,
-                index.cs.zk_rows as usize,
-            )?;
+            let sorted: Vec<_> = if log_up {
+                vec![]
+            } else {
+                lookup::constraints::sorted(
+                    dummy_lookup_value,
+                    &joint_lookup_table_d8,
+                    index.cs.domain.d1,
+                    &index.cs.gates,
+                    &witness,
+                    joint_combiner,
+                    table_id_combiner,
+                    &lcs.configuration.lookup_info,
+                    index.cs.zk_rows as usize,
+                )?
+            };
 
             //~~ * Randomize the last `EVALS` rows in each of the sorted polynomials
             //~~   in order to add zero-knowledge to the protocol.
@@ -581,6 +668,43 @@ where
                 .map(|v| v.evaluate_over_domain_by_ref(index.cs.domain.d8))
                 .collect();
 
+            //~~ * If the lookups use the logUp argument, compute the multiplicities of the
+            //~~   table entries, randomize their last `EVALS` rows, commit to them and
+            //~~   absorb the commitment with the Fq-Sponge.
+            if log_up {
+                let multiplicities = lookup::constraints::multiplicities(
+                    dummy_lookup_value,
+                    &joint_lookup_table_d8,
+                    index.cs.domain.d1,
+                    &index.cs.gates,
+                    &witness,
+                    joint_combiner,
+                    table_id_combiner,
+                    &lcs.configuration.lookup_info,
+                    index.cs.zk_rows as usize,
+                )?;
+                let multiplicities = lookup::constraints::zk_patch(
+                    multiplicities,
+                    index.cs.domain.d1,
+                    index.cs.zk_rows as usize,
+                    rng,
+                );
+                let multiplicities_comm =
+                    index
+                        .srs
+                        .commit_evaluations(index.cs.domain.d1, &multiplicities, rng);
+                absorb_commitment(&mut fq_sponge, &multiplicities_comm.commitment);
+
+                let multiplicities_coeffs = multiplicities.interpolate_by_ref();
+                let multiplicities8 =
+                    multiplicities_coeffs.evaluate_over_domain_by_ref(index.cs.domain.d8);
+
+                lookup_context.multiplicities = Some(multiplicities);
+                lookup_context.multiplicities_coeffs = Some(multiplicities_coeffs);
+                lookup_context.multiplicities_comm = Some(multiplicities_comm);
+                lookup_context.multiplicities8 = Some(multiplicities8);
+            }
+
             lookup_context.joint_combiner = Some(joint_combiner);
             lookup_context.sorted = Some(sorted);
             lookup_context.sorted_coeffs = Some(sorted_coeffs);
@@ -590,6 +714,9 @@ where
             lookup_context.joint_lookup_table = Some(joint_lookup_table);
         }
 
+        drop(phase);
+        let phase = debug_span!("permutation").entered();
+
         //~ 1. Sample $\beta$ with the Fq-Sponge.
         let beta = fq_sponge.challenge();
 
@@ -598,24 +725,42 @@ where
 
         //~ 1. If using lookup:
         if let Some(lcs) = lookup_constraint_system {
-            //~~ * Compute the lookup aggregation polynomial.
+            //~~ * Compute the lookup aggregation polynomial,
+            //~~   or the running sum of the logUp argument.
             let joint_lookup_table_d8 = lookup_context.joint_lookup_table_d8.as_ref().unwrap();
 
-            let aggreg = lookup::constraints::aggregation::<_, G::ScalarField>(
-                lookup_context.dummy_lookup_value.unwrap(),
-                joint_lookup_table_d8,
-                index.cs.domain.d1,
-                &index.cs.gates,
-                &witness,
-                &lookup_context.joint_combiner.unwrap(),
-                &lookup_context.table_id_combiner.unwrap(),
-                beta,
-                gamma,
-                lookup_context.sorted.as_ref().unwrap(),
-                rng,
-                &lcs.configuration.lookup_info,
-                index.cs.zk_rows as usize,
-            )?;
+            let aggreg = if let Some(multiplicities) = lookup_context.multiplicities.as_ref() {
+                lookup::constraints::log_up_aggregation::<_, G::ScalarField>(
+                    lookup_context.dummy_lookup_value.unwrap(),
+                    joint_lookup_table_d8,
+                    index.cs.domain.d1,
+                    &index.cs.gates,
+                    &witness,
+                    &lookup_context.joint_combiner.unwrap(),
+                    &lookup_context.table_id_combiner.unwrap(),
+                    gamma,
+                    multiplicities,
+                    rng,
+                    &lcs.configuration.lookup_info,
+                    index.cs.zk_rows as usize,
+                )?
+            } else {
+                lookup::constraints::aggregation::<_, G::ScalarField>(
+                    lookup_context.dummy_lookup_value.unwrap(),
+                    joint_lookup_table_d8,
+                    index.cs.domain.d1,
+                    &index.cs.gates,
+                    &witness,
+                    &lookup_context.joint_combiner.unwrap(),
+                    &lookup_context.table_id_combiner.unwrap(),
+                    beta,
+                    gamma,
+                    lookup_context.sorted.as_ref().unwrap(),
+                    rng,
+                    &lcs.configuration.lookup_info,
+                    index.cs.zk_rows as usize,
+                )?
+            };
 
             //~~ * Commit to the aggregation polynomial.
             let aggreg_comm = index
@@ -641,9 +786,25 @@ where
         //~ 1. Compute the permutation aggregation polynomial $z$.
         internal_tracing::checkpoint!(internal_traces; z_permutation_aggregation_polynomial);
         let z_poly = index.perm_aggreg(&witness, &beta, &gamma, rng)?;
+        // the witness is only used in coefficient form from now on
+        drop(witness);
 
         //~ 1. Commit (hidding) to the permutation aggregation polynomial $z$.
-        let z_comm = index.srs.commit(&z_poly, num_chunks, rng);
+        //
+        // The witness and z are evaluated over the larger domains of the
+        // quotient alongside the commitment, as they do not depend on alpha.
+        let z_blinders = sample_blinders(num_chunks, hiding, rng);
+        let (z_comm, lagrange) = rayon::join(
+            || commit(index, &z_poly, &z_blinders),
+            || {
+                internal_tracing::checkpoint!(internal_traces; eval_witness_polynomials_over_domains);
+                match index.memory_mode {
+                    ProverMemoryMode::Standard => index.cs.evaluate(&witness_poly, &z_poly),
+                    ProverMemoryMode::Low => index.cs.evaluate_low_memory(&witness_poly, &z_poly),
+                }
+            },
+        );
+        let z_comm = z_comm?;
 
         //~ 1. Absorb the permutation aggregation polynomial $z$ with the Fq-Sponge.
         absorb_commitment(&mut fq_sponge, &z_comm.commitment);
@@ -655,9 +816,12 @@ where
         let alpha: G::ScalarField = alpha_chal.to_field(endo_r);
 
         //~ 1. TODO: instantiate alpha?
-        let mut all_alphas = index.powers_of_alpha.clone();
+        let mut all_alphas = index.powers_of_alpha().clone();
         all_alphas.instantiate(alpha);
 
+        drop(phase);
+        let phase = debug_span!("quotient").entered();
+
         //~ 1. Compute the quotient polynomial (the $t$ in $f = Z_H \cdot t$).
         //~    The quotient polynomial is computed by adding all these polynomials together:
         //~~ * the combined constraints for all the gates
@@ -676,13 +840,12 @@ where
                 table: joint_lookup_table_d8,
                 runtime_selector: lcs.runtime_selector.as_ref(),
                 runtime_table: lookup_context.runtime_table_d8.as_ref(),
+                multiplicities: lookup_context.multiplicities8.as_ref(),
             })
         } else {
             None
         };
 
-        internal_tracing::checkpoint!(internal_traces; eval_witness_polynomials_over_domains);
-        let lagrange = index.cs.evaluate(&witness_poly, &z_poly);
         internal_tracing::checkpoint!(internal_traces; compute_index_evals);
         let env = {
             let mut index_evals = HashMap::new();
@@ -722,6 +885,14 @@ where
                 index_evals.insert(GateType::Rot64, selector);
             }
 
+            if let Some(selectors) = &column_evaluations.cairo_selectors8 {
+                index_evals.extend(turshi::circuit_gates().into_iter().zip(selectors));
+            }
+
+            if let Some(selectors) = &column_evaluations.poseidon2_selectors8 {
+                index_evals.extend(poseidon2::circuit_gates().into_iter().zip(selectors));
+            }
+
             let mds = &G::sponge_params().mds;
             Environment {
                 constants: Constants {
@@ -794,6 +965,8 @@ where
                     column_evaluations.foreign_field_mul_selector8.is_some();
                 let xor_enabled = column_evaluations.xor_selector8.is_some();
                 let rot_enabled = column_evaluations.rot_selector8.is_some();
+                let cairo_enabled = column_evaluations.cairo_selectors8.is_some();
+                let poseidon2_enabled = column_evaluations.poseidon2_selectors8.is_some();
 
                 for gate in [
                     (
@@ -818,6 +991,14 @@ where
                     (&Xor16::default(), xor_enabled),
                     // Rot gate
                     (&Rot64::default(), rot_enabled),
+                    // Cairo gates
+                    (&turshi::Claim::default(), cairo_enabled),
+                    (&turshi::Instruction::default(), cairo_enabled),
+                    (&turshi::Flags::default(), cairo_enabled),
+                    (&turshi::Transition::default(), cairo_enabled),
+                    // Poseidon2 gates
+                    (&poseidon2::Poseidon2External::default(), poseidon2_enabled),
+                    (&poseidon2::Poseidon2Internal::default(), poseidon2_enabled),
                 ]
                 .into_iter()
                 .filter_map(|(gate, is_enabled)| if is_enabled { Some(gate) } else { None })
@@ -868,7 +1049,7 @@ where
             }
 
             // public polynomial
-            let mut f = t4.interpolate() + t8.interpolate();
+            let mut f = fft.interpolate(t4) + fft.interpolate(t8);
             f += &public_poly;
 
             // divide contributions with vanishing polynomial
@@ -884,11 +1065,18 @@ where
         };
 
         //~ 1. commit (hiding) to the quotient polynomial $t$
-        let t_comm = { index.srs.commit(&quotient_poly, 7 * num_chunks, rng) };
+        let t_comm = commit(
+            index,
+            &quotient_poly,
+            &sample_blinders(7 * num_chunks, hiding, rng),
+        )?;
 
         //~ 1. Absorb the commitment of the quotient polynomial with the Fq-Sponge.
         absorb_commitment(&mut fq_sponge, &t_comm.commitment);
 
+        drop(phase);
+        let phase = debug_span!("evaluations").entered();
+
         //~ 1. Sample $\zeta'$ with the Fq-Sponge.
         let zeta_chal = ScalarChallenge(fq_sponge.challenge());
 
@@ -948,6 +1136,18 @@ where
                         zeta_omega: runtime_table.evaluate_chunks(zeta_omega),
                     }
                 });
+            //~~ * the multiplicities polynomial, if the lookups use the logUp argument
+            lookup_context.lookup_multiplicities_eval = lookup_context
+                .multiplicities_coeffs
+                .as_ref()
+                .map(|multiplicities| {
+                    let multiplicities =
+                        multiplicities.to_chunked_polynomial(num_chunks, index.max_poly_size);
+                    PointEvaluations {
+                        zeta: multiplicities.evaluate_chunks(zeta),
+                        zeta_omega: multiplicities.evaluate_chunks(zeta_omega),
+                    }
+                });
         }
 
         //~ 1. Chunk evaluate the following polynomials at both $\zeta$ and $\zeta \omega$:
@@ -990,34 +1190,28 @@ where
 
         internal_tracing::checkpoint!(internal_traces; chunk_eval_zeta_omega_poly);
         let chunked_evals = ProofEvaluations::<PointEvaluations<Vec<G::ScalarField>>> {
-            public: {
-                let chunked = public_poly.to_chunked_polynomial(num_chunks, index.max_poly_size);
-                Some(PointEvaluations {
-                    zeta: chunked.evaluate_chunks(zeta),
-                    zeta_omega: chunked.evaluate_chunks(zeta_omega),
-                })
-            },
+            public: Some(PointEvaluations {
+                zeta: public_poly.eval_chunks(num_chunks, index.max_poly_size, zeta),
+                zeta_omega: public_poly.eval_chunks(num_chunks, index.max_poly_size, zeta_omega),
+            }),
             s: array::from_fn(|i| {
                 chunked_evals_for_evaluations(&column_evaluations.permutation_coefficients8[i])
             }),
             coefficients: array::from_fn(|i| {
                 chunked_evals_for_evaluations(&column_evaluations.coefficients8[i])
             }),
-            w: array::from_fn(|i| {
-                let chunked =
-                    witness_poly[i].to_chunked_polynomial(num_chunks, index.max_poly_size);
-                PointEvaluations {
-                    zeta: chunked.evaluate_chunks(zeta),
-                    zeta_omega: chunked.evaluate_chunks(zeta_omega),
-                }
+            w: array::from_fn(|i| PointEvaluations {
+                zeta: witness_poly[i].eval_chunks(num_chunks, index.max_poly_size, zeta),
+                zeta_omega: witness_poly[i].eval_chunks(
+                    num_chunks,
+                    index.max_poly_size,
+                    zeta_omega,
+                ),
             }),
 
-            z: {
-                let chunked = z_poly.to_chunked_polynomial(num_chunks, index.max_poly_size);
-                PointEvaluations {
-                    zeta: chunked.evaluate_chunks(zeta),
-                    zeta_omega: chunked.evaluate_chunks(zeta_omega),
-                }
+            z: PointEvaluations {
+                zeta: z_poly.eval_chunks(num_chunks, index.max_poly_size, zeta),
+                zeta_omega: z_poly.eval_chunks(num_chunks, index.max_poly_size, zeta_omega),
             },
 
             lookup_aggregation: lookup_context.lookup_aggregation_eval.take(),
@@ -1059,6 +1253,14 @@ where
                 .rot_selector8
                 .as_ref()
                 .map(chunked_evals_for_selector),
+            cairo_selectors: column_evaluations
+                .cairo_selectors8
+                .as_ref()
+                .map(|selectors| selectors.each_ref().map(chunked_evals_for_selector)),
+            poseidon2_selectors: column_evaluations
+                .poseidon2_selectors8
+                .as_ref()
+                .map(|selectors| selectors.each_ref().map(chunked_evals_for_selector)),
 
             runtime_lookup_table_selector: lookup_constraint_system.as_ref().and_then(|lcs| {
                 lcs.runtime_selector
@@ -1089,6 +1291,13 @@ where
                     .as_ref()
                     .map(chunked_evals_for_selector)
             }),
+            vector_lookup_selector: lookup_constraint_system.as_ref().and_then(|lcs| {
+                lcs.lookup_selectors
+                    .vector_lookup
+                    .as_ref()
+                    .map(chunked_evals_for_selector)
+            }),
+            lookup_multiplicities: lookup_context.lookup_multiplicities_eval.take(),
         };
 
         let zeta_to_srs_len = zeta.pow([index.max_poly_size as u64]);
@@ -1122,12 +1331,16 @@ where
                 // the circuit polynomial
                 let f = {
                     let (_lin_constant, mut lin) =
-                        index.linearization.to_polynomial(&env, zeta, &evals);
+                        index.linearization().to_polynomial(&env, zeta, &evals);
                     lin += &f;
                     lin.interpolate()
                 };
 
                 drop(env);
+                // the evaluations over the larger domains are only used by the
+                // quotient and the linearization
+                drop(lagrange);
+                lookup_context.drop_evaluations();
 
                 // see https://o1-labs.github.io/proof-systems/kimchi/maller_15.html#the-prover-side
                 f.to_chunked_polynomial(num_chunks, index.max_poly_size)
@@ -1138,6 +1351,8 @@ where
                 .to_chunked_polynomial(7 * num_chunks, index.max_poly_size)
                 .linearize(zeta_to_srs_len);
 
+            drop(quotient_poly);
+
             &f_chunked - &t_chunked.scale(zeta_to_domain_size - G::ScalarField::one())
         };
 
@@ -1322,6 +1537,19 @@ where
         if let Some(rot_selector8) = &column_evaluations.rot_selector8 {
             polynomials.push((evaluations_form(rot_selector8), non_hiding(num_chunks)));
         }
+        if let Some(cairo_selectors8) = &column_evaluations.cairo_selectors8 {
+            for cairo_selector8 in cairo_selectors8 {
+                polynomials.push((evaluations_form(cairo_selector8), non_hiding(num_chunks)));
+            }
+        }
+        if let Some(poseidon2_selectors8) = &column_evaluations.poseidon2_selectors8 {
+            for poseidon2_selector8 in poseidon2_selectors8 {
+                polynomials.push((
+                    evaluations_form(poseidon2_selector8),
+                    non_hiding(num_chunks),
+                ));
+            }
+        }
 
         //~~ * optionally, the runtime table
         //~ 1. if using lookup:
@@ -1399,27 +1627,53 @@ where
             if let Some(runtime_lookup_table_selector) = &lcs.runtime_selector {
                 polynomials.push((
                     evaluations_form(runtime_lookup_table_selector),
-                    non_hiding(1),
+                    non_hiding(num_chunks),
                 ))
             }
             if let Some(xor_lookup_selector) = &lcs.lookup_selectors.xor {
-                polynomials.push((evaluations_form(xor_lookup_selector), non_hiding(1)))
+                polynomials.push((
+                    evaluations_form(xor_lookup_selector),
+                    non_hiding(num_chunks),
+                ))
             }
             if let Some(lookup_gate_selector) = &lcs.lookup_selectors.lookup {
-                polynomials.push((evaluations_form(lookup_gate_selector), non_hiding(1)))
+                polynomials.push((
+                    evaluations_form(lookup_gate_selector),
+                    non_hiding(num_chunks),
+                ))
             }
             if let Some(range_check_lookup_selector) = &lcs.lookup_selectors.range_check {
-                polynomials.push((evaluations_form(range_check_lookup_selector), non_hiding(1)))
+                polynomials.push((
+                    evaluations_form(range_check_lookup_selector),
+                    non_hiding(num_chunks),
+                ))
             }
             if let Some(foreign_field_mul_lookup_selector) = &lcs.lookup_selectors.ffmul {
                 polynomials.push((
                     evaluations_form(foreign_field_mul_lookup_selector),
-                    non_hiding(1),
+                    non_hiding(num_chunks),
                 ))
             }
+            if let Some(vector_lookup_selector) = &lcs.lookup_selectors.vector_lookup {
+                polynomials.push((
+                    evaluations_form(vector_lookup_selector),
+                    non_hiding(num_chunks),
+                ))
+            }
+
+            //~~ * the multiplicities polynomial, if the lookups use the logUp argument
+            if let Some(multiplicities) = lookup_context.multiplicities_coeffs.as_ref() {
+                let multiplicities_comm = lookup_context.multiplicities_comm.as_ref().unwrap();
+                polynomials.push((
+                    coefficients_form(multiplicities),
+                    multiplicities_comm.blinders.clone(),
+                ));
+            }
         }
 
         //~ 1. Create an aggregated evaluation proof for all of these polynomials at $\zeta$ and $\zeta\omega$ using $u$ and $v$.
+        drop(phase);
+        let _phase = debug_span!("opening").entered();
         internal_tracing::checkpoint!(internal_traces; create_aggregated_ipa);
         let proof = OpenProof::open(
             &*index.srs,
@@ -1439,6 +1693,7 @@ where
                 aggreg: a.commitment,
                 sorted: s.iter().map(|c| c.commitment.clone()).collect(),
                 runtime: lookup_context.runtime_table_comm.map(|x| x.commitment),
+                multiplicities: lookup_context.multiplicities_comm.map(|x| x.commitment),
             });
 
         let proof = Self {
@@ -1584,6 +1839,7 @@ pub mod caml {
                 aggreg,
                 sorted,
                 runtime,
+                multiplicities: _,
             }: LookupCommitments<G>,
         ) -> Self {
             Self {
@@ -1610,6 +1866,7 @@ pub mod caml {
                 aggreg: aggreg.into(),
                 sorted: sorted.into_iter().map(Into::into).collect(),
                 runtime: runtime.map(Into::into),
+                multiplicities: None,
             }
         }
     }
@@ -4,20 +4,48 @@ use crate::{
     alphas::Alphas,
     circuits::{
         berkeley_columns::{BerkeleyChallengeTerm, Column},
-        constraints::{ColumnEvaluations, ConstraintSystem},
+        constraints::{num_chunks, ColumnEvaluations, ConstraintSystem},
         expr::{Linearization, PolishToken},
+        gate::Circuit,
+        lookup::{
+            lookups::{LookupInfo, LookupPatterns},
+            runtime_tables::{RuntimeTable, RuntimeTableSpec},
+        },
+        wires::{COLUMNS, PERMUTS},
     },
     curve::KimchiCurve,
+    error::{ProverError, ProverIndexCacheError},
     linearization::expr_linearization,
     o1_utils::lazy_cache::LazyCache,
     verifier_index::VerifierIndex,
 };
-use ark_ff::PrimeField;
+use ark_ff::{PrimeField, Zero};
+use ark_poly::{EvaluationDomain, Evaluations};
+use blake2::{Blake2s256, Digest};
 use mina_poseidon::FqSponge;
-use poly_commitment::{OpenProof, SRS as _};
+use o1_utils::hasher::CryptoDigest;
+use once_cell::sync::OnceCell;
+use poly_commitment::{commitment::PolyComm, OpenProof, SRS as _};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::serde_as;
-use std::sync::Arc;
+use std::{
+    borrow::Cow,
+    io::{Read, Write},
+    sync::Arc,
+};
+
+/// The symbolic linearization of a circuit, which can compile to concrete types
+/// once certain values are learned in the protocol
+pub type CircuitLinearization<F> =
+    Linearization<Vec<PolishToken<F, Column, BerkeleyChallengeTerm>>, Column>;
+
+/// The linearization of a circuit, with the mapping between the powers of
+/// alpha and the constraints of the circuit
+pub type LinearizationAndAlphas<F> = (CircuitLinearization<F>, Alphas<F>);
+
+/// The magic bytes starting a prover index written by
+/// [ProverIndex::serialize_to]
+pub const PROVER_INDEX_MAGIC: [u8; 4] = *b"KPIX";
 
 /// The index used by the prover
 #[serde_as]
@@ -28,14 +56,10 @@ pub struct ProverIndex<G: KimchiCurve, OpeningProof: OpenProof<G>> {
     #[serde(bound = "ConstraintSystem<G::ScalarField>: Serialize + DeserializeOwned")]
     pub cs: Arc<ConstraintSystem<G::ScalarField>>,
 
-    /// The symbolic linearization of our circuit, which can compile to concrete types once certain values are learned in the protocol.
-    #[serde(skip)]
-    pub linearization:
-        Linearization<Vec<PolishToken<G::ScalarField, Column, BerkeleyChallengeTerm>>, Column>,
-
-    /// The mapping between powers of alpha and constraints
-    #[serde(skip)]
-    pub powers_of_alpha: Alphas<G::ScalarField>,
+    /// The symbolic linearization of our circuit and the mapping between powers of alpha and constraints,
+    /// see [Self::linearization] and [Self::powers_of_alpha]
+    #[serde(skip, default = "default_linearization")]
+    pub linearization: Arc<LazyCache<LinearizationAndAlphas<G::ScalarField>>>,
 
     /// polynomial commitment keys
     #[serde(skip)]
@@ -52,17 +76,84 @@ pub struct ProverIndex<G: KimchiCurve, OpeningProof: OpenProof<G>> {
     #[serde(skip)]
     pub verifier_index: Option<VerifierIndex<G, OpeningProof>>,
 
+    /// The verifier index computed on first use when [Self::verifier_index]
+    /// is not set, shared by the clones of this prover index
+    #[serde(skip)]
+    pub(crate) verifier_index_cache: Arc<OnceCell<VerifierIndex<G, OpeningProof>>>,
+
     /// The verifier index digest corresponding to this prover index
     #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
     pub verifier_index_digest: Option<G::BaseField>,
+
+    /// Whether the runtime tables are committed to without hiding, so that a
+    /// verifier knowing their content can check the commitment of a proof
+    /// against [Self::runtime_table_commitment]
+    #[serde(default)]
+    pub public_runtime_tables: bool,
+
+    /// How the prover trades time for memory, see [ProverMemoryMode]
+    #[serde(default)]
+    pub memory_mode: ProverMemoryMode,
 }
 //~spec:endcode
 
+/// How the prover trades proving time for memory, set with
+/// [ProverIndex::with_memory_mode]
+///
+/// In [ProverMemoryMode::Low], the prover index does not keep the evaluations
+/// of the fixed columns of the circuit over the larger domains: each proof
+/// recomputes them from the gates of the constraint system, which is cheaper
+/// than storing them, and drops them once it is done. The witness is only
+/// evaluated over the domain `d8` needed by the quotient, without the shifted
+/// copies of [ConstraintSystem::evaluate], which the constraints read with a
+/// row offset instead.
+///
+/// The estimated peak memory of a proof, see
+/// [ProverIndex::estimated_peak_memory], for a circuit with the default gates
+/// and no lookups is:
+///
+/// | rows | [ProverMemoryMode::Standard] | [ProverMemoryMode::Low] |
+/// |------|------------------------------|-------------------------|
+/// | 2^16 | 1.4 GB                       | 0.9 GB                  |
+/// | 2^17 | 2.8 GB                       | 1.8 GB                  |
+/// | 2^18 | 5.7 GB                       | 3.6 GB                  |
+/// | 2^19 | 11.3 GB                      | 7.2 GB                  |
+/// | 2^20 | 22.7 GB                      | 14.4 GB                 |
+///
+/// [ProverMemoryMode::Low] thus proves circuits of up to 2^20 rows on machines
+/// with 16 GB of memory, at the cost of the FFTs of the fixed columns in each
+/// proof. It does not change the proofs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProverMemoryMode {
+    /// The column evaluations are computed once and kept by the prover index
+    #[default]
+    Standard,
+    /// The column evaluations are recomputed for each proof, and the
+    /// intermediate polynomials are kept only for as long as they are used
+    Low,
+}
+
+/// Creates the cache of the evaluations of the fixed columns of `cs`, which
+/// are computed on first use
+fn column_evaluations_cache<F: PrimeField>(
+    cs: &Arc<ConstraintSystem<F>>,
+) -> LazyCache<ColumnEvaluations<F>> {
+    let cs = Arc::clone(cs);
+    LazyCache::new(move || cs.column_evaluations(&cs.evaluated_column_coefficients()))
+}
+
+fn default_linearization<F: PrimeField>() -> Arc<LazyCache<LinearizationAndAlphas<F>>> {
+    Arc::new(LazyCache::preinit(Default::default()))
+}
+
 impl<G: KimchiCurve, OpeningProof: OpenProof<G>> ProverIndex<G, OpeningProof>
 where
     G::BaseField: PrimeField,
 {
     /// this function compiles the index from constraints
+    ///
+    /// In `lazy_mode`, the column evaluations and the linearization are
+    /// computed on first use, see [Self::materialize].
     pub fn create(
         mut cs: ConstraintSystem<G::ScalarField>,
         endo_q: G::ScalarField,
@@ -72,30 +163,339 @@ where
         let max_poly_size = srs.max_poly_size();
         cs.endo = endo_q;
 
-        // pre-compute the linearization
-        let (linearization, powers_of_alpha) = expr_linearization(Some(&cs.feature_flags), true);
-
-        let evaluated_column_coefficients = cs.evaluated_column_coefficients();
+        // the linearization, computed on first use in lazy mode
+        let feature_flags = cs.feature_flags;
+        let linearization = LazyCache::new(move || expr_linearization(Some(&feature_flags), true));
 
         let cs = Arc::new(cs);
-        let cs_clone = Arc::clone(&cs);
-        let column_evaluations =
-            LazyCache::new(move || cs_clone.column_evaluations(&evaluated_column_coefficients));
+        let column_evaluations = column_evaluations_cache(&cs);
         if !lazy_mode {
             // precompute the values
             column_evaluations.get();
+            linearization.get();
         };
 
         ProverIndex {
             cs,
-            linearization,
-            powers_of_alpha,
+            linearization: Arc::new(linearization),
             srs,
             max_poly_size,
             column_evaluations: Arc::new(column_evaluations),
             verifier_index: None,
+            verifier_index_cache: Arc::new(OnceCell::new()),
             verifier_index_digest: None,
+            public_runtime_tables: false,
+            memory_mode: ProverMemoryMode::Standard,
+        }
+    }
+
+    /// Sets how the prover trades time for memory, see [ProverMemoryMode].
+    ///
+    /// In [ProverMemoryMode::Low], the column evaluations cached by the index
+    /// are released, and are no longer cached by the proofs.
+    pub fn with_memory_mode(mut self, memory_mode: ProverMemoryMode) -> Self {
+        if memory_mode == ProverMemoryMode::Low {
+            self.column_evaluations = Arc::new(column_evaluations_cache(&self.cs));
+        }
+        self.memory_mode = memory_mode;
+        self
+    }
+
+    /// Returns the index used by a proof, or by the commitments of the
+    /// verifier index: the index itself, or in [ProverMemoryMode::Low] and
+    /// unless the column evaluations are cached already, a copy of it whose
+    /// column evaluations are computed for the proof and dropped with it.
+    pub(crate) fn for_proof(&self) -> Cow<'_, Self> {
+        if self.memory_mode == ProverMemoryMode::Low && !self.column_evaluations.is_initialized() {
+            Cow::Owned(ProverIndex {
+                column_evaluations: Arc::new(column_evaluations_cache(&self.cs)),
+                ..self.clone()
+            })
+        } else {
+            Cow::Borrowed(self)
+        }
+    }
+
+    /// Estimates the peak memory of a proof in bytes, from the number of
+    /// polynomials the prover holds at the computation of the quotient, over
+    /// which domain, for the memory mode of the index, see [ProverMemoryMode].
+    /// It does not account for the SRS, and for the lookup tables held by the
+    /// constraint system.
+    pub fn estimated_peak_memory(&self) -> usize {
+        let n = self.cs.domain.d1.size();
+        let flags = &self.cs.feature_flags;
+
+        // the fixed columns: permutation, coefficients, and selectors
+        let optional_selectors8 = [
+            flags.range_check0,
+            flags.range_check1,
+            flags.foreign_field_add,
+            flags.foreign_field_mul,
+            flags.xor,
+            flags.rot,
+        ]
+        .into_iter()
+        .filter(|enabled| *enabled)
+        .count()
+            + if flags.cairo { 4 } else { 0 }
+            + if flags.poseidon2 { 2 } else { 0 };
+        let columns = (PERMUTS + COLUMNS + 4 + optional_selectors8) * 8 * n + 2 * 4 * n;
+
+        // the witness and z over d1, in evaluation and coefficient forms, and
+        // over the larger domains
+        let witness = 2 * (COLUMNS + 1) * n;
+        let witness_over_domains = match self.memory_mode {
+            // this and next rows over d4 and d8
+            ProverMemoryMode::Standard => 2 * (COLUMNS + 1) * (4 * n + 8 * n),
+            // this rows over d8, and z on the next rows
+            ProverMemoryMode::Low => (COLUMNS + 2) * 8 * n,
+        };
+
+        // the sorted or multiplicities polynomials, with the aggregation,
+        // table and runtime table polynomials, over d1 and d8
+        let lookup = if flags.lookup_features.patterns == LookupPatterns::default() {
+            0
+        } else {
+            let lookup_info = LookupInfo::create(flags.lookup_features);
+            (lookup_info.sorted_columns().max(1) + 3) * (2 * n + 8 * n)
+        };
+
+        // the quotient over d4 and d8, and the temporaries of the evaluation
+        // of the constraints
+        let quotient = 4 * n + 8 * n + 4 * 8 * n;
+
+        let elements = columns + witness + witness_over_domains + lookup + quotient;
+        elements * core::mem::size_of::<G::ScalarField>()
+    }
+
+    /// The number of chunks of the commitments of the polynomials of the
+    /// circuit, more than one when its domain is larger than the SRS
+    pub fn num_chunks(&self) -> usize {
+        num_chunks(self.cs.domain.d1.size(), self.max_poly_size)
+    }
+
+    /// The symbolic linearization of our circuit, which can compile to
+    /// concrete types once certain values are learned in the protocol.
+    pub fn linearization(&self) -> &CircuitLinearization<G::ScalarField> {
+        &self.linearization.get().0
+    }
+
+    /// The mapping between powers of alpha and constraints
+    pub fn powers_of_alpha(&self) -> &Alphas<G::ScalarField> {
+        &self.linearization.get().1
+    }
+
+    /// Computes the values of the index that are computed on first use in
+    /// lazy mode, and shared by the proofs created with the index and its
+    /// clones: the column evaluations, the lookup constraint system with the
+    /// polynomials of its tables, the domain precomputations, the
+    /// linearization and the verifier index. In [ProverMemoryMode::Low], the
+    /// column evaluations are left to each proof.
+    ///
+    /// Long-running services call it once after creating the index, so that
+    /// their first proof is not slower than the following ones.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the lookup constraint system fails to be created.
+    pub fn materialize(&self) -> Result<(), ProverError> {
+        if self.memory_mode == ProverMemoryMode::Standard {
+            self.column_evaluations.get();
+        }
+        self.cs
+            .lookup_constraint_system
+            .try_get_or_err()
+            .map_err(ProverError::from)?;
+        self.cs.precomputations();
+        self.linearization.get();
+        if self.verifier_index.is_none() {
+            self.verifier_index_cache
+                .get_or_init(|| self.create_verifier_index());
+        }
+        Ok(())
+    }
+
+    /// Returns the verifier index set in [Self::verifier_index], or the one
+    /// computed on first use and shared by the clones of this index.
+    pub(crate) fn cached_verifier_index(&self) -> &VerifierIndex<G, OpeningProof> {
+        match &self.verifier_index {
+            Some(verifier_index) => verifier_index,
+            None => self
+                .verifier_index_cache
+                .get_or_init(|| self.create_verifier_index()),
+        }
+    }
+
+    /// Returns the IDs and capacities of the runtime tables declared by the
+    /// constraint system, in the order expected by the prover.
+    pub fn runtime_table_specs(&self) -> Vec<RuntimeTableSpec> {
+        match self.cs.lookup_constraint_system.get() {
+            Ok(Some(lcs)) => lcs.runtime_tables.clone().unwrap_or_default(),
+            _ => vec![],
+        }
+    }
+
+    /// Returns the runtime tables declared by the constraint system, with all
+    /// their entries set to zero, to be filled during witness generation.
+    pub fn runtime_tables(&self) -> Vec<RuntimeTable<G::ScalarField>> {
+        self.runtime_table_specs()
+            .iter()
+            .map(RuntimeTable::new)
+            .collect()
+    }
+
+    /// Returns the commitment to `runtime_tables` of the proofs created with
+    /// [Self::public_runtime_tables] set, which a verifier knowing the content
+    /// of the tables checks with [crate::verifier::verify_runtime_tables].
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the runtime tables do not match the configuration of
+    /// the constraint system.
+    pub fn runtime_table_commitment(
+        &self,
+        runtime_tables: &[RuntimeTable<G::ScalarField>],
+    ) -> Result<PolyComm<G>, ProverError> {
+        let num_chunks = self.num_chunks();
+        let evals = self.runtime_table_evaluations(runtime_tables)?;
+        let runtime_table =
+            Evaluations::from_vec_and_domain(evals, self.cs.domain.d1).interpolate();
+        Ok(self.srs.commit_non_hiding(&runtime_table, num_chunks))
+    }
+
+    /// Returns the evaluations over `d1` of the concatenation of
+    /// `runtime_tables`, placed after the fixed tables, with zeros in the
+    /// zero-knowledge rows.
+    pub(crate) fn runtime_table_evaluations(
+        &self,
+        runtime_tables: &[RuntimeTable<G::ScalarField>],
+    ) -> Result<Vec<G::ScalarField>, ProverError> {
+        let lcs = self
+            .cs
+            .lookup_constraint_system
+            .try_get_or_err()
+            .map_err(ProverError::from)?
+            .as_ref()
+            .ok_or(ProverError::RuntimeTablesInconsistent)?;
+        let cfg_runtime_tables = lcs
+            .runtime_tables
+            .as_ref()
+            .ok_or(ProverError::RuntimeTablesInconsistent)?;
+
+        // The runtime tables must have the IDs and lengths of the configuration,
+        // in the same order
+        let expected_runtime: Vec<_> = cfg_runtime_tables
+            .iter()
+            .map(|rt| (rt.id, rt.len))
+            .collect();
+        let runtime: Vec<_> = runtime_tables
+            .iter()
+            .map(|rt| (rt.id, rt.data.len()))
+            .collect();
+        if expected_runtime != runtime {
+            return Err(ProverError::RuntimeTablesInconsistent);
+        }
+
+        let mut offset = lcs
+            .runtime_table_offset
+            .expect("runtime configuration missing offset");
+        let mut evals = vec![G::ScalarField::zero(); self.cs.domain.d1.size()];
+        for rt in runtime_tables {
+            let range = offset..(offset + rt.data.len());
+            evals[range].copy_from_slice(&rt.data);
+            offset += rt.data.len();
+        }
+        Ok(evals)
+    }
+
+    /// The digest of the circuit of the index, that is of its public input size
+    /// and of its gates, padded to the size of its domain.
+    pub fn circuit_digest(&self) -> [u8; 32] {
+        Circuit::from(self.cs.as_ref()).digest()
+    }
+
+    /// Writes the index to `writer`, to be loaded with [Self::load_from]
+    /// instead of being created again. The lazy fields of the index are
+    /// computed to be written.
+    ///
+    /// The index is written after the magic bytes [PROVER_INDEX_MAGIC], the
+    /// [digest of its circuit](Self::circuit_digest), and the BLAKE2s hash of
+    /// its encoding. Its SRS, linearization and verifier index are not
+    /// written.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the index fails to be encoded or written.
+    pub fn serialize_to<W: Write>(&self, mut writer: W) -> Result<(), ProverIndexCacheError> {
+        let body = rmp_serde::to_vec(self)
+            .map_err(|e| ProverIndexCacheError::Serialization(e.to_string()))?;
+        let hash = Blake2s256::digest(&body);
+        let circuit_digest = self.circuit_digest();
+        for bytes in [&PROVER_INDEX_MAGIC[..], &circuit_digest, &hash, &body] {
+            writer
+                .write_all(bytes)
+                .map_err(|e| ProverIndexCacheError::Io(e.to_string()))?;
         }
+        Ok(())
+    }
+
+    /// Loads an index written by [Self::serialize_to] from `reader`, with the
+    /// SRS `srs`, checking that it is the index of the circuit of digest
+    /// `circuit_digest` (see [Self::circuit_digest]) and that it has not been
+    /// altered.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the index fails to be read or decoded, if it is not
+    /// the one of the circuit, if its content does not match its hash, or if
+    /// `srs` does not have the maximal polynomial size of the index.
+    pub fn load_from<R: Read>(
+        mut reader: R,
+        srs: Arc<OpeningProof::SRS>,
+        circuit_digest: [u8; 32],
+    ) -> Result<Self, ProverIndexCacheError>
+    where
+        OpeningProof: Default,
+        OpeningProof::SRS: Default,
+    {
+        let mut bytes = vec![];
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|e| ProverIndexCacheError::Io(e.to_string()))?;
+        let bytes = bytes
+            .strip_prefix(&PROVER_INDEX_MAGIC)
+            .ok_or(ProverIndexCacheError::InvalidMagic)?;
+        let (digest, bytes) = bytes
+            .split_first_chunk::<32>()
+            .ok_or(ProverIndexCacheError::InvalidMagic)?;
+        if *digest != circuit_digest {
+            return Err(ProverIndexCacheError::CircuitMismatch);
+        }
+        let (hash, body) = bytes
+            .split_first_chunk::<32>()
+            .ok_or(ProverIndexCacheError::Corrupted)?;
+        if Blake2s256::digest(body).as_slice() != hash {
+            return Err(ProverIndexCacheError::Corrupted);
+        }
+
+        let mut index: Self = rmp_serde::from_slice(body)
+            .map_err(|e| ProverIndexCacheError::Serialization(e.to_string()))?;
+        if index.circuit_digest() != circuit_digest {
+            return Err(ProverIndexCacheError::CircuitMismatch);
+        }
+        if srs.max_poly_size() != index.max_poly_size {
+            return Err(ProverIndexCacheError::SrsMismatch(
+                index.max_poly_size,
+                srs.max_poly_size(),
+            ));
+        }
+
+        index.linearization = Arc::new(LazyCache::preinit(expr_linearization(
+            Some(&index.cs.feature_flags),
+            true,
+        )));
+        index.srs = srs;
+        Ok(index)
     }
 
     /// Retrieve or compute the digest for the corresponding verifier index.
@@ -132,13 +532,7 @@ where
             return verifier_index_digest;
         }
 
-        match &self.verifier_index {
-            None => {
-                let verifier_index = self.verifier_index();
-                verifier_index.digest::<EFqSponge>()
-            }
-            Some(verifier_index) => verifier_index.digest::<EFqSponge>(),
-        }
+        self.cached_verifier_index().digest::<EFqSponge>()
     }
 }
 
@@ -150,10 +544,8 @@ pub mod testing {
     };
     use ark_ff::PrimeField;
     use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as D};
-    use poly_commitment::{
-        ipa::{OpeningProof, SRS},
-        precomputed_srs, OpenProof,
-    };
+    use poly_commitment::{ipa::OpeningProof, precomputed_srs, OpenProof};
+    use srs_store::SrsStore;
 
     #[allow(clippy::too_many_arguments)]
     pub fn new_index_for_test_with_lookups_and_custom_srs<
@@ -170,6 +562,7 @@ pub mod testing {
         override_srs_size: Option<usize>,
         mut get_srs: F,
         lazy_mode: bool,
+        log_up: bool,
     ) -> ProverIndex<G, OpeningProof>
     where
         G::BaseField: PrimeField,
@@ -184,6 +577,7 @@ pub mod testing {
             .disable_gates_checks(disable_gates_checks)
             .max_poly_size(override_srs_size)
             .lazy_mode(lazy_mode)
+            .log_up(log_up)
             .build()
             .unwrap();
 
@@ -200,6 +594,7 @@ pub mod testing {
     /// # Panics
     ///
     /// Will panic if `constraint system` is not built with `gates` input.
+    #[allow(clippy::too_many_arguments)]
     pub fn new_index_for_test_with_lookups<G: KimchiCurve>(
         gates: Vec<CircuitGate<G::ScalarField>>,
         public: usize,
@@ -209,6 +604,7 @@ pub mod testing {
         disable_gates_checks: bool,
         override_srs_size: Option<usize>,
         lazy_mode: bool,
+        log_up: bool,
     ) -> ProverIndex<G, OpeningProof<G>>
     where
         G::BaseField: PrimeField,
@@ -225,17 +621,20 @@ pub mod testing {
             |d1: D<G::ScalarField>, size: usize| {
                 let log2_size = size.ilog2();
                 let srs = if log2_size <= precomputed_srs::SERIALIZED_SRS_SIZE {
-                    // TODO: we should trim it if it's smaller
+                    // The precomputed SRS is not trimmed, as the expected proofs
+                    // of the serialization regression tests depend on its size
                     precomputed_srs::get_srs_test()
                 } else {
-                    // TODO: we should resume the SRS generation starting from the serialized one
-                    SRS::<G>::create(size)
+                    SrsStore::from_env()
+                        .get::<G>(size)
+                        .unwrap_or_else(|e| panic!("cannot get an SRS of size {size}: {e}"))
                 };
 
                 srs.get_lagrange_basis(d1);
                 srs
             },
             lazy_mode,
+            log_up,
         )
     }
 
@@ -247,6 +646,16 @@ pub mod testing {
         G::BaseField: PrimeField,
         G::ScalarField: PrimeField,
     {
-        new_index_for_test_with_lookups::<G>(gates, public, 0, vec![], None, false, None, false)
+        new_index_for_test_with_lookups::<G>(
+            gates,
+            public,
+            0,
+            vec![],
+            None,
+            false,
+            None,
+            false,
+            false,
+        )
     }
 }
@@ -0,0 +1,112 @@
+use crate::{
+    accumulation::{accumulate, prove_with_accumulators},
+    circuits::{
+        polynomials::generic::testing::{create_circuit, fill_in_witness},
+        wires::COLUMNS,
+    },
+    error::{AccumulationError, VerifyError},
+    proof::ProverProof,
+    prover_index::{testing::new_index_for_test_with_lookups, ProverIndex},
+    verifier::{verify, Context},
+};
+use ark_ff::Zero;
+use core::array;
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use poly_commitment::{commitment::CommitmentCurve, ipa::OpeningProof};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+/// The index of the generic circuit with `public` public inputs, taking
+/// `prev_challenges` previous challenges, and its witness
+fn generic_circuit(
+    public: &[Fp],
+    prev_challenges: usize,
+) -> (ProverIndex<Vesta, OpeningProof<Vesta>>, [Vec<Fp>; COLUMNS]) {
+    let gates = create_circuit(0, public.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, public);
+    let index = new_index_for_test_with_lookups::<Vesta>(
+        gates,
+        public.len(),
+        prev_challenges,
+        vec![],
+        None,
+        false,
+        None,
+        false,
+        false,
+    );
+    (index, witness)
+}
+
+#[test]
+fn test_accumulation() {
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let rng = &mut rand::rngs::OsRng;
+
+    // two proofs of different circuits
+    let public = vec![Fp::from(3u8); 5];
+    let circuits = [generic_circuit(&public, 0), generic_circuit(&[], 0)];
+    let publics = [public.as_slice(), &[]];
+    let verifier_indexes = circuits.each_ref().map(|(index, _)| index.verifier_index());
+    let proofs = circuits.map(|(index, witness)| {
+        ProverProof::create::<BaseSponge, ScalarSponge, _>(&group_map, witness, &[], &index, rng)
+            .unwrap()
+    });
+    let contexts: Vec<_> = proofs
+        .iter()
+        .zip(&verifier_indexes)
+        .zip(publics)
+        .map(|((proof, verifier_index), public_input)| Context {
+            verifier_index,
+            proof,
+            public_input,
+        })
+        .collect();
+
+    let (circuit, witness) = generic_circuit(&[], contexts.len());
+    let deferred = prove_with_accumulators::<Vesta, BaseSponge, ScalarSponge, _>(
+        &group_map,
+        &contexts,
+        &circuit,
+        witness.clone(),
+        rng,
+    )
+    .unwrap();
+    assert_eq!(deferred.prev_challenges.len(), contexts.len());
+    verify::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>>(
+        &group_map,
+        &circuit.verifier_index(),
+        &deferred,
+        &[],
+    )
+    .unwrap();
+
+    // the circuit takes two proofs
+    assert!(matches!(
+        prove_with_accumulators::<Vesta, BaseSponge, ScalarSponge, _>(
+            &group_map,
+            &contexts[..1],
+            &circuit,
+            witness,
+            rng,
+        ),
+        Err(AccumulationError::WrongNumberOfProofs(2, 1))
+    ));
+
+    // a proof given with another public input
+    let other_public = vec![Fp::from(4u8); 5];
+    let mut contexts = contexts;
+    contexts[0].public_input = &other_public;
+    assert!(matches!(
+        accumulate::<Vesta, BaseSponge, ScalarSponge>(&group_map, &contexts),
+        Err(VerifyError::OpenProof)
+    ));
+}
@@ -21,7 +21,7 @@ use mina_poseidon::{
 use num_bigint::BigUint;
 use o1_utils::{BitwiseOps, FieldHelpers, RandomField};
 
-use super::framework::TestFramework;
+use super::framework::{FailureKind, TestFramework};
 
 type PallasField = <Pallas as AffineRepr>::BaseField;
 type VestaField = <Vesta as AffineRepr>::BaseField;
@@ -345,21 +345,23 @@ fn test_bad_and() {
     }
     witness[4][2] = PallasField::zero();
 
-    assert_eq!(
-        TestFramework::<Vesta>::default()
-            .gates(gates)
-            .witness(witness)
-            .setup()
-            .prove_and_verify::<VestaBaseSponge, VestaScalarSponge>(),
-        Err(String::from(
-            "Custom { row: 2, err: \"generic: incorrect gate\" }"
-        ))
-    );
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_expecting_failure::<VestaBaseSponge, VestaScalarSponge>(FailureKind::Constraint {
+            row: 2,
+            typ: GateType::Generic,
+            constraint: 2,
+        });
 }
 
 #[test]
 fn test_serialization_regression() {
-    // Generated with commit 1494cf973d40fb276465929eb7db1952c5de7bdc
+    // Generated with commit 1494cf973d40fb276465929eb7db1952c5de7bdc, with the
+    // absent evaluations of the Cairo, vector lookup and Poseidon2 selectors and
+    // of the lookup multiplicities appended, as well as the absent commitment
+    // to the multiplicities
     let buf_expected = vec![
         149, 148, 159, 145, 145, 196, 33, 36, 165, 245, 213, 186, 207, 201, 96, 141, 145, 71, 154,
         187, 239, 170, 150, 114, 105, 170, 226, 168, 160, 25, 82, 94, 241, 119, 173, 45, 239, 224,
@@ -403,7 +405,7 @@ fn test_serialization_regression() {
         240, 215, 91, 219, 180, 177, 54, 179, 229, 138, 252, 189, 136, 112, 138, 181, 230, 155,
         114, 11, 92, 244, 164, 202, 133, 66, 225, 108, 124, 21, 128, 196, 33, 10, 2, 2, 88, 246,
         207, 17, 158, 39, 228, 153, 4, 54, 41, 25, 195, 101, 83, 1, 151, 172, 61, 162, 203, 3, 216,
-        239, 140, 208, 167, 117, 30, 128, 147, 149, 145, 145, 196, 33, 168, 20, 0, 160, 190, 182,
+        239, 140, 208, 167, 117, 30, 128, 148, 149, 145, 145, 196, 33, 168, 20, 0, 160, 190, 182,
         71, 220, 31, 185, 150, 70, 29, 86, 10, 187, 56, 88, 112, 10, 173, 72, 132, 234, 249, 40,
         65, 168, 178, 217, 178, 10, 0, 145, 145, 196, 33, 125, 1, 225, 118, 245, 42, 119, 14, 112,
         28, 173, 45, 226, 47, 202, 47, 128, 139, 126, 117, 75, 226, 200, 6, 223, 103, 43, 32, 169,
@@ -414,35 +416,35 @@ fn test_serialization_regression() {
         196, 33, 227, 157, 66, 26, 83, 194, 100, 178, 85, 122, 6, 156, 33, 54, 51, 94, 110, 28,
         251, 86, 25, 23, 154, 120, 182, 208, 228, 246, 68, 117, 236, 14, 128, 145, 145, 196, 33,
         243, 151, 191, 177, 116, 24, 152, 176, 112, 7, 6, 9, 1, 84, 149, 151, 3, 202, 122, 119, 48,
-        240, 186, 59, 162, 57, 55, 188, 171, 75, 97, 1, 128, 192, 149, 220, 0, 16, 146, 196, 33,
-        224, 152, 195, 71, 46, 249, 164, 109, 115, 126, 223, 209, 35, 33, 201, 228, 17, 79, 245,
-        207, 238, 3, 133, 82, 139, 241, 209, 179, 74, 250, 252, 32, 0, 196, 33, 36, 48, 163, 190,
-        148, 134, 115, 172, 128, 67, 162, 36, 176, 96, 119, 56, 45, 11, 11, 91, 15, 235, 164, 77,
-        231, 118, 27, 201, 253, 210, 218, 29, 0, 146, 196, 33, 92, 40, 50, 24, 164, 89, 253, 180,
-        19, 181, 207, 221, 6, 53, 35, 192, 211, 153, 164, 93, 72, 51, 115, 42, 68, 155, 0, 39, 120,
-        110, 63, 24, 128, 196, 33, 212, 106, 62, 139, 172, 167, 41, 28, 3, 54, 177, 153, 133, 166,
-        213, 45, 23, 5, 151, 208, 107, 78, 191, 245, 174, 178, 173, 103, 73, 167, 12, 46, 0, 146,
-        196, 33, 99, 37, 180, 55, 215, 170, 173, 254, 209, 118, 71, 198, 174, 54, 140, 87, 208, 2,
-        137, 195, 55, 66, 49, 72, 30, 105, 97, 76, 64, 30, 149, 46, 0, 196, 33, 187, 83, 231, 253,
-        36, 164, 169, 56, 40, 74, 39, 164, 143, 156, 38, 5, 238, 233, 224, 254, 42, 26, 223, 180,
-        4, 138, 114, 189, 24, 8, 147, 3, 128, 146, 196, 33, 214, 248, 85, 127, 130, 217, 152, 60,
-        34, 62, 21, 60, 62, 114, 245, 208, 27, 204, 164, 167, 67, 200, 20, 161, 124, 236, 18, 16,
-        58, 24, 143, 5, 0, 196, 33, 178, 185, 115, 105, 66, 21, 51, 170, 224, 36, 187, 81, 32, 86,
-        77, 61, 205, 8, 243, 153, 91, 189, 6, 79, 1, 255, 102, 45, 198, 226, 134, 29, 0, 146, 196,
-        33, 107, 157, 62, 160, 168, 130, 81, 105, 68, 177, 134, 222, 48, 53, 246, 197, 9, 144, 76,
-        113, 99, 153, 56, 108, 60, 63, 202, 62, 251, 241, 83, 47, 0, 196, 33, 154, 225, 166, 53,
-        169, 246, 162, 212, 120, 159, 221, 5, 145, 20, 168, 83, 7, 131, 241, 68, 80, 4, 19, 190,
-        36, 67, 93, 55, 74, 135, 158, 49, 128, 146, 196, 33, 126, 91, 134, 159, 147, 229, 22, 241,
-        214, 58, 134, 178, 225, 137, 147, 224, 139, 152, 240, 98, 3, 103, 69, 208, 94, 31, 52, 88,
-        77, 7, 149, 55, 0, 196, 33, 111, 187, 150, 224, 164, 94, 91, 94, 24, 202, 106, 203, 137,
-        141, 151, 6, 251, 43, 195, 85, 170, 98, 83, 183, 39, 251, 66, 207, 118, 87, 218, 21, 128,
-        146, 196, 33, 37, 23, 28, 167, 53, 39, 201, 88, 54, 123, 42, 144, 72, 17, 8, 229, 138, 237,
-        253, 43, 221, 28, 197, 115, 150, 145, 144, 58, 167, 123, 85, 20, 0, 196, 33, 162, 65, 229,
-        69, 125, 63, 218, 120, 69, 74, 145, 144, 28, 209, 73, 125, 214, 32, 48, 115, 100, 98, 89,
-        56, 59, 64, 207, 239, 52, 79, 183, 32, 0, 146, 196, 33, 165, 136, 235, 209, 28, 15, 225,
-        127, 10, 78, 198, 46, 77, 17, 93, 190, 249, 146, 240, 142, 49, 147, 182, 141, 94, 201, 39,
-        39, 197, 56, 103, 37, 0, 196, 33, 53, 169, 79, 96, 3, 82, 127, 139, 179, 252, 93, 45, 15,
-        117, 103, 196, 244, 243, 89, 253, 41, 198, 2, 82, 210, 182, 157, 116, 222, 84, 172, 16,
+        240, 186, 59, 162, 57, 55, 188, 171, 75, 97, 1, 128, 192, 192, 149, 220, 0, 16, 146, 196,
+        33, 224, 152, 195, 71, 46, 249, 164, 109, 115, 126, 223, 209, 35, 33, 201, 228, 17, 79,
+        245, 207, 238, 3, 133, 82, 139, 241, 209, 179, 74, 250, 252, 32, 0, 196, 33, 36, 48, 163,
+        190, 148, 134, 115, 172, 128, 67, 162, 36, 176, 96, 119, 56, 45, 11, 11, 91, 15, 235, 164,
+        77, 231, 118, 27, 201, 253, 210, 218, 29, 0, 146, 196, 33, 92, 40, 50, 24, 164, 89, 253,
+        180, 19, 181, 207, 221, 6, 53, 35, 192, 211, 153, 164, 93, 72, 51, 115, 42, 68, 155, 0, 39,
+        120, 110, 63, 24, 128, 196, 33, 212, 106, 62, 139, 172, 167, 41, 28, 3, 54, 177, 153, 133,
+        166, 213, 45, 23, 5, 151, 208, 107, 78, 191, 245, 174, 178, 173, 103, 73, 167, 12, 46, 0,
+        146, 196, 33, 99, 37, 180, 55, 215, 170, 173, 254, 209, 118, 71, 198, 174, 54, 140, 87,
+        208, 2, 137, 195, 55, 66, 49, 72, 30, 105, 97, 76, 64, 30, 149, 46, 0, 196, 33, 187, 83,
+        231, 253, 36, 164, 169, 56, 40, 74, 39, 164, 143, 156, 38, 5, 238, 233, 224, 254, 42, 26,
+        223, 180, 4, 138, 114, 189, 24, 8, 147, 3, 128, 146, 196, 33, 214, 248, 85, 127, 130, 217,
+        152, 60, 34, 62, 21, 60, 62, 114, 245, 208, 27, 204, 164, 167, 67, 200, 20, 161, 124, 236,
+        18, 16, 58, 24, 143, 5, 0, 196, 33, 178, 185, 115, 105, 66, 21, 51, 170, 224, 36, 187, 81,
+        32, 86, 77, 61, 205, 8, 243, 153, 91, 189, 6, 79, 1, 255, 102, 45, 198, 226, 134, 29, 0,
+        146, 196, 33, 107, 157, 62, 160, 168, 130, 81, 105, 68, 177, 134, 222, 48, 53, 246, 197, 9,
+        144, 76, 113, 99, 153, 56, 108, 60, 63, 202, 62, 251, 241, 83, 47, 0, 196, 33, 154, 225,
+        166, 53, 169, 246, 162, 212, 120, 159, 221, 5, 145, 20, 168, 83, 7, 131, 241, 68, 80, 4,
+        19, 190, 36, 67, 93, 55, 74, 135, 158, 49, 128, 146, 196, 33, 126, 91, 134, 159, 147, 229,
+        22, 241, 214, 58, 134, 178, 225, 137, 147, 224, 139, 152, 240, 98, 3, 103, 69, 208, 94, 31,
+        52, 88, 77, 7, 149, 55, 0, 196, 33, 111, 187, 150, 224, 164, 94, 91, 94, 24, 202, 106, 203,
+        137, 141, 151, 6, 251, 43, 195, 85, 170, 98, 83, 183, 39, 251, 66, 207, 118, 87, 218, 21,
+        128, 146, 196, 33, 37, 23, 28, 167, 53, 39, 201, 88, 54, 123, 42, 144, 72, 17, 8, 229, 138,
+        237, 253, 43, 221, 28, 197, 115, 150, 145, 144, 58, 167, 123, 85, 20, 0, 196, 33, 162, 65,
+        229, 69, 125, 63, 218, 120, 69, 74, 145, 144, 28, 209, 73, 125, 214, 32, 48, 115, 100, 98,
+        89, 56, 59, 64, 207, 239, 52, 79, 183, 32, 0, 146, 196, 33, 165, 136, 235, 209, 28, 15,
+        225, 127, 10, 78, 198, 46, 77, 17, 93, 190, 249, 146, 240, 142, 49, 147, 182, 141, 94, 201,
+        39, 39, 197, 56, 103, 37, 0, 196, 33, 53, 169, 79, 96, 3, 82, 127, 139, 179, 252, 93, 45,
+        15, 117, 103, 196, 244, 243, 89, 253, 41, 198, 2, 82, 210, 182, 157, 116, 222, 84, 172, 16,
         128, 146, 196, 33, 36, 192, 51, 27, 179, 112, 132, 42, 59, 121, 161, 93, 252, 82, 255, 43,
         211, 79, 85, 151, 225, 25, 255, 178, 177, 218, 253, 197, 243, 141, 141, 50, 128, 196, 33,
         211, 167, 99, 187, 185, 244, 188, 10, 106, 243, 113, 70, 66, 48, 139, 91, 15, 159, 194,
@@ -478,7 +480,7 @@ fn test_serialization_regression() {
         69, 153, 179, 60, 196, 32, 16, 161, 242, 238, 35, 203, 56, 64, 61, 45, 232, 213, 84, 21,
         145, 154, 49, 208, 179, 147, 158, 146, 215, 167, 246, 159, 4, 87, 76, 120, 170, 23, 196,
         33, 121, 227, 28, 56, 43, 207, 127, 209, 138, 117, 222, 133, 254, 3, 66, 85, 176, 179, 37,
-        35, 200, 152, 199, 33, 246, 130, 159, 192, 144, 157, 184, 6, 128, 220, 0, 26, 146, 145,
+        35, 200, 152, 199, 33, 246, 130, 159, 192, 144, 157, 184, 6, 128, 220, 0, 30, 146, 145,
         196, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         0, 0, 0, 0, 0, 145, 196, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 159, 146, 145, 196, 32, 24, 85, 231, 154, 170, 14, 90, 95,
@@ -653,9 +655,9 @@ fn test_serialization_regression() {
         32, 110, 102, 247, 171, 156, 186, 16, 115, 183, 126, 129, 59, 82, 133, 202, 1, 119, 113,
         215, 111, 189, 2, 187, 238, 111, 133, 22, 51, 15, 39, 51, 3, 145, 196, 32, 46, 115, 234,
         37, 231, 189, 140, 98, 240, 154, 252, 196, 198, 141, 226, 230, 137, 106, 186, 156, 43, 162,
-        61, 143, 220, 223, 83, 90, 79, 215, 253, 49, 192, 192, 192, 196, 32, 25, 212, 127, 17, 107,
-        251, 186, 229, 239, 230, 182, 195, 40, 232, 118, 45, 205, 78, 253, 203, 141, 81, 175, 186,
-        185, 116, 235, 41, 158, 240, 90, 22, 144,
+        61, 143, 220, 223, 83, 90, 79, 215, 253, 49, 192, 192, 192, 192, 192, 192, 192, 196, 32,
+        25, 212, 127, 17, 107, 251, 186, 229, 239, 230, 182, 195, 40, 232, 118, 45, 205, 78, 253,
+        203, 141, 81, 175, 186, 185, 116, 235, 41, 158, 240, 90, 22, 144,
     ];
     prove_and_check_serialization_regression::<Vesta, VestaBaseSponge, VestaScalarSponge>(
         8,
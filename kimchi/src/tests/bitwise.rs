@@ -0,0 +1,188 @@
+use super::framework::{FailureKind, TestFramework};
+use crate::circuits::{
+    gate::{CircuitGate, Connect, GateType},
+    polynomial::COLUMNS,
+    polynomials::{
+        bitwise::{self, BitwiseOp},
+        xor,
+    },
+};
+use ark_ff::{One, Zero};
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use num_bigint::BigUint;
+use o1_utils::{BigUintFieldHelpers, BitwiseOps, FieldHelpers, RandomField};
+
+type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+const OPS: [BitwiseOp; 3] = [BitwiseOp::And, BitwiseOp::Or, BitwiseOp::Nand];
+
+fn setup_bitwise(op: BitwiseOp, input1: Fp, input2: Fp, bits: usize) -> TestFramework<Vesta> {
+    let mut gates = vec![];
+    let next_row = CircuitGate::<Fp>::extend_bitwise(&mut gates, op, bits);
+    assert_eq!(next_row, xor::num_xors(bits) + 2);
+
+    let witness = bitwise::create_bitwise_witness(op, input1, input2, bits);
+    assert_eq!(witness[0].len(), next_row);
+    let output = op.compute(&input1.to_biguint(), &input2.to_biguint(), bits);
+    assert_eq!(witness[5][next_row - 1], output.to_field().unwrap());
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+}
+
+#[test]
+// Checks the operations on words against their definition bit per bit
+fn test_bitwise_compute() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    for _ in 0..10 {
+        let input1 = RandomField::<Fp>::gen_field_with_bits(rng, 64).to_biguint();
+        let input2 = RandomField::<Fp>::gen_field_with_bits(rng, 64).to_biguint();
+        for op in OPS {
+            let output = op.compute(&input1, &input2, 64);
+            for i in 0..64 {
+                let (a, b) = (input1.bit(i), input2.bit(i));
+                let expected = match op {
+                    BitwiseOp::And => a && b,
+                    BitwiseOp::Or => a || b,
+                    BitwiseOp::Nand => !(a && b),
+                };
+                assert_eq!(output.bit(i), expected);
+            }
+            assert!(output.bits() <= 64);
+        }
+    }
+}
+
+#[test]
+// Checks the witness of the operations on random words of 32 and 64 bits
+fn test_bitwise_random() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    for bits in [32, 64] {
+        for op in OPS {
+            let input1 = rng.gen_field_with_bits(bits);
+            let input2 = rng.gen_field_with_bits(bits);
+            let runner = setup_bitwise(op, input1, input2, bits).setup();
+            let witness = bitwise::create_bitwise_witness(op, input1, input2, bits);
+            assert_eq!(runner.prover_index().verify(&witness, &[]), Ok(()));
+        }
+    }
+}
+
+#[test]
+// Checks the operations on the all-zero and all-one words
+fn test_bitwise_extremes() {
+    let ones = Fp::from(u64::MAX);
+    for op in OPS {
+        for (input1, input2) in [(Fp::zero(), Fp::zero()), (Fp::zero(), ones), (ones, ones)] {
+            let runner = setup_bitwise(op, input1, input2, 64).setup();
+            let witness = bitwise::create_bitwise_witness(op, input1, input2, 64);
+            assert_eq!(runner.prover_index().verify(&witness, &[]), Ok(()));
+        }
+    }
+}
+
+#[test]
+fn test_prove_and_verify_bitwise() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let input1 = rng.gen_field_with_bits(32);
+    let input2 = rng.gen_field_with_bits(32);
+    setup_bitwise(BitwiseOp::Nand, input1, input2, 32)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+// Chains NOT gadgets, so that the output of the first one is negated back
+fn test_not_chain() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let bits = 64;
+    let input = rng.gen_field_with_bits(bits);
+
+    let mut gates = vec![];
+    let next_row = CircuitGate::<Fp>::extend_not(&mut gates, bits);
+    assert_eq!(next_row, xor::num_xors(bits) + 1);
+    CircuitGate::<Fp>::extend_not(&mut gates, bits);
+    gates.connect_cell_pair((0, 2), (next_row, 0));
+    gates.connect_cell_pair((0, 0), (next_row, 2));
+
+    let mut witness: [Vec<Fp>; COLUMNS] = core::array::from_fn(|_| vec![]);
+    bitwise::extend_not_witness(&mut witness, input, bits);
+    let output = witness[2][0];
+    assert_eq!(
+        output.to_biguint(),
+        BigUint::bitwise_not(&input.to_biguint(), Some(bits))
+    );
+    bitwise::extend_not_witness(&mut witness, output, bits);
+    assert_eq!(witness[2][next_row], input);
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+// The NOT gadget is not satisfied by the XOR with another word than the
+// all-one word
+fn test_bad_not() {
+    let bits = 32;
+    let input = Fp::from(0xdeadbeefu32);
+    let mut gates = vec![];
+    CircuitGate::<Fp>::extend_not(&mut gates, bits);
+
+    let mut witness = bitwise::create_not_witness(input, bits);
+    let zero_row = xor::num_xors(bits);
+    let other = Fp::from(0x7fffffffu32);
+    let output = BigUint::bitwise_xor(&input.to_biguint(), &other.to_biguint());
+    xor::init_xor(
+        &mut witness,
+        0,
+        bits,
+        (input, other, output.to_field().unwrap()),
+    );
+    witness[3][zero_row] = other;
+
+    let runner = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness.clone())
+        .setup();
+    assert!(runner.prover_index().verify(&witness, &[]).is_err());
+}
+
+#[test]
+// The output of the operation is constrained by the second generic gate
+fn test_bad_bitwise_output() {
+    let bits = 32;
+    let input1 = Fp::from(0xf0f0f0f0u32);
+    let input2 = Fp::from(0x12345678u32);
+    for op in OPS {
+        let mut witness = bitwise::create_bitwise_witness(op, input1, input2, bits);
+        let op_row = xor::num_xors(bits) + 1;
+        witness[5][op_row] += Fp::one();
+
+        setup_bitwise(op, input1, input2, bits)
+            .witness(witness)
+            .setup()
+            .prove_expecting_failure::<BaseSponge, ScalarSponge>(FailureKind::Constraint {
+                row: op_row,
+                typ: GateType::Generic,
+                constraint: 2,
+            });
+    }
+}
+
+#[test]
+#[should_panic(expected = "Bits must be a positive multiple of 16")]
+fn test_bitwise_bad_length() {
+    let mut gates = vec![];
+    CircuitGate::<Fp>::extend_bitwise(&mut gates, BitwiseOp::Or, 24);
+}
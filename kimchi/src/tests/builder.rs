@@ -0,0 +1,159 @@
+use super::framework::TestFramework;
+use crate::{
+    circuits::{builder::CircuitBuilder, gate::CircuitGate, wires::COLUMNS},
+    curve::KimchiCurve,
+};
+use ark_ff::{One, Zero};
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    permutation::poseidon_block_cipher,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+fn prove_and_verify(gates: Vec<CircuitGate<Fp>>, witness: [Vec<Fp>; COLUMNS], public: Vec<Fp>) {
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(public)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+// Proves the knowledge of y such that x * y + 1 = 43
+fn arithmetic_circuit() -> CircuitBuilder<Fp> {
+    let mut builder = CircuitBuilder::new();
+    let x = builder.public_input();
+    let y = builder.private_input();
+    let one = builder.constant(Fp::one());
+    let x_times_y = builder.mul(x, y);
+    let sum = builder.add(x_times_y, one);
+    let expected = builder.constant(Fp::from(43u64));
+    builder.assert_equal(sum, expected);
+    builder
+}
+
+#[test]
+fn test_builder_arithmetic() {
+    let (gates, public, witness) = arithmetic_circuit().build();
+    assert_eq!(public, 1);
+    // The 3 generic gates of a row each, after the public input
+    assert_eq!(gates.len(), 4);
+
+    let public_inputs = vec![Fp::from(6u64)];
+    let witness = witness(&public_inputs, &[Fp::from(7u64)]);
+    prove_and_verify(gates, witness, public_inputs);
+}
+
+#[test]
+#[should_panic]
+fn test_builder_wrong_input() {
+    let (gates, _, witness) = arithmetic_circuit().build();
+
+    let public_inputs = vec![Fp::from(6u64)];
+    let witness = witness(&public_inputs, &[Fp::from(8u64)]);
+    prove_and_verify(gates, witness, public_inputs);
+}
+
+#[test]
+fn test_builder_gadgets() {
+    let params = Vesta::sponge_params();
+    let mut builder = CircuitBuilder::new();
+    let a = builder.public_input();
+    let b = builder.private_input();
+    let c = builder.xor(a, b, 32);
+    builder.range_check(c, 20);
+    let d = builder.sub(c, a);
+    let output = builder.poseidon(params, [a, b, d]);
+    let expected = builder.private_input();
+    builder.assert_equal(output[0], expected);
+
+    let (gates, _, witness) = builder.build();
+
+    let (a, b) = (0x1234_5678u64, 0x1235_4321u64);
+    let mut state = vec![Fp::from(a), Fp::from(b), Fp::from(a ^ b) - Fp::from(a)];
+    poseidon_block_cipher::<Fp, SpongeParams>(params, &mut state);
+    let public_inputs = vec![Fp::from(a)];
+    let witness = witness(&public_inputs, &[Fp::from(b), state[0]]);
+
+    prove_and_verify(gates, witness, public_inputs);
+}
+
+// Writes the private values to the private addresses of a memory of 8 cells,
+// overwriting the first one, and reads them back with a cell never written
+fn memory_circuit() -> CircuitBuilder<Fp> {
+    let mut builder = CircuitBuilder::new();
+    let addresses: Vec<_> = (0..3).map(|_| builder.private_input()).collect();
+    let values: Vec<_> = (0..3).map(|_| builder.private_input()).collect();
+    let memory = builder.memory(Vesta::sponge_params(), 8);
+    for (address, value) in addresses.iter().zip(&values) {
+        builder.write(memory, *address, *value);
+    }
+    let overwritten = builder.constant(Fp::from(1234u64));
+    builder.write(memory, addresses[0], overwritten);
+
+    let first = builder.read(memory, addresses[0]);
+    builder.assert_equal(first, overwritten);
+    let second = builder.read(memory, addresses[1]);
+    builder.assert_equal(second, values[1]);
+    let unwritten = builder.private_input();
+    let zero = builder.read(memory, unwritten);
+    let expected = builder.constant(Fp::zero());
+    builder.assert_equal(zero, expected);
+    builder
+}
+
+#[test]
+fn test_builder_memory() {
+    let (gates, _, witness) = memory_circuit().build();
+    let private: Vec<_> = [5u64, 0, 7, 10, 20, 30, 3]
+        .into_iter()
+        .map(Fp::from)
+        .collect();
+    prove_and_verify(gates, witness(&[], &private), vec![]);
+}
+
+#[test]
+// Reads the cells of a read-only memory at a private address, and the cell at
+// an address out of its bounds
+fn test_builder_rom() {
+    let params = Vesta::sponge_params();
+    let mut builder = CircuitBuilder::new();
+    let address = builder.private_input();
+    let expected = builder.private_input();
+    let values: Vec<_> = [3u64, 1, 4, 1, 5]
+        .into_iter()
+        .map(|value| builder.constant(Fp::from(value)))
+        .collect();
+    let rom = builder.rom(params, &values);
+    let value = builder.read(rom, address);
+    builder.assert_equal(value, expected);
+    let (gates, _, witness) = builder.build();
+
+    let runner = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness(&[], &[Fp::from(2u64), Fp::from(4u64)]))
+        .setup();
+    let index = runner.prover_index();
+    for (address, value) in [(0u64, 3u64), (4, 5)] {
+        let witness = witness(&[], &[Fp::from(address), Fp::from(value)]);
+        assert_eq!(index.verify(&witness, &[]), Ok(()));
+    }
+    // The cell at the address 5 reads as zero, but is out of the memory
+    let witness = witness(&[], &[Fp::from(5u64), Fp::zero()]);
+    assert!(index.verify(&witness, &[]).is_err());
+}
+
+#[test]
+#[should_panic(expected = "cannot write to a read-only memory")]
+fn test_builder_rom_write() {
+    let mut builder = CircuitBuilder::<Fp>::new();
+    let value = builder.private_input();
+    let rom = builder.rom(Vesta::sponge_params(), &[value]);
+    builder.write(rom, value, value);
+}
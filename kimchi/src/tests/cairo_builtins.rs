@@ -0,0 +1,122 @@
+use super::framework::TestFramework;
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::{CircuitGate, CircuitGateError},
+    polynomial::COLUMNS,
+    polynomials::{
+        cairo_builtins::{extend_cairo_bitwise_witness, extend_cairo_range_check_witness},
+        range_check,
+    },
+};
+use ark_ff::{Field, Zero};
+use core::array;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use turshi::{Bitwise, CairoMemory, RangeCheck};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type VestaBaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type VestaScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+const BITWISE_BYTES: usize = 8;
+
+// Creates the sub-circuits and the witness of the builtin instances of a memory
+fn builtins_circuit(mem: &CairoMemory<Fp>) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS]) {
+    let mut gates = vec![];
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    for segment in mem.builtins() {
+        for instance in segment.instances(mem) {
+            let cells: Vec<Fp> = instance.into_iter().map(Option::unwrap).collect();
+            match segment.builtin().name() {
+                "range_check" => {
+                    CircuitGate::extend_cairo_range_check(&mut gates);
+                    extend_cairo_range_check_witness(&mut witness, cells[0]);
+                }
+                "bitwise" => {
+                    CircuitGate::extend_cairo_bitwise(&mut gates, BITWISE_BYTES);
+                    extend_cairo_bitwise_witness(&mut witness, cells[0], cells[1], BITWISE_BYTES);
+                    let last = witness[0].len() - 1;
+                    assert_eq!(
+                        [witness[1][last], witness[0][last], witness[2][last]],
+                        [cells[2], cells[3], cells[4]]
+                    );
+                }
+                name => panic!("no sub-circuit for the {name} builtin"),
+            }
+        }
+    }
+    (gates, witness)
+}
+
+fn builtins_memory() -> CairoMemory<Fp> {
+    let mut mem = CairoMemory::new(vec![Fp::from(0x208b7fff7fff7ffeu64)]);
+    mem.add_builtin(10, RangeCheck);
+    mem.add_builtin(20, Bitwise::new(BITWISE_BYTES as u64 * 8));
+    mem.write(Fp::from(10u32), Fp::zero());
+    mem.write(Fp::from(11u32), Fp::from(u64::MAX));
+    mem.write(Fp::from(12u32), Fp::from(2u32).pow([128]) - Fp::from(1u32));
+    for (addr, value) in [(20u32, 0xdeadbeefu64), (21, 0xf0f0f0f0f0f0f0f0)] {
+        mem.write(Fp::from(addr), Fp::from(value));
+    }
+    for addr in 22..25u32 {
+        mem.read(Fp::from(addr)).unwrap();
+    }
+    mem.validate_builtins().unwrap();
+    mem
+}
+
+#[test]
+fn test_cairo_builtins_witness() {
+    let mem = builtins_memory();
+    let (gates, witness) = builtins_circuit(&mem);
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+
+    for row in 0..witness[0].len() {
+        assert_eq!(
+            cs.gates[row].verify_witness::<Vesta>(row, &witness, &cs, &witness[0][0..cs.public]),
+            Ok(())
+        );
+    }
+}
+
+#[test]
+fn test_cairo_range_check_overflow() {
+    let mut gates = vec![];
+    CircuitGate::<Fp>::extend_cairo_range_check(&mut gates);
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+
+    // 2^128 split as a 65-bit high limb
+    let two_to_64 = Fp::from(2u32).pow([64]);
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero()]);
+    witness[4][0] = two_to_64;
+    witness[5][0] = two_to_64.square();
+    range_check::witness::extend_single(&mut witness, Fp::zero());
+    range_check::witness::extend_single(&mut witness, two_to_64);
+
+    // the high limb breaks a copy constraint with the zero cell
+    let res = (0..witness[0].len())
+        .map(|row| {
+            cs.gates[row].verify_witness::<Vesta>(row, &witness, &cs, &witness[0][0..cs.public])
+        })
+        .find(Result::is_err);
+    assert!(matches!(
+        res,
+        Some(Err(CircuitGateError::CopyConstraint { .. }))
+    ));
+}
+
+#[test]
+fn test_prove_and_verify_cairo_builtins() {
+    let mem = builtins_memory();
+    let (gates, witness) = builtins_circuit(&mem);
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<VestaBaseSponge, VestaScalarSponge>()
+        .unwrap();
+}
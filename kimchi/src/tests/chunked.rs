@@ -1,10 +1,14 @@
 use super::framework::TestFramework;
-use crate::circuits::{
-    gate::CircuitGate,
-    polynomials::generic::GenericGateSpec,
-    wires::{Wire, COLUMNS},
+use crate::{
+    circuits::{
+        gate::CircuitGate,
+        polynomials::{generic::GenericGateSpec, xor},
+        wires::{Wire, COLUMNS},
+    },
+    proof::RecursionChallenge,
 };
 use ark_ff::{UniformRand, Zero};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Radix2EvaluationDomain as D};
 use core::array;
 use itertools::iterate;
 use mina_curves::pasta::{Fp, Vesta, VestaParameters};
@@ -12,17 +16,25 @@ use mina_poseidon::{
     constants::PlonkSpongeConstantsKimchi,
     sponge::{DefaultFqSponge, DefaultFrSponge},
 };
+use o1_utils::math;
+use poly_commitment::{commitment::b_poly_coefficients, ipa::SRS, SRS as _};
 
 type SpongeParams = PlonkSpongeConstantsKimchi;
 type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
 type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
 
-fn test_generic_gate_with_srs_override(
-    circuit_size_log_2: usize,
-    override_srs_size: Option<usize>,
-) {
+/// Creates an SRS of `size` points, smaller than the domain `d1` of the
+/// circuits of the tests, which are thus committed in several chunks
+fn small_srs(d1: D<Fp>, size: usize) -> SRS<Vesta> {
+    let srs = SRS::create(size);
+    srs.get_lagrange_basis(d1);
+    srs
+}
+
+/// Creates a circuit of `circuit_size` double generic gates, the first of
+/// which are 5 public inputs, with its witness
+fn generic_circuit(circuit_size: usize) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS], Vec<Fp>) {
     let public = vec![Fp::from(1u8); 5];
-    let circuit_size = (1 << circuit_size_log_2) - 15;
 
     let mut gates_row = iterate(0, |&i| i + 1);
     let mut gates = Vec::with_capacity(circuit_size);
@@ -75,6 +87,38 @@ fn test_generic_gate_with_srs_override(
         ));
     }
 
+    (gates, witness, public)
+}
+
+/// Creates a circuit of at least `circuit_size` rows of 64-bit xors, which
+/// use lookups, after one public input, with its witness
+fn xor_circuit(circuit_size: usize) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS], Vec<Fp>) {
+    let public = vec![Fp::from(1u8)];
+    let mut gates = vec![CircuitGate::create_generic_gadget(
+        Wire::for_row(0),
+        GenericGateSpec::Pub,
+        None,
+    )];
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero()]);
+    witness[0][0] = public[0];
+
+    let rng = &mut rand::rngs::OsRng;
+    while gates.len() < circuit_size {
+        CircuitGate::<Fp>::extend_xor_gadget(&mut gates, 64);
+        let input1 = Fp::from(u64::rand(rng));
+        let input2 = Fp::from(u64::rand(rng));
+        xor::extend_xor_witness(&mut witness, input1, input2, 64);
+    }
+
+    (gates, witness, public)
+}
+
+fn test_generic_gate_with_srs_override(
+    circuit_size_log_2: usize,
+    override_srs_size: Option<usize>,
+) {
+    let (gates, witness, public) = generic_circuit((1 << circuit_size_log_2) - 15);
+
     // create and verify proof based on the witness
     let framework = TestFramework::<Vesta>::default()
         .gates(gates)
@@ -91,23 +135,108 @@ fn test_generic_gate_with_srs_override(
         .unwrap();
 }
 
-// Disabled, too slow
-/*#[test]
-fn test_2_to_20_chunked_generic_gate_pub() {
-    test_generic_gate_with_srs_override(20, Some(1 << 16))
-}*/
+#[test]
+fn test_chunked_generic_gate_pub() {
+    let (gates, witness, public) = generic_circuit((1 << 10) - 15);
 
-// Disabled, too slow
-/*#[test]
-fn test_2_to_18_chunked_generic_gate_pub() {
+    let runner = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(public)
+        .override_srs_size(1 << 8)
+        .setup_with_custom_srs(small_srs);
+
+    let verifier_index = runner.prover_index().verifier_index();
+    assert_eq!(verifier_index.num_chunks(), 4);
+    assert_eq!(runner.prover_index().num_chunks(), 4);
+
+    runner
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+fn test_chunked_xor_lookups() {
+    for log_up in [false, true] {
+        let (gates, witness, public) = xor_circuit(600);
+
+        let runner = TestFramework::<Vesta>::default()
+            .gates(gates)
+            .witness(witness)
+            .public_inputs(public)
+            .log_up(log_up)
+            .override_srs_size(1 << 8)
+            .setup_with_custom_srs(small_srs);
+        assert_eq!(runner.prover_index().num_chunks(), 4);
+
+        runner
+            .prove_and_verify::<BaseSponge, ScalarSponge>()
+            .unwrap();
+    }
+}
+
+#[test]
+fn test_chunked_recursion() {
+    let (gates, witness, public) = generic_circuit((1 << 10) - 15);
+
+    let runner = TestFramework::<Vesta>::default()
+        .num_prev_challenges(1)
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(public)
+        .override_srs_size(1 << 8)
+        .setup_with_custom_srs(small_srs);
+
+    // previous opening for recursion, whose commitment has one chunk
+    let index = runner.prover_index();
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let prev_challenges = {
+        let k = math::ceil_log2(index.srs.g.len());
+        let chals: Vec<_> = (0..k).map(|_| Fp::rand(rng)).collect();
+        let comm = {
+            let coeffs = b_poly_coefficients(&chals);
+            let b = DensePolynomial::from_coefficients_vec(coeffs);
+            index.srs.commit_non_hiding(&b, 1)
+        };
+        RecursionChallenge::new(chals, comm)
+    };
+
+    runner
+        .recursion(vec![prev_challenges])
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+fn heavy_test_2_to_18_chunked_generic_gate_pub() {
     test_generic_gate_with_srs_override(18, Some(1 << 16))
-}*/
+}
 
 #[test]
 fn heavy_test_2_to_17_chunked_generic_gate_pub() {
     test_generic_gate_with_srs_override(17, Some(1 << 16))
 }
 
+#[test]
+fn heavy_test_2_to_17_chunked_xor_lookups() {
+    let (gates, witness, public) = xor_circuit((1 << 17) - 1000);
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(public)
+        .override_srs_size(1 << 16)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+// Disabled, too slow
+/*#[test]
+fn test_2_to_20_chunked_generic_gate_pub() {
+    test_generic_gate_with_srs_override(20, Some(1 << 16))
+}*/
+
 // Disabled; redundant, just for comparison
 /*#[test]
 fn test_2_to_16_unchunked_generic_gate_pub() {
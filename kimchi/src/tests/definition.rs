@@ -0,0 +1,104 @@
+use super::framework::TestFramework;
+use crate::circuits::{
+    definition::{CircuitDefinition, CircuitFormatError, CIRCUIT_FORMAT_VERSION},
+    lookup::{runtime_tables::RuntimeTableCfg, tables::LookupTable},
+    polynomials::generic::testing::{create_circuit, fill_in_witness},
+    wires::COLUMNS,
+};
+use ark_ff::Zero;
+use core::array;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+fn circuit_definition() -> CircuitDefinition<Fp> {
+    let mut definition = CircuitDefinition::new(5, create_circuit(0, 5));
+    definition.lookup_tables = vec![LookupTable::new(
+        2,
+        vec![
+            (0..8u64).map(Fp::from).collect(),
+            (0..8u64).map(|x| Fp::from(x * x)).collect(),
+        ],
+    )
+    .unwrap()];
+    definition.runtime_tables = Some(vec![RuntimeTableCfg::with_capacity(3, 4)]);
+    definition.log_up = true;
+    definition
+}
+
+#[test]
+fn test_circuit_definition_round_trip() {
+    let definition = circuit_definition();
+
+    let bytes = definition.to_bytes().unwrap();
+    assert_eq!(
+        CircuitDefinition::from_bytes(&bytes),
+        Ok(definition.clone())
+    );
+
+    let json = definition.to_json().unwrap();
+    assert!(json.contains(&format!("\"version\": {CIRCUIT_FORMAT_VERSION}")));
+    assert_eq!(CircuitDefinition::from_json(&json), Ok(definition.clone()));
+
+    let cs = definition.constraint_system().build().unwrap();
+    // The gates of the constraint system are padded with zero gates
+    assert_eq!(
+        &cs.gates[..definition.gates.len()],
+        definition.gates.as_slice()
+    );
+    assert_eq!(cs.public, 5);
+    assert!(cs.feature_flags.lookup_features.log_up);
+}
+
+#[test]
+fn test_circuit_definition_prove() {
+    let definition = CircuitDefinition::<Fp>::new(5, create_circuit(0, 5));
+    let definition = CircuitDefinition::from_bytes(&definition.to_bytes().unwrap()).unwrap();
+
+    let public = vec![Fp::from(3u8); definition.public_input_size];
+    let mut witness: [Vec<Fp>; COLUMNS] =
+        array::from_fn(|_| vec![Fp::zero(); definition.gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    TestFramework::<Vesta>::default()
+        .gates(definition.gates)
+        .witness(witness)
+        .public_inputs(public)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+fn test_circuit_definition_invalid_data() {
+    let definition = circuit_definition();
+
+    let mut bytes = definition.to_bytes().unwrap();
+    assert_eq!(
+        CircuitDefinition::<Fp>::from_bytes(&bytes[1..]),
+        Err(CircuitFormatError::InvalidMagic)
+    );
+    bytes[4] += 1;
+    assert_eq!(
+        CircuitDefinition::<Fp>::from_bytes(&bytes),
+        Err(CircuitFormatError::UnsupportedVersion(
+            CIRCUIT_FORMAT_VERSION + 1
+        ))
+    );
+
+    let json = definition.to_json().unwrap().replacen(
+        &format!("\"version\": {CIRCUIT_FORMAT_VERSION}"),
+        "\"version\": 0",
+        1,
+    );
+    assert_eq!(
+        CircuitDefinition::<Fp>::from_json(&json),
+        Err(CircuitFormatError::UnsupportedVersion(0))
+    );
+}
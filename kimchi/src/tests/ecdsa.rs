@@ -0,0 +1,104 @@
+use super::framework::{create_witness, find_generic_row, set_copied_cell, verify_rows};
+use crate::circuits::{
+    constraints::{ConstraintFailure, ConstraintSystem, GateError},
+    gate::{CircuitGate, GateType},
+    polynomial::COLUMNS,
+    polynomials::{
+        ecdsa::{gadget::EcdsaCells, witness::extend_ecdsa_verify_witness, Point},
+        foreign_field_common::BigUintForeignFieldHelpers,
+        keccak::gadget::extend_keccak256_witness,
+    },
+};
+use ark_ff::{One, Zero};
+use core::array;
+use mina_curves::pasta::Fp;
+use num_bigint::BigUint;
+use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+
+// Signs the Keccak-256 hash of `message` with a fixed key, and returns the
+// hash, the signature and the public key
+fn sign(message: &[u8]) -> (BigUint, BigUint, BigUint, Point) {
+    let mut scratch: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let hash = extend_keccak256_witness(&mut scratch, message);
+
+    let secp = Secp256k1::new();
+    let secret_key = SecretKey::from_slice(&[0x2a; 32]).unwrap();
+    let public_key = PublicKey::from_secret_key(&secp, &secret_key).serialize_uncompressed();
+    let signature = secp
+        .sign_ecdsa(&Message::from_digest_slice(&hash).unwrap(), &secret_key)
+        .serialize_compact();
+
+    (
+        BigUint::from_bytes_be(&hash),
+        BigUint::from_bytes_be(&signature[..32]),
+        BigUint::from_bytes_be(&signature[32..]),
+        (
+            BigUint::from_bytes_be(&public_key[1..33]),
+            BigUint::from_bytes_be(&public_key[33..]),
+        ),
+    )
+}
+
+#[test]
+// Verifies a signature of secp256k1 with the gadget, and checks that the
+// signature of another message is rejected
+fn test_ecdsa_verify() {
+    let (hash, r, s, public_key) = sign(b"kimchi");
+    let mut gates = vec![];
+    let EcdsaCells {
+        hash: hash_cells,
+        r: r_cells,
+        s: s_cells,
+        public_key: key_cells,
+    } = CircuitGate::<Fp>::extend_ecdsa_verify(&mut gates);
+    let (witness, valid) =
+        create_witness(|witness| extend_ecdsa_verify_witness(witness, &hash, &r, &s, &public_key));
+    assert!(valid);
+    assert_eq!(gates.len(), 65916);
+    assert_eq!(gates.len(), witness[0].len());
+
+    // The cells of the gadget hold the limbs of the inputs
+    for (cells, value) in [
+        (hash_cells, &hash),
+        (r_cells, &r),
+        (s_cells, &s),
+        (key_cells.0, &public_key.0),
+        (key_cells.1, &public_key.1),
+    ] {
+        for ((row, col), limb) in cells.into_iter().zip(value.to_field_limbs::<Fp>()) {
+            assert_eq!(witness[col][row], limb);
+        }
+    }
+
+    let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+    assert_eq!(verify_rows(&gates, &cs, &witness), Ok(()));
+
+    // The signature of another message fails the final check
+    let (other_hash, ..) = sign(b"mina");
+    let (wrong, valid) = create_witness(|witness| {
+        extend_ecdsa_verify_witness(witness, &other_hash, &r, &s, &public_key)
+    });
+    assert!(!valid);
+    assert!(verify_rows(&gates, &cs, &wrong).is_err());
+
+    // So does a witness claiming another hash
+    let mut wrong = witness.clone();
+    let (row, col) = hash_cells[0];
+    wrong[col][row] = Fp::zero();
+    assert!(verify_rows(&gates, &cs, &wrong).is_err());
+
+    // Flipping a bit of the decomposition of the scalar u1, along its copies,
+    // is only caught by the generic gates decomposing u1 and selecting the
+    // points to add
+    let bit_row = find_generic_row(&gates, [2, 1, -1, 0, 0, -1, 0, 0, 1, 0]);
+    let bit = witness[1][bit_row];
+    let mut wrong = witness;
+    set_copied_cell(&gates, &mut wrong, (bit_row, 1), Fp::one() - bit);
+    assert!(matches!(
+        verify_rows(&gates, &cs, &wrong),
+        Err(GateError::Constraint(ConstraintFailure {
+            typ: GateType::Generic,
+            ..
+        }))
+    ));
+}
@@ -0,0 +1,140 @@
+use super::framework::{create_witness, find_generic_row, set_copied_cell, verify_rows};
+use crate::circuits::{
+    constraints::{ConstraintFailure, ConstraintSystem, GateError},
+    gate::{CircuitGate, GateType},
+    polynomials::{
+        eddsa::{
+            challenge, decode, ed25519_generator, ed25519_order, encode, gadget::EddsaCells,
+            is_on_curve, scalar_mul, witness::extend_eddsa_verify_witness, Point,
+        },
+        foreign_field_common::BigUintForeignFieldHelpers,
+    },
+};
+use ark_ff::{One, Zero};
+use mina_curves::pasta::Fp;
+use num_bigint::BigUint;
+use sha2::{Digest, Sha512};
+
+// The secret key of the first test vector of RFC 8032
+const SECRET_KEY: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+
+// Signs `message` with the secret key `SECRET_KEY`, and returns the
+// signature and the public key
+fn sign(message: &[u8]) -> (Point, BigUint, Point) {
+    let l = ed25519_order();
+    let hash = Sha512::digest(hex::decode(SECRET_KEY).unwrap());
+    let mut scalar = [0u8; 32];
+    scalar.copy_from_slice(&hash[..32]);
+    scalar[0] &= 0xf8;
+    scalar[31] &= 0x7f;
+    scalar[31] |= 0x40;
+    let scalar = BigUint::from_bytes_le(&scalar);
+    let public_key = scalar_mul(&ed25519_generator(), &scalar);
+
+    let nonce = Sha512::new()
+        .chain_update(&hash[32..])
+        .chain_update(message)
+        .finalize();
+    let nonce = BigUint::from_bytes_le(&nonce) % &l;
+    let r = scalar_mul(&ed25519_generator(), &nonce);
+    let k = challenge::<Sha512>(&r, &public_key, message) % &l;
+    (r, (nonce + k * scalar) % &l, public_key)
+}
+
+#[test]
+// Checks the signing of the test with the first test vector of RFC 8032
+fn test_eddsa_rfc8032() {
+    let (r, s, public_key) = sign(b"");
+    assert_eq!(
+        hex::encode(encode(&public_key)),
+        "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"
+    );
+    let mut signature = encode(&r).to_vec();
+    signature.extend(s.to_bytes_le());
+    signature.resize(64, 0);
+    assert_eq!(
+        hex::encode(signature),
+        "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065\
+         224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"
+    );
+    assert!(is_on_curve(&r) && is_on_curve(&public_key));
+    assert_eq!(decode(&encode(&r)), Some(r));
+    assert_eq!(decode(&encode(&public_key)), Some(public_key));
+}
+
+#[test]
+// Verifies a signature of Ed25519 with the gadget, and checks that the
+// signature of another message and a malleated signature are rejected
+fn test_eddsa_verify() {
+    let (r, s, public_key) = sign(b"kimchi");
+    let digest = challenge::<Sha512>(&r, &public_key, b"kimchi");
+    let mut gates = vec![];
+    let EddsaCells {
+        digest: digest_cells,
+        r: r_cells,
+        s: s_cells,
+        public_key: key_cells,
+    } = CircuitGate::<Fp>::extend_eddsa_verify(&mut gates);
+    let (witness, valid) = create_witness(|witness| {
+        extend_eddsa_verify_witness(witness, &digest, &r, &s, &public_key)
+    });
+    assert!(valid);
+    assert_eq!(gates.len(), 85946);
+    assert_eq!(gates.len(), witness[0].len());
+
+    // The cells of the gadget hold the limbs of the inputs
+    let digest_limbs = [&digest % BigUint::binary_modulus(), &digest >> 264];
+    let digest_limbs = digest_limbs.iter().flat_map(|v| v.to_field_limbs::<Fp>());
+    for ((row, col), limb) in digest_cells.into_iter().zip(digest_limbs) {
+        assert_eq!(witness[col][row], limb);
+    }
+    for (cells, value) in [
+        (r_cells.0, &r.0),
+        (r_cells.1, &r.1),
+        (s_cells, &s),
+        (key_cells.0, &public_key.0),
+        (key_cells.1, &public_key.1),
+    ] {
+        for ((row, col), limb) in cells.into_iter().zip(value.to_field_limbs::<Fp>()) {
+            assert_eq!(witness[col][row], limb);
+        }
+    }
+
+    let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+    assert_eq!(verify_rows(&gates, &cs, &witness), Ok(()));
+
+    // The signature of another message fails the final check
+    let other_digest = challenge::<Sha512>(&r, &public_key, b"mina");
+    let (wrong, valid) = create_witness(|witness| {
+        extend_eddsa_verify_witness(witness, &other_digest, &r, &s, &public_key)
+    });
+    assert!(!valid);
+    assert!(verify_rows(&gates, &cs, &wrong).is_err());
+
+    // So does the malleated signature `(R, S + ℓ)`
+    let (wrong, _) = create_witness(|witness| {
+        extend_eddsa_verify_witness(witness, &digest, &r, &(&s + ed25519_order()), &public_key)
+    });
+    assert!(verify_rows(&gates, &cs, &wrong).is_err());
+
+    // And a witness claiming another digest
+    let mut wrong = witness.clone();
+    let (row, col) = digest_cells[0];
+    wrong[col][row] = Fp::zero();
+    assert!(verify_rows(&gates, &cs, &wrong).is_err());
+
+    // Flipping a bit of the decomposition of the scalar S, along its copies,
+    // is only caught by the generic gates decomposing S and selecting the
+    // points to add
+    let bit_row = find_generic_row(&gates, [2, 1, -1, 0, 0, -1, 0, 0, 1, 0]);
+    let bit = witness[1][bit_row];
+    let mut wrong = witness;
+    set_copied_cell(&gates, &mut wrong, (bit_row, 1), Fp::one() - bit);
+    assert!(matches!(
+        verify_rows(&gates, &cs, &wrong),
+        Err(GateError::Constraint(ConstraintFailure {
+            typ: GateType::Generic,
+            ..
+        }))
+    ));
+}
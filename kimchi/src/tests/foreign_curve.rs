@@ -0,0 +1,266 @@
+use super::framework::{verify_rows, TestFramework};
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::CircuitGate,
+    polynomial::COLUMNS,
+    polynomials::{
+        ecdsa::{secp256k1_generator, secp256k1_order},
+        foreign_curve::{
+            gadget::{ForeignCurveAddCells, ForeignCurveCircuit, ForeignCurveScalarMulCells},
+            witness::{
+                extend_foreign_curve_add_witness, extend_foreign_curve_scalar_mul_witness,
+                ForeignCurveWitness,
+            },
+            ForeignCurve, Point,
+        },
+        foreign_field_builder::{
+            gadget::{ElementCells, ForeignFieldCircuit},
+            sqrt,
+            witness::ForeignFieldWitness,
+        },
+        foreign_field_common::BigUintForeignFieldHelpers,
+    },
+};
+use ark_ff::{One, Zero};
+use core::array;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use num_bigint::BigUint;
+use o1_utils::BigUintHelpers;
+
+type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+fn prove_and_verify(gates: Vec<CircuitGate<Fp>>, witness: [Vec<Fp>; COLUMNS]) {
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+// Checks that the cells hold the limbs of `value`
+fn assert_cells(witness: &[Vec<Fp>; COLUMNS], cells: &ElementCells, value: &BigUint) {
+    for ((row, col), limb) in cells.iter().zip(value.to_field_limbs::<Fp>()) {
+        assert_eq!(witness[*col][*row], limb);
+    }
+}
+
+// The generator of secp256r1
+fn secp256r1_generator() -> Point {
+    (
+        BigUint::from_hex("6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296"),
+        BigUint::from_hex("4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5"),
+    )
+}
+
+fn add_witness(curve: &ForeignCurve, left: &Point, right: &Point) -> [Vec<Fp>; COLUMNS] {
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let sum = extend_foreign_curve_add_witness(&mut witness, curve, left, right);
+    assert_eq!(sum, curve.add(left, right));
+    witness
+}
+
+#[test]
+fn test_foreign_curve_arithmetic() {
+    for (curve, generator, order) in [
+        (
+            ForeignCurve::secp256k1(),
+            secp256k1_generator(),
+            secp256k1_order(),
+        ),
+        (
+            ForeignCurve::secp256r1(),
+            secp256r1_generator(),
+            BigUint::from_hex("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551"),
+        ),
+    ] {
+        assert!(curve.is_on_curve(&generator));
+        assert!(curve.is_on_curve(&curve.offset_point()));
+        assert_eq!(curve.scalar_mul(&generator, &order), None);
+        assert_eq!(
+            curve.scalar_mul(&generator, &(&order + BigUint::one())),
+            Some(generator.clone())
+        );
+        assert_eq!(
+            curve.scalar_mul(&generator, &BigUint::from(3u32)),
+            curve.add(&curve.double(&generator), &generator)
+        );
+        assert_eq!(curve.add(&generator, &curve.negate(&generator)), None);
+    }
+
+    // Square roots modulo a prime equal to 1 modulo 8
+    let p = BigUint::from(17u32);
+    for x in 1..17u32 {
+        let square = BigUint::from(x * x % 17);
+        let root = sqrt(&square, &p).unwrap();
+        assert_eq!(&root * &root % &p, square);
+    }
+    assert_eq!(sqrt(&BigUint::from(3u32), &p), None);
+}
+
+#[test]
+// Adds points of secp256k1, including a point to itself and to its opposite
+fn test_foreign_curve_add() {
+    let curve = ForeignCurve::secp256k1();
+    let g = secp256k1_generator();
+    let g2 = curve.double(&g);
+
+    let mut gates = vec![];
+    let ForeignCurveAddCells {
+        left,
+        right,
+        sum,
+        infinity,
+    } = CircuitGate::<Fp>::extend_foreign_curve_add(&mut gates, &curve);
+    assert_eq!(gates.len(), 397);
+    let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+
+    let witness = add_witness(&curve, &g, &g2);
+    let g3 = curve.add(&g, &g2).unwrap();
+    for (cells, value) in [
+        (left.0, &g.0),
+        (left.1, &g.1),
+        (right.0, &g2.0),
+        (right.1, &g2.1),
+        (sum.0, &g3.0),
+        (sum.1, &g3.1),
+    ] {
+        assert_cells(&witness, &cells, value);
+    }
+    assert_eq!(witness[infinity.1][infinity.0], Fp::zero());
+    prove_and_verify(gates.clone(), witness.clone());
+
+    // The flag of the point at infinity cannot be set
+    let mut wrong = witness;
+    wrong[infinity.1][infinity.0] = Fp::one();
+    assert!(verify_rows(&gates, &cs, &wrong).is_err());
+
+    // Doubling
+    let witness = add_witness(&curve, &g2, &g2);
+    assert_cells(&witness, &sum.0, &curve.double(&g2).0);
+    assert_eq!(witness[infinity.1][infinity.0], Fp::zero());
+    assert_eq!(verify_rows(&gates, &cs, &witness), Ok(()));
+
+    // Opposite points
+    let witness = add_witness(&curve, &g2, &curve.negate(&g2));
+    assert_eq!(witness[infinity.1][infinity.0], Fp::one());
+    assert_eq!(verify_rows(&gates, &cs, &witness), Ok(()));
+    let mut wrong = witness;
+    wrong[infinity.1][infinity.0] = Fp::zero();
+    assert!(verify_rows(&gates, &cs, &wrong).is_err());
+
+    // A point off the curve is rejected
+    let mut wrong = add_witness(&curve, &g, &g2);
+    let (row, col) = left.1[0];
+    wrong[col][row] += Fp::one();
+    assert!(verify_rows(&gates, &cs, &wrong).is_err());
+}
+
+#[test]
+// Multiplies a point of secp256r1, whose parameter `a` is not zero, by a
+// 16-bit scalar with windows of 3 bits
+fn test_foreign_curve_scalar_mul() {
+    let curve = ForeignCurve::secp256r1();
+    let point = curve
+        .scalar_mul(&secp256r1_generator(), &BigUint::from(0xc0ffeeu32))
+        .unwrap();
+    let scalar = BigUint::from(0xbeefu32);
+
+    let mut gates = vec![];
+    let ForeignCurveScalarMulCells {
+        point: point_cells,
+        scalar: scalar_cells,
+        product,
+        infinity,
+    } = CircuitGate::<Fp>::extend_foreign_curve_scalar_mul(&mut gates, &curve, 16, 3);
+    let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let expected = curve.scalar_mul(&point, &scalar).unwrap();
+    assert_eq!(
+        extend_foreign_curve_scalar_mul_witness(&mut witness, &curve, &point, &scalar, 16, 3),
+        Some(expected.clone())
+    );
+    assert_eq!(gates.len(), witness[0].len());
+    for (cells, value) in [
+        (point_cells.0, &point.0),
+        (point_cells.1, &point.1),
+        (scalar_cells, &scalar),
+        (product.0, &expected.0),
+        (product.1, &expected.1),
+    ] {
+        assert_cells(&witness, &cells, value);
+    }
+    assert_eq!(witness[infinity.1][infinity.0], Fp::zero());
+    assert_eq!(verify_rows(&gates, &cs, &witness), Ok(()));
+    prove_and_verify(gates.clone(), witness.clone());
+
+    // A product by zero is the point at infinity
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let zero = BigUint::zero();
+    assert_eq!(
+        extend_foreign_curve_scalar_mul_witness(&mut witness, &curve, &point, &zero, 16, 3),
+        None
+    );
+    assert_eq!(witness[infinity.1][infinity.0], Fp::one());
+    assert_eq!(verify_rows(&gates, &cs, &witness), Ok(()));
+
+    // The flag of the point at infinity cannot be cleared
+    let mut wrong = witness;
+    wrong[infinity.1][infinity.0] = Fp::zero();
+    assert!(verify_rows(&gates, &cs, &wrong).is_err());
+}
+
+#[test]
+// Chains the gadgets within a foreign field circuit over secp256k1:
+// 2 * (P + Q) with a doubling, checked against a scalar multiplication
+fn test_foreign_curve_chain() {
+    let curve = ForeignCurve::secp256k1();
+    let p = secp256k1_generator();
+    let q = curve.double(&curve.double(&p));
+    let scalar = BigUint::from(10u32);
+
+    let mut gates = vec![];
+    let mut builder = ForeignFieldCircuit::new(&mut gates);
+    let circuit = ForeignCurveCircuit::new(&mut builder, &curve);
+    let p_cells = circuit.point(&mut builder);
+    let q_cells = circuit.point(&mut builder);
+    let (sum, _) = circuit.add(&mut builder, &p_cells, &q_cells);
+    let doubled = circuit.double(&mut builder, &sum);
+    let (_, product, _) = circuit.scalar_mul(&mut builder, &p_cells, 4, 2);
+    builder.assert_equal(&doubled.0, &product.0);
+    builder.assert_equal(&doubled.1, &product.1);
+    builder.finish();
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let mut builder = ForeignFieldWitness::new(&mut witness);
+    let circuit = ForeignCurveWitness::new(&mut builder, &curve);
+    circuit.point(&mut builder, &p);
+    circuit.point(&mut builder, &q);
+    let (sum, _) = circuit.add(&mut builder, &p, &q);
+    let doubled = circuit.double(&mut builder, &sum);
+    let (product, infinity) = circuit.scalar_mul(&mut builder, &p, &scalar, 4, 2);
+    builder.finish();
+    assert!(!infinity);
+    assert_eq!(doubled, product);
+
+    let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+    assert_eq!(verify_rows(&gates, &cs, &witness), Ok(()));
+}
+
+#[test]
+fn test_foreign_curve_scalar_mul_rows() {
+    let mut gates = vec![];
+    CircuitGate::<Fp>::extend_foreign_curve_scalar_mul(
+        &mut gates,
+        &ForeignCurve::secp256k1(),
+        256,
+        4,
+    );
+    assert_eq!(gates.len(), 51242);
+}
@@ -0,0 +1,158 @@
+use super::framework::{verify_rows, TestFramework};
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::CircuitGate,
+    polynomial::COLUMNS,
+    polynomials::{
+        foreign_field_builder::{gadget::ForeignFieldCircuit, witness::ForeignFieldWitness},
+        foreign_field_wide::{
+            gadget::{self, WideForeignFieldMulCells},
+            witness::{self, extend_wide_foreign_field_mul_witness},
+        },
+        modexp::{limb_count, to_limbs},
+    },
+};
+use ark_ff::One;
+use core::array;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use num_bigint::{BigUint, RandBigInt};
+
+type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+// A random odd modulus of `bits` bits
+fn random_modulus(bits: usize) -> BigUint {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    rng.gen_biguint(bits as u64) | (BigUint::one() << (bits - 1)) | BigUint::one()
+}
+
+// Checks the multiplication of random elements modulo a random modulus of
+// `bits` bits, and returns the gates, the witness and the cells
+fn test_wide_mul(
+    bits: usize,
+    rows: usize,
+) -> (
+    Vec<CircuitGate<Fp>>,
+    [Vec<Fp>; COLUMNS],
+    WideForeignFieldMulCells,
+) {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let modulus = random_modulus(bits);
+    let left = rng.gen_biguint_below(&modulus);
+    let right = rng.gen_biguint_below(&modulus);
+
+    let mut gates = vec![];
+    let cells = CircuitGate::<Fp>::extend_wide_foreign_field_mul(&mut gates, &modulus);
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let result = extend_wide_foreign_field_mul_witness(&mut witness, &left, &right, &modulus);
+    assert_eq!(result, &left * &right % &modulus);
+    assert_eq!(gates.len(), rows);
+    assert_eq!(gates.len(), witness[0].len());
+
+    // The cells of the gadget hold the limbs of the inputs and of the result
+    let count = limb_count(bits);
+    for (cells, value) in [
+        (&cells.left, &left),
+        (&cells.right, &right),
+        (&cells.result, &result),
+    ] {
+        assert_eq!(cells.len(), count);
+        for (&(row, col), limb) in cells.iter().zip(to_limbs(value, count)) {
+            assert_eq!(witness[col][row], Fp::from(limb));
+        }
+    }
+
+    let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+    assert_eq!(verify_rows(&gates, &cs, &witness), Ok(()));
+    (gates, witness, cells)
+}
+
+#[test]
+// Multiplies modulo a 512-bit modulus, and proves it
+fn test_wide_mul_512() {
+    let (gates, witness, _) = test_wide_mul(512, 236);
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+// Multiplies modulo a 1024-bit modulus, and checks that another result is
+// rejected
+fn test_wide_mul_1024() {
+    let (gates, witness, cells) = test_wide_mul(1024, 579);
+
+    let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+    let mut wrong = witness;
+    let (row, col) = cells.result[0];
+    wrong[col][row] += Fp::one();
+    assert!(verify_rows(&gates, &cs, &wrong).is_err());
+}
+
+#[test]
+// Chains a multiplication and a squaring within a foreign field circuit
+fn test_wide_mul_chain() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let modulus = random_modulus(768);
+    let (a, b) = (
+        rng.gen_biguint_below(&modulus),
+        rng.gen_biguint_below(&modulus),
+    );
+
+    let mut gates = vec![];
+    let mut builder = ForeignFieldCircuit::<Fp>::new(&mut gates);
+    let a_cells = gadget::wide_element(&mut builder, &modulus);
+    let b_cells = gadget::wide_element(&mut builder, &modulus);
+    let product = gadget::wide_mul(&mut builder, &a_cells, &b_cells, &modulus);
+    let square = gadget::wide_mul(&mut builder, &product, &product, &modulus);
+    builder.finish();
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let mut builder = ForeignFieldWitness::new(&mut witness);
+    witness::wide_element(&mut builder, &a, &modulus);
+    witness::wide_element(&mut builder, &b, &modulus);
+    let product_value = witness::wide_mul(&mut builder, &a, &b, &modulus);
+    let square_value = witness::wide_mul(&mut builder, &product_value, &product_value, &modulus);
+    builder.finish();
+    assert_eq!(
+        square_value,
+        (&a * &b).modpow(&BigUint::from(2u32), &modulus)
+    );
+
+    let count = limb_count(768);
+    for (&(row, col), limb) in square.iter().zip(to_limbs(&square_value, count)) {
+        assert_eq!(witness[col][row], Fp::from(limb));
+    }
+    let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+    assert_eq!(verify_rows(&gates, &cs, &witness), Ok(()));
+}
+
+#[test]
+// Checks that an element equal to the modulus is rejected by the bound check
+fn test_wide_element_bound() {
+    let modulus = random_modulus(512);
+    let mut gates = vec![];
+    let mut builder = ForeignFieldCircuit::<Fp>::new(&mut gates);
+    let cells = gadget::wide_element(&mut builder, &modulus);
+    builder.finish();
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let mut builder = ForeignFieldWitness::new(&mut witness);
+    witness::wide_element(&mut builder, &(&modulus - 1u32), &modulus);
+    builder.finish();
+    let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+    assert_eq!(verify_rows(&gates, &cs, &witness), Ok(()));
+
+    // Increments the element to the modulus, whose lowest limb is odd
+    let (row, col) = cells[0];
+    witness[col][row] += Fp::one();
+    assert!(verify_rows(&gates, &cs, &witness).is_err());
+}
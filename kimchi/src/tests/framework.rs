@@ -2,29 +2,32 @@
 
 use crate::{
     circuits::{
-        gate::CircuitGate,
+        constraints::{ConstraintSystem, GateError},
+        gate::{CircuitGate, GateType},
         lookup::{
             runtime_tables::{RuntimeTable, RuntimeTableCfg},
             tables::LookupTable,
         },
-        wires::COLUMNS,
+        wires::{Wire, COLUMNS},
     },
     curve::KimchiCurve,
+    error::ProverError,
     plonk_sponge::FrSponge,
     proof::{ProverProof, RecursionChallenge},
     prover_index::{
         testing::{
             new_index_for_test_with_lookups, new_index_for_test_with_lookups_and_custom_srs,
         },
-        ProverIndex,
+        ProverIndex, ProverMemoryMode,
     },
-    verifier::verify,
+    verifier::{verify, verify_runtime_tables},
     verifier_index::VerifierIndex,
 };
 use ark_ff::PrimeField;
 use ark_poly::Radix2EvaluationDomain as D;
-use core::fmt::Write;
+use core::{array, fmt::Write};
 use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Vesta};
 use mina_poseidon::sponge::FqSponge;
 use num_bigint::BigUint;
 use poly_commitment::{
@@ -49,6 +52,53 @@ fn heap_allocated() -> usize {
 
 // aliases
 
+/// The failure expected by [TestRunner::prove_expecting_failure]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FailureKind {
+    /// The constraint of index `constraint` (starting from 1) of the gate at
+    /// `row` is not satisfied by the witness
+    Constraint {
+        row: usize,
+        typ: GateType,
+        constraint: usize,
+    },
+    /// The cell `src` is wired to the cell `dst` which has a different value
+    DisconnectedWires { src: Wire, dst: Wire },
+    /// The lookup at `row` does not find its value in the tables
+    ValueNotInTable { row: usize },
+    /// The runtime tables do not match the configuration of the index
+    RuntimeTablesInconsistent,
+}
+
+impl FailureKind {
+    fn matches_gate_error<F: core::fmt::Display>(&self, err: &GateError<F>) -> bool {
+        match (self, err) {
+            (
+                FailureKind::Constraint {
+                    row,
+                    typ,
+                    constraint,
+                },
+                GateError::Constraint(failure),
+            ) => failure.row == *row && failure.typ == *typ && failure.constraint == *constraint,
+            (FailureKind::DisconnectedWires { src, dst }, GateError::DisconnectedWires(s, d)) => {
+                src == s && dst == d
+            }
+            _ => false,
+        }
+    }
+
+    fn matches_prover_error(&self, err: &ProverError) -> bool {
+        match (self, err) {
+            (FailureKind::ValueNotInTable { row }, ProverError::ValueNotInTable(r)) => row == r,
+            (FailureKind::RuntimeTablesInconsistent, ProverError::RuntimeTablesInconsistent) => {
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
 #[derive(Default, Clone)]
 pub(crate) struct TestFramework<G: KimchiCurve, OpeningProof: OpenProof<G> = DlogOpeningProof<G>>
 where
@@ -62,11 +112,15 @@ where
     lookup_tables: Vec<LookupTable<G::ScalarField>>,
     runtime_tables_setup: Option<Vec<RuntimeTableCfg<G::ScalarField>>>,
     runtime_tables: Vec<RuntimeTable<G::ScalarField>>,
+    public_runtime_tables: bool,
+    expected_runtime_tables: Option<Vec<RuntimeTable<G::ScalarField>>>,
     recursion: Vec<RecursionChallenge<G>>,
     num_prev_challenges: usize,
     disable_gates_checks: bool,
     override_srs_size: Option<usize>,
     lazy_mode: bool,
+    log_up: bool,
+    memory_mode: ProverMemoryMode,
 
     prover_index: Option<ProverIndex<G, OpeningProof>>,
     verifier_index: Option<VerifierIndex<G, OpeningProof>>,
@@ -128,6 +182,24 @@ where
         self
     }
 
+    /// Declares runtime tables by their IDs and capacities, see
+    /// [RuntimeTableCfg::with_capacity]
+    #[must_use]
+    pub(crate) fn runtime_tables_capacities(self, capacities: Vec<(i32, usize)>) -> Self {
+        self.runtime_tables_setup(
+            capacities
+                .into_iter()
+                .map(|(id, capacity)| RuntimeTableCfg::with_capacity(id, capacity))
+                .collect(),
+        )
+    }
+
+    #[must_use]
+    pub(crate) fn public_runtime_tables(mut self, public_runtime_tables: bool) -> Self {
+        self.public_runtime_tables = public_runtime_tables;
+        self
+    }
+
     #[must_use]
     pub(crate) fn disable_gates_checks(mut self, disable_gates_checks: bool) -> Self {
         self.disable_gates_checks = disable_gates_checks;
@@ -146,6 +218,18 @@ where
         self
     }
 
+    #[must_use]
+    pub(crate) fn log_up(mut self, log_up: bool) -> Self {
+        self.log_up = log_up;
+        self
+    }
+
+    #[must_use]
+    pub(crate) fn memory_mode(mut self, memory_mode: ProverMemoryMode) -> Self {
+        self.memory_mode = memory_mode;
+        self
+    }
+
     pub(crate) fn with_logs(mut self, with_logs: bool) -> Self {
         self.with_logs = with_logs;
         self
@@ -164,7 +248,7 @@ where
         let lookup_tables = core::mem::take(&mut self.lookup_tables);
         let runtime_tables_setup = self.runtime_tables_setup.take();
 
-        let index = new_index_for_test_with_lookups_and_custom_srs(
+        let mut index = new_index_for_test_with_lookups_and_custom_srs(
             self.gates.take().unwrap(),
             self.public_inputs.len(),
             self.num_prev_challenges,
@@ -174,6 +258,7 @@ where
             self.override_srs_size,
             get_srs,
             self.lazy_mode,
+            self.log_up,
         );
         println!(
             "- time to create prover index: {:?}s",
@@ -187,6 +272,8 @@ where
             );
         }
 
+        index.public_runtime_tables = self.public_runtime_tables;
+        let index = index.with_memory_mode(self.memory_mode);
         self.verifier_index = Some(index.verifier_index());
         self.prover_index = Some(index);
 
@@ -206,7 +293,7 @@ where
         let lookup_tables = core::mem::take(&mut self.lookup_tables);
         let runtime_tables_setup = self.runtime_tables_setup.take();
 
-        let index = new_index_for_test_with_lookups::<G>(
+        let mut index = new_index_for_test_with_lookups::<G>(
             self.gates.take().unwrap(),
             self.public_inputs.len(),
             self.num_prev_challenges,
@@ -215,6 +302,7 @@ where
             self.disable_gates_checks,
             self.override_srs_size,
             self.lazy_mode,
+            self.log_up,
         );
         println!(
             "- time to create prover index: {:?}s",
@@ -229,6 +317,8 @@ where
             );
         };
 
+        index.public_runtime_tables = self.public_runtime_tables;
+        let index = index.with_memory_mode(self.memory_mode);
         self.verifier_index = Some(index.verifier_index());
         self.prover_index = Some(index);
 
@@ -252,6 +342,17 @@ where
         self
     }
 
+    /// Checks the commitment to the runtime tables of the proof against the
+    /// expected tables, which requires public runtime tables
+    #[must_use]
+    pub(crate) fn expected_runtime_tables(
+        mut self,
+        expected_runtime_tables: Vec<RuntimeTable<G::ScalarField>>,
+    ) -> Self {
+        self.0.expected_runtime_tables = Some(expected_runtime_tables);
+        self
+    }
+
     #[must_use]
     pub(crate) fn recursion(mut self, recursion: Vec<RecursionChallenge<G>>) -> Self {
         self.0.recursion = recursion;
@@ -268,9 +369,10 @@ where
         self.0.prover_index.as_ref().unwrap()
     }
 
-    /// Create a proof. This helper can be used when we want to test the prover
-    /// raises an exception
-    pub(crate) fn prove<EFqSponge, EFrSponge>(self) -> Result<(), String>
+    /// Create a proof expecting it to fail with the `expected` failure, either
+    /// when verifying the witness or while proving, and panic otherwise. This
+    /// helper is meant for the soundness tests of the gadgets.
+    pub(crate) fn prove_expecting_failure<EFqSponge, EFrSponge>(self, expected: FailureKind)
     where
         EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
         EFrSponge: FrSponge<G::ScalarField>,
@@ -278,17 +380,19 @@ where
         let prover = self.0.prover_index.unwrap();
         let witness = self.0.witness.unwrap();
 
-        if !self.0.disable_gates_checks {
-            // Note: this is already done by ProverProof::create_recursive::()
-            //       not sure why we do it here
-            prover
-                .verify(&witness, &self.0.public_inputs)
-                .map_err(|e| format!("{e:?}"))?;
+        // the witness is verified even if the gates checks are disabled, as
+        // most failures are only caught there
+        if let Err(err) = prover.verify(&witness, &self.0.public_inputs) {
+            assert!(
+                expected.matches_gate_error(&err),
+                "expected {expected:?}, but the witness verification failed with: {err}"
+            );
+            return;
         }
 
         let group_map = <G as CommitmentCurve>::Map::setup();
 
-        ProverProof::create_recursive::<EFqSponge, EFrSponge, _>(
+        let res = ProverProof::create_recursive::<EFqSponge, EFrSponge, _>(
             &group_map,
             witness,
             &self.0.runtime_tables,
@@ -296,9 +400,14 @@ where
             self.0.recursion,
             None,
             &mut rand::rngs::OsRng,
-        )
-        .map_err(|e| e.to_string())?;
-        Ok(())
+        );
+        match res {
+            Ok(_) => panic!("expected {expected:?}, but the proof was created"),
+            Err(err) => assert!(
+                expected.matches_prover_error(&err),
+                "expected {expected:?}, but the prover failed with: {err}"
+            ),
+        }
     }
 
     /// Create and verify a proof
@@ -315,7 +424,7 @@ where
             //       not sure why we do it here
             prover
                 .verify(&witness, &self.0.public_inputs)
-                .map_err(|e| format!("{e:?}"))?;
+                .map_err(|e| e.to_string())?;
         }
 
         // add the proof to the batch
@@ -360,6 +469,12 @@ where
             &self.0.public_inputs,
         )
         .map_err(|e| e.to_string())?;
+        if let Some(expected_runtime_tables) = &self.0.expected_runtime_tables {
+            let expected = prover
+                .runtime_table_commitment(expected_runtime_tables)
+                .map_err(|e| e.to_string())?;
+            verify_runtime_tables(&proof, &expected).map_err(|e| e.to_string())?;
+        }
         println!("- time to verify: {}ms", start.elapsed().as_millis());
         if self.0.with_logs {
             let bytes = heap_allocated();
@@ -410,7 +525,7 @@ where
             //       not sure why we do it here
             prover
                 .verify(&witness, &self.0.public_inputs)
-                .map_err(|e| format!("{e:?}"))?;
+                .map_err(|e| e.to_string())?;
         }
 
         let group_map = <G as CommitmentCurve>::Map::setup();
@@ -450,3 +565,66 @@ where
         println!("{line}");
     }
 }
+
+/// Creates a witness by extending an empty one with `extend`, and returns it
+/// with the output of `extend`
+pub(crate) fn create_witness<F: PrimeField, T>(
+    extend: impl FnOnce(&mut [Vec<F>; COLUMNS]) -> T,
+) -> ([Vec<F>; COLUMNS], T) {
+    let mut witness: [Vec<F>; COLUMNS] = array::from_fn(|_| vec![]);
+    let output = extend(&mut witness);
+    (witness, output)
+}
+
+/// Sets the cell `(row, col)` of `witness` to `value`, as well as the cells
+/// it is copied to by the wires of `gates`, so that the copy constraints still
+/// hold and only the constraints of the gates can reject the witness
+pub(crate) fn set_copied_cell(
+    gates: &[CircuitGate<Fp>],
+    witness: &mut [Vec<Fp>; COLUMNS],
+    (row, col): (usize, usize),
+    value: Fp,
+) {
+    let mut cell = Wire { row, col };
+    loop {
+        witness[cell.col][cell.row] = value;
+        cell = gates[cell.row].wires[cell.col];
+        if cell == (Wire { row, col }) {
+            break;
+        }
+    }
+}
+
+/// Returns the row of the first generic gate of `gates` of coefficients
+/// `coeffs`, given as small signed integers
+pub(crate) fn find_generic_row(gates: &[CircuitGate<Fp>], coeffs: [i64; 10]) -> usize {
+    let coeffs = coeffs.map(|c| match c {
+        c if c < 0 => -Fp::from(c.unsigned_abs()),
+        c => Fp::from(c.unsigned_abs()),
+    });
+    gates
+        .iter()
+        .position(|gate| gate.typ == GateType::Generic && gate.coeffs == coeffs)
+        .expect("no generic gate has the coefficients")
+}
+
+/// Checks the copy constraints and the constraints of the gates `gates` of
+/// `cs` on `witness`, generic gates included, one row at a time from the last
+/// one, where the gadgets have their final checks. The error is the one of
+/// the first failing row. The lookups are not checked.
+pub(crate) fn verify_rows(
+    gates: &[CircuitGate<Fp>],
+    cs: &ConstraintSystem<Fp>,
+    witness: &[Vec<Fp>; COLUMNS],
+) -> Result<(), GateError<Fp>> {
+    for (row, gate) in gates.iter().enumerate().rev() {
+        gate.verify_witness::<Vesta>(row, witness, cs, &[])
+            .map_err(|err| GateError::Gate { row, err })?;
+        // the constraints of the generic gates are not evaluated by
+        // CircuitGate::verify_witness
+        if gate.typ == GateType::Generic {
+            gate.verify_generic(row, witness, &[])?;
+        }
+    }
+    Ok(())
+}
@@ -1,15 +1,24 @@
-use super::framework::TestFramework;
-use crate::circuits::{
-    polynomials::generic::testing::{create_circuit, fill_in_witness},
-    wires::COLUMNS,
+use super::framework::{FailureKind, TestFramework};
+use crate::{
+    circuits::{
+        gate::{Connect, GateType},
+        polynomials::generic::testing::{create_circuit, fill_in_witness},
+        wires::{Wire, COLUMNS},
+    },
+    error::VerifyError,
+    proof::ProverProof,
+    prover_index::testing::new_index_for_test,
+    verifier::{batch_verify_same_index, public_commitment, verify_with_public_commitment},
 };
-use ark_ff::Zero;
+use ark_ff::{One, Zero};
 use core::array;
+use groupmap::GroupMap;
 use mina_curves::pasta::{Fp, Vesta, VestaParameters};
 use mina_poseidon::{
     constants::PlonkSpongeConstantsKimchi,
     sponge::{DefaultFqSponge, DefaultFrSponge},
 };
+use poly_commitment::{commitment::CommitmentCurve, ipa::OpeningProof};
 
 type SpongeParams = PlonkSpongeConstantsKimchi;
 type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
@@ -89,6 +98,46 @@ fn test_generic_gate_pub_empty() {
         .unwrap();
 }
 
+#[test]
+fn test_generic_gate_bad_witness() {
+    let gates = create_circuit(0, 0);
+
+    // create witness, with a wrong output of the addition of the fourth row
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+    witness[2][3] += Fp::one();
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_expecting_failure::<BaseSponge, ScalarSponge>(FailureKind::Constraint {
+            row: 3,
+            typ: GateType::Generic,
+            constraint: 1,
+        });
+}
+
+#[test]
+fn test_generic_gate_disconnected_wires() {
+    let mut gates = create_circuit(0, 0);
+    gates.connect_cell_pair((0, 0), (1, 3));
+
+    // create witness, with a different value in the second wired cell
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+    witness[3][1] += Fp::one();
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_expecting_failure::<BaseSponge, ScalarSponge>(FailureKind::DisconnectedWires {
+            src: Wire { row: 0, col: 0 },
+            dst: Wire { row: 1, col: 3 },
+        });
+}
+
 #[cfg(feature = "bn254")]
 #[test]
 fn test_generic_gate_kzg() {
@@ -122,3 +171,109 @@ fn test_generic_gate_kzg() {
     .prove_and_verify::<BaseSponge, ScalarSponge>()
     .unwrap();
 }
+
+#[test]
+fn test_batch_verify_same_index() {
+    let gates = create_circuit(0, 5);
+    let rows = gates.len();
+    let index = new_index_for_test::<Vesta>(gates, 5);
+    let verifier_index = index.verifier_index();
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+
+    let publics: Vec<Vec<Fp>> = (0..3u8).map(|i| vec![Fp::from(i); 5]).collect();
+    let proofs: Vec<_> = publics
+        .iter()
+        .map(|public| {
+            let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); rows]);
+            fill_in_witness(0, &mut witness, public);
+            ProverProof::create::<BaseSponge, ScalarSponge, _>(
+                &group_map,
+                witness,
+                &[],
+                &index,
+                &mut rand::rngs::OsRng,
+            )
+            .unwrap()
+        })
+        .collect();
+
+    let mut batch: Vec<_> = proofs
+        .iter()
+        .zip(&publics)
+        .map(|(proof, public)| (proof, public.as_slice()))
+        .collect();
+    batch_verify_same_index::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>>(
+        &group_map,
+        &verifier_index,
+        &batch,
+    )
+    .unwrap();
+
+    // a proof given with the public input of another one
+    batch[1].1 = &publics[2];
+    assert!(matches!(
+        batch_verify_same_index::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>>(
+            &group_map,
+            &verifier_index,
+            &batch,
+        ),
+        Err(VerifyError::OpenProof)
+    ));
+}
+
+#[test]
+fn test_verify_with_public_commitment() {
+    let public = vec![Fp::from(5u8); 20];
+    let gates = create_circuit(0, public.len());
+    let rows = gates.len();
+    let index = new_index_for_test::<Vesta>(gates, public.len());
+    let verifier_index = index.verifier_index();
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); rows]);
+    fill_in_witness(0, &mut witness, &public);
+    let proof = ProverProof::create::<BaseSponge, ScalarSponge, _>(
+        &group_map,
+        witness,
+        &[],
+        &index,
+        &mut rand::rngs::OsRng,
+    )
+    .unwrap();
+
+    let public_comm = public_commitment(&verifier_index, &public).unwrap();
+    verify_with_public_commitment::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>>(
+        &group_map,
+        &verifier_index,
+        &proof,
+        &public_comm,
+    )
+    .unwrap();
+
+    // the commitment to another public input
+    let mut other = public.clone();
+    other[3] = Fp::from(6u8);
+    let other_comm = public_commitment(&verifier_index, &other).unwrap();
+    assert!(
+        verify_with_public_commitment::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>>(
+            &group_map,
+            &verifier_index,
+            &proof,
+            &other_comm,
+        )
+        .is_err()
+    );
+
+    // a proof without the evaluations of its public input polynomial
+    let mut proof = proof;
+    proof.evals.public = None;
+    assert!(matches!(
+        verify_with_public_commitment::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>>(
+            &group_map,
+            &verifier_index,
+            &proof,
+            &public_comm,
+        ),
+        Err(VerifyError::MissingPublicInputEvaluation)
+    ));
+}
@@ -1,16 +1,39 @@
 use core::array;
 
+use super::framework::TestFramework;
 use crate::{
-    circuits::polynomials::keccak::{
-        constants::KECCAK_COLS, witness::extend_keccak_witness, Keccak,
+    circuits::{
+        gate::CircuitGate,
+        polynomial::COLUMNS,
+        polynomials::keccak::{
+            constants::{KECCAK_COLS, ROUNDS},
+            gadget::{extend_keccak256_witness, RATE_WORDS, ROUND_ROWS},
+            witness::extend_keccak_witness,
+            Keccak,
+        },
     },
     curve::KimchiCurve,
 };
-use ark_ff::{Field, PrimeField, Zero};
-use mina_curves::pasta::Pallas;
+use ark_ff::{Field, One, PrimeField, Zero};
+use mina_curves::pasta::{Fp, Pallas, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
 use num_bigint::BigUint;
 use o1_utils::{BigUintHelpers, FieldHelpers};
 
+/// A random message of 1080 bits
+const RANDOM_BLOCK: &str = "832588523900cca2ea9b8c0395d295aa39f9a9285a982b71cc8475067a8175f38f235a2234abc982a2dfaaddff2895a28598021895206a733a22bccd21f124df1413858a8f9a1134df285a888b099a8c2235eecdf2345f3afd32f3ae323526689172850672938104892357aad32523523f423423a214325d13523aadb21414124aaadf32523126";
+const RANDOM_BLOCK_HASH: &str = "845e9dd4e22b4917a80c5419a0ddb3eebf5f4f7cc6035d827314a18b718f751f";
+
+/// A message of 3 blocks
+const THREE_BLOCKS: &str = "832588523900cca2ea9b8c0395d295aa39f9a9285a982b71cc8475067a8175f38f235a2234abc982a2dfaaddff2895a28598021895206a733a22bccd21f124df1413858a8f9a1134df285a888b099a8c2235eecdf2345f3afd32f3ae323526689172850672938104892357aad32523523f423423a214325d13523aadb21414124aaadf32523126832588523900cca2ea9b8c0395d295aa39f9a9285a982b71cc8475067a8175f38f235a2234abc982a2dfaaddff2895a28598021895206a733a22bccd21f124df1413858a8f9a1134df285a888b099a8c2235eecdf2345f3afd32f3ae323526689172850672938104892357aad32523523f423423a214325d13523aadb21414124aaadf32523126832588523900cca2ea9b8c0395d295aa39f9a9285a982b71cc8475067a8175f38f235a2234abc982a2dfaaddff2895a28598021895206a733a22bccd21f124df1413858a8f9a1134df285a888b099a8c2235eecdf2345f3afd32f3ae323526689172850672938104892357aad32523523f";
+const THREE_BLOCKS_HASH: &str = "7e369e1a4362148fca24c67c76f14dbe24b75c73e9b0efdb8c46056c8514287e";
+
+/// The hash of the message of one zero byte
+const ZERO_HASH: &str = "bc36789e7a1e281436464229828f817d6612f7b477d66591ff96a9e064bcc98a";
+
 fn create_keccak_witness<G: KimchiCurve>(message: BigUint) -> [Vec<G::ScalarField>; KECCAK_COLS]
 where
     G::BaseField: PrimeField,
@@ -91,11 +114,8 @@ where
 #[test]
 // Tests a random block of 1080 bits
 fn test_random_block() {
-    let expected_random = setup_keccak_test::<Pallas>(
-        BigUint::from_hex("832588523900cca2ea9b8c0395d295aa39f9a9285a982b71cc8475067a8175f38f235a2234abc982a2dfaaddff2895a28598021895206a733a22bccd21f124df1413858a8f9a1134df285a888b099a8c2235eecdf2345f3afd32f3ae323526689172850672938104892357aad32523523f423423a214325d13523aadb21414124aaadf32523126"),
-    );
-    let hash_random =
-        BigUint::from_hex("845e9dd4e22b4917a80c5419a0ddb3eebf5f4f7cc6035d827314a18b718f751f");
+    let expected_random = setup_keccak_test::<Pallas>(BigUint::from_hex(RANDOM_BLOCK));
+    let hash_random = BigUint::from_hex(RANDOM_BLOCK_HASH);
     assert_eq!(expected_random, hash_random);
 }
 
@@ -103,16 +123,87 @@ fn test_random_block() {
 // Test hash of message zero with 1 byte input length
 fn test_zero() {
     let expected1 = setup_keccak_test::<Pallas>(BigUint::from_bytes_be(&[0x00]));
-    let hash1 =
-        BigUint::from_hex("bc36789e7a1e281436464229828f817d6612f7b477d66591ff96a9e064bcc98a");
+    let hash1 = BigUint::from_hex(ZERO_HASH);
     assert_eq!(expected1, hash1);
 }
 
 #[test]
 // Test hash of message using 3 blocks
 fn test_blocks() {
-    let expected_3blocks = setup_keccak_test::<Pallas>(BigUint::from_hex("832588523900cca2ea9b8c0395d295aa39f9a9285a982b71cc8475067a8175f38f235a2234abc982a2dfaaddff2895a28598021895206a733a22bccd21f124df1413858a8f9a1134df285a888b099a8c2235eecdf2345f3afd32f3ae323526689172850672938104892357aad32523523f423423a214325d13523aadb21414124aaadf32523126832588523900cca2ea9b8c0395d295aa39f9a9285a982b71cc8475067a8175f38f235a2234abc982a2dfaaddff2895a28598021895206a733a22bccd21f124df1413858a8f9a1134df285a888b099a8c2235eecdf2345f3afd32f3ae323526689172850672938104892357aad32523523f423423a214325d13523aadb21414124aaadf32523126832588523900cca2ea9b8c0395d295aa39f9a9285a982b71cc8475067a8175f38f235a2234abc982a2dfaaddff2895a28598021895206a733a22bccd21f124df1413858a8f9a1134df285a888b099a8c2235eecdf2345f3afd32f3ae323526689172850672938104892357aad32523523f"));
-    let hash_3blocks =
-        BigUint::from_hex("7e369e1a4362148fca24c67c76f14dbe24b75c73e9b0efdb8c46056c8514287e");
+    let expected_3blocks = setup_keccak_test::<Pallas>(BigUint::from_hex(THREE_BLOCKS));
+    let hash_3blocks = BigUint::from_hex(THREE_BLOCKS_HASH);
     assert_eq!(expected_3blocks, hash_3blocks);
 }
+
+type VestaBaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+type VestaScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+// Returns the witness of the Keccak-256 gadget hashing `message`, and its
+// digest
+fn create_keccak256_gadget_witness(message: &[u8]) -> ([Vec<Fp>; COLUMNS], [u8; 32]) {
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let digest = extend_keccak256_witness(&mut witness, message);
+    (witness, digest)
+}
+
+#[test]
+// Checks the digests of the gadget against the ones of the tests above
+fn test_keccak256_gadget_digests() {
+    for (message, hash) in [
+        (vec![0x00], ZERO_HASH),
+        (
+            BigUint::from_hex(RANDOM_BLOCK).to_bytes_be(),
+            RANDOM_BLOCK_HASH,
+        ),
+        (
+            BigUint::from_hex(THREE_BLOCKS).to_bytes_be(),
+            THREE_BLOCKS_HASH,
+        ),
+    ] {
+        let (witness, digest) = create_keccak256_gadget_witness(&message);
+        let blocks = Keccak::num_blocks(message.len());
+        assert_eq!(
+            witness[0].len(),
+            2 + blocks * (5 * RATE_WORDS + ROUNDS * ROUND_ROWS)
+        );
+        assert_eq!(BigUint::from_bytes_be(&digest), BigUint::from_hex(hash));
+    }
+}
+
+#[test]
+// Proves the hash of a message of one block with the gadget, and checks that
+// a wrong witness is rejected
+fn test_keccak256_gadget_prove_and_verify() {
+    let message = b"kimchi";
+    let mut gates = vec![];
+    let cells = CircuitGate::<Fp>::extend_keccak256(&mut gates, 1);
+    let (witness, digest) = create_keccak256_gadget_witness(message);
+    assert_eq!(gates.len(), witness[0].len());
+
+    // The cells of the gadget hold the padded message and the digest
+    let padded = Keccak::pad(message);
+    for (cell, bytes) in cells.message.iter().zip(padded.chunks(8)) {
+        let word = u64::from_le_bytes(bytes.try_into().unwrap());
+        assert_eq!(witness[cell.1][cell.0], Fp::from(word));
+    }
+    for (cell, bytes) in cells.digest.iter().zip(digest.chunks(8)) {
+        let word = u64::from_le_bytes(bytes.try_into().unwrap());
+        assert_eq!(witness[cell.1][cell.0], Fp::from(word));
+    }
+
+    let runner = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness.clone())
+        .setup();
+    runner.prover_index().verify(&witness, &[]).unwrap();
+
+    // A digest which is not the one of the message breaks the last XOR
+    let mut wrong = witness.clone();
+    let (row, col) = cells.digest[0];
+    wrong[col][row] += Fp::one();
+    assert!(runner.prover_index().verify(&wrong, &[]).is_err());
+
+    runner
+        .prove_and_verify::<VestaBaseSponge, VestaScalarSponge>()
+        .unwrap();
+}
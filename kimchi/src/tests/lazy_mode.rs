@@ -1,9 +1,12 @@
 use super::framework::TestFramework;
-use crate::circuits::{
-    gate::CircuitGate,
-    polynomial::COLUMNS,
-    polynomials::{generic::GenericGateSpec, xor},
-    wires::Wire,
+use crate::{
+    circuits::{
+        gate::CircuitGate,
+        polynomial::COLUMNS,
+        polynomials::{generic::GenericGateSpec, xor},
+        wires::Wire,
+    },
+    prover_index::testing::new_index_for_test_with_lookups,
 };
 use ark_ff::Zero;
 use core::array;
@@ -87,3 +90,51 @@ fn test_lazy_mode_benchmark() {
             .unwrap();
     }
 }
+
+#[test]
+fn test_lazy_mode_materialize() {
+    let mut gates = vec![CircuitGate::create_generic_gadget(
+        Wire::for_row(0),
+        GenericGateSpec::Pub,
+        None,
+    )];
+    CircuitGate::<Fp>::extend_xor_gadget(&mut gates, 64);
+
+    let index = new_index_for_test_with_lookups::<Vesta>(
+        gates,
+        1,
+        0,
+        vec![],
+        None,
+        false,
+        None,
+        true,
+        false,
+    );
+
+    // Nothing is computed before it is used
+    assert!(!index.column_evaluations.is_initialized());
+    assert!(!index.linearization.is_initialized());
+    assert!(!index.cs.lookup_constraint_system.is_initialized());
+    assert!(index.cs.cached_precomputations().is_none());
+
+    // The verifier index is shared by the clones of the index, and does not
+    // compute the domain precomputations
+    let verifier_index = index.clone().verifier_index();
+    assert!(index.verifier_index_cache.get().is_some());
+    assert!(index.linearization.is_initialized());
+    assert!(index.cs.cached_precomputations().is_none());
+    assert!(verifier_index
+        .permutation_vanishing_polynomial_m
+        .get()
+        .is_none());
+
+    index.materialize().unwrap();
+    assert!(index.column_evaluations.is_initialized());
+    assert!(index.cs.lookup_constraint_system.is_initialized());
+    let precomputations = index.cs.cached_precomputations().unwrap();
+    assert_eq!(
+        verifier_index.permutation_vanishing_polynomial_m(),
+        &precomputations.permutation_vanishing_polynomial_m
+    );
+}
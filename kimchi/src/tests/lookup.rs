@@ -1,14 +1,14 @@
-use super::framework::{print_witness, TestFramework};
+use super::framework::{print_witness, FailureKind, TestFramework};
 use crate::circuits::{
     gate::{CircuitGate, GateType},
     lookup::{
         runtime_tables::{RuntimeTable, RuntimeTableCfg},
-        tables::LookupTable,
+        tables::{LookupTable, LookupTableBuilder},
     },
     polynomial::COLUMNS,
     wires::Wire,
 };
-use ark_ff::{UniformRand, Zero};
+use ark_ff::{PrimeField, UniformRand, Zero};
 use core::array;
 use mina_curves::pasta::{Fp, Vesta, VestaParameters};
 use mina_poseidon::{
@@ -21,11 +21,20 @@ type SpongeParams = PlonkSpongeConstantsKimchi;
 type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
 type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
 
-fn setup_lookup_proof(use_values_from_table: bool, num_lookups: usize, table_sizes: Vec<usize>) {
-    let seed: [u8; 32] = thread_rng().gen();
-    eprintln!("Seed: {:?}", seed);
-    let mut rng = StdRng::from_seed(seed);
-
+/// Creates a circuit of `num_lookups` lookup gates in random tables of sizes
+/// `table_sizes`, and its witness, looking up random values if
+/// `use_values_from_table` is false
+#[allow(clippy::type_complexity)]
+pub(super) fn create_lookup_circuit(
+    rng: &mut StdRng,
+    use_values_from_table: bool,
+    num_lookups: usize,
+    table_sizes: &[usize],
+) -> (
+    Vec<CircuitGate<Fp>>,
+    [Vec<Fp>; COLUMNS],
+    Vec<LookupTable<Fp>>,
+) {
     let mut lookup_table_values: Vec<Vec<_>> = table_sizes
         .iter()
         .map(|size| (0..*size).map(|_| rng.gen()).collect())
@@ -101,10 +110,27 @@ fn setup_lookup_proof(use_values_from_table: bool, num_lookups: usize, table_siz
         ]
     };
 
+    (gates, witness, lookup_tables)
+}
+
+fn setup_lookup_proof(
+    use_values_from_table: bool,
+    num_lookups: usize,
+    table_sizes: Vec<usize>,
+    log_up: bool,
+) {
+    let seed: [u8; 32] = thread_rng().gen();
+    eprintln!("Seed: {:?}", seed);
+    let mut rng = StdRng::from_seed(seed);
+
+    let (gates, witness, lookup_tables) =
+        create_lookup_circuit(&mut rng, use_values_from_table, num_lookups, &table_sizes);
+
     TestFramework::<Vesta>::default()
         .gates(gates)
         .witness(witness)
         .lookup_tables(lookup_tables)
+        .log_up(log_up)
         .setup()
         .prove_and_verify::<BaseSponge, ScalarSponge>()
         .unwrap();
@@ -112,30 +138,47 @@ fn setup_lookup_proof(use_values_from_table: bool, num_lookups: usize, table_siz
 
 #[test]
 fn lookup_gate_proving_works() {
-    setup_lookup_proof(true, 500, vec![256])
+    setup_lookup_proof(true, 500, vec![256], false)
 }
 
 #[test]
 #[should_panic]
 fn lookup_gate_rejects_bad_lookups() {
-    setup_lookup_proof(false, 500, vec![256])
+    setup_lookup_proof(false, 500, vec![256], false)
 }
 
 #[test]
 fn lookup_gate_proving_works_multiple_tables() {
-    setup_lookup_proof(true, 500, vec![100, 50, 50, 2, 2])
+    setup_lookup_proof(true, 500, vec![100, 50, 50, 2, 2], false)
 }
 
 #[test]
 #[should_panic]
 fn lookup_gate_rejects_bad_lookups_multiple_tables() {
-    setup_lookup_proof(false, 500, vec![100, 50, 50, 2, 2])
+    setup_lookup_proof(false, 500, vec![100, 50, 50, 2, 2], false)
+}
+
+#[test]
+fn lookup_gate_proving_works_log_up() {
+    setup_lookup_proof(true, 500, vec![256], true)
+}
+
+#[test]
+#[should_panic]
+fn lookup_gate_rejects_bad_lookups_log_up() {
+    setup_lookup_proof(false, 500, vec![256], true)
+}
+
+#[test]
+fn lookup_gate_proving_works_multiple_tables_log_up() {
+    setup_lookup_proof(true, 500, vec![100, 50, 50, 2, 2], true)
 }
 
 fn setup_successful_runtime_table_test(
     runtime_table_cfgs: Vec<RuntimeTableCfg<Fp>>,
     runtime_tables: Vec<RuntimeTable<Fp>>,
     lookups: Vec<i32>,
+    log_up: bool,
 ) {
     let seed: [u8; 32] = thread_rng().gen();
     eprintln!("Seed: {:?}", seed);
@@ -193,6 +236,7 @@ fn setup_successful_runtime_table_test(
         .gates(gates)
         .witness(witness)
         .runtime_tables_setup(runtime_table_cfgs)
+        .log_up(log_up)
         .setup()
         .runtime_tables(runtime_tables)
         .prove_and_verify::<BaseSponge, ScalarSponge>()
@@ -320,18 +364,17 @@ fn test_negative_test_runtime_table_value_not_in_table() {
     };
 
     // run prover only as the error should be raised while creating the proof.
-    let err = TestFramework::<Vesta>::default()
+    TestFramework::<Vesta>::default()
         .gates(gates)
         .witness(witness)
         .runtime_tables_setup(vec![cfg])
         .setup()
         .runtime_tables(vec![runtime_table])
-        .prove::<BaseSponge, ScalarSponge>()
-        .unwrap_err();
-
-    // The whole witness is wrong, but we check the first row is incorrect as it
-    // is the error returned by the prover call.
-    assert_eq!(err, "the lookup failed to find a match in the table: row=0");
+        // The whole witness is wrong, but we check the first row is incorrect
+        // as it is the error returned by the prover call.
+        .prove_expecting_failure::<BaseSponge, ScalarSponge>(FailureKind::ValueNotInTable {
+            row: 0,
+        });
 }
 
 #[test]
@@ -382,18 +425,15 @@ fn test_negative_test_runtime_table_prover_with_undefined_id_in_index_and_witnes
     };
 
     // We only run the prover. No need to verify.
-    let err = TestFramework::<Vesta>::default()
+    TestFramework::<Vesta>::default()
         .gates(gates)
         .witness(witness)
         .runtime_tables_setup(vec![cfg])
         .setup()
         .runtime_tables(vec![runtime_table])
-        .prove::<BaseSponge, ScalarSponge>()
-        .unwrap_err();
-    assert_eq!(
-        err,
-        "the runtime tables provided did not match the index's configuration"
-    );
+        .prove_expecting_failure::<BaseSponge, ScalarSponge>(
+            FailureKind::RuntimeTablesInconsistent,
+        );
 }
 
 #[test]
@@ -442,18 +482,15 @@ fn test_negative_test_runtime_table_prover_uses_undefined_id_in_index_and_witnes
     };
 
     // We only run the prover. No need to verify.
-    let err = TestFramework::<Vesta>::default()
+    TestFramework::<Vesta>::default()
         .gates(gates)
         .witness(witness)
         .runtime_tables_setup(vec![cfg])
         .setup()
         .runtime_tables(vec![runtime_table])
-        .prove::<BaseSponge, ScalarSponge>()
-        .unwrap_err();
-    assert_eq!(
-        err,
-        "the runtime tables provided did not match the index's configuration"
-    );
+        .prove_expecting_failure::<BaseSponge, ScalarSponge>(
+            FailureKind::RuntimeTablesInconsistent,
+        );
 }
 
 #[test]
@@ -529,18 +566,15 @@ fn test_runtime_table_with_more_than_one_runtime_table_data_given_by_prover() {
     print_witness(&witness, 0, 20);
 
     // run test
-    let err = TestFramework::<Vesta>::default()
+    TestFramework::<Vesta>::default()
         .gates(gates)
         .witness(witness)
         .runtime_tables_setup(vec![cfg])
         .setup()
         .runtime_tables(runtime_tables)
-        .prove_and_verify::<BaseSponge, ScalarSponge>()
-        .unwrap_err();
-    assert_eq!(
-        err,
-        "the runtime tables provided did not match the index's configuration"
-    );
+        .prove_expecting_failure::<BaseSponge, ScalarSponge>(
+            FailureKind::RuntimeTablesInconsistent,
+        );
 }
 
 #[test]
@@ -558,7 +592,7 @@ fn test_runtime_table_only_one_table_with_id_zero_with_non_zero_entries_fixed_va
 
     let lookups: Vec<i32> = [0; 20].into();
 
-    setup_successful_runtime_table_test(vec![cfg], vec![runtime_table], lookups);
+    setup_successful_runtime_table_test(vec![cfg], vec![runtime_table], lookups, false);
 }
 
 #[test]
@@ -585,7 +619,33 @@ fn test_runtime_table_only_one_table_with_id_zero_with_non_zero_entries_random_v
 
     let lookups: Vec<i32> = [0; 20].into();
 
-    setup_successful_runtime_table_test(vec![cfg], vec![runtime_table], lookups);
+    setup_successful_runtime_table_test(vec![cfg], vec![runtime_table], lookups, false);
+}
+
+#[test]
+fn test_runtime_table_log_up() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+
+    let first_column = [8u32, 9, 8, 7, 1];
+    let cfgs: Vec<_> = (1..3)
+        .map(|id| RuntimeTableCfg {
+            id,
+            first_column: first_column.into_iter().map(Into::into).collect(),
+        })
+        .collect();
+    let runtime_tables = cfgs
+        .iter()
+        .map(|cfg| RuntimeTable {
+            id: cfg.id(),
+            data: (0..first_column.len())
+                .map(|_| UniformRand::rand(rng))
+                .collect(),
+        })
+        .collect();
+
+    let lookups: Vec<i32> = (0..20).map(|i| i % 2 + 1).collect();
+
+    setup_successful_runtime_table_test(cfgs, runtime_tables, lookups, true);
 }
 
 // This test verifies that if there is a table with ID 0, it contains a row with only zeroes.
@@ -729,3 +789,235 @@ fn test_dummy_zero_entry_is_counted_while_computing_domain_size() {
     // As the dummy entry has been added, we reached the next power of two
     assert!(domain_size == (1 << (power_of_2 + 1)));
 }
+
+// A read-only memory image of 10 values
+fn memory_image() -> Vec<Fp> {
+    (0..10u64).map(|i| Fp::from(i * i + 7)).collect()
+}
+
+// Fills a runtime table declared by its capacity with a read-only memory image,
+// and reads it in a lookup gate
+fn setup_memory_image_test(
+    public_runtime_tables: bool,
+    expected_image: &[Fp],
+) -> Result<(), String> {
+    let table_id = 1;
+    let gates = vec![
+        CircuitGate::new(GateType::Lookup, Wire::for_row(0), vec![]),
+        CircuitGate::new(GateType::Lookup, Wire::for_row(1), vec![]),
+    ];
+
+    let runner = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .runtime_tables_capacities(vec![(table_id, 16)])
+        .public_runtime_tables(public_runtime_tables)
+        .setup();
+
+    // The tables are sized by the index and filled at witness time
+    let mut runtime_tables = runner.prover_index().runtime_tables();
+    assert_eq!(runtime_tables.len(), 1);
+    assert_eq!(runtime_tables[0].capacity(), 16);
+    runtime_tables[0].write_all(0, &memory_image());
+
+    let mut expected_tables = runner.prover_index().runtime_tables();
+    expected_tables[0].write_all(0, expected_image);
+
+    let witness = {
+        let mut cols: [_; COLUMNS] = array::from_fn(|_col| vec![Fp::zero(); 2]);
+        for row in 0..2 {
+            cols[0][row] = Fp::from(table_id);
+            for (i, chunk) in cols[1..7].chunks_mut(2).enumerate() {
+                let address = 3 * row + i;
+                chunk[0][row] = Fp::from(address as u64);
+                chunk[1][row] = runtime_tables[0].read(address);
+            }
+        }
+        cols
+    };
+
+    runner
+        .witness(witness)
+        .runtime_tables(runtime_tables)
+        .expected_runtime_tables(expected_tables)
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+}
+
+#[test]
+fn test_runtime_table_memory_image() {
+    setup_memory_image_test(true, &memory_image()).unwrap();
+}
+
+#[test]
+fn test_negative_test_runtime_table_memory_image_not_expected() {
+    let mut image = memory_image();
+
+    // The commitment of a hiding runtime table cannot be checked
+    assert_eq!(
+        setup_memory_image_test(false, &image),
+        Err("the commitment to the runtime tables does not match the expected tables".into())
+    );
+
+    // Another image
+    image[9] += Fp::from(1u64);
+    assert_eq!(
+        setup_memory_image_test(true, &image),
+        Err("the commitment to the runtime tables does not match the expected tables".into())
+    );
+}
+
+// A toy 8-bit substitution box, whose entries are (input, output, flags)
+fn sbox_entry(input: u64) -> [Fp; 3] {
+    let output = (input * input * input + 99) % 256;
+    let flags = (output >> 7) | (u64::from(input.count_ones() % 2 == 1) << 1);
+    [input.into(), output.into(), flags.into()]
+}
+
+/// Creates a circuit of vector lookups in an 8-bit substitution box, and its
+/// witness, with a wrong output if `valid` is false
+pub(super) fn create_vector_lookup_circuit(
+    valid: bool,
+) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS], LookupTable<Fp>) {
+    let table_id = 2;
+    let num_lookups = 20;
+    let sbox = LookupTableBuilder::new(table_id, 3)
+        .entries((0..256).map(sbox_entry))
+        .build();
+    assert_eq!((sbox.width(), sbox.len()), (3, 256));
+
+    let gates = (0..num_lookups)
+        .map(|i| CircuitGate::new(GateType::VectorLookup, Wire::for_row(i), vec![]))
+        .collect();
+
+    let witness = {
+        let mut cols: [_; COLUMNS] = array::from_fn(|_col| vec![Fp::zero(); num_lookups]);
+        for row in 0..num_lookups {
+            cols[0][row] = Fp::from(table_id as u64);
+            for i in 0..3 {
+                // The fourth value of the tuples is 0, the table being narrower
+                let entry = sbox_entry((13 * (3 * row + i) as u64) % 256);
+                for (j, value) in entry.into_iter().enumerate() {
+                    cols[4 * i + 1 + j][row] = value;
+                }
+            }
+        }
+        if !valid {
+            cols[2][7] += Fp::from(1u64);
+        }
+        cols
+    };
+
+    (gates, witness, sbox)
+}
+
+fn setup_vector_lookup_proof(valid: bool) {
+    let (gates, witness, sbox) = create_vector_lookup_circuit(valid);
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .lookup_tables(vec![sbox])
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+fn test_vector_lookup_sbox() {
+    setup_vector_lookup_proof(true)
+}
+
+#[test]
+#[should_panic]
+fn test_negative_test_vector_lookup_wrong_output() {
+    setup_vector_lookup_proof(false)
+}
+
+/// Writes the table of the squares of `0..64`, as `x, x^2` entries, in the
+/// format read by [LookupTable::from_file].
+fn squares_table_file() -> String {
+    let mut file = "# x, x^2\n".to_string();
+    for x in 0u64..64 {
+        if x % 2 == 0 {
+            file.push_str(&format!("{x}, {}\n", x * x));
+        } else {
+            file.push_str(&format!("{x:#x} {:#x}\n\n", x * x));
+        }
+    }
+    file
+}
+
+#[test]
+fn test_lookup_table_from_file() {
+    let table_id = 7;
+    let num_lookups = 20;
+    let path = std::env::temp_dir().join(format!("squares_{}.txt", std::process::id()));
+    std::fs::write(&path, squares_table_file()).unwrap();
+    let squares = LookupTable::<Fp>::from_file(table_id, &path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!((squares.width(), squares.len()), (2, 64));
+
+    let gates = (0..num_lookups)
+        .map(|i| CircuitGate::new(GateType::Lookup, Wire::for_row(i), vec![]))
+        .collect();
+
+    let witness = {
+        let mut cols: [_; COLUMNS] = array::from_fn(|_col| vec![Fp::zero(); num_lookups]);
+        for row in 0..num_lookups {
+            cols[0][row] = Fp::from(table_id as u64);
+            for i in 0..3 {
+                let x = (7 * (3 * row + i) as u64) % 64;
+                cols[2 * i + 1][row] = Fp::from(x);
+                cols[2 * i + 2][row] = Fp::from(x * x);
+            }
+        }
+        cols
+    };
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .lookup_tables(vec![squares])
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+fn test_lookup_table_from_invalid_data() {
+    use crate::circuits::lookup::index::LookupError;
+
+    let squares = LookupTable::<Fp>::from_reader(3, squares_table_file().as_bytes()).unwrap();
+    assert_eq!(squares.data[1][5], Fp::from(25u64));
+
+    assert!(matches!(
+        LookupTable::<Fp>::from_reader(3, "1, 2\n3\n".as_bytes()),
+        Err(LookupError::InvalidTableFile { line: 2, .. })
+    ));
+    assert!(matches!(
+        LookupTable::<Fp>::from_reader(3, "1, 2\n3, x\n".as_bytes()),
+        Err(LookupError::InvalidTableFile { line: 2, .. })
+    ));
+    // The modulus of the field is not a valid value
+    let modulus = format!("{}\n", num_bigint::BigUint::from(Fp::MODULUS));
+    assert!(matches!(
+        LookupTable::<Fp>::from_reader(3, modulus.as_bytes()),
+        Err(LookupError::InvalidTableFile { line: 1, .. })
+    ));
+    assert!(matches!(
+        LookupTable::<Fp>::from_reader(3, "# nothing\n".as_bytes()),
+        Err(LookupError::EmptyTable)
+    ));
+    // A table with ID 0 needs an entry of all zeros
+    assert!(matches!(
+        LookupTable::<Fp>::from_reader(0, "1, 2\n".as_bytes()),
+        Err(LookupError::TableIDZeroMustHaveZeroEntry)
+    ));
+    assert!(matches!(
+        LookupTable::new(3, vec![vec![Fp::zero(); 2], vec![Fp::zero()]]),
+        Err(LookupError::InconsistentTableLength)
+    ));
+    assert!(matches!(
+        LookupTable::<Fp>::from_file(3, "/nonexistent/table.txt"),
+        Err(LookupError::InvalidTableFile { line: 0, .. })
+    ));
+}
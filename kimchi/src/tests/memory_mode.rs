@@ -0,0 +1,102 @@
+use super::framework::TestFramework;
+use crate::{
+    circuits::{
+        gate::CircuitGate,
+        polynomial::COLUMNS,
+        polynomials::{generic::GenericGateSpec, xor},
+        wires::Wire,
+    },
+    proof::ProverProof,
+    prover_index::{testing::new_index_for_test_with_lookups, ProverMemoryMode},
+};
+use ark_ff::Zero;
+use core::array;
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use poly_commitment::commitment::CommitmentCurve;
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+/// A public input followed by a 64-bit xor, which uses lookups
+fn xor_circuit() -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS]) {
+    let mut gates = vec![CircuitGate::create_generic_gadget(
+        Wire::for_row(0),
+        GenericGateSpec::Pub,
+        None,
+    )];
+    CircuitGate::<Fp>::extend_xor_gadget(&mut gates, 64);
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero()]);
+    witness[0][0] = Fp::from(1u8);
+    xor::extend_xor_witness(
+        &mut witness,
+        Fp::from(0xdead_beefu64),
+        Fp::from(0xcafeu64),
+        64,
+    );
+
+    (gates, witness)
+}
+
+#[test]
+fn test_low_memory_mode() {
+    let (gates, witness) = xor_circuit();
+
+    let runner = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(vec![Fp::from(1u8)])
+        .memory_mode(ProverMemoryMode::Low)
+        .setup();
+    let index = runner.prover_index().clone();
+
+    runner
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+
+    // The column evaluations were computed for the verifier index and the
+    // proof, and dropped with them
+    assert!(!index.column_evaluations.is_initialized());
+    index.materialize().unwrap();
+    assert!(!index.column_evaluations.is_initialized());
+}
+
+#[test]
+fn test_low_memory_mode_same_proof() {
+    let (gates, witness) = xor_circuit();
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+
+    let index = new_index_for_test_with_lookups::<Vesta>(
+        gates,
+        1,
+        0,
+        vec![],
+        None,
+        false,
+        None,
+        false,
+        false,
+    );
+    let low_memory_index = index.clone().with_memory_mode(ProverMemoryMode::Low);
+    assert!(low_memory_index.estimated_peak_memory() < index.estimated_peak_memory());
+
+    let prove = |index| {
+        ProverProof::create::<BaseSponge, ScalarSponge, _>(
+            &group_map,
+            witness.clone(),
+            &[],
+            index,
+            &mut o1_utils::tests::make_test_rng(Some([0; 32])),
+        )
+        .unwrap()
+    };
+
+    // The memory mode does not change the proofs
+    assert_eq!(prove(&index), prove(&low_memory_index));
+}
@@ -1,21 +1,39 @@
 // IMPROVEME: move all tests in top-level directory tests
+mod accumulation;
 mod and;
+mod bitwise;
+mod builder;
+mod cairo_builtins;
 mod chunked;
+mod definition;
 mod ec;
+mod ecdsa;
+mod eddsa;
 mod endomul;
 mod endomul_scalar;
+mod foreign_curve;
 mod foreign_field_add;
 mod foreign_field_mul;
+mod foreign_field_wide;
 mod framework;
 mod generic;
 mod keccak;
 mod lazy_mode;
 mod lookup;
+mod memory_mode;
+mod modexp;
+mod mutation;
 mod not;
+mod optimizer;
 mod poseidon;
+mod poseidon2;
+mod proof_format;
 mod range_check;
 mod recursion;
 mod rot;
 mod serde;
+mod solver;
+mod stats;
 mod varbasemul;
 mod xor;
+mod zk_level;
@@ -0,0 +1,130 @@
+use super::framework::{
+    create_witness, find_generic_row, set_copied_cell, verify_rows, TestFramework,
+};
+use crate::circuits::{
+    constraints::{ConstraintFailure, ConstraintSystem, GateError},
+    gate::{CircuitGate, GateType},
+    polynomial::COLUMNS,
+    polynomials::{
+        foreign_field_common::KimchiForeignElement,
+        modexp::{
+            gadget::ModExpCells, limb_count, to_limbs, witness::extend_modexp_witness, RSA_EXPONENT,
+        },
+    },
+};
+use ark_ff::One;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use num_bigint::{BigUint, RandBigInt};
+use o1_utils::foreign_field::ForeignFieldHelpers;
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+// Checks the exponentiation of a random base modulo a random odd modulus of
+// `bits` bits, and returns the gates, the witness and the cells
+fn test_modexp(
+    bits: usize,
+    exponent: &BigUint,
+    window: usize,
+    rows: usize,
+) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS], ModExpCells) {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let modulus = rng.gen_biguint(bits as u64) | (BigUint::one() << (bits - 1)) | BigUint::one();
+    let base = rng.gen_biguint_below(&modulus);
+
+    let mut gates = vec![];
+    let cells = CircuitGate::<Fp>::extend_modexp(&mut gates, bits, exponent, window);
+    let (witness, result) = create_witness(|witness| {
+        extend_modexp_witness(witness, bits, exponent, window, &base, &modulus)
+    });
+    assert_eq!(result, base.modpow(exponent, &modulus));
+    assert_eq!(gates.len(), rows);
+    assert_eq!(gates.len(), witness[0].len());
+
+    // The cells of the gadget hold the limbs of the inputs and of the result
+    let count = limb_count(bits);
+    for (cells, value) in [
+        (&cells.base, &base),
+        (&cells.modulus, &modulus),
+        (&cells.result, &result),
+    ] {
+        for (&(row, col), limb) in cells.iter().zip(to_limbs(value, count)) {
+            assert_eq!(witness[col][row], Fp::from(limb));
+        }
+    }
+
+    let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+    assert_eq!(verify_rows(&gates, &cs, &witness), Ok(()));
+    (gates, witness, cells)
+}
+
+#[test]
+// Raises to the RSA exponent modulo a 2048-bit modulus, and checks that
+// another result is rejected
+fn test_modexp_rsa_2048() {
+    let (gates, witness, cells) = test_modexp(2048, &BigUint::from(RSA_EXPONENT), 1, 19805);
+
+    let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+    let mut wrong = witness.clone();
+    let (row, col) = cells.result[0];
+    wrong[col][row] += Fp::one();
+    assert!(verify_rows(&gates, &cs, &wrong).is_err());
+
+    // So do a product of limbs and a carry changed along their copies, which
+    // only the generic gates computing them reject
+    let product_row = find_generic_row(&gates, [0, 0, -1, 1, 0, 1, 1, -1, 0, 0]);
+    let two_to_limb = KimchiForeignElement::<Fp>::two_to_limb();
+    let carry_row = gates
+        .iter()
+        .position(|gate| gate.typ == GateType::Generic && gate.coeffs[2] == -two_to_limb)
+        .unwrap();
+    for (row, col) in [(product_row, 2), (carry_row, 2)] {
+        let mut wrong = witness.clone();
+        set_copied_cell(
+            &gates,
+            &mut wrong,
+            (row, col),
+            witness[col][row] + Fp::one(),
+        );
+        assert!(matches!(
+            verify_rows(&gates, &cs, &wrong),
+            Err(GateError::Constraint(ConstraintFailure {
+                typ: GateType::Generic,
+                ..
+            }))
+        ));
+    }
+}
+
+#[test]
+// Proves the cube of a base modulo a 256-bit modulus, which also checks the
+// lookups of the range checks
+fn test_modexp_prove() {
+    let (gates, witness, _) = test_modexp(256, &BigUint::from(3u32), 1, 121);
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+// Raises to an exponent with windows of 3 bits, whose table has 7 powers
+fn test_modexp_window() {
+    test_modexp(2048, &BigUint::from(0b111_010_000_101u32), 3, 21461);
+}
+
+#[test]
+// Checks the number of rows of the exponentiation modulo a 4096-bit modulus
+fn test_modexp_rsa_4096_rows() {
+    let mut gates = vec![];
+    CircuitGate::<Fp>::extend_modexp(&mut gates, 4096, &BigUint::from(RSA_EXPONENT), 1);
+    assert_eq!(gates.len(), 66998);
+}
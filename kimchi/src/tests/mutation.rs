@@ -0,0 +1,380 @@
+//! Witness mutation fuzzing of the built-in gadgets: perturbing any cell of a
+//! valid witness that the constraints read must make the witness verification
+//! fail, otherwise the mutated cell is a soundness hole.
+
+use super::framework::TestFramework;
+use crate::{
+    circuits::{
+        builder::CircuitBuilder,
+        constraints::ConstraintSystem,
+        gate::{CircuitGate, Connect, GateType},
+        polynomial::COLUMNS,
+        polynomials::{
+            and,
+            ecdsa::secp256k1_generator,
+            foreign_curve::{witness::extend_foreign_curve_add_witness, ForeignCurve},
+            foreign_field_add,
+            foreign_field_add::witness::FFOps,
+            foreign_field_mul,
+            foreign_field_wide::witness::extend_wide_foreign_field_mul_witness,
+            generic::{GenericGateSpec, GENERIC_COEFFS, GENERIC_REGISTERS},
+            not, poseidon, range_check, rot,
+            rot::RotMode,
+            xor,
+        },
+        wires::{Wire, PERMUTS},
+    },
+    curve::KimchiCurve,
+};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{Field, UniformRand, Zero};
+use core::array;
+use itertools::Itertools;
+use mina_curves::pasta::{Fp, Fq, Pallas, Vesta};
+use num_bigint::{BigUint, RandBigInt};
+use o1_utils::{FieldHelpers, RandomField};
+use rand::{rngs::StdRng, seq::SliceRandom};
+
+/// Returns the cells `(row, col)` of a witness of `num_rows` rows read by the
+/// constraints of the circuit of `cs`: the registers of the generic gates with a
+/// non-zero coefficient, the cells wired to another one, and the cells on which
+/// the constraints of the other gates depend given their coefficients. The
+/// latter are found by perturbing each cell of a random witness, which changes
+/// the evaluations of the constraints of the gate with high probability if they
+/// depend on it. The lookups are not verified with the witness, so their cells
+/// are only included when another constraint reads them.
+fn read_cells(cs: &ConstraintSystem<Fp>, num_rows: usize, rng: &mut StdRng) -> Vec<(usize, usize)> {
+    let mut cells = vec![];
+    for (row, gate) in cs.gates.iter().enumerate() {
+        if gate.typ == GateType::Generic {
+            for (coeffs, registers) in [(0, 0), (GENERIC_COEFFS, GENERIC_REGISTERS)] {
+                let coeff = |i: usize| gate.coeffs.get(coeffs + i).is_some_and(|c| !c.is_zero());
+                let mul = coeff(3);
+                let read = [coeff(0) || mul, coeff(1) || mul, coeff(2)];
+                for (col, _) in read.iter().enumerate().filter(|(_, read)| **read) {
+                    cells.push((row, registers + col));
+                }
+            }
+        } else {
+            // the current and next rows of the gate
+            let mut witness: [Vec<Fp>; COLUMNS] =
+                array::from_fn(|_| vec![Fp::rand(rng), Fp::rand(rng)]);
+            let evaluations = gate
+                .constraint_evaluations::<Vesta>(0, &witness, cs)
+                .unwrap();
+            for (offset, col) in (0..2).cartesian_product(0..COLUMNS) {
+                let value = witness[col][offset];
+                witness[col][offset] = Fp::rand(rng);
+                if gate
+                    .constraint_evaluations::<Vesta>(0, &witness, cs)
+                    .unwrap()
+                    != evaluations
+                {
+                    cells.push((row + offset, col));
+                }
+                witness[col][offset] = value;
+            }
+        }
+
+        for col in 0..PERMUTS {
+            if gate.wires[col] != (Wire { row, col }) {
+                cells.push((row, col));
+            }
+        }
+    }
+    cells.sort_unstable();
+    cells.dedup();
+    cells.retain(|(row, _)| *row < num_rows);
+    cells
+}
+
+/// Perturbs up to `num_mutations` random cells read by the constraints of the
+/// valid `witness` of the circuit `gates`, each by a random non-zero value,
+/// and asserts that the witness verification rejects each of the mutations,
+/// reporting all the accepted ones. The cells `free` are left out, as the
+/// constraints do not determine them for this witness.
+pub(crate) fn assert_mutations_rejected(
+    gates: Vec<CircuitGate<Fp>>,
+    witness: [Vec<Fp>; COLUMNS],
+    public: Vec<Fp>,
+    free: &[(usize, usize)],
+    num_mutations: usize,
+) {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+
+    let runner = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness.clone())
+        .public_inputs(public.clone())
+        .setup();
+    let index = runner.prover_index();
+    index
+        .verify(&witness, &public)
+        .expect("the witness to mutate should be valid");
+
+    let mut cells = read_cells(&index.cs, witness[0].len(), rng);
+    cells.retain(|cell| !free.contains(cell));
+    cells.shuffle(rng);
+    cells.truncate(num_mutations);
+
+    let mut accepted = vec![];
+    let mut mutated = witness;
+    for (row, col) in cells {
+        let delta = loop {
+            let delta = Fp::rand(rng);
+            if !delta.is_zero() {
+                break delta;
+            }
+        };
+        mutated[col][row] += delta;
+        if index.verify(&mutated, &public).is_ok() {
+            accepted.push(format!(
+                "row {row}, column {col} ({:?} gate)",
+                index.cs.gates[row].typ
+            ));
+        }
+        mutated[col][row] -= delta;
+    }
+
+    assert!(
+        accepted.is_empty(),
+        "the witness verification accepted the mutation of the cells:\n{}",
+        accepted.join("\n")
+    );
+}
+
+const NUM_MUTATIONS: usize = 200;
+
+#[test]
+fn test_mutations_generic() {
+    let public = vec![Fp::from(3u8), Fp::from(5u8)];
+    let mut gates: Vec<_> = (0..2)
+        .map(|row| {
+            CircuitGate::create_generic_gadget(Wire::for_row(row), GenericGateSpec::Pub, None)
+        })
+        .collect();
+    // 3 * 5 = 15, then 15 + 1 = 16
+    gates.push(CircuitGate::create_generic_gadget(
+        Wire::for_row(2),
+        GenericGateSpec::Mul {
+            output_coeff: None,
+            mul_coeff: None,
+        },
+        Some(GenericGateSpec::Plus(Fp::from(1u8))),
+    ));
+    gates.connect_cell_pair((0, 0), (2, 0));
+    gates.connect_cell_pair((1, 0), (2, 1));
+    gates.connect_cell_pair((2, 2), (2, 3));
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); 3]);
+    witness[0][0] = public[0];
+    witness[0][1] = public[1];
+    witness[0][2] = public[0];
+    witness[1][2] = public[1];
+    witness[2][2] = Fp::from(15u8);
+    witness[3][2] = Fp::from(15u8);
+    witness[5][2] = Fp::from(16u8);
+
+    assert_mutations_rejected(gates, witness, public, &[], NUM_MUTATIONS);
+}
+
+#[test]
+fn test_mutations_xor() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let input1 = rng.gen_field_with_bits(64);
+    let input2 = rng.gen_field_with_bits(64);
+
+    let mut gates = vec![];
+    CircuitGate::<Fp>::extend_xor_gadget(&mut gates, 64);
+    let witness = xor::create_xor_witness(input1, input2, 64);
+
+    assert_mutations_rejected(gates, witness, vec![], &[], NUM_MUTATIONS);
+}
+
+#[test]
+fn test_mutations_and() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let input1 = rng.gen_field_with_bits(64);
+    let input2 = rng.gen_field_with_bits(64);
+
+    let mut gates = vec![];
+    CircuitGate::<Fp>::extend_and(&mut gates, 8);
+    let witness = and::create_and_witness(input1, input2, 8);
+
+    assert_mutations_rejected(gates, witness, vec![], &[], NUM_MUTATIONS);
+}
+
+#[test]
+fn test_mutations_not() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let input = rng.gen_field_with_bits(64);
+
+    // the public input holds the all ones word of 64 bits
+    let mut gates = vec![CircuitGate::<Fp>::create_generic_gadget(
+        Wire::for_row(0),
+        GenericGateSpec::Pub,
+        None,
+    )];
+    CircuitGate::<Fp>::extend_not_gadget_checked_length(&mut gates, 0, 64);
+    let public = vec![Fp::from(2u8).pow([64]) - Fp::from(1u8)];
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero()]);
+    witness[0][0] = public[0];
+    not::extend_not_witness_checked_length(&mut witness, input, Some(64));
+
+    assert_mutations_rejected(gates, witness, public, &[], NUM_MUTATIONS);
+}
+
+#[test]
+fn test_mutations_rot() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let word = u64::rand(rng);
+
+    // the public input holds the zero of the range check of the excess
+    let mut gates = vec![CircuitGate::<Fp>::create_generic_gadget(
+        Wire::for_row(0),
+        GenericGateSpec::Pub,
+        None,
+    )];
+    CircuitGate::<Fp>::extend_rot(&mut gates, 13, RotMode::Left, 0);
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero()]);
+    rot::extend_rot(&mut witness, word, 13, RotMode::Left);
+
+    assert_mutations_rejected(gates, witness, vec![Fp::zero()], &[], NUM_MUTATIONS);
+}
+
+#[test]
+fn test_mutations_multi_range_check() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let [v0, v1, v2] = array::from_fn(|_| rng.gen_field_with_bits(88));
+
+    let (_, gates) = CircuitGate::<Fp>::create_multi_range_check(0);
+    let witness = range_check::witness::create_multi(v0, v1, v2);
+
+    assert_mutations_rejected(gates, witness, vec![], &[], NUM_MUTATIONS);
+}
+
+#[test]
+fn test_mutations_foreign_field_add() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let modulus = Fq::modulus_biguint();
+    let left: BigUint = Fq::rand(rng).into();
+    let right: BigUint = Fq::rand(rng).into();
+
+    // the public input holds the 1 of the overflow of the bound addition
+    let mut gates = vec![CircuitGate::<Fp>::create_generic_gadget(
+        Wire::for_row(0),
+        GenericGateSpec::Pub,
+        None,
+    )];
+    CircuitGate::extend_chain_ffadd(&mut gates, 0, &mut 1, &[FFOps::Add], &modulus);
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero()]);
+    witness[0][0] = Fp::from(1u8);
+    let add_witness =
+        foreign_field_add::witness::create_chain::<Fp>(&[left, right], &[FFOps::Add], modulus);
+    for col in 0..COLUMNS {
+        witness[col].extend(add_witness[col].iter());
+    }
+
+    assert_mutations_rejected(gates, witness, vec![Fp::from(1u8)], &[], NUM_MUTATIONS);
+}
+
+#[test]
+fn test_mutations_foreign_field_mul() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let modulus = Fq::modulus_biguint();
+    let left: BigUint = Fq::rand(rng).into();
+    let right: BigUint = Fq::rand(rng).into();
+
+    let (_, gates) = CircuitGate::<Fp>::create_foreign_field_mul(0, &modulus);
+    let (witness, _) = foreign_field_mul::witness::create(&left, &right, &modulus);
+
+    assert_mutations_rejected(gates, witness, vec![], &[], NUM_MUTATIONS);
+}
+
+#[test]
+fn test_mutations_wide_foreign_field_mul() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let modulus = rng.gen_biguint(512) | (BigUint::from(1u32) << 511u32) | BigUint::from(1u32);
+    let left = rng.gen_biguint_below(&modulus);
+    let right = rng.gen_biguint_below(&modulus);
+
+    let mut gates = vec![];
+    CircuitGate::<Fp>::extend_wide_foreign_field_mul(&mut gates, &modulus);
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    extend_wide_foreign_field_mul_witness(&mut witness, &left, &right, &modulus);
+
+    assert_mutations_rejected(gates, witness, vec![], &[], NUM_MUTATIONS);
+}
+
+#[test]
+fn test_mutations_foreign_curve_add() {
+    let curve = ForeignCurve::secp256k1();
+    let left = secp256k1_generator();
+    let right = curve.double(&left);
+
+    let mut gates = vec![];
+    CircuitGate::<Fp>::extend_foreign_curve_add(&mut gates, &curve);
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    extend_foreign_curve_add_witness(&mut witness, &curve, &left, &right);
+
+    assert_mutations_rejected(gates, witness, vec![], &[], NUM_MUTATIONS);
+}
+
+#[test]
+fn test_mutations_poseidon() {
+    let round_constants = &*Vesta::sponge_params().round_constants;
+    let (gates, _) = CircuitGate::<Fp>::create_poseidon_gadget(
+        0,
+        [Wire::for_row(0), Wire::for_row(poseidon::POS_ROWS_PER_HASH)],
+        round_constants,
+    );
+    let mut witness: [Vec<Fp>; COLUMNS] =
+        array::from_fn(|_| vec![Fp::zero(); poseidon::POS_ROWS_PER_HASH + 1]);
+    let input = [Fp::from(1u8), Fp::from(2u8), Fp::from(3u8)];
+    poseidon::generate_witness(0, Vesta::sponge_params(), &mut witness, input);
+
+    assert_mutations_rejected(gates, witness, vec![], &[], NUM_MUTATIONS);
+}
+
+#[test]
+fn test_mutations_complete_add() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let p = Pallas::generator() * <Pallas as AffineRepr>::ScalarField::rand(rng);
+    let q = Pallas::generator() * <Pallas as AffineRepr>::ScalarField::rand(rng);
+    let pq = (p + q).into_affine();
+    let (p, q) = (p.into_affine(), q.into_affine());
+
+    // a circuit needs at least two gates
+    let gates = vec![
+        CircuitGate::new(GateType::CompleteAdd, Wire::for_row(0), vec![]),
+        CircuitGate::zero(Wire::for_row(1)),
+    ];
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); 2]);
+    let s = (q.y - p.y) / (q.x - p.x);
+    witness[0][0] = p.x;
+    witness[1][0] = p.y;
+    witness[2][0] = q.x;
+    witness[3][0] = q.y;
+    witness[4][0] = pq.x;
+    witness[5][0] = pq.y;
+    witness[8][0] = s;
+    witness[10][0] = (q.x - p.x).inverse().unwrap();
+
+    // the inverse of y2 - y1 is only constrained when x1 = x2
+    assert_mutations_rejected(gates, witness, vec![], &[(0, 9)], NUM_MUTATIONS);
+}
+
+#[test]
+fn test_mutations_memory() {
+    let mut builder = CircuitBuilder::new();
+    let address = builder.private_input();
+    let value = builder.private_input();
+    let memory = builder.memory(Vesta::sponge_params(), 4);
+    builder.write(memory, address, value);
+    let read = builder.read(memory, address);
+    builder.assert_equal(read, value);
+    let (gates, _, witness) = builder.build();
+    let witness = witness(&[], &[Fp::from(3u64), Fp::from(42u64)]);
+
+    assert_mutations_rejected(gates, witness, vec![], &[], NUM_MUTATIONS);
+}
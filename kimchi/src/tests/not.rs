@@ -1,6 +1,6 @@
 use crate::{
     circuits::{
-        constraints::ConstraintSystem,
+        constraints::{ConstraintFailure, ConstraintSystem, GateError},
         gate::{CircuitGate, CircuitGateError, GateType},
         polynomial::COLUMNS,
         polynomials::{generic::GenericGateSpec, not, xor},
@@ -403,11 +403,17 @@ fn test_bad_not_gnrc() {
         false,
         None,
         false,
+        false,
     );
-    assert_eq!(
+    assert!(matches!(
         index.cs.gates[1].verify::<Vesta, OpeningProof<Vesta>>(1, &witness, &index, &[]),
-        Err(("generic: incorrect gate").to_string())
-    );
+        Err(GateError::Constraint(ConstraintFailure {
+            row: 1,
+            typ: GateType::Generic,
+            constraint: 1,
+            values: Some(_),
+        }))
+    ));
 }
 
 #[test]
@@ -0,0 +1,190 @@
+use super::framework::TestFramework;
+use crate::circuits::{
+    builder::CircuitBuilder,
+    gate::{CircuitGate, Connect, GateType},
+    optimizer::optimize,
+    polynomial::COLUMNS,
+    polynomials::{generic::GenericGateSpec, xor},
+    wires::Wire,
+};
+use ark_ff::{One, Zero};
+use core::array;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+fn prove_and_verify(gates: Vec<CircuitGate<Fp>>, witness: [Vec<Fp>; COLUMNS], public: Vec<Fp>) {
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(public)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+fn single_generic(row: usize, spec: GenericGateSpec<Fp>) -> CircuitGate<Fp> {
+    CircuitGate::create_generic_gadget(Wire::for_row(row), spec, None)
+}
+
+fn add() -> GenericGateSpec<Fp> {
+    GenericGateSpec::Add {
+        left_coeff: None,
+        right_coeff: None,
+        output_coeff: None,
+    }
+}
+
+fn mul() -> GenericGateSpec<Fp> {
+    GenericGateSpec::Mul {
+        output_coeff: None,
+        mul_coeff: None,
+    }
+}
+
+// Computes ((x + y) * y + 3) in a single generic gate per row, with a copy
+// of x + y through a generic gate whose coefficients are all zero, and
+// checks it against the public output
+fn hand_built_circuit() -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS], Vec<Fp>) {
+    let (x, y) = (Fp::from(5u64), Fp::from(7u64));
+    let sum = x + y;
+    let product = sum * y;
+    let output = product + Fp::from(3u64);
+
+    let mut gates = vec![
+        // 0: the public output
+        single_generic(0, GenericGateSpec::Pub),
+        // 1: x + y = sum
+        single_generic(1, add()),
+        // 2: the copy of sum
+        CircuitGate::create_generic(Wire::for_row(2), [Fp::zero(); 10]),
+        // 3: sum * y = product
+        single_generic(3, mul()),
+        // 4: product + 3 = output
+        single_generic(4, GenericGateSpec::Plus(Fp::from(3u64))),
+        // 5: y = 7
+        single_generic(5, GenericGateSpec::Const(y)),
+    ];
+    gates.connect_cell_pair((1, 2), (2, 0));
+    gates.connect_cell_pair((2, 0), (3, 0));
+    gates.connect_cell_pair((1, 1), (3, 1));
+    gates.connect_cell_pair((3, 1), (5, 0));
+    gates.connect_cell_pair((3, 2), (4, 0));
+    gates.connect_cell_pair((4, 2), (0, 0));
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    let rows = [
+        [output, Fp::zero(), Fp::zero()],
+        [x, y, sum],
+        [sum, Fp::zero(), Fp::zero()],
+        [sum, y, product],
+        [product, Fp::zero(), output],
+        [y, Fp::zero(), Fp::zero()],
+    ];
+    for (row, values) in rows.iter().enumerate() {
+        for (col, value) in values.iter().enumerate() {
+            witness[col][row] = *value;
+        }
+    }
+
+    (gates, witness, vec![output])
+}
+
+#[test]
+fn test_optimizer_merges_generic_gates() {
+    let (gates, witness, public) = hand_built_circuit();
+    prove_and_verify(gates.clone(), witness.clone(), public.clone());
+
+    let optimized = optimize(&gates, public.len());
+    let report = optimized.report;
+    assert_eq!(report.rows_before, 6);
+    assert_eq!(report.rows_after, 3);
+    assert_eq!(report.rows_saved(), 3);
+    assert_eq!(report.merged_gates, 2);
+    assert_eq!(report.dead_gates, 1);
+    assert_eq!(report.unwired_cells, 1);
+    assert_eq!(
+        report.to_string(),
+        "6 rows -> 3 rows (3 saved, 50.0%): 2 generic gates merged, 1 dead gates removed, 1 cells unwired"
+    );
+
+    // The multiplication moves to the second half of the addition
+    assert_eq!(optimized.gates[1].coeffs[5..], gates[3].coeffs[..5]);
+    assert_eq!(optimized.gates[1].wires[2], Wire::new(1, 3));
+
+    let witness = optimized.witness(&witness);
+    prove_and_verify(optimized.gates, witness, public);
+}
+
+#[test]
+fn test_optimizer_keeps_gadget_rows() {
+    // A constant, a 16-bit XOR in a Xor16 and a Zero row, and two constants
+    let mut gates = vec![single_generic(0, GenericGateSpec::Const(Fp::one()))];
+    let xor_row = gates.len();
+    let after_xor = CircuitGate::extend_xor_gadget(&mut gates, 16);
+    for row in after_xor..after_xor + 2 {
+        gates.push(single_generic(row, GenericGateSpec::Const(Fp::one())));
+    }
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    let xor_witness = xor::create_xor_witness(Fp::from(3u64), Fp::from(5u64), 16);
+    for (col, values) in witness.iter_mut().zip(&xor_witness) {
+        col[xor_row..after_xor].copy_from_slice(values);
+    }
+    for row in [0, after_xor, after_xor + 1] {
+        witness[0][row] = Fp::one();
+    }
+
+    // The constant after the XOR merges into the first one, and the row of
+    // zeros read by the Xor16 gate stays wired
+    let optimized = optimize(&gates, 0);
+    assert_eq!(optimized.report.rows_saved(), 1);
+    assert_eq!(optimized.gates[0].coeffs[5..], gates[after_xor].coeffs[..5]);
+    assert_eq!(optimized.gates[xor_row], gates[xor_row]);
+    assert_eq!(optimized.gates[after_xor - 1], gates[after_xor - 1]);
+
+    let witness = optimized.witness(&witness);
+    prove_and_verify(optimized.gates, witness, vec![]);
+
+    // The row read by a gate reading its next row stays in place, even with
+    // coefficients all zero
+    let gates = vec![
+        CircuitGate::new(GateType::Xor16, Wire::for_row(0), vec![]),
+        CircuitGate::create_generic(Wire::for_row(1), [Fp::zero(); 10]),
+        single_generic(2, GenericGateSpec::Const(Fp::one())),
+        single_generic(3, GenericGateSpec::Const(Fp::one())),
+        CircuitGate::create_generic(Wire::for_row(4), [Fp::zero(); 10]),
+    ];
+    let optimized = optimize(&gates, 0);
+    assert_eq!(optimized.report.rows_after, 3);
+    assert_eq!(optimized.report.merged_gates, 1);
+    assert_eq!(optimized.report.dead_gates, 1);
+    assert_eq!(optimized.gates[1], gates[1]);
+    assert_eq!(optimized.gates[2].coeffs[5..], gates[3].coeffs[..5]);
+}
+
+#[test]
+fn test_optimizer_builder_circuit() {
+    // The builder already packs two generic gates per row
+    let mut builder = CircuitBuilder::<Fp>::new();
+    let x = builder.public_input();
+    let y = builder.private_input();
+    let x_times_y = builder.mul(x, y);
+    let z = builder.constant(Fp::from(42u64));
+    builder.assert_equal(x_times_y, z);
+    let (gates, public, witness) = builder.build();
+
+    let optimized = optimize(&gates, public);
+    assert_eq!(optimized.report.rows_saved(), 0);
+    assert_eq!(optimized.gates, gates);
+
+    let public_inputs = vec![Fp::from(6u64)];
+    let witness = optimized.witness(&witness(&public_inputs, &[Fp::from(7u64)]));
+    prove_and_verify(optimized.gates, witness, public_inputs);
+}
@@ -0,0 +1,121 @@
+use crate::{
+    circuits::{
+        gate::{CircuitGate, CircuitGateError, GateType},
+        polynomials::poseidon2::{self, ROWS_PER_HASH},
+        wires::{Wire, COLUMNS},
+    },
+    prover_index::testing::new_index_for_test,
+    tests::framework::TestFramework,
+};
+use ark_ff::Zero;
+use core::array;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    pasta::fp_poseidon2,
+    poseidon2::permutation,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+const NUM_PERMUTATIONS: usize = 2;
+
+/// Creates a circuit of `NUM_PERMUTATIONS` Poseidon2 permutations, and its
+/// witness
+pub(super) fn create_poseidon2_circuit() -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS]) {
+    let params = fp_poseidon2::static_params();
+    let mut gates = vec![];
+    let mut witness: [Vec<Fp>; COLUMNS] =
+        array::from_fn(|_| vec![Fp::zero(); (ROWS_PER_HASH + 1) * NUM_PERMUTATIONS]);
+
+    let mut row = 0;
+    for i in 0..NUM_PERMUTATIONS {
+        let (permutation_gates, output_row) = CircuitGate::create_poseidon2_gadget(
+            row,
+            [Wire::for_row(row), Wire::for_row(row + ROWS_PER_HASH)],
+            params,
+        );
+        gates.extend(permutation_gates);
+
+        let input = [0, 1, 2].map(|j| Fp::from((3 * i + j) as u64));
+        poseidon2::generate_witness(row, params, &mut witness, input);
+
+        let mut output = input;
+        permutation(params, &mut output);
+        assert_eq!(output, array::from_fn(|j| witness[j][output_row]));
+
+        row = output_row + 1;
+    }
+
+    (gates, witness)
+}
+
+#[test]
+fn test_poseidon2() {
+    let (gates, witness) = create_poseidon2_circuit();
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+fn test_poseidon2_gates_reject_wrong_witness() {
+    let (gates, witness) = create_poseidon2_circuit();
+    let index = new_index_for_test::<Vesta>(gates, 0);
+
+    for (row, gate) in index.cs.gates.iter().take(ROWS_PER_HASH).enumerate() {
+        assert_eq!(
+            gate.verify_witness::<Vesta>(row, &witness, &index.cs, &[]),
+            Ok(())
+        );
+    }
+
+    // A wrong state of the first external row
+    let mut bad_witness = witness.clone();
+    bad_witness[4][0] += Fp::from(1u64);
+    assert_eq!(
+        index.cs.gates[0].verify_witness::<Vesta>(0, &bad_witness, &index.cs, &[]),
+        Err(CircuitGateError::Constraint(GateType::Poseidon2External, 5))
+    );
+
+    // A wrong S-box output of an internal row
+    let mut bad_witness = witness.clone();
+    bad_witness[7][2] += Fp::from(1u64);
+    assert_eq!(
+        index.cs.gates[2].verify_witness::<Vesta>(2, &bad_witness, &index.cs, &[]),
+        Err(CircuitGateError::Constraint(GateType::Poseidon2Internal, 5))
+    );
+
+    // A wrong output of the permutation
+    let mut bad_witness = witness;
+    bad_witness[1][ROWS_PER_HASH] += Fp::from(1u64);
+    let last_row = ROWS_PER_HASH - 1;
+    assert_eq!(
+        index.cs.gates[last_row].verify_witness::<Vesta>(last_row, &bad_witness, &index.cs, &[]),
+        Err(CircuitGateError::Constraint(
+            GateType::Poseidon2External,
+            14
+        ))
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_poseidon2_wrong_output() {
+    let (gates, mut witness) = create_poseidon2_circuit();
+    witness[0][ROWS_PER_HASH] += Fp::from(1u64);
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
@@ -0,0 +1,74 @@
+use crate::{
+    circuits::{
+        polynomials::generic::testing::{create_circuit, fill_in_witness},
+        wires::COLUMNS,
+    },
+    proof::ProverProof,
+    proof_format::{KimchiVerifier, PublicInput},
+    prover_index::testing::new_index_for_test,
+};
+use ark_ff::Zero;
+use core::array;
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use o1_utils::proof::{EncodedProof, Proof, ProofError, ProofRegistry};
+use poly_commitment::{commitment::CommitmentCurve, ipa::OpeningProof};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+type VestaProof = ProverProof<Vesta, OpeningProof<Vesta>>;
+
+#[test]
+fn test_kimchi_proof_registry() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    let index = new_index_for_test(gates, public.len());
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proof = ProverProof::create::<BaseSponge, ScalarSponge, _>(
+        &group_map,
+        witness,
+        &[],
+        &index,
+        &mut rand::rngs::OsRng,
+    )
+    .unwrap();
+
+    let mut registry = ProofRegistry::new();
+    registry.register::<VestaProof>(KimchiVerifier {
+        group_map,
+        verifier_index: index.verifier_index(),
+    });
+    assert!(registry.contains(&VestaProof::FORMAT));
+
+    let statement = PublicInput(public.clone());
+    let encoded = EncodedProof::new(&proof, &statement).unwrap();
+    let bytes = rmp_serde::to_vec(&encoded).unwrap();
+    let decoded: EncodedProof = rmp_serde::from_slice(&bytes).unwrap();
+    assert_eq!(registry.verify(&decoded), Ok(()));
+
+    // the proof does not attest another public input
+    let mut wrong = public;
+    wrong[0] += Fp::from(1u8);
+    let encoded = EncodedProof::new(&proof, &PublicInput(wrong)).unwrap();
+    assert!(matches!(
+        registry.verify(&encoded),
+        Err(ProofError::Invalid(_))
+    ));
+
+    // proofs of unregistered formats are rejected
+    let mut unknown = decoded;
+    unknown.format.version += 1;
+    assert_eq!(
+        registry.verify(&unknown),
+        Err(ProofError::UnknownFormat(unknown.format.clone()))
+    );
+}
@@ -53,7 +53,17 @@ fn create_test_prover_index(
         CircuitGate::<Fp>::create_multi_range_check(0)
     };
 
-    new_index_for_test_with_lookups(gates, public_size, 0, vec![], None, false, None, false)
+    new_index_for_test_with_lookups(
+        gates,
+        public_size,
+        0,
+        vec![],
+        None,
+        false,
+        None,
+        false,
+        false,
+    )
 }
 
 #[test]
@@ -1245,3 +1255,62 @@ fn verify_compact_multi_range_check_proof() {
         .prove_and_verify::<BaseSponge, ScalarSponge>()
         .unwrap();
 }
+
+#[test]
+fn verify_range_check_bits() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+
+    for bits in [1, 12, 64, 87, 88, 89, 100, 176, 200, 254] {
+        let (_next_row, gates) = CircuitGate::<Fp>::create_range_check_bits(0, bits);
+        let index = new_index_for_test_with_lookups::<Vesta>(
+            gates,
+            0,
+            0,
+            vec![],
+            None,
+            false,
+            None,
+            false,
+            false,
+        );
+
+        // Largest and random values in range
+        let two_to_bits = BigUint::from(2u32).pow(bits as u32);
+        for value in [&two_to_bits - 1u32, rng.gen_biguint_below(&two_to_bits)] {
+            let witness = range_check::witness::create_bits::<PallasField>(value.into(), bits);
+            index.verify(&witness, &[]).unwrap();
+        }
+
+        // Smallest value out of range
+        let witness = range_check::witness::create_bits::<PallasField>(two_to_bits.into(), bits);
+        assert!(index.verify(&witness, &[]).is_err());
+    }
+}
+
+#[test]
+fn verify_range_check_bits_proof() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+
+    let mut gates = vec![];
+    let mut curr_row = 0;
+    CircuitGate::<Fp>::extend_range_check_bits(&mut gates, &mut curr_row, 40);
+    CircuitGate::<Fp>::extend_range_check_bits(&mut gates, &mut curr_row, 200);
+    assert_eq!(curr_row, 15);
+
+    let mut witness = range_check::witness::create_bits::<PallasField>(
+        rng.gen_biguint_below(&BigUint::from(2u32).pow(40)).into(),
+        40,
+    );
+    range_check::witness::extend_bits::<PallasField>(
+        &mut witness,
+        rng.gen_biguint_below(&BigUint::from(2u32).pow(200)).into(),
+        200,
+    );
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
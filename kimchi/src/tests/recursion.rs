@@ -1,25 +1,35 @@
 use super::framework::TestFramework;
 use crate::{
     circuits::{
+        builder::CircuitBuilder,
         polynomials::generic::testing::{create_circuit, fill_in_witness},
+        recursion::{replay_transcript, DeferredValues, ProofShape, ProofVars},
         wires::COLUMNS,
     },
-    proof::RecursionChallenge,
+    curve::KimchiCurve,
+    proof::{ProverProof, RecursionChallenge},
+    prover_index::testing::new_index_for_test,
 };
 use ark_ff::{UniformRand, Zero};
 use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
 use core::array;
-use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Fq, Pallas, PallasParameters, Vesta, VestaParameters};
 use mina_poseidon::{
     constants::PlonkSpongeConstantsKimchi,
     sponge::{DefaultFqSponge, DefaultFrSponge},
 };
 use o1_utils::math;
-use poly_commitment::{commitment::b_poly_coefficients, SRS as _};
+use poly_commitment::{
+    commitment::{b_poly_coefficients, CommitmentCurve},
+    SRS as _,
+};
 
 type SpongeParams = PlonkSpongeConstantsKimchi;
 type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
 type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+type PallasBaseSponge = DefaultFqSponge<PallasParameters, SpongeParams>;
+type PallasScalarSponge = DefaultFrSponge<Fq, SpongeParams>;
 
 #[test]
 fn test_recursion() {
@@ -55,3 +65,82 @@ fn test_recursion() {
         .prove_and_verify::<BaseSponge, ScalarSponge>()
         .unwrap();
 }
+
+#[test]
+fn test_replay_transcript_in_circuit() {
+    // a proof over Vesta
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+    let index = new_index_for_test::<Vesta>(gates, public.len());
+    let verifier_index = index.verifier_index();
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proof = ProverProof::create::<BaseSponge, ScalarSponge, _>(
+        &group_map,
+        witness,
+        &[],
+        &index,
+        &mut rand::rngs::OsRng,
+    )
+    .unwrap();
+    let deferred =
+        DeferredValues::compute::<BaseSponge, ScalarSponge>(&verifier_index, &proof, &public)
+            .unwrap();
+
+    // its transcript, replayed in a circuit over Pallas whose public inputs
+    // are the digest of the verifier index and the deferred values
+    let shape = ProofShape::new(&verifier_index);
+    let mut builder = CircuitBuilder::<Fq>::new();
+    let digest = builder.public_input();
+    let combined_inner_product: Vec<_> = deferred
+        .combined_inner_product_absorbed()
+        .iter()
+        .map(|_| builder.public_input())
+        .collect();
+    let challenges: Vec<_> = deferred
+        .challenges_in_base_field()
+        .iter()
+        .map(|_| builder.public_input())
+        .collect();
+    let sponge_digest = builder.public_input();
+    let u = builder.public_input();
+    let proof_vars = ProofVars::private_inputs(&mut builder, &shape);
+    let outputs = replay_transcript(
+        &mut builder,
+        Vesta::other_curve_sponge_params(),
+        digest,
+        &proof_vars,
+        &combined_inner_product,
+    );
+    let expected = [
+        outputs.beta,
+        outputs.gamma,
+        outputs.alpha_chal,
+        outputs.zeta_chal,
+    ]
+    .into_iter()
+    .chain(outputs.ipa_challenges)
+    .chain([outputs.c]);
+    for (output, challenge) in expected.zip(challenges) {
+        builder.assert_equal(output, challenge);
+    }
+    builder.assert_equal(outputs.sponge_digest, sponge_digest);
+    builder.assert_equal(outputs.u, u);
+
+    let (gates, _, witness) = builder.build();
+    let mut public_values = vec![verifier_index.digest::<BaseSponge>()];
+    public_values.extend(deferred.combined_inner_product_absorbed());
+    public_values.extend(deferred.challenges_in_base_field());
+    public_values.extend([deferred.sponge_digest, deferred.u]);
+    let private_values = ProofVars::values(&verifier_index, &proof, &public).unwrap();
+    let witness = witness(&public_values, &private_values);
+
+    TestFramework::<Pallas>::default()
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(public_values)
+        .setup()
+        .prove_and_verify::<PallasBaseSponge, PallasScalarSponge>()
+        .unwrap();
+}
@@ -1,13 +1,23 @@
 use crate::{
     bench::BenchmarkCtx,
     circuits::{
+        gate::CircuitGate,
+        lookup::tables::LookupTable,
         polynomials::generic::testing::{create_circuit, fill_in_witness},
         wires::COLUMNS,
     },
+    error::{ProverIndexCacheError, VerifierKeyError},
     proof::ProverProof,
-    prover_index::testing::new_index_for_test,
+    prover_index::{
+        testing::{new_index_for_test, new_index_for_test_with_lookups},
+        ProverIndex,
+    },
+    tests::{
+        lookup::{create_lookup_circuit, create_vector_lookup_circuit},
+        poseidon2::create_poseidon2_circuit,
+    },
     verifier::verify,
-    verifier_index::VerifierIndex,
+    verifier_index::{CompactVerifierIndex, VerifierIndex},
 };
 use ark_ec::short_weierstrass::Affine;
 use ark_ff::Zero;
@@ -23,12 +33,56 @@ use poly_commitment::{
     ipa::{OpeningProof, SRS},
     SRS as _,
 };
-use std::time::Instant;
+use std::{sync::Arc, time::Instant};
 
 type SpongeParams = PlonkSpongeConstantsKimchi;
 type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
 type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
 
+/// Creates a proof of the circuit `gates` without public input, checks that
+/// it is unchanged by a round-trip through rmp_serde and that the decoded
+/// proof verifies, and returns it
+fn check_rmp_serde_round_trip(
+    gates: Vec<CircuitGate<Fp>>,
+    witness: [Vec<Fp>; COLUMNS],
+    lookup_tables: Vec<LookupTable<Fp>>,
+    log_up: bool,
+) -> ProverProof<Vesta, OpeningProof<Vesta>> {
+    let index = new_index_for_test_with_lookups::<Vesta>(
+        gates,
+        0,
+        0,
+        lookup_tables,
+        None,
+        false,
+        None,
+        false,
+        log_up,
+    );
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proof = ProverProof::create::<BaseSponge, ScalarSponge, _>(
+        &group_map,
+        witness,
+        &[],
+        &index,
+        &mut rand::rngs::OsRng,
+    )
+    .unwrap();
+
+    let bytes = rmp_serde::to_vec(&proof).unwrap();
+    let decoded: ProverProof<Vesta, OpeningProof<Vesta>> = rmp_serde::from_slice(&bytes).unwrap();
+    assert_eq!(decoded, proof);
+    verify::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>>(
+        &group_map,
+        &index.verifier_index(),
+        &decoded,
+        &[],
+    )
+    .unwrap();
+
+    decoded
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -52,6 +106,42 @@ mod tests {
         ctx.batch_verification(&vec![(de_pf, public_input)]);
     }
 
+    #[test]
+    fn test_rmp_serde_vector_lookup() {
+        let (gates, witness, table) = create_vector_lookup_circuit(true);
+        let proof = check_rmp_serde_round_trip(gates, witness, vec![table], false);
+
+        // the evaluations of the Cairo selectors preceding the one of the
+        // vector lookup selector are absent
+        assert!(proof.evals.cairo_selectors.is_none());
+        assert!(proof.evals.vector_lookup_selector.is_some());
+    }
+
+    #[test]
+    fn test_rmp_serde_log_up() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let (gates, witness, tables) = create_lookup_circuit(&mut rng, true, 100, &[64]);
+        let proof = check_rmp_serde_round_trip(gates, witness, tables, true);
+
+        // the evaluations preceding the one of the multiplicities are absent
+        assert!(proof.evals.cairo_selectors.is_none());
+        assert!(proof.evals.vector_lookup_selector.is_none());
+        assert!(proof.evals.lookup_multiplicities.is_some());
+    }
+
+    #[test]
+    fn test_rmp_serde_poseidon2() {
+        let (gates, witness) = create_poseidon2_circuit();
+        let proof = check_rmp_serde_round_trip(gates, witness, vec![], false);
+
+        // the optional evaluations preceding the ones of the Poseidon2
+        // selectors are absent
+        assert!(proof.evals.cairo_selectors.is_none());
+        assert!(proof.evals.vector_lookup_selector.is_none());
+        assert!(proof.evals.lookup_multiplicities.is_none());
+        assert!(proof.evals.poseidon2_selectors.is_some());
+    }
+
     #[test]
     pub fn test_serialization() {
         let public = vec![Fp::from(3u8); 5];
@@ -88,8 +178,8 @@ mod tests {
         // add srs with lagrange bases
         let srs = SRS::<Affine<VestaParameters>>::create(verifier_index.max_poly_size);
         srs.get_lagrange_basis(verifier_index.domain);
-        verifier_index_deserialize.powers_of_alpha = index.powers_of_alpha;
-        verifier_index_deserialize.linearization = index.linearization;
+        verifier_index_deserialize.powers_of_alpha = index.powers_of_alpha().clone();
+        verifier_index_deserialize.linearization = index.linearization().clone();
         verifier_index_deserialize.srs = std::sync::Arc::new(srs);
 
         // verify the proof
@@ -103,4 +193,142 @@ mod tests {
         .unwrap();
         println!("- time to verify: {}ms", start.elapsed().as_millis());
     }
+
+    #[test]
+    fn test_prover_index_cache() {
+        let public = vec![Fp::from(3u8); 5];
+        let gates = create_circuit(0, public.len());
+        let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+        fill_in_witness(0, &mut witness, &public);
+
+        // the column evaluations of a lazy index are computed to be written
+        let index = new_index_for_test_with_lookups::<Vesta>(
+            gates,
+            public.len(),
+            0,
+            vec![],
+            None,
+            false,
+            None,
+            true,
+            false,
+        );
+        let circuit_digest = index.circuit_digest();
+        let mut bytes = vec![];
+        index.serialize_to(&mut bytes).unwrap();
+
+        let loaded = ProverIndex::<Vesta, OpeningProof<Vesta>>::load_from(
+            bytes.as_slice(),
+            index.srs.clone(),
+            circuit_digest,
+        )
+        .unwrap();
+        assert_eq!(loaded.circuit_digest(), circuit_digest);
+
+        let group_map = <Vesta as CommitmentCurve>::Map::setup();
+        let proof = ProverProof::create::<BaseSponge, ScalarSponge, _>(
+            &group_map,
+            witness,
+            &[],
+            &loaded,
+            &mut rand::rngs::OsRng,
+        )
+        .unwrap();
+        verify::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>>(
+            &group_map,
+            &index.verifier_index(),
+            &proof,
+            &public,
+        )
+        .unwrap();
+
+        // the index of another circuit
+        let mut other_digest = circuit_digest;
+        other_digest[0] ^= 1;
+        assert!(matches!(
+            ProverIndex::<Vesta, OpeningProof<Vesta>>::load_from(
+                bytes.as_slice(),
+                index.srs.clone(),
+                other_digest,
+            ),
+            Err(ProverIndexCacheError::CircuitMismatch)
+        ));
+
+        // an altered index
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        assert!(matches!(
+            ProverIndex::<Vesta, OpeningProof<Vesta>>::load_from(
+                bytes.as_slice(),
+                index.srs.clone(),
+                circuit_digest,
+            ),
+            Err(ProverIndexCacheError::Corrupted)
+        ));
+        assert!(matches!(
+            ProverIndex::<Vesta, OpeningProof<Vesta>>::load_from(
+                &bytes[1..],
+                index.srs.clone(),
+                circuit_digest,
+            ),
+            Err(ProverIndexCacheError::InvalidMagic)
+        ));
+    }
+
+    #[test]
+    fn test_compact_verifier_index() {
+        let public = vec![Fp::from(3u8); 5];
+        let gates = create_circuit(0, public.len());
+        let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+        fill_in_witness(0, &mut witness, &public);
+
+        let index = new_index_for_test::<Vesta>(gates, public.len());
+        let verifier_index = index.verifier_index();
+        let group_map = <Vesta as CommitmentCurve>::Map::setup();
+        let proof = ProverProof::create::<BaseSponge, ScalarSponge, _>(
+            &group_map,
+            witness,
+            &[],
+            &index,
+            &mut rand::rngs::OsRng,
+        )
+        .unwrap();
+
+        let compact = verifier_index.to_compact();
+        let bytes = compact.to_bytes().unwrap();
+        let decoded = CompactVerifierIndex::<Vesta>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes().unwrap(), bytes);
+        assert_eq!(
+            decoded.blake2_digest().unwrap(),
+            compact.blake2_digest().unwrap()
+        );
+        assert_eq!(
+            decoded.digest::<BaseSponge>(),
+            verifier_index.digest::<BaseSponge>()
+        );
+
+        // the reconstructed index verifies the proofs of the circuit
+        let loaded = VerifierIndex::<Vesta, OpeningProof<Vesta>>::from_compact(
+            decoded.clone(),
+            index.srs.clone(),
+        )
+        .unwrap();
+        assert_eq!(loaded.shift, verifier_index.shift);
+        assert_eq!(loaded.endo, verifier_index.endo);
+        verify::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>>(
+            &group_map, &loaded, &proof, &public,
+        )
+        .unwrap();
+
+        // an SRS of another size
+        let srs = Arc::new(SRS::<Vesta>::create(loaded.max_poly_size / 2));
+        assert!(matches!(
+            VerifierIndex::<Vesta, OpeningProof<Vesta>>::from_compact(decoded, srs),
+            Err(VerifierKeyError::SrsMismatch(_, _))
+        ));
+        assert!(matches!(
+            CompactVerifierIndex::<Vesta>::from_bytes(&bytes[1..]),
+            Err(VerifierKeyError::InvalidMagic)
+        ));
+    }
 }
@@ -0,0 +1,130 @@
+use super::framework::TestFramework;
+use crate::{
+    circuits::{
+        gate::{CircuitGate, Connect, GateType},
+        polynomials::{
+            generic::GenericGateSpec,
+            poseidon::{self, POS_ROWS_PER_HASH},
+        },
+        wires::{Wire, COLUMNS},
+        witness::solver::{SolverError, WitnessSolver},
+    },
+    curve::KimchiCurve,
+};
+use ark_ff::Zero;
+use core::array;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+// A circuit with the public input x and the private input y, computing
+// z = x * y in the row 1, and z + 5 in the row 2
+fn generic_circuit() -> Vec<CircuitGate<Fp>> {
+    let mut gates = vec![
+        CircuitGate::create_generic_gadget(Wire::for_row(0), GenericGateSpec::Pub, None),
+        CircuitGate::create_generic_gadget(
+            Wire::for_row(1),
+            GenericGateSpec::Mul {
+                output_coeff: None,
+                mul_coeff: None,
+            },
+            None,
+        ),
+        CircuitGate::create_generic_gadget(
+            Wire::for_row(2),
+            GenericGateSpec::Plus(Fp::from(5u64)),
+            None,
+        ),
+    ];
+    gates.connect_cell_pair((0, 0), (1, 0));
+    gates.connect_cell_pair((1, 2), (2, 0));
+    gates
+}
+
+#[test]
+fn test_solve_generic() {
+    let gates = generic_circuit();
+    let public = vec![Fp::from(6u64)];
+    let mut solver = WitnessSolver::new(&gates, &public);
+    solver.assign((1, 1), Fp::from(7u64));
+    let witness = solver.solve::<Vesta>().unwrap();
+
+    assert_eq!(witness[0][1], Fp::from(6u64));
+    assert_eq!(witness[2][1], Fp::from(42u64));
+    assert_eq!(witness[2][2], Fp::from(47u64));
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(public)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+fn test_solve_generic_backwards() {
+    // The private input is derived from the output of the circuit
+    let gates = generic_circuit();
+    let mut solver = WitnessSolver::new(&gates, &[Fp::from(6u64)]);
+    solver.assign((2, 2), Fp::from(47u64));
+    let witness = solver.solve::<Vesta>().unwrap();
+
+    assert_eq!(witness[1][1], Fp::from(7u64));
+}
+
+#[test]
+fn test_solve_poseidon() {
+    let params = Vesta::sponge_params();
+    let (gates, _) = CircuitGate::<Fp>::create_poseidon_gadget(
+        0,
+        [Wire::for_row(0), Wire::for_row(POS_ROWS_PER_HASH)],
+        &params.round_constants,
+    );
+    let input = [1, 2, 3].map(Fp::from);
+
+    let mut solver = WitnessSolver::new(&gates, &[]);
+    solver.assign_row(0, &input);
+    let witness = solver.solve::<Vesta>().unwrap();
+
+    let mut expected: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    poseidon::generate_witness(0, params, &mut expected, input);
+    assert_eq!(witness, expected);
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+fn test_solve_errors() {
+    let gates = generic_circuit();
+
+    // The private input is missing
+    let solver = WitnessSolver::new(&gates, &[Fp::from(6u64)]);
+    assert_eq!(solver.solve::<Vesta>(), Err(SolverError::Unsolved(1, 1)));
+
+    // The output of the circuit contradicts the inputs
+    let mut solver = WitnessSolver::new(&gates, &[Fp::from(6u64)]);
+    solver
+        .assign((1, 1), Fp::from(7u64))
+        .assign((2, 2), Fp::from(48u64));
+    assert_eq!(solver.solve::<Vesta>(), Err(SolverError::Unsatisfied(2)));
+
+    // The rows of the XOR gadget have no rule
+    let (_, gates) = CircuitGate::<Fp>::create_xor_gadget(0, 16);
+    let solver = WitnessSolver::new(&gates, &[]);
+    assert_eq!(
+        solver.solve::<Vesta>(),
+        Err(SolverError::UnsupportedGate(0, GateType::Xor16))
+    );
+}
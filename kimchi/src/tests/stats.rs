@@ -0,0 +1,97 @@
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::{CircuitGate, GateType},
+    lookup::lookups::LookupPattern,
+    polynomials::generic::{testing::create_circuit, GenericGateSpec},
+    wires::Wire,
+};
+use mina_curves::pasta::Fp;
+use std::collections::BTreeMap;
+
+fn xor_circuit(bits: usize) -> Vec<CircuitGate<Fp>> {
+    let mut gates = vec![CircuitGate::<Fp>::create_generic_gadget(
+        Wire::for_row(0),
+        GenericGateSpec::Pub,
+        None,
+    )];
+    CircuitGate::extend_xor_gadget(&mut gates, bits);
+    gates
+}
+
+#[test]
+fn test_stats_generic() {
+    let cs = ConstraintSystem::<Fp>::create(create_circuit(0, 3))
+        .public(3)
+        .build()
+        .unwrap();
+    let stats = cs.stats();
+
+    assert_eq!(stats.rows, 23);
+    assert_eq!(stats.public, 3);
+    assert_eq!(stats.domain_size, 32);
+    assert_eq!(stats.zk_rows, 3);
+    assert_eq!(stats.gates, BTreeMap::from([(GateType::Generic, 23)]));
+    assert!(stats.lookups.is_empty());
+    assert_eq!(stats.max_degree, 3);
+
+    // The public input, the witness, the permutation and the quotient
+    assert_eq!(stats.cost.msms, 24);
+    assert_eq!(stats.cost.msm_size, 32);
+    assert_eq!(stats.cost.opening_rounds, 5);
+    assert_eq!(stats.cost.max_fft_size(), 256);
+    assert_eq!(
+        stats.cost.ffts,
+        BTreeMap::from([(32, 17), (128, 2), (256, 17)])
+    );
+}
+
+#[test]
+fn test_stats_xor_lookups() {
+    let cs = ConstraintSystem::<Fp>::create(xor_circuit(64))
+        .public(1)
+        .build()
+        .unwrap();
+    let stats = cs.stats();
+
+    // 4 Xor16 gates of 4 lookups each, and the final generic gate
+    assert_eq!(
+        stats.gates,
+        BTreeMap::from([(GateType::Generic, 2), (GateType::Xor16, 4)])
+    );
+    assert_eq!(stats.lookups, BTreeMap::from([(LookupPattern::Xor, 16)]));
+    assert!(!stats.log_up);
+    // The 5 sorted polynomials of plookup and the aggregation
+    assert_eq!(stats.cost.msms, 24 + 6);
+
+    let log_up = ConstraintSystem::<Fp>::create(xor_circuit(64))
+        .public(1)
+        .log_up(true)
+        .build()
+        .unwrap()
+        .stats();
+    assert!(log_up.log_up);
+    // The multiplicities and the aggregation
+    assert_eq!(log_up.cost.msms, 24 + 2);
+    assert!(log_up.cost.ffts[&log_up.domain_size] < stats.cost.ffts[&stats.domain_size]);
+}
+
+#[test]
+fn test_stats_compare_gadgets() {
+    let stats = |bits| {
+        ConstraintSystem::<Fp>::create(xor_circuit(bits))
+            .public(1)
+            .build()
+            .unwrap()
+            .stats()
+    };
+    let (small, large) = (stats(16), stats(128));
+    assert!(small.rows < large.rows);
+    assert_eq!(small.lookups[&LookupPattern::Xor], 4);
+    assert_eq!(large.lookups[&LookupPattern::Xor], 32);
+    assert_eq!(small.max_degree, large.max_degree);
+
+    let display = large.to_string();
+    assert!(display.starts_with("10 rows (1 public)"), "{display}");
+    assert!(display.contains("gates: Generic: 2, Xor16: 8"), "{display}");
+    assert!(display.contains("lookups (plookup): Xor: 32"), "{display}");
+}
@@ -1,7 +1,7 @@
-use super::framework::TestFramework;
+use super::framework::{FailureKind, TestFramework};
 use crate::{
     circuits::{
-        constraints::ConstraintSystem,
+        constraints::{ConstraintFailure, ConstraintSystem, GateError},
         gate::{CircuitGate, CircuitGateError, Connect, GateType},
         polynomial::COLUMNS,
         polynomials::{generic::GenericGateSpec, xor},
@@ -19,7 +19,7 @@ use mina_poseidon::{
     constants::PlonkSpongeConstantsKimchi,
     sponge::{DefaultFqSponge, DefaultFrSponge},
 };
-use num_bigint::BigUint;
+use num_bigint::{BigUint, RandBigInt};
 use o1_utils::{BigUintHelpers, BitwiseOps, FieldHelpers, RandomField};
 use poly_commitment::{
     ipa::{endos, OpeningProof, SRS},
@@ -265,6 +265,32 @@ fn test_bad_xor_decompsition() {
     verify_bad_xor_decomposition::<Vesta>(&mut witness, cs);
 }
 
+#[test]
+// Test that the witness verification of the prover pinpoints the failing xor constraint
+fn test_bad_xor_verify_pinpoints_constraint() {
+    let (cs, mut witness) = setup_xor::<Vesta>(None, None, Some(16));
+    // the first nibble of the first input makes the 1st constraint fail
+    witness[3][0] += PallasField::one();
+
+    let runner = TestFramework::<Vesta>::default()
+        .gates(cs.gates.to_vec())
+        .witness(witness.clone())
+        .setup();
+    let err = runner.prover_index().verify(&witness, &[]).unwrap_err();
+    assert!(matches!(
+        err,
+        GateError::Constraint(ConstraintFailure {
+            row: 0,
+            typ: GateType::Xor16,
+            constraint: 1,
+            values: Some((left, right)),
+        }) if left != right
+    ));
+    assert!(err
+        .to_string()
+        .starts_with("row 0: constraint 1 of the Xor16 gate is not satisfied"));
+}
+
 #[test]
 // Tests that the extend xor function works as expected
 fn test_extend_xor() {
@@ -333,16 +359,13 @@ fn test_bad_xor() {
         witness[COLUMNS - i][0] = PallasField::zero();
     }
 
-    assert_eq!(
-        TestFramework::<Vesta>::default()
-            .gates(gates)
-            .witness(witness)
-            .setup()
-            .prove_and_verify::<VestaBaseSponge, VestaScalarSponge>(),
-        Err(String::from(
-            "the lookup failed to find a match in the table: row=0"
-        ))
-    );
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_expecting_failure::<VestaBaseSponge, VestaScalarSponge>(
+            FailureKind::ValueNotInTable { row: 0 },
+        );
 }
 
 #[test]
@@ -416,3 +439,67 @@ fn test_xor_finalization() {
         .prove_and_verify::<BaseSponge, ScalarSponge>()
         .unwrap();
 }
+
+#[test]
+// Tests the XOR of words wider than the native field, and of a last limb
+// shorter than the others
+fn test_wide_xor() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    for (bits, rows) in [(128, vec![0]), (200, vec![0, 9]), (256, vec![0, 9])] {
+        let mut gates = vec![];
+        let next_row = CircuitGate::<Fp>::extend_wide_xor_gadget(&mut gates, bits);
+        assert_eq!(xor::wide_xor_rows(bits), rows);
+        assert_eq!(next_row, xor::num_xors(bits) + rows.len());
+
+        let input1 = rng.gen_biguint(bits as u64);
+        let input2 = rng.gen_biguint(bits as u64);
+        let output = BigUint::bitwise_xor(&input1, &input2);
+        let witness = xor::create_wide_xor_witness::<Fp>(&input1, &input2, bits);
+        assert_eq!(witness[0].len(), next_row);
+
+        // The limbs of the words are in the first rows of the limbs
+        for (col, word) in [&input1, &input2, &output].into_iter().enumerate() {
+            let limbs = rows.iter().rev().fold(BigUint::zero(), |acc, row| {
+                (acc << xor::XOR_LIMB_BITS) + witness[col][*row].to_biguint()
+            });
+            assert_eq!(&limbs, word);
+        }
+
+        let cs = ConstraintSystem::create(gates).build().unwrap();
+        for row in 0..next_row {
+            assert_eq!(
+                cs.gates[row].verify_witness::<Vesta>(row, &witness, &cs, &[]),
+                Ok(())
+            );
+        }
+    }
+}
+
+#[test]
+// End-to-end test of the XOR of 256-bit words
+fn test_prove_and_verify_wide_xor() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+
+    let bits = 256;
+    let mut gates = vec![];
+    CircuitGate::<Fp>::extend_wide_xor_gadget(&mut gates, bits);
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let input1 = rng.gen_biguint(bits as u64);
+    let input2 = rng.gen_biguint(bits as u64);
+    xor::extend_wide_xor_witness(&mut witness, &input1, &input2, bits);
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<VestaBaseSponge, VestaScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+#[should_panic(expected = "Bits must be greater or equal than the inputs length")]
+fn test_wide_xor_overflow() {
+    let input = BigUint::from(1u8) << 256;
+    xor::create_wide_xor_witness::<Fp>(&input, &BigUint::zero(), 256);
+}
@@ -0,0 +1,177 @@
+use crate::{
+    circuits::{
+        constraints::{
+            zk_rows_strict_lower_bound, ConstraintSystem, ZkLevel, ZK_ROWS_BY_DEFAULT,
+            ZK_ROWS_DISABLED,
+        },
+        gate::CircuitGate,
+        polynomials::{
+            generic::testing::{create_circuit, fill_in_witness},
+            xor,
+        },
+        wires::COLUMNS,
+    },
+    curve::KimchiCurve,
+    error::SetupError,
+    proof::ProverProof,
+    prover_index::ProverIndex,
+    verifier::verify,
+};
+use ark_ff::Zero;
+use core::array;
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use o1_utils::RandomField;
+use poly_commitment::{
+    commitment::CommitmentCurve,
+    ipa::{OpeningProof, SRS},
+    precomputed_srs, SRS as _,
+};
+use std::sync::Arc;
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+fn index_with_zk_level(
+    gates: Vec<CircuitGate<Fp>>,
+    public: usize,
+    zk_level: ZkLevel,
+) -> Result<ProverIndex<Vesta, OpeningProof<Vesta>>, SetupError> {
+    let cs = ConstraintSystem::create(gates)
+        .public(public)
+        .zk_level(zk_level)
+        .build()?;
+    let srs: SRS<Vesta> = precomputed_srs::get_srs_test();
+    srs.get_lagrange_basis(cs.domain.d1);
+    Ok(ProverIndex::create(
+        cs,
+        *Vesta::other_curve_endo(),
+        Arc::new(srs),
+        false,
+    ))
+}
+
+fn prove(
+    index: &ProverIndex<Vesta, OpeningProof<Vesta>>,
+    witness: [Vec<Fp>; COLUMNS],
+) -> ProverProof<Vesta, OpeningProof<Vesta>> {
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    ProverProof::create::<BaseSponge, ScalarSponge, _>(
+        &group_map,
+        witness,
+        &[],
+        index,
+        &mut rand::rngs::OsRng,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_zk_level_disabled() {
+    // A circuit of 30 rows fits in a domain of 32 rows without
+    // zero-knowledge, but not with the default zero-knowledge rows
+    let public_size = 30 - create_circuit::<Fp>(0, 0).len();
+    let public = vec![Fp::from(7u8); public_size];
+    let gates = create_circuit(0, public_size);
+    let rows = gates.len();
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); rows]);
+    fill_in_witness(0, &mut witness, &public);
+
+    let hiding = index_with_zk_level(gates.clone(), public_size, ZkLevel::Full).unwrap();
+    assert_eq!(hiding.cs.zk_rows, ZK_ROWS_BY_DEFAULT);
+    assert_eq!(hiding.cs.domain.d1.size, 64);
+
+    let index = index_with_zk_level(gates, public_size, ZkLevel::Disabled).unwrap();
+    assert_eq!(index.cs.zk_rows, ZK_ROWS_DISABLED);
+    assert_eq!(index.cs.domain.d1.size, 32);
+    let verifier_index = index.verifier_index();
+    assert_eq!(verifier_index.zk_level, ZkLevel::Disabled);
+    assert_eq!(verifier_index.to_compact().zk_level, ZkLevel::Disabled);
+
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proof = prove(&index, witness.clone());
+    verify::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>>(
+        &group_map,
+        &verifier_index,
+        &proof,
+        &public,
+    )
+    .unwrap();
+
+    // Without blinding, the commitments to the witness only depend on it
+    let other_proof = prove(&index, witness);
+    assert_eq!(proof.commitments.w_comm, other_proof.commitments.w_comm);
+}
+
+#[test]
+fn test_zk_level_disabled_with_lookups() {
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+    let bits = 64;
+    let mut gates = vec![];
+    CircuitGate::<Fp>::extend_xor_gadget(&mut gates, bits);
+    let witness = xor::create_xor_witness(
+        rng.gen_field_with_bits(bits),
+        rng.gen_field_with_bits(bits),
+        bits,
+    );
+
+    let index = index_with_zk_level(gates, 0, ZkLevel::Disabled).unwrap();
+    let proof = prove(&index, witness);
+    verify::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>>(
+        &<Vesta as CommitmentCurve>::Map::setup(),
+        &index.verifier_index(),
+        &proof,
+        &[],
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_zk_level_rows() {
+    let public = vec![Fp::from(7u8); 5];
+    let gates = create_circuit(0, public.len());
+    let rows = gates.len();
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); rows]);
+    fill_in_witness(0, &mut witness, &public);
+
+    let index = index_with_zk_level(gates.clone(), public.len(), ZkLevel::Rows(6)).unwrap();
+    assert_eq!(index.cs.zk_rows, 6);
+    let verifier_index = index.verifier_index();
+    assert_eq!(verifier_index.zk_level, ZkLevel::Rows(6));
+    let proof = prove(&index, witness);
+    verify::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>>(
+        &<Vesta as CommitmentCurve>::Map::setup(),
+        &verifier_index,
+        &proof,
+        &public,
+    )
+    .unwrap();
+
+    // Two rows are not enough for zero-knowledge with a single chunk
+    assert!(matches!(
+        index_with_zk_level(gates.clone(), public.len(), ZkLevel::Rows(2)),
+        Err(SetupError::ConstraintSystem(_))
+    ));
+
+    // With chunks, the number of rows depends on the number of chunks
+    let chunked = |zk_level| {
+        ConstraintSystem::<Fp>::create(gates.clone())
+            .public(public.len())
+            .max_poly_size(Some(8))
+            .zk_level(zk_level)
+            .build()
+    };
+    let cs = chunked(ZkLevel::Full).unwrap();
+    let zk_rows = zk_rows_strict_lower_bound(cs.domain.d1.size as usize / 8) as u64 + 1;
+    assert_eq!(cs.zk_rows, zk_rows);
+    assert_eq!(chunked(ZkLevel::Rows(zk_rows)).unwrap().zk_rows, zk_rows);
+    assert!(matches!(
+        chunked(ZkLevel::Rows(zk_rows - 1)),
+        Err(SetupError::ConstraintSystem(_))
+    ));
+}
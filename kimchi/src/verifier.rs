@@ -8,7 +8,7 @@ use crate::{
         expr::{Constants, PolishToken},
         gate::GateType,
         lookup::{lookups::LookupPattern, tables::combine_table},
-        polynomials::permutation,
+        polynomials::{permutation, poseidon2, turshi},
         scalars::RandomOracles,
         wires::{COLUMNS, PERMUTS},
     },
@@ -69,24 +69,36 @@ impl<'a, G: KimchiCurve, OpeningProof: OpenProof<G>> Context<'a, G, OpeningProof
                     .as_ref()?,
             ),
             LookupRuntimeTable => self.proof.commitments.lookup.as_ref()?.runtime.as_ref(),
+            LookupMultiplicities => self
+                .proof
+                .commitments
+                .lookup
+                .as_ref()?
+                .multiplicities
+                .as_ref(),
             Index(t) => {
                 use GateType::*;
                 match t {
                     Zero => None,
                     Generic => Some(&self.verifier_index.generic_comm),
-                    Lookup => None,
+                    Lookup | VectorLookup => None,
                     CompleteAdd => Some(&self.verifier_index.complete_add_comm),
                     VarBaseMul => Some(&self.verifier_index.mul_comm),
                     EndoMul => Some(&self.verifier_index.emul_comm),
                     EndoMulScalar => Some(&self.verifier_index.endomul_scalar_comm),
                     Poseidon => Some(&self.verifier_index.psm_comm),
-                    CairoClaim | CairoInstruction | CairoFlags | CairoTransition => None,
+                    CairoClaim => Some(&self.verifier_index.cairo_comms.as_ref()?[0]),
+                    CairoInstruction => Some(&self.verifier_index.cairo_comms.as_ref()?[1]),
+                    CairoFlags => Some(&self.verifier_index.cairo_comms.as_ref()?[2]),
+                    CairoTransition => Some(&self.verifier_index.cairo_comms.as_ref()?[3]),
                     RangeCheck0 => Some(self.verifier_index.range_check0_comm.as_ref()?),
                     RangeCheck1 => Some(self.verifier_index.range_check1_comm.as_ref()?),
                     ForeignFieldAdd => Some(self.verifier_index.foreign_field_add_comm.as_ref()?),
                     ForeignFieldMul => Some(self.verifier_index.foreign_field_mul_comm.as_ref()?),
                     Xor16 => Some(self.verifier_index.xor_comm.as_ref()?),
                     Rot64 => Some(self.verifier_index.rot_comm.as_ref()?),
+                    Poseidon2External => Some(&self.verifier_index.poseidon2_comms.as_ref()?[0]),
+                    Poseidon2Internal => Some(&self.verifier_index.poseidon2_comms.as_ref()?[1]),
                 }
             }
         }
@@ -114,6 +126,26 @@ where
         index: &VerifierIndex<G, OpeningProof>,
         public_comm: &PolyComm<G>,
         public_input: Option<&[G::ScalarField]>,
+    ) -> Result<OraclesResult<G, EFqSponge>> {
+        self.oracles_with_digest::<EFqSponge, EFrSponge>(
+            index,
+            index.digest::<EFqSponge>(),
+            public_comm,
+            public_input,
+        )
+    }
+
+    /// Runs the random oracle argument as [Self::oracles], with the digest
+    /// `verifier_index_digest` of `index` computed beforehand
+    fn oracles_with_digest<
+        EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+        EFrSponge: FrSponge<G::ScalarField>,
+    >(
+        &self,
+        index: &VerifierIndex<G, OpeningProof>,
+        verifier_index_digest: G::BaseField,
+        public_comm: &PolyComm<G>,
+        public_input: Option<&[G::ScalarField]>,
     ) -> Result<OraclesResult<G, EFqSponge>> {
         //~
         //~ #### Fiat-Shamir argument
@@ -123,14 +155,7 @@ where
         let n = index.domain.size;
         let (_, endo_r) = G::endos();
 
-        let chunk_size = {
-            let d1_size = index.domain.size();
-            if d1_size < index.max_poly_size {
-                1
-            } else {
-                d1_size / index.max_poly_size
-            }
-        };
+        let chunk_size = index.num_chunks();
 
         let zk_rows = index.zk_rows;
 
@@ -140,7 +165,6 @@ where
         let mut fq_sponge = EFqSponge::new(G::other_curve_sponge_params());
 
         //~ 1. Absorb the digest of the VerifierIndex.
-        let verifier_index_digest = index.digest::<EFqSponge>();
         fq_sponge.absorb_fq(&[verifier_index_digest]);
 
         //~ 1. Absorb the commitments of the previous challenges with the Fq-sponge.
@@ -196,7 +220,7 @@ where
             None
         };
 
-        if index.lookup_index.is_some() {
+        if let Some(li) = &index.lookup_index {
             let lookup_commits = self
                 .commitments
                 .lookup
@@ -207,6 +231,16 @@ where
             for com in &lookup_commits.sorted {
                 absorb_commitment(&mut fq_sponge, com);
             }
+
+            //~~ * if the lookups use the logUp argument, absorb the commitment to the
+            //~~   multiplicities polynomial.
+            if li.lookup_info.features.log_up {
+                let multiplicities = lookup_commits
+                    .multiplicities
+                    .as_ref()
+                    .ok_or(VerifyError::LookupCommitmentMissing)?;
+                absorb_commitment(&mut fq_sponge, multiplicities);
+            }
         }
 
         // --- PlonK - Round 2
@@ -529,12 +563,26 @@ where
                         .as_ref()
                         .map(|_| Column::Index(GateType::Rot64)),
                 )
+                .chain(
+                    index
+                        .cairo_comms
+                        .as_ref()
+                        .into_iter()
+                        .flat_map(|_| turshi::circuit_gates().map(Column::Index)),
+                )
+                .chain(
+                    index
+                        .poseidon2_comms
+                        .as_ref()
+                        .into_iter()
+                        .flat_map(|_| poseidon2::circuit_gates().map(Column::Index)),
+                )
                 .chain(
                     index
                         .lookup_index
                         .as_ref()
                         .map(|li| {
-                            (0..li.lookup_info.max_per_row + 1)
+                            (0..li.lookup_info.sorted_columns())
                                 .map(Column::LookupSorted)
                                 .chain([Column::LookupAggreg, Column::LookupTable].into_iter())
                                 .chain(
@@ -570,6 +618,17 @@ where
                                 .chain(self.evals.foreign_field_mul_lookup_selector.as_ref().map(
                                     |_| Column::LookupKindIndex(LookupPattern::ForeignFieldMul),
                                 ))
+                                .chain(
+                                    self.evals.vector_lookup_selector.as_ref().map(|_| {
+                                        Column::LookupKindIndex(LookupPattern::VectorLookup)
+                                    }),
+                                )
+                                .chain(
+                                    li.lookup_info
+                                        .features
+                                        .log_up
+                                        .then_some(Column::LookupMultiplicities),
+                                )
                         })
                         .into_iter()
                         .flatten(),
@@ -653,6 +712,10 @@ where
         lookup_gate_lookup_selector,
         range_check_lookup_selector,
         foreign_field_mul_lookup_selector,
+        cairo_selectors,
+        vector_lookup_selector,
+        lookup_multiplicities,
+        poseidon2_selectors,
     } = &proof.evals;
 
     let check_eval_len = |eval: &PointEvaluations<Vec<_>>, str: &'static str| -> Result<()> {
@@ -730,6 +793,12 @@ where
     if let Some(rot_selector) = rot_selector {
         check_eval_len(rot_selector, "rot selector")?
     }
+    for cairo_selector in cairo_selectors.iter().flatten() {
+        check_eval_len(cairo_selector, "cairo selector")?
+    }
+    for poseidon2_selector in poseidon2_selectors.iter().flatten() {
+        check_eval_len(poseidon2_selector, "poseidon2 selector")?
+    }
 
     // Lookup selectors
 
@@ -754,12 +823,70 @@ where
             "foreign field mul lookup selector",
         )?
     }
+    if let Some(vector_lookup_selector) = vector_lookup_selector {
+        check_eval_len(vector_lookup_selector, "vector lookup selector")?
+    }
+    if let Some(lookup_multiplicities) = lookup_multiplicities {
+        check_eval_len(lookup_multiplicities, "lookup multiplicities")?
+    }
 
     Ok(())
 }
 
-fn to_batch<'a, G, EFqSponge, EFrSponge, OpeningProof: OpenProof<G>>(
+/// Commits to the negated public input polynomial of `public_input`, as
+/// absorbed by the verifier.
+///
+/// The commitment is deterministic, and binds the public input: it can be
+/// computed once by the party holding the public input, and given in its place
+/// to [`verify_with_public_commitment`], whose cost does not depend on the
+/// size of the public input.
+///
+/// # Errors
+///
+/// Will give error if `public_input` is not of the size expected by
+/// `verifier_index`.
+pub fn public_commitment<G, OpeningProof: OpenProof<G>>(
     verifier_index: &VerifierIndex<G, OpeningProof>,
+    public_input: &[G::ScalarField],
+) -> Result<PolyComm<G>>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+{
+    if public_input.len() != verifier_index.public {
+        return Err(VerifyError::IncorrectPubicInputLength(
+            verifier_index.public,
+        ));
+    }
+    let chunk_size = verifier_index.num_chunks();
+    let lgr_comm = verifier_index
+        .srs()
+        .get_lagrange_basis(verifier_index.domain);
+    let com: Vec<_> = lgr_comm.iter().take(verifier_index.public).collect();
+    if public_input.is_empty() {
+        Ok(PolyComm::new(vec![
+            verifier_index
+                .srs()
+                .blinding_commitment();
+            chunk_size
+        ]))
+    } else {
+        let elm: Vec<_> = public_input.iter().map(|s| -*s).collect();
+        let public_comm = PolyComm::<G>::multi_scalar_mul(&com, &elm);
+        Ok(verifier_index
+            .srs()
+            .mask_custom(
+                public_comm.clone(),
+                &public_comm.map(|_| G::ScalarField::one()),
+            )
+            .unwrap()
+            .commitment)
+    }
+}
+
+pub(crate) fn to_batch<'a, G, EFqSponge, EFrSponge, OpeningProof: OpenProof<G>>(
+    verifier_index: &VerifierIndex<G, OpeningProof>,
+    verifier_index_digest: G::BaseField,
     proof: &'a ProverProof<G, OpeningProof>,
     public_input: &'a [<G as AffineRepr>::ScalarField],
 ) -> Result<BatchEvaluationProof<'a, G, EFqSponge, OpeningProof>>
@@ -777,6 +904,35 @@ where
     //~ Essentially, this steps verifies that $f(\zeta) = t(\zeta) * Z_H(\zeta)$.
     //~
 
+    // commit to the negated public input polynomial
+    let public_comm = public_commitment(verifier_index, public_input)?;
+
+    to_batch_with_public_commitment::<G, EFqSponge, EFrSponge, OpeningProof>(
+        verifier_index,
+        verifier_index_digest,
+        proof,
+        public_comm,
+        Some(public_input),
+    )
+}
+
+/// Partially verifies `proof` as [to_batch], with the commitment `public_comm`
+/// to its public input computed beforehand. Without the public input
+/// `public_input`, its evaluations are taken from the proof, and checked
+/// against `public_comm` by the opening proof.
+fn to_batch_with_public_commitment<'a, G, EFqSponge, EFrSponge, OpeningProof: OpenProof<G>>(
+    verifier_index: &VerifierIndex<G, OpeningProof>,
+    verifier_index_digest: G::BaseField,
+    proof: &'a ProverProof<G, OpeningProof>,
+    public_comm: PolyComm<G>,
+    public_input: Option<&'a [G::ScalarField]>,
+) -> Result<BatchEvaluationProof<'a, G, EFqSponge, OpeningProof>>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+{
     let zk_rows = verifier_index.zk_rows;
 
     if proof.prev_challenges.len() != verifier_index.prev_challenges {
@@ -785,49 +941,21 @@ where
             proof.prev_challenges.len(),
         ));
     }
-    if public_input.len() != verifier_index.public {
-        return Err(VerifyError::IncorrectPubicInputLength(
-            verifier_index.public,
-        ));
-    }
 
     //~ 1. Check the length of evaluations inside the proof.
-    let chunk_size = {
-        let d1_size = verifier_index.domain.size();
-        if d1_size < verifier_index.max_poly_size {
-            1
-        } else {
-            d1_size / verifier_index.max_poly_size
-        }
-    };
+    let chunk_size = verifier_index.num_chunks();
     check_proof_evals_len(proof, chunk_size)?;
 
     //~ 1. Commit to the negated public input polynomial.
-    let public_comm = {
-        if public_input.len() != verifier_index.public {
-            return Err(VerifyError::IncorrectPubicInputLength(
-                verifier_index.public,
-            ));
-        }
-        let lgr_comm = verifier_index
-            .srs()
-            .get_lagrange_basis(verifier_index.domain);
-        let com: Vec<_> = lgr_comm.iter().take(verifier_index.public).collect();
-        if public_input.is_empty() {
-            PolyComm::new(vec![verifier_index.srs().blinding_commitment(); chunk_size])
-        } else {
-            let elm: Vec<_> = public_input.iter().map(|s| -*s).collect();
-            let public_comm = PolyComm::<G>::multi_scalar_mul(&com, &elm);
-            verifier_index
-                .srs()
-                .mask_custom(
-                    public_comm.clone(),
-                    &public_comm.map(|_| G::ScalarField::one()),
-                )
-                .unwrap()
-                .commitment
-        }
-    };
+    // The commitment is computed by the caller, from the public input or
+    // ahead of time, see `public_commitment`.
+    if public_comm.len() != chunk_size {
+        return Err(VerifyError::IncorrectCommitmentLength(
+            "public input",
+            chunk_size,
+            public_comm.len(),
+        ));
+    }
 
     //~ 1. Run the [Fiat-Shamir argument](#fiat-shamir-argument).
     let OraclesResult {
@@ -841,7 +969,12 @@ where
         ft_eval0,
         combined_inner_product,
         ..
-    } = proof.oracles::<EFqSponge, EFrSponge>(verifier_index, &public_comm, Some(public_input))?;
+    } = proof.oracles_with_digest::<EFqSponge, EFrSponge>(
+        verifier_index,
+        verifier_index_digest,
+        &public_comm,
+        public_input,
+    )?;
 
     //~ 1. Combine the chunked polynomials' evaluations
     //~    (TODO: most likely only the quotient polynomial is chunked)
@@ -851,7 +984,7 @@ where
     let context = Context {
         verifier_index,
         proof,
-        public_input,
+        public_input: public_input.unwrap_or_default(),
     };
 
     //~ 1. Compute the commitment to the linearized polynomial $f$.
@@ -1009,6 +1142,20 @@ where
             .as_ref()
             .map(|_| Column::Index(GateType::Rot64)),
     )
+    .chain(
+        verifier_index
+            .cairo_comms
+            .as_ref()
+            .into_iter()
+            .flat_map(|_| turshi::circuit_gates().map(Column::Index)),
+    )
+    .chain(
+        verifier_index
+            .poseidon2_comms
+            .as_ref()
+            .into_iter()
+            .flat_map(|_| poseidon2::circuit_gates().map(Column::Index)),
+    )
     //~~ * lookup commitments
     //~
     .chain(
@@ -1017,7 +1164,7 @@ where
             .as_ref()
             .map(|li| {
                 // add evaluations of sorted polynomials
-                (0..li.lookup_info.max_per_row + 1)
+                (0..li.lookup_info.sorted_columns())
                     .map(Column::LookupSorted)
                     // add evaluations of the aggreg polynomial
                     .chain([Column::LookupAggreg].into_iter())
@@ -1131,6 +1278,19 @@ where
                     .as_ref()
                     .map(|_| Column::LookupKindIndex(LookupPattern::ForeignFieldMul)),
             )
+            .chain(
+                li.lookup_selectors
+                    .vector_lookup
+                    .as_ref()
+                    .map(|_| Column::LookupKindIndex(LookupPattern::VectorLookup)),
+            )
+            // add evaluations of the multiplicities polynomial
+            .chain(
+                li.lookup_info
+                    .features
+                    .log_up
+                    .then_some(Column::LookupMultiplicities),
+            )
         })
         .into_iter()
         .flatten()
@@ -1186,6 +1346,78 @@ where
     batch_verify::<G, EFqSponge, EFrSponge, OpeningProof>(group_map, &proofs)
 }
 
+/// Verifies a proof [`ProverProof`] as [`verify`], with its public input given
+/// as the commitment `public_comm` computed by [`public_commitment`].
+///
+/// The commitment is absorbed by the Fiat-Shamir argument, which binds the
+/// proof to it, and the evaluations of the public input polynomial given by
+/// the proof are checked against it by the opening proof. The cost of the
+/// verification does not depend on the size of the public input.
+///
+/// # Errors
+///
+/// Will give error if the proof does not hold the evaluations of its public
+/// input polynomial, or if it is not verified as valid.
+pub fn verify_with_public_commitment<G, EFqSponge, EFrSponge, OpeningProof: OpenProof<G>>(
+    group_map: &G::Map,
+    verifier_index: &VerifierIndex<G, OpeningProof>,
+    proof: &ProverProof<G, OpeningProof>,
+    public_comm: &PolyComm<G>,
+) -> Result<()>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+{
+    if proof.evals.public.is_none() {
+        return Err(VerifyError::MissingPublicInputEvaluation);
+    }
+    let batch = to_batch_with_public_commitment::<G, EFqSponge, EFrSponge, OpeningProof>(
+        verifier_index,
+        verifier_index.digest::<EFqSponge>(),
+        proof,
+        public_comm.clone(),
+        None,
+    )?;
+    let srs = verifier_index.srs();
+    if OpeningProof::verify(srs, group_map, &mut [batch], &mut thread_rng()) {
+        Ok(())
+    } else {
+        Err(VerifyError::OpenProof)
+    }
+}
+
+/// Checks that the runtime tables used by `proof` are the ones committed to by
+/// `expected`, as computed by
+/// [`ProverIndex::runtime_table_commitment`](crate::prover_index::ProverIndex::runtime_table_commitment)
+/// from the content of the tables known to the verifier. The proof must have
+/// been created with public runtime tables, and must still be verified with
+/// [`verify`].
+///
+/// # Errors
+///
+/// Will give error if the proof has no runtime tables, or if they do not match
+/// `expected`.
+pub fn verify_runtime_tables<G, OpeningProof: OpenProof<G>>(
+    proof: &ProverProof<G, OpeningProof>,
+    expected: &PolyComm<G>,
+) -> Result<()>
+where
+    G: KimchiCurve,
+{
+    let runtime = proof
+        .commitments
+        .lookup
+        .as_ref()
+        .and_then(|lookup| lookup.runtime.as_ref())
+        .ok_or(VerifyError::IncorrectRuntimeProof)?;
+    if runtime != expected {
+        return Err(VerifyError::IncorrectRuntimeTables);
+    }
+    Ok(())
+}
+
 /// This function verifies the batch of zk-proofs
 ///     proofs: vector of Plonk proofs
 ///     RETURN: verification status
@@ -1234,6 +1466,7 @@ where
     {
         batch.push(to_batch::<G, EFqSponge, EFrSponge, OpeningProof>(
             verifier_index,
+            verifier_index.digest::<EFqSponge>(),
             proof,
             public_input,
         )?);
@@ -1246,3 +1479,56 @@ where
         Err(VerifyError::OpenProof)
     }
 }
+
+/// Verifies a batch of proofs `proofs`, given with their public input, that
+/// were all created for the circuit of the verifier index `verifier_index`.
+///
+/// The digest of the verifier index is computed once for the whole batch, and
+/// the openings of the proofs are checked together, in a single
+/// multi-scalar multiplication whose terms are combined with random scalars.
+/// This is cheaper than verifying each proof on its own, or than
+/// [`batch_verify`] when all the proofs share the same index.
+///
+/// # Errors
+///
+/// Will give error if one of the proofs is not valid.
+#[allow(clippy::type_complexity)]
+pub fn batch_verify_same_index<G, EFqSponge, EFrSponge, OpeningProof: OpenProof<G>>(
+    group_map: &G::Map,
+    verifier_index: &VerifierIndex<G, OpeningProof>,
+    proofs: &[(&ProverProof<G, OpeningProof>, &[G::ScalarField])],
+) -> Result<()>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+{
+    if proofs.is_empty() {
+        return Ok(());
+    }
+
+    let verifier_index_digest = verifier_index.digest::<EFqSponge>();
+    let mut batch = proofs
+        .iter()
+        .map(|(proof, public_input)| {
+            to_batch::<G, EFqSponge, EFrSponge, OpeningProof>(
+                verifier_index,
+                verifier_index_digest,
+                proof,
+                public_input,
+            )
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if OpeningProof::verify(
+        verifier_index.srs(),
+        group_map,
+        &mut batch,
+        &mut thread_rng(),
+    ) {
+        Ok(())
+    } else {
+        Err(VerifyError::OpenProof)
+    }
+}
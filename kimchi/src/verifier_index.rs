@@ -5,16 +5,20 @@ use crate::{
     alphas::Alphas,
     circuits::{
         berkeley_columns::{BerkeleyChallengeTerm, Column},
+        constraints::{num_chunks, FeatureFlags, ZkLevel},
         expr::{Linearization, PolishToken},
         lookup::{index::LookupSelectors, lookups::LookupInfo},
-        polynomials::permutation::{vanishes_on_last_n_rows, zk_w},
+        polynomials::permutation::{vanishes_on_last_n_rows, zk_w, Shifts},
         wires::{COLUMNS, PERMUTS},
     },
     curve::KimchiCurve,
+    error::VerifierKeyError,
+    linearization::expr_linearization,
     prover_index::ProverIndex,
 };
 use ark_ff::{One, PrimeField};
-use ark_poly::{univariate::DensePolynomial, Radix2EvaluationDomain as D};
+use ark_poly::{univariate::DensePolynomial, EvaluationDomain, Radix2EvaluationDomain as D};
+use blake2::{Blake2s256, Digest};
 use core::array;
 use mina_poseidon::FqSponge;
 use once_cell::sync::OnceCell;
@@ -127,6 +131,18 @@ pub struct VerifierIndex<G: KimchiCurve, OpeningProof: OpenProof<G>> {
     #[serde(bound = "Option<PolyComm<G>>: Serialize + DeserializeOwned")]
     pub rot_comm: Option<PolyComm<G>>,
 
+    /// Cairo gates polynomial commitments, in the order claim, instruction,
+    /// flags and transition
+    #[serde(default)]
+    #[serde(bound = "Option<[PolyComm<G>; 4]>: Serialize + DeserializeOwned")]
+    pub cairo_comms: Option<[PolyComm<G>; 4]>,
+
+    /// Poseidon2 gates polynomial commitments, in the order external and
+    /// internal rounds
+    #[serde(default)]
+    #[serde(bound = "Option<[PolyComm<G>; 2]>: Serialize + DeserializeOwned")]
+    pub poseidon2_comms: Option<[PolyComm<G>; 2]>,
+
     /// wire coordinate shifts
     #[serde_as(as = "[o1_utils::serialization::SerdeAs; PERMUTS]")]
     pub shift: [G::ScalarField; PERMUTS],
@@ -150,14 +166,88 @@ pub struct VerifierIndex<G: KimchiCurve, OpeningProof: OpenProof<G>> {
     /// The mapping between powers of alpha and constraints
     #[serde(skip)]
     pub powers_of_alpha: Alphas<G::ScalarField>,
+
+    /// The zero-knowledge level of the proofs, see [`ZkLevel`]
+    #[serde(default)]
+    pub zk_level: ZkLevel,
 }
 //~spec:endcode
 
+/// The magic bytes starting the binary form of a [`CompactVerifierIndex`]
+pub const VERIFIER_KEY_MAGIC: [u8; 4] = *b"KVRK";
+
+/// The version of the binary form of a [`CompactVerifierIndex`], increased on
+/// each change of its fields
+pub const VERIFIER_KEY_FORMAT_VERSION: u32 = 2;
+
+/// The minimal form of a [`VerifierIndex`], holding its commitments, the size
+/// of its domain, its feature flags and the few sizes of its circuit.
+///
+/// The other fields of the verifier index are derived from these and from the
+/// SRS by [`VerifierIndex::from_compact`]. Its binary form and its digests,
+/// see [`Self::digest`] and [`Self::blake2_digest`], are suitable to register
+/// a circuit outside of this library.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
+pub struct CompactVerifierIndex<G: KimchiCurve> {
+    /// size of the evaluation domain
+    pub domain_size: u64,
+    /// maximal size of polynomial section
+    pub max_poly_size: usize,
+    /// the number of randomized rows to achieve zero knowledge
+    pub zk_rows: u64,
+    /// the zero-knowledge level of the proofs
+    pub zk_level: ZkLevel,
+    /// number of public inputs
+    pub public: usize,
+    /// number of previous evaluation challenges, for recursive proving
+    pub prev_challenges: usize,
+    /// the optional gates and lookups of the circuit
+    pub feature_flags: FeatureFlags,
+
+    /// permutation commitment array
+    pub sigma_comm: [PolyComm<G>; PERMUTS],
+    /// coefficient commitment array
+    pub coefficients_comm: [PolyComm<G>; COLUMNS],
+    /// generic gate commitment array
+    pub generic_comm: PolyComm<G>,
+    /// poseidon constraint selector polynomial commitment
+    pub psm_comm: PolyComm<G>,
+    /// EC addition selector polynomial commitment
+    pub complete_add_comm: PolyComm<G>,
+    /// EC variable base scalar multiplication selector polynomial commitment
+    pub mul_comm: PolyComm<G>,
+    /// endoscalar multiplication selector polynomial commitment
+    pub emul_comm: PolyComm<G>,
+    /// endoscalar multiplication scalar computation selector polynomial commitment
+    pub endomul_scalar_comm: PolyComm<G>,
+    /// RangeCheck0 polynomial commitments
+    pub range_check0_comm: Option<PolyComm<G>>,
+    /// RangeCheck1 polynomial commitments
+    pub range_check1_comm: Option<PolyComm<G>>,
+    /// Foreign field addition gates polynomial commitments
+    pub foreign_field_add_comm: Option<PolyComm<G>>,
+    /// Foreign field multiplication gates polynomial commitments
+    pub foreign_field_mul_comm: Option<PolyComm<G>>,
+    /// Xor commitments
+    pub xor_comm: Option<PolyComm<G>>,
+    /// Rot commitments
+    pub rot_comm: Option<PolyComm<G>>,
+    /// Cairo gates polynomial commitments
+    pub cairo_comms: Option<[PolyComm<G>; 4]>,
+    /// Poseidon2 gates polynomial commitments
+    pub poseidon2_comms: Option<[PolyComm<G>; 2]>,
+    /// lookup commitments and configuration
+    pub lookup_index: Option<LookupVerifierIndex<G>>,
+}
+
 impl<G: KimchiCurve, OpeningProof: OpenProof<G>> ProverIndex<G, OpeningProof>
 where
     G::BaseField: PrimeField,
 {
-    /// Produces the [`VerifierIndex`] from the prover's [`ProverIndex`].
+    /// Produces the [`VerifierIndex`] from the prover's [`ProverIndex`]. Unless
+    /// the field `verifier_index` of the prover index is set, it is computed
+    /// on first use and shared by the clones of the prover index.
     ///
     /// # Panics
     ///
@@ -166,10 +256,15 @@ where
     where
         VerifierIndex<G, OpeningProof>: Clone,
     {
-        if let Some(verifier_index) = &self.verifier_index {
-            return verifier_index.clone();
-        }
+        self.cached_verifier_index().clone()
+    }
 
+    /// Computes the [`VerifierIndex`] from the prover's [`ProverIndex`].
+    ///
+    /// The evaluations of the verifier index that depend on the domain
+    /// precomputations are only set if these have already been computed, and
+    /// are otherwise computed on first use.
+    pub(crate) fn create_verifier_index(&self) -> VerifierIndex<G, OpeningProof> {
         let mask_fixed = |commitment: PolyComm<G>| {
             let blinders = commitment.map(|_| G::ScalarField::one());
             self.srs
@@ -209,15 +304,18 @@ where
                 })
         };
 
+        // in low memory mode, the column evaluations are computed for the
+        // commitments and dropped with them
+        let index = self.for_proof();
         // Defined as variable for convenience to avoid verbosity
-        let column_evaluations = self.column_evaluations.get();
+        let column_evaluations = index.column_evaluations.get();
 
         // TODO: Switch to commit_evaluations for all index polys
         VerifierIndex {
             domain,
             max_poly_size: self.max_poly_size,
             zk_rows: self.cs.zk_rows,
-            powers_of_alpha: self.powers_of_alpha.clone(),
+            powers_of_alpha: self.powers_of_alpha().clone(),
             public: self.cs.public,
             prev_challenges: self.cs.prev_challenges,
             srs: Arc::clone(&self.srs),
@@ -289,17 +387,27 @@ where
                 .rot_selector8
                 .as_ref()
                 .map(|eval8| self.srs.commit_evaluations_non_hiding(domain, eval8)),
+            cairo_comms: column_evaluations.cairo_selectors8.as_ref().map(|evals8| {
+                evals8
+                    .each_ref()
+                    .map(|eval8| self.srs.commit_evaluations_non_hiding(domain, eval8))
+            }),
+            poseidon2_comms: column_evaluations
+                .poseidon2_selectors8
+                .as_ref()
+                .map(|evals8| {
+                    evals8
+                        .each_ref()
+                        .map(|eval8| self.srs.commit_evaluations_non_hiding(domain, eval8))
+                }),
 
             shift: self.cs.shift,
             permutation_vanishing_polynomial_m: {
                 let cell = OnceCell::new();
-                cell.set(
-                    self.cs
-                        .precomputations()
-                        .permutation_vanishing_polynomial_m
-                        .clone(),
-                )
-                .unwrap();
+                if let Some(precomputations) = self.cs.cached_precomputations() {
+                    cell.set(precomputations.permutation_vanishing_polynomial_m.clone())
+                        .unwrap();
+                }
                 cell
             },
             w: {
@@ -309,7 +417,8 @@ where
             },
             endo: self.cs.endo,
             lookup_index,
-            linearization: self.linearization.clone(),
+            linearization: self.linearization().clone(),
+            zk_level: self.cs.zk_level,
         }
     }
 }
@@ -323,6 +432,13 @@ impl<G: KimchiCurve, OpeningProof: OpenProof<G>> VerifierIndex<G, OpeningProof>
         &self.srs
     }
 
+    /// The number of chunks of the commitments of the polynomials of the
+    /// circuit, and of their evaluations in the proofs: more than one when
+    /// the domain is larger than the SRS
+    pub fn num_chunks(&self) -> usize {
+        num_chunks(self.domain.size(), self.max_poly_size)
+    }
+
     /// Gets permutation_vanishing_polynomial_m from [`VerifierIndex`] lazily
     pub fn permutation_vanishing_polynomial_m(&self) -> &DensePolynomial<G::ScalarField> {
         self.permutation_vanishing_polynomial_m
@@ -396,17 +512,22 @@ impl<G: KimchiCurve, OpeningProof: OpenProof<G>> VerifierIndex<G, OpeningProof>
     pub fn digest<EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>>(
         &self,
     ) -> G::BaseField {
-        let mut fq_sponge = EFqSponge::new(G::other_curve_sponge_params());
+        self.to_compact().digest::<EFqSponge>()
+    }
+
+    /// Returns the [`CompactVerifierIndex`] of the index, whose feature flags
+    /// are the ones of its optional commitments and of its lookup
+    /// configuration
+    pub fn to_compact(&self) -> CompactVerifierIndex<G> {
         // We fully expand this to make the compiler check that we aren't missing any commitments
         let VerifierIndex {
-            domain: _,
-            max_poly_size: _,
-            zk_rows: _,
+            domain,
+            max_poly_size,
+            zk_rows,
             srs: _,
-            public: _,
-            prev_challenges: _,
+            public,
+            prev_challenges,
 
-            // Always present
             sigma_comm,
             coefficients_comm,
             generic_comm,
@@ -416,15 +537,15 @@ impl<G: KimchiCurve, OpeningProof: OpenProof<G>> VerifierIndex<G, OpeningProof>
             emul_comm,
             endomul_scalar_comm,
 
-            // Optional gates
             range_check0_comm,
             range_check1_comm,
             foreign_field_add_comm,
             foreign_field_mul_comm,
             xor_comm,
             rot_comm,
+            cairo_comms,
+            poseidon2_comms,
 
-            // Lookup index; optional
             lookup_index,
 
             shift: _,
@@ -434,7 +555,223 @@ impl<G: KimchiCurve, OpeningProof: OpenProof<G>> VerifierIndex<G, OpeningProof>
 
             linearization: _,
             powers_of_alpha: _,
-        } = &self;
+            zk_level,
+        } = self;
+
+        let feature_flags = FeatureFlags {
+            range_check0: range_check0_comm.is_some(),
+            range_check1: range_check1_comm.is_some(),
+            foreign_field_add: foreign_field_add_comm.is_some(),
+            foreign_field_mul: foreign_field_mul_comm.is_some(),
+            xor: xor_comm.is_some(),
+            rot: rot_comm.is_some(),
+            lookup_features: lookup_index
+                .as_ref()
+                .map(|lookup_index| lookup_index.lookup_info.features)
+                .unwrap_or_default(),
+            cairo: cairo_comms.is_some(),
+            poseidon2: poseidon2_comms.is_some(),
+        };
+
+        CompactVerifierIndex {
+            domain_size: domain.size,
+            max_poly_size: *max_poly_size,
+            zk_rows: *zk_rows,
+            zk_level: *zk_level,
+            public: *public,
+            prev_challenges: *prev_challenges,
+            feature_flags,
+            sigma_comm: sigma_comm.clone(),
+            coefficients_comm: coefficients_comm.clone(),
+            generic_comm: generic_comm.clone(),
+            psm_comm: psm_comm.clone(),
+            complete_add_comm: complete_add_comm.clone(),
+            mul_comm: mul_comm.clone(),
+            emul_comm: emul_comm.clone(),
+            endomul_scalar_comm: endomul_scalar_comm.clone(),
+            range_check0_comm: range_check0_comm.clone(),
+            range_check1_comm: range_check1_comm.clone(),
+            foreign_field_add_comm: foreign_field_add_comm.clone(),
+            foreign_field_mul_comm: foreign_field_mul_comm.clone(),
+            xor_comm: xor_comm.clone(),
+            rot_comm: rot_comm.clone(),
+            cairo_comms: cairo_comms.clone(),
+            poseidon2_comms: poseidon2_comms.clone(),
+            lookup_index: lookup_index.clone(),
+        }
+    }
+
+    /// Reconstructs the [`VerifierIndex`] of a [`CompactVerifierIndex`], with
+    /// the SRS `srs`. Its shifts, linearization and endoscalar coefficient are
+    /// computed again.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the domain size of `compact` is not a power of two
+    /// supported by the field, or if `srs` does not have its maximal
+    /// polynomial size.
+    pub fn from_compact(
+        compact: CompactVerifierIndex<G>,
+        srs: Arc<OpeningProof::SRS>,
+    ) -> Result<Self, VerifierKeyError> {
+        let CompactVerifierIndex {
+            domain_size,
+            max_poly_size,
+            zk_rows,
+            zk_level,
+            public,
+            prev_challenges,
+            feature_flags,
+            sigma_comm,
+            coefficients_comm,
+            generic_comm,
+            psm_comm,
+            complete_add_comm,
+            mul_comm,
+            emul_comm,
+            endomul_scalar_comm,
+            range_check0_comm,
+            range_check1_comm,
+            foreign_field_add_comm,
+            foreign_field_mul_comm,
+            xor_comm,
+            rot_comm,
+            cairo_comms,
+            poseidon2_comms,
+            lookup_index,
+        } = compact;
+
+        let domain = usize::try_from(domain_size)
+            .ok()
+            .and_then(D::<G::ScalarField>::new)
+            .filter(|domain| domain.size == domain_size)
+            .ok_or(VerifierKeyError::InvalidDomain(domain_size))?;
+        if srs.max_poly_size() != max_poly_size {
+            return Err(VerifierKeyError::SrsMismatch(
+                max_poly_size,
+                srs.max_poly_size(),
+            ));
+        }
+        let (linearization, powers_of_alpha) = expr_linearization(Some(&feature_flags), true);
+
+        Ok(VerifierIndex {
+            domain,
+            max_poly_size,
+            zk_rows,
+            srs,
+            public,
+            prev_challenges,
+            sigma_comm,
+            coefficients_comm,
+            generic_comm,
+            psm_comm,
+            complete_add_comm,
+            mul_comm,
+            emul_comm,
+            endomul_scalar_comm,
+            range_check0_comm,
+            range_check1_comm,
+            foreign_field_add_comm,
+            foreign_field_mul_comm,
+            xor_comm,
+            rot_comm,
+            cairo_comms,
+            poseidon2_comms,
+            shift: *Shifts::new(&domain).shifts(),
+            permutation_vanishing_polynomial_m: OnceCell::new(),
+            w: OnceCell::new(),
+            endo: *G::other_curve_endo(),
+            lookup_index,
+            linearization,
+            powers_of_alpha,
+            zk_level,
+        })
+    }
+}
+
+impl<G: KimchiCurve> CompactVerifierIndex<G> {
+    /// Writes the binary form of the index, made of the magic bytes
+    /// [`VERIFIER_KEY_MAGIC`], the version [`VERIFIER_KEY_FORMAT_VERSION`] as
+    /// a little-endian `u32`, and the MessagePack encoding of the index
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the index fails to be encoded.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, VerifierKeyError> {
+        let mut bytes = VERIFIER_KEY_MAGIC.to_vec();
+        bytes.extend(VERIFIER_KEY_FORMAT_VERSION.to_le_bytes());
+        rmp_serde::encode::write(&mut bytes, self)
+            .map_err(|e| VerifierKeyError::Serialization(e.to_string()))?;
+        Ok(bytes)
+    }
+
+    /// Reads an index from its binary form, see [`Self::to_bytes`]
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `bytes` does not start with [`VERIFIER_KEY_MAGIC`],
+    /// if its version is not [`VERIFIER_KEY_FORMAT_VERSION`], or if the index
+    /// fails to be decoded.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, VerifierKeyError> {
+        let (version, bytes) = bytes
+            .strip_prefix(&VERIFIER_KEY_MAGIC)
+            .and_then(|bytes| bytes.split_first_chunk::<4>())
+            .ok_or(VerifierKeyError::InvalidMagic)?;
+        let version = u32::from_le_bytes(*version);
+        if version != VERIFIER_KEY_FORMAT_VERSION {
+            return Err(VerifierKeyError::UnsupportedVersion(version));
+        }
+        rmp_serde::from_slice(bytes).map_err(|e| VerifierKeyError::Serialization(e.to_string()))
+    }
+
+    /// The BLAKE2s digest of the binary form of the index
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the index fails to be encoded.
+    pub fn blake2_digest(&self) -> Result<[u8; 32], VerifierKeyError> {
+        Ok(Blake2s256::digest(self.to_bytes()?).into())
+    }
+
+    /// The Poseidon digest of the commitments of the index, equal to
+    /// [`VerifierIndex::digest`]
+    pub fn digest<EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>>(
+        &self,
+    ) -> G::BaseField {
+        let CompactVerifierIndex {
+            domain_size: _,
+            max_poly_size: _,
+            zk_rows: _,
+            zk_level: _,
+            public: _,
+            prev_challenges: _,
+            feature_flags: _,
+
+            // Always present
+            sigma_comm,
+            coefficients_comm,
+            generic_comm,
+            psm_comm,
+            complete_add_comm,
+            mul_comm,
+            emul_comm,
+            endomul_scalar_comm,
+
+            // Optional gates
+            range_check0_comm,
+            range_check1_comm,
+            foreign_field_add_comm,
+            foreign_field_mul_comm,
+            xor_comm,
+            rot_comm,
+            cairo_comms,
+            poseidon2_comms,
+
+            // Lookup index; optional
+            lookup_index,
+        } = self;
+
+        let mut fq_sponge = EFqSponge::new(G::other_curve_sponge_params());
 
         // Always present
 
@@ -477,6 +814,18 @@ impl<G: KimchiCurve, OpeningProof: OpenProof<G>> VerifierIndex<G, OpeningProof>
             absorb_commitment(&mut fq_sponge, rot_comm);
         }
 
+        if let Some(cairo_comms) = cairo_comms {
+            for comm in cairo_comms {
+                absorb_commitment(&mut fq_sponge, comm);
+            }
+        }
+
+        if let Some(poseidon2_comms) = poseidon2_comms {
+            for comm in poseidon2_comms {
+                absorb_commitment(&mut fq_sponge, comm);
+            }
+        }
+
         // Lookup index; optional
 
         if let Some(LookupVerifierIndex {
@@ -492,6 +841,7 @@ impl<G: KimchiCurve, OpeningProof: OpenProof<G>> VerifierIndex<G, OpeningProof>
                     lookup,
                     range_check,
                     ffmul,
+                    vector_lookup,
                 },
         }) = lookup_index
         {
@@ -517,6 +867,9 @@ impl<G: KimchiCurve, OpeningProof: OpenProof<G>> VerifierIndex<G, OpeningProof>
             if let Some(ffmul) = ffmul {
                 absorb_commitment(&mut fq_sponge, ffmul);
             }
+            if let Some(vector_lookup) = vector_lookup {
+                absorb_commitment(&mut fq_sponge, vector_lookup);
+            }
         }
         fq_sponge.digest_fq()
     }
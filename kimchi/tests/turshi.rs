@@ -1,9 +1,28 @@
-use kimchi::circuits::{
-    gate::CircuitGate,
-    polynomials::turshi::{testing::*, witness::*},
+use groupmap::GroupMap;
+use kimchi::{
+    cairo::{cairo_constraint_system, prove_cairo, verify_cairo, CairoStatement},
+    circuits::{
+        gate::CircuitGate,
+        polynomials::turshi::{testing::*, witness::*, CLAIM_PUBLIC_INPUTS},
+    },
+    curve::KimchiCurve,
+    prover_index::ProverIndex,
 };
-use mina_curves::pasta::Fp as F;
-use turshi::{CairoMemory, CairoProgram};
+use mina_curves::pasta::{Fp as F, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use poly_commitment::{
+    commitment::CommitmentCurve,
+    ipa::{OpeningProof, SRS},
+    SRS as _,
+};
+use std::sync::Arc;
+use turshi::{CairoMemory, CairoProgram, Output};
+
+type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+type ScalarSponge = DefaultFrSponge<F, PlonkSpongeConstantsKimchi>;
 
 #[test]
 fn test_cairo_should_fail() {
@@ -80,3 +99,108 @@ fn test_cairo_gate() {
         assert_eq!(Ok(()), res_ensure);
     }
 }
+
+// Memory of a program writing 10, 20 and 410 to its output segment at 41
+fn output_program_memory() -> CairoMemory<F> {
+    let instrs = [
+        0x400380007ffc7ffd,
+        0x482680017ffc8000,
+        1,
+        0x208b7fff7fff7ffe,
+        0x480680017fff8000,
+        10,
+        0x48307fff7fff8000,
+        0x48507fff7fff8000,
+        0x48307ffd7fff8000,
+        0x480a7ffd7fff8000,
+        0x48127ffb7fff8000,
+        0x1104800180018000,
+        -11,
+        0x48127ff87fff8000,
+        0x1104800180018000,
+        -14,
+        0x48127ff67fff8000,
+        0x1104800180018000,
+        -17,
+        0x208b7fff7fff7ffe,
+    ]
+    .iter()
+    .map(|&i: &i64| F::from(i))
+    .collect();
+
+    let mut mem = CairoMemory::<F>::new(instrs);
+    mem.write(F::from(21u32), F::from(41u32)); // beginning of outputs
+    mem.write(F::from(22u32), F::from(44u32)); // end of outputs
+    mem.write(F::from(23u32), F::from(44u32)); //end of program
+    mem.add_builtin(41, Output);
+    mem
+}
+
+#[test]
+fn test_cairo_public_input() {
+    let mut mem = output_program_memory();
+    let prog = CairoProgram::new(&mut mem, 5);
+
+    let public = cairo_public_input(&prog);
+    assert_eq!(public.len(), CLAIM_PUBLIC_INPUTS);
+    assert_eq!(CairoStatement::new(&prog).public_input(), public);
+
+    let circuit = CircuitGate::<F>::create_cairo_circuit(prog.trace().len());
+    let witness = cairo_witness_with_public_input(&prog);
+    assert_eq!(witness[0][..public.len()], public);
+
+    // the claimed pointers are copied to the claim
+    for (row, gate) in circuit.iter().enumerate().take(CLAIM_PUBLIC_INPUTS) {
+        let wire = gate.wires[0];
+        assert_eq!(witness[wire.col][wire.row], witness[0][row]);
+    }
+
+    // Verify each gate of the execution
+    for (row, gate) in circuit.iter().enumerate().skip(public.len()) {
+        let res_ensure = ensure_cairo_gate(gate, row, &witness);
+        assert_eq!(Ok(()), res_ensure);
+    }
+}
+
+#[test]
+fn test_prove_and_verify_cairo() {
+    let mut mem = output_program_memory();
+    let prog = CairoProgram::new(&mut mem, 5);
+
+    let cs = cairo_constraint_system(prog.trace().len()).unwrap();
+    let srs = SRS::<Vesta>::create(cs.domain.d1.size as usize);
+    srs.get_lagrange_basis(cs.domain.d1);
+    let &endo_q = Vesta::other_curve_endo();
+    let index = ProverIndex::<Vesta, OpeningProof<Vesta>>::create(cs, endo_q, Arc::new(srs), false);
+    let verifier_index = index.verifier_index();
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+
+    let (proof, statement) = prove_cairo::<Vesta, BaseSponge, ScalarSponge, _, _>(
+        &group_map,
+        &index,
+        &prog,
+        &mut rand::rngs::OsRng,
+    )
+    .unwrap();
+    assert_eq!(statement, CairoStatement::new(&prog));
+    assert!(verify_cairo::<Vesta, BaseSponge, ScalarSponge, _>(
+        &group_map,
+        &verifier_index,
+        &statement,
+        &proof
+    )
+    .is_ok());
+
+    // the proof does not hold for other pointers
+    for i in 0..CLAIM_PUBLIC_INPUTS {
+        let mut other = statement.clone();
+        other.pointers[i] += F::from(1u32);
+        assert!(verify_cairo::<Vesta, BaseSponge, ScalarSponge, _>(
+            &group_map,
+            &verifier_index,
+            &other,
+            &proof
+        )
+        .is_err());
+    }
+}
@@ -9,13 +9,17 @@ use core::{
 use libflate::zlib::{Decoder, Encoder};
 use regex::Regex;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::io::{Read, Write};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
 
 pub const PAGE_ADDRESS_SIZE: u32 = 12;
 pub const PAGE_SIZE: u32 = 1 << PAGE_ADDRESS_SIZE;
 pub const PAGE_ADDRESS_MASK: u32 = PAGE_SIZE - 1;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Page {
     pub index: u32,
     #[serde(deserialize_with = "from_base64", serialize_with = "to_base64")]
@@ -48,7 +52,7 @@ where
 }
 
 // The renaming below keeps compatibility with OP Cannon's state format
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct State {
     pub memory: Vec<Page>,
     #[serde(
@@ -69,10 +73,235 @@ pub struct State {
     pub exited: bool,
     pub step: u64,
     pub registers: [u32; 32],
+    #[serde(
+        rename = "lastHint",
+        alias = "last_hint",
+        default,
+        deserialize_with = "deserialize_last_hint",
+        serialize_with = "serialize_last_hint"
+    )]
     pub last_hint: Option<Vec<u8>>,
+    /// The preimage being read, which is not part of the state of Cannon
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub preimage: Option<Vec<u8>>,
 }
 
+/// The last hint is written by Cannon as a hexadecimal string, and was written
+/// by previous versions of the o1vm as an array of bytes
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LastHint {
+    Hex(String),
+    Bytes(Vec<u8>),
+}
+
+fn deserialize_last_hint<'de, D>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<LastHint>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(LastHint::Bytes(bytes)) => Ok(Some(bytes)),
+        Some(LastHint::Hex(s)) => hex::decode(s.trim_start_matches("0x"))
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+    }
+}
+
+fn serialize_last_hint<S>(v: &Option<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match v {
+        None => serializer.serialize_none(),
+        Some(bytes) => serializer.serialize_str(&format!("0x{}", hex::encode(bytes))),
+    }
+}
+
+/// The version of the binary state of the single-threaded Cannon virtual
+/// machine, which is the first byte of the state
+pub const CANNON_STATE_VERSION: u8 = 0;
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(e: E) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> std::io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_be_bytes(bytes))
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> std::io::Result<u8> {
+    let mut byte = [0u8];
+    reader.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+impl State {
+    /// Writes the state in the binary format of Cannon, made of the following
+    /// fields, the integers being encoded in big endian:
+    ///
+    /// | Field                          | Encoding                      |
+    /// |--------------------------------|-------------------------------|
+    /// | version                        | u8, [CANNON_STATE_VERSION]    |
+    /// | number of pages                | u32                           |
+    /// | for each page: index and data  | u32, [PAGE_SIZE] bytes        |
+    /// | preimage key                   | 32 bytes                      |
+    /// | preimage offset                | u32                           |
+    /// | pc, next pc, lo, hi, heap      | u32 each                      |
+    /// | exit code                      | u8                            |
+    /// | exited                         | u8, 0 or 1                    |
+    /// | step                           | u64                           |
+    /// | registers                      | 32 u32                        |
+    /// | last hint                      | u32 length followed by bytes  |
+    ///
+    /// The preimage being read is not part of the binary state.
+    pub fn write_binary<W: Write + ?Sized>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&[CANNON_STATE_VERSION])?;
+        writer.write_all(&(self.memory.len() as u32).to_be_bytes())?;
+        for page in self.memory.iter() {
+            if page.data.len() != PAGE_SIZE as usize {
+                return Err(invalid_data(format!(
+                    "the page {} is not of {PAGE_SIZE} bytes",
+                    page.index
+                )));
+            }
+            writer.write_all(&page.index.to_be_bytes())?;
+            writer.write_all(&page.data)?;
+        }
+        writer.write_all(&self.preimage_key)?;
+        for x in [
+            self.preimage_offset,
+            self.pc,
+            self.next_pc,
+            self.lo,
+            self.hi,
+            self.heap,
+        ] {
+            writer.write_all(&x.to_be_bytes())?;
+        }
+        writer.write_all(&[self.exit, self.exited as u8])?;
+        writer.write_all(&self.step.to_be_bytes())?;
+        for register in self.registers.iter() {
+            writer.write_all(&register.to_be_bytes())?;
+        }
+        let last_hint = self.last_hint.as_deref().unwrap_or_default();
+        writer.write_all(&(last_hint.len() as u32).to_be_bytes())?;
+        writer.write_all(last_hint)
+    }
+
+    /// Reads a state written in the binary format of Cannon, see
+    /// [Self::write_binary]
+    pub fn read_binary<R: Read>(reader: &mut R) -> std::io::Result<Self> {
+        let version = read_u8(reader)?;
+        if version != CANNON_STATE_VERSION {
+            return Err(invalid_data(format!(
+                "unsupported version {version} of the Cannon state"
+            )));
+        }
+        let num_pages = read_u32(reader)?;
+        let memory = (0..num_pages)
+            .map(|_| {
+                let index = read_u32(reader)?;
+                let mut data = vec![0u8; PAGE_SIZE as usize];
+                reader.read_exact(&mut data)?;
+                Ok(Page { index, data })
+            })
+            .collect::<std::io::Result<_>>()?;
+        let mut preimage_key = [0u8; 32];
+        reader.read_exact(&mut preimage_key)?;
+        let preimage_offset = read_u32(reader)?;
+        let pc = read_u32(reader)?;
+        let next_pc = read_u32(reader)?;
+        let lo = read_u32(reader)?;
+        let hi = read_u32(reader)?;
+        let heap = read_u32(reader)?;
+        let exit = read_u8(reader)?;
+        let exited = match read_u8(reader)? {
+            0 => false,
+            1 => true,
+            b => return Err(invalid_data(format!("invalid boolean {b}"))),
+        };
+        let mut step = [0u8; 8];
+        reader.read_exact(&mut step)?;
+        let mut registers = [0u32; 32];
+        for register in registers.iter_mut() {
+            *register = read_u32(reader)?;
+        }
+        let last_hint_len = read_u32(reader)?;
+        let mut last_hint = vec![0u8; last_hint_len as usize];
+        reader.read_exact(&mut last_hint)?;
+        Ok(State {
+            memory,
+            preimage_key,
+            preimage_offset,
+            pc,
+            next_pc,
+            lo,
+            hi,
+            heap,
+            exit,
+            exited,
+            step: u64::from_be_bytes(step),
+            registers,
+            last_hint: (last_hint_len > 0).then_some(last_hint),
+            preimage: None,
+        })
+    }
+
+    /// Reads a state from the file `path`, in JSON if its extension is
+    /// `.json` and in the binary format otherwise, gzip-compressed if the
+    /// extension is followed by `.gz`, as written by Cannon
+    pub fn read_file(path: &Path) -> std::io::Result<Self> {
+        let file = BufReader::new(File::open(path)?);
+        let (mut reader, json): (Box<dyn Read>, _) = match state_file_format(path) {
+            (json, true) => (Box::new(libflate::gzip::Decoder::new(file)?), json),
+            (json, false) => (Box::new(file), json),
+        };
+        if json {
+            serde_json::from_reader(reader).map_err(invalid_data)
+        } else {
+            Self::read_binary(&mut reader)
+        }
+    }
+
+    /// Writes the state to the file `path`, in the format given by its
+    /// extension, see [Self::read_file]
+    pub fn write_file(&self, path: &Path) -> std::io::Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+        let (json, gzip) = state_file_format(path);
+        let write = |writer: &mut dyn Write| -> std::io::Result<()> {
+            if json {
+                serde_json::to_writer(&mut *writer, self).map_err(invalid_data)
+            } else {
+                self.write_binary(writer)
+            }
+        };
+        if gzip {
+            let mut encoder = libflate::gzip::Encoder::new(file)?;
+            write(&mut encoder)?;
+            encoder.finish().into_result()?.flush()
+        } else {
+            let mut file = file;
+            write(&mut file)?;
+            file.flush()
+        }
+    }
+}
+
+/// Returns whether the state file `path` is in JSON, and whether it is
+/// compressed, from its extension
+fn state_file_format(path: &Path) -> (bool, bool) {
+    let gzip = path.extension().is_some_and(|ext| ext == "gz");
+    let path = if gzip {
+        Path::new(path.file_stem().unwrap_or_default())
+    } else {
+        path
+    };
+    (path.extension().is_some_and(|ext| ext == "json"), gzip)
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct ParsePreimageKeyError(String);
 
@@ -218,6 +447,9 @@ pub struct VmConfiguration {
     pub stop_at: StepFrequency,
     pub snapshot_state_at: StepFrequency,
     pub info_at: StepFrequency,
+    /// The steps at which the commitment to the state is computed, see
+    /// [crate::pickles::chunk::StateCommitment]
+    pub commit_state_at: StepFrequency,
     pub proof_fmt: String,
     pub snapshot_fmt: String,
     pub pprof_cpu: bool,
@@ -235,6 +467,7 @@ impl Default for VmConfiguration {
             stop_at: StepFrequency::Never,
             snapshot_state_at: StepFrequency::Never,
             info_at: StepFrequency::Never,
+            commit_state_at: StepFrequency::Never,
             proof_fmt: "proof-%d.json".to_string(),
             snapshot_fmt: "state-%d.json".to_string(),
             pprof_cpu: false,
@@ -512,4 +745,69 @@ mod tests {
         );
         assert!(PreimageKey::from_str("0x01").is_err());
     }
+
+    fn sample_state() -> State {
+        let mut data = vec![0u8; PAGE_SIZE as usize];
+        data[..4].copy_from_slice(&[0x24, 0x02, 0x10, 0x96]);
+        State {
+            memory: vec![Page { index: 3, data }],
+            preimage_key: [7; 32],
+            preimage_offset: 8,
+            pc: 0x3000,
+            next_pc: 0x3004,
+            lo: 1,
+            hi: 2,
+            heap: 0x4000_0000,
+            exit: 5,
+            exited: true,
+            step: 1 << 40,
+            registers: std::array::from_fn(|i| i as u32),
+            last_hint: Some(vec![0, 0, 0, 1, 42]),
+            preimage: None,
+        }
+    }
+
+    #[test]
+    fn test_binary_state_round_trip() {
+        let state = sample_state();
+        let mut bytes = vec![];
+        state.write_binary(&mut bytes).unwrap();
+        assert_eq!(bytes[0], CANNON_STATE_VERSION);
+        assert_eq!(
+            bytes.len(),
+            1 + 4 + 4 + 4096 + 32 + 6 * 4 + 2 + 8 + 32 * 4 + 4 + 5
+        );
+        assert_eq!(State::read_binary(&mut bytes.as_slice()).unwrap(), state);
+
+        bytes[0] = 1;
+        assert!(State::read_binary(&mut bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_state_json_last_hint() {
+        let state = sample_state();
+        let json = serde_json::to_value(&state).unwrap();
+        assert_eq!(json["lastHint"], "0x000000012a");
+        assert!(json.get("preimage").is_none());
+
+        // The states written with the last hint as an array of bytes can
+        // still be read
+        let mut json = json;
+        json.as_object_mut().unwrap().remove("lastHint");
+        json["last_hint"] = serde_json::json!([0, 0, 0, 1, 42]);
+        assert_eq!(serde_json::from_value::<State>(json).unwrap(), state);
+    }
+
+    #[test]
+    fn test_state_files() {
+        let state = sample_state();
+        let dir = std::env::temp_dir();
+        for name in ["state.json", "state.json.gz", "state.bin", "state.bin.gz"] {
+            let path = dir.join(format!("o1vm-{}-{name}", std::process::id()));
+            state.write_file(&path).unwrap();
+            let read = State::read_file(&path);
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(read.unwrap(), state, "{name}");
+        }
+    }
 }
@@ -7,7 +7,7 @@ pub struct MipsVmConfigurationArgs {
         long,
         value_name = "FILE",
         default_value = "state.json",
-        help = "initial state file"
+        help = "initial state file, in JSON (.json) or in the binary format of Cannon, optionally gzipped (.gz)"
     )]
     input: String,
 
@@ -15,7 +15,7 @@ pub struct MipsVmConfigurationArgs {
         long,
         value_name = "FILE",
         default_value = "out.json",
-        help = "output state file"
+        help = "output state file, in the format given by its extension"
     )]
     output: String,
 
@@ -51,6 +51,14 @@ pub struct MipsVmConfigurationArgs {
     #[arg(long = "info-at", value_name = "FREQ", default_value = "never")]
     info_at: StepFrequency,
 
+    #[arg(
+        long = "commit-state-at",
+        value_name = "FREQ",
+        default_value = "never",
+        help = "log the commitment to the state of the virtual machine, chaining the proofs of the chunks"
+    )]
+    commit_state_at: StepFrequency,
+
     #[arg(long = "pprof.cpu", action = clap::ArgAction::SetTrue)]
     pprof_cpu: bool,
 
@@ -82,6 +90,7 @@ impl From<MipsVmConfigurationArgs> for VmConfiguration {
             stop_at: cfg.stop_at,
             snapshot_state_at: cfg.snapshot_state_at,
             info_at: cfg.info_at,
+            commit_state_at: cfg.commit_state_at,
             proof_fmt: cfg.proof_fmt,
             snapshot_fmt: cfg.snapshot_fmt,
             pprof_cpu: cfg.pprof_cpu,
@@ -101,12 +110,49 @@ impl From<MipsVmConfigurationArgs> for VmConfiguration {
     }
 }
 
+/// The source of the preimages read by the program: the host program given
+/// after `--`, a directory of preimages, or a transcript of a previous run
 #[derive(Parser, Debug, Clone)]
-pub struct RunArgs {
-    #[arg(long = "preimage-db-dir", value_name = "PREIMAGE_DB_DIR")]
+pub struct OracleArgs {
+    #[arg(
+        long = "preimage-db-dir",
+        value_name = "PREIMAGE_DB_DIR",
+        help = "read the preimages from the files <key>.txt of PREIMAGE_DB_DIR when no host program is given"
+    )]
     pub preimage_db_dir: Option<String>,
+    #[arg(
+        long = "record-transcript",
+        value_name = "FILE",
+        help = "write the preimages and hints exchanged with the host to FILE"
+    )]
+    pub record_transcript: Option<String>,
+    #[arg(
+        long = "replay-transcript",
+        value_name = "FILE",
+        conflicts_with = "record_transcript",
+        help = "answer the preimage requests and hints from a transcript written by --record-transcript, instead of the host"
+    )]
+    pub replay_transcript: Option<String>,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct RunArgs {
     #[arg(long = "srs-filepath", value_name = "SRS_CACHE")]
     pub srs_cache: Option<String>,
+    #[arg(
+        long = "packed-selectors",
+        action = clap::ArgAction::SetTrue,
+        help = "commit to the instruction selectors with fewer columns, see SelectorLayout::Packed"
+    )]
+    pub packed_selectors: bool,
+    #[arg(
+        long = "dry-run",
+        value_name = "DOMAIN_SIZE",
+        help = "run the program without proving it, and report the cost of proving it over a domain of size DOMAIN_SIZE"
+    )]
+    pub dry_run: Option<usize>,
+    #[command(flatten)]
+    pub oracle: OracleArgs,
     // it's important that vm_cfg is last in order to properly parse the host field
     #[command(flatten)]
     pub vm_cfg: MipsVmConfigurationArgs,
@@ -126,9 +172,48 @@ pub struct GenStateJsonArgs {
     pub output: String,
 }
 
+#[derive(Parser, Debug, Clone)]
+pub struct ProveArgs {
+    #[arg(
+        long = "artifact",
+        value_name = "FILE",
+        default_value = "proof.bin",
+        help = "output proof artifact file"
+    )]
+    pub artifact: String,
+    // it's important that run is last in order to properly parse the host field
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct VerifyArgs {
+    #[arg(
+        long = "artifact",
+        value_name = "FILE",
+        default_value = "proof.bin",
+        help = "proof artifact file"
+    )]
+    pub artifact: String,
+    #[arg(long = "srs-filepath", value_name = "SRS_CACHE")]
+    pub srs_cache: Option<String>,
+    #[arg(
+        long = "packed-selectors",
+        action = clap::ArgAction::SetTrue,
+        help = "verify proofs made with the packed selectors, see SelectorLayout::Packed"
+    )]
+    pub packed_selectors: bool,
+}
+
 #[derive(Subcommand, Clone, Debug)]
 pub enum Cannon {
     Run(RunArgs),
+    /// Run the program until it halts, and write the proofs of the chunks of
+    /// its execution
+    Prove(ProveArgs),
+    /// Check the proofs of the chunks of an artifact written by `prove`, which
+    /// are not a proof of the execution
+    Verify(VerifyArgs),
     #[command(name = "test-optimism-preimage-read")]
     TestPreimageRead(RunArgs),
     #[command(name = "gen-state-json")]
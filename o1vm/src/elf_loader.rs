@@ -1,112 +1,187 @@
-use crate::cannon::{Page, State, PAGE_SIZE};
+use crate::{
+    cannon::{Page, State, PAGE_SIZE},
+    profiler::Symbols,
+};
 use elf::{
+    abi::{
+        EM_MIPS, EM_RISCV, ET_DYN, PT_LOAD, R_RISCV_NONE, R_RISCV_RELATIVE, SHT_REL, SHT_RELA,
+        STT_FUNC,
+    },
     endian::{BigEndian, EndianParse, LittleEndian},
-    section::SectionHeader,
+    file::Class,
     ElfBytes,
 };
 use log::debug;
-use std::{collections::HashMap, path::Path};
+use std::{collections::BTreeMap, path::Path};
 
 pub enum Architecture {
     Mips,
     RiscV32,
+    RiscV64,
 }
 
-pub fn make_state<T: EndianParse>(file: ElfBytes<T>) -> Result<State, String> {
-    // Checking it is RISC-V
-
-    let (shdrs_opt, strtab_opt) = file
-        .section_headers_with_strtab()
-        .expect("shdrs offsets should be valid");
-    let (shdrs, strtab) = (
-        shdrs_opt.expect("Should have shdrs"),
-        strtab_opt.expect("Should have strtab"),
-    );
-
-    // Parse the shdrs and collect them into a map keyed on their zero-copied name
-    let sections_by_name: HashMap<&str, SectionHeader> = shdrs
-        .iter()
-        .map(|shdr| {
-            (
-                strtab
-                    .get(shdr.sh_name as usize)
-                    .expect("Failed to get section name"),
-                shdr,
-            )
-        })
-        .collect();
+/// The address at which the position-independent executables are loaded,
+/// leaving the first pages unmapped to catch the null pointer dereferences
+pub const PIE_BASE_ADDRESS: u32 = 0x10000;
 
-    debug!("Loading the text section, which contains the executable code.");
-    // Getting the executable code.
-    let text_section = sections_by_name
-        .get(".text")
-        .expect("Should have .text section");
-
-    let (text_section_data, _) = file
-        .section_data(text_section)
-        .expect("Failed to read data from .text section");
-
-    // address of starting instruction in code section
-    let code_section_starting_address = text_section.sh_addr as usize;
-    let code_section_size = text_section.sh_size as usize;
-    // address of last instruction in code section
-    let code_section_end_address = code_section_starting_address + code_section_size - 1;
-    debug!(
-        "The executable code starts at address {}, has size {} bytes, and ends at address {}.",
-        code_section_starting_address, code_section_size, code_section_end_address
-    );
-
-    // Building the memory pages
-    let mut memory: Vec<Page> = vec![];
-    let page_size_usize: usize = PAGE_SIZE.try_into().unwrap();
-    // Padding to get the right number of pages. We suppose that the memory
-    // index starts at 0.
-
-    // the address that the first page starts on
-    let start_page_address: usize =
-        (code_section_starting_address / page_size_usize) * page_size_usize;
-
-    // the address that the last page starts on
-    let end_page_address = (code_section_end_address / page_size_usize) * page_size_usize;
-
-    let first_page_index = start_page_address / page_size_usize;
-
-    let last_page_index = end_page_address / page_size_usize;
-
-    let mut data_offset = 0;
-    (first_page_index..=last_page_index).for_each(|page_index| {
-        let mut data = vec![0; page_size_usize];
-        // Special case where all code fits in one page
-        if first_page_index == last_page_index {
-            let data_length = code_section_end_address - code_section_starting_address;
-            let page_offset = code_section_starting_address - start_page_address;
-            data[page_offset..page_offset + data_length]
-                .copy_from_slice(&text_section_data[0..data_length]);
-            data_offset += data_length;
-        } else {
-            let data_length = if page_index == last_page_index {
-                code_section_end_address - end_page_address
-            } else {
-                page_size_usize
-            };
-            let page_offset = if page_index == first_page_index {
-                code_section_starting_address - start_page_address
-            } else {
-                0
-            };
-            data[page_offset..page_offset + data_length]
-                .copy_from_slice(&text_section_data[data_offset..data_offset + data_length]);
-
-            data_offset += data_length;
+// The relocation types of MIPS, which are not defined by [elf::abi]
+const R_MIPS_NONE: u32 = 0;
+/// Adds the load address to a 32-bit word
+const R_MIPS_REL32: u32 = 3;
+
+/// The memory of the program, by page index
+type Pages = BTreeMap<u32, Vec<u8>>;
+
+/// Writes the bytes `bytes` at the address `address`, allocating the pages
+/// touched
+fn write_bytes(pages: &mut Pages, address: u32, bytes: &[u8]) {
+    let page_size = PAGE_SIZE as usize;
+    for (i, byte) in bytes.iter().enumerate() {
+        let address = address as usize + i;
+        let page = pages
+            .entry((address / page_size) as u32)
+            .or_insert_with(|| vec![0; page_size]);
+        page[address % page_size] = *byte;
+    }
+}
+
+fn read_bytes<const N: usize>(pages: &Pages, address: u32) -> Result<[u8; N], String> {
+    let page_size = PAGE_SIZE as usize;
+    let mut bytes = [0; N];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        let address = address as usize + i;
+        let page = pages
+            .get(&((address / page_size) as u32))
+            .ok_or_else(|| format!("The address {address:#x} is not loaded"))?;
+        *byte = page[address % page_size];
+    }
+    Ok(bytes)
+}
+
+fn to_u32(value: u64, what: &str) -> Result<u32, String> {
+    u32::try_from(value).map_err(|_| format!("The {what} {value:#x} does not fit in 32 bits"))
+}
+
+/// Loads the segments of the program, the bytes of the segment beyond the data
+/// of the file, like the BSS section, being zeroed. The segments do not have
+/// to be contiguous, the pages in between being left unmapped.
+fn load_segments<T: EndianParse>(file: &ElfBytes<T>, base: u32) -> Result<Pages, String> {
+    let segments = file
+        .segments()
+        .ok_or("The ELF file has no program headers")?;
+    let mut pages = Pages::new();
+    for phdr in segments.iter().filter(|phdr| phdr.p_type == PT_LOAD) {
+        let address = base
+            .checked_add(to_u32(phdr.p_vaddr, "segment address")?)
+            .ok_or("The segment does not fit in the address space")?;
+        let data = file
+            .segment_data(&phdr)
+            .map_err(|e| format!("Failed to read the segment data: {e}"))?;
+        debug!(
+            "Loading a segment of {} bytes, {} of them from the file, at address {:#x}",
+            phdr.p_memsz, phdr.p_filesz, address
+        );
+        write_bytes(&mut pages, address, data);
+        let zeroed = phdr.p_memsz.saturating_sub(phdr.p_filesz);
+        if zeroed > 0 {
+            let zeroed_address = address
+                .checked_add(data.len() as u32)
+                .ok_or("The segment does not fit in the address space")?;
+            write_bytes(
+                &mut pages,
+                zeroed_address,
+                &vec![0; to_u32(zeroed, "segment size")? as usize],
+            );
         }
-        let page = Page {
-            index: page_index as u32,
-            data,
+    }
+    Ok(pages)
+}
+
+/// Applies the relocations of the position-independent executable `file`,
+/// loaded at the address `base`. Only the relative relocations, adding the
+/// load address to a word, are supported, as the programs are statically
+/// linked.
+fn apply_relocations<T: EndianParse>(
+    file: &ElfBytes<T>,
+    base: u32,
+    pages: &mut Pages,
+) -> Result<(), String> {
+    let Some(shdrs) = file.section_headers() else {
+        return Ok(());
+    };
+    let little_endian = file.ehdr.endianness.is_little();
+    let write_u32 = |pages: &mut Pages, address: u32, value: u32| {
+        let bytes = if little_endian {
+            value.to_le_bytes()
+        } else {
+            value.to_be_bytes()
         };
-        memory.push(page);
-    });
+        write_bytes(pages, address, &bytes)
+    };
+    for shdr in shdrs.iter() {
+        match (file.ehdr.e_machine, shdr.sh_type) {
+            (EM_RISCV, SHT_RELA) => {
+                let relas = file
+                    .section_data_as_relas(&shdr)
+                    .map_err(|e| format!("Failed to read the relocations: {e}"))?;
+                for rela in relas {
+                    match rela.r_type {
+                        R_RISCV_NONE => (),
+                        R_RISCV_RELATIVE => {
+                            let address = base.wrapping_add(to_u32(rela.r_offset, "offset")?);
+                            write_u32(pages, address, base.wrapping_add(rela.r_addend as u32));
+                        }
+                        r_type => return Err(format!("Unsupported relocation type {r_type}")),
+                    }
+                }
+            }
+            (EM_MIPS, SHT_REL) => {
+                let rels = file
+                    .section_data_as_rels(&shdr)
+                    .map_err(|e| format!("Failed to read the relocations: {e}"))?;
+                for rel in rels {
+                    match rel.r_type {
+                        R_MIPS_NONE => (),
+                        // The relocations against a symbol would need the
+                        // dynamic symbol table
+                        R_MIPS_REL32 if rel.r_sym == 0 => {
+                            // The addend is the word being relocated
+                            let address = base.wrapping_add(to_u32(rel.r_offset, "offset")?);
+                            let bytes = read_bytes(pages, address)?;
+                            let addend = if little_endian {
+                                u32::from_le_bytes(bytes)
+                            } else {
+                                u32::from_be_bytes(bytes)
+                            };
+                            write_u32(pages, address, addend.wrapping_add(base));
+                        }
+                        r_type => return Err(format!("Unsupported relocation type {r_type}")),
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    Ok(())
+}
 
-    // FIXME: add data section into memory for static data saved in the binary
+/// Builds the initial state of the program `file`, loading its segments in
+/// memory. The position-independent executables are loaded at
+/// [PIE_BASE_ADDRESS], and relocated.
+pub fn make_state<T: EndianParse>(file: ElfBytes<T>) -> Result<State, String> {
+    let base = if file.ehdr.e_type == ET_DYN {
+        PIE_BASE_ADDRESS
+    } else {
+        0
+    };
+    let mut pages = load_segments(&file, base)?;
+    if base != 0 {
+        apply_relocations(&file, base, &mut pages)?;
+    }
+    let memory: Vec<Page> = pages
+        .into_iter()
+        .map(|(index, data)| Page { index, data })
+        .collect();
 
     // FIXME: we're lucky that RISCV32i and MIPS have the same number of
     let registers: [u32; 32] = [0; 32];
@@ -117,8 +192,8 @@ pub fn make_state<T: EndianParse>(file: ElfBytes<T>) -> Result<State, String> {
     let preimage_offset = 0;
 
     // Entry point of the program
-    let pc: u32 = file.ehdr.e_entry as u32;
-    assert!(pc != 0, "Entry point is 0. The documentation of the ELF library says that it means the ELF doesn't have an entry point. This is not supported. This can happen if the binary given is an object file and not an executable file. You might need to call the linker (ld) before running the binary.");
+    let pc: u32 = base.wrapping_add(file.ehdr.e_entry as u32);
+    assert!(file.ehdr.e_entry != 0, "Entry point is 0. The documentation of the ELF library says that it means the ELF doesn't have an entry point. This is not supported. This can happen if the binary given is an object file and not an executable file. You might need to call the linker (ld) before running the binary.");
     let next_pc: u32 = pc + 4u32;
 
     let state = State {
@@ -163,5 +238,128 @@ pub fn parse_elf(arch: Architecture, path: &Path) -> Result<State, String> {
             assert_eq!(file.ehdr.e_machine, 243);
             make_state(file)
         }
+        Architecture::RiscV64 => {
+            let file =
+                ElfBytes::<LittleEndian>::minimal_parse(slice).expect("Open ELF file failed.");
+            assert_eq!(file.ehdr.e_machine, 243);
+            assert_eq!(file.ehdr.class, Class::ELF64);
+            // FIXME: the state is shared with the 32 bits VMs, and is extended
+            // to 64 bits when creating the interpreter environment.
+            assert!(
+                file.ehdr.e_entry <= u32::MAX as u64,
+                "The entry point must fit in 32 bits"
+            );
+            make_state(file)
+        }
+    }
+}
+
+fn make_symbols<T: EndianParse>(file: ElfBytes<T>) -> Result<Symbols, String> {
+    let (symbols, strtab) = file
+        .symbol_table()
+        .map_err(|e| format!("Failed to read the symbol table: {e}"))?
+        .ok_or("The ELF file has no symbol table")?;
+    symbols
+        .iter()
+        .filter(|symbol| symbol.st_symtype() == STT_FUNC)
+        .map(|symbol| {
+            let name = strtab
+                .get(symbol.st_name as usize)
+                .map_err(|e| format!("Failed to get symbol name: {e}"))?;
+            Ok((
+                symbol.st_value as u32,
+                symbol.st_size as u32,
+                name.to_string(),
+            ))
+        })
+        .collect::<Result<Vec<_>, String>>()
+        .map(Symbols::new)
+}
+
+/// Returns the functions of the ELF file, used to profile the programs (see
+/// [crate::profiler])
+pub fn parse_symbols(arch: Architecture, path: &Path) -> Result<Symbols, String> {
+    let file_data = std::fs::read(path).map_err(|e| format!("Could not read file: {e}"))?;
+    let slice = file_data.as_slice();
+    match arch {
+        Architecture::Mips => {
+            make_symbols(ElfBytes::<BigEndian>::minimal_parse(slice).map_err(|e| e.to_string())?)
+        }
+        Architecture::RiscV32 | Architecture::RiscV64 => {
+            make_symbols(ElfBytes::<LittleEndian>::minimal_parse(slice).map_err(|e| e.to_string())?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_u16(bytes: &mut Vec<u8>, value: u16) {
+        bytes.extend(value.to_le_bytes())
+    }
+
+    fn push_u32(bytes: &mut Vec<u8>, value: u32) {
+        bytes.extend(value.to_le_bytes())
+    }
+
+    #[test]
+    fn test_load_position_independent_executable() {
+        let mut elf = vec![0x7F, b'E', b'L', b'F', 1, 1, 1];
+        elf.resize(16, 0);
+        push_u16(&mut elf, ET_DYN);
+        push_u16(&mut elf, EM_RISCV);
+        // Version, entry point, and offsets of the program and section headers
+        for value in [1, 4, 52, 0x120] {
+            push_u32(&mut elf, value);
+        }
+        // Flags, then the sizes and numbers of the headers
+        push_u32(&mut elf, 0);
+        for value in [52, 32, 2, 40, 2, 0] {
+            push_u16(&mut elf, value);
+        }
+        // The code at address 0, then the data at address 0x3FFC, crossing a
+        // page boundary and followed by the BSS
+        for (offset, address, file_size, memory_size) in
+            [(0x100, 0, 8, 8), (0x108, 0x3FFC, 8, 0x108)]
+        {
+            for value in [
+                PT_LOAD,
+                offset,
+                address,
+                address,
+                file_size,
+                memory_size,
+                0,
+                4,
+            ] {
+                push_u32(&mut elf, value);
+            }
+        }
+        elf.resize(0x100, 0);
+        for value in [0x13, 0x13, 0, 0xDEADBEEF] {
+            push_u32(&mut elf, value);
+        }
+        // The pointer to the code in the data is relocated
+        for value in [0x3FFC, R_RISCV_RELATIVE, 4] {
+            push_u32(&mut elf, value);
+        }
+        elf.resize(0x120 + 40, 0);
+        for value in [0, SHT_RELA, 0, 0, 0x110, 12, 0, 0, 4, 12] {
+            push_u32(&mut elf, value);
+        }
+
+        let state = make_state(ElfBytes::<LittleEndian>::minimal_parse(&elf).unwrap()).unwrap();
+        assert_eq!(state.pc, PIE_BASE_ADDRESS + 4);
+        let indices: Vec<u32> = state.memory.iter().map(|page| page.index).collect();
+        let first_page = PIE_BASE_ADDRESS / PAGE_SIZE;
+        assert_eq!(indices, vec![first_page, first_page + 3, first_page + 4]);
+        assert_eq!(state.memory[0].data[0..8], [0x13, 0, 0, 0, 0x13, 0, 0, 0]);
+        assert_eq!(
+            state.memory[1].data[0xFFC..],
+            (PIE_BASE_ADDRESS + 4).to_le_bytes()
+        );
+        assert_eq!(state.memory[2].data[0..4], 0xDEADBEEFu32.to_le_bytes());
+        assert!(state.memory[2].data[4..].iter().all(|byte| *byte == 0));
     }
 }
@@ -0,0 +1,418 @@
+//! A server of the GDB remote serial protocol, to debug the programs run by
+//! the interpreters with gdb. The execution, and so the witness generation,
+//! is paused while gdb inspects the registers and the memory of the program.
+//!
+//! The server is given the stream of the connection with gdb, for instance:
+//!
+//! ```ignore
+//! let (mut stream, _) = std::net::TcpListener::bind("127.0.0.1:9001")?.accept()?;
+//! GdbStub::new(env).serve(&mut stream)?;
+//! ```
+//!
+//! and gdb is attached with `target remote 127.0.0.1:9001`. The stub supports
+//! reading and writing the registers and the memory, single-stepping,
+//! continuing, and software breakpoints. A running program can not be
+//! interrupted from gdb, so a breakpoint must be set before continuing.
+//!
+//! See <https://sourceware.org/gdb/current/onlinedocs/gdb.html/Remote-Protocol.html>.
+
+use crate::{
+    cannon::{Meta, Start, VmConfiguration},
+    interpreters::{mips::witness as mips_witness, riscv32im::witness as riscv32im_witness},
+    merkle_memory,
+    preimage_oracle::PreImageOracleT,
+};
+use ark_ff::{Field, PrimeField};
+use std::{
+    collections::BTreeSet,
+    fmt::Write as _,
+    io::{self, Read, Write},
+};
+
+/// A program debugged with gdb
+pub trait Target {
+    /// Whether the registers are sent in big endian to gdb
+    const BIG_ENDIAN: bool;
+
+    /// Returns the registers, in the order of the `g` packet of gdb for the
+    /// architecture
+    fn registers(&self) -> Vec<u32>;
+
+    /// Sets the register `index`, in the order of [Target::registers].
+    /// Returns false if the register can not be written.
+    fn write_register(&mut self, index: usize, value: u32) -> bool;
+
+    fn instruction_pointer(&self) -> u32;
+
+    fn read_byte(&self, addr: u32) -> u8;
+
+    fn write_byte(&mut self, addr: u32, value: u8);
+
+    /// Executes one instruction
+    fn step(&mut self);
+
+    /// Returns the exit code of the program once it has halted
+    fn exit_code(&self) -> Option<u8>;
+}
+
+/// The server of the GDB remote serial protocol for the target `T`
+pub struct GdbStub<T> {
+    pub target: T,
+    breakpoints: BTreeSet<u32>,
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut s, byte| {
+        let _ = write!(s, "{byte:02x}");
+        s
+    })
+}
+
+fn parse_hex(s: &str) -> Option<u32> {
+    u32::from_str_radix(s, 16).ok()
+}
+
+fn parse_hex_bytes(s: &str) -> Option<Vec<u8>> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn checksum(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte))
+}
+
+fn read_byte<S: Read>(stream: &mut S) -> io::Result<Option<u8>> {
+    let mut byte = [0u8];
+    match stream.read(&mut byte)? {
+        0 => Ok(None),
+        _ => Ok(Some(byte[0])),
+    }
+}
+
+/// Reads the next packet sent by gdb, acknowledging it. Returns `None` when
+/// the connection is closed.
+fn read_packet<S: Read + Write>(stream: &mut S) -> io::Result<Option<String>> {
+    loop {
+        // The acknowledgements and interruptions are skipped
+        match read_byte(stream)? {
+            None => return Ok(None),
+            Some(b'$') => (),
+            Some(_) => continue,
+        }
+        let mut data = vec![];
+        loop {
+            match read_byte(stream)? {
+                None => return Ok(None),
+                Some(b'#') => break,
+                Some(byte) => data.push(byte),
+            }
+        }
+        let (Some(high), Some(low)) = (read_byte(stream)?, read_byte(stream)?) else {
+            return Ok(None);
+        };
+        let expected = std::str::from_utf8(&[high, low])
+            .ok()
+            .and_then(|s| u8::from_str_radix(s, 16).ok());
+        if expected == Some(checksum(&data)) {
+            stream.write_all(b"+")?;
+            return Ok(Some(String::from_utf8_lossy(&data).into_owned()));
+        }
+        stream.write_all(b"-")?;
+    }
+}
+
+fn write_packet<S: Write>(stream: &mut S, data: &str) -> io::Result<()> {
+    write!(stream, "${data}#{:02x}", checksum(data.as_bytes()))?;
+    stream.flush()
+}
+
+impl<T: Target> GdbStub<T> {
+    pub fn new(target: T) -> Self {
+        GdbStub {
+            target,
+            breakpoints: BTreeSet::new(),
+        }
+    }
+
+    /// Answers the requests of gdb until it kills the program, detaches, or
+    /// closes the connection
+    pub fn serve<S: Read + Write>(&mut self, stream: &mut S) -> io::Result<()> {
+        while let Some(packet) = read_packet(stream)? {
+            match packet.as_str() {
+                "k" => return Ok(()),
+                "D" => return write_packet(stream, "OK"),
+                _ => write_packet(stream, &self.answer(&packet))?,
+            }
+        }
+        Ok(())
+    }
+
+    fn stop_reply(&self) -> String {
+        match self.target.exit_code() {
+            Some(exit_code) => format!("W{exit_code:02x}"),
+            // Stopped by SIGTRAP
+            None => "S05".to_string(),
+        }
+    }
+
+    fn register_bytes(value: u32) -> [u8; 4] {
+        if T::BIG_ENDIAN {
+            value.to_be_bytes()
+        } else {
+            value.to_le_bytes()
+        }
+    }
+
+    fn register_from_bytes(bytes: &[u8]) -> Option<u32> {
+        let bytes: [u8; 4] = bytes.try_into().ok()?;
+        Some(if T::BIG_ENDIAN {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+
+    /// Returns the answer to the packet `packet`. The packets not supported
+    /// are answered with an empty packet, as required by the protocol.
+    fn answer(&mut self, packet: &str) -> String {
+        const ERROR: &str = "E01";
+        let (command, args) = packet.split_at(1.min(packet.len()));
+        match command {
+            "?" => self.stop_reply(),
+            "g" => hex(&self
+                .target
+                .registers()
+                .into_iter()
+                .flat_map(Self::register_bytes)
+                .collect::<Vec<_>>()),
+            "G" => {
+                let Some(bytes) = parse_hex_bytes(args) else {
+                    return ERROR.to_string();
+                };
+                // The registers that can not be written are left unchanged
+                for (index, value) in bytes.chunks_exact(4).enumerate() {
+                    let value = Self::register_from_bytes(value).unwrap();
+                    self.target.write_register(index, value);
+                }
+                "OK".to_string()
+            }
+            "p" => parse_hex(args)
+                .and_then(|index| self.target.registers().get(index as usize).copied())
+                .map_or(ERROR.to_string(), |value| hex(&Self::register_bytes(value))),
+            "P" => {
+                let written = args.split_once('=').and_then(|(index, value)| {
+                    let value = Self::register_from_bytes(&parse_hex_bytes(value)?)?;
+                    Some(
+                        self.target
+                            .write_register(parse_hex(index)? as usize, value),
+                    )
+                });
+                if written == Some(true) {
+                    "OK".to_string()
+                } else {
+                    ERROR.to_string()
+                }
+            }
+            "m" => {
+                let Some((addr, len)) = args
+                    .split_once(',')
+                    .and_then(|(addr, len)| Some((parse_hex(addr)?, parse_hex(len)?)))
+                else {
+                    return ERROR.to_string();
+                };
+                hex(&(0..len)
+                    .map(|i| self.target.read_byte(addr.wrapping_add(i)))
+                    .collect::<Vec<_>>())
+            }
+            "M" => {
+                let Some((addr, bytes)) = args.split_once(':').and_then(|(range, bytes)| {
+                    let (addr, _len) = range.split_once(',')?;
+                    Some((parse_hex(addr)?, parse_hex_bytes(bytes)?))
+                }) else {
+                    return ERROR.to_string();
+                };
+                for (i, byte) in bytes.into_iter().enumerate() {
+                    self.target.write_byte(addr.wrapping_add(i as u32), byte);
+                }
+                "OK".to_string()
+            }
+            "s" => {
+                if self.target.exit_code().is_none() {
+                    self.target.step();
+                }
+                self.stop_reply()
+            }
+            "c" => {
+                while self.target.exit_code().is_none() {
+                    self.target.step();
+                    if self
+                        .breakpoints
+                        .contains(&self.target.instruction_pointer())
+                    {
+                        break;
+                    }
+                }
+                self.stop_reply()
+            }
+            // Software and hardware breakpoints are handled the same way
+            "Z" | "z" => {
+                let mut fields = args.split(',');
+                let (Some(kind), Some(addr)) = (fields.next(), fields.next().and_then(parse_hex))
+                else {
+                    return ERROR.to_string();
+                };
+                if kind != "0" && kind != "1" {
+                    return String::new();
+                }
+                if command == "Z" {
+                    self.breakpoints.insert(addr);
+                } else {
+                    self.breakpoints.remove(&addr);
+                }
+                "OK".to_string()
+            }
+            // There is a single thread
+            "H" => "OK".to_string(),
+            "q" if args.starts_with("Supported") => "PacketSize=1000".to_string(),
+            "q" if args == "Attached" => "1".to_string(),
+            _ => String::new(),
+        }
+    }
+}
+
+/// The registers follow the order of gdb for RISC-V: the general purpose
+/// registers, followed by the program counter.
+impl<Fp: Field> Target for riscv32im_witness::Env<Fp> {
+    const BIG_ENDIAN: bool = false;
+
+    fn registers(&self) -> Vec<u32> {
+        let mut registers = self.registers.general_purpose.to_vec();
+        registers.push(self.registers.current_instruction_pointer);
+        registers
+    }
+
+    fn write_register(&mut self, index: usize, value: u32) -> bool {
+        match index {
+            // The register x0 is always zero
+            0 => value == 0,
+            1..=31 => {
+                self.registers.general_purpose[index] = value;
+                true
+            }
+            32 => {
+                self.registers.current_instruction_pointer = value;
+                self.registers.next_instruction_pointer = value.wrapping_add(4);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn instruction_pointer(&self) -> u32 {
+        self.registers.current_instruction_pointer
+    }
+
+    fn read_byte(&self, addr: u32) -> u8 {
+        self.memory.get(addr)
+    }
+
+    fn write_byte(&mut self, addr: u32, value: u8) {
+        self.memory.set(addr, value);
+        if let Some(merkle_memory) = self.merkle_memory.as_mut() {
+            merkle_memory.update(addr & !3, merkle_memory::word_at(&self.memory, addr));
+        }
+    }
+
+    fn step(&mut self) {
+        riscv32im_witness::Env::step(self);
+    }
+
+    fn exit_code(&self) -> Option<u8> {
+        self.halt
+            .then_some(self.exit_code.unwrap_or_default() as u8)
+    }
+}
+
+/// A MIPS program, with the configuration given to each step of the execution
+pub struct MipsTarget<Fp, PreImageOracle: PreImageOracleT> {
+    pub env: mips_witness::Env<Fp, PreImageOracle>,
+    pub config: VmConfiguration,
+    pub metadata: Option<Meta>,
+    pub start: Start,
+}
+
+/// The registers follow the order of gdb for MIPS: the general purpose
+/// registers, followed by `sr`, `lo`, `hi`, `bad`, `cause` and `pc`. The
+/// floating point registers are not sent.
+impl<Fp: PrimeField, PreImageOracle: PreImageOracleT> Target for MipsTarget<Fp, PreImageOracle> {
+    const BIG_ENDIAN: bool = true;
+
+    fn registers(&self) -> Vec<u32> {
+        let registers = &self.env.registers;
+        let mut values = registers.general_purpose.to_vec();
+        values.extend([
+            0,
+            registers.lo,
+            registers.hi,
+            0,
+            0,
+            registers.current_instruction_pointer,
+        ]);
+        values
+    }
+
+    fn write_register(&mut self, index: usize, value: u32) -> bool {
+        let registers = &mut self.env.registers;
+        match index {
+            // The register $zero is always zero
+            0 => value == 0,
+            1..=31 => {
+                registers.general_purpose[index] = value;
+                true
+            }
+            33 => {
+                registers.lo = value;
+                true
+            }
+            34 => {
+                registers.hi = value;
+                true
+            }
+            37 => {
+                registers.current_instruction_pointer = value;
+                registers.next_instruction_pointer = value.wrapping_add(4);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn instruction_pointer(&self) -> u32 {
+        self.env.registers.current_instruction_pointer
+    }
+
+    fn read_byte(&self, addr: u32) -> u8 {
+        self.env.memory.get(addr)
+    }
+
+    fn write_byte(&mut self, addr: u32, value: u8) {
+        self.env.memory.set(addr, value);
+        if let Some(merkle_memory) = self.env.merkle_memory.as_mut() {
+            merkle_memory.update(addr & !3, merkle_memory::word_at(&self.env.memory, addr));
+        }
+    }
+
+    fn step(&mut self) {
+        self.env.step(&self.config, &self.metadata, &self.start);
+    }
+
+    /// The exit code is the argument of the system call `exit`, left in the
+    /// register $a0
+    fn exit_code(&self) -> Option<u8> {
+        self.env
+            .halt
+            .then_some(self.env.registers.general_purpose[4] as u8)
+    }
+}
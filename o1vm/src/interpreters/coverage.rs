@@ -0,0 +1,273 @@
+//! A report of the parts of the circuit of a zkVM exercised by an execution.
+//!
+//! For each instruction executed, the report gives the number of rows
+//! selecting it, the constraints and the lookups active on these rows, and
+//! the scratch columns holding a non-zero value on one of them. A scratch
+//! column written by an instruction but read by none of its constraints and
+//! lookups is flagged: nothing prevents a prover from putting any value in it.
+//! The scratch columns never written by the execution are listed, as well as
+//! the instructions never executed, which are not covered by the tests
+//! running it.
+//!
+//! The executed instructions are recorded by an [ExecutionCoverage], and the
+//! report is built by the constraints environments of
+//! [super::mips::constraints::Env::coverage] and
+//! [super::riscv32im::constraints::Env::coverage].
+//!
+//! A column is seen as written when it holds a non-zero value: a column
+//! written with zero on every row is reported as never written.
+
+use crate::lookups::Lookup;
+use ark_ff::Zero;
+use kimchi::circuits::expr::{ExprInner, Operations};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{Display, Formatter},
+};
+
+/// A scratch column of the circuit of a zkVM
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ScratchColumn {
+    Scratch(usize),
+    ScratchInverse(usize),
+}
+
+impl Display for ScratchColumn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScratchColumn::Scratch(i) => write!(f, "scratch[{i}]"),
+            ScratchColumn::ScratchInverse(i) => write!(f, "scratch_inverse[{i}]"),
+        }
+    }
+}
+
+/// Calls `f` on the columns read by `expr`
+fn visit_columns<C, Column>(expr: &Operations<ExprInner<C, Column>>, f: &mut impl FnMut(&Column)) {
+    use ExprInner::*;
+    use Operations::*;
+    match expr {
+        Atom(Cell(variable)) => f(&variable.col),
+        Atom(Constant(_)) | Atom(VanishesOnZeroKnowledgeAndPreviousRows) => (),
+        Atom(UnnormalizedLagrangeBasis(_)) => (),
+        Pow(x, _) | Double(x) | Square(x) | Cache(_, x) => visit_columns(x, f),
+        Add(x, y) | Mul(x, y) | Sub(x, y) | IfFeature(_, x, y) => {
+            visit_columns(x, f);
+            visit_columns(y, f)
+        }
+    }
+}
+
+/// The constraints and the lookups of an instruction, without its selector
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InstructionConstraints {
+    pub constraints: usize,
+    pub lookups: usize,
+    /// The scratch columns read by the constraints or by the lookups
+    pub constrained: BTreeSet<ScratchColumn>,
+}
+
+impl InstructionConstraints {
+    /// Collects the scratch columns read by `constraints` and `lookups`,
+    /// `scratch` giving the scratch column of a column of the circuit, if any
+    pub fn new<C, Column>(
+        constraints: &[Operations<ExprInner<C, Column>>],
+        lookups: &[Lookup<Operations<ExprInner<C, Column>>>],
+        scratch: impl Fn(&Column) -> Option<ScratchColumn>,
+    ) -> Self {
+        let mut constrained = BTreeSet::new();
+        let mut visit = |column: &Column| constrained.extend(scratch(column));
+        for constraint in constraints {
+            visit_columns(constraint, &mut visit);
+        }
+        for lookup in lookups {
+            visit_columns(&lookup.magnitude, &mut visit);
+            for value in &lookup.value {
+                visit_columns(value, &mut visit);
+            }
+        }
+        InstructionConstraints {
+            constraints: constraints.len(),
+            lookups: lookups.len(),
+            constrained,
+        }
+    }
+}
+
+/// The instructions executed by a run of a zkVM, with the scratch columns
+/// written on their rows
+#[derive(Clone, Debug)]
+pub struct ExecutionCoverage<I> {
+    executed: BTreeMap<I, (usize, BTreeSet<ScratchColumn>)>,
+}
+
+impl<I: Ord> Default for ExecutionCoverage<I> {
+    fn default() -> Self {
+        ExecutionCoverage {
+            executed: BTreeMap::new(),
+        }
+    }
+}
+
+impl<I: Ord + Copy> ExecutionCoverage<I> {
+    /// Records a row of the execution, selecting `instruction`, with the
+    /// values of the scratch columns and of the inverse scratch columns on
+    /// the row
+    pub fn record<F: Zero>(
+        &mut self,
+        instruction: I,
+        scratch: impl IntoIterator<Item = F>,
+        scratch_inverse: impl IntoIterator<Item = F>,
+    ) {
+        let (rows, written) = self.executed.entry(instruction).or_default();
+        *rows += 1;
+        for (i, value) in scratch.into_iter().enumerate() {
+            if !value.is_zero() {
+                written.insert(ScratchColumn::Scratch(i));
+            }
+        }
+        for (i, value) in scratch_inverse.into_iter().enumerate() {
+            if !value.is_zero() {
+                written.insert(ScratchColumn::ScratchInverse(i));
+            }
+        }
+    }
+
+    /// The number of rows recorded
+    pub fn rows(&self) -> usize {
+        self.executed.values().map(|(rows, _)| rows).sum()
+    }
+
+    /// Builds the report of the execution, over the instructions
+    /// `instructions` of a circuit with `scratch_size` scratch columns and
+    /// `scratch_inverse_size` inverse scratch columns, `constraints` giving
+    /// the constraints of an executed instruction
+    pub fn report(
+        &self,
+        instructions: impl IntoIterator<Item = I>,
+        scratch_size: usize,
+        scratch_inverse_size: usize,
+        mut constraints: impl FnMut(I) -> InstructionConstraints,
+    ) -> CoverageReport<I> {
+        let mut executed = vec![];
+        let mut unexercised = vec![];
+        for instruction in instructions {
+            match self.executed.get(&instruction) {
+                Some((rows, written)) => {
+                    let constraints = constraints(instruction);
+                    executed.push(InstructionCoverage {
+                        instruction,
+                        rows: *rows,
+                        constraints: constraints.constraints,
+                        lookups: constraints.lookups,
+                        written: written.clone(),
+                        constrained: constraints.constrained,
+                    })
+                }
+                None => unexercised.push(instruction),
+            }
+        }
+        let written: BTreeSet<_> = self.executed.values().flat_map(|(_, w)| w).collect();
+        let never_written = (0..scratch_size)
+            .map(ScratchColumn::Scratch)
+            .chain((0..scratch_inverse_size).map(ScratchColumn::ScratchInverse))
+            .filter(|column| !written.contains(column))
+            .collect();
+        CoverageReport {
+            executed,
+            unexercised,
+            never_written,
+        }
+    }
+}
+
+/// The coverage of an instruction executed, see [CoverageReport]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InstructionCoverage<I> {
+    pub instruction: I,
+    /// The number of rows selecting the instruction
+    pub rows: usize,
+    /// The number of constraints of the instruction, active on its rows
+    pub constraints: usize,
+    /// The number of lookups of the instruction, active on its rows
+    pub lookups: usize,
+    /// The scratch columns holding a non-zero value on one of the rows
+    pub written: BTreeSet<ScratchColumn>,
+    /// The scratch columns read by the constraints or by the lookups of the
+    /// instruction
+    pub constrained: BTreeSet<ScratchColumn>,
+}
+
+impl<I> InstructionCoverage<I> {
+    /// The scratch columns written on the rows of the instruction, but read
+    /// by none of its constraints and lookups
+    pub fn unconstrained(&self) -> Vec<ScratchColumn> {
+        self.written
+            .difference(&self.constrained)
+            .copied()
+            .collect()
+    }
+}
+
+/// The report of the coverage of the circuit of a zkVM by an execution, see
+/// the [module documentation](self)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CoverageReport<I> {
+    /// The instructions executed, in the order of the instructions of the
+    /// circuit
+    pub executed: Vec<InstructionCoverage<I>>,
+    /// The instructions of the circuit never executed
+    pub unexercised: Vec<I>,
+    /// The scratch columns holding zero on every row
+    pub never_written: Vec<ScratchColumn>,
+}
+
+impl<I: Copy> CoverageReport<I> {
+    /// The number of constraints active on a row of the execution, summed
+    /// over the instructions executed
+    pub fn active_constraints(&self) -> usize {
+        self.executed.iter().map(|i| i.constraints).sum()
+    }
+
+    /// The scratch columns written but unconstrained, by instruction
+    pub fn unconstrained(&self) -> Vec<(I, ScratchColumn)> {
+        self.executed
+            .iter()
+            .flat_map(|i| {
+                i.unconstrained()
+                    .into_iter()
+                    .map(move |column| (i.instruction, column))
+            })
+            .collect()
+    }
+}
+
+impl<I: std::fmt::Debug> Display for CoverageReport<I> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let join = |columns: &[ScratchColumn]| {
+            columns
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        writeln!(
+            f,
+            "{} of {} instructions executed",
+            self.executed.len(),
+            self.executed.len() + self.unexercised.len()
+        )?;
+        for i in &self.executed {
+            writeln!(
+                f,
+                "  {:?}: {} rows, {} constraints, {} lookups",
+                i.instruction, i.rows, i.constraints, i.lookups
+            )?;
+            let unconstrained = i.unconstrained();
+            if !unconstrained.is_empty() {
+                writeln!(f, "    unconstrained: [{}]", join(&unconstrained))?;
+            }
+        }
+        writeln!(f, "not executed: {:?}", self.unexercised)?;
+        write!(f, "never written: [{}]", join(&self.never_written))
+    }
+}
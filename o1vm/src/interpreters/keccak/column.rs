@@ -2,19 +2,21 @@
 //! are aliases for the actual Keccak witness columns also defined here.
 use self::{Absorbs::*, Sponges::*, Steps::*};
 use crate::{
-    interpreters::keccak::{ZKVM_KECCAK_COLS_CURR, ZKVM_KECCAK_COLS_NEXT},
+    interpreters::keccak::{
+        variant::MAX_RATE_IN_BYTES, ZKVM_KECCAK_COLS_CURR, ZKVM_KECCAK_COLS_NEXT,
+    },
     RelationColumnType,
 };
 use kimchi::circuits::polynomials::keccak::constants::{
     CHI_SHIFTS_B_LEN, CHI_SHIFTS_B_OFF, CHI_SHIFTS_SUM_LEN, CHI_SHIFTS_SUM_OFF, PIRHO_DENSE_E_LEN,
     PIRHO_DENSE_E_OFF, PIRHO_DENSE_ROT_E_LEN, PIRHO_DENSE_ROT_E_OFF, PIRHO_EXPAND_ROT_E_LEN,
     PIRHO_EXPAND_ROT_E_OFF, PIRHO_QUOTIENT_E_LEN, PIRHO_QUOTIENT_E_OFF, PIRHO_REMAINDER_E_LEN,
-    PIRHO_REMAINDER_E_OFF, PIRHO_SHIFTS_E_LEN, PIRHO_SHIFTS_E_OFF, QUARTERS, RATE_IN_BYTES,
-    SPONGE_BYTES_LEN, SPONGE_BYTES_OFF, SPONGE_COLS, SPONGE_NEW_STATE_LEN, SPONGE_NEW_STATE_OFF,
-    SPONGE_SHIFTS_LEN, SPONGE_SHIFTS_OFF, SPONGE_ZEROS_LEN, SPONGE_ZEROS_OFF, STATE_LEN,
-    THETA_DENSE_C_LEN, THETA_DENSE_C_OFF, THETA_DENSE_ROT_C_LEN, THETA_DENSE_ROT_C_OFF,
-    THETA_EXPAND_ROT_C_LEN, THETA_EXPAND_ROT_C_OFF, THETA_QUOTIENT_C_LEN, THETA_QUOTIENT_C_OFF,
-    THETA_REMAINDER_C_LEN, THETA_REMAINDER_C_OFF, THETA_SHIFTS_C_LEN, THETA_SHIFTS_C_OFF,
+    PIRHO_REMAINDER_E_OFF, PIRHO_SHIFTS_E_LEN, PIRHO_SHIFTS_E_OFF, QUARTERS, SPONGE_BYTES_LEN,
+    SPONGE_BYTES_OFF, SPONGE_COLS, SPONGE_NEW_STATE_LEN, SPONGE_NEW_STATE_OFF, SPONGE_SHIFTS_LEN,
+    SPONGE_SHIFTS_OFF, SPONGE_ZEROS_LEN, SPONGE_ZEROS_OFF, STATE_LEN, THETA_DENSE_C_LEN,
+    THETA_DENSE_C_OFF, THETA_DENSE_ROT_C_LEN, THETA_DENSE_ROT_C_OFF, THETA_EXPAND_ROT_C_LEN,
+    THETA_EXPAND_ROT_C_OFF, THETA_QUOTIENT_C_LEN, THETA_QUOTIENT_C_OFF, THETA_REMAINDER_C_LEN,
+    THETA_REMAINDER_C_OFF, THETA_SHIFTS_C_LEN, THETA_SHIFTS_C_OFF,
 };
 use kimchi_msm::{
     columns::{Column, ColumnIndexer},
@@ -51,11 +53,11 @@ const PAD_FLAGS_OFF: usize = STATUS_LEN + SPONGE_COLS; // Offset of the Pad flag
 const PAD_LEN_OFF: usize = 0; // Offset of the PadLength column inside the sponge coefficients
 const PAD_TWO_OFF: usize = 1; // Offset of the TwoToPad column inside the sponge coefficients
 const PAD_SUFFIX_OFF: usize = 2; // Offset of the PadSuffix column inside the sponge coefficients
-/// The padding suffix of 1088 bits is stored as 5 field elements: 1x12 + 4x31 bytes
-pub(crate) const PAD_SUFFIX_LEN: usize = 5;
+/// The padding suffix of up to 1344 bits is stored as 6 field elements: 1x13 + 5x31 bytes
+pub(crate) const PAD_SUFFIX_LEN: usize = 6;
 const PAD_BYTES_OFF: usize = PAD_SUFFIX_OFF + PAD_SUFFIX_LEN; // Offset of the PadBytesFlags inside the sponge coefficients
-/// The maximum number of padding bytes involved
-pub(crate) const PAD_BYTES_LEN: usize = RATE_IN_BYTES;
+/// The maximum number of padding bytes involved, the largest rate of the variants
+pub(crate) const PAD_BYTES_LEN: usize = MAX_RATE_IN_BYTES;
 
 const FLAG_ROUND_OFF: usize = N_ZKVM_KECCAK_REL_COLS; // Offset of the Round selector inside DynamicSelector
 const FLAG_FST_OFF: usize = FLAG_ROUND_OFF + 1; // Offset of the Absorb(First) selector inside DynamicSelector
@@ -104,10 +106,10 @@ pub enum ColumnAlias {
     RoundNumber, // Only nonzero when Selector(Flag::Round) = 1 : Round 0 | 1 ..=23
     RoundConstants(usize), // Only nonzero when Selector(Flag::Round) = 1 : Round constants
 
-    PadLength,            // Only nonzero when Selector(Flag::Pad) = 1 : Length 0 | 1 ..=136
-    TwoToPad,             // Only nonzero when Selector(Flag::Pad) = 1 : 2^PadLength
-    PadSuffix(usize),     // Only nonzero when Selector(Flag::Pad) = 1 : 5 field elements
-    PadBytesFlags(usize), // Only nonzero when Selector(Flag::Pad) = 1 : 136 boolean values
+    PadLength,        // Only nonzero when Selector(Flag::Pad) = 1 : Length 0 | 1 ..=rate
+    TwoToPad,         // Only nonzero when Selector(Flag::Pad) = 1 : 2^PadLength
+    PadSuffix(usize), // Only nonzero when Selector(Flag::Pad) = 1 : 6 field elements
+    PadBytesFlags(usize), // Only nonzero when Selector(Flag::Pad) = 1 : 168 boolean values
 }
 
 /// Variants of Keccak steps available for the interpreter.
@@ -348,11 +350,11 @@ impl From<ColumnAlias> for usize {
 ///
 ///   2073..=2078 -> selectors
 ///
-///   803..=945 -> pad_flags
+///   803..=978 -> pad_flags
 ///             -> 803: PadLength
 ///             -> 804: TwoToPad
-///             -> 805..=809: PadSuffix
-///             -> 810..=945: PadBytesFlags
+///             -> 805..=810: PadSuffix
+///             -> 811..=978: PadBytesFlags
 ///
 pub type KeccakWitness<T> = Witness<N_ZKVM_KECCAK_REL_COLS, T>;
 
@@ -3,7 +3,7 @@ use crate::{
     interpreters::keccak::{
         helpers::{ArithHelpers, BoolHelpers, LogupHelpers},
         interpreter::{Interpreter, KeccakInterpreter},
-        Constraint, KeccakColumn,
+        Constraint, KeccakColumn, KeccakVariant,
     },
     lookups::Lookup,
     E,
@@ -25,6 +25,8 @@ pub struct Env<Fp> {
     pub constraints: Vec<E<Fp>>,
     /// Variables that are looked up in the circuit
     pub lookups: Vec<Lookup<E<Fp>>>,
+    /// The hash function constrained by the circuit
+    pub variant: KeccakVariant,
 }
 
 impl<F: Field> Default for Env<F> {
@@ -32,6 +34,7 @@ impl<F: Field> Default for Env<F> {
         Self {
             constraints: Vec::new(),
             lookups: Vec::new(),
+            variant: KeccakVariant::default(),
         }
     }
 }
@@ -79,4 +82,8 @@ impl<F: Field> Interpreter<F> for Env<F> {
     }
 }
 
-impl<F: Field> KeccakInterpreter<F> for Env<F> {}
+impl<F: Field> KeccakInterpreter<F> for Env<F> {
+    fn variant(&self) -> KeccakVariant {
+        self.variant
+    }
+}
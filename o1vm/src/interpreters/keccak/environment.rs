@@ -8,12 +8,12 @@ use crate::interpreters::keccak::{
         Sponges::{self, *},
         Steps,
         Steps::*,
-        PAD_SUFFIX_LEN,
+        PAD_BYTES_LEN, PAD_SUFFIX_LEN,
     },
     constraints::Env as ConstraintsEnv,
     grid_index, pad_blocks, standardize,
     witness::Env as WitnessEnv,
-    KeccakColumn, DIM, HASH_BYTELENGTH, QUARTERS, WORDS_IN_HASH,
+    KeccakColumn, KeccakVariant, DIM, QUARTERS, WORD_LENGTH_IN_BITS,
 };
 
 use ark_ff::Field;
@@ -25,7 +25,7 @@ use kimchi::{
     },
     o1_utils::Two,
 };
-use std::array;
+use std::{array, collections::VecDeque};
 
 /// This struct contains all that needs to be kept track of during the execution of the Keccak step interpreter
 #[derive(Clone, Debug)]
@@ -37,6 +37,8 @@ pub struct KeccakEnv<F> {
     pub witness_env: WitnessEnv<F>,
     /// Current step
     pub step: Option<Steps>,
+    /// The hash function computed
+    pub variant: KeccakVariant,
 
     /// Hash index in the circuit
     pub(crate) hash_idx: u64,
@@ -52,13 +54,18 @@ pub struct KeccakEnv<F> {
 
     /// Padded preimage data
     pub(crate) padded: Vec<u8>,
-    /// Byte-length of the 10*1 pad (<=136)
+    /// Byte-length of the 10*1 pad (<= the rate of the variant)
     pub(crate) pad_len: u64,
+    /// The outputs of the messages hashed so far, the last one being the
+    /// bytes squeezed so far from the current message
+    pub(crate) outputs: Vec<Vec<u8>>,
+    /// The messages left to be hashed after the current one, in order
+    pub(crate) pending: VecDeque<Vec<u8>>,
 
     /// Precomputed 2^pad_len
-    two_to_pad: [F; RATE_IN_BYTES],
-    /// Precomputed suffixes for the padding blocks
-    pad_suffixes: [[F; PAD_SUFFIX_LEN]; RATE_IN_BYTES],
+    two_to_pad: [F; PAD_BYTES_LEN],
+    /// Precomputed suffixes for the padding blocks of the variant
+    pad_suffixes: Vec<[F; PAD_SUFFIX_LEN]>,
 }
 
 impl<F: Field> Default for KeccakEnv<F> {
@@ -67,6 +74,7 @@ impl<F: Field> Default for KeccakEnv<F> {
             constraints_env: ConstraintsEnv::default(),
             witness_env: WitnessEnv::default(),
             step: None,
+            variant: KeccakVariant::default(),
             hash_idx: 0,
             step_idx: 0,
             block_idx: 0,
@@ -74,44 +82,104 @@ impl<F: Field> Default for KeccakEnv<F> {
             blocks_left_to_absorb: 0,
             padded: vec![],
             pad_len: 0,
+            outputs: vec![],
+            pending: VecDeque::new(),
             two_to_pad: array::from_fn(|i| F::two_pow(1 + i as u64)),
-            pad_suffixes: array::from_fn(|i| pad_blocks::<F>(1 + i)),
+            pad_suffixes: Self::pad_suffixes(KeccakVariant::default()),
         }
     }
 }
 
 impl<F: Field> KeccakEnv<F> {
-    /// Starts a new Keccak environment for a given hash index and bytestring of preimage data
+    /// Starts a new Keccak environment for a given hash index and bytestring of preimage data,
+    /// computing Keccak-256
     pub fn new(hash_idx: u64, preimage: &[u8]) -> Self {
-        // Must update the flag type at each step from the witness interpretation
+        Self::new_with_variant(hash_idx, preimage, KeccakVariant::Keccak256)
+    }
+
+    /// Starts a new Keccak environment for a given hash index and bytestring of preimage data,
+    /// computing the hash function `variant`
+    pub fn new_with_variant(hash_idx: u64, preimage: &[u8], variant: KeccakVariant) -> Self {
         let mut env = KeccakEnv::<F> {
-            hash_idx,
+            variant,
+            pad_suffixes: Self::pad_suffixes(variant),
             ..Default::default()
         };
+        env.constraints_env.variant = variant;
+        env.witness_env.variant = variant;
+        env.start_message(hash_idx, preimage);
+        env
+    }
+
+    /// Computes the suffixes of the paddings of all lengths of `variant`
+    fn pad_suffixes(variant: KeccakVariant) -> Vec<[F; PAD_SUFFIX_LEN]> {
+        (1..=variant.rate_in_bytes())
+            .map(|len| pad_blocks::<F>(variant, len))
+            .collect()
+    }
+
+    /// Starts a new Keccak environment hashing the messages `messages` one
+    /// after the other in a single trace, with consecutive hash indices from
+    /// `hash_idx`. The messages are delimited by the root absorb and the
+    /// squeeze selectors, and the steps of each of them are only chained to
+    /// the ones of the same hash index.
+    pub fn new_batch(hash_idx: u64, messages: &[&[u8]], variant: KeccakVariant) -> Self {
+        let (first, rest) = messages
+            .split_first()
+            .expect("At least one message must be hashed");
+        let mut env = Self::new_with_variant(hash_idx, first, variant);
+        for message in rest {
+            env.push_message(message);
+        }
+        env
+    }
+
+    /// Queues the message `preimage`, to be hashed after the ones already in
+    /// the environment with the next hash index
+    pub fn push_message(&mut self, preimage: &[u8]) {
+        if self.step.is_none() {
+            self.start_message(self.hash_idx + 1, preimage);
+        } else {
+            self.pending.push_back(preimage.to_vec());
+        }
+    }
 
-        // Store hash index in the witness
-        env.write_column(KeccakColumn::HashIndex, env.hash_idx);
+    /// Resets the state of the sponge to hash the message `preimage` with the
+    /// hash index `hash_idx`
+    fn start_message(&mut self, hash_idx: u64, preimage: &[u8]) {
+        self.hash_idx = hash_idx;
 
         // Update the number of blocks left to be absorbed depending on the length of the preimage
-        env.blocks_left_to_absorb = Keccak::num_blocks(preimage.len()) as u64;
+        self.blocks_left_to_absorb = self.variant.num_blocks(preimage.len()) as u64;
 
         // Configure first step depending on number of blocks remaining, updating the selector for the row
-        env.step = if env.blocks_left_to_absorb == 1 {
+        self.step = if self.blocks_left_to_absorb == 1 {
             Some(Sponge(Absorb(Only)))
         } else {
             Some(Sponge(Absorb(First)))
         };
-        env.step_idx = 0;
+        self.step_idx = 0;
 
         // Root state (all zeros) shall be used for the first step
-        env.prev_block = vec![0u64; STATE_LEN];
+        self.prev_block = vec![0u64; STATE_LEN];
 
-        // Pad preimage with the 10*1 padding rule
-        env.padded = Keccak::pad(preimage);
-        env.block_idx = 0;
-        env.pad_len = (env.padded.len() - preimage.len()) as u64;
+        // Pad preimage with the domain separation bits and the 10*1 padding rule
+        self.padded = self.variant.pad(preimage);
+        self.block_idx = 0;
+        self.pad_len = (self.padded.len() - preimage.len()) as u64;
+        self.outputs.push(vec![]);
+    }
 
-        env
+    /// Returns the bytes of the output squeezed so far from the current
+    /// message, the whole digest once its last step is executed
+    pub fn output(&self) -> &[u8] {
+        self.outputs.last().map_or(&[], |output| output)
+    }
+
+    /// Returns the digests of the messages hashed so far, in order, followed by
+    /// the bytes squeezed so far from the current message
+    pub fn outputs(&self) -> &[Vec<u8>] {
+        &self.outputs
     }
 
     /// Writes an integer value to a column of the Keccak witness
@@ -153,9 +221,17 @@ impl<F: Field> KeccakEnv<F> {
             Sponge(typ) => self.run_sponge(typ),
             Round(i) => self.run_round(i),
         }
+        self.write_column(KeccakColumn::HashIndex, self.hash_idx);
         self.write_column(KeccakColumn::StepIndex, self.step_idx);
 
         self.update_step();
+
+        // The next message of the batch, if any, starts from the root state
+        if self.step.is_none() {
+            if let Some(preimage) = self.pending.pop_front() {
+                self.start_message(self.hash_idx + 1, &preimage);
+            }
+        }
     }
 
     /// This function updates the next step of the environment depending on the current step
@@ -164,11 +240,21 @@ impl<F: Field> KeccakEnv<F> {
             Some(step) => match step {
                 Sponge(sponge) => match sponge {
                     Absorb(_) => self.step = Some(Round(0)),
-                    Squeeze => self.step = None,
+                    Squeeze => {
+                        // The extendable-output functions permute the state
+                        // again before each additional squeeze
+                        if self.output().len() < self.variant.output_bytelength() {
+                            self.step = Some(Round(0));
+                        } else {
+                            self.step = None;
+                        }
+                    }
                 },
                 Round(round) => {
                     if round < ROUNDS as u64 - 1 {
                         self.step = Some(Round(round + 1));
+                    } else if self.blocks_left_to_absorb == 0 {
+                        self.step = Some(Sponge(Squeeze));
                     } else {
                         self.blocks_left_to_absorb -= 1;
                         match self.blocks_left_to_absorb {
@@ -208,7 +294,8 @@ impl<F: Field> KeccakEnv<F> {
             KeccakColumn::TwoToPad,
             self.two_to_pad[self.pad_len as usize - 1],
         );
-        let pad_range = RATE_IN_BYTES - self.pad_len as usize..RATE_IN_BYTES;
+        let rate = self.variant.rate_in_bytes();
+        let pad_range = rate - self.pad_len as usize..rate;
         for i in pad_range {
             self.write_column(KeccakColumn::PadBytesFlags(i), 1);
         }
@@ -231,12 +318,13 @@ impl<F: Field> KeccakEnv<F> {
         self.set_flag_absorb(absorb);
 
         // Compute witness values
-        let ini_idx = RATE_IN_BYTES * self.block_idx as usize;
-        let mut block = self.padded[ini_idx..ini_idx + RATE_IN_BYTES].to_vec();
+        let rate = self.variant.rate_in_bytes();
+        let ini_idx = rate * self.block_idx as usize;
+        let mut block = self.padded[ini_idx..ini_idx + rate].to_vec();
         self.write_column(KeccakColumn::BlockIndex, self.block_idx);
 
         // Pad with zeros
-        block.append(&mut vec![0; CAPACITY_IN_BYTES]);
+        block.append(&mut vec![0; self.variant.capacity_in_bytes()]);
 
         //    Round + Mode of Operation (Sponge)
         //    state -> permutation(state) -> state'
@@ -285,15 +373,24 @@ impl<F: Field> KeccakEnv<F> {
         let shifts = Keccak::shift(&state);
         let dense = Keccak::collapse(&Keccak::reset(&shifts));
         let bytes = Keccak::bytestring(&dense);
+        // At most a rate of bytes is squeezed at once, out of the words that
+        // are constrained in all the squeezes of the variant
+        let len = (self.variant.output_bytelength() - self.output().len())
+            .min(self.variant.rate_in_bytes());
+        let words = self.variant.squeeze_words();
 
         // Write squeeze-related columns
         for (idx, value) in state.iter().enumerate() {
             self.write_column(KeccakColumn::Input(idx), *value);
         }
-        for (idx, value) in bytes.iter().enumerate().take(HASH_BYTELENGTH) {
+        for (idx, value) in bytes
+            .iter()
+            .enumerate()
+            .take(words * WORD_LENGTH_IN_BITS / 8)
+        {
             self.write_column(KeccakColumn::SpongeBytes(idx), *value);
         }
-        for idx in 0..WORDS_IN_HASH * QUARTERS {
+        for idx in 0..words * QUARTERS {
             self.write_column(KeccakColumn::SpongeShifts(idx), shifts[idx]);
             self.write_column(KeccakColumn::SpongeShifts(100 + idx), shifts[100 + idx]);
             self.write_column(KeccakColumn::SpongeShifts(200 + idx), shifts[200 + idx]);
@@ -301,6 +398,11 @@ impl<F: Field> KeccakEnv<F> {
         }
 
         // Rest is zero thanks to null_state
+
+        // Update environment
+        if let Some(output) = self.outputs.last_mut() {
+            output.extend(bytes.iter().take(len).map(|byte| *byte as u8));
+        }
     }
     /// Assigns the witness values needed in the round step for the given round index
     fn run_round(&mut self, round: u64) {
@@ -6,12 +6,12 @@ use crate::{
         column::{PAD_BYTES_LEN, PAD_SUFFIX_LEN, ROUND_CONST_LEN},
         grid_index,
         helpers::{ArithHelpers, BoolHelpers, LogupHelpers},
+        pad_chunk,
         Absorbs::*,
         Constraint::{self, *},
-        KeccakColumn,
+        KeccakColumn, KeccakVariant,
         Sponges::*,
         Steps::{self, *},
-        WORDS_IN_HASH,
     },
     lookups::{Lookup, LookupTableIDs::*},
 };
@@ -22,10 +22,9 @@ use kimchi::{
         constants::{
             CHI_SHIFTS_B_LEN, CHI_SHIFTS_SUM_LEN, DIM, PIRHO_DENSE_E_LEN, PIRHO_DENSE_ROT_E_LEN,
             PIRHO_EXPAND_ROT_E_LEN, PIRHO_QUOTIENT_E_LEN, PIRHO_REMAINDER_E_LEN,
-            PIRHO_SHIFTS_E_LEN, QUARTERS, RATE_IN_BYTES, SHIFTS, SHIFTS_LEN, SPONGE_BYTES_LEN,
-            SPONGE_SHIFTS_LEN, SPONGE_ZEROS_LEN, STATE_LEN, THETA_DENSE_C_LEN,
-            THETA_DENSE_ROT_C_LEN, THETA_EXPAND_ROT_C_LEN, THETA_QUOTIENT_C_LEN,
-            THETA_REMAINDER_C_LEN, THETA_SHIFTS_C_LEN, THETA_STATE_A_LEN,
+            PIRHO_SHIFTS_E_LEN, QUARTERS, SHIFTS, SHIFTS_LEN, SPONGE_BYTES_LEN, SPONGE_SHIFTS_LEN,
+            STATE_LEN, THETA_DENSE_C_LEN, THETA_DENSE_ROT_C_LEN, THETA_EXPAND_ROT_C_LEN,
+            THETA_QUOTIENT_C_LEN, THETA_REMAINDER_C_LEN, THETA_SHIFTS_C_LEN, THETA_STATE_A_LEN,
         },
         OFF,
     },
@@ -64,40 +63,48 @@ pub trait KeccakInterpreter<F: One + Debug + Zero>
 where
     Self: Interpreter<F> + LogupHelpers<F> + BoolHelpers<F> + ArithHelpers<F>,
 {
-    /// Creates all 879 constraints/checks to the environment:
-    /// - 733 constraints of degree 1
-    /// - 146 constraints of degree 2
+    /// Returns the hash function of the circuit, whose rate and padding
+    /// determine the constraints and the lookups of the sponge steps
+    fn variant(&self) -> KeccakVariant;
+
+    /// Creates all 913 constraints/checks of Keccak-256 to the environment:
+    /// - 734 constraints of degree 1
+    /// - 179 constraints of degree 2
     ///
     /// Where:
     /// - if Steps::Round(_)                -> only 389 constraints added
     /// - if Steps::Sponge::Absorb::First   -> only 332 constraints added (232 + 100)
     /// - if Steps::Sponge::Absorb::Middle  -> only 232 constraints added
-    /// - if Steps::Sponge::Absorb::Last    -> only 374 constraints added (232 + 136 + 6)
-    /// - if Steps::Sponge::Absorb::Only    -> only 474 constraints added (232 + 136 + 100 + 6)
+    /// - if Steps::Sponge::Absorb::Last    -> only 407 constraints added (232 + 168 + 7)
+    /// - if Steps::Sponge::Absorb::Only    -> only 507 constraints added (232 + 168 + 100 + 7)
     /// - if Steps::Sponge::Squeeze         -> only 16  constraints added
     ///
     /// So:
-    /// - At most, 474 constraints are added per row
+    /// - At most, 507 constraints are added per row
+    ///
+    /// The other variants only differ in the number of absorb constraints,
+    /// which check that the capacity of the new state is zero, and in the
+    /// number of squeeze constraints, which cover the words of the output.
     ///
     /// In particular, after folding:
-    /// - 136 columns should be added for the degree-2 constraints of the flags
+    /// - 168 columns should be added for the degree-2 constraints of the flags
     /// - 5   columns should be added for the degree-2 constraints of the round
-    /// - 10  columns should be added for the degree-2 constraints of the sponge
-    ///   - for each of the 5 constraints, 2 columns are added for block_in_padding
+    /// - 12  columns should be added for the degree-2 constraints of the sponge
+    ///   - for each of the 6 constraints, 2 columns are added for block_in_padding
     fn constraints(&mut self, step: Steps) {
-        // CORRECTNESS OF FLAGS: 136 CONSTRAINTS
-        // - 136 constraints of degree 2
+        // CORRECTNESS OF FLAGS: 168 CONSTRAINTS
+        // - 168 constraints of degree 2
         // Of which:
-        // - 136 constraints are added only if is_pad() holds
+        // - 168 constraints are added only if is_pad() holds
         self.constrain_flags(step);
 
-        // SPONGE CONSTRAINTS: 32 + 3*100 + 16 + 6 = 354 CONSTRAINTS
+        // SPONGE CONSTRAINTS: 32 + 3*100 + 16 + 7 = 355 CONSTRAINTS
         // - 349 of degree 1
-        // - 5 of degree 2
+        // - 6 of degree 2
         // Of which:
         // - 232 constraints are added only if is_absorb() holds
         // - 100 constraints are added only if is_root() holds
-        // - 6 constraints are added only if is_pad() holds
+        // - 7 constraints are added only if is_pad() holds
         // - 16 constraints are added only if is_squeeze() holds
         self.constrain_sponge(step);
 
@@ -109,30 +116,30 @@ where
         self.constrain_round(step);
     }
 
-    /// Constrains 136 checks of correctness of mode flags
-    /// - 136 constraints of degree 2
+    /// Constrains 168 checks of correctness of mode flags
+    /// - 168 constraints of degree 2
     ///
     /// Of which:
-    /// - 136 constraints are added only if is_pad() holds
+    /// - 168 constraints are added only if is_pad() holds
     fn constrain_flags(&mut self, step: Steps)
     where
         Self: Interpreter<F>,
     {
         // Booleanity of sponge flags:
-        // - 136 constraints of degree 2
+        // - 168 constraints of degree 2
         self.constrain_booleanity(step);
     }
 
-    /// Constrains 136 checks of booleanity for some mode flags.
-    /// - 136 constraints of degree 2
+    /// Constrains 168 checks of booleanity for some mode flags.
+    /// - 168 constraints of degree 2
     ///
     /// Of which,
-    /// - 136 constraints are added only if is_pad() holds
+    /// - 168 constraints are added only if is_pad() holds
     fn constrain_booleanity(&mut self, step: Steps)
     where
         Self: Interpreter<F>,
     {
-        for i in 0..RATE_IN_BYTES {
+        for i in 0..PAD_BYTES_LEN {
             // Bytes are either involved on padding or not
             self.constrain(
                 BooleanityPadding(i),
@@ -142,14 +149,14 @@ where
         }
     }
 
-    /// Constrains 354 checks of sponge steps
+    /// Constrains 355 checks of sponge steps of Keccak-256
     /// - 349 of degree 1
-    /// - 5 of degree 2
+    /// - 6 of degree 2
     ///
     /// Of which:
     /// - 232 constraints are added only if is_absorb() holds
     /// - 100 constraints are added only if is_root() holds
-    /// - 6 constraints are added only if is_pad() holds
+    /// - 7 constraints are added only if is_pad() holds
     /// - 16 constraints are added only if is_squeeze() holds
     fn constrain_sponge(&mut self, step: Steps) {
         self.constrain_absorb(step);
@@ -157,16 +164,23 @@ where
         self.constrain_squeeze(step);
     }
 
-    /// Constrains 332 checks of absorb sponges
+    /// Constrains 332 checks of absorb sponges of Keccak-256
     /// - 332 of degree 1
     ///
     /// Of which:
-    /// - 232 constraints are added only if is_absorb() holds
+    /// - 232 constraints are added only if is_absorb() holds, of which the
+    ///   number of zeros of the capacity depends on the rate of the variant
     /// - 100 constraints are added only if is_root() holds
     fn constrain_absorb(&mut self, step: Steps) {
-        for (i, zero) in self.sponge_zeros().iter().enumerate() {
+        // Each expanded quarter of the state holds 2 bytes
+        let rate_in_quarters = self.variant().rate_in_bytes() / 2;
+        for i in rate_in_quarters..QUARTERS * DIM * DIM {
             // Absorb phase pads with zeros the new state
-            self.constrain(AbsorbZeroPad(i), self.is_absorb(step), zero.clone());
+            self.constrain(
+                AbsorbZeroPad(i - rate_in_quarters),
+                self.is_absorb(step),
+                self.new_state(i),
+            );
         }
         for i in 0..QUARTERS * DIM * DIM {
             // In first absorb, root state is all zeros
@@ -191,21 +205,23 @@ where
         }
     }
 
-    /// Constrains 6 checks of padding absorb sponges
+    /// Constrains 7 checks of padding absorb sponges
     /// - 1 of degree 1
-    /// - 5 of degree 2
+    /// - 6 of degree 2
     ///
     /// Of which:
-    /// - 6 constraints are added only if is_pad() holds
+    /// - 7 constraints are added only if is_pad() holds
     fn constrain_padding(&mut self, step: Steps) {
-        // Check that the padding is located at the end of the message
-        let pad_at_end = (0..RATE_IN_BYTES).fold(Self::zero(), |acc, i| {
+        // Check that the padding is located at the end of the rate of the
+        // message, and that the flags after the rate are zero
+        let pad_at_end = (0..PAD_BYTES_LEN).fold(Self::zero(), |acc, i| {
             acc * Self::two() + self.in_padding(i)
         });
+        let after_rate = Self::two_pow((PAD_BYTES_LEN - self.variant().rate_in_bytes()) as u64);
         self.constrain(
             PadAtEnd,
             self.is_pad(step),
-            self.two_to_pad() - Self::one() - pad_at_end,
+            (self.two_to_pad() - Self::one()) * after_rate - pad_at_end,
         );
         // Check that the padding value is correct
         for i in 0..PAD_SUFFIX_LEN {
@@ -217,15 +233,16 @@ where
         }
     }
 
-    /// Constrains 16 checks of squeeze sponges
+    /// Constrains 16 checks of squeeze sponges of Keccak-256, one per quarter
+    /// of the words squeezed by the variant
     /// - 16 of degree 1
     ///
     /// Of which:
     /// - 16 constraints are added only if is_squeeze() holds
     fn constrain_squeeze(&mut self, step: Steps) {
         let sponge_shifts = self.vec_sponge_shifts();
-        for i in 0..QUARTERS * WORDS_IN_HASH {
-            // In squeeze, check shifts correspond to the 256-bit prefix digest of the old state (current)
+        for i in 0..QUARTERS * self.variant().squeeze_words() {
+            // In squeeze, check shifts correspond to the squeezed prefix of the old state (current)
             self.constrain(
                 SqueezeShifts(i),
                 self.is_squeeze(step),
@@ -428,7 +445,8 @@ where
     // LOOKUPS OPERATIONS //
     ////////////////////////
 
-    /// Creates all possible lookups to the Keccak constraints environment:
+    /// Creates all possible lookups of Keccak-256 to the Keccak constraints
+    /// environment (the number of preimage bytes is the rate of the variant):
     /// - 2225 lookups for the step row
     /// - 2 lookups for the inter-step channel
     /// - 136 lookups for the syscall channel (preimage bytes)
@@ -477,18 +495,18 @@ where
         self.lookup_syscall_hash(step);
     }
 
-    /// When in Absorb mode, reads Lookups containing the 136 bytes of the block of the preimage
-    /// - if is_absorb, adds 136 lookups
+    /// When in Absorb mode, reads Lookups containing the rate bytes of the block of the preimage
+    /// - if is_absorb, adds 136 lookups for Keccak-256
     /// - otherwise, adds 0 lookups
     // TODO: optimize this by using a single lookup reusing PadSuffix
     fn lookup_syscall_preimage(&mut self, step: Steps) {
-        for i in 0..RATE_IN_BYTES {
+        let rate = self.variant().rate_in_bytes();
+        for i in 0..rate {
             self.read_syscall(
                 self.is_absorb(step),
                 vec![
                     self.hash_index(),
-                    self.block_index() * Self::constant(RATE_IN_BYTES as u64)
-                        + Self::constant(i as u64),
+                    self.block_index() * Self::constant(rate as u64) + Self::constant(i as u64),
                     self.sponge_byte(i),
                 ],
             );
@@ -533,20 +551,13 @@ where
     /// - 1 extra lookup if is_pad()
     fn lookups_sponge(&mut self, step: Steps) {
         // PADDING LOOKUPS
+        // The entries of the padding of the variant start at its offset
         // Power of two corresponds to 2^pad_length
-        // Pad suffixes correspond to 10*1 rule
-        self.lookup_pad(
-            self.is_pad(step),
-            vec![
-                self.pad_length(),
-                self.two_to_pad(),
-                self.pad_suffix(0),
-                self.pad_suffix(1),
-                self.pad_suffix(2),
-                self.pad_suffix(3),
-                self.pad_suffix(4),
-            ],
-        );
+        // Pad suffixes correspond to the domain separation bits and 10*1 rule
+        let offset = Self::constant(self.variant().pad_table_offset() as u64);
+        let mut pad = vec![offset + self.pad_length(), self.two_to_pad()];
+        pad.extend((0..PAD_SUFFIX_LEN).map(|i| self.pad_suffix(i)));
+        self.lookup_pad(self.is_pad(step), pad);
         // BYTES LOOKUPS
         // Checking the 200 bytes of the absorb phase together with the length
         // bytes is performed in the SyscallReadPreimage rows (4 byte lookups
@@ -831,7 +842,7 @@ where
     }
 
     /// Returns a variable that encodes the bytelength of the padding if any
-    /// [0..136)
+    /// [1..rate]
     fn pad_length(&self) -> Self::Variable {
         self.variable(KeccakColumn::PadLength)
     }
@@ -848,39 +859,29 @@ where
     }
 
     /// Returns a variable that encodes the `idx`-th chunk of the padding suffix
-    /// - if `idx` = 0, then the length is 12 bytes at most
-    /// - if `idx` = [1..5), then the length is 31 bytes at most
+    /// - if `idx` = 0, then the length is 13 bytes at most
+    /// - if `idx` = [1..6), then the length is 31 bytes at most
     fn pad_suffix(&self, idx: usize) -> Self::Variable {
         self.variable(KeccakColumn::PadSuffix(idx))
     }
 
     /// Returns a variable that encodes the `idx`-th block of bytes of the new block
-    /// by composing the bytes variables, with `idx` in [0..5)
+    /// by composing the bytes variables, with `idx` in [0..6)
     fn bytes_block(&self, idx: usize) -> Vec<Self::Variable> {
-        let sponge_bytes = self.sponge_bytes();
-        match idx {
-            0 => sponge_bytes[0..12].to_vec(),
-            1..=4 => sponge_bytes[12 + (idx - 1) * 31..12 + idx * 31].to_vec(),
-            _ => panic!("No more blocks of bytes can be part of padding"),
-        }
+        self.sponge_bytes()[pad_chunk(idx)].to_vec()
     }
 
-    /// Returns the 136 flags indicating which bytes of the new block are
+    /// Returns the 168 flags indicating which bytes of the new block are
     /// involved in the padding, as variables
     fn pad_bytes_flags(&self) -> [Self::Variable; PAD_BYTES_LEN] {
         array::from_fn(|idx| self.variable(KeccakColumn::PadBytesFlags(idx)))
     }
 
-    /// Returns a vector of pad bytes flags as variables, with `idx` in [0..5)
-    /// - if `idx` = 0, then the length of the block is at most 12
-    /// - if `idx` = [1..5), then the length of the block is at most 31
+    /// Returns a vector of pad bytes flags as variables, with `idx` in [0..6)
+    /// - if `idx` = 0, then the length of the block is at most 13
+    /// - if `idx` = [1..6), then the length of the block is at most 31
     fn flags_block(&self, idx: usize) -> Vec<Self::Variable> {
-        let pad_bytes_flags = self.pad_bytes_flags();
-        match idx {
-            0 => pad_bytes_flags[0..12].to_vec(),
-            1..=4 => pad_bytes_flags[12 + (idx - 1) * 31..12 + idx * 31].to_vec(),
-            _ => panic!("No more blocks of flags can be part of padding"),
-        }
+        self.pad_bytes_flags()[pad_chunk(idx)].to_vec()
     }
 
     /// This function returns a degree-2 variable that is computed as the
@@ -924,12 +925,6 @@ where
         self.variable(KeccakColumn::Output(idx))
     }
 
-    /// Returns the last 32 terms that are added to the new block in an absorb
-    /// sponge, as variables which should be zeros
-    fn sponge_zeros(&self) -> [Self::Variable; SPONGE_ZEROS_LEN] {
-        array::from_fn(|idx| self.variable(KeccakColumn::SpongeZeros(idx)))
-    }
-
     /// Returns the 400 terms that compose the shifts of the sponge, as variables
     fn vec_sponge_shifts(&self) -> [Self::Variable; SPONGE_SHIFTS_LEN] {
         array::from_fn(|idx| self.variable(KeccakColumn::SpongeShifts(idx)))
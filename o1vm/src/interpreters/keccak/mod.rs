@@ -1,35 +1,33 @@
 use crate::{
-    interpreters::keccak::column::{ColumnAlias as KeccakColumn, Steps::*, PAD_SUFFIX_LEN},
+    interpreters::keccak::column::{
+        ColumnAlias as KeccakColumn, Steps::*, PAD_BYTES_LEN, PAD_SUFFIX_LEN,
+    },
     lookups::LookupTableIDs,
 };
 use ark_ff::Field;
-use kimchi::circuits::polynomials::keccak::constants::{
-    DIM, KECCAK_COLS, QUARTERS, RATE_IN_BYTES, STATE_LEN,
-};
+use kimchi::circuits::polynomials::keccak::constants::{DIM, KECCAK_COLS, QUARTERS, STATE_LEN};
+use std::{array, ops::Range};
 
 pub mod column;
 pub mod constraints;
 pub mod environment;
 pub mod helpers;
 pub mod interpreter;
+pub mod precompile;
 #[cfg(test)]
 pub mod tests;
+pub mod variant;
 pub mod witness;
 
 pub use column::{Absorbs, Sponges, Steps};
+pub use variant::KeccakVariant;
 
-/// Desired output length of the hash in bits
-pub(crate) const HASH_BITLENGTH: usize = 256;
-/// Desired output length of the hash in bytes
-pub(crate) const HASH_BYTELENGTH: usize = HASH_BITLENGTH / 8;
 /// Length of each word in the Keccak state, in bits
 pub(crate) const WORD_LENGTH_IN_BITS: usize = 64;
 /// Number of columns required in the `curr` part of the witness
 pub(crate) const ZKVM_KECCAK_COLS_CURR: usize = KECCAK_COLS;
 /// Number of columns required in the `next` part of the witness, corresponding to the output length
 pub(crate) const ZKVM_KECCAK_COLS_NEXT: usize = STATE_LEN;
-/// Number of words that fit in the hash digest
-pub(crate) const WORDS_IN_HASH: usize = HASH_BITLENGTH / WORD_LENGTH_IN_BITS;
 
 /// Errors that can occur during the check of the witness
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -87,30 +85,36 @@ fn grid_index(length: usize, i: usize, y: usize, x: usize, q: usize) -> usize {
     }
 }
 
-/// This function returns a vector of field elements that represent the 5 padding suffixes.
-/// The first one uses at most 12 bytes, and the rest use at most 31 bytes.
-pub fn pad_blocks<F: Field>(pad_bytelength: usize) -> [F; PAD_SUFFIX_LEN] {
+/// Returns the range of the bytes of the block that are composed in the
+/// `idx`-th chunk of the padding suffix, with `idx` in [0..6). The first chunk
+/// uses 13 bytes, and the rest use 31 bytes, so that the chunks cover the
+/// largest rate of the variants.
+pub(crate) fn pad_chunk(idx: usize) -> Range<usize> {
+    match idx {
+        0 => 0..13,
+        1..=5 => 13 + (idx - 1) * 31..13 + idx * 31,
+        _ => panic!("No more chunks can be part of padding"),
+    }
+}
+
+/// This function returns a vector of field elements that represent the 6 padding suffixes
+/// of a padding of `pad_bytelength` bytes for the hash function `variant`. The padding is
+/// located at the end of the rate of the variant, and the suffixes are laid out over the
+/// largest rate, as given by [pad_chunk].
+pub fn pad_blocks<F: Field>(variant: KeccakVariant, pad_bytelength: usize) -> [F; PAD_SUFFIX_LEN] {
+    let rate = variant.rate_in_bytes();
     assert!(pad_bytelength > 0, "Padding length must be at least 1 byte");
     assert!(
-        pad_bytelength <= 136,
-        "Padding length must be at most 136 bytes",
+        pad_bytelength <= rate,
+        "Padding length must be at most {rate} bytes",
     );
-    // Blocks to store padding. The first one uses at most 12 bytes, and the rest use at most 31 bytes.
-    let mut blocks = [F::zero(); PAD_SUFFIX_LEN];
-    let mut pad = [F::zero(); RATE_IN_BYTES];
-    pad[RATE_IN_BYTES - pad_bytelength] = F::one();
-    pad[RATE_IN_BYTES - 1] += F::from(0x80u8);
-    blocks[0] = pad
-        .iter()
-        .take(12)
-        .fold(F::zero(), |acc, x| acc * F::from(256u32) + *x);
-    for (i, block) in blocks.iter_mut().enumerate().take(5).skip(1) {
-        // take 31 elements from pad, starting at 12 + (i - 1) * 31 and fold them into a single Fp
-        *block = pad
+    let mut pad = [F::zero(); PAD_BYTES_LEN];
+    pad[rate - pad_bytelength] = F::from(variant.domain_suffix());
+    pad[rate - 1] += F::from(0x80u8);
+    // Fold the bytes of each chunk into a single field element, in big endian
+    array::from_fn(|i| {
+        pad[pad_chunk(i)]
             .iter()
-            .skip(12 + (i - 1) * 31)
-            .take(31)
-            .fold(F::zero(), |acc, x| acc * F::from(256u32) + *x);
-    }
-    blocks
+            .fold(F::zero(), |acc, x| acc * F::from(256u32) + *x)
+    })
 }
@@ -0,0 +1,102 @@
+//! The Keccak precompile: a system call hashing the memory of the guest with
+//! Keccak-256, whose execution is delegated to the Keccak interpreter instead
+//! of running the hash function instruction by instruction.
+//!
+//! The system call `keccak256(addr, len, output)` absorbs at most 4 bytes of
+//! the input at each execution, updating `addr` and `len` in place and leaving
+//! the instruction pointer unchanged, so that it is executed again until the
+//! whole input is absorbed. The last execution, with `len` equal to 0, writes
+//! the digest at the address `output` and moves to the next instruction.
+//!
+//! As for the preimages, the bytes absorbed are written to the
+//! [crate::lookups::LookupTableIDs::SyscallLookup] table, indexed by the hash
+//! counter and their position in the input, and read by the Keccak sub-trace,
+//! which writes back the digest, read by the last execution.
+
+use ark_ff::Field;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+
+/// The number of the system call, outside of the range of the numbers of the
+/// Linux system calls of the MIPS and RISC-V architectures
+pub const SYSCALL_KECCAK256: u32 = 0x10000;
+
+/// The maximal number of bytes absorbed by an execution of the system call
+pub const KECCAK_PRECOMPILE_CHUNK_LEN: usize = 4;
+
+/// The values given by the witness environment to bind an execution of the
+/// system call to the Keccak sub-trace
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeccakHint {
+    /// The index of the hash, shared with the preimages
+    HashCounter,
+    /// The number of bytes absorbed by the previous executions
+    ByteCounter,
+    /// The i-th byte absorbed by the execution, or 0
+    Byte(usize),
+    /// Whether at least `i + 1` bytes are absorbed by the execution
+    HasByte(usize),
+    /// The digest without its most significant byte, as for the preimage keys
+    Digest,
+}
+
+/// The state of the current call to the Keccak precompile
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeccakPrecompile {
+    /// The input absorbed so far
+    pub input: Vec<u8>,
+    /// The index of the hash computed by the call
+    pub hash_index: u64,
+    /// The bytes absorbed by the last execution
+    #[serde(skip)]
+    pub absorbed: Vec<u8>,
+    /// The digest written by the last execution, if it ended the call
+    #[serde(skip)]
+    pub digest: Option<[u8; 32]>,
+}
+
+impl KeccakPrecompile {
+    /// Absorbs the bytes `bytes` in the input of the hash of index
+    /// `hash_index`
+    pub fn absorb(&mut self, hash_index: u64, bytes: &[u8]) {
+        assert!(bytes.len() <= KECCAK_PRECOMPILE_CHUNK_LEN);
+        self.hash_index = hash_index;
+        self.absorbed = bytes.to_vec();
+        self.input.extend_from_slice(bytes);
+        self.digest = None;
+    }
+
+    /// Ends the call, returning its input and its digest
+    pub fn finalize(&mut self, hash_index: u64) -> (Vec<u8>, [u8; 32]) {
+        self.hash_index = hash_index;
+        self.absorbed.clear();
+        let input = std::mem::take(&mut self.input);
+        let digest: [u8; 32] = Keccak256::digest(&input).into();
+        self.digest = Some(digest);
+        (input, digest)
+    }
+
+    /// Returns the value of the hint `hint` for the last execution, or 0 for
+    /// the digest, which does not fit in 64 bits
+    pub fn hint_u64(&self, hint: KeccakHint) -> u64 {
+        match hint {
+            KeccakHint::HashCounter => self.hash_index,
+            KeccakHint::ByteCounter => (self.input.len() - self.absorbed.len()) as u64,
+            KeccakHint::Byte(i) => self.absorbed.get(i).copied().unwrap_or(0) as u64,
+            KeccakHint::HasByte(i) => (i < self.absorbed.len()) as u64,
+            KeccakHint::Digest => 0,
+        }
+    }
+
+    /// Returns the value of the hint `hint` for the last execution
+    pub fn hint<F: Field>(&self, hint: KeccakHint) -> F {
+        match hint {
+            KeccakHint::Digest => self.digest.map_or(F::zero(), |digest| {
+                digest[1..].iter().fold(F::zero(), |acc, byte| {
+                    acc * F::from(256u64) + F::from(*byte)
+                })
+            }),
+            _ => F::from(self.hint_u64(hint)),
+        }
+    }
+}
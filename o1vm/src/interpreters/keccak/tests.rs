@@ -3,8 +3,9 @@ use crate::{
         column::{Absorbs::*, Sponges::*, Steps::*},
         environment::KeccakEnv,
         interpreter::KeccakInterpreter,
+        pad_blocks,
         Constraint::*,
-        Error, KeccakColumn,
+        Error, KeccakColumn, KeccakVariant,
     },
     lookups::{FixedLookupTables, LookupTable, LookupTableIDs::*},
 };
@@ -15,7 +16,10 @@ use kimchi::{
     o1_utils::{self, FieldHelpers, Two},
 };
 use rand::Rng;
-use sha3::{Digest, Keccak256};
+use sha3::{
+    digest::{ExtendableOutput, XofReader},
+    Digest, Keccak256, Sha3_256, Sha3_512, Shake128, Shake256,
+};
 use std::collections::HashMap;
 
 // FIXME: we should check with other fields too
@@ -23,19 +27,37 @@ use ark_bn254::Fr as Fp;
 
 #[test]
 fn test_pad_blocks() {
-    let blocks_1 = crate::interpreters::keccak::pad_blocks::<Fp>(1);
+    let blocks_1 = pad_blocks::<Fp>(KeccakVariant::Keccak256, 1);
     assert_eq!(blocks_1[0], Fp::from(0x00));
     assert_eq!(blocks_1[1], Fp::from(0x00));
     assert_eq!(blocks_1[2], Fp::from(0x00));
     assert_eq!(blocks_1[3], Fp::from(0x00));
-    assert_eq!(blocks_1[4], Fp::from(0x81));
+    assert_eq!(blocks_1[4], Fp::from(0x8100));
+    assert_eq!(blocks_1[5], Fp::from(0x00));
 
-    let blocks_136 = crate::interpreters::keccak::pad_blocks::<Fp>(136);
-    assert_eq!(blocks_136[0], Fp::from(0x010000000000000000000000u128));
+    let blocks_136 = pad_blocks::<Fp>(KeccakVariant::Keccak256, 136);
+    assert_eq!(blocks_136[0], Fp::from(0x01000000000000000000000000u128));
     assert_eq!(blocks_136[1], Fp::from(0x00));
     assert_eq!(blocks_136[2], Fp::from(0x00));
     assert_eq!(blocks_136[3], Fp::from(0x00));
-    assert_eq!(blocks_136[4], Fp::from(0x80));
+    assert_eq!(blocks_136[4], Fp::from(0x8000));
+    assert_eq!(blocks_136[5], Fp::from(0x00));
+
+    // The padding of SHA3 starts with its domain separation bits
+    let blocks_sha3 = pad_blocks::<Fp>(KeccakVariant::Sha3_256, 1);
+    assert_eq!(blocks_sha3[4], Fp::from(0x8600));
+
+    // The padding of SHAKE128 ends at the last byte of its rate of 168 bytes
+    let blocks_shake_1 = pad_blocks::<Fp>(KeccakVariant::Shake128(32), 1);
+    assert_eq!(blocks_shake_1[4], Fp::from(0x00));
+    assert_eq!(blocks_shake_1[5], Fp::from(0x9f));
+
+    let blocks_shake_168 = pad_blocks::<Fp>(KeccakVariant::Shake128(32), 168);
+    assert_eq!(
+        blocks_shake_168[0],
+        Fp::from(0x1f000000000000000000000000u128)
+    );
+    assert_eq!(blocks_shake_168[5], Fp::from(0x80));
 }
 
 #[test]
@@ -56,16 +78,36 @@ fn test_is_in_table() {
             Fp::zero(),
             Fp::zero(),
             Fp::zero(),
-            Fp::from(0x81) // Least significant chunk of padding suffix
+            Fp::from(0x8100),
+            Fp::zero() // Least significant chunk of padding suffix
+        ]
+    )
+    .is_some());
+    assert!(LookupTable::is_in_table(
+        &table_pad,
+        vec![
+            Fp::from(136),                              // Length of padding
+            Fp::two_pow(136),                           // 2^length of padding
+            Fp::from(0x01000000000000000000000000u128), // Most significant chunk of padding suffix
+            Fp::zero(),
+            Fp::zero(),
+            Fp::zero(),
+            Fp::from(0x8000),
+            Fp::zero() // Least significant chunk of padding suffix
         ]
     )
     .is_some());
+    // The paddings of SHAKE128 come after the ones of Keccak-256, SHA3-256
+    // and SHA3-512
+    let shake128 = KeccakVariant::Shake128(32);
+    assert_eq!(shake128.pad_table_offset(), 136 + 136 + 72);
     assert!(LookupTable::is_in_table(
         &table_pad,
         vec![
-            Fp::from(136),                            // Length of padding
-            Fp::two_pow(136),                         // 2^length of padding
-            Fp::from(0x010000000000000000000000u128), // Most significant chunk of padding suffix
+            Fp::from((shake128.pad_table_offset() + 168) as u64), // Offset and length of padding
+            Fp::two_pow(168),                                     // 2^length of padding
+            Fp::from(0x1f000000000000000000000000u128), // Most significant chunk of padding suffix
+            Fp::zero(),
             Fp::zero(),
             Fp::zero(),
             Fp::zero(),
@@ -73,7 +115,26 @@ fn test_is_in_table() {
         ]
     )
     .is_some());
-    assert!(LookupTable::is_in_table(&table_pad, vec![Fp::from(137u32)]).is_none());
+    // The padding of Keccak-256 is not the one of SHA3-256
+    assert!(LookupTable::is_in_table(
+        &table_pad,
+        vec![
+            Fp::from((KeccakVariant::Sha3_256.pad_table_offset() + 1) as u64),
+            Fp::two_pow(1),
+            Fp::zero(),
+            Fp::zero(),
+            Fp::zero(),
+            Fp::zero(),
+            Fp::from(0x8100),
+            Fp::zero()
+        ]
+    )
+    .is_none());
+    assert!(LookupTable::is_in_table(
+        &table_pad,
+        vec![Fp::from((KeccakVariant::pad_table_length() + 1) as u64)]
+    )
+    .is_none());
     // RoundConstantsLookup
     assert!(LookupTable::is_in_table(
         &table_round_constants,
@@ -162,6 +223,96 @@ fn test_keccak_witness_satisfies_constraints() {
     }
 }
 
+#[test]
+fn test_keccak_variants() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+
+    fn shake<H: Default + sha3::digest::Update + ExtendableOutput>(
+        preimage: &[u8],
+        len: usize,
+    ) -> Vec<u8> {
+        let mut hasher = H::default();
+        hasher.update(preimage);
+        let mut output = vec![0; len];
+        hasher.finalize_xof().read(&mut output);
+        output
+    }
+
+    for _ in 0..4 {
+        let bytelength = rng.gen_range(0..500);
+        let preimage: Vec<u8> = (0..bytelength).map(|_| rng.gen()).collect();
+        // The outputs of the extendable-output functions span several
+        // squeezes
+        let len = rng.gen_range(1..500);
+        let variants = [
+            (
+                KeccakVariant::Keccak256,
+                Keccak256::digest(&preimage).to_vec(),
+            ),
+            (
+                KeccakVariant::Sha3_256,
+                Sha3_256::digest(&preimage).to_vec(),
+            ),
+            (
+                KeccakVariant::Sha3_512,
+                Sha3_512::digest(&preimage).to_vec(),
+            ),
+            (
+                KeccakVariant::Shake128(len),
+                shake::<Shake128>(&preimage, len),
+            ),
+            (
+                KeccakVariant::Shake256(len),
+                shake::<Shake256>(&preimage, len),
+            ),
+        ];
+        for (variant, expected) in variants {
+            let mut keccak_env = KeccakEnv::<Fp>::new_with_variant(0, &preimage, variant);
+            let mut squeezes = 0;
+            while keccak_env.step.is_some() {
+                let step = keccak_env.step.unwrap();
+                if step == Sponge(Squeeze) {
+                    squeezes += 1;
+                }
+                keccak_env.step();
+                // The witness of each variant satisfies its constraints and
+                // its lookups in the fixed tables
+                keccak_env.witness_env.constraints(step);
+                keccak_env.witness_env.lookups(step);
+                assert!(
+                    keccak_env.witness_env.errors.is_empty(),
+                    "{variant:?} {step:?}: {:?}",
+                    keccak_env.witness_env.errors
+                );
+            }
+            assert_eq!(keccak_env.output(), expected, "{variant:?}");
+            assert_eq!(squeezes, variant.num_squeezes());
+        }
+    }
+}
+
+#[test]
+fn test_keccak_variant_padding_is_bound() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+
+    let preimage: Vec<u8> = (0..100).map(|_| rng.gen()).collect();
+
+    // The witness of SHA3-256 has the rate of Keccak-256 but not its padding,
+    // so it does not satisfy the pad lookup of a Keccak-256 circuit
+    let mut keccak_env = KeccakEnv::<Fp>::new_with_variant(0, &preimage, KeccakVariant::Sha3_256);
+    let step = keccak_env.step.unwrap();
+    assert_eq!(step, Sponge(Absorb(Only)));
+    keccak_env.step();
+    keccak_env.witness_env.lookups(step);
+    assert!(keccak_env.witness_env.errors.is_empty());
+    keccak_env.witness_env.variant = KeccakVariant::Keccak256;
+    keccak_env.witness_env.lookups(step);
+    assert_eq!(
+        keccak_env.witness_env.errors,
+        vec![Error::Lookup(PadLookup)]
+    );
+}
+
 #[test]
 fn test_regression_number_of_lookups_and_constraints_and_degree() {
     let mut rng = o1_utils::tests::make_test_rng(None);
@@ -218,23 +369,23 @@ fn test_regression_number_of_lookups_and_constraints_and_degree() {
             }
             Sponge(Absorb(Last)) => {
                 assert_eq!(keccak_env.constraints_env.lookups.len(), 539);
-                assert_eq!(keccak_env.constraints_env.constraints.len(), 374);
+                assert_eq!(keccak_env.constraints_env.constraints.len(), 407);
                 // We have 2 different degrees of constraints in Squeeze
                 assert_eq!(constraint_degrees.len(), 2);
                 // 233 degree-1 constraints
                 assert_eq!(constraint_degrees[&1], 233);
-                // 136 degree-2 constraints
-                assert_eq!(constraint_degrees[&2], 141);
+                // 174 degree-2 constraints
+                assert_eq!(constraint_degrees[&2], 174);
             }
             Sponge(Absorb(Only)) => {
                 assert_eq!(keccak_env.constraints_env.lookups.len(), 538);
-                assert_eq!(keccak_env.constraints_env.constraints.len(), 474);
+                assert_eq!(keccak_env.constraints_env.constraints.len(), 507);
                 // We have 2 different degrees of constraints in Squeeze
                 assert_eq!(constraint_degrees.len(), 2);
                 // 333 degree-1 constraints
                 assert_eq!(constraint_degrees[&1], 333);
-                // 136 degree-2 constraints
-                assert_eq!(constraint_degrees[&2], 141);
+                // 174 degree-2 constraints
+                assert_eq!(constraint_degrees[&2], 174);
             }
             Sponge(Squeeze) => {
                 assert_eq!(keccak_env.constraints_env.lookups.len(), 602);
@@ -262,6 +413,44 @@ fn test_regression_number_of_lookups_and_constraints_and_degree() {
     }
 }
 
+#[test]
+fn test_keccak_batch_of_messages() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+
+    // Small messages, as the nodes of a Merkle tree, and a longer one
+    let mut messages: Vec<Vec<u8>> = (0..4)
+        .map(|_| (0..64).map(|_| rng.gen()).collect())
+        .collect();
+    messages.push((0..300).map(|_| rng.gen()).collect());
+    let slices: Vec<&[u8]> = messages.iter().map(|message| message.as_slice()).collect();
+
+    let mut keccak_env = KeccakEnv::<Fp>::new_batch(7, &slices, KeccakVariant::Keccak256);
+    let mut hash_indices = vec![];
+    while keccak_env.step.is_some() {
+        let step = keccak_env.step.unwrap();
+        keccak_env.step();
+        keccak_env.witness_env.constraints(step);
+        keccak_env.witness_env.lookups(step);
+        assert!(keccak_env.witness_env.errors.is_empty());
+        // Each message starts from the root state, with its own hash index
+        let hash_index = keccak_env.witness_env.witness[KeccakColumn::HashIndex];
+        if step == Sponge(Absorb(First)) || step == Sponge(Absorb(Only)) {
+            assert_eq!(
+                keccak_env.witness_env.witness[KeccakColumn::StepIndex],
+                Fp::zero()
+            );
+            hash_indices.push(hash_index);
+        }
+        assert_eq!(Some(&hash_index), hash_indices.last());
+    }
+    assert_eq!(hash_indices, (7..12u64).map(Fp::from).collect::<Vec<_>>());
+    let digests: Vec<Vec<u8>> = messages
+        .iter()
+        .map(|message| Keccak256::digest(message).to_vec())
+        .collect();
+    assert_eq!(keccak_env.outputs(), digests);
+}
+
 #[test]
 fn test_keccak_witness_satisfies_lookups() {
     let mut rng = o1_utils::tests::make_test_rng(None);
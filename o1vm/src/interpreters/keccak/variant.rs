@@ -0,0 +1,137 @@
+//! The members of the Keccak family of hash functions that the interpreter can
+//! compute. They share the `Keccak-f[1600]` permutation, and differ in the
+//! number of bytes absorbed and squeezed by the sponge at each permutation
+//! (the rate), in the domain separation bits appended to the message before
+//! the 10*1 padding, and in the length of their output.
+
+use crate::interpreters::keccak::WORD_LENGTH_IN_BITS;
+use kimchi::circuits::polynomials::keccak::constants::{CAPACITY_IN_BYTES, RATE_IN_BYTES};
+
+/// The largest rate of the variants, the one of SHAKE128, in bytes
+pub const MAX_RATE_IN_BYTES: usize = 168;
+
+/// One variant for each of the paddings (pairs of rate and domain separation
+/// bits), in the order of their entries in the pad table
+const PADDINGS: [KeccakVariant; 5] = [
+    KeccakVariant::Keccak256,
+    KeccakVariant::Sha3_256,
+    KeccakVariant::Sha3_512,
+    KeccakVariant::Shake128(0),
+    KeccakVariant::Shake256(0),
+];
+
+/// A member of the Keccak family of hash functions
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum KeccakVariant {
+    /// Keccak-256, as used by Ethereum, with the padding of the original
+    /// Keccak submission
+    #[default]
+    Keccak256,
+    /// SHA3-256, as standardized in FIPS 202
+    Sha3_256,
+    /// SHA3-512, as standardized in FIPS 202
+    Sha3_512,
+    /// The extendable-output function SHAKE128, squeezing the given number of
+    /// bytes
+    Shake128(usize),
+    /// The extendable-output function SHAKE256, squeezing the given number of
+    /// bytes
+    Shake256(usize),
+}
+
+impl KeccakVariant {
+    /// The number of bytes absorbed, or squeezed, between two permutations
+    pub fn rate_in_bytes(&self) -> usize {
+        match self {
+            KeccakVariant::Keccak256 | KeccakVariant::Sha3_256 | KeccakVariant::Shake256(_) => {
+                RATE_IN_BYTES
+            }
+            KeccakVariant::Sha3_512 => 72,
+            KeccakVariant::Shake128(_) => 168,
+        }
+    }
+
+    /// The number of bytes of the state that are never absorbed nor squeezed
+    pub fn capacity_in_bytes(&self) -> usize {
+        RATE_IN_BYTES + CAPACITY_IN_BYTES - self.rate_in_bytes()
+    }
+
+    /// The first byte of the padding, made of the domain separation bits
+    /// followed by the first bit of the 10*1 rule, least significant bit
+    /// first
+    pub fn domain_suffix(&self) -> u8 {
+        match self {
+            KeccakVariant::Keccak256 => 0x01,
+            KeccakVariant::Sha3_256 | KeccakVariant::Sha3_512 => 0x06,
+            KeccakVariant::Shake128(_) | KeccakVariant::Shake256(_) => 0x1f,
+        }
+    }
+
+    /// The number of bytes of the output
+    pub fn output_bytelength(&self) -> usize {
+        match self {
+            KeccakVariant::Keccak256 | KeccakVariant::Sha3_256 => 32,
+            KeccakVariant::Sha3_512 => 64,
+            KeccakVariant::Shake128(len) | KeccakVariant::Shake256(len) => *len,
+        }
+    }
+
+    /// The number of words of the state that are squeezed at once. They cover
+    /// the output, of which the last squeeze of an extendable-output function
+    /// may only use a prefix.
+    pub fn squeeze_words(&self) -> usize {
+        self.output_bytelength()
+            .min(self.rate_in_bytes())
+            .div_ceil(WORD_LENGTH_IN_BITS / 8)
+    }
+
+    /// The offset of the entries of the padding of the variant in the pad
+    /// table, which is shared by all the variants. The entry of a padding of
+    /// `len` bytes is at index `pad_table_offset() + len - 1`.
+    pub fn pad_table_offset(&self) -> usize {
+        PADDINGS
+            .iter()
+            .take_while(|variant| {
+                (variant.rate_in_bytes(), variant.domain_suffix())
+                    != (self.rate_in_bytes(), self.domain_suffix())
+            })
+            .map(KeccakVariant::rate_in_bytes)
+            .sum()
+    }
+
+    /// The number of entries of the pad table, one per padding length of each
+    /// of the paddings
+    pub fn pad_table_length() -> usize {
+        PADDINGS.iter().map(KeccakVariant::rate_in_bytes).sum()
+    }
+
+    /// One variant for each of the paddings, in the order of their entries in
+    /// the pad table
+    pub fn paddings() -> [KeccakVariant; 5] {
+        PADDINGS
+    }
+
+    /// Number of blocks to be absorbed on input a given preimage bytelength
+    pub fn num_blocks(&self, bytelength: usize) -> usize {
+        bytelength / self.rate_in_bytes() + 1
+    }
+
+    /// Number of squeezes needed to output the digest, with a permutation
+    /// between two of them
+    pub fn num_squeezes(&self) -> usize {
+        self.output_bytelength()
+            .div_ceil(self.rate_in_bytes())
+            .max(1)
+    }
+
+    /// Pads the message `message` to a multiple of the rate, with the domain
+    /// separation bits and the 10*1 rule
+    pub fn pad(&self, message: &[u8]) -> Vec<u8> {
+        let padded_len = self.num_blocks(message.len()) * self.rate_in_bytes();
+        let mut padded = vec![0; padded_len];
+        padded[..message.len()].copy_from_slice(message);
+        padded[message.len()] = self.domain_suffix();
+        padded[padded_len - 1] |= 0x80;
+        padded
+    }
+}
@@ -13,7 +13,7 @@ use crate::{
         column::KeccakWitness,
         helpers::{ArithHelpers, BoolHelpers, LogupHelpers},
         interpreter::{Interpreter, KeccakInterpreter},
-        Constraint, Error, KeccakColumn,
+        Constraint, Error, KeccakColumn, KeccakVariant,
     },
     lookups::{
         FixedLookupTables, Lookup, LookupTable,
@@ -37,6 +37,8 @@ pub struct Env<F> {
     pub multiplicities: HashMap<LookupTableIDs, Vec<u32>>,
     /// If any, an error that occurred during the execution of the constraints, to help with debugging
     pub(crate) errors: Vec<Error>,
+    /// The hash function computed by the witness
+    pub variant: KeccakVariant,
 }
 
 impl<F: Field> Default for Env<F> {
@@ -66,6 +68,7 @@ impl<F: Field> Default for Env<F> {
                 m
             },
             errors: vec![],
+            variant: KeccakVariant::default(),
         }
     }
 }
@@ -121,4 +124,8 @@ impl<F: Field> Interpreter<F> for Env<F> {
     }
 }
 
-impl<F: Field> KeccakInterpreter<F> for Env<F> {}
+impl<F: Field> KeccakInterpreter<F> for Env<F> {
+    fn variant(&self) -> KeccakVariant {
+        self.variant
+    }
+}
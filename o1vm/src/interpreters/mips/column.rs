@@ -6,7 +6,11 @@ use kimchi_msm::{
     columns::{Column, ColumnIndexer},
     witness::Witness,
 };
-use std::ops::{Index, IndexMut};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    ops::{Index, IndexMut},
+};
 use strum::EnumCount;
 
 use super::{ITypeInstruction, JTypeInstruction, RTypeInstruction};
@@ -54,6 +58,140 @@ pub const N_MIPS_SEL_COLS: usize =
 /// All the witness columns used in MIPS
 pub const N_MIPS_COLS: usize = N_MIPS_REL_COLS + N_MIPS_SEL_COLS;
 
+/// The number of values of the low part of the index of an instruction, when
+/// the selectors are packed, see [SelectorLayout::Packed].
+pub const N_PACKED_SEL_LOW_COLS: usize = 8;
+
+/// The number of values of the high part of the index of an instruction, when
+/// the selectors are packed, see [SelectorLayout::Packed].
+pub const N_PACKED_SEL_HIGH_COLS: usize = N_MIPS_SEL_COLS.div_ceil(N_PACKED_SEL_LOW_COLS);
+
+/// The layout of the instruction selectors in the committed columns. The
+/// witness only records the index of the instruction of each row, from which
+/// the selector columns are built by the prover.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SelectorLayout {
+    /// One boolean column per instruction, see [N_MIPS_SEL_COLS].
+    #[default]
+    OneHot,
+    /// The index of the instruction is split into its quotient and its
+    /// remainder by [N_PACKED_SEL_LOW_COLS], each part being encoded by one
+    /// boolean column per value. The selector of an instruction
+    /// is the product of the columns of its two parts, so that far fewer
+    /// columns are committed to, at the cost of one more degree for the
+    /// constraints.
+    Packed,
+}
+
+impl SelectorLayout {
+    /// Returns the number of selector columns
+    pub fn num_columns(self) -> usize {
+        match self {
+            SelectorLayout::OneHot => N_MIPS_SEL_COLS,
+            SelectorLayout::Packed => N_PACKED_SEL_HIGH_COLS + N_PACKED_SEL_LOW_COLS,
+        }
+    }
+
+    /// Returns the selector columns set to one for the instruction of index
+    /// `i`, the product of which is the selector of the instruction
+    pub fn columns(self, i: usize) -> Vec<usize> {
+        assert!(
+            i < N_MIPS_SEL_COLS,
+            "The maximum index is {}, got {}",
+            N_MIPS_SEL_COLS,
+            i
+        );
+        match self {
+            SelectorLayout::OneHot => vec![i],
+            SelectorLayout::Packed => vec![
+                i / N_PACKED_SEL_LOW_COLS,
+                N_PACKED_SEL_HIGH_COLS + i % N_PACKED_SEL_LOW_COLS,
+            ],
+        }
+    }
+
+    /// Returns the pairs of selector columns which select no instruction, as
+    /// the column of the last high part and the columns of the low parts which
+    /// complete it above [N_MIPS_SEL_COLS], when the selectors are packed and
+    /// the number of instructions is not a multiple of [N_PACKED_SEL_LOW_COLS]
+    pub fn unused_pairs(self) -> Option<(usize, std::ops::Range<usize>)> {
+        let used_low = N_MIPS_SEL_COLS % N_PACKED_SEL_LOW_COLS;
+        match self {
+            SelectorLayout::OneHot => None,
+            SelectorLayout::Packed => (used_low != 0).then_some((
+                N_PACKED_SEL_HIGH_COLS - 1,
+                N_PACKED_SEL_HIGH_COLS + used_low..N_PACKED_SEL_HIGH_COLS + N_PACKED_SEL_LOW_COLS,
+            )),
+        }
+    }
+
+    /// Returns the groups of selector columns of which exactly one is set to
+    /// one on each row
+    #[allow(clippy::single_range_in_vec_init)]
+    pub fn groups(self) -> Vec<std::ops::Range<usize>> {
+        match self {
+            SelectorLayout::OneHot => vec![0..N_MIPS_SEL_COLS],
+            SelectorLayout::Packed => vec![
+                0..N_PACKED_SEL_HIGH_COLS,
+                N_PACKED_SEL_HIGH_COLS..N_PACKED_SEL_HIGH_COLS + N_PACKED_SEL_LOW_COLS,
+            ],
+        }
+    }
+}
+
+/// The number of scratch columns of each kind used by the rows of an
+/// instruction
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScratchWidth {
+    pub scratch: usize,
+    pub scratch_inverse: usize,
+}
+
+impl ScratchWidth {
+    /// Returns the width covering both `self` and `other`
+    pub fn max(self, other: Self) -> Self {
+        ScratchWidth {
+            scratch: self.scratch.max(other.scratch),
+            scratch_inverse: self.scratch_inverse.max(other.scratch_inverse),
+        }
+    }
+}
+
+/// The scratch columns used by each instruction, derived from the columns
+/// allocated by the constraints of the instructions, see
+/// [crate::interpreters::mips::constraints::scratch_layout].
+/// The witness environment only allocates the columns of the instruction it
+/// executes, and the rows are padded with zeros to the width of the trace,
+/// which is the one of the widest instruction.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScratchLayout {
+    widths: BTreeMap<Instruction, ScratchWidth>,
+}
+
+impl ScratchLayout {
+    pub fn new(widths: BTreeMap<Instruction, ScratchWidth>) -> Self {
+        ScratchLayout { widths }
+    }
+
+    /// Returns the width of the rows of the instruction `instruction`
+    pub fn width(&self, instruction: Instruction) -> ScratchWidth {
+        self.widths.get(&instruction).copied().unwrap_or_else(|| {
+            panic!("The instruction {instruction:?} is not in the scratch layout")
+        })
+    }
+
+    /// Returns the width of the widest instruction
+    pub fn max_width(&self) -> ScratchWidth {
+        self.widths
+            .values()
+            .fold(ScratchWidth::default(), |acc, width| acc.max(*width))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Instruction, &ScratchWidth)> {
+        self.widths.iter()
+    }
+}
+
 /// Abstract columns (or variables of our multi-variate polynomials) that will
 /// be used to describe our constraints.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
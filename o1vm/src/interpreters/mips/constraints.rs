@@ -1,38 +1,68 @@
 use crate::{
-    interpreters::mips::{
-        column::{
-            ColumnAlias as MIPSColumn, MIPS_BYTE_COUNTER_OFF, MIPS_CHUNK_BYTES_LEN,
-            MIPS_END_OF_PREIMAGE_OFF, MIPS_HASH_COUNTER_OFF, MIPS_HAS_N_BYTES_OFF,
-            MIPS_LENGTH_BYTES_OFF, MIPS_NUM_BYTES_READ_OFF, MIPS_PREIMAGE_BYTES_OFF,
-            MIPS_PREIMAGE_CHUNK_OFF, MIPS_PREIMAGE_KEY, N_MIPS_REL_COLS,
+    interpreters::{
+        coverage::{CoverageReport, ExecutionCoverage, InstructionConstraints, ScratchColumn},
+        keccak::precompile::KeccakHint,
+        mips::{
+            column::{
+                ColumnAlias as MIPSColumn, MIPS_BYTE_COUNTER_OFF, MIPS_CHUNK_BYTES_LEN,
+                MIPS_END_OF_PREIMAGE_OFF, MIPS_HASH_COUNTER_OFF, MIPS_HAS_N_BYTES_OFF,
+                MIPS_LENGTH_BYTES_OFF, MIPS_NUM_BYTES_READ_OFF, MIPS_PREIMAGE_BYTES_OFF,
+                MIPS_PREIMAGE_CHUNK_OFF, MIPS_PREIMAGE_KEY, N_MIPS_REL_COLS,
+            },
+            interpreter::{interpret_instruction, InterpreterEnv},
+            Endianness, Instruction,
         },
-        interpreter::{interpret_instruction, InterpreterEnv},
-        Instruction,
     },
     lookups::{Lookup, LookupTableIDs},
-    E,
+    pickles::proof::WitnessColumns,
+    RelationColumnType, E,
 };
-use ark_ff::{Field, One};
-use kimchi::circuits::{
-    expr::{ConstantTerm::Literal, Expr, ExprInner, Operations, Variable},
-    gate::CurrOrNext,
+use ark_ff::{Field, One, PrimeField, Zero};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as D};
+use kimchi::{
+    circuits::{
+        berkeley_columns::BerkeleyChallenges,
+        expr::{
+            ColumnEvaluations, ConstantTerm::Literal, Constants, Expr, ExprError, ExprInner,
+            Operations, Variable,
+        },
+        gate::CurrOrNext,
+    },
+    proof::PointEvaluations,
 };
-use kimchi_msm::columns::ColumnIndexer as _;
-use std::array;
+use kimchi_msm::columns::{Column, ColumnIndexer as _};
+use mina_curves::pasta::Fp as PastaFp;
+use mina_poseidon::pasta::fp_kimchi;
+use std::{array, collections::BTreeMap, panic::Location, sync::OnceLock};
 use strum::IntoEnumIterator;
 
-use super::column::N_MIPS_SEL_COLS;
+use super::column::{ScratchLayout, ScratchWidth, SelectorLayout};
 
 /// The environment keeping the constraints between the different polynomials
 pub struct Env<Fp> {
     scratch_state_idx: usize,
     scratch_state_idx_inverse: usize,
+    /// The end of the scratch columns at fixed offsets read by the current
+    /// instruction, see [MIPS_HASH_COUNTER_OFF]
+    fixed_scratch_end: usize,
     /// A list of constraints, which are multi-variate polynomials over a field,
     /// represented using the expression framework of `kimchi`.
     constraints: Vec<E<Fp>>,
+    /// The locations in the interpreter where the constraints are added, used
+    /// as their labels, see [Env::debug_witness].
+    labels: Vec<&'static Location<'static>>,
     lookups: Vec<Lookup<E<Fp>>>,
     /// Selector (as expression) for the constraints of the environment.
     selector: Option<E<Fp>>,
+    /// Whether the memory accesses are authenticated against the Merkle root
+    /// of the memory, see [crate::merkle_memory].
+    merkle_memory: bool,
+    /// The byte order of the memory, see [Env::with_endianness]
+    endianness: Endianness,
+    /// Whether the memory is accessed by words, see [Env::with_word_memory]
+    word_memory: bool,
+    /// The layout of the selector columns, see [Env::with_selector_layout]
+    selector_layout: SelectorLayout,
 }
 
 impl<Fp: Field> Default for Env<Fp> {
@@ -40,9 +70,15 @@ impl<Fp: Field> Default for Env<Fp> {
         Self {
             scratch_state_idx: 0,
             scratch_state_idx_inverse: 0,
+            fixed_scratch_end: 0,
             constraints: Vec::new(),
+            labels: Vec::new(),
             lookups: Vec::new(),
             selector: None,
+            merkle_memory: false,
+            endianness: Endianness::Big,
+            word_memory: false,
+            selector_layout: SelectorLayout::OneHot,
         }
     }
 }
@@ -84,11 +120,19 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         // Sanity check: we only want to activate once per instruction
         assert!(self.selector.is_none(), "A selector has been already activated. You might need to reset the environment if you want to start a new instruction.");
         let n = usize::from(selector) - N_MIPS_REL_COLS;
-        self.selector = Some(self.variable(MIPSColumn::Selector(n)))
+        let selector = self
+            .selector_layout
+            .columns(n)
+            .into_iter()
+            .map(|i| self.variable(MIPSColumn::Selector(i)))
+            .reduce(|acc, var| acc * var)
+            .unwrap();
+        self.selector = Some(selector)
     }
 
     fn add_constraint(&mut self, assert_equals_zero: Self::Variable) {
-        self.constraints.push(assert_equals_zero)
+        self.constraints.push(assert_equals_zero);
+        self.labels.push(Location::caller());
     }
 
     fn check_is_zero(_assert_equals_zero: &Self::Variable) {
@@ -173,6 +217,38 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         // No-op, witness only
     }
 
+    unsafe fn fetch_memory_word(
+        &mut self,
+        _addr: &Self::Variable,
+        output: Self::Position,
+    ) -> Self::Variable {
+        self.variable(output)
+    }
+
+    unsafe fn push_memory_word(&mut self, _addr: &Self::Variable, _value: Self::Variable) {
+        // No-op, witness only
+    }
+
+    fn authenticate_memory_access(
+        &mut self,
+        addr: &Self::Variable,
+        old_value: &Self::Variable,
+        new_value: &Self::Variable,
+    ) {
+        if self.merkle_memory {
+            let instruction_counter = self.instruction_counter();
+            self.add_lookup(Lookup::write_one(
+                LookupTableIDs::MerkleMemoryLookup,
+                vec![
+                    instruction_counter,
+                    addr.clone(),
+                    old_value.clone(),
+                    new_value.clone(),
+                ],
+            ));
+        }
+    }
+
     fn constant(x: u32) -> Self::Variable {
         Self::Variable::constant(Operations::from(Literal(Fp::from(x))))
     }
@@ -405,6 +481,22 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
 
     fn report_exit(&mut self, _exit_code: &Self::Variable) {}
 
+    fn report_stdout_write(
+        &mut self,
+        _is_stdout: &Self::Variable,
+        _addr: &Self::Variable,
+        _len: &Self::Variable,
+    ) {
+    }
+
+    fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    fn word_memory(&self) -> bool {
+        self.word_memory
+    }
+
     /// This function checks that the preimage is read correctly.
     /// It adds 13 constraints, and 5 lookups for the communication channel.
     /// In particular, at every step it writes the bytes of the preimage into
@@ -417,6 +509,8 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         len: &Self::Variable,
         pos: Self::Position,
     ) -> Self::Variable {
+        // The preimage columns are at fixed offsets, after the ones allocated
+        self.fixed_scratch_end = MIPS_PREIMAGE_KEY + 1;
         // How many hashes have been performed so far in the circuit
         let hash_counter = self.variable(Self::Position::ScratchState(MIPS_HASH_COUNTER_OFF));
 
@@ -630,32 +724,103 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         // No-op, witness only
     }
 
+    fn request_advice_write(
+        &mut self,
+        _addr: &Self::Variable,
+        _len: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable {
+        self.variable(pos)
+    }
+
+    unsafe fn request_keccak(
+        &mut self,
+        _addr: &Self::Variable,
+        _len: &Self::Variable,
+        _output: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable {
+        self.variable(pos)
+    }
+
+    unsafe fn keccak_hint(&mut self, _hint: KeccakHint, pos: Self::Position) -> Self::Variable {
+        self.variable(pos)
+    }
+
     fn reset(&mut self) {
         self.scratch_state_idx = 0;
         self.scratch_state_idx_inverse = 0;
+        self.fixed_scratch_end = 0;
         self.constraints.clear();
+        self.labels.clear();
         self.lookups.clear();
         self.selector = None;
     }
 }
 
 impl<Fp: Field> Env<Fp> {
+    /// Return an environment where the memory accesses are authenticated
+    /// against the Merkle root of the memory.
+    pub fn with_merkle_memory() -> Self {
+        Self {
+            merkle_memory: true,
+            ..Self::default()
+        }
+    }
+
+    /// Return an environment for the programs whose memory has the byte
+    /// order `endianness`.
+    pub fn with_endianness(endianness: Endianness) -> Self {
+        Self {
+            endianness,
+            ..Self::default()
+        }
+    }
+
+    /// Return an environment where the memory is accessed by words, see
+    /// [InterpreterEnv::word_memory].
+    pub fn with_word_memory(self) -> Self {
+        Self {
+            word_memory: true,
+            ..self
+        }
+    }
+
+    /// Return an environment building the selectors of the instructions from
+    /// the columns of the layout `selector_layout`.
+    pub fn with_selector_layout(self, selector_layout: SelectorLayout) -> Self {
+        Self {
+            selector_layout,
+            ..self
+        }
+    }
+
     /// Return the constraints for the selector.
-    /// Each selector must be a boolean.
+    /// Each selector column must be a boolean, exactly one column of each
+    /// group of the layout must be set, and the columns set must select an
+    /// instruction, see [SelectorLayout::unused_pairs].
     pub fn get_selector_constraints(&self) -> Vec<E<Fp>> {
         let one = <Self as InterpreterEnv>::Variable::one();
-        let mut enforce_bool: Vec<E<Fp>> = (0..N_MIPS_SEL_COLS)
+        let mut enforce_bool: Vec<E<Fp>> = (0..self.selector_layout.num_columns())
             .map(|i| {
                 let var = self.variable(MIPSColumn::Selector(i));
                 (var.clone() - one.clone()) * var.clone()
             })
             .collect();
-        let enforce_one_activation = (0..N_MIPS_SEL_COLS).fold(E::<Fp>::one(), |res, i| {
-            let var = self.variable(MIPSColumn::Selector(i));
-            res - var.clone()
+        let enforce_one_activation = self.selector_layout.groups().into_iter().map(|group| {
+            group.fold(E::<Fp>::one(), |res, i| {
+                let var = self.variable(MIPSColumn::Selector(i));
+                res - var.clone()
+            })
         });
 
-        enforce_bool.push(enforce_one_activation);
+        enforce_bool.extend(enforce_one_activation);
+        if let Some((high, low)) = self.selector_layout.unused_pairs() {
+            let unused_low = low.fold(E::<Fp>::zero(), |res, i| {
+                res + self.variable(MIPSColumn::Selector(i))
+            });
+            enforce_bool.push(self.variable(MIPSColumn::Selector(high)) * unused_low);
+        }
         enforce_bool
     }
 
@@ -665,6 +830,14 @@ impl<Fp: Field> Env<Fp> {
             .unwrap_or_else(|| panic!("Selector is not set"))
     }
 
+    /// Return the number of scratch columns used by the current instruction
+    pub fn scratch_width(&self) -> ScratchWidth {
+        ScratchWidth {
+            scratch: self.scratch_state_idx.max(self.fixed_scratch_end),
+            scratch_inverse: self.scratch_state_idx_inverse,
+        }
+    }
+
     /// Return the constraints for the current instruction, without the selector
     pub fn get_constraints(&self) -> Vec<E<Fp>> {
         self.constraints.clone()
@@ -675,8 +848,252 @@ impl<Fp: Field> Env<Fp> {
     }
 }
 
+/// A constraint of an instruction which does not hold on a row of a witness,
+/// see [Env::debug_witness]
+#[derive(Clone, Debug)]
+pub struct UnsatisfiedConstraint<Fp> {
+    /// The row of the witness
+    pub row: usize,
+    /// The instruction selected on the row
+    pub instruction: Instruction,
+    /// The index of the constraint among the ones of the instruction
+    pub index: usize,
+    /// The location in the interpreter where the constraint is added
+    pub label: &'static Location<'static>,
+    /// The value of the constraint on the row, instead of zero
+    pub value: Fp,
+    /// The values on the row of the scratch columns used by the instruction
+    pub scratch: Vec<Fp>,
+    /// The values on the row of the inverse scratch columns used by the
+    /// instruction
+    pub scratch_inverse: Vec<Fp>,
+}
+
+impl<Fp: std::fmt::Display> std::fmt::Display for UnsatisfiedConstraint<Fp> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |values: &[Fp]| {
+            values
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        writeln!(
+            f,
+            "row {}: the constraint {} of {:?}, added at {}, evaluates to {}",
+            self.row, self.index, self.instruction, self.label, self.value
+        )?;
+        writeln!(f, "  scratch: [{}]", join(&self.scratch))?;
+        write!(f, "  scratch inverse: [{}]", join(&self.scratch_inverse))
+    }
+}
+
+/// The values of the columns of a witness on a row, and on the next one
+struct RowEvaluations<'a, Fp> {
+    witness: &'a WitnessColumns<Vec<Fp>, Vec<Fp>>,
+    /// The inverses of the inverse scratch columns of the witness
+    scratch_inverse: &'a [Vec<Fp>],
+    row: usize,
+}
+
+impl<Fp: Copy> ColumnEvaluations<Fp> for RowEvaluations<'_, Fp> {
+    type Column = Column<RelationColumnType>;
+
+    fn evaluate(&self, col: Self::Column) -> Result<PointEvaluations<Fp>, ExprError<Self::Column>> {
+        // The selector column holds the indices of the instructions, and is
+        // not read by the constraints
+        let column = match col {
+            Column::Relation(RelationColumnType::Scratch(i)) => self.witness.scratch.get(i),
+            Column::Relation(RelationColumnType::ScratchInverse(i)) => self.scratch_inverse.get(i),
+            Column::Relation(RelationColumnType::LookupState(i)) => {
+                self.witness.lookup_state.get(i)
+            }
+            Column::Relation(RelationColumnType::InstructionCounter) => {
+                Some(&self.witness.instruction_counter)
+            }
+            Column::Relation(RelationColumnType::Error) => Some(&self.witness.error),
+            _ => None,
+        }
+        .filter(|column| self.row < column.len())
+        .ok_or(ExprError::MissingEvaluation(col, CurrOrNext::Curr))?;
+        Ok(PointEvaluations {
+            zeta: column[self.row],
+            zeta_omega: column[(self.row + 1) % column.len()],
+        })
+    }
+}
+
+impl Env<PastaFp> {
+    /// Evaluates the constraints of each instruction, built with the
+    /// configuration of the environment, on the rows of the witness `witness`
+    /// where the instruction is selected, and returns the ones which do not
+    /// hold, by row.
+    /// As in [crate::pickles::proof::ProofInputs], the selector column of the
+    /// witness holds the index of the instruction of each row, and the inverse
+    /// scratch columns hold the values to invert.
+    ///
+    /// This is meant for debugging: the prover only tells that the
+    /// combination of all the constraints does not vanish on the domain.
+    pub fn debug_witness(
+        &mut self,
+        witness: &WitnessColumns<Vec<PastaFp>, Vec<PastaFp>>,
+    ) -> Result<Vec<UnsatisfiedConstraint<PastaFp>>, ExprError<Column<RelationColumnType>>> {
+        let domain =
+            D::<PastaFp>::new(witness.selector.len().max(1)).expect("the witness fits in a domain");
+        // The constraints only use literal constants
+        let constants = Constants {
+            endo_coefficient: PastaFp::zero(),
+            mds: &fp_kimchi::static_params().mds,
+            zk_rows: 0,
+        };
+        let challenges = BerkeleyChallenges {
+            alpha: PastaFp::zero(),
+            beta: PastaFp::zero(),
+            gamma: PastaFp::zero(),
+            joint_combiner: PastaFp::zero(),
+        };
+        let scratch_inverse: Vec<Vec<PastaFp>> = witness
+            .scratch_inverse
+            .iter()
+            .map(|column| {
+                let mut column = column.clone();
+                ark_ff::batch_inversion(&mut column);
+                column
+            })
+            .collect();
+        self.reset();
+        let mut unsatisfied = vec![];
+        for instruction in Instruction::iter().flat_map(|instr_typ| instr_typ.into_iter()) {
+            let selector = PastaFp::from((usize::from(instruction) - N_MIPS_REL_COLS) as u64);
+            interpret_instruction(self, instruction);
+            let rows = witness
+                .selector
+                .iter()
+                .enumerate()
+                .filter(|(_, s)| **s == selector);
+            for (row, _) in rows {
+                let evaluations = RowEvaluations {
+                    witness,
+                    scratch_inverse: &scratch_inverse,
+                    row,
+                };
+                for (index, (constraint, label)) in
+                    self.constraints.iter().zip(self.labels.iter()).enumerate()
+                {
+                    let value = constraint.evaluate_(
+                        domain,
+                        domain.element(row),
+                        &evaluations,
+                        &constants,
+                        &challenges,
+                    )?;
+                    if !value.is_zero() {
+                        unsatisfied.push(UnsatisfiedConstraint {
+                            row,
+                            instruction,
+                            index,
+                            label,
+                            value,
+                            scratch: (0..self.scratch_state_idx)
+                                .map(|i| witness.scratch[i][row])
+                                .collect(),
+                            scratch_inverse: (0..self.scratch_state_idx_inverse)
+                                .map(|i| scratch_inverse[i][row])
+                                .collect(),
+                        });
+                    }
+                }
+            }
+            self.reset();
+        }
+        unsatisfied.sort_by_key(|constraint| constraint.row);
+        Ok(unsatisfied)
+    }
+
+    /// Returns the coverage of the circuit, built with the configuration of
+    /// the environment, by the execution giving the witness `witness`, laid
+    /// out as in [Env::debug_witness]. See [crate::interpreters::coverage]
+    /// for the content of the report.
+    pub fn coverage(
+        &mut self,
+        witness: &WitnessColumns<Vec<PastaFp>, Vec<PastaFp>>,
+    ) -> CoverageReport<Instruction> {
+        let instructions: Vec<Instruction> = Instruction::iter()
+            .flat_map(|instr_typ| instr_typ.into_iter())
+            .collect();
+        let selectors: BTreeMap<usize, Instruction> = instructions
+            .iter()
+            .map(|instruction| (usize::from(*instruction) - N_MIPS_REL_COLS, *instruction))
+            .collect();
+        let mut execution = ExecutionCoverage::default();
+        for (row, selector) in witness.selector.iter().enumerate() {
+            let selector = selector.into_bigint().as_ref()[0] as usize;
+            let instruction = selectors[&selector];
+            execution.record(
+                instruction,
+                witness.scratch.iter().map(|column| column[row]),
+                witness.scratch_inverse.iter().map(|column| column[row]),
+            );
+        }
+        self.reset();
+        execution.report(
+            instructions,
+            witness.scratch.len(),
+            witness.scratch_inverse.len(),
+            |instruction| {
+                interpret_instruction(self, instruction);
+                let constraints =
+                    InstructionConstraints::new(&self.constraints, &self.lookups, |column| {
+                        match column {
+                            Column::Relation(RelationColumnType::Scratch(i)) => {
+                                Some(ScratchColumn::Scratch(*i))
+                            }
+                            Column::Relation(RelationColumnType::ScratchInverse(i)) => {
+                                Some(ScratchColumn::ScratchInverse(*i))
+                            }
+                            _ => None,
+                        }
+                    });
+                self.reset();
+                constraints
+            },
+        )
+    }
+}
+
+/// Returns the scratch columns used by each instruction, with the memory in
+/// either byte order and accessed by bytes or by words, see [ScratchLayout].
+/// The layout is derived once.
+pub fn scratch_layout() -> &'static ScratchLayout {
+    static SCRATCH_LAYOUT: OnceLock<ScratchLayout> = OnceLock::new();
+    SCRATCH_LAYOUT.get_or_init(|| {
+        let mut widths = BTreeMap::new();
+        for endianness in [Endianness::Big, Endianness::Little] {
+            for word_memory in [false, true] {
+                let mut mips_con_env = Env::<PastaFp>::with_endianness(endianness);
+                mips_con_env.word_memory = word_memory;
+                for instr in Instruction::iter().flat_map(|instr_typ| instr_typ.into_iter()) {
+                    interpret_instruction(&mut mips_con_env, instr);
+                    let width: &mut ScratchWidth = widths.entry(instr).or_default();
+                    *width = width.max(mips_con_env.scratch_width());
+                    mips_con_env.reset();
+                }
+            }
+        }
+        ScratchLayout::new(widths)
+    })
+}
+
 pub fn get_all_constraints<Fp: Field>() -> Vec<E<Fp>> {
-    let mut mips_con_env = Env::<Fp>::default();
+    get_all_constraints_with_selector_layout(SelectorLayout::default())
+}
+
+/// Returns the constraints of all the instructions, with the selectors laid out
+/// as `selector_layout`
+pub fn get_all_constraints_with_selector_layout<Fp: Field>(
+    selector_layout: SelectorLayout,
+) -> Vec<E<Fp>> {
+    let mut mips_con_env = Env::<Fp>::default().with_selector_layout(selector_layout);
     let mut constraints = Instruction::iter()
         .flat_map(|instr_typ| instr_typ.into_iter())
         .fold(vec![], |mut acc, instr| {
@@ -1,8 +1,14 @@
 use crate::{
     cannon::PAGE_ADDRESS_SIZE,
-    interpreters::mips::registers::{
-        REGISTER_CURRENT_IP, REGISTER_HEAP_POINTER, REGISTER_HI, REGISTER_LO, REGISTER_NEXT_IP,
-        REGISTER_PREIMAGE_KEY_END, REGISTER_PREIMAGE_OFFSET,
+    interpreters::{
+        keccak::precompile::{KeccakHint, KECCAK_PRECOMPILE_CHUNK_LEN, SYSCALL_KECCAK256},
+        mips::{
+            registers::{
+                REGISTER_CURRENT_IP, REGISTER_HEAP_POINTER, REGISTER_HI, REGISTER_LO,
+                REGISTER_NEXT_IP, REGISTER_PREIMAGE_KEY_END, REGISTER_PREIMAGE_OFFSET,
+            },
+            Endianness,
+        },
     },
     lookups::{Lookup, LookupTableIDs},
 };
@@ -17,6 +23,9 @@ pub const FD_HINT_READ: u32 = 3;
 pub const FD_HINT_WRITE: u32 = 4;
 pub const FD_PREIMAGE_READ: u32 = 5;
 pub const FD_PREIMAGE_WRITE: u32 = 6;
+/// The file descriptor of the advice given by the prover to the program. The
+/// advice is not constrained, and the program must check it.
+pub const FD_ADVICE_READ: u32 = 7;
 
 pub const SYSCALL_MMAP: u32 = 4090;
 pub const SYSCALL_BRK: u32 = 4045;
@@ -53,11 +62,13 @@ pub enum RTypeInstruction {
     SyscallExitGroup,             // syscall (ExitGroup)
     SyscallReadHint,              // syscall (Read 3)
     SyscallReadPreimage,          // syscall (Read 5)
+    SyscallReadAdvice,            // syscall (Read 7)
     SyscallReadOther,             // syscall (Read ?)
     SyscallWriteHint,             // syscall (Write 4)
     SyscallWritePreimage,         // syscall (Write 6)
     SyscallWriteOther,            // syscall (Write ?)
     SyscallFcntl,                 // syscall (Fcntl)
+    SyscallKeccak256,             // syscall (Keccak256)
     SyscallOther,                 // syscall (Brk, Clone, ?)
     MoveZero,                     // movz
     MoveNonZero,                  // movn
@@ -83,6 +94,10 @@ pub enum RTypeInstruction {
     MultiplyToRegister,           // mul
     CountLeadingOnes,             // clo
     CountLeadingZeros,            // clz
+    MultiplyAdd,                  // madd
+    MultiplyAddUnsigned,          // maddu
+    MultiplySub,                  // msub
+    MultiplySubUnsigned,          // msubu
 }
 
 #[derive(
@@ -191,6 +206,9 @@ pub trait InterpreterEnv {
 
     /// Add a constraint to the proof system, asserting that
     /// `assert_equals_zero` is 0.
+    /// The location of the caller is used to label the constraint, see
+    /// [crate::interpreters::mips::constraints::Env::debug_witness].
+    #[track_caller]
     fn add_constraint(&mut self, assert_equals_zero: Self::Variable);
 
     /// Activate the selector for the given instruction.
@@ -200,6 +218,7 @@ pub trait InterpreterEnv {
     fn check_is_zero(assert_equals_zero: &Self::Variable);
 
     /// Assert that the value `assert_equals_zero` is 0, and add a constraint in the proof system.
+    #[track_caller]
     fn assert_is_zero(&mut self, assert_equals_zero: Self::Variable) {
         Self::check_is_zero(&assert_equals_zero);
         self.add_constraint(assert_equals_zero);
@@ -209,6 +228,7 @@ pub trait InterpreterEnv {
     fn check_equal(x: &Self::Variable, y: &Self::Variable);
 
     /// Assert that the values `x` and `y` are equal, and add a constraint in the proof system.
+    #[track_caller]
     fn assert_equal(&mut self, x: Self::Variable, y: Self::Variable) {
         // NB: We use a different function to give a better error message for debugging.
         Self::check_equal(&x, &y);
@@ -219,6 +239,7 @@ pub trait InterpreterEnv {
     fn check_boolean(x: &Self::Variable);
 
     /// Assert that the value `x` is boolean, and add a constraint in the proof system.
+    #[track_caller]
     fn assert_boolean(&mut self, x: Self::Variable) {
         Self::check_boolean(&x);
         self.add_constraint(x.clone() * x.clone() - x);
@@ -445,6 +466,37 @@ pub trait InterpreterEnv {
     /// manually add the lookups for this memory operation.
     unsafe fn push_memory_access(&mut self, addr: &Self::Variable, value: Self::Variable);
 
+    /// Fetch the word at the aligned address `addr`, i.e. the value of a 4-byte load at this
+    /// address following [Self::endianness], and store it in local position `output`.
+    ///
+    /// # Safety
+    ///
+    /// No lookups or other constraints are added as part of this operation. The caller must
+    /// manually add the lookups for this memory operation.
+    unsafe fn fetch_memory_word(
+        &mut self,
+        addr: &Self::Variable,
+        output: Self::Position,
+    ) -> Self::Variable;
+
+    /// Set the word at the aligned address `addr` to `value`, following [Self::endianness].
+    ///
+    /// # Safety
+    ///
+    /// No lookups or other constraints are added as part of this operation. The caller must
+    /// manually add the lookups for this memory operation.
+    unsafe fn push_memory_word(&mut self, addr: &Self::Variable, value: Self::Variable);
+
+    /// Authenticate the access to the memory address `addr`, changing its value from `old_value`
+    /// to `new_value`, against the Merkle root of the memory. This is a no-op when the memory is
+    /// not Merkleized, see [crate::merkle_memory].
+    fn authenticate_memory_access(
+        &mut self,
+        addr: &Self::Variable,
+        old_value: &Self::Variable,
+        new_value: &Self::Variable,
+    );
+
     /// Access the memory address `addr`, adding constraints asserting that the old value was
     /// `old_value` and that the new value will be `new_value`.
     ///
@@ -480,12 +532,18 @@ pub trait InterpreterEnv {
             vec![addr.clone(), new_accessed, new_value.clone()],
         ));
         self.range_check64(&elapsed_time);
+        self.authenticate_memory_access(addr, old_value, new_value);
 
         // Update instruction counter after accessing a memory address.
         self.increase_instruction_counter();
     }
 
     fn read_memory(&mut self, addr: &Self::Variable) -> Self::Variable {
+        if self.word_memory() {
+            return self
+                .read_memory_bytes(addr, &[(0, Self::constant(1))])
+                .remove(0);
+        }
         let value = {
             let value_location = self.alloc_scratch();
             unsafe { self.fetch_memory(addr, value_location) }
@@ -497,6 +555,9 @@ pub trait InterpreterEnv {
     }
 
     fn write_memory(&mut self, addr: &Self::Variable, new_value: Self::Variable) {
+        if self.word_memory() {
+            return self.write_memory_bytes(addr, &[(0, Self::constant(1))], vec![new_value]);
+        }
         let old_value = {
             let value_location = self.alloc_scratch();
             unsafe { self.fetch_memory(addr, value_location) }
@@ -509,6 +570,151 @@ pub trait InterpreterEnv {
         };
     }
 
+    /// Returns the byte order of the memory, fixed when the environment is
+    /// created
+    fn endianness(&self) -> Endianness;
+
+    /// Returns whether the memory is accessed by aligned words rather than by
+    /// bytes, fixed when the environment is created.
+    /// The memory lookups and the authenticated accesses are then indexed by
+    /// the address of the words: a 4-byte access takes one access instead of
+    /// four, while the accesses to some bytes of a word decompose it once for
+    /// all of them, see [Self::read_memory_bytes].
+    fn word_memory(&self) -> bool;
+
+    /// Reads the bytes at the addresses `addr + offset` for the offsets
+    /// `offsets`, given with the flag of the byte being in the word containing
+    /// `addr`.
+    ///
+    /// Only the word containing `addr` is accessed: the value returned for the
+    /// bytes outside of it must be ignored by the caller. When the memory is
+    /// accessed by bytes, `addr` is read again in their place, and when it is
+    /// accessed by words, the word is accessed once and its bytes are shared
+    /// between the offsets.
+    fn read_memory_bytes(
+        &mut self,
+        addr: &Self::Variable,
+        offsets: &[(i32, Self::Variable)],
+    ) -> Vec<Self::Variable> {
+        if !self.word_memory() {
+            return offsets
+                .iter()
+                .map(|(offset, in_word)| {
+                    self.read_memory(&in_word_address::<Self>(addr, *offset, in_word))
+                })
+                .collect();
+        }
+        let word = access_word_bytes(self, addr);
+        unsafe {
+            self.access_memory(&word.addr, &word.value, &word.value);
+        };
+        offsets
+            .iter()
+            .map(|(offset, _)| {
+                let byte = word.byte_at(*offset);
+                let pos = self.alloc_scratch();
+                self.copy(&byte, pos)
+            })
+            .collect()
+    }
+
+    /// Writes the bytes `bytes` at the addresses `addr + offset` for the
+    /// offsets `offsets`, given as in [Self::read_memory_bytes].
+    ///
+    /// Only the word containing `addr` is written: the bytes outside of it
+    /// must be the ones read at `addr`. When the memory is accessed by bytes,
+    /// they are written back at `addr` before the byte at `addr`, and when it
+    /// is accessed by words, the word is updated with a single access.
+    fn write_memory_bytes(
+        &mut self,
+        addr: &Self::Variable,
+        offsets: &[(i32, Self::Variable)],
+        bytes: Vec<Self::Variable>,
+    ) {
+        let bytes: Vec<_> = offsets
+            .iter()
+            .map(|(offset, _)| *offset)
+            .zip(bytes)
+            .collect();
+        if !self.word_memory() {
+            let (at_addr, others): (Vec<_>, Vec<_>) = offsets
+                .iter()
+                .zip(bytes)
+                .partition(|((offset, _), _)| *offset == 0);
+            for ((offset, in_word), (_, byte)) in others.into_iter().chain(at_addr) {
+                self.write_memory(&in_word_address::<Self>(addr, *offset, in_word), byte);
+            }
+            return;
+        }
+        let word = access_word_bytes(self, addr);
+        let new_value = {
+            let value = word.with_bytes(&bytes);
+            let pos = self.alloc_scratch();
+            self.copy(&value, pos)
+        };
+        unsafe {
+            self.access_memory(&word.addr, &word.value, &new_value);
+        };
+        unsafe {
+            self.push_memory_word(&word.addr, new_value);
+        };
+    }
+
+    /// Reads the `size` bytes at the address `addr` and composes them into a
+    /// word, following [Self::endianness]
+    fn read_memory_word(&mut self, addr: &Self::Variable, size: u32) -> Self::Variable {
+        if self.word_memory() && size == 4 {
+            let word_addr = word_address(self, addr);
+            self.assert_equal(addr.clone(), word_addr);
+            let value = {
+                let value_location = self.alloc_scratch();
+                unsafe { self.fetch_memory_word(addr, value_location) }
+            };
+            unsafe {
+                self.access_memory(addr, &value, &value);
+            };
+            return value;
+        }
+        let endianness = self.endianness();
+        let offsets: Vec<_> = (0..size)
+            .map(|i| (endianness.byte_offset(size, i) as i32, Self::constant(1)))
+            .collect();
+        self.read_memory_bytes(addr, &offsets)
+            .into_iter()
+            .reduce(|word, byte| word * Self::constant(1 << 8) + byte)
+            .unwrap()
+    }
+
+    /// Writes the bytes `bytes` of a word, given from the most to the least
+    /// significant one, at the address `addr`, following [Self::endianness]
+    fn write_memory_word(&mut self, addr: &Self::Variable, bytes: Vec<Self::Variable>) {
+        let size = bytes.len() as u32;
+        if self.word_memory() && size == 4 {
+            let word_addr = word_address(self, addr);
+            self.assert_equal(addr.clone(), word_addr);
+            let new_value = bytes
+                .into_iter()
+                .reduce(|word, byte| word * Self::constant(1 << 8) + byte)
+                .unwrap();
+            let old_value = {
+                let value_location = self.alloc_scratch();
+                unsafe { self.fetch_memory_word(addr, value_location) }
+            };
+            unsafe {
+                self.access_memory(addr, &old_value, &new_value);
+            };
+            unsafe {
+                self.push_memory_word(addr, new_value);
+            };
+            return;
+        }
+        let endianness = self.endianness();
+        let offsets: Vec<_> = (0..size)
+            .map(|i| (endianness.byte_offset(size, i) as i32, Self::constant(1)))
+            .collect();
+        self.write_memory_bytes(addr, &offsets, bytes);
+    }
+
     /// Adds a lookup to the RangeCheck16Lookup table
     fn lookup_16bits(&mut self, value: &Self::Variable) {
         self.add_lookup(Lookup::read_one(
@@ -518,14 +724,19 @@ pub trait InterpreterEnv {
     }
 
     /// Range checks with 2 lookups to the RangeCheck16Lookup table that a value
-    /// is at most 2^`bits`-1  (bits <= 16).
+    /// is at most 2^`bits`-1  (bits <= 16), or with 1 lookup if `bits` is 16.
     fn range_check16(&mut self, value: &Self::Variable, bits: u32) {
         assert!(bits <= 16);
         // 0 <= value < 2^bits
         // First, check lowerbound: 0 <= value < 2^16
         self.lookup_16bits(value);
-        // Second, check upperbound: value + 2^16 - 2^bits < 2^16
-        self.lookup_16bits(&(value.clone() + Self::constant(1 << 16) - Self::constant(1 << bits)));
+        // Second, check upperbound: value + 2^16 - 2^bits < 2^16, which is
+        // the same lookup as the first one for a full-width check
+        if bits < 16 {
+            self.lookup_16bits(
+                &(value.clone() + Self::constant(1 << 16) - Self::constant(1 << bits)),
+            );
+        }
     }
 
     /// Adds a lookup to the ByteLookup table
@@ -537,14 +748,19 @@ pub trait InterpreterEnv {
     }
 
     /// Range checks with 2 lookups to the ByteLookup table that a value
-    /// is at most 2^`bits`-1  (bits <= 8).
+    /// is at most 2^`bits`-1  (bits <= 8), or with 1 lookup if `bits` is 8.
     fn range_check8(&mut self, value: &Self::Variable, bits: u32) {
         assert!(bits <= 8);
         // 0 <= value < 2^bits
         // First, check lowerbound: 0 <= value < 2^8
         self.lookup_8bits(value);
-        // Second, check upperbound: value + 2^8 - 2^bits < 2^8
-        self.lookup_8bits(&(value.clone() + Self::constant(1 << 8) - Self::constant(1 << bits)));
+        // Second, check upperbound: value + 2^8 - 2^bits < 2^8, which is
+        // the same lookup as the first one for a full-width check
+        if bits < 8 {
+            self.lookup_8bits(
+                &(value.clone() + Self::constant(1 << 8) - Self::constant(1 << bits)),
+            );
+        }
     }
 
     /// Adds a lookup to the AtMost4Lookup table
@@ -963,10 +1179,269 @@ pub trait InterpreterEnv {
 
     fn request_hint_write(&mut self, addr: &Self::Variable, len: &Self::Variable);
 
+    /// Report the write of the `len` bytes starting from `addr` to the
+    /// standard output when `is_stdout` is 1. The output is part of the public
+    /// claim about the execution, see [crate::pickles::claim].
+    fn report_stdout_write(
+        &mut self,
+        is_stdout: &Self::Variable,
+        addr: &Self::Variable,
+        len: &Self::Variable,
+    );
+
+    /// Request at most `len` bytes of the advice given by the prover, store
+    /// them starting from `addr`, and return the number of bytes actually
+    /// read, which is 0 when the advice is exhausted.
+    /// The number of bytes actually read will be set into `pos`.
+    fn request_advice_write(
+        &mut self,
+        addr: &Self::Variable,
+        len: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable;
+
+    /// Absorb at most 4 bytes of the input of the Keccak precompile, starting
+    /// from `addr` and up to `len` bytes, and return the number of bytes
+    /// absorbed, which is set into `pos`. When `len` is 0, the call ends and
+    /// the digest of the input is written starting from `output`. See
+    /// [crate::interpreters::keccak::precompile].
+    ///
+    /// # Safety
+    ///
+    /// No lookups or other constraints are added as part of this operation.
+    /// The caller must bind the bytes absorbed and the digest to the Keccak
+    /// sub-trace with [Self::keccak_hint].
+    unsafe fn request_keccak(
+        &mut self,
+        addr: &Self::Variable,
+        len: &Self::Variable,
+        output: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable;
+
+    /// Return the value of the hint `hint` of the last call to
+    /// [Self::request_keccak], set into `pos`.
+    ///
+    /// # Safety
+    ///
+    /// No lookups or other constraints are added as part of this operation.
+    unsafe fn keccak_hint(&mut self, hint: KeccakHint, pos: Self::Position) -> Self::Variable;
+
     /// Reset the environment to handle the next instruction
     fn reset(&mut self);
 }
 
+/// Returns the address at the offset `offset` of the address `addr` if
+/// `in_word` is set, or `addr` otherwise
+fn in_word_address<Env: InterpreterEnv + ?Sized>(
+    addr: &Env::Variable,
+    offset: i32,
+    in_word: &Env::Variable,
+) -> Env::Variable {
+    match offset {
+        0 => addr.clone(),
+        offset if offset > 0 => addr.clone() + in_word.clone() * Env::constant(offset as u32),
+        offset => addr.clone() - in_word.clone() * Env::constant(offset.unsigned_abs()),
+    }
+}
+
+/// The word containing an address, decomposed into its bytes, when the memory
+/// is accessed by words, see [InterpreterEnv::word_memory]
+struct WordBytes<Env: InterpreterEnv + ?Sized> {
+    /// The address of the word
+    addr: Env::Variable,
+    /// The value of the word, following the endianness of the memory
+    value: Env::Variable,
+    /// The bytes of the word, from the most to the least significant one
+    bytes: [Env::Variable; 4],
+    /// The flags of the offset of the address in the word, the one of the
+    /// offset being set
+    lanes: [Env::Variable; 4],
+    endianness: Endianness,
+}
+
+impl<Env: InterpreterEnv + ?Sized> WordBytes<Env> {
+    /// Returns the byte at the offset `lane` from the address of the word
+    fn byte_of_lane(&self, lane: i32) -> Env::Variable {
+        self.bytes[self.endianness.byte_offset(4, lane as u32) as usize].clone()
+    }
+
+    /// Returns the flags of the lanes `lane - offset` which are in the word,
+    /// i.e. of the offsets of the address for which the byte at `offset` from
+    /// the address is at the offset `lane` from the address of the word
+    fn lanes_to(&self, lane: i32, offset: i32) -> Option<Env::Variable> {
+        (0..4)
+            .contains(&(lane - offset))
+            .then(|| self.lanes[(lane - offset) as usize].clone())
+    }
+
+    /// Returns the byte at the offset `offset` from the address, or zero if it
+    /// is outside of the word
+    fn byte_at(&self, offset: i32) -> Env::Variable {
+        (0..4)
+            .filter_map(|lane| {
+                self.lanes_to(lane, offset)
+                    .map(|flag| flag * self.byte_of_lane(lane))
+            })
+            .reduce(|acc, byte| acc + byte)
+            .unwrap_or_else(|| Env::constant(0))
+    }
+
+    /// Returns the value of the word with the bytes `bytes` written at their
+    /// offset from the address, the ones outside of the word being ignored
+    fn with_bytes(&self, bytes: &[(i32, Env::Variable)]) -> Env::Variable {
+        (0..4)
+            .map(|lane| {
+                let (written, flag) = bytes
+                    .iter()
+                    .filter_map(|(offset, byte)| {
+                        self.lanes_to(lane, *offset)
+                            .map(|flag| (flag.clone() * byte.clone(), flag))
+                    })
+                    .fold(
+                        (Env::constant(0), Env::constant(0)),
+                        |(written, flag), (byte, byte_flag)| (written + byte, flag + byte_flag),
+                    );
+                let byte = written + (Env::constant(1) - flag) * self.byte_of_lane(lane);
+                let shift = 8 * (3 - self.endianness.byte_offset(4, lane as u32));
+                byte * Env::constant(1 << shift)
+            })
+            .reduce(|acc, byte| acc + byte)
+            .unwrap()
+    }
+}
+
+/// Returns the address of the word containing the address `addr`, i.e. `addr`
+/// with its two lowest bits cleared. The index of the word is range checked to
+/// 30 bits, so that the returned address is aligned.
+fn word_address<Env: InterpreterEnv + ?Sized>(
+    env: &mut Env,
+    addr: &Env::Variable,
+) -> Env::Variable {
+    let index_low = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(addr, 18, 2, pos) }
+    };
+    let index_high = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(addr, 32, 18, pos) }
+    };
+    env.lookup_16bits(&index_low);
+    env.range_check16(&index_high, 14);
+    (index_high * Env::constant(1 << 16) + index_low) * Env::constant(4)
+}
+
+/// Decomposes the word containing the address `addr` into its bytes, which are
+/// range checked, without accessing it.
+fn access_word_bytes<Env: InterpreterEnv + ?Sized>(
+    env: &mut Env,
+    addr: &Env::Variable,
+) -> WordBytes<Env> {
+    // The offset of the address in its word, given by its two lowest bits
+    let [bit0, bit1] = [0, 1].map(|i| {
+        let pos = env.alloc_scratch();
+        let bit = unsafe { env.bitmask(addr, i + 1, i, pos) };
+        env.assert_boolean(bit.clone());
+        bit
+    });
+    let lanes = [0, 1, 2, 3].map(|lane| {
+        let flag = |bit: &Env::Variable, set: bool| {
+            if set {
+                bit.clone()
+            } else {
+                Env::constant(1) - bit.clone()
+            }
+        };
+        flag(&bit1, lane & 2 != 0) * flag(&bit0, lane & 1 != 0)
+    });
+    let word_addr = word_address(env, addr);
+    env.assert_equal(
+        addr.clone(),
+        word_addr.clone() + bit1 * Env::constant(2) + bit0,
+    );
+    let value = {
+        let pos = env.alloc_scratch();
+        unsafe { env.fetch_memory_word(&word_addr, pos) }
+    };
+    let bytes = [0, 1, 2, 3].map(|i| {
+        let pos = env.alloc_scratch();
+        let byte = unsafe { env.bitmask(&value, 32 - 8 * i, 24 - 8 * i, pos) };
+        env.lookup_8bits(&byte);
+        byte
+    });
+    let composed = bytes
+        .iter()
+        .cloned()
+        .reduce(|word, byte| word * Env::constant(1 << 8) + byte)
+        .unwrap();
+    env.assert_equal(value.clone(), composed);
+    WordBytes {
+        addr: word_addr,
+        value,
+        bytes,
+        lanes,
+        endianness: env.endianness(),
+    }
+}
+
+/// Returns the new value of the preimage key register written by
+/// `SyscallWritePreimage` when the memory is accessed by words, and the number
+/// of bytes read from the memory.
+///
+/// As in cannon, the bytes are read from `addr` up to the end of its word, and
+/// written after the `bytes_to_preserve` first bytes of `register_bytes`, given
+/// from the most to the least significant one. Only the word containing `addr`
+/// is accessed.
+fn write_preimage_key_by_word<Env: InterpreterEnv + ?Sized>(
+    env: &mut Env,
+    addr: &Env::Variable,
+    bytes_to_preserve: &Env::Variable,
+    register_bytes: [Env::Variable; 4],
+) -> (Env::Variable, Env::Variable) {
+    let preserve = [0, 1, 2, 3].map(|p| env.equal(bytes_to_preserve, &Env::constant(p)));
+    let word = access_word_bytes(env, addr);
+    unsafe {
+        env.access_memory(&word.addr, &word.value, &word.value);
+    };
+    let (value, bytes_read) = register_bytes.into_iter().enumerate().fold(
+        (Env::constant(0), Env::constant(0)),
+        |(value, bytes_read), (i, register_byte)| {
+            // When `p` bytes are preserved, the byte `i` of the register is
+            // overwritten by the byte at `addr + i - p` if it is in the word,
+            // which `byte_at` reads as zero otherwise
+            let (byte, overwrite) = (0..=i)
+                .map(|p| {
+                    let offset = (i - p) as i32;
+                    let in_word = (0..4 - offset)
+                        .map(|lane| word.lanes[lane as usize].clone())
+                        .reduce(|acc, flag| acc + flag)
+                        .unwrap();
+                    (
+                        preserve[p].clone() * word.byte_at(offset),
+                        preserve[p].clone() * in_word,
+                    )
+                })
+                .reduce(|(byte, overwrite), (b, o)| (byte + b, overwrite + o))
+                .unwrap();
+            (
+                value * Env::constant(1 << 8)
+                    + byte
+                    + (Env::constant(1) - overwrite.clone()) * register_byte,
+                bytes_read + overwrite,
+            )
+        },
+    );
+    let value = {
+        let pos = env.alloc_scratch();
+        env.copy(&value, pos)
+    };
+    let bytes_read = {
+        let pos = env.alloc_scratch();
+        env.copy(&bytes_read, pos)
+    };
+    (value, bytes_read)
+}
+
 pub fn interpret_instruction<Env: InterpreterEnv>(env: &mut Env, instr: Instruction) {
     env.activate_selector(instr);
 
@@ -983,16 +1458,7 @@ pub fn interpret_instruction<Env: InterpreterEnv>(env: &mut Env, instr: Instruct
 // required to reach the size of the domain.
 pub fn interpret_noop<Env: InterpreterEnv>(env: &mut Env) {
     let instruction_pointer = env.get_instruction_pointer();
-    let instruction = {
-        let v0 = env.read_memory(&instruction_pointer);
-        let v1 = env.read_memory(&(instruction_pointer.clone() + Env::constant(1)));
-        let v2 = env.read_memory(&(instruction_pointer.clone() + Env::constant(2)));
-        let v3 = env.read_memory(&(instruction_pointer.clone() + Env::constant(3)));
-        (v0 * Env::constant(1 << 24))
-            + (v1 * Env::constant(1 << 16))
-            + (v2 * Env::constant(1 << 8))
-            + v3
-    };
+    let instruction = env.read_memory_word(&instruction_pointer, 4);
     let opcode = {
         let pos = env.alloc_scratch();
         unsafe { env.bitmask(&instruction, 32, 26, pos) }
@@ -1007,16 +1473,7 @@ pub fn interpret_noop<Env: InterpreterEnv>(env: &mut Env) {
 pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RTypeInstruction) {
     let instruction_pointer = env.get_instruction_pointer();
     let next_instruction_pointer = env.get_next_instruction_pointer();
-    let instruction = {
-        let v0 = env.read_memory(&instruction_pointer);
-        let v1 = env.read_memory(&(instruction_pointer.clone() + Env::constant(1)));
-        let v2 = env.read_memory(&(instruction_pointer.clone() + Env::constant(2)));
-        let v3 = env.read_memory(&(instruction_pointer.clone() + Env::constant(3)));
-        (v0 * Env::constant(1 << 24))
-            + (v1 * Env::constant(1 << 16))
-            + (v2 * Env::constant(1 << 8))
-            + v3
-    };
+    let instruction = env.read_memory_word(&instruction_pointer, 4);
     let opcode = {
         let pos = env.alloc_scratch();
         unsafe { env.bitmask(&instruction, 32, 26, pos) }
@@ -1207,6 +1664,19 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RTypeInstructi
             env.set_instruction_pointer(next_instruction_pointer.clone());
             env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
         }
+        RTypeInstruction::SyscallReadAdvice => {
+            let addr = env.read_register(&Env::constant(5));
+            let length = env.read_register(&Env::constant(6));
+            // FIXME: the advice is not constrained, the program must check it
+            let read_length = {
+                let pos = env.alloc_scratch();
+                env.request_advice_write(&addr, &length, pos)
+            };
+            env.write_register(&Env::constant(2), read_length);
+            env.write_register(&Env::constant(7), Env::constant(0));
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+        }
         RTypeInstruction::SyscallReadOther => {
             let fd_id = env.read_register(&Env::constant(4));
             let mut check_equal = |expected_fd_id: u32| {
@@ -1303,74 +1773,87 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RTypeInstructi
             env.lookup_8bits(&r2);
             env.lookup_8bits(&r3);
 
-            // We choose our read address so that the bytes we read come aligned with the target
-            // bytes in the register, to avoid an expensive bitshift.
-            let read_address = addr.clone() - bytes_to_preserve_in_register.clone();
-
-            let m0 = env.read_memory(&read_address);
-            let m1 = env.read_memory(&(read_address.clone() + Env::constant(1)));
-            let m2 = env.read_memory(&(read_address.clone() + Env::constant(2)));
-            let m3 = env.read_memory(&(read_address.clone() + Env::constant(3)));
-
-            // Now, for some complexity. From the perspective of the write operation, we should be
-            // reading the `4 - bytes_to_preserve_in_register`. However, to match cannon 1:1, we
-            // only want to read the bytes up to the end of the current word.
-            let [overwrite_0, overwrite_1, overwrite_2, overwrite_3] = {
-                let next_word_addr = {
-                    let byte_subaddr = {
-                        // FIXME: Requires a range check
-                        let pos = env.alloc_scratch();
-                        unsafe { env.bitmask(&addr, 2, 0, pos) }
+            let (value, bytes_read) = if env.word_memory() {
+                write_preimage_key_by_word(
+                    env,
+                    &addr,
+                    &bytes_to_preserve_in_register,
+                    [r0, r1, r2, r3],
+                )
+            } else {
+                // We choose our read address so that the bytes we read come aligned with the target
+                // bytes in the register, to avoid an expensive bitshift.
+                let read_address = addr.clone() - bytes_to_preserve_in_register.clone();
+
+                let m0 = env.read_memory(&read_address);
+                let m1 = env.read_memory(&(read_address.clone() + Env::constant(1)));
+                let m2 = env.read_memory(&(read_address.clone() + Env::constant(2)));
+                let m3 = env.read_memory(&(read_address.clone() + Env::constant(3)));
+
+                // Now, for some complexity. From the perspective of the write operation, we should be
+                // reading the `4 - bytes_to_preserve_in_register`. However, to match cannon 1:1, we
+                // only want to read the bytes up to the end of the current word.
+                let [overwrite_0, overwrite_1, overwrite_2, overwrite_3] = {
+                    let next_word_addr = {
+                        let byte_subaddr = {
+                            // FIXME: Requires a range check
+                            let pos = env.alloc_scratch();
+                            unsafe { env.bitmask(&addr, 2, 0, pos) }
+                        };
+                        env.range_check2(&byte_subaddr);
+                        addr.clone() + Env::constant(4) - byte_subaddr
                     };
-                    env.range_check2(&byte_subaddr);
-                    addr.clone() + Env::constant(4) - byte_subaddr
-                };
-                let overwrite_0 = {
-                    // We always write the first byte if we're not preserving it, since it will
-                    // have been read from `addr`.
-                    env.equal(&bytes_to_preserve_in_register, &Env::constant(0))
-                };
-                let overwrite_1 = {
-                    // We write the second byte if:
-                    //   we wrote the first byte
-                    overwrite_0.clone()
-                    //   and this isn't the start of the next word (which implies `overwrite_0`),
-                    - env.equal(&(read_address.clone() + Env::constant(1)), &next_word_addr)
-                    //   or this byte was read from `addr`
-                    + env.equal(&bytes_to_preserve_in_register, &Env::constant(1))
-                };
-                let overwrite_2 = {
-                    // We write the third byte if:
-                    //   we wrote the second byte
-                    overwrite_1.clone()
-                    //   and this isn't the start of the next word (which implies `overwrite_1`),
-                    - env.equal(&(read_address.clone() + Env::constant(2)), &next_word_addr)
-                    //   or this byte was read from `addr`
-                    + env.equal(&bytes_to_preserve_in_register, &Env::constant(2))
-                };
-                let overwrite_3 = {
-                    // We write the fourth byte if:
-                    //   we wrote the third byte
-                    overwrite_2.clone()
-                    //   and this isn't the start of the next word (which implies `overwrite_2`),
-                    - env.equal(&(read_address.clone() + Env::constant(3)), &next_word_addr)
-                    //   or this byte was read from `addr`
-                    + env.equal(&bytes_to_preserve_in_register, &Env::constant(3))
+                    let overwrite_0 = {
+                        // We always write the first byte if we're not preserving it, since it will
+                        // have been read from `addr`.
+                        env.equal(&bytes_to_preserve_in_register, &Env::constant(0))
+                    };
+                    let overwrite_1 = {
+                        // We write the second byte if:
+                        //   we wrote the first byte
+                        overwrite_0.clone()
+                        //   and this isn't the start of the next word (which implies `overwrite_0`),
+                        - env.equal(&(read_address.clone() + Env::constant(1)), &next_word_addr)
+                        //   or this byte was read from `addr`
+                        + env.equal(&bytes_to_preserve_in_register, &Env::constant(1))
+                    };
+                    let overwrite_2 = {
+                        // We write the third byte if:
+                        //   we wrote the second byte
+                        overwrite_1.clone()
+                        //   and this isn't the start of the next word (which implies `overwrite_1`),
+                        - env.equal(&(read_address.clone() + Env::constant(2)), &next_word_addr)
+                        //   or this byte was read from `addr`
+                        + env.equal(&bytes_to_preserve_in_register, &Env::constant(2))
+                    };
+                    let overwrite_3 = {
+                        // We write the fourth byte if:
+                        //   we wrote the third byte
+                        overwrite_2.clone()
+                        //   and this isn't the start of the next word (which implies `overwrite_2`),
+                        - env.equal(&(read_address.clone() + Env::constant(3)), &next_word_addr)
+                        //   or this byte was read from `addr`
+                        + env.equal(&bytes_to_preserve_in_register, &Env::constant(3))
+                    };
+                    [overwrite_0, overwrite_1, overwrite_2, overwrite_3]
                 };
-                [overwrite_0, overwrite_1, overwrite_2, overwrite_3]
-            };
 
-            let value = {
-                let value = ((overwrite_0.clone() * m0
-                    + (Env::constant(1) - overwrite_0.clone()) * r0)
-                    * Env::constant(1 << 24))
-                    + ((overwrite_1.clone() * m1 + (Env::constant(1) - overwrite_1.clone()) * r1)
-                        * Env::constant(1 << 16))
-                    + ((overwrite_2.clone() * m2 + (Env::constant(1) - overwrite_2.clone()) * r2)
-                        * Env::constant(1 << 8))
-                    + (overwrite_3.clone() * m3 + (Env::constant(1) - overwrite_3.clone()) * r3);
-                let pos = env.alloc_scratch();
-                env.copy(&value, pos)
+                let value = {
+                    let value = ((overwrite_0.clone() * m0
+                        + (Env::constant(1) - overwrite_0.clone()) * r0)
+                        * Env::constant(1 << 24))
+                        + ((overwrite_1.clone() * m1
+                            + (Env::constant(1) - overwrite_1.clone()) * r1)
+                            * Env::constant(1 << 16))
+                        + ((overwrite_2.clone() * m2
+                            + (Env::constant(1) - overwrite_2.clone()) * r2)
+                            * Env::constant(1 << 8))
+                        + (overwrite_3.clone() * m3
+                            + (Env::constant(1) - overwrite_3.clone()) * r3);
+                    let pos = env.alloc_scratch();
+                    env.copy(&value, pos)
+                };
+                (value, overwrite_0 + overwrite_1 + overwrite_2 + overwrite_3)
             };
 
             // Update the preimage key.
@@ -1381,10 +1864,7 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RTypeInstructi
                 Env::constant(0u32),
             );
             // Return the number of bytes read.
-            env.write_register(
-                &Env::constant(2),
-                overwrite_0 + overwrite_1 + overwrite_2 + overwrite_3,
-            );
+            env.write_register(&Env::constant(2), bytes_read);
             // Set the error register to 0.
             env.write_register(&Env::constant(7), Env::constant(0u32));
 
@@ -1394,16 +1874,15 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RTypeInstructi
         }
         RTypeInstruction::SyscallWriteOther => {
             let fd_id = env.read_register(&Env::constant(4));
+            let addr = env.read_register(&Env::constant(5));
             let write_length = env.read_register(&Env::constant(6));
-            let mut check_equal = |expected_fd_id: u32| {
-                // FIXME: Requires constraints
-                let pos = env.alloc_scratch();
-                unsafe { env.test_zero(&(fd_id.clone() - Env::constant(expected_fd_id)), pos) }
-            };
-            let is_stdout = check_equal(FD_STDOUT);
-            let is_stderr = check_equal(FD_STDERR);
-            let is_preimage_write = check_equal(FD_PREIMAGE_WRITE);
-            let is_hint_write = check_equal(FD_HINT_WRITE);
+            // The output of the program is part of its public claim, so the
+            // file descriptor is constrained
+            let is_stdout = env.equal(&fd_id, &Env::constant(FD_STDOUT));
+            let is_stderr = env.equal(&fd_id, &Env::constant(FD_STDERR));
+            let is_preimage_write = env.equal(&fd_id, &Env::constant(FD_PREIMAGE_WRITE));
+            let is_hint_write = env.equal(&fd_id, &Env::constant(FD_HINT_WRITE));
+            env.report_stdout_write(&is_stdout, &addr, &write_length);
 
             // FIXME: Should assert that `is_preimage_write` and `is_hint_write` cannot be true
             // here.
@@ -1451,6 +1930,33 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RTypeInstructi
             env.set_instruction_pointer(next_instruction_pointer.clone());
             env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
         }
+        RTypeInstruction::SyscallKeccak256 => {
+            let addr = env.read_register(&Env::constant(4));
+            let length = env.read_register(&Env::constant(5));
+            let output = env.read_register(&Env::constant(6));
+            let is_last = env.is_zero(&length);
+            let absorbed = {
+                let pos = env.alloc_scratch();
+                unsafe { env.request_keccak(&addr, &length, &output, pos) }
+            };
+            lookup_keccak_precompile(env, &absorbed, &is_last);
+            env.write_register(&Env::constant(4), addr + absorbed.clone());
+            env.write_register(&Env::constant(5), length - absorbed);
+            // The number of the system call is kept until the last execution
+            env.write_register(
+                &Env::constant(2),
+                (Env::constant(1) - is_last.clone()) * Env::constant(SYSCALL_KECCAK256),
+            );
+            env.write_register(&Env::constant(7), Env::constant(0));
+            // The system call is executed again until the input is absorbed
+            env.set_instruction_pointer(
+                is_last.clone() * next_instruction_pointer.clone()
+                    + (Env::constant(1) - is_last.clone()) * instruction_pointer,
+            );
+            env.set_next_instruction_pointer(
+                next_instruction_pointer + is_last * Env::constant(4u32),
+            );
+        }
         RTypeInstruction::SyscallOther => {
             let syscall_num = env.read_register(&Env::constant(2));
             let is_sysbrk = env.equal(&syscall_num, &Env::constant(SYSCALL_BRK));
@@ -1728,22 +2234,135 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RTypeInstructi
             env.set_instruction_pointer(next_instruction_pointer.clone());
             env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
         }
+        RTypeInstruction::MultiplyAdd
+        | RTypeInstruction::MultiplyAddUnsigned
+        | RTypeInstruction::MultiplySub
+        | RTypeInstruction::MultiplySubUnsigned => {
+            let rs = env.read_register(&rs);
+            let rt = env.read_register(&rt);
+            let (product_hi, product_lo) = {
+                // Fixme: constrain
+                let hi_pos = env.alloc_scratch();
+                let lo_pos = env.alloc_scratch();
+                match instr {
+                    RTypeInstruction::MultiplyAdd | RTypeInstruction::MultiplySub => unsafe {
+                        env.mul_hi_lo_signed(&rs, &rt, hi_pos, lo_pos)
+                    },
+                    _ => unsafe { env.mul_hi_lo(&rs, &rt, hi_pos, lo_pos) },
+                }
+            };
+            let hi = env.read_register(&Env::constant(REGISTER_HI as u32));
+            let lo = env.read_register(&Env::constant(REGISTER_LO as u32));
+            let (hi, lo) = match instr {
+                RTypeInstruction::MultiplyAdd | RTypeInstruction::MultiplyAddUnsigned => {
+                    add_hi_lo(env, (hi, lo), (product_hi, product_lo))
+                }
+                _ => sub_hi_lo(env, (hi, lo), (product_hi, product_lo)),
+            };
+            env.write_register(&Env::constant(REGISTER_HI as u32), hi);
+            env.write_register(&Env::constant(REGISTER_LO as u32), lo);
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+        }
+    };
+}
+
+/// Returns the 64-bit sum, modulo 2^64, of the values `x` and `y`, given as
+/// their 32 most and least significant bits, as for the registers `hi` and
+/// `lo`.
+fn add_hi_lo<Env: InterpreterEnv>(
+    env: &mut Env,
+    (x_hi, x_lo): (Env::Variable, Env::Variable),
+    (y_hi, y_lo): (Env::Variable, Env::Variable),
+) -> (Env::Variable, Env::Variable) {
+    let two_to_32 = Env::constant(1 << 16) * Env::constant(1 << 16);
+    let add = |env: &mut Env, x: Env::Variable, y: Env::Variable| {
+        let out_pos = env.alloc_scratch();
+        let overflow_pos = env.alloc_scratch();
+        let (out, overflow) = unsafe { env.add_witness(&x, &y, out_pos, overflow_pos) };
+        // FIXME: Requires a range check
+        env.assert_boolean(overflow.clone());
+        env.assert_equal(out.clone() + overflow.clone() * two_to_32.clone(), x + y);
+        (out, overflow)
+    };
+    let (lo, carry) = add(env, x_lo, y_lo);
+    let (hi, _) = add(env, x_hi, y_hi);
+    let (hi, _) = add(env, hi, carry);
+    (hi, lo)
+}
+
+/// Returns the 64-bit difference, modulo 2^64, of the values `x` and `y`,
+/// given as their 32 most and least significant bits, as for the registers
+/// `hi` and `lo`.
+fn sub_hi_lo<Env: InterpreterEnv>(
+    env: &mut Env,
+    (x_hi, x_lo): (Env::Variable, Env::Variable),
+    (y_hi, y_lo): (Env::Variable, Env::Variable),
+) -> (Env::Variable, Env::Variable) {
+    let two_to_32 = Env::constant(1 << 16) * Env::constant(1 << 16);
+    let sub = |env: &mut Env, x: Env::Variable, y: Env::Variable| {
+        let out_pos = env.alloc_scratch();
+        let underflow_pos = env.alloc_scratch();
+        let (out, underflow) = unsafe { env.sub_witness(&x, &y, out_pos, underflow_pos) };
+        // FIXME: Requires a range check
+        env.assert_boolean(underflow.clone());
+        env.assert_equal(out.clone() + y, x + underflow.clone() * two_to_32.clone());
+        (out, underflow)
     };
+    let (lo, borrow) = sub(env, x_lo, y_lo);
+    let (hi, _) = sub(env, x_hi, y_hi);
+    let (hi, _) = sub(env, hi, borrow);
+    (hi, lo)
+}
+
+/// Binds the bytes absorbed by an execution of the Keccak precompile, and the
+/// digest written by the last one, to the Keccak sub-trace through the
+/// [LookupTableIDs::SyscallLookup] table, as for the preimages.
+// FIXME: the bytes are not bound to the memory, and their number to the
+// length of the input
+fn lookup_keccak_precompile<Env: InterpreterEnv>(
+    env: &mut Env,
+    absorbed: &Env::Variable,
+    is_last: &Env::Variable,
+) {
+    let hint = |env: &mut Env, hint| {
+        let pos = env.alloc_scratch();
+        unsafe { env.keccak_hint(hint, pos) }
+    };
+    let hash_counter = hint(env, KeccakHint::HashCounter);
+    let byte_counter = hint(env, KeccakHint::ByteCounter);
+    let mut num_bytes = Env::constant(0);
+    for i in 0..KECCAK_PRECOMPILE_CHUNK_LEN {
+        let byte = hint(env, KeccakHint::Byte(i));
+        let has_byte = hint(env, KeccakHint::HasByte(i));
+        env.assert_boolean(has_byte.clone());
+        env.lookup_8bits(&byte);
+        // COMMUNICATION CHANNEL: Write the byte of the input
+        env.add_lookup(Lookup::write_if(
+            has_byte.clone(),
+            LookupTableIDs::SyscallLookup,
+            vec![
+                hash_counter.clone(),
+                byte_counter.clone() + Env::constant(i as u32),
+                byte,
+            ],
+        ));
+        num_bytes = num_bytes + has_byte;
+    }
+    env.assert_equal(num_bytes, absorbed.clone());
+    // COMMUNICATION CHANNEL: Read the digest
+    let digest = hint(env, KeccakHint::Digest);
+    env.add_lookup(Lookup::read_if(
+        is_last.clone(),
+        LookupTableIDs::SyscallLookup,
+        vec![hash_counter, digest],
+    ));
 }
 
 pub fn interpret_jtype<Env: InterpreterEnv>(env: &mut Env, instr: JTypeInstruction) {
     let instruction_pointer = env.get_instruction_pointer();
     let next_instruction_pointer = env.get_next_instruction_pointer();
-    let instruction = {
-        let v0 = env.read_memory(&instruction_pointer);
-        let v1 = env.read_memory(&(instruction_pointer.clone() + Env::constant(1)));
-        let v2 = env.read_memory(&(instruction_pointer.clone() + Env::constant(2)));
-        let v3 = env.read_memory(&(instruction_pointer.clone() + Env::constant(3)));
-        (v0 * Env::constant(1 << 24))
-            + (v1 * Env::constant(1 << 16))
-            + (v2 * Env::constant(1 << 8))
-            + v3
-    };
+    let instruction = env.read_memory_word(&instruction_pointer, 4);
     let opcode = {
         let pos = env.alloc_scratch();
         unsafe { env.bitmask(&instruction, 32, 26, pos) }
@@ -1779,16 +2398,7 @@ pub fn interpret_jtype<Env: InterpreterEnv>(env: &mut Env, instr: JTypeInstructi
 pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: ITypeInstruction) {
     let instruction_pointer = env.get_instruction_pointer();
     let next_instruction_pointer = env.get_next_instruction_pointer();
-    let instruction = {
-        let v0 = env.read_memory(&instruction_pointer);
-        let v1 = env.read_memory(&(instruction_pointer.clone() + Env::constant(1)));
-        let v2 = env.read_memory(&(instruction_pointer.clone() + Env::constant(2)));
-        let v3 = env.read_memory(&(instruction_pointer.clone() + Env::constant(3)));
-        (v0 * Env::constant(1 << 24))
-            + (v1 * Env::constant(1 << 16))
-            + (v2 * Env::constant(1 << 8))
-            + v3
-    };
+    let instruction = env.read_memory_word(&instruction_pointer, 4);
     let opcode = {
         let pos = env.alloc_scratch();
         unsafe { env.bitmask(&instruction, 32, 26, pos) }
@@ -2106,9 +2716,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: ITypeInstructi
                 // FIXME: Requires a range check
                 res
             };
-            let v0 = env.read_memory(&addr);
-            let v1 = env.read_memory(&(addr.clone() + Env::constant(1)));
-            let value = (v0 * Env::constant(1 << 8)) + v1;
+            let value = env.read_memory_word(&addr, 2);
             let value = env.sign_extend(&value, 16);
             env.write_register(&dest, value);
             env.set_instruction_pointer(next_instruction_pointer.clone());
@@ -2127,14 +2735,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: ITypeInstructi
                 res
             };
             // We load 4 bytes, i.e. one word.
-            let v0 = env.read_memory(&addr);
-            let v1 = env.read_memory(&(addr.clone() + Env::constant(1)));
-            let v2 = env.read_memory(&(addr.clone() + Env::constant(2)));
-            let v3 = env.read_memory(&(addr.clone() + Env::constant(3)));
-            let value = (v0 * Env::constant(1 << 24))
-                + (v1 * Env::constant(1 << 16))
-                + (v2 * Env::constant(1 << 8))
-                + v3;
+            let value = env.read_memory_word(&addr, 4);
             env.write_register(&dest, value);
             env.set_instruction_pointer(next_instruction_pointer.clone());
             env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
@@ -2169,9 +2770,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: ITypeInstructi
                 // FIXME: Requires a range check
                 res
             };
-            let v0 = env.read_memory(&addr);
-            let v1 = env.read_memory(&(addr.clone() + Env::constant(1)));
-            let value = v0 * Env::constant(1 << 8) + v1;
+            let value = env.read_memory_word(&addr, 2);
             env.write_register(&dest, value);
             env.set_instruction_pointer(next_instruction_pointer.clone());
             env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
@@ -2194,15 +2793,25 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: ITypeInstructi
                 unsafe { env.bitmask(&addr, 2, 0, pos) }
             };
 
-            let overwrite_3 = env.equal(&byte_subaddr, &Env::constant(0));
-            let overwrite_2 = env.equal(&byte_subaddr, &Env::constant(1)) + overwrite_3.clone();
-            let overwrite_1 = env.equal(&byte_subaddr, &Env::constant(2)) + overwrite_2.clone();
-            let overwrite_0 = env.equal(&byte_subaddr, &Env::constant(3)) + overwrite_1.clone();
+            // The bytes from the one at `addr` to the least significant byte
+            // of its word are accessed, i.e. the ones following `addr` in
+            // big-endian and the ones preceding it in little-endian.
+            let endianness = env.endianness();
+            let lane = |i| Env::constant(endianness.byte_offset(4, i));
+            let step = endianness.byte_step();
+
+            let overwrite_3 = env.equal(&byte_subaddr, &lane(0));
+            let overwrite_2 = env.equal(&byte_subaddr, &lane(1)) + overwrite_3.clone();
+            let overwrite_1 = env.equal(&byte_subaddr, &lane(2)) + overwrite_2.clone();
+            let overwrite_0 = env.equal(&byte_subaddr, &lane(3)) + overwrite_1.clone();
 
-            let m0 = env.read_memory(&addr);
-            let m1 = env.read_memory(&(addr.clone() + Env::constant(1)));
-            let m2 = env.read_memory(&(addr.clone() + Env::constant(2)));
-            let m3 = env.read_memory(&(addr.clone() + Env::constant(3)));
+            let offsets = [
+                (0, overwrite_0.clone()),
+                (step, overwrite_1.clone()),
+                (2 * step, overwrite_2.clone()),
+                (3 * step, overwrite_3.clone()),
+            ];
+            let [m0, m1, m2, m3] = env.read_memory_bytes(&addr, &offsets).try_into().unwrap();
 
             let [r0, r1, r2, r3] = {
                 let initial_register_value = env.read_register(&rt);
@@ -2263,18 +2872,26 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: ITypeInstructi
             };
             env.range_check2(&byte_subaddr);
 
-            let overwrite_0 = env.equal(&byte_subaddr, &Env::constant(3));
-            let overwrite_1 = env.equal(&byte_subaddr, &Env::constant(2)) + overwrite_0.clone();
-            let overwrite_2 = env.equal(&byte_subaddr, &Env::constant(1)) + overwrite_1.clone();
-            let overwrite_3 = env.equal(&byte_subaddr, &Env::constant(0)) + overwrite_2.clone();
+            let endianness = env.endianness();
+            let lane = |i| Env::constant(endianness.byte_offset(4, i));
+            let step = endianness.byte_step();
+
+            let overwrite_0 = env.equal(&byte_subaddr, &lane(3));
+            let overwrite_1 = env.equal(&byte_subaddr, &lane(2)) + overwrite_0.clone();
+            let overwrite_2 = env.equal(&byte_subaddr, &lane(1)) + overwrite_1.clone();
+            let overwrite_3 = env.equal(&byte_subaddr, &lane(0)) + overwrite_2.clone();
 
             // The `-3` here feels odd, but simulates the `<< 24` in cannon, and matches the
-            // behavior defined in the spec.
+            // behavior defined in the spec, the bytes accessed being the ones from the most
+            // significant byte of the word to the one at `addr`.
             // See e.g. 'MIPS IV Instruction Set' Rev 3.2, Table A-31 for reference.
-            let m0 = env.read_memory(&(addr.clone() - Env::constant(3)));
-            let m1 = env.read_memory(&(addr.clone() - Env::constant(2)));
-            let m2 = env.read_memory(&(addr.clone() - Env::constant(1)));
-            let m3 = env.read_memory(&addr);
+            let offsets = [
+                (-3 * step, overwrite_0.clone()),
+                (-2 * step, overwrite_1.clone()),
+                (-step, overwrite_2.clone()),
+                (0, overwrite_3.clone()),
+            ];
+            let [m0, m1, m2, m3] = env.read_memory_bytes(&addr, &offsets).try_into().unwrap();
 
             let [r0, r1, r2, r3] = {
                 let initial_register_value = env.read_register(&rt);
@@ -2366,8 +2983,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: ITypeInstructi
             env.lookup_8bits(&v0);
             env.lookup_8bits(&v1);
 
-            env.write_memory(&addr, v0);
-            env.write_memory(&(addr.clone() + Env::constant(1)), v1);
+            env.write_memory_word(&addr, vec![v0, v1]);
             env.set_instruction_pointer(next_instruction_pointer.clone());
             env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
         }
@@ -2417,10 +3033,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: ITypeInstructi
                     - v3.clone();
                 env.is_zero(&res)
             };
-            env.write_memory(&addr, v0);
-            env.write_memory(&(addr.clone() + Env::constant(1)), v1);
-            env.write_memory(&(addr.clone() + Env::constant(2)), v2);
-            env.write_memory(&(addr.clone() + Env::constant(3)), v3);
+            env.write_memory_word(&addr, vec![v0, v1, v2, v3]);
             env.set_instruction_pointer(next_instruction_pointer.clone());
             env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
         }
@@ -2461,10 +3074,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: ITypeInstructi
             env.lookup_8bits(&v2);
             env.lookup_8bits(&v3);
 
-            env.write_memory(&addr, v0);
-            env.write_memory(&(addr.clone() + Env::constant(1)), v1);
-            env.write_memory(&(addr.clone() + Env::constant(2)), v2);
-            env.write_memory(&(addr.clone() + Env::constant(3)), v3);
+            env.write_memory_word(&addr, vec![v0, v1, v2, v3]);
             // Write status flag.
             env.write_register(&rt, Env::constant(1));
             env.set_instruction_pointer(next_instruction_pointer.clone());
@@ -2489,15 +3099,25 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: ITypeInstructi
             };
             env.range_check2(&byte_subaddr);
 
-            let overwrite_3 = env.equal(&byte_subaddr, &Env::constant(0));
-            let overwrite_2 = env.equal(&byte_subaddr, &Env::constant(1)) + overwrite_3.clone();
-            let overwrite_1 = env.equal(&byte_subaddr, &Env::constant(2)) + overwrite_2.clone();
-            let overwrite_0 = env.equal(&byte_subaddr, &Env::constant(3)) + overwrite_1.clone();
-
-            let m0 = env.read_memory(&addr);
-            let m1 = env.read_memory(&(addr.clone() + Env::constant(1)));
-            let m2 = env.read_memory(&(addr.clone() + Env::constant(2)));
-            let m3 = env.read_memory(&(addr.clone() + Env::constant(3)));
+            // The bytes from the one at `addr` to the least significant byte
+            // of its word are accessed, i.e. the ones following `addr` in
+            // big-endian and the ones preceding it in little-endian.
+            let endianness = env.endianness();
+            let lane = |i| Env::constant(endianness.byte_offset(4, i));
+            let step = endianness.byte_step();
+
+            let overwrite_3 = env.equal(&byte_subaddr, &lane(0));
+            let overwrite_2 = env.equal(&byte_subaddr, &lane(1)) + overwrite_3.clone();
+            let overwrite_1 = env.equal(&byte_subaddr, &lane(2)) + overwrite_2.clone();
+            let overwrite_0 = env.equal(&byte_subaddr, &lane(3)) + overwrite_1.clone();
+
+            let offsets = [
+                (0, overwrite_0.clone()),
+                (step, overwrite_1.clone()),
+                (2 * step, overwrite_2.clone()),
+                (3 * step, overwrite_3.clone()),
+            ];
+            let [m0, m1, m2, m3] = env.read_memory_bytes(&addr, &offsets).try_into().unwrap();
 
             let [r0, r1, r2, r3] = {
                 let initial_register_value = env.read_register(&rt);
@@ -2554,10 +3174,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: ITypeInstructi
                 )
             };
 
-            env.write_memory(&addr, v0);
-            env.write_memory(&(addr.clone() + Env::constant(1)), v1);
-            env.write_memory(&(addr.clone() + Env::constant(2)), v2);
-            env.write_memory(&(addr.clone() + Env::constant(3)), v3);
+            env.write_memory_bytes(&addr, &offsets, vec![v0, v1, v2, v3]);
             env.set_instruction_pointer(next_instruction_pointer.clone());
             env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
         }
@@ -2579,18 +3196,26 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: ITypeInstructi
             };
             env.range_check2(&byte_subaddr);
 
-            let overwrite_0 = env.equal(&byte_subaddr, &Env::constant(3));
-            let overwrite_1 = env.equal(&byte_subaddr, &Env::constant(2)) + overwrite_0.clone();
-            let overwrite_2 = env.equal(&byte_subaddr, &Env::constant(1)) + overwrite_1.clone();
-            let overwrite_3 = env.equal(&byte_subaddr, &Env::constant(0)) + overwrite_2.clone();
+            let endianness = env.endianness();
+            let lane = |i| Env::constant(endianness.byte_offset(4, i));
+            let step = endianness.byte_step();
+
+            let overwrite_0 = env.equal(&byte_subaddr, &lane(3));
+            let overwrite_1 = env.equal(&byte_subaddr, &lane(2)) + overwrite_0.clone();
+            let overwrite_2 = env.equal(&byte_subaddr, &lane(1)) + overwrite_1.clone();
+            let overwrite_3 = env.equal(&byte_subaddr, &lane(0)) + overwrite_2.clone();
 
             // The `-3` here feels odd, but simulates the `<< 24` in cannon, and matches the
-            // behavior defined in the spec.
+            // behavior defined in the spec, the bytes accessed being the ones from the most
+            // significant byte of the word to the one at `addr`.
             // See e.g. 'MIPS IV Instruction Set' Rev 3.2, Table A-31 for reference.
-            let m0 = env.read_memory(&(addr.clone() - Env::constant(3)));
-            let m1 = env.read_memory(&(addr.clone() - Env::constant(2)));
-            let m2 = env.read_memory(&(addr.clone() - Env::constant(1)));
-            let m3 = env.read_memory(&addr);
+            let offsets = [
+                (-3 * step, overwrite_0.clone()),
+                (-2 * step, overwrite_1.clone()),
+                (-step, overwrite_2.clone()),
+                (0, overwrite_3.clone()),
+            ];
+            let [m0, m1, m2, m3] = env.read_memory_bytes(&addr, &offsets).try_into().unwrap();
 
             let [r0, r1, r2, r3] = {
                 let initial_register_value = env.read_register(&rt);
@@ -2647,10 +3272,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: ITypeInstructi
                 )
             };
 
-            env.write_memory(&(addr.clone() - Env::constant(3)), v0);
-            env.write_memory(&(addr.clone() - Env::constant(2)), v1);
-            env.write_memory(&(addr.clone() - Env::constant(1)), v2);
-            env.write_memory(&addr.clone(), v3);
+            env.write_memory_bytes(&addr, &offsets, vec![v0, v1, v2, v3]);
             env.set_instruction_pointer(next_instruction_pointer.clone());
             env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
         }
@@ -35,4 +35,35 @@ pub const MAXIMUM_DEGREE_CONSTRAINTS: u64 = 6;
 
 /// Total number of constraints for all instructions, including the constraints
 /// added for the selectors.
-pub const TOTAL_NUMBER_OF_CONSTRAINTS: usize = 466;
+pub const TOTAL_NUMBER_OF_CONSTRAINTS: usize = 559;
+
+/// The byte order of the memory of the guest, used to compose the words read
+/// by the loads, the stores and the instruction fetches. The programs built
+/// for the `mips` targets are big-endian, the ones built for the `mipsel`
+/// targets little-endian.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Endianness {
+    #[default]
+    Big,
+    Little,
+}
+
+impl Endianness {
+    /// Returns the offset, from the address of an access of `size` bytes, of
+    /// its byte of index `i`, the byte of index 0 being the most significant
+    pub fn byte_offset(self, size: u32, i: u32) -> u32 {
+        match self {
+            Endianness::Big => i,
+            Endianness::Little => size - 1 - i,
+        }
+    }
+
+    /// Returns the difference between the addresses of two consecutive bytes
+    /// of a word, from the most to the least significant one
+    pub fn byte_step(self) -> i32 {
+        match self {
+            Endianness::Big => 1,
+            Endianness::Little => -1,
+        }
+    }
+}
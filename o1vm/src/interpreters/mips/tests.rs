@@ -1,19 +1,36 @@
 // Here live the unit tests for the MIPS instructions
 use crate::{
     interpreters::mips::{
-        column::{N_MIPS_REL_COLS, N_MIPS_SEL_COLS},
+        column::{
+            SelectorLayout, N_MIPS_REL_COLS, N_MIPS_SEL_COLS, N_PACKED_SEL_HIGH_COLS,
+            N_PACKED_SEL_LOW_COLS, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE,
+        },
         constraints,
         interpreter::{self, debugging::InstructionParts, InterpreterEnv},
         tests_helpers::*,
-        ITypeInstruction, JTypeInstruction, RTypeInstruction, MAXIMUM_DEGREE_CONSTRAINTS,
-        TOTAL_NUMBER_OF_CONSTRAINTS,
+        Endianness, ITypeInstruction, JTypeInstruction, RTypeInstruction,
+        MAXIMUM_DEGREE_CONSTRAINTS, TOTAL_NUMBER_OF_CONSTRAINTS,
     },
+    lookups::{Lookup, LookupTableIDs},
+    merkle_memory::{self, MerkleMemory},
     preimage_oracle::PreImageOracleT,
-    E,
+    RelationColumnType, E,
 };
 
-use kimchi::o1_utils;
+use ark_ff::{One, Zero};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as D};
+use kimchi::{
+    circuits::{
+        berkeley_columns::BerkeleyChallenges,
+        expr::{ColumnEvaluations, Constants, ExprError},
+        gate::CurrOrNext,
+    },
+    o1_utils,
+    proof::PointEvaluations,
+};
+use kimchi_msm::columns::Column;
 use mina_curves::pasta::Fp;
+use mina_poseidon::pasta::fp_kimchi;
 use rand::Rng;
 use strum::{EnumCount, IntoEnumIterator};
 
@@ -73,7 +90,11 @@ fn test_all_instructions_have_a_usize_representation_smaller_than_the_number_of_
 mod rtype {
 
     use super::*;
-    use crate::interpreters::mips::{interpreter::interpret_rtype, RTypeInstruction};
+    use crate::interpreters::{
+        keccak::precompile::SYSCALL_KECCAK256,
+        mips::{interpreter::interpret_rtype, RTypeInstruction},
+    };
+    use sha3::{Digest, Keccak256};
 
     #[test]
     fn test_unit_syscall_read_preimage() {
@@ -122,6 +143,8 @@ mod rtype {
             // Set maximum number of bytes to read in this call
             dummy_env.registers[6] = rng.gen_range(1..=4);
 
+            dummy_env
+                .allocate_scratch_state(Instruction::RType(RTypeInstruction::SyscallReadPreimage));
             interpret_rtype(&mut dummy_env, RTypeInstruction::SyscallReadPreimage);
 
             // Update the address to store the next bytes with the offset
@@ -138,7 +161,7 @@ mod rtype {
         let length_byte = u64::to_be_bytes(preimage.len() as u64);
         for (i, b) in length_byte.iter().enumerate() {
             assert_eq!(
-                dummy_env.memory[0].1[i + addr as usize],
+                dummy_env.memory.get(i as u32 + addr),
                 *b,
                 "{}-th length byte does not match",
                 i
@@ -147,7 +170,7 @@ mod rtype {
         // Check that the preimage bytes are stored afterwards in the memory
         for (i, b) in preimage.iter().enumerate() {
             assert_eq!(
-                dummy_env.memory[0].1[i + addr as usize + 8],
+                dummy_env.memory.get(i as u32 + addr + 8),
                 *b,
                 "{}-th preimage byte does not match",
                 i
@@ -155,6 +178,76 @@ mod rtype {
         }
     }
 
+    #[test]
+    fn test_unit_syscall_read_advice() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let mut dummy_env = dummy_env(&mut rng);
+        // Instruction:  syscall (Read 7)
+        let advice: Vec<u8> = (0..6).map(|_| rng.gen_range(0u8..=255)).collect();
+        dummy_env.enqueue_advice(&advice);
+        let addr = 100;
+        let mut read = 0;
+        loop {
+            dummy_env.reset_scratch_state();
+            dummy_env.reset_scratch_state_inverse();
+            dummy_env.registers[5] = addr + read;
+            dummy_env.registers[6] = 8;
+            dummy_env
+                .allocate_scratch_state(Instruction::RType(RTypeInstruction::SyscallReadAdvice));
+            interpret_rtype(&mut dummy_env, RTypeInstruction::SyscallReadAdvice);
+            // At most 4 bytes are read at once, and 0 when the advice is
+            // exhausted
+            assert!(dummy_env.registers[2] <= 4);
+            assert_eq!(dummy_env.registers[7], 0);
+            if dummy_env.registers[2] == 0 {
+                break;
+            }
+            read += dummy_env.registers[2];
+        }
+        assert_eq!(read, 6);
+        assert_eq!(
+            dummy_env.memory.page(0).unwrap()[addr as usize..addr as usize + 6],
+            advice
+        );
+    }
+
+    #[test]
+    fn test_unit_syscall_keccak256() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let mut dummy_env = dummy_env(&mut rng);
+        // Instruction: syscall (Keccak256)
+        let input: Vec<u8> = (0..10).map(|_| rng.gen_range(0u8..=255)).collect();
+        let (addr, output) = (100, 200);
+        dummy_env.memory.page_mut(0)[addr..addr + 10].copy_from_slice(&input);
+        dummy_env.registers[2] = SYSCALL_KECCAK256;
+        dummy_env.registers[4] = addr as u32;
+        dummy_env.registers[5] = 10;
+        dummy_env.registers[6] = output as u32;
+        let hash_counter = dummy_env.hash_counter;
+        let mut executions = 0;
+        // The system call is executed again until the input is absorbed, at
+        // most 4 bytes at a time
+        while dummy_env.registers[2] == SYSCALL_KECCAK256 {
+            dummy_env.reset_scratch_state();
+            dummy_env.reset_scratch_state_inverse();
+            dummy_env
+                .allocate_scratch_state(Instruction::RType(RTypeInstruction::SyscallKeccak256));
+            interpret_rtype(&mut dummy_env, RTypeInstruction::SyscallKeccak256);
+            executions += 1;
+        }
+        assert_eq!(executions, 4);
+        assert_eq!(dummy_env.registers[2], 0);
+        assert_eq!(dummy_env.registers[4], addr as u32 + 10);
+        assert_eq!(dummy_env.registers[5], 0);
+        let digest: [u8; 32] = Keccak256::digest(&input).into();
+        assert_eq!(
+            dummy_env.memory.page(0).unwrap()[output..output + 32],
+            digest
+        );
+        assert_eq!(dummy_env.hash_counter, hash_counter + 1);
+        assert!(dummy_env.keccak_env.is_some());
+    }
+
     #[test]
     fn test_unit_sub_instruction() {
         let mut rng = o1_utils::tests::make_test_rng(None);
@@ -183,6 +276,167 @@ mod rtype {
         interpret_rtype(&mut dummy_env, RTypeInstruction::Sub);
         assert_eq!(dummy_env.registers.general_purpose[reg_dst], exp_res);
     }
+
+    // Writes the instruction with the given opcode and function, reading the
+    // registers 1 and 2 and writing the register 3, and checks its decoding
+    fn write_rtype(
+        dummy_env: &mut crate::interpreters::mips::witness::Env<
+            ark_bn254::Fr,
+            OnDiskPreImageOracle,
+        >,
+        op_code: u32,
+        funct: u32,
+        instr: RTypeInstruction,
+    ) {
+        write_instruction(
+            dummy_env,
+            InstructionParts {
+                op_code,
+                rs: 1,
+                rt: 2,
+                rd: 3,
+                shamt: 0b00000,
+                funct,
+            },
+        );
+        assert_eq!(dummy_env.decode_instruction().0, Instruction::RType(instr));
+    }
+
+    #[test]
+    fn test_unit_multiply_accumulate_instructions() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let mut dummy_env = dummy_env(&mut rng);
+        let cases = [
+            (0b000000, RTypeInstruction::MultiplyAdd),
+            (0b000001, RTypeInstruction::MultiplyAddUnsigned),
+            (0b000100, RTypeInstruction::MultiplySub),
+            (0b000101, RTypeInstruction::MultiplySubUnsigned),
+        ];
+        for (funct, instr) in cases {
+            // Instruction: madd/maddu/msub/msubu $at, $v0
+            write_rtype(&mut dummy_env, 0b011100, funct, instr);
+            let (rs, rt): (u32, u32) = (rng.gen(), rng.gen());
+            let (hi, lo): (u32, u32) = (rng.gen(), rng.gen());
+            dummy_env.registers[1] = rs;
+            dummy_env.registers[2] = rt;
+            dummy_env.registers.hi = hi;
+            dummy_env.registers.lo = lo;
+            let product = match instr {
+                RTypeInstruction::MultiplyAdd | RTypeInstruction::MultiplySub => {
+                    (rs as i32 as i64 * rt as i32 as i64) as u64
+                }
+                _ => rs as u64 * rt as u64,
+            };
+            let acc = ((hi as u64) << 32) | lo as u64;
+            let expected = match instr {
+                RTypeInstruction::MultiplyAdd | RTypeInstruction::MultiplyAddUnsigned => {
+                    acc.wrapping_add(product)
+                }
+                _ => acc.wrapping_sub(product),
+            };
+            dummy_env.reset_scratch_state();
+            dummy_env.allocate_scratch_state(Instruction::RType(instr));
+            interpret_rtype(&mut dummy_env, instr);
+            assert_eq!(dummy_env.registers.hi, (expected >> 32) as u32);
+            assert_eq!(dummy_env.registers.lo, expected as u32);
+        }
+    }
+
+    #[test]
+    fn test_unit_multiply_accumulate_wraps_around() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let mut dummy_env = dummy_env(&mut rng);
+        // maddu with a carry from lo to hi and an overflow of hi
+        write_rtype(
+            &mut dummy_env,
+            0b011100,
+            0b000001,
+            RTypeInstruction::MultiplyAddUnsigned,
+        );
+        dummy_env.registers[1] = 2;
+        dummy_env.registers[2] = 0x8000_0000;
+        dummy_env.registers.hi = 0xFFFF_FFFF;
+        dummy_env.registers.lo = 0x0000_0001;
+        interpret_rtype(&mut dummy_env, RTypeInstruction::MultiplyAddUnsigned);
+        assert_eq!(dummy_env.registers.hi, 0);
+        assert_eq!(dummy_env.registers.lo, 1);
+
+        // msub of a negative product, with a borrow from hi to lo
+        write_rtype(
+            &mut dummy_env,
+            0b011100,
+            0b000100,
+            RTypeInstruction::MultiplySub,
+        );
+        dummy_env.registers[1] = -1i32 as u32;
+        dummy_env.registers[2] = 2;
+        dummy_env.registers.hi = 0;
+        dummy_env.registers.lo = 0xFFFF_FFFF;
+        dummy_env.reset_scratch_state();
+        dummy_env.allocate_scratch_state(Instruction::RType(RTypeInstruction::MultiplySub));
+        interpret_rtype(&mut dummy_env, RTypeInstruction::MultiplySub);
+        assert_eq!(dummy_env.registers.hi, 1);
+        assert_eq!(dummy_env.registers.lo, 1);
+    }
+
+    #[test]
+    fn test_unit_conditional_move_instructions() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let mut dummy_env = dummy_env(&mut rng);
+        let cases = [
+            (0b001010, RTypeInstruction::MoveZero, 0, true),
+            (0b001010, RTypeInstruction::MoveZero, 7, false),
+            (0b001011, RTypeInstruction::MoveNonZero, 0, false),
+            (0b001011, RTypeInstruction::MoveNonZero, 7, true),
+        ];
+        for (funct, instr, rt, moved) in cases {
+            // Instruction: movz/movn $v1, $at, $v0
+            write_rtype(&mut dummy_env, 0b000000, funct, instr);
+            dummy_env.registers[1] = 0xCAFE;
+            dummy_env.registers[2] = rt;
+            dummy_env.registers[3] = 0xBEEF;
+            dummy_env.reset_scratch_state();
+            dummy_env.reset_scratch_state_inverse();
+            dummy_env.allocate_scratch_state(Instruction::RType(instr));
+            interpret_rtype(&mut dummy_env, instr);
+            let expected = if moved { 0xCAFE } else { 0xBEEF };
+            assert_eq!(dummy_env.registers[3], expected);
+        }
+    }
+
+    #[test]
+    fn test_unit_count_leading_instructions() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let mut dummy_env = dummy_env(&mut rng);
+        for rs in [0, 1, 0x0000_FFFF, 0x8000_0000, 0xFFFF_FFFF, 0xF0F0_0000] {
+            // Instruction: clz $v1, $at
+            write_rtype(
+                &mut dummy_env,
+                0b011100,
+                0b100000,
+                RTypeInstruction::CountLeadingZeros,
+            );
+            dummy_env.registers[1] = rs;
+            dummy_env.reset_scratch_state();
+            dummy_env
+                .allocate_scratch_state(Instruction::RType(RTypeInstruction::CountLeadingZeros));
+            interpret_rtype(&mut dummy_env, RTypeInstruction::CountLeadingZeros);
+            assert_eq!(dummy_env.registers[3], rs.leading_zeros());
+
+            // Instruction: clo $v1, $at
+            write_rtype(
+                &mut dummy_env,
+                0b011100,
+                0b100001,
+                RTypeInstruction::CountLeadingOnes,
+            );
+            dummy_env.reset_scratch_state();
+            dummy_env
+                .allocate_scratch_state(Instruction::RType(RTypeInstruction::CountLeadingOnes));
+            interpret_rtype(&mut dummy_env, RTypeInstruction::CountLeadingOnes);
+            assert_eq!(dummy_env.registers[3], rs.leading_ones());
+        }
+    }
 }
 
 mod itype {
@@ -266,6 +520,93 @@ mod itype {
         assert_eq!(dummy_env.registers.general_purpose[1], 0xa0000);
     }
 
+    #[test]
+    fn test_unit_store32_instruction_with_merkle_memory() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        // sw instruction
+        let mut dummy_env = dummy_env(&mut rng);
+        // Instruction: 0b101011 11101 00100 00000 00000 000000 sw $a0, 0(29)
+        let addr: u32 = rng.gen_range(0u32..100u32) & !3;
+        dummy_env.registers[29] = addr;
+        dummy_env.registers[4] = 0x8192A3B4;
+        write_instruction(
+            &mut dummy_env,
+            InstructionParts {
+                op_code: 0b101011,
+                rs: 0b11101,
+                rt: 0b00100,
+                rd: 0b00000,
+                shamt: 0b00000,
+                funct: 0b000000,
+            },
+        );
+        dummy_env.enable_merkle_memory();
+        let root = dummy_env.merkle_memory.as_ref().unwrap().root();
+        interpret_itype(&mut dummy_env, ITypeInstruction::Store32);
+
+        // One access for each byte of the instruction, and of the word stored
+        let accesses = dummy_env.take_authenticated_accesses();
+        assert_eq!(accesses.len(), 8);
+        assert!(accesses[4..].iter().all(|access| access.addr == addr));
+        assert_eq!(accesses[7].new_word, 0x8192A3B4);
+        assert_eq!(
+            merkle_memory::verify_accesses(root, &accesses),
+            Some(MerkleMemory::from_memory(&dummy_env.memory).root())
+        );
+
+        // The accesses are sent to the Merkle memory channel
+        let mut con_env = constraints::Env::<Fp>::with_merkle_memory();
+        interpret_itype(&mut con_env, ITypeInstruction::Store32);
+        let merkle_memory_lookups = |lookups: Vec<Lookup<E<Fp>>>| {
+            lookups
+                .iter()
+                .filter(|lookup| lookup.table_id == LookupTableIDs::MerkleMemoryLookup)
+                .count()
+        };
+        assert_eq!(merkle_memory_lookups(con_env.get_lookups()), 8);
+        let mut con_env = constraints::Env::<Fp>::default();
+        interpret_itype(&mut con_env, ITypeInstruction::Store32);
+        assert_eq!(merkle_memory_lookups(con_env.get_lookups()), 0);
+    }
+
+    #[test]
+    fn test_snapshot_resume() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let mut dummy_env = dummy_env(&mut rng);
+        dummy_env.enqueue_advice(&[1, 2, 3]);
+        // Instruction: 0b101011 11101 00100 00000 00000 000000 sw $a0, 0(29)
+        dummy_env.registers[29] = 8;
+        dummy_env.registers[4] = 0x8192A3B4;
+        write_instruction(
+            &mut dummy_env,
+            InstructionParts {
+                op_code: 0b101011,
+                rs: 0b11101,
+                rt: 0b00100,
+                rd: 0b00000,
+                shamt: 0b00000,
+                funct: 0b000000,
+            },
+        );
+        interpret_itype(&mut dummy_env, ITypeInstruction::Store32);
+        let snapshot = serde_json::to_vec(&dummy_env.snapshot()).unwrap();
+
+        let mut resumed = super::dummy_env(&mut rng);
+        resumed.resume(serde_json::from_slice(&snapshot).unwrap());
+        assert_eq!(resumed.instruction_counter, dummy_env.instruction_counter);
+        assert_eq!(
+            resumed.registers.general_purpose,
+            dummy_env.registers.general_purpose
+        );
+        assert_eq!(
+            resumed.registers.next_instruction_pointer,
+            dummy_env.registers.next_instruction_pointer
+        );
+        assert_eq!(resumed.memory, dummy_env.memory);
+        assert_eq!(resumed.memory_write_index, dummy_env.memory_write_index);
+        assert_eq!(resumed.syscall_env.advice, [1, 2, 3]);
+    }
+
     #[test]
     fn test_unit_load16_instruction() {
         let mut rng = o1_utils::tests::make_test_rng(None);
@@ -277,8 +618,7 @@ mod itype {
         let addr: u32 = rng.gen_range(0u32..100u32);
         let aligned_addr: u32 = (addr / 4) * 4;
         dummy_env.registers[29] = aligned_addr;
-        let mem = &dummy_env.memory[0];
-        let mem = &mem.1;
+        let mem = dummy_env.memory.page(0).unwrap();
         let v0 = mem[aligned_addr as usize];
         let v1 = mem[(aligned_addr + 1) as usize];
         let v = ((v0 as u32) << 8) + (v1 as u32);
@@ -310,8 +650,7 @@ mod itype {
         let addr: u32 = rng.gen_range(0u32..100u32);
         let aligned_addr: u32 = (addr / 4) * 4;
         dummy_env.registers[29] = aligned_addr;
-        let mem = &dummy_env.memory[0];
-        let mem = &mem.1;
+        let mem = dummy_env.memory.page(0).unwrap();
         let v0 = mem[aligned_addr as usize];
         let v1 = mem[(aligned_addr + 1) as usize];
         let v2 = mem[(aligned_addr + 2) as usize];
@@ -331,6 +670,280 @@ mod itype {
         interpret_itype(&mut dummy_env, ITypeInstruction::Load32);
         assert_eq!(dummy_env.registers.general_purpose[4], exp_v);
     }
+
+    fn write_itype(
+        dummy_env: &mut crate::interpreters::mips::witness::Env<
+            ark_bn254::Fr,
+            OnDiskPreImageOracle,
+        >,
+        op_code: u32,
+        immediate: u32,
+        instr: ITypeInstruction,
+    ) {
+        write_instruction(
+            dummy_env,
+            InstructionParts {
+                op_code,
+                rs: 1,
+                rt: 2,
+                rd: immediate >> 11,
+                shamt: (immediate >> 6) & 0x1F,
+                funct: immediate & 0x3F,
+            },
+        );
+        assert_eq!(dummy_env.decode_instruction().0, Instruction::IType(instr));
+        dummy_env.reset_scratch_state();
+        dummy_env.reset_scratch_state_inverse();
+        dummy_env.allocate_scratch_state(Instruction::IType(instr));
+        interpret_itype(dummy_env, instr);
+    }
+
+    #[test]
+    fn test_unit_little_endian_load_store_instructions() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let mut dummy_env = dummy_env(&mut rng);
+        dummy_env.endianness = Endianness::Little;
+        let addr: u32 = rng.gen_range(0u32..100u32) & !3;
+        dummy_env.registers[1] = addr;
+
+        // sw $2, 0($1)
+        dummy_env.registers[2] = 0x8192A3B4;
+        write_itype(&mut dummy_env, 0x2b, 0, ITypeInstruction::Store32);
+        let mem = dummy_env.memory.page(0).unwrap();
+        assert_eq!(
+            mem[addr as usize..addr as usize + 4],
+            [0xB4, 0xA3, 0x92, 0x81]
+        );
+
+        // lh $2, 2($1)
+        write_itype(&mut dummy_env, 0x21, 2, ITypeInstruction::Load16);
+        assert_eq!(dummy_env.registers[2], 0xFFFF8192);
+
+        // lw $2, 0($1)
+        dummy_env.registers[2] = 0;
+        write_itype(&mut dummy_env, 0x23, 0, ITypeInstruction::Load32);
+        assert_eq!(dummy_env.registers[2], 0x8192A3B4);
+    }
+
+    #[test]
+    fn test_unit_little_endian_load_word_left_right_instructions() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let mut dummy_env = dummy_env(&mut rng);
+        dummy_env.endianness = Endianness::Little;
+        let addr: u32 = rng.gen_range(0u32..100u32) & !3;
+        dummy_env.memory.page_mut(0)[addr as usize..addr as usize + 4]
+            .copy_from_slice(&[0x11, 0x22, 0x33, 0x44]);
+        dummy_env.registers[1] = addr;
+
+        // lwl $2, 1($1) loads the bytes up to the address in the most
+        // significant bytes of the register
+        dummy_env.registers[2] = 0xAABBCCDD;
+        write_itype(&mut dummy_env, 0x22, 1, ITypeInstruction::LoadWordLeft);
+        assert_eq!(dummy_env.registers[2], 0x2211CCDD);
+
+        // lwr $2, 1($1) loads the bytes from the address in the least
+        // significant bytes of the register
+        dummy_env.registers[2] = 0xAABBCCDD;
+        write_itype(&mut dummy_env, 0x26, 1, ITypeInstruction::LoadWordRight);
+        assert_eq!(dummy_env.registers[2], 0xAA443322);
+    }
+
+    #[test]
+    fn test_unit_partial_word_accesses_stay_in_the_aligned_word() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        for endianness in [Endianness::Big, Endianness::Little] {
+            for subaddr in 0..4 {
+                let mut dummy_env = dummy_env(&mut rng);
+                dummy_env.endianness = endianness;
+                let word_addr: u32 = rng.gen_range(4u32..96u32) & !3;
+                dummy_env.registers[1] = word_addr + subaddr;
+                let neighbours = || (word_addr - 4..word_addr).chain(word_addr + 4..word_addr + 8);
+                let last_accesses: Vec<_> = neighbours()
+                    .map(|addr| dummy_env.memory_write_index.get(addr))
+                    .collect();
+                // lwl, lwr, swl and swr $2, 0($1) only access the bytes of the
+                // word containing the address
+                for (op_code, instr) in [
+                    (0x22, ITypeInstruction::LoadWordLeft),
+                    (0x26, ITypeInstruction::LoadWordRight),
+                    (0x2a, ITypeInstruction::StoreWordLeft),
+                    (0x2e, ITypeInstruction::StoreWordRight),
+                ] {
+                    write_itype(&mut dummy_env, op_code, 0, instr);
+                }
+                assert_eq!(
+                    neighbours()
+                        .map(|addr| dummy_env.memory_write_index.get(addr))
+                        .collect::<Vec<_>>(),
+                    last_accesses,
+                    "{endianness:?}, {subaddr}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_unit_unaligned_word_accesses_for_both_endiannesses() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        for endianness in [Endianness::Big, Endianness::Little] {
+            let mut dummy_env = dummy_env(&mut rng);
+            dummy_env.endianness = endianness;
+            // The offsets of the most and the least significant bytes of a
+            // word, for the instructions loading and storing them
+            let (left, right) = match endianness {
+                Endianness::Big => (0, 3),
+                Endianness::Little => (3, 0),
+            };
+            let addr: u32 = rng.gen_range(0u32..100u32) | 1;
+            let bytes: [u8; 4] = dummy_env.memory.page(0).unwrap()
+                [addr as usize..addr as usize + 4]
+                .try_into()
+                .unwrap();
+            let word = match endianness {
+                Endianness::Big => u32::from_be_bytes(bytes),
+                Endianness::Little => u32::from_le_bytes(bytes),
+            };
+            dummy_env.registers[1] = addr;
+
+            // The unaligned word is loaded by a pair of lwl and lwr
+            write_itype(&mut dummy_env, 0x22, left, ITypeInstruction::LoadWordLeft);
+            write_itype(&mut dummy_env, 0x26, right, ITypeInstruction::LoadWordRight);
+            assert_eq!(dummy_env.registers[2], word);
+
+            // And stored by a pair of swl and swr
+            let value = rng.gen_range(0u32..=u32::MAX);
+            dummy_env.registers[2] = value;
+            write_itype(&mut dummy_env, 0x2a, left, ITypeInstruction::StoreWordLeft);
+            write_itype(
+                &mut dummy_env,
+                0x2e,
+                right,
+                ITypeInstruction::StoreWordRight,
+            );
+            let expected = match endianness {
+                Endianness::Big => value.to_be_bytes(),
+                Endianness::Little => value.to_le_bytes(),
+            };
+            assert_eq!(
+                dummy_env.memory.page(0).unwrap()[addr as usize..addr as usize + 4],
+                expected
+            );
+        }
+    }
+    #[test]
+    fn test_unit_load_store_instructions_with_word_memory() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        for endianness in [Endianness::Big, Endianness::Little] {
+            let mut byte_env = dummy_env(&mut rng);
+            byte_env.endianness = endianness;
+            let mut word_env = dummy_env(&mut rng);
+            word_env.endianness = endianness;
+            word_env.memory = byte_env.memory.clone();
+            word_env.registers = byte_env.registers.clone();
+            word_env.enable_word_memory();
+            let addr: u32 = rng.gen_range(0u32..100u32) & !3;
+            byte_env.registers[1] = addr;
+            word_env.registers[1] = addr;
+
+            // Each access of the same word is done by word
+            for (op_code, immediate, instr) in [
+                (0x2b, 0, ITypeInstruction::Store32),
+                (0x20, 1, ITypeInstruction::Load8),
+                (0x24, 2, ITypeInstruction::Load8Unsigned),
+                (0x21, 2, ITypeInstruction::Load16),
+                (0x25, 0, ITypeInstruction::Load16Unsigned),
+                (0x28, 3, ITypeInstruction::Store8),
+                (0x29, 2, ITypeInstruction::Store16),
+                (0x22, 1, ITypeInstruction::LoadWordLeft),
+                (0x26, 2, ITypeInstruction::LoadWordRight),
+                (0x2a, 1, ITypeInstruction::StoreWordLeft),
+                (0x2e, 2, ITypeInstruction::StoreWordRight),
+                (0x23, 0, ITypeInstruction::Load32),
+            ] {
+                let value = rng.gen_range(0u32..=u32::MAX);
+                byte_env.registers[2] = value;
+                word_env.registers[2] = value;
+                write_itype(&mut byte_env, op_code, immediate, instr);
+                write_itype(&mut word_env, op_code, immediate, instr);
+                assert_eq!(
+                    word_env.registers.general_purpose, byte_env.registers.general_purpose,
+                    "{instr:?}"
+                );
+                assert_eq!(
+                    word_env.memory.page(0).unwrap()[addr as usize..addr as usize + 4],
+                    byte_env.memory.page(0).unwrap()[addr as usize..addr as usize + 4],
+                    "{instr:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_unit_unaligned_load32_with_word_memory() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let mut dummy_env = dummy_env(&mut rng);
+        dummy_env.enable_word_memory();
+        dummy_env.registers[1] = rng.gen_range(0u32..100u32) | 1;
+        // lw $2, 0($1) must be aligned to be accessed by word
+        write_itype(&mut dummy_env, 0x23, 0, ITypeInstruction::Load32);
+    }
+}
+
+#[test]
+fn test_write_preimage_with_word_memory() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    for offset in 0..4 {
+        for write_length in 0..32 {
+            let mut byte_env = dummy_env(&mut rng);
+            let mut word_env = dummy_env(&mut rng);
+            word_env.memory = byte_env.memory.clone();
+            word_env.registers = byte_env.registers.clone();
+            word_env.enable_word_memory();
+            let addr = (rng.gen_range(8u32..100u32) & !3) + offset;
+            for env in [&mut byte_env, &mut word_env] {
+                env.registers[5] = addr;
+                env.registers[6] = write_length;
+                interpreter::interpret_rtype(env, RTypeInstruction::SyscallWritePreimage);
+            }
+            // Only the bytes of the word of `addr` are read, in both modes
+            assert_eq!(
+                word_env.registers.iter().collect::<Vec<_>>(),
+                byte_env.registers.iter().collect::<Vec<_>>(),
+                "addr: {addr}, length: {write_length}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_word_memory_constraints_degree() {
+    let mut mips_con_env = constraints::Env::<Fp>::default().with_word_memory();
+    for instr in Instruction::iter().flat_map(|instr_typ| instr_typ.into_iter()) {
+        interpreter::interpret_instruction(&mut mips_con_env, instr);
+        let max_degree = mips_con_env
+            .get_constraints()
+            .iter()
+            .map(|c| c.degree(1, 0))
+            .max()
+            .unwrap_or(0);
+        assert!(max_degree <= MAXIMUM_DEGREE_CONSTRAINTS, "{instr:?}");
+        mips_con_env.reset();
+    }
+}
+
+#[test]
+fn test_word_memory_halves_the_columns_of_word_accesses() {
+    let width = |mut env: constraints::Env<Fp>, instr| {
+        interpreter::interpret_itype(&mut env, instr);
+        env.scratch_width().scratch
+    };
+    for instr in [ITypeInstruction::Load32, ITypeInstruction::Store32] {
+        let bytes = width(constraints::Env::default(), instr);
+        let words = width(constraints::Env::default().with_word_memory(), instr);
+        // The instruction and the word are accessed by word
+        assert_eq!(bytes - words, 2 * 4, "{instr:?}");
+    }
 }
 
 #[test]
@@ -381,3 +994,125 @@ fn test_regression_constraints_with_selectors() {
     let max_degree = constraints.iter().map(|c| c.degree(1, 0)).max().unwrap();
     assert_eq!(max_degree, MAXIMUM_DEGREE_CONSTRAINTS);
 }
+
+#[test]
+fn test_packed_selectors() {
+    let layout = SelectorLayout::Packed;
+    // Each instruction is selected by its own pair of columns
+    let pairs: std::collections::BTreeSet<Vec<usize>> =
+        (0..N_MIPS_SEL_COLS).map(|i| layout.columns(i)).collect();
+    assert_eq!(pairs.len(), N_MIPS_SEL_COLS);
+    assert!(pairs
+        .iter()
+        .flatten()
+        .all(|column| *column < layout.num_columns()));
+
+    // Only the selector constraints change, with one boolean check per column,
+    // one activation check per group of columns and one check of the pairs
+    // selecting no instruction, and the selectors add one more degree
+    let constraints = constraints::get_all_constraints_with_selector_layout::<Fp>(layout);
+    assert_eq!(
+        constraints.len(),
+        TOTAL_NUMBER_OF_CONSTRAINTS - (N_MIPS_SEL_COLS + 1) + (layout.num_columns() + 3)
+    );
+    let max_degree = constraints.iter().map(|c| c.degree(1, 0)).max().unwrap();
+    assert_eq!(max_degree, MAXIMUM_DEGREE_CONSTRAINTS + 1);
+}
+
+/// The values of the selector columns of a row, set to one for the columns
+/// `set`
+struct SelectorRow(Vec<usize>);
+
+impl ColumnEvaluations<Fp> for SelectorRow {
+    type Column = Column<RelationColumnType>;
+
+    fn evaluate(&self, col: Self::Column) -> Result<PointEvaluations<Fp>, ExprError<Self::Column>> {
+        match col {
+            Column::DynamicSelector(i) => {
+                let value = Fp::from(self.0.contains(&i));
+                Ok(PointEvaluations {
+                    zeta: value,
+                    zeta_omega: value,
+                })
+            }
+            _ => Err(ExprError::MissingEvaluation(col, CurrOrNext::Curr)),
+        }
+    }
+}
+
+#[test]
+fn test_packed_selectors_reject_the_pairs_selecting_no_instruction() {
+    let layout = SelectorLayout::Packed;
+    let constraints = constraints::Env::<Fp>::default()
+        .with_selector_layout(layout)
+        .get_selector_constraints();
+    let domain = D::<Fp>::new(1).unwrap();
+    let holds = |set: Vec<usize>| {
+        let constants = Constants {
+            endo_coefficient: Fp::zero(),
+            mds: &fp_kimchi::static_params().mds,
+            zk_rows: 0,
+        };
+        let challenges = BerkeleyChallenges {
+            alpha: Fp::zero(),
+            beta: Fp::zero(),
+            gamma: Fp::zero(),
+            joint_combiner: Fp::zero(),
+        };
+        constraints.iter().all(|constraint| {
+            constraint
+                .evaluate_(
+                    domain,
+                    Fp::one(),
+                    &SelectorRow(set.clone()),
+                    &constants,
+                    &challenges,
+                )
+                .unwrap()
+                .is_zero()
+        })
+    };
+    let (unused_high, unused_low) = layout.unused_pairs().unwrap();
+    for high in 0..N_PACKED_SEL_HIGH_COLS {
+        for low in 0..N_PACKED_SEL_LOW_COLS {
+            let i = high * N_PACKED_SEL_LOW_COLS + low;
+            let columns = vec![high, N_PACKED_SEL_HIGH_COLS + low];
+            // Only the pairs of the instructions are accepted
+            assert_eq!(holds(columns.clone()), i < N_MIPS_SEL_COLS, "{columns:?}");
+            assert_eq!(
+                high == unused_high && unused_low.contains(&columns[1]),
+                i >= N_MIPS_SEL_COLS
+            );
+        }
+    }
+}
+
+#[test]
+fn test_scratch_layout() {
+    let layout = constraints::scratch_layout();
+    // Every instruction fits in the columns of the trace, and the widest ones
+    // set their width
+    let max_width = layout.max_width();
+    assert_eq!(max_width.scratch, SCRATCH_SIZE);
+    assert!(max_width.scratch_inverse <= SCRATCH_SIZE_INVERSE);
+    assert_eq!(
+        layout
+            .width(Instruction::RType(RTypeInstruction::SyscallReadPreimage))
+            .scratch,
+        SCRATCH_SIZE
+    );
+    // The witness only allocates the scratch state of the instruction
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let mut dummy_env = dummy_env(&mut rng);
+    dummy_env.reset_scratch_state();
+    dummy_env.reset_scratch_state_inverse();
+    assert!(dummy_env.scratch_state.is_empty());
+    assert!(dummy_env.scratch_state_inverse.is_empty());
+    let instruction = Instruction::RType(RTypeInstruction::Add);
+    dummy_env.allocate_scratch_state(instruction);
+    assert_eq!(
+        dummy_env.scratch_state.len(),
+        layout.width(instruction).scratch
+    );
+    assert!(dummy_env.scratch_state.len() < SCRATCH_SIZE);
+}
@@ -1,10 +1,12 @@
 use crate::{
     cannon::{Hint, Preimage, PAGE_ADDRESS_MASK, PAGE_ADDRESS_SIZE, PAGE_SIZE},
     interpreters::mips::{
-        interpreter::{debugging::InstructionParts, InterpreterEnv},
+        interpreter::debugging::InstructionParts,
         registers::Registers,
         witness::{Env as WEnv, LookupMultiplicities, SyscallEnv},
+        Endianness,
     },
+    memory::SparseMemory,
     preimage_oracle::PreImageOracleT,
 };
 use rand::{CryptoRng, Rng, RngCore};
@@ -43,55 +45,25 @@ where
     let mut env = WEnv {
         // Set it to 2 to run 1 instruction that access registers if
         instruction_counter: 2,
-        // Only 8kb of memory (two PAGE_ADDRESS_SIZE)
-        memory: vec![
-            // Read/write memory
-            // Initializing with random data
-            (
-                0,
-                (0..PAGE_SIZE).map(|_| rng.gen_range(0u8..=255)).collect(),
-            ),
-            // Executable memory. Allocating 4 * 4kB
-            (PAGE_INDEX_EXECUTABLE_MEMORY, vec![0; PAGE_SIZE as usize]),
-            (
-                PAGE_INDEX_EXECUTABLE_MEMORY + 1,
-                vec![0; PAGE_SIZE as usize],
-            ),
-            (
-                PAGE_INDEX_EXECUTABLE_MEMORY + 2,
-                vec![0; PAGE_SIZE as usize],
-            ),
-            (
-                PAGE_INDEX_EXECUTABLE_MEMORY + 3,
-                vec![0; PAGE_SIZE as usize],
-            ),
-        ],
-        last_memory_accesses: [0; 3],
-        memory_write_index: vec![
-            // Read/write memory
-            (0, vec![0; PAGE_SIZE as usize]),
-            // Executable memory. Allocating 4 * 4kB
-            (PAGE_INDEX_EXECUTABLE_MEMORY, vec![0; PAGE_SIZE as usize]),
-            (
-                PAGE_INDEX_EXECUTABLE_MEMORY + 1,
-                vec![0; PAGE_SIZE as usize],
-            ),
-            (
-                PAGE_INDEX_EXECUTABLE_MEMORY + 2,
-                vec![0; PAGE_SIZE as usize],
-            ),
-            (
-                PAGE_INDEX_EXECUTABLE_MEMORY + 3,
-                vec![0; PAGE_SIZE as usize],
-            ),
-        ],
-        last_memory_write_index_accesses: [0; 3],
+        // Read/write memory, initialized with random data. The executable
+        // memory is allocated on demand.
+        memory: [(
+            0,
+            (0..PAGE_SIZE).map(|_| rng.gen_range(0u8..=255)).collect(),
+        )]
+        .into_iter()
+        .collect(),
+        memory_write_index: SparseMemory::new(),
+        merkle_memory: None,
+        authenticated_accesses: Vec::new(),
+        endianness: Endianness::Big,
+        word_memory: false,
         registers: Registers::default(),
         registers_write_index: Registers::default(),
         scratch_state_idx: 0,
         scratch_state_idx_inverse: 0,
-        scratch_state: [Fp::from(0); SCRATCH_SIZE],
-        scratch_state_inverse: [Fp::from(0); SCRATCH_SIZE_INVERSE],
+        scratch_state: vec![Fp::from(0); SCRATCH_SIZE],
+        scratch_state_inverse: vec![Fp::from(0); SCRATCH_SIZE_INVERSE],
         lookup_multiplicities: LookupMultiplicities::new(),
         lookup_state_idx: 0,
         lookup_state: vec![],
@@ -106,6 +78,8 @@ where
         preimage_key: None,
         keccak_env: None,
         hash_counter: 0,
+        keccak_precompile: Default::default(),
+        state_commitments: vec![],
     };
     // Initialize general purpose registers with random values
     for reg in env.registers.general_purpose.iter_mut() {
@@ -122,11 +96,12 @@ pub(crate) fn write_instruction(
     instruction_parts: InstructionParts,
 ) {
     let instr = instruction_parts.encode();
-    let instr_pointer: u32 = env.get_instruction_pointer().try_into().unwrap();
+    let instr_pointer = env.registers.current_instruction_pointer;
     let page = instr_pointer >> PAGE_ADDRESS_SIZE;
     let page_address = (instr_pointer & PAGE_ADDRESS_MASK) as usize;
-    env.memory[page as usize].1[page_address] = ((instr >> 24) & 0xFF) as u8;
-    env.memory[page as usize].1[page_address + 1] = ((instr >> 16) & 0xFF) as u8;
-    env.memory[page as usize].1[page_address + 2] = ((instr >> 8) & 0xFF) as u8;
-    env.memory[page as usize].1[page_address + 3] = (instr & 0xFF) as u8;
+    let bytes = match env.endianness {
+        Endianness::Big => instr.to_be_bytes(),
+        Endianness::Little => instr.to_le_bytes(),
+    };
+    env.memory.page_mut(page)[page_address..page_address + 4].copy_from_slice(&bytes);
 }
@@ -1,11 +1,16 @@
-use super::column::{N_MIPS_SEL_COLS, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE};
+use super::column::N_MIPS_SEL_COLS;
 use crate::{
     cannon::{
         Hint, Meta, Page, Start, State, StepFrequency, VmConfiguration, PAGE_ADDRESS_MASK,
         PAGE_ADDRESS_SIZE, PAGE_SIZE,
     },
     interpreters::{
-        keccak::environment::KeccakEnv,
+        keccak::{
+            environment::KeccakEnv,
+            precompile::{
+                KeccakHint, KeccakPrecompile, KECCAK_PRECOMPILE_CHUNK_LEN, SYSCALL_KECCAK256,
+            },
+        },
         mips::{
             column::{
                 ColumnAlias as Column, MIPS_BYTE_COUNTER_OFF, MIPS_CHUNK_BYTES_LEN,
@@ -13,25 +18,31 @@ use crate::{
                 MIPS_LENGTH_BYTES_OFF, MIPS_NUM_BYTES_READ_OFF, MIPS_PREIMAGE_BYTES_OFF,
                 MIPS_PREIMAGE_CHUNK_OFF, MIPS_PREIMAGE_KEY,
             },
+            constraints,
             interpreter::{
                 self, ITypeInstruction, Instruction, InterpreterEnv, JTypeInstruction,
                 RTypeInstruction,
             },
             registers::Registers,
+            Endianness,
         },
     },
     lookups::{Lookup, LookupTableIDs},
-    preimage_oracle::PreImageOracleT,
+    memory::SparseMemory,
+    merkle_memory::{self, AuthenticatedAccess, MerkleMemory},
+    pickles::chunk::{BoundaryState, StateCommitment},
+    preimage_oracle::{HostStream, PreImageOracleT},
     ramlookup::LookupMode,
     utils::memory_size,
 };
-use ark_ff::{Field, PrimeField};
+use ark_ff::PrimeField;
 use core::panic;
 use kimchi::o1_utils::Two;
 use kimchi_msm::LogupTableID;
 use log::{debug, info};
+use serde::{Deserialize, Serialize};
 use std::{
-    array,
+    collections::VecDeque,
     fs::File,
     io::{BufWriter, Write},
 };
@@ -53,19 +64,34 @@ pub const NUM_LOOKUP_TERMS: usize =
     NUM_GLOBAL_LOOKUP_TERMS + NUM_DECODING_LOOKUP_TERMS + NUM_INSTRUCTION_LOOKUP_TERMS;
 // TODO: Delete and use a vector instead
 
-#[derive(Clone, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct SyscallEnv {
     pub last_hint: Option<Vec<u8>>,
+    /// The advice given by the prover, not read yet by the program
+    pub advice: VecDeque<u8>,
+    /// The advice read by the program so far, which is its input
+    pub input: Vec<u8>,
+    /// The bytes written by the program on the standard output so far
+    pub output: Vec<u8>,
+    /// The code given to the `exit_group` system call, once the program
+    /// exited
+    #[serde(default)]
+    pub exit_code: Option<u8>,
 }
 
 impl SyscallEnv {
     pub fn create(state: &State) -> Self {
         SyscallEnv {
             last_hint: state.last_hint.clone(),
+            advice: VecDeque::new(),
+            input: Vec::new(),
+            output: Vec::new(),
+            exit_code: state.exited.then_some(state.exit),
         }
     }
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LookupMultiplicities {
     pub pad_lookup: Vec<u64>,
     pub round_constants_lookup: Vec<u64>,
@@ -96,6 +122,27 @@ impl Default for LookupMultiplicities {
     }
 }
 
+/// A snapshot of the state of an execution, from which it can be resumed
+/// after a crash without replaying it, see [Env::snapshot] and
+/// [Env::resume]. The preimage oracle and the Keccak environment of the last
+/// preimage read are not part of the snapshot.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub instruction_counter: u64,
+    pub memory: SparseMemory<u32, u8>,
+    pub memory_write_index: SparseMemory<u32, u64>,
+    pub registers: Registers<u32>,
+    pub registers_write_index: Registers<u64>,
+    pub halt: bool,
+    pub syscall_env: SyscallEnv,
+    pub preimage: Option<Vec<u8>>,
+    pub preimage_bytes_read: u64,
+    pub preimage_key: Option<[u8; 32]>,
+    pub hash_counter: u64,
+    pub keccak_precompile: KeccakPrecompile,
+    pub lookup_multiplicities: LookupMultiplicities,
+}
+
 /// This structure represents the environment the virtual machine state will use
 /// to transition. This environment will be used by the interpreter. The virtual
 /// machine has access to its internal state and some external memory. In
@@ -103,16 +150,26 @@ impl Default for LookupMultiplicities {
 /// that is used to verify the preimage requested during the execution.
 pub struct Env<Fp, PreImageOracle: PreImageOracleT> {
     pub instruction_counter: u64,
-    pub memory: Vec<(u32, Vec<u8>)>,
-    pub last_memory_accesses: [usize; 3],
-    pub memory_write_index: Vec<(u32, Vec<u64>)>,
-    pub last_memory_write_index_accesses: [usize; 3],
+    pub memory: SparseMemory<u32, u8>,
+    pub memory_write_index: SparseMemory<u32, u64>,
+    /// The Merkle tree of the memory, when the memory accesses are
+    /// authenticated, see [Env::enable_merkle_memory]
+    pub merkle_memory: Option<MerkleMemory>,
+    /// The memory accesses authenticated since the last call to
+    /// [Env::take_authenticated_accesses]
+    pub authenticated_accesses: Vec<AuthenticatedAccess>,
+    /// The byte order of the memory, see [Env::create_with_endianness]
+    pub endianness: Endianness,
+    /// Whether the memory is accessed by words, see [Env::enable_word_memory]
+    pub word_memory: bool,
     pub registers: Registers<u32>,
     pub registers_write_index: Registers<u64>,
     pub scratch_state_idx: usize,
     pub scratch_state_idx_inverse: usize,
-    pub scratch_state: [Fp; SCRATCH_SIZE],
-    pub scratch_state_inverse: [Fp; SCRATCH_SIZE_INVERSE],
+    /// The scratch columns of the current instruction, as many as given by
+    /// its [crate::interpreters::mips::column::ScratchLayout]
+    pub scratch_state: Vec<Fp>,
+    pub scratch_state_inverse: Vec<Fp>,
     pub lookup_state_idx: usize,
     pub lookup_state: Vec<Fp>,
     // tracks the arity of every lookup
@@ -128,11 +185,12 @@ pub struct Env<Fp, PreImageOracle: PreImageOracleT> {
     pub preimage_key: Option<[u8; 32]>,
     pub keccak_env: Option<KeccakEnv<Fp>>,
     pub hash_counter: u64,
+    /// The state of the current call to the Keccak precompile
+    pub keccak_precompile: KeccakPrecompile,
     pub lookup_multiplicities: LookupMultiplicities,
-}
-
-fn fresh_scratch_state<Fp: Field, const N: usize>() -> [Fp; N] {
-    array::from_fn(|_| Fp::zero())
+    /// The commitments to the state computed at the steps given by
+    /// [VmConfiguration::commit_state_at]
+    pub state_commitments: Vec<StateCommitment>,
 }
 
 impl<Fp: PrimeField, PreImageOracle: PreImageOracleT> InterpreterEnv for Env<Fp, PreImageOracle> {
@@ -140,12 +198,22 @@ impl<Fp: PrimeField, PreImageOracle: PreImageOracleT> InterpreterEnv for Env<Fp,
 
     fn alloc_scratch(&mut self) -> Self::Position {
         let scratch_idx = self.scratch_state_idx;
+        assert!(
+            scratch_idx < self.scratch_state.len(),
+            "The instruction allocates more than the {} scratch columns of its layout",
+            self.scratch_state.len()
+        );
         self.scratch_state_idx += 1;
         Column::ScratchState(scratch_idx)
     }
 
     fn alloc_scratch_inverse(&mut self) -> Self::Position {
         let scratch_idx = self.scratch_state_idx_inverse;
+        assert!(
+            scratch_idx < self.scratch_state_inverse.len(),
+            "The instruction allocates more than the {} inverse scratch columns of its layout",
+            self.scratch_state_inverse.len()
+        );
         self.scratch_state_idx_inverse += 1;
         Column::ScratchStateInverse(scratch_idx)
     }
@@ -228,6 +296,9 @@ impl<Fp: PrimeField, PreImageOracle: PreImageOracleT> InterpreterEnv for Env<Fp,
                 LookupTableIDs::RegisterLookup => (),
                 LookupTableIDs::SyscallLookup => (),
                 LookupTableIDs::KeccakStepLookup => (),
+                LookupTableIDs::MerkleMemoryLookup => (),
+                LookupTableIDs::PoseidonLookup => (),
+                LookupTableIDs::PrecompileLookup => (),
             }
         }
         //Update arity
@@ -299,21 +370,20 @@ impl<Fp: PrimeField, PreImageOracle: PreImageOracleT> InterpreterEnv for Env<Fp,
         output: Self::Position,
     ) -> Self::Variable {
         let addr: u32 = (*addr).try_into().unwrap();
-        let page = addr >> PAGE_ADDRESS_SIZE;
-        let page_address = (addr & PAGE_ADDRESS_MASK) as usize;
-        let memory_page_idx = self.get_memory_page_index(page);
-        let value = self.memory[memory_page_idx].1[page_address];
+        let value = self.memory.get(addr);
         self.write_column(output, value.into());
         value.into()
     }
 
     unsafe fn push_memory(&mut self, addr: &Self::Variable, value: Self::Variable) {
         let addr: u32 = (*addr).try_into().unwrap();
-        let page = addr >> PAGE_ADDRESS_SIZE;
-        let page_address = (addr & PAGE_ADDRESS_MASK) as usize;
-        let memory_page_idx = self.get_memory_page_index(page);
-        self.memory[memory_page_idx].1[page_address] =
-            value.try_into().expect("push_memory values fit in a u8");
+        self.memory.set(
+            addr,
+            value.try_into().expect("push_memory values fit in a u8"),
+        );
+        if let Some(merkle_memory) = self.merkle_memory.as_mut() {
+            merkle_memory.update(addr & !3, merkle_memory::word_at(&self.memory, addr));
+        }
     }
 
     unsafe fn fetch_memory_access(
@@ -322,20 +392,67 @@ impl<Fp: PrimeField, PreImageOracle: PreImageOracleT> InterpreterEnv for Env<Fp,
         output: Self::Position,
     ) -> Self::Variable {
         let addr: u32 = (*addr).try_into().unwrap();
-        let page = addr >> PAGE_ADDRESS_SIZE;
-        let page_address = (addr & PAGE_ADDRESS_MASK) as usize;
-        let memory_write_index_page_idx = self.get_memory_access_page_index(page);
-        let value = self.memory_write_index[memory_write_index_page_idx].1[page_address];
+        let value = self.memory_write_index.get(addr);
         self.write_column(output, value);
         value
     }
 
     unsafe fn push_memory_access(&mut self, addr: &Self::Variable, value: Self::Variable) {
-        let addr = *addr as u32;
-        let page = addr >> PAGE_ADDRESS_SIZE;
-        let page_address = (addr & PAGE_ADDRESS_MASK) as usize;
-        let memory_write_index_page_idx = self.get_memory_access_page_index(page);
-        self.memory_write_index[memory_write_index_page_idx].1[page_address] = value;
+        self.memory_write_index.set(*addr as u32, value);
+    }
+
+    unsafe fn fetch_memory_word(
+        &mut self,
+        addr: &Self::Variable,
+        output: Self::Position,
+    ) -> Self::Variable {
+        let addr: u32 = (*addr).try_into().unwrap();
+        let value = (0..4).fold(0u64, |word, i| {
+            let byte = self.memory.get(addr + self.endianness.byte_offset(4, i));
+            (word << 8) + byte as u64
+        });
+        self.write_column(output, value);
+        value
+    }
+
+    unsafe fn push_memory_word(&mut self, addr: &Self::Variable, value: Self::Variable) {
+        let addr: u32 = (*addr).try_into().unwrap();
+        for i in 0..4 {
+            let byte = (value >> (8 * (3 - i))) as u8;
+            self.memory
+                .set(addr + self.endianness.byte_offset(4, i), byte);
+        }
+        if let Some(merkle_memory) = self.merkle_memory.as_mut() {
+            merkle_memory.update(addr, merkle_memory::word_at(&self.memory, addr));
+        }
+    }
+
+    fn authenticate_memory_access(
+        &mut self,
+        addr: &Self::Variable,
+        _old_value: &Self::Variable,
+        new_value: &Self::Variable,
+    ) {
+        if let Some(merkle_memory) = self.merkle_memory.as_mut() {
+            let addr: u32 = (*addr).try_into().unwrap();
+            let old_word = merkle_memory::word_at(&self.memory, addr);
+            let new_word = if self.word_memory {
+                // The words are authenticated with their bytes in the order of
+                // their addresses
+                let new_word: u32 = (*new_value).try_into().expect("memory words fit in a u32");
+                match self.endianness {
+                    Endianness::Big => new_word,
+                    Endianness::Little => new_word.swap_bytes(),
+                }
+            } else {
+                let mut new_word = old_word.to_be_bytes();
+                new_word[(addr & 3) as usize] =
+                    (*new_value).try_into().expect("memory values fit in a u8");
+                u32::from_be_bytes(new_word)
+            };
+            let access = merkle_memory.access(addr, old_word, new_word);
+            self.authenticated_accesses.push(access);
+        }
     }
 
     fn constant(x: u32) -> Self::Variable {
@@ -685,7 +802,17 @@ impl<Fp: PrimeField, PreImageOracle: PreImageOracleT> InterpreterEnv for Env<Fp,
         }
     }
 
+    fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    fn word_memory(&self) -> bool {
+        self.word_memory
+    }
+
     fn report_exit(&mut self, exit_code: &Self::Variable) {
+        // As for Linux, only the least significant byte of the code is kept
+        self.syscall_env.exit_code = Some(*exit_code as u8);
         println!(
             "Exited with code {} at step {}",
             *exit_code,
@@ -871,13 +998,7 @@ impl<Fp: PrimeField, PreImageOracle: PreImageOracleT> InterpreterEnv for Env<Fp,
             // Push memory access
             unsafe { self.push_memory_access(&(*addr + i), self.next_instruction_counter()) };
             // Fetch the value without allocating witness columns
-            let value = {
-                let addr: u32 = (*addr).try_into().unwrap();
-                let page = addr >> PAGE_ADDRESS_SIZE;
-                let page_address = (addr & PAGE_ADDRESS_MASK) as usize;
-                let memory_page_idx = self.get_memory_page_index(page);
-                self.memory[memory_page_idx].1[page_address]
-            };
+            let value = self.memory.get((*addr).try_into().unwrap());
             last_hint.push(value);
         }
 
@@ -899,15 +1020,111 @@ impl<Fp: PrimeField, PreImageOracle: PreImageOracleT> InterpreterEnv for Env<Fp,
         self.syscall_env.last_hint = Some(remaining);
     }
 
+    fn report_stdout_write(
+        &mut self,
+        is_stdout: &Self::Variable,
+        addr: &Self::Variable,
+        len: &Self::Variable,
+    ) {
+        if *is_stdout == 1 {
+            // Fetch the values without allocating witness columns
+            let bytes = (0..*len).map(|i| self.memory.get((*addr + i).try_into().unwrap()));
+            self.syscall_env.output.extend(bytes);
+        }
+    }
+
+    fn request_advice_write(
+        &mut self,
+        addr: &Self::Variable,
+        len: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable {
+        // We read at most 4 bytes, up to the end of the word, as for the
+        // preimage.
+        let max_read_len = std::cmp::min(*len, 4 - (addr & 3));
+        let advice: Vec<u8> = self
+            .syscall_env
+            .advice
+            .drain(..std::cmp::min(max_read_len as usize, self.syscall_env.advice.len()))
+            .collect();
+        let advice = self.preimage_oracle.read_stream(HostStream::Advice, advice);
+        assert!(
+            advice.len() as u64 <= max_read_len,
+            "The advice read from the oracle is longer than requested"
+        );
+        let actual_read_len = advice.len() as u64;
+        for (i, byte) in (0..).zip(advice) {
+            self.syscall_env.input.push(byte);
+            unsafe {
+                self.push_memory(&(*addr + i), byte as u64);
+                self.push_memory_access(&(*addr + i), self.next_instruction_counter());
+            }
+        }
+        self.write_column(pos, actual_read_len);
+        actual_read_len
+    }
+
+    unsafe fn request_keccak(
+        &mut self,
+        addr: &Self::Variable,
+        len: &Self::Variable,
+        output: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable {
+        let absorbed_len = std::cmp::min(*len, KECCAK_PRECOMPILE_CHUNK_LEN as u64);
+        let bytes: Vec<u8> = (0..absorbed_len)
+            .map(|i| {
+                let addr = *addr + i;
+                unsafe { self.push_memory_access(&addr, self.next_instruction_counter()) };
+                self.memory.get(addr.try_into().unwrap())
+            })
+            .collect();
+        if *len == 0 {
+            let (input, digest) = self.keccak_precompile.finalize(self.hash_counter);
+            for (i, byte) in digest.iter().enumerate() {
+                let addr = *output + i as u64;
+                unsafe {
+                    self.push_memory(&addr, *byte as u64);
+                    self.push_memory_access(&addr, self.next_instruction_counter());
+                }
+            }
+            debug!("Keccak precompile called, triggering Keccak process");
+            self.keccak_env = Some(KeccakEnv::<Fp>::new(self.hash_counter, &input));
+            self.hash_counter += 1;
+        } else {
+            self.keccak_precompile.absorb(self.hash_counter, &bytes);
+        }
+        self.write_column(pos, absorbed_len);
+        absorbed_len
+    }
+
+    unsafe fn keccak_hint(&mut self, hint: KeccakHint, pos: Self::Position) -> Self::Variable {
+        self.write_field_column(pos, self.keccak_precompile.hint(hint));
+        // The digest is only used in the lookups
+        self.keccak_precompile.hint_u64(hint)
+    }
+
     fn reset(&mut self) {
-        self.scratch_state_idx = 0;
-        self.scratch_state = fresh_scratch_state();
-        self.selector = N_MIPS_SEL_COLS;
+        self.reset_scratch_state();
     }
 }
 
 impl<Fp: PrimeField, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
+    /// Creates the environment running the program given by the state
+    /// `state`, whose memory is big-endian
     pub fn create(page_size: usize, state: State, preimage_oracle: PreImageOracle) -> Self {
+        Self::create_with_endianness(page_size, state, preimage_oracle, Endianness::Big)
+    }
+
+    /// Creates the environment running the program given by the state
+    /// `state`, whose memory, including the instructions, has the byte order
+    /// `endianness`
+    pub fn create_with_endianness(
+        page_size: usize,
+        state: State,
+        preimage_oracle: PreImageOracle,
+        endianness: Endianness,
+    ) -> Self {
         let initial_instruction_pointer = state.pc;
         let next_instruction_pointer = state.next_pc;
 
@@ -915,23 +1132,13 @@ impl<Fp: PrimeField, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
 
         let syscall_env = SyscallEnv::create(&state);
 
-        let mut initial_memory: Vec<(u32, Vec<u8>)> = state
+        assert_eq!(page_size, PAGE_SIZE as usize);
+        let initial_memory: SparseMemory<u32, u8> = state
             .memory
             .into_iter()
-            // Check that the conversion from page data is correct
             .map(|page| (page.index, page.data))
             .collect();
 
-        for (_address, initial_memory) in initial_memory.iter_mut() {
-            initial_memory.extend((0..(page_size - initial_memory.len())).map(|_| 0u8));
-            assert_eq!(initial_memory.len(), page_size);
-        }
-
-        let memory_offsets = initial_memory
-            .iter()
-            .map(|(offset, _)| *offset)
-            .collect::<Vec<_>>();
-
         let initial_registers = {
             let preimage_key = {
                 let mut preimage_key = [0u32; 8];
@@ -955,20 +1162,20 @@ impl<Fp: PrimeField, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
         };
 
         Env {
-            instruction_counter: state.step,
-            memory: initial_memory.clone(),
-            last_memory_accesses: [0usize; 3],
-            memory_write_index: memory_offsets
-                .iter()
-                .map(|offset| (*offset, vec![0u64; page_size]))
-                .collect(),
-            last_memory_write_index_accesses: [0usize; 3],
+            // The step of the state is the number of instructions executed
+            instruction_counter: state.step * MAX_ACC,
+            memory: initial_memory,
+            memory_write_index: SparseMemory::new(),
+            merkle_memory: None,
+            authenticated_accesses: Vec::new(),
+            endianness,
+            word_memory: false,
             registers: initial_registers.clone(),
             registers_write_index: Registers::default(),
             scratch_state_idx: 0,
             scratch_state_idx_inverse: 0,
-            scratch_state: fresh_scratch_state(),
-            scratch_state_inverse: fresh_scratch_state(),
+            scratch_state: vec![],
+            scratch_state_inverse: vec![],
             lookup_state_idx: 0,
             lookup_state: vec![],
             lookup_arity: vec![],
@@ -981,19 +1188,123 @@ impl<Fp: PrimeField, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
             preimage_key: None,
             keccak_env: None,
             hash_counter: 0,
+            keccak_precompile: KeccakPrecompile::default(),
             lookup_multiplicities: LookupMultiplicities::new(),
+            state_commitments: vec![],
+        }
+    }
+
+    /// Append `advice` to the advice given to the program, which reads it
+    /// from the file descriptor [interpreter::FD_ADVICE_READ].
+    pub fn enqueue_advice(&mut self, advice: &[u8]) {
+        self.syscall_env.advice.extend(advice)
+    }
+
+    /// Authenticate the memory accesses against the Merkle root of the
+    /// current memory from now on, see [InterpreterEnv::authenticate_memory_access].
+    pub fn enable_merkle_memory(&mut self) {
+        self.merkle_memory = Some(MerkleMemory::from_memory(&self.memory));
+    }
+
+    /// Access the memory by words rather than by bytes, see
+    /// [InterpreterEnv::word_memory]. The last accesses being then tracked by
+    /// word, this must be enabled before the execution starts.
+    pub fn enable_word_memory(&mut self) {
+        self.word_memory = true;
+    }
+
+    /// Returns the memory accesses authenticated since the last call, to
+    /// check them at the end of a chunk of the execution.
+    pub fn take_authenticated_accesses(&mut self) -> Vec<AuthenticatedAccess> {
+        std::mem::take(&mut self.authenticated_accesses)
+    }
+
+    /// Returns a snapshot of the state of the execution, from which it can be
+    /// resumed with [Env::resume].
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            instruction_counter: self.instruction_counter,
+            memory: self.memory.clone(),
+            memory_write_index: self.memory_write_index.clone(),
+            registers: self.registers.clone(),
+            registers_write_index: self.registers_write_index.clone(),
+            halt: self.halt,
+            syscall_env: self.syscall_env.clone(),
+            preimage: self.preimage.clone(),
+            preimage_bytes_read: self.preimage_bytes_read,
+            preimage_key: self.preimage_key,
+            hash_counter: self.hash_counter,
+            keccak_precompile: self.keccak_precompile.clone(),
+            lookup_multiplicities: self.lookup_multiplicities.clone(),
         }
     }
 
+    /// Resumes the execution from the snapshot `snapshot`, keeping the
+    /// preimage oracle of the environment. When the memory accesses are
+    /// authenticated, the Merkle tree is rebuilt from the memory of the
+    /// snapshot, and the accesses not taken yet are dropped.
+    pub fn resume(&mut self, snapshot: Snapshot) {
+        let Snapshot {
+            instruction_counter,
+            memory,
+            memory_write_index,
+            registers,
+            registers_write_index,
+            halt,
+            syscall_env,
+            preimage,
+            preimage_bytes_read,
+            preimage_key,
+            hash_counter,
+            keccak_precompile,
+            lookup_multiplicities,
+        } = snapshot;
+        self.instruction_counter = instruction_counter;
+        self.memory = memory;
+        self.memory_write_index = memory_write_index;
+        self.registers = registers;
+        self.registers_write_index = registers_write_index;
+        self.halt = halt;
+        self.syscall_env = syscall_env;
+        self.preimage = preimage;
+        self.preimage_bytes_read = preimage_bytes_read;
+        self.preimage_key = preimage_key;
+        self.hash_counter = hash_counter;
+        self.keccak_precompile = keccak_precompile;
+        self.lookup_multiplicities = lookup_multiplicities;
+        self.keccak_env = None;
+        if self.merkle_memory.is_some() {
+            self.enable_merkle_memory();
+        }
+        self.authenticated_accesses.clear();
+    }
+
+    /// Resets the scratch columns, which are then allocated for the next
+    /// instruction by [Self::allocate_scratch_state]
     pub fn reset_scratch_state(&mut self) {
         self.scratch_state_idx = 0;
-        self.scratch_state = fresh_scratch_state();
+        self.scratch_state.clear();
         self.selector = N_MIPS_SEL_COLS;
     }
 
+    /// Resets the inverse scratch columns, which are then allocated for the
+    /// next instruction by [Self::allocate_scratch_state]
     pub fn reset_scratch_state_inverse(&mut self) {
         self.scratch_state_idx_inverse = 0;
-        self.scratch_state_inverse = fresh_scratch_state();
+        self.scratch_state_inverse.clear();
+    }
+
+    /// Allocates the scratch columns of the instruction `instruction`, as
+    /// many as given by the [constraints::scratch_layout], all set to zero
+    pub fn allocate_scratch_state(&mut self, instruction: Instruction) {
+        let width = constraints::scratch_layout().width(instruction);
+        self.scratch_state_idx = 0;
+        self.scratch_state_idx_inverse = 0;
+        self.scratch_state.clear();
+        self.scratch_state.resize(width.scratch, Fp::zero());
+        self.scratch_state_inverse.clear();
+        self.scratch_state_inverse
+            .resize(width.scratch_inverse, Fp::zero());
     }
 
     pub fn reset_lookup_state(&mut self) {
@@ -1014,74 +1325,19 @@ impl<Fp: PrimeField, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
         }
     }
 
-    pub fn update_last_memory_access(&mut self, i: usize) {
-        let [i_0, i_1, _] = self.last_memory_accesses;
-        self.last_memory_accesses = [i, i_0, i_1]
-    }
-
-    pub fn get_memory_page_index(&mut self, page: u32) -> usize {
-        for &i in self.last_memory_accesses.iter() {
-            if self.memory_write_index[i].0 == page {
-                return i;
-            }
-        }
-        for (i, (page_index, _memory)) in self.memory.iter_mut().enumerate() {
-            if *page_index == page {
-                self.update_last_memory_access(i);
-                return i;
-            }
-        }
-
-        // Memory not found; dynamically allocate
-        let memory = vec![0u8; PAGE_SIZE as usize];
-        self.memory.push((page, memory));
-        let i = self.memory.len() - 1;
-        self.update_last_memory_access(i);
-        i
-    }
-
-    pub fn update_last_memory_write_index_access(&mut self, i: usize) {
-        let [i_0, i_1, _] = self.last_memory_write_index_accesses;
-        self.last_memory_write_index_accesses = [i, i_0, i_1]
-    }
-
-    pub fn get_memory_access_page_index(&mut self, page: u32) -> usize {
-        for &i in self.last_memory_write_index_accesses.iter() {
-            if self.memory_write_index[i].0 == page {
-                return i;
-            }
-        }
-        for (i, (page_index, _memory_write_index)) in self.memory_write_index.iter_mut().enumerate()
-        {
-            if *page_index == page {
-                self.update_last_memory_write_index_access(i);
-                return i;
-            }
-        }
-
-        // Memory not found; dynamically allocate
-        let memory_write_index = vec![0u64; PAGE_SIZE as usize];
-        self.memory_write_index.push((page, memory_write_index));
-        let i = self.memory_write_index.len() - 1;
-        self.update_last_memory_write_index_access(i);
-        i
-    }
-
-    pub fn get_memory_direct(&mut self, addr: u32) -> u8 {
-        let page = addr >> PAGE_ADDRESS_SIZE;
-        let page_address = (addr & PAGE_ADDRESS_MASK) as usize;
-        let memory_idx = self.get_memory_page_index(page);
-        self.memory[memory_idx].1[page_address]
+    pub fn get_memory_direct(&self, addr: u32) -> u8 {
+        self.memory.get(addr)
     }
 
     pub fn decode_instruction(&mut self) -> (Instruction, u32) {
-        let instruction =
-            ((self.get_memory_direct(self.registers.current_instruction_pointer) as u32) << 24)
-                | ((self.get_memory_direct(self.registers.current_instruction_pointer + 1) as u32)
-                    << 16)
-                | ((self.get_memory_direct(self.registers.current_instruction_pointer + 2) as u32)
-                    << 8)
-                | (self.get_memory_direct(self.registers.current_instruction_pointer + 3) as u32);
+        let instruction = {
+            let pc = self.registers.current_instruction_pointer;
+            let bytes = [0, 1, 2, 3].map(|i| self.get_memory_direct(pc + i));
+            match self.endianness {
+                Endianness::Big => u32::from_be_bytes(bytes),
+                Endianness::Little => u32::from_le_bytes(bytes),
+            }
+        };
         let opcode = {
             match instruction >> 26 {
                 0x00 => match instruction & 0x3F {
@@ -1119,6 +1375,9 @@ impl<Fp: PrimeField, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
                             interpreter::FD_PREIMAGE_READ => {
                                 Instruction::RType(RTypeInstruction::SyscallReadPreimage)
                             }
+                            interpreter::FD_ADVICE_READ => {
+                                Instruction::RType(RTypeInstruction::SyscallReadAdvice)
+                            }
                             _ => Instruction::RType(RTypeInstruction::SyscallReadOther),
                         },
                         4004 => match self.registers.general_purpose[4] {
@@ -1131,6 +1390,7 @@ impl<Fp: PrimeField, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
                             _ => Instruction::RType(RTypeInstruction::SyscallWriteOther),
                         },
                         4055 => Instruction::RType(RTypeInstruction::SyscallFcntl),
+                        SYSCALL_KECCAK256 => Instruction::RType(RTypeInstruction::SyscallKeccak256),
                         _ => {
                             // NB: This has well-defined behavior. Don't panic!
                             Instruction::RType(RTypeInstruction::SyscallOther)
@@ -1182,6 +1442,10 @@ impl<Fp: PrimeField, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
                 0x0E => Instruction::IType(ITypeInstruction::XorImmediate),
                 0x0F => Instruction::IType(ITypeInstruction::LoadUpperImmediate),
                 0x1C => match instruction & 0x3F {
+                    0x00 => Instruction::RType(RTypeInstruction::MultiplyAdd),
+                    0x01 => Instruction::RType(RTypeInstruction::MultiplyAddUnsigned),
+                    0x04 => Instruction::RType(RTypeInstruction::MultiplySub),
+                    0x05 => Instruction::RType(RTypeInstruction::MultiplySubUnsigned),
                     0x02 => Instruction::RType(RTypeInstruction::MultiplyToRegister),
                     0x20 => Instruction::RType(RTypeInstruction::CountLeadingZeros),
                     0x21 => Instruction::RType(RTypeInstruction::CountLeadingOnes),
@@ -1252,9 +1516,11 @@ impl<Fp: PrimeField, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
         self.reset_scratch_state_inverse();
         self.reset_lookup_state();
         let (opcode, _instruction) = self.decode_instruction();
+        self.allocate_scratch_state(opcode);
 
         self.pp_info(&config.info_at, metadata, start);
         self.snapshot_state_at(&config.snapshot_state_at);
+        self.commit_state_at(&config.commit_state_at);
 
         interpreter::interpret_instruction(self, opcode);
 
@@ -1316,15 +1582,11 @@ impl<Fp: PrimeField, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
 
     fn get_opcode(&mut self) -> Option<u32> {
         let (page_id, page_address) = self.page_address();
-        for (page_index, memory) in self.memory.iter() {
-            if page_id == *page_index {
-                let memory_slice: [u8; 4] = memory[page_address..page_address + 4]
-                    .try_into()
-                    .expect("Couldn't read 4 bytes at given address");
-                return Some(u32::from_be_bytes(memory_slice));
-            }
-        }
-        None
+        let memory = self.memory.page(page_id)?;
+        let memory_slice: [u8; 4] = memory[page_address..page_address + 4]
+            .try_into()
+            .expect("Couldn't read 4 bytes at given address");
+        Some(u32::from_be_bytes(memory_slice))
     }
 
     fn snapshot_state_at(&mut self, at: &StepFrequency) {
@@ -1335,38 +1597,7 @@ impl<Fp: PrimeField, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
             );
             let file = File::create(filename.clone()).expect("Impossible to open file");
             let mut writer = BufWriter::new(file);
-            let mut preimage_key = [0u8; 32];
-            for i in 0..8 {
-                let bytes = u32::to_be_bytes(self.registers.preimage_key[i]);
-                for j in 0..4 {
-                    preimage_key[4 * i + j] = bytes[j]
-                }
-            }
-            let memory = self
-                .memory
-                .clone()
-                .into_iter()
-                .map(|(idx, data)| Page { index: idx, data })
-                .collect();
-            let s: State = State {
-                pc: self.registers.current_instruction_pointer,
-                next_pc: self.registers.next_instruction_pointer,
-                step: self.normalized_instruction_counter(),
-                registers: self.registers.general_purpose,
-                lo: self.registers.lo,
-                hi: self.registers.hi,
-                heap: self.registers.heap_pointer,
-                // FIXME: it should be the exit code. We do not keep it in the
-                // witness atm
-                exit: if self.halt { 1 } else { 0 },
-                last_hint: self.syscall_env.last_hint.clone(),
-                exited: self.halt,
-                preimage_offset: self.registers.preimage_offset,
-                preimage_key,
-                memory,
-                preimage: self.preimage.clone(),
-            };
-            let _ = serde_json::to_writer(&mut writer, &s);
+            let _ = serde_json::to_writer(&mut writer, &self.to_state());
             info!(
                 "Snapshot state in {}, step {}",
                 filename,
@@ -1376,6 +1607,65 @@ impl<Fp: PrimeField, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
         }
     }
 
+    /// Returns the current state, in the format of Cannon, see
+    /// [State::write_file]
+    pub fn to_state(&self) -> State {
+        let mut preimage_key = [0u8; 32];
+        for (bytes, word) in preimage_key
+            .chunks_exact_mut(4)
+            .zip(self.registers.preimage_key.iter())
+        {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+        State {
+            memory: self
+                .memory
+                .iter()
+                .map(|(index, data)| Page {
+                    index: *index,
+                    data: data.clone(),
+                })
+                .collect(),
+            preimage_key,
+            preimage_offset: self.registers.preimage_offset,
+            pc: self.registers.current_instruction_pointer,
+            next_pc: self.registers.next_instruction_pointer,
+            lo: self.registers.lo,
+            hi: self.registers.hi,
+            heap: self.registers.heap_pointer,
+            exit: self.syscall_env.exit_code.unwrap_or(0),
+            exited: self.syscall_env.exit_code.is_some(),
+            step: self.normalized_instruction_counter(),
+            registers: self.registers.general_purpose,
+            last_hint: self.syscall_env.last_hint.clone(),
+            preimage: self.preimage.clone(),
+        }
+    }
+
+    /// Returns the commitment to the current state, which is the one of the
+    /// boundary state of a chunk starting at the current step. The memory
+    /// accesses must be authenticated, see [Env::enable_merkle_memory].
+    pub fn state_commitment(&self) -> StateCommitment {
+        StateCommitment {
+            step: self.normalized_instruction_counter(),
+            commitment: BoundaryState::of_env(self).commitment(),
+        }
+    }
+
+    fn commit_state_at(&mut self, at: &StepFrequency) {
+        if self.should_trigger_at(at) {
+            if self.merkle_memory.is_none() {
+                self.enable_merkle_memory();
+            }
+            let commitment = self.state_commitment();
+            info!(
+                "State commitment at step {}: {}",
+                commitment.step, commitment.commitment
+            );
+            self.state_commitments.push(commitment);
+        }
+    }
+
     fn pp_info(&mut self, at: &StepFrequency, meta: &Option<Meta>, start: &Start) {
         if self.should_trigger_at(at) {
             let elapsed = start.time.elapsed();
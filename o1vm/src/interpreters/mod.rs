@@ -1,10 +1,29 @@
+/// A report of the coverage of the circuits of the zkVMs by an execution.
+pub mod coverage;
+
 /// An interpreter for an optimised version of Keccak
 pub mod keccak;
 
 /// An interpreter for the MIPS instruction set.
 pub mod mips;
 
+/// A circuit for the Poseidon permutation of Mina, backing the Poseidon
+/// precompile.
+pub mod poseidon;
+
+/// Custom precompiles given by the users of the zkVM.
+pub mod precompile;
+
 /// An interpreter for the RISC-V 32IM instruction set, following the specification
 /// on
 /// [riscv.org](https://riscv.org/wp-content/uploads/2019/12/riscv-spec-20191213.pdf).
 pub mod riscv32im;
+
+/// An interpreter for the RISC-V 64IM instruction set, following the specification
+/// on
+/// [riscv.org](https://riscv.org/wp-content/uploads/2019/12/riscv-spec-20191213.pdf).
+pub mod riscv64im;
+
+/// The semantics of the operations of the RISC-V interpreters depending on
+/// the width of the registers.
+pub mod xlen;
@@ -0,0 +1,71 @@
+//! This module defines the columns of the Poseidon sub-trace, one row
+//! computing one permutation.
+use crate::{
+    interpreters::poseidon::{ROUNDS, STATE_SIZE},
+    RelationColumnType,
+};
+use kimchi_msm::{
+    columns::{Column, ColumnIndexer},
+    witness::Witness,
+};
+use std::ops::{Index, IndexMut};
+
+/// The number of columns used by the Poseidon circuit: the selector, the index
+/// of the permutation and the [STATE_SIZE] elements of the state before the
+/// first round and after each of the [ROUNDS] rounds
+pub const N_ZKVM_POSEIDON_COLS: usize = 2 + (ROUNDS + 1) * STATE_SIZE;
+
+const STATE_OFF: usize = 2; // The offset of the states inside the witness columns
+
+/// The columns of the Poseidon circuit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PoseidonColumn {
+    /// Whether the row computes a permutation requested by the CPU, the
+    /// padding rows computing the permutation of the zero state
+    Selector,
+    /// The index of the permutation, to distinguish inside the communication
+    /// channel with the CPU
+    Index,
+    /// The element `i` of the state after `r` rounds, as `State(r, i)`. The
+    /// input of the permutation is `State(0, _)` and its output
+    /// `State(ROUNDS, _)`.
+    State(usize, usize),
+}
+
+impl From<PoseidonColumn> for usize {
+    /// Returns the witness column index for the given column
+    fn from(column: PoseidonColumn) -> usize {
+        match column {
+            PoseidonColumn::Selector => 0,
+            PoseidonColumn::Index => 1,
+            PoseidonColumn::State(round, i) => {
+                assert!(round <= ROUNDS && i < STATE_SIZE);
+                STATE_OFF + round * STATE_SIZE + i
+            }
+        }
+    }
+}
+
+/// The witness columns used by the Poseidon circuit
+pub type PoseidonWitness<T> = Witness<N_ZKVM_POSEIDON_COLS, T>;
+
+impl<T: Clone> Index<PoseidonColumn> for PoseidonWitness<T> {
+    type Output = T;
+
+    fn index(&self, index: PoseidonColumn) -> &Self::Output {
+        &self.cols[usize::from(index)]
+    }
+}
+
+impl<T: Clone> IndexMut<PoseidonColumn> for PoseidonWitness<T> {
+    fn index_mut(&mut self, index: PoseidonColumn) -> &mut Self::Output {
+        &mut self.cols[usize::from(index)]
+    }
+}
+
+impl ColumnIndexer<RelationColumnType> for PoseidonColumn {
+    const N_COL: usize = N_ZKVM_POSEIDON_COLS;
+    fn to_column(self) -> Column<RelationColumnType> {
+        Column::Relation(RelationColumnType::Scratch(usize::from(self)))
+    }
+}
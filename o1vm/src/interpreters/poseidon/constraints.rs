@@ -0,0 +1,59 @@
+//! This module contains the constraints for one Poseidon permutation.
+use crate::{
+    interpreters::poseidon::{
+        interpreter::{Interpreter, PoseidonInterpreter},
+        Constraint, PoseidonColumn,
+    },
+    lookups::Lookup,
+    E,
+};
+use ark_ff::Field;
+use kimchi::circuits::{
+    expr::{ConstantTerm::Literal, Expr, ExprInner, Operations, Variable},
+    gate::CurrOrNext,
+};
+use kimchi_msm::columns::ColumnIndexer;
+
+/// This struct contains all that needs to be kept track of during the
+/// execution of the Poseidon interpreter
+#[derive(Clone, Debug)]
+pub struct Env<Fp> {
+    /// Constraints that are added to the circuit
+    pub constraints: Vec<E<Fp>>,
+    /// Variables that are looked up in the circuit
+    pub lookups: Vec<Lookup<E<Fp>>>,
+}
+
+impl<F: Field> Default for Env<F> {
+    fn default() -> Self {
+        Self {
+            constraints: Vec::new(),
+            lookups: Vec::new(),
+        }
+    }
+}
+
+impl<F: Field> Interpreter<F> for Env<F> {
+    type Variable = E<F>;
+
+    fn constant_field(x: F) -> Self::Variable {
+        Self::Variable::constant(Operations::from(Literal(x)))
+    }
+
+    fn variable(&self, column: PoseidonColumn) -> Self::Variable {
+        Expr::Atom(ExprInner::Cell(Variable {
+            col: column.to_column(),
+            row: CurrOrNext::Curr,
+        }))
+    }
+
+    fn constrain(&mut self, _tag: Constraint, x: Self::Variable) {
+        self.constraints.push(x);
+    }
+
+    fn add_lookup(&mut self, lookup: Lookup<Self::Variable>) {
+        self.lookups.push(lookup);
+    }
+}
+
+impl<F: Field> PoseidonInterpreter<F> for Env<F> {}
@@ -0,0 +1,99 @@
+//! This module defines the Poseidon interpreter, in charge of the constraints
+//! and the lookups of a permutation, shared by the witness and the constraints
+//! environments.
+use crate::{
+    interpreters::poseidon::{
+        Constraint::{self, *},
+        PoseidonColumn, ROUNDS, STATE_SIZE,
+    },
+    lookups::{Lookup, LookupTableIDs::PoseidonLookup},
+};
+use ark_ff::{Field, One, Zero};
+use mina_poseidon::poseidon::ArithmeticSpongeParams;
+use std::fmt::Debug;
+
+/// This trait includes functionalities needed to obtain the variables of the
+/// Poseidon circuit needed for constraints and witness
+pub trait Interpreter<F: One + Debug + Zero> {
+    type Variable: std::ops::Mul<Self::Variable, Output = Self::Variable>
+        + std::ops::Add<Self::Variable, Output = Self::Variable>
+        + std::ops::Sub<Self::Variable, Output = Self::Variable>
+        + Clone
+        + Debug
+        + One
+        + Zero;
+
+    /// Creates a variable from a constant field element
+    fn constant_field(x: F) -> Self::Variable;
+
+    /// Returns the variable corresponding to a given column.
+    fn variable(&self, column: PoseidonColumn) -> Self::Variable;
+
+    /// Adds one constraint to the environment
+    fn constrain(&mut self, tag: Constraint, x: Self::Variable);
+
+    /// Adds a given Lookup to the environment
+    fn add_lookup(&mut self, lookup: Lookup<Self::Variable>);
+}
+
+pub trait PoseidonInterpreter<F: Field>
+where
+    Self: Interpreter<F>,
+{
+    /// Creates all 166 constraints of a row:
+    /// - 1 constraint of degree 2, for the booleanity of the selector
+    /// - 165 constraints of degree 7, one for each element of the state after
+    ///   each round
+    ///
+    /// The constraints do not depend on the selector, the padding rows
+    /// computing the permutation of the zero state.
+    fn constraints(&mut self, params: &ArithmeticSpongeParams<F>) {
+        let selector = self.variable(PoseidonColumn::Selector);
+        self.constrain(
+            BooleanitySelector,
+            selector.clone() * (selector - Self::Variable::one()),
+        );
+
+        for round in 0..ROUNDS {
+            let sbox: Vec<Self::Variable> = (0..STATE_SIZE)
+                .map(|j| {
+                    let x = self.variable(PoseidonColumn::State(round, j));
+                    let x2 = x.clone() * x.clone();
+                    let x4 = x2.clone() * x2.clone();
+                    x4 * x2 * x
+                })
+                .collect();
+            for i in 0..STATE_SIZE {
+                // S-box, then MDS matrix, then round constants, as the state
+                // of Kimchi has no initial round constants
+                let expected = sbox.iter().zip(params.mds[i].iter()).fold(
+                    Self::constant_field(params.round_constants[round][i]),
+                    |acc, (x, m)| acc + Self::constant_field(*m) * x.clone(),
+                );
+                let state = self.variable(PoseidonColumn::State(round + 1, i));
+                self.constrain(Round(round, i), state - expected);
+            }
+        }
+    }
+
+    /// Adds the 2 lookups of the communication channel with the CPU, reading
+    /// the input of the permutation and writing its output, when the selector
+    /// is set
+    fn lookups(&mut self) {
+        let selector = self.variable(PoseidonColumn::Selector);
+        let index = self.variable(PoseidonColumn::Index);
+        let state = |env: &Self, round| -> Vec<Self::Variable> {
+            (0..STATE_SIZE)
+                .map(|i| env.variable(PoseidonColumn::State(round, i)))
+                .collect()
+        };
+
+        // COMMUNICATION CHANNEL: Read the input of the permutation
+        let input = [vec![index.clone(), Self::Variable::zero()], state(self, 0)].concat();
+        self.add_lookup(Lookup::read_if(selector.clone(), PoseidonLookup, input));
+
+        // COMMUNICATION CHANNEL: Write the output of the permutation
+        let output = [vec![index, Self::Variable::one()], state(self, ROUNDS)].concat();
+        self.add_lookup(Lookup::write_if(selector, PoseidonLookup, output));
+    }
+}
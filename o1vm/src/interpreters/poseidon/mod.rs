@@ -0,0 +1,42 @@
+//! A circuit for the Poseidon permutation used by Mina, backing the Poseidon
+//! precompile of the zkVM, so that the guests hashing with Poseidon, for
+//! instance to verify a Mina state, do not emulate the arithmetic of the field
+//! with 32-bit words.
+//!
+//! The permutation is the one of [mina_poseidon::pasta::fp_kimchi], made of
+//! [ROUNDS] full rounds applying the S-box `x^7` to each element of the state,
+//! the MDS matrix, and the round constants. Each row of the Poseidon sub-trace
+//! computes a whole permutation, storing the state after each round, and is
+//! bound to the system call of the CPU which requested it through the
+//! [crate::lookups::LookupTableIDs::PoseidonLookup] table, see [precompile].
+use mina_poseidon::constants::{PlonkSpongeConstantsKimchi, SpongeConstants};
+
+pub mod column;
+pub mod constraints;
+pub mod interpreter;
+pub mod precompile;
+#[cfg(test)]
+pub mod tests;
+pub mod witness;
+
+pub use column::PoseidonColumn;
+
+/// The number of field elements of the state of the permutation
+pub const STATE_SIZE: usize = PlonkSpongeConstantsKimchi::SPONGE_WIDTH;
+/// The number of full rounds of the permutation
+pub const ROUNDS: usize = PlonkSpongeConstantsKimchi::PERM_ROUNDS_FULL;
+
+/// Errors that can occur during the check of the witness
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    Constraint(Constraint),
+}
+
+/// All the names for constraints involved in the Poseidon circuit
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Constraint {
+    /// The selector of the permutations is boolean
+    BooleanitySelector,
+    /// The element `i` of the state after the round `r`, as `Round(r, i)`
+    Round(usize, usize),
+}
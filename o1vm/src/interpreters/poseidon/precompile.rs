@@ -0,0 +1,91 @@
+//! The Poseidon precompile: a system call permuting a state stored in the
+//! memory of the guest with the Poseidon permutation of Mina, whose execution
+//! is delegated to the Poseidon circuit.
+//!
+//! The system call `poseidon(state)` replaces in place the [STATE_SIZE]
+//! elements of the base field of Pallas stored at the address `state` by their
+//! permutation. Each element is encoded on [FIELD_ELEMENT_BYTES] bytes in
+//! little endian, and reduced modulo the order of the field when read.
+//!
+//! The CPU writes the input of the permutation to the
+//! [crate::lookups::LookupTableIDs::PoseidonLookup] table, indexed by the
+//! number of permutations requested before and tagged with 0, and reads its
+//! output, tagged with 1, written by the Poseidon sub-trace, see
+//! [super::interpreter::PoseidonInterpreter::lookups].
+
+use crate::interpreters::poseidon::STATE_SIZE;
+use ark_ff::{Field, PrimeField};
+use kimchi::o1_utils::FieldHelpers;
+use mina_curves::pasta::Fp;
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi, pasta::fp_kimchi, permutation::poseidon_block_cipher,
+};
+
+/// The number of the system call, following the one of the Keccak precompile
+pub const SYSCALL_POSEIDON: u32 = 0x10001;
+
+/// The number of bytes encoding an element of the state
+pub const FIELD_ELEMENT_BYTES: usize = 32;
+
+/// The number of bytes encoding the state
+pub const POSEIDON_STATE_BYTES: usize = STATE_SIZE * FIELD_ELEMENT_BYTES;
+
+/// The values given by the witness environment to bind an execution of the
+/// system call to the Poseidon sub-trace
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoseidonHint {
+    /// The i-th element of the input of the permutation
+    Input(usize),
+    /// The i-th element of the output of the permutation
+    Output(usize),
+}
+
+/// The last permutation computed by the precompile
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PoseidonPrecompile {
+    pub input: [Fp; STATE_SIZE],
+    pub output: [Fp; STATE_SIZE],
+}
+
+impl PoseidonPrecompile {
+    /// Permutes the state encoded by `bytes`, returning the encoding of the
+    /// output
+    pub fn permute(&mut self, bytes: &[u8]) -> Vec<u8> {
+        self.input = decode_state(bytes);
+        self.output = permutation(self.input);
+        encode_state(&self.output)
+    }
+
+    /// Returns the value of the hint `hint` for the last permutation
+    pub fn hint<F: Field>(&self, hint: PoseidonHint) -> F {
+        let x = match hint {
+            PoseidonHint::Input(i) => self.input[i],
+            PoseidonHint::Output(i) => self.output[i],
+        };
+        // The witness environments are generic over the field, which is the
+        // base field of Pallas in practice
+        x.into_bigint().0.iter().rev().fold(F::zero(), |acc, limb| {
+            acc * F::from(1u128 << 64) + F::from(*limb)
+        })
+    }
+}
+
+/// Returns the permutation of the state `input`
+pub fn permutation(input: [Fp; STATE_SIZE]) -> [Fp; STATE_SIZE] {
+    let mut state = input.to_vec();
+    poseidon_block_cipher::<Fp, PlonkSpongeConstantsKimchi>(fp_kimchi::static_params(), &mut state);
+    state.try_into().unwrap()
+}
+
+/// Decodes the state stored in the memory of the guest
+pub fn decode_state(bytes: &[u8]) -> [Fp; STATE_SIZE] {
+    assert_eq!(bytes.len(), POSEIDON_STATE_BYTES);
+    std::array::from_fn(|i| {
+        Fp::from_le_bytes_mod_order(&bytes[i * FIELD_ELEMENT_BYTES..(i + 1) * FIELD_ELEMENT_BYTES])
+    })
+}
+
+/// Encodes the state to be stored in the memory of the guest
+pub fn encode_state(state: &[Fp; STATE_SIZE]) -> Vec<u8> {
+    state.iter().flat_map(|x| x.to_bytes()).collect()
+}
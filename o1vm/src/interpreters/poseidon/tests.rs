@@ -0,0 +1,86 @@
+use crate::{
+    interpreters::poseidon::{
+        constraints,
+        interpreter::PoseidonInterpreter,
+        precompile::{decode_state, encode_state, permutation},
+        witness, Constraint, Error, PoseidonColumn, ROUNDS, STATE_SIZE,
+    },
+    lookups::LookupTableIDs,
+};
+use ark_ff::{One, PrimeField, UniformRand, Zero};
+use kimchi::o1_utils;
+use mina_curves::pasta::Fp;
+use mina_poseidon::pasta::fp_kimchi;
+use std::array;
+
+#[test]
+fn test_permutation_satisfies_constraints() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let params = fp_kimchi::static_params();
+    let input: [Fp; STATE_SIZE] = array::from_fn(|_| Fp::rand(&mut rng));
+    let mut env = witness::Env::permutation(params, 3, input);
+    assert!(env.check(params).is_empty());
+    assert_eq!(env.witness[PoseidonColumn::Selector], Fp::one());
+    assert_eq!(env.witness[PoseidonColumn::Index], Fp::from(3u64));
+    assert_eq!(env.output(), permutation(input));
+}
+
+#[test]
+fn test_padding_satisfies_constraints() {
+    let params = fp_kimchi::static_params();
+    let mut env = witness::Env::padding(params, [Fp::zero(); STATE_SIZE]);
+    assert!(env.check(params).is_empty());
+    assert_eq!(env.witness[PoseidonColumn::Selector], Fp::zero());
+}
+
+#[test]
+fn test_wrong_round_is_detected() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let params = fp_kimchi::static_params();
+    let input: [Fp; STATE_SIZE] = array::from_fn(|_| Fp::rand(&mut rng));
+    let mut env = witness::Env::permutation(params, 0, input);
+    env.witness[PoseidonColumn::State(10, 1)] += Fp::one();
+    let errors = env.check(params).to_vec();
+    // The state is wrong after the round 9, and the whole round 10 depends on
+    // it through the MDS matrix
+    assert!(errors.contains(&Error::Constraint(Constraint::Round(9, 1))));
+    for i in 0..STATE_SIZE {
+        assert!(errors.contains(&Error::Constraint(Constraint::Round(10, i))));
+    }
+    assert_eq!(errors.len(), 1 + STATE_SIZE);
+
+    env.witness[PoseidonColumn::State(10, 1)] -= Fp::one();
+    env.witness[PoseidonColumn::Selector] = Fp::from(2u64);
+    assert_eq!(
+        env.check(params),
+        [Error::Constraint(Constraint::BooleanitySelector)]
+    );
+}
+
+#[test]
+fn test_regression_number_of_constraints() {
+    let mut env = constraints::Env::<Fp>::default();
+    env.constraints(fp_kimchi::static_params());
+    env.lookups();
+    assert_eq!(env.constraints.len(), 1 + ROUNDS * STATE_SIZE);
+    let max_degree = env.constraints.iter().map(|c| c.degree(1, 0)).max();
+    assert_eq!(max_degree, Some(7));
+    assert_eq!(env.lookups.len(), 2);
+    assert!(env
+        .lookups
+        .iter()
+        .all(|lookup| lookup.table_id == LookupTableIDs::PoseidonLookup
+            && lookup.value.len() == 2 + STATE_SIZE));
+}
+
+#[test]
+fn test_encode_decode_state() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let state: [Fp; STATE_SIZE] = array::from_fn(|_| Fp::rand(&mut rng));
+    assert_eq!(decode_state(&encode_state(&state)), state);
+    // The elements are reduced modulo the order of the field
+    assert_eq!(
+        decode_state(&[0xFF; 96]),
+        [Fp::from_le_bytes_mod_order(&[0xFF; 32]); STATE_SIZE]
+    );
+}
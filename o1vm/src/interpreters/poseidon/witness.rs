@@ -0,0 +1,104 @@
+//! This file contains the witness of the Poseidon permutation for the zkVM
+//! project. The states after each round are computed with the permutation of
+//! [mina_poseidon], to avoid code duplication.
+use crate::{
+    interpreters::poseidon::{
+        column::PoseidonWitness,
+        interpreter::{Interpreter, PoseidonInterpreter},
+        Constraint, Error, PoseidonColumn, ROUNDS, STATE_SIZE,
+    },
+    lookups::Lookup,
+};
+use ark_ff::Field;
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi, permutation::full_round,
+    poseidon::ArithmeticSpongeParams,
+};
+
+/// This struct contains the witness of one row of the Poseidon circuit
+#[derive(Clone, Debug)]
+pub struct Env<F> {
+    /// The columns of the row
+    pub witness: PoseidonWitness<F>,
+    /// If any, an error that occurred during the execution of the
+    /// constraints, to help with debugging
+    pub(crate) errors: Vec<Error>,
+}
+
+impl<F: Field> Default for Env<F> {
+    fn default() -> Self {
+        Self {
+            witness: PoseidonWitness::default(),
+            errors: vec![],
+        }
+    }
+}
+
+impl<F: Field> Env<F> {
+    /// Creates the row computing the permutation of index `index` of the
+    /// state `input`, requested by the CPU
+    pub fn permutation(
+        params: &ArithmeticSpongeParams<F>,
+        index: u64,
+        input: [F; STATE_SIZE],
+    ) -> Self {
+        let mut env = Self::padding(params, input);
+        env.witness[PoseidonColumn::Selector] = F::one();
+        env.witness[PoseidonColumn::Index] = F::from(index);
+        env
+    }
+
+    /// Creates a row computing the permutation of `input` which is not bound
+    /// to the CPU, like the padding rows, permuting the zero state
+    pub fn padding(params: &ArithmeticSpongeParams<F>, input: [F; STATE_SIZE]) -> Self {
+        let mut env = Self::default();
+        let mut state = input.to_vec();
+        for round in 0..=ROUNDS {
+            if round > 0 {
+                full_round::<F, PlonkSpongeConstantsKimchi>(params, &mut state, round - 1);
+            }
+            for (i, x) in state.iter().enumerate() {
+                env.witness[PoseidonColumn::State(round, i)] = *x;
+            }
+        }
+        env
+    }
+
+    /// Returns the output of the permutation computed by the row
+    pub fn output(&self) -> [F; STATE_SIZE] {
+        std::array::from_fn(|i| self.witness[PoseidonColumn::State(ROUNDS, i)])
+    }
+
+    /// Checks the constraints of the row, returning the errors found
+    pub fn check(&mut self, params: &ArithmeticSpongeParams<F>) -> &[Error] {
+        self.errors.clear();
+        self.constraints(params);
+        self.lookups();
+        &self.errors
+    }
+}
+
+impl<F: Field> Interpreter<F> for Env<F> {
+    type Variable = F;
+
+    fn constant_field(x: F) -> Self::Variable {
+        x
+    }
+
+    fn variable(&self, column: PoseidonColumn) -> Self::Variable {
+        self.witness[column]
+    }
+
+    /// Checks the constraint `tag` by checking that the input `x` is zero
+    fn constrain(&mut self, tag: Constraint, x: Self::Variable) {
+        if x != F::zero() {
+            self.errors.push(Error::Constraint(tag));
+        }
+    }
+
+    fn add_lookup(&mut self, _lookup: Lookup<Self::Variable>) {
+        // The communication channel is a RAM table, without multiplicities
+    }
+}
+
+impl<F: Field> PoseidonInterpreter<F> for Env<F> {}
@@ -0,0 +1,359 @@
+//! Custom precompiles, given by the users of the zkVM, so that an
+//! application-specific accelerator can be added without forking the
+//! interpreters.
+//!
+//! A custom precompile is a system call `syscall(input, output)`, called with
+//! the address `input` in a0 and the address `output` in a1. It reads
+//! [Precompile::input_len] bytes at `input`, computes its result with
+//! [Precompile::execute] and writes the [Precompile::output_len] bytes of the
+//! result at `output`.
+//!
+//! Its execution is delegated to a sub-trace of its own, with one row per
+//! call. The first [N_CALL_COLS] columns of a row identify the call, see
+//! [CallColumn], and the [Precompile::columns] next ones are computed by
+//! [Precompile::witness] and constrained by [Precompile::constraints].
+//!
+//! The CPU writes each call to the [LookupTableIDs::PrecompileLookup] table as
+//! `[syscall, index, input, output]`, where `index` is the number of calls to
+//! a custom precompile before it, and the row of the sub-trace computing the
+//! call reads it back, see [PrecompileRegistry::lookups].
+//!
+//! The precompiles are registered in a [PrecompileRegistry], held by the
+//! witness environment of the interpreter, see
+//! [crate::interpreters::riscv32im::witness::Env::register_precompile].
+
+use crate::{
+    lookups::{Lookup, LookupTableIDs},
+    RelationColumnType, E,
+};
+use ark_ff::{Field, Zero};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as D};
+use kimchi::{
+    circuits::{
+        berkeley_columns::BerkeleyChallenges,
+        expr::{
+            ColumnEvaluations, ConstantTerm::Literal, Constants, Expr, ExprError, ExprInner,
+            Operations, Variable,
+        },
+        gate::CurrOrNext,
+    },
+    proof::PointEvaluations,
+};
+use kimchi_msm::columns::Column;
+use mina_curves::pasta::Fp as PastaFp;
+use mina_poseidon::pasta::fp_kimchi;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// The number of columns identifying the call computed by a row of the
+/// sub-trace of a precompile
+pub const N_CALL_COLS: usize = 3;
+
+/// The columns of the sub-trace of a precompile identifying the call computed
+/// by the row, read from the communication channel with the CPU
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CallColumn {
+    /// The index of the call among the calls to the custom precompiles
+    Index,
+    /// The address of the input
+    Input,
+    /// The address of the output
+    Output,
+}
+
+impl From<CallColumn> for usize {
+    fn from(column: CallColumn) -> usize {
+        match column {
+            CallColumn::Index => 0,
+            CallColumn::Input => 1,
+            CallColumn::Output => 2,
+        }
+    }
+}
+
+/// Returns the variable of the column `column` of the sub-trace of a
+/// precompile, on the current row
+pub fn call_variable<F: Field>(column: CallColumn) -> E<F> {
+    cell(usize::from(column), CurrOrNext::Curr)
+}
+
+/// Returns the variable of the `i`-th column of a precompile, following the
+/// [N_CALL_COLS] columns of the call, on the row `row`
+pub fn variable<F: Field>(i: usize, row: CurrOrNext) -> E<F> {
+    cell(N_CALL_COLS + i, row)
+}
+
+/// Returns the constant `x`, to be used in the constraints of a precompile
+pub fn constant<F: Field>(x: F) -> E<F> {
+    E::constant(Operations::from(Literal(x)))
+}
+
+fn cell<F: Field>(i: usize, row: CurrOrNext) -> E<F> {
+    Expr::Atom(ExprInner::Cell(Variable {
+        col: Column::Relation(RelationColumnType::Scratch(i)),
+        row,
+    }))
+}
+
+/// A precompile given by the user
+pub trait Precompile<F: Field> {
+    /// The name of the precompile, used in the errors
+    fn name(&self) -> &str;
+
+    /// The number of the system call, passed in register a7
+    fn syscall(&self) -> u32;
+
+    /// The number of bytes of the input
+    fn input_len(&self) -> usize;
+
+    /// The number of bytes of the output
+    fn output_len(&self) -> usize;
+
+    /// Computes the output of the precompile on the input `input`, of
+    /// [Precompile::input_len] bytes. The output must have
+    /// [Precompile::output_len] bytes.
+    fn execute(&self, input: &[u8]) -> Vec<u8>;
+
+    /// The number of columns of the sub-trace of the precompile, besides the
+    /// [N_CALL_COLS] columns of the call
+    fn columns(&self) -> usize;
+
+    /// Returns the [Precompile::columns] values of the row computing the
+    /// output `output` from the input `input`
+    fn witness(&self, input: &[u8], output: &[u8]) -> Vec<F>;
+
+    /// The constraints of a row of the sub-trace, over the columns given by
+    /// [variable] and [call_variable]
+    fn constraints(&self) -> Vec<E<F>>;
+
+    /// The lookups of a row of the sub-trace, besides the one reading the
+    /// call from the CPU. There are none by default.
+    fn lookups(&self) -> Vec<Lookup<E<F>>> {
+        vec![]
+    }
+}
+
+/// A call to a custom precompile, as recorded by the witness environment
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrecompileCall {
+    pub syscall: u32,
+    /// The address of the input
+    pub input_addr: u64,
+    /// The address of the output
+    pub output_addr: u64,
+    pub input: Vec<u8>,
+    pub output: Vec<u8>,
+}
+
+/// Errors raised when registering a precompile
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PrecompileError {
+    #[error("the system call {0} is implemented by the interpreter")]
+    Reserved(u32),
+    #[error("the system call {0} is already implemented by the precompile {1}")]
+    AlreadyRegistered(u32, String),
+}
+
+/// A constraint of a precompile which does not hold on a row of its
+/// sub-trace
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnsatisfiedConstraint<F> {
+    pub row: usize,
+    /// The index of the constraint in [Precompile::constraints]
+    pub index: usize,
+    pub value: F,
+}
+
+/// The precompiles registered by the user, by system call, and the calls made
+/// to them by the program
+#[derive(Default)]
+pub struct PrecompileRegistry<F> {
+    precompiles: BTreeMap<u32, Box<dyn Precompile<F>>>,
+    /// The calls to the precompiles, in order
+    pub calls: Vec<PrecompileCall>,
+}
+
+impl<F: Field> PrecompileRegistry<F> {
+    /// Registers the precompile `precompile`, which must not implement the
+    /// system call of another precompile
+    pub fn register(&mut self, precompile: Box<dyn Precompile<F>>) -> Result<(), PrecompileError> {
+        let syscall = precompile.syscall();
+        if let Some(registered) = self.precompiles.get(&syscall) {
+            return Err(PrecompileError::AlreadyRegistered(
+                syscall,
+                registered.name().to_string(),
+            ));
+        }
+        self.precompiles.insert(syscall, precompile);
+        Ok(())
+    }
+
+    /// Returns the precompile implementing the system call `syscall`, if any
+    pub fn get(&self, syscall: u32) -> Option<&dyn Precompile<F>> {
+        self.precompiles.get(&syscall).map(|p| p.as_ref())
+    }
+
+    /// Returns the system calls of the registered precompiles
+    pub fn syscalls(&self) -> impl Iterator<Item = u32> + '_ {
+        self.precompiles.keys().copied()
+    }
+
+    /// Executes the precompile implementing `syscall` on the input `input`
+    /// read at `input_addr`, and records the call. Returns the index of the
+    /// call and the output to write at `output_addr`.
+    pub fn call(
+        &mut self,
+        syscall: u32,
+        input_addr: u64,
+        output_addr: u64,
+        input: Vec<u8>,
+    ) -> (u64, Vec<u8>) {
+        let precompile = self
+            .get(syscall)
+            .unwrap_or_else(|| panic!("No precompile implements the system call {syscall}"));
+        let output = precompile.execute(&input);
+        assert_eq!(
+            output.len(),
+            precompile.output_len(),
+            "The precompile {} returned {} bytes instead of {}",
+            precompile.name(),
+            output.len(),
+            precompile.output_len()
+        );
+        let index = self.calls.len() as u64;
+        self.calls.push(PrecompileCall {
+            syscall,
+            input_addr,
+            output_addr,
+            input,
+            output: output.clone(),
+        });
+        (index, output)
+    }
+
+    /// Returns the rows of the sub-trace of the precompile implementing
+    /// `syscall`, one for each of its calls, in order
+    pub fn witness(&self, syscall: u32) -> Vec<Vec<F>> {
+        let precompile = self.precompile(syscall);
+        self.calls
+            .iter()
+            .enumerate()
+            .filter(|(_, call)| call.syscall == syscall)
+            .map(|(index, call)| {
+                let row = precompile.witness(&call.input, &call.output);
+                assert_eq!(
+                    row.len(),
+                    precompile.columns(),
+                    "The precompile {} returned a row of {} columns instead of {}",
+                    precompile.name(),
+                    row.len(),
+                    precompile.columns()
+                );
+                [
+                    vec![
+                        F::from(index as u64),
+                        F::from(call.input_addr),
+                        F::from(call.output_addr),
+                    ],
+                    row,
+                ]
+                .concat()
+            })
+            .collect()
+    }
+
+    /// Returns the constraints of a row of the sub-trace of the precompile
+    /// implementing `syscall`
+    pub fn constraints(&self, syscall: u32) -> Vec<E<F>> {
+        self.precompile(syscall).constraints()
+    }
+
+    /// Returns the lookups of a row of the sub-trace of the precompile
+    /// implementing `syscall`: the one reading the call written by the CPU,
+    /// followed by the ones of the precompile
+    pub fn lookups(&self, syscall: u32) -> Vec<Lookup<E<F>>> {
+        let precompile = self.precompile(syscall);
+        // COMMUNICATION CHANNEL: Read the call written by the CPU
+        let call = Lookup::read_one(
+            LookupTableIDs::PrecompileLookup,
+            vec![
+                constant(F::from(syscall)),
+                call_variable(CallColumn::Index),
+                call_variable(CallColumn::Input),
+                call_variable(CallColumn::Output),
+            ],
+        );
+        [vec![call], precompile.lookups()].concat()
+    }
+
+    fn precompile(&self, syscall: u32) -> &dyn Precompile<F> {
+        self.get(syscall)
+            .unwrap_or_else(|| panic!("No precompile implements the system call {syscall}"))
+    }
+}
+
+impl PrecompileRegistry<PastaFp> {
+    /// Evaluates the constraints of the precompile implementing `syscall` on
+    /// the rows of its sub-trace, and returns the ones which do not hold
+    pub fn check(
+        &self,
+        syscall: u32,
+    ) -> Result<Vec<UnsatisfiedConstraint<PastaFp>>, ExprError<Column<RelationColumnType>>> {
+        let rows = self.witness(syscall);
+        let constraints = self.constraints(syscall);
+        let domain = D::<PastaFp>::new(rows.len().max(1)).expect("the sub-trace fits in a domain");
+        // The constraints of the precompiles only use literal constants
+        let constants = Constants {
+            endo_coefficient: PastaFp::zero(),
+            mds: &fp_kimchi::static_params().mds,
+            zk_rows: 0,
+        };
+        let challenges = BerkeleyChallenges {
+            alpha: PastaFp::zero(),
+            beta: PastaFp::zero(),
+            gamma: PastaFp::zero(),
+            joint_combiner: PastaFp::zero(),
+        };
+        let mut unsatisfied = vec![];
+        for row in 0..rows.len() {
+            let evaluations = RowEvaluations { rows: &rows, row };
+            for (index, constraint) in constraints.iter().enumerate() {
+                let value = constraint.evaluate_(
+                    domain,
+                    domain.element(row),
+                    &evaluations,
+                    &constants,
+                    &challenges,
+                )?;
+                if !value.is_zero() {
+                    unsatisfied.push(UnsatisfiedConstraint { row, index, value });
+                }
+            }
+        }
+        Ok(unsatisfied)
+    }
+}
+
+/// The values of the columns of the sub-trace of a precompile on a row, and
+/// on the next one
+struct RowEvaluations<'a, F> {
+    rows: &'a [Vec<F>],
+    row: usize,
+}
+
+impl<F: Copy> ColumnEvaluations<F> for RowEvaluations<'_, F> {
+    type Column = Column<RelationColumnType>;
+
+    fn evaluate(&self, col: Self::Column) -> Result<PointEvaluations<F>, ExprError<Self::Column>> {
+        let value = |row: usize| match col {
+            Column::Relation(RelationColumnType::Scratch(i)) => self.rows[row].get(i).copied(),
+            _ => None,
+        };
+        let next = (self.row + 1) % self.rows.len();
+        match (value(self.row), value(next)) {
+            (Some(zeta), Some(zeta_omega)) => Ok(PointEvaluations { zeta, zeta_omega }),
+            _ => Err(ExprError::MissingEvaluation(col, CurrOrNext::Curr)),
+        }
+    }
+}
@@ -1,8 +1,12 @@
 use super::{
     interpreter::{
-        IInstruction,
-        Instruction::{self, IType, MType, RType, SBType, SType, SyscallType, UJType, UType},
-        RInstruction, SBInstruction, SInstruction, SyscallInstruction, UInstruction, UJInstruction,
+        AInstruction, CInstruction, IInstruction,
+        Instruction::{
+            self, AType, CSRType, CType, IType, MType, RType, SBType, SType, SyscallType, UJType,
+            UType,
+        },
+        MInstruction, RInstruction, SBInstruction, SInstruction, SyscallInstruction, UInstruction,
+        UJInstruction,
     },
     INSTRUCTION_SET_SIZE, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE,
 };
@@ -113,6 +117,51 @@ impl From<Instruction> for usize {
                     + SyscallInstruction::COUNT
                     + mtype as usize
             }
+            CType(ctype) => {
+                SCRATCH_SIZE
+                    + SCRATCH_SIZE_INVERSE
+                    + 1
+                    + RInstruction::COUNT
+                    + IInstruction::COUNT
+                    + SInstruction::COUNT
+                    + SBInstruction::COUNT
+                    + UInstruction::COUNT
+                    + UJInstruction::COUNT
+                    + SyscallInstruction::COUNT
+                    + MInstruction::COUNT
+                    + ctype as usize
+            }
+            AType(atype) => {
+                SCRATCH_SIZE
+                    + SCRATCH_SIZE_INVERSE
+                    + 1
+                    + RInstruction::COUNT
+                    + IInstruction::COUNT
+                    + SInstruction::COUNT
+                    + SBInstruction::COUNT
+                    + UInstruction::COUNT
+                    + UJInstruction::COUNT
+                    + SyscallInstruction::COUNT
+                    + MInstruction::COUNT
+                    + CInstruction::COUNT
+                    + atype as usize
+            }
+            CSRType(csrtype) => {
+                SCRATCH_SIZE
+                    + SCRATCH_SIZE_INVERSE
+                    + 1
+                    + RInstruction::COUNT
+                    + IInstruction::COUNT
+                    + SInstruction::COUNT
+                    + SBInstruction::COUNT
+                    + UInstruction::COUNT
+                    + UJInstruction::COUNT
+                    + SyscallInstruction::COUNT
+                    + MInstruction::COUNT
+                    + CInstruction::COUNT
+                    + AInstruction::COUNT
+                    + csrtype as usize
+            }
         }
     }
 }
@@ -0,0 +1,290 @@
+//! Logs of the instructions executed by the interpreter, in the format of the
+//! commit log of spike (`spike -l --log-commits`), so that an execution can be
+//! diffed against the one of a reference simulator with the existing tooling.
+//!
+//! Each instruction is logged on two lines. The first one gives the
+//! instruction pointer, the encoding and the disassembly of the instruction.
+//! The second one, the commit, gives the privilege level, the instruction
+//! pointer and the encoding again, followed by the register written and the
+//! memory read and written by the instruction:
+//!
+//! ```text
+//! core   0: 0x0000000000011094 (0x00a00513) addi    a0, zero, 10
+//! core   0: 0 0x00011094 (0x00a00513) x10 0x0000000a
+//! core   0: 0x0000000000011098 (0xc118) c.sw    a4, 0(a0)
+//! core   0: 0 0x00011098 (0xc118) mem 0x40800000 0x00000007
+//! ```
+//!
+//! The disassembly follows the one of spike, without its pseudo-instructions,
+//! and the compressed instructions are given the operands of their expansion:
+//! the commits are the lines to compare. The system calls are handled by the
+//! host, as by the proxy kernel of spike, and the registers they change are
+//! logged as written.
+
+use super::{interpreter::Instruction, witness::bits_of};
+use crate::memory::SparseMemory;
+use std::io::{self, Write};
+
+/// The ABI names of the general purpose registers
+pub const REGISTER_NAMES: [&str; 32] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2", "a3", "a4",
+    "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4",
+    "t5", "t6",
+];
+
+/// The privilege level of the programs: they run in user mode, as under the
+/// proxy kernel
+const PRIVILEGE_LEVEL: u8 = 0;
+
+/// An instruction executed by the interpreter
+#[derive(Clone, Debug)]
+pub struct Commit {
+    pub instruction_pointer: u32,
+    pub instruction: Instruction,
+    /// The encoding of the instruction in memory, on 16 bits for the
+    /// compressed instructions
+    pub raw_encoding: u32,
+    /// The encoding of the instruction, or of its expansion in the base
+    /// instruction set
+    pub encoding: u32,
+    /// The general purpose registers before the execution of the instruction
+    pub registers_before: [u32; 32],
+    /// The general purpose registers after the execution of the instruction
+    pub registers_after: [u32; 32],
+}
+
+/// A memory access made by an instruction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MemoryAccess {
+    Load {
+        addr: u32,
+    },
+    /// The store of the `size` bytes of `value`
+    Store {
+        addr: u32,
+        value: u32,
+        size: u32,
+    },
+}
+
+/// Returns the encoding of the instruction at the address `addr`, on 16 bits
+/// for a compressed instruction
+pub fn raw_instruction(memory: &SparseMemory<u32, u8>, addr: u32) -> u32 {
+    let low_half = u16::from_le_bytes([memory.get(addr), memory.get(addr + 1)]);
+    // The two lowest bits of a compressed instruction are never 0b11
+    if low_half & 0b11 != 0b11 {
+        return low_half as u32;
+    }
+    u32::from_le_bytes([
+        memory.get(addr),
+        memory.get(addr + 1),
+        memory.get(addr + 2),
+        memory.get(addr + 3),
+    ])
+}
+
+fn sign_extend(x: u32, bits: u32) -> i32 {
+    ((x << (32 - bits)) as i32) >> (32 - bits)
+}
+
+fn immediate_i(encoding: u32) -> i32 {
+    sign_extend(bits_of(encoding, 32, 20), 12)
+}
+
+fn immediate_s(encoding: u32) -> i32 {
+    sign_extend(
+        (bits_of(encoding, 32, 25) << 5) | bits_of(encoding, 12, 7),
+        12,
+    )
+}
+
+fn immediate_b(encoding: u32) -> i32 {
+    sign_extend(
+        (bits_of(encoding, 32, 31) << 12)
+            | (bits_of(encoding, 8, 7) << 11)
+            | (bits_of(encoding, 31, 25) << 5)
+            | (bits_of(encoding, 12, 8) << 1),
+        13,
+    )
+}
+
+fn immediate_j(encoding: u32) -> i32 {
+    sign_extend(
+        (bits_of(encoding, 32, 31) << 20)
+            | (bits_of(encoding, 20, 12) << 12)
+            | (bits_of(encoding, 21, 20) << 11)
+            | (bits_of(encoding, 31, 21) << 1),
+        21,
+    )
+}
+
+fn pc_relative(offset: i32) -> String {
+    if offset < 0 {
+        format!("pc - {}", offset.unsigned_abs())
+    } else {
+        format!("pc + {}", offset)
+    }
+}
+
+/// Returns the disassembly of the instruction `instruction`, with the
+/// encoding `encoding`, in the format of spike
+pub fn disassemble(instruction: Instruction, encoding: u32) -> String {
+    let rd = REGISTER_NAMES[bits_of(encoding, 12, 7) as usize];
+    let rs1 = REGISTER_NAMES[bits_of(encoding, 20, 15) as usize];
+    let rs2 = REGISTER_NAMES[bits_of(encoding, 25, 20) as usize];
+    let funct3 = bits_of(encoding, 15, 12);
+    let operands = match bits_of(encoding, 7, 0) {
+        0b0110111 | 0b0010111 => format!("{rd}, 0x{:x}", bits_of(encoding, 32, 12)),
+        0b1101111 => format!("{rd}, {}", pc_relative(immediate_j(encoding))),
+        0b1100111 | 0b0000011 => format!("{rd}, {}({rs1})", immediate_i(encoding)),
+        0b1100011 => format!("{rs1}, {rs2}, {}", pc_relative(immediate_b(encoding))),
+        0b0100011 => format!("{rs2}, {}({rs1})", immediate_s(encoding)),
+        0b0010011 if funct3 == 0b001 || funct3 == 0b101 => {
+            format!("{rd}, {rs1}, {}", bits_of(encoding, 25, 20))
+        }
+        0b0010011 => format!("{rd}, {rs1}, {}", immediate_i(encoding)),
+        0b0110011 => format!("{rd}, {rs1}, {rs2}"),
+        0b0101111 if bits_of(encoding, 32, 27) == 0b00010 => format!("{rd}, ({rs1})"),
+        0b0101111 => format!("{rd}, {rs2}, ({rs1})"),
+        0b1110011 if funct3 != 0 => {
+            let csr = bits_of(encoding, 32, 20);
+            if funct3 & 0b100 != 0 {
+                format!("{rd}, 0x{csr:x}, {}", bits_of(encoding, 20, 15))
+            } else {
+                format!("{rd}, 0x{csr:x}, {rs1}")
+            }
+        }
+        _ => String::new(),
+    };
+    format!("{:<7} {}", instruction.to_string(), operands)
+        .trim_end()
+        .to_string()
+}
+
+/// Returns the registers written by the instruction of the commit `commit`
+/// with their new value, except `zero`
+pub fn register_writes(commit: &Commit) -> Vec<(usize, u32)> {
+    let encoding = commit.encoding;
+    let rd = bits_of(encoding, 12, 7) as usize;
+    match bits_of(encoding, 7, 0) {
+        // The system calls
+        0b1110011 if bits_of(encoding, 15, 12) == 0 => (1..32)
+            .filter(|i| commit.registers_before[*i] != commit.registers_after[*i])
+            .map(|i| (i, commit.registers_after[i]))
+            .collect(),
+        0b0110111 | 0b0010111 | 0b1101111 | 0b1100111 | 0b0000011 | 0b0010011 | 0b0110011
+        | 0b0101111 | 0b1110011
+            if rd != 0 =>
+        {
+            vec![(rd, commit.registers_after[rd])]
+        }
+        _ => vec![],
+    }
+}
+
+/// Returns the memory accesses made by the instruction of the commit
+/// `commit`, the memory `memory` being the one after its execution
+pub fn memory_accesses(commit: &Commit, memory: &SparseMemory<u32, u8>) -> Vec<MemoryAccess> {
+    let encoding = commit.encoding;
+    let rs1 = commit.registers_before[bits_of(encoding, 20, 15) as usize];
+    let rs2 = commit.registers_before[bits_of(encoding, 25, 20) as usize];
+    let size = 1 << (bits_of(encoding, 15, 12) & 0b11);
+    let word_at = |addr: u32| {
+        u32::from_le_bytes([
+            memory.get(addr),
+            memory.get(addr + 1),
+            memory.get(addr + 2),
+            memory.get(addr + 3),
+        ])
+    };
+    match bits_of(encoding, 7, 0) {
+        0b0000011 => vec![MemoryAccess::Load {
+            addr: rs1.wrapping_add(immediate_i(encoding) as u32),
+        }],
+        0b0100011 => vec![MemoryAccess::Store {
+            addr: rs1.wrapping_add(immediate_s(encoding) as u32),
+            value: if size == 4 {
+                rs2
+            } else {
+                rs2 & ((1 << (8 * size)) - 1)
+            },
+            size,
+        }],
+        0b0101111 => match bits_of(encoding, 32, 27) {
+            // lr.w
+            0b00010 => vec![MemoryAccess::Load { addr: rs1 }],
+            // sc.w, which always succeeds
+            0b00011 => vec![MemoryAccess::Store {
+                addr: rs1,
+                value: rs2,
+                size: 4,
+            }],
+            _ => vec![
+                MemoryAccess::Load { addr: rs1 },
+                MemoryAccess::Store {
+                    addr: rs1,
+                    value: word_at(rs1),
+                    size: 4,
+                },
+            ],
+        },
+        _ => vec![],
+    }
+}
+
+/// The log of the instructions executed, written as they are executed
+pub struct CommitLog {
+    writer: Box<dyn Write>,
+}
+
+impl CommitLog {
+    pub fn new(writer: Box<dyn Write>) -> Self {
+        CommitLog { writer }
+    }
+
+    /// Logs the instruction of the commit `commit`, the memory `memory` being
+    /// the one after its execution
+    pub fn record(&mut self, commit: &Commit, memory: &SparseMemory<u32, u8>) -> io::Result<()> {
+        // The width of the encoding, in hexadecimal digits
+        let width = if commit.raw_encoding & 0b11 == 0b11 {
+            8
+        } else {
+            4
+        };
+        // The instruction pointer is sign-extended, as by spike
+        writeln!(
+            self.writer,
+            "core   0: 0x{:016x} (0x{:08x}) {}",
+            commit.instruction_pointer as i32 as i64,
+            commit.raw_encoding,
+            disassemble(commit.instruction, commit.encoding)
+        )?;
+        write!(
+            self.writer,
+            "core   0: {} 0x{:08x} (0x{:0width$x})",
+            PRIVILEGE_LEVEL, commit.instruction_pointer, commit.raw_encoding
+        )?;
+        for (register, value) in register_writes(commit) {
+            write!(self.writer, " x{:<2} 0x{:08x}", register, value)?;
+        }
+        let accesses = memory_accesses(commit, memory);
+        for access in accesses.iter() {
+            if let MemoryAccess::Load { addr } = access {
+                write!(self.writer, " mem 0x{:08x}", addr)?;
+            }
+        }
+        for access in accesses.iter() {
+            if let MemoryAccess::Store { addr, value, size } = access {
+                let width = 2 * *size as usize;
+                write!(self.writer, " mem 0x{:08x} 0x{:0width$x}", addr, value)?;
+            }
+        }
+        writeln!(self.writer)
+    }
+
+    /// Flushes the log, and returns the writer it is written to
+    pub fn into_writer(mut self) -> io::Result<Box<dyn Write>> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
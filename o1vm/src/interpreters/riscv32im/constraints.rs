@@ -1,27 +1,40 @@
 use super::{
     column::{Column, E},
-    interpreter::{Instruction, InterpreterEnv},
-    INSTRUCTION_SET_SIZE,
+    interpreter::{InstructionSet, InterpreterEnv},
 };
 use crate::{
-    interpreters::riscv32im::{constraints::ConstantTerm::Literal, SCRATCH_SIZE},
-    lookups::Lookup,
+    interpreters::{
+        coverage::{CoverageReport, ExecutionCoverage, InstructionConstraints, ScratchColumn},
+        keccak::precompile::KeccakHint,
+        poseidon::precompile::PoseidonHint,
+        riscv32im::constraints::ConstantTerm::Literal,
+        xlen::Rv32,
+    },
+    lookups::{Lookup, LookupTableIDs},
 };
 use ark_ff::{Field, One};
 use kimchi::circuits::{
     expr::{ConstantTerm, Expr, ExprInner, Operations, Variable},
     gate::CurrOrNext,
 };
+use std::marker::PhantomData;
+use strum::IntoEnumIterator;
 
-pub struct Env<F: Field> {
+/// The constraint environment, generic over the register width and the
+/// instruction set, see [InstructionSet]. It defaults to RV32IM.
+pub struct Env<F: Field, X: InstructionSet = Rv32> {
     pub scratch_state_idx: usize,
     pub scratch_state_idx_inverse: usize,
     pub lookups: Vec<Lookup<E<F>>>,
     pub constraints: Vec<E<F>>,
     pub selector: Option<E<F>>,
+    /// Whether the memory accesses are authenticated against the Merkle root
+    /// of the memory, see [crate::merkle_memory]
+    pub merkle_memory: bool,
+    _xlen: PhantomData<X>,
 }
 
-impl<Fp: Field> Default for Env<Fp> {
+impl<Fp: Field, X: InstructionSet> Default for Env<Fp, X> {
     fn default() -> Self {
         Self {
             scratch_state_idx: 0,
@@ -29,11 +42,15 @@ impl<Fp: Field> Default for Env<Fp> {
             constraints: Vec::new(),
             lookups: Vec::new(),
             selector: None,
+            merkle_memory: false,
+            _xlen: PhantomData,
         }
     }
 }
 
-impl<Fp: Field> InterpreterEnv for Env<Fp> {
+impl<Fp: Field, X: InstructionSet> InterpreterEnv for Env<Fp, X> {
+    type Xlen = X;
+
     /// In the concrete implementation for the constraints, the interpreter will
     /// work over columns. The position in this case can be seen as a new
     /// variable/input of our circuit.
@@ -66,10 +83,10 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         }))
     }
 
-    fn activate_selector(&mut self, selector: Instruction) {
+    fn activate_selector(&mut self, selector: X::Instruction) {
         // Sanity check: we only want to activate once per instruction
         assert!(self.selector.is_none(), "A selector has been already activated. You might need to reset the environment if you want to start a new instruction.");
-        let n = usize::from(selector) - SCRATCH_SIZE - 1;
+        let n = selector.into() - X::SCRATCH_SIZE - 1;
         self.selector = Some(self.variable(Column::Selector(n)))
     }
 
@@ -159,7 +176,27 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         // No-op, witness only
     }
 
-    fn constant(x: u32) -> Self::Variable {
+    fn authenticate_memory_access(
+        &mut self,
+        addr: &Self::Variable,
+        old_value: &Self::Variable,
+        new_value: &Self::Variable,
+    ) {
+        if self.merkle_memory {
+            let instruction_counter = self.instruction_counter();
+            self.add_lookup(Lookup::write_one(
+                LookupTableIDs::MerkleMemoryLookup,
+                vec![
+                    instruction_counter,
+                    addr.clone(),
+                    old_value.clone(),
+                    new_value.clone(),
+                ],
+            ));
+        }
+    }
+
+    fn constant(x: u64) -> Self::Variable {
         Self::Variable::constant(Operations::from(Literal(Fp::from(x))))
     }
 
@@ -420,32 +457,135 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         res
     }
 
+    unsafe fn random_byte(
+        &mut self,
+        _if_is_true: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        self.variable(position)
+    }
+
+    unsafe fn executed_instructions(
+        &mut self,
+        _upper_half: bool,
+        position: Self::Position,
+    ) -> Self::Variable {
+        self.variable(position)
+    }
+
     fn set_halted(&mut self, _flag: Self::Variable) {
         // TODO
     }
 
     fn report_exit(&mut self, _exit_code: &Self::Variable) {}
 
+    fn request_preimage_write(
+        &mut self,
+        _addr: &Self::Variable,
+        _len: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable {
+        // FIXME: the bytes read are not constrained to be the preimage
+        self.variable(pos)
+    }
+
+    fn request_hint_write(&mut self, _addr: &Self::Variable, _len: &Self::Variable) {
+        // No-op, witness only
+    }
+
+    fn request_stdin_write(
+        &mut self,
+        _addr: &Self::Variable,
+        _len: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable {
+        self.variable(pos)
+    }
+
+    fn request_output_write(
+        &mut self,
+        _fd: &Self::Variable,
+        _addr: &Self::Variable,
+        _len: &Self::Variable,
+    ) {
+        // No-op, witness only
+    }
+
+    fn request_advice_write(
+        &mut self,
+        _addr: &Self::Variable,
+        _len: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable {
+        self.variable(pos)
+    }
+
+    unsafe fn request_keccak(
+        &mut self,
+        _addr: &Self::Variable,
+        _len: &Self::Variable,
+        _output: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable {
+        self.variable(pos)
+    }
+
+    unsafe fn keccak_hint(&mut self, _hint: KeccakHint, pos: Self::Position) -> Self::Variable {
+        self.variable(pos)
+    }
+
+    unsafe fn request_poseidon(
+        &mut self,
+        _addr: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable {
+        self.variable(pos)
+    }
+
+    unsafe fn poseidon_hint(&mut self, _hint: PoseidonHint, pos: Self::Position) -> Self::Variable {
+        self.variable(pos)
+    }
+
+    unsafe fn request_precompile(
+        &mut self,
+        _syscall: &Self::Variable,
+        _input: &Self::Variable,
+        _output: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable {
+        self.variable(pos)
+    }
+
     fn reset(&mut self) {
         self.scratch_state_idx = 0;
+        self.scratch_state_idx_inverse = 0;
         self.constraints.clear();
         self.lookups.clear();
         self.selector = None;
     }
 }
 
-impl<Fp: Field> Env<Fp> {
+impl<Fp: Field, X: InstructionSet> Env<Fp, X> {
+    /// Return an environment where the memory accesses are authenticated
+    /// against the Merkle root of the memory.
+    pub fn with_merkle_memory() -> Self {
+        Self {
+            merkle_memory: true,
+            ..Self::default()
+        }
+    }
+
     /// Return the constraints for the selector.
     /// Each selector must be a boolean.
     pub fn get_selector_constraints(&self) -> Vec<E<Fp>> {
         let one = <Self as InterpreterEnv>::Variable::one();
-        let mut enforce_bool: Vec<E<Fp>> = (0..INSTRUCTION_SET_SIZE)
+        let mut enforce_bool: Vec<E<Fp>> = (0..X::INSTRUCTION_SET_SIZE)
             .map(|i| {
                 let var = self.variable(Column::Selector(i));
                 (var.clone() - one.clone()) * var.clone()
             })
             .collect();
-        let enforce_one_activation = (0..INSTRUCTION_SET_SIZE).fold(E::<Fp>::one(), |res, i| {
+        let enforce_one_activation = (0..X::INSTRUCTION_SET_SIZE).fold(E::<Fp>::one(), |res, i| {
             let var = self.variable(Column::Selector(i));
             res - var.clone()
         });
@@ -468,4 +608,35 @@ impl<Fp: Field> Env<Fp> {
     pub fn get_lookups(&self) -> Vec<Lookup<E<Fp>>> {
         self.lookups.clone()
     }
+
+    /// Returns the coverage of the circuit, built with the configuration of
+    /// the environment, by the execution recorded in `execution`, see
+    /// [super::witness::Env::enable_coverage] and
+    /// [crate::interpreters::coverage] for the content of the report.
+    pub fn coverage(
+        &mut self,
+        execution: &ExecutionCoverage<X::Instruction>,
+    ) -> CoverageReport<X::Instruction> {
+        self.reset();
+        execution.report(
+            X::Instruction::iter().flat_map(|instr_typ| instr_typ.into_iter()),
+            X::SCRATCH_SIZE,
+            X::SCRATCH_SIZE_INVERSE,
+            |instruction| {
+                X::interpret_instruction(self, instruction);
+                let constraints =
+                    InstructionConstraints::new(&self.constraints, &self.lookups, |column| {
+                        match column {
+                            Column::ScratchState(i) => Some(ScratchColumn::Scratch(*i)),
+                            Column::ScratchStateInverse(i) => {
+                                Some(ScratchColumn::ScratchInverse(*i))
+                            }
+                            _ => None,
+                        }
+                    });
+                self.reset();
+                constraints
+            },
+        )
+    }
 }
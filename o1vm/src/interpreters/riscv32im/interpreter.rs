@@ -1,5 +1,8 @@
 //! This module implement an interpreter for the RISCV32 IM instruction set
-//! architecture.
+//! architecture, with the instructions of the C extension encoded on 16 bits
+//! (see [CInstruction]), the atomic instructions of the A extension (see
+//! [AInstruction]) and the CSR instructions of the Zicsr extension (see
+//! [CSRInstruction]).
 //!
 //! The implementation mostly follows (and copy) code from the MIPS interpreter
 //! available [here](../mips/interpreter.rs).
@@ -28,12 +31,84 @@
 //! most significant bit being the sign - 1 for negative, 0 for positive).
 //! By default, unsigned operations are performed.
 
-use super::registers::{REGISTER_CURRENT_IP, REGISTER_HEAP_POINTER, REGISTER_NEXT_IP};
-use crate::lookups::{Lookup, LookupTableIDs};
+use super::{
+    registers::{
+        CSR_CYCLE, REGISTER_CSR_OFFSET, REGISTER_CURRENT_IP, REGISTER_HEAP_POINTER,
+        REGISTER_NEXT_IP, REGISTER_PREIMAGE_KEY_END, REGISTER_PREIMAGE_OFFSET,
+        REGISTER_PROGRAM_BREAK,
+    },
+    witness::MAX_ACC,
+    INSTRUCTION_SET_SIZE, PAGE_ADDRESS_SIZE, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE,
+};
+use crate::{
+    interpreters::{
+        keccak::precompile::{KeccakHint, KECCAK_PRECOMPILE_CHUNK_LEN, SYSCALL_KECCAK256},
+        poseidon::{
+            precompile::{PoseidonHint, SYSCALL_POSEIDON},
+            STATE_SIZE as POSEIDON_STATE_SIZE,
+        },
+        xlen::{Rv32, Xlen},
+    },
+    lookups::{Lookup, LookupTableIDs},
+};
 use ark_ff::{One, Zero};
 use strum::{EnumCount, IntoEnumIterator};
 use strum_macros::{EnumCount, EnumIter};
 
+// Numbers of the Linux system calls of RISC-V, passed in register a7
+pub const SYSCALL_READ: u32 = 63;
+pub const SYSCALL_WRITE: u32 = 64;
+pub const SYSCALL_EXIT: u32 = 93;
+pub const SYSCALL_EXIT_GROUP: u32 = 94;
+pub const SYSCALL_BRK: u32 = 214;
+pub const SYSCALL_MMAP: u32 = 222;
+pub const SYSCALL_GETRANDOM: u32 = 278;
+pub const SYSCALL_CLOCK_GETTIME64: u32 = 403;
+
+/// The flag of `mmap` requesting a mapping that is not backed by a file
+pub const MAP_ANONYMOUS: u32 = 0x20;
+
+/// The maximal number of bytes written by `getrandom`, which may return less
+/// bytes than requested
+pub const GETRANDOM_MAX_LENGTH: u32 = 4;
+
+// File descriptors of the standard channels, forwarded to the host
+pub const FD_STDIN: u32 = 0;
+pub const FD_STDOUT: u32 = 1;
+pub const FD_STDERR: u32 = 2;
+
+// File descriptors of the channels to the preimage oracle, following Cannon
+pub const FD_HINT_READ: u32 = 3;
+pub const FD_HINT_WRITE: u32 = 4;
+pub const FD_PREIMAGE_READ: u32 = 5;
+pub const FD_PREIMAGE_WRITE: u32 = 6;
+/// The file descriptor of the advice given by the prover to the program. The
+/// advice is not constrained, and the program must check it.
+pub const FD_ADVICE_READ: u32 = 7;
+
+// The registers of the arguments and of the result of the system calls
+const REGISTER_A0: u64 = 10;
+const REGISTER_A1: u64 = 11;
+const REGISTER_A2: u64 = 12;
+// The register of the number of the system calls
+const REGISTER_A7: u64 = 17;
+
+/// The system calls implemented by the interpreter, which cannot be
+/// implemented by a custom precompile, see
+/// [crate::interpreters::precompile]
+pub const BUILTIN_SYSCALLS: [u32; 10] = [
+    SYSCALL_READ,
+    SYSCALL_WRITE,
+    SYSCALL_EXIT,
+    SYSCALL_EXIT_GROUP,
+    SYSCALL_BRK,
+    SYSCALL_MMAP,
+    SYSCALL_GETRANDOM,
+    SYSCALL_CLOCK_GETTIME64,
+    SYSCALL_KECCAK256,
+    SYSCALL_POSEIDON,
+];
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, EnumCount, EnumIter, Hash, Ord, PartialOrd)]
 pub enum Instruction {
     RType(RInstruction),
@@ -44,6 +119,9 @@ pub enum Instruction {
     UJType(UJInstruction),
     SyscallType(SyscallInstruction),
     MType(MInstruction),
+    CType(CInstruction),
+    AType(AInstruction),
+    CSRType(CSRInstruction),
 }
 
 // See
@@ -308,7 +386,68 @@ pub enum UJInstruction {
 )]
 pub enum SyscallInstruction {
     #[default]
+    /// A system call that is not supported, which halts the VM
     SyscallSuccess,
+    /// `exit` and `exit_group`: halts the VM with the exit code in a0
+    SyscallExitGroup,
+    /// `brk`: sets the program break to the address in a0, unless it is 0, and
+    /// returns the program break
+    SyscallBrk,
+    /// `mmap`: returns the address in a0 if it is not 0, and allocates
+    /// otherwise the number of bytes in a1, rounded up to a number of pages,
+    /// at the end of the heap. Only anonymous mappings are supported.
+    SyscallMmap,
+    /// `clock_gettime64`: writes the time to the 64-bit `timespec` at the
+    /// address in a1. The clock is deterministic, and always reads the epoch.
+    SyscallClockGettime,
+    /// `getrandom`: writes at most [GETRANDOM_MAX_LENGTH] bytes of the random
+    /// stream given by the host at the address in a0, up to the number of
+    /// bytes in a1, and returns the number of bytes written
+    SyscallGetRandom,
+    /// `read` on [FD_STDIN]: writes at most 4 bytes of the standard input
+    /// given by the host at the address in a1, up to the number of bytes in a2,
+    /// and returns the number of bytes written, which is 0 at the end of the
+    /// input
+    SyscallReadStdin,
+    /// `write` on [FD_STDOUT] or [FD_STDERR]: sends the number of bytes in a2
+    /// at the address in a1 to the host, and returns it
+    SyscallWriteOutput,
+    /// `read` on [FD_HINT_READ]: the hint responses are unused, and the
+    /// number of bytes in a2 is returned as if they were read
+    SyscallReadHint,
+    /// `read` on [FD_PREIMAGE_READ]: writes at most 4 bytes of the preimage
+    /// whose key was written before at the address in a1, up to the number of
+    /// bytes in a2, and returns the number of bytes written. The preimage is
+    /// prefixed by its length, as a big-endian 64-bit integer.
+    SyscallReadPreimage,
+    /// `read` on [FD_ADVICE_READ]: writes at most 4 bytes of the advice given
+    /// by the prover at the address in a1, up to the number of bytes in a2,
+    /// and returns the number of bytes written, which is 0 when the advice is
+    /// exhausted
+    SyscallReadAdvice,
+    /// `write` on [FD_HINT_WRITE]: sends the number of bytes in a2 at the
+    /// address in a1 to the preimage oracle, as hints prefixed by their
+    /// length, as a big-endian 32-bit integer
+    SyscallWriteHint,
+    /// `write` on [FD_PREIMAGE_WRITE]: writes at most 4 bytes at the address
+    /// in a1 to the key of the preimage to read, where the number of bytes in
+    /// a2 is the number of bytes of the key left to write, and returns the
+    /// number of bytes written
+    SyscallWritePreimage,
+    /// `keccak256`: absorbs at most 4 bytes of the input of the Keccak
+    /// precompile at the address in a0, up to the number of bytes in a1, which
+    /// are updated in place, and is executed again until the input is
+    /// absorbed. The last execution writes the digest at the address in a2.
+    /// See [crate::interpreters::keccak::precompile].
+    SyscallKeccak256,
+    /// `poseidon`: replaces the state of the Poseidon precompile at the
+    /// address in a0 by its permutation.
+    /// See [crate::interpreters::poseidon::precompile].
+    SyscallPoseidon,
+    /// A custom precompile registered by the user, called with the address
+    /// of its input in a0 and the address of its output in a1.
+    /// See [crate::interpreters::precompile].
+    SyscallPrecompile,
 }
 
 /// M extension instructions
@@ -319,55 +458,365 @@ pub enum SyscallInstruction {
 pub enum MInstruction {
     /// Format: `mul rd, rs1, rs2`
     ///
-    /// Description: performs an 32-bit 32-bit multiplication of signed rs1
-    /// by signed rs2 and places the lower 32 bits in the destination register.
+    /// Description: performs an XLEN-bit by XLEN-bit multiplication of signed rs1
+    /// by signed rs2 and places the lower XLEN bits in the destination register.
     /// Implementation: `x[rd] = x[rs1] * x[rs2]`
     #[default]
     Mul, // mul
     /// Format: `mulh rd, rs1, rs2`
     ///
-    /// Description: performs an 32-bit 32-bit multiplication of signed rs1 by
-    /// signed rs2 and places the upper 32 bits in the destination register.
-    /// Implementation: `x[rd] = (x[rs1] * x[rs2]) >> 32`
+    /// Description: performs an XLEN-bit by XLEN-bit multiplication of signed rs1 by
+    /// signed rs2 and places the upper XLEN bits in the destination register.
+    /// Implementation: `x[rd] = (x[rs1] * x[rs2]) >> XLEN`
     Mulh, // mulh
     /// Format: `mulhsu rd, rs1, rs2`
     ///
-    /// Description: performs an 32-bit 32-bit multiplication of signed rs1 by
-    /// unsigned rs2 and places the upper 32 bits in the destination register.
-    /// Implementation: `x[rd] = (x[rs1] * x[rs2]) >> 32`
+    /// Description: performs an XLEN-bit by XLEN-bit multiplication of signed rs1 by
+    /// unsigned rs2 and places the upper XLEN bits in the destination register.
+    /// Implementation: `x[rd] = (x[rs1] * x[rs2]) >> XLEN`
     Mulhsu, // mulhsu
     /// Format: `mulhu rd, rs1, rs2`
     ///
-    /// Description: performs an 32-bit 32-bit multiplication of unsigned rs1 by
-    /// unsigned rs2 and places the upper 32 bits in the destination register.
-    /// Implementation: `x[rd] = (x[rs1] * x[rs2]) >> 32`
+    /// Description: performs an XLEN-bit by XLEN-bit multiplication of unsigned rs1 by
+    /// unsigned rs2 and places the upper XLEN bits in the destination register.
+    /// Implementation: `x[rd] = (x[rs1] * x[rs2]) >> XLEN`
     Mulhu, // mulhu
     /// Format: `div rd, rs1, rs2`
     ///
-    /// Description: perform an 32 bits by 32 bits signed integer division of
+    /// Description: perform an XLEN bits by XLEN bits signed integer division of
     /// rs1 by rs2, rounding towards zero
     /// Implementation: `x[rd] = x[rs1] /s x[rs2]`
     Div, // div
     /// Format: `divu rd, rs1, rs2`
     ///
-    /// Description: performs an 32 bits by 32 bits unsigned integer division of
+    /// Description: performs an XLEN bits by XLEN bits unsigned integer division of
     /// rs1 by rs2, rounding towards zero.
     /// Implementation: `x[rd] = x[rs1] /u x[rs2]`
     Divu, // divu
     /// Format: `rem rd, rs1, rs2`
     ///
-    /// Description: performs an 32 bits by 32 bits signed integer reminder of
+    /// Description: performs an XLEN bits by XLEN bits signed integer reminder of
     /// rs1 by rs2.
     /// Implementation: `x[rd] = x[rs1] %s x[rs2]`
     Rem, // rem
     /// Format: `remu rd, rs1, rs2`
     ///
-    /// Description: performs an 32 bits by 32 bits unsigned integer reminder of
+    /// Description: performs an XLEN bits by XLEN bits unsigned integer reminder of
     /// rs1 by rs2.
     /// Implementation: `x[rd] = x[rs1] %u x[rs2]`
     Remu, // remu
 }
 
+/// Instructions of the C standard extension, encoded on 16 bits.
+/// Each compressed instruction expands to a single instruction of the base
+/// instruction set, given in the description of the variants, and increases
+/// the instruction pointer by 2 instead of 4.
+/// The floating point instructions, and `c.ebreak`, are not supported.
+///
+/// The registers `rd'`, `rs1'` and `rs2'` are encoded on 3 bits, and refer to
+/// the registers `x8` to `x15`.
+///
+/// See the chapter "C" Standard Extension for Compressed Instructions of the
+/// [specification](https://riscv.org/wp-content/uploads/2019/12/riscv-spec-20191213.pdf).
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, EnumCount, EnumIter, Default, Hash, Ord, PartialOrd,
+)]
+pub enum CInstruction {
+    #[default]
+    /// Format: `c.addi4spn rd', nzuimm`
+    ///
+    /// Description: Adds a zero-extended non-zero immediate, scaled by 4, to
+    /// the stack pointer and writes the result to rd'.
+    /// Expands to `addi rd', x2, nzuimm`.
+    AddImmediate4StackPointer, // c.addi4spn
+    /// Format: `c.lw rd', uimm(rs1')`
+    ///
+    /// Description: Loads a 32-bit value from memory into register rd'.
+    /// Expands to `lw rd', uimm(rs1')`.
+    LoadWord, // c.lw
+    /// Format: `c.sw rs2', uimm(rs1')`
+    ///
+    /// Description: Stores the 32-bit value in register rs2' to memory.
+    /// Expands to `sw rs2', uimm(rs1')`.
+    StoreWord, // c.sw
+    /// Format: `c.addi rd, imm`
+    ///
+    /// Description: Adds the sign-extended 6-bit immediate to the value in
+    /// register rd and writes the result to rd. `c.nop` is encoded as
+    /// `c.addi x0, 0`.
+    /// Expands to `addi rd, rd, imm`.
+    AddImmediate, // c.addi
+    /// Format: `c.jal offset`
+    ///
+    /// Description: Jumps to the instruction pointer plus the sign-extended
+    /// offset, and writes the address of the following instruction to x1.
+    /// Expands to `jal x1, offset`.
+    JumpAndLink, // c.jal
+    /// Format: `c.li rd, imm`
+    ///
+    /// Description: Loads the sign-extended 6-bit immediate into register rd.
+    /// Expands to `addi rd, x0, imm`.
+    LoadImmediate, // c.li
+    /// Format: `c.addi16sp nzimm`
+    ///
+    /// Description: Adds a sign-extended non-zero immediate, scaled by 16, to
+    /// the stack pointer.
+    /// Expands to `addi x2, x2, nzimm`.
+    AddImmediate16StackPointer, // c.addi16sp
+    /// Format: `c.lui rd, nzimm`
+    ///
+    /// Description: Loads the sign-extended non-zero 6-bit immediate into bits
+    /// 17-12 of register rd, clears the bottom 12 bits, and sign-extends bit 17
+    /// into all higher bits.
+    /// Expands to `lui rd, nzimm`.
+    LoadUpperImmediate, // c.lui
+    /// Format: `c.srli rd', shamt`
+    ///
+    /// Description: Performs a logical right shift of the value in register rd'
+    /// and writes the result to rd'.
+    /// Expands to `srli rd', rd', shamt`.
+    ShiftRightLogicalImmediate, // c.srli
+    /// Format: `c.srai rd', shamt`
+    ///
+    /// Description: Performs an arithmetic right shift of the value in register
+    /// rd' and writes the result to rd'.
+    /// Expands to `srai rd', rd', shamt`.
+    ShiftRightArithmeticImmediate, // c.srai
+    /// Format: `c.andi rd', imm`
+    ///
+    /// Description: Computes the bitwise AND of the value in register rd' and
+    /// the sign-extended 6-bit immediate, and writes the result to rd'.
+    /// Expands to `andi rd', rd', imm`.
+    AndImmediate, // c.andi
+    /// Format: `c.sub rd', rs2'`
+    ///
+    /// Description: Subtracts the value in register rs2' from the value in
+    /// register rd', and writes the result to rd'.
+    /// Expands to `sub rd', rd', rs2'`.
+    Sub, // c.sub
+    /// Format: `c.xor rd', rs2'`
+    ///
+    /// Description: Computes the bitwise XOR of the values in registers rd' and
+    /// rs2', and writes the result to rd'.
+    /// Expands to `xor rd', rd', rs2'`.
+    Xor, // c.xor
+    /// Format: `c.or rd', rs2'`
+    ///
+    /// Description: Computes the bitwise OR of the values in registers rd' and
+    /// rs2', and writes the result to rd'.
+    /// Expands to `or rd', rd', rs2'`.
+    Or, // c.or
+    /// Format: `c.and rd', rs2'`
+    ///
+    /// Description: Computes the bitwise AND of the values in registers rd' and
+    /// rs2', and writes the result to rd'.
+    /// Expands to `and rd', rd', rs2'`.
+    And, // c.and
+    /// Format: `c.j offset`
+    ///
+    /// Description: Jumps to the instruction pointer plus the sign-extended
+    /// offset.
+    /// Expands to `jal x0, offset`.
+    Jump, // c.j
+    /// Format: `c.beqz rs1', offset`
+    ///
+    /// Description: Takes the branch if the value in register rs1' is zero.
+    /// Expands to `beq rs1', x0, offset`.
+    BranchEqZero, // c.beqz
+    /// Format: `c.bnez rs1', offset`
+    ///
+    /// Description: Takes the branch if the value in register rs1' is not zero.
+    /// Expands to `bne rs1', x0, offset`.
+    BranchNeqZero, // c.bnez
+    /// Format: `c.slli rd, shamt`
+    ///
+    /// Description: Performs a logical left shift of the value in register rd
+    /// and writes the result to rd.
+    /// Expands to `slli rd, rd, shamt`.
+    ShiftLeftLogicalImmediate, // c.slli
+    /// Format: `c.lwsp rd, uimm(x2)`
+    ///
+    /// Description: Loads a 32-bit value from memory at the stack pointer plus
+    /// a zero-extended offset, scaled by 4, into register rd.
+    /// Expands to `lw rd, uimm(x2)`.
+    LoadWordStackPointer, // c.lwsp
+    /// Format: `c.jr rs1`
+    ///
+    /// Description: Jumps to the address in register rs1.
+    /// Expands to `jalr x0, 0(rs1)`.
+    JumpRegister, // c.jr
+    /// Format: `c.mv rd, rs2`
+    ///
+    /// Description: Copies the value in register rs2 into register rd.
+    /// Expands to `add rd, x0, rs2`.
+    Move, // c.mv
+    /// Format: `c.jalr rs1`
+    ///
+    /// Description: Jumps to the address in register rs1, and writes the
+    /// address of the following instruction to x1.
+    /// Expands to `jalr x1, 0(rs1)`.
+    JumpAndLinkRegister, // c.jalr
+    /// Format: `c.add rd, rs2`
+    ///
+    /// Description: Adds the values in registers rd and rs2 and writes the
+    /// result to rd.
+    /// Expands to `add rd, rd, rs2`.
+    Add, // c.add
+    /// Format: `c.swsp rs2, uimm(x2)`
+    ///
+    /// Description: Stores the 32-bit value in register rs2 to memory at the
+    /// stack pointer plus a zero-extended offset, scaled by 4.
+    /// Expands to `sw rs2, uimm(x2)`.
+    StoreWordStackPointer, // c.swsp
+}
+
+/// Instructions of the A standard extension, i.e. the atomic instructions.
+/// The VM executes a single hart, so the instructions are executed with
+/// single-threaded semantics: the ordering bits `aq` and `rl` are ignored, and
+/// a store conditional always succeeds.
+///
+/// The atomic memory operations (AMO) load the word at the address in register
+/// rs1 into register rd, apply a binary operator to the loaded value and the
+/// value in register rs2, and store the result at the address in register rs1.
+///
+/// See the chapter "A" Standard Extension for Atomic Instructions of the
+/// [specification](https://riscv.org/wp-content/uploads/2019/12/riscv-spec-20191213.pdf).
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, EnumCount, EnumIter, Default, Hash, Ord, PartialOrd,
+)]
+pub enum AInstruction {
+    #[default]
+    /// Format: `lr.w rd, (rs1)`
+    ///
+    /// Description: Loads the word at the address in register rs1 into
+    /// register rd, and registers a reservation on the address.
+    /// Implementation: `x[rd] = M[x[rs1]][31:0]`
+    LoadReserved, // lr.w
+    /// Format: `sc.w rd, rs2, (rs1)`
+    ///
+    /// Description: Stores the word in register rs2 at the address in register
+    /// rs1 if a reservation exists on the address, and writes 0 to rd on
+    /// success. As there is a single hart, the store always succeeds.
+    /// Implementation: `M[x[rs1]] = x[rs2][31:0]; x[rd] = 0`
+    StoreConditional, // sc.w
+    /// Format: `amoswap.w rd, rs2, (rs1)`
+    ///
+    /// Description: Atomically swaps the word at the address in register rs1
+    /// with the value in register rs2.
+    /// Implementation: `x[rd] = M[x[rs1]]; M[x[rs1]] = x[rs2]`
+    AtomicSwap, // amoswap.w
+    /// Format: `amoadd.w rd, rs2, (rs1)`
+    ///
+    /// Description: Atomically adds the value in register rs2 to the word at
+    /// the address in register rs1.
+    /// Implementation: `x[rd] = M[x[rs1]]; M[x[rs1]] = x[rd] + x[rs2]`
+    AtomicAdd, // amoadd.w
+    /// Format: `amoxor.w rd, rs2, (rs1)`
+    ///
+    /// Description: Atomically applies a bitwise XOR of the value in register
+    /// rs2 to the word at the address in register rs1.
+    /// Implementation: `x[rd] = M[x[rs1]]; M[x[rs1]] = x[rd] ^ x[rs2]`
+    AtomicXor, // amoxor.w
+    /// Format: `amoand.w rd, rs2, (rs1)`
+    ///
+    /// Description: Atomically applies a bitwise AND of the value in register
+    /// rs2 to the word at the address in register rs1.
+    /// Implementation: `x[rd] = M[x[rs1]]; M[x[rs1]] = x[rd] & x[rs2]`
+    AtomicAnd, // amoand.w
+    /// Format: `amoor.w rd, rs2, (rs1)`
+    ///
+    /// Description: Atomically applies a bitwise OR of the value in register
+    /// rs2 to the word at the address in register rs1.
+    /// Implementation: `x[rd] = M[x[rs1]]; M[x[rs1]] = x[rd] | x[rs2]`
+    AtomicOr, // amoor.w
+    /// Format: `amomin.w rd, rs2, (rs1)`
+    ///
+    /// Description: Atomically stores the minimum of the value in register rs2
+    /// and the word at the address in register rs1, as signed integers.
+    /// Implementation: `x[rd] = M[x[rs1]]; M[x[rs1]] = min_s(x[rd], x[rs2])`
+    AtomicMin, // amomin.w
+    /// Format: `amomax.w rd, rs2, (rs1)`
+    ///
+    /// Description: Atomically stores the maximum of the value in register rs2
+    /// and the word at the address in register rs1, as signed integers.
+    /// Implementation: `x[rd] = M[x[rs1]]; M[x[rs1]] = max_s(x[rd], x[rs2])`
+    AtomicMax, // amomax.w
+    /// Format: `amominu.w rd, rs2, (rs1)`
+    ///
+    /// Description: Atomically stores the minimum of the value in register rs2
+    /// and the word at the address in register rs1, as unsigned integers.
+    /// Implementation: `x[rd] = M[x[rs1]]; M[x[rs1]] = min_u(x[rd], x[rs2])`
+    AtomicMinUnsigned, // amominu.w
+    /// Format: `amomaxu.w rd, rs2, (rs1)`
+    ///
+    /// Description: Atomically stores the maximum of the value in register rs2
+    /// and the word at the address in register rs1, as unsigned integers.
+    /// Implementation: `x[rd] = M[x[rs1]]; M[x[rs1]] = max_u(x[rd], x[rs2])`
+    AtomicMaxUnsigned, // amomaxu.w
+}
+
+/// Instructions of the Zicsr extension, accessing the control and status
+/// registers (CSR).
+/// The machine-level CSR listed in
+/// [CSR_ADDRESSES](super::registers::CSR_ADDRESSES) are stored with the
+/// registers. The counters `cycle`, `time` and `instret` are read-only, and are
+/// all given by the number of instructions executed; reading them is the
+/// instruction [CSRInstruction::ReadCounter].
+///
+/// See the chapter "Zicsr", Control and Status Register (CSR) Instructions of
+/// the [specification](https://riscv.org/wp-content/uploads/2019/12/riscv-spec-20191213.pdf).
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, EnumCount, EnumIter, Default, Hash, Ord, PartialOrd,
+)]
+pub enum CSRInstruction {
+    #[default]
+    /// Format: `csrrw rd, csr, rs1`
+    ///
+    /// Description: Atomically swaps the values in the CSR and in register
+    /// rs1.
+    /// Implementation: `t = CSRs[csr]; CSRs[csr] = x[rs1]; x[rd] = t`
+    ReadWrite, // csrrw
+    /// Format: `csrrs rd, csr, rs1`
+    ///
+    /// Description: Reads the CSR into register rd, and sets the bits of the
+    /// CSR that are set in register rs1.
+    /// Implementation: `t = CSRs[csr]; CSRs[csr] = t | x[rs1]; x[rd] = t`
+    ReadSet, // csrrs
+    /// Format: `csrrc rd, csr, rs1`
+    ///
+    /// Description: Reads the CSR into register rd, and clears the bits of the
+    /// CSR that are set in register rs1.
+    /// Implementation: `t = CSRs[csr]; CSRs[csr] = t & ~x[rs1]; x[rd] = t`
+    ReadClear, // csrrc
+    /// Format: `csrrwi rd, csr, uimm`
+    ///
+    /// Description: Reads the CSR into register rd, and writes the 5-bit
+    /// zero-extended immediate to the CSR.
+    /// Implementation: `x[rd] = CSRs[csr]; CSRs[csr] = zext(uimm)`
+    ReadWriteImmediate, // csrrwi
+    /// Format: `csrrsi rd, csr, uimm`
+    ///
+    /// Description: Reads the CSR into register rd, and sets the bits of the
+    /// CSR that are set in the 5-bit zero-extended immediate.
+    /// Implementation: `t = CSRs[csr]; CSRs[csr] = t | zext(uimm); x[rd] = t`
+    ReadSetImmediate, // csrrsi
+    /// Format: `csrrci rd, csr, uimm`
+    ///
+    /// Description: Reads the CSR into register rd, and clears the bits of the
+    /// CSR that are set in the 5-bit zero-extended immediate.
+    /// Implementation: `t = CSRs[csr]; CSRs[csr] = t & ~zext(uimm); x[rd] = t`
+    ReadClearImmediate, // csrrci
+    /// Format: `rdcycle rd`, `rdtime rd`, `rdinstret rd`, and `rdcycleh rd`,
+    /// `rdtimeh rd`, `rdinstreth rd` for the upper halves
+    ///
+    /// Description: Reads one of the counters `cycle`, `time` and `instret`.
+    /// They are encoded as `csrrs rd, csr, x0`, or as any of `csrrc`, `csrrsi`
+    /// and `csrrci` leaving the CSR unchanged.
+    /// Implementation: `x[rd] = instret[31:0]`, or `x[rd] = instret[63:32]`
+    /// for the upper halves
+    ReadCounter, // rdcycle, rdtime, rdinstret
+}
+
 impl IntoIterator for Instruction {
     type Item = Instruction;
     type IntoIter = std::vec::IntoIter<Instruction>;
@@ -430,6 +879,27 @@ impl IntoIterator for Instruction {
                 }
                 iter_contents.into_iter()
             }
+            Instruction::CType(_) => {
+                let mut iter_contents = Vec::with_capacity(CInstruction::COUNT);
+                for ctype in CInstruction::iter() {
+                    iter_contents.push(Instruction::CType(ctype));
+                }
+                iter_contents.into_iter()
+            }
+            Instruction::AType(_) => {
+                let mut iter_contents = Vec::with_capacity(AInstruction::COUNT);
+                for atype in AInstruction::iter() {
+                    iter_contents.push(Instruction::AType(atype));
+                }
+                iter_contents.into_iter()
+            }
+            Instruction::CSRType(_) => {
+                let mut iter_contents = Vec::with_capacity(CSRInstruction::COUNT);
+                for csrtype in CSRInstruction::iter() {
+                    iter_contents.push(Instruction::CSRType(csrtype));
+                }
+                iter_contents.into_iter()
+            }
         }
     }
 }
@@ -445,6 +915,9 @@ impl std::fmt::Display for Instruction {
             Instruction::UJType(ujtype) => write!(f, "{}", ujtype),
             Instruction::SyscallType(_syscall) => write!(f, "ecall"),
             Instruction::MType(mtype) => write!(f, "{}", mtype),
+            Instruction::CType(ctype) => write!(f, "{}", ctype),
+            Instruction::AType(atype) => write!(f, "{}", atype),
+            Instruction::CSRType(csrtype) => write!(f, "{}", csrtype),
         }
     }
 }
@@ -545,7 +1018,124 @@ impl std::fmt::Display for MInstruction {
     }
 }
 
+impl std::fmt::Display for CInstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CInstruction::AddImmediate4StackPointer => write!(f, "c.addi4spn"),
+            CInstruction::LoadWord => write!(f, "c.lw"),
+            CInstruction::StoreWord => write!(f, "c.sw"),
+            CInstruction::AddImmediate => write!(f, "c.addi"),
+            CInstruction::JumpAndLink => write!(f, "c.jal"),
+            CInstruction::LoadImmediate => write!(f, "c.li"),
+            CInstruction::AddImmediate16StackPointer => write!(f, "c.addi16sp"),
+            CInstruction::LoadUpperImmediate => write!(f, "c.lui"),
+            CInstruction::ShiftRightLogicalImmediate => write!(f, "c.srli"),
+            CInstruction::ShiftRightArithmeticImmediate => write!(f, "c.srai"),
+            CInstruction::AndImmediate => write!(f, "c.andi"),
+            CInstruction::Sub => write!(f, "c.sub"),
+            CInstruction::Xor => write!(f, "c.xor"),
+            CInstruction::Or => write!(f, "c.or"),
+            CInstruction::And => write!(f, "c.and"),
+            CInstruction::Jump => write!(f, "c.j"),
+            CInstruction::BranchEqZero => write!(f, "c.beqz"),
+            CInstruction::BranchNeqZero => write!(f, "c.bnez"),
+            CInstruction::ShiftLeftLogicalImmediate => write!(f, "c.slli"),
+            CInstruction::LoadWordStackPointer => write!(f, "c.lwsp"),
+            CInstruction::JumpRegister => write!(f, "c.jr"),
+            CInstruction::Move => write!(f, "c.mv"),
+            CInstruction::JumpAndLinkRegister => write!(f, "c.jalr"),
+            CInstruction::Add => write!(f, "c.add"),
+            CInstruction::StoreWordStackPointer => write!(f, "c.swsp"),
+        }
+    }
+}
+
+impl std::fmt::Display for AInstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AInstruction::LoadReserved => write!(f, "lr.w"),
+            AInstruction::StoreConditional => write!(f, "sc.w"),
+            AInstruction::AtomicSwap => write!(f, "amoswap.w"),
+            AInstruction::AtomicAdd => write!(f, "amoadd.w"),
+            AInstruction::AtomicXor => write!(f, "amoxor.w"),
+            AInstruction::AtomicAnd => write!(f, "amoand.w"),
+            AInstruction::AtomicOr => write!(f, "amoor.w"),
+            AInstruction::AtomicMin => write!(f, "amomin.w"),
+            AInstruction::AtomicMax => write!(f, "amomax.w"),
+            AInstruction::AtomicMinUnsigned => write!(f, "amominu.w"),
+            AInstruction::AtomicMaxUnsigned => write!(f, "amomaxu.w"),
+        }
+    }
+}
+
+impl std::fmt::Display for CSRInstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CSRInstruction::ReadWrite => write!(f, "csrrw"),
+            CSRInstruction::ReadSet => write!(f, "csrrs"),
+            CSRInstruction::ReadClear => write!(f, "csrrc"),
+            CSRInstruction::ReadWriteImmediate => write!(f, "csrrwi"),
+            CSRInstruction::ReadSetImmediate => write!(f, "csrrsi"),
+            CSRInstruction::ReadClearImmediate => write!(f, "csrrci"),
+            CSRInstruction::ReadCounter => write!(f, "rdcounter"),
+        }
+    }
+}
+
+/// The instruction set interpreted over registers of [Xlen] bits: the
+/// instructions of [Instruction] for [Rv32], and the ones of
+/// [crate::interpreters::riscv64im::interpreter::Instruction] for
+/// [Rv64](crate::interpreters::xlen::Rv64).
+///
+/// The instructions of the base instruction set and of the M extension are
+/// interpreted by the same functions for both widths (see [interpret_rtype]
+/// for instance), the width of the registers being given by
+/// [InterpreterEnv::Xlen].
+pub trait InstructionSet: Xlen + Sized {
+    /// The instructions, each one having a selector
+    type Instruction: Copy
+        + Eq
+        + Ord
+        + std::hash::Hash
+        + std::fmt::Debug
+        + std::fmt::Display
+        + IntoEnumIterator
+        + IntoIterator<Item = Self::Instruction>
+        + Into<usize>;
+
+    /// The minimal number of columns required for the instructions
+    const SCRATCH_SIZE: usize;
+
+    /// The number of columns holding inverses required for the instructions
+    const SCRATCH_SIZE_INVERSE: usize;
+
+    /// The number of instructions, i.e. of selectors
+    const INSTRUCTION_SET_SIZE: usize;
+
+    fn interpret_instruction<Env: InterpreterEnv<Xlen = Self>>(
+        env: &mut Env,
+        instr: Self::Instruction,
+    );
+}
+
+impl InstructionSet for Rv32 {
+    type Instruction = Instruction;
+
+    const SCRATCH_SIZE: usize = SCRATCH_SIZE;
+
+    const SCRATCH_SIZE_INVERSE: usize = SCRATCH_SIZE_INVERSE;
+
+    const INSTRUCTION_SET_SIZE: usize = INSTRUCTION_SET_SIZE;
+
+    fn interpret_instruction<Env: InterpreterEnv<Xlen = Self>>(env: &mut Env, instr: Instruction) {
+        interpret_instruction(env, instr)
+    }
+}
+
 pub trait InterpreterEnv {
+    /// The width of the registers, and the instructions interpreted
+    type Xlen: InstructionSet;
+
     /// A position can be seen as an indexed variable
     type Position;
 
@@ -578,7 +1168,7 @@ pub trait InterpreterEnv {
     fn add_constraint(&mut self, assert_equals_zero: Self::Variable);
 
     /// Activate the selector for the given instruction.
-    fn activate_selector(&mut self, selector: Instruction);
+    fn activate_selector(&mut self, selector: <Self::Xlen as InstructionSet>::Instruction);
 
     /// Check that the witness value in `assert_equals_zero` is 0; otherwise abort.
     fn check_is_zero(assert_equals_zero: &Self::Variable);
@@ -823,6 +1413,16 @@ pub trait InterpreterEnv {
     /// manually add the lookups for this memory operation.
     unsafe fn push_memory_access(&mut self, addr: &Self::Variable, value: Self::Variable);
 
+    /// Authenticate the access to the memory address `addr`, changing its value from `old_value`
+    /// to `new_value`, against the Merkle root of the memory. This is a no-op when the memory is
+    /// not Merkleized, see [crate::merkle_memory].
+    fn authenticate_memory_access(
+        &mut self,
+        addr: &Self::Variable,
+        old_value: &Self::Variable,
+        new_value: &Self::Variable,
+    );
+
     /// Access the memory address `addr`, adding constraints asserting that the old value was
     /// `old_value` and that the new value will be `new_value`.
     ///
@@ -858,6 +1458,7 @@ pub trait InterpreterEnv {
             vec![addr.clone(), new_accessed, new_value.clone()],
         ));
         self.range_check64(&elapsed_time);
+        self.authenticate_memory_access(addr, old_value, new_value);
 
         // Update instruction counter after accessing a memory address.
         self.increase_instruction_counter();
@@ -896,14 +1497,19 @@ pub trait InterpreterEnv {
     }
 
     /// Range checks with 2 lookups to the RangeCheck16Lookup table that a value
-    /// is at most 2^`bits`-1  (bits <= 16).
+    /// is at most 2^`bits`-1  (bits <= 16), or with 1 lookup if `bits` is 16.
     fn range_check16(&mut self, value: &Self::Variable, bits: u32) {
         assert!(bits <= 16);
         // 0 <= value < 2^bits
         // First, check lowerbound: 0 <= value < 2^16
         self.lookup_16bits(value);
-        // Second, check upperbound: value + 2^16 - 2^bits < 2^16
-        self.lookup_16bits(&(value.clone() + Self::constant(1 << 16) - Self::constant(1 << bits)));
+        // Second, check upperbound: value + 2^16 - 2^bits < 2^16, which is
+        // the same lookup as the first one for a full-width check
+        if bits < 16 {
+            self.lookup_16bits(
+                &(value.clone() + Self::constant(1 << 16) - Self::constant(1 << bits)),
+            );
+        }
     }
 
     /// Adds a lookup to the ByteLookup table
@@ -915,14 +1521,19 @@ pub trait InterpreterEnv {
     }
 
     /// Range checks with 2 lookups to the ByteLookup table that a value
-    /// is at most 2^`bits`-1  (bits <= 8).
+    /// is at most 2^`bits`-1  (bits <= 8), or with 1 lookup if `bits` is 8.
     fn range_check8(&mut self, value: &Self::Variable, bits: u32) {
         assert!(bits <= 8);
         // 0 <= value < 2^bits
         // First, check lowerbound: 0 <= value < 2^8
         self.lookup_8bits(value);
-        // Second, check upperbound: value + 2^8 - 2^bits < 2^8
-        self.lookup_8bits(&(value.clone() + Self::constant(1 << 8) - Self::constant(1 << bits)));
+        // Second, check upperbound: value + 2^8 - 2^bits < 2^8, which is
+        // the same lookup as the first one for a full-width check
+        if bits < 8 {
+            self.lookup_8bits(
+                &(value.clone() + Self::constant(1 << 8) - Self::constant(1 << bits)),
+            );
+        }
     }
 
     /// Adds a lookup to the AtMost4Lookup table
@@ -938,7 +1549,7 @@ pub trait InterpreterEnv {
     }
 
     fn set_instruction_pointer(&mut self, ip: Self::Variable) {
-        let idx = Self::constant(REGISTER_CURRENT_IP as u32);
+        let idx = Self::constant(REGISTER_CURRENT_IP as u64);
         let new_accessed = self.instruction_counter() + Self::constant(1);
         unsafe {
             self.push_register_access(&idx, new_accessed.clone());
@@ -953,7 +1564,7 @@ pub trait InterpreterEnv {
     }
 
     fn get_instruction_pointer(&mut self) -> Self::Variable {
-        let idx = Self::constant(REGISTER_CURRENT_IP as u32);
+        let idx = Self::constant(REGISTER_CURRENT_IP as u64);
         let ip = {
             let value_location = self.alloc_scratch();
             unsafe { self.fetch_register(&idx, value_location) }
@@ -966,7 +1577,7 @@ pub trait InterpreterEnv {
     }
 
     fn set_next_instruction_pointer(&mut self, ip: Self::Variable) {
-        let idx = Self::constant(REGISTER_NEXT_IP as u32);
+        let idx = Self::constant(REGISTER_NEXT_IP as u64);
         let new_accessed = self.instruction_counter() + Self::constant(1);
         unsafe {
             self.push_register_access(&idx, new_accessed.clone());
@@ -981,7 +1592,7 @@ pub trait InterpreterEnv {
     }
 
     fn get_next_instruction_pointer(&mut self) -> Self::Variable {
-        let idx = Self::constant(REGISTER_NEXT_IP as u32);
+        let idx = Self::constant(REGISTER_NEXT_IP as u64);
         let ip = {
             let value_location = self.alloc_scratch();
             unsafe { self.fetch_register(&idx, value_location) }
@@ -993,7 +1604,7 @@ pub trait InterpreterEnv {
         ip
     }
 
-    fn constant(x: u32) -> Self::Variable;
+    fn constant(x: u64) -> Self::Variable;
 
     /// Extract the bits from the variable `x` between `highest_bit` and `lowest_bit`, and store
     /// the result in `position`.
@@ -1005,7 +1616,7 @@ pub trait InterpreterEnv {
     /// the source variable `x` and that the returned value fits in `highest_bit - lowest_bit`
     /// bits.
     ///
-    /// Do not call this function with highest_bit - lowest_bit >= 32.
+    /// Do not call this function with highest_bit greater than XLEN.
     // TODO: embed the range check in the function when highest_bit - lowest_bit <= 16?
     unsafe fn bitmask(
         &mut self,
@@ -1189,7 +1800,7 @@ pub trait InterpreterEnv {
         position: Self::Position,
     ) -> Self::Variable;
 
-    /// Returns `((x * y) >> 32`, storing the results in `position`.
+    /// Returns `((x * y) >> XLEN`, storing the results in `position`.
     ///
     /// # Safety
     ///
@@ -1217,7 +1828,7 @@ pub trait InterpreterEnv {
         position: Self::Position,
     ) -> Self::Variable;
 
-    /// Returns `((x * y) >> 32`, storing the results in `position`.
+    /// Returns `((x * y) >> XLEN`, storing the results in `position`.
     ///
     /// # Safety
     ///
@@ -1245,7 +1856,7 @@ pub trait InterpreterEnv {
         position: Self::Position,
     ) -> Self::Variable;
 
-    /// Returns `((x * y) >> 32`, storing the results in `position`.
+    /// Returns `((x * y) >> XLEN`, storing the results in `position`.
     ///
     /// # Safety
     ///
@@ -1267,9 +1878,8 @@ pub trait InterpreterEnv {
     /// ensure that the pair of returned values correspond to the given values `x` and `y`, and
     /// that they fall within the desired range.
     ///
-    /// Division by zero will create a panic! exception. The RISC-V
-    /// specification leaves the case unspecified, and therefore we prefer to
-    /// forbid this case while building the witness.
+    /// As specified by the RV32M extension, the division by zero returns -1,
+    /// and the division of `-2^31` by -1 returns `-2^31`.
     unsafe fn div_signed(
         &mut self,
         x: &Self::Variable,
@@ -1284,6 +1894,9 @@ pub trait InterpreterEnv {
     /// There are no constraints on the returned values; callers must manually add constraints to
     /// ensure that the pair of returned values correspond to the given values `x` and `y`, and
     /// that they fall within the desired range.
+    ///
+    /// As specified by the RV32M extension, the remainder of the division by
+    /// zero is `x`, and the remainder of the division of `-2^31` by -1 is 0.
     unsafe fn mod_signed(
         &mut self,
         x: &Self::Variable,
@@ -1299,9 +1912,8 @@ pub trait InterpreterEnv {
     /// ensure that the pair of returned values correspond to the given values `x` and `y`, and
     /// that they fall within the desired range.
     ///
-    /// Division by zero will create a panic! exception. The RISC-V
-    /// specification leaves the case unspecified, and therefore we prefer to
-    /// forbid this case while building the witness.
+    /// As specified by the RV32M extension, the division by zero returns
+    /// `2^32 - 1`.
     unsafe fn div(
         &mut self,
         x: &Self::Variable,
@@ -1316,6 +1928,9 @@ pub trait InterpreterEnv {
     /// There are no constraints on the returned values; callers must manually add constraints to
     /// ensure that the pair of returned values correspond to the given values `x` and `y`, and
     /// that they fall within the desired range.
+    ///
+    /// As specified by the RV32M extension, the remainder of the division by
+    /// zero is `x`.
     unsafe fn mod_unsigned(
         &mut self,
         x: &Self::Variable,
@@ -1349,6 +1964,33 @@ pub trait InterpreterEnv {
 
     fn copy(&mut self, x: &Self::Variable, position: Self::Position) -> Self::Variable;
 
+    /// Returns the next byte of the random stream given by the host if `if_is_true` is `1`, or
+    /// 0 otherwise, storing the result in `position`.
+    ///
+    /// # Safety
+    ///
+    /// There are no constraints on the returned value; callers must manually add constraints to
+    /// ensure that it is correctly constructed.
+    unsafe fn random_byte(
+        &mut self,
+        if_is_true: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable;
+
+    /// Returns the lower 32 bits of the number of instructions executed before
+    /// the current one, or its upper 32 bits if `upper_half` is set, storing
+    /// the result in `position`.
+    ///
+    /// # Safety
+    ///
+    /// There are no constraints on the returned value; callers must manually add constraints to
+    /// ensure that it is correctly constructed.
+    unsafe fn executed_instructions(
+        &mut self,
+        upper_half: bool,
+        position: Self::Position,
+    ) -> Self::Variable;
+
     /// Increases the heap pointer by `by_amount` if `if_is_true` is `1`, and returns the previous
     /// value of the heap pointer.
     fn increase_heap_pointer(
@@ -1356,7 +1998,7 @@ pub trait InterpreterEnv {
         by_amount: &Self::Variable,
         if_is_true: &Self::Variable,
     ) -> Self::Variable {
-        let idx = Self::constant(REGISTER_HEAP_POINTER as u32);
+        let idx = Self::constant(REGISTER_HEAP_POINTER as u64);
         let old_ptr = {
             let value_location = self.alloc_scratch();
             unsafe { self.fetch_register(&idx, value_location) }
@@ -1376,27 +2018,137 @@ pub trait InterpreterEnv {
     /// Given a variable `x`, this function extends it to a signed integer of
     /// `bitlength` bits.
     fn sign_extend(&mut self, x: &Self::Variable, bitlength: u32) -> Self::Variable {
-        assert!(bitlength <= 32);
+        assert!(bitlength <= Self::Xlen::BITS);
         // FIXME: Constrain `high_bit`
         let high_bit = {
             let pos = self.alloc_scratch();
             unsafe { self.bitmask(x, bitlength, bitlength - 1, pos) }
         };
-        // Casting in u64 for special case of bitlength = 0 to avoid overflow.
+        // The bits from `bitlength` to XLEN, none for bitlength = XLEN.
         // No condition for constant time execution.
-        // Decomposing the steps for readability.
-        let v: u64 = (1u64 << (32 - bitlength)) - 1;
-        let v: u64 = v << bitlength;
-        let v: u32 = v as u32;
+        let v: u64 = Self::Xlen::MASK & u64::MAX.checked_shl(bitlength).unwrap_or(0);
         high_bit * Self::constant(v) + x.clone()
     }
 
     fn report_exit(&mut self, exit_code: &Self::Variable);
 
+    /// Request the preimage oracle for `len` bytes and store the bytes starting
+    /// from `addr`, and it returns the number of bytes actually read.
+    /// The number of bytes actually read will be set into `pos`.
+    /// The first 8 bytes will be the length of the preimage, encoded as an
+    /// unsigned 64bits, and the rest will be the preimage.
+    fn request_preimage_write(
+        &mut self,
+        addr: &Self::Variable,
+        len: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable;
+
+    /// Send the `len` bytes starting from `addr` to the preimage oracle as
+    /// hints.
+    fn request_hint_write(&mut self, addr: &Self::Variable, len: &Self::Variable);
+
+    /// Request at most `len` bytes of the standard input, store them starting
+    /// from `addr`, and return the number of bytes actually read, which is 0
+    /// at the end of the input.
+    /// The number of bytes actually read will be set into `pos`.
+    fn request_stdin_write(
+        &mut self,
+        addr: &Self::Variable,
+        len: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable;
+
+    /// Send the `len` bytes starting from `addr` to the host channel of the
+    /// file descriptor `fd`, which is either [FD_STDOUT] or [FD_STDERR].
+    fn request_output_write(
+        &mut self,
+        fd: &Self::Variable,
+        addr: &Self::Variable,
+        len: &Self::Variable,
+    );
+
+    /// Request at most `len` bytes of the advice given by the prover, store
+    /// them starting from `addr`, and return the number of bytes actually
+    /// read, which is 0 when the advice is exhausted.
+    /// The number of bytes actually read will be set into `pos`.
+    fn request_advice_write(
+        &mut self,
+        addr: &Self::Variable,
+        len: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable;
+
+    /// Absorb at most 4 bytes of the input of the Keccak precompile, starting
+    /// from `addr` and up to `len` bytes, and return the number of bytes
+    /// absorbed, which is set into `pos`. When `len` is 0, the call ends and
+    /// the digest of the input is written starting from `output`.
+    ///
+    /// # Safety
+    ///
+    /// No lookups or other constraints are added as part of this operation.
+    /// The caller must bind the bytes absorbed and the digest to the Keccak
+    /// sub-trace with [Self::keccak_hint].
+    unsafe fn request_keccak(
+        &mut self,
+        addr: &Self::Variable,
+        len: &Self::Variable,
+        output: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable;
+
+    /// Return the value of the hint `hint` of the last call to
+    /// [Self::request_keccak], set into `pos`.
+    ///
+    /// # Safety
+    ///
+    /// No lookups or other constraints are added as part of this operation.
+    unsafe fn keccak_hint(&mut self, hint: KeccakHint, pos: Self::Position) -> Self::Variable;
+
+    /// Replace the state of the Poseidon precompile stored at `addr` by its
+    /// permutation, and return the index of the permutation, which is set
+    /// into `pos`.
+    ///
+    /// # Safety
+    ///
+    /// No lookups or other constraints are added as part of this operation.
+    /// The caller must bind the input and the output of the permutation to
+    /// the Poseidon sub-trace with [Self::poseidon_hint].
+    unsafe fn request_poseidon(
+        &mut self,
+        addr: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable;
+
+    /// Return the value of the hint `hint` of the last call to
+    /// [Self::request_poseidon], set into `pos`.
+    ///
+    /// # Safety
+    ///
+    /// No lookups or other constraints are added as part of this operation.
+    unsafe fn poseidon_hint(&mut self, hint: PoseidonHint, pos: Self::Position) -> Self::Variable;
+
+    /// Execute the custom precompile implementing the system call `syscall`
+    /// on its input at `input`, write its output at `output`, and return the
+    /// index of the call, which is set into `pos`.
+    ///
+    /// # Safety
+    ///
+    /// No lookups or other constraints are added as part of this operation.
+    /// The caller must write the call to the communication channel with the
+    /// sub-trace of the precompile.
+    unsafe fn request_precompile(
+        &mut self,
+        syscall: &Self::Variable,
+        input: &Self::Variable,
+        output: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable;
+
     fn reset(&mut self);
 }
 
-pub fn interpret_instruction<Env: InterpreterEnv>(env: &mut Env, instr: Instruction) {
+pub fn interpret_instruction<Env: InterpreterEnv<Xlen = Rv32>>(env: &mut Env, instr: Instruction) {
     env.activate_selector(instr);
     match instr {
         Instruction::RType(rtype) => interpret_rtype(env, rtype),
@@ -1407,78 +2159,80 @@ pub fn interpret_instruction<Env: InterpreterEnv>(env: &mut Env, instr: Instruct
         Instruction::UJType(ujtype) => interpret_ujtype(env, ujtype),
         Instruction::SyscallType(syscall) => interpret_syscall(env, syscall),
         Instruction::MType(mtype) => interpret_mtype(env, mtype),
+        Instruction::CType(ctype) => interpret_ctype(env, ctype),
+        Instruction::AType(atype) => interpret_atype(env, atype),
+        Instruction::CSRType(csrtype) => interpret_csrtype(env, csrtype),
     }
 }
 
-/// Interpret an R-type instruction.
-/// The encoding of an R-type instruction is as follows:
-/// ```text
-/// | 31               25 | 24      20 | 19     15 | 14        12 | 11    7 | 6      0 |
-/// | funct5 & funct 2    |     rs2    |    rs1    |    funct3    |    rd   |  opcode  |
-/// ```
-/// Following the documentation found
-/// [here](https://www.cs.cornell.edu/courses/cs3410/2024fa/assignments/cpusim/riscv-instructions.pdf)
-pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RInstruction) {
-    let instruction_pointer = env.get_instruction_pointer();
-    let next_instruction_pointer = env.get_next_instruction_pointer();
-
-    let instruction = {
-        let v0 = env.read_memory(&instruction_pointer);
-        let v1 = env.read_memory(&(instruction_pointer.clone() + Env::constant(1)));
-        let v2 = env.read_memory(&(instruction_pointer.clone() + Env::constant(2)));
-        let v3 = env.read_memory(&(instruction_pointer.clone() + Env::constant(3)));
-        (v3 * Env::constant(1 << 24))
-            + (v2 * Env::constant(1 << 16))
-            + (v1 * Env::constant(1 << 8))
-            + v0
-    };
+/// Read the 32 bits instruction stored at the address `instruction_pointer`.
+/// The instructions are encoded in little-endian order.
+pub(crate) fn fetch_instruction<Env: InterpreterEnv>(
+    env: &mut Env,
+    instruction_pointer: &Env::Variable,
+) -> Env::Variable {
+    let v0 = env.read_memory(instruction_pointer);
+    let v1 = env.read_memory(&(instruction_pointer.clone() + Env::constant(1)));
+    let v2 = env.read_memory(&(instruction_pointer.clone() + Env::constant(2)));
+    let v3 = env.read_memory(&(instruction_pointer.clone() + Env::constant(3)));
+    (v3 * Env::constant(1 << 24))
+        + (v2 * Env::constant(1 << 16))
+        + (v1 * Env::constant(1 << 8))
+        + v0
+}
 
+/// Decompose an instruction encoded as an R-type instruction, and return the
+/// registers `rd`, `rs1` and `rs2`.
+pub(crate) fn decode_rtype<Env: InterpreterEnv>(
+    env: &mut Env,
+    instruction: &Env::Variable,
+) -> (Env::Variable, Env::Variable, Env::Variable) {
     // FIXME: constrain the opcode to match the instruction given as a parameter
     let opcode = {
         let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 7, 0, pos) }
+        unsafe { env.bitmask(instruction, 7, 0, pos) }
     };
     env.range_check8(&opcode, 7);
 
     let rd = {
         let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 12, 7, pos) }
+        unsafe { env.bitmask(instruction, 12, 7, pos) }
     };
     env.range_check8(&rd, 5);
 
     let funct3 = {
         let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 15, 12, pos) }
+        unsafe { env.bitmask(instruction, 15, 12, pos) }
     };
     env.range_check8(&funct3, 3);
 
     let rs1 = {
         let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 20, 15, pos) }
+        unsafe { env.bitmask(instruction, 20, 15, pos) }
     };
     env.range_check8(&rs1, 5);
 
     let rs2 = {
         let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 25, 20, pos) }
+        unsafe { env.bitmask(instruction, 25, 20, pos) }
     };
     env.range_check8(&rs2, 5);
 
     let funct2 = {
         let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 27, 25, pos) }
+        unsafe { env.bitmask(instruction, 27, 25, pos) }
     };
     env.range_check8(&funct2, 2);
 
     let funct5 = {
         let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 32, 27, pos) }
+        unsafe { env.bitmask(instruction, 32, 27, pos) }
     };
     env.range_check8(&funct5, 5);
 
     // Check correctness of decomposition
     env.add_constraint(
-        instruction
+        instruction.clone()
     - (opcode.clone() * Env::constant(1 << 0))    // opcode at bits 0-6
     - (rd.clone() * Env::constant(1 << 7))        // rd at bits 7-11
     - (funct3.clone() * Env::constant(1 << 12))   // funct3 at bits 12-14
@@ -1488,53 +2242,220 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RInstruction)
     - (funct5.clone() * Env::constant(1 << 27)), // funct5 at bits 27-31
     );
 
-    match instr {
-        RInstruction::Add => {
-            // add: x[rd] = x[rs1] + x[rs2]
-            let local_rs1 = env.read_register(&rs1);
-            let local_rs2 = env.read_register(&rs2);
-            let local_rd = {
-                let overflow_scratch = env.alloc_scratch();
-                let rd_scratch = env.alloc_scratch();
-                let (local_rd, _overflow) = unsafe {
-                    env.add_witness(&local_rs1, &local_rs2, rd_scratch, overflow_scratch)
-                };
-                local_rd
-            };
-            env.write_register(&rd, local_rd);
-
-            env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
-        }
-        RInstruction::Sub => {
-            /* sub: x[rd] = x[rs1] - x[rs2] */
-            let local_rs1 = env.read_register(&rs1);
-            let local_rs2 = env.read_register(&rs2);
-            let local_rd = {
-                let underflow_scratch = env.alloc_scratch();
-                let rd_scratch = env.alloc_scratch();
-                let (local_rd, _underflow) = unsafe {
-                    env.sub_witness(&local_rs1, &local_rs2, rd_scratch, underflow_scratch)
-                };
-                local_rd
-            };
-            env.write_register(&rd, local_rd);
+    (rd, rs1, rs2)
+}
 
-            env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
-        }
-        RInstruction::ShiftLeftLogical => {
-            /* sll: x[rd] = x[rs1] << x[rs2] */
-            let local_rs1 = env.read_register(&rs1);
-            let local_rs2 = env.read_register(&rs2);
-            let local_rd = {
-                let rd_scratch = env.alloc_scratch();
-                unsafe { env.shift_left(&local_rs1, &local_rs2, rd_scratch) }
-            };
-            env.write_register(&rd, local_rd);
+/// Decompose an instruction encoded as an I-type instruction, and return the
+/// register `rd`, the register `rs1`, the 12 bits immediate and the shift
+/// amount held in its lower log2(XLEN) bits.
+pub(crate) fn decode_itype<Env: InterpreterEnv>(
+    env: &mut Env,
+    instruction: &Env::Variable,
+) -> (Env::Variable, Env::Variable, Env::Variable, Env::Variable) {
+    let opcode = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(instruction, 7, 0, pos) }
+    };
+    env.range_check8(&opcode, 7);
 
-            env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+    let rd = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(instruction, 12, 7, pos) }
+    };
+    env.range_check8(&rd, 5);
+
+    let funct3 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(instruction, 15, 12, pos) }
+    };
+    env.range_check8(&funct3, 3);
+
+    let rs1 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(instruction, 20, 15, pos) }
+    };
+    env.range_check8(&rs1, 5);
+
+    let imm = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(instruction, 32, 20, pos) }
+    };
+
+    env.range_check16(&imm, 12);
+
+    // The shift amount of the shifts by an immediate has log2(XLEN) bits
+    let shamt_bits = Env::Xlen::BITS.trailing_zeros();
+    let shamt = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&imm, shamt_bits, 0, pos) }
+    };
+    env.range_check8(&shamt, shamt_bits);
+
+    let imm_header = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&imm, 12, shamt_bits, pos) }
+    };
+    env.range_check8(&imm_header, 12 - shamt_bits);
+
+    // check the correctness of the immediate and shamt
+    env.add_constraint(
+        imm.clone() - (imm_header.clone() * Env::constant(1 << shamt_bits)) - shamt.clone(),
+    );
+
+    // check correctness of decomposition
+    env.add_constraint(
+        instruction.clone()
+            - (opcode.clone() * Env::constant(1 << 0))    // opcode at bits 0-6
+            - (rd.clone() * Env::constant(1 << 7))        // rd at bits 7-11
+            - (funct3.clone() * Env::constant(1 << 12))   // funct3 at bits 12-14
+            - (rs1.clone() * Env::constant(1 << 15))      // rs1 at bits 15-19
+            - (imm.clone() * Env::constant(1 << 20)), // imm at bits 20-32
+    );
+
+    (rd, rs1, imm, shamt)
+}
+
+/// Decompose an instruction encoded as an S-type instruction, and return the
+/// address of the store, `x[rs1] + sext(offset)`, and the value of `x[rs2]`.
+pub(crate) fn decode_stype<Env: InterpreterEnv>(
+    env: &mut Env,
+    instruction: &Env::Variable,
+) -> (Env::Variable, Env::Variable) {
+    /* fetch opcode from instruction bit 0 - 6 for a total len of 7 */
+    let opcode = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(instruction, 7, 0, pos) }
+    };
+    /* verify opcode is 7 bits */
+    env.range_check8(&opcode, 7);
+
+    let imm0_4 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(instruction, 12, 7, pos) }
+        // bytes 7-11
+    };
+    env.range_check8(&imm0_4, 5);
+    let funct3 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(instruction, 15, 12, pos) }
+    };
+    env.range_check8(&funct3, 3);
+
+    let rs1 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(instruction, 20, 15, pos) }
+    };
+    env.range_check8(&rs1, 5);
+    let rs2 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(instruction, 25, 20, pos) }
+    };
+    env.range_check8(&rs2, 5);
+
+    let imm5_11 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(instruction, 32, 25, pos) }
+        // bytes 25-31
+    };
+    env.range_check8(&imm5_11, 7);
+
+    // check correctness of decomposition of S type function
+    env.add_constraint(
+        instruction.clone()
+         - (opcode.clone() * Env::constant(1 << 0))    // opcode at bits 0-6
+         - (imm0_4.clone() * Env::constant(1 << 7))    // imm0_4 at bits 7-11
+         - (funct3.clone() * Env::constant(1 << 12))   // funct3 at bits 12-14
+         - (rs1.clone() * Env::constant(1 << 15))      // rs1 at bits 15-19
+         - (rs2.clone() * Env::constant(1 << 20))      // rs2 at bits 20-24
+         - (imm5_11.clone() * Env::constant(1 << 25)), // imm5_11 at bits 25-31
+    );
+
+    let local_rs1 = env.read_register(&rs1);
+    let local_imm0_11 = {
+        let imm0_11 = imm5_11 * Env::constant(1 << 5) + imm0_4;
+        env.sign_extend(&imm0_11, 12)
+    };
+    let address = {
+        let address_scratch = env.alloc_scratch();
+        let overflow_scratch = env.alloc_scratch();
+        let (address, _overflow) = unsafe {
+            env.add_witness(
+                &local_rs1,
+                &local_imm0_11,
+                address_scratch,
+                overflow_scratch,
+            )
+        };
+        address
+    };
+    let local_rs2 = env.read_register(&rs2);
+
+    (address, local_rs2)
+}
+
+/// Interpret an R-type instruction.
+/// The encoding of an R-type instruction is as follows:
+/// ```text
+/// | 31               25 | 24      20 | 19     15 | 14        12 | 11    7 | 6      0 |
+/// | funct5 & funct 2    |     rs2    |    rs1    |    funct3    |    rd   |  opcode  |
+/// ```
+/// Following the documentation found
+/// [here](https://www.cs.cornell.edu/courses/cs3410/2024fa/assignments/cpusim/riscv-instructions.pdf)
+pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RInstruction) {
+    let instruction_pointer = env.get_instruction_pointer();
+    let next_instruction_pointer = env.get_next_instruction_pointer();
+
+    let instruction = fetch_instruction(env, &instruction_pointer);
+    let (rd, rs1, rs2) = decode_rtype(env, &instruction);
+
+    match instr {
+        RInstruction::Add => {
+            // add: x[rd] = x[rs1] + x[rs2]
+            let local_rs1 = env.read_register(&rs1);
+            let local_rs2 = env.read_register(&rs2);
+            let local_rd = {
+                let overflow_scratch = env.alloc_scratch();
+                let rd_scratch = env.alloc_scratch();
+                let (local_rd, _overflow) = unsafe {
+                    env.add_witness(&local_rs1, &local_rs2, rd_scratch, overflow_scratch)
+                };
+                local_rd
+            };
+            env.write_register(&rd, local_rd);
+
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
+        }
+        RInstruction::Sub => {
+            /* sub: x[rd] = x[rs1] - x[rs2] */
+            let local_rs1 = env.read_register(&rs1);
+            let local_rs2 = env.read_register(&rs2);
+            let local_rd = {
+                let underflow_scratch = env.alloc_scratch();
+                let rd_scratch = env.alloc_scratch();
+                let (local_rd, _underflow) = unsafe {
+                    env.sub_witness(&local_rs1, &local_rs2, rd_scratch, underflow_scratch)
+                };
+                local_rd
+            };
+            env.write_register(&rd, local_rd);
+
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
+        }
+        RInstruction::ShiftLeftLogical => {
+            /* sll: x[rd] = x[rs1] << x[rs2][log2(XLEN)-1:0] */
+            let local_rs1 = env.read_register(&rs1);
+            let local_rs2 = env.read_register(&rs2);
+            let shamt = shift_amount(env, &local_rs2);
+            let local_rd = {
+                let rd_scratch = env.alloc_scratch();
+                unsafe { env.shift_left(&local_rs1, &shamt, rd_scratch) }
+            };
+            env.write_register(&rd, local_rd);
+
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         RInstruction::SetLessThan => {
             /* slt: x[rd] = (x[rs1] < x[rs2]) ? 1 : 0 */
@@ -1547,7 +2468,7 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RInstruction)
             env.write_register(&rd, local_rd);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         RInstruction::SetLessThanUnsigned => {
             /* sltu: x[rd] = (x[rs1] < (u)x[rs2]) ? 1 : 0 */
@@ -1560,7 +2481,7 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RInstruction)
             env.write_register(&rd, local_rd);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         RInstruction::Xor => {
             /* xor: x[rd] = x[rs1] ^ x[rs2] */
@@ -1573,33 +2494,35 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RInstruction)
             env.write_register(&rd, local_rd);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         RInstruction::ShiftRightLogical => {
-            /* srl: x[rd] = x[rs1] >> x[rs2] */
+            /* srl: x[rd] = x[rs1] >> x[rs2][log2(XLEN)-1:0] */
             let local_rs1 = env.read_register(&rs1);
             let local_rs2 = env.read_register(&rs2);
+            let shamt = shift_amount(env, &local_rs2);
             let local_rd = {
                 let pos = env.alloc_scratch();
-                unsafe { env.shift_right(&local_rs1, &local_rs2, pos) }
+                unsafe { env.shift_right(&local_rs1, &shamt, pos) }
             };
             env.write_register(&rd, local_rd);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         RInstruction::ShiftRightArithmetic => {
-            /* sra: x[rd] = x[rs1] >> x[rs2] */
+            /* sra: x[rd] = x[rs1] >>s x[rs2][log2(XLEN)-1:0] */
             let local_rs1 = env.read_register(&rs1);
             let local_rs2 = env.read_register(&rs2);
+            let shamt = shift_amount(env, &local_rs2);
             let local_rd = {
                 let pos = env.alloc_scratch();
-                unsafe { env.shift_right_arithmetic(&local_rs1, &local_rs2, pos) }
+                unsafe { env.shift_right_arithmetic(&local_rs1, &shamt, pos) }
             };
             env.write_register(&rd, local_rd);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         RInstruction::Or => {
             /* or: x[rd] = x[rs1] | x[rs2] */
@@ -1612,7 +2535,7 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RInstruction)
             env.write_register(&rd, local_rd);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         RInstruction::And => {
             /* and: x[rd] = x[rs1] & x[rs2] */
@@ -1625,95 +2548,44 @@ pub fn interpret_rtype<Env: InterpreterEnv>(env: &mut Env, instr: RInstruction)
             env.write_register(&rd, local_rd);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
-        }
-        RInstruction::Fence => {
-            unimplemented!("Fence")
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
-        RInstruction::FenceI => {
-            unimplemented!("FenceI")
+        RInstruction::Fence | RInstruction::FenceI => {
+            // The execution is sequential and the code is never modified: the
+            // memory and the instruction fetches are always ordered, so the
+            // fences are no-ops
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
     };
 }
 
+/// Returns the shift amount of the shifts by a register, given by the
+/// `log2(XLEN)` lowest bits of the register `rs2`.
+fn shift_amount<Env: InterpreterEnv>(env: &mut Env, rs2: &Env::Variable) -> Env::Variable {
+    // FIXME: constrain the decomposition of rs2
+    let pos = env.alloc_scratch();
+    unsafe { env.bitmask(rs2, Env::Xlen::BITS.trailing_zeros(), 0, pos) }
+}
+
 /// Interpret an I-type instruction.
 /// The encoding of an I-type instruction is as follows:
 /// ```text
 /// | 31     20 | 19     15 | 14    12 | 11    7 | 6      0 |
 /// | immediate |    rs1    |  funct3  |    rd   |  opcode  |
 /// ```
+/// The loads read the memory byte per byte, the most significant byte first,
+/// so that misaligned addresses are supported without trapping, including
+/// values spanning two pages. The constraints are the same whatever the
+/// alignment of the address.
 /// Following the documentation found
 /// [here](https://www.cs.cornell.edu/courses/cs3410/2024fa/assignments/cpusim/riscv-instructions.pdf)
 pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction) {
     let instruction_pointer = env.get_instruction_pointer();
     let next_instruction_pointer = env.get_next_instruction_pointer();
 
-    let instruction = {
-        let v0 = env.read_memory(&instruction_pointer);
-        let v1 = env.read_memory(&(instruction_pointer.clone() + Env::constant(1)));
-        let v2 = env.read_memory(&(instruction_pointer.clone() + Env::constant(2)));
-        let v3 = env.read_memory(&(instruction_pointer.clone() + Env::constant(3)));
-        (v3 * Env::constant(1 << 24))
-            + (v2 * Env::constant(1 << 16))
-            + (v1 * Env::constant(1 << 8))
-            + v0
-    };
-
-    let opcode = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 7, 0, pos) }
-    };
-    env.range_check8(&opcode, 7);
-
-    let rd = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 12, 7, pos) }
-    };
-    env.range_check8(&rd, 5);
-
-    let funct3 = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 15, 12, pos) }
-    };
-    env.range_check8(&funct3, 3);
-
-    let rs1 = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 20, 15, pos) }
-    };
-    env.range_check8(&rs1, 5);
-
-    let imm = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 32, 20, pos) }
-    };
-
-    env.range_check16(&imm, 12);
-
-    let shamt = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&imm, 5, 0, pos) }
-    };
-    env.range_check8(&shamt, 5);
-
-    let imm_header = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&imm, 12, 5, pos) }
-    };
-    env.range_check8(&imm_header, 7);
-
-    // check the correctness of the immediate and shamt
-    env.add_constraint(imm.clone() - (imm_header.clone() * Env::constant(1 << 5)) - shamt.clone());
-
-    // check correctness of decomposition
-    env.add_constraint(
-        instruction
-            - (opcode.clone() * Env::constant(1 << 0))    // opcode at bits 0-6
-            - (rd.clone() * Env::constant(1 << 7))        // rd at bits 7-11
-            - (funct3.clone() * Env::constant(1 << 12))   // funct3 at bits 12-14
-            - (rs1.clone() * Env::constant(1 << 15))      // rs1 at bits 15-19
-            - (imm.clone() * Env::constant(1 << 20)), // imm at bits 20-32
-    );
+    let instruction = fetch_instruction(env, &instruction_pointer);
+    let (rd, rs1, imm, shamt) = decode_itype(env, &instruction);
 
     match instr {
         IInstruction::LoadByte => {
@@ -1733,7 +2605,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             let value = env.sign_extend(&value, 8);
             env.write_register(&rd, value);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         IInstruction::LoadHalf => {
             // lh:  x[rd] = sext(M[x[rs1] + sext(offset)][15:0])
@@ -1754,7 +2626,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             let value = env.sign_extend(&value, 16);
             env.write_register(&rd, value);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         IInstruction::LoadWord => {
             // lw:  x[rd] = sext(M[x[rs1] + sext(offset)][31:0])
@@ -1779,7 +2651,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             let value = env.sign_extend(&value, 32);
             env.write_register(&rd, value);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         IInstruction::LoadByteUnsigned => {
             //lbu: x[rd] = M[x[rs1] + sext(offset)][7:0]
@@ -1797,7 +2669,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             let value = env.read_memory(&address);
             env.write_register(&rd, value);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         IInstruction::LoadHalfUnsigned => {
             // lhu: x[rd] = M[x[rs1] + sext(offset)][15:0]
@@ -1817,7 +2689,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             let value = (v0 * Env::constant(1 << 8)) + v1;
             env.write_register(&rd, value);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         IInstruction::ShiftLeftLogicalImmediate => {
             // slli: x[rd] = x[rs1] << shamt
@@ -1830,7 +2702,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
 
             env.write_register(&rd, local_rd);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         IInstruction::ShiftRightLogicalImmediate => {
             // srli: x[rd] = x[rs1] >> shamt
@@ -1841,7 +2713,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             };
             env.write_register(&rd, local_rd);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         IInstruction::ShiftRightArithmeticImmediate => {
             // srai: x[rd] = x[rs1] >> shamt
@@ -1853,7 +2725,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             };
             env.write_register(&rd, local_rd);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         IInstruction::SetLessThanImmediate => {
             // slti: x[rd] = (x[rs1] < sext(immediate)) ? 1 : 0
@@ -1865,7 +2737,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             };
             env.write_register(&rd, local_rd);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         IInstruction::SetLessThanImmediateUnsigned => {
             // sltiu: x[rd] = (x[rs1] < (u)sext(immediate)) ? 1 : 0
@@ -1877,7 +2749,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             };
             env.write_register(&rd, local_rd);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         IInstruction::AddImmediate => {
             // addi: x[rd] = x[rs1] + sext(immediate)
@@ -1893,7 +2765,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             };
             env.write_register(&rd, local_rd);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         IInstruction::XorImmediate => {
             // xori: x[rd] = x[rs1] ^ sext(immediate)
@@ -1905,7 +2777,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             };
             env.write_register(&rd, local_rd);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         IInstruction::OrImmediate => {
             // ori: x[rd] = x[rs1] | sext(immediate)
@@ -1917,7 +2789,7 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             };
             env.write_register(&rd, local_rd);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         IInstruction::AndImmediate => {
             // andi: x[rd] = x[rs1] & sext(immediate)
@@ -1929,26 +2801,30 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
             };
             env.write_register(&rd, local_rd);
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         IInstruction::JumpAndLinkRegister => {
-            let addr = env.read_register(&rs1);
             // jalr:
             //  t  = pc+4;
-            //  pc = (x[rs1] + sext(offset)) & ∼1; <- NOT NOW
-            //  pc = (x[rs1] + sext(offset)); <- PLEASE FIXME
+            //  pc = (x[rs1] + sext(offset)) & ∼1;
             //  x[rd] = t
+            let addr = env.read_register(&rs1);
             let offset = env.sign_extend(&imm, 12);
-            let new_addr = {
+            let target = {
                 let res_scratch = env.alloc_scratch();
                 let overflow_scratch = env.alloc_scratch();
                 let (res, _overflow) =
                     unsafe { env.add_witness(&addr, &offset, res_scratch, overflow_scratch) };
                 res
             };
+            // Clear the least significant bit of the target
+            let new_addr = {
+                let pos = env.alloc_scratch();
+                unsafe { env.bitmask(&target, Env::Xlen::BITS, 1, pos) }
+            } * Env::constant(2);
             env.write_register(&rd, next_instruction_pointer.clone());
             env.set_instruction_pointer(new_addr.clone());
-            env.set_next_instruction_pointer(new_addr.clone() + Env::constant(4u32));
+            env.set_next_instruction_pointer(new_addr.clone() + Env::constant(4u64));
         }
     };
 }
@@ -1959,6 +2835,8 @@ pub fn interpret_itype<Env: InterpreterEnv>(env: &mut Env, instr: IInstruction)
 /// | 31     25 | 24      20 | 19     15 | 14        12 | 11    7 | 6      0 |
 /// | immediate |     rs2    |    rs1    |    funct3    |    imm  |  opcode  |
 /// ```
+/// As the loads, the stores write the memory byte per byte, the most
+/// significant byte first, and support misaligned addresses.
 /// Following the documentation found
 /// [here](https://www.cs.cornell.edu/courses/cs3410/2024fa/assignments/cpusim/riscv-instructions.pdf)
 pub fn interpret_stype<Env: InterpreterEnv>(env: &mut Env, instr: SInstruction) {
@@ -1967,116 +2845,35 @@ pub fn interpret_stype<Env: InterpreterEnv>(env: &mut Env, instr: SInstruction)
     /* compute the next instruction ptr and add one, as well record raml lookup */
     let next_instruction_pointer = env.get_next_instruction_pointer();
     /* read instruction from ip address */
-    let instruction = {
-        let v0 = env.read_memory(&instruction_pointer);
-        let v1 = env.read_memory(&(instruction_pointer.clone() + Env::constant(1)));
-        let v2 = env.read_memory(&(instruction_pointer.clone() + Env::constant(2)));
-        let v3 = env.read_memory(&(instruction_pointer.clone() + Env::constant(3)));
-        (v3 * Env::constant(1 << 24))
-            + (v2 * Env::constant(1 << 16))
-            + (v1 * Env::constant(1 << 8))
-            + v0
-    };
+    let instruction = fetch_instruction(env, &instruction_pointer);
+    let (address, local_rs2) = decode_stype(env, &instruction);
 
-    /* fetch opcode from instruction bit 0 - 6 for a total len of 7 */
-    let opcode = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 7, 0, pos) }
-    };
-    /* verify opcode is 7 bits */
-    env.range_check8(&opcode, 7);
-
-    let imm0_4 = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 12, 7, pos) }
-        // bytes 7-11
-    };
-    env.range_check8(&imm0_4, 5);
-    let funct3 = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 15, 12, pos) }
-    };
-    env.range_check8(&funct3, 3);
-
-    let rs1 = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 20, 15, pos) }
-    };
-    env.range_check8(&rs1, 5);
-    let rs2 = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 25, 20, pos) }
-    };
-    env.range_check8(&rs2, 5);
-
-    let imm5_11 = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 32, 25, pos) }
-        // bytes 25-31
-    };
-    env.range_check8(&imm5_11, 7);
-
-    // check correctness of decomposition of S type function
-    env.add_constraint(
-        instruction
-         - (opcode.clone() * Env::constant(1 << 0))    // opcode at bits 0-6
-         - (imm0_4.clone() * Env::constant(1 << 7))    // imm0_4 at bits 7-11
-         - (funct3.clone() * Env::constant(1 << 12))   // funct3 at bits 12-14
-         - (rs1.clone() * Env::constant(1 << 15))      // rs1 at bits 15-19
-         - (rs2.clone() * Env::constant(1 << 20))      // rs2 at bits 20-24
-         - (imm5_11.clone() * Env::constant(1 << 25)), // imm5_11 at bits 25-31
-    );
-
-    let local_rs1 = env.read_register(&rs1);
-    let local_imm0_4 = env.sign_extend(&imm0_4, 5);
-    let local_imm5_11 = env.sign_extend(&imm5_11, 7);
-    let local_imm0_11 = {
-        let pos = env.alloc_scratch();
-        let shift_pos = env.alloc_scratch();
-        let shifted_imm5_11 =
-            unsafe { env.shift_left(&local_imm5_11, &Env::constant(5), shift_pos) };
-        let local_imm0_11 = unsafe { env.or_witness(&shifted_imm5_11, &local_imm0_4, pos) };
-        env.sign_extend(&local_imm0_11, 11)
-    };
-    let address = {
-        let address_scratch = env.alloc_scratch();
-        let overflow_scratch = env.alloc_scratch();
-        let (address, _overflow) = unsafe {
-            env.add_witness(
-                &local_rs1,
-                &local_imm0_11,
-                address_scratch,
-                overflow_scratch,
-            )
-        };
-        address
-    };
-    let local_rs2 = env.read_register(&rs2);
-
-    match instr {
-        SInstruction::StoreByte => {
-            // sb: M[x[rs1] + sext(offset)] = x[rs2][7:0]
-            let v0 = {
-                let value_scratch = env.alloc_scratch();
-                unsafe { env.bitmask(&local_rs2, 8, 0, value_scratch) }
-            };
+    match instr {
+        SInstruction::StoreByte => {
+            // sb: M[x[rs1] + sext(offset)] = x[rs2][7:0]
+            let v0 = {
+                let value_scratch = env.alloc_scratch();
+                unsafe { env.bitmask(&local_rs2, 8, 0, value_scratch) }
+            };
 
             env.lookup_8bits(&v0);
             env.write_memory(&address, v0);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         SInstruction::StoreHalf => {
             // sh: M[x[rs1] + sext(offset)] = x[rs2][15:0]
+            // The most significant byte is stored first, as read by lh and
+            // lhu.
             let [v0, v1] = [
                 {
                     let value_scratch = env.alloc_scratch();
-                    unsafe { env.bitmask(&local_rs2, 8, 0, value_scratch) }
+                    unsafe { env.bitmask(&local_rs2, 16, 8, value_scratch) }
                 },
                 {
                     let value_scratch = env.alloc_scratch();
-                    unsafe { env.bitmask(&local_rs2, 16, 8, value_scratch) }
+                    unsafe { env.bitmask(&local_rs2, 8, 0, value_scratch) }
                 },
             ];
 
@@ -2084,10 +2881,10 @@ pub fn interpret_stype<Env: InterpreterEnv>(env: &mut Env, instr: SInstruction)
             env.lookup_8bits(&v1);
 
             env.write_memory(&address, v0);
-            env.write_memory(&(address.clone() + Env::constant(1u32)), v1);
+            env.write_memory(&(address.clone() + Env::constant(1u64)), v1);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         SInstruction::StoreWord => {
             // sw: M[x[rs1] + sext(offset)] = x[rs2][31:0]
@@ -2116,12 +2913,12 @@ pub fn interpret_stype<Env: InterpreterEnv>(env: &mut Env, instr: SInstruction)
             env.lookup_8bits(&v3);
 
             env.write_memory(&address, v0);
-            env.write_memory(&(address.clone() + Env::constant(1u32)), v1);
-            env.write_memory(&(address.clone() + Env::constant(2u32)), v2);
-            env.write_memory(&(address.clone() + Env::constant(3u32)), v3);
+            env.write_memory(&(address.clone() + Env::constant(1u64)), v1);
+            env.write_memory(&(address.clone() + Env::constant(2u64)), v2);
+            env.write_memory(&(address.clone() + Env::constant(3u64)), v3);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
     };
 }
@@ -2136,17 +2933,10 @@ pub fn interpret_stype<Env: InterpreterEnv>(env: &mut Env, instr: SInstruction)
 /// [here](https://www.cs.cornell.edu/courses/cs3410/2024fa/assignments/cpusim/riscv-instructions.pdf)
 pub fn interpret_sbtype<Env: InterpreterEnv>(env: &mut Env, instr: SBInstruction) {
     let instruction_pointer = env.get_instruction_pointer();
-    let next_instruction_pointer = env.get_next_instruction_pointer();
-    let instruction = {
-        let v0 = env.read_memory(&instruction_pointer);
-        let v1 = env.read_memory(&(instruction_pointer.clone() + Env::constant(1)));
-        let v2 = env.read_memory(&(instruction_pointer.clone() + Env::constant(2)));
-        let v3 = env.read_memory(&(instruction_pointer.clone() + Env::constant(3)));
-        (v3 * Env::constant(1 << 24))
-            + (v2 * Env::constant(1 << 16))
-            + (v1 * Env::constant(1 << 8))
-            + v0
-    };
+    // The next instruction pointer is not used by the branches, but it is
+    // read to consume its previous value in the register lookups.
+    let _next_instruction_pointer = env.get_next_instruction_pointer();
+    let instruction = fetch_instruction(env, &instruction_pointer);
     let opcode = {
         let pos = env.alloc_scratch();
         unsafe { env.bitmask(&instruction, 7, 0, pos) }
@@ -2201,14 +2991,14 @@ pub fn interpret_sbtype<Env: InterpreterEnv>(env: &mut Env, instr: SBInstruction
         // check correctness of decomposition of SB type function
         env.add_constraint(
             instruction.clone()
-                - (opcode * Env::constant(1 << 0))    // opcode at bits 0-7
-                - (imm11.clone() * Env::constant(1 << 7))     // imm11 at bits 8
-                - (imm1_4.clone() * Env::constant(1 << 8))    // imm1_4 at bits 9-11
-                - (funct3 * Env::constant(1 << 11))   // funct3 at bits 11-14
-                - (rs1.clone() * Env::constant(1 << 14))      // rs1 at bits 15-20
-                - (rs2.clone() * Env::constant(1 << 19))      // rs2 at bits 20-24
-                - (imm5_10.clone() * Env::constant(1 << 24))  // imm5_10 at bits 25-30
-                - (imm12.clone() * Env::constant(1 << 31)), // imm12 at bits 31
+                - (opcode * Env::constant(1 << 0))    // opcode at bits 0-6
+                - (imm11.clone() * Env::constant(1 << 7))     // imm11 at bit 7
+                - (imm1_4.clone() * Env::constant(1 << 8))    // imm1_4 at bits 8-11
+                - (funct3 * Env::constant(1 << 12))   // funct3 at bits 12-14
+                - (rs1.clone() * Env::constant(1 << 15))      // rs1 at bits 15-19
+                - (rs2.clone() * Env::constant(1 << 20))      // rs2 at bits 20-24
+                - (imm5_10.clone() * Env::constant(1 << 25))  // imm5_10 at bits 25-30
+                - (imm12.clone() * Env::constant(1 << 31)), // imm12 at bit 31
         );
 
         (imm12 * Env::constant(1 << 12))
@@ -2219,165 +3009,57 @@ pub fn interpret_sbtype<Env: InterpreterEnv>(env: &mut Env, instr: SBInstruction
     // extra bit is because the 0th bit in the immediate is always 0 i.e you cannot jump to an odd address
     let imm0_12 = env.sign_extend(&imm0_12, 13);
 
-    match instr {
+    let local_rs1 = env.read_register(&rs1);
+    let local_rs2 = env.read_register(&rs2);
+
+    // Whether the branch is taken
+    let take_branch = match instr {
         SBInstruction::BranchEq => {
             // beq: if (x[rs1] == x[rs2]) pc += sext(offset)
-            let local_rs1 = env.read_register(&rs1);
-            let local_rs2 = env.read_register(&rs2);
-
-            let equals = env.equal(&local_rs1, &local_rs2);
-            let offset = (Env::constant(1) - equals.clone()) * Env::constant(4) + equals * imm0_12;
-            let offset = env.sign_extend(&offset, 12);
-            let addr = {
-                let res_scratch = env.alloc_scratch();
-                let overflow_scratch = env.alloc_scratch();
-                let (res, _overflow) = unsafe {
-                    env.add_witness(
-                        &next_instruction_pointer,
-                        &offset,
-                        res_scratch,
-                        overflow_scratch,
-                    )
-                };
-                // FIXME: Requires a range check
-                res
-            };
-            env.set_instruction_pointer(next_instruction_pointer);
-            env.set_next_instruction_pointer(addr);
+            env.equal(&local_rs1, &local_rs2)
         }
         SBInstruction::BranchNeq => {
             // bne: if (x[rs1] != x[rs2]) pc += sext(offset)
-            let local_rs1 = env.read_register(&rs1);
-            let local_rs2 = env.read_register(&rs2);
-
-            let equals = env.equal(&local_rs1, &local_rs2);
-            let offset = equals.clone() * Env::constant(4) + (Env::constant(1) - equals) * imm0_12;
-            let addr = {
-                let res_scratch = env.alloc_scratch();
-                let overflow_scratch = env.alloc_scratch();
-                let (res, _overflow) = unsafe {
-                    env.add_witness(
-                        &next_instruction_pointer,
-                        &offset,
-                        res_scratch,
-                        overflow_scratch,
-                    )
-                };
-                // FIXME: Requires a range check
-                res
-            };
-            env.set_instruction_pointer(next_instruction_pointer);
-            env.set_next_instruction_pointer(addr);
+            Env::constant(1) - env.equal(&local_rs1, &local_rs2)
         }
         SBInstruction::BranchLessThan => {
             // blt: if (x[rs1] < x[rs2]) pc += sext(offset)
-            let local_rs1 = env.read_register(&rs1);
-            let local_rs2 = env.read_register(&rs2);
-
-            let less_than = {
-                let rd_scratch = env.alloc_scratch();
-                unsafe { env.test_less_than_signed(&local_rs1, &local_rs2, rd_scratch) }
-            };
-            let offset = (less_than.clone()) * imm0_12
-                + (Env::constant(1) - less_than.clone()) * Env::constant(4);
-
-            let addr = {
-                let res_scratch = env.alloc_scratch();
-                let overflow_scratch = env.alloc_scratch();
-                let (res, _overflow) = unsafe {
-                    env.add_witness(
-                        &next_instruction_pointer,
-                        &offset,
-                        res_scratch,
-                        overflow_scratch,
-                    )
-                };
-                // FIXME: Requires a range check
-                res
-            };
-            env.set_instruction_pointer(next_instruction_pointer);
-            env.set_next_instruction_pointer(addr);
+            let rd_scratch = env.alloc_scratch();
+            unsafe { env.test_less_than_signed(&local_rs1, &local_rs2, rd_scratch) }
         }
         SBInstruction::BranchGreaterThanEqual => {
             // bge: if (x[rs1] >= x[rs2]) pc += sext(offset)
-            let local_rs1 = env.read_register(&rs1);
-            let local_rs2 = env.read_register(&rs2);
-
-            let less_than = {
-                let rd_scratch = env.alloc_scratch();
-                unsafe { env.test_less_than_signed(&local_rs1, &local_rs2, rd_scratch) }
-            };
-
-            let offset =
-                less_than.clone() * Env::constant(4) + (Env::constant(1) - less_than) * imm0_12;
             // greater than equal is the negation of less than
-            let addr = {
-                let res_scratch = env.alloc_scratch();
-                let overflow_scratch = env.alloc_scratch();
-                let (res, _overflow) = unsafe {
-                    env.add_witness(
-                        &next_instruction_pointer,
-                        &offset,
-                        res_scratch,
-                        overflow_scratch,
-                    )
-                };
-                // FIXME: Requires a range check
-                res
-            };
-            env.set_instruction_pointer(next_instruction_pointer);
-            env.set_next_instruction_pointer(addr);
+            let rd_scratch = env.alloc_scratch();
+            Env::constant(1)
+                - unsafe { env.test_less_than_signed(&local_rs1, &local_rs2, rd_scratch) }
         }
         SBInstruction::BranchLessThanUnsigned => {
             // bltu: if (x[rs1] <u x[rs2]) pc += sext(offset)
-            let local_rs1 = env.read_register(&rs1);
-            let local_rs2 = env.read_register(&rs2);
-
-            let less_than = {
-                let rd_scratch = env.alloc_scratch();
-                unsafe { env.test_less_than(&local_rs1, &local_rs2, rd_scratch) }
-            };
-
-            let offset = (Env::constant(1) - less_than.clone()) * Env::constant(4)
-                + less_than.clone() * imm0_12;
-
-            let addr = {
-                let res_scratch = env.alloc_scratch();
-                let overflow_scratch = env.alloc_scratch();
-                let (res, _overflow) = unsafe {
-                    env.add_witness(&instruction_pointer, &offset, res_scratch, overflow_scratch)
-                };
-                // FIXME: Requires a range check
-                res
-            };
-
-            env.set_instruction_pointer(next_instruction_pointer);
-            env.set_next_instruction_pointer(addr);
+            let rd_scratch = env.alloc_scratch();
+            unsafe { env.test_less_than(&local_rs1, &local_rs2, rd_scratch) }
         }
         SBInstruction::BranchGreaterThanEqualUnsigned => {
             // bgeu: if (x[rs1] >=u x[rs2]) pc += sext(offset)
-            let local_rs1 = env.read_register(&rs1);
-            let local_rs2 = env.read_register(&rs2);
-
-            let rd_scratch = env.alloc_scratch();
-            let less_than = unsafe { env.test_less_than(&local_rs1, &local_rs2, rd_scratch) };
-            let offset =
-                less_than.clone() * Env::constant(4) + (Env::constant(1) - less_than) * imm0_12;
-
             // greater than equal is the negation of less than
-            let addr = {
-                let res_scratch = env.alloc_scratch();
-                let overflow_scratch = env.alloc_scratch();
-                let (res, _overflow) = unsafe {
-                    env.add_witness(&instruction_pointer, &offset, res_scratch, overflow_scratch)
-                };
-                res
-            };
-
-            env.set_instruction_pointer(next_instruction_pointer);
-            env.set_next_instruction_pointer(addr);
+            let rd_scratch = env.alloc_scratch();
+            Env::constant(1) - unsafe { env.test_less_than(&local_rs1, &local_rs2, rd_scratch) }
         }
     };
+
+    let offset =
+        take_branch.clone() * imm0_12 + (Env::constant(1) - take_branch) * Env::constant(4);
+    let addr = {
+        let res_scratch = env.alloc_scratch();
+        let overflow_scratch = env.alloc_scratch();
+        let (res, _overflow) = unsafe {
+            env.add_witness(&instruction_pointer, &offset, res_scratch, overflow_scratch)
+        };
+        // FIXME: Requires a range check
+        res
+    };
+    env.set_instruction_pointer(addr.clone());
+    env.set_next_instruction_pointer(addr + Env::constant(4u64));
 }
 
 /// Interpret an U-type instruction.
@@ -2392,16 +3074,7 @@ pub fn interpret_utype<Env: InterpreterEnv>(env: &mut Env, instr: UInstruction)
     let instruction_pointer = env.get_instruction_pointer();
     let next_instruction_pointer = env.get_next_instruction_pointer();
 
-    let instruction = {
-        let v0 = env.read_memory(&instruction_pointer);
-        let v1 = env.read_memory(&(instruction_pointer.clone() + Env::constant(1)));
-        let v2 = env.read_memory(&(instruction_pointer.clone() + Env::constant(2)));
-        let v3 = env.read_memory(&(instruction_pointer.clone() + Env::constant(3)));
-        (v3 * Env::constant(1 << 24))
-            + (v2 * Env::constant(1 << 16))
-            + (v1 * Env::constant(1 << 8))
-            + v0
-    };
+    let instruction = fetch_instruction(env, &instruction_pointer);
 
     let opcode = {
         let pos = env.alloc_scratch();
@@ -2442,7 +3115,7 @@ pub fn interpret_utype<Env: InterpreterEnv>(env: &mut Env, instr: UInstruction)
             env.write_register(&rd, local_imm);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         UInstruction::AddUpperImmediate => {
             // auipc: x[rd] = pc + sext(immediate[31:12] << 12)
@@ -2460,7 +3133,7 @@ pub fn interpret_utype<Env: InterpreterEnv>(env: &mut Env, instr: UInstruction)
             env.write_register(&rd, local_rd);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
     };
 }
@@ -2493,16 +3166,7 @@ pub fn interpret_ujtype<Env: InterpreterEnv>(env: &mut Env, instr: UJInstruction
     let instruction_pointer = env.get_instruction_pointer();
     let next_instruction_pointer = env.get_next_instruction_pointer();
 
-    let instruction = {
-        let v0 = env.read_memory(&instruction_pointer);
-        let v1 = env.read_memory(&(instruction_pointer.clone() + Env::constant(1)));
-        let v2 = env.read_memory(&(instruction_pointer.clone() + Env::constant(2)));
-        let v3 = env.read_memory(&(instruction_pointer.clone() + Env::constant(3)));
-        (v3 * Env::constant(1 << 24))
-            + (v2 * Env::constant(1 << 16))
-            + (v1 * Env::constant(1 << 8))
-            + v0
-    };
+    let instruction = fetch_instruction(env, &instruction_pointer);
 
     let opcode = {
         let pos = env.alloc_scratch();
@@ -2562,126 +3226,520 @@ pub fn interpret_ujtype<Env: InterpreterEnv>(env: &mut Env, instr: UJInstruction
             };
             env.write_register(&rd, next_instruction_pointer.clone());
             env.set_instruction_pointer(new_addr.clone());
-            env.set_next_instruction_pointer(new_addr + Env::constant(4u32));
+            env.set_next_instruction_pointer(new_addr + Env::constant(4u64));
         }
     }
 }
 
-pub fn interpret_syscall<Env: InterpreterEnv>(env: &mut Env, _instr: SyscallInstruction) {
-    // FIXME: check if it is syscall success. There is only one syscall atm
-    env.set_halted(Env::constant(1));
-}
-
-/// Interpret an M-type instruction.
-/// The encoding of an M-type instruction is as follows:
-/// ```text
-/// | 31     27 | 26    25 | 24     20 | 19     15 | 14        12 | 11    7 | 6      0 |
-/// |   00000   |    01    |    rs2    |    rs1    |    funct3    |    rd   |  opcode  |
-/// ```
-/// Following the documentation found
-/// [here](https://www.cs.cornell.edu/courses/cs3410/2024fa/assignments/cpusim/riscv-instructions.pdf)
-pub fn interpret_mtype<Env: InterpreterEnv>(env: &mut Env, instr: MInstruction) {
-    let instruction_pointer = env.get_instruction_pointer();
-    let next_instruction_pointer = env.get_next_instruction_pointer();
-
-    let instruction = {
-        let v0 = env.read_memory(&instruction_pointer);
-        let v1 = env.read_memory(&(instruction_pointer.clone() + Env::constant(1)));
-        let v2 = env.read_memory(&(instruction_pointer.clone() + Env::constant(2)));
-        let v3 = env.read_memory(&(instruction_pointer.clone() + Env::constant(3)));
-        (v3 * Env::constant(1 << 24))
-            + (v2 * Env::constant(1 << 16))
-            + (v1 * Env::constant(1 << 8))
-            + v0
-    };
-
-    let opcode = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 7, 0, pos) }
-    };
-    env.range_check8(&opcode, 7);
-
-    let rd = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 12, 7, pos) }
-    };
-    env.range_check8(&rd, 5);
-
-    let funct3 = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 15, 12, pos) }
-    };
-    env.range_check8(&funct3, 3);
-
-    let rs1 = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 20, 15, pos) }
-    };
-    env.range_check8(&rs1, 5);
-
-    let rs2 = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 25, 20, pos) }
-    };
-    env.range_check8(&rs2, 5);
-
-    let funct2 = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 27, 25, pos) }
-    };
-    // FIXME: check it is equal to 01?
-    env.range_check8(&funct2, 2);
-
-    let funct5 = {
-        let pos = env.alloc_scratch();
-        unsafe { env.bitmask(&instruction, 32, 27, pos) }
-    };
-    // FIXME: check it is equal to 00000?
-    env.range_check8(&funct5, 5);
+/// Interpret a system call, following the calling convention of Linux: the
+/// number of the system call is in register a7, which is used to decode the
+/// instruction, its arguments in registers a0 to a5, and its result is written
+/// in register a0.
+pub fn interpret_syscall<Env: InterpreterEnv>(env: &mut Env, instr: SyscallInstruction) {
+    let a0 = Env::constant(REGISTER_A0);
+    let a1 = Env::constant(REGISTER_A1);
+    let a2 = Env::constant(REGISTER_A2);
 
-    // Check decomposition of M type instruction
-    env.add_constraint(
-        instruction
-            - (opcode.clone() * Env::constant(1 << 0))    // opcode at bits 0-6
-            - (rd.clone() * Env::constant(1 << 7))        // rd at bits 7-11
-            - (funct3.clone() * Env::constant(1 << 12))   // funct3 at bits 12-14
-            - (rs1.clone() * Env::constant(1 << 15))      // rs1 at bits 15-19
-            - (rs2.clone() * Env::constant(1 << 20))      // rs2 at bits 20-24
-            - (funct2.clone() * Env::constant(1 << 25))   // funct2 at bits 25-26
-            - (funct5.clone() * Env::constant(1 << 27)), // funct5 at bits 27-31
-    );
+    // The system calls halting the VM leave the instruction pointer unchanged
+    match instr {
+        SyscallInstruction::SyscallSuccess => {
+            env.set_halted(Env::constant(1));
+            return;
+        }
+        SyscallInstruction::SyscallExitGroup => {
+            let exit_code = env.read_register(&a0);
+            env.report_exit(&exit_code);
+            env.set_halted(Env::constant(1));
+            return;
+        }
+        _ => (),
+    }
 
+    let next_instruction_pointer = env.get_next_instruction_pointer();
     match instr {
-        MInstruction::Mul => {
-            // x[rd] = x[rs1] * x[rs2]
-            let rs1 = env.read_register(&rs1);
-            let rs2 = env.read_register(&rs2);
-            // FIXME: constrain
-            let res = {
+        SyscallInstruction::SyscallKeccak256 => {
+            let instruction_pointer = env.get_instruction_pointer();
+            let addr = env.read_register(&a0);
+            let length = env.read_register(&a1);
+            let output = env.read_register(&a2);
+            let is_last = env.is_zero(&length);
+            let absorbed = {
                 let pos = env.alloc_scratch();
-                unsafe { env.mul_lo_signed(&rs1, &rs2, pos) }
+                unsafe { env.request_keccak(&addr, &length, &output, pos) }
             };
-            env.write_register(&rd, res);
-
-            env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            lookup_keccak_precompile(env, &absorbed, &is_last);
+            env.write_register(&a0, addr + absorbed.clone());
+            env.write_register(&a1, length - absorbed);
+            // The system call is executed again until the input is absorbed
+            env.set_instruction_pointer(
+                is_last.clone() * next_instruction_pointer.clone()
+                    + (Env::constant(1) - is_last.clone()) * instruction_pointer,
+            );
+            env.set_next_instruction_pointer(
+                next_instruction_pointer + is_last * Env::constant(4u64),
+            );
+            return;
         }
-        MInstruction::Mulh => {
-            // x[rd] = (signed(x[rs1]) * signed(x[rs2])) >> 32
-            let rs1 = env.read_register(&rs1);
-            let rs2 = env.read_register(&rs2);
-            // FIXME: constrain
-            let res = {
+        SyscallInstruction::SyscallPoseidon => {
+            let addr = env.read_register(&a0);
+            let index = {
+                let pos = env.alloc_scratch();
+                unsafe { env.request_poseidon(&addr, pos) }
+            };
+            lookup_poseidon_precompile(env, index);
+        }
+        SyscallInstruction::SyscallPrecompile => {
+            let syscall = env.read_register(&Env::constant(REGISTER_A7));
+            let input = env.read_register(&a0);
+            let output = env.read_register(&a1);
+            let index = {
+                let pos = env.alloc_scratch();
+                unsafe { env.request_precompile(&syscall, &input, &output, pos) }
+            };
+            // COMMUNICATION CHANNEL: Write the call to the precompile
+            // FIXME: the input and the output are not bound to the memory
+            env.add_lookup(Lookup::write_one(
+                LookupTableIDs::PrecompileLookup,
+                vec![syscall, index, input, output],
+            ));
+        }
+        SyscallInstruction::SyscallBrk => {
+            let requested_break = env.read_register(&a0);
+            let is_query = env.is_zero(&requested_break);
+            let idx = Env::constant(REGISTER_PROGRAM_BREAK as u64);
+            let old_break = {
+                let value_location = env.alloc_scratch();
+                unsafe { env.fetch_register(&idx, value_location) }
+            };
+            let new_break = {
+                let pos = env.alloc_scratch();
+                env.copy(
+                    &(is_query.clone() * old_break.clone()
+                        + (Env::constant(1) - is_query) * requested_break),
+                    pos,
+                )
+            };
+            unsafe {
+                env.access_register(&idx, &old_break, &new_break);
+            };
+            unsafe {
+                env.push_register(&idx, new_break.clone());
+            };
+            env.write_register(&a0, new_break);
+        }
+        SyscallInstruction::SyscallMmap => {
+            let requested_alloc_size = env.read_register(&a1);
+            let size_in_pages = {
+                // FIXME: Requires a range check
+                let pos = env.alloc_scratch();
+                unsafe { env.bitmask(&requested_alloc_size, 32, PAGE_ADDRESS_SIZE, pos) }
+            };
+            let requires_extra_page = {
+                let remainder = requested_alloc_size
+                    - (size_in_pages.clone() * Env::constant(1 << PAGE_ADDRESS_SIZE));
+                Env::constant(1) - env.is_zero(&remainder)
+            };
+            let actual_alloc_size =
+                (size_in_pages + requires_extra_page) * Env::constant(1 << PAGE_ADDRESS_SIZE);
+            let address = env.read_register(&a0);
+            let address_is_zero = env.is_zero(&address);
+            let old_heap_ptr = env.increase_heap_pointer(&actual_alloc_size, &address_is_zero);
+            let return_position = {
+                let pos = env.alloc_scratch();
+                env.copy(
+                    &(address_is_zero.clone() * old_heap_ptr
+                        + (Env::constant(1) - address_is_zero) * address),
+                    pos,
+                )
+            };
+            env.write_register(&a0, return_position);
+        }
+        SyscallInstruction::SyscallClockGettime => {
+            // struct timespec { int64_t tv_sec; int64_t tv_nsec; }
+            let address = env.read_register(&a1);
+            for i in 0..16 {
+                env.write_memory(&(address.clone() + Env::constant(i)), Env::constant(0));
+            }
+            env.write_register(&a0, Env::constant(0));
+        }
+        SyscallInstruction::SyscallGetRandom => {
+            let address = env.read_register(&a0);
+            let length = env.read_register(&a1);
+            let mut written_length = Env::constant(0);
+            for i in 0..GETRANDOM_MAX_LENGTH {
+                // FIXME: Requires constraints
+                let is_written = {
+                    let pos = env.alloc_scratch();
+                    unsafe { env.test_less_than(&Env::constant(i as u64), &length, pos) }
+                };
+                let byte_address = address.clone() + Env::constant(i as u64);
+                let old_value = {
+                    let value_location = env.alloc_scratch();
+                    unsafe { env.fetch_memory(&byte_address, value_location) }
+                };
+                let random_byte = {
+                    let pos = env.alloc_scratch();
+                    unsafe { env.random_byte(&is_written, pos) }
+                };
+                env.lookup_8bits(&random_byte);
+                // The bytes past the requested length are left unchanged
+                let new_value = {
+                    let pos = env.alloc_scratch();
+                    env.copy(
+                        &(is_written.clone() * random_byte
+                            + (Env::constant(1) - is_written.clone()) * old_value.clone()),
+                        pos,
+                    )
+                };
+                unsafe {
+                    env.access_memory(&byte_address, &old_value, &new_value);
+                };
+                unsafe {
+                    env.push_memory(&byte_address, new_value);
+                };
+                written_length = written_length + is_written;
+            }
+            env.write_register(&a0, written_length);
+        }
+        SyscallInstruction::SyscallReadStdin => {
+            let addr = env.read_register(&a1);
+            let length = env.read_register(&a2);
+            let read_length = {
+                let pos = env.alloc_scratch();
+                env.request_stdin_write(&addr, &length, pos)
+            };
+            env.write_register(&a0, read_length);
+        }
+        SyscallInstruction::SyscallWriteOutput => {
+            let fd = env.read_register(&a0);
+            let addr = env.read_register(&a1);
+            let length = env.read_register(&a2);
+            env.request_output_write(&fd, &addr, &length);
+            env.write_register(&a0, length);
+        }
+        SyscallInstruction::SyscallReadHint => {
+            // We don't really write here, since the value is unused, per the cannon
+            // implementation. Just claim that we wrote the correct length.
+            let length = env.read_register(&a2);
+            env.write_register(&a0, length);
+        }
+        SyscallInstruction::SyscallReadPreimage => {
+            let addr = env.read_register(&a1);
+            let length = env.read_register(&a2);
+            let preimage_offset =
+                env.read_register(&Env::constant(REGISTER_PREIMAGE_OFFSET as u64));
+
+            let read_length = {
+                let pos = env.alloc_scratch();
+                env.request_preimage_write(&addr, &length, pos)
+            };
+            env.write_register(
+                &Env::constant(REGISTER_PREIMAGE_OFFSET as u64),
+                preimage_offset + read_length.clone(),
+            );
+            env.write_register(&a0, read_length);
+        }
+        SyscallInstruction::SyscallReadAdvice => {
+            let addr = env.read_register(&a1);
+            let length = env.read_register(&a2);
+            // FIXME: the advice is not constrained, the program must check it
+            let read_length = {
+                let pos = env.alloc_scratch();
+                env.request_advice_write(&addr, &length, pos)
+            };
+            env.write_register(&a0, read_length);
+        }
+        SyscallInstruction::SyscallWriteHint => {
+            let addr = env.read_register(&a1);
+            let length = env.read_register(&a2);
+            env.request_hint_write(&addr, &length);
+            env.write_register(&a0, length);
+        }
+        SyscallInstruction::SyscallWritePreimage => {
+            let addr = env.read_register(&a1);
+            let write_length = env.read_register(&a2);
+
+            // Cannon assumes that the remaining `byte_length` represents how much remains to be
+            // read (i.e. all write calls send the full data in one syscall, and attempt to retry
+            // with the rest until there is a success). This also simplifies the implementation
+            // here, so we will follow suit.
+            // The key has 32 bytes, so `32 - write_length` bytes have already been written, and
+            // the next byte goes to the register `KEY_END - ceil(write_length / 4)`, after the
+            // `(4 - write_length % 4) % 4` bytes already written in it.
+            let bytes_left_in_register = {
+                let pos = env.alloc_scratch();
+                unsafe { env.bitmask(&write_length, 2, 0, pos) }
+            };
+            env.lookup_2bits(&bytes_left_in_register);
+            let starts_register = env.is_zero(&bytes_left_in_register);
+            let bytes_to_preserve_in_register = {
+                let pos = env.alloc_scratch();
+                env.copy(
+                    &((Env::constant(1) - starts_register.clone())
+                        * (Env::constant(4) - bytes_left_in_register)),
+                    pos,
+                )
+            };
+            let register_idx = {
+                let registers_left_to_write_after_this = {
+                    let pos = env.alloc_scratch();
+                    // The virtual register is 32 bits wide, so we can just read 6 bytes. If the
+                    // register has an incorrect value, it will be unprovable and we'll fault.
+                    unsafe { env.bitmask(&write_length, 6, 2, pos) }
+                };
+                env.range_check8(&registers_left_to_write_after_this, 4);
+                Env::constant(REGISTER_PREIMAGE_KEY_END as u64)
+                    - registers_left_to_write_after_this
+                    - (Env::constant(1) - starts_register.clone())
+            };
+
+            let [r0, r1, r2, r3] = {
+                let register_value = {
+                    let initial_register_value = env.read_register(&register_idx);
+
+                    // We should clear the register if our offset into the read will replace all of its
+                    // bytes.
+                    let should_clear_register = starts_register;
+
+                    let pos = env.alloc_scratch();
+                    env.copy(
+                        &((Env::constant(1) - should_clear_register) * initial_register_value),
+                        pos,
+                    )
+                };
+                [
+                    {
+                        let pos = env.alloc_scratch();
+                        unsafe { env.bitmask(&register_value, 32, 24, pos) }
+                    },
+                    {
+                        let pos = env.alloc_scratch();
+                        unsafe { env.bitmask(&register_value, 24, 16, pos) }
+                    },
+                    {
+                        let pos = env.alloc_scratch();
+                        unsafe { env.bitmask(&register_value, 16, 8, pos) }
+                    },
+                    {
+                        let pos = env.alloc_scratch();
+                        unsafe { env.bitmask(&register_value, 8, 0, pos) }
+                    },
+                ]
+            };
+            env.lookup_8bits(&r0);
+            env.lookup_8bits(&r1);
+            env.lookup_8bits(&r2);
+            env.lookup_8bits(&r3);
+
+            // We choose our read address so that the bytes we read come aligned with the target
+            // bytes in the register, to avoid an expensive bitshift.
+            let read_address = addr.clone() - bytes_to_preserve_in_register.clone();
+
+            let m0 = env.read_memory(&read_address);
+            let m1 = env.read_memory(&(read_address.clone() + Env::constant(1)));
+            let m2 = env.read_memory(&(read_address.clone() + Env::constant(2)));
+            let m3 = env.read_memory(&(read_address.clone() + Env::constant(3)));
+
+            // Now, for some complexity. From the perspective of the write operation, we should be
+            // reading the `4 - bytes_to_preserve_in_register`. However, to match cannon 1:1, we
+            // only want to read the bytes up to the end of the current word.
+            let [overwrite_0, overwrite_1, overwrite_2, overwrite_3] = {
+                let next_word_addr = {
+                    let byte_subaddr = {
+                        // FIXME: Requires a range check
+                        let pos = env.alloc_scratch();
+                        unsafe { env.bitmask(&addr, 2, 0, pos) }
+                    };
+                    env.lookup_2bits(&byte_subaddr);
+                    addr.clone() + Env::constant(4) - byte_subaddr
+                };
+                let overwrite_0 = {
+                    // We always write the first byte if we're not preserving it, since it will
+                    // have been read from `addr`.
+                    env.equal(&bytes_to_preserve_in_register, &Env::constant(0))
+                };
+                let overwrite_1 = {
+                    // We write the second byte if:
+                    //   we wrote the first byte
+                    overwrite_0.clone()
+                    //   and this isn't the start of the next word (which implies `overwrite_0`),
+                    - env.equal(&(read_address.clone() + Env::constant(1)), &next_word_addr)
+                    //   or this byte was read from `addr`
+                    + env.equal(&bytes_to_preserve_in_register, &Env::constant(1))
+                };
+                let overwrite_2 = {
+                    // We write the third byte if:
+                    //   we wrote the second byte
+                    overwrite_1.clone()
+                    //   and this isn't the start of the next word (which implies `overwrite_1`),
+                    - env.equal(&(read_address.clone() + Env::constant(2)), &next_word_addr)
+                    //   or this byte was read from `addr`
+                    + env.equal(&bytes_to_preserve_in_register, &Env::constant(2))
+                };
+                let overwrite_3 = {
+                    // We write the fourth byte if:
+                    //   we wrote the third byte
+                    overwrite_2.clone()
+                    //   and this isn't the start of the next word (which implies `overwrite_2`),
+                    - env.equal(&(read_address.clone() + Env::constant(3)), &next_word_addr)
+                    //   or this byte was read from `addr`
+                    + env.equal(&bytes_to_preserve_in_register, &Env::constant(3))
+                };
+                [overwrite_0, overwrite_1, overwrite_2, overwrite_3]
+            };
+
+            let value = {
+                let value = ((overwrite_0.clone() * m0
+                    + (Env::constant(1) - overwrite_0.clone()) * r0)
+                    * Env::constant(1 << 24))
+                    + ((overwrite_1.clone() * m1 + (Env::constant(1) - overwrite_1.clone()) * r1)
+                        * Env::constant(1 << 16))
+                    + ((overwrite_2.clone() * m2 + (Env::constant(1) - overwrite_2.clone()) * r2)
+                        * Env::constant(1 << 8))
+                    + (overwrite_3.clone() * m3 + (Env::constant(1) - overwrite_3.clone()) * r3);
+                let pos = env.alloc_scratch();
+                env.copy(&value, pos)
+            };
+
+            // Update the preimage key.
+            env.write_register(&register_idx, value);
+            // Reset the preimage offset.
+            env.write_register(
+                &Env::constant(REGISTER_PREIMAGE_OFFSET as u64),
+                Env::constant(0u64),
+            );
+            // Return the number of bytes read.
+            env.write_register(&a0, overwrite_0 + overwrite_1 + overwrite_2 + overwrite_3);
+        }
+        SyscallInstruction::SyscallSuccess | SyscallInstruction::SyscallExitGroup => {
+            unreachable!()
+        }
+    }
+
+    env.set_instruction_pointer(next_instruction_pointer.clone());
+    env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
+}
+
+/// Binds the bytes absorbed by an execution of the Keccak precompile, and the
+/// digest written by the last one, to the Keccak sub-trace through the
+/// [LookupTableIDs::SyscallLookup] table, as for the preimages of the MIPS
+/// interpreter.
+// FIXME: the bytes are not bound to the memory, and their number to the
+// length of the input
+fn lookup_keccak_precompile<Env: InterpreterEnv>(
+    env: &mut Env,
+    absorbed: &Env::Variable,
+    is_last: &Env::Variable,
+) {
+    let hint = |env: &mut Env, hint| {
+        let pos = env.alloc_scratch();
+        unsafe { env.keccak_hint(hint, pos) }
+    };
+    let hash_counter = hint(env, KeccakHint::HashCounter);
+    let byte_counter = hint(env, KeccakHint::ByteCounter);
+    let mut num_bytes = Env::constant(0);
+    for i in 0..KECCAK_PRECOMPILE_CHUNK_LEN {
+        let byte = hint(env, KeccakHint::Byte(i));
+        let has_byte = hint(env, KeccakHint::HasByte(i));
+        env.assert_boolean(&has_byte);
+        env.lookup_8bits(&byte);
+        // COMMUNICATION CHANNEL: Write the byte of the input
+        env.add_lookup(Lookup::write_if(
+            has_byte.clone(),
+            LookupTableIDs::SyscallLookup,
+            vec![
+                hash_counter.clone(),
+                byte_counter.clone() + Env::constant(i as u64),
+                byte,
+            ],
+        ));
+        num_bytes = num_bytes + has_byte;
+    }
+    env.assert_equal(num_bytes, absorbed.clone());
+    // COMMUNICATION CHANNEL: Read the digest
+    let digest = hint(env, KeccakHint::Digest);
+    env.add_lookup(Lookup::read_if(
+        is_last.clone(),
+        LookupTableIDs::SyscallLookup,
+        vec![hash_counter, digest],
+    ));
+}
+
+/// Binds the input and the output of the permutation of index `index`,
+/// computed by the Poseidon precompile, to the Poseidon sub-trace through the
+/// [LookupTableIDs::PoseidonLookup] table.
+// FIXME: the input and the output are not bound to the memory
+fn lookup_poseidon_precompile<Env: InterpreterEnv>(env: &mut Env, index: Env::Variable) {
+    let mut hints = |hint: fn(usize) -> PoseidonHint| -> Vec<Env::Variable> {
+        (0..POSEIDON_STATE_SIZE)
+            .map(|i| {
+                let pos = env.alloc_scratch();
+                unsafe { env.poseidon_hint(hint(i), pos) }
+            })
+            .collect()
+    };
+    let input = hints(PoseidonHint::Input);
+    let output = hints(PoseidonHint::Output);
+    // COMMUNICATION CHANNEL: Write the input of the permutation
+    env.add_lookup(Lookup::write_one(
+        LookupTableIDs::PoseidonLookup,
+        [vec![index.clone(), Env::constant(0)], input].concat(),
+    ));
+    // COMMUNICATION CHANNEL: Read the output of the permutation
+    env.add_lookup(Lookup::read_one(
+        LookupTableIDs::PoseidonLookup,
+        [vec![index, Env::constant(1)], output].concat(),
+    ));
+}
+
+/// Interpret an M-type instruction.
+/// The encoding of an M-type instruction is as follows:
+/// ```text
+/// | 31     27 | 26    25 | 24     20 | 19     15 | 14        12 | 11    7 | 6      0 |
+/// |   00000   |    01    |    rs2    |    rs1    |    funct3    |    rd   |  opcode  |
+/// ```
+/// Following the documentation found
+/// [here](https://www.cs.cornell.edu/courses/cs3410/2024fa/assignments/cpusim/riscv-instructions.pdf)
+pub fn interpret_mtype<Env: InterpreterEnv>(env: &mut Env, instr: MInstruction) {
+    let instruction_pointer = env.get_instruction_pointer();
+    let next_instruction_pointer = env.get_next_instruction_pointer();
+
+    let instruction = fetch_instruction(env, &instruction_pointer);
+    // The M-type instructions use the encoding of the R-type instructions
+    // FIXME: check funct2 is equal to 01 and funct5 to 00000?
+    let (rd, rs1, rs2) = decode_rtype(env, &instruction);
+
+    match instr {
+        MInstruction::Mul => {
+            // x[rd] = x[rs1] * x[rs2]
+            let rs1 = env.read_register(&rs1);
+            let rs2 = env.read_register(&rs2);
+            // FIXME: constrain
+            let res = {
+                let pos = env.alloc_scratch();
+                unsafe { env.mul_lo_signed(&rs1, &rs2, pos) }
+            };
+            env.write_register(&rd, res);
+
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
+        }
+        MInstruction::Mulh => {
+            // x[rd] = (signed(x[rs1]) * signed(x[rs2])) >> XLEN
+            let rs1 = env.read_register(&rs1);
+            let rs2 = env.read_register(&rs2);
+            // FIXME: constrain
+            let res = {
                 let pos = env.alloc_scratch();
                 unsafe { env.mul_hi_signed(&rs1, &rs2, pos) }
             };
             env.write_register(&rd, res);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         MInstruction::Mulhsu => {
-            // x[rd] = (signed(x[rs1]) * x[rs2]) >> 32
+            // x[rd] = (signed(x[rs1]) * x[rs2]) >> XLEN
             let rs1 = env.read_register(&rs1);
             let rs2 = env.read_register(&rs2);
             // FIXME: constrain
@@ -2692,10 +3750,10 @@ pub fn interpret_mtype<Env: InterpreterEnv>(env: &mut Env, instr: MInstruction)
             env.write_register(&rd, res);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         MInstruction::Mulhu => {
-            // x[rd] = (x[rs1] * x[rs2]) >> 32
+            // x[rd] = (x[rs1] * x[rs2]) >> XLEN
             let rs1 = env.read_register(&rs1);
             let rs2 = env.read_register(&rs2);
             // FIXME: constrain
@@ -2706,63 +3764,1081 @@ pub fn interpret_mtype<Env: InterpreterEnv>(env: &mut Env, instr: MInstruction)
             env.write_register(&rd, res);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         MInstruction::Div => {
             // x[rd] = signed(x[rs1]) / signed(x[rs2])
             let rs1 = env.read_register(&rs1);
             let rs2 = env.read_register(&rs2);
-            // FIXME: constrain
-            let res = {
-                let pos = env.alloc_scratch();
-                unsafe { env.div_signed(&rs1, &rs2, pos) }
-            };
-            env.write_register(&rd, res);
+            let (quotient, _remainder) = signed_division(env, &rs1, &rs2);
+            env.write_register(&rd, quotient);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         MInstruction::Divu => {
             // x[rd] = x[rs1] / x[rs2]
             let rs1 = env.read_register(&rs1);
             let rs2 = env.read_register(&rs2);
-            // FIXME: constrain
-            let res = {
-                let pos = env.alloc_scratch();
-                unsafe { env.div(&rs1, &rs2, pos) }
-            };
-            env.write_register(&rd, res);
+            let (quotient, _remainder) = unsigned_division(env, &rs1, &rs2);
+            env.write_register(&rd, quotient);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         MInstruction::Rem => {
             // x[rd] = signed(x[rs1]) % signed(x[rs2])
             let rs1 = env.read_register(&rs1);
             let rs2 = env.read_register(&rs2);
-            // FIXME: constrain
-            let res = {
-                let pos = env.alloc_scratch();
-                unsafe { env.mod_signed(&rs1, &rs2, pos) }
-            };
-            env.write_register(&rd, res);
+            let (_quotient, remainder) = signed_division(env, &rs1, &rs2);
+            env.write_register(&rd, remainder);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
         MInstruction::Remu => {
             // x[rd] = x[rs1] % x[rs2]
             let rs1 = env.read_register(&rs1);
             let rs2 = env.read_register(&rs2);
-            // FIXME: constrain
-            let res = {
-                let pos = env.alloc_scratch();
-                unsafe { env.mod_unsigned(&rs1, &rs2, pos) }
-            };
-            env.write_register(&rd, res);
+            let (_quotient, remainder) = unsigned_division(env, &rs1, &rs2);
+            env.write_register(&rd, remainder);
 
             env.set_instruction_pointer(next_instruction_pointer.clone());
-            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
         }
     }
 }
+
+/// Decomposes the XLEN bits of `x` into 16-bit limbs, the least significant
+/// first, the last limb having `last_limb_bits` bits, and returns the value
+/// recomposed from the limbs.
+fn decompose_16bits_limbs<Env: InterpreterEnv>(
+    env: &mut Env,
+    x: &Env::Variable,
+    highest_bit: u32,
+    last_limb_bits: u32,
+) -> Env::Variable {
+    let mut lowest_bit = 0;
+    let mut limbs = vec![];
+    while lowest_bit < highest_bit {
+        let bits = if lowest_bit + 16 >= highest_bit {
+            last_limb_bits
+        } else {
+            16
+        };
+        let limb = {
+            let pos = env.alloc_scratch();
+            unsafe { env.bitmask(x, lowest_bit + bits, lowest_bit, pos) }
+        };
+        env.range_check16(&limb, bits);
+        limbs.push((limb, lowest_bit));
+        lowest_bit += bits;
+    }
+    limbs
+        .into_iter()
+        .map(|(limb, lowest_bit)| {
+            if lowest_bit == 0 {
+                limb
+            } else {
+                limb * Env::constant(1 << lowest_bit)
+            }
+        })
+        .rev()
+        .reduce(|acc, limb| acc + limb)
+        .unwrap()
+}
+
+/// Constrains `x` to be a XLEN-bit value, by decomposing it into 16-bit
+/// limbs.
+fn range_check_xlen<Env: InterpreterEnv>(env: &mut Env, x: &Env::Variable) {
+    let recomposed = decompose_16bits_limbs(env, x, Env::Xlen::BITS, 16);
+    env.assert_equal(x.clone(), recomposed);
+}
+
+/// Constrains `x` to be a XLEN-bit value, and returns its sign bit, by
+/// decomposing it into the sign bit, a 15-bit limb and 16-bit limbs.
+fn sign_bit<Env: InterpreterEnv>(env: &mut Env, x: &Env::Variable) -> Env::Variable {
+    let sign_position = Env::Xlen::BITS - 1;
+    let low = decompose_16bits_limbs(env, x, sign_position, 15);
+    let sign = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(x, sign_position + 1, sign_position, pos) }
+    };
+    env.assert_boolean(&sign);
+    env.assert_equal(
+        x.clone(),
+        sign.clone() * Env::constant(1 << sign_position) + low,
+    );
+    sign
+}
+
+/// Returns the absolute value of the signed XLEN-bit value `x` with sign bit
+/// `sign`, that is `x` if `sign` is 0, or `2^XLEN - x` otherwise.
+// The terms are ordered so that the witness never goes below zero.
+fn absolute_value<Env: InterpreterEnv>(
+    env: &mut Env,
+    x: &Env::Variable,
+    sign: &Env::Variable,
+) -> Env::Variable {
+    let two_to_xlen = Env::constant(Env::Xlen::MASK) + Env::constant(1);
+    let pos = env.alloc_scratch();
+    env.copy(
+        &((x.clone() + sign.clone() * two_to_xlen) - sign.clone() * x.clone() * Env::constant(2)),
+        pos,
+    )
+}
+
+/// Constrains `bound` to be the divisor minus the remainder minus one when
+/// the divisor is not zero, and zero otherwise, and range checks it, so that
+/// the remainder is strictly lower than the divisor.
+fn constrain_remainder_bound<Env: InterpreterEnv>(
+    env: &mut Env,
+    divisor: &Env::Variable,
+    remainder: &Env::Variable,
+    divisor_is_zero: &Env::Variable,
+) {
+    // bound = (1 - divisor_is_zero) * (divisor - remainder - 1), with the
+    // terms ordered so that the witness never goes below zero
+    let one = Env::constant(1);
+    let bound = {
+        let pos = env.alloc_scratch();
+        env.copy(
+            &((divisor.clone() + divisor_is_zero.clone() * (remainder.clone() + one.clone()))
+                - (remainder.clone() + one + divisor_is_zero.clone() * divisor.clone())),
+            pos,
+        )
+    };
+    range_check_xlen(env, &bound);
+}
+
+/// Returns the quotient and the remainder of the unsigned division of `x` by
+/// `y`, as specified by the M extension: the division by zero returns the
+/// quotient `2^XLEN - 1` and the remainder `x`.
+pub(crate) fn unsigned_division<Env: InterpreterEnv>(
+    env: &mut Env,
+    x: &Env::Variable,
+    y: &Env::Variable,
+) -> (Env::Variable, Env::Variable) {
+    let quotient = {
+        let pos = env.alloc_scratch();
+        unsafe { env.div(x, y, pos) }
+    };
+    range_check_xlen(env, &quotient);
+    let remainder = {
+        let pos = env.alloc_scratch();
+        unsafe { env.mod_unsigned(x, y, pos) }
+    };
+    range_check_xlen(env, &remainder);
+
+    // x = quotient * y + remainder, which also holds for the division by zero
+    env.assert_equal(x.clone(), quotient.clone() * y.clone() + remainder.clone());
+
+    let y_is_zero = env.is_zero(y);
+    constrain_remainder_bound(env, y, &remainder, &y_is_zero);
+    env.assert_equal(
+        y_is_zero.clone() * quotient.clone(),
+        y_is_zero.clone() * Env::constant(Env::Xlen::MASK),
+    );
+    env.assert_equal(y_is_zero.clone() * remainder.clone(), y_is_zero * x.clone());
+    (quotient, remainder)
+}
+
+/// Returns the quotient and the remainder of the signed division of `x` by
+/// `y`, rounding the quotient towards zero, as specified by the M extension:
+/// the division by zero returns the quotient `-1` and the remainder `x`, and
+/// the division of `-2^(XLEN - 1)` by `-1` overflows, returning the quotient
+/// `-2^(XLEN - 1)` and the remainder 0.
+///
+/// The absolute values verify `|x| = |quotient| * |y| + |remainder|`, in all
+/// the cases, and `|remainder| < |y|` when `y` is not zero. The sign of the
+/// remainder is the sign of `x`, and the sign of the quotient the product of
+/// the signs of `x` and `y`, except in the corner cases which are constrained
+/// explicitly.
+pub(crate) fn signed_division<Env: InterpreterEnv>(
+    env: &mut Env,
+    x: &Env::Variable,
+    y: &Env::Variable,
+) -> (Env::Variable, Env::Variable) {
+    let quotient = {
+        let pos = env.alloc_scratch();
+        unsafe { env.div_signed(x, y, pos) }
+    };
+    let remainder = {
+        let pos = env.alloc_scratch();
+        unsafe { env.mod_signed(x, y, pos) }
+    };
+    let x_sign = sign_bit(env, x);
+    let y_sign = sign_bit(env, y);
+    let quotient_sign = sign_bit(env, &quotient);
+    let remainder_sign = sign_bit(env, &remainder);
+    let x_abs = absolute_value(env, x, &x_sign);
+    let y_abs = absolute_value(env, y, &y_sign);
+    let quotient_abs = absolute_value(env, &quotient, &quotient_sign);
+    let remainder_abs = absolute_value(env, &remainder, &remainder_sign);
+
+    env.assert_equal(
+        x_abs,
+        quotient_abs.clone() * y_abs.clone() + remainder_abs.clone(),
+    );
+
+    let y_is_zero = env.is_zero(y);
+    constrain_remainder_bound(env, &y_abs, &remainder_abs, &y_is_zero);
+    env.assert_equal(
+        y_is_zero.clone() * quotient.clone(),
+        y_is_zero.clone() * Env::constant(Env::Xlen::MASK),
+    );
+    env.assert_equal(
+        y_is_zero.clone() * remainder.clone(),
+        y_is_zero.clone() * x.clone(),
+    );
+
+    // -2^(XLEN - 1) / -1
+    let min = Env::constant(1 << (Env::Xlen::BITS - 1));
+    let overflow = {
+        let x_is_min = env.equal(x, &min);
+        let y_is_minus_one = env.equal(y, &Env::constant(Env::Xlen::MASK));
+        let pos = env.alloc_scratch();
+        env.copy(&(x_is_min * y_is_minus_one), pos)
+    };
+    env.assert_equal(overflow.clone() * quotient.clone(), overflow.clone() * min);
+    env.assert_is_zero(overflow.clone() * remainder.clone());
+
+    // A non-zero remainder has the sign of x
+    env.assert_equal(
+        remainder_abs.clone() * remainder_sign,
+        remainder_abs * x_sign.clone(),
+    );
+
+    // A non-zero quotient has the sign x_sign xor y_sign, outside of the
+    // corner cases
+    let expected_quotient_sign = {
+        let pos = env.alloc_scratch();
+        env.copy(
+            &((x_sign.clone() + y_sign.clone()) - x_sign * y_sign * Env::constant(2)),
+            pos,
+        )
+    };
+    let is_not_corner_case = {
+        let pos = env.alloc_scratch();
+        env.copy(
+            &((Env::constant(1) - y_is_zero) * (Env::constant(1) - overflow)),
+            pos,
+        )
+    };
+    env.assert_equal(
+        quotient_abs.clone() * is_not_corner_case.clone() * quotient_sign,
+        quotient_abs * is_not_corner_case * expected_quotient_sign,
+    );
+    (quotient, remainder)
+}
+
+/// Decompose the compressed instruction `instruction` into the fields given as
+/// pairs `(highest_bit, lowest_bit)`, the bits not covered by the fields being
+/// constrained to be the bits of `encoding`. The fixed bits of the encoding of
+/// an instruction (quadrant, funct3, etc.) are therefore checked without
+/// allocating a column.
+fn decompose_compressed<Env: InterpreterEnv, const N: usize>(
+    env: &mut Env,
+    instruction: &Env::Variable,
+    encoding: u32,
+    fields: [(u32, u32); N],
+) -> [Env::Variable; N] {
+    let fields = fields.map(|(highest_bit, lowest_bit)| {
+        let field = {
+            let pos = env.alloc_scratch();
+            unsafe { env.bitmask(instruction, highest_bit, lowest_bit, pos) }
+        };
+        if highest_bit - lowest_bit == 1 {
+            env.assert_boolean(&field);
+        } else {
+            env.range_check8(&field, highest_bit - lowest_bit);
+        }
+        (field, lowest_bit)
+    });
+    // Check correctness of decomposition
+    env.add_constraint(fields.iter().fold(
+        instruction.clone() - Env::constant(encoding as u64),
+        |acc, (field, lowest_bit)| acc - field.clone() * Env::constant(1 << lowest_bit),
+    ));
+    fields.map(|(field, _)| field)
+}
+
+/// Read the value of `bytes` bytes stored at `address`, with the same byte
+/// order as [IInstruction::LoadWord], i.e. the most significant byte first
+pub(crate) fn load_bytes<Env: InterpreterEnv>(
+    env: &mut Env,
+    address: &Env::Variable,
+    bytes: u32,
+) -> Env::Variable {
+    (0..bytes)
+        .map(|i| {
+            let byte = env.read_memory(&(address.clone() + Env::constant(i as u64)));
+            let lowest_bit = 8 * (bytes - 1 - i);
+            if lowest_bit == 0 {
+                byte
+            } else {
+                byte * Env::constant(1 << lowest_bit)
+            }
+        })
+        .reduce(|acc, byte| acc + byte)
+        .unwrap()
+}
+
+/// Write the value `value` of `bytes` bytes at `address`, with the same byte
+/// order as [SInstruction::StoreWord], i.e. the most significant byte first
+pub(crate) fn store_bytes<Env: InterpreterEnv>(
+    env: &mut Env,
+    address: &Env::Variable,
+    value: &Env::Variable,
+    bytes: u32,
+) {
+    for i in 0..bytes {
+        let lowest_bit = 8 * (bytes - 1 - i);
+        let byte = {
+            let value_scratch = env.alloc_scratch();
+            unsafe { env.bitmask(value, lowest_bit + 8, lowest_bit, value_scratch) }
+        };
+        env.lookup_8bits(&byte);
+        env.write_memory(&(address.clone() + Env::constant(i as u64)), byte);
+    }
+}
+
+/// Interpret a compressed instruction, i.e. an instruction of the C extension.
+/// The compressed instructions are encoded on 16 bits, using the formats:
+/// ```text
+/// | 15  13 | 12 | 11       7 | 6       2 | 1  0 |
+/// | funct4      | rd/rs1     |    rs2    |  op  |  CR
+/// | funct3 |imm | rd/rs1     |    imm    |  op  |  CI
+/// | funct3 |        imm      |    rs2    |  op  |  CSS
+/// | funct3 |        imm            | rd' |  op  |  CIW
+/// | funct3 |  imm    | rs1'  |  imm| rd' |  op  |  CL
+/// | funct3 |  imm    | rs1'  |  imm| rs2'|  op  |  CS
+/// | funct6          | rd'/rs1'|fn2 | rs2'|  op  |  CA
+/// | funct3 | offset  | rs1'  |  offset   |  op  |  CB
+/// | funct3 |        jump target        |  op  |  CJ
+/// ```
+/// where `op` is the quadrant of the instruction, which is never `11`.
+///
+/// The instruction pointer is increased by 2. As the next instruction pointer
+/// is expected to be the instruction pointer plus 4 by the other instructions,
+/// it is set accordingly.
+pub fn interpret_ctype<Env: InterpreterEnv>(env: &mut Env, instr: CInstruction) {
+    let instruction_pointer = env.get_instruction_pointer();
+    // The next instruction pointer is only read to consume the current value
+    // of the register, as it is not used by the compressed instructions.
+    let _next_instruction_pointer = env.get_next_instruction_pointer();
+
+    let instruction = {
+        let v0 = env.read_memory(&instruction_pointer);
+        let v1 = env.read_memory(&(instruction_pointer.clone() + Env::constant(1)));
+        (v1 * Env::constant(1 << 8)) + v0
+    };
+
+    // Registers x8 to x15, encoded on 3 bits
+    let compressed_register = |r: Env::Variable| r + Env::constant(8);
+    // The encoding of the fixed bits of the instruction, given its quadrant
+    // and funct3
+    let encoding = |funct3: u32, quadrant: u32| (funct3 << 13) | quadrant;
+
+    match instr {
+        CInstruction::AddImmediate4StackPointer => {
+            // c.addi4spn: x[8 + rd'] = x[2] + nzuimm
+            let [rd, imm3, imm2, imm9_6, imm5_4] = decompose_compressed(
+                env,
+                &instruction,
+                encoding(0b000, 0b00),
+                [(5, 2), (6, 5), (7, 6), (11, 7), (13, 11)],
+            );
+            let imm = imm9_6 * Env::constant(1 << 6)
+                + imm5_4 * Env::constant(1 << 4)
+                + imm3 * Env::constant(1 << 3)
+                + imm2 * Env::constant(1 << 2);
+            let local_sp = env.read_register(&Env::constant(2));
+            let local_rd = {
+                let overflow_scratch = env.alloc_scratch();
+                let rd_scratch = env.alloc_scratch();
+                let (local_rd, _overflow) =
+                    unsafe { env.add_witness(&local_sp, &imm, rd_scratch, overflow_scratch) };
+                local_rd
+            };
+            env.write_register(&compressed_register(rd), local_rd);
+        }
+        CInstruction::LoadWord => {
+            // c.lw: x[8 + rd'] = M[x[8 + rs1'] + uimm][31:0]
+            let [rd, imm6, imm2, rs1, imm5_3] = decompose_compressed(
+                env,
+                &instruction,
+                encoding(0b010, 0b00),
+                [(5, 2), (6, 5), (7, 6), (10, 7), (13, 10)],
+            );
+            let offset = imm6 * Env::constant(1 << 6)
+                + imm5_3 * Env::constant(1 << 3)
+                + imm2 * Env::constant(1 << 2);
+            let base = env.read_register(&compressed_register(rs1));
+            let address = {
+                let address_scratch = env.alloc_scratch();
+                let overflow_scratch = env.alloc_scratch();
+                let (address, _overflow) =
+                    unsafe { env.add_witness(&base, &offset, address_scratch, overflow_scratch) };
+                address
+            };
+            let value = load_bytes(env, &address, 4);
+            env.write_register(&compressed_register(rd), value);
+        }
+        CInstruction::StoreWord => {
+            // c.sw: M[x[8 + rs1'] + uimm] = x[8 + rs2'][31:0]
+            let [rs2, imm6, imm2, rs1, imm5_3] = decompose_compressed(
+                env,
+                &instruction,
+                encoding(0b110, 0b00),
+                [(5, 2), (6, 5), (7, 6), (10, 7), (13, 10)],
+            );
+            let offset = imm6 * Env::constant(1 << 6)
+                + imm5_3 * Env::constant(1 << 3)
+                + imm2 * Env::constant(1 << 2);
+            let base = env.read_register(&compressed_register(rs1));
+            let address = {
+                let address_scratch = env.alloc_scratch();
+                let overflow_scratch = env.alloc_scratch();
+                let (address, _overflow) =
+                    unsafe { env.add_witness(&base, &offset, address_scratch, overflow_scratch) };
+                address
+            };
+            let value = env.read_register(&compressed_register(rs2));
+            store_bytes(env, &address, &value, 4);
+        }
+        CInstruction::AddImmediate | CInstruction::LoadImmediate => {
+            // c.addi: x[rd] = x[rd] + sext(imm)
+            // c.li: x[rd] = sext(imm)
+            let funct3 = if instr == CInstruction::AddImmediate {
+                0b000
+            } else {
+                0b010
+            };
+            let [imm4_0, rd, imm5] = decompose_compressed(
+                env,
+                &instruction,
+                encoding(funct3, 0b01),
+                [(7, 2), (12, 7), (13, 12)],
+            );
+            let imm = env.sign_extend(&(imm5 * Env::constant(1 << 5) + imm4_0), 6);
+            let local_rd = if instr == CInstruction::AddImmediate {
+                let local_rd = env.read_register(&rd);
+                let overflow_scratch = env.alloc_scratch();
+                let rd_scratch = env.alloc_scratch();
+                let (local_rd, _overflow) =
+                    unsafe { env.add_witness(&local_rd, &imm, rd_scratch, overflow_scratch) };
+                local_rd
+            } else {
+                imm
+            };
+            env.write_register(&rd, local_rd);
+        }
+        CInstruction::AddImmediate16StackPointer => {
+            // c.addi16sp: x[2] = x[2] + sext(nzimm)
+            let [imm5, imm8_7, imm6, imm4, imm9] = decompose_compressed(
+                env,
+                &instruction,
+                encoding(0b011, 0b01) | (2 << 7),
+                [(3, 2), (5, 3), (6, 5), (7, 6), (13, 12)],
+            );
+            let imm = imm9 * Env::constant(1 << 9)
+                + imm8_7 * Env::constant(1 << 7)
+                + imm6 * Env::constant(1 << 6)
+                + imm5 * Env::constant(1 << 5)
+                + imm4 * Env::constant(1 << 4);
+            let imm = env.sign_extend(&imm, 10);
+            let local_sp = env.read_register(&Env::constant(2));
+            let local_sp = {
+                let overflow_scratch = env.alloc_scratch();
+                let rd_scratch = env.alloc_scratch();
+                let (local_sp, _overflow) =
+                    unsafe { env.add_witness(&local_sp, &imm, rd_scratch, overflow_scratch) };
+                local_sp
+            };
+            env.write_register(&Env::constant(2), local_sp);
+        }
+        CInstruction::LoadUpperImmediate => {
+            // c.lui: x[rd] = sext(nzimm[17:12] << 12)
+            let [imm16_12, rd, imm17] = decompose_compressed(
+                env,
+                &instruction,
+                encoding(0b011, 0b01),
+                [(7, 2), (12, 7), (13, 12)],
+            );
+            let imm = imm17 * Env::constant(1 << 17) + imm16_12 * Env::constant(1 << 12);
+            let imm = env.sign_extend(&imm, 18);
+            env.write_register(&rd, imm);
+        }
+        CInstruction::ShiftRightLogicalImmediate | CInstruction::ShiftRightArithmeticImmediate => {
+            // c.srli: x[8 + rd'] = x[8 + rd'] >>u shamt
+            // c.srai: x[8 + rd'] = x[8 + rd'] >>s shamt
+            // The bit 12 is the highest bit of the shift amount, which must be
+            // 0 for RV32C.
+            let funct2 = if instr == CInstruction::ShiftRightLogicalImmediate {
+                0b00
+            } else {
+                0b01
+            };
+            let [shamt, rd] = decompose_compressed(
+                env,
+                &instruction,
+                encoding(0b100, 0b01) | (funct2 << 10),
+                [(7, 2), (10, 7)],
+            );
+            let rd = compressed_register(rd);
+            let local_rd = env.read_register(&rd);
+            let local_rd = {
+                let rd_scratch = env.alloc_scratch();
+                if instr == CInstruction::ShiftRightLogicalImmediate {
+                    unsafe { env.shift_right(&local_rd, &shamt, rd_scratch) }
+                } else {
+                    unsafe { env.shift_right_arithmetic(&local_rd, &shamt, rd_scratch) }
+                }
+            };
+            env.write_register(&rd, local_rd);
+        }
+        CInstruction::AndImmediate => {
+            // c.andi: x[8 + rd'] = x[8 + rd'] & sext(imm)
+            let [imm4_0, rd, imm5] = decompose_compressed(
+                env,
+                &instruction,
+                encoding(0b100, 0b01) | (0b10 << 10),
+                [(7, 2), (10, 7), (13, 12)],
+            );
+            let imm = env.sign_extend(&(imm5 * Env::constant(1 << 5) + imm4_0), 6);
+            let rd = compressed_register(rd);
+            let local_rd = env.read_register(&rd);
+            let local_rd = {
+                let rd_scratch = env.alloc_scratch();
+                unsafe { env.and_witness(&local_rd, &imm, rd_scratch) }
+            };
+            env.write_register(&rd, local_rd);
+        }
+        CInstruction::Sub | CInstruction::Xor | CInstruction::Or | CInstruction::And => {
+            // c.sub: x[8 + rd'] = x[8 + rd'] - x[8 + rs2']
+            // c.xor: x[8 + rd'] = x[8 + rd'] ^ x[8 + rs2']
+            // c.or: x[8 + rd'] = x[8 + rd'] | x[8 + rs2']
+            // c.and: x[8 + rd'] = x[8 + rd'] & x[8 + rs2']
+            let funct2 = match instr {
+                CInstruction::Sub => 0b00,
+                CInstruction::Xor => 0b01,
+                CInstruction::Or => 0b10,
+                _ => 0b11,
+            };
+            let [rs2, rd] = decompose_compressed(
+                env,
+                &instruction,
+                encoding(0b100, 0b01) | (0b11 << 10) | (funct2 << 5),
+                [(5, 2), (10, 7)],
+            );
+            let rd = compressed_register(rd);
+            let local_rd = env.read_register(&rd);
+            let local_rs2 = env.read_register(&compressed_register(rs2));
+            let local_rd = match instr {
+                CInstruction::Sub => {
+                    let underflow_scratch = env.alloc_scratch();
+                    let rd_scratch = env.alloc_scratch();
+                    let (local_rd, _underflow) = unsafe {
+                        env.sub_witness(&local_rd, &local_rs2, rd_scratch, underflow_scratch)
+                    };
+                    local_rd
+                }
+                CInstruction::Xor => {
+                    let rd_scratch = env.alloc_scratch();
+                    unsafe { env.xor_witness(&local_rd, &local_rs2, rd_scratch) }
+                }
+                CInstruction::Or => {
+                    let rd_scratch = env.alloc_scratch();
+                    unsafe { env.or_witness(&local_rd, &local_rs2, rd_scratch) }
+                }
+                _ => {
+                    let rd_scratch = env.alloc_scratch();
+                    unsafe { env.and_witness(&local_rd, &local_rs2, rd_scratch) }
+                }
+            };
+            env.write_register(&rd, local_rd);
+        }
+        CInstruction::JumpAndLink | CInstruction::Jump => {
+            // c.jal: x[1] = pc + 2; pc += sext(offset)
+            // c.j: pc += sext(offset)
+            let funct3 = if instr == CInstruction::JumpAndLink {
+                0b001
+            } else {
+                0b101
+            };
+            let [imm5, imm3_1, imm7, imm6, imm10, imm9_8, imm4, imm11] = decompose_compressed(
+                env,
+                &instruction,
+                encoding(funct3, 0b01),
+                [
+                    (3, 2),
+                    (6, 3),
+                    (7, 6),
+                    (8, 7),
+                    (9, 8),
+                    (11, 9),
+                    (12, 11),
+                    (13, 12),
+                ],
+            );
+            let offset = imm11 * Env::constant(1 << 11)
+                + imm10 * Env::constant(1 << 10)
+                + imm9_8 * Env::constant(1 << 8)
+                + imm7 * Env::constant(1 << 7)
+                + imm6 * Env::constant(1 << 6)
+                + imm5 * Env::constant(1 << 5)
+                + imm4 * Env::constant(1 << 4)
+                + imm3_1 * Env::constant(1 << 1);
+            let offset = env.sign_extend(&offset, 12);
+            let new_addr = {
+                let res_scratch = env.alloc_scratch();
+                let overflow_scratch = env.alloc_scratch();
+                let (res, _overflow) = unsafe {
+                    env.add_witness(&instruction_pointer, &offset, res_scratch, overflow_scratch)
+                };
+                res
+            };
+            if instr == CInstruction::JumpAndLink {
+                env.write_register(
+                    &Env::constant(1),
+                    instruction_pointer.clone() + Env::constant(2),
+                );
+            }
+            env.set_instruction_pointer(new_addr.clone());
+            env.set_next_instruction_pointer(new_addr + Env::constant(4u64));
+            return;
+        }
+        CInstruction::BranchEqZero | CInstruction::BranchNeqZero => {
+            // c.beqz: if (x[8 + rs1'] == 0) pc += sext(offset)
+            // c.bnez: if (x[8 + rs1'] != 0) pc += sext(offset)
+            let funct3 = if instr == CInstruction::BranchEqZero {
+                0b110
+            } else {
+                0b111
+            };
+            let [imm5, imm2_1, imm7_6, rs1, imm4_3, imm8] = decompose_compressed(
+                env,
+                &instruction,
+                encoding(funct3, 0b01),
+                [(3, 2), (5, 3), (7, 5), (10, 7), (12, 10), (13, 12)],
+            );
+            let offset = imm8 * Env::constant(1 << 8)
+                + imm7_6 * Env::constant(1 << 6)
+                + imm5 * Env::constant(1 << 5)
+                + imm4_3 * Env::constant(1 << 3)
+                + imm2_1 * Env::constant(1 << 1);
+            let offset = env.sign_extend(&offset, 9);
+            let local_rs1 = env.read_register(&compressed_register(rs1));
+            let is_zero = env.is_zero(&local_rs1);
+            let take_branch = if instr == CInstruction::BranchEqZero {
+                is_zero
+            } else {
+                Env::constant(1) - is_zero
+            };
+            let offset =
+                take_branch.clone() * offset + (Env::constant(1) - take_branch) * Env::constant(2);
+            let new_addr = {
+                let res_scratch = env.alloc_scratch();
+                let overflow_scratch = env.alloc_scratch();
+                let (res, _overflow) = unsafe {
+                    env.add_witness(&instruction_pointer, &offset, res_scratch, overflow_scratch)
+                };
+                // FIXME: Requires a range check
+                res
+            };
+            env.set_instruction_pointer(new_addr.clone());
+            env.set_next_instruction_pointer(new_addr + Env::constant(4u64));
+            return;
+        }
+        CInstruction::ShiftLeftLogicalImmediate => {
+            // c.slli: x[rd] = x[rd] << shamt
+            // The bit 12 is the highest bit of the shift amount, which must be
+            // 0 for RV32C.
+            let [shamt, rd] =
+                decompose_compressed(env, &instruction, encoding(0b000, 0b10), [(7, 2), (12, 7)]);
+            let local_rd = env.read_register(&rd);
+            let local_rd = {
+                let rd_scratch = env.alloc_scratch();
+                unsafe { env.shift_left(&local_rd, &shamt, rd_scratch) }
+            };
+            env.write_register(&rd, local_rd);
+        }
+        CInstruction::LoadWordStackPointer => {
+            // c.lwsp: x[rd] = M[x[2] + uimm][31:0]
+            let [imm7_6, imm4_2, rd, imm5] = decompose_compressed(
+                env,
+                &instruction,
+                encoding(0b010, 0b10),
+                [(4, 2), (7, 4), (12, 7), (13, 12)],
+            );
+            let offset = imm7_6 * Env::constant(1 << 6)
+                + imm5 * Env::constant(1 << 5)
+                + imm4_2 * Env::constant(1 << 2);
+            let base = env.read_register(&Env::constant(2));
+            let address = {
+                let address_scratch = env.alloc_scratch();
+                let overflow_scratch = env.alloc_scratch();
+                let (address, _overflow) =
+                    unsafe { env.add_witness(&base, &offset, address_scratch, overflow_scratch) };
+                address
+            };
+            let value = load_bytes(env, &address, 4);
+            env.write_register(&rd, value);
+        }
+        CInstruction::JumpRegister | CInstruction::JumpAndLinkRegister => {
+            // c.jr: pc = x[rs1]
+            // c.jalr: t = pc + 2; pc = x[rs1]; x[1] = t
+            let funct4 = if instr == CInstruction::JumpRegister {
+                0b1000
+            } else {
+                0b1001
+            };
+            let [rs1] = decompose_compressed(env, &instruction, (funct4 << 12) | 0b10, [(12, 7)]);
+            let new_addr = env.read_register(&rs1);
+            if instr == CInstruction::JumpAndLinkRegister {
+                env.write_register(
+                    &Env::constant(1),
+                    instruction_pointer.clone() + Env::constant(2),
+                );
+            }
+            env.set_instruction_pointer(new_addr.clone());
+            env.set_next_instruction_pointer(new_addr + Env::constant(4u64));
+            return;
+        }
+        CInstruction::Move | CInstruction::Add => {
+            // c.mv: x[rd] = x[rs2]
+            // c.add: x[rd] = x[rd] + x[rs2]
+            let funct4 = if instr == CInstruction::Move {
+                0b1000
+            } else {
+                0b1001
+            };
+            let [rs2, rd] =
+                decompose_compressed(env, &instruction, (funct4 << 12) | 0b10, [(7, 2), (12, 7)]);
+            let local_rs2 = env.read_register(&rs2);
+            let local_rd = if instr == CInstruction::Add {
+                let local_rd = env.read_register(&rd);
+                let overflow_scratch = env.alloc_scratch();
+                let rd_scratch = env.alloc_scratch();
+                let (local_rd, _overflow) =
+                    unsafe { env.add_witness(&local_rd, &local_rs2, rd_scratch, overflow_scratch) };
+                local_rd
+            } else {
+                local_rs2
+            };
+            env.write_register(&rd, local_rd);
+        }
+        CInstruction::StoreWordStackPointer => {
+            // c.swsp: M[x[2] + uimm] = x[rs2][31:0]
+            let [rs2, imm7_6, imm5_2] = decompose_compressed(
+                env,
+                &instruction,
+                encoding(0b110, 0b10),
+                [(7, 2), (9, 7), (13, 9)],
+            );
+            let offset = imm7_6 * Env::constant(1 << 6) + imm5_2 * Env::constant(1 << 2);
+            let base = env.read_register(&Env::constant(2));
+            let address = {
+                let address_scratch = env.alloc_scratch();
+                let overflow_scratch = env.alloc_scratch();
+                let (address, _overflow) =
+                    unsafe { env.add_witness(&base, &offset, address_scratch, overflow_scratch) };
+                address
+            };
+            let value = env.read_register(&rs2);
+            store_bytes(env, &address, &value, 4);
+        }
+    };
+
+    env.set_instruction_pointer(instruction_pointer.clone() + Env::constant(2u64));
+    env.set_next_instruction_pointer(instruction_pointer + Env::constant(6u64));
+}
+
+/// Interpret an instruction of the A extension.
+/// The encoding of the atomic instructions is as follows:
+/// ```text
+/// | 31    27 | 26 | 25 | 24      20 | 19     15 | 14        12 | 11    7 | 6      0 |
+/// |  funct5  | aq | rl |     rs2    |    rs1    |    funct3    |    rd   |  opcode  |
+/// ```
+/// The VM executing a single hart, the bits `aq` and `rl` are ignored.
+pub fn interpret_atype<Env: InterpreterEnv>(env: &mut Env, instr: AInstruction) {
+    let instruction_pointer = env.get_instruction_pointer();
+    let next_instruction_pointer = env.get_next_instruction_pointer();
+
+    let instruction = fetch_instruction(env, &instruction_pointer);
+
+    // FIXME: constrain the opcode to match the instruction given as a parameter
+    let opcode = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 7, 0, pos) }
+    };
+    env.range_check8(&opcode, 7);
+
+    let rd = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 12, 7, pos) }
+    };
+    env.range_check8(&rd, 5);
+
+    let funct3 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 15, 12, pos) }
+    };
+    env.range_check8(&funct3, 3);
+
+    let rs1 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 20, 15, pos) }
+    };
+    env.range_check8(&rs1, 5);
+
+    let rs2 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 25, 20, pos) }
+    };
+    env.range_check8(&rs2, 5);
+
+    let aq_rl = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 27, 25, pos) }
+    };
+    env.range_check8(&aq_rl, 2);
+
+    let funct5 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 32, 27, pos) }
+    };
+    env.range_check8(&funct5, 5);
+
+    // Check correctness of decomposition
+    env.add_constraint(
+        instruction
+    - (opcode.clone() * Env::constant(1 << 0))    // opcode at bits 0-6
+    - (rd.clone() * Env::constant(1 << 7))        // rd at bits 7-11
+    - (funct3.clone() * Env::constant(1 << 12))   // funct3 at bits 12-14
+    - (rs1.clone() * Env::constant(1 << 15))      // rs1 at bits 15-19
+    - (rs2.clone() * Env::constant(1 << 20))      // rs2 at bits 20-24
+    - (aq_rl.clone() * Env::constant(1 << 25))    // aq and rl at bits 25-26
+    - (funct5.clone() * Env::constant(1 << 27)), // funct5 at bits 27-31
+    );
+
+    let address = env.read_register(&rs1);
+
+    match instr {
+        AInstruction::LoadReserved => {
+            // lr.w: x[rd] = M[x[rs1]][31:0]
+            // The reservation is not recorded, as the store conditional of
+            // the single hart always succeeds.
+            let value = load_bytes(env, &address, 4);
+            env.write_register(&rd, value);
+        }
+        AInstruction::StoreConditional => {
+            // sc.w: M[x[rs1]] = x[rs2][31:0]; x[rd] = 0
+            let local_rs2 = env.read_register(&rs2);
+            store_bytes(env, &address, &local_rs2, 4);
+            env.write_register(&rd, Env::constant(0));
+        }
+        _ => {
+            // amo<op>.w: x[rd] = M[x[rs1]]; M[x[rs1]] = x[rd] <op> x[rs2]
+            // The value of rs2 is read before writing rd, which can be the same
+            // register.
+            let local_rs2 = env.read_register(&rs2);
+            let value = load_bytes(env, &address, 4);
+            let new_value = match instr {
+                AInstruction::AtomicSwap => local_rs2,
+                AInstruction::AtomicAdd => {
+                    let overflow_scratch = env.alloc_scratch();
+                    let res_scratch = env.alloc_scratch();
+                    let (res, _overflow) = unsafe {
+                        env.add_witness(&value, &local_rs2, res_scratch, overflow_scratch)
+                    };
+                    res
+                }
+                AInstruction::AtomicXor => {
+                    let res_scratch = env.alloc_scratch();
+                    unsafe { env.xor_witness(&value, &local_rs2, res_scratch) }
+                }
+                AInstruction::AtomicAnd => {
+                    let res_scratch = env.alloc_scratch();
+                    unsafe { env.and_witness(&value, &local_rs2, res_scratch) }
+                }
+                AInstruction::AtomicOr => {
+                    let res_scratch = env.alloc_scratch();
+                    unsafe { env.or_witness(&value, &local_rs2, res_scratch) }
+                }
+                AInstruction::AtomicMin
+                | AInstruction::AtomicMax
+                | AInstruction::AtomicMinUnsigned
+                | AInstruction::AtomicMaxUnsigned => {
+                    let less_than = {
+                        let res_scratch = env.alloc_scratch();
+                        match instr {
+                            AInstruction::AtomicMin | AInstruction::AtomicMax => unsafe {
+                                env.test_less_than_signed(&value, &local_rs2, res_scratch)
+                            },
+                            _ => unsafe { env.test_less_than(&value, &local_rs2, res_scratch) },
+                        }
+                    };
+                    let (lower, greater) = (
+                        less_than.clone() * value.clone()
+                            + (Env::constant(1) - less_than.clone()) * local_rs2.clone(),
+                        less_than.clone() * local_rs2
+                            + (Env::constant(1) - less_than) * value.clone(),
+                    );
+                    match instr {
+                        AInstruction::AtomicMin | AInstruction::AtomicMinUnsigned => lower,
+                        _ => greater,
+                    }
+                }
+                AInstruction::LoadReserved | AInstruction::StoreConditional => unreachable!(),
+            };
+            store_bytes(env, &address, &new_value, 4);
+            env.write_register(&rd, value);
+        }
+    };
+
+    env.set_instruction_pointer(next_instruction_pointer.clone());
+    env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
+}
+
+/// Interpret an instruction of the Zicsr extension.
+/// The encoding of a CSR instruction is as follows:
+/// ```text
+/// | 31            20 | 19         15 | 14        12 | 11    7 | 6      0 |
+/// |       csr        |  rs1 or uimm  |    funct3    |    rd   |  opcode  |
+/// ```
+/// The CSR at address `csr` is accessed as the register with index
+/// `REGISTER_CSR_OFFSET + csr`.
+pub fn interpret_csrtype<Env: InterpreterEnv>(env: &mut Env, instr: CSRInstruction) {
+    let instruction_pointer = env.get_instruction_pointer();
+    let next_instruction_pointer = env.get_next_instruction_pointer();
+
+    let instruction = fetch_instruction(env, &instruction_pointer);
+
+    // FIXME: constrain the opcode to match the instruction given as a parameter
+    let opcode = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 7, 0, pos) }
+    };
+    env.range_check8(&opcode, 7);
+
+    let rd = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 12, 7, pos) }
+    };
+    env.range_check8(&rd, 5);
+
+    let funct3 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 15, 12, pos) }
+    };
+    env.range_check8(&funct3, 3);
+
+    // The source register, or the immediate value of the immediate forms
+    let rs1 = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 20, 15, pos) }
+    };
+    env.range_check8(&rs1, 5);
+
+    let csr = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&instruction, 32, 20, pos) }
+    };
+    env.range_check16(&csr, 12);
+
+    // Check correctness of decomposition
+    env.add_constraint(
+        instruction
+    - (opcode.clone() * Env::constant(1 << 0))    // opcode at bits 0-6
+    - (rd.clone() * Env::constant(1 << 7))        // rd at bits 7-11
+    - (funct3.clone() * Env::constant(1 << 12))   // funct3 at bits 12-14
+    - (rs1.clone() * Env::constant(1 << 15))      // rs1 at bits 15-19
+    - (csr.clone() * Env::constant(1 << 20)), // csr at bits 20-31
+    );
+
+    match instr {
+        CSRInstruction::ReadCounter => {
+            // The counter is left unchanged
+            env.add_constraint(rs1);
+            // csr = 0xC00 + upper_half * 0x80 + counter, where the counter is
+            // 0 for cycle, 1 for time and 2 for instret
+            let counter = {
+                let pos = env.alloc_scratch();
+                unsafe { env.bitmask(&csr, 2, 0, pos) }
+            };
+            env.range_check8(&counter, 2);
+            let upper_half = {
+                let pos = env.alloc_scratch();
+                unsafe { env.bitmask(&csr, 8, 7, pos) }
+            };
+            env.assert_boolean(&upper_half);
+            env.add_constraint(
+                csr - Env::constant(CSR_CYCLE as u64)
+                    - upper_half.clone() * Env::constant(0x80)
+                    - counter,
+            );
+            let (low, high) = {
+                let low_pos = env.alloc_scratch();
+                let high_pos = env.alloc_scratch();
+                unsafe {
+                    (
+                        env.executed_instructions(false, low_pos),
+                        env.executed_instructions(true, high_pos),
+                    )
+                }
+            };
+            // The instruction counter is increased by MAX_ACC at each step
+            let instruction_counter = env.instruction_counter();
+            env.add_constraint(
+                instruction_counter
+                    - (high.clone() * Env::constant(1 << 16) * Env::constant(1 << 16)
+                        + low.clone())
+                        * Env::constant(MAX_ACC),
+            );
+            let value = upper_half.clone() * high + (Env::constant(1) - upper_half) * low;
+            env.write_register(&rd, value);
+        }
+        _ => {
+            let idx = Env::constant(REGISTER_CSR_OFFSET as u64) + csr;
+            // The CSR is read and written with a single access, as for the
+            // heap pointer
+            let value = {
+                let value_location = env.alloc_scratch();
+                unsafe { env.fetch_register(&idx, value_location) }
+            };
+            let source = match instr {
+                CSRInstruction::ReadWrite | CSRInstruction::ReadSet | CSRInstruction::ReadClear => {
+                    env.read_register(&rs1)
+                }
+                _ => rs1,
+            };
+            let new_value = match instr {
+                CSRInstruction::ReadWrite | CSRInstruction::ReadWriteImmediate => source,
+                CSRInstruction::ReadSet | CSRInstruction::ReadSetImmediate => {
+                    let res_scratch = env.alloc_scratch();
+                    unsafe { env.or_witness(&value, &source, res_scratch) }
+                }
+                CSRInstruction::ReadClear | CSRInstruction::ReadClearImmediate => {
+                    let res_scratch = env.alloc_scratch();
+                    let mask = Env::constant(0xFFFFFFFF) - source;
+                    unsafe { env.and_witness(&value, &mask, res_scratch) }
+                }
+                CSRInstruction::ReadCounter => unreachable!(),
+            };
+            unsafe {
+                env.access_register(&idx, &value, &new_value);
+            };
+            unsafe {
+                env.push_register(&idx, new_value);
+            };
+            // The source register is read before writing rd, which can be the
+            // same register.
+            env.write_register(&rd, value);
+        }
+    };
+
+    env.set_instruction_pointer(next_instruction_pointer.clone());
+    env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
+}
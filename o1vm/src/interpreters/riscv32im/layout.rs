@@ -0,0 +1,82 @@
+use super::{witness::MMAP_START_ADDRESS, PAGE_ADDRESS_SIZE, PAGE_SIZE};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use thiserror::Error;
+
+/// The top of the stack of the programs using the default layout
+pub const STACK_TOP_ADDRESS: u32 = 0x408004f0;
+
+/// The regions of the memory of the guest, given to
+/// [super::witness::Env::create_with_layout] so that the programs linked with
+/// custom scripts, like the embedded ones, can be run.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryLayout {
+    /// The addresses at which the segments of the program, code and data,
+    /// can be loaded
+    pub code: Range<u32>,
+    /// The address of the first region allocated by `mmap`
+    pub heap_base: u32,
+    /// The initial value of the stack pointer, the stack growing down
+    pub stack_top: u32,
+    /// A region reserved to the memory-mapped devices, where neither the
+    /// program nor the heap or the stack can be placed
+    pub io: Option<Range<u32>>,
+}
+
+impl Default for MemoryLayout {
+    fn default() -> Self {
+        MemoryLayout {
+            code: 0..0x40000000,
+            heap_base: MMAP_START_ADDRESS,
+            stack_top: STACK_TOP_ADDRESS,
+            io: None,
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MemoryLayoutError {
+    #[error("the page at address {0:#x} is outside of the code region")]
+    PageOutsideOfCode(u32),
+    #[error("the page at address {0:#x} is in the reserved region")]
+    PageInReservedRegion(u32),
+    #[error("the heap base {0:#x} is in the code or the reserved region")]
+    InvalidHeapBase(u32),
+    #[error("the stack top {0:#x} is in the code or the reserved region")]
+    InvalidStackTop(u32),
+}
+
+fn overlap(a: &Range<u32>, b: &Range<u32>) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
+impl MemoryLayout {
+    fn is_reserved(&self, address: u32) -> bool {
+        self.code.contains(&address) || self.io.as_ref().is_some_and(|io| io.contains(&address))
+    }
+
+    /// Checks that the pages of index `pages`, loaded from the segments of the
+    /// program, fit in the code region and that the heap and the stack start
+    /// outside of the code and the reserved regions
+    pub fn validate<I: IntoIterator<Item = u32>>(&self, pages: I) -> Result<(), MemoryLayoutError> {
+        for page in pages {
+            let address = page << PAGE_ADDRESS_SIZE;
+            let page_range = address..address.saturating_add(PAGE_SIZE);
+            if !overlap(&page_range, &self.code) {
+                return Err(MemoryLayoutError::PageOutsideOfCode(address));
+            }
+            if self.io.as_ref().is_some_and(|io| overlap(&page_range, io)) {
+                return Err(MemoryLayoutError::PageInReservedRegion(address));
+            }
+        }
+        if self.is_reserved(self.heap_base) {
+            return Err(MemoryLayoutError::InvalidHeapBase(self.heap_base));
+        }
+        // The stack pointer points to the last word pushed, so the stack is
+        // made of the addresses below its top
+        if self.is_reserved(self.stack_top.wrapping_sub(1)) {
+            return Err(MemoryLayoutError::InvalidStackTop(self.stack_top));
+        }
+        Ok(())
+    }
+}
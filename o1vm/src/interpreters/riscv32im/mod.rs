@@ -1,9 +1,9 @@
 /// The minimal number of columns required for the VM
-pub const SCRATCH_SIZE: usize = 39;
-pub const SCRATCH_SIZE_INVERSE: usize = 1;
+pub const SCRATCH_SIZE: usize = 52;
+pub const SCRATCH_SIZE_INVERSE: usize = 11;
 
 /// Number of instructions in the ISA
-pub const INSTRUCTION_SET_SIZE: usize = 48;
+pub const INSTRUCTION_SET_SIZE: usize = 106;
 
 pub const PAGE_ADDRESS_SIZE: u32 = 12;
 pub const PAGE_SIZE: u32 = 1 << PAGE_ADDRESS_SIZE;
@@ -12,10 +12,16 @@ pub const PAGE_ADDRESS_MASK: u32 = PAGE_SIZE - 1;
 /// List all columns used by the interpreter
 pub mod column;
 
+/// Logs of the instructions executed, in the format of spike
+pub mod commit_log;
+
 pub mod constraints;
 
 pub mod interpreter;
 
+/// The layout of the memory of the guest programs
+pub mod layout;
+
 /// All the registers used by the ISA
 pub mod registers;
 
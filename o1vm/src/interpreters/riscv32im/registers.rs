@@ -7,6 +7,91 @@ pub const N_GP_REGISTERS: usize = 32;
 pub const REGISTER_CURRENT_IP: usize = N_GP_REGISTERS + 1;
 pub const REGISTER_NEXT_IP: usize = N_GP_REGISTERS + 2;
 pub const REGISTER_HEAP_POINTER: usize = N_GP_REGISTERS + 3;
+pub const REGISTER_PROGRAM_BREAK: usize = N_GP_REGISTERS + 4;
+pub const REGISTER_PREIMAGE_KEY_START: usize = N_GP_REGISTERS + 5;
+pub const REGISTER_PREIMAGE_KEY_END: usize = REGISTER_PREIMAGE_KEY_START + 8;
+pub const REGISTER_PREIMAGE_OFFSET: usize = REGISTER_PREIMAGE_KEY_END;
+/// The control and status register (CSR) at address `a` is the register with
+/// index `REGISTER_CSR_OFFSET + a`.
+pub const REGISTER_CSR_OFFSET: usize = REGISTER_PREIMAGE_OFFSET + 1;
+
+/// Machine status register
+pub const CSR_MSTATUS: usize = 0x300;
+/// Machine ISA register
+pub const CSR_MISA: usize = 0x301;
+/// Machine interrupt-enable register
+pub const CSR_MIE: usize = 0x304;
+/// Machine trap-handler base address
+pub const CSR_MTVEC: usize = 0x305;
+/// Scratch register for machine trap handlers
+pub const CSR_MSCRATCH: usize = 0x340;
+/// Machine exception program counter
+pub const CSR_MEPC: usize = 0x341;
+/// Machine trap cause
+pub const CSR_MCAUSE: usize = 0x342;
+/// Machine bad address or instruction
+pub const CSR_MTVAL: usize = 0x343;
+/// Machine interrupt pending
+pub const CSR_MIP: usize = 0x344;
+/// Vendor ID
+pub const CSR_MVENDORID: usize = 0xF11;
+/// Architecture ID
+pub const CSR_MARCHID: usize = 0xF12;
+/// Implementation ID
+pub const CSR_MIMPID: usize = 0xF13;
+/// Hardware thread ID
+pub const CSR_MHARTID: usize = 0xF14;
+
+/// Cycle counter, i.e. the number of instructions executed
+pub const CSR_CYCLE: usize = 0xC00;
+/// Timer, i.e. the number of instructions executed
+pub const CSR_TIME: usize = 0xC01;
+/// Number of instructions retired
+pub const CSR_INSTRET: usize = 0xC02;
+/// Upper 32 bits of [CSR_CYCLE]
+pub const CSR_CYCLEH: usize = 0xC80;
+/// Upper 32 bits of [CSR_TIME]
+pub const CSR_TIMEH: usize = 0xC81;
+/// Upper 32 bits of [CSR_INSTRET]
+pub const CSR_INSTRETH: usize = 0xC82;
+
+pub const N_CSR_REGISTERS: usize = 13;
+
+/// The addresses of the CSR stored in the registers. The counters are not
+/// stored, their value being given by the instruction counter.
+pub const CSR_ADDRESSES: [usize; N_CSR_REGISTERS] = [
+    CSR_MSTATUS,
+    CSR_MISA,
+    CSR_MIE,
+    CSR_MTVEC,
+    CSR_MSCRATCH,
+    CSR_MEPC,
+    CSR_MCAUSE,
+    CSR_MTVAL,
+    CSR_MIP,
+    CSR_MVENDORID,
+    CSR_MARCHID,
+    CSR_MIMPID,
+    CSR_MHARTID,
+];
+
+/// The value of `misa` for RV32IMAC: MXL = 1 (32 bits), and the extensions A,
+/// C, I and M.
+pub const MISA: u32 = (1 << 30) | (1 << 0) | (1 << 2) | (1 << 8) | (1 << 12);
+
+/// The position of the CSR at `address` in [Registers::csrs], if it is stored
+pub fn csr_index(address: usize) -> Option<usize> {
+    CSR_ADDRESSES.iter().position(|a| *a == address)
+}
+
+/// Whether the CSR at `address` is one of the read-only counters `cycle`,
+/// `time` and `instret`, or their upper halves
+pub fn is_counter_csr(address: usize) -> bool {
+    matches!(
+        address,
+        CSR_CYCLE | CSR_TIME | CSR_INSTRET | CSR_CYCLEH | CSR_TIMEH | CSR_INSTRETH
+    )
+}
 
 /// This represents the internal state of the virtual machine.
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
@@ -28,7 +113,19 @@ pub struct Registers<T> {
     pub general_purpose: [T; N_GP_REGISTERS],
     pub current_instruction_pointer: T,
     pub next_instruction_pointer: T,
+    /// The end of the memory of the anonymous mappings, growing with `mmap`
     pub heap_pointer: T,
+    /// The end of the data segment of the program, set with `brk`
+    pub program_break: T,
+    /// The key of the preimage requested to the preimage oracle, as 8 words
+    /// in big-endian order
+    pub preimage_key: [T; 8],
+    /// The number of bytes of the preimage already read, including the 8
+    /// bytes of its length
+    pub preimage_offset: T,
+    /// The machine-level control and status registers, at the addresses given
+    /// by [CSR_ADDRESSES].
+    pub csrs: [T; N_CSR_REGISTERS],
 }
 
 impl<T: Clone> Index<usize> for Registers<T> {
@@ -43,6 +140,14 @@ impl<T: Clone> Index<usize> for Registers<T> {
             &self.next_instruction_pointer
         } else if index == REGISTER_HEAP_POINTER {
             &self.heap_pointer
+        } else if index == REGISTER_PROGRAM_BREAK {
+            &self.program_break
+        } else if (REGISTER_PREIMAGE_KEY_START..REGISTER_PREIMAGE_KEY_END).contains(&index) {
+            &self.preimage_key[index - REGISTER_PREIMAGE_KEY_START]
+        } else if index == REGISTER_PREIMAGE_OFFSET {
+            &self.preimage_offset
+        } else if let Some(csr) = index.checked_sub(REGISTER_CSR_OFFSET).and_then(csr_index) {
+            &self.csrs[csr]
         } else {
             panic!("Index out of bounds");
         }
@@ -59,6 +164,14 @@ impl<T: Clone> IndexMut<usize> for Registers<T> {
             &mut self.next_instruction_pointer
         } else if index == REGISTER_HEAP_POINTER {
             &mut self.heap_pointer
+        } else if index == REGISTER_PROGRAM_BREAK {
+            &mut self.program_break
+        } else if (REGISTER_PREIMAGE_KEY_START..REGISTER_PREIMAGE_KEY_END).contains(&index) {
+            &mut self.preimage_key[index - REGISTER_PREIMAGE_KEY_START]
+        } else if index == REGISTER_PREIMAGE_OFFSET {
+            &mut self.preimage_offset
+        } else if let Some(csr) = index.checked_sub(REGISTER_CSR_OFFSET).and_then(csr_index) {
+            &mut self.csrs[csr]
         } else {
             panic!("Index out of bounds");
         }
@@ -116,6 +229,7 @@ pub enum RegisterAlias {
     /// Next instruction pointer
     NextIp,
     HeapPointer,
+    ProgramBreak,
 }
 
 impl<T: Clone> Index<RegisterAlias> for Registers<T> {
@@ -160,6 +274,7 @@ impl<T: Clone> Index<RegisterAlias> for Registers<T> {
             RegisterAlias::Ip => &self.current_instruction_pointer,
             RegisterAlias::NextIp => &self.next_instruction_pointer,
             RegisterAlias::HeapPointer => &self.heap_pointer,
+            RegisterAlias::ProgramBreak => &self.program_break,
         }
     }
 }
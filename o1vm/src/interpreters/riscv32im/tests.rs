@@ -2,16 +2,60 @@ use super::{
     registers::Registers, witness::Env, INSTRUCTION_SET_SIZE, PAGE_SIZE, SCRATCH_SIZE,
     SCRATCH_SIZE_INVERSE,
 };
-use crate::interpreters::riscv32im::{
-    constraints,
-    interpreter::{
-        interpret_instruction, IInstruction, Instruction, InterpreterEnv, MInstruction,
-        RInstruction, SBInstruction, SInstruction, SyscallInstruction, UInstruction, UJInstruction,
+use crate::{
+    cannon::{Hint, Preimage},
+    gdb_stub::GdbStub,
+    interpreters::{
+        coverage::ScratchColumn,
+        keccak::precompile::SYSCALL_KECCAK256,
+        poseidon::{
+            self,
+            precompile::{decode_state, encode_state, SYSCALL_POSEIDON},
+        },
+        precompile::{self, Precompile, PrecompileError, PrecompileRegistry, N_CALL_COLS},
+        riscv32im::{
+            constraints,
+            interpreter::{
+                interpret_instruction, AInstruction, CInstruction, CSRInstruction, IInstruction,
+                Instruction, InterpreterEnv, MInstruction, RInstruction, SBInstruction,
+                SInstruction, SyscallInstruction, UInstruction, UJInstruction, FD_ADVICE_READ,
+                FD_HINT_READ, FD_HINT_WRITE, FD_PREIMAGE_READ, FD_PREIMAGE_WRITE, FD_STDERR,
+                FD_STDIN, FD_STDOUT, MAP_ANONYMOUS, SYSCALL_BRK, SYSCALL_CLOCK_GETTIME64,
+                SYSCALL_EXIT, SYSCALL_EXIT_GROUP, SYSCALL_GETRANDOM, SYSCALL_MMAP, SYSCALL_READ,
+                SYSCALL_WRITE,
+            },
+            registers::{
+                CSR_CYCLE, CSR_INSTRET, CSR_INSTRETH, CSR_MEPC, CSR_MISA, CSR_MSCRATCH,
+                CSR_MSTATUS, CSR_TIME, MISA, REGISTER_CSR_OFFSET,
+            },
+            witness::{
+                decompress_instruction, ExecutionResult, ExitStatus, MAX_ACC, MMAP_START_ADDRESS,
+            },
+        },
+    },
+    lookups::LookupTableIDs,
+    memory::SparseMemory,
+    merkle_memory::{self, MerkleMemory},
+    preimage_oracle::{
+        read_transcript, NullPreImageOracle, PreImageOracleT, RecordingOracle, ReplayOracle,
     },
+    E,
 };
 use ark_ff::Zero;
+use kimchi::circuits::gate::CurrOrNext;
 use mina_curves::pasta::Fp;
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi, pasta::fp_kimchi, permutation::poseidon_block_cipher,
+};
 use rand::{CryptoRng, Rng, RngCore};
+use sha3::{Digest, Keccak256};
+use std::{
+    array,
+    cell::RefCell,
+    collections::VecDeque,
+    io::{Cursor, Read, Write},
+    rc::Rc,
+};
 use strum::{EnumCount, IntoEnumIterator};
 
 // Sanity check that we have as many selector as we have instructions
@@ -34,6 +78,9 @@ fn test_regression_selectors_for_instructions() {
             + UJInstruction::COUNT
             + SyscallInstruction::COUNT
             + MInstruction::COUNT
+            + CInstruction::COUNT
+            + AInstruction::COUNT
+            + CSRInstruction::COUNT
     );
     // All instructions are degree 1 or 2.
     constraints
@@ -44,10 +91,10 @@ fn test_regression_selectors_for_instructions() {
 pub fn dummy_env() -> Env<Fp> {
     Env {
         instruction_counter: 0,
-        memory: vec![(0, vec![0; PAGE_SIZE.try_into().unwrap()])],
-        last_memory_accesses: [0; 3],
-        memory_write_index: vec![(0, vec![0; PAGE_SIZE.try_into().unwrap()])],
-        last_memory_write_index_accesses: [0; 3],
+        memory: SparseMemory::new(),
+        memory_write_index: SparseMemory::new(),
+        merkle_memory: None,
+        authenticated_accesses: Vec::new(),
         registers: Registers::default(),
         registers_write_index: Registers::default(),
         scratch_state_idx: 0,
@@ -56,6 +103,28 @@ pub fn dummy_env() -> Env<Fp> {
         scratch_state_inverse: [Fp::zero(); SCRATCH_SIZE_INVERSE],
         halt: false,
         selector: INSTRUCTION_SET_SIZE,
+        random_stream: Box::new(std::iter::empty()),
+        preimage_oracle: Box::new(NullPreImageOracle),
+        preimage: None,
+        last_hint: None,
+        advice: VecDeque::new(),
+        stdin: Box::new(std::io::empty()),
+        stdout: Box::new(std::io::sink()),
+        stderr: Box::new(std::io::sink()),
+        written_stdout: vec![],
+        written_stderr: vec![],
+        exit_code: None,
+        profiler: None,
+        commit_log: None,
+        coverage: None,
+        max_cycles: None,
+        cycle_limit_exceeded: false,
+        keccak_precompile: Default::default(),
+        hash_counter: 0,
+        keccak_inputs: vec![],
+        poseidon_precompile: Default::default(),
+        poseidon_inputs: vec![],
+        precompiles: PrecompileRegistry::default(),
     }
 }
 
@@ -478,10 +547,10 @@ pub fn test_instruction_decoding_add() {
     let mut env: Env<Fp> = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_add_instruction(&mut rng);
-    env.memory[0].1[0] = instruction[0];
-    env.memory[0].1[1] = instruction[1];
-    env.memory[0].1[2] = instruction[2];
-    env.memory[0].1[3] = instruction[3];
+    env.memory.page_mut(0)[0] = instruction[0];
+    env.memory.page_mut(0)[1] = instruction[1];
+    env.memory.page_mut(0)[2] = instruction[2];
+    env.memory.page_mut(0)[3] = instruction[3];
     let (opcode, _instruction) = env.decode_instruction();
     assert_eq!(opcode, Instruction::RType(RInstruction::Add));
 }
@@ -491,10 +560,10 @@ pub fn test_instruction_decoding_sub() {
     let mut env: Env<Fp> = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_sub_instruction(&mut rng);
-    env.memory[0].1[0] = instruction[0];
-    env.memory[0].1[1] = instruction[1];
-    env.memory[0].1[2] = instruction[2];
-    env.memory[0].1[3] = instruction[3];
+    env.memory.page_mut(0)[0] = instruction[0];
+    env.memory.page_mut(0)[1] = instruction[1];
+    env.memory.page_mut(0)[2] = instruction[2];
+    env.memory.page_mut(0)[3] = instruction[3];
     let (opcode, _instruction) = env.decode_instruction();
     assert_eq!(opcode, Instruction::RType(RInstruction::Sub));
 }
@@ -504,10 +573,10 @@ pub fn test_instruction_decoding_sll() {
     let mut env: Env<Fp> = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_sll_instruction(&mut rng);
-    env.memory[0].1[0] = instruction[0];
-    env.memory[0].1[1] = instruction[1];
-    env.memory[0].1[2] = instruction[2];
-    env.memory[0].1[3] = instruction[3];
+    env.memory.page_mut(0)[0] = instruction[0];
+    env.memory.page_mut(0)[1] = instruction[1];
+    env.memory.page_mut(0)[2] = instruction[2];
+    env.memory.page_mut(0)[3] = instruction[3];
     let (opcode, _instruction) = env.decode_instruction();
     assert_eq!(opcode, Instruction::RType(RInstruction::ShiftLeftLogical));
 }
@@ -517,10 +586,10 @@ pub fn test_instruction_decoding_slt() {
     let mut env: Env<Fp> = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_slt_instruction(&mut rng);
-    env.memory[0].1[0] = instruction[0];
-    env.memory[0].1[1] = instruction[1];
-    env.memory[0].1[2] = instruction[2];
-    env.memory[0].1[3] = instruction[3];
+    env.memory.page_mut(0)[0] = instruction[0];
+    env.memory.page_mut(0)[1] = instruction[1];
+    env.memory.page_mut(0)[2] = instruction[2];
+    env.memory.page_mut(0)[3] = instruction[3];
     let (opcode, _instruction) = env.decode_instruction();
     assert_eq!(opcode, Instruction::RType(RInstruction::SetLessThan));
 }
@@ -530,10 +599,10 @@ pub fn test_instruction_decoding_sltu() {
     let mut env: Env<Fp> = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_sltu_instruction(&mut rng);
-    env.memory[0].1[0] = instruction[0];
-    env.memory[0].1[1] = instruction[1];
-    env.memory[0].1[2] = instruction[2];
-    env.memory[0].1[3] = instruction[3];
+    env.memory.page_mut(0)[0] = instruction[0];
+    env.memory.page_mut(0)[1] = instruction[1];
+    env.memory.page_mut(0)[2] = instruction[2];
+    env.memory.page_mut(0)[3] = instruction[3];
     let (opcode, _instruction) = env.decode_instruction();
     assert_eq!(
         opcode,
@@ -546,10 +615,10 @@ pub fn test_instruction_decoding_xor() {
     let mut env: Env<Fp> = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_xor_instruction(&mut rng);
-    env.memory[0].1[0] = instruction[0];
-    env.memory[0].1[1] = instruction[1];
-    env.memory[0].1[2] = instruction[2];
-    env.memory[0].1[3] = instruction[3];
+    env.memory.page_mut(0)[0] = instruction[0];
+    env.memory.page_mut(0)[1] = instruction[1];
+    env.memory.page_mut(0)[2] = instruction[2];
+    env.memory.page_mut(0)[3] = instruction[3];
     let (opcode, _instruction) = env.decode_instruction();
     assert_eq!(opcode, Instruction::RType(RInstruction::Xor));
 }
@@ -559,10 +628,10 @@ pub fn test_instruction_decoding_srl() {
     let mut env: Env<Fp> = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_srl_instruction(&mut rng);
-    env.memory[0].1[0] = instruction[0];
-    env.memory[0].1[1] = instruction[1];
-    env.memory[0].1[2] = instruction[2];
-    env.memory[0].1[3] = instruction[3];
+    env.memory.page_mut(0)[0] = instruction[0];
+    env.memory.page_mut(0)[1] = instruction[1];
+    env.memory.page_mut(0)[2] = instruction[2];
+    env.memory.page_mut(0)[3] = instruction[3];
     let (opcode, _instruction) = env.decode_instruction();
     assert_eq!(opcode, Instruction::RType(RInstruction::ShiftRightLogical));
 }
@@ -572,10 +641,10 @@ pub fn test_instruction_decoding_sr1() {
     let mut env: Env<Fp> = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_sra_instruction(&mut rng);
-    env.memory[0].1[0] = instruction[0];
-    env.memory[0].1[1] = instruction[1];
-    env.memory[0].1[2] = instruction[2];
-    env.memory[0].1[3] = instruction[3];
+    env.memory.page_mut(0)[0] = instruction[0];
+    env.memory.page_mut(0)[1] = instruction[1];
+    env.memory.page_mut(0)[2] = instruction[2];
+    env.memory.page_mut(0)[3] = instruction[3];
     let (opcode, _instruction) = env.decode_instruction();
     assert_eq!(
         opcode,
@@ -588,10 +657,10 @@ pub fn test_instruction_decoding_or() {
     let mut env: Env<Fp> = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_or_instruction(&mut rng);
-    env.memory[0].1[0] = instruction[0];
-    env.memory[0].1[1] = instruction[1];
-    env.memory[0].1[2] = instruction[2];
-    env.memory[0].1[3] = instruction[3];
+    env.memory.page_mut(0)[0] = instruction[0];
+    env.memory.page_mut(0)[1] = instruction[1];
+    env.memory.page_mut(0)[2] = instruction[2];
+    env.memory.page_mut(0)[3] = instruction[3];
     let (opcode, _instruction) = env.decode_instruction();
     assert_eq!(opcode, Instruction::RType(RInstruction::Or));
 }
@@ -601,10 +670,10 @@ pub fn test_instruction_decoding_and() {
     let mut env: Env<Fp> = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_and_instruction(&mut rng);
-    env.memory[0].1[0] = instruction[0];
-    env.memory[0].1[1] = instruction[1];
-    env.memory[0].1[2] = instruction[2];
-    env.memory[0].1[3] = instruction[3];
+    env.memory.page_mut(0)[0] = instruction[0];
+    env.memory.page_mut(0)[1] = instruction[1];
+    env.memory.page_mut(0)[2] = instruction[2];
+    env.memory.page_mut(0)[3] = instruction[3];
     let (opcode, _instruction) = env.decode_instruction();
     assert_eq!(opcode, Instruction::RType(RInstruction::And));
 }
@@ -642,10 +711,10 @@ pub fn test_instruction_decoding_mul() {
     let mut env: Env<Fp> = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_mul_instruction(&mut rng);
-    env.memory[0].1[0] = instruction[0];
-    env.memory[0].1[1] = instruction[1];
-    env.memory[0].1[2] = instruction[2];
-    env.memory[0].1[3] = instruction[3];
+    env.memory.page_mut(0)[0] = instruction[0];
+    env.memory.page_mut(0)[1] = instruction[1];
+    env.memory.page_mut(0)[2] = instruction[2];
+    env.memory.page_mut(0)[3] = instruction[3];
     let (opcode, _instruction) = env.decode_instruction();
     assert_eq!(opcode, Instruction::MType(MInstruction::Mul));
 }
@@ -655,10 +724,10 @@ pub fn test_instruction_decoding_mulh() {
     let mut env: Env<Fp> = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_mulh_instruction(&mut rng);
-    env.memory[0].1[0] = instruction[0];
-    env.memory[0].1[1] = instruction[1];
-    env.memory[0].1[2] = instruction[2];
-    env.memory[0].1[3] = instruction[3];
+    env.memory.page_mut(0)[0] = instruction[0];
+    env.memory.page_mut(0)[1] = instruction[1];
+    env.memory.page_mut(0)[2] = instruction[2];
+    env.memory.page_mut(0)[3] = instruction[3];
     let (opcode, _instruction) = env.decode_instruction();
     assert_eq!(opcode, Instruction::MType(MInstruction::Mulh));
 }
@@ -668,10 +737,10 @@ pub fn test_instruction_decoding_mulhsu() {
     let mut env: Env<Fp> = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_mulhsu_instruction(&mut rng);
-    env.memory[0].1[0] = instruction[0];
-    env.memory[0].1[1] = instruction[1];
-    env.memory[0].1[2] = instruction[2];
-    env.memory[0].1[3] = instruction[3];
+    env.memory.page_mut(0)[0] = instruction[0];
+    env.memory.page_mut(0)[1] = instruction[1];
+    env.memory.page_mut(0)[2] = instruction[2];
+    env.memory.page_mut(0)[3] = instruction[3];
     let (opcode, _instruction) = env.decode_instruction();
     assert_eq!(opcode, Instruction::MType(MInstruction::Mulhsu));
 }
@@ -681,10 +750,10 @@ pub fn test_instruction_decoding_mulhu() {
     let mut env: Env<Fp> = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_mulhu_instruction(&mut rng);
-    env.memory[0].1[0] = instruction[0];
-    env.memory[0].1[1] = instruction[1];
-    env.memory[0].1[2] = instruction[2];
-    env.memory[0].1[3] = instruction[3];
+    env.memory.page_mut(0)[0] = instruction[0];
+    env.memory.page_mut(0)[1] = instruction[1];
+    env.memory.page_mut(0)[2] = instruction[2];
+    env.memory.page_mut(0)[3] = instruction[3];
     let (opcode, _instruction) = env.decode_instruction();
     assert_eq!(opcode, Instruction::MType(MInstruction::Mulhu));
 }
@@ -694,10 +763,10 @@ pub fn test_instruction_decoding_div() {
     let mut env: Env<Fp> = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_div_instruction(&mut rng);
-    env.memory[0].1[0] = instruction[0];
-    env.memory[0].1[1] = instruction[1];
-    env.memory[0].1[2] = instruction[2];
-    env.memory[0].1[3] = instruction[3];
+    env.memory.page_mut(0)[0] = instruction[0];
+    env.memory.page_mut(0)[1] = instruction[1];
+    env.memory.page_mut(0)[2] = instruction[2];
+    env.memory.page_mut(0)[3] = instruction[3];
     let (opcode, _instruction) = env.decode_instruction();
     assert_eq!(opcode, Instruction::MType(MInstruction::Div));
 }
@@ -707,10 +776,10 @@ pub fn test_instruction_decoding_divu() {
     let mut env: Env<Fp> = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_divu_instruction(&mut rng);
-    env.memory[0].1[0] = instruction[0];
-    env.memory[0].1[1] = instruction[1];
-    env.memory[0].1[2] = instruction[2];
-    env.memory[0].1[3] = instruction[3];
+    env.memory.page_mut(0)[0] = instruction[0];
+    env.memory.page_mut(0)[1] = instruction[1];
+    env.memory.page_mut(0)[2] = instruction[2];
+    env.memory.page_mut(0)[3] = instruction[3];
     let (opcode, _instruction) = env.decode_instruction();
     assert_eq!(opcode, Instruction::MType(MInstruction::Divu));
 }
@@ -720,10 +789,10 @@ pub fn test_instruction_decoding_rem() {
     let mut env: Env<Fp> = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_rem_instruction(&mut rng);
-    env.memory[0].1[0] = instruction[0];
-    env.memory[0].1[1] = instruction[1];
-    env.memory[0].1[2] = instruction[2];
-    env.memory[0].1[3] = instruction[3];
+    env.memory.page_mut(0)[0] = instruction[0];
+    env.memory.page_mut(0)[1] = instruction[1];
+    env.memory.page_mut(0)[2] = instruction[2];
+    env.memory.page_mut(0)[3] = instruction[3];
     let (opcode, _instruction) = env.decode_instruction();
     assert_eq!(opcode, Instruction::MType(MInstruction::Rem));
 }
@@ -733,10 +802,10 @@ pub fn test_instruction_decoding_remu() {
     let mut env: Env<Fp> = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_remu_instruction(&mut rng);
-    env.memory[0].1[0] = instruction[0];
-    env.memory[0].1[1] = instruction[1];
-    env.memory[0].1[2] = instruction[2];
-    env.memory[0].1[3] = instruction[3];
+    env.memory.page_mut(0)[0] = instruction[0];
+    env.memory.page_mut(0)[1] = instruction[1];
+    env.memory.page_mut(0)[2] = instruction[2];
+    env.memory.page_mut(0)[3] = instruction[3];
     let (opcode, _instruction) = env.decode_instruction();
     assert_eq!(opcode, Instruction::MType(MInstruction::Remu));
 }
@@ -744,13 +813,7 @@ pub fn test_instruction_decoding_remu() {
 #[test]
 fn test_regression_number_of_constraints_per_instruction() {
     let mut env = constraints::Env::<Fp>::default();
-    let unimplemented_instructions = [
-        Instruction::RType(RInstruction::Fence),
-        Instruction::RType(RInstruction::FenceI),
-    ];
-    let instructions = Instruction::iter()
-        .flat_map(|x| x.into_iter())
-        .filter(|x| !unimplemented_instructions.contains(x));
+    let instructions = Instruction::iter().flat_map(|x| x.into_iter());
     for instruction in instructions {
         interpret_instruction(&mut env, instruction);
         println!("{:?}", instruction);
@@ -787,10 +850,10 @@ fn test_regression_number_of_constraints_per_instruction() {
                     assert_eq!(env.constraints.len(), 4);
                 }
                 RInstruction::Fence => {
-                    unimplemented!("Fence should not be implemented");
+                    assert_eq!(env.constraints.len(), 1);
                 }
                 RInstruction::FenceI => {
-                    unimplemented!("FenceI should not be implemented");
+                    assert_eq!(env.constraints.len(), 1);
                 }
             },
             Instruction::IType(itype) => match itype {
@@ -888,6 +951,51 @@ fn test_regression_number_of_constraints_per_instruction() {
                 SyscallInstruction::SyscallSuccess => {
                     assert_eq!(env.constraints.len(), 0);
                 }
+                SyscallInstruction::SyscallExitGroup => {
+                    assert_eq!(env.constraints.len(), 0);
+                }
+                SyscallInstruction::SyscallBrk => {
+                    assert_eq!(env.constraints.len(), 6);
+                }
+                SyscallInstruction::SyscallMmap => {
+                    assert_eq!(env.constraints.len(), 8);
+                }
+                SyscallInstruction::SyscallClockGettime => {
+                    assert_eq!(env.constraints.len(), 3);
+                }
+                SyscallInstruction::SyscallGetRandom => {
+                    assert_eq!(env.constraints.len(), 7);
+                }
+                SyscallInstruction::SyscallReadHint => {
+                    assert_eq!(env.constraints.len(), 3);
+                }
+                SyscallInstruction::SyscallReadPreimage => {
+                    assert_eq!(env.constraints.len(), 6);
+                }
+                SyscallInstruction::SyscallReadAdvice => {
+                    assert_eq!(env.constraints.len(), 3);
+                }
+                SyscallInstruction::SyscallReadStdin => {
+                    assert_eq!(env.constraints.len(), 3);
+                }
+                SyscallInstruction::SyscallWriteOutput => {
+                    assert_eq!(env.constraints.len(), 3);
+                }
+                SyscallInstruction::SyscallWriteHint => {
+                    assert_eq!(env.constraints.len(), 3);
+                }
+                SyscallInstruction::SyscallWritePreimage => {
+                    assert_eq!(env.constraints.len(), 28);
+                }
+                SyscallInstruction::SyscallKeccak256 => {
+                    assert_eq!(env.constraints.len(), 13);
+                }
+                SyscallInstruction::SyscallPoseidon => {
+                    assert_eq!(env.constraints.len(), 0);
+                }
+                SyscallInstruction::SyscallPrecompile => {
+                    assert_eq!(env.constraints.len(), 0);
+                }
             },
             Instruction::MType(mtype) => match mtype {
                 MInstruction::Mul => {
@@ -903,19 +1011,1809 @@ fn test_regression_number_of_constraints_per_instruction() {
                     assert_eq!(env.constraints.len(), 4);
                 }
                 MInstruction::Div => {
-                    assert_eq!(env.constraints.len(), 4);
+                    assert_eq!(env.constraints.len(), 34);
                 }
                 MInstruction::Divu => {
-                    assert_eq!(env.constraints.len(), 4);
+                    assert_eq!(env.constraints.len(), 13);
                 }
                 MInstruction::Rem => {
-                    assert_eq!(env.constraints.len(), 4);
+                    assert_eq!(env.constraints.len(), 34);
                 }
                 MInstruction::Remu => {
+                    assert_eq!(env.constraints.len(), 13);
+                }
+            },
+            Instruction::CType(ctype) => match ctype {
+                CInstruction::AddImmediate4StackPointer => {
+                    assert_eq!(env.constraints.len(), 6);
+                }
+                CInstruction::LoadWord => {
+                    assert_eq!(env.constraints.len(), 6);
+                }
+                CInstruction::StoreWord => {
+                    assert_eq!(env.constraints.len(), 3);
+                }
+                CInstruction::AddImmediate => {
+                    assert_eq!(env.constraints.len(), 5);
+                }
+                CInstruction::JumpAndLink => {
+                    assert_eq!(env.constraints.len(), 10);
+                }
+                CInstruction::LoadImmediate => {
+                    assert_eq!(env.constraints.len(), 5);
+                }
+                CInstruction::AddImmediate16StackPointer => {
+                    assert_eq!(env.constraints.len(), 8);
+                }
+                CInstruction::LoadUpperImmediate => {
+                    assert_eq!(env.constraints.len(), 5);
+                }
+                CInstruction::ShiftRightLogicalImmediate => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                CInstruction::ShiftRightArithmeticImmediate => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                CInstruction::AndImmediate => {
+                    assert_eq!(env.constraints.len(), 5);
+                }
+                CInstruction::Sub => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                CInstruction::Xor => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                CInstruction::Or => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                CInstruction::And => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                CInstruction::Jump => {
+                    assert_eq!(env.constraints.len(), 7);
+                }
+                CInstruction::BranchEqZero => {
+                    assert_eq!(env.constraints.len(), 5);
+                }
+                CInstruction::BranchNeqZero => {
+                    assert_eq!(env.constraints.len(), 5);
+                }
+                CInstruction::ShiftLeftLogicalImmediate => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                CInstruction::LoadWordStackPointer => {
+                    assert_eq!(env.constraints.len(), 5);
+                }
+                CInstruction::JumpRegister => {
+                    assert_eq!(env.constraints.len(), 1);
+                }
+                CInstruction::Move => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                CInstruction::JumpAndLinkRegister => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                CInstruction::Add => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                CInstruction::StoreWordStackPointer => {
+                    assert_eq!(env.constraints.len(), 1);
+                }
+            },
+            Instruction::AType(atype) => match atype {
+                AInstruction::LoadReserved => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                AInstruction::StoreConditional => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                AInstruction::AtomicSwap => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                AInstruction::AtomicAdd => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                AInstruction::AtomicXor => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                AInstruction::AtomicAnd => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                AInstruction::AtomicOr => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                AInstruction::AtomicMin => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                AInstruction::AtomicMax => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                AInstruction::AtomicMinUnsigned => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                AInstruction::AtomicMaxUnsigned => {
                     assert_eq!(env.constraints.len(), 4);
                 }
             },
+            Instruction::CSRType(csrtype) => match csrtype {
+                CSRInstruction::ReadWrite => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                CSRInstruction::ReadSet => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                CSRInstruction::ReadClear => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                CSRInstruction::ReadWriteImmediate => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                CSRInstruction::ReadSetImmediate => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                CSRInstruction::ReadClearImmediate => {
+                    assert_eq!(env.constraints.len(), 4);
+                }
+                CSRInstruction::ReadCounter => {
+                    assert_eq!(env.constraints.len(), 8);
+                }
+            },
         }
         env.reset()
     }
 }
+
+// Encodings taken from the disassembly of programs compiled with RVC, each
+// given with the instruction of the base instruction set it expands to.
+const COMPRESSED_INSTRUCTIONS: [(u16, CInstruction, Instruction); 25] = [
+    // c.addi4spn s0, 16
+    (
+        0x0800,
+        CInstruction::AddImmediate4StackPointer,
+        Instruction::IType(IInstruction::AddImmediate),
+    ),
+    // c.lw a0, 4(a1)
+    (
+        0x41C8,
+        CInstruction::LoadWord,
+        Instruction::IType(IInstruction::LoadWord),
+    ),
+    // c.sw a0, 4(a1)
+    (
+        0xC1C8,
+        CInstruction::StoreWord,
+        Instruction::SType(SInstruction::StoreWord),
+    ),
+    // c.addi sp, -16
+    (
+        0x1141,
+        CInstruction::AddImmediate,
+        Instruction::IType(IInstruction::AddImmediate),
+    ),
+    // c.jal 8
+    (
+        0x2021,
+        CInstruction::JumpAndLink,
+        Instruction::UJType(UJInstruction::JumpAndLink),
+    ),
+    // c.li a0, 0
+    (
+        0x4501,
+        CInstruction::LoadImmediate,
+        Instruction::IType(IInstruction::AddImmediate),
+    ),
+    // c.addi16sp -64
+    (
+        0x7139,
+        CInstruction::AddImmediate16StackPointer,
+        Instruction::IType(IInstruction::AddImmediate),
+    ),
+    // c.lui a0, 1
+    (
+        0x6505,
+        CInstruction::LoadUpperImmediate,
+        Instruction::UType(UInstruction::LoadUpperImmediate),
+    ),
+    // c.srli a0, 1
+    (
+        0x8105,
+        CInstruction::ShiftRightLogicalImmediate,
+        Instruction::IType(IInstruction::ShiftRightLogicalImmediate),
+    ),
+    // c.srai a0, 1
+    (
+        0x8505,
+        CInstruction::ShiftRightArithmeticImmediate,
+        Instruction::IType(IInstruction::ShiftRightArithmeticImmediate),
+    ),
+    // c.andi a0, -1
+    (
+        0x997D,
+        CInstruction::AndImmediate,
+        Instruction::IType(IInstruction::AndImmediate),
+    ),
+    // c.sub a0, a1
+    (
+        0x8D0D,
+        CInstruction::Sub,
+        Instruction::RType(RInstruction::Sub),
+    ),
+    // c.xor a0, a1
+    (
+        0x8D2D,
+        CInstruction::Xor,
+        Instruction::RType(RInstruction::Xor),
+    ),
+    // c.or a0, a1
+    (
+        0x8D4D,
+        CInstruction::Or,
+        Instruction::RType(RInstruction::Or),
+    ),
+    // c.and a0, a1
+    (
+        0x8D6D,
+        CInstruction::And,
+        Instruction::RType(RInstruction::And),
+    ),
+    // c.j 8
+    (
+        0xA021,
+        CInstruction::Jump,
+        Instruction::UJType(UJInstruction::JumpAndLink),
+    ),
+    // c.beqz a0, 8
+    (
+        0xC501,
+        CInstruction::BranchEqZero,
+        Instruction::SBType(SBInstruction::BranchEq),
+    ),
+    // c.bnez a0, 8
+    (
+        0xE501,
+        CInstruction::BranchNeqZero,
+        Instruction::SBType(SBInstruction::BranchNeq),
+    ),
+    // c.slli a0, 1
+    (
+        0x0506,
+        CInstruction::ShiftLeftLogicalImmediate,
+        Instruction::IType(IInstruction::ShiftLeftLogicalImmediate),
+    ),
+    // c.lwsp ra, 12(sp)
+    (
+        0x40B2,
+        CInstruction::LoadWordStackPointer,
+        Instruction::IType(IInstruction::LoadWord),
+    ),
+    // c.jr ra
+    (
+        0x8082,
+        CInstruction::JumpRegister,
+        Instruction::IType(IInstruction::JumpAndLinkRegister),
+    ),
+    // c.mv a0, a1
+    (
+        0x852E,
+        CInstruction::Move,
+        Instruction::RType(RInstruction::Add),
+    ),
+    // c.jalr a0
+    (
+        0x9502,
+        CInstruction::JumpAndLinkRegister,
+        Instruction::IType(IInstruction::JumpAndLinkRegister),
+    ),
+    // c.add a0, a1
+    (
+        0x952E,
+        CInstruction::Add,
+        Instruction::RType(RInstruction::Add),
+    ),
+    // c.swsp ra, 12(sp)
+    (
+        0xC606,
+        CInstruction::StoreWordStackPointer,
+        Instruction::SType(SInstruction::StoreWord),
+    ),
+];
+
+#[test]
+pub fn test_instruction_decoding_compressed() {
+    for (compressed, ctype, expanded) in COMPRESSED_INSTRUCTIONS {
+        let mut env: Env<Fp> = dummy_env();
+        env.memory.page_mut(0)[0..2].copy_from_slice(&compressed.to_le_bytes());
+        let (instruction, decompressed) = env.decode_instruction();
+        assert_eq!(instruction, Instruction::CType(ctype));
+        assert_eq!(decompressed, decompress_instruction(compressed));
+
+        // The expansion is decoded as the corresponding base instruction
+        env.memory.page_mut(0)[0..4].copy_from_slice(&decompressed.to_le_bytes());
+        let (instruction, _) = env.decode_instruction();
+        assert_eq!(instruction, expanded, "{ctype}");
+    }
+}
+
+#[test]
+pub fn test_decompression() {
+    // addi sp, sp, -16
+    assert_eq!(decompress_instruction(0x1141), 0xFF010113);
+    // addi sp, sp, -64
+    assert_eq!(decompress_instruction(0x7139), 0xFC010113);
+    // addi s0, sp, 16
+    assert_eq!(decompress_instruction(0x0800), 0x01010413);
+    // sw ra, 12(sp)
+    assert_eq!(decompress_instruction(0xC606), 0x00112623);
+    // lw ra, 12(sp)
+    assert_eq!(decompress_instruction(0x40B2), 0x00C12083);
+    // jalr x0, 0(ra)
+    assert_eq!(decompress_instruction(0x8082), 0x00008067);
+    // add a0, x0, a1
+    assert_eq!(decompress_instruction(0x852E), 0x00B00533);
+    // sub a0, a0, a1
+    assert_eq!(decompress_instruction(0x8D0D), 0x40B50533);
+    // beq a0, x0, 8
+    assert_eq!(decompress_instruction(0xC501), 0x00050463);
+    // jal x1, 8
+    assert_eq!(decompress_instruction(0x2021), 0x008000EF);
+}
+
+fn write_program(env: &mut Env<Fp>, program: &[u8]) {
+    env.memory.page_mut(0)[0..program.len()].copy_from_slice(program);
+    env.registers.current_instruction_pointer = 0;
+    env.registers.next_instruction_pointer = 4;
+}
+
+#[test]
+pub fn test_compressed_function_call() {
+    let mut env: Env<Fp> = dummy_env();
+    let program: Vec<u8> = [
+        0x1141u16, // addi sp, sp, -16
+        0xC606,    // sw ra, 12(sp)
+        0xC422,    // sw s0, 8(sp)
+        0x0800,    // addi s0, sp, 16
+        0x4501,    // li a0, 0
+        0x40B2,    // lw ra, 12(sp)
+        0x4422,    // lw s0, 8(sp)
+        0x0141,    // addi sp, sp, 16
+        0x8082,    // ret
+    ]
+    .iter()
+    .flat_map(|instruction| instruction.to_le_bytes())
+    .collect();
+    write_program(&mut env, &program);
+    env.registers[1] = 0x400;
+    env.registers[2] = 0x800;
+    env.registers[8] = 0x1234;
+    env.registers[10] = 42;
+
+    for i in 0..4 {
+        env.step();
+        assert_eq!(env.registers.current_instruction_pointer, 2 * (i + 1));
+        assert_eq!(env.registers.next_instruction_pointer, 2 * (i + 1) + 4);
+    }
+    assert_eq!(env.registers[2], 0x7F0);
+    assert_eq!(env.registers[8], 0x800);
+    assert_eq!(read_bytes(&env, 0x7FC, 4), [0, 0, 0x04, 0x00]);
+    assert_eq!(read_bytes(&env, 0x7F8, 4), [0, 0, 0x12, 0x34]);
+
+    for _ in 0..5 {
+        env.step();
+    }
+    assert_eq!(env.registers[1], 0x400);
+    assert_eq!(env.registers[2], 0x800);
+    assert_eq!(env.registers[8], 0x1234);
+    assert_eq!(env.registers[10], 0);
+    assert_eq!(env.registers.current_instruction_pointer, 0x400);
+    assert_eq!(env.registers.next_instruction_pointer, 0x404);
+}
+
+#[test]
+pub fn test_mixed_instruction_sizes() {
+    let mut env: Env<Fp> = dummy_env();
+    let program: Vec<u8> = [
+        0x00150513u32.to_le_bytes().to_vec(), // addi a0, a0, 1
+        0x0505u16.to_le_bytes().to_vec(),     // c.addi a0, 1
+        0x00150513u32.to_le_bytes().to_vec(), // addi a0, a0, 1
+        0xC501u16.to_le_bytes().to_vec(),     // c.beqz a0, 8
+        0x2021u16.to_le_bytes().to_vec(),     // c.jal 8
+    ]
+    .concat();
+    write_program(&mut env, &program);
+
+    let instruction_pointers = [4, 6, 10, 12, 20];
+    for instruction_pointer in instruction_pointers {
+        env.step();
+        assert_eq!(
+            env.registers.current_instruction_pointer,
+            instruction_pointer
+        );
+        assert_eq!(
+            env.registers.next_instruction_pointer,
+            instruction_pointer + 4
+        );
+    }
+    assert_eq!(env.registers[10], 3);
+    // c.jal links the address of the instruction following it
+    assert_eq!(env.registers[1], 14);
+}
+
+fn encode_atomic(funct5: u32, rd: u32, rs1: u32, rs2: u32) -> u32 {
+    (funct5 << 27) | (rs2 << 20) | (rs1 << 15) | (0b010 << 12) | (rd << 7) | 0b0101111
+}
+
+const ATOMIC_INSTRUCTIONS: [(u32, AInstruction); 11] = [
+    (0b00010, AInstruction::LoadReserved),
+    (0b00011, AInstruction::StoreConditional),
+    (0b00001, AInstruction::AtomicSwap),
+    (0b00000, AInstruction::AtomicAdd),
+    (0b00100, AInstruction::AtomicXor),
+    (0b01100, AInstruction::AtomicAnd),
+    (0b01000, AInstruction::AtomicOr),
+    (0b10000, AInstruction::AtomicMin),
+    (0b10100, AInstruction::AtomicMax),
+    (0b11000, AInstruction::AtomicMinUnsigned),
+    (0b11100, AInstruction::AtomicMaxUnsigned),
+];
+
+#[test]
+pub fn test_instruction_decoding_atomics() {
+    let mut env: Env<Fp> = dummy_env();
+    for (funct5, atype) in ATOMIC_INSTRUCTIONS {
+        // The ordering bits aq and rl are ignored
+        for aq_rl in 0..4 {
+            let instruction = encode_atomic(funct5, 7, 5, 6) | (aq_rl << 25);
+            env.memory.page_mut(0)[0..4].copy_from_slice(&instruction.to_le_bytes());
+            let (opcode, _instruction) = env.decode_instruction();
+            assert_eq!(opcode, Instruction::AType(atype));
+        }
+    }
+}
+
+fn read_word(env: &Env<Fp>, address: usize) -> u32 {
+    u32::from_be_bytes(read_bytes(env, address as u32, 4).try_into().unwrap())
+}
+
+fn write_word(env: &mut Env<Fp>, address: usize, value: u32) {
+    env.memory.page_mut(0)[address..address + 4].copy_from_slice(&value.to_be_bytes());
+}
+
+#[test]
+pub fn test_atomic_memory_operations() {
+    let old_value = -2i32 as u32;
+    let operand = 3;
+    let expected_values = [
+        (AInstruction::AtomicSwap, operand),
+        (AInstruction::AtomicAdd, 1),
+        (AInstruction::AtomicXor, -3i32 as u32),
+        (AInstruction::AtomicAnd, 2),
+        (AInstruction::AtomicOr, u32::MAX),
+        (AInstruction::AtomicMin, old_value),
+        (AInstruction::AtomicMax, operand),
+        (AInstruction::AtomicMinUnsigned, operand),
+        (AInstruction::AtomicMaxUnsigned, old_value),
+    ];
+    for (atype, expected_value) in expected_values {
+        let mut env: Env<Fp> = dummy_env();
+        let (funct5, _) = ATOMIC_INSTRUCTIONS
+            .into_iter()
+            .find(|(_, a)| *a == atype)
+            .unwrap();
+        // amo<op>.w x7, x6, (x5)
+        write_program(&mut env, &encode_atomic(funct5, 7, 5, 6).to_le_bytes());
+        write_word(&mut env, 0x100, old_value);
+        env.registers[5] = 0x100;
+        env.registers[6] = operand;
+
+        assert_eq!(env.step(), Instruction::AType(atype));
+        assert_eq!(env.registers[7], old_value, "{atype}");
+        assert_eq!(read_word(&env, 0x100), expected_value, "{atype}");
+        assert_eq!(env.registers.current_instruction_pointer, 4);
+    }
+}
+
+#[test]
+pub fn test_atomic_destination_is_source() {
+    let mut env: Env<Fp> = dummy_env();
+    // amoadd.w x6, x6, (x5)
+    write_program(&mut env, &encode_atomic(0b00000, 6, 5, 6).to_le_bytes());
+    write_word(&mut env, 0x100, 40);
+    env.registers[5] = 0x100;
+    env.registers[6] = 2;
+
+    env.step();
+    assert_eq!(env.registers[6], 40);
+    assert_eq!(read_word(&env, 0x100), 42);
+}
+
+#[test]
+pub fn test_load_reserved_store_conditional() {
+    let mut env: Env<Fp> = dummy_env();
+    let program: Vec<u8> = [
+        encode_atomic(0b00010, 7, 5, 0), // lr.w x7, (x5)
+        0x00138393,                      // addi x7, x7, 1
+        encode_atomic(0b00011, 8, 5, 7), // sc.w x8, x7, (x5)
+    ]
+    .iter()
+    .flat_map(|instruction| instruction.to_le_bytes())
+    .collect();
+    write_program(&mut env, &program);
+    write_word(&mut env, 0x100, 41);
+    env.registers[5] = 0x100;
+    env.registers[8] = 1;
+
+    env.step();
+    assert_eq!(env.registers[7], 41);
+    env.step();
+    env.step();
+    // The store conditional of the single hart always succeeds
+    assert_eq!(env.registers[8], 0);
+    assert_eq!(read_word(&env, 0x100), 42);
+}
+
+fn encode_csr(funct3: u32, rd: u32, rs1: u32, csr: usize) -> u32 {
+    ((csr as u32) << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | 0b1110011
+}
+
+#[test]
+pub fn test_instruction_decoding_csr() {
+    let mut env: Env<Fp> = dummy_env();
+    let instructions = [
+        (0b001, CSRInstruction::ReadWrite),
+        (0b010, CSRInstruction::ReadSet),
+        (0b011, CSRInstruction::ReadClear),
+        (0b101, CSRInstruction::ReadWriteImmediate),
+        (0b110, CSRInstruction::ReadSetImmediate),
+        (0b111, CSRInstruction::ReadClearImmediate),
+    ];
+    for (funct3, csrtype) in instructions {
+        let instruction = encode_csr(funct3, 7, 5, CSR_MSCRATCH);
+        env.memory.page_mut(0)[0..4].copy_from_slice(&instruction.to_le_bytes());
+        let (opcode, _instruction) = env.decode_instruction();
+        assert_eq!(opcode, Instruction::CSRType(csrtype));
+    }
+    // rdcycle x7
+    env.memory.page_mut(0)[0..4].copy_from_slice(&encode_csr(0b010, 7, 0, CSR_CYCLE).to_le_bytes());
+    let (opcode, _instruction) = env.decode_instruction();
+    assert_eq!(opcode, Instruction::CSRType(CSRInstruction::ReadCounter));
+    // ecall
+    env.memory.page_mut(0)[0..4].copy_from_slice(&0x00000073u32.to_le_bytes());
+    let (opcode, _instruction) = env.decode_instruction();
+    assert_eq!(
+        opcode,
+        Instruction::SyscallType(SyscallInstruction::SyscallSuccess)
+    );
+}
+
+#[test]
+#[should_panic]
+pub fn test_write_read_only_counter() {
+    let mut env: Env<Fp> = dummy_env();
+    // csrrw x7, time, x5
+    env.memory.page_mut(0)[0..4].copy_from_slice(&encode_csr(0b001, 7, 5, CSR_TIME).to_le_bytes());
+    env.decode_instruction();
+}
+
+#[test]
+pub fn test_csr_read_modify_write() {
+    let old_value = 0b1100;
+    let operand = 0b1010;
+    let expected_values = [
+        (0b001, CSRInstruction::ReadWrite, operand),
+        (0b010, CSRInstruction::ReadSet, 0b1110),
+        (0b011, CSRInstruction::ReadClear, 0b0100),
+        (0b101, CSRInstruction::ReadWriteImmediate, operand),
+        (0b110, CSRInstruction::ReadSetImmediate, 0b1110),
+        (0b111, CSRInstruction::ReadClearImmediate, 0b0100),
+    ];
+    for (funct3, csrtype, expected_value) in expected_values {
+        let mut env: Env<Fp> = dummy_env();
+        // The immediate forms use the value of the field rs1
+        let rs1 = if funct3 & 0b100 == 0 { 5 } else { operand };
+        write_program(
+            &mut env,
+            &encode_csr(funct3, 7, rs1, CSR_MSCRATCH).to_le_bytes(),
+        );
+        env.registers[REGISTER_CSR_OFFSET + CSR_MSCRATCH] = old_value;
+        env.registers[5] = operand;
+
+        assert_eq!(env.step(), Instruction::CSRType(csrtype));
+        assert_eq!(env.registers[7], old_value, "{csrtype}");
+        assert_eq!(
+            env.registers[REGISTER_CSR_OFFSET + CSR_MSCRATCH],
+            expected_value,
+            "{csrtype}"
+        );
+        assert_eq!(env.registers.current_instruction_pointer, 4);
+    }
+}
+
+#[test]
+pub fn test_csr_swap_with_destination_as_source() {
+    let mut env: Env<Fp> = dummy_env();
+    // csrrw x5, mepc, x5
+    write_program(&mut env, &encode_csr(0b001, 5, 5, CSR_MEPC).to_le_bytes());
+    env.registers[REGISTER_CSR_OFFSET + CSR_MEPC] = 0x1000;
+    env.registers[5] = 0x2000;
+
+    env.step();
+    assert_eq!(env.registers[5], 0x1000);
+    assert_eq!(env.registers[REGISTER_CSR_OFFSET + CSR_MEPC], 0x2000);
+}
+
+#[test]
+pub fn test_csr_probe_at_startup() {
+    let mut env: Env<Fp> = dummy_env();
+    env.registers[REGISTER_CSR_OFFSET + CSR_MISA] = MISA;
+    let program: Vec<u8> = [
+        encode_csr(0b010, 5, 0, CSR_MISA),    // csrr x5, misa
+        encode_csr(0b110, 0, 8, CSR_MSTATUS), // csrsi mstatus, 8
+        encode_csr(0b010, 6, 0, CSR_MSTATUS), // csrr x6, mstatus
+    ]
+    .iter()
+    .flat_map(|instruction| instruction.to_le_bytes())
+    .collect();
+    write_program(&mut env, &program);
+
+    for _ in 0..3 {
+        env.step();
+    }
+    assert_eq!(env.registers[5], MISA);
+    assert_eq!(env.registers[6], 8);
+    // x0 is left unchanged
+    assert_eq!(env.registers[0], 0);
+}
+
+#[test]
+pub fn test_counters() {
+    let mut env: Env<Fp> = dummy_env();
+    let program: Vec<u8> = [
+        0x00000013,                            // nop
+        0x00000013,                            // nop
+        encode_csr(0b010, 5, 0, CSR_CYCLE),    // rdcycle x5
+        encode_csr(0b010, 6, 0, CSR_TIME),     // rdtime x6
+        encode_csr(0b010, 7, 0, CSR_INSTRET),  // rdinstret x7
+        encode_csr(0b010, 8, 0, CSR_INSTRETH), // rdinstreth x8
+    ]
+    .iter()
+    .flat_map(|instruction| instruction.to_le_bytes())
+    .collect();
+    write_program(&mut env, &program);
+
+    for _ in 0..6 {
+        env.step();
+    }
+    // The counters give the number of instructions executed before reading
+    // them
+    assert_eq!(env.registers[5], 2);
+    assert_eq!(env.registers[6], 3);
+    assert_eq!(env.registers[7], 4);
+    assert_eq!(env.registers[8], 0);
+
+    // The upper halves of the counters, after 2^32 + 3 instructions
+    let mut env: Env<Fp> = dummy_env();
+    env.instruction_counter = ((1 << 32) + 3) * MAX_ACC;
+    write_program(
+        &mut env,
+        &[
+            encode_csr(0b010, 7, 0, CSR_INSTRET),
+            encode_csr(0b010, 8, 0, CSR_INSTRETH),
+        ]
+        .iter()
+        .flat_map(|instruction| instruction.to_le_bytes())
+        .collect::<Vec<u8>>(),
+    );
+    env.step();
+    env.step();
+    assert_eq!(env.registers[7], 3);
+    assert_eq!(env.registers[8], 1);
+}
+
+fn encode_load(funct3: u32, rd: u32, rs1: u32, imm: u32) -> u32 {
+    (imm << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | 0b0000011
+}
+
+fn encode_store(funct3: u32, rs1: u32, rs2: u32, imm: u32) -> u32 {
+    ((imm >> 5) << 25)
+        | (rs2 << 20)
+        | (rs1 << 15)
+        | (funct3 << 12)
+        | ((imm & 0x1F) << 7)
+        | 0b0100011
+}
+
+// The addresses of the misaligned accesses, at every offset from a word, the
+// last ones spanning two pages
+fn misaligned_addresses() -> Vec<u32> {
+    (0x100..0x104).chain(PAGE_SIZE - 3..PAGE_SIZE).collect()
+}
+
+fn read_bytes(env: &Env<Fp>, address: u32, len: u32) -> Vec<u8> {
+    (address..address + len)
+        .map(|address| env.memory.get(address))
+        .collect()
+}
+
+fn write_bytes(env: &mut Env<Fp>, address: u32, bytes: &[u8]) {
+    for (address, byte) in (address..).zip(bytes) {
+        env.memory.set(address, *byte);
+    }
+}
+
+#[test]
+pub fn test_misaligned_loads() {
+    let bytes = [0x81, 0x92, 0xA3, 0xB4];
+    // (funct3, instruction, expected value)
+    let loads = [
+        (0b010, IInstruction::LoadWord, 0x8192A3B4),
+        (0b001, IInstruction::LoadHalf, 0xFFFF8192),
+        (0b101, IInstruction::LoadHalfUnsigned, 0x8192),
+        (0b000, IInstruction::LoadByte, 0xFFFFFF81),
+        (0b100, IInstruction::LoadByteUnsigned, 0x81),
+    ];
+    for address in misaligned_addresses() {
+        for (funct3, itype, expected_value) in loads {
+            let mut env: Env<Fp> = dummy_env();
+            // l<size> x7, 2(x5)
+            write_program(&mut env, &encode_load(funct3, 7, 5, 2).to_le_bytes());
+            write_bytes(&mut env, address, &bytes);
+            env.registers[5] = address - 2;
+
+            assert_eq!(env.step(), Instruction::IType(itype));
+            assert_eq!(env.registers[7], expected_value, "{itype} at {address:#x}");
+        }
+    }
+}
+
+#[test]
+pub fn test_misaligned_stores() {
+    let value = 0x8192A3B4;
+    // (funct3, instruction, expected bytes)
+    let stores = [
+        (0b010, SInstruction::StoreWord, vec![0x81, 0x92, 0xA3, 0xB4]),
+        (0b001, SInstruction::StoreHalf, vec![0xA3, 0xB4, 0x00, 0x00]),
+        (0b000, SInstruction::StoreByte, vec![0xB4, 0x00, 0x00, 0x00]),
+    ];
+    for address in misaligned_addresses() {
+        for (funct3, stype, expected_bytes) in stores.clone() {
+            let mut env: Env<Fp> = dummy_env();
+            // s<size> x6, 2(x5)
+            write_program(&mut env, &encode_store(funct3, 5, 6, 2).to_le_bytes());
+            env.registers[5] = address - 2;
+            env.registers[6] = value;
+
+            assert_eq!(env.step(), Instruction::SType(stype));
+            assert_eq!(
+                read_bytes(&env, address, 4),
+                expected_bytes,
+                "{stype} at {address:#x}"
+            );
+        }
+    }
+}
+
+#[test]
+pub fn test_misaligned_store_load_round_trip() {
+    // (store funct3, load funct3, value, loaded value)
+    let accesses = [
+        (0b010, 0b010, 0xDEADBEEF, 0xDEADBEEF),
+        (0b001, 0b001, 0xBEEF, 0xFFFFBEEF),
+        (0b001, 0b101, 0xBEEF, 0xBEEF),
+    ];
+    for address in misaligned_addresses() {
+        for (store_funct3, load_funct3, value, loaded_value) in accesses {
+            let mut env: Env<Fp> = dummy_env();
+            let program: Vec<u8> = [
+                encode_store(store_funct3, 5, 6, 0), // s<size> x6, 0(x5)
+                encode_load(load_funct3, 7, 5, 0),   // l<size> x7, 0(x5)
+            ]
+            .iter()
+            .flat_map(|instruction| instruction.to_le_bytes())
+            .collect();
+            write_program(&mut env, &program);
+            env.registers[5] = address;
+            env.registers[6] = value;
+
+            env.step();
+            env.step();
+            assert_eq!(env.registers[7], loaded_value, "at {address:#x}");
+        }
+    }
+}
+
+#[test]
+pub fn test_merkle_memory() {
+    let mut env: Env<Fp> = dummy_env();
+    let program: Vec<u8> = [
+        encode_store(0b010, 5, 6, 0), // sw x6, 0(x5)
+        encode_load(0b010, 7, 5, 0),  // lw x7, 0(x5)
+    ]
+    .iter()
+    .flat_map(|instruction| instruction.to_le_bytes())
+    .collect();
+    write_program(&mut env, &program);
+    // The misaligned word spans two words of the memory
+    env.registers[5] = 0x102;
+    env.registers[6] = 0x8192A3B4;
+    env.enable_merkle_memory();
+    let root = env.merkle_memory.as_ref().unwrap().root();
+
+    env.step();
+    env.step();
+    assert_eq!(env.registers[7], 0x8192A3B4);
+    let accesses = env.take_authenticated_accesses();
+    assert!(accesses
+        .iter()
+        .any(|access| access.addr == 0x100 && access.new_word == 0x00008192));
+    assert!(accesses
+        .iter()
+        .any(|access| access.addr == 0x104 && access.new_word == 0xA3B40000));
+    assert_eq!(
+        merkle_memory::verify_accesses(root, &accesses),
+        Some(MerkleMemory::from_memory(&env.memory).root())
+    );
+
+    // The accesses are sent to the Merkle memory channel
+    let merkle_memory_lookups = |env: &constraints::Env<Fp>| {
+        env.lookups
+            .iter()
+            .filter(|lookup| lookup.table_id == LookupTableIDs::MerkleMemoryLookup)
+            .count()
+    };
+    let mut con_env = constraints::Env::<Fp>::with_merkle_memory();
+    interpret_instruction(&mut con_env, Instruction::SType(SInstruction::StoreWord));
+    assert!(merkle_memory_lookups(&con_env) >= 4);
+    let mut con_env = constraints::Env::<Fp>::default();
+    interpret_instruction(&mut con_env, Instruction::SType(SInstruction::StoreWord));
+    assert_eq!(merkle_memory_lookups(&con_env), 0);
+}
+
+#[test]
+fn test_full_width_range_checks_use_one_lookup() {
+    let count = |env: &constraints::Env<Fp>, table_id| {
+        env.lookups
+            .iter()
+            .filter(|lookup| lookup.table_id == table_id)
+            .count()
+    };
+    let x = <constraints::Env<Fp> as InterpreterEnv>::constant(3);
+    for (bits, expected) in [(5, 2), (16, 1)] {
+        let mut env = constraints::Env::<Fp>::default();
+        env.range_check16(&x, bits);
+        assert_eq!(count(&env, LookupTableIDs::RangeCheck16Lookup), expected);
+    }
+    for (bits, expected) in [(5, 2), (8, 1)] {
+        let mut env = constraints::Env::<Fp>::default();
+        env.range_check8(&x, bits);
+        assert_eq!(count(&env, LookupTableIDs::ByteLookup), expected);
+    }
+}
+
+const ECALL: u32 = 0x00000073;
+
+fn syscall_env(syscall_number: u32) -> Env<Fp> {
+    let mut env: Env<Fp> = dummy_env();
+    write_program(&mut env, &ECALL.to_le_bytes());
+    env.registers[17] = syscall_number;
+    env
+}
+
+#[test]
+pub fn test_instruction_decoding_syscalls() {
+    let syscalls = [
+        (SYSCALL_EXIT, SyscallInstruction::SyscallExitGroup),
+        (SYSCALL_EXIT_GROUP, SyscallInstruction::SyscallExitGroup),
+        (SYSCALL_BRK, SyscallInstruction::SyscallBrk),
+        (SYSCALL_MMAP, SyscallInstruction::SyscallMmap),
+        (
+            SYSCALL_CLOCK_GETTIME64,
+            SyscallInstruction::SyscallClockGettime,
+        ),
+        (SYSCALL_GETRANDOM, SyscallInstruction::SyscallGetRandom),
+        (SYSCALL_KECCAK256, SyscallInstruction::SyscallKeccak256),
+        (SYSCALL_POSEIDON, SyscallInstruction::SyscallPoseidon),
+        (42, SyscallInstruction::SyscallSuccess),
+    ];
+    for (syscall_number, syscall) in syscalls {
+        let mut env = syscall_env(syscall_number);
+        env.registers[13] = MAP_ANONYMOUS;
+        let (opcode, _instruction) = env.decode_instruction();
+        assert_eq!(opcode, Instruction::SyscallType(syscall));
+    }
+
+    // The reads and writes are decoded from the file descriptor in a0
+    let syscalls = [
+        (
+            SYSCALL_READ,
+            FD_HINT_READ,
+            SyscallInstruction::SyscallReadHint,
+        ),
+        (
+            SYSCALL_READ,
+            FD_PREIMAGE_READ,
+            SyscallInstruction::SyscallReadPreimage,
+        ),
+        (SYSCALL_READ, FD_STDIN, SyscallInstruction::SyscallReadStdin),
+        (
+            SYSCALL_WRITE,
+            FD_STDOUT,
+            SyscallInstruction::SyscallWriteOutput,
+        ),
+        (
+            SYSCALL_WRITE,
+            FD_STDERR,
+            SyscallInstruction::SyscallWriteOutput,
+        ),
+        (
+            SYSCALL_READ,
+            FD_ADVICE_READ,
+            SyscallInstruction::SyscallReadAdvice,
+        ),
+        (
+            SYSCALL_WRITE,
+            FD_HINT_WRITE,
+            SyscallInstruction::SyscallWriteHint,
+        ),
+        (
+            SYSCALL_WRITE,
+            FD_PREIMAGE_WRITE,
+            SyscallInstruction::SyscallWritePreimage,
+        ),
+        (
+            SYSCALL_READ,
+            FD_HINT_WRITE,
+            SyscallInstruction::SyscallSuccess,
+        ),
+        (
+            SYSCALL_WRITE,
+            FD_PREIMAGE_READ,
+            SyscallInstruction::SyscallSuccess,
+        ),
+    ];
+    for (syscall_number, fd, syscall) in syscalls {
+        let mut env = syscall_env(syscall_number);
+        env.registers[10] = fd;
+        let (opcode, _instruction) = env.decode_instruction();
+        assert_eq!(opcode, Instruction::SyscallType(syscall));
+    }
+}
+
+#[test]
+#[should_panic]
+pub fn test_mmap_file_backed() {
+    let mut env = syscall_env(SYSCALL_MMAP);
+    // MAP_PRIVATE
+    env.registers[13] = 0x02;
+    env.decode_instruction();
+}
+
+#[test]
+pub fn test_syscall_exit_group() {
+    let mut env = syscall_env(SYSCALL_EXIT_GROUP);
+    env.registers[10] = 3;
+    env.step();
+    assert!(env.halt);
+    assert_eq!(env.registers.current_instruction_pointer, 0);
+}
+
+#[test]
+pub fn test_syscall_brk() {
+    let mut env = syscall_env(SYSCALL_BRK);
+    env.registers.program_break = 0x3000;
+    // Query the program break
+    env.registers[10] = 0;
+    env.step();
+    assert_eq!(env.registers[10], 0x3000);
+    assert_eq!(env.registers.current_instruction_pointer, 4);
+
+    // Move the program break
+    let mut env = syscall_env(SYSCALL_BRK);
+    env.registers.program_break = 0x3000;
+    env.registers[10] = 0x3123;
+    env.step();
+    assert_eq!(env.registers[10], 0x3123);
+    assert_eq!(env.registers.program_break, 0x3123);
+    assert!(!env.halt);
+}
+
+#[test]
+pub fn test_syscall_mmap() {
+    let mut env: Env<Fp> = dummy_env();
+    write_program(
+        &mut env,
+        &[ECALL, ECALL]
+            .iter()
+            .flat_map(|instruction| instruction.to_le_bytes())
+            .collect::<Vec<u8>>(),
+    );
+    env.registers.heap_pointer = MMAP_START_ADDRESS;
+    env.registers[17] = SYSCALL_MMAP;
+    env.registers[13] = MAP_ANONYMOUS;
+    // The allocations are rounded up to a number of pages
+    env.registers[11] = PAGE_SIZE + 1;
+    env.step();
+    assert_eq!(env.registers[10], MMAP_START_ADDRESS);
+    env.registers[10] = 0;
+    env.registers[11] = PAGE_SIZE;
+    env.step();
+    assert_eq!(env.registers[10], MMAP_START_ADDRESS + 2 * PAGE_SIZE);
+    assert_eq!(
+        env.registers.heap_pointer,
+        MMAP_START_ADDRESS + 3 * PAGE_SIZE
+    );
+
+    // The address given by the program is returned as is
+    let mut env = syscall_env(SYSCALL_MMAP);
+    env.registers.heap_pointer = MMAP_START_ADDRESS;
+    env.registers[13] = MAP_ANONYMOUS;
+    env.registers[10] = 0x50000000;
+    env.registers[11] = PAGE_SIZE;
+    env.step();
+    assert_eq!(env.registers[10], 0x50000000);
+    assert_eq!(env.registers.heap_pointer, MMAP_START_ADDRESS);
+}
+
+#[test]
+pub fn test_syscall_clock_gettime() {
+    let mut env = syscall_env(SYSCALL_CLOCK_GETTIME64);
+    write_bytes(&mut env, 0x100, &[0xFF; 17]);
+    env.registers[10] = 1;
+    env.registers[11] = 0x100;
+    env.step();
+    assert_eq!(env.registers[10], 0);
+    assert_eq!(
+        read_bytes(&env, 0x100, 17),
+        [vec![0; 16], vec![0xFF]].concat()
+    );
+}
+
+#[test]
+pub fn test_syscall_getrandom() {
+    let mut env: Env<Fp> = dummy_env();
+    write_program(
+        &mut env,
+        &[ECALL, ECALL, ECALL]
+            .iter()
+            .flat_map(|instruction| instruction.to_le_bytes())
+            .collect::<Vec<u8>>(),
+    );
+    env.random_stream = Box::new(1..);
+    env.registers[17] = SYSCALL_GETRANDOM;
+    write_bytes(&mut env, 0x100, &[0xFF; 8]);
+
+    // At most 4 bytes are written at once
+    env.registers[10] = 0x100;
+    env.registers[11] = 6;
+    env.step();
+    assert_eq!(env.registers[10], 4);
+    // The bytes past the requested length are left unchanged
+    env.registers[10] = 0x104;
+    env.registers[11] = 2;
+    env.step();
+    assert_eq!(env.registers[10], 2);
+    assert_eq!(read_bytes(&env, 0x100, 8), [1, 2, 3, 4, 5, 6, 0xFF, 0xFF]);
+    // No byte is consumed when none is requested
+    env.registers[10] = 0x106;
+    env.registers[11] = 0;
+    env.step();
+    assert_eq!(env.registers[10], 0);
+    assert_eq!(env.random_stream.next(), Some(7));
+}
+
+/// A writer appending to a buffer shared with the test, to read the
+/// transcript of an oracle owned by the environment
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Runs `getrandom` for 6 bytes and reads the advice, returning the memory
+/// written by the program
+fn run_random_and_advice(env: &mut Env<Fp>) -> Vec<u8> {
+    write_program(
+        env,
+        &[ECALL; 5]
+            .iter()
+            .flat_map(|instruction| instruction.to_le_bytes())
+            .collect::<Vec<u8>>(),
+    );
+    write_bytes(env, 0x100, &[0xFF; 12]);
+    for (addr, len) in [(0x100, 4), (0x104, 2)] {
+        env.registers[17] = SYSCALL_GETRANDOM;
+        env.registers[10] = addr;
+        env.registers[11] = len;
+        env.step();
+    }
+    for (addr, len) in [(0x106, 2), (0x108, 4), (0x10C, 4)] {
+        env.registers[17] = SYSCALL_READ;
+        env.registers[10] = FD_ADVICE_READ;
+        env.registers[11] = addr;
+        env.registers[12] = len;
+        env.step();
+    }
+    read_bytes(env, 0x100, 12)
+}
+
+#[test]
+pub fn test_transcript_replays_random_and_advice() {
+    let transcript = SharedBuffer::default();
+    let mut env: Env<Fp> = dummy_env();
+    env.random_stream = Box::new(1..);
+    env.enqueue_advice(&[10, 11, 12, 13, 14]);
+    env.preimage_oracle = Box::new(RecordingOracle::new(NullPreImageOracle, transcript.clone()));
+    let recorded = run_random_and_advice(&mut env);
+    assert_eq!(recorded, [1, 2, 3, 4, 5, 6, 10, 11, 12, 13, 14, 0xFF]);
+
+    // The replay is given neither random bytes nor advice
+    let transcript = read_transcript(transcript.0.borrow().as_slice()).unwrap();
+    let mut env: Env<Fp> = dummy_env();
+    env.preimage_oracle = Box::new(ReplayOracle::new(transcript));
+    assert_eq!(run_random_and_advice(&mut env), recorded);
+    assert_eq!(env.random_stream.next(), None);
+}
+
+#[test]
+pub fn test_syscall_keccak256() {
+    let input = b"The quick brown fox jumps over the lazy dog";
+    let mut env = syscall_env(SYSCALL_KECCAK256);
+    write_bytes(&mut env, 0x100, input);
+    env.registers[10] = 0x100;
+    env.registers[11] = input.len() as u32;
+    env.registers[12] = 0x200;
+
+    // The system call is executed again until the input is absorbed, at most
+    // 4 bytes at a time
+    let mut steps = 0;
+    while env.registers.current_instruction_pointer == 0 {
+        env.step();
+        steps += 1;
+    }
+    assert_eq!(steps, input.len().div_ceil(4) + 1);
+    assert_eq!(env.registers[10], 0x100 + input.len() as u32);
+    assert_eq!(env.registers[11], 0);
+    let digest: [u8; 32] = Keccak256::digest(input).into();
+    assert_eq!(read_bytes(&env, 0x200, 32), digest);
+    assert_eq!(env.hash_counter, 1);
+    assert_eq!(env.keccak_inputs, vec![input.to_vec()]);
+}
+
+#[test]
+pub fn test_syscall_poseidon() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let input: [Fp; poseidon::STATE_SIZE] = array::from_fn(|_| rng.gen());
+    let mut env = syscall_env(SYSCALL_POSEIDON);
+    write_bytes(&mut env, 0x100, &encode_state(&input));
+    env.registers[10] = 0x100;
+    env.step();
+    assert_eq!(env.registers.current_instruction_pointer, 4);
+
+    // The state is permuted in place, as by the sponge of Mina
+    let mut expected = input.to_vec();
+    poseidon_block_cipher::<Fp, PlonkSpongeConstantsKimchi>(
+        fp_kimchi::static_params(),
+        &mut expected,
+    );
+    let output = decode_state(&read_bytes(&env, 0x100, 96));
+    assert_eq!(output.to_vec(), expected);
+    assert_eq!(env.poseidon_inputs.len(), 1);
+    assert_eq!(decode_state(&env.poseidon_inputs[0]), input);
+
+    // The row of the Poseidon sub-trace computing the permutation satisfies
+    // the constraints
+    let mut row = poseidon::witness::Env::permutation(fp_kimchi::static_params(), 0, input);
+    assert!(row.check(fp_kimchi::static_params()).is_empty());
+    assert_eq!(row.output(), output);
+}
+
+/// A custom precompile multiplying two 32-bit words into a 64-bit word
+struct WideningMul;
+
+const SYSCALL_WIDENING_MUL: u32 = 0x20000;
+
+impl Precompile<Fp> for WideningMul {
+    fn name(&self) -> &str {
+        "widening_mul"
+    }
+
+    fn syscall(&self) -> u32 {
+        SYSCALL_WIDENING_MUL
+    }
+
+    fn input_len(&self) -> usize {
+        8
+    }
+
+    fn output_len(&self) -> usize {
+        8
+    }
+
+    fn execute(&self, input: &[u8]) -> Vec<u8> {
+        let x = u32::from_le_bytes(input[0..4].try_into().unwrap()) as u64;
+        let y = u32::from_le_bytes(input[4..8].try_into().unwrap()) as u64;
+        (x * y).to_le_bytes().to_vec()
+    }
+
+    fn columns(&self) -> usize {
+        3
+    }
+
+    fn witness(&self, input: &[u8], output: &[u8]) -> Vec<Fp> {
+        vec![
+            Fp::from(u32::from_le_bytes(input[0..4].try_into().unwrap())),
+            Fp::from(u32::from_le_bytes(input[4..8].try_into().unwrap())),
+            Fp::from(u64::from_le_bytes(output.try_into().unwrap())),
+        ]
+    }
+
+    fn constraints(&self) -> Vec<E<Fp>> {
+        let [x, y, z] = array::from_fn(|i| precompile::variable(i, CurrOrNext::Curr));
+        vec![z - x * y]
+    }
+}
+
+#[test]
+pub fn test_register_precompile() {
+    let mut env: Env<Fp> = dummy_env();
+    assert_eq!(env.register_precompile(Box::new(WideningMul)), Ok(()));
+    assert_eq!(
+        env.register_precompile(Box::new(WideningMul)),
+        Err(PrecompileError::AlreadyRegistered(
+            SYSCALL_WIDENING_MUL,
+            "widening_mul".to_string()
+        ))
+    );
+
+    /// A precompile trying to replace a system call of the interpreter
+    struct Shadowing;
+
+    impl Precompile<Fp> for Shadowing {
+        fn name(&self) -> &str {
+            "shadowing"
+        }
+        fn syscall(&self) -> u32 {
+            SYSCALL_POSEIDON
+        }
+        fn input_len(&self) -> usize {
+            0
+        }
+        fn output_len(&self) -> usize {
+            0
+        }
+        fn execute(&self, _input: &[u8]) -> Vec<u8> {
+            vec![]
+        }
+        fn columns(&self) -> usize {
+            0
+        }
+        fn witness(&self, _input: &[u8], _output: &[u8]) -> Vec<Fp> {
+            vec![]
+        }
+        fn constraints(&self) -> Vec<E<Fp>> {
+            vec![]
+        }
+    }
+    assert_eq!(
+        env.register_precompile(Box::new(Shadowing)),
+        Err(PrecompileError::Reserved(SYSCALL_POSEIDON))
+    );
+}
+
+#[test]
+pub fn test_syscall_precompile() {
+    let mut env: Env<Fp> = dummy_env();
+    env.register_precompile(Box::new(WideningMul)).unwrap();
+    write_program(
+        &mut env,
+        &[ECALL, ECALL]
+            .iter()
+            .flat_map(|instruction| instruction.to_le_bytes())
+            .collect::<Vec<u8>>(),
+    );
+    let (x, y) = (0xFFFF_FFFFu32, 0x1234_5678u32);
+    write_bytes(
+        &mut env,
+        0x100,
+        &[x.to_le_bytes(), y.to_le_bytes()].concat(),
+    );
+    write_bytes(&mut env, 0x108, &[3, 0, 0, 0, 5, 0, 0, 0]);
+    for (input, output) in [(0x100, 0x200), (0x108, 0x208)] {
+        env.registers[17] = SYSCALL_WIDENING_MUL;
+        env.registers[10] = input;
+        env.registers[11] = output;
+        let (opcode, _instruction) = env.decode_instruction();
+        assert_eq!(
+            opcode,
+            Instruction::SyscallType(SyscallInstruction::SyscallPrecompile)
+        );
+        env.step();
+    }
+    assert_eq!(
+        read_bytes(&env, 0x200, 8),
+        (x as u64 * y as u64).to_le_bytes()
+    );
+    assert_eq!(read_bytes(&env, 0x208, 8), 15u64.to_le_bytes());
+
+    // The rows of the sub-trace identify the calls, read from the CPU, and
+    // satisfy the constraints of the precompile
+    let rows = env.precompiles.witness(SYSCALL_WIDENING_MUL);
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1][..N_CALL_COLS], [1, 0x108, 0x208].map(Fp::from));
+    assert!(env
+        .precompiles
+        .check(SYSCALL_WIDENING_MUL)
+        .unwrap()
+        .is_empty());
+    let lookups = env.precompiles.lookups(SYSCALL_WIDENING_MUL);
+    assert_eq!(lookups.len(), 1);
+    assert_eq!(lookups[0].table_id, LookupTableIDs::PrecompileLookup);
+
+    // A call whose output is not the product is rejected
+    env.precompiles.calls[0].output = 0u64.to_le_bytes().to_vec();
+    let unsatisfied = env.precompiles.check(SYSCALL_WIDENING_MUL).unwrap();
+    assert_eq!(unsatisfied.len(), 1);
+    assert_eq!((unsatisfied[0].row, unsatisfied[0].index), (0, 0));
+}
+
+/// The hints received by the preimage oracle
+type Hints = Rc<RefCell<Vec<Vec<u8>>>>;
+
+/// A preimage oracle answering with the key itself, reversed, and recording
+/// the hints it receives
+struct TestPreImageOracle {
+    hints: Hints,
+}
+
+impl PreImageOracleT for TestPreImageOracle {
+    fn get_preimage(&mut self, key: [u8; 32]) -> Preimage {
+        Preimage::create(key.iter().rev().copied().collect())
+    }
+
+    fn hint(&mut self, hint: Hint) {
+        self.hints.borrow_mut().push(hint.get())
+    }
+}
+
+/// Creates an environment running `n` system calls `syscall_number` on the
+/// file descriptor `fd`
+fn preimage_env(n: usize, syscall_number: u32, fd: u32) -> (Env<Fp>, Hints) {
+    let mut env: Env<Fp> = dummy_env();
+    write_program(
+        &mut env,
+        &vec![ECALL; n]
+            .iter()
+            .flat_map(|instruction| instruction.to_le_bytes())
+            .collect::<Vec<u8>>(),
+    );
+    let hints = Rc::new(RefCell::new(vec![]));
+    env.preimage_oracle = Box::new(TestPreImageOracle {
+        hints: hints.clone(),
+    });
+    env.registers[17] = syscall_number;
+    env.registers[10] = fd;
+    (env, hints)
+}
+
+/// Writes the `length` bytes at `address` to the file descriptor `fd` the way
+/// a program would, looping on the partial writes, and returns the number of
+/// system calls
+fn write_all(env: &mut Env<Fp>, fd: u32, address: u32, length: u32) -> usize {
+    let mut written = 0;
+    let mut calls = 0;
+    while written < length {
+        env.registers[10] = fd;
+        env.registers[11] = address + written;
+        env.registers[12] = length - written;
+        env.step();
+        calls += 1;
+        assert!(env.registers[10] > 0);
+        written += env.registers[10];
+    }
+    calls
+}
+
+#[test]
+pub fn test_syscall_write_preimage_key() {
+    // At most 4 bytes are written at once, up to the end of the word in memory
+    // and to the end of the register of the key, so that a key that is not
+    // aligned is written 2 bytes at a time
+    for (address, expected_calls) in [(0x100, 8), (0x102, 16)] {
+        let (mut env, _hints) = preimage_env(16, SYSCALL_WRITE, FD_PREIMAGE_WRITE);
+        let key: Vec<u8> = (1..=32).collect();
+        write_bytes(&mut env, address, &key);
+        env.registers.preimage_key = [0xFFFFFFFF; 8];
+        env.registers.preimage_offset = 3;
+        let calls = write_all(&mut env, FD_PREIMAGE_WRITE, address, 32);
+        assert_eq!(calls, expected_calls);
+        for i in 0..8 {
+            assert_eq!(
+                env.registers.preimage_key[i],
+                u32::from_be_bytes(key[4 * i..4 * i + 4].try_into().unwrap())
+            );
+        }
+        // The next read starts at the beginning of the new preimage
+        assert_eq!(env.registers.preimage_offset, 0);
+        assert!(!env.halt);
+    }
+}
+
+#[test]
+pub fn test_syscall_read_preimage() {
+    let (mut env, _hints) = preimage_env(16, SYSCALL_READ, FD_PREIMAGE_READ);
+    let key: [u8; 32] = array::from_fn(|i| i as u8 + 1);
+    for i in 0..8 {
+        env.registers.preimage_key[i] =
+            u32::from_be_bytes(key[4 * i..4 * i + 4].try_into().unwrap());
+    }
+    let mut read = 0;
+    loop {
+        env.registers[10] = FD_PREIMAGE_READ;
+        env.registers[11] = 0x100 + read;
+        env.registers[12] = 64 - read;
+        env.step();
+        // At most 4 bytes are read at once
+        assert!(env.registers[10] <= 4);
+        if env.registers[10] == 0 {
+            break;
+        }
+        read += env.registers[10];
+        assert_eq!(env.registers.preimage_offset, read);
+    }
+    // The preimage is prefixed with its length
+    assert_eq!(read, 40);
+    assert_eq!(
+        read_bytes(&env, 0x100, 40),
+        [
+            32u64.to_be_bytes().to_vec(),
+            key.iter().rev().copied().collect()
+        ]
+        .concat()
+    );
+}
+
+#[test]
+pub fn test_syscall_write_hint() {
+    let (mut env, hints) = preimage_env(4, SYSCALL_WRITE, FD_HINT_WRITE);
+    write_bytes(&mut env, 0x100, &[0, 0, 0, 3, 1, 2, 3, 0, 0, 0, 2, 4, 5]);
+    // The second hint is written in two parts
+    env.registers[11] = 0x100;
+    env.registers[12] = 12;
+    env.step();
+    assert_eq!(env.registers[10], 12);
+    assert_eq!(*hints.borrow(), vec![vec![1, 2, 3]]);
+    env.registers[10] = FD_HINT_WRITE;
+    env.registers[11] = 0x10C;
+    env.registers[12] = 1;
+    env.step();
+    assert_eq!(env.registers[10], 1);
+    assert_eq!(*hints.borrow(), vec![vec![1, 2, 3], vec![4, 5]]);
+    assert_eq!(env.last_hint, Some(vec![]));
+
+    // The hint responses are ignored
+    env.registers[17] = SYSCALL_READ;
+    env.registers[10] = FD_HINT_READ;
+    env.registers[12] = 8;
+    env.step();
+    assert_eq!(env.registers[10], 8);
+    assert!(!env.halt);
+}
+
+#[test]
+pub fn test_syscall_read_advice() {
+    let (mut env, _hints) = preimage_env(8, SYSCALL_READ, FD_ADVICE_READ);
+    env.enqueue_advice(&[1, 2, 3]);
+    env.enqueue_advice(&[4, 5, 6, 7]);
+    // The advice is read at most 4 bytes at a time, up to the end of the word
+    let mut lengths = vec![];
+    let mut read = 0;
+    loop {
+        env.registers[10] = FD_ADVICE_READ;
+        env.registers[11] = 0x102 + read;
+        env.registers[12] = 16 - read;
+        env.step();
+        if env.registers[10] == 0 {
+            break;
+        }
+        lengths.push(env.registers[10]);
+        read += env.registers[10];
+    }
+    assert_eq!(lengths, vec![2, 4, 1]);
+    assert_eq!(read_bytes(&env, 0x102, 7), vec![1, 2, 3, 4, 5, 6, 7]);
+    assert!(env.advice.is_empty());
+    assert!(!env.halt);
+}
+
+#[test]
+pub fn test_syscall_read_stdin() {
+    let (mut env, _hints) = preimage_env(8, SYSCALL_READ, FD_STDIN);
+    env.stdin = Box::new(Cursor::new(vec![1, 2, 3, 4, 5, 6]));
+    // The input is read at most 4 bytes at a time, up to the end of the word
+    let mut lengths = vec![];
+    let mut read = 0;
+    loop {
+        env.registers[10] = FD_STDIN;
+        env.registers[11] = 0x102 + read;
+        env.registers[12] = 16 - read;
+        env.step();
+        if env.registers[10] == 0 {
+            break;
+        }
+        lengths.push(env.registers[10]);
+        read += env.registers[10];
+    }
+    assert_eq!(lengths, vec![2, 4]);
+    assert_eq!(read_bytes(&env, 0x102, 6), vec![1, 2, 3, 4, 5, 6]);
+    assert!(!env.halt);
+}
+
+fn encode_addi(rd: u32, rs1: u32, imm: u32) -> u32 {
+    (imm << 20) | (rs1 << 15) | (rd << 7) | 0b0010011
+}
+
+// An environment running a program writing "hello" to its standard output,
+// "!" to its standard error, and exiting with the code 3
+fn output_program_env() -> Env<Fp> {
+    let mut env: Env<Fp> = dummy_env();
+    let program = [
+        // write(1, 0x100, 5)
+        encode_addi(10, 0, FD_STDOUT),
+        encode_addi(11, 0, 0x100),
+        encode_addi(12, 0, 5),
+        encode_addi(17, 0, SYSCALL_WRITE),
+        ECALL,
+        // write(2, 0x105, 1)
+        encode_addi(10, 0, FD_STDERR),
+        encode_addi(11, 0, 0x105),
+        encode_addi(12, 0, 1),
+        ECALL,
+        // exit(3)
+        encode_addi(10, 0, 3),
+        encode_addi(17, 0, SYSCALL_EXIT),
+        ECALL,
+    ];
+    write_program(
+        &mut env,
+        &program
+            .iter()
+            .flat_map(|instruction| instruction.to_le_bytes())
+            .collect::<Vec<u8>>(),
+    );
+    write_bytes(&mut env, 0x100, b"hello!");
+    env
+}
+
+#[test]
+pub fn test_run_with_output() {
+    assert_eq!(
+        output_program_env().run(),
+        ExecutionResult {
+            status: ExitStatus::Exited(3),
+            steps: 12,
+            stdout: b"hello".to_vec(),
+            stderr: b"!".to_vec(),
+        }
+    );
+}
+
+#[test]
+pub fn test_run_with_max_cycles() {
+    // The program is halted before writing to its standard error
+    let mut env = output_program_env();
+    env.max_cycles = Some(7);
+    assert_eq!(
+        env.run(),
+        ExecutionResult {
+            status: ExitStatus::CycleLimitExceeded,
+            steps: 7,
+            stdout: b"hello".to_vec(),
+            stderr: vec![],
+        }
+    );
+    assert!(env.halt);
+
+    // A program exiting at the limit is not stopped
+    let mut env = output_program_env();
+    env.max_cycles = Some(12);
+    assert_eq!(env.run().status, ExitStatus::Exited(3));
+}
+
+// The connection with gdb, replaying the packets `input` and recording the
+// answers of the stub
+struct GdbConnection {
+    input: Cursor<Vec<u8>>,
+    output: Vec<u8>,
+}
+
+impl Read for GdbConnection {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.input.read(buf)
+    }
+}
+
+impl Write for GdbConnection {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.output.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn gdb_packet(data: &str) -> String {
+    let checksum = data.bytes().fold(0u8, |sum, byte| sum.wrapping_add(byte));
+    format!("${data}#{checksum:02x}")
+}
+
+#[test]
+pub fn test_gdb_session() {
+    let session = [
+        ("?", "S05"),
+        // Break before the first write
+        ("Z0,10,4", "OK"),
+        ("c", "S05"),
+        ("p11", "40000000"),
+        ("m100,5", "68656c6c6f"),
+        ("M100,1:4a", "OK"),
+        ("s", "S05"),
+        ("p20", "14000000"),
+        ("z0,10,4", "OK"),
+        ("c", "W03"),
+    ];
+    let mut connection = GdbConnection {
+        input: Cursor::new(
+            session
+                .iter()
+                .map(|(request, _)| format!("+{}", gdb_packet(request)))
+                .chain(["+$k#6b".to_string()])
+                .collect::<String>()
+                .into_bytes(),
+        ),
+        output: vec![],
+    };
+    let mut stub = GdbStub::new(output_program_env());
+    stub.serve(&mut connection).unwrap();
+
+    let expected: String = session
+        .iter()
+        .map(|(_, answer)| format!("+{}", gdb_packet(answer)))
+        .chain(["+".to_string()])
+        .collect();
+    assert_eq!(String::from_utf8(connection.output).unwrap(), expected);
+    assert_eq!(stub.target.written_stdout, b"Jello");
+    assert_eq!(stub.target.exit_code, Some(3));
+}
+
+#[test]
+pub fn test_snapshot_resume() {
+    let mut env = output_program_env();
+    // Stop after the first write
+    for _ in 0..6 {
+        env.step();
+    }
+    let snapshot = serde_json::to_string(&env.snapshot()).unwrap();
+
+    let mut resumed: Env<Fp> = dummy_env();
+    resumed.resume(serde_json::from_str(&snapshot).unwrap());
+    assert_eq!(resumed.run(), output_program_env().run());
+}
+
+/// The quotient and the remainder of a division, as specified by the RISC-V
+/// specification, section 13.2
+fn expected_division(mtype: MInstruction, x: u32, y: u32) -> u32 {
+    let (sx, sy) = (x as i32, y as i32);
+    match mtype {
+        MInstruction::Div if y == 0 => u32::MAX,
+        MInstruction::Div => sx.wrapping_div(sy) as u32,
+        MInstruction::Divu => x.checked_div(y).unwrap_or(u32::MAX),
+        MInstruction::Rem if y == 0 => x,
+        MInstruction::Rem => sx.wrapping_rem(sy) as u32,
+        MInstruction::Remu => x.checked_rem(y).unwrap_or(x),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+pub fn test_division_corner_cases() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let mut values = vec![
+        0,
+        1,
+        2,
+        7,
+        u32::MAX,
+        -2i32 as u32,
+        -7i32 as u32,
+        i32::MIN as u32,
+        i32::MAX as u32,
+    ];
+    values.extend((0..8).map(|_| rng.gen::<u32>()));
+    for (funct3, mtype) in [
+        (0b100u32, MInstruction::Div),
+        (0b101, MInstruction::Divu),
+        (0b110, MInstruction::Rem),
+        (0b111, MInstruction::Remu),
+    ] {
+        for &x in values.iter() {
+            for &y in values.iter() {
+                let mut env: Env<Fp> = dummy_env();
+                // <op> x7, x5, x6
+                let instruction = (1 << 25) | (6 << 20) | (5 << 15) | (funct3 << 12) | (7 << 7);
+                write_program(&mut env, &(instruction | 0b0110011).to_le_bytes());
+                env.registers[5] = x;
+                env.registers[6] = y;
+
+                assert_eq!(env.step(), Instruction::MType(mtype));
+                assert_eq!(
+                    env.registers[7],
+                    expected_division(mtype, x, y),
+                    "{mtype} {x:#x} {y:#x}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+pub fn test_coverage_of_an_execution() {
+    let mut env: Env<Fp> = dummy_env();
+    let program: Vec<u8> = [
+        0x00150513u32, // addi a0, a0, 1
+        0x00150513,    // addi a0, a0, 1
+        0x00a505b3,    // add a1, a0, a0
+    ]
+    .iter()
+    .flat_map(|instruction| instruction.to_le_bytes())
+    .collect();
+    write_program(&mut env, &program);
+    env.enable_coverage();
+    for _ in 0..3 {
+        env.step();
+    }
+    assert_eq!(env.registers[11], 4);
+    let execution = env.take_coverage().unwrap();
+    assert_eq!(execution.rows(), 3);
+    assert!(env.coverage.is_none());
+
+    let mut constraints_env = constraints::Env::<Fp>::default();
+    let report = constraints_env.coverage(&execution);
+    let instructions: Vec<_> = report
+        .executed
+        .iter()
+        .map(|i| (i.instruction, i.rows))
+        .collect();
+    assert_eq!(
+        instructions,
+        [
+            (Instruction::RType(RInstruction::Add), 1),
+            (Instruction::IType(IInstruction::AddImmediate), 2)
+        ]
+    );
+    assert_eq!(report.unexercised.len(), INSTRUCTION_SET_SIZE - 2);
+    for instruction in report.executed.iter() {
+        assert!(instruction.constraints > 0);
+        assert!(instruction.written.contains(&ScratchColumn::Scratch(0)));
+        assert!(instruction.constrained.contains(&ScratchColumn::Scratch(0)));
+    }
+    assert!(report
+        .never_written
+        .contains(&ScratchColumn::Scratch(SCRATCH_SIZE - 1)));
+    assert_eq!(
+        report.active_constraints(),
+        report.executed.iter().map(|i| i.constraints).sum::<usize>()
+    );
+    let rendered = report.to_string();
+    assert!(rendered.starts_with(&format!(
+        "2 of {INSTRUCTION_SET_SIZE} instructions executed\n"
+    )));
+    assert!(rendered.contains("  RType(Add): 1 rows, "));
+    assert!(rendered.contains("  IType(AddImmediate): 2 rows, "));
+}
@@ -2,26 +2,57 @@
 //       to the SAME register/memory address?
 use super::{
     column::Column,
+    commit_log::{self, Commit, CommitLog},
     interpreter::{
-        self, IInstruction, Instruction, InterpreterEnv, MInstruction, RInstruction, SBInstruction,
-        SInstruction, SyscallInstruction, UInstruction, UJInstruction,
+        self, AInstruction, CInstruction, CSRInstruction, IInstruction, Instruction,
+        InterpreterEnv, MInstruction, RInstruction, SBInstruction, SInstruction,
+        SyscallInstruction, UInstruction, UJInstruction, BUILTIN_SYSCALLS, FD_ADVICE_READ,
+        FD_HINT_READ, FD_HINT_WRITE, FD_PREIMAGE_READ, FD_PREIMAGE_WRITE, FD_STDERR, FD_STDIN,
+        FD_STDOUT, MAP_ANONYMOUS, SYSCALL_BRK, SYSCALL_CLOCK_GETTIME64, SYSCALL_EXIT,
+        SYSCALL_EXIT_GROUP, SYSCALL_GETRANDOM, SYSCALL_MMAP, SYSCALL_READ, SYSCALL_WRITE,
     },
-    registers::Registers,
+    layout::{MemoryLayout, MemoryLayoutError},
+    registers::{self, Registers, CSR_MISA, MISA, REGISTER_CSR_OFFSET},
     INSTRUCTION_SET_SIZE, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE,
 };
 use crate::{
-    cannon::{State, PAGE_ADDRESS_MASK, PAGE_ADDRESS_SIZE, PAGE_SIZE},
+    cannon::{Hint, State, PAGE_ADDRESS_SIZE, PAGE_SIZE},
+    interpreters::{
+        coverage::ExecutionCoverage,
+        keccak::precompile::{
+            KeccakHint, KeccakPrecompile, KECCAK_PRECOMPILE_CHUNK_LEN, SYSCALL_KECCAK256,
+        },
+        poseidon::precompile::{
+            PoseidonHint, PoseidonPrecompile, POSEIDON_STATE_BYTES, SYSCALL_POSEIDON,
+        },
+        precompile::{Precompile, PrecompileCall, PrecompileError, PrecompileRegistry},
+        xlen::{Rv32, Xlen},
+    },
     lookups::Lookup,
+    memory::SparseMemory,
+    merkle_memory::{self, AuthenticatedAccess, MerkleMemory},
+    preimage_oracle::{HostStream, NullPreImageOracle, PreImageOracleT},
+    profiler::{ControlFlow, ProfileReport, Profiler, Symbols},
 };
 use ark_ff::Field;
-use std::array;
+use serde::{Deserialize, Serialize};
+use std::{
+    array,
+    collections::VecDeque,
+    io::{Read, Write},
+};
 
 /// Maximum number of register accesses per instruction (based on demo)
 // FIXME: can be different
 pub const MAX_NB_REG_ACC: u64 = 7;
-/// Maximum number of memory accesses per instruction (based on demo)
+/// Maximum number of memory accesses per instruction, reached by the system
+/// call `clock_gettime64` (4 bytes of instruction and 16 bytes of `timespec`)
 // FIXME: can be different
-pub const MAX_NB_MEM_ACC: u64 = 12;
+pub const MAX_NB_MEM_ACC: u64 = 20;
+
+/// The address of the first anonymous mapping created with `mmap`, above the
+/// stack
+pub const MMAP_START_ADDRESS: u32 = 0x41000000;
 /// Maximum number of memory or register accesses per instruction
 pub const MAX_ACC: u64 = MAX_NB_REG_ACC + MAX_NB_MEM_ACC;
 
@@ -38,10 +69,14 @@ pub const NUM_LOOKUP_TERMS: usize =
 /// that is used to verify the preimage requested during the execution.
 pub struct Env<Fp> {
     pub instruction_counter: u64,
-    pub memory: Vec<(u32, Vec<u8>)>,
-    pub last_memory_accesses: [usize; 3],
-    pub memory_write_index: Vec<(u32, Vec<u64>)>,
-    pub last_memory_write_index_accesses: [usize; 3],
+    pub memory: SparseMemory<u32, u8>,
+    pub memory_write_index: SparseMemory<u32, u64>,
+    /// The Merkle tree of the memory, when the memory accesses are
+    /// authenticated, see [Env::enable_merkle_memory]
+    pub merkle_memory: Option<MerkleMemory>,
+    /// The memory accesses authenticated since the last call to
+    /// [Env::take_authenticated_accesses]
+    pub authenticated_accesses: Vec<AuthenticatedAccess>,
     pub registers: Registers<u32>,
     pub registers_write_index: Registers<u64>,
     pub scratch_state_idx: usize,
@@ -50,6 +85,116 @@ pub struct Env<Fp> {
     pub scratch_state_inverse: [Fp; SCRATCH_SIZE_INVERSE],
     pub halt: bool,
     pub selector: usize,
+    /// The bytes returned by the system call `getrandom`. The stream is given
+    /// by the host, so that the execution is deterministic, and is empty by
+    /// default.
+    pub random_stream: Box<dyn Iterator<Item = u8>>,
+    /// The oracle answering the preimage requests and receiving the hints.
+    /// No oracle is available by default.
+    pub preimage_oracle: Box<dyn PreImageOracleT>,
+    /// The preimage being read, fetched from the oracle at the first read
+    pub preimage: Option<Vec<u8>>,
+    /// The bytes of the hints written, not yet sent to the oracle
+    pub last_hint: Option<Vec<u8>>,
+    /// The advice given by the prover, not read yet by the program. See
+    /// [Env::enqueue_advice].
+    pub advice: VecDeque<u8>,
+    /// The standard input of the program, given by the host. It is empty by
+    /// default.
+    pub stdin: Box<dyn Read>,
+    /// The host channel the standard output of the program is forwarded to.
+    /// The output is discarded by default.
+    pub stdout: Box<dyn Write>,
+    /// The host channel the standard error of the program is forwarded to.
+    /// The output is discarded by default.
+    pub stderr: Box<dyn Write>,
+    /// The bytes written by the program to its standard output, returned in
+    /// the [ExecutionResult]
+    pub written_stdout: Vec<u8>,
+    /// The bytes written by the program to its standard error, returned in
+    /// the [ExecutionResult]
+    pub written_stderr: Vec<u8>,
+    /// The exit code given by the program to `exit`
+    pub exit_code: Option<u32>,
+    /// The profiler recording the execution, see [Env::enable_profiling]
+    pub profiler: Option<Profiler>,
+    /// The log of the instructions executed, see [Env::enable_commit_log]
+    pub commit_log: Option<CommitLog>,
+    /// The instructions executed and the scratch columns they write, see
+    /// [Env::enable_coverage]
+    pub coverage: Option<ExecutionCoverage<Instruction>>,
+    /// The number of instructions after which the execution is halted, if
+    /// any, so that a program running away does not exhaust the host.
+    /// There is no limit by default.
+    pub max_cycles: Option<u64>,
+    /// Set when the execution is halted by [Env::max_cycles]
+    pub cycle_limit_exceeded: bool,
+    /// The state of the current call to the Keccak precompile
+    pub keccak_precompile: KeccakPrecompile,
+    /// The number of hashes computed by the Keccak precompile
+    pub hash_counter: u64,
+    /// The inputs of the hashes computed by the Keccak precompile, in order,
+    /// to be given to the Keccak sub-trace
+    pub keccak_inputs: Vec<Vec<u8>>,
+    /// The last permutation computed by the Poseidon precompile
+    pub poseidon_precompile: PoseidonPrecompile,
+    /// The encodings of the inputs of the permutations computed by the
+    /// Poseidon precompile, in order, to be given to the Poseidon sub-trace.
+    /// See [crate::interpreters::poseidon::precompile::decode_state].
+    pub poseidon_inputs: Vec<Vec<u8>>,
+    /// The custom precompiles registered by the user, with the calls made to
+    /// them, see [Env::register_precompile]
+    pub precompiles: PrecompileRegistry<Fp>,
+}
+
+/// How the execution of a program ended
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExitStatus {
+    /// The program exited with the given exit code
+    Exited(u32),
+    /// The program halted without an exit code
+    Halted,
+    /// The program was halted after running for [Env::max_cycles]
+    /// instructions
+    CycleLimitExceeded,
+}
+
+/// The result of the execution of a program, see [Env::run]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecutionResult {
+    pub status: ExitStatus,
+    /// The number of instructions executed, or cycles
+    pub steps: u64,
+    /// The bytes written by the program to its standard output
+    pub stdout: Vec<u8>,
+    /// The bytes written by the program to its standard error
+    pub stderr: Vec<u8>,
+}
+
+/// A snapshot of the state of an execution, from which it can be resumed
+/// after a crash without replaying it, see [Env::snapshot] and
+/// [Env::resume]. The host resources of the environment, like the preimage
+/// oracle or the standard streams, are not part of the snapshot.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub instruction_counter: u64,
+    pub memory: SparseMemory<u32, u8>,
+    pub memory_write_index: SparseMemory<u32, u64>,
+    pub registers: Registers<u32>,
+    pub registers_write_index: Registers<u64>,
+    pub halt: bool,
+    pub preimage: Option<Vec<u8>>,
+    pub last_hint: Option<Vec<u8>>,
+    pub advice: VecDeque<u8>,
+    pub written_stdout: Vec<u8>,
+    pub written_stderr: Vec<u8>,
+    pub exit_code: Option<u32>,
+    pub cycle_limit_exceeded: bool,
+    pub keccak_precompile: KeccakPrecompile,
+    pub hash_counter: u64,
+    pub keccak_inputs: Vec<Vec<u8>>,
+    pub poseidon_inputs: Vec<Vec<u8>>,
+    pub precompile_calls: Vec<PrecompileCall>,
 }
 
 fn fresh_scratch_state<Fp: Field, const N: usize>() -> [Fp; N] {
@@ -57,6 +202,8 @@ fn fresh_scratch_state<Fp: Field, const N: usize>() -> [Fp; N] {
 }
 
 impl<Fp: Field> InterpreterEnv for Env<Fp> {
+    type Xlen = Rv32;
+
     type Position = Column;
 
     fn alloc_scratch(&mut self) -> Self::Position {
@@ -172,21 +319,26 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         output: Self::Position,
     ) -> Self::Variable {
         let addr: u32 = (*addr).try_into().unwrap();
-        let page = addr >> PAGE_ADDRESS_SIZE;
-        let page_address = (addr & PAGE_ADDRESS_MASK) as usize;
-        let memory_page_idx = self.get_memory_page_index(page);
-        let value = self.memory[memory_page_idx].1[page_address];
+        let value = self.memory.get(addr);
         self.write_column(output, value.into());
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.record_memory_access(addr);
+        }
         value.into()
     }
 
     unsafe fn push_memory(&mut self, addr: &Self::Variable, value: Self::Variable) {
         let addr: u32 = (*addr).try_into().unwrap();
-        let page = addr >> PAGE_ADDRESS_SIZE;
-        let page_address = (addr & PAGE_ADDRESS_MASK) as usize;
-        let memory_page_idx = self.get_memory_page_index(page);
-        self.memory[memory_page_idx].1[page_address] =
-            value.try_into().expect("push_memory values fit in a u8");
+        self.memory.set(
+            addr,
+            value.try_into().expect("push_memory values fit in a u8"),
+        );
+        if let Some(merkle_memory) = self.merkle_memory.as_mut() {
+            merkle_memory.update(addr & !3, merkle_memory::word_at(&self.memory, addr));
+        }
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.record_memory_access(addr);
+        }
     }
 
     unsafe fn fetch_memory_access(
@@ -195,24 +347,34 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         output: Self::Position,
     ) -> Self::Variable {
         let addr: u32 = (*addr).try_into().unwrap();
-        let page = addr >> PAGE_ADDRESS_SIZE;
-        let page_address = (addr & PAGE_ADDRESS_MASK) as usize;
-        let memory_write_index_page_idx = self.get_memory_access_page_index(page);
-        let value = self.memory_write_index[memory_write_index_page_idx].1[page_address];
+        let value = self.memory_write_index.get(addr);
         self.write_column(output, value);
         value
     }
 
     unsafe fn push_memory_access(&mut self, addr: &Self::Variable, value: Self::Variable) {
-        let addr = *addr as u32;
-        let page = addr >> PAGE_ADDRESS_SIZE;
-        let page_address = (addr & PAGE_ADDRESS_MASK) as usize;
-        let memory_write_index_page_idx = self.get_memory_access_page_index(page);
-        self.memory_write_index[memory_write_index_page_idx].1[page_address] = value;
+        self.memory_write_index.set(*addr as u32, value);
     }
 
-    fn constant(x: u32) -> Self::Variable {
-        x as u64
+    fn authenticate_memory_access(
+        &mut self,
+        addr: &Self::Variable,
+        _old_value: &Self::Variable,
+        new_value: &Self::Variable,
+    ) {
+        if let Some(merkle_memory) = self.merkle_memory.as_mut() {
+            let addr: u32 = (*addr).try_into().unwrap();
+            let old_word = merkle_memory::word_at(&self.memory, addr);
+            let mut new_word = old_word.to_be_bytes();
+            new_word[(addr & 3) as usize] =
+                (*new_value).try_into().expect("memory values fit in a u8");
+            let access = merkle_memory.access(addr, old_word, u32::from_be_bytes(new_word));
+            self.authenticated_accesses.push(access);
+        }
+    }
+
+    fn constant(x: u64) -> Self::Variable {
+        x
     }
 
     unsafe fn bitmask(
@@ -222,17 +384,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         lowest_bit: u32,
         position: Self::Position,
     ) -> Self::Variable {
-        assert!(
-            lowest_bit < highest_bit,
-            "The lowest bit must be strictly lower than the highest bit"
-        );
-        assert!(
-            highest_bit <= 32,
-            "The interpreter is for a 32bits architecture"
-        );
-        let x: u32 = (*x).try_into().unwrap();
-        let res = (x >> lowest_bit) & ((1 << (highest_bit - lowest_bit)) - 1);
-        let res = res as u64;
+        let res = Rv32::bitmask(*x, highest_bit, lowest_bit);
         self.write_column(position, res);
         res
     }
@@ -243,10 +395,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         by: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let by: u32 = (*by).try_into().unwrap();
-        let res = x << by;
-        let res = res as u64;
+        let res = Rv32::shift_left(*x, *by);
         self.write_column(position, res);
         res
     }
@@ -257,10 +406,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         by: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let by: u32 = (*by).try_into().unwrap();
-        let res = x >> by;
-        let res = res as u64;
+        let res = Rv32::shift_right(*x, *by);
         self.write_column(position, res);
         res
     }
@@ -271,10 +417,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         by: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let by: u32 = (*by).try_into().unwrap();
-        let res = ((x as i32) >> by) as u32;
-        let res = res as u64;
+        let res = Rv32::shift_right_arithmetic(*x, *by);
         self.write_column(position, res);
         res
     }
@@ -326,10 +469,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = if x < y { 1 } else { 0 };
-        let res = res as u64;
+        let res = Rv32::less_than(*x, *y);
         self.write_column(position, res);
         res
     }
@@ -340,10 +480,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = if (x as i32) < (y as i32) { 1 } else { 0 };
-        let res = res as u64;
+        let res = Rv32::less_than_signed(*x, *y);
         self.write_column(position, res);
         res
     }
@@ -354,10 +491,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = x & y;
-        let res = res as u64;
+        let res = Rv32::and(*x, *y);
         self.write_column(position, res);
         res
     }
@@ -368,10 +502,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = !(x | y);
-        let res = res as u64;
+        let res = Rv32::nor(*x, *y);
         self.write_column(position, res);
         res
     }
@@ -382,10 +513,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = x | y;
-        let res = res as u64;
+        let res = Rv32::or(*x, *y);
         self.write_column(position, res);
         res
     }
@@ -396,10 +524,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = x ^ y;
-        let res = res as u64;
+        let res = Rv32::xor(*x, *y);
         self.write_column(position, res);
         res
     }
@@ -411,14 +536,10 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         out_position: Self::Position,
         overflow_position: Self::Position,
     ) -> (Self::Variable, Self::Variable) {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        // https://doc.rust-lang.org/std/primitive.u32.html#method.overflowing_add
-        let res = x.overflowing_add(y);
-        let (res_, overflow) = (res.0 as u64, res.1 as u64);
-        self.write_column(out_position, res_);
+        let (res, overflow) = Rv32::overflowing_add(*x, *y);
+        self.write_column(out_position, res);
         self.write_column(overflow_position, overflow);
-        (res_, overflow)
+        (res, overflow)
     }
 
     unsafe fn sub_witness(
@@ -428,14 +549,10 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         out_position: Self::Position,
         underflow_position: Self::Position,
     ) -> (Self::Variable, Self::Variable) {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        // https://doc.rust-lang.org/std/primitive.u32.html#method.overflowing_sub
-        let res = x.overflowing_sub(y);
-        let (res_, underflow) = (res.0 as u64, res.1 as u64);
-        self.write_column(out_position, res_);
+        let (res, underflow) = Rv32::overflowing_sub(*x, *y);
+        self.write_column(out_position, res);
         self.write_column(underflow_position, underflow);
-        (res_, underflow)
+        (res, underflow)
     }
 
     unsafe fn mul_signed_witness(
@@ -444,10 +561,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = ((x as i32) * (y as i32)) as u32;
-        let res = res as u64;
+        let res = Rv32::mul_lo(*x, *y);
         self.write_column(position, res);
         res
     }
@@ -458,11 +572,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: i32 = (*x).try_into().unwrap();
-        let y: i32 = (*y).try_into().unwrap();
-        let res = (x as i64) * (y as i64);
-        let res = (res >> 32) as i32;
-        let res = res as u64;
+        let res = Rv32::mul_hi_signed(*x, *y);
         self.write_column(position, res);
         res
     }
@@ -473,11 +583,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: i32 = (*x).try_into().unwrap();
-        let y: i32 = (*y).try_into().unwrap();
-        let res = ((x as i64) * (y as i64)) as u64;
-        let res = (res & ((1 << 32) - 1)) as u32;
-        let res = res as u64;
+        let res = Rv32::mul_lo(*x, *y);
         self.write_column(position, res);
         res
     }
@@ -488,11 +594,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = (x as u64) * (y as u64);
-        let res = (res >> 32) as u32;
-        let res = res as u64;
+        let res = Rv32::mul_hi(*x, *y);
         self.write_column(position, res);
         res
     }
@@ -503,11 +605,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = (((x as i32) as i64) * (y as i64)) as u64;
-        let res = (res >> 32) as u32;
-        let res = res as u64;
+        let res = Rv32::mul_hi_signed_unsigned(*x, *y);
         self.write_column(position, res);
         res
     }
@@ -518,10 +616,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: i32 = (*x).try_into().unwrap();
-        let y: i32 = (*y).try_into().unwrap();
-        let res = (x / y) as u32;
-        let res = res as u64;
+        let res = Rv32::div_signed(*x, *y);
         self.write_column(position, res);
         res
     }
@@ -532,11 +627,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = (x as u64) * (y as u64);
-        let res = (res & ((1 << 32) - 1)) as u32;
-        let res = res as u64;
+        let res = Rv32::mul_lo(*x, *y);
         self.write_column(position, res);
         res
     }
@@ -547,10 +638,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: i32 = (*x).try_into().unwrap();
-        let y: i32 = (*y).try_into().unwrap();
-        let res = (x % y) as u32;
-        let res = res as u64;
+        let res = Rv32::rem_signed(*x, *y);
         self.write_column(position, res);
         res
     }
@@ -561,10 +649,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = x / y;
-        let res = res as u64;
+        let res = Rv32::div(*x, *y);
         self.write_column(position, res);
         res
     }
@@ -575,10 +660,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         y: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let y: u32 = (*y).try_into().unwrap();
-        let res = x % y;
-        let res = res as u64;
+        let res = Rv32::rem(*x, *y);
         self.write_column(position, res);
         res
     }
@@ -588,9 +670,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         x: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let res = x.leading_zeros();
-        let res = res as u64;
+        let res = Rv32::count_leading_zeros(*x);
         self.write_column(position, res);
         res
     }
@@ -600,9 +680,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         x: &Self::Variable,
         position: Self::Position,
     ) -> Self::Variable {
-        let x: u32 = (*x).try_into().unwrap();
-        let res = x.leading_ones();
-        let res = res as u64;
+        let res = Rv32::count_leading_ones(*x);
         self.write_column(position, res);
         res
     }
@@ -612,6 +690,42 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         *x
     }
 
+    unsafe fn random_byte(
+        &mut self,
+        if_is_true: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = if *if_is_true == 1 {
+            let byte = self.random_stream.next().into_iter().collect();
+            let byte = self.preimage_oracle.read_stream(HostStream::Random, byte);
+            assert_eq!(
+                byte.len(),
+                1,
+                "The random stream given by the host is exhausted"
+            );
+            byte[0].into()
+        } else {
+            0
+        };
+        self.write_column(position, res);
+        res
+    }
+
+    unsafe fn executed_instructions(
+        &mut self,
+        upper_half: bool,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let count = self.normalized_instruction_counter();
+        let res = if upper_half {
+            count >> 32
+        } else {
+            count & 0xFFFFFFFF
+        };
+        self.write_column(position, res);
+        res
+    }
+
     fn set_halted(&mut self, flag: Self::Variable) {
         if flag == 0 {
             self.halt = false
@@ -623,6 +737,7 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
     }
 
     fn report_exit(&mut self, exit_code: &Self::Variable) {
+        self.exit_code = Some(*exit_code as u32);
         println!(
             "Exited with code {} at step {}",
             *exit_code,
@@ -630,6 +745,225 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
         );
     }
 
+    fn request_preimage_write(
+        &mut self,
+        addr: &Self::Variable,
+        len: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable {
+        // The beginning of the syscall
+        if self.registers.preimage_offset == 0 {
+            let mut preimage_key = [0u8; 32];
+            for i in 0..8 {
+                let bytes = u32::to_be_bytes(self.registers.preimage_key[i]);
+                for j in 0..4 {
+                    preimage_key[4 * i + j] = bytes[j]
+                }
+            }
+            let preimage = self.preimage_oracle.get_preimage(preimage_key).get();
+            self.preimage = Some(preimage);
+        }
+
+        const LENGTH_SIZE: usize = 8;
+
+        let preimage_len = self
+            .preimage
+            .as_ref()
+            .expect("to have a preimage if we're requesting it at a non-zero offset")
+            .len();
+        let preimage_offset = self.registers.preimage_offset as u64;
+
+        let max_read_len =
+            std::cmp::min(preimage_offset + len, (preimage_len + LENGTH_SIZE) as u64)
+                - preimage_offset;
+
+        // We read at most 4 bytes, ensuring that we respect word alignment.
+        // Here, if the address is not aligned, the first call will read < 4
+        // but the next calls will be 4 bytes (because the actual address would
+        // be updated with the offset) until reaching the end of the preimage
+        // (where the last call could be less than 4 bytes).
+        let actual_read_len = std::cmp::min(max_read_len, 4 - (addr & 3));
+
+        let bytes: Vec<u8> = (0..actual_read_len)
+            .map(|i| {
+                let idx = (preimage_offset + i) as usize;
+                // The first 8 bytes of the read preimage are the preimage
+                // length, followed by the body of the preimage
+                if idx < LENGTH_SIZE {
+                    u64::to_be_bytes(preimage_len as u64)[idx]
+                } else {
+                    self.preimage.as_ref().unwrap()[idx - LENGTH_SIZE]
+                }
+            })
+            .collect();
+        self.write_input(*addr, &bytes);
+
+        // Update the number of bytes read from the oracle in this step (can
+        // include bytelength and preimage bytes)
+        self.write_column(pos, actual_read_len);
+        actual_read_len
+    }
+
+    fn request_hint_write(&mut self, addr: &Self::Variable, len: &Self::Variable) {
+        let mut last_hint = match std::mem::take(&mut self.last_hint) {
+            Some(mut last_hint) => {
+                last_hint.reserve(*len as usize);
+                last_hint
+            }
+            None => Vec::with_capacity(*len as usize),
+        };
+
+        last_hint.extend(self.read_output(*addr, *len));
+
+        let len = last_hint.len();
+        let mut idx = 0;
+
+        while idx + 4 <= len {
+            let hint_len = u32::from_be_bytes(last_hint[idx..idx + 4].try_into().unwrap()) as usize;
+            idx += 4;
+            if idx + hint_len <= len {
+                let hint = last_hint[idx..idx + hint_len].to_vec();
+                idx += hint_len;
+                self.preimage_oracle.hint(Hint::create(hint));
+            } else {
+                // The hint is not complete yet, keep its length with it
+                idx -= 4;
+                break;
+            }
+        }
+
+        let remaining = last_hint[idx..len].to_vec();
+
+        self.last_hint = Some(remaining);
+    }
+
+    fn request_advice_write(
+        &mut self,
+        addr: &Self::Variable,
+        len: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable {
+        // We read at most 4 bytes, up to the end of the word, as for the
+        // preimage.
+        let max_read_len = std::cmp::min(*len, 4 - (addr & 3));
+        let advice: Vec<u8> = self
+            .advice
+            .drain(..std::cmp::min(max_read_len as usize, self.advice.len()))
+            .collect();
+        let advice = self.preimage_oracle.read_stream(HostStream::Advice, advice);
+        assert!(
+            advice.len() as u64 <= max_read_len,
+            "The advice read from the oracle is longer than requested"
+        );
+        let actual_read_len = advice.len() as u64;
+        self.write_input(*addr, &advice);
+        self.write_column(pos, actual_read_len);
+        actual_read_len
+    }
+
+    fn request_stdin_write(
+        &mut self,
+        addr: &Self::Variable,
+        len: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable {
+        // We read at most 4 bytes, up to the end of the word, as for the
+        // preimage.
+        let mut input = vec![0; std::cmp::min(*len, 4 - (addr & 3)) as usize];
+        let actual_read_len = self
+            .stdin
+            .read(&mut input)
+            .expect("Failed to read the standard input");
+        self.write_input(*addr, &input[..actual_read_len]);
+        self.write_column(pos, actual_read_len as u64);
+        actual_read_len as u64
+    }
+
+    fn request_output_write(
+        &mut self,
+        fd: &Self::Variable,
+        addr: &Self::Variable,
+        len: &Self::Variable,
+    ) {
+        let output = self.read_output(*addr, *len);
+        let (channel, written) = match *fd as u32 {
+            FD_STDOUT => (&mut self.stdout, &mut self.written_stdout),
+            FD_STDERR => (&mut self.stderr, &mut self.written_stderr),
+            fd => panic!("Unknown output file descriptor {fd}"),
+        };
+        channel
+            .write_all(&output)
+            .expect("Failed to write the output of the program");
+        written.extend(output);
+    }
+
+    unsafe fn request_keccak(
+        &mut self,
+        addr: &Self::Variable,
+        len: &Self::Variable,
+        output: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable {
+        let absorbed_len = std::cmp::min(*len, KECCAK_PRECOMPILE_CHUNK_LEN as u64);
+        let bytes = self.read_output(*addr, absorbed_len);
+        if *len == 0 {
+            let (input, digest) = self.keccak_precompile.finalize(self.hash_counter);
+            self.write_input(*output, &digest);
+            self.keccak_inputs.push(input);
+            self.hash_counter += 1;
+        } else {
+            self.keccak_precompile.absorb(self.hash_counter, &bytes);
+        }
+        self.write_column(pos, absorbed_len);
+        absorbed_len
+    }
+
+    unsafe fn keccak_hint(&mut self, hint: KeccakHint, pos: Self::Position) -> Self::Variable {
+        self.write_field_column(pos, self.keccak_precompile.hint(hint));
+        // The digest is only used in the lookups
+        self.keccak_precompile.hint_u64(hint)
+    }
+
+    unsafe fn request_poseidon(
+        &mut self,
+        addr: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable {
+        let input = self.read_output(*addr, POSEIDON_STATE_BYTES as u64);
+        let output = self.poseidon_precompile.permute(&input);
+        self.write_input(*addr, &output);
+        let index = self.poseidon_inputs.len() as u64;
+        self.poseidon_inputs.push(input);
+        self.write_column(pos, index);
+        index
+    }
+
+    unsafe fn poseidon_hint(&mut self, hint: PoseidonHint, pos: Self::Position) -> Self::Variable {
+        self.write_field_column(pos, self.poseidon_precompile.hint(hint));
+        // The elements of the state are only used in the lookups
+        0
+    }
+
+    unsafe fn request_precompile(
+        &mut self,
+        syscall: &Self::Variable,
+        input: &Self::Variable,
+        output: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable {
+        let syscall = *syscall as u32;
+        let input_len = self
+            .precompiles
+            .get(syscall)
+            .unwrap_or_else(|| panic!("No precompile implements the system call {syscall}"))
+            .input_len();
+        let input_bytes = self.read_output(*input, input_len as u64);
+        let (index, output_bytes) = self.precompiles.call(syscall, *input, *output, input_bytes);
+        self.write_input(*output, &output_bytes);
+        self.write_column(pos, index);
+        index
+    }
+
     fn reset(&mut self) {
         self.scratch_state_idx = 0;
         self.scratch_state = fresh_scratch_state();
@@ -638,51 +972,69 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
 }
 
 impl<Fp: Field> Env<Fp> {
+    /// Creates the environment running the program given by the state
+    /// `state`, with the default [MemoryLayout]
     pub fn create(page_size: usize, state: State) -> Self {
+        Self::create_with_layout(page_size, state, &MemoryLayout::default())
+            .expect("The program does not fit in the default memory layout")
+    }
+
+    /// Creates the environment running the program given by the state
+    /// `state`, with the memory layout `layout`, against which the pages of
+    /// the program are validated
+    pub fn create_with_layout(
+        page_size: usize,
+        state: State,
+        layout: &MemoryLayout,
+    ) -> Result<Self, MemoryLayoutError> {
+        layout.validate(state.memory.iter().map(|page| page.index))?;
         let initial_instruction_pointer = state.pc;
         let next_instruction_pointer = state.next_pc;
 
         let selector = INSTRUCTION_SET_SIZE;
 
-        let mut initial_memory: Vec<(u32, Vec<u8>)> = state
+        assert_eq!(page_size, PAGE_SIZE as usize);
+        let initial_memory: SparseMemory<u32, u8> = state
             .memory
             .into_iter()
-            // Check that the conversion from page data is correct
             .map(|page| (page.index, page.data))
             .collect();
 
-        for (_address, initial_memory) in initial_memory.iter_mut() {
-            initial_memory.extend((0..(page_size - initial_memory.len())).map(|_| 0u8));
-            assert_eq!(initial_memory.len(), page_size);
-        }
-
-        let memory_offsets = initial_memory
-            .iter()
-            .map(|(offset, _)| *offset)
-            .collect::<Vec<_>>();
-
         let initial_registers = {
-            Registers {
+            let mut registers = Registers {
                 general_purpose: state.registers,
                 current_instruction_pointer: initial_instruction_pointer,
                 next_instruction_pointer,
-                heap_pointer: state.heap,
-            }
+                heap_pointer: if state.heap == 0 {
+                    layout.heap_base
+                } else {
+                    state.heap
+                },
+                // The data segment ends with the last page of the program
+                program_break: initial_memory
+                    .iter()
+                    .next_back()
+                    .map_or(0, |(page, _)| (page + 1) << PAGE_ADDRESS_SIZE),
+                preimage_key: array::from_fn(|i| {
+                    u32::from_be_bytes(state.preimage_key[4 * i..4 * i + 4].try_into().unwrap())
+                }),
+                preimage_offset: state.preimage_offset,
+                csrs: Default::default(),
+            };
+            registers[REGISTER_CSR_OFFSET + CSR_MISA] = MISA;
+            registers
         };
 
         let mut registers = initial_registers.clone();
-        registers[2] = 0x408004f0;
         // set the stack pointer to the top of the stack
+        registers[2] = layout.stack_top;
 
-        Env {
+        Ok(Env {
             instruction_counter: state.step,
-            memory: initial_memory.clone(),
-            last_memory_accesses: [0usize; 3],
-            memory_write_index: memory_offsets
-                .iter()
-                .map(|offset| (*offset, vec![0u64; page_size]))
-                .collect(),
-            last_memory_write_index_accesses: [0usize; 3],
+            memory: initial_memory,
+            memory_write_index: SparseMemory::new(),
+            merkle_memory: None,
+            authenticated_accesses: Vec::new(),
             registers,
             registers_write_index: Registers::default(),
             scratch_state_idx: 0,
@@ -691,14 +1043,243 @@ impl<Fp: Field> Env<Fp> {
             scratch_state_inverse: fresh_scratch_state(),
             halt: state.exited,
             selector,
+            random_stream: Box::new(std::iter::empty()),
+            preimage_oracle: Box::new(NullPreImageOracle),
+            preimage: state.preimage,
+            last_hint: state.last_hint,
+            advice: VecDeque::new(),
+            stdin: Box::new(std::io::empty()),
+            stdout: Box::new(std::io::sink()),
+            stderr: Box::new(std::io::sink()),
+            written_stdout: vec![],
+            written_stderr: vec![],
+            exit_code: None,
+            profiler: None,
+            commit_log: None,
+            coverage: None,
+            max_cycles: None,
+            cycle_limit_exceeded: false,
+            keccak_precompile: KeccakPrecompile::default(),
+            hash_counter: 0,
+            keccak_inputs: vec![],
+            poseidon_precompile: PoseidonPrecompile::default(),
+            poseidon_inputs: vec![],
+            precompiles: PrecompileRegistry::default(),
+        })
+    }
+
+    /// Execute the program until it halts, or until it reaches
+    /// [Env::max_cycles], and return its exit status and output
+    pub fn run(&mut self) -> ExecutionResult {
+        while !self.halt {
+            self.step();
+        }
+        self.stdout
+            .flush()
+            .expect("Failed to flush the standard output");
+        self.stderr
+            .flush()
+            .expect("Failed to flush the standard error");
+        let status = match self.exit_code {
+            _ if self.cycle_limit_exceeded => ExitStatus::CycleLimitExceeded,
+            Some(exit_code) => ExitStatus::Exited(exit_code),
+            None => ExitStatus::Halted,
+        };
+        ExecutionResult {
+            status,
+            steps: self.normalized_instruction_counter(),
+            stdout: std::mem::take(&mut self.written_stdout),
+            stderr: std::mem::take(&mut self.written_stderr),
+        }
+    }
+
+    /// Returns a snapshot of the state of the execution, from which it can be
+    /// resumed with [Env::resume].
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            instruction_counter: self.instruction_counter,
+            memory: self.memory.clone(),
+            memory_write_index: self.memory_write_index.clone(),
+            registers: self.registers.clone(),
+            registers_write_index: self.registers_write_index.clone(),
+            halt: self.halt,
+            preimage: self.preimage.clone(),
+            last_hint: self.last_hint.clone(),
+            advice: self.advice.clone(),
+            written_stdout: self.written_stdout.clone(),
+            written_stderr: self.written_stderr.clone(),
+            exit_code: self.exit_code,
+            cycle_limit_exceeded: self.cycle_limit_exceeded,
+            keccak_precompile: self.keccak_precompile.clone(),
+            hash_counter: self.hash_counter,
+            keccak_inputs: self.keccak_inputs.clone(),
+            poseidon_inputs: self.poseidon_inputs.clone(),
+            precompile_calls: self.precompiles.calls.clone(),
+        }
+    }
+
+    /// Resumes the execution from the snapshot `snapshot`, keeping the host
+    /// resources of the environment. When the memory accesses are
+    /// authenticated, the Merkle tree is rebuilt from the memory of the
+    /// snapshot, and the accesses not taken yet are dropped.
+    pub fn resume(&mut self, snapshot: Snapshot) {
+        let Snapshot {
+            instruction_counter,
+            memory,
+            memory_write_index,
+            registers,
+            registers_write_index,
+            halt,
+            preimage,
+            last_hint,
+            advice,
+            written_stdout,
+            written_stderr,
+            exit_code,
+            cycle_limit_exceeded,
+            keccak_precompile,
+            hash_counter,
+            keccak_inputs,
+            poseidon_inputs,
+            precompile_calls,
+        } = snapshot;
+        self.instruction_counter = instruction_counter;
+        self.memory = memory;
+        self.memory_write_index = memory_write_index;
+        self.registers = registers;
+        self.registers_write_index = registers_write_index;
+        self.halt = halt;
+        self.preimage = preimage;
+        self.last_hint = last_hint;
+        self.advice = advice;
+        self.written_stdout = written_stdout;
+        self.written_stderr = written_stderr;
+        self.exit_code = exit_code;
+        self.cycle_limit_exceeded = cycle_limit_exceeded;
+        self.keccak_precompile = keccak_precompile;
+        self.hash_counter = hash_counter;
+        self.keccak_inputs = keccak_inputs;
+        self.poseidon_inputs = poseidon_inputs;
+        self.precompiles.calls = precompile_calls;
+        if self.merkle_memory.is_some() {
+            self.enable_merkle_memory();
+        }
+        self.authenticated_accesses.clear();
+    }
+
+    /// Write the bytes given by the host starting from `addr`. The bytes are
+    /// not constrained.
+    fn write_input(&mut self, addr: u64, input: &[u8]) {
+        for (i, byte) in (0..).zip(input) {
+            unsafe {
+                self.push_memory(&(addr + i), *byte as u64);
+                self.push_memory_access(&(addr + i), self.next_instruction_counter());
+            }
         }
     }
 
+    /// Read the `len` bytes starting from `addr` sent to the host, without
+    /// allocating witness columns
+    fn read_output(&mut self, addr: u64, len: u64) -> Vec<u8> {
+        (addr..addr + len)
+            .map(|addr| {
+                unsafe { self.push_memory_access(&addr, self.next_instruction_counter()) };
+                self.memory.get(addr.try_into().unwrap())
+            })
+            .collect()
+    }
+
+    /// Registers the custom precompile `precompile`, implementing a system
+    /// call which is neither one of [BUILTIN_SYSCALLS] nor the one of another
+    /// precompile. See [crate::interpreters::precompile].
+    pub fn register_precompile(
+        &mut self,
+        precompile: Box<dyn Precompile<Fp>>,
+    ) -> Result<(), PrecompileError> {
+        let syscall = precompile.syscall();
+        if BUILTIN_SYSCALLS.contains(&syscall) {
+            return Err(PrecompileError::Reserved(syscall));
+        }
+        self.precompiles.register(precompile)
+    }
+
+    /// Append `advice` to the advice given to the program, which reads it
+    /// from the file descriptor [FD_ADVICE_READ].
+    pub fn enqueue_advice(&mut self, advice: &[u8]) {
+        self.advice.extend(advice)
+    }
+
+    /// Authenticate the memory accesses against the Merkle root of the
+    /// current memory from now on, see [interpreter::InterpreterEnv::authenticate_memory_access].
+    pub fn enable_merkle_memory(&mut self) {
+        self.merkle_memory = Some(MerkleMemory::from_memory(&self.memory));
+    }
+
+    /// Returns the memory accesses authenticated since the last call, to
+    /// check them at the end of a chunk of the execution.
+    pub fn take_authenticated_accesses(&mut self) -> Vec<AuthenticatedAccess> {
+        std::mem::take(&mut self.authenticated_accesses)
+    }
+
+    /// Records the instructions executed and the memory accesses from now on,
+    /// attributing the instructions to the functions given by `symbols`.
+    /// The profile is returned by [Env::take_profile].
+    pub fn enable_profiling(&mut self, symbols: Symbols) {
+        self.profiler = Some(Profiler::new(symbols));
+    }
+
+    /// Returns the profile of the execution since profiling was enabled, and
+    /// stops profiling
+    pub fn take_profile(&mut self) -> Option<ProfileReport> {
+        self.profiler.take().map(Profiler::into_report)
+    }
+
+    /// Logs the instructions executed from now on to `writer`, in the format
+    /// of the commit log of spike, see [commit_log]. The log is closed by
+    /// [Env::take_commit_log].
+    pub fn enable_commit_log(&mut self, writer: Box<dyn Write>) {
+        self.commit_log = Some(CommitLog::new(writer));
+    }
+
+    /// Stops logging the instructions executed, and returns the writer the
+    /// log was written to, flushed
+    pub fn take_commit_log(&mut self) -> Option<std::io::Result<Box<dyn Write>>> {
+        self.commit_log.take().map(CommitLog::into_writer)
+    }
+
+    /// Records the instructions executed from now on, with the scratch
+    /// columns they write. The record is returned by [Env::take_coverage],
+    /// and the coverage of the circuit is reported by
+    /// [super::constraints::Env::coverage].
+    pub fn enable_coverage(&mut self) {
+        self.coverage = Some(ExecutionCoverage::default());
+    }
+
+    /// Returns the instructions executed since the coverage was enabled, and
+    /// stops recording them
+    pub fn take_coverage(&mut self) -> Option<ExecutionCoverage<Instruction>> {
+        self.coverage.take()
+    }
+
     pub fn next_instruction_counter(&self) -> u64 {
         (self.normalized_instruction_counter() + 1) * MAX_ACC
     }
 
+    /// Decode the instruction at the instruction pointer, and return it with
+    /// its encoding. A compressed instruction is returned with the encoding of
+    /// its expansion in the base instruction set, see [decompress_instruction].
     pub fn decode_instruction(&mut self) -> (Instruction, u32) {
+        // The two lowest bits of a compressed instruction are never 0b11
+        let low_half = u16::from_le_bytes([
+            self.get_memory_direct(self.registers.current_instruction_pointer),
+            self.get_memory_direct(self.registers.current_instruction_pointer + 1),
+        ]);
+        if low_half & 0b11 != 0b11 {
+            return (
+                decode_compressed_instruction(low_half),
+                decompress_instruction(low_half),
+            );
+        }
         /* https://www.cs.cornell.edu/courses/cs3410/2024fa/assignments/cpusim/riscv-instructions.pdf */
         let instruction =
             ((self.get_memory_direct(self.registers.current_instruction_pointer) as u32) << 24)
@@ -711,134 +1292,78 @@ impl<Fp: Field> Env<Fp> {
         let opcode = {
             match instruction & 0b1111111 // bits 0-6
             {
-                0b0110111 => Instruction::UType(UInstruction::LoadUpperImmediate),
-                0b0010111 => Instruction::UType(UInstruction::AddUpperImmediate),
-                0b1101111 => Instruction::UJType(UJInstruction::JumpAndLink),
-                0b1100011 =>
-                match (instruction >> 12) & 0x7 // bits 12-14 for func3
-                {
-                    0b000 => Instruction::SBType(SBInstruction::BranchEq),
-                    0b001 => Instruction::SBType(SBInstruction::BranchNeq),
-                    0b100 => Instruction::SBType(SBInstruction::BranchLessThan),
-                    0b101 => Instruction::SBType(SBInstruction::BranchGreaterThanEqual),
-                    0b110 => Instruction::SBType(SBInstruction::BranchLessThanUnsigned),
-                    0b111 => Instruction::SBType(SBInstruction::BranchGreaterThanEqualUnsigned),
-                    _ => panic!("Unknown SBType instruction with full inst {}", instruction),
-                },
-                0b1100111 => Instruction::IType(IInstruction::JumpAndLinkRegister),
-                0b0000011 =>
-                match (instruction >> 12) & 0x7 // bits 12-14 for func3
-                {
-                    0b000 => Instruction::IType(IInstruction::LoadByte),
-                    0b001 => Instruction::IType(IInstruction::LoadHalf),
-                    0b010 => Instruction::IType(IInstruction::LoadWord),
-                    0b100 => Instruction::IType(IInstruction::LoadByteUnsigned),
-                    0b101 => Instruction::IType(IInstruction::LoadHalfUnsigned),
-                    _ => panic!("Unknown IType instruction with full inst {}", instruction),
-                },
-                0b0100011 =>
-                match (instruction >> 12) & 0x7 // bits 12-14 for func3
-                {
-                    0b000 => Instruction::SType(SInstruction::StoreByte),
-                    0b001 => Instruction::SType(SInstruction::StoreHalf),
-                    0b010 => Instruction::SType(SInstruction::StoreWord),
-                    _ => panic!("Unknown SType instruction with full inst {}", instruction),
-                },
-                0b0010011 =>
-                match (instruction >> 12) & 0x7 // bits 12-14 for func3
-                {
-                    0b000 => Instruction::IType(IInstruction::AddImmediate),
-                    0b010 => Instruction::IType(IInstruction::SetLessThanImmediate),
-                    0b011 => Instruction::IType(IInstruction::SetLessThanImmediateUnsigned),
-                    0b100 => Instruction::IType(IInstruction::XorImmediate),
-                    0b110 => Instruction::IType(IInstruction::OrImmediate),
-                    0b111 => Instruction::IType(IInstruction::AndImmediate),
-                    0b001 => Instruction::IType(IInstruction::ShiftLeftLogicalImmediate),
-                    0b101 =>
-                    match (instruction >> 30) & 0x1 // bit 30 in simm component of IType
+                // FIXME: we should implement more syscalls here, and check the register state.
+                // Even better, only one constructor call ecall, and in the
+                // interpreter, we do the action depending on it
+                0b0101111 => {
+                    // Only the 32 bits atomic instructions are supported
+                    let funct3 = (instruction >> 12) & 0x7; // bits 12-14 for funct3
+                    assert_eq!(funct3, 0b010, "Unknown AType instruction with full inst {}", instruction);
+                    match instruction >> 27 // bits 27-31 for funct5
                     {
-                    0b0 => Instruction::IType(IInstruction::ShiftRightLogicalImmediate),
-                    0b1 => Instruction::IType(IInstruction::ShiftRightArithmeticImmediate),
-                    _ => panic!("Unknown IType in shift right instructions with full inst {}", instruction),
-                    },
-                    _ => panic!("Unknown IType instruction with full inst {}", instruction),
+                        0b00010 => Instruction::AType(AInstruction::LoadReserved),
+                        0b00011 => Instruction::AType(AInstruction::StoreConditional),
+                        0b00001 => Instruction::AType(AInstruction::AtomicSwap),
+                        0b00000 => Instruction::AType(AInstruction::AtomicAdd),
+                        0b00100 => Instruction::AType(AInstruction::AtomicXor),
+                        0b01100 => Instruction::AType(AInstruction::AtomicAnd),
+                        0b01000 => Instruction::AType(AInstruction::AtomicOr),
+                        0b10000 => Instruction::AType(AInstruction::AtomicMin),
+                        0b10100 => Instruction::AType(AInstruction::AtomicMax),
+                        0b11000 => Instruction::AType(AInstruction::AtomicMinUnsigned),
+                        0b11100 => Instruction::AType(AInstruction::AtomicMaxUnsigned),
+                        _ => panic!("Unknown AType instruction with full inst {}", instruction),
+                    }
                 },
-                0b0110011 => {
-                    let funct5 = instruction >> 27 & 0x1F; // bits 27-31 for funct5
-                    let funct2 = instruction >> 25 & 0x3; // bits 25-26 for func2
-                    let funct3 = instruction >> 12 & 0x7; // bits 12-14 for func3
-                    match funct2 {
-                        // These are the instructions for the base integer set
-                        0b00 => {
-                            // The integer set have two sets of instructions
-                            // using a different funct5 value
-                            match funct5 {
-                                0b00000 => {
-                                    // Note: all possible values are handled here
-                                    match funct3 {
-                                        0b000 => Instruction::RType(RInstruction::Add),
-                                        0b001 => Instruction::RType(RInstruction::ShiftLeftLogical),
-                                        0b010 => Instruction::RType(RInstruction::SetLessThan),
-                                        0b011 => Instruction::RType(RInstruction::SetLessThanUnsigned),
-                                        0b100 => Instruction::RType(RInstruction::Xor),
-                                        0b101 => Instruction::RType(RInstruction::ShiftRightLogical),
-                                        0b110 => Instruction::RType(RInstruction::Or),
-                                        0b111 => Instruction::RType(RInstruction::And),
-                                        _ => panic!("This case should never happen as funct3 is 8 bits long and all possible case are implemented. However, we still have an unknown opcode 0110011 instruction with full inst {} (funct5 = {}, funct2 = {}, funct3 = {})", instruction, funct5, funct2, funct3),
-                                    }
-                                },
-                                // Note that there are still some values unhandled here.
-                                0b01000 => {
-                                    // Note that there are still 6 values unhandled here.
-                                    match funct3 {
-                                        0b000 => Instruction::RType(RInstruction::Sub),
-                                        0b101 => Instruction::RType(RInstruction::ShiftRightArithmetic),
-                                        _ => panic!("Unknown opcode 0110011 instruction with full inst {} (funct5 = {}, funct2 = {}, funct3 = {})", instruction, funct5, funct2, funct3),
-                                    }
-                                },
-                                // All the unhandled cases
-                                1_u32..=7_u32 | 9_u32..=u32::MAX =>
-                                    panic!("Unknown opcode 0110011 instruction with full inst {} (funct5 = {}, funct2 = {}, funct3 = {})", instruction, funct5, funct2, funct3),
+                0b1110011 => {
+                    let funct3 = (instruction >> 12) & 0x7;
+                    let rs1 = (instruction >> 15) & 0x1F;
+                    let csr = (instruction >> 20) as usize;
+                    if funct3 == 0b000 {
+                        match self.registers[17] {
+                            SYSCALL_EXIT | SYSCALL_EXIT_GROUP => Instruction::SyscallType(SyscallInstruction::SyscallExitGroup),
+                            SYSCALL_BRK => Instruction::SyscallType(SyscallInstruction::SyscallBrk),
+                            SYSCALL_KECCAK256 => Instruction::SyscallType(SyscallInstruction::SyscallKeccak256),
+                            SYSCALL_POSEIDON => Instruction::SyscallType(SyscallInstruction::SyscallPoseidon),
+                            SYSCALL_MMAP => {
+                                let flags = self.registers[13];
+                                assert!(flags & MAP_ANONYMOUS != 0, "Only anonymous mappings are supported, got the flags {:#x}", flags);
+                                Instruction::SyscallType(SyscallInstruction::SyscallMmap)
                             }
-                        },
-                        // These are the instructions for the M type
-                        0b01 => {
-                            match funct5 {
-                                // All instructions for the M type have the same
-                                // funct5 value. Still catching it here to be
-                                // sure we do not misinterpret an instruction
-                                0b00000 => {
-                                    match funct3 {
-                                        0b000 => Instruction::MType(MInstruction::Mul),
-                                        0b001 => Instruction::MType(MInstruction::Mulh),
-                                        0b010 => Instruction::MType(MInstruction::Mulhsu),
-                                        0b011 => Instruction::MType(MInstruction::Mulhu),
-                                        0b100 => Instruction::MType(MInstruction::Div),
-                                        0b101 => Instruction::MType(MInstruction::Divu),
-                                        0b110 => Instruction::MType(MInstruction::Rem),
-                                        0b111 => Instruction::MType(MInstruction::Remu),
-                                        _ => panic!("This case should never happen as funct3 is 8 bits long and all possible case are implemented. However, we still have an unknown opcode 0110011 instruction with full inst {} (funct5 = {}, funct2 = {}, funct3 = {})", instruction, funct5, funct2, funct3),
-                                    }
-                                },
-                                // Note that there are still some values unhandled here.
-                                1_u32..=u32::MAX => panic!("Unknown 0110011 instruction with full inst {} (funct5 = {}, funct2 = {}, funct3 = {})", instruction, funct5, funct2, funct3),
-                            }
-                        },
-                        _ => panic!("Unknown RType 0110011 instruction with full inst {} (funct5 = {}, funct2 = {}, funct3 = {})", instruction, funct5, funct2, funct3),
+                            SYSCALL_CLOCK_GETTIME64 => Instruction::SyscallType(SyscallInstruction::SyscallClockGettime),
+                            SYSCALL_GETRANDOM => Instruction::SyscallType(SyscallInstruction::SyscallGetRandom),
+                            SYSCALL_READ if self.registers[10] == FD_HINT_READ => Instruction::SyscallType(SyscallInstruction::SyscallReadHint),
+                            SYSCALL_READ if self.registers[10] == FD_PREIMAGE_READ => Instruction::SyscallType(SyscallInstruction::SyscallReadPreimage),
+                            SYSCALL_READ if self.registers[10] == FD_STDIN => Instruction::SyscallType(SyscallInstruction::SyscallReadStdin),
+                            SYSCALL_WRITE if self.registers[10] == FD_STDOUT || self.registers[10] == FD_STDERR => Instruction::SyscallType(SyscallInstruction::SyscallWriteOutput),
+                            SYSCALL_READ if self.registers[10] == FD_ADVICE_READ => Instruction::SyscallType(SyscallInstruction::SyscallReadAdvice),
+                            SYSCALL_WRITE if self.registers[10] == FD_HINT_WRITE => Instruction::SyscallType(SyscallInstruction::SyscallWriteHint),
+                            SYSCALL_WRITE if self.registers[10] == FD_PREIMAGE_WRITE => Instruction::SyscallType(SyscallInstruction::SyscallWritePreimage),
+                            n if self.precompiles.get(n).is_some() => Instruction::SyscallType(SyscallInstruction::SyscallPrecompile),
+                            _ => Instruction::SyscallType(SyscallInstruction::SyscallSuccess),
+                        }
+                    } else if registers::is_counter_csr(csr) {
+                        // The counters are read-only: only the forms leaving
+                        // the CSR unchanged are allowed
+                        match funct3 {
+                            0b010 | 0b011 | 0b110 | 0b111 if rs1 == 0 => Instruction::CSRType(CSRInstruction::ReadCounter),
+                            _ => panic!("Write to the read-only CSR {:#x} with full inst {}", csr, instruction),
+                        }
+                    } else if registers::csr_index(csr).is_none() {
+                        panic!("Unknown CSR {:#x} with full inst {}", csr, instruction)
+                    } else {
+                        match funct3 {
+                            0b001 => Instruction::CSRType(CSRInstruction::ReadWrite),
+                            0b010 => Instruction::CSRType(CSRInstruction::ReadSet),
+                            0b011 => Instruction::CSRType(CSRInstruction::ReadClear),
+                            0b101 => Instruction::CSRType(CSRInstruction::ReadWriteImmediate),
+                            0b110 => Instruction::CSRType(CSRInstruction::ReadSetImmediate),
+                            0b111 => Instruction::CSRType(CSRInstruction::ReadClearImmediate),
+                            _ => panic!("Unknown CSRType instruction with full inst {}", instruction),
+                        }
                     }
                 }
-                0b0001111 =>
-                match (instruction >> 12) & 0x7 // bits 12-14 for func3
-                {
-                    0b000 => Instruction::RType(RInstruction::Fence),
-                    0b001 => Instruction::RType(RInstruction::FenceI),
-                    _ => panic!("Unknown RType 0001111 (Fence) instruction with full inst {}", instruction),
-                },
-                // FIXME: we should implement more syscalls here, and check the register state.
-                // Even better, only one constructor call ecall, and in the
-                // interpreter, we do the action depending on it
-                0b1110011 => Instruction::SyscallType(SyscallInstruction::SyscallSuccess),
-                _ => panic!("Unknown instruction with full inst {:b}, and opcode {:b}", instruction, instruction & 0b1111111),
+                _ => decode_base_instruction::<Rv32>(instruction),
             }
         };
         (opcode, instruction)
@@ -848,12 +1373,53 @@ impl<Fp: Field> Env<Fp> {
     pub fn step(&mut self) -> Instruction {
         self.reset_scratch_state();
         self.reset_scratch_state_inverse();
-        let (opcode, _instruction) = self.decode_instruction();
+        let (opcode, instruction) = self.decode_instruction();
+        let instruction_pointer = self.registers.current_instruction_pointer;
+        let commit = self.commit_log.is_some().then(|| Commit {
+            instruction_pointer,
+            instruction: opcode,
+            raw_encoding: commit_log::raw_instruction(&self.memory, instruction_pointer),
+            encoding: instruction,
+            registers_before: self.registers.general_purpose,
+            registers_after: self.registers.general_purpose,
+        });
 
         interpreter::interpret_instruction(self, opcode);
 
+        if let (Some(commit_log), Some(mut commit)) = (self.commit_log.as_mut(), commit) {
+            commit.registers_after = self.registers.general_purpose;
+            commit_log
+                .record(&commit, &self.memory)
+                .expect("Failed to write the commit log");
+        }
+
+        if let Some(coverage) = self.coverage.as_mut() {
+            coverage.record(
+                opcode,
+                self.scratch_state.iter().copied(),
+                self.scratch_state_inverse.iter().copied(),
+            );
+        }
+
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.record_instruction(
+                instruction_pointer,
+                &opcode.to_string(),
+                control_flow(instruction),
+            );
+        }
+
         self.instruction_counter = self.next_instruction_counter();
 
+        if !self.halt
+            && self
+                .max_cycles
+                .is_some_and(|max_cycles| self.normalized_instruction_counter() >= max_cycles)
+        {
+            self.halt = true;
+            self.cycle_limit_exceeded = true;
+        }
+
         // Integer division by MAX_ACC to obtain the actual instruction count
         if self.halt {
             println!(
@@ -889,72 +1455,363 @@ impl<Fp: Field> Env<Fp> {
         }
     }
 
-    pub fn update_last_memory_access(&mut self, i: usize) {
-        let [i_0, i_1, _] = self.last_memory_accesses;
-        self.last_memory_accesses = [i, i_0, i_1]
+    pub fn get_memory_direct(&self, addr: u32) -> u8 {
+        self.memory.get(addr)
     }
 
-    pub fn get_memory_page_index(&mut self, page: u32) -> usize {
-        for &i in self.last_memory_accesses.iter() {
-            if self.memory_write_index[i].0 == page {
-                return i;
+    /// The actual number of instructions executed results from dividing the
+    /// instruction counter by MAX_ACC (floor).
+    ///
+    /// NOTE: actually, in practice it will be less than that, as there is no
+    ///       single instruction that performs all of them.
+    pub fn normalized_instruction_counter(&self) -> u64 {
+        self.instruction_counter / MAX_ACC
+    }
+}
+
+/// Decode an instruction of the base integer instruction set and of the M
+/// extension, i.e. the instructions whose encoding and semantics are shared
+/// by RV32IM and RV64IM. The width of the registers is only involved in the
+/// encoding of the shift amount of the immediate shifts.
+/// Panics on the instructions outside of this common subset.
+pub fn decode_base_instruction<X: Xlen>(instruction: u32) -> Instruction {
+    match instruction & 0b1111111 // bits 0-6
+    {
+        0b0110111 => Instruction::UType(UInstruction::LoadUpperImmediate),
+        0b0010111 => Instruction::UType(UInstruction::AddUpperImmediate),
+        0b1101111 => Instruction::UJType(UJInstruction::JumpAndLink),
+        0b1100011 =>
+        match (instruction >> 12) & 0x7 // bits 12-14 for func3
+        {
+            0b000 => Instruction::SBType(SBInstruction::BranchEq),
+            0b001 => Instruction::SBType(SBInstruction::BranchNeq),
+            0b100 => Instruction::SBType(SBInstruction::BranchLessThan),
+            0b101 => Instruction::SBType(SBInstruction::BranchGreaterThanEqual),
+            0b110 => Instruction::SBType(SBInstruction::BranchLessThanUnsigned),
+            0b111 => Instruction::SBType(SBInstruction::BranchGreaterThanEqualUnsigned),
+            _ => panic!("Unknown SBType instruction with full inst {}", instruction),
+        },
+        0b1100111 => Instruction::IType(IInstruction::JumpAndLinkRegister),
+        0b0000011 =>
+        match (instruction >> 12) & 0x7 // bits 12-14 for func3
+        {
+            0b000 => Instruction::IType(IInstruction::LoadByte),
+            0b001 => Instruction::IType(IInstruction::LoadHalf),
+            0b010 => Instruction::IType(IInstruction::LoadWord),
+            0b100 => Instruction::IType(IInstruction::LoadByteUnsigned),
+            0b101 => Instruction::IType(IInstruction::LoadHalfUnsigned),
+            _ => panic!("Unknown IType instruction with full inst {}", instruction),
+        },
+        0b0100011 =>
+        match (instruction >> 12) & 0x7 // bits 12-14 for func3
+        {
+            0b000 => Instruction::SType(SInstruction::StoreByte),
+            0b001 => Instruction::SType(SInstruction::StoreHalf),
+            0b010 => Instruction::SType(SInstruction::StoreWord),
+            _ => panic!("Unknown SType instruction with full inst {}", instruction),
+        },
+        0b0010011 => {
+            // The shift amount of the immediate shifts is encoded on the
+            // log2(XLEN) lowest bits of the immediate, and the bits above must
+            // be zero, except bit 30 which selects the arithmetic shift.
+            let shamt_bits = X::BITS.trailing_zeros();
+            let funct = instruction >> (20 + shamt_bits);
+            match (instruction >> 12) & 0x7 // bits 12-14 for func3
+            {
+                0b000 => Instruction::IType(IInstruction::AddImmediate),
+                0b010 => Instruction::IType(IInstruction::SetLessThanImmediate),
+                0b011 => Instruction::IType(IInstruction::SetLessThanImmediateUnsigned),
+                0b100 => Instruction::IType(IInstruction::XorImmediate),
+                0b110 => Instruction::IType(IInstruction::OrImmediate),
+                0b111 => Instruction::IType(IInstruction::AndImmediate),
+                0b001 if funct == 0 => Instruction::IType(IInstruction::ShiftLeftLogicalImmediate),
+                0b101 if funct & !(1 << (10 - shamt_bits)) == 0 =>
+                match (instruction >> 30) & 0x1 // bit 30 in simm component of IType
+                {
+                0b0 => Instruction::IType(IInstruction::ShiftRightLogicalImmediate),
+                0b1 => Instruction::IType(IInstruction::ShiftRightArithmeticImmediate),
+                _ => panic!("Unknown IType in shift right instructions with full inst {}", instruction),
+                },
+                _ => panic!("Unknown IType instruction with full inst {}", instruction),
             }
-        }
-        for (i, (page_index, _memory)) in self.memory.iter_mut().enumerate() {
-            if *page_index == page {
-                self.update_last_memory_access(i);
-                return i;
+        },
+        0b0110011 => {
+            let funct5 = instruction >> 27 & 0x1F; // bits 27-31 for funct5
+            let funct2 = instruction >> 25 & 0x3; // bits 25-26 for func2
+            let funct3 = instruction >> 12 & 0x7; // bits 12-14 for func3
+            match funct2 {
+                // These are the instructions for the base integer set
+                0b00 => {
+                    // The integer set have two sets of instructions
+                    // using a different funct5 value
+                    match funct5 {
+                        0b00000 => {
+                            // Note: all possible values are handled here
+                            match funct3 {
+                                0b000 => Instruction::RType(RInstruction::Add),
+                                0b001 => Instruction::RType(RInstruction::ShiftLeftLogical),
+                                0b010 => Instruction::RType(RInstruction::SetLessThan),
+                                0b011 => Instruction::RType(RInstruction::SetLessThanUnsigned),
+                                0b100 => Instruction::RType(RInstruction::Xor),
+                                0b101 => Instruction::RType(RInstruction::ShiftRightLogical),
+                                0b110 => Instruction::RType(RInstruction::Or),
+                                0b111 => Instruction::RType(RInstruction::And),
+                                _ => panic!("This case should never happen as funct3 is 8 bits long and all possible case are implemented. However, we still have an unknown opcode 0110011 instruction with full inst {} (funct5 = {}, funct2 = {}, funct3 = {})", instruction, funct5, funct2, funct3),
+                            }
+                        },
+                        // Note that there are still some values unhandled here.
+                        0b01000 => {
+                            // Note that there are still 6 values unhandled here.
+                            match funct3 {
+                                0b000 => Instruction::RType(RInstruction::Sub),
+                                0b101 => Instruction::RType(RInstruction::ShiftRightArithmetic),
+                                _ => panic!("Unknown opcode 0110011 instruction with full inst {} (funct5 = {}, funct2 = {}, funct3 = {})", instruction, funct5, funct2, funct3),
+                            }
+                        },
+                        // All the unhandled cases
+                        1_u32..=7_u32 | 9_u32..=u32::MAX =>
+                            panic!("Unknown opcode 0110011 instruction with full inst {} (funct5 = {}, funct2 = {}, funct3 = {})", instruction, funct5, funct2, funct3),
+                    }
+                },
+                // These are the instructions for the M type
+                0b01 => {
+                    match funct5 {
+                        // All instructions for the M type have the same
+                        // funct5 value. Still catching it here to be
+                        // sure we do not misinterpret an instruction
+                        0b00000 => {
+                            match funct3 {
+                                0b000 => Instruction::MType(MInstruction::Mul),
+                                0b001 => Instruction::MType(MInstruction::Mulh),
+                                0b010 => Instruction::MType(MInstruction::Mulhsu),
+                                0b011 => Instruction::MType(MInstruction::Mulhu),
+                                0b100 => Instruction::MType(MInstruction::Div),
+                                0b101 => Instruction::MType(MInstruction::Divu),
+                                0b110 => Instruction::MType(MInstruction::Rem),
+                                0b111 => Instruction::MType(MInstruction::Remu),
+                                _ => panic!("This case should never happen as funct3 is 8 bits long and all possible case are implemented. However, we still have an unknown opcode 0110011 instruction with full inst {} (funct5 = {}, funct2 = {}, funct3 = {})", instruction, funct5, funct2, funct3),
+                            }
+                        },
+                        // Note that there are still some values unhandled here.
+                        1_u32..=u32::MAX => panic!("Unknown 0110011 instruction with full inst {} (funct5 = {}, funct2 = {}, funct3 = {})", instruction, funct5, funct2, funct3),
+                    }
+                },
+                _ => panic!("Unknown RType 0110011 instruction with full inst {} (funct5 = {}, funct2 = {}, funct3 = {})", instruction, funct5, funct2, funct3),
             }
         }
-
-        // Memory not found; dynamically allocate
-        let memory = vec![0u8; PAGE_SIZE as usize];
-        self.memory.push((page, memory));
-        let i = self.memory.len() - 1;
-        self.update_last_memory_access(i);
-        i
+        0b0001111 =>
+        match (instruction >> 12) & 0x7 // bits 12-14 for func3
+        {
+            0b000 => Instruction::RType(RInstruction::Fence),
+            0b001 => Instruction::RType(RInstruction::FenceI),
+            _ => panic!("Unknown RType 0001111 (Fence) instruction with full inst {}", instruction),
+        },
+        _ => panic!("Unknown instruction with full inst {:b}, and opcode {:b}", instruction, instruction & 0b1111111),
     }
+}
 
-    pub fn update_last_memory_write_index_access(&mut self, i: usize) {
-        let [i_0, i_1, _] = self.last_memory_write_index_accesses;
-        self.last_memory_write_index_accesses = [i, i_0, i_1]
-    }
+/// Decode a compressed instruction, i.e. an instruction of the C extension
+/// encoded on 16 bits.
+/// See the table "RVC opcode map" of the
+/// [specification](https://riscv.org/wp-content/uploads/2019/12/riscv-spec-20191213.pdf).
+pub fn decode_compressed_instruction(instruction: u16) -> Instruction {
+    let quadrant = instruction & 0b11;
+    let funct3 = instruction >> 13;
+    let bit12 = (instruction >> 12) & 0b1;
+    let rd = (instruction >> 7) & 0b11111;
+    let rs2 = (instruction >> 2) & 0b11111;
+    let ctype = match (quadrant, funct3) {
+        (0b00, 0b000) if instruction != 0 => CInstruction::AddImmediate4StackPointer,
+        (0b00, 0b010) => CInstruction::LoadWord,
+        (0b00, 0b110) => CInstruction::StoreWord,
+        (0b01, 0b000) => CInstruction::AddImmediate,
+        (0b01, 0b001) => CInstruction::JumpAndLink,
+        (0b01, 0b010) => CInstruction::LoadImmediate,
+        (0b01, 0b011) if rd == 2 => CInstruction::AddImmediate16StackPointer,
+        (0b01, 0b011) => CInstruction::LoadUpperImmediate,
+        // The shift amounts are encoded on 5 bits for RV32C, the bit 12 must be 0
+        (0b01, 0b100) => match ((instruction >> 10) & 0b11, bit12, (instruction >> 5) & 0b11) {
+            (0b00, 0, _) => CInstruction::ShiftRightLogicalImmediate,
+            (0b01, 0, _) => CInstruction::ShiftRightArithmeticImmediate,
+            (0b10, _, _) => CInstruction::AndImmediate,
+            (0b11, 0, 0b00) => CInstruction::Sub,
+            (0b11, 0, 0b01) => CInstruction::Xor,
+            (0b11, 0, 0b10) => CInstruction::Or,
+            (0b11, 0, 0b11) => CInstruction::And,
+            _ => panic!(
+                "Unknown compressed instruction with full inst {:b}",
+                instruction
+            ),
+        },
+        (0b01, 0b101) => CInstruction::Jump,
+        (0b01, 0b110) => CInstruction::BranchEqZero,
+        (0b01, 0b111) => CInstruction::BranchNeqZero,
+        (0b10, 0b000) if bit12 == 0 => CInstruction::ShiftLeftLogicalImmediate,
+        (0b10, 0b010) => CInstruction::LoadWordStackPointer,
+        (0b10, 0b100) => match (bit12, rs2, rd) {
+            (0, 0, _) => CInstruction::JumpRegister,
+            (0, _, _) => CInstruction::Move,
+            (1, 0, 0) => panic!("c.ebreak is not supported"),
+            (1, 0, _) => CInstruction::JumpAndLinkRegister,
+            _ => CInstruction::Add,
+        },
+        (0b10, 0b110) => CInstruction::StoreWordStackPointer,
+        _ => panic!(
+            "Unknown compressed instruction with full inst {:b}, quadrant {:b} and funct3 {:b}",
+            instruction, quadrant, funct3
+        ),
+    };
+    Instruction::CType(ctype)
+}
 
-    pub fn get_memory_access_page_index(&mut self, page: u32) -> usize {
-        for &i in self.last_memory_write_index_accesses.iter() {
-            if self.memory_write_index[i].0 == page {
-                return i;
-            }
+/// Expand a compressed instruction into the equivalent instruction of the base
+/// instruction set, encoded on 32 bits.
+pub fn decompress_instruction(instruction: u16) -> u32 {
+    let Instruction::CType(ctype) = decode_compressed_instruction(instruction) else {
+        unreachable!("compressed instructions are decoded as CType instructions")
+    };
+    let c = instruction as u32;
+    let bits = |highest_bit: u32, lowest_bit: u32| bits_of(c, highest_bit, lowest_bit);
+    // Sign-extend the lowest `bitlength` bits of `x`
+    let sext =
+        |x: u32, bitlength: u32| (((x << (32 - bitlength)) as i32) >> (32 - bitlength)) as u32;
+    let rd = bits(12, 7);
+    let rs2 = bits(7, 2);
+    // Registers x8 to x15, encoded on 3 bits
+    let rd_prime = bits(5, 2) + 8;
+    let rs1_prime = bits(10, 7) + 8;
+    let imm6 = sext((bits(13, 12) << 5) | bits(7, 2), 6);
+
+    let rtype = |funct7: u32, rs2: u32, rs1: u32, funct3: u32, rd: u32| {
+        (funct7 << 25) | (rs2 << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | 0b0110011
+    };
+    let itype = |imm: u32, rs1: u32, funct3: u32, rd: u32, opcode: u32| {
+        ((imm & 0xFFF) << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | opcode
+    };
+    let stype = |imm: u32, rs2: u32, rs1: u32| {
+        (bits_of(imm, 12, 5) << 25)
+            | (rs2 << 20)
+            | (rs1 << 15)
+            | (0b010 << 12)
+            | (bits_of(imm, 5, 0) << 7)
+            | 0b0100011
+    };
+    let sbtype = |imm: u32, rs1: u32, funct3: u32| {
+        (bits_of(imm, 13, 12) << 31)
+            | (bits_of(imm, 11, 5) << 25)
+            | (rs1 << 15)
+            | (funct3 << 12)
+            | (bits_of(imm, 5, 1) << 8)
+            | (bits_of(imm, 12, 11) << 7)
+            | 0b1100011
+    };
+    let ujtype = |imm: u32, rd: u32| {
+        (bits_of(imm, 21, 20) << 31)
+            | (bits_of(imm, 11, 1) << 21)
+            | (bits_of(imm, 12, 11) << 20)
+            | (bits_of(imm, 20, 12) << 12)
+            | (rd << 7)
+            | 0b1101111
+    };
+    let jump_offset = sext(
+        (bits(13, 12) << 11)
+            | (bits(9, 8) << 10)
+            | (bits(11, 9) << 8)
+            | (bits(7, 6) << 7)
+            | (bits(8, 7) << 6)
+            | (bits(3, 2) << 5)
+            | (bits(12, 11) << 4)
+            | (bits(6, 3) << 1),
+        12,
+    );
+    let branch_offset = sext(
+        (bits(13, 12) << 8)
+            | (bits(7, 5) << 6)
+            | (bits(3, 2) << 5)
+            | (bits(12, 10) << 3)
+            | (bits(5, 3) << 1),
+        9,
+    );
+
+    match ctype {
+        CInstruction::AddImmediate4StackPointer => {
+            let imm =
+                (bits(11, 7) << 6) | (bits(13, 11) << 4) | (bits(6, 5) << 3) | (bits(7, 6) << 2);
+            itype(imm, 2, 0b000, rd_prime, 0b0010011)
         }
-        for (i, (page_index, _memory_write_index)) in self.memory_write_index.iter_mut().enumerate()
-        {
-            if *page_index == page {
-                self.update_last_memory_write_index_access(i);
-                return i;
-            }
+        CInstruction::LoadWord => {
+            let imm = (bits(6, 5) << 6) | (bits(13, 10) << 3) | (bits(7, 6) << 2);
+            itype(imm, rs1_prime, 0b010, rd_prime, 0b0000011)
+        }
+        CInstruction::StoreWord => {
+            let imm = (bits(6, 5) << 6) | (bits(13, 10) << 3) | (bits(7, 6) << 2);
+            stype(imm, rd_prime, rs1_prime)
+        }
+        CInstruction::AddImmediate => itype(imm6, rd, 0b000, rd, 0b0010011),
+        CInstruction::JumpAndLink => ujtype(jump_offset, 1),
+        CInstruction::LoadImmediate => itype(imm6, 0, 0b000, rd, 0b0010011),
+        CInstruction::AddImmediate16StackPointer => {
+            let imm = sext(
+                (bits(13, 12) << 9)
+                    | (bits(5, 3) << 7)
+                    | (bits(6, 5) << 6)
+                    | (bits(3, 2) << 5)
+                    | (bits(7, 6) << 4),
+                10,
+            );
+            itype(imm, 2, 0b000, 2, 0b0010011)
+        }
+        CInstruction::LoadUpperImmediate => {
+            let imm = sext((bits(13, 12) << 17) | (bits(7, 2) << 12), 18);
+            (imm & 0xFFFFF000) | (rd << 7) | 0b0110111
+        }
+        CInstruction::ShiftRightLogicalImmediate => {
+            itype(rs2, rs1_prime, 0b101, rs1_prime, 0b0010011)
+        }
+        CInstruction::ShiftRightArithmeticImmediate => {
+            itype(0x400 | rs2, rs1_prime, 0b101, rs1_prime, 0b0010011)
+        }
+        CInstruction::AndImmediate => itype(imm6, rs1_prime, 0b111, rs1_prime, 0b0010011),
+        CInstruction::Sub => rtype(0b0100000, rd_prime, rs1_prime, 0b000, rs1_prime),
+        CInstruction::Xor => rtype(0, rd_prime, rs1_prime, 0b100, rs1_prime),
+        CInstruction::Or => rtype(0, rd_prime, rs1_prime, 0b110, rs1_prime),
+        CInstruction::And => rtype(0, rd_prime, rs1_prime, 0b111, rs1_prime),
+        CInstruction::Jump => ujtype(jump_offset, 0),
+        CInstruction::BranchEqZero => sbtype(branch_offset, rs1_prime, 0b000),
+        CInstruction::BranchNeqZero => sbtype(branch_offset, rs1_prime, 0b001),
+        CInstruction::ShiftLeftLogicalImmediate => itype(rs2, rd, 0b001, rd, 0b0010011),
+        CInstruction::LoadWordStackPointer => {
+            let imm = (bits(4, 2) << 6) | (bits(13, 12) << 5) | (bits(7, 4) << 2);
+            itype(imm, 2, 0b010, rd, 0b0000011)
+        }
+        CInstruction::JumpRegister => itype(0, rd, 0b000, 0, 0b1100111),
+        CInstruction::Move => rtype(0, rs2, 0, 0b000, rd),
+        CInstruction::JumpAndLinkRegister => itype(0, rd, 0b000, 1, 0b1100111),
+        CInstruction::Add => rtype(0, rs2, rd, 0b000, rd),
+        CInstruction::StoreWordStackPointer => {
+            let imm = (bits(9, 7) << 6) | (bits(13, 9) << 2);
+            stype(imm, rs2, 2)
         }
-
-        // Memory not found; dynamically allocate
-        let memory_write_index = vec![0u64; PAGE_SIZE as usize];
-        self.memory_write_index.push((page, memory_write_index));
-        let i = self.memory_write_index.len() - 1;
-        self.update_last_memory_write_index_access(i);
-        i
     }
+}
 
-    pub fn get_memory_direct(&mut self, addr: u32) -> u8 {
-        let page = addr >> PAGE_ADDRESS_SIZE;
-        let page_address = (addr & PAGE_ADDRESS_MASK) as usize;
-        let memory_idx = self.get_memory_page_index(page);
-        self.memory[memory_idx].1[page_address]
+/// The effect on the call stack of the instruction `instruction`, encoded on
+/// 32 bits, following the calling convention: a jump linking the return
+/// address in `ra` or `t0` is a call, and a jump to the address in one of them
+/// without linking is a return.
+pub fn control_flow(instruction: u32) -> ControlFlow {
+    let opcode = bits_of(instruction, 7, 0);
+    let rd = bits_of(instruction, 12, 7);
+    let rs1 = bits_of(instruction, 20, 15);
+    let is_link = |register| register == 1 || register == 5;
+    match opcode {
+        0b1101111 | 0b1100111 if is_link(rd) => ControlFlow::Call,
+        0b1100111 if rd == 0 && is_link(rs1) => ControlFlow::Return,
+        _ => ControlFlow::Other,
     }
+}
 
-    /// The actual number of instructions executed results from dividing the
-    /// instruction counter by MAX_ACC (floor).
-    ///
-    /// NOTE: actually, in practice it will be less than that, as there is no
-    ///       single instruction that performs all of them.
-    pub fn normalized_instruction_counter(&self) -> u64 {
-        self.instruction_counter / MAX_ACC
-    }
+/// The bits of `x` between `highest_bit` (excluded) and `lowest_bit`
+pub(crate) fn bits_of(x: u32, highest_bit: u32, lowest_bit: u32) -> u32 {
+    (x >> lowest_bit) & ((1 << (highest_bit - lowest_bit)) - 1)
 }
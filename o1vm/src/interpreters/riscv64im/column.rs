@@ -0,0 +1,129 @@
+use super::{
+    interpreter::{
+        IInstruction, IWInstruction,
+        Instruction::{
+            self, IType, IWType, LSType, MType, MWType, RType, RWType, SBType, SType, SyscallType,
+            UJType, UType,
+        },
+        MInstruction, MWInstruction, RInstruction, RWInstruction, SBInstruction, SInstruction,
+        SyscallInstruction, UInstruction, UJInstruction,
+    },
+    SCRATCH_SIZE, SCRATCH_SIZE_INVERSE,
+};
+use strum::EnumCount;
+
+/// The columns are the ones of RV32IM, see
+/// [riscv32im::column::Column](crate::interpreters::riscv32im::column::Column).
+/// The conversion of a column into an index assumes the sizes of RV32IM, and
+/// is therefore not used for RV64IM.
+pub use crate::interpreters::riscv32im::column::{Column, E};
+
+impl From<Instruction> for usize {
+    fn from(instr: Instruction) -> usize {
+        let offset = SCRATCH_SIZE + SCRATCH_SIZE_INVERSE + 1;
+        match instr {
+            RType(rtype) => offset + rtype as usize,
+            IType(itype) => offset + RInstruction::COUNT + itype as usize,
+            SType(stype) => offset + RInstruction::COUNT + IInstruction::COUNT + stype as usize,
+            SBType(sbtype) => {
+                offset
+                    + RInstruction::COUNT
+                    + IInstruction::COUNT
+                    + SInstruction::COUNT
+                    + sbtype as usize
+            }
+            UType(utype) => {
+                offset
+                    + RInstruction::COUNT
+                    + IInstruction::COUNT
+                    + SInstruction::COUNT
+                    + SBInstruction::COUNT
+                    + utype as usize
+            }
+            UJType(ujtype) => {
+                offset
+                    + RInstruction::COUNT
+                    + IInstruction::COUNT
+                    + SInstruction::COUNT
+                    + SBInstruction::COUNT
+                    + UInstruction::COUNT
+                    + ujtype as usize
+            }
+            SyscallType(syscalltype) => {
+                offset
+                    + RInstruction::COUNT
+                    + IInstruction::COUNT
+                    + SInstruction::COUNT
+                    + SBInstruction::COUNT
+                    + UInstruction::COUNT
+                    + UJInstruction::COUNT
+                    + syscalltype as usize
+            }
+            MType(mtype) => {
+                offset
+                    + RInstruction::COUNT
+                    + IInstruction::COUNT
+                    + SInstruction::COUNT
+                    + SBInstruction::COUNT
+                    + UInstruction::COUNT
+                    + UJInstruction::COUNT
+                    + SyscallInstruction::COUNT
+                    + mtype as usize
+            }
+            RWType(rwtype) => {
+                offset
+                    + RInstruction::COUNT
+                    + IInstruction::COUNT
+                    + SInstruction::COUNT
+                    + SBInstruction::COUNT
+                    + UInstruction::COUNT
+                    + UJInstruction::COUNT
+                    + SyscallInstruction::COUNT
+                    + MInstruction::COUNT
+                    + rwtype as usize
+            }
+            IWType(iwtype) => {
+                offset
+                    + RInstruction::COUNT
+                    + IInstruction::COUNT
+                    + SInstruction::COUNT
+                    + SBInstruction::COUNT
+                    + UInstruction::COUNT
+                    + UJInstruction::COUNT
+                    + SyscallInstruction::COUNT
+                    + MInstruction::COUNT
+                    + RWInstruction::COUNT
+                    + iwtype as usize
+            }
+            MWType(mwtype) => {
+                offset
+                    + RInstruction::COUNT
+                    + IInstruction::COUNT
+                    + SInstruction::COUNT
+                    + SBInstruction::COUNT
+                    + UInstruction::COUNT
+                    + UJInstruction::COUNT
+                    + SyscallInstruction::COUNT
+                    + MInstruction::COUNT
+                    + RWInstruction::COUNT
+                    + IWInstruction::COUNT
+                    + mwtype as usize
+            }
+            LSType(lstype) => {
+                offset
+                    + RInstruction::COUNT
+                    + IInstruction::COUNT
+                    + SInstruction::COUNT
+                    + SBInstruction::COUNT
+                    + UInstruction::COUNT
+                    + UJInstruction::COUNT
+                    + SyscallInstruction::COUNT
+                    + MInstruction::COUNT
+                    + RWInstruction::COUNT
+                    + IWInstruction::COUNT
+                    + MWInstruction::COUNT
+                    + lstype as usize
+            }
+        }
+    }
+}
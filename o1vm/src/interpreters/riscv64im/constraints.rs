@@ -0,0 +1,5 @@
+use crate::interpreters::{riscv32im, xlen::Rv64};
+
+/// The constraint environment of RV64IM, which is the one of RV32IM over 64
+/// bits registers, see [riscv32im::constraints::Env].
+pub type Env<F> = riscv32im::constraints::Env<F, Rv64>;
@@ -0,0 +1,675 @@
+//! This module implement an interpreter for the RISCV64 IM instruction set
+//! architecture.
+//!
+//! The instructions shared with RV32IM, i.e. the base instruction set and the
+//! M extension, are interpreted by the functions of the [RISCV32 IM
+//! interpreter](super::super::riscv32im::interpreter), over 64 bits registers
+//! (see [Rv64]). This module only defines the instructions specific to
+//! RV64IM: the instructions `lwu`, `ld` and `sd`, and the instructions
+//! suffixed by `w` which operate on the lower 32 bits of their operands and
+//! sign-extend the 32 bits result to 64 bits.
+//!
+//! The values are stored in memory with the byte order of RV32IM, i.e. the
+//! most significant byte first.
+//!
+//! ## Credits
+//!
+//! We would like to thank the authors of the following documentations:
+//! - <https://msyksphinz-self.github.io/riscv-isadoc/html/rvm.html> ([CC BY
+//!   4.0](https://creativecommons.org/licenses/by/4.0/)) from
+//!   [msyksphinz-self](https://github.com/msyksphinz-self/riscv-isadoc)
+//! - <https://www.cs.cornell.edu/courses/cs3410/2024fa/assignments/cpusim/riscv-instructions.pdf>
+//!   from the course [CS 3410: Computer System Organization and
+//!   Programming](https://www.cs.cornell.edu/courses/cs3410/2024fa/home.html) at
+//!   Cornell University.
+//!
+//! The format and description of each instruction is taken from these sources,
+//! and copied in this file for offline reference.
+//! If you are the author of the above documentations and would like to add or
+//! modify the credits, please open a pull request.
+
+use super::{INSTRUCTION_SET_SIZE, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE};
+use crate::interpreters::{
+    riscv32im::interpreter::{
+        decode_itype, decode_rtype, decode_stype, fetch_instruction, interpret_itype,
+        interpret_mtype, interpret_rtype, interpret_sbtype, interpret_stype, interpret_ujtype,
+        interpret_utype, load_bytes, signed_division, store_bytes, unsigned_division,
+    },
+    xlen::Rv64,
+};
+use strum::{EnumCount, IntoEnumIterator};
+use strum_macros::{EnumCount, EnumIter};
+
+pub use crate::interpreters::riscv32im::interpreter::{
+    IInstruction, InstructionSet, InterpreterEnv, MInstruction, RInstruction, SBInstruction,
+    SInstruction, UInstruction, UJInstruction,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, EnumCount, EnumIter, Hash, Ord, PartialOrd)]
+pub enum Instruction {
+    RType(RInstruction),
+    IType(IInstruction),
+    SType(SInstruction),
+    SBType(SBInstruction),
+    UType(UInstruction),
+    UJType(UJInstruction),
+    SyscallType(SyscallInstruction),
+    MType(MInstruction),
+    RWType(RWInstruction),
+    IWType(IWInstruction),
+    MWType(MWInstruction),
+    LSType(LSInstruction),
+}
+
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, EnumCount, EnumIter, Default, Hash, Ord, PartialOrd,
+)]
+pub enum SyscallInstruction {
+    #[default]
+    SyscallSuccess,
+}
+
+/// The R-type instructions operating on the lower 32 bits of their operands
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, EnumCount, EnumIter, Default, Hash, Ord, PartialOrd,
+)]
+pub enum RWInstruction {
+    #[default]
+    /// Format: `addw rd, rs1, rs2`
+    ///
+    /// Description: Adds the lower 32 bits of the registers rs1 and rs2 and
+    /// stores the sign-extended lower 32 bits of the result in rd.
+    /// Arithmetic overflow is ignored.
+    AddWord, // addw
+    /// Format: `subw rd, rs1, rs2`
+    ///
+    /// Description: Subs the lower 32 bits of the register rs2 from the lower
+    /// 32 bits of rs1 and stores the sign-extended lower 32 bits of the result
+    /// in rd. Arithmetic overflow is ignored.
+    SubWord, // subw
+    /// Format: `sllw rd, rs1, rs2`
+    ///
+    /// Description: Performs logical left shift on the lower 32 bits of the
+    /// value in register rs1 by the shift amount held in the lower 5 bits of
+    /// register rs2, and stores the sign-extended 32 bits result in rd.
+    ShiftLeftLogicalWord, // sllw
+    /// Format: `srlw rd, rs1, rs2`
+    ///
+    /// Description: Performs logical right shift on the lower 32 bits of the
+    /// value in register rs1 by the shift amount held in the lower 5 bits of
+    /// register rs2, and stores the sign-extended 32 bits result in rd.
+    ShiftRightLogicalWord, // srlw
+    /// Format: `sraw rd, rs1, rs2`
+    ///
+    /// Description: Performs arithmetic right shift on the lower 32 bits of
+    /// the value in register rs1 by the shift amount held in the lower 5 bits
+    /// of register rs2, and stores the sign-extended 32 bits result in rd.
+    ShiftRightArithmeticWord, // sraw
+}
+
+/// The I-type instructions operating on the lower 32 bits of their operands
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, EnumCount, EnumIter, Default, Hash, Ord, PartialOrd,
+)]
+pub enum IWInstruction {
+    #[default]
+    /// Format: `addiw rd, rs1, imm`
+    ///
+    /// Description: Adds the sign-extended 12-bit immediate to register rs1
+    /// and stores the sign-extended lower 32 bits of the result in rd.
+    /// Arithmetic overflow is ignored. ADDIW rd, rs1, 0 is used to implement
+    /// the SEXT.W rd, rs1 assembler pseudo-instruction.
+    AddImmediateWord, // addiw
+    /// Format: `slliw rd, rs1, shamt`
+    ///
+    /// Description: Performs logical left shift on the lower 32 bits of the
+    /// value in register rs1 by the shift amount held in the lower 5 bits of
+    /// the immediate, and stores the sign-extended 32 bits result in rd.
+    ShiftLeftLogicalImmediateWord, // slliw
+    /// Format: `srliw rd, rs1, shamt`
+    ///
+    /// Description: Performs logical right shift on the lower 32 bits of the
+    /// value in register rs1 by the shift amount held in the lower 5 bits of
+    /// the immediate, and stores the sign-extended 32 bits result in rd.
+    ShiftRightLogicalImmediateWord, // srliw
+    /// Format: `sraiw rd, rs1, shamt`
+    ///
+    /// Description: Performs arithmetic right shift on the lower 32 bits of
+    /// the value in register rs1 by the shift amount held in the lower 5 bits
+    /// of the immediate, and stores the sign-extended 32 bits result in rd.
+    ShiftRightArithmeticImmediateWord, // sraiw
+}
+
+/// The M extension instructions operating on the lower 32 bits of their
+/// operands
+/// Following <https://msyksphinz-self.github.io/riscv-isadoc/html/rvm.html>
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, EnumCount, EnumIter, Default, Hash, Ord, PartialOrd,
+)]
+pub enum MWInstruction {
+    /// Format: `mulw rd, rs1, rs2`
+    ///
+    /// Description: performs an 32-bit 32-bit multiplication of the lower 32
+    /// bits of rs1 and rs2 and places the sign-extended lower 32 bits of the
+    /// result in the destination register.
+    /// Implementation: `x[rd] = sext((x[rs1] * x[rs2])[31:0])`
+    #[default]
+    MulWord, // mulw
+    /// Format: `divw rd, rs1, rs2`
+    ///
+    /// Description: perform an 32 bits by 32 bits signed integer division of
+    /// the lower 32 bits of rs1 by the lower 32 bits of rs2, rounding towards
+    /// zero, and sign-extends the 32 bits result.
+    /// Implementation: `x[rd] = sext(x[rs1][31:0] /s x[rs2][31:0])`
+    DivWord, // divw
+    /// Format: `divuw rd, rs1, rs2`
+    ///
+    /// Description: perform an 32 bits by 32 bits unsigned integer division of
+    /// the lower 32 bits of rs1 by the lower 32 bits of rs2, rounding towards
+    /// zero, and sign-extends the 32 bits result.
+    /// Implementation: `x[rd] = sext(x[rs1][31:0] /u x[rs2][31:0])`
+    DivuWord, // divuw
+    /// Format: `remw rd, rs1, rs2`
+    ///
+    /// Description: perform an 32 bits by 32 bits signed integer reminder of
+    /// the lower 32 bits of rs1 by the lower 32 bits of rs2, and sign-extends
+    /// the 32 bits result.
+    /// Implementation: `x[rd] = sext(x[rs1][31:0] %s x[rs2][31:0])`
+    RemWord, // remw
+    /// Format: `remuw rd, rs1, rs2`
+    ///
+    /// Description: perform an 32 bits by 32 bits unsigned integer reminder of
+    /// the lower 32 bits of rs1 by the lower 32 bits of rs2, and sign-extends
+    /// the 32 bits result.
+    /// Implementation: `x[rd] = sext(x[rs1][31:0] %u x[rs2][31:0])`
+    RemuWord, // remuw
+}
+
+/// The loads and stores specific to RV64IM, encoded as I-type and S-type
+/// instructions
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, EnumCount, EnumIter, Default, Hash, Ord, PartialOrd,
+)]
+pub enum LSInstruction {
+    #[default]
+    /// Format: `lwu rd, offset(rs1)`
+    ///
+    /// Description: Loads a 32-bit value from memory and zero-extends this to
+    /// 64 bits before storing it in register rd.
+    LoadWordUnsigned, // lwu
+    /// Format: `ld rd, offset(rs1)`
+    ///
+    /// Description: Loads a 64-bit value from memory into register rd.
+    LoadDoubleWord, // ld
+    /// Format: `sd rs2, offset(rs1)`
+    ///
+    /// Description: Store 64-bit, values from register rs2 to memory.
+    StoreDoubleWord, // sd
+}
+
+impl IntoIterator for Instruction {
+    type Item = Instruction;
+    type IntoIter = std::vec::IntoIter<Instruction>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            Instruction::RType(_) => {
+                let mut iter_contents = Vec::with_capacity(RInstruction::COUNT);
+                for rtype in RInstruction::iter() {
+                    iter_contents.push(Instruction::RType(rtype));
+                }
+                iter_contents.into_iter()
+            }
+            Instruction::IType(_) => {
+                let mut iter_contents = Vec::with_capacity(IInstruction::COUNT);
+                for itype in IInstruction::iter() {
+                    iter_contents.push(Instruction::IType(itype));
+                }
+                iter_contents.into_iter()
+            }
+            Instruction::SType(_) => {
+                let mut iter_contents = Vec::with_capacity(SInstruction::COUNT);
+                for stype in SInstruction::iter() {
+                    iter_contents.push(Instruction::SType(stype));
+                }
+                iter_contents.into_iter()
+            }
+            Instruction::SBType(_) => {
+                let mut iter_contents = Vec::with_capacity(SBInstruction::COUNT);
+                for sbtype in SBInstruction::iter() {
+                    iter_contents.push(Instruction::SBType(sbtype));
+                }
+                iter_contents.into_iter()
+            }
+            Instruction::UType(_) => {
+                let mut iter_contents = Vec::with_capacity(UInstruction::COUNT);
+                for utype in UInstruction::iter() {
+                    iter_contents.push(Instruction::UType(utype));
+                }
+                iter_contents.into_iter()
+            }
+            Instruction::UJType(_) => {
+                let mut iter_contents = Vec::with_capacity(UJInstruction::COUNT);
+                for ujtype in UJInstruction::iter() {
+                    iter_contents.push(Instruction::UJType(ujtype));
+                }
+                iter_contents.into_iter()
+            }
+            Instruction::SyscallType(_) => {
+                let mut iter_contents = Vec::with_capacity(SyscallInstruction::COUNT);
+                for syscall in SyscallInstruction::iter() {
+                    iter_contents.push(Instruction::SyscallType(syscall));
+                }
+                iter_contents.into_iter()
+            }
+            Instruction::MType(_) => {
+                let mut iter_contents = Vec::with_capacity(MInstruction::COUNT);
+                for mtype in MInstruction::iter() {
+                    iter_contents.push(Instruction::MType(mtype));
+                }
+                iter_contents.into_iter()
+            }
+            Instruction::RWType(_) => {
+                let mut iter_contents = Vec::with_capacity(RWInstruction::COUNT);
+                for rwtype in RWInstruction::iter() {
+                    iter_contents.push(Instruction::RWType(rwtype));
+                }
+                iter_contents.into_iter()
+            }
+            Instruction::IWType(_) => {
+                let mut iter_contents = Vec::with_capacity(IWInstruction::COUNT);
+                for iwtype in IWInstruction::iter() {
+                    iter_contents.push(Instruction::IWType(iwtype));
+                }
+                iter_contents.into_iter()
+            }
+            Instruction::MWType(_) => {
+                let mut iter_contents = Vec::with_capacity(MWInstruction::COUNT);
+                for mwtype in MWInstruction::iter() {
+                    iter_contents.push(Instruction::MWType(mwtype));
+                }
+                iter_contents.into_iter()
+            }
+            Instruction::LSType(_) => {
+                let mut iter_contents = Vec::with_capacity(LSInstruction::COUNT);
+                for lstype in LSInstruction::iter() {
+                    iter_contents.push(Instruction::LSType(lstype));
+                }
+                iter_contents.into_iter()
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Instruction::RType(rtype) => write!(f, "{}", rtype),
+            Instruction::IType(itype) => write!(f, "{}", itype),
+            Instruction::SType(stype) => write!(f, "{}", stype),
+            Instruction::SBType(sbtype) => write!(f, "{}", sbtype),
+            Instruction::UType(utype) => write!(f, "{}", utype),
+            Instruction::UJType(ujtype) => write!(f, "{}", ujtype),
+            Instruction::SyscallType(_syscall) => write!(f, "ecall"),
+            Instruction::MType(mtype) => write!(f, "{}", mtype),
+            Instruction::RWType(rwtype) => write!(f, "{}", rwtype),
+            Instruction::IWType(iwtype) => write!(f, "{}", iwtype),
+            Instruction::MWType(mwtype) => write!(f, "{}", mwtype),
+            Instruction::LSType(lstype) => write!(f, "{}", lstype),
+        }
+    }
+}
+
+impl std::fmt::Display for RWInstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RWInstruction::AddWord => write!(f, "addw"),
+            RWInstruction::SubWord => write!(f, "subw"),
+            RWInstruction::ShiftLeftLogicalWord => write!(f, "sllw"),
+            RWInstruction::ShiftRightLogicalWord => write!(f, "srlw"),
+            RWInstruction::ShiftRightArithmeticWord => write!(f, "sraw"),
+        }
+    }
+}
+
+impl std::fmt::Display for IWInstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IWInstruction::AddImmediateWord => write!(f, "addiw"),
+            IWInstruction::ShiftLeftLogicalImmediateWord => write!(f, "slliw"),
+            IWInstruction::ShiftRightLogicalImmediateWord => write!(f, "srliw"),
+            IWInstruction::ShiftRightArithmeticImmediateWord => write!(f, "sraiw"),
+        }
+    }
+}
+
+impl std::fmt::Display for MWInstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MWInstruction::MulWord => write!(f, "mulw"),
+            MWInstruction::DivWord => write!(f, "divw"),
+            MWInstruction::DivuWord => write!(f, "divuw"),
+            MWInstruction::RemWord => write!(f, "remw"),
+            MWInstruction::RemuWord => write!(f, "remuw"),
+        }
+    }
+}
+
+impl std::fmt::Display for LSInstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LSInstruction::LoadWordUnsigned => write!(f, "lwu"),
+            LSInstruction::LoadDoubleWord => write!(f, "ld"),
+            LSInstruction::StoreDoubleWord => write!(f, "sd"),
+        }
+    }
+}
+
+impl InstructionSet for Rv64 {
+    type Instruction = Instruction;
+
+    const SCRATCH_SIZE: usize = SCRATCH_SIZE;
+
+    const SCRATCH_SIZE_INVERSE: usize = SCRATCH_SIZE_INVERSE;
+
+    const INSTRUCTION_SET_SIZE: usize = INSTRUCTION_SET_SIZE;
+
+    fn interpret_instruction<Env: InterpreterEnv<Xlen = Self>>(env: &mut Env, instr: Instruction) {
+        interpret_instruction(env, instr)
+    }
+}
+
+pub fn interpret_instruction<Env: InterpreterEnv<Xlen = Rv64>>(env: &mut Env, instr: Instruction) {
+    env.activate_selector(instr);
+    match instr {
+        Instruction::RType(rtype) => interpret_rtype(env, rtype),
+        Instruction::IType(itype) => interpret_itype(env, itype),
+        Instruction::SType(stype) => interpret_stype(env, stype),
+        Instruction::SBType(sbtype) => interpret_sbtype(env, sbtype),
+        Instruction::UType(utype) => interpret_utype(env, utype),
+        Instruction::UJType(ujtype) => interpret_ujtype(env, ujtype),
+        Instruction::SyscallType(syscall) => interpret_syscall(env, syscall),
+        Instruction::MType(mtype) => interpret_mtype(env, mtype),
+        Instruction::RWType(rwtype) => interpret_rwtype(env, rwtype),
+        Instruction::IWType(iwtype) => interpret_iwtype(env, iwtype),
+        Instruction::MWType(mwtype) => interpret_mwtype(env, mwtype),
+        Instruction::LSType(lstype) => interpret_lstype(env, lstype),
+    }
+}
+
+/// Return the lower 32 bits of `x`.
+fn lower_word<Env: InterpreterEnv>(env: &mut Env, x: &Env::Variable) -> Env::Variable {
+    let pos = env.alloc_scratch();
+    unsafe { env.bitmask(x, 32, 0, pos) }
+}
+
+/// Sign-extend the lower 32 bits of `x` to 64 bits, as done by the
+/// instructions suffixed by `w` with their result.
+fn sign_extend_word<Env: InterpreterEnv>(env: &mut Env, x: &Env::Variable) -> Env::Variable {
+    let word = lower_word(env, x);
+    env.sign_extend(&word, 32)
+}
+
+/// Interpret an R-type instruction operating on the lower 32 bits of its
+/// operands. The encoding is the one of the R-type instructions, see
+/// [interpret_rtype].
+pub fn interpret_rwtype<Env: InterpreterEnv>(env: &mut Env, instr: RWInstruction) {
+    let instruction_pointer = env.get_instruction_pointer();
+    let next_instruction_pointer = env.get_next_instruction_pointer();
+
+    let instruction = fetch_instruction(env, &instruction_pointer);
+    let (rd, rs1, rs2) = decode_rtype(env, &instruction);
+
+    let local_rs1 = env.read_register(&rs1);
+    let local_rs2 = env.read_register(&rs2);
+
+    match instr {
+        RWInstruction::AddWord => {
+            /* addw: x[rd] = sext((x[rs1] + x[rs2])[31:0]) */
+            let sum = {
+                let overflow_scratch = env.alloc_scratch();
+                let rd_scratch = env.alloc_scratch();
+                let (sum, _overflow) = unsafe {
+                    env.add_witness(&local_rs1, &local_rs2, rd_scratch, overflow_scratch)
+                };
+                sum
+            };
+            let local_rd = sign_extend_word(env, &sum);
+            env.write_register(&rd, local_rd);
+        }
+        RWInstruction::SubWord => {
+            /* subw: x[rd] = sext((x[rs1] - x[rs2])[31:0]) */
+            let difference = {
+                let underflow_scratch = env.alloc_scratch();
+                let rd_scratch = env.alloc_scratch();
+                let (difference, _underflow) = unsafe {
+                    env.sub_witness(&local_rs1, &local_rs2, rd_scratch, underflow_scratch)
+                };
+                difference
+            };
+            let local_rd = sign_extend_word(env, &difference);
+            env.write_register(&rd, local_rd);
+        }
+        RWInstruction::ShiftLeftLogicalWord => {
+            /* sllw: x[rd] = sext((x[rs1] << x[rs2][4:0])[31:0]) */
+            let shamt = {
+                let pos = env.alloc_scratch();
+                unsafe { env.bitmask(&local_rs2, 5, 0, pos) }
+            };
+            let shifted = {
+                let pos = env.alloc_scratch();
+                unsafe { env.shift_left(&local_rs1, &shamt, pos) }
+            };
+            let local_rd = sign_extend_word(env, &shifted);
+            env.write_register(&rd, local_rd);
+        }
+        RWInstruction::ShiftRightLogicalWord => {
+            /* srlw: x[rd] = sext(x[rs1][31:0] >> x[rs2][4:0]) */
+            let shamt = {
+                let pos = env.alloc_scratch();
+                unsafe { env.bitmask(&local_rs2, 5, 0, pos) }
+            };
+            let word = lower_word(env, &local_rs1);
+            let shifted = {
+                let pos = env.alloc_scratch();
+                unsafe { env.shift_right(&word, &shamt, pos) }
+            };
+            let local_rd = env.sign_extend(&shifted, 32);
+            env.write_register(&rd, local_rd);
+        }
+        RWInstruction::ShiftRightArithmeticWord => {
+            /* sraw: x[rd] = sext(x[rs1][31:0] >>s x[rs2][4:0]) */
+            let shamt = {
+                let pos = env.alloc_scratch();
+                unsafe { env.bitmask(&local_rs2, 5, 0, pos) }
+            };
+            // The arithmetic shift of the sign-extended word is already
+            // sign-extended
+            let word = sign_extend_word(env, &local_rs1);
+            let local_rd = {
+                let pos = env.alloc_scratch();
+                unsafe { env.shift_right_arithmetic(&word, &shamt, pos) }
+            };
+            env.write_register(&rd, local_rd);
+        }
+    };
+
+    env.set_instruction_pointer(next_instruction_pointer.clone());
+    env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
+}
+
+/// Interpret an I-type instruction operating on the lower 32 bits of its
+/// operand. The encoding is the one of the I-type instructions, see
+/// [interpret_itype]. The shift amount of the shifts is held in the lower 5
+/// bits of the immediate, the sixth bit being zero.
+pub fn interpret_iwtype<Env: InterpreterEnv>(env: &mut Env, instr: IWInstruction) {
+    let instruction_pointer = env.get_instruction_pointer();
+    let next_instruction_pointer = env.get_next_instruction_pointer();
+
+    let instruction = fetch_instruction(env, &instruction_pointer);
+    let (rd, rs1, imm, shamt) = decode_itype(env, &instruction);
+
+    let local_rs1 = env.read_register(&rs1);
+
+    match instr {
+        IWInstruction::AddImmediateWord => {
+            // addiw: x[rd] = sext((x[rs1] + sext(immediate))[31:0])
+            let local_imm = env.sign_extend(&imm, 12);
+            let sum = {
+                let overflow_scratch = env.alloc_scratch();
+                let rd_scratch = env.alloc_scratch();
+                let (sum, _overflow) = unsafe {
+                    env.add_witness(&local_rs1, &local_imm, rd_scratch, overflow_scratch)
+                };
+                sum
+            };
+            let local_rd = sign_extend_word(env, &sum);
+            env.write_register(&rd, local_rd);
+        }
+        IWInstruction::ShiftLeftLogicalImmediateWord => {
+            // slliw: x[rd] = sext((x[rs1] << shamt)[31:0])
+            let shifted = {
+                let pos = env.alloc_scratch();
+                unsafe { env.shift_left(&local_rs1, &shamt, pos) }
+            };
+            let local_rd = sign_extend_word(env, &shifted);
+            env.write_register(&rd, local_rd);
+        }
+        IWInstruction::ShiftRightLogicalImmediateWord => {
+            // srliw: x[rd] = sext(x[rs1][31:0] >> shamt)
+            let word = lower_word(env, &local_rs1);
+            let shifted = {
+                let pos = env.alloc_scratch();
+                unsafe { env.shift_right(&word, &shamt, pos) }
+            };
+            let local_rd = env.sign_extend(&shifted, 32);
+            env.write_register(&rd, local_rd);
+        }
+        IWInstruction::ShiftRightArithmeticImmediateWord => {
+            // sraiw: x[rd] = sext(x[rs1][31:0] >>s shamt)
+            // The arithmetic shift of the sign-extended word is already
+            // sign-extended
+            let word = sign_extend_word(env, &local_rs1);
+            let local_rd = {
+                let pos = env.alloc_scratch();
+                unsafe { env.shift_right_arithmetic(&word, &shamt, pos) }
+            };
+            env.write_register(&rd, local_rd);
+        }
+    };
+
+    env.set_instruction_pointer(next_instruction_pointer.clone());
+    env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
+}
+
+/// Interpret an M-type instruction operating on the lower 32 bits of its
+/// operands. The encoding is the one of the M-type instructions, see
+/// [interpret_mtype].
+///
+/// The divisions are the 64 bits divisions of the operands sign-extended (or
+/// zero-extended for the unsigned ones) from 32 bits, whose lower 32 bits are
+/// the result of the 32 bits divisions, including the division by zero and
+/// the overflowing division of `-2^31` by `-1`.
+pub fn interpret_mwtype<Env: InterpreterEnv>(env: &mut Env, instr: MWInstruction) {
+    let instruction_pointer = env.get_instruction_pointer();
+    let next_instruction_pointer = env.get_next_instruction_pointer();
+
+    let instruction = fetch_instruction(env, &instruction_pointer);
+    // FIXME: check funct2 is equal to 01 and funct5 to 00000?
+    let (rd, rs1, rs2) = decode_rtype(env, &instruction);
+
+    let rs1 = env.read_register(&rs1);
+    let rs2 = env.read_register(&rs2);
+
+    let res = match instr {
+        MWInstruction::MulWord => {
+            // x[rd] = sext((x[rs1] * x[rs2])[31:0])
+            // FIXME: constrain
+            let product = {
+                let pos = env.alloc_scratch();
+                unsafe { env.mul_lo(&rs1, &rs2, pos) }
+            };
+            sign_extend_word(env, &product)
+        }
+        MWInstruction::DivWord => {
+            // x[rd] = sext(signed(x[rs1][31:0]) / signed(x[rs2][31:0]))
+            let rs1 = sign_extend_word(env, &rs1);
+            let rs2 = sign_extend_word(env, &rs2);
+            let (quotient, _remainder) = signed_division(env, &rs1, &rs2);
+            sign_extend_word(env, &quotient)
+        }
+        MWInstruction::DivuWord => {
+            // x[rd] = sext(x[rs1][31:0] / x[rs2][31:0])
+            let rs1 = lower_word(env, &rs1);
+            let rs2 = lower_word(env, &rs2);
+            let (quotient, _remainder) = unsigned_division(env, &rs1, &rs2);
+            sign_extend_word(env, &quotient)
+        }
+        MWInstruction::RemWord => {
+            // x[rd] = sext(signed(x[rs1][31:0]) % signed(x[rs2][31:0]))
+            let rs1 = sign_extend_word(env, &rs1);
+            let rs2 = sign_extend_word(env, &rs2);
+            let (_quotient, remainder) = signed_division(env, &rs1, &rs2);
+            sign_extend_word(env, &remainder)
+        }
+        MWInstruction::RemuWord => {
+            // x[rd] = sext(x[rs1][31:0] % x[rs2][31:0])
+            let rs1 = lower_word(env, &rs1);
+            let rs2 = lower_word(env, &rs2);
+            let (_quotient, remainder) = unsigned_division(env, &rs1, &rs2);
+            sign_extend_word(env, &remainder)
+        }
+    };
+    env.write_register(&rd, res);
+
+    env.set_instruction_pointer(next_instruction_pointer.clone());
+    env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
+}
+
+/// Interpret a load or a store specific to RV64IM. The loads are encoded as
+/// I-type instructions, see [interpret_itype], and the store as an S-type
+/// instruction, see [interpret_stype]. As the other loads and stores, they
+/// access the memory byte per byte, the most significant byte first.
+pub fn interpret_lstype<Env: InterpreterEnv>(env: &mut Env, instr: LSInstruction) {
+    let instruction_pointer = env.get_instruction_pointer();
+    let next_instruction_pointer = env.get_next_instruction_pointer();
+
+    let instruction = fetch_instruction(env, &instruction_pointer);
+
+    match instr {
+        LSInstruction::LoadWordUnsigned | LSInstruction::LoadDoubleWord => {
+            // lwu: x[rd] = M[x[rs1] + sext(offset)][31:0]
+            // ld:  x[rd] = M[x[rs1] + sext(offset)][63:0]
+            let (rd, rs1, imm, _shamt) = decode_itype(env, &instruction);
+            let base = env.read_register(&rs1);
+            let offset = env.sign_extend(&imm, 12);
+            let address = {
+                let address_scratch = env.alloc_scratch();
+                let overflow_scratch = env.alloc_scratch();
+                let (address, _overflow) =
+                    unsafe { env.add_witness(&base, &offset, address_scratch, overflow_scratch) };
+                address
+            };
+            // Add a range check here for address
+            let bytes = match instr {
+                LSInstruction::LoadWordUnsigned => 4,
+                _ => 8,
+            };
+            let value = load_bytes(env, &address, bytes);
+            env.write_register(&rd, value);
+        }
+        LSInstruction::StoreDoubleWord => {
+            // sd: M[x[rs1] + sext(offset)] = x[rs2][63:0]
+            let (address, local_rs2) = decode_stype(env, &instruction);
+            store_bytes(env, &address, &local_rs2, 8);
+        }
+    };
+
+    env.set_instruction_pointer(next_instruction_pointer.clone());
+    env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u64));
+}
+
+pub fn interpret_syscall<Env: InterpreterEnv>(env: &mut Env, _instr: SyscallInstruction) {
+    // FIXME: check if it is syscall success. There is only one syscall atm
+    env.set_halted(Env::constant(1));
+}
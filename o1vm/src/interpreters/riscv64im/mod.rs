@@ -0,0 +1,26 @@
+/// The minimal number of columns required for the VM
+pub const SCRATCH_SIZE: usize = 68;
+pub const SCRATCH_SIZE_INVERSE: usize = 4;
+
+/// Number of instructions in the ISA
+pub const INSTRUCTION_SET_SIZE: usize = 65;
+
+pub const PAGE_ADDRESS_SIZE: u64 = 12;
+pub const PAGE_SIZE: u64 = 1 << PAGE_ADDRESS_SIZE;
+pub const PAGE_ADDRESS_MASK: u64 = PAGE_SIZE - 1;
+
+/// List all columns used by the interpreter
+pub mod column;
+
+pub mod constraints;
+
+pub mod interpreter;
+
+/// All the registers used by the ISA, which are the same as the ones of
+/// RV32IM, holding 64 bits values.
+pub use super::riscv32im::registers;
+
+pub mod witness;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,443 @@
+use super::{
+    constraints,
+    interpreter::{
+        interpret_instruction, IInstruction, IWInstruction, Instruction, InterpreterEnv,
+        LSInstruction, MInstruction, MWInstruction, RInstruction, RWInstruction, SBInstruction,
+        SInstruction, SyscallInstruction, UInstruction, UJInstruction,
+    },
+    registers::Registers,
+    witness::Env,
+    INSTRUCTION_SET_SIZE, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE,
+};
+use crate::{
+    interpreters::xlen::{Rv32, Rv64, Xlen},
+    memory::SparseMemory,
+};
+use ark_ff::Zero;
+use mina_curves::pasta::Fp;
+use strum::{EnumCount, IntoEnumIterator};
+
+// Sanity check that we have as many selector as we have instructions
+#[test]
+fn test_regression_selectors_for_instructions() {
+    let con_env = constraints::Env::<Fp>::default();
+    let constraints = con_env.get_selector_constraints();
+    assert_eq!(
+        // We subtract 1 as we have one boolean check per sel
+        // and 1 constraint to check that one and only one
+        // sel is activated
+        constraints.len() - 1,
+        // This should match the list in
+        // crate::interpreters::riscv64im::interpreter::Instruction
+        RInstruction::COUNT
+            + IInstruction::COUNT
+            + SInstruction::COUNT
+            + SBInstruction::COUNT
+            + UInstruction::COUNT
+            + UJInstruction::COUNT
+            + SyscallInstruction::COUNT
+            + MInstruction::COUNT
+            + RWInstruction::COUNT
+            + IWInstruction::COUNT
+            + MWInstruction::COUNT
+            + LSInstruction::COUNT
+    );
+    // All instructions are degree 1 or 2.
+    constraints
+        .iter()
+        .for_each(|c| assert!(c.degree(1, 0) == 2 || c.degree(1, 0) == 1));
+}
+
+// The scratch state must be large enough for the instruction using the most
+// columns, and not larger.
+#[test]
+fn test_regression_scratch_size() {
+    let mut env = constraints::Env::<Fp>::default();
+    let (scratch_size, scratch_size_inverse) = Instruction::iter()
+        .flat_map(|x| x.into_iter())
+        .map(|instruction| {
+            interpret_instruction(&mut env, instruction);
+            let sizes = (env.scratch_state_idx, env.scratch_state_idx_inverse);
+            env.reset();
+            sizes
+        })
+        .fold((0, 0), |(size, size_inverse), (s, s_inverse)| {
+            (size.max(s), size_inverse.max(s_inverse))
+        });
+    assert_eq!(scratch_size, SCRATCH_SIZE);
+    assert_eq!(scratch_size_inverse, SCRATCH_SIZE_INVERSE);
+}
+
+pub fn dummy_env() -> Env<Fp> {
+    Env {
+        instruction_counter: 0,
+        memory: SparseMemory::new(),
+        memory_write_index: SparseMemory::new(),
+        registers: Registers {
+            next_instruction_pointer: 4,
+            ..Registers::default()
+        },
+        registers_write_index: Registers::default(),
+        scratch_state_idx: 0,
+        scratch_state: [Fp::zero(); SCRATCH_SIZE],
+        scratch_state_inverse_idx: 0,
+        scratch_state_inverse: [Fp::zero(); SCRATCH_SIZE_INVERSE],
+        halt: false,
+        selector: INSTRUCTION_SET_SIZE,
+    }
+}
+
+fn encode_rtype(opcode: u32, funct3: u32, funct7: u32, rd: u32, rs1: u32, rs2: u32) -> u32 {
+    opcode | (rd << 7) | (funct3 << 12) | (rs1 << 15) | (rs2 << 20) | (funct7 << 25)
+}
+
+fn encode_itype(opcode: u32, funct3: u32, rd: u32, rs1: u32, imm: i32) -> u32 {
+    opcode | (rd << 7) | (funct3 << 12) | (rs1 << 15) | (((imm as u32) & 0xFFF) << 20)
+}
+
+fn encode_stype(funct3: u32, rs1: u32, rs2: u32, imm: i32) -> u32 {
+    let imm = imm as u32;
+    0b0100011
+        | ((imm & 0x1F) << 7)
+        | (funct3 << 12)
+        | (rs1 << 15)
+        | (rs2 << 20)
+        | (((imm >> 5) & 0x7F) << 25)
+}
+
+fn encode_sbtype(funct3: u32, rs1: u32, rs2: u32, imm: i32) -> u32 {
+    let imm = imm as u32;
+    0b1100011
+        | (((imm >> 11) & 0x1) << 7)
+        | (((imm >> 1) & 0xF) << 8)
+        | (funct3 << 12)
+        | (rs1 << 15)
+        | (rs2 << 20)
+        | (((imm >> 5) & 0x3F) << 25)
+        | (((imm >> 12) & 0x1) << 31)
+}
+
+/// Write the instruction at the current instruction pointer, and execute it
+fn execute(env: &mut Env<Fp>, instruction: u32) -> Instruction {
+    let instruction_pointer = env.registers.current_instruction_pointer;
+    for (i, byte) in instruction.to_le_bytes().into_iter().enumerate() {
+        env.memory.set(instruction_pointer + i as u64, byte);
+    }
+    env.step()
+}
+
+#[test]
+fn test_instruction_decoding_rv64_instructions() {
+    let cases = [
+        (
+            encode_rtype(0b0111011, 0b000, 0b0000000, 1, 2, 3),
+            Instruction::RWType(RWInstruction::AddWord),
+        ),
+        (
+            encode_rtype(0b0111011, 0b000, 0b0100000, 1, 2, 3),
+            Instruction::RWType(RWInstruction::SubWord),
+        ),
+        (
+            encode_rtype(0b0111011, 0b001, 0b0000000, 1, 2, 3),
+            Instruction::RWType(RWInstruction::ShiftLeftLogicalWord),
+        ),
+        (
+            encode_rtype(0b0111011, 0b101, 0b0000000, 1, 2, 3),
+            Instruction::RWType(RWInstruction::ShiftRightLogicalWord),
+        ),
+        (
+            encode_rtype(0b0111011, 0b101, 0b0100000, 1, 2, 3),
+            Instruction::RWType(RWInstruction::ShiftRightArithmeticWord),
+        ),
+        (
+            encode_rtype(0b0111011, 0b000, 0b0000001, 1, 2, 3),
+            Instruction::MWType(MWInstruction::MulWord),
+        ),
+        (
+            encode_rtype(0b0111011, 0b100, 0b0000001, 1, 2, 3),
+            Instruction::MWType(MWInstruction::DivWord),
+        ),
+        (
+            encode_rtype(0b0111011, 0b101, 0b0000001, 1, 2, 3),
+            Instruction::MWType(MWInstruction::DivuWord),
+        ),
+        (
+            encode_rtype(0b0111011, 0b110, 0b0000001, 1, 2, 3),
+            Instruction::MWType(MWInstruction::RemWord),
+        ),
+        (
+            encode_rtype(0b0111011, 0b111, 0b0000001, 1, 2, 3),
+            Instruction::MWType(MWInstruction::RemuWord),
+        ),
+        (
+            encode_itype(0b0000011, 0b110, 1, 2, -8),
+            Instruction::LSType(LSInstruction::LoadWordUnsigned),
+        ),
+        (
+            encode_itype(0b0000011, 0b011, 1, 2, 8),
+            Instruction::LSType(LSInstruction::LoadDoubleWord),
+        ),
+        (
+            encode_itype(0b0011011, 0b000, 1, 2, -1),
+            Instruction::IWType(IWInstruction::AddImmediateWord),
+        ),
+        (
+            encode_itype(0b0011011, 0b001, 1, 2, 31),
+            Instruction::IWType(IWInstruction::ShiftLeftLogicalImmediateWord),
+        ),
+        (
+            encode_itype(0b0011011, 0b101, 1, 2, 31),
+            Instruction::IWType(IWInstruction::ShiftRightLogicalImmediateWord),
+        ),
+        (
+            encode_itype(0b0011011, 0b101, 1, 2, 0x400 | 31),
+            Instruction::IWType(IWInstruction::ShiftRightArithmeticImmediateWord),
+        ),
+        (
+            encode_itype(0b0010011, 0b101, 1, 2, 0x400 | 63),
+            Instruction::IType(IInstruction::ShiftRightArithmeticImmediate),
+        ),
+        (
+            encode_stype(0b011, 2, 3, -16),
+            Instruction::LSType(LSInstruction::StoreDoubleWord),
+        ),
+    ];
+    for (encoding, expected) in cases {
+        let mut env = dummy_env();
+        env.memory.page_mut(0)[0..4].copy_from_slice(&encoding.to_le_bytes());
+        let (instruction, _) = env.decode_instruction();
+        assert_eq!(instruction, expected, "{expected} decoded as {instruction}");
+    }
+}
+
+#[test]
+fn test_word_instructions_sign_extend_their_result() {
+    let mut env = dummy_env();
+    env.registers[2] = 0x7FFF_FFFF;
+    env.registers[3] = 1;
+    // addw x1, x2, x3
+    execute(&mut env, encode_rtype(0b0111011, 0b000, 0b0000000, 1, 2, 3));
+    assert_eq!(env.registers[1], 0xFFFF_FFFF_8000_0000);
+    // add x4, x2, x3
+    execute(&mut env, encode_rtype(0b0110011, 0b000, 0b0000000, 4, 2, 3));
+    assert_eq!(env.registers[4], 0x8000_0000);
+    // addiw x5, x1, -1
+    execute(&mut env, encode_itype(0b0011011, 0b000, 5, 1, -1));
+    assert_eq!(env.registers[5], 0x7FFF_FFFF);
+    // subw x6, x0, x3
+    execute(&mut env, encode_rtype(0b0111011, 0b000, 0b0100000, 6, 0, 3));
+    assert_eq!(env.registers[6], u64::MAX);
+    // sraiw x7, x1, 4
+    execute(&mut env, encode_itype(0b0011011, 0b101, 7, 1, 0x400 | 4));
+    assert_eq!(env.registers[7], 0xFFFF_FFFF_F800_0000);
+    // srliw x8, x1, 4
+    execute(&mut env, encode_itype(0b0011011, 0b101, 8, 1, 4));
+    assert_eq!(env.registers[8], 0x0800_0000);
+    // slliw x9, x3, 31
+    execute(&mut env, encode_itype(0b0011011, 0b001, 9, 3, 31));
+    assert_eq!(env.registers[9], 0xFFFF_FFFF_8000_0000);
+    // slli x10, x3, 63
+    execute(&mut env, encode_itype(0b0010011, 0b001, 10, 3, 63));
+    assert_eq!(env.registers[10], 1 << 63);
+    // srai x11, x10, 63
+    execute(&mut env, encode_itype(0b0010011, 0b101, 11, 10, 0x400 | 63));
+    assert_eq!(env.registers[11], u64::MAX);
+    assert_eq!(env.registers.current_instruction_pointer, 36);
+}
+
+#[test]
+fn test_multiplications_and_divisions() {
+    let mut env = dummy_env();
+    env.registers[2] = u64::MAX; // -1
+    env.registers[3] = 1 << 63;
+    env.registers[4] = 0;
+    // mulh x1, x2, x3: -1 * -2^63 = 2^63
+    execute(&mut env, encode_rtype(0b0110011, 0b001, 0b0000001, 1, 2, 3));
+    assert_eq!(env.registers[1], 0);
+    // mulhu x5, x2, x3: (2^64 - 1) * 2^63 >> 64 = 2^63 - 1
+    execute(&mut env, encode_rtype(0b0110011, 0b011, 0b0000001, 5, 2, 3));
+    assert_eq!(env.registers[5], (1 << 63) - 1);
+    // mulhsu x6, x2, x3: -1 * 2^63 >> 64 = -1
+    execute(&mut env, encode_rtype(0b0110011, 0b010, 0b0000001, 6, 2, 3));
+    assert_eq!(env.registers[6], u64::MAX);
+    // div x7, x3, x2: overflow returns the dividend
+    execute(&mut env, encode_rtype(0b0110011, 0b100, 0b0000001, 7, 3, 2));
+    assert_eq!(env.registers[7], 1 << 63);
+    // divu x8, x3, x4: division by zero returns 2^64 - 1
+    execute(&mut env, encode_rtype(0b0110011, 0b101, 0b0000001, 8, 3, 4));
+    assert_eq!(env.registers[8], u64::MAX);
+    // remu x9, x3, x4: the remainder of the division by zero is the dividend
+    execute(&mut env, encode_rtype(0b0110011, 0b111, 0b0000001, 9, 3, 4));
+    assert_eq!(env.registers[9], 1 << 63);
+    // divw x10, x2, x3: x3 is 0 on 32 bits
+    execute(
+        &mut env,
+        encode_rtype(0b0111011, 0b100, 0b0000001, 10, 2, 3),
+    );
+    assert_eq!(env.registers[10], u64::MAX);
+    // mulw x11, x2, x2: 0xFFFFFFFF * 0xFFFFFFFF has 1 as lower 32 bits
+    execute(
+        &mut env,
+        encode_rtype(0b0111011, 0b000, 0b0000001, 11, 2, 2),
+    );
+    assert_eq!(env.registers[11], 1);
+    // divuw x12, x2, x11: 0xFFFFFFFF / 1, sign-extended
+    execute(
+        &mut env,
+        encode_rtype(0b0111011, 0b101, 0b0000001, 12, 2, 11),
+    );
+    assert_eq!(env.registers[12], u64::MAX);
+    // remw x13, x2, x2
+    execute(
+        &mut env,
+        encode_rtype(0b0111011, 0b110, 0b0000001, 13, 2, 2),
+    );
+    assert_eq!(env.registers[13], 0);
+}
+
+// The values are stored in memory as in RV32IM, the most significant byte
+// first
+#[test]
+fn test_memory_byte_order() {
+    let mut env = dummy_env();
+    env.registers[2] = 0x800;
+    env.registers[3] = 0x8877_6655_4433_2211;
+    // sd x3, 8(x2)
+    execute(&mut env, encode_stype(0b011, 2, 3, 8));
+    assert_eq!(
+        env.memory.page(0).unwrap()[0x808..0x810],
+        [0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11]
+    );
+    // ld x4, 8(x2)
+    execute(&mut env, encode_itype(0b0000011, 0b011, 4, 2, 8));
+    assert_eq!(env.registers[4], 0x8877_6655_4433_2211);
+    // lw x5, 8(x2)
+    execute(&mut env, encode_itype(0b0000011, 0b010, 5, 2, 8));
+    assert_eq!(env.registers[5], 0xFFFF_FFFF_8877_6655);
+    // lwu x6, 8(x2)
+    execute(&mut env, encode_itype(0b0000011, 0b110, 6, 2, 8));
+    assert_eq!(env.registers[6], 0x8877_6655);
+    // lhu x7, 12(x2)
+    execute(&mut env, encode_itype(0b0000011, 0b101, 7, 2, 12));
+    assert_eq!(env.registers[7], 0x4433);
+    // lb x8, 8(x2)
+    execute(&mut env, encode_itype(0b0000011, 0b000, 8, 2, 8));
+    assert_eq!(env.registers[8], 0xFFFF_FFFF_FFFF_FF88);
+    // sw x3, -8(x2)
+    execute(&mut env, encode_stype(0b010, 2, 3, -8));
+    assert_eq!(
+        env.memory.page(0).unwrap()[0x7F8..0x800],
+        [0x44, 0x33, 0x22, 0x11, 0, 0, 0, 0]
+    );
+}
+
+#[test]
+fn test_control_flow() {
+    let mut env = dummy_env();
+    env.registers[2] = u64::MAX;
+    env.registers[3] = 1;
+    // bltu x3, x2, 16: taken
+    let instruction = execute(&mut env, encode_sbtype(0b110, 3, 2, 16));
+    assert_eq!(
+        instruction,
+        Instruction::SBType(SBInstruction::BranchLessThanUnsigned)
+    );
+    assert_eq!(env.registers.current_instruction_pointer, 16);
+    assert_eq!(env.registers.next_instruction_pointer, 20);
+    // blt x3, x2, 16: not taken, as x2 is -1
+    execute(&mut env, encode_sbtype(0b100, 3, 2, 16));
+    assert_eq!(env.registers.current_instruction_pointer, 20);
+    assert_eq!(env.registers.next_instruction_pointer, 24);
+    // bne x3, x2, -20: taken
+    execute(&mut env, encode_sbtype(0b001, 3, 2, -20));
+    assert_eq!(env.registers.current_instruction_pointer, 0);
+    // jalr x1, 41(x0): the least significant bit of the target is cleared
+    execute(&mut env, encode_itype(0b1100111, 0b000, 1, 0, 41));
+    assert_eq!(env.registers[1], 4);
+    assert_eq!(env.registers.current_instruction_pointer, 40);
+    assert_eq!(env.registers.next_instruction_pointer, 44);
+    // lui x4, 0x80000: the immediate is sign-extended
+    execute(&mut env, 0b0110111 | (4 << 7) | (0x80000 << 12));
+    assert_eq!(env.registers[4], 0xFFFF_FFFF_8000_0000);
+    // auipc x5, 0x1
+    execute(&mut env, 0b0010111 | (5 << 7) | (0x1 << 12));
+    assert_eq!(env.registers[5], 44 + 0x1000);
+}
+
+#[test]
+fn test_instruction_can_be_converted_into_string() {
+    let instruction = Instruction::RWType(RWInstruction::AddWord);
+    assert_eq!(instruction.to_string(), "addw");
+
+    let instruction = Instruction::LSType(LSInstruction::LoadDoubleWord);
+    assert_eq!(instruction.to_string(), "ld");
+
+    let instruction = Instruction::LSType(LSInstruction::StoreDoubleWord);
+    assert_eq!(instruction.to_string(), "sd");
+
+    let instruction = Instruction::MWType(MWInstruction::RemuWord);
+    assert_eq!(instruction.to_string(), "remuw");
+}
+
+#[test]
+pub fn test_witness_bitmask_bounds() {
+    let mut env: Env<Fp> = dummy_env();
+    let input = 0x8000_0000_0000_0001;
+    let output = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&input, 64, 63, pos) }
+    };
+    assert_eq!(output, 1);
+    let output = {
+        let pos = env.alloc_scratch();
+        unsafe { env.bitmask(&input, 64, 0, pos) }
+    };
+    assert_eq!(output, input);
+}
+
+#[test]
+fn test_fences_are_no_ops() {
+    let mut env = dummy_env();
+    let registers = env.registers.clone();
+    // fence rw, rw
+    let instruction = execute(&mut env, 0x0330000f);
+    assert_eq!(instruction, Instruction::RType(RInstruction::Fence));
+    // fence.i
+    let instruction = execute(&mut env, 0x0000100f);
+    assert_eq!(instruction, Instruction::RType(RInstruction::FenceI));
+    assert_eq!(env.registers.general_purpose, registers.general_purpose);
+    assert_eq!(
+        env.registers.current_instruction_pointer,
+        registers.current_instruction_pointer + 8
+    );
+}
+
+#[test]
+fn test_xlen_semantics() {
+    // -2^(XLEN - 1) / -1 overflows, and its remainder is zero
+    assert_eq!(Rv32::div_signed(1 << 31, u32::MAX as u64), 1 << 31);
+    assert_eq!(Rv64::div_signed(1 << 63, u64::MAX), 1 << 63);
+    assert_eq!(Rv32::rem_signed(1 << 31, u32::MAX as u64), 0);
+    assert_eq!(Rv64::rem_signed(1 << 63, u64::MAX), 0);
+    // The division by zero
+    assert_eq!(Rv32::div_signed(7, 0), u32::MAX as u64);
+    assert_eq!(Rv32::div(7, 0), u32::MAX as u64);
+    assert_eq!(Rv64::rem_signed(u64::MAX, 0), u64::MAX);
+    // The signed operands are read on XLEN bits
+    assert_eq!(Rv32::mul_hi_signed(u32::MAX as u64, u32::MAX as u64), 0);
+    assert_eq!(Rv32::mul_hi(u32::MAX as u64, u32::MAX as u64), 0xFFFF_FFFE);
+    assert_eq!(Rv32::less_than_signed(1 << 31, 0), 1);
+    assert_eq!(Rv64::less_than_signed(1 << 31, 0), 0);
+    assert_eq!(Rv32::shift_right_arithmetic(1 << 31, 31), u32::MAX as u64);
+    assert_eq!(Rv64::shift_right_arithmetic(1 << 31, 31), 1);
+    assert_eq!(Rv32::overflowing_add(u32::MAX as u64, 1), (0, 1));
+    assert_eq!(Rv64::overflowing_add(u32::MAX as u64, 1), (1 << 32, 0));
+    assert_eq!(Rv32::count_leading_zeros(1), 31);
+    assert_eq!(Rv64::count_leading_ones(u64::MAX << 1), 63);
+}
+
+#[test]
+#[should_panic(expected = "does not fit in 32 bits")]
+fn test_xlen_rejects_wider_values() {
+    Rv32::and(1 << 32, 1);
+}
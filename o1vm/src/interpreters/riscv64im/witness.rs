@@ -0,0 +1,851 @@
+// TODO: do we want to be more restrictive and refer to the number of accesses
+//       to the SAME register/memory address?
+use super::{
+    column::Column,
+    interpreter::{
+        self, IWInstruction, Instruction, InterpreterEnv, LSInstruction, MWInstruction,
+        RWInstruction, SyscallInstruction,
+    },
+    registers::Registers,
+    INSTRUCTION_SET_SIZE, PAGE_SIZE, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE,
+};
+use crate::{
+    cannon::State,
+    interpreters::{
+        keccak::precompile::KeccakHint,
+        poseidon::precompile::PoseidonHint,
+        riscv32im::{
+            interpreter::Instruction as BaseInstruction, witness::decode_base_instruction,
+        },
+        xlen::{Rv64, Xlen},
+    },
+    lookups::Lookup,
+    memory::SparseMemory,
+};
+use ark_ff::{Field, PrimeField};
+use log::debug;
+use std::array;
+
+/// Maximum number of register accesses per instruction (based on demo)
+// FIXME: can be different
+pub const MAX_NB_REG_ACC: u64 = 7;
+/// Maximum number of memory accesses per instruction (based on demo)
+// FIXME: can be different
+pub const MAX_NB_MEM_ACC: u64 = 12;
+/// Maximum number of memory or register accesses per instruction
+pub const MAX_ACC: u64 = MAX_NB_REG_ACC + MAX_NB_MEM_ACC;
+
+pub const NUM_GLOBAL_LOOKUP_TERMS: usize = 1;
+pub const NUM_DECODING_LOOKUP_TERMS: usize = 2;
+pub const NUM_INSTRUCTION_LOOKUP_TERMS: usize = 5;
+pub const NUM_LOOKUP_TERMS: usize =
+    NUM_GLOBAL_LOOKUP_TERMS + NUM_DECODING_LOOKUP_TERMS + NUM_INSTRUCTION_LOOKUP_TERMS;
+
+/// The system calls of RV32IM other than the halting one are not interpreted
+/// by RV64IM, see [interpreter::interpret_syscall]
+const UNSUPPORTED_SYSCALL: &str = "RV64IM only supports the halting system call";
+
+/// This structure represents the environment the virtual machine state will use
+/// to transition. This environment will be used by the interpreter. The virtual
+/// machine has access to its internal state and some external memory. In
+/// addition to that, it has access to the environment of the Keccak interpreter
+/// that is used to verify the preimage requested during the execution.
+pub struct Env<Fp> {
+    pub instruction_counter: u64,
+    pub memory: SparseMemory<u64, u8>,
+    pub memory_write_index: SparseMemory<u64, u64>,
+    pub registers: Registers<u64>,
+    pub registers_write_index: Registers<u64>,
+    pub scratch_state_idx: usize,
+    pub scratch_state: [Fp; SCRATCH_SIZE],
+    pub scratch_state_inverse_idx: usize,
+    pub scratch_state_inverse: [Fp; SCRATCH_SIZE_INVERSE],
+    pub halt: bool,
+    pub selector: usize,
+}
+
+fn fresh_scratch_state<Fp: Field, const N: usize>() -> [Fp; N] {
+    array::from_fn(|_| Fp::zero())
+}
+
+impl<Fp: PrimeField> InterpreterEnv for Env<Fp> {
+    type Xlen = Rv64;
+
+    type Position = Column;
+
+    fn alloc_scratch(&mut self) -> Self::Position {
+        let scratch_idx = self.scratch_state_idx;
+        self.scratch_state_idx += 1;
+        Column::ScratchState(scratch_idx)
+    }
+
+    fn alloc_scratch_inverse(&mut self) -> Self::Position {
+        let scratch_inverse_idx = self.scratch_state_inverse_idx;
+        self.scratch_state_inverse_idx += 1;
+        Column::ScratchStateInverse(scratch_inverse_idx)
+    }
+
+    type Variable = u64;
+
+    fn variable(&self, column: Self::Position) -> Self::Variable {
+        match column {
+            Column::ScratchState(idx) => {
+                let value = self.scratch_state[idx].into_bigint();
+                let limbs = value.as_ref();
+                assert!(
+                    limbs[1..].iter().all(|limb| *limb == 0),
+                    "The scratch column {idx} does not hold a 64 bits value"
+                );
+                limbs[0]
+            }
+            Column::ScratchStateInverse(idx) => {
+                panic!("The inverse column {idx} holds a field element, not a 64 bits value")
+            }
+            Column::InstructionCounter => self.instruction_counter,
+            Column::Selector(s) => (self.selector == s) as u64,
+        }
+    }
+
+    fn add_constraint(&mut self, _assert_equals_zero: Self::Variable) {
+        // No-op for witness
+        // Do not assert that _assert_equals_zero is zero here!
+        // Some variables may have placeholders that do not faithfully
+        // represent the underlying values.
+    }
+
+    fn activate_selector(&mut self, instruction: Instruction) {
+        self.selector = instruction.into();
+    }
+
+    fn check_is_zero(assert_equals_zero: &Self::Variable) {
+        assert_eq!(*assert_equals_zero, 0);
+    }
+
+    fn check_equal(x: &Self::Variable, y: &Self::Variable) {
+        assert_eq!(*x, *y);
+    }
+
+    fn assert_boolean(&mut self, x: &Self::Variable) {
+        if *x != 0 && *x != 1 {
+            panic!("The value {} is not a boolean", *x);
+        }
+    }
+
+    fn add_lookup(&mut self, _lookup: Lookup<Self::Variable>) {
+        // No-op, constraints only
+        // TODO: keep track of multiplicities of fixed tables here as in Keccak?
+    }
+
+    fn instruction_counter(&self) -> Self::Variable {
+        self.instruction_counter
+    }
+
+    fn increase_instruction_counter(&mut self) {
+        self.instruction_counter += 1;
+    }
+
+    unsafe fn fetch_register(
+        &mut self,
+        idx: &Self::Variable,
+        output: Self::Position,
+    ) -> Self::Variable {
+        let res = self.registers[*idx as usize];
+        self.write_column(output, res);
+        res
+    }
+
+    unsafe fn push_register_if(
+        &mut self,
+        idx: &Self::Variable,
+        value: Self::Variable,
+        if_is_true: &Self::Variable,
+    ) {
+        if *if_is_true == 1 {
+            self.registers[*idx as usize] = value
+        } else if *if_is_true == 0 {
+            // No-op
+        } else {
+            panic!("Bad value for flag in push_register: {}", *if_is_true);
+        }
+    }
+
+    unsafe fn fetch_register_access(
+        &mut self,
+        idx: &Self::Variable,
+        output: Self::Position,
+    ) -> Self::Variable {
+        let res = self.registers_write_index[*idx as usize];
+        self.write_column(output, res);
+        res
+    }
+
+    unsafe fn push_register_access_if(
+        &mut self,
+        idx: &Self::Variable,
+        value: Self::Variable,
+        if_is_true: &Self::Variable,
+    ) {
+        if *if_is_true == 1 {
+            self.registers_write_index[*idx as usize] = value
+        } else if *if_is_true == 0 {
+            // No-op
+        } else {
+            panic!("Bad value for flag in push_register: {}", *if_is_true);
+        }
+    }
+
+    unsafe fn fetch_memory(
+        &mut self,
+        addr: &Self::Variable,
+        output: Self::Position,
+    ) -> Self::Variable {
+        let value = self.memory.get(*addr);
+        self.write_column(output, value.into());
+        value.into()
+    }
+
+    unsafe fn push_memory(&mut self, addr: &Self::Variable, value: Self::Variable) {
+        self.memory.set(
+            *addr,
+            value.try_into().expect("push_memory values fit in a u8"),
+        );
+    }
+
+    unsafe fn fetch_memory_access(
+        &mut self,
+        addr: &Self::Variable,
+        output: Self::Position,
+    ) -> Self::Variable {
+        let value = self.memory_write_index.get(*addr);
+        self.write_column(output, value);
+        value
+    }
+
+    unsafe fn push_memory_access(&mut self, addr: &Self::Variable, value: Self::Variable) {
+        self.memory_write_index.set(*addr, value);
+    }
+
+    fn authenticate_memory_access(
+        &mut self,
+        _addr: &Self::Variable,
+        _old_value: &Self::Variable,
+        _new_value: &Self::Variable,
+    ) {
+        // No-op, the memory is not Merkleized
+    }
+
+    fn constant(x: u64) -> Self::Variable {
+        x
+    }
+
+    unsafe fn bitmask(
+        &mut self,
+        x: &Self::Variable,
+        highest_bit: u32,
+        lowest_bit: u32,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = Rv64::bitmask(*x, highest_bit, lowest_bit);
+        self.write_column(position, res);
+        res
+    }
+
+    unsafe fn shift_left(
+        &mut self,
+        x: &Self::Variable,
+        by: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = Rv64::shift_left(*x, *by);
+        self.write_column(position, res);
+        res
+    }
+
+    unsafe fn shift_right(
+        &mut self,
+        x: &Self::Variable,
+        by: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = Rv64::shift_right(*x, *by);
+        self.write_column(position, res);
+        res
+    }
+
+    unsafe fn shift_right_arithmetic(
+        &mut self,
+        x: &Self::Variable,
+        by: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = Rv64::shift_right_arithmetic(*x, *by);
+        self.write_column(position, res);
+        res
+    }
+
+    unsafe fn test_zero(&mut self, x: &Self::Variable, position: Self::Position) -> Self::Variable {
+        let res = if *x == 0 { 1 } else { 0 };
+        self.write_column(position, res);
+        res
+    }
+
+    fn is_zero(&mut self, x: &Self::Variable) -> Self::Variable {
+        // write the result
+        let pos = self.alloc_scratch();
+        let res = if *x == 0 { 1 } else { 0 };
+        self.write_column(pos, res);
+        // write the non deterministic advice inv_or_zero
+        let pos = self.alloc_scratch_inverse();
+        if *x == 0 {
+            self.write_field_column(pos, Fp::zero());
+        } else {
+            self.write_field_column(pos, Fp::from(*x));
+        };
+        // return the result
+        res
+    }
+
+    fn equal(&mut self, x: &Self::Variable, y: &Self::Variable) -> Self::Variable {
+        // We replicate is_zero(x-y), but working on field elt,
+        // to avoid subtraction overflow in the witness interpreter for u64
+        let to_zero_test = Fp::from(*x) - Fp::from(*y);
+        let res = {
+            let pos = self.alloc_scratch();
+            let is_zero: u64 = if to_zero_test == Fp::zero() { 1 } else { 0 };
+            self.write_column(pos, is_zero);
+            is_zero
+        };
+        let pos = self.alloc_scratch_inverse();
+        if to_zero_test == Fp::zero() {
+            self.write_field_column(pos, Fp::zero());
+        } else {
+            self.write_field_column(pos, to_zero_test);
+        };
+        res
+    }
+
+    unsafe fn test_less_than(
+        &mut self,
+        x: &Self::Variable,
+        y: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = Rv64::less_than(*x, *y);
+        self.write_column(position, res);
+        res
+    }
+
+    unsafe fn test_less_than_signed(
+        &mut self,
+        x: &Self::Variable,
+        y: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = Rv64::less_than_signed(*x, *y);
+        self.write_column(position, res);
+        res
+    }
+
+    unsafe fn and_witness(
+        &mut self,
+        x: &Self::Variable,
+        y: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = Rv64::and(*x, *y);
+        self.write_column(position, res);
+        res
+    }
+
+    unsafe fn nor_witness(
+        &mut self,
+        x: &Self::Variable,
+        y: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = Rv64::nor(*x, *y);
+        self.write_column(position, res);
+        res
+    }
+
+    unsafe fn or_witness(
+        &mut self,
+        x: &Self::Variable,
+        y: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = Rv64::or(*x, *y);
+        self.write_column(position, res);
+        res
+    }
+
+    unsafe fn xor_witness(
+        &mut self,
+        x: &Self::Variable,
+        y: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = Rv64::xor(*x, *y);
+        self.write_column(position, res);
+        res
+    }
+
+    unsafe fn add_witness(
+        &mut self,
+        x: &Self::Variable,
+        y: &Self::Variable,
+        out_position: Self::Position,
+        overflow_position: Self::Position,
+    ) -> (Self::Variable, Self::Variable) {
+        let (res, overflow) = Rv64::overflowing_add(*x, *y);
+        self.write_column(out_position, res);
+        self.write_column(overflow_position, overflow);
+        (res, overflow)
+    }
+
+    unsafe fn sub_witness(
+        &mut self,
+        x: &Self::Variable,
+        y: &Self::Variable,
+        out_position: Self::Position,
+        underflow_position: Self::Position,
+    ) -> (Self::Variable, Self::Variable) {
+        let (res, underflow) = Rv64::overflowing_sub(*x, *y);
+        self.write_column(out_position, res);
+        self.write_column(underflow_position, underflow);
+        (res, underflow)
+    }
+
+    unsafe fn mul_signed_witness(
+        &mut self,
+        x: &Self::Variable,
+        y: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = Rv64::mul_lo(*x, *y);
+        self.write_column(position, res);
+        res
+    }
+
+    unsafe fn mul_hi_signed(
+        &mut self,
+        x: &Self::Variable,
+        y: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = Rv64::mul_hi_signed(*x, *y);
+        self.write_column(position, res);
+        res
+    }
+
+    unsafe fn mul_lo_signed(
+        &mut self,
+        x: &Self::Variable,
+        y: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = Rv64::mul_lo(*x, *y);
+        self.write_column(position, res);
+        res
+    }
+
+    unsafe fn mul_hi(
+        &mut self,
+        x: &Self::Variable,
+        y: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = Rv64::mul_hi(*x, *y);
+        self.write_column(position, res);
+        res
+    }
+
+    unsafe fn mul_hi_signed_unsigned(
+        &mut self,
+        x: &Self::Variable,
+        y: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = Rv64::mul_hi_signed_unsigned(*x, *y);
+        self.write_column(position, res);
+        res
+    }
+
+    unsafe fn div_signed(
+        &mut self,
+        x: &Self::Variable,
+        y: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = Rv64::div_signed(*x, *y);
+        self.write_column(position, res);
+        res
+    }
+
+    unsafe fn mul_lo(
+        &mut self,
+        x: &Self::Variable,
+        y: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = Rv64::mul_lo(*x, *y);
+        self.write_column(position, res);
+        res
+    }
+
+    unsafe fn mod_signed(
+        &mut self,
+        x: &Self::Variable,
+        y: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = Rv64::rem_signed(*x, *y);
+        self.write_column(position, res);
+        res
+    }
+
+    unsafe fn div(
+        &mut self,
+        x: &Self::Variable,
+        y: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = Rv64::div(*x, *y);
+        self.write_column(position, res);
+        res
+    }
+
+    unsafe fn mod_unsigned(
+        &mut self,
+        x: &Self::Variable,
+        y: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = Rv64::rem(*x, *y);
+        self.write_column(position, res);
+        res
+    }
+
+    unsafe fn count_leading_zeros(
+        &mut self,
+        x: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = Rv64::count_leading_zeros(*x);
+        self.write_column(position, res);
+        res
+    }
+
+    unsafe fn count_leading_ones(
+        &mut self,
+        x: &Self::Variable,
+        position: Self::Position,
+    ) -> Self::Variable {
+        let res = Rv64::count_leading_ones(*x);
+        self.write_column(position, res);
+        res
+    }
+
+    fn copy(&mut self, x: &Self::Variable, position: Self::Position) -> Self::Variable {
+        self.write_column(position, *x);
+        *x
+    }
+
+    fn set_halted(&mut self, flag: Self::Variable) {
+        if flag == 0 {
+            self.halt = false
+        } else if flag == 1 {
+            self.halt = true
+        } else {
+            panic!("Bad value for flag in set_halted: {}", flag);
+        }
+    }
+
+    fn report_exit(&mut self, exit_code: &Self::Variable) {
+        debug!(
+            "Exited with code {} at step {}",
+            *exit_code,
+            self.normalized_instruction_counter()
+        );
+    }
+
+    unsafe fn random_byte(
+        &mut self,
+        _if_is_true: &Self::Variable,
+        _position: Self::Position,
+    ) -> Self::Variable {
+        unreachable!("{UNSUPPORTED_SYSCALL}")
+    }
+
+    unsafe fn executed_instructions(
+        &mut self,
+        _upper_half: bool,
+        _position: Self::Position,
+    ) -> Self::Variable {
+        unreachable!("{UNSUPPORTED_SYSCALL}")
+    }
+
+    fn request_preimage_write(
+        &mut self,
+        _addr: &Self::Variable,
+        _len: &Self::Variable,
+        _pos: Self::Position,
+    ) -> Self::Variable {
+        unreachable!("{UNSUPPORTED_SYSCALL}")
+    }
+
+    fn request_hint_write(&mut self, _addr: &Self::Variable, _len: &Self::Variable) {
+        unreachable!("{UNSUPPORTED_SYSCALL}")
+    }
+
+    fn request_stdin_write(
+        &mut self,
+        _addr: &Self::Variable,
+        _len: &Self::Variable,
+        _pos: Self::Position,
+    ) -> Self::Variable {
+        unreachable!("{UNSUPPORTED_SYSCALL}")
+    }
+
+    fn request_output_write(
+        &mut self,
+        _fd: &Self::Variable,
+        _addr: &Self::Variable,
+        _len: &Self::Variable,
+    ) {
+        unreachable!("{UNSUPPORTED_SYSCALL}")
+    }
+
+    fn request_advice_write(
+        &mut self,
+        _addr: &Self::Variable,
+        _len: &Self::Variable,
+        _pos: Self::Position,
+    ) -> Self::Variable {
+        unreachable!("{UNSUPPORTED_SYSCALL}")
+    }
+
+    unsafe fn request_keccak(
+        &mut self,
+        _addr: &Self::Variable,
+        _len: &Self::Variable,
+        _output: &Self::Variable,
+        _pos: Self::Position,
+    ) -> Self::Variable {
+        unreachable!("{UNSUPPORTED_SYSCALL}")
+    }
+
+    unsafe fn keccak_hint(&mut self, _hint: KeccakHint, _pos: Self::Position) -> Self::Variable {
+        unreachable!("{UNSUPPORTED_SYSCALL}")
+    }
+
+    unsafe fn request_poseidon(
+        &mut self,
+        _addr: &Self::Variable,
+        _pos: Self::Position,
+    ) -> Self::Variable {
+        unreachable!("{UNSUPPORTED_SYSCALL}")
+    }
+
+    unsafe fn poseidon_hint(
+        &mut self,
+        _hint: PoseidonHint,
+        _pos: Self::Position,
+    ) -> Self::Variable {
+        unreachable!("{UNSUPPORTED_SYSCALL}")
+    }
+
+    unsafe fn request_precompile(
+        &mut self,
+        _syscall: &Self::Variable,
+        _input: &Self::Variable,
+        _output: &Self::Variable,
+        _pos: Self::Position,
+    ) -> Self::Variable {
+        unreachable!("{UNSUPPORTED_SYSCALL}")
+    }
+
+    fn reset(&mut self) {
+        self.scratch_state_idx = 0;
+        self.scratch_state = fresh_scratch_state();
+        self.selector = INSTRUCTION_SET_SIZE;
+    }
+}
+
+impl<Fp: PrimeField> Env<Fp> {
+    /// Create the environment from the state of a program. The state
+    /// describes the program with 32 bits addresses and registers, which are
+    /// extended to 64 bits.
+    pub fn create(page_size: usize, state: State) -> Self {
+        let initial_instruction_pointer = state.pc;
+        let next_instruction_pointer = state.next_pc;
+
+        let selector = INSTRUCTION_SET_SIZE;
+
+        assert_eq!(page_size, PAGE_SIZE as usize);
+        let initial_memory: SparseMemory<u64, u8> = state
+            .memory
+            .into_iter()
+            .map(|page| (page.index.into(), page.data))
+            .collect();
+
+        let initial_registers = {
+            Registers {
+                general_purpose: state.registers.map(u64::from),
+                current_instruction_pointer: initial_instruction_pointer.into(),
+                next_instruction_pointer: next_instruction_pointer.into(),
+                heap_pointer: state.heap.into(),
+                program_break: 0,
+                preimage_key: [0; 8],
+                preimage_offset: 0,
+                csrs: Default::default(),
+            }
+        };
+
+        let mut registers = initial_registers.clone();
+        registers[2] = 0x408004f0;
+        // set the stack pointer to the top of the stack
+
+        Env {
+            instruction_counter: state.step,
+            memory: initial_memory,
+            memory_write_index: SparseMemory::new(),
+            registers,
+            registers_write_index: Registers::default(),
+            scratch_state_idx: 0,
+            scratch_state: fresh_scratch_state(),
+            scratch_state_inverse_idx: 0,
+            scratch_state_inverse: fresh_scratch_state(),
+            halt: state.exited,
+            selector,
+        }
+    }
+
+    pub fn next_instruction_counter(&self) -> u64 {
+        (self.normalized_instruction_counter() + 1) * MAX_ACC
+    }
+
+    pub fn decode_instruction(&mut self) -> (Instruction, u32) {
+        /* https://www.cs.cornell.edu/courses/cs3410/2024fa/assignments/cpusim/riscv-instructions.pdf */
+        let instruction_pointer = self.registers.current_instruction_pointer;
+        let instruction = u32::from_le_bytes(array::from_fn(|i| {
+            self.get_memory_direct(instruction_pointer + i as u64)
+        }));
+        let opcode = {
+            match instruction & 0b1111111 // bits 0-6
+            {
+                0b0000011 if (instruction >> 12) & 0x7 == 0b110 => Instruction::LSType(LSInstruction::LoadWordUnsigned),
+                0b0000011 if (instruction >> 12) & 0x7 == 0b011 => Instruction::LSType(LSInstruction::LoadDoubleWord),
+                0b0100011 if (instruction >> 12) & 0x7 == 0b011 => Instruction::LSType(LSInstruction::StoreDoubleWord),
+                0b0011011 => {
+                    // The shift amount of the word shifts is encoded on 5
+                    // bits, and the bits above must be zero, except bit 30
+                    // which selects the arithmetic shift
+                    let funct7 = instruction >> 25; // bits 25-31 for funct7
+                    match (instruction >> 12) & 0x7 // bits 12-14 for func3
+                    {
+                        0b000 => Instruction::IWType(IWInstruction::AddImmediateWord),
+                        0b001 if funct7 == 0 => Instruction::IWType(IWInstruction::ShiftLeftLogicalImmediateWord),
+                        0b101 if funct7 == 0b0000000 => Instruction::IWType(IWInstruction::ShiftRightLogicalImmediateWord),
+                        0b101 if funct7 == 0b0100000 => Instruction::IWType(IWInstruction::ShiftRightArithmeticImmediateWord),
+                        _ => panic!("Unknown IType 0011011 instruction with full inst {}", instruction),
+                    }
+                },
+                0b0111011 => {
+                    let funct5 = instruction >> 27 & 0x1F; // bits 27-31 for funct5
+                    let funct2 = instruction >> 25 & 0x3; // bits 25-26 for func2
+                    let funct3 = instruction >> 12 & 0x7; // bits 12-14 for func3
+                    match (funct5, funct2, funct3) {
+                        // These are the word instructions for the base integer set
+                        (0b00000, 0b00, 0b000) => Instruction::RWType(RWInstruction::AddWord),
+                        (0b00000, 0b00, 0b001) => Instruction::RWType(RWInstruction::ShiftLeftLogicalWord),
+                        (0b00000, 0b00, 0b101) => Instruction::RWType(RWInstruction::ShiftRightLogicalWord),
+                        (0b01000, 0b00, 0b000) => Instruction::RWType(RWInstruction::SubWord),
+                        (0b01000, 0b00, 0b101) => Instruction::RWType(RWInstruction::ShiftRightArithmeticWord),
+                        // These are the word instructions for the M type
+                        (0b00000, 0b01, 0b000) => Instruction::MWType(MWInstruction::MulWord),
+                        (0b00000, 0b01, 0b100) => Instruction::MWType(MWInstruction::DivWord),
+                        (0b00000, 0b01, 0b101) => Instruction::MWType(MWInstruction::DivuWord),
+                        (0b00000, 0b01, 0b110) => Instruction::MWType(MWInstruction::RemWord),
+                        (0b00000, 0b01, 0b111) => Instruction::MWType(MWInstruction::RemuWord),
+                        _ => panic!("Unknown opcode 0111011 instruction with full inst {} (funct5 = {}, funct2 = {}, funct3 = {})", instruction, funct5, funct2, funct3),
+                    }
+                }
+                // FIXME: we should implement more syscalls here, and check the register state.
+                // Even better, only one constructor call ecall, and in the
+                // interpreter, we do the action depending on it
+                0b1110011 => Instruction::SyscallType(SyscallInstruction::SyscallSuccess),
+                // The instructions shared with RV32IM
+                _ => match decode_base_instruction::<Rv64>(instruction) {
+                    BaseInstruction::RType(rtype) => Instruction::RType(rtype),
+                    BaseInstruction::IType(itype) => Instruction::IType(itype),
+                    BaseInstruction::SType(stype) => Instruction::SType(stype),
+                    BaseInstruction::SBType(sbtype) => Instruction::SBType(sbtype),
+                    BaseInstruction::UType(utype) => Instruction::UType(utype),
+                    BaseInstruction::UJType(ujtype) => Instruction::UJType(ujtype),
+                    BaseInstruction::MType(mtype) => Instruction::MType(mtype),
+                    instr => unreachable!("The instruction {instr} is not in the base instruction set"),
+                },
+            }
+        };
+        (opcode, instruction)
+    }
+
+    /// Execute a single step in the RISCV64i program
+    pub fn step(&mut self) -> Instruction {
+        self.reset_scratch_state();
+        self.reset_scratch_state_inverse();
+        let (opcode, _instruction) = self.decode_instruction();
+
+        interpreter::interpret_instruction(self, opcode);
+
+        self.instruction_counter = self.next_instruction_counter();
+
+        // Integer division by MAX_ACC to obtain the actual instruction count
+        if self.halt {
+            debug!(
+                "Halted at step={} instruction={:?}",
+                self.normalized_instruction_counter(),
+                opcode
+            );
+        }
+        opcode
+    }
+
+    pub fn reset_scratch_state(&mut self) {
+        self.scratch_state_idx = 0;
+        self.scratch_state = fresh_scratch_state();
+        self.selector = INSTRUCTION_SET_SIZE;
+    }
+
+    pub fn reset_scratch_state_inverse(&mut self) {
+        self.scratch_state_inverse_idx = 0;
+        self.scratch_state_inverse = fresh_scratch_state();
+    }
+
+    pub fn write_column(&mut self, column: Column, value: u64) {
+        self.write_field_column(column, value.into())
+    }
+
+    pub fn write_field_column(&mut self, column: Column, value: Fp) {
+        match column {
+            Column::ScratchState(idx) => self.scratch_state[idx] = value,
+            Column::ScratchStateInverse(idx) => self.scratch_state_inverse[idx] = value,
+            Column::InstructionCounter => panic!("Cannot overwrite the column {:?}", column),
+            Column::Selector(s) => self.selector = s,
+        }
+    }
+
+    pub fn get_memory_direct(&self, addr: u64) -> u8 {
+        self.memory.get(addr)
+    }
+
+    /// The actual number of instructions executed results from dividing the
+    /// instruction counter by MAX_ACC (floor).
+    ///
+    /// NOTE: actually, in practice it will be less than that, as there is no
+    ///       single instruction that performs all of them.
+    pub fn normalized_instruction_counter(&self) -> u64 {
+        self.instruction_counter / MAX_ACC
+    }
+}
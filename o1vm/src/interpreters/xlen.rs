@@ -0,0 +1,184 @@
+//! The semantics of the RISC-V operations whose result depends on the width
+//! XLEN of the registers, shared by the witness environments of
+//! [super::riscv32im] and [super::riscv64im].
+//!
+//! The values are given as `u64` holding XLEN bits, and are interpreted in
+//! two's complement by the signed operations. The results are XLEN bits
+//! values, following the specification for the corner cases of the division
+//! and of the remainder.
+
+/// The width of the registers of a RISC-V base ISA
+pub trait Xlen {
+    /// XLEN, in bits
+    const BITS: u32;
+
+    /// The mask of the XLEN lowest bits
+    const MASK: u64 = u64::MAX >> (64 - Self::BITS);
+
+    /// Checks that `x` holds XLEN bits
+    fn check(x: u64) -> u64 {
+        assert!(
+            x <= Self::MASK,
+            "The value {x:#x} does not fit in {} bits",
+            Self::BITS
+        );
+        x
+    }
+
+    /// Interprets `x` as a signed XLEN bits value
+    fn signed(x: u64) -> i64 {
+        let shift = 64 - Self::BITS;
+        ((Self::check(x) << shift) as i64) >> shift
+    }
+
+    /// Truncates `x` to its XLEN lowest bits
+    fn truncate(x: i128) -> u64 {
+        (x as u64) & Self::MASK
+    }
+
+    /// Returns the bits `lowest_bit` (included) to `highest_bit` (excluded)
+    /// of `x`
+    fn bitmask(x: u64, highest_bit: u32, lowest_bit: u32) -> u64 {
+        assert!(
+            lowest_bit < highest_bit,
+            "The lowest bit must be strictly lower than the highest bit"
+        );
+        assert!(
+            highest_bit <= Self::BITS,
+            "The interpreter is for a {} bits architecture",
+            Self::BITS
+        );
+        (Self::check(x) >> lowest_bit) & (u64::MAX >> (64 - (highest_bit - lowest_bit)))
+    }
+
+    fn shift_left(x: u64, by: u64) -> u64 {
+        assert!(by < Self::BITS as u64, "The shift must be lower than XLEN");
+        (Self::check(x) << by) & Self::MASK
+    }
+
+    fn shift_right(x: u64, by: u64) -> u64 {
+        assert!(by < Self::BITS as u64, "The shift must be lower than XLEN");
+        Self::check(x) >> by
+    }
+
+    fn shift_right_arithmetic(x: u64, by: u64) -> u64 {
+        assert!(by < Self::BITS as u64, "The shift must be lower than XLEN");
+        Self::truncate((Self::signed(x) >> by) as i128)
+    }
+
+    fn less_than(x: u64, y: u64) -> u64 {
+        (Self::check(x) < Self::check(y)) as u64
+    }
+
+    fn less_than_signed(x: u64, y: u64) -> u64 {
+        (Self::signed(x) < Self::signed(y)) as u64
+    }
+
+    fn and(x: u64, y: u64) -> u64 {
+        Self::check(x) & Self::check(y)
+    }
+
+    fn or(x: u64, y: u64) -> u64 {
+        Self::check(x) | Self::check(y)
+    }
+
+    fn xor(x: u64, y: u64) -> u64 {
+        Self::check(x) ^ Self::check(y)
+    }
+
+    fn nor(x: u64, y: u64) -> u64 {
+        !Self::or(x, y) & Self::MASK
+    }
+
+    /// Returns `x + y` and whether the addition overflowed
+    fn overflowing_add(x: u64, y: u64) -> (u64, u64) {
+        let res = Self::check(x) as u128 + Self::check(y) as u128;
+        (
+            Self::truncate(res as i128),
+            (res > Self::MASK as u128) as u64,
+        )
+    }
+
+    /// Returns `x - y` and whether the subtraction underflowed
+    fn overflowing_sub(x: u64, y: u64) -> (u64, u64) {
+        let res = Self::check(x).wrapping_sub(Self::check(y)) & Self::MASK;
+        (res, (x < y) as u64)
+    }
+
+    /// The lowest XLEN bits of `x * y`, which are the same for signed and
+    /// unsigned operands
+    fn mul_lo(x: u64, y: u64) -> u64 {
+        Self::check(x).wrapping_mul(Self::check(y)) & Self::MASK
+    }
+
+    fn mul_hi(x: u64, y: u64) -> u64 {
+        let res = Self::check(x) as u128 * Self::check(y) as u128;
+        ((res >> Self::BITS) as u64) & Self::MASK
+    }
+
+    fn mul_hi_signed(x: u64, y: u64) -> u64 {
+        let res = Self::signed(x) as i128 * Self::signed(y) as i128;
+        Self::truncate(res >> Self::BITS)
+    }
+
+    fn mul_hi_signed_unsigned(x: u64, y: u64) -> u64 {
+        let res = Self::signed(x) as i128 * Self::check(y) as i128;
+        Self::truncate(res >> Self::BITS)
+    }
+
+    /// The division by zero returns all ones
+    fn div(x: u64, y: u64) -> u64 {
+        Self::check(x)
+            .checked_div(Self::check(y))
+            .unwrap_or(Self::MASK)
+    }
+
+    /// The division by zero returns -1, and `-2^(XLEN - 1) / -1` overflows
+    /// to `-2^(XLEN - 1)`
+    fn div_signed(x: u64, y: u64) -> u64 {
+        let (x, y) = (Self::signed(x), Self::signed(y));
+        if y == 0 {
+            Self::MASK
+        } else {
+            Self::truncate(x as i128 / y as i128)
+        }
+    }
+
+    /// The remainder of the division by zero is the dividend
+    fn rem(x: u64, y: u64) -> u64 {
+        Self::check(x).checked_rem(Self::check(y)).unwrap_or(x)
+    }
+
+    /// The remainder of the division by zero is the dividend, and the one of
+    /// `-2^(XLEN - 1) / -1` is zero
+    fn rem_signed(x: u64, y: u64) -> u64 {
+        let (sx, sy) = (Self::signed(x), Self::signed(y));
+        if sy == 0 {
+            x
+        } else {
+            Self::truncate(sx as i128 % sy as i128)
+        }
+    }
+
+    fn count_leading_zeros(x: u64) -> u64 {
+        (Self::check(x).leading_zeros() - (64 - Self::BITS)) as u64
+    }
+
+    fn count_leading_ones(x: u64) -> u64 {
+        (Self::check(x) << (64 - Self::BITS)).leading_ones() as u64
+    }
+}
+
+/// The registers of RV32, of 32 bits
+pub struct Rv32;
+
+impl Xlen for Rv32 {
+    const BITS: u32 = 32;
+}
+
+/// The registers of RV64, of 64 bits
+pub struct Rv64;
+
+impl Xlen for Rv64 {
+    const BITS: u32 = 64;
+}
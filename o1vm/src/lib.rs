@@ -6,6 +6,9 @@ pub mod cli;
 /// A module to load ELF files.
 pub mod elf_loader;
 
+/// Debugging of the programs with gdb.
+pub mod gdb_stub;
+
 pub mod interpreters;
 
 /// Pickles flavor of the o1vm.
@@ -14,16 +17,30 @@ pub mod pickles;
 /// Instantiation of the lookups for the VM project.
 pub mod lookups;
 
+/// Sparse memory of the virtual machines.
+pub mod memory;
+
+/// Commitment to the memory as a Merkle tree.
+pub mod merkle_memory;
+
 /// Preimage oracle interface used by the zkVM.
 pub mod preimage_oracle;
 
+/// Profiling of the programs run by the zkVM.
+pub mod profiler;
+
 /// The RAM lookup argument.
 pub mod ramlookup;
 
 pub mod utils;
 
+#[cfg(not(target_arch = "wasm32"))]
 pub mod test_preimage_read;
 
+/// JavaScript bindings of the verifier.
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
 use crate::pickles::column_env::RelationColumnType;
 use kimchi::circuits::{
     berkeley_columns::BerkeleyChallengeTerm,
@@ -1,13 +1,13 @@
 //! Instantiation of the lookups for the VM project.
 
 use self::LookupTableIDs::*;
-use crate::{interpreters::keccak::pad_blocks, ramlookup::RAMLookup};
+use crate::{
+    interpreters::keccak::{pad_blocks, KeccakVariant},
+    ramlookup::RAMLookup,
+};
 use ark_ff::{Field, PrimeField};
 use kimchi::{
-    circuits::polynomials::keccak::{
-        constants::{RATE_IN_BYTES, ROUNDS},
-        Keccak, RC,
-    },
+    circuits::polynomials::keccak::{constants::ROUNDS, Keccak, RC},
     o1_utils::{FieldHelpers, Two},
 };
 use kimchi_msm::{LogupTable, LogupWitness, LookupTableID};
@@ -28,8 +28,10 @@ pub enum LookupTableIDs {
     // PadLookup ID is 0 because this is the only fixed table whose first entry
     // is not 0. This way, it is guaranteed that the 0 value is not always in
     // the tables after the randomization with the joint combiner is applied.
-    /// All [1..136] values of possible padding lengths, the value 2^len, and
-    /// the 5 corresponding pad suffixes with the 10*1 rule
+    /// For each of the paddings of the Keccak variants, all [1..rate] values
+    /// of possible padding lengths shifted by the offset of the padding in
+    /// the table, the value 2^len, and the 6 corresponding pad suffixes with
+    /// the domain separation bits and the 10*1 rule
     PadLookup = 0,
     /// 24-row table with all possible values for round and their round constant
     /// in expanded form (in big endian) [0..=23]
@@ -57,6 +59,12 @@ pub enum LookupTableIDs {
     SyscallLookup = 9,
     /// Input/Output of Keccak steps
     KeccakStepLookup = 10,
+    /// Memory accesses to authenticate against the Merkle root of the memory
+    MerkleMemoryLookup = 11,
+    /// Input/Output of the permutations of the Poseidon precompile
+    PoseidonLookup = 12,
+    /// Calls to the custom precompiles, see [crate::interpreters::precompile]
+    PrecompileLookup = 13,
 }
 
 impl LookupTableID for LookupTableIDs {
@@ -77,18 +85,22 @@ impl LookupTableID for LookupTableIDs {
             8 => RegisterLookup,
             9 => SyscallLookup,
             10 => KeccakStepLookup,
+            11 => MerkleMemoryLookup,
+            12 => PoseidonLookup,
+            13 => PrecompileLookup,
             _ => panic!("Invalid table ID"),
         }
     }
 
     fn length(&self) -> usize {
         match self {
-            PadLookup => RATE_IN_BYTES,
+            PadLookup => KeccakVariant::pad_table_length(),
             RoundConstantsLookup => ROUNDS,
             AtMost4Lookup => 5,
             ByteLookup => 1 << 8,
             RangeCheck16Lookup | SparseLookup | ResetLookup => 1 << 16,
-            MemoryLookup | RegisterLookup | SyscallLookup | KeccakStepLookup => {
+            MemoryLookup | RegisterLookup | SyscallLookup | KeccakStepLookup
+            | MerkleMemoryLookup | PoseidonLookup | PrecompileLookup => {
                 panic!("RAM Tables do not have a fixed length")
             }
         }
@@ -98,7 +110,8 @@ impl LookupTableID for LookupTableIDs {
         match self {
             PadLookup | RoundConstantsLookup | AtMost4Lookup | ByteLookup | RangeCheck16Lookup
             | SparseLookup | ResetLookup => true,
-            MemoryLookup | RegisterLookup | SyscallLookup | KeccakStepLookup => false,
+            MemoryLookup | RegisterLookup | SyscallLookup | KeccakStepLookup
+            | MerkleMemoryLookup | PoseidonLookup | PrecompileLookup => false,
         }
     }
 
@@ -134,7 +147,10 @@ impl LookupTableID for LookupTableIDs {
             Self::MemoryLookup
             | Self::RegisterLookup
             | Self::SyscallLookup
-            | Self::KeccakStepLookup => None,
+            | Self::KeccakStepLookup
+            | Self::MerkleMemoryLookup
+            | Self::PoseidonLookup
+            | Self::PrecompileLookup => None,
         }
     }
 
@@ -151,6 +167,9 @@ impl LookupTableID for LookupTableIDs {
             Self::RegisterLookup,
             Self::SyscallLookup,
             Self::KeccakStepLookup,
+            Self::MerkleMemoryLookup,
+            Self::PoseidonLookup,
+            Self::PrecompileLookup,
         ]
     }
 }
@@ -230,25 +249,24 @@ impl<F: Field> FixedLookupTables<F> for LookupTable<F> {
                     None
                 }
             }
-            MemoryLookup | RegisterLookup | SyscallLookup | KeccakStepLookup => None,
+            MemoryLookup | RegisterLookup | SyscallLookup | KeccakStepLookup
+            | MerkleMemoryLookup | PoseidonLookup | PrecompileLookup => None,
         }
     }
 
     fn table_pad() -> Self {
         Self {
             table_id: PadLookup,
-            entries: (1..=PadLookup.length())
-                .map(|i| {
-                    let suffix = pad_blocks(i);
-                    vec![
-                        F::from(i as u64),
-                        F::two_pow(i as u64),
-                        suffix[0],
-                        suffix[1],
-                        suffix[2],
-                        suffix[3],
-                        suffix[4],
-                    ]
+            entries: KeccakVariant::paddings()
+                .into_iter()
+                .flat_map(|variant| {
+                    let offset = variant.pad_table_offset();
+                    (1..=variant.rate_in_bytes()).map(move |i| {
+                        let suffix = pad_blocks::<F>(variant, i);
+                        let mut entry = vec![F::from((offset + i) as u64), F::two_pow(i as u64)];
+                        entry.extend(suffix);
+                        entry
+                    })
                 })
                 .collect(),
         }
@@ -0,0 +1,156 @@
+//! The memory of the virtual machines, as a sparse set of pages allocated on
+//! demand.
+
+use crate::cannon::{PAGE_ADDRESS_MASK, PAGE_ADDRESS_SIZE, PAGE_SIZE};
+use serde::{Deserialize, Serialize};
+use std::collections::{btree_map, BTreeMap};
+
+/// An address of the memory, which is split into the index of a page and the
+/// offset in this page
+pub trait Address: Copy + Ord {
+    /// The index of the page containing the address
+    fn page(self) -> Self;
+
+    /// The offset of the address in its page
+    fn offset(self) -> usize;
+}
+
+impl Address for u32 {
+    fn page(self) -> Self {
+        self >> PAGE_ADDRESS_SIZE
+    }
+
+    fn offset(self) -> usize {
+        (self & PAGE_ADDRESS_MASK) as usize
+    }
+}
+
+impl Address for u64 {
+    fn page(self) -> Self {
+        self >> PAGE_ADDRESS_SIZE
+    }
+
+    fn offset(self) -> usize {
+        (self & PAGE_ADDRESS_MASK as u64) as usize
+    }
+}
+
+/// A memory split into pages of [PAGE_SIZE] values, only keeping the pages
+/// that have been written. The pages are kept in a map from their index, so
+/// that a page is found in logarithmic time in the number of pages, and they
+/// are allocated on demand, filled with the default value.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(deserialize = "A: Ord + Deserialize<'de>, T: Deserialize<'de>"))]
+pub struct SparseMemory<A, T> {
+    pages: BTreeMap<A, Vec<T>>,
+}
+
+impl<A: Address, T: Copy + Default> SparseMemory<A, T> {
+    pub fn new() -> Self {
+        SparseMemory {
+            pages: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the value at the address `addr`, which is the default value if
+    /// the page has not been allocated
+    pub fn get(&self, addr: A) -> T {
+        self.pages
+            .get(&addr.page())
+            .map_or_else(T::default, |page| page[addr.offset()])
+    }
+
+    /// Sets the value at the address `addr`, allocating its page if needed
+    pub fn set(&mut self, addr: A, value: T) {
+        self.page_mut(addr.page())[addr.offset()] = value
+    }
+
+    /// Returns the page with index `page`, if it has been allocated
+    pub fn page(&self, page: A) -> Option<&[T]> {
+        self.pages.get(&page).map(Vec::as_slice)
+    }
+
+    /// Returns the page with index `page`, allocating it if needed
+    pub fn page_mut(&mut self, page: A) -> &mut [T] {
+        self.pages
+            .entry(page)
+            .or_insert_with(|| vec![T::default(); PAGE_SIZE as usize])
+    }
+
+    /// Returns the number of pages allocated
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    /// Returns the pages allocated with their index, by increasing index
+    pub fn iter(&self) -> btree_map::Iter<'_, A, Vec<T>> {
+        self.pages.iter()
+    }
+}
+
+impl<A: Address, T: Copy + Default> Default for SparseMemory<A, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builds the memory from pages given with their index. The pages shorter
+/// than [PAGE_SIZE] are padded with the default value.
+impl<A: Address, T: Copy + Default> FromIterator<(A, Vec<T>)> for SparseMemory<A, T> {
+    fn from_iter<I: IntoIterator<Item = (A, Vec<T>)>>(pages: I) -> Self {
+        SparseMemory {
+            pages: pages
+                .into_iter()
+                .map(|(index, mut page)| {
+                    assert!(page.len() <= PAGE_SIZE as usize);
+                    page.resize(PAGE_SIZE as usize, T::default());
+                    (index, page)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<A, T> IntoIterator for SparseMemory<A, T> {
+    type Item = (A, Vec<T>);
+    type IntoIter = btree_map::IntoIter<A, Vec<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.pages.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pages_are_allocated_on_write() {
+        let mut memory = SparseMemory::<u32, u8>::new();
+        assert_eq!(memory.get(0x12345), 0);
+        assert!(memory.is_empty());
+        memory.set(0x12345, 7);
+        memory.set(0xFFFFFFFF, 8);
+        assert_eq!(memory.get(0x12345), 7);
+        assert_eq!(memory.get(0xFFFFFFFF), 8);
+        assert_eq!(memory.get(0x12346), 0);
+        assert_eq!(
+            memory.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+            vec![0x12, 0xFFFFF]
+        );
+        assert_eq!(memory.page(0x12).unwrap()[0x345], 7);
+        assert_eq!(memory.page(0x13), None);
+    }
+
+    #[test]
+    fn test_pages_are_padded() {
+        let memory: SparseMemory<u64, u64> = [(3, vec![1, 2])].into_iter().collect();
+        assert_eq!(memory.page(3).unwrap().len(), PAGE_SIZE as usize);
+        assert_eq!(memory.get((3 << PAGE_ADDRESS_SIZE) + 1), 2);
+        assert_eq!(memory.get((3 << PAGE_ADDRESS_SIZE) + 2), 0);
+    }
+}
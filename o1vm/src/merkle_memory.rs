@@ -0,0 +1,275 @@
+//! A commitment to the memory of the virtual machines as a Merkle tree, used
+//! to carry the memory between the chunks of an execution that are proven
+//! separately.
+//!
+//! The leaves of the tree are the Poseidon hashes of the 32-bit words of the
+//! memory, stored in big endian, and the inner nodes the Poseidon hashes of
+//! their two children. The tree covers the whole 32-bit address space, and
+//! only the nodes that differ from the ones of an empty memory are stored.
+//!
+//! Each access to the memory produces an [AuthenticatedAccess], which contains
+//! the Merkle path of the word accessed, and the roots of the tree before and
+//! after the access. A chunk is valid if the roots of its accesses chain from
+//! the root at the start of the chunk to the root at its end, see
+//! [verify_accesses].
+
+use crate::{cannon::PAGE_ADDRESS_SIZE, memory::SparseMemory};
+use mina_curves::pasta::Fp;
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    pasta::fp_kimchi,
+    poseidon::{ArithmeticSponge, Sponge},
+};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use std::collections::{BTreeSet, HashMap};
+
+/// The depth of the tree, with one leaf for each word of the 32-bit address
+/// space
+pub const MERKLE_MEMORY_DEPTH: usize = 30;
+
+fn hash(input: &[Fp]) -> Fp {
+    let mut sponge =
+        ArithmeticSponge::<Fp, PlonkSpongeConstantsKimchi>::new(fp_kimchi::static_params());
+    sponge.absorb(input);
+    sponge.squeeze()
+}
+
+/// Returns the leaf of the tree for the word `word`
+pub fn hash_leaf(word: u32) -> Fp {
+    hash(&[Fp::from(word)])
+}
+
+/// Returns the inner node of the tree with children `left` and `right`
+pub fn hash_node(left: Fp, right: Fp) -> Fp {
+    hash(&[left, right])
+}
+
+/// Returns the word of `memory` containing the address `addr`
+pub fn word_at(memory: &SparseMemory<u32, u8>, addr: u32) -> u32 {
+    let addr = addr & !3;
+    u32::from_be_bytes(std::array::from_fn(|i| memory.get(addr + i as u32)))
+}
+
+/// Returns the root of the tree obtained by setting the word at address
+/// `addr` to `word`, given the Merkle path of this address
+pub fn root_from_path(addr: u32, word: u32, path: &[Fp]) -> Fp {
+    assert_eq!(path.len(), MERKLE_MEMORY_DEPTH);
+    let index = addr >> 2;
+    path.iter()
+        .enumerate()
+        .fold(hash_leaf(word), |node, (level, sibling)| {
+            if (index >> level) & 1 == 0 {
+                hash_node(node, *sibling)
+            } else {
+                hash_node(*sibling, node)
+            }
+        })
+}
+
+/// An access to the memory, changing the word at address `addr` from
+/// `old_word` to `new_word`, authenticated against the roots of the memory
+/// before and after the access
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthenticatedAccess {
+    /// The address of the word accessed, which is aligned on 4 bytes
+    pub addr: u32,
+    pub old_word: u32,
+    /// The word after the access, which is `old_word` for a load
+    pub new_word: u32,
+    /// The siblings of the nodes on the path from the leaf of the word to the
+    /// root, starting from the leaf
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub path: Vec<Fp>,
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    pub old_root: Fp,
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    pub new_root: Fp,
+}
+
+impl AuthenticatedAccess {
+    /// Checks that the path opens the old word against the old root, and the
+    /// new word against the new root
+    pub fn verify(&self) -> bool {
+        self.addr & 3 == 0
+            && root_from_path(self.addr, self.old_word, &self.path) == self.old_root
+            && root_from_path(self.addr, self.new_word, &self.path) == self.new_root
+    }
+}
+
+/// Checks the accesses of a chunk of an execution starting with the memory
+/// root `root`, and returns the root at the end of the chunk
+pub fn verify_accesses(root: Fp, accesses: &[AuthenticatedAccess]) -> Option<Fp> {
+    accesses.iter().try_fold(root, |root, access| {
+        (access.old_root == root && access.verify()).then_some(access.new_root)
+    })
+}
+
+/// The Merkle tree of the memory
+#[derive(Clone, Debug)]
+pub struct MerkleMemory {
+    /// The nodes that differ from the ones of the empty memory, indexed by
+    /// their level, starting from 0 for the leaves, and their index in the
+    /// level
+    nodes: HashMap<(usize, u32), Fp>,
+    /// The nodes of the empty memory at each level
+    empty_nodes: Vec<Fp>,
+}
+
+impl MerkleMemory {
+    /// Returns the tree of the empty memory
+    pub fn new() -> Self {
+        let mut empty_nodes = vec![hash_leaf(0)];
+        for level in 0..MERKLE_MEMORY_DEPTH {
+            empty_nodes.push(hash_node(empty_nodes[level], empty_nodes[level]));
+        }
+        MerkleMemory {
+            nodes: HashMap::new(),
+            empty_nodes,
+        }
+    }
+
+    /// Returns the tree of the memory `memory`. The tree is built level by
+    /// level, so that each node is only hashed once.
+    pub fn from_memory(memory: &SparseMemory<u32, u8>) -> Self {
+        let mut tree = Self::new();
+        let mut indices = BTreeSet::new();
+        for (page, data) in memory.iter() {
+            for (offset, word) in data.chunks_exact(4).enumerate() {
+                let word = u32::from_be_bytes(word.try_into().unwrap());
+                if word != 0 {
+                    let index = (page << (PAGE_ADDRESS_SIZE - 2)) + offset as u32;
+                    tree.set_node(0, index, hash_leaf(word));
+                    indices.insert(index);
+                }
+            }
+        }
+        for level in 1..=MERKLE_MEMORY_DEPTH {
+            indices = indices.into_iter().map(|index| index >> 1).collect();
+            for &index in indices.iter() {
+                let node = hash_node(
+                    tree.node(level - 1, 2 * index),
+                    tree.node(level - 1, 2 * index + 1),
+                );
+                tree.set_node(level, index, node);
+            }
+        }
+        tree
+    }
+
+    fn node(&self, level: usize, index: u32) -> Fp {
+        self.nodes
+            .get(&(level, index))
+            .copied()
+            .unwrap_or(self.empty_nodes[level])
+    }
+
+    fn set_node(&mut self, level: usize, index: u32, node: Fp) {
+        if node == self.empty_nodes[level] {
+            self.nodes.remove(&(level, index));
+        } else {
+            self.nodes.insert((level, index), node);
+        }
+    }
+
+    pub fn root(&self) -> Fp {
+        self.node(MERKLE_MEMORY_DEPTH, 0)
+    }
+
+    /// Returns the Merkle path of the word at address `addr`
+    pub fn path(&self, addr: u32) -> Vec<Fp> {
+        let index = addr >> 2;
+        (0..MERKLE_MEMORY_DEPTH)
+            .map(|level| self.node(level, (index >> level) ^ 1))
+            .collect()
+    }
+
+    /// Sets the word at address `addr` to `word`, updating the nodes on its
+    /// path. Nothing is updated if the word is unchanged.
+    pub fn update(&mut self, addr: u32, word: u32) {
+        let index = addr >> 2;
+        let leaf = hash_leaf(word);
+        if leaf == self.node(0, index) {
+            return;
+        }
+        self.set_node(0, index, leaf);
+        let mut node = leaf;
+        for level in 0..MERKLE_MEMORY_DEPTH {
+            let index = index >> level;
+            let sibling = self.node(level, index ^ 1);
+            node = if index & 1 == 0 {
+                hash_node(node, sibling)
+            } else {
+                hash_node(sibling, node)
+            };
+            self.set_node(level + 1, index >> 1, node);
+        }
+    }
+
+    /// Changes the word at address `addr` from `old_word` to `new_word`, and
+    /// returns the authenticated access
+    pub fn access(&mut self, addr: u32, old_word: u32, new_word: u32) -> AuthenticatedAccess {
+        let addr = addr & !3;
+        debug_assert_eq!(self.node(0, addr >> 2), hash_leaf(old_word));
+        let path = self.path(addr);
+        let old_root = self.root();
+        self.update(addr, new_word);
+        AuthenticatedAccess {
+            addr,
+            old_word,
+            new_word,
+            path,
+            old_root,
+            new_root: self.root(),
+        }
+    }
+}
+
+impl Default for MerkleMemory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accesses_chain_the_roots() {
+        let mut tree = MerkleMemory::new();
+        let empty_root = tree.root();
+        let accesses = vec![
+            tree.access(0x1000, 0, 0x01020304),
+            tree.access(0xFFFFFFFC, 0, 42),
+            tree.access(0x1002, 0x01020304, 0x01020304),
+            tree.access(0x1001, 0x01020304, 0),
+        ];
+        assert_eq!(accesses[2].addr, 0x1000);
+        assert_eq!(accesses[2].old_root, accesses[2].new_root);
+        assert_eq!(verify_accesses(empty_root, &accesses), Some(tree.root()));
+        assert_eq!(verify_accesses(tree.root(), &accesses), None);
+
+        let mut forged = accesses.clone();
+        forged[1].new_word = 43;
+        assert_eq!(verify_accesses(empty_root, &forged), None);
+
+        // Clearing the words gives back the empty memory
+        tree.access(0xFFFFFFFC, 42, 0);
+        assert_eq!(tree.root(), empty_root);
+        assert!(tree.nodes.is_empty());
+    }
+
+    #[test]
+    fn test_tree_from_memory() {
+        let mut memory = SparseMemory::<u32, u8>::new();
+        let mut tree = MerkleMemory::new();
+        for (addr, byte) in [(0x10, 1), (0x13, 2), (0x5000, 3), (0x7FFFFFFF, 4)] {
+            memory.set(addr, byte);
+            tree.update(addr & !3, word_at(&memory, addr));
+        }
+        assert_eq!(MerkleMemory::from_memory(&memory).root(), tree.root());
+        assert_ne!(tree.root(), MerkleMemory::new().root());
+    }
+}
@@ -0,0 +1,228 @@
+//! Proof artifacts: the proofs of the chunks of a whole execution, which can
+//! be stored and checked without running the interpreter again.
+//!
+//! An artifact is made of the claim about the execution (see [super::claim]),
+//! the parameters the proofs of its chunks were made for ([VerifierIndex]),
+//! the state at the end of the execution, and the proofs of the chunks,
+//! encoded (see [o1_utils::proof::EncodedProof]).
+//!
+//! An artifact is not a proof of the execution: the proofs of the chunks are
+//! independent and have no public input (see [super::chunk]), so that nothing
+//! binds them to each other, to the program, to the final state or to the
+//! claim. [ProofArtifact::check_chunk_proofs] only checks the proofs.
+//!
+//! The SRS and the constraints are not read from the artifact: they are
+//! trusted parameters of the verifier, see [ProofArtifact::check_chunk_proofs].
+
+use kimchi::circuits::domains::EvaluationDomains;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use o1_utils::proof::{EncodedProof, Proof as _, ProofError};
+use poly_commitment::ipa::SRS;
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{
+    chunk::{self, BoundaryState},
+    claim::{ClaimError, PublicClaim},
+    proof::{O1vmVerifier, Proof},
+    prover::{self, ProverError},
+    verifier,
+};
+use crate::{
+    cannon::{Meta, Start, VmConfiguration},
+    interpreters::mips::{column::SelectorLayout, witness as mips_witness},
+    preimage_oracle::PreImageOracleT,
+    E,
+};
+
+/// Errors of the creation and of the verification of proof artifacts
+#[derive(Error, Debug, Clone)]
+pub enum ArtifactError {
+    #[error("the artifact could not be serialized: {0}")]
+    Serialization(String),
+
+    #[error("the artifact could not be deserialized: {0}")]
+    Deserialization(String),
+
+    #[error("the proof of the chunk {0} could not be created: {1}")]
+    Prover(usize, ProverError),
+
+    #[error("the chunk {0} does not resume the execution where the previous one ended")]
+    BrokenChain(usize),
+
+    #[error("the proof of the chunk {0} is invalid: {1}")]
+    InvalidChunk(usize, ProofError),
+
+    #[error("the proofs of the chunks are invalid as a batch")]
+    InvalidBatch,
+
+    #[error("the proofs were made over a domain of size {expected}, got {found}")]
+    DomainMismatch { expected: usize, found: usize },
+
+    #[error("the proofs were made for {expected} constraints, got {found}")]
+    ConstraintsMismatch { expected: usize, found: usize },
+
+    #[error("the program did not halt at the end of the last chunk")]
+    NotHalted,
+
+    #[error("the claim does not match the final state: {0}")]
+    Claim(#[from] ClaimError),
+}
+
+/// The parameters the proofs of the chunks of an artifact were made for. They
+/// are only checked against the ones of the verifier, which never uses the
+/// artifact's.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VerifierIndex {
+    /// The size of the domain, i.e. the number of cycles of a chunk
+    pub domain_size: usize,
+    /// The number of constraints the proofs were made for
+    pub num_constraints: usize,
+    /// The layout of the selectors the constraints were built for
+    #[serde(default)]
+    pub selector_layout: SelectorLayout,
+}
+
+/// The proofs of the chunks of a whole execution, see [self]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProofArtifact {
+    /// The claim about the execution, as made by the prover
+    pub claim: PublicClaim,
+    pub verifier_index: VerifierIndex,
+    /// The state at the end of the execution, as given by the prover
+    pub final_state: BoundaryState,
+    /// The proofs of the chunks, in the order of the execution
+    pub chunks: Vec<EncodedProof>,
+}
+
+/// Runs the program of the environment `env` until it halts, proving each
+/// chunk of the trace over the domain `domain`, and returns the artifact
+/// holding the proofs of the chunks. The constraints `constraints` must be built for
+/// the selector layout `selector_layout`.
+#[allow(clippy::too_many_arguments)]
+pub fn prove_execution<PreImageOracle: PreImageOracleT, RNG: RngCore + CryptoRng>(
+    env: &mut mips_witness::Env<Fp, PreImageOracle>,
+    domain: EvaluationDomains<Fp>,
+    srs: &SRS<Vesta>,
+    constraints: &[E<Fp>],
+    selector_layout: SelectorLayout,
+    config: &VmConfiguration,
+    metadata: &Option<Meta>,
+    start: &Start,
+    rng: &mut RNG,
+) -> Result<ProofArtifact, ArtifactError> {
+    if env.merkle_memory.is_none() {
+        env.enable_merkle_memory();
+    }
+    let initial = BoundaryState::of_env(env);
+    let mut state = initial.commitment();
+    let mut chunks = vec![];
+    while !env.halt {
+        let index = chunks.len();
+        let mut chunk = chunk::run_chunk(env, domain, config, metadata, start, rng);
+        chunk.inputs.selector_layout = selector_layout;
+        // Only checked by the prover, see [chunk::check_chunk]
+        state = chunk::check_chunk(state, &chunk).ok_or(ArtifactError::BrokenChain(index))?;
+        let proof = prover::prove::<
+            Vesta,
+            DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>,
+            DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>,
+            _,
+        >(domain, srs, chunk.inputs, constraints, rng)
+        .map_err(|e| ArtifactError::Prover(index, e))?;
+        let encoded = EncodedProof::new(&proof, &())
+            .map_err(|e| ArtifactError::Serialization(e.to_string()))?;
+        chunks.push(encoded);
+    }
+    let final_state = BoundaryState::of_env(env);
+    Ok(ProofArtifact {
+        claim: PublicClaim::of_states(&initial, &final_state),
+        verifier_index: VerifierIndex {
+            domain_size: domain.d1.size as usize,
+            num_constraints: constraints.len(),
+            selector_layout,
+        },
+        final_state,
+        chunks,
+    })
+}
+
+impl ProofArtifact {
+    /// Serializes the artifact
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ArtifactError> {
+        rmp_serde::to_vec(self).map_err(|e| ArtifactError::Serialization(e.to_string()))
+    }
+
+    /// Deserializes an artifact
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ArtifactError> {
+        rmp_serde::from_slice(bytes).map_err(|e| ArtifactError::Deserialization(e.to_string()))
+    }
+
+    /// Checks that the proofs of the chunks verify with the trusted domain,
+    /// SRS and constraints of `verifier`, and that the final state, at which
+    /// the program halted, is the one the claim is made about. The proofs are
+    /// verified as a batch, see [verifier::verify_batch].
+    ///
+    /// This does not show that the program was executed: the proofs are not
+    /// bound to the program, to the final state or to the claim, see [self].
+    ///
+    /// The verifier index of the artifact is only compared to the parameters
+    /// of `verifier`, to report proofs made for other ones.
+    pub fn check_chunk_proofs(&self, verifier: &O1vmVerifier<Vesta>) -> Result<(), ArtifactError> {
+        let index = &self.verifier_index;
+        let O1vmVerifier {
+            domain,
+            srs,
+            constraints,
+        } = verifier;
+        if index.domain_size != domain.d1.size as usize {
+            return Err(ArtifactError::DomainMismatch {
+                expected: index.domain_size,
+                found: domain.d1.size as usize,
+            });
+        }
+        if index.num_constraints != constraints.len() {
+            return Err(ArtifactError::ConstraintsMismatch {
+                expected: index.num_constraints,
+                found: constraints.len(),
+            });
+        }
+        let proofs: Vec<Proof<Vesta>> = self
+            .chunks
+            .iter()
+            .enumerate()
+            .map(|(i, encoded)| {
+                encoded
+                    .decode()
+                    .map(|(proof, ())| proof)
+                    .map_err(|e| ArtifactError::InvalidChunk(i, e))
+            })
+            .collect::<Result<_, _>>()?;
+        let batch: Vec<_> = proofs.iter().collect();
+        let valid = verifier::verify_batch::<
+            Vesta,
+            DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>,
+            DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>,
+        >(*domain, srs, constraints, &batch);
+        if !valid {
+            // The batch does not tell which proofs are invalid. It is rejected
+            // even if none is found.
+            for (i, proof) in proofs.iter().enumerate() {
+                proof
+                    .verify(verifier, &())
+                    .map_err(|e| ArtifactError::InvalidChunk(i, e))?;
+            }
+            return Err(ArtifactError::InvalidBatch);
+        }
+        if !self.final_state.halted {
+            return Err(ArtifactError::NotHalted);
+        }
+        self.claim.check_final_state(&self.final_state)?;
+        Ok(())
+    }
+}
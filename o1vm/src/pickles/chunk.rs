@@ -0,0 +1,259 @@
+//! Chunks: the execution is split into chunks of a fixed number of cycles,
+//! the size of the domain, and each chunk is proven separately.
+//!
+//! The proofs of the chunks are independent: they have no public input, and
+//! nothing in a proof tells at which state of the virtual machine its trace
+//! starts or ends. A set of proofs is thus not a proof of an execution, as
+//! the proofs of chunks of different executions, or of the same chunk, are
+//! indistinguishable from the ones of consecutive chunks.
+//!
+//! The state of the virtual machine at the boundary between two chunks, made
+//! of the registers, of the root of the Merkle tree of the memory (see
+//! [crate::merkle_memory]), of the digests of the input and the output of
+//! the program (see [super::claim]) and of whether it halted, is committed to
+//! with a Poseidon hash by the witness environment. The prover can check that
+//! the chunks it runs resume the execution where the previous ones ended, see
+//! [check_chunk]. Chaining the proofs themselves would need the commitments to
+//! be recomputed in-circuit from the registers and the memory of the trace,
+//! which the trace does not hold.
+
+use ark_ff::{UniformRand, Zero};
+use kimchi::circuits::domains::EvaluationDomains;
+use mina_curves::pasta::{Fp, Vesta};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    pasta::fp_kimchi,
+    poseidon::{ArithmeticSponge, Sponge},
+};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use super::{claim, proof::ProofInputs};
+use crate::{
+    cannon::{Meta, Start, VmConfiguration},
+    interpreters::mips::{
+        column::N_MIPS_REL_COLS, registers::Registers, witness as mips_witness, Instruction,
+    },
+    merkle_memory::{self, AuthenticatedAccess},
+    preimage_oracle::PreImageOracleT,
+};
+
+/// The state of the virtual machine between two chunks
+#[serde_as]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BoundaryState {
+    pub registers: Registers<u32>,
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    pub memory_root: Fp,
+    /// The digest of the advice read so far, see [claim::stream_digest]
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    pub input_digest: Fp,
+    /// The digest of the output written so far
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    pub output_digest: Fp,
+    /// Whether the program halted
+    pub halted: bool,
+}
+
+impl BoundaryState {
+    /// Returns the current state of the environment `env`, which must
+    /// authenticate its memory accesses, see
+    /// [mips_witness::Env::enable_merkle_memory].
+    pub fn of_env<F, PreImageOracle: PreImageOracleT>(
+        env: &mips_witness::Env<F, PreImageOracle>,
+    ) -> Self {
+        let memory_root = env
+            .merkle_memory
+            .as_ref()
+            .expect("The boundary states require the Merkle memory")
+            .root();
+        BoundaryState {
+            registers: env.registers.clone(),
+            memory_root,
+            input_digest: claim::stream_digest(&env.syscall_env.input),
+            output_digest: claim::stream_digest(&env.syscall_env.output),
+            halted: env.halt,
+        }
+    }
+
+    /// Returns the Poseidon hash of the registers followed by the memory root,
+    /// the digests of the input and of the output, and the halting flag
+    pub fn commitment(&self) -> Fp {
+        let mut sponge =
+            ArithmeticSponge::<Fp, PlonkSpongeConstantsKimchi>::new(fp_kimchi::static_params());
+        let registers: Vec<Fp> = self.registers.iter().map(|r| Fp::from(*r)).collect();
+        sponge.absorb(&registers);
+        sponge.absorb(&[
+            self.memory_root,
+            self.input_digest,
+            self.output_digest,
+            Fp::from(self.halted),
+        ]);
+        sponge.squeeze()
+    }
+}
+
+/// The commitment to the state of the virtual machine at a step, computed by
+/// the witness environment at the steps given by
+/// [VmConfiguration::commit_state_at], to compare runs of the interpreter.
+/// The commitment is not part of any proof, see the documentation of the
+/// module.
+#[serde_as]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateCommitment {
+    pub step: u64,
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    pub commitment: Fp,
+}
+
+/// A chunk of the execution, with the trace to prove and its boundary states.
+///
+/// A chunk can be serialized, so that the witness generation and the proving
+/// can be run separately.
+#[derive(Serialize, Deserialize)]
+pub struct Chunk {
+    pub inputs: ProofInputs<Vesta>,
+    pub input: BoundaryState,
+    pub output: BoundaryState,
+    /// The memory accesses of the chunk, leading from the memory root of the
+    /// input state to the one of the output state
+    pub accesses: Vec<AuthenticatedAccess>,
+}
+
+/// Checks that the chunk `chunk` resumes the execution at the state with
+/// commitment `state`, at which the previous chunk ended, and that its memory
+/// accesses are authenticated. Returns the commitment to the state at the end
+/// of the chunk.
+///
+/// This is checked out of circuit, by the one running the chunks: the proof
+/// of the chunk is not bound to its boundary states.
+pub fn check_chunk(state: Fp, chunk: &Chunk) -> Option<Fp> {
+    let memory_root = merkle_memory::verify_accesses(chunk.input.memory_root, &chunk.accesses)?;
+    (chunk.input.commitment() == state && memory_root == chunk.output.memory_root)
+        .then(|| chunk.output.commitment())
+}
+
+/// Runs the interpreter until the trace fills the domain `domain` or the
+/// program halts, and returns the chunk executed. The trace of a program
+/// halting before the end of the domain is padded with no-ops.
+/// The Merkle memory is enabled on the first chunk if needed.
+pub fn run_chunk<PreImageOracle: PreImageOracleT, RNG: RngCore>(
+    env: &mut mips_witness::Env<Fp, PreImageOracle>,
+    domain: EvaluationDomains<Fp>,
+    config: &VmConfiguration,
+    metadata: &Option<Meta>,
+    start: &Start,
+    rng: &mut RNG,
+) -> Chunk {
+    if env.merkle_memory.is_none() {
+        env.enable_merkle_memory();
+    }
+    let domain_size = domain.d1.size as usize;
+    let input = BoundaryState::of_env(env);
+    let mut inputs: ProofInputs<Vesta> = ProofInputs::new(domain_size);
+    while !env.halt && inputs.evaluations.instruction_counter.len() < domain_size {
+        let _instr: Instruction = env.step(config, metadata, start);
+        push_row(env, &mut inputs, rng);
+    }
+    if inputs.evaluations.instruction_counter.len() < domain_size {
+        pad(env, &mut inputs, domain_size, rng);
+    }
+    let output = BoundaryState::of_env(env);
+    Chunk {
+        inputs,
+        input,
+        output,
+        accesses: env.take_authenticated_accesses(),
+    }
+}
+
+/// Adds the row of the step just executed to the trace
+fn push_row<PreImageOracle: PreImageOracleT, RNG: RngCore>(
+    env: &mips_witness::Env<Fp, PreImageOracle>,
+    inputs: &mut ProofInputs<Vesta>,
+    rng: &mut RNG,
+) {
+    // The rows are padded with zeros after the scratch columns of the
+    // instruction
+    for (i, scratch_chunk) in inputs.evaluations.scratch.iter_mut().enumerate() {
+        scratch_chunk.push(env.scratch_state.get(i).copied().unwrap_or_default());
+    }
+    for (i, scratch_chunk) in inputs.evaluations.scratch_inverse.iter_mut().enumerate() {
+        scratch_chunk.push(
+            env.scratch_state_inverse
+                .get(i)
+                .copied()
+                .unwrap_or_default(),
+        );
+    }
+    // Lookup state
+    {
+        let proof_inputs_length = inputs.evaluations.lookup_state.len();
+        let environment_length = env.lookup_state.len();
+        let lookup_state_size = std::cmp::max(proof_inputs_length, environment_length);
+        for idx in 0..lookup_state_size {
+            if idx >= environment_length {
+                // We pad with 0s for dummy lookups missing from the environment.
+                inputs.evaluations.lookup_state[idx].push(Fp::zero());
+            } else if idx >= proof_inputs_length {
+                // We create a new column filled with 0s in the proof inputs.
+                let mut new_vec = vec![Fp::zero(); inputs.evaluations.instruction_counter.len()];
+                new_vec.push(Fp::from(env.lookup_state[idx]));
+                inputs.evaluations.lookup_state.push(new_vec);
+            } else {
+                // Push the value to the column.
+                inputs.evaluations.lookup_state[idx].push(Fp::from(env.lookup_state[idx]));
+            }
+        }
+    }
+    inputs
+        .evaluations
+        .instruction_counter
+        .push(Fp::from(env.instruction_counter));
+    // FIXME: Might be another value
+    inputs.evaluations.error.push(Fp::rand(rng));
+
+    inputs
+        .evaluations
+        .selector
+        .push(Fp::from((env.selector - N_MIPS_REL_COLS) as u64));
+}
+
+/// Pads the trace with no-ops up to `domain_size` rows
+fn pad<PreImageOracle: PreImageOracleT, RNG: RngCore>(
+    env: &mips_witness::Env<Fp, PreImageOracle>,
+    inputs: &mut ProofInputs<Vesta>,
+    domain_size: usize,
+    rng: &mut RNG,
+) {
+    let zero = Fp::zero();
+    // FIXME: Find a better way to get instruction selectors that doesn't
+    // reveal internals.
+    let noop_selector: Fp = {
+        let noop: usize = Instruction::NoOp.into();
+        Fp::from((noop - N_MIPS_REL_COLS) as u64)
+    };
+    inputs
+        .evaluations
+        .scratch
+        .iter_mut()
+        .for_each(|x| x.resize(domain_size, zero));
+    inputs
+        .evaluations
+        .scratch_inverse
+        .iter_mut()
+        .for_each(|x| x.resize(domain_size, zero));
+    inputs
+        .evaluations
+        .instruction_counter
+        .resize(domain_size, Fp::from(env.instruction_counter));
+    inputs
+        .evaluations
+        .error
+        .resize_with(domain_size, || Fp::rand(rng));
+    inputs
+        .evaluations
+        .selector
+        .resize(domain_size, noop_selector);
+}
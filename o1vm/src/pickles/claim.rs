@@ -0,0 +1,176 @@
+//! The claim about an execution: which program ran, on which input, and what
+//! it output and exited with.
+//!
+//! The claim is made of the commitment to the initial state of the program,
+//! with its code loaded in memory (see [BoundaryState::commitment]), the
+//! digests of the advice read by the program and of the bytes it wrote on the
+//! standard output, and its exit code. The digests are part of the states
+//! committed to at the boundaries of the chunks.
+//!
+//! The claim is not verified. It is made by the prover, from the final state
+//! it gives with the proofs, and no constraint ties it, or the final state, to
+//! the trace: the proofs of the chunks have no public input, see
+//! [super::chunk]. The checks of this module only compare the claim to other
+//! values: [PublicClaim::check_final_state] to the final state, and
+//! [PublicClaim::check] to the expected program, input and output.
+
+use ark_ff::PrimeField;
+use mina_curves::pasta::Fp;
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    pasta::fp_kimchi,
+    poseidon::{ArithmeticSponge, Sponge},
+};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use thiserror::Error;
+
+use super::chunk::BoundaryState;
+use crate::{
+    interpreters::mips::witness as mips_witness, merkle_memory::MerkleMemory,
+    preimage_oracle::PreImageOracleT,
+};
+
+/// The register holding the exit code given to the `exit_group` system call
+pub const EXIT_CODE_REGISTER: usize = 4;
+
+/// Returns the exit code of the program in the final state `last`: as for the
+/// kernel, and for the witness environment, only the low byte of the status
+/// given to `exit_group` is kept
+pub fn exit_code(last: &BoundaryState) -> u8 {
+    last.registers.general_purpose[EXIT_CODE_REGISTER] as u8
+}
+
+/// The number of bytes packed in a field element when hashing a stream
+const STREAM_CHUNK_LEN: usize = 31;
+
+/// Errors of the checks of a claim
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ClaimError {
+    #[error("the claim is made for another program")]
+    CodeMismatch,
+
+    #[error("the claim is made for another input")]
+    InputMismatch,
+
+    #[error("the claim is made for another output")]
+    OutputMismatch,
+
+    #[error("the exit code {claimed} does not match the final state, which has {found}")]
+    ExitCodeMismatch { claimed: u8, found: u8 },
+}
+
+/// Returns the Poseidon hash of the bytes `bytes`, absorbed by chunks of
+/// [STREAM_CHUNK_LEN] bytes after their length
+pub fn stream_digest(bytes: &[u8]) -> Fp {
+    let mut sponge =
+        ArithmeticSponge::<Fp, PlonkSpongeConstantsKimchi>::new(fp_kimchi::static_params());
+    sponge.absorb(&[Fp::from(bytes.len() as u64)]);
+    let chunks: Vec<Fp> = bytes
+        .chunks(STREAM_CHUNK_LEN)
+        .map(Fp::from_le_bytes_mod_order)
+        .collect();
+    if !chunks.is_empty() {
+        sponge.absorb(&chunks);
+    }
+    sponge.squeeze()
+}
+
+/// Returns the commitment to the initial state of the program of the
+/// environment `env`, to be compared to the code commitment of a claim
+pub fn code_commitment<F, PreImageOracle: PreImageOracleT>(
+    env: &mips_witness::Env<F, PreImageOracle>,
+) -> Fp {
+    BoundaryState {
+        registers: env.registers.clone(),
+        memory_root: MerkleMemory::from_memory(&env.memory).root(),
+        input_digest: stream_digest(&env.syscall_env.input),
+        output_digest: stream_digest(&env.syscall_env.output),
+        halted: env.halt,
+    }
+    .commitment()
+}
+
+/// The claim about an execution, made by the prover and not verified, see
+/// [self]
+#[serde_as]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicClaim {
+    /// The commitment to the initial state, made of the code of the program
+    /// loaded in memory and of the registers
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    pub code_commitment: Fp,
+    /// The digest of the advice read by the program, see [stream_digest]
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    pub input_digest: Fp,
+    /// The digest of the bytes written on the standard output
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    pub output_digest: Fp,
+    /// The code given by the program to the `exit_group` system call, see
+    /// [exit_code]
+    pub exit_code: u8,
+}
+
+impl PublicClaim {
+    /// Returns the claim about the execution going from the state `initial`
+    /// to the state `last`
+    pub fn of_states(initial: &BoundaryState, last: &BoundaryState) -> Self {
+        PublicClaim {
+            code_commitment: initial.commitment(),
+            input_digest: last.input_digest,
+            output_digest: last.output_digest,
+            exit_code: exit_code(last),
+        }
+    }
+
+    /// Checks that the claim is made about the final state `last`. This does
+    /// not show that the program reached `last`, see [self].
+    pub fn check_final_state(&self, last: &BoundaryState) -> Result<(), ClaimError> {
+        if self.input_digest != last.input_digest {
+            return Err(ClaimError::InputMismatch);
+        }
+        if self.output_digest != last.output_digest {
+            return Err(ClaimError::OutputMismatch);
+        }
+        let exit_code = exit_code(last);
+        if self.exit_code != exit_code {
+            return Err(ClaimError::ExitCodeMismatch {
+                claimed: self.exit_code,
+                found: exit_code,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks that the claim is made about the program starting from the
+    /// state with commitment `code_commitment`, run on the input `input` and
+    /// writing the output `output`. This does not show that the program was
+    /// run, see [self].
+    pub fn check(
+        &self,
+        code_commitment: Fp,
+        input: &[u8],
+        output: &[u8],
+    ) -> Result<(), ClaimError> {
+        if self.code_commitment != code_commitment {
+            return Err(ClaimError::CodeMismatch);
+        }
+        if self.input_digest != stream_digest(input) {
+            return Err(ClaimError::InputMismatch);
+        }
+        if self.output_digest != stream_digest(output) {
+            return Err(ClaimError::OutputMismatch);
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for PublicClaim {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "program {} run on input {} exited with code {} and output {}",
+            self.code_commitment, self.input_digest, self.exit_code, self.output_digest
+        )
+    }
+}
@@ -3,7 +3,7 @@ use ark_poly::{Evaluations, Radix2EvaluationDomain};
 use kimchi_msm::columns::Column;
 
 use crate::{
-    interpreters::mips::column::{N_MIPS_SEL_COLS, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE},
+    interpreters::mips::column::{SCRATCH_SIZE, SCRATCH_SIZE_INVERSE},
     pickles::proof::WitnessColumns,
 };
 use kimchi::circuits::{
@@ -30,7 +30,7 @@ pub enum RelationColumnType {
 pub struct ColumnEnvironment<'a, F: FftField> {
     /// The witness column polynomials. Includes relation columns and dynamic
     /// selector columns.
-    pub witness: &'a WitnessColumns<Evals<F>, [Evals<F>; N_MIPS_SEL_COLS]>,
+    pub witness: &'a WitnessColumns<Evals<F>, Vec<Evals<F>>>,
     /// The value `prod_{j != 1} (1 - ω^j)`, used for efficiently
     /// computing the evaluations of the unnormalized Lagrange basis
     /// polynomials.
@@ -44,9 +44,12 @@ pub struct ColumnEnvironment<'a, F: FftField> {
     pub domain: EvaluationDomains<F>,
 }
 
-pub fn get_all_columns(num_lookup_columns: usize) -> Vec<Column<RelationColumnType>> {
+pub fn get_all_columns(
+    num_lookup_columns: usize,
+    num_selector_columns: usize,
+) -> Vec<Column<RelationColumnType>> {
     let mut cols = Vec::<Column<RelationColumnType>>::with_capacity(
-        SCRATCH_SIZE + SCRATCH_SIZE_INVERSE + num_lookup_columns + 2 + N_MIPS_SEL_COLS,
+        SCRATCH_SIZE + SCRATCH_SIZE_INVERSE + num_lookup_columns + 2 + num_selector_columns,
     );
     for i in 0..SCRATCH_SIZE {
         cols.push(Column::Relation(RelationColumnType::Scratch(i)));
@@ -59,13 +62,13 @@ pub fn get_all_columns(num_lookup_columns: usize) -> Vec<Column<RelationColumnTy
     }
     cols.push(Column::Relation(RelationColumnType::InstructionCounter));
     cols.push(Column::Relation(RelationColumnType::Error));
-    for i in 0..N_MIPS_SEL_COLS {
+    for i in 0..num_selector_columns {
         cols.push(Column::DynamicSelector(i));
     }
     cols
 }
 
-impl<G> WitnessColumns<G, [G; N_MIPS_SEL_COLS]> {
+impl<G> WitnessColumns<G, Vec<G>> {
     pub fn get_column(&self, col: &Column<RelationColumnType>) -> Option<&G> {
         match *col {
             Column::Relation(i) => match i {
@@ -75,16 +78,9 @@ impl<G> WitnessColumns<G, [G; N_MIPS_SEL_COLS]> {
                 RelationColumnType::InstructionCounter => Some(&self.instruction_counter),
                 RelationColumnType::Error => Some(&self.error),
             },
-            Column::DynamicSelector(i) => {
-                assert!(
-                    i < N_MIPS_SEL_COLS,
-                    "We do not have that many dynamic selector columns. We have {} columns and index {} was given",
-                    N_MIPS_SEL_COLS,
-                    i
-                );
-                let res = &self.selector[i];
-                Some(res)
-            }
+            // The number of selector columns depends on their layout, and is
+            // given by the proof to the verifier
+            Column::DynamicSelector(i) => self.selector.get(i),
             _ => {
                 panic!(
                     "We should not have any other type of columns. The column {:?} was given",
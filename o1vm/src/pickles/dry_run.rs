@@ -0,0 +1,136 @@
+//! Dry runs: the program is run by the interpreter as for proving it, without
+//! storing the trace, to report the cost of proving it.
+//!
+//! The [DryRunReport] gives the number of rows of each instruction, the number
+//! of chunks of the execution and of commitments made to prove them, and the
+//! memory used, so that the size of the domain and the hardware can be chosen
+//! before a proving run.
+
+use mina_curves::pasta::Fp;
+use std::collections::BTreeMap;
+
+use super::DEGREE_QUOTIENT_POLYNOMIAL;
+use crate::{
+    cannon::{Meta, Start, VmConfiguration, PAGE_SIZE},
+    interpreters::mips::{
+        column::{SelectorLayout, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE},
+        witness as mips_witness, Instruction,
+    },
+    preimage_oracle::PreImageOracleT,
+};
+
+/// The cost of proving an execution over a domain of a given size
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DryRunReport {
+    /// The size of the domain, i.e. the number of rows of a chunk
+    pub domain_size: usize,
+    /// The layout of the selectors the cost is computed for
+    pub selector_layout: SelectorLayout,
+    /// The number of instructions executed
+    pub cycles: u64,
+    /// The number of rows of each instruction
+    pub rows: BTreeMap<Instruction, u64>,
+    /// The largest number of lookup columns used by an instruction
+    pub num_lookup_columns: usize,
+    /// The largest number of bytes of the memory of the guest
+    pub peak_guest_memory: usize,
+}
+
+impl DryRunReport {
+    /// The number of chunks the execution is split into
+    pub fn num_chunks(&self) -> usize {
+        (self.cycles as usize).div_ceil(self.domain_size)
+    }
+
+    /// The number of traces of the size of the domain needed by the rows of
+    /// the instruction `instruction`, if it was proven on its own
+    pub fn instances(&self, instruction: Instruction) -> usize {
+        self.rows
+            .get(&instruction)
+            .map_or(0, |rows| (*rows as usize).div_ceil(self.domain_size))
+    }
+
+    /// The number of columns of the trace of a chunk
+    pub fn num_columns(&self) -> usize {
+        SCRATCH_SIZE
+            + SCRATCH_SIZE_INVERSE
+            + self.num_lookup_columns
+            + 2
+            + self.selector_layout.num_columns()
+    }
+
+    /// The number of commitments made to prove a chunk: one for each column
+    /// and one for each chunk of the quotient polynomial
+    pub fn commitments_per_chunk(&self) -> usize {
+        self.num_columns() + DEGREE_QUOTIENT_POLYNOMIAL as usize
+    }
+
+    /// The number of commitments made to prove the whole execution
+    pub fn projected_commitments(&self) -> usize {
+        self.num_chunks() * self.commitments_per_chunk()
+    }
+
+    /// The number of bytes of the trace of a chunk, held by the prover
+    pub fn witness_bytes(&self) -> usize {
+        self.num_columns() * self.domain_size * std::mem::size_of::<Fp>()
+    }
+}
+
+impl std::fmt::Display for DryRunReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} cycles in {} chunks of {} rows, {} commitments of {} columns",
+            self.cycles,
+            self.num_chunks(),
+            self.domain_size,
+            self.projected_commitments(),
+            self.num_columns()
+        )?;
+        writeln!(
+            f,
+            "peak memory: {} bytes of trace per chunk, {} bytes of guest memory",
+            self.witness_bytes(),
+            self.peak_guest_memory
+        )?;
+        for (instruction, rows) in self.rows.iter() {
+            writeln!(
+                f,
+                "{instruction:?}: {rows} rows, {} instances",
+                self.instances(*instruction)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs the program of the environment `env` until it halts, and returns the
+/// cost of proving it over a domain of size `domain_size`, with the selector
+/// layout `selector_layout`. The memory accesses are not authenticated, and
+/// the rows are not stored.
+pub fn dry_run<PreImageOracle: PreImageOracleT>(
+    env: &mut mips_witness::Env<Fp, PreImageOracle>,
+    domain_size: usize,
+    selector_layout: SelectorLayout,
+    config: &VmConfiguration,
+    metadata: &Option<Meta>,
+    start: &Start,
+) -> DryRunReport {
+    let mut report = DryRunReport {
+        domain_size,
+        selector_layout,
+        cycles: 0,
+        rows: BTreeMap::new(),
+        num_lookup_columns: 0,
+        peak_guest_memory: 0,
+    };
+    while !env.halt {
+        let instruction = env.step(config, metadata, start);
+        report.cycles += 1;
+        *report.rows.entry(instruction).or_insert(0) += 1;
+        report.num_lookup_columns = report.num_lookup_columns.max(env.lookup_state.len());
+    }
+    // The pages of the memory are never freed
+    report.peak_guest_memory = env.memory.len() * PAGE_SIZE as usize;
+    report
+}
@@ -1,20 +1,55 @@
-use ark_ff::{One, PrimeField, Zero};
+use ark_ff::{Field, One, PrimeField, Zero};
 use ark_poly::{univariate::DensePolynomial, Evaluations, Polynomial, Radix2EvaluationDomain};
 use kimchi::{circuits::domains::EvaluationDomains, curve::KimchiCurve, plonk_sponge::FrSponge};
 use mina_poseidon::FqSponge;
 use o1_utils::ExtendedDensePolynomial;
 use poly_commitment::{commitment::absorb_commitment, ipa::SRS, OpenProof, SRS as _};
-//TODO Parralelize
-//use rayon::prelude::*;
+use rayon::prelude::*;
+
 use super::lookup_columns::{ELookup, LookupChallenges, LookupEvalEnvironment};
 use crate::pickles::lookup_columns::*;
 use kimchi::{circuits::expr::l0_1, groupmap::GroupMap};
 use poly_commitment::{ipa::OpeningProof, utils::DensePolynomialOrEvaluations, PolyComm};
 use rand::{CryptoRng, RngCore};
 
+/// The number of terms of the chunks whose running sums are computed in
+/// parallel
+const RUNNING_SUM_CHUNK_SIZE: usize = 1 << 12;
+
+/// Returns the running sums of the terms `terms` starting from `init`, i.e.
+/// the i-th element is `init` plus the sum of the first i + 1 terms.
+/// The running sums of chunks of the terms are computed in parallel from
+/// zero, then shifted by the sum of the chunks before them.
+pub fn running_sum<F: Field>(init: F, terms: &[F]) -> Vec<F> {
+    let mut acc = terms.to_vec();
+    acc.par_chunks_mut(RUNNING_SUM_CHUNK_SIZE)
+        .for_each(|chunk| {
+            for i in 1..chunk.len() {
+                let previous = chunk[i - 1];
+                chunk[i] += previous;
+            }
+        });
+    // Stitching the chunks: each one starts from the last sum of the previous
+    let offsets: Vec<F> = acc
+        .chunks(RUNNING_SUM_CHUNK_SIZE)
+        .scan(init, |offset, chunk| {
+            let chunk_offset = *offset;
+            *offset += chunk[chunk.len() - 1];
+            Some(chunk_offset)
+        })
+        .collect();
+    acc.par_chunks_mut(RUNNING_SUM_CHUNK_SIZE)
+        .zip(offsets)
+        .for_each(|(chunk, offset)| chunk.iter_mut().for_each(|x| *x += offset));
+    acc
+}
+
 /// This prover takes one Public Input and one Public Output
 /// It then proves that the sum 1/(beta + table) = PI - PO
 /// where the table term are term from fixed lookup or RAMLookup
+///
+/// Note that this prover is not called by the o1vm prover yet, which does not
+/// prove the lookups for the moment (see [super::prover::prove]).
 
 pub fn lookup_prove<
     G: KimchiCurve,
@@ -56,7 +91,7 @@ where
     // Compute the 1/beta+sum_i gamma^i value_i for each lookup term
     // The inversions is commputed in batch in the end
     let mut inverses: Vec<Vec<G::ScalarField>> = wires
-        .iter()
+        .par_iter()
         .zip(arity)
         .map(|(inner_vec, arity)| {
             arity
@@ -75,19 +110,13 @@ where
         .collect();
     // Perform the inversion
     inverses
-        .iter_mut()
+        .par_iter_mut()
         .for_each(|inner_vec| ark_ff::batch_inversion(inner_vec));
     // Compute the accumulator
     // Init at acc_init
-    let mut partial_sum = acc_init;
-    let mut acc = vec![];
-    for inner in inverses.iter_mut() {
-        for x in inner.iter_mut() {
-            partial_sum += *x;
-            acc.push(partial_sum)
-        }
-    }
-    let acc_final = acc[acc.len()];
+    let terms: Vec<G::ScalarField> = inverses.iter().flatten().copied().collect();
+    let acc = running_sum(acc_init, &terms);
+    let acc_final = acc.last().copied().unwrap_or(acc_init);
     let columns = ColumnEnv {
         wires,
         inverses,
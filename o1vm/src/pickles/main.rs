@@ -1,175 +1,217 @@
-use ark_ff::{UniformRand, Zero};
 use clap::Parser;
 use kimchi::circuits::domains::EvaluationDomains;
-use log::debug;
+use log::{debug, error, info, warn};
 use mina_curves::pasta::{Fp, Vesta, VestaParameters};
 use mina_poseidon::{
     constants::PlonkSpongeConstantsKimchi,
     sponge::{DefaultFqSponge, DefaultFrSponge},
 };
 use o1vm::{
-    cannon::{self, Start, State},
+    cannon::{self, Meta, Start, State},
     cli, elf_loader,
     interpreters::mips::{
-        column::N_MIPS_REL_COLS,
+        column::SelectorLayout,
         constraints as mips_constraints,
         witness::{self as mips_witness},
-        Instruction,
     },
-    pickles::{proof::ProofInputs, prover, verifier},
-    preimage_oracle::{NullPreImageOracle, PreImageOracle, PreImageOracleT},
+    pickles::{
+        artifact::{self, ProofArtifact},
+        chunk::{self, BoundaryState},
+        dry_run,
+        proof::{O1vmVerifier, ProofInputs},
+        prover, verifier,
+    },
+    preimage_oracle::{
+        self, DiskPreImageOracle, NullPreImageOracle, PreImageOracle, PreImageOracleT,
+        RecordingOracle, ReplayOracle,
+    },
     test_preimage_read, E,
 };
-use poly_commitment::{ipa::SRS, precomputed_srs::TestSRS, SRS as _};
+use poly_commitment::{ipa::SRS, SRS as _};
 use rand::rngs::ThreadRng;
+use srs_store::SrsStore;
 use std::{fs::File, io::BufReader, path::Path, process::ExitCode, time::Instant};
 
-pub fn cannon_main(args: cli::cannon::RunArgs) {
-    let mut rng = rand::thread_rng();
+/// Reads the initial state and the metadata of the program
+fn load_state(configuration: &cannon::VmConfiguration) -> (State, Option<Meta>) {
+    // The state is read in JSON or in the binary format of Cannon
+    let state = State::read_file(Path::new(&configuration.input_state_file))
+        .unwrap_or_else(|e| panic!("Error reading input state file: {e}"));
 
-    let configuration: cannon::VmConfiguration = args.vm_cfg.into();
-
-    let file =
-        File::open(&configuration.input_state_file).expect("Error opening input state file ");
-
-    let reader = BufReader::new(file);
-    // Read the JSON contents of the file as an instance of `State`.
-    let state: State = serde_json::from_reader(reader).expect("Error reading input state file");
-
-    let meta = &configuration.metadata_file.as_ref().map(|f| {
+    let meta = configuration.metadata_file.as_ref().map(|f| {
         let meta_file =
             File::open(f).unwrap_or_else(|_| panic!("Could not open metadata file {}", f));
         serde_json::from_reader(BufReader::new(meta_file))
             .unwrap_or_else(|_| panic!("Error deserializing metadata file {}", f))
     });
+    (state, meta)
+}
 
-    // Initialize some data used for statistical computations
-    let start = Start::create(state.step as usize);
-
-    let (srs, domain_fp) = match &args.srs_cache {
+fn load_srs(srs_cache: &Option<String>) -> (SRS<Vesta>, EvaluationDomains<Fp>) {
+    match srs_cache {
         Some(cache) => {
             debug!("Loading SRS from cache {}", cache);
-            let file_path = Path::new(cache);
-            let file = File::open(file_path).expect("Error opening SRS cache file");
-            let srs: SRS<Vesta> = {
-                // By convention, proof systems serializes a TestSRS with filename 'test_<CURVE_NAME>.srs'.
-                // The benefit of using this is you don't waste time verifying the SRS.
-                if file_path
-                    .file_name()
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    .starts_with("test_")
-                {
-                    let test_srs: TestSRS<Vesta> = rmp_serde::from_read(&file).unwrap();
-                    From::from(test_srs)
-                } else {
-                    rmp_serde::from_read(&file).unwrap()
-                }
-            };
+            let srs: SRS<Vesta> = srs_store::load(Path::new(cache))
+                .and_then(|srs| srs_store::verify(&srs).map(|_| srs))
+                .unwrap_or_else(|e| panic!("Error loading the SRS cache file: {e}"));
             debug!("SRS loaded successfully from cache");
             let domain_fp = EvaluationDomains::<Fp>::create(srs.size()).unwrap();
             (srs, domain_fp)
         }
         None => {
-            debug!("No SRS cache provided. Creating SRS from scratch with domain size 2^16");
+            let store = SrsStore::from_env();
+            debug!(
+                "No SRS cache provided. Getting SRS with domain size 2^16 from {:?}",
+                store.dir()
+            );
             let domain_size = 1 << 16;
-            let srs = SRS::create(domain_size);
+            let srs = store
+                .get::<Vesta>(domain_size)
+                .unwrap_or_else(|e| panic!("Error getting the SRS: {e}"));
             let domain_fp = EvaluationDomains::<Fp>::create(srs.size()).unwrap();
             srs.get_lagrange_basis(domain_fp.d1);
-            debug!("SRS created successfully");
+            debug!("SRS obtained successfully");
             (srs, domain_fp)
         }
-    };
+    }
+}
 
-    // Initialize the environments
-    let mut mips_wit_env = match configuration.host.clone() {
-        Some(host) => {
+/// Returns the preimage oracle given by the arguments `args`: the transcript
+/// to replay, the host program, or the directory of preimages, in this order
+/// of precedence. The interactions with the oracle are recorded if requested.
+fn create_oracle(
+    configuration: &cannon::VmConfiguration,
+    args: &cli::cannon::OracleArgs,
+) -> Box<dyn PreImageOracleT> {
+    if let Some(replay) = &args.replay_transcript {
+        let file = File::open(replay).expect("Error opening the transcript file");
+        let transcript = preimage_oracle::read_transcript(BufReader::new(file))
+            .unwrap_or_else(|e| panic!("Error reading the transcript file: {e}"));
+        debug!(
+            "Replaying {} interactions from {}",
+            transcript.len(),
+            replay
+        );
+        return Box::new(ReplayOracle::new(transcript));
+    }
+    let oracle: Box<dyn PreImageOracleT> = match (configuration.host.clone(), &args.preimage_db_dir)
+    {
+        (Some(host), _) => {
             let mut po = PreImageOracle::create(host);
             let _child = po.start();
-            mips_witness::Env::<Fp, Box<dyn PreImageOracleT>>::create(
-                cannon::PAGE_SIZE as usize,
-                state,
-                Box::new(po),
-            )
+            Box::new(po)
         }
-        None => {
+        (None, Some(dir)) => {
+            debug!("Reading the preimages from {}", dir);
+            Box::new(DiskPreImageOracle::new(dir))
+        }
+        (None, None) => {
             debug!("No preimage oracle provided 🤞");
             // warning: the null preimage oracle has no data and will crash the program if used
-            mips_witness::Env::<Fp, Box<dyn PreImageOracleT>>::create(
-                cannon::PAGE_SIZE as usize,
-                state,
-                Box::new(NullPreImageOracle),
-            )
+            Box::new(NullPreImageOracle)
         }
     };
+    match &args.record_transcript {
+        Some(record) => {
+            let file = File::create(record).expect("Error creating the transcript file");
+            Box::new(RecordingOracle::new(oracle, file))
+        }
+        None => oracle,
+    }
+}
 
-    let constraints = mips_constraints::get_all_constraints::<Fp>();
-    let domain_size = domain_fp.d1.size as usize;
+fn create_env(
+    configuration: &cannon::VmConfiguration,
+    state: State,
+    oracle_args: &cli::cannon::OracleArgs,
+) -> mips_witness::Env<Fp, Box<dyn PreImageOracleT>> {
+    let oracle = create_oracle(configuration, oracle_args);
+    mips_witness::Env::<Fp, Box<dyn PreImageOracleT>>::create(
+        cannon::PAGE_SIZE as usize,
+        state,
+        oracle,
+    )
+}
 
-    let mut curr_proof_inputs: ProofInputs<Vesta> = ProofInputs::new(domain_size);
-    while !mips_wit_env.halt {
-        let _instr: Instruction = mips_wit_env.step(&configuration, meta, &start);
-        for (scratch, scratch_chunk) in mips_wit_env
-            .scratch_state
-            .iter()
-            .zip(curr_proof_inputs.evaluations.scratch.iter_mut())
-        {
-            scratch_chunk.push(*scratch);
-        }
-        for (scratch, scratch_chunk) in mips_wit_env
-            .scratch_state_inverse
-            .iter()
-            .zip(curr_proof_inputs.evaluations.scratch_inverse.iter_mut())
-        {
-            scratch_chunk.push(*scratch);
-        }
-        // Lookup state
-        {
-            let proof_inputs_length = curr_proof_inputs.evaluations.lookup_state.len();
-            let environment_length = mips_wit_env.lookup_state.len();
-            let lookup_state_size = std::cmp::max(proof_inputs_length, environment_length);
-            for idx in 0..lookup_state_size {
-                if idx >= environment_length {
-                    // We pad with 0s for dummy lookups missing from the environment.
-                    curr_proof_inputs.evaluations.lookup_state[idx].push(Fp::zero());
-                } else if idx >= proof_inputs_length {
-                    // We create a new column filled with 0s in the proof inputs.
-                    let mut new_vec =
-                        vec![Fp::zero(); curr_proof_inputs.evaluations.instruction_counter.len()];
-                    new_vec.push(Fp::from(mips_wit_env.lookup_state[idx]));
-                    curr_proof_inputs.evaluations.lookup_state.push(new_vec);
-                } else {
-                    // Push the value to the column.
-                    curr_proof_inputs.evaluations.lookup_state[idx]
-                        .push(Fp::from(mips_wit_env.lookup_state[idx]));
-                }
-            }
-        }
-        curr_proof_inputs
-            .evaluations
-            .instruction_counter
-            .push(Fp::from(mips_wit_env.instruction_counter));
-        // FIXME: Might be another value
-        curr_proof_inputs.evaluations.error.push(Fp::rand(&mut rng));
+/// Writes the state of the environment at the end of the execution, in the
+/// format given by the extension of the output state file
+fn write_output_state<PreImageOracle: PreImageOracleT>(
+    configuration: &cannon::VmConfiguration,
+    env: &mips_witness::Env<Fp, PreImageOracle>,
+) {
+    env.to_state()
+        .write_file(Path::new(&configuration.output_state_file))
+        .unwrap_or_else(|e| panic!("Error writing output state file: {e}"));
+}
+
+fn selector_layout(args: &cli::cannon::RunArgs) -> SelectorLayout {
+    if args.packed_selectors {
+        SelectorLayout::Packed
+    } else {
+        SelectorLayout::OneHot
+    }
+}
 
-        curr_proof_inputs
-            .evaluations
-            .selector
-            .push(Fp::from((mips_wit_env.selector - N_MIPS_REL_COLS) as u64));
+pub fn cannon_main(args: cli::cannon::RunArgs) {
+    let mut rng = rand::thread_rng();
 
-        if curr_proof_inputs.evaluations.instruction_counter.len() == domain_size {
-            prove_and_verify(domain_fp, &srs, &constraints, curr_proof_inputs, &mut rng);
+    let selector_layout = selector_layout(&args);
+    let configuration: cannon::VmConfiguration = args.vm_cfg.into();
 
-            curr_proof_inputs = ProofInputs::new(domain_size);
-        }
+    let (state, meta) = load_state(&configuration);
+    let meta = &meta;
+
+    // Initialize some data used for statistical computations
+    let start = Start::create(state.step as usize);
+
+    if let Some(domain_size) = args.dry_run {
+        let mut mips_wit_env = create_env(&configuration, state, &args.oracle);
+        let report = dry_run::dry_run(
+            &mut mips_wit_env,
+            domain_size,
+            selector_layout,
+            &configuration,
+            meta,
+            &start,
+        );
+        info!("Dry run over a domain of size {domain_size}:\n{report}");
+        write_output_state(&configuration, &mips_wit_env);
+        return;
     }
 
-    if curr_proof_inputs.evaluations.instruction_counter.len() < domain_size {
-        debug!("Padding witness for proof generation");
-        pad(&mips_wit_env, &mut curr_proof_inputs, &mut rng);
-        prove_and_verify(domain_fp, &srs, &constraints, curr_proof_inputs, &mut rng);
+    let (srs, domain_fp) = load_srs(&args.srs_cache);
+
+    // Initialize the environments
+    let mut mips_wit_env = create_env(&configuration, state, &args.oracle);
+
+    let constraints =
+        mips_constraints::get_all_constraints_with_selector_layout::<Fp>(selector_layout);
+
+    // Each chunk must resume the execution at the state at which the previous
+    // one ended. This is only checked here, as the proofs of the chunks are
+    // independent, see [chunk].
+    mips_wit_env.enable_merkle_memory();
+    let mut state = BoundaryState::of_env(&mips_wit_env).commitment();
+    while !mips_wit_env.halt {
+        let mut chunk = chunk::run_chunk(
+            &mut mips_wit_env,
+            domain_fp,
+            &configuration,
+            meta,
+            &start,
+            &mut rng,
+        );
+        chunk.inputs.selector_layout = selector_layout;
+        state = chunk::check_chunk(state, &chunk)
+            .expect("The chunk does not resume the execution where the previous one ended");
+        debug!(
+            "{} memory accesses authenticated, state at the end of the chunk: {}",
+            chunk.accesses.len(),
+            state
+        );
+        prove_and_verify(domain_fp, &srs, &constraints, chunk.inputs, &mut rng);
     }
+    write_output_state(&configuration, &mips_wit_env);
 }
 
 fn prove_and_verify(
@@ -204,50 +246,106 @@ fn prove_and_verify(
     assert!(verif);
 }
 
-fn pad(
-    witness_env: &mips_witness::Env<Fp, Box<dyn PreImageOracleT>>,
-    curr_proof_inputs: &mut ProofInputs<Vesta>,
-    rng: &mut ThreadRng,
-) {
-    let zero = Fp::zero();
-    // FIXME: Find a better way to get instruction selectors that doesn't
-    // reveal internals.
-    let noop_selector: Fp = {
-        let noop: usize = Instruction::NoOp.into();
-        Fp::from((noop - N_MIPS_REL_COLS) as u64)
-    };
-    curr_proof_inputs
-        .evaluations
-        .scratch
-        .iter_mut()
-        .for_each(|x| x.resize(x.capacity(), zero));
-    curr_proof_inputs
-        .evaluations
-        .scratch_inverse
-        .iter_mut()
-        .for_each(|x| x.resize(x.capacity(), zero));
-    curr_proof_inputs.evaluations.instruction_counter.resize(
-        curr_proof_inputs.evaluations.instruction_counter.capacity(),
-        Fp::from(witness_env.instruction_counter),
-    );
-    curr_proof_inputs
-        .evaluations
-        .error
-        .resize_with(curr_proof_inputs.evaluations.error.capacity(), || {
-            Fp::rand(rng)
-        });
-    curr_proof_inputs.evaluations.selector.resize(
-        curr_proof_inputs.evaluations.selector.capacity(),
-        noop_selector,
+/// Proves the execution of the program until it halts, and writes the proof
+/// artifact, to be checked by [verify_main]
+pub fn prove_main(args: cli::cannon::ProveArgs) {
+    let mut rng = rand::thread_rng();
+
+    let selector_layout = selector_layout(&args.run);
+    let configuration: cannon::VmConfiguration = args.run.vm_cfg.into();
+    let (state, meta) = load_state(&configuration);
+    let start = Start::create(state.step as usize);
+    let (srs, domain_fp) = load_srs(&args.run.srs_cache);
+    let mut mips_wit_env = create_env(&configuration, state, &args.run.oracle);
+    let constraints =
+        mips_constraints::get_all_constraints_with_selector_layout::<Fp>(selector_layout);
+
+    let artifact = artifact::prove_execution(
+        &mut mips_wit_env,
+        domain_fp,
+        &srs,
+        &constraints,
+        selector_layout,
+        &configuration,
+        &meta,
+        &start,
+        &mut rng,
+    )
+    .unwrap_or_else(|e| panic!("Error proving the execution: {e}"));
+    write_output_state(&configuration, &mips_wit_env);
+    let bytes = artifact
+        .to_bytes()
+        .unwrap_or_else(|e| panic!("Error serializing the proof artifact: {e}"));
+    std::fs::write(&args.artifact, bytes).expect("Error writing the proof artifact file");
+    info!(
+        "Proofs of {} chunks written to {}, with the claim {}, which they do not prove",
+        artifact.chunks.len(),
+        args.artifact,
+        artifact.claim
     );
 }
 
+/// Checks the proofs of the chunks of an artifact written by [prove_main],
+/// without running the program. This does not prove the execution, see
+/// [ProofArtifact::check_chunk_proofs], which is reported with the result.
+pub fn verify_main(args: cli::cannon::VerifyArgs) -> ExitCode {
+    let bytes = std::fs::read(&args.artifact).expect("Error reading the proof artifact file");
+    let artifact = match ProofArtifact::from_bytes(&bytes) {
+        Ok(artifact) => artifact,
+        Err(e) => {
+            error!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    // The SRS and the constraints are the verifier's, not the artifact's
+    let selector_layout = if args.packed_selectors {
+        SelectorLayout::Packed
+    } else {
+        SelectorLayout::OneHot
+    };
+    if artifact.verifier_index.selector_layout != selector_layout {
+        error!(
+            "The proofs were made for the selector layout {:?}, got {:?}",
+            artifact.verifier_index.selector_layout, selector_layout
+        );
+        return ExitCode::FAILURE;
+    }
+    let (srs, domain) = load_srs(&args.srs_cache);
+    let verifier = O1vmVerifier {
+        domain,
+        srs,
+        constraints: mips_constraints::get_all_constraints_with_selector_layout::<Fp>(
+            selector_layout,
+        ),
+    };
+    let start_verification = Instant::now();
+    match artifact.check_chunk_proofs(&verifier) {
+        Ok(()) => {
+            info!(
+                "Checked the proofs of {chunks} chunks in {elapsed} ms",
+                chunks = artifact.chunks.len(),
+                elapsed = start_verification.elapsed().as_millis(),
+            );
+            warn!(
+                "The artifact is not a proof of execution: the proofs of the chunks are not \
+                 bound to each other, to the program or to the claim {claim}",
+                claim = artifact.claim
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            error!("The proof artifact is invalid: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
 fn gen_state_json(arg: cli::cannon::GenStateJsonArgs) -> Result<(), String> {
     let path = Path::new(&arg.input);
     let state = elf_loader::parse_elf(elf_loader::Architecture::Mips, path)?;
-    let file = File::create(&arg.output).expect("Error creating output state file");
-    serde_json::to_writer_pretty(file, &state).expect("Error writing output state file");
-    Ok(())
+    state
+        .write_file(Path::new(&arg.output))
+        .map_err(|e| format!("Error writing output state file: {e}"))
 }
 
 pub fn main() -> ExitCode {
@@ -258,6 +356,12 @@ pub fn main() -> ExitCode {
             cli::cannon::Cannon::Run(args) => {
                 cannon_main(args);
             }
+            cli::cannon::Cannon::Prove(args) => {
+                prove_main(args);
+            }
+            cli::cannon::Cannon::Verify(args) => {
+                return verify_main(args);
+            }
             cli::cannon::Cannon::TestPreimageRead(args) => {
                 test_preimage_read::main(args);
             }
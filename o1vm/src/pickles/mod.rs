@@ -3,8 +3,13 @@
 //! each instruction using the Pasta curves and the IPA PCS.
 //!
 //! A proof is generated for each set of N continuous instructions, where N is
-//! the size of the supported SRS. The proofs will then be aggregated using
-//! a modified version of pickles.
+//! the size of the supported SRS (see [chunk]). The proofs are independent:
+//! they are not chained by the states of the virtual machine at their
+//! boundaries, and will have to be before being aggregated using a modified
+//! version of pickles. Until then, the proofs of the chunks of an execution
+//! are gathered in an [artifact::ProofArtifact], which holds one proof per
+//! chunk: no aggregation into a single proof is implemented yet. Their
+//! verification is only batched, see [verifier::verify_batch].
 //!
 //! You can run this flavor by using:
 //!
@@ -12,7 +17,11 @@
 //! O1VM_FLAVOR=pickles bash run-code.sh
 //! ```
 
+pub mod artifact;
+pub mod chunk;
+pub mod claim;
 pub mod column_env;
+pub mod dry_run;
 pub mod lookup_columns;
 pub mod lookup_env;
 pub mod lookup_prover;
@@ -1,7 +1,24 @@
-use kimchi::{curve::KimchiCurve, proof::PointEvaluations};
-use poly_commitment::{ipa::OpeningProof, PolyComm};
+use kimchi::{circuits::domains::EvaluationDomains, curve::KimchiCurve, proof::PointEvaluations};
+use mina_curves::pasta::{Fp, Fq, Pallas, PallasParameters, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use o1_utils::proof::{ProofError, ProofFormat};
+use poly_commitment::{
+    ipa::{OpeningProof, SRS},
+    PolyComm,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_with::{
+    de::DeserializeAsWrap, ser::SerializeAsWrap, serde_as, DeserializeAs, SerializeAs,
+};
 
-use crate::interpreters::mips::column::{N_MIPS_SEL_COLS, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE};
+use super::verifier::verify;
+use crate::{
+    interpreters::mips::column::{SelectorLayout, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE},
+    E,
+};
 
 pub struct WitnessColumns<G, S> {
     pub scratch: [G; SCRATCH_SIZE],
@@ -12,12 +29,87 @@ pub struct WitnessColumns<G, S> {
     pub selector: S,
 }
 
+impl<G, S, GAs, SAs> SerializeAs<WitnessColumns<G, S>> for WitnessColumns<GAs, SAs>
+where
+    GAs: SerializeAs<G>,
+    SAs: SerializeAs<S>,
+{
+    fn serialize_as<Ser>(
+        source: &WitnessColumns<G, S>,
+        serializer: Ser,
+    ) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: Serializer,
+    {
+        (
+            SerializeAsWrap::<_, [GAs; SCRATCH_SIZE]>::new(&source.scratch),
+            SerializeAsWrap::<_, [GAs; SCRATCH_SIZE_INVERSE]>::new(&source.scratch_inverse),
+            SerializeAsWrap::<_, Vec<GAs>>::new(&source.lookup_state),
+            SerializeAsWrap::<_, GAs>::new(&source.instruction_counter),
+            SerializeAsWrap::<_, GAs>::new(&source.error),
+            SerializeAsWrap::<_, SAs>::new(&source.selector),
+        )
+            .serialize(serializer)
+    }
+}
+
+impl<'de, G, S, GAs, SAs> DeserializeAs<'de, WitnessColumns<G, S>> for WitnessColumns<GAs, SAs>
+where
+    GAs: DeserializeAs<'de, G>,
+    SAs: DeserializeAs<'de, S>,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<WitnessColumns<G, S>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (scratch, scratch_inverse, lookup_state, instruction_counter, error, selector) =
+            DeserializeAsWrap::<
+                _,
+                (
+                    [GAs; SCRATCH_SIZE],
+                    [GAs; SCRATCH_SIZE_INVERSE],
+                    Vec<GAs>,
+                    GAs,
+                    GAs,
+                    SAs,
+                ),
+            >::deserialize(deserializer)?
+            .into_inner();
+        Ok(WitnessColumns {
+            scratch,
+            scratch_inverse,
+            lookup_state,
+            instruction_counter,
+            error,
+            selector,
+        })
+    }
+}
+
+/// The witness of a chunk, which can be serialized to be proven later or on
+/// another machine
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct ProofInputs<G: KimchiCurve> {
+    #[serde_as(
+        as = "WitnessColumns<Vec<o1_utils::serialization::SerdeAs>, Vec<o1_utils::serialization::SerdeAs>>"
+    )]
     pub evaluations: WitnessColumns<Vec<G::ScalarField>, Vec<G::ScalarField>>,
+    /// The layout of the selector columns built from the indices of the
+    /// instructions of the selector column
+    #[serde(default)]
+    pub selector_layout: SelectorLayout,
 }
 
 impl<G: KimchiCurve> ProofInputs<G> {
     pub fn new(domain_size: usize) -> Self {
+        Self::new_with_selector_layout(domain_size, SelectorLayout::default())
+    }
+
+    /// Returns empty inputs, whose selectors are committed to with the layout
+    /// `selector_layout`
+    pub fn new_with_selector_layout(domain_size: usize, selector_layout: SelectorLayout) -> Self {
         ProofInputs {
             evaluations: WitnessColumns {
                 scratch: std::array::from_fn(|_| Vec::with_capacity(domain_size)),
@@ -27,17 +119,69 @@ impl<G: KimchiCurve> ProofInputs<G> {
                 error: Vec::with_capacity(domain_size),
                 selector: Vec::with_capacity(domain_size),
             },
+            selector_layout,
         }
     }
 }
 
 // FIXME: should we blind the commitment?
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "G: ark_serialize::CanonicalDeserialize + ark_serialize::CanonicalSerialize")]
 pub struct Proof<G: KimchiCurve> {
-    pub commitments: WitnessColumns<PolyComm<G>, [PolyComm<G>; N_MIPS_SEL_COLS]>,
-    pub zeta_evaluations: WitnessColumns<G::ScalarField, [G::ScalarField; N_MIPS_SEL_COLS]>,
-    pub zeta_omega_evaluations: WitnessColumns<G::ScalarField, [G::ScalarField; N_MIPS_SEL_COLS]>,
+    /// The commitments to the columns, the number of selector columns
+    /// depending on the layout of the selectors
+    #[serde_as(as = "WitnessColumns<serde_with::Same, Vec<serde_with::Same>>")]
+    pub commitments: WitnessColumns<PolyComm<G>, Vec<PolyComm<G>>>,
+    #[serde_as(
+        as = "WitnessColumns<o1_utils::serialization::SerdeAs, Vec<o1_utils::serialization::SerdeAs>>"
+    )]
+    pub zeta_evaluations: WitnessColumns<G::ScalarField, Vec<G::ScalarField>>,
+    #[serde_as(
+        as = "WitnessColumns<o1_utils::serialization::SerdeAs, Vec<o1_utils::serialization::SerdeAs>>"
+    )]
+    pub zeta_omega_evaluations: WitnessColumns<G::ScalarField, Vec<G::ScalarField>>,
     pub quotient_commitment: PolyComm<G>,
     pub quotient_evaluations: PointEvaluations<Vec<G::ScalarField>>,
     /// IPA opening proof
     pub opening_proof: OpeningProof<G>,
 }
+
+/// The data needed to verify the proofs of the o1vm
+#[derive(Clone)]
+pub struct O1vmVerifier<G: KimchiCurve> {
+    pub domain: EvaluationDomains<G::ScalarField>,
+    pub srs: SRS<G>,
+    pub constraints: Vec<E<G::ScalarField>>,
+}
+
+// The public inputs are not supported, so the statement of a proof is empty:
+// the proof of a chunk is not bound to the states of the virtual machine at
+// its boundaries, see [super::chunk].
+macro_rules! impl_o1vm_proof {
+    ($curve:ty, $params:ty, $scalar:ty, $name:literal) => {
+        impl o1_utils::proof::Proof for Proof<$curve> {
+            const FORMAT: ProofFormat = ProofFormat::new($name, 4);
+
+            type Statement = ();
+
+            type Verifier = O1vmVerifier<$curve>;
+
+            fn verify(&self, verifier: &Self::Verifier, _statement: &()) -> Result<(), ProofError> {
+                let valid = verify::<
+                    $curve,
+                    DefaultFqSponge<$params, PlonkSpongeConstantsKimchi>,
+                    DefaultFrSponge<$scalar, PlonkSpongeConstantsKimchi>,
+                >(verifier.domain, &verifier.srs, &verifier.constraints, self);
+                if valid {
+                    Ok(())
+                } else {
+                    Err(ProofError::Invalid("the o1vm proof does not verify".into()))
+                }
+            }
+        }
+    };
+}
+
+impl_o1vm_proof!(Vesta, VestaParameters, Fp, "o1vm-pickles-vesta-ipa");
+impl_o1vm_proof!(Pallas, PallasParameters, Fq, "o1vm-pickles-pallas-ipa");
@@ -1,5 +1,3 @@
-use std::array;
-
 use ark_ff::{One, PrimeField, Zero};
 use ark_poly::{univariate::DensePolynomial, Evaluations, Polynomial, Radix2EvaluationDomain as D};
 use kimchi::{
@@ -30,8 +28,12 @@ use super::{
     proof::{Proof, ProofInputs, WitnessColumns},
     DEGREE_QUOTIENT_POLYNOMIAL,
 };
-use crate::{interpreters::mips::column::N_MIPS_SEL_COLS, E};
+use crate::{
+    interpreters::mips::column::{N_MIPS_SEL_COLS, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE},
+    E,
+};
 use thiserror::Error;
+use tracing::{debug_span, instrument};
 
 /// Errors that can arise when creating a proof
 #[derive(Error, Debug, Clone)]
@@ -52,6 +54,20 @@ pub enum ProverError {
 ///
 /// The final proof consists of the opening proof, the commitments and the
 /// evaluations at ζ and ζω.
+#[instrument(
+    name = "prove",
+    skip_all,
+    level = "debug",
+    fields(
+        prover = "o1vm",
+        domain_size = domain.d1.size,
+        columns = SCRATCH_SIZE
+            + SCRATCH_SIZE_INVERSE
+            + inputs.evaluations.lookup_state.len()
+            + 2
+            + inputs.selector_layout.num_columns(),
+    )
+)]
 pub fn prove<
     G: KimchiCurve,
     EFqSponge: FqSponge<G::BaseField, G, G::ScalarField> + Clone,
@@ -77,11 +93,16 @@ where
     // Round 1: Creating and absorbing column commitments
     ////////////////////////////////////////////////////////////////////////////
 
+    let phase = debug_span!("commit_witness").entered();
+
     debug!("Prover: interpolating all columns, including the selectors");
-    let ProofInputs { evaluations } = inputs;
+    let ProofInputs {
+        evaluations,
+        selector_layout,
+    } = inputs;
     let polys: WitnessColumns<
         DensePolynomial<G::ScalarField>,
-        [DensePolynomial<G::ScalarField>; N_MIPS_SEL_COLS],
+        Vec<DensePolynomial<G::ScalarField>>,
     > = {
         let WitnessColumns {
             scratch,
@@ -92,20 +113,22 @@ where
             selector,
         } = evaluations;
 
-        let domain_size = domain.d1.size as usize;
-
-        // Build the selectors
-        let selector: [Vec<G::ScalarField>; N_MIPS_SEL_COLS] = array::from_fn(|i| {
-            let mut s_i = Vec::with_capacity(domain_size);
-            for s in &selector {
-                s_i.push(if G::ScalarField::from(i as u64) == *s {
-                    G::ScalarField::one()
-                } else {
-                    G::ScalarField::zero()
-                })
+        // Build the selectors, with the columns of the instruction of each
+        // row set to one
+        let selector = {
+            let mut columns =
+                vec![vec![G::ScalarField::zero(); selector.len()]; selector_layout.num_columns()];
+            for (row, s) in selector.iter().enumerate() {
+                if let Some(i) =
+                    (0..N_MIPS_SEL_COLS).find(|i| G::ScalarField::from(*i as u64) == *s)
+                {
+                    for column in selector_layout.columns(i) {
+                        columns[column][row] = G::ScalarField::one();
+                    }
+                }
             }
-            s_i
-        });
+            columns
+        };
 
         let eval_col = |evals: Vec<G::ScalarField>| {
             Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(evals, domain.d1)
@@ -131,12 +154,12 @@ where
             lookup_state,
             instruction_counter: eval_col(instruction_counter),
             error: eval_col(error.clone()),
-            selector: selector.try_into().unwrap(),
+            selector,
         }
     };
 
     debug!("Prover: committing to all columns, including the selectors");
-    let commitments: WitnessColumns<PolyComm<G>, [PolyComm<G>; N_MIPS_SEL_COLS]> = {
+    let commitments: WitnessColumns<PolyComm<G>, Vec<PolyComm<G>>> = {
         let WitnessColumns {
             scratch,
             scratch_inverse,
@@ -166,15 +189,15 @@ where
             lookup_state,
             instruction_counter: comm(instruction_counter),
             error: comm(error),
-            selector: selector.try_into().unwrap(),
+            selector,
         }
     };
 
     debug!("Prover: evaluating all columns, including the selectors, on d8");
     // We evaluate on a domain higher than d1 for the quotient polynomial.
     // Based on the regression test
-    // `test_regression_constraints_with_selectors`, the highest degree is 6.
-    // Therefore, we do evaluate on d8.
+    // `test_regression_constraints_with_selectors`, the highest degree is 6,
+    // or 7 with the packed selectors. Therefore, we do evaluate on d8.
     let evaluations_d8 = {
         let WitnessColumns {
             scratch,
@@ -203,7 +226,7 @@ where
             lookup_state,
             instruction_counter: eval_d8(instruction_counter),
             error: eval_d8(error),
-            selector: selector.try_into().unwrap(),
+            selector,
         }
     };
 
@@ -224,10 +247,13 @@ where
         absorb_commitment(&mut fq_sponge, comm)
     }
 
+    drop(phase);
     ////////////////////////////////////////////////////////////////////////////
     // Round 2: Creating and committing to the quotient polynomial
     ////////////////////////////////////////////////////////////////////////////
 
+    let phase = debug_span!("quotient").entered();
+
     let (_, endo_r) = G::endos();
 
     // Constraints combiner
@@ -259,12 +285,9 @@ where
 
     debug!("Prover: computing the quotient polynomial");
     // Hint:
-    // To debug individual constraint, you can revert the following commits that implement the
-    // check for individual constraints.
-    // ```
-    // git revert 8e87244a98d55b90d175ad389611a3c98bd16b34
-    // git revert 96d42c127ef025869c91e5fed680e0e383108706
-    // ```
+    // To debug individual constraints, evaluate them on the rows of the
+    // witness with `crate::interpreters::mips::constraints::Env::debug_witness`,
+    // which reports the constraints that do not hold and where they are added.
     let quotient_poly: DensePolynomial<G::ScalarField> = {
         // Compute ∑ α^i constraint_i as an expression
         let combined_expr =
@@ -315,10 +338,13 @@ where
         .unwrap();
     absorb_commitment(&mut fq_sponge, &quotient_commitment.commitment);
 
+    drop(phase);
     ////////////////////////////////////////////////////////////////////////////
     // Round 3: Evaluations at ζ and ζω
     ////////////////////////////////////////////////////////////////////////////
 
+    let phase = debug_span!("evaluations").entered();
+
     debug!("Prover: evaluating all columns, including the selectors, at ζ and ζω");
     let zeta_chal = ScalarChallenge(fq_sponge.challenge());
 
@@ -345,15 +371,14 @@ where
             lookup_state,
             instruction_counter: eval(instruction_counter),
             error: eval(error),
-            selector: selector.try_into().unwrap(),
+            selector,
         }
     };
     // All evaluations at ζ
-    let zeta_evaluations: WitnessColumns<G::ScalarField, [G::ScalarField; N_MIPS_SEL_COLS]> =
-        evals(&zeta);
+    let zeta_evaluations: WitnessColumns<G::ScalarField, Vec<G::ScalarField>> = evals(&zeta);
 
     // All evaluations at ζω
-    let zeta_omega_evaluations: WitnessColumns<G::ScalarField, [G::ScalarField; N_MIPS_SEL_COLS]> =
+    let zeta_omega_evaluations: WitnessColumns<G::ScalarField, Vec<G::ScalarField>> =
         evals(&zeta_omega);
 
     let chunked_quotient = quotient_poly
@@ -422,10 +447,13 @@ where
         fr_sponge.absorb(quotient_zeta_eval);
         fr_sponge.absorb(quotient_zeta_omega_eval);
     }
+    drop(phase);
     ////////////////////////////////////////////////////////////////////////////
     // Round 4: Opening proof w/o linearization polynomial
     ////////////////////////////////////////////////////////////////////////////
 
+    let _phase = debug_span!("opening").entered();
+
     let mut polynomials: Vec<_> = polys.scratch.into_iter().collect();
     polynomials.extend(polys.scratch_inverse);
     polynomials.extend(polys.lookup_state);
@@ -1,24 +1,44 @@
 use std::time::Instant;
 
 use super::{
-    super::interpreters::mips::column::SCRATCH_SIZE,
-    proof::{ProofInputs, WitnessColumns},
+    super::interpreters::mips::column::{SelectorLayout, SCRATCH_SIZE},
+    proof::{O1vmVerifier, Proof, ProofInputs, WitnessColumns},
     prover::prove,
 };
 use crate::{
-    interpreters::riscv32im::SCRATCH_SIZE_INVERSE,
-    pickles::{column_env::RelationColumnType, verifier::verify},
+    cannon::{Page, Start, State, StepFrequency, VmConfiguration, PAGE_SIZE},
+    interpreters::{
+        mips::{
+            constraints as mips_constraints, registers::Registers, witness as mips_witness,
+            Instruction, RTypeInstruction,
+        },
+        riscv32im::SCRATCH_SIZE_INVERSE,
+    },
+    merkle_memory::MerkleMemory,
+    pickles::{
+        artifact::{prove_execution, ArtifactError, ProofArtifact},
+        chunk::{check_chunk, run_chunk, BoundaryState, Chunk, StateCommitment},
+        claim::{self, stream_digest, ClaimError, PublicClaim},
+        column_env::RelationColumnType,
+        dry_run::dry_run,
+        lookup_prover::running_sum,
+        verifier::verify,
+    },
+    preimage_oracle::NullPreImageOracle,
 };
 use ark_ff::{Field, One, UniformRand, Zero};
 use kimchi::circuits::{domains::EvaluationDomains, expr::Expr, gate::CurrOrNext};
 use kimchi_msm::columns::Column;
 use log::debug;
-use mina_curves::pasta::{Fq, Pallas, PallasParameters};
+use mina_curves::pasta::{Fp, Fq, Pallas, PallasParameters, Vesta};
 use mina_poseidon::{
     constants::PlonkSpongeConstantsKimchi,
     sponge::{DefaultFqSponge, DefaultFrSponge},
 };
-use o1_utils::tests::make_test_rng;
+use o1_utils::{
+    proof::{EncodedProof, ProofError, ProofRegistry},
+    tests::make_test_rng,
+};
 use poly_commitment::SRS;
 
 fn zero_to_n_minus_one(n: usize) -> Vec<Fq> {
@@ -29,6 +49,7 @@ fn zero_to_n_minus_one(n: usize) -> Vec<Fq> {
 fn test_small_circuit() {
     let domain = EvaluationDomains::<Fq>::create(8).unwrap();
     let srs = SRS::create(8);
+    let mut rng = make_test_rng(None);
     let proof_input = ProofInputs::<Pallas> {
         evaluations: WitnessColumns {
             scratch: std::array::from_fn(|_| zero_to_n_minus_one(8)),
@@ -43,6 +64,7 @@ fn test_small_circuit() {
                 .collect(),
             selector: zero_to_n_minus_one(8),
         },
+        selector_layout: SelectorLayout::OneHot,
     };
     let mut expr = Expr::zero();
     for i in 0..SCRATCH_SIZE {
@@ -65,7 +87,6 @@ fn test_small_circuit() {
         Column::Relation(RelationColumnType::Error),
         CurrOrNext::Curr,
     );
-    let mut rng = make_test_rng(None);
 
     type BaseSponge = DefaultFqSponge<PallasParameters, PlonkSpongeConstantsKimchi>;
     type ScalarSponge = DefaultFrSponge<Fq, PlonkSpongeConstantsKimchi>;
@@ -87,6 +108,28 @@ fn test_small_circuit() {
         (instant_after_verification - instant_before_verification).as_millis()
     );
     assert!(verif, "Verification fails");
+
+    // the proof can also be verified through a registry of proof formats
+    let mut registry = ProofRegistry::new();
+    registry.register::<Proof<Pallas>>(O1vmVerifier {
+        domain,
+        srs,
+        constraints: vec![expr],
+    });
+    let encoded = EncodedProof::new(&proof, &()).unwrap();
+    assert_eq!(registry.verify(&encoded), Ok(()));
+
+    // the proofs encoded with a state column and a statement are rejected
+    let mut encoded = encoded;
+    encoded.format.version = 3;
+    assert!(matches!(
+        registry.verify(&encoded),
+        Err(ProofError::UnknownFormat(_))
+    ));
+    assert!(matches!(
+        encoded.decode::<Proof<Pallas>>(),
+        Err(ProofError::FormatMismatch { .. })
+    ));
 }
 
 #[test]
@@ -116,3 +159,589 @@ fn test_arkworks_batch_inversion_with_zeroes_and_random() {
     ark_ff::batch_inversion::<Fq>(&mut output);
     assert_eq!(output, exp_output);
 }
+
+#[test]
+fn test_chunks_chain_the_boundary_states() {
+    let mut tree = MerkleMemory::new();
+    let chunk = |input: &BoundaryState, output: &BoundaryState, accesses| Chunk {
+        inputs: ProofInputs::new(8),
+        input: input.clone(),
+        output: output.clone(),
+        accesses,
+    };
+    let initial = BoundaryState {
+        registers: Registers::default(),
+        memory_root: tree.root(),
+        input_digest: stream_digest(&[]),
+        output_digest: stream_digest(&[]),
+        halted: false,
+    };
+    let accesses = vec![tree.access(0x1000, 0, 42)];
+    let mut middle = BoundaryState {
+        registers: Registers::default(),
+        memory_root: tree.root(),
+        input_digest: stream_digest(&[]),
+        output_digest: stream_digest(&[]),
+        halted: false,
+    };
+    middle.registers.general_purpose[1] = 42;
+    let first = chunk(&initial, &middle, accesses);
+    let accesses = vec![tree.access(0x1000, 42, 42)];
+    let second = chunk(&middle, &middle, accesses);
+
+    let state = initial.commitment();
+    let state = check_chunk(state, &first).unwrap();
+    assert_eq!(state, middle.commitment());
+    assert_eq!(check_chunk(state, &second), Some(state));
+
+    // the chunks can not be reordered
+    assert_eq!(check_chunk(initial.commitment(), &second), None);
+    // the memory root at the end of a chunk must follow from its accesses
+    let forged = chunk(&initial, &middle, vec![]);
+    assert_eq!(check_chunk(initial.commitment(), &forged), None);
+    assert_ne!(initial.commitment(), Fp::zero());
+}
+
+/// Returns an environment running the program `program`, loaded at the
+/// address 0 of a page whose bytes `data` are written from the offset 0x100
+fn env_of_program(program: &[u32], data: &[u8]) -> mips_witness::Env<Fp, NullPreImageOracle> {
+    let mut page = vec![0u8; PAGE_SIZE as usize];
+    for (i, instruction) in program.iter().enumerate() {
+        page[4 * i..4 * (i + 1)].copy_from_slice(&instruction.to_be_bytes());
+    }
+    page[0x100..0x100 + data.len()].copy_from_slice(data);
+    let state = State {
+        memory: vec![Page {
+            index: 0,
+            data: page,
+        }],
+        preimage_key: [0; 32],
+        preimage_offset: 0,
+        pc: 0,
+        next_pc: 4,
+        lo: 0,
+        hi: 0,
+        heap: 0,
+        exit: 0,
+        exited: false,
+        step: 0,
+        registers: [0; 32],
+        last_hint: None,
+        preimage: None,
+    };
+    mips_witness::Env::<Fp, NullPreImageOracle>::create(
+        PAGE_SIZE as usize,
+        state,
+        NullPreImageOracle,
+    )
+}
+
+/// addiu $v0, $zero, 4246 (exit_group); addiu $a0, $zero, 7; syscall
+const EXIT_PROGRAM: [u32; 3] = [0x24021096, 0x24040007, 0x0000000c];
+
+#[test]
+fn test_proof_artifact_of_an_execution() {
+    // The combination of the constraints of all the instructions recurses
+    // deeply
+    stacker::grow(32 << 20, || {
+        let mut env = env_of_program(&EXIT_PROGRAM, &[]);
+        let domain = EvaluationDomains::<Fp>::create(8).unwrap();
+        let srs = SRS::create(8);
+        let constraints = mips_constraints::get_all_constraints::<Fp>();
+        let mut rng = make_test_rng(None);
+        let artifact = prove_execution(
+            &mut env,
+            domain,
+            &srs,
+            &constraints,
+            SelectorLayout::OneHot,
+            &VmConfiguration::default(),
+            &None,
+            &Start::create(0),
+            &mut rng,
+        )
+        .unwrap();
+        assert_eq!(artifact.chunks.len(), 1);
+        assert_eq!(artifact.claim.exit_code, 7);
+
+        let verifier = O1vmVerifier {
+            domain,
+            srs,
+            constraints,
+        };
+
+        // The artifact is checked after a round trip through its encoding
+        let artifact = ProofArtifact::from_bytes(&artifact.to_bytes().unwrap()).unwrap();
+        artifact.check_chunk_proofs(&verifier).unwrap();
+
+        // The claim must be the one of the final state given by the prover
+        let mut forged = artifact.clone();
+        forged.claim.exit_code = 0;
+        assert!(matches!(
+            forged.check_chunk_proofs(&verifier),
+            Err(ArtifactError::Claim(ClaimError::ExitCodeMismatch { .. }))
+        ));
+        // but neither is bound to the proofs, so that they can be changed
+        // together
+        let mut forged = artifact.clone();
+        forged.final_state.output_digest = stream_digest(b"forged");
+        forged.claim.output_digest = forged.final_state.output_digest;
+        forged.check_chunk_proofs(&verifier).unwrap();
+        let mut forged = artifact.clone();
+        forged.final_state.halted = false;
+        assert!(matches!(
+            forged.check_chunk_proofs(&verifier),
+            Err(ArtifactError::NotHalted)
+        ));
+
+        // The parameters of the proofs are the verifier's
+        let other_verifier = O1vmVerifier {
+            constraints: verifier.constraints[1..].to_vec(),
+            ..verifier.clone()
+        };
+        assert!(matches!(
+            artifact.check_chunk_proofs(&other_verifier),
+            Err(ArtifactError::ConstraintsMismatch { .. })
+        ));
+        let other_verifier = O1vmVerifier {
+            domain: EvaluationDomains::<Fp>::create(16).unwrap(),
+            ..verifier.clone()
+        };
+        assert!(matches!(
+            artifact.check_chunk_proofs(&other_verifier),
+            Err(ArtifactError::DomainMismatch {
+                expected: 8,
+                found: 16
+            })
+        ));
+    })
+}
+
+#[test]
+fn test_public_claim_of_an_execution() {
+    // addiu $v0, $zero, 4004 (write); addiu $a0, $zero, 1 (stdout);
+    // addiu $a1, $zero, 0x100; addiu $a2, $zero, 3; syscall;
+    // addiu $v0, $zero, 4246 (exit_group); addiu $a0, $zero, 7; syscall
+    let program: [u32; 8] = [
+        0x24020fa4, 0x24040001, 0x24050100, 0x24060003, 0x0000000c, 0x24021096, 0x24040007,
+        0x0000000c,
+    ];
+    let mut env = env_of_program(&program, b"abc");
+    env.enable_merkle_memory();
+    let initial = BoundaryState::of_env(&env);
+    assert_eq!(initial.commitment(), claim::code_commitment(&env));
+    while !env.halt {
+        env.step(&VmConfiguration::default(), &None, &Start::create(0));
+    }
+    assert_eq!(env.syscall_env.output, b"abc");
+    let last = BoundaryState::of_env(&env);
+    let public_claim = PublicClaim::of_states(&initial, &last);
+    assert_eq!(public_claim.exit_code, 7);
+    public_claim.check_final_state(&last).unwrap();
+    let other_claim = PublicClaim {
+        exit_code: 0,
+        ..public_claim
+    };
+    assert_eq!(
+        other_claim.check_final_state(&last),
+        Err(ClaimError::ExitCodeMismatch {
+            claimed: 0,
+            found: 7
+        })
+    );
+    // Only the low byte of the status is kept, as by the kernel
+    let mut truncated = last.clone();
+    truncated.registers.general_purpose[claim::EXIT_CODE_REGISTER] = 0x107;
+    assert_eq!(claim::exit_code(&truncated), 7);
+    public_claim
+        .check(initial.commitment(), &[], b"abc")
+        .unwrap();
+    assert_eq!(
+        public_claim.check(initial.commitment(), &[], b"abd"),
+        Err(ClaimError::OutputMismatch)
+    );
+    assert_eq!(
+        public_claim.check(initial.commitment(), b"abc", b"abc"),
+        Err(ClaimError::InputMismatch)
+    );
+    assert_eq!(
+        public_claim.check(last.commitment(), &[], b"abc"),
+        Err(ClaimError::CodeMismatch)
+    );
+}
+
+#[test]
+fn test_chunk_serialization_round_trip() {
+    let mut env = env_of_program(&EXIT_PROGRAM, &[]);
+    let domain = EvaluationDomains::<Fp>::create(8).unwrap();
+    let mut rng = make_test_rng(None);
+    let chunk = run_chunk(
+        &mut env,
+        domain,
+        &VmConfiguration::default(),
+        &None,
+        &Start::create(0),
+        &mut rng,
+    );
+    let bytes = rmp_serde::to_vec(&chunk).unwrap();
+    let decoded: Chunk = rmp_serde::from_slice(&bytes).unwrap();
+
+    assert_eq!(decoded.input.commitment(), chunk.input.commitment());
+    assert_eq!(decoded.output.commitment(), chunk.output.commitment());
+    assert_eq!(decoded.accesses, chunk.accesses);
+    let (columns, decoded_columns) = (&chunk.inputs.evaluations, &decoded.inputs.evaluations);
+    assert_eq!(decoded_columns.scratch, columns.scratch);
+    assert_eq!(decoded_columns.scratch_inverse, columns.scratch_inverse);
+    assert_eq!(decoded_columns.lookup_state, columns.lookup_state);
+    assert_eq!(
+        decoded_columns.instruction_counter,
+        columns.instruction_counter
+    );
+    assert_eq!(decoded_columns.error, columns.error);
+    assert_eq!(decoded_columns.selector, columns.selector);
+    assert_eq!(
+        check_chunk(chunk.input.commitment(), &decoded),
+        Some(chunk.output.commitment())
+    );
+}
+
+#[test]
+fn test_proof_with_packed_selectors() {
+    // The combination of the constraints of all the instructions recurses
+    // deeply
+    stacker::grow(32 << 20, || {
+        let mut env = env_of_program(&EXIT_PROGRAM, &[]);
+        let domain = EvaluationDomains::<Fp>::create(8).unwrap();
+        let srs = SRS::create(8);
+        let layout = SelectorLayout::Packed;
+        let constraints = mips_constraints::get_all_constraints_with_selector_layout::<Fp>(layout);
+        let mut rng = make_test_rng(None);
+        let artifact = prove_execution(
+            &mut env,
+            domain,
+            &srs,
+            &constraints,
+            layout,
+            &VmConfiguration::default(),
+            &None,
+            &Start::create(0),
+            &mut rng,
+        )
+        .unwrap();
+        let verifier = O1vmVerifier {
+            domain,
+            srs,
+            constraints,
+        };
+        artifact.check_chunk_proofs(&verifier).unwrap();
+
+        let (proof, ()): (Proof<Vesta>, ()) = artifact.chunks[0].decode().unwrap();
+        assert_eq!(proof.commitments.selector.len(), layout.num_columns());
+        assert!(layout.num_columns() < SelectorLayout::OneHot.num_columns());
+
+        // The proofs are bound to the layout of the selectors
+        let one_hot_verifier = O1vmVerifier {
+            constraints: mips_constraints::get_all_constraints::<Fp>(),
+            ..verifier
+        };
+        assert!(matches!(
+            artifact.check_chunk_proofs(&one_hot_verifier),
+            Err(ArtifactError::ConstraintsMismatch { .. })
+        ));
+    })
+}
+
+#[test]
+fn test_dry_run_matches_the_chunks() {
+    let domain = EvaluationDomains::<Fp>::create(2).unwrap();
+    let mut env = env_of_program(&EXIT_PROGRAM, &[]);
+    let report = dry_run(
+        &mut env,
+        2,
+        SelectorLayout::OneHot,
+        &VmConfiguration::default(),
+        &None,
+        &Start::create(0),
+    );
+    assert_eq!(report.cycles, 3);
+    assert_eq!(report.rows.values().sum::<u64>(), report.cycles);
+    assert_eq!(report.instances(Instruction::NoOp), 0);
+
+    let mut env = env_of_program(&EXIT_PROGRAM, &[]);
+    let mut rng = make_test_rng(None);
+    let mut chunks = vec![];
+    while !env.halt {
+        chunks.push(run_chunk(
+            &mut env,
+            domain,
+            &VmConfiguration::default(),
+            &None,
+            &Start::create(0),
+            &mut rng,
+        ));
+    }
+    assert_eq!(report.num_chunks(), chunks.len());
+    let num_lookup_columns = chunks
+        .iter()
+        .map(|chunk| chunk.inputs.evaluations.lookup_state.len())
+        .max()
+        .unwrap();
+    assert_eq!(report.num_lookup_columns, num_lookup_columns);
+    let columns = &chunks[0].inputs.evaluations;
+    assert_eq!(
+        report.num_columns(),
+        columns.scratch.len()
+            + columns.scratch_inverse.len()
+            + num_lookup_columns
+            + 2
+            + SelectorLayout::OneHot.num_columns()
+    );
+}
+
+#[test]
+fn test_state_commitments_at_chunk_boundaries() {
+    let domain = EvaluationDomains::<Fp>::create(2).unwrap();
+    let config = VmConfiguration {
+        commit_state_at: StepFrequency::Every(2),
+        ..VmConfiguration::default()
+    };
+    let mut env = env_of_program(&EXIT_PROGRAM, &[]);
+    let mut rng = make_test_rng(None);
+    let mut chunks = vec![];
+    while !env.halt {
+        chunks.push(run_chunk(
+            &mut env,
+            domain,
+            &config,
+            &None,
+            &Start::create(0),
+            &mut rng,
+        ));
+    }
+    let commitments: Vec<_> = chunks
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| StateCommitment {
+            step: 2 * i as u64,
+            commitment: chunk.input.commitment(),
+        })
+        .collect();
+    assert_eq!(env.state_commitments, commitments);
+}
+
+#[test]
+fn test_artifact_chunks_verified_as_a_batch() {
+    stacker::grow(32 << 20, || {
+        let mut env = env_of_program(&EXIT_PROGRAM, &[]);
+        let domain = EvaluationDomains::<Fp>::create(2).unwrap();
+        let srs = SRS::create(2);
+        let constraints = mips_constraints::get_all_constraints::<Fp>();
+        let mut rng = make_test_rng(None);
+        let artifact = prove_execution(
+            &mut env,
+            domain,
+            &srs,
+            &constraints,
+            SelectorLayout::OneHot,
+            &VmConfiguration::default(),
+            &None,
+            &Start::create(0),
+            &mut rng,
+        )
+        .unwrap();
+        assert_eq!(artifact.chunks.len(), 2);
+        let verifier = O1vmVerifier {
+            domain,
+            srs,
+            constraints,
+        };
+        artifact.check_chunk_proofs(&verifier).unwrap();
+
+        // An invalid proof makes the batch fail, and is then found
+        let mut forged = artifact.clone();
+        let (mut proof, ()): (Proof<Vesta>, ()) = forged.chunks[1].decode().unwrap();
+        proof.zeta_evaluations.error += Fp::one();
+        forged.chunks[1] = EncodedProof::new(&proof, &()).unwrap();
+        assert!(matches!(
+            forged.check_chunk_proofs(&verifier),
+            Err(ArtifactError::InvalidChunk(1, _))
+        ));
+    })
+}
+
+#[test]
+fn test_exported_state_resumes_the_execution() {
+    let mut env = env_of_program(&EXIT_PROGRAM, &[]);
+    let config = VmConfiguration::default();
+    env.step(&config, &None, &Start::create(0));
+    let state = env.to_state();
+    assert!(!state.exited);
+    let mut resumed = mips_witness::Env::<Fp, NullPreImageOracle>::create(
+        PAGE_SIZE as usize,
+        state,
+        NullPreImageOracle,
+    );
+    while !env.halt {
+        env.step(&config, &None, &Start::create(0));
+        resumed.step(&config, &None, &Start::create(0));
+    }
+    let state = env.to_state();
+    assert_eq!(resumed.to_state(), state);
+    assert!(state.exited);
+    assert_eq!(state.exit, 7);
+}
+
+#[test]
+fn test_debug_witness_locates_the_unsatisfied_constraints() {
+    let mut env = env_of_program(&EXIT_PROGRAM, &[]);
+    let domain = EvaluationDomains::<Fp>::create(8).unwrap();
+    let mut rng = make_test_rng(None);
+    let mut chunk = run_chunk(
+        &mut env,
+        domain,
+        &VmConfiguration::default(),
+        &None,
+        &Start::create(0),
+        &mut rng,
+    );
+    let mut constraints_env = mips_constraints::Env::<Fp>::default();
+    let witness = &mut chunk.inputs.evaluations;
+    assert!(constraints_env.debug_witness(witness).unwrap().is_empty());
+
+    // Corrupt the scratch columns of the system call exiting the program
+    let row = 2;
+    for column in witness.scratch.iter_mut() {
+        column[row] += Fp::one();
+    }
+    let unsatisfied = constraints_env.debug_witness(witness).unwrap();
+    assert!(!unsatisfied.is_empty());
+    for constraint in unsatisfied.iter() {
+        assert!(constraint.to_string().starts_with("row 2: "));
+        assert_eq!(constraint.row, row);
+        assert_eq!(
+            constraint.instruction,
+            Instruction::RType(RTypeInstruction::SyscallExitGroup)
+        );
+        assert!(constraint.label.file().ends_with(".rs"));
+        assert_eq!(constraint.scratch[0], witness.scratch[0][row]);
+    }
+}
+
+#[test]
+fn test_running_sum_stitches_the_chunks() {
+    let mut rng = make_test_rng(None);
+    // Spanning several chunks, the last one being partial
+    let terms: Vec<Fp> = (0..10_000).map(|_| Fp::rand(&mut rng)).collect();
+    let init = Fp::rand(&mut rng);
+    let expected: Vec<Fp> = terms
+        .iter()
+        .scan(init, |sum, term| {
+            *sum += term;
+            Some(*sum)
+        })
+        .collect();
+    assert_eq!(running_sum(init, &terms), expected);
+    assert!(running_sum(init, &[]).is_empty());
+}
+
+/// Loads and stores bytes, half-words and words, aligned or not, at the data
+/// at 0x100 before exiting
+const LOAD_STORE_PROGRAM: [u32; 14] = [
+    0x24080100, // addiu $t0, $zero, 0x100
+    0x8D090000, // lw $t1, 0($t0)
+    0xA1090005, // sb $t1, 5($t0)
+    0x910A0005, // lbu $t2, 5($t0)
+    0x850B0002, // lh $t3, 2($t0)
+    0xA5090006, // sh $t1, 6($t0)
+    0x890C0001, // lwl $t4, 1($t0)
+    0xB9090009, // swr $t1, 9($t0)
+    0x810D0007, // lb $t5, 7($t0)
+    0xA909000A, // swl $t1, 10($t0)
+    0x990E000E, // lwr $t6, 14($t0)
+    0x24021096, // addiu $v0, $zero, 4246 (exit_group)
+    0x24040007, // addiu $a0, $zero, 7
+    0x0000000c, // syscall
+];
+
+#[test]
+fn test_word_memory_matches_the_byte_memory() {
+    let data: Vec<u8> = (0..16u8).map(|i| 0x80 | (7 * i)).collect();
+    let domain = EvaluationDomains::<Fp>::create(16).unwrap();
+    let mut rng = make_test_rng(None);
+    let mut run = |word_memory: bool| {
+        let mut env = env_of_program(&LOAD_STORE_PROGRAM, &data);
+        if word_memory {
+            env.enable_word_memory();
+        }
+        let chunk = run_chunk(
+            &mut env,
+            domain,
+            &VmConfiguration::default(),
+            &None,
+            &Start::create(0),
+            &mut rng,
+        );
+        assert!(env.halt);
+        (env.to_state(), chunk)
+    };
+    let (byte_state, byte_chunk) = run(false);
+    let (word_state, word_chunk) = run(true);
+    assert_eq!(word_state, byte_state);
+    assert_eq!(word_state.exit, 7);
+
+    // The witness of each mode satisfies the constraints of its mode
+    let mut byte_env = mips_constraints::Env::<Fp>::default();
+    let byte_witness = &byte_chunk.inputs.evaluations;
+    assert!(byte_env.debug_witness(byte_witness).unwrap().is_empty());
+    let mut word_env = mips_constraints::Env::<Fp>::default().with_word_memory();
+    let word_witness = &word_chunk.inputs.evaluations;
+    assert!(word_env.debug_witness(word_witness).unwrap().is_empty());
+    // but not the ones of the other mode
+    assert!(!byte_env.debug_witness(word_witness).unwrap().is_empty());
+
+    // The accesses authenticated by word chain the memory roots too
+    assert_eq!(
+        check_chunk(word_chunk.input.commitment(), &word_chunk),
+        Some(word_chunk.output.commitment())
+    );
+}
+
+#[test]
+fn test_coverage_reports_the_executed_instructions() {
+    let mut env = env_of_program(&EXIT_PROGRAM, &[]);
+    let domain = EvaluationDomains::<Fp>::create(8).unwrap();
+    let mut rng = make_test_rng(None);
+    let chunk = run_chunk(
+        &mut env,
+        domain,
+        &VmConfiguration::default(),
+        &None,
+        &Start::create(0),
+        &mut rng,
+    );
+    let mut constraints_env = mips_constraints::Env::<Fp>::default();
+    let witness = &chunk.inputs.evaluations;
+    let report = constraints_env.coverage(witness);
+    let rows: usize = report.executed.iter().map(|i| i.rows).sum();
+    assert_eq!(rows, witness.selector.len());
+    let exit = report
+        .executed
+        .iter()
+        .find(|i| i.instruction == Instruction::RType(RTypeInstruction::SyscallExitGroup))
+        .unwrap();
+    assert!(exit.constraints > 0);
+    assert!(!exit.written.is_empty());
+    assert!(report.unexercised.len() > report.executed.len());
+    assert!(!report.never_written.is_empty());
+    assert!(report.active_constraints() >= exit.constraints);
+    let rendered = report.to_string();
+    assert!(rendered.starts_with(&format!(
+        "{} of {} instructions executed\n",
+        report.executed.len(),
+        report.executed.len() + report.unexercised.len()
+    )));
+    assert!(rendered.contains(&format!(
+        "  RType(SyscallExitGroup): {} rows, {} constraints, ",
+        exit.rows, exit.constraints
+    )));
+}
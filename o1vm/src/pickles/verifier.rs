@@ -4,7 +4,7 @@ use rand::thread_rng;
 
 use kimchi::{
     circuits::{
-        berkeley_columns::BerkeleyChallenges,
+        berkeley_columns::{BerkeleyChallengeTerm, BerkeleyChallenges},
         domains::EvaluationDomains,
         expr::{ColumnEvaluations, Constants, Expr, ExprError, PolishToken},
         gate::CurrOrNext,
@@ -27,11 +27,11 @@ use super::{
     column_env::{get_all_columns, RelationColumnType},
     proof::{Proof, WitnessColumns},
 };
-use crate::{interpreters::mips::column::N_MIPS_SEL_COLS, E};
+use crate::E;
 use kimchi_msm::columns::Column;
 
-type CommitmentColumns<G> = WitnessColumns<PolyComm<G>, [PolyComm<G>; N_MIPS_SEL_COLS]>;
-type EvaluationColumns<F> = WitnessColumns<F, [F; N_MIPS_SEL_COLS]>;
+type CommitmentColumns<G> = WitnessColumns<PolyComm<G>, Vec<PolyComm<G>>>;
+type EvaluationColumns<F> = WitnessColumns<F, Vec<F>>;
 
 struct ColumnEval<'a, G: AffineRepr> {
     commitment: &'a CommitmentColumns<G>,
@@ -62,6 +62,8 @@ impl<G: AffineRepr> ColumnEvaluations<G::ScalarField> for ColumnEval<'_, G> {
     }
 }
 
+type PolishConstraints<F> = [PolishToken<F, Column<RelationColumnType>, BerkeleyChallengeTerm>];
+
 pub fn verify<
     G: KimchiCurve,
     EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
@@ -72,6 +74,61 @@ pub fn verify<
     constraints: &[E<G::ScalarField>],
     proof: &Proof<G>,
 ) -> bool
+where
+    <G as AffineRepr>::BaseField: PrimeField,
+{
+    verify_batch::<G, EFqSponge, EFrSponge>(domain, srs, constraints, &[proof])
+}
+
+/// Verifies the proofs `proofs`, made over the domain `domain` for the constraints `constraints`.
+///
+/// The opening proofs are checked together, with a single multi-scalar
+/// multiplication, instead of one for each proof, which dominates the cost of
+/// the verification. A batch is only known to be invalid as a whole: the
+/// invalid proofs must be found by verifying them one by one.
+///
+/// This is not an aggregation: each proof is still given to the verifier, and
+/// the quotients are checked proof by proof, so that the cost of verifying a
+/// batch remains linear in its size.
+pub fn verify_batch<
+    G: KimchiCurve,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+>(
+    domain: EvaluationDomains<G::ScalarField>,
+    srs: &<OpeningProof<G> as OpenProof<G>>::SRS,
+    constraints: &[E<G::ScalarField>],
+    proofs: &[&Proof<G>],
+) -> bool
+where
+    <G as AffineRepr>::BaseField: PrimeField,
+{
+    let combined_expr =
+        Expr::combine_constraints(0..(constraints.len() as u32), constraints.to_vec());
+    let polish = combined_expr.to_polish();
+    let batch: Option<Vec<_>> = proofs
+        .iter()
+        .map(|proof| prepare_opening::<G, EFqSponge, EFrSponge>(domain, &polish, proof))
+        .collect();
+    let group_map = G::Map::setup();
+    batch.is_some_and(|mut batch| {
+        OpeningProof::verify(srs, &group_map, &mut batch, &mut thread_rng())
+    })
+}
+
+/// Checks the quotient of the proof `proof` against the combination `polish`
+/// of the constraints, and returns its opening proof, to be verified by the
+/// caller, or `None` if the quotient is invalid
+fn prepare_opening<
+    'a,
+    G: KimchiCurve,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+>(
+    domain: EvaluationDomains<G::ScalarField>,
+    polish: &PolishConstraints<G::ScalarField>,
+    proof: &'a Proof<G>,
+) -> Option<BatchEvaluationProof<'a, G, EFqSponge, OpeningProof<G>>>
 where
     <G as AffineRepr>::BaseField: PrimeField,
 {
@@ -197,11 +254,8 @@ where
         zk_rows: 0,
     };
 
-    let combined_expr =
-        Expr::combine_constraints(0..(constraints.len() as u32), constraints.to_vec());
-
     let numerator_zeta = PolishToken::evaluate(
-        combined_expr.to_polish().as_slice(),
+        polish,
         domain.d1,
         zeta,
         &column_eval,
@@ -215,25 +269,28 @@ where
     let u_chal = fr_sponge.challenge();
     let u = u_chal.to_field(endo_r);
 
-    let mut evaluations: Vec<_> = get_all_columns(column_eval.commitment.lookup_state.len())
-        .into_iter()
-        .map(|column| {
-            let commitment = column_eval
-                .commitment
-                .get_column(&column)
-                .unwrap_or_else(|| panic!("Could not get `commitment` for `Evaluation`"))
-                .clone();
+    let mut evaluations: Vec<_> = get_all_columns(
+        column_eval.commitment.lookup_state.len(),
+        column_eval.commitment.selector.len(),
+    )
+    .into_iter()
+    .map(|column| {
+        let commitment = column_eval
+            .commitment
+            .get_column(&column)
+            .unwrap_or_else(|| panic!("Could not get `commitment` for `Evaluation`"))
+            .clone();
 
-            let evaluations = column_eval
-                .evaluate(column)
-                .unwrap_or_else(|_| panic!("Could not get `evaluations` for `Evaluation`"));
+        let evaluations = column_eval
+            .evaluate(column)
+            .unwrap_or_else(|_| panic!("Could not get `evaluations` for `Evaluation`"));
 
-            Evaluation {
-                commitment,
-                evaluations: vec![vec![evaluations.zeta], vec![evaluations.zeta_omega]],
-            }
-        })
-        .collect();
+        Evaluation {
+            commitment,
+            evaluations: vec![vec![evaluations.zeta], vec![evaluations.zeta_omega]],
+        }
+    })
+    .collect();
 
     evaluations.push(Evaluation {
         commitment: proof.quotient_commitment.clone(),
@@ -262,8 +319,6 @@ where
         combined_inner_product,
     };
 
-    let group_map = G::Map::setup();
-
     // Check the actual quotient works.
     let (quotient_zeta, _) = quotient_evaluations.zeta.iter().fold(
         (G::ScalarField::zero(), G::ScalarField::one()),
@@ -274,5 +329,5 @@ where
         },
     );
     (quotient_zeta == numerator_zeta / (zeta.pow([domain.d1.size]) - G::ScalarField::one()))
-        && OpeningProof::verify(srs, &group_map, &mut [batch], &mut thread_rng())
+        .then_some(batch)
 }
@@ -1,16 +1,29 @@
+use crate::cannon::{Hint, Preimage};
+#[cfg(not(target_arch = "wasm32"))]
 use crate::cannon::{
-    Hint, HostProgram, Preimage, HINT_CLIENT_READ_FD, HINT_CLIENT_WRITE_FD,
-    PREIMAGE_CLIENT_READ_FD, PREIMAGE_CLIENT_WRITE_FD,
+    HostProgram, HINT_CLIENT_READ_FD, HINT_CLIENT_WRITE_FD, PREIMAGE_CLIENT_READ_FD,
+    PREIMAGE_CLIENT_WRITE_FD,
 };
+#[cfg(not(target_arch = "wasm32"))]
 use command_fds::{CommandFdExt, FdMapping};
+#[cfg(not(target_arch = "wasm32"))]
 use log::debug;
+#[cfg(not(target_arch = "wasm32"))]
 use os_pipe::{PipeReader, PipeWriter};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use std::{collections::HashMap, path::PathBuf};
+#[cfg(not(target_arch = "wasm32"))]
 use std::{
     io::{Read, Write},
     os::fd::{AsRawFd, FromRawFd, OwnedFd},
     process::{Child, Command},
 };
 
+// The preimage oracle runs as a separate process, which is not available when
+// compiling to WebAssembly. Only the interface is kept here, for the
+// interpreters.
+#[cfg(not(target_arch = "wasm32"))]
 pub struct PreImageOracle {
     pub cmd: Command,
     pub oracle_client: RW,
@@ -23,6 +36,24 @@ pub trait PreImageOracleT {
     fn get_preimage(&mut self, key: [u8; 32]) -> Preimage;
 
     fn hint(&mut self, hint: Hint);
+
+    /// Called with the bytes `bytes` read by the program from the stream
+    /// `stream` given by the host, and returns the bytes the program must
+    /// use. The oracles record or replay them, the others return them
+    /// unchanged.
+    fn read_stream(&mut self, _stream: HostStream, bytes: Vec<u8>) -> Vec<u8> {
+        bytes
+    }
+}
+
+/// The streams given by the host to the program, besides the preimages and
+/// the hints
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HostStream {
+    /// The random bytes returned by `getrandom`
+    Random,
+    /// The advice given by the prover
+    Advice,
 }
 
 pub struct ReadWrite<R, W> {
@@ -30,6 +61,7 @@ pub struct ReadWrite<R, W> {
     pub writer: W,
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 pub struct RW(pub ReadWrite<PipeReader, PipeWriter>);
 
 // Here, we implement `os_pipe::pipe` in a way that allows us to pass flags. In particular, we
@@ -37,7 +69,13 @@ pub struct RW(pub ReadWrite<PipeReader, PipeWriter>);
 // `DIRECT` to handle writes as single atomic operations (up to splitting at the buffer size).
 // This fixes the IPC hangs. This is bad, but the hang is worse.
 
-#[cfg(not(any(target_os = "ios", target_os = "macos", target_os = "haiku", windows)))]
+#[cfg(not(any(
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "haiku",
+    target_arch = "wasm32",
+    windows
+)))]
 fn create_pipe() -> std::io::Result<(PipeReader, PipeWriter)> {
     let mut fds: [libc::c_int; 2] = [0; 2];
     let res = unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_DIRECT) };
@@ -90,6 +128,7 @@ pub fn create_pipe() -> std::io::Result<(PipeReader, PipeWriter)> {
 //     |     ar  <---- bw    |
 //     |     aw  ----> br    |
 //
+#[cfg(not(target_arch = "wasm32"))]
 pub fn create_bidirectional_channel() -> Option<(RW, RW)> {
     let (ar, bw) = create_pipe().ok()?;
     let (br, aw) = create_pipe().ok()?;
@@ -105,6 +144,7 @@ pub fn create_bidirectional_channel() -> Option<(RW, RW)> {
     ))
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl PreImageOracle {
     pub fn create(host_program: HostProgram) -> PreImageOracle {
         let mut cmd = Command::new(&host_program.name);
@@ -167,6 +207,78 @@ impl PreImageOracleT for NullPreImageOracle {
     }
 }
 
+/// The type of the preimage keys given by the Keccak-256 hash of the preimage,
+/// stored in their first byte
+pub const KECCAK_256_KEY_TYPE: u8 = 2;
+
+/// A preimage oracle answering from preimages held in memory, to embed the
+/// o1vm in a program providing them. The hints are ignored.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryPreImageOracle {
+    pub preimages: HashMap<[u8; 32], Vec<u8>>,
+}
+
+impl MemoryPreImageOracle {
+    pub fn insert(&mut self, key: [u8; 32], preimage: Vec<u8>) {
+        self.preimages.insert(key, preimage);
+    }
+
+    /// Adds the preimage `preimage` under its Keccak-256 key, which is
+    /// returned
+    pub fn insert_keccak(&mut self, preimage: Vec<u8>) -> [u8; 32] {
+        let mut key: [u8; 32] = Keccak256::digest(&preimage).into();
+        key[0] = KECCAK_256_KEY_TYPE;
+        self.insert(key, preimage);
+        key
+    }
+}
+
+impl PreImageOracleT for MemoryPreImageOracle {
+    fn get_preimage(&mut self, key: [u8; 32]) -> Preimage {
+        let preimage = self
+            .preimages
+            .get(&key)
+            .unwrap_or_else(|| panic!("No preimage for the key {}", hex::encode(key)));
+        Preimage::create(preimage.clone())
+    }
+
+    fn hint(&mut self, _hint: Hint) {}
+}
+
+/// A preimage oracle reading the preimages from a directory, in the layout of
+/// the key-value store of the OP program on disk: the preimage of the key `k`
+/// is hex-encoded in the file `<hex of k>.txt`. The hints are ignored, the
+/// directory having to hold all the preimages read by the program.
+#[derive(Clone, Debug)]
+pub struct DiskPreImageOracle {
+    pub dir: PathBuf,
+}
+
+impl DiskPreImageOracle {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        DiskPreImageOracle { dir: dir.into() }
+    }
+
+    /// Returns the path of the file holding the preimage of the key `key`
+    pub fn path(&self, key: [u8; 32]) -> PathBuf {
+        self.dir.join(format!("{}.txt", hex::encode(key)))
+    }
+}
+
+impl PreImageOracleT for DiskPreImageOracle {
+    fn get_preimage(&mut self, key: [u8; 32]) -> Preimage {
+        let path = self.path(key);
+        let contents = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Could not read the preimage {}: {e}", path.display()));
+        let preimage = hex::decode(contents.trim())
+            .unwrap_or_else(|e| panic!("Invalid preimage in {}: {e}", path.display()));
+        Preimage::create(preimage)
+    }
+
+    fn hint(&mut self, _hint: Hint) {}
+}
+
+#[cfg(not(target_arch = "wasm32"))]
 impl PreImageOracleT for PreImageOracle {
     // The preimage protocol goes as follows
     // 1. Ask for data through a key
@@ -242,9 +354,165 @@ impl PreImageOracleT for Box<dyn PreImageOracleT> {
     fn hint(&mut self, hint: Hint) {
         self.as_mut().hint(hint)
     }
+
+    fn read_stream(&mut self, stream: HostStream, bytes: Vec<u8>) -> Vec<u8> {
+        self.as_mut().read_stream(stream, bytes)
+    }
+}
+
+/// An interaction of the program with the host, recorded in a transcript by
+/// [RecordingOracle] and replayed by [ReplayOracle]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TranscriptEntry {
+    Preimage {
+        #[serde(with = "hex")]
+        key: [u8; 32],
+        #[serde(with = "hex")]
+        preimage: Vec<u8>,
+    },
+    Hint(#[serde(with = "hex")] Vec<u8>),
+    Stream {
+        stream: HostStream,
+        #[serde(with = "hex")]
+        bytes: Vec<u8>,
+    },
+}
+
+/// Reads a transcript written by a [RecordingOracle], one JSON entry per line
+pub fn read_transcript<R: std::io::BufRead>(reader: R) -> std::io::Result<Vec<TranscriptEntry>> {
+    reader
+        .lines()
+        .filter(|line| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+        .collect()
 }
 
-#[cfg(test)]
+/// A preimage oracle forwarding the requests to the oracle `oracle`, and
+/// writing the requests with their responses to `writer`, one JSON entry per
+/// line. Each entry is flushed as soon as it is written, so that the
+/// transcript of a run ending with a crash is complete.
+pub struct RecordingOracle<O, W> {
+    pub oracle: O,
+    pub writer: W,
+}
+
+impl<O, W: std::io::Write> RecordingOracle<O, W> {
+    pub fn new(oracle: O, writer: W) -> Self {
+        RecordingOracle { oracle, writer }
+    }
+
+    fn record(&mut self, entry: &TranscriptEntry) {
+        serde_json::to_writer(&mut self.writer, entry)
+            .map_err(std::io::Error::from)
+            .and_then(|()| writeln!(self.writer))
+            .and_then(|()| self.writer.flush())
+            .expect("Could not write the transcript entry");
+    }
+}
+
+impl<O: PreImageOracleT, W: std::io::Write> PreImageOracleT for RecordingOracle<O, W> {
+    fn get_preimage(&mut self, key: [u8; 32]) -> Preimage {
+        let preimage = self.oracle.get_preimage(key).get();
+        self.record(&TranscriptEntry::Preimage {
+            key,
+            preimage: preimage.clone(),
+        });
+        Preimage::create(preimage)
+    }
+
+    fn hint(&mut self, hint: Hint) {
+        let hint = hint.get();
+        self.record(&TranscriptEntry::Hint(hint.clone()));
+        self.oracle.hint(Hint::create(hint))
+    }
+
+    fn read_stream(&mut self, stream: HostStream, bytes: Vec<u8>) -> Vec<u8> {
+        let bytes = self.oracle.read_stream(stream, bytes);
+        self.record(&TranscriptEntry::Stream {
+            stream,
+            bytes: bytes.clone(),
+        });
+        bytes
+    }
+}
+
+/// A preimage oracle answering the requests from a transcript recorded by a
+/// [RecordingOracle], so that a run can be reproduced without the host. The
+/// requests must be the ones of the transcript, in the same order. The bytes
+/// read from the streams of the host are the recorded ones, whatever the
+/// program was given.
+pub struct ReplayOracle {
+    transcript: std::vec::IntoIter<TranscriptEntry>,
+    /// The number of entries replayed so far
+    pub position: usize,
+}
+
+impl ReplayOracle {
+    pub fn new(transcript: Vec<TranscriptEntry>) -> Self {
+        ReplayOracle {
+            transcript: transcript.into_iter(),
+            position: 0,
+        }
+    }
+
+    fn next_entry(&mut self) -> TranscriptEntry {
+        let entry = self.transcript.next().unwrap_or_else(|| {
+            panic!(
+                "The execution diverged from the transcript: no entry left after {} entries",
+                self.position
+            )
+        });
+        self.position += 1;
+        entry
+    }
+}
+
+impl PreImageOracleT for ReplayOracle {
+    fn get_preimage(&mut self, key: [u8; 32]) -> Preimage {
+        match self.next_entry() {
+            TranscriptEntry::Preimage {
+                key: recorded_key,
+                preimage,
+            } if recorded_key == key => Preimage::create(preimage),
+            entry => panic!(
+                "The execution diverged from the transcript at entry {}: requested the preimage of {}, recorded {:?}",
+                self.position - 1,
+                hex::encode(key),
+                entry
+            ),
+        }
+    }
+
+    fn hint(&mut self, hint: Hint) {
+        let hint = hint.get();
+        match self.next_entry() {
+            TranscriptEntry::Hint(recorded) if recorded == hint => (),
+            entry => panic!(
+                "The execution diverged from the transcript at entry {}: hinted {}, recorded {:?}",
+                self.position - 1,
+                hex::encode(hint),
+                entry
+            ),
+        }
+    }
+
+    fn read_stream(&mut self, stream: HostStream, _bytes: Vec<u8>) -> Vec<u8> {
+        match self.next_entry() {
+            TranscriptEntry::Stream {
+                stream: recorded_stream,
+                bytes,
+            } if recorded_stream == stream => bytes,
+            entry => panic!(
+                "The execution diverged from the transcript at entry {}: read the stream {:?}, recorded {:?}",
+                self.position - 1,
+                stream,
+                entry
+            ),
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
 mod tests {
     use super::*;
 
@@ -319,4 +587,63 @@ mod tests {
         assert_eq!(n, len);
         assert_eq!(data, msg2);
     }
+
+    /// An oracle returning the key as preimage
+    struct EchoOracle;
+
+    impl PreImageOracleT for EchoOracle {
+        fn get_preimage(&mut self, key: [u8; 32]) -> Preimage {
+            Preimage::create(key.to_vec())
+        }
+
+        fn hint(&mut self, _hint: Hint) {}
+    }
+
+    fn record_run<O: PreImageOracleT>(oracle: &mut O) -> Vec<Vec<u8>> {
+        oracle.hint(Hint::create(b"l1-block".to_vec()));
+        (0..3u8)
+            .map(|i| oracle.get_preimage([i; 32]).get())
+            .collect()
+    }
+
+    #[test]
+    fn test_transcript_replay() {
+        let mut recording = RecordingOracle::new(EchoOracle, vec![]);
+        let preimages = record_run(&mut recording);
+        let transcript = read_transcript(recording.writer.as_slice()).unwrap();
+        assert_eq!(transcript.len(), 4);
+
+        let mut replay = ReplayOracle::new(transcript);
+        assert_eq!(record_run(&mut replay), preimages);
+        assert_eq!(replay.position, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "diverged from the transcript at entry 1")]
+    fn test_transcript_replay_divergence() {
+        let mut recording = RecordingOracle::new(EchoOracle, vec![]);
+        record_run(&mut recording);
+        let transcript = read_transcript(recording.writer.as_slice()).unwrap();
+
+        let mut replay = ReplayOracle::new(transcript);
+        replay.hint(Hint::create(b"l1-block".to_vec()));
+        replay.get_preimage([1; 32]);
+    }
+
+    #[test]
+    fn test_memory_and_disk_oracles() {
+        let mut memory = MemoryPreImageOracle::default();
+        let key = memory.insert_keccak(b"hello".to_vec());
+        assert_eq!(key[0], KECCAK_256_KEY_TYPE);
+        assert_eq!(key[1..], Keccak256::digest(b"hello")[1..]);
+        assert_eq!(memory.get_preimage(key).get(), b"hello");
+
+        let dir = std::env::temp_dir().join(format!("o1vm-preimages-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut disk = DiskPreImageOracle::new(&dir);
+        std::fs::write(disk.path(key), hex::encode(b"hello")).unwrap();
+        let preimage = disk.get_preimage(key).get();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(preimage, b"hello");
+    }
 }
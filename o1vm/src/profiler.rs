@@ -0,0 +1,195 @@
+//! Profiling of the programs run by the virtual machines, to find where the
+//! cycles, and therefore the proving cost, are spent.
+//!
+//! The [Profiler] records the instructions executed, attributing each of them
+//! to the function containing it, given by the symbols of the ELF file of the
+//! program (see [crate::elf_loader::parse_symbols]), and the memory accesses.
+//! The calls and returns are followed to rebuild the call stacks, which can be
+//! exported as folded stacks to draw a flamegraph, see
+//! [ProfileReport::write_folded_stacks].
+
+use crate::memory::Address;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+};
+
+/// The functions of a program, with the range of addresses of their code
+#[derive(Clone, Debug, Default)]
+pub struct Symbols {
+    /// The name and the size of the functions, indexed by their address
+    functions: BTreeMap<u32, (u32, String)>,
+}
+
+impl Symbols {
+    /// Builds the symbols from the address, size and name of the functions
+    pub fn new<I: IntoIterator<Item = (u32, u32, String)>>(functions: I) -> Self {
+        Symbols {
+            functions: functions
+                .into_iter()
+                .map(|(addr, size, name)| (addr, (size, name)))
+                .collect(),
+        }
+    }
+
+    /// Returns the name of the function containing the address `addr`. A
+    /// function with no size is supposed to extend up to the next one.
+    pub fn function_at(&self, addr: u32) -> Option<&str> {
+        let (start, (size, name)) = self.functions.range(..=addr).next_back()?;
+        (*size == 0 || addr - start < *size).then_some(name.as_str())
+    }
+}
+
+/// The effect of an instruction on the call stack
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ControlFlow {
+    /// The instruction calls the function at the next instruction pointer
+    Call,
+    /// The instruction returns to the caller
+    Return,
+    Other,
+}
+
+/// The profile of an execution
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileReport {
+    /// The number of instructions executed
+    pub cycles: u64,
+    /// The number of times each instruction was executed, by mnemonic
+    pub instructions: BTreeMap<String, u64>,
+    /// The cycles spent in each function, excluding the functions it calls
+    pub functions: BTreeMap<String, u64>,
+    /// The number of memory accesses to each page, by index
+    pub pages: BTreeMap<u32, u64>,
+    /// The cycles spent in each call stack, given by the names of the
+    /// functions separated by `;`, starting from the outermost one
+    pub stacks: BTreeMap<String, u64>,
+}
+
+impl ProfileReport {
+    /// Writes the call stacks in the folded format, one stack followed by its
+    /// number of cycles per line, as read by the flamegraph tools
+    pub fn write_folded_stacks<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for (stack, cycles) in self.stacks.iter() {
+            writeln!(writer, "{stack} {cycles}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Records the execution of a program
+#[derive(Clone, Debug, Default)]
+pub struct Profiler {
+    symbols: Symbols,
+    /// The functions being executed, starting from the outermost one
+    stack: Vec<String>,
+    /// Set by a call, so that the function reached by the next instruction is
+    /// pushed on the stack
+    pending_call: bool,
+    report: ProfileReport,
+}
+
+impl Profiler {
+    pub fn new(symbols: Symbols) -> Self {
+        Profiler {
+            symbols,
+            ..Profiler::default()
+        }
+    }
+
+    /// Records the execution of the instruction `mnemonic` at address
+    /// `instruction_pointer`
+    pub fn record_instruction(
+        &mut self,
+        instruction_pointer: u32,
+        mnemonic: &str,
+        control_flow: ControlFlow,
+    ) {
+        let function = match self.symbols.function_at(instruction_pointer) {
+            Some(name) => name.to_string(),
+            None => format!("{instruction_pointer:#x}"),
+        };
+        if self.pending_call || self.stack.is_empty() {
+            self.stack.push(function.clone());
+        } else if self.stack.last() != Some(&function) {
+            // A jump to another function without a call, like a tail call,
+            // replaces the current function
+            *self.stack.last_mut().unwrap() = function.clone();
+        }
+        self.pending_call = control_flow == ControlFlow::Call;
+
+        self.report.cycles += 1;
+        *self
+            .report
+            .instructions
+            .entry(mnemonic.to_string())
+            .or_default() += 1;
+        *self.report.functions.entry(function).or_default() += 1;
+        *self.report.stacks.entry(self.stack.join(";")).or_default() += 1;
+
+        if control_flow == ControlFlow::Return && self.stack.len() > 1 {
+            self.stack.pop();
+        }
+    }
+
+    /// Records an access to the memory at address `addr`
+    pub fn record_memory_access(&mut self, addr: u32) {
+        *self.report.pages.entry(addr.page()).or_default() += 1;
+    }
+
+    pub fn report(&self) -> &ProfileReport {
+        &self.report
+    }
+
+    pub fn into_report(self) -> ProfileReport {
+        self.report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_call_stacks() {
+        let symbols = Symbols::new([
+            (0x100, 0x10, "main".to_string()),
+            (0x200, 0x10, "f".to_string()),
+            (0x300, 0, "g".to_string()),
+        ]);
+        assert_eq!(symbols.function_at(0x10F), Some("main"));
+        assert_eq!(symbols.function_at(0x110), None);
+        assert_eq!(symbols.function_at(0x400), Some("g"));
+
+        let mut profiler = Profiler::new(symbols);
+        for (pc, mnemonic, control_flow) in [
+            (0x100, "jal", ControlFlow::Call),
+            (0x200, "jal", ControlFlow::Call),
+            (0x200, "addi", ControlFlow::Other),
+            (0x204, "jalr", ControlFlow::Return),
+            (0x204, "jal", ControlFlow::Other),
+            (0x300, "jalr", ControlFlow::Return),
+            (0x104, "ecall", ControlFlow::Other),
+            (0x120, "ecall", ControlFlow::Other),
+        ] {
+            profiler.record_instruction(pc, mnemonic, control_flow);
+        }
+        profiler.record_memory_access(0x1FFF);
+        profiler.record_memory_access(0x1000);
+
+        let report = profiler.into_report();
+        assert_eq!(report.cycles, 8);
+        assert_eq!(report.instructions["jal"], 3);
+        assert_eq!(report.functions["main"], 2);
+        assert_eq!(report.functions["g"], 1);
+        assert_eq!(report.functions["0x120"], 1);
+        assert_eq!(report.pages, BTreeMap::from([(1, 2)]));
+        let mut folded = Vec::new();
+        report.write_folded_stacks(&mut folded).unwrap();
+        assert_eq!(
+            String::from_utf8(folded).unwrap(),
+            "0x120 1\nmain 2\nmain;f 2\nmain;f;f 2\nmain;g 1\n"
+        );
+    }
+}
@@ -15,7 +15,7 @@ pub fn main(args: cli::cannon::RunArgs) -> ExitCode {
     use rand::Rng;
 
     let configuration: VmConfiguration = args.vm_cfg.into();
-    let preimage_db_dir = args.preimage_db_dir;
+    let preimage_db_dir = args.oracle.preimage_db_dir;
 
     if let Some(preimage_key_dir) = preimage_db_dir {
         let host_program = configuration.host.expect("No host program specified");
@@ -0,0 +1,51 @@
+//! JavaScript bindings to verify the proofs of executions of the pickles
+//! flavor of the o1vm, for light clients and browser dashboards. Only the
+//! verifier is exposed.
+//!
+//! The SRS is given serialized with MessagePack, and its size sets the size of
+//! the domain of the proofs. The proofs are given as an [EncodedProof]
+//! serialized with MessagePack.
+//!
+//! ```js
+//! const verifier = new ExecutionProofVerifier(srsBytes);
+//! verifier.verify(proofBytes); // throws if the proof is rejected
+//! ```
+
+use crate::{
+    interpreters::mips::constraints,
+    pickles::proof::{O1vmVerifier, Proof},
+};
+use kimchi::circuits::domains::EvaluationDomains;
+use mina_curves::pasta::{Fp, Vesta};
+use o1_utils::proof::{EncodedProof, Proof as _};
+use poly_commitment::{ipa::SRS, SRS as _};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub struct ExecutionProofVerifier(O1vmVerifier<Vesta>);
+
+#[wasm_bindgen]
+impl ExecutionProofVerifier {
+    /// Create a verifier of the MIPS executions from an SRS serialized with
+    /// MessagePack
+    #[wasm_bindgen(constructor)]
+    pub fn new(srs: &[u8]) -> Result<ExecutionProofVerifier, JsError> {
+        let srs: SRS<Vesta> = rmp_serde::from_slice(srs)?;
+        let domain = EvaluationDomains::<Fp>::create(srs.size())
+            .map_err(|_| JsError::new("the size of the SRS is not a valid domain size"))?;
+        Ok(ExecutionProofVerifier(O1vmVerifier {
+            domain,
+            srs,
+            constraints: constraints::get_all_constraints::<Fp>(),
+        }))
+    }
+
+    /// Verify an execution proof, encoded along with its statement.
+    /// An error describing why the proof is rejected is thrown otherwise.
+    pub fn verify(&self, encoded_proof: &[u8]) -> Result<(), JsError> {
+        let encoded: EncodedProof = rmp_serde::from_slice(encoded_proof)?;
+        let (proof, statement) = encoded.decode::<Proof<Vesta>>()?;
+        proof.verify(&self.0, &statement)?;
+        Ok(())
+    }
+}
@@ -14,8 +14,9 @@ fn test_correctly_parsing_elf() {
     // This is the output we get by running objdump -d fibonacci
     assert_eq!(state.pc, 69932);
 
-    // We do have only one page of memory
-    assert_eq!(state.memory.len(), 1);
-    // Which is the 17th
-    assert_eq!(state.memory[0].index, 17);
+    // We do have two pages of memory, the 16th with the headers of the ELF
+    // file, and the 17th with the code
+    assert_eq!(state.memory.len(), 2);
+    assert_eq!(state.memory[0].index, 16);
+    assert_eq!(state.memory[1].index, 17);
 }
@@ -3,6 +3,7 @@ use o1vm::{
     elf_loader::Architecture,
     interpreters::riscv32im::{
         interpreter::{IInstruction, Instruction, RInstruction},
+        layout::{MemoryLayout, MemoryLayoutError},
         registers::RegisterAlias::*,
         witness::Env,
         PAGE_SIZE,
@@ -89,6 +90,44 @@ fn test_fibonacci_7() {
     }
 }
 
+#[test]
+fn test_profile_fibonacci_7() {
+    let curr_dir = std::env::current_dir().unwrap();
+    let path = curr_dir.join(std::path::PathBuf::from(
+        "resources/programs/riscv32im/bin/fibonacci-7",
+    ));
+    let state = o1vm::elf_loader::parse_elf(Architecture::RiscV32, &path).unwrap();
+    let symbols = o1vm::elf_loader::parse_symbols(Architecture::RiscV32, &path).unwrap();
+    let mut witness = Env::<Fp>::create(PAGE_SIZE.try_into().unwrap(), state);
+    assert_eq!(symbols.function_at(0x1117c), Some("_start"));
+    assert_eq!(symbols.function_at(0x110d4), Some("fibonacci"));
+    witness.enable_profiling(symbols);
+
+    while !witness.halt {
+        witness.step();
+    }
+
+    let report = witness.take_profile().unwrap();
+    assert_eq!(report.cycles, witness.normalized_instruction_counter());
+    assert_eq!(report.instructions["ecall"], 1);
+    // This is the output we get by running objdump -d fibonacci-7: 29
+    // instructions of _start are executed, up to the exit
+    assert_eq!(report.functions["_start"], 29);
+    assert_eq!(report.functions.values().sum::<u64>(), report.cycles);
+    assert_eq!(report.stacks.values().sum::<u64>(), report.cycles);
+    assert!(report
+        .stacks
+        .contains_key("_start;fibonacci;fibonacci;fibonacci"));
+    // The registers are saved on the stack
+    assert!(report.pages[&(0x408004f0 >> 12)] > 0);
+
+    let mut folded = Vec::new();
+    report.write_folded_stacks(&mut folded).unwrap();
+    let folded = String::from_utf8(folded).unwrap();
+    assert_eq!(folded.lines().count(), report.stacks.len());
+    assert!(folded.starts_with("_start 29\n_start;fibonacci "));
+}
+
 #[test]
 fn test_sll() {
     let curr_dir = std::env::current_dir().unwrap();
@@ -359,7 +398,6 @@ fn test_slt() {
 }
 
 #[test]
-#[should_panic]
 fn test_div_by_zero() {
     let curr_dir = std::env::current_dir().unwrap();
     let path = curr_dir.join(std::path::PathBuf::from(
@@ -371,10 +409,11 @@ fn test_div_by_zero() {
     while !witness.halt {
         witness.step();
     }
+
+    assert_eq!(witness.registers[T2], u32::MAX);
 }
 
 #[test]
-#[should_panic]
 fn test_divu_by_zero() {
     let curr_dir = std::env::current_dir().unwrap();
     let path = curr_dir.join(std::path::PathBuf::from(
@@ -386,10 +425,11 @@ fn test_divu_by_zero() {
     while !witness.halt {
         witness.step();
     }
+
+    assert_eq!(witness.registers[T2], u32::MAX);
 }
 
 #[test]
-#[should_panic]
 fn test_rem_by_zero() {
     let curr_dir = std::env::current_dir().unwrap();
     let path = curr_dir.join(std::path::PathBuf::from(
@@ -401,10 +441,11 @@ fn test_rem_by_zero() {
     while !witness.halt {
         witness.step();
     }
+
+    assert_eq!(witness.registers[T2], 42);
 }
 
 #[test]
-#[should_panic]
 fn test_remu_by_zero() {
     let curr_dir = std::env::current_dir().unwrap();
     let path = curr_dir.join(std::path::PathBuf::from(
@@ -416,6 +457,8 @@ fn test_remu_by_zero() {
     while !witness.halt {
         witness.step();
     }
+
+    assert_eq!(witness.registers[T2], 42);
 }
 
 #[test]
@@ -450,3 +493,102 @@ fn test_jal() {
     assert_eq!(witness.registers[T0], 12);
     assert_eq!(witness.registers[T1], 7);
 }
+
+#[test]
+fn test_custom_memory_layout() {
+    let curr_dir = std::env::current_dir().unwrap();
+    let path = curr_dir.join(std::path::PathBuf::from(
+        "resources/programs/riscv32im/bin/fibonacci-7",
+    ));
+    let state = || o1vm::elf_loader::parse_elf(Architecture::RiscV32, &path).unwrap();
+    let layout = MemoryLayout {
+        code: 0x10000..0x20000,
+        heap_base: 0x30000000,
+        stack_top: 0x20000000,
+        io: Some(0x40000000..0x40001000),
+    };
+    let mut witness =
+        Env::<Fp>::create_with_layout(PAGE_SIZE.try_into().unwrap(), state(), &layout).unwrap();
+    assert_eq!(witness.registers[Sp], 0x20000000);
+    assert_eq!(witness.registers.heap_pointer, 0x30000000);
+    while !witness.halt {
+        witness.step();
+        if witness.registers.current_instruction_pointer == 0x1117c {
+            assert_eq!(witness.registers.general_purpose[10], 13);
+        }
+    }
+
+    for (layout, error) in [
+        (
+            MemoryLayout {
+                code: 0x20000..0x30000,
+                ..layout.clone()
+            },
+            MemoryLayoutError::PageOutsideOfCode(0x10000),
+        ),
+        (
+            MemoryLayout {
+                io: Some(0x11000..0x12000),
+                ..layout.clone()
+            },
+            MemoryLayoutError::PageInReservedRegion(0x11000),
+        ),
+        (
+            MemoryLayout {
+                heap_base: 0x40000800,
+                ..layout.clone()
+            },
+            MemoryLayoutError::InvalidHeapBase(0x40000800),
+        ),
+        (
+            MemoryLayout {
+                stack_top: 0x18000,
+                ..layout.clone()
+            },
+            MemoryLayoutError::InvalidStackTop(0x18000),
+        ),
+    ] {
+        assert_eq!(
+            Env::<Fp>::create_with_layout(PAGE_SIZE.try_into().unwrap(), state(), &layout).err(),
+            Some(error)
+        );
+    }
+}
+
+#[test]
+fn test_commit_log_fibonacci_7() {
+    let curr_dir = std::env::current_dir().unwrap();
+    let path = curr_dir.join(std::path::PathBuf::from(
+        "resources/programs/riscv32im/bin/fibonacci-7",
+    ));
+    let state = o1vm::elf_loader::parse_elf(Architecture::RiscV32, &path).unwrap();
+    let mut witness = Env::<Fp>::create(PAGE_SIZE.try_into().unwrap(), state);
+    let log_path = std::env::temp_dir().join(format!("o1vm-commit-log-{}.txt", std::process::id()));
+    witness.enable_commit_log(Box::new(std::fs::File::create(&log_path).unwrap()));
+
+    while !witness.halt {
+        witness.step();
+    }
+
+    witness.take_commit_log().unwrap().unwrap();
+    let log = std::fs::read_to_string(&log_path).unwrap();
+    std::fs::remove_file(&log_path).unwrap();
+    // Each instruction is logged on two lines
+    assert_eq!(
+        log.lines().count() as u64,
+        2 * witness.normalized_instruction_counter()
+    );
+    let mut lines = log.lines();
+    assert_eq!(
+        lines.next(),
+        Some("core   0: 0x000000000001112c (0xff010113) addi    sp, sp, -16")
+    );
+    assert_eq!(
+        lines.next(),
+        Some("core   0: 0 0x0001112c (0xff010113) x2  0x408004e0")
+    );
+    // The return address stored by the first call to fibonacci, and loaded
+    // back by the last return
+    assert!(log.contains("core   0: 0 0x000110d8 (0x00112623) mem 0x408004dc 0x00011140\n"));
+    assert!(log.contains("core   0: 0 0x00011114 (0x00c12083) x1  0x00011140 mem 0x408004dc\n"));
+}
@@ -17,10 +17,12 @@ use kimchi::{
 };
 use mina_curves::pasta::{Fq, Pallas as GAffine, PallasParameters, Vesta as GAffineOther};
 use mina_poseidon::{constants::PlonkSpongeConstantsKimchi, sponge::DefaultFqSponge};
+use o1_utils::lazy_cache::LazyCache;
 use serde::{Deserialize, Serialize};
 use std::{
     fs::{File, OpenOptions},
     io::{BufReader, BufWriter, Seek, SeekFrom::Start},
+    sync::Arc,
 };
 use wasm_bindgen::prelude::*;
 use wasm_types::FlatVector as WasmFlatVector;
@@ -206,9 +208,10 @@ pub fn caml_pasta_fq_plonk_index_decode(
             .map_err(|e| JsError::new(&format!("caml_pasta_fq_plonk_index_decode: {}", e)))?;
 
     index.srs = srs.0.clone();
-    let (linearization, powers_of_alpha) = expr_linearization(Some(&index.cs.feature_flags), true);
-    index.linearization = linearization;
-    index.powers_of_alpha = powers_of_alpha;
+    index.linearization = Arc::new(LazyCache::preinit(expr_linearization(
+        Some(&index.cs.feature_flags),
+        true,
+    )));
 
     Ok(WasmPastaFqPlonkIndex(Box::new(index)))
 }
@@ -249,9 +252,10 @@ pub fn caml_pasta_fq_plonk_index_read(
     )
     .map_err(|err| JsValue::from_str(&format!("caml_pasta_fq_plonk_index_read: {err}")))?;
     t.srs = srs.0.clone();
-    let (linearization, powers_of_alpha) = expr_linearization(Some(&t.cs.feature_flags), true);
-    t.linearization = linearization;
-    t.powers_of_alpha = powers_of_alpha;
+    t.linearization = Arc::new(LazyCache::preinit(expr_linearization(
+        Some(&t.cs.feature_flags),
+        true,
+    )));
 
     //
     Ok(WasmPastaFqPlonkIndex(Box::new(t)))
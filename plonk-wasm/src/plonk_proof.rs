@@ -192,7 +192,8 @@ macro_rules! impl_proof {
                     LookupCommitments {
                         sorted: x.sorted.iter().map(Into::into).collect(),
                         aggreg: x.aggreg.clone().into(),
-                        runtime: x.runtime.clone().map(Into::into)
+                        runtime: x.runtime.clone().map(Into::into),
+                        multiplicities: None,
                     }
                 }
             }
@@ -202,7 +203,8 @@ macro_rules! impl_proof {
                     LookupCommitments {
                         sorted: x.sorted.into_iter().map(Into::into).collect(),
                         aggreg: x.aggreg.into(),
-                        runtime: x.runtime.map(Into::into)
+                        runtime: x.runtime.map(Into::into),
+                        multiplicities: None,
                     }
                 }
             }
@@ -822,6 +824,10 @@ macro_rules! impl_proof {
                     lookup_gate_lookup_selector: None,
                     range_check_lookup_selector: None,
                     foreign_field_mul_lookup_selector: None,
+                    cairo_selectors: None,
+                    vector_lookup_selector: None,
+                    lookup_multiplicities: None,
+                    poseidon2_selectors: None,
                     public: None,
                 };
 
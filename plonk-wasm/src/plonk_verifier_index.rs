@@ -4,7 +4,7 @@ use ark_ff::One;
 use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as Domain};
 use kimchi::{
     circuits::{
-        constraints::FeatureFlags,
+        constraints::{FeatureFlags, ZkLevel},
         lookup::{
             index::LookupSelectors,
             lookups::{LookupFeatures, LookupInfo, LookupPatterns},
@@ -323,6 +323,7 @@ macro_rules! impl_verification_key {
                         lookup: x.lookup.map(Into::into),
                         range_check: x.range_check.map(Into::into),
                         ffmul: x.ffmul.map(Into::into),
+                        vector_lookup: None,
                     }
                 }
             }
@@ -334,6 +335,7 @@ macro_rules! impl_verification_key {
                         lookup: x.lookup.clone().map(Into::into),
                         range_check: x.range_check.clone().map(Into::into),
                         ffmul: x.ffmul.clone().map(Into::into),
+                        vector_lookup: None,
                     }
                 }
             }
@@ -699,6 +701,14 @@ macro_rules! impl_verification_key {
                     .lookup_index.as_ref()
                     .map_or(false, |li| li.lookup_info.features.patterns.lookup);
 
+                let vector_lookup = index
+                    .lookup_index.as_ref()
+                    .map_or(false, |li| li.lookup_info.features.patterns.vector_lookup);
+
+                let log_up = index
+                    .lookup_index.as_ref()
+                    .map_or(false, |li| li.lookup_info.features.log_up);
+
                 let runtime_tables = index
                     .lookup_index.as_ref()
                     .map_or(false, |li| li.runtime_tables_selector.is_some());
@@ -708,6 +718,7 @@ macro_rules! impl_verification_key {
                     lookup,
                     range_check: range_check0 || range_check1 || rot,
                     foreign_field_mul,
+                    vector_lookup,
                 };
 
                 FeatureFlags {
@@ -721,7 +732,10 @@ macro_rules! impl_verification_key {
                         patterns,
                         joint_lookup_used: patterns.joint_lookups_used(),
                         uses_runtime_tables: runtime_tables,
+                        log_up,
                     },
+                    cairo: false,
+                    poseidon2: false,
                 }
             }
 
@@ -765,6 +779,8 @@ macro_rules! impl_verification_key {
                         foreign_field_add_comm: (&evals.foreign_field_add_comm).as_ref().map(Into::into),
                         foreign_field_mul_comm: (&evals.foreign_field_mul_comm).as_ref().map(Into::into),
                         rot_comm: (&evals.rot_comm).as_ref().map(Into::into),
+                        cairo_comms: None,
+                        poseidon2_comms: None,
 
                         w: {
                             let res = once_cell::sync::OnceCell::new();
@@ -798,6 +814,7 @@ macro_rules! impl_verification_key {
                         linearization,
                         powers_of_alpha,
                         lookup_index: index.lookup_index.map(Into::into),
+                        zk_level: ZkLevel::Full,
                     }
                 };
                 (index, srs.0.clone())
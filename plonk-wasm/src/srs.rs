@@ -275,6 +275,7 @@ pub mod fp {
             h,
             g,
             lagrange_bases: HashMapCache::new(),
+            backend: None,
         };
         Arc::new(srs).into()
     }
@@ -355,6 +356,7 @@ pub mod fq {
             h,
             g,
             lagrange_bases: HashMapCache::new(),
+            backend: None,
         };
         Arc::new(srs).into()
     }
@@ -0,0 +1,238 @@
+//! This module defines the backends computing the multi-scalar
+//! multiplications (MSM) and the fast Fourier transforms (FFT) of the prover.
+//!
+//! Committing to polynomials and moving them between their coefficient and
+//! evaluation forms dominates the proving time of large circuits. These
+//! operations are delegated to a [Backend], made of a [MsmBackend] and of a
+//! [FftBackend]:
+//!
+//! * [CpuBackend], the default one, runs them on the CPU with arkworks,
+//! * with the feature `gpu`, [gpu::GpuBackend] runs them on a device provided
+//!   by an external crate (e.g. with CUDA or Metal), and falls back to the CPU
+//!   for the operations the device does not handle.
+//!
+//! A backend is attached to an [SRS](crate::ipa::SRS) with
+//! [SRS::with_backend](crate::ipa::SRS::with_backend), and is then used by its
+//! commitments and by the prover of kimchi.
+
+use ark_ec::{AffineRepr, VariableBaseMSM};
+use ark_ff::{FftField, PrimeField};
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, Evaluations, Radix2EvaluationDomain as D,
+};
+use std::fmt::Debug;
+
+/// A backend computing multi-scalar multiplications over the curve `G`
+pub trait MsmBackend<G: AffineRepr>: Debug + Send + Sync {
+    /// Computes `sum_i scalars[i] * bases[i]`, with the scalars in their
+    /// big integer representation.
+    /// The bases and the scalars are expected to have the same length.
+    fn msm_bigint(
+        &self,
+        bases: &[G],
+        scalars: &[<G::ScalarField as PrimeField>::BigInt],
+    ) -> G::Group;
+
+    /// Computes `sum_i scalars[i] * bases[i]`.
+    /// The bases and the scalars are expected to have the same length.
+    fn msm(&self, bases: &[G], scalars: &[G::ScalarField]) -> G::Group {
+        let scalars: Vec<_> = scalars.iter().map(|s| s.into_bigint()).collect();
+        self.msm_bigint(bases, &scalars)
+    }
+}
+
+/// A backend computing fast Fourier transforms over the field `F`
+pub trait FftBackend<F: FftField>: Debug + Send + Sync {
+    /// Replaces the coefficients `coeffs` of a polynomial by its evaluations
+    /// over `domain`
+    fn fft_in_place(&self, domain: D<F>, coeffs: &mut Vec<F>);
+
+    /// Replaces the evaluations `evals` of a polynomial over `domain` by its
+    /// coefficients
+    fn ifft_in_place(&self, domain: D<F>, evals: &mut Vec<F>);
+
+    /// Returns the polynomial of evaluations `evals`
+    fn interpolate(&self, evals: Evaluations<F, D<F>>) -> DensePolynomial<F> {
+        let domain = evals.domain();
+        let mut coeffs = evals.evals;
+        self.ifft_in_place(domain, &mut coeffs);
+        DensePolynomial { coeffs }
+    }
+
+    /// Returns the evaluations of `poly` over `domain`
+    fn evaluate_over_domain(
+        &self,
+        poly: &DensePolynomial<F>,
+        domain: D<F>,
+    ) -> Evaluations<F, D<F>> {
+        let mut evals = poly.coeffs.clone();
+        self.fft_in_place(domain, &mut evals);
+        Evaluations::from_vec_and_domain(evals, domain)
+    }
+}
+
+/// A backend computing both the MSMs over the curve `G` and the FFTs over its
+/// scalar field, see [self]
+pub trait Backend<G: AffineRepr>: MsmBackend<G> + FftBackend<G::ScalarField> {
+    /// Returns the backend as a [MsmBackend]
+    fn as_msm(&self) -> &dyn MsmBackend<G>;
+
+    /// Returns the backend as a [FftBackend]
+    fn as_fft(&self) -> &dyn FftBackend<G::ScalarField>;
+}
+
+impl<G: AffineRepr, B: MsmBackend<G> + FftBackend<G::ScalarField>> Backend<G> for B {
+    fn as_msm(&self) -> &dyn MsmBackend<G> {
+        self
+    }
+
+    fn as_fft(&self) -> &dyn FftBackend<G::ScalarField> {
+        self
+    }
+}
+
+/// The default backend, running on the CPU with arkworks
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CpuBackend;
+
+impl<G: AffineRepr> MsmBackend<G> for CpuBackend {
+    fn msm_bigint(
+        &self,
+        bases: &[G],
+        scalars: &[<G::ScalarField as PrimeField>::BigInt],
+    ) -> G::Group {
+        G::Group::msm_bigint(bases, scalars)
+    }
+
+    fn msm(&self, bases: &[G], scalars: &[G::ScalarField]) -> G::Group {
+        G::Group::msm_unchecked(bases, scalars)
+    }
+}
+
+impl<F: FftField> FftBackend<F> for CpuBackend {
+    fn fft_in_place(&self, domain: D<F>, coeffs: &mut Vec<F>) {
+        domain.fft_in_place(coeffs)
+    }
+
+    fn ifft_in_place(&self, domain: D<F>, evals: &mut Vec<F>) {
+        domain.ifft_in_place(evals)
+    }
+
+    fn interpolate(&self, evals: Evaluations<F, D<F>>) -> DensePolynomial<F> {
+        evals.interpolate()
+    }
+
+    fn evaluate_over_domain(
+        &self,
+        poly: &DensePolynomial<F>,
+        domain: D<F>,
+    ) -> Evaluations<F, D<F>> {
+        poly.evaluate_over_domain_by_ref(domain)
+    }
+}
+
+/// The hook for the backends running on a GPU.
+///
+/// This crate does not drive any GPU itself: an external crate implements
+/// [gpu::GpuMsm] and [gpu::GpuFft] for its device, and the prover uses it
+/// through a [gpu::GpuBackend].
+#[cfg(feature = "gpu")]
+pub mod gpu {
+    use super::{CpuBackend, FftBackend, MsmBackend};
+    use ark_ec::AffineRepr;
+    use ark_ff::{FftField, PrimeField};
+    use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as D};
+    use std::fmt::Debug;
+
+    /// The size under which an operation is run on the CPU by default, as
+    /// transferring its inputs to the device costs more than it saves
+    pub const GPU_MIN_SIZE: usize = 1 << 14;
+
+    /// A device running MSMs over the curve `G`, implemented by an external
+    /// crate
+    pub trait GpuMsm<G: AffineRepr>: Debug + Send + Sync {
+        /// Computes `sum_i scalars[i] * bases[i]`, or returns `None` if the
+        /// device cannot run it (e.g. because it is out of memory), in which
+        /// case it is run on the CPU instead
+        fn msm_bigint(
+            &self,
+            bases: &[G],
+            scalars: &[<G::ScalarField as PrimeField>::BigInt],
+        ) -> Option<G::Group>;
+    }
+
+    /// A device running FFTs over the field `F`, implemented by an external
+    /// crate
+    pub trait GpuFft<F: FftField>: Debug + Send + Sync {
+        /// Replaces the coefficients `values` by their evaluations over
+        /// `domain` if `inverse` is false, and the evaluations `values` over
+        /// `domain` by their coefficients otherwise. `values` has the size of
+        /// `domain`.
+        ///
+        /// Returns `None` if the device cannot run it, in which case `values`
+        /// must be left untouched, and the FFT is run on the CPU instead.
+        fn fft_in_place(&self, domain: D<F>, values: &mut [F], inverse: bool) -> Option<()>;
+    }
+
+    /// A backend running the operations of size at least `min_size` on the
+    /// device `Dev`, and the other ones on the CPU
+    #[derive(Clone, Debug)]
+    pub struct GpuBackend<Dev> {
+        /// The device running the operations
+        pub device: Dev,
+        /// The size from which an operation is run on the device
+        pub min_size: usize,
+    }
+
+    impl<Dev> GpuBackend<Dev> {
+        /// Creates a backend running the operations of size at least
+        /// [GPU_MIN_SIZE] on the device `device`
+        pub fn new(device: Dev) -> Self {
+            Self {
+                device,
+                min_size: GPU_MIN_SIZE,
+            }
+        }
+    }
+
+    impl<G: AffineRepr, Dev: GpuMsm<G>> MsmBackend<G> for GpuBackend<Dev> {
+        fn msm_bigint(
+            &self,
+            bases: &[G],
+            scalars: &[<G::ScalarField as PrimeField>::BigInt],
+        ) -> G::Group {
+            if bases.len() >= self.min_size {
+                if let Some(res) = self.device.msm_bigint(bases, scalars) {
+                    return res;
+                }
+            }
+            CpuBackend.msm_bigint(bases, scalars)
+        }
+    }
+
+    impl<F: FftField, Dev: GpuFft<F>> FftBackend<F> for GpuBackend<Dev> {
+        fn fft_in_place(&self, domain: D<F>, coeffs: &mut Vec<F>) {
+            // The device works on vectors of the size of the domain, while
+            // the CPU also accepts polynomials of a smaller degree
+            if domain.size() >= self.min_size && coeffs.len() <= domain.size() {
+                let len = coeffs.len();
+                coeffs.resize(domain.size(), F::zero());
+                if self.device.fft_in_place(domain, coeffs, false).is_some() {
+                    return;
+                }
+                coeffs.truncate(len);
+            }
+            CpuBackend.fft_in_place(domain, coeffs)
+        }
+
+        fn ifft_in_place(&self, domain: D<F>, evals: &mut Vec<F>) {
+            if domain.size() >= self.min_size
+                && evals.len() == domain.size()
+                && self.device.fft_in_place(domain, evals, true).is_some()
+            {
+                return;
+            }
+            CpuBackend.ifft_in_place(domain, evals)
+        }
+    }
+}
@@ -6,9 +6,10 @@
 //!    scaling factor scalar producing the batched opening proof
 //! 3. Verify batch of batched opening proofs
 
+use crate::backend::{CpuBackend, MsmBackend};
 use ark_ec::{
     models::short_weierstrass::Affine as SWJAffine, short_weierstrass::SWCurveConfig, AffineRepr,
-    CurveGroup, VariableBaseMSM,
+    CurveGroup,
 };
 use ark_ff::{BigInteger, Field, One, PrimeField, Zero};
 use ark_poly::univariate::DensePolynomial;
@@ -326,6 +327,20 @@ impl<C: AffineRepr> PolyComm<C> {
     ///
     /// Panics if `com` and `elm` are not of the same size.
     pub fn multi_scalar_mul(com: &[&PolyComm<C>], elm: &[C::ScalarField]) -> Self {
+        Self::multi_scalar_mul_with_backend(com, elm, &CpuBackend)
+    }
+
+    /// Same as [PolyComm::multi_scalar_mul], with the multi-scalar
+    /// multiplications computed by `backend`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `com` and `elm` are not of the same size.
+    pub fn multi_scalar_mul_with_backend(
+        com: &[&PolyComm<C>],
+        elm: &[C::ScalarField],
+        backend: &dyn MsmBackend<C>,
+    ) -> Self {
         assert_eq!(com.len(), elm.len());
 
         if com.is_empty() || elm.is_empty() {
@@ -355,7 +370,7 @@ impl<C: AffineRepr> PolyComm<C> {
                     .into_par_iter()
                     .chunks(subchunk_size)
                     .zip(scalars.into_par_iter().chunks(subchunk_size))
-                    .map(|(psc, ssc)| C::Group::msm_bigint(&psc, &ssc).into_affine())
+                    .map(|(psc, ssc)| backend.msm_bigint(&psc, &ssc).into_affine())
                     .reduce(C::zero, |x, y| (x + y).into())
             })
             .collect();
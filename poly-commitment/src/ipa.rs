@@ -4,6 +4,7 @@
 //! Setting](https://eprint.iacr.org/2016/263)
 
 use crate::{
+    backend::{Backend, CpuBackend, FftBackend, MsmBackend},
     commitment::{
         b_poly, b_poly_coefficients, combine_commitments, shift_scalar, squeeze_challenge,
         squeeze_prechallenge, BatchEvaluationProof, CommitmentCurve, EndoCurve,
@@ -13,7 +14,7 @@ use crate::{
     utils::combine_polys,
     BlindedCommitment, PolyComm, PolynomialsToCombine, SRS as SRSTrait,
 };
-use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ec::{AffineRepr, CurveGroup};
 use ark_ff::{BigInteger, Field, One, PrimeField, UniformRand, Zero};
 use ark_poly::{
     univariate::DensePolynomial, EvaluationDomain, Evaluations, Radix2EvaluationDomain as D,
@@ -30,7 +31,7 @@ use rand::{CryptoRng, RngCore};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
-use std::{cmp::min, iter::Iterator, ops::AddAssign};
+use std::{cmp::min, iter::Iterator, ops::AddAssign, sync::Arc};
 
 #[serde_as]
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -50,6 +51,12 @@ pub struct SRS<G> {
     /// Commitments to Lagrange bases, per domain size
     #[serde(skip)]
     pub lagrange_bases: HashMapCache<usize, Vec<PolyComm<G>>>,
+
+    /// The backend computing the multi-scalar multiplications of the SRS and
+    /// the FFTs of the provers using it, or `None` for [CpuBackend], see
+    /// [crate::backend]
+    #[serde(skip)]
+    pub backend: Option<Arc<dyn Backend<G>>>,
 }
 
 impl<G> PartialEq for SRS<G>
@@ -81,6 +88,51 @@ where
 }
 
 fn point_of_random_bytes<G: CommitmentCurve>(map: &G::Map, random_bytes: &[u8]) -> G
+where
+    G::BaseField: Field,
+{
+    let t = field_of_random_bytes::<G>(random_bytes);
+    let (x, y) = map.to_group(t);
+    G::of_coordinates(x, y).mul_by_cofactor()
+}
+
+/// Same as [point_of_random_bytes] for many inputs, sharing the field
+/// inversions of the group map.
+fn points_of_random_bytes<G: CommitmentCurve>(map: &G::Map, random_bytes: &[Vec<u8>]) -> Vec<G>
+where
+    G::BaseField: Field,
+{
+    let ts: Vec<_> = random_bytes
+        .iter()
+        .map(|bytes| field_of_random_bytes::<G>(bytes))
+        .collect();
+    map.to_group_batch(&ts)
+        .into_iter()
+        .map(|(x, y)| G::of_coordinates(x, y).mul_by_cofactor())
+        .collect()
+}
+
+/// The bytes the `i`-th generator of the SRS is derived from
+fn generator_bytes(i: usize) -> Vec<u8> {
+    let mut h = Blake2b512::new();
+    h.update((i as u32).to_be_bytes());
+    h.finalize().to_vec()
+}
+
+/// The blinding generator `h` of the SRS
+fn blinding_generator<G: CommitmentCurve>(map: &G::Map) -> G
+where
+    G::BaseField: Field,
+{
+    let mut h = Blake2b512::new();
+    h.update("srs_misc".as_bytes());
+    // FIXME: This is for retrocompatibility with a previous version
+    // that was using a list initialisation. It is not necessary.
+    h.update(0_u32.to_be_bytes());
+    point_of_random_bytes(map, &h.finalize())
+}
+
+fn field_of_random_bytes<G: CommitmentCurve>(random_bytes: &[u8]) -> G::BaseField
 where
     G::BaseField: Field,
 {
@@ -106,14 +158,20 @@ where
         base_fields.push(t)
     }
 
-    let t = G::BaseField::from_base_prime_field_elems(base_fields).unwrap();
-
-    let (x, y) = map.to_group(t);
-    G::of_coordinates(x, y).mul_by_cofactor()
+    G::BaseField::from_base_prime_field_elems(base_fields).unwrap()
 }
 
 /// Additional methods for the SRS structure
 impl<G: CommitmentCurve> SRS<G> {
+    /// Returns the SRS with its multi-scalar multiplications, and the FFTs of
+    /// the provers using it, computed by `backend`, see [crate::backend]
+    pub fn with_backend(self, backend: Arc<dyn Backend<G>>) -> Self {
+        Self {
+            backend: Some(backend),
+            ..self
+        }
+    }
+
     /// This function verifies a batch of polynomial commitment opening proofs.
     /// Return `true` if the verification is successful, `false` otherwise.
     pub fn verify<EFqSponge, RNG>(
@@ -122,6 +180,46 @@ impl<G: CommitmentCurve> SRS<G> {
         batch: &mut [BatchEvaluationProof<G, EFqSponge, OpeningProof<G>>],
         rng: &mut RNG,
     ) -> bool
+    where
+        EFqSponge: FqSponge<G::BaseField, G, G::ScalarField>,
+        RNG: RngCore + CryptoRng,
+        G::BaseField: PrimeField,
+    {
+        self.check_openings(group_map, batch, rng, true).is_some()
+    }
+
+    /// Verifies a batch of opening proofs as [Self::verify], except that the
+    /// final commitment `sg` of each proof is not checked to be the
+    /// commitment to the polynomial of its challenges, which is the only part
+    /// of the verification linear in the size of the SRS.
+    ///
+    /// Returns the challenges of each proof if the verification is
+    /// successful. The pairs of challenges and `sg` are then accumulators, to
+    /// be checked later, for instance as the previous challenges of a proof
+    /// which opens them.
+    pub fn verify_deferred<EFqSponge, RNG>(
+        &self,
+        group_map: &G::Map,
+        batch: &mut [BatchEvaluationProof<G, EFqSponge, OpeningProof<G>>],
+        rng: &mut RNG,
+    ) -> Option<Vec<Vec<G::ScalarField>>>
+    where
+        EFqSponge: FqSponge<G::BaseField, G, G::ScalarField>,
+        RNG: RngCore + CryptoRng,
+        G::BaseField: PrimeField,
+    {
+        self.check_openings(group_map, batch, rng, false)
+    }
+
+    /// Checks a batch of opening proofs, with the commitment `sg` of each
+    /// proof if `check_sg` is set, and returns their challenges
+    fn check_openings<EFqSponge, RNG>(
+        &self,
+        group_map: &G::Map,
+        batch: &mut [BatchEvaluationProof<G, EFqSponge, OpeningProof<G>>],
+        rng: &mut RNG,
+        check_sg: bool,
+    ) -> Option<Vec<Vec<G::ScalarField>>>
     where
         EFqSponge: FqSponge<G::BaseField, G, G::ScalarField>,
         RNG: RngCore + CryptoRng,
@@ -159,11 +257,13 @@ impl<G: CommitmentCurve> SRS<G> {
         // TODO: This will need adjusting
         let padding = padded_length - nonzero_length;
         let mut points = vec![self.h];
-        points.extend(self.g.clone());
-        points.extend(vec![G::zero(); padding]);
+        if check_sg {
+            points.extend(self.g.clone());
+            points.extend(vec![G::zero(); padding]);
+        }
 
-        let mut scalars = vec![G::ScalarField::zero(); padded_length + 1];
-        assert_eq!(scalars.len(), points.len());
+        let mut scalars = vec![G::ScalarField::zero(); points.len()];
+        let mut challenges = Vec::with_capacity(batch.len());
 
         // sample randomiser to scale the proofs with
         let rand_base = G::ScalarField::rand(rng);
@@ -209,8 +309,6 @@ impl<G: CommitmentCurve> SRS<G> {
                 res
             };
 
-            let s = b_poly_coefficients(&chal);
-
             let neg_rand_base_i = -rand_base_i;
 
             // TERM
@@ -218,7 +316,11 @@ impl<G: CommitmentCurve> SRS<G> {
             //
             // we also add -sg_rand_base_i * G to check correctness of sg.
             points.push(opening.sg);
-            scalars.push(neg_rand_base_i * opening.z1 - sg_rand_base_i);
+            if check_sg {
+                scalars.push(neg_rand_base_i * opening.z1 - sg_rand_base_i);
+            } else {
+                scalars.push(neg_rand_base_i * opening.z1);
+            }
 
             // Here we add
             // sg_rand_base_i * ( < s, self.g > )
@@ -226,7 +328,8 @@ impl<G: CommitmentCurve> SRS<G> {
             // < sg_rand_base_i s, self.g >
             //
             // to check correctness of the sg component.
-            {
+            if check_sg {
+                let s = b_poly_coefficients(&chal);
                 let terms: Vec<_> = s.par_iter().map(|s| sg_rand_base_i * s).collect();
 
                 for (i, term) in terms.iter().enumerate() {
@@ -277,11 +380,12 @@ impl<G: CommitmentCurve> SRS<G> {
 
             rand_base_i *= &rand_base;
             sg_rand_base_i *= &sg_rand_base;
+            challenges.push(chal);
         }
 
         // Verify the equation in two chunks, which is optimal for our SRS size.
         // (see the comment to the `benchmark_msm_parallel_vesta` MSM benchmark)
-        let chunk_size = points.len() / 2;
+        let chunk_size = core::cmp::max(points.len() / 2, 1);
         let msm_res = points
             .into_par_iter()
             .chunks(chunk_size)
@@ -291,14 +395,14 @@ impl<G: CommitmentCurve> SRS<G> {
                     .into_iter()
                     .map(|c| c.into_bigint())
                     .collect::<Vec<_>>();
-                G::Group::msm_bigint(&bases, &coeffs_bigint)
+                self.msm_backend().msm_bigint(&bases, &coeffs_bigint)
             })
             .reduce(G::Group::zero, |mut l, r| {
                 l += r;
                 l
             });
 
-        msm_res == G::Group::zero()
+        (msm_res == G::Group::zero()).then_some(challenges)
     }
 
     /// This function creates a trusted-setup SRS instance for circuits with
@@ -321,21 +425,30 @@ impl<G: CommitmentCurve> SRS<G> {
             .collect();
 
         // Compute a blinder
-        let h = {
-            let mut h = Blake2b512::new();
-            h.update("srs_misc".as_bytes());
-            // FIXME: This is for retrocompatibility with a previous version
-            // that was using a list initialisation. It is not necessary.
-            h.update(0_u32.to_be_bytes());
-            point_of_random_bytes(&m, &h.finalize())
-        };
+        let h = blinding_generator(&m);
 
         Self {
             g,
             h,
             lagrange_bases: HashMapCache::new(),
+            backend: None,
         }
     }
+
+    /// Check that the generators at the given indices and the blinding
+    /// generator are the ones derived by [SRS::create].
+    /// It allows to check the integrity of an SRS read from an untrusted
+    /// source, without generating it again.
+    pub fn verify_generators(&self, indices: &[usize]) -> bool {
+        let m = G::Map::setup();
+        let random_bytes: Vec<_> = indices.iter().map(|i| generator_bytes(*i)).collect();
+        let expected = points_of_random_bytes::<G>(&m, &random_bytes);
+        self.h == blinding_generator(&m)
+            && indices
+                .iter()
+                .zip(expected)
+                .all(|(i, g)| self.g.get(*i) == Some(&g))
+    }
 }
 
 impl<G: CommitmentCurve> SRS<G>
@@ -350,27 +463,17 @@ where
 
         let g: Vec<_> = (0..depth)
             .into_par_iter()
-            .map(|i| {
-                let mut h = Blake2b512::new();
-                h.update((i as u32).to_be_bytes());
-                point_of_random_bytes(&m, &h.finalize())
-            })
+            .map(|i| point_of_random_bytes(&m, &generator_bytes(i)))
             .collect();
 
         // Compute a blinder
-        let h = {
-            let mut h = Blake2b512::new();
-            h.update("srs_misc".as_bytes());
-            // FIXME: This is for retrocompatibility with a previous version
-            // that was using a list initialisation. It is not necessary.
-            h.update(0_u32.to_be_bytes());
-            point_of_random_bytes(&m, &h.finalize())
-        };
+        let h = blinding_generator(&m);
 
         Self {
             g,
             h,
             lagrange_bases: HashMapCache::new(),
+            backend: None,
         }
     }
 }
@@ -425,13 +528,14 @@ where
         num_chunks: usize,
     ) -> PolyComm<G> {
         let is_zero = plnm.is_zero();
+        let backend = self.msm_backend();
 
         // chunk while committing
         let mut chunks: Vec<_> = if is_zero {
             vec![G::zero()]
         } else if plnm.len() < self.g.len() {
-            vec![G::Group::msm(&self.g[..plnm.len()], &plnm.coeffs)
-                .unwrap()
+            vec![backend
+                .msm(&self.g[..plnm.len()], &plnm.coeffs)
                 .into_affine()]
         } else if plnm.len() == self.g.len() {
             // when processing a single chunk, it's faster to parallelise
@@ -439,8 +543,8 @@ where
             // `benchmark_msm_parallel_vesta` MSM benchmark)
             let n = self.g.len();
             let (r1, r2) = rayon::join(
-                || G::Group::msm(&self.g[..n / 2], &plnm.coeffs[..n / 2]).unwrap(),
-                || G::Group::msm(&self.g[n / 2..n], &plnm.coeffs[n / 2..n]).unwrap(),
+                || backend.msm(&self.g[..n / 2], &plnm.coeffs[..n / 2]),
+                || backend.msm(&self.g[n / 2..n], &plnm.coeffs[n / 2..n]),
             );
 
             vec![(r1 + r2).into_affine()]
@@ -453,7 +557,7 @@ where
                         .into_iter()
                         .map(|c| c.into_bigint())
                         .collect::<Vec<_>>();
-                    let chunk_res = G::Group::msm_bigint(&self.g, &chunk_coeffs);
+                    let chunk_res = backend.msm_bigint(&self.g, &chunk_coeffs);
                     chunk_res.into_affine()
                 })
                 .collect()
@@ -491,7 +595,11 @@ where
     ) -> PolyComm<G> {
         let basis = self.get_lagrange_basis(domain);
         let commit_evaluations = |evals: &Vec<G::ScalarField>, basis: &Vec<PolyComm<G>>| {
-            PolyComm::<G>::multi_scalar_mul(&basis.iter().collect::<Vec<_>>()[..], &evals[..])
+            PolyComm::<G>::multi_scalar_mul_with_backend(
+                &basis.iter().collect::<Vec<_>>()[..],
+                &evals[..],
+                self.msm_backend(),
+            )
         };
         match domain.size.cmp(&plnm.domain().size) {
             std::cmp::Ordering::Less => {
@@ -527,28 +635,17 @@ where
     fn create(depth: usize) -> Self {
         let m = G::Map::setup();
 
-        let g: Vec<_> = (0..depth)
-            .map(|i| {
-                let mut h = Blake2b512::new();
-                h.update((i as u32).to_be_bytes());
-                point_of_random_bytes(&m, &h.finalize())
-            })
-            .collect();
+        let random_bytes: Vec<_> = (0..depth).map(generator_bytes).collect();
+        let g = points_of_random_bytes(&m, &random_bytes);
 
         // Compute a blinder
-        let h = {
-            let mut h = Blake2b512::new();
-            h.update("srs_misc".as_bytes());
-            // FIXME: This is for retrocompatibility with a previous version
-            // that was using a list initialisation. It is not necessary.
-            h.update(0_u32.to_be_bytes());
-            point_of_random_bytes(&m, &h.finalize())
-        };
+        let h = blinding_generator(&m);
 
         Self {
             g,
             h,
             lagrange_bases: HashMapCache::new(),
+            backend: None,
         }
     }
 
@@ -566,6 +663,20 @@ where
     fn size(&self) -> usize {
         self.g.len()
     }
+
+    fn msm_backend(&self) -> &dyn MsmBackend<G> {
+        match &self.backend {
+            Some(backend) => backend.as_msm(),
+            None => &CpuBackend,
+        }
+    }
+
+    fn fft_backend(&self) -> &dyn FftBackend<G::ScalarField> {
+        match &self.backend {
+            Some(backend) => backend.as_fft(),
+            None => &CpuBackend,
+        }
+    }
 }
 
 impl<G: CommitmentCurve> SRS<G> {
@@ -694,25 +805,29 @@ impl<G: CommitmentCurve> SRS<G> {
             let rand_r = <G::ScalarField as UniformRand>::rand(rng);
 
             // Pedersen commitment to a_lo,rand_l,<a_hi,b_lo>
-            let l = G::Group::msm_bigint(
-                &[g_lo, &[self.h, u_base]].concat(),
-                &[a_hi, &[rand_l, inner_prod(a_hi, b_lo)]]
-                    .concat()
-                    .iter()
-                    .map(|x| x.into_bigint())
-                    .collect::<Vec<_>>(),
-            )
-            .into_affine();
-
-            let r = G::Group::msm_bigint(
-                &[g_hi, &[self.h, u_base]].concat(),
-                &[a_lo, &[rand_r, inner_prod(a_lo, b_hi)]]
-                    .concat()
-                    .iter()
-                    .map(|x| x.into_bigint())
-                    .collect::<Vec<_>>(),
-            )
-            .into_affine();
+            let l = self
+                .msm_backend()
+                .msm_bigint(
+                    &[g_lo, &[self.h, u_base]].concat(),
+                    &[a_hi, &[rand_l, inner_prod(a_hi, b_lo)]]
+                        .concat()
+                        .iter()
+                        .map(|x| x.into_bigint())
+                        .collect::<Vec<_>>(),
+                )
+                .into_affine();
+
+            let r = self
+                .msm_backend()
+                .msm_bigint(
+                    &[g_hi, &[self.h, u_base]].concat(),
+                    &[a_lo, &[rand_r, inner_prod(a_lo, b_hi)]]
+                        .concat()
+                        .iter()
+                        .map(|x| x.into_bigint())
+                        .collect::<Vec<_>>(),
+                )
+                .into_affine();
 
             lr.push((l, r));
             blinders.push((rand_l, rand_r));
@@ -10,8 +10,11 @@
 //! parameter.
 
 use crate::{
-    commitment::*, ipa::SRS, utils::combine_polys, CommitmentError, PolynomialsToCombine,
-    SRS as SRSTrait,
+    backend::{FftBackend, MsmBackend},
+    commitment::*,
+    ipa::SRS,
+    utils::combine_polys,
+    CommitmentError, PolynomialsToCombine, SRS as SRSTrait,
 };
 
 use ark_ec::{pairing::Pairing, AffineRepr, VariableBaseMSM};
@@ -330,6 +333,14 @@ impl<
     fn size(&self) -> usize {
         self.full_srs.g.len()
     }
+
+    fn msm_backend(&self) -> &dyn MsmBackend<G> {
+        self.full_srs.msm_backend()
+    }
+
+    fn fft_backend(&self) -> &dyn FftBackend<F> {
+        self.full_srs.fft_backend()
+    }
 }
 
 /// The polynomial that evaluates to each of `evals` for the respective `elm`s.
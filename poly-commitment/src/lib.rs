@@ -1,3 +1,4 @@
+pub mod backend;
 mod combine;
 pub mod commitment;
 pub mod error;
@@ -13,6 +14,7 @@ pub mod pbt_srs;
 pub use commitment::PolyComm;
 
 use crate::{
+    backend::{CpuBackend, FftBackend, MsmBackend},
     commitment::{BatchEvaluationProof, BlindedCommitment, CommitmentCurve},
     error::CommitmentError,
     utils::DensePolynomialOrEvaluations,
@@ -167,6 +169,18 @@ pub trait SRS<G: CommitmentCurve>: Clone + Sized + Sync + Send {
     fn get_lagrange_basis_from_domain_size(&self, domain_size: usize) -> &Vec<PolyComm<G>>;
 
     fn size(&self) -> usize;
+
+    /// The backend computing the multi-scalar multiplications of the SRS, see
+    /// [backend]
+    fn msm_backend(&self) -> &dyn MsmBackend<G> {
+        &CpuBackend
+    }
+
+    /// The backend computing the FFTs of the provers using the SRS, see
+    /// [backend]
+    fn fft_backend(&self) -> &dyn FftBackend<G::ScalarField> {
+        &CpuBackend
+    }
 }
 
 #[allow(type_alias_bounds)]
@@ -64,6 +64,7 @@ impl<G> From<TestSRS<G>> for SRS<G> {
             g: value.g,
             h: value.h,
             lagrange_bases: HashMapCache::new_from_hashmap(value.lagrange_bases),
+            backend: None,
         }
     }
 }
@@ -72,7 +73,7 @@ impl<G> From<TestSRS<G>> for SRS<G> {
 pub const SERIALIZED_SRS_SIZE: u32 = 16;
 
 /// The path of the serialized SRS.
-fn get_srs_path<G: NamedCurve>(srs_type: StoredSRSType) -> PathBuf {
+pub fn get_srs_path<G: NamedCurve>(srs_type: StoredSRSType) -> PathBuf {
     let test_prefix: String = (match srs_type {
         StoredSRSType::Test => "test_",
         StoredSRSType::Prod => "",
@@ -140,6 +141,7 @@ mod tests {
             g,
             h,
             lagrange_bases,
+            backend: None,
         };
         let srs_bytes = rmp_serde::to_vec(&srs).unwrap();
         let output = hex::encode(srs_bytes.clone());
@@ -0,0 +1,154 @@
+#![cfg(feature = "gpu")]
+
+use ark_ec::AffineRepr;
+use ark_ff::{PrimeField, UniformRand};
+use ark_poly::{
+    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Evaluations,
+    Radix2EvaluationDomain as D,
+};
+use mina_curves::pasta::{Fp, Vesta};
+use poly_commitment::{
+    backend::{
+        gpu::{GpuBackend, GpuFft, GpuMsm, GPU_MIN_SIZE},
+        CpuBackend, FftBackend, MsmBackend,
+    },
+    ipa::SRS,
+    SRS as _,
+};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+/// A device running the operations on the CPU and counting them, or refusing
+/// all of them if `unavailable` is set
+#[derive(Debug, Default)]
+struct MockDevice {
+    unavailable: bool,
+    msms: AtomicUsize,
+    ffts: AtomicUsize,
+}
+
+impl GpuMsm<Vesta> for MockDevice {
+    fn msm_bigint(
+        &self,
+        bases: &[Vesta],
+        scalars: &[<Fp as PrimeField>::BigInt],
+    ) -> Option<<Vesta as AffineRepr>::Group> {
+        if self.unavailable {
+            return None;
+        }
+        self.msms.fetch_add(1, Ordering::Relaxed);
+        Some(CpuBackend.msm_bigint(bases, scalars))
+    }
+}
+
+impl GpuFft<Fp> for MockDevice {
+    fn fft_in_place(&self, domain: D<Fp>, values: &mut [Fp], inverse: bool) -> Option<()> {
+        if self.unavailable {
+            return None;
+        }
+        assert_eq!(values.len(), domain.size());
+        self.ffts.fetch_add(1, Ordering::Relaxed);
+        let mut vec = values.to_vec();
+        if inverse {
+            CpuBackend.ifft_in_place(domain, &mut vec);
+        } else {
+            CpuBackend.fft_in_place(domain, &mut vec);
+        }
+        values.copy_from_slice(&vec);
+        Some(())
+    }
+}
+
+fn mock_backend(unavailable: bool, min_size: usize) -> GpuBackend<MockDevice> {
+    GpuBackend {
+        device: MockDevice {
+            unavailable,
+            ..MockDevice::default()
+        },
+        min_size,
+    }
+}
+
+#[test]
+fn test_gpu_backend_new() {
+    let backend = GpuBackend::new(MockDevice::default());
+    assert_eq!(backend.min_size, GPU_MIN_SIZE);
+}
+
+#[test]
+// The backend running on a device computes the same results as the CPU, both
+// when the device runs the operations and when it falls back to the CPU
+fn test_gpu_backend_matches_cpu() {
+    let n = 64;
+    let domain = D::<Fp>::new(n).unwrap();
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+
+    // The device runs everything, refuses everything, or is only used from a
+    // size larger than the operations
+    for (unavailable, min_size) in [(false, 0), (true, 0), (false, 2 * n)] {
+        let backend = mock_backend(unavailable, min_size);
+
+        let bases: Vec<Vesta> = (0..n)
+            .map(|_| (Vesta::generator() * Fp::rand(rng)).into())
+            .collect();
+        let scalars: Vec<Fp> = (0..n).map(|_| Fp::rand(rng)).collect();
+        assert_eq!(
+            MsmBackend::<Vesta>::msm(&backend, &bases, &scalars),
+            MsmBackend::<Vesta>::msm(&CpuBackend, &bases, &scalars)
+        );
+
+        // full and partial polynomials
+        for size in [n, n / 2] {
+            let poly = DensePolynomial::<Fp>::rand(size - 1, rng);
+            assert_eq!(
+                backend.evaluate_over_domain(&poly, domain),
+                CpuBackend.evaluate_over_domain(&poly, domain)
+            );
+        }
+        let evals =
+            Evaluations::from_vec_and_domain((0..n).map(|_| Fp::rand(rng)).collect(), domain);
+        assert_eq!(
+            backend.interpolate(evals.clone()),
+            CpuBackend.interpolate(evals)
+        );
+
+        let used = !unavailable && min_size <= n;
+        assert_eq!(
+            backend.device.msms.load(Ordering::Relaxed),
+            usize::from(used)
+        );
+        assert_eq!(
+            backend.device.ffts.load(Ordering::Relaxed),
+            if used { 3 } else { 0 }
+        );
+    }
+}
+
+#[test]
+// An SRS attached to the backend commits as the SRS running on the CPU
+fn test_gpu_backend_commitments() {
+    let n = 64;
+    let domain = D::<Fp>::new(n).unwrap();
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+
+    let srs = SRS::<Vesta>::create(n);
+    let backend = Arc::new(mock_backend(false, 0));
+    let srs_backend = SRS::<Vesta>::create(n).with_backend(backend.clone());
+
+    // chunked, full and partial polynomials
+    for size in [2 * n, n, n / 2] {
+        let poly = DensePolynomial::<Fp>::rand(size - 1, rng);
+        assert_eq!(
+            srs.commit_non_hiding(&poly, 2),
+            srs_backend.commit_non_hiding(&poly, 2)
+        );
+    }
+    let evals = Evaluations::from_vec_and_domain((0..n).map(|_| Fp::rand(rng)).collect(), domain);
+    assert_eq!(
+        srs.commit_evaluations_non_hiding(domain, &evals),
+        srs_backend.commit_evaluations_non_hiding(domain, &evals)
+    );
+    assert!(backend.device.msms.load(Ordering::Relaxed) > 0);
+}
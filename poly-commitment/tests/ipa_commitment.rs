@@ -1,4 +1,5 @@
-use ark_ff::{One, UniformRand, Zero};
+use ark_ec::AffineRepr;
+use ark_ff::{One, PrimeField, UniformRand, Zero};
 use ark_poly::{
     univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Evaluations, Polynomial,
     Radix2EvaluationDomain as D, Radix2EvaluationDomain,
@@ -10,6 +11,7 @@ use mina_poseidon::{
 };
 use o1_utils::ExtendedDensePolynomial;
 use poly_commitment::{
+    backend::{CpuBackend, FftBackend, MsmBackend},
     commitment::{combined_inner_product, BatchEvaluationProof, CommitmentCurve, Evaluation},
     ipa::SRS,
     pbt_srs,
@@ -19,6 +21,10 @@ use poly_commitment::{
 use rand::Rng;
 use std::{
     array,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
     time::{Duration, Instant},
 };
 
@@ -378,3 +384,64 @@ fn test_dlog_commitment() {
     assert!(srs.verify::<DefaultFqSponge<VestaParameters, SC>, _>(&group_map, &mut proofs, rng));
     println!("verification time: {:?}", start.elapsed());
 }
+
+/// A backend running on the CPU and counting the operations it computes
+#[derive(Debug, Default)]
+struct CountingBackend {
+    msms: AtomicUsize,
+    ffts: AtomicUsize,
+}
+
+impl MsmBackend<VestaG> for CountingBackend {
+    fn msm_bigint(
+        &self,
+        bases: &[VestaG],
+        scalars: &[<Fp as PrimeField>::BigInt],
+    ) -> <VestaG as AffineRepr>::Group {
+        self.msms.fetch_add(1, Ordering::Relaxed);
+        CpuBackend.msm_bigint(bases, scalars)
+    }
+}
+
+impl FftBackend<Fp> for CountingBackend {
+    fn fft_in_place(&self, domain: D<Fp>, coeffs: &mut Vec<Fp>) {
+        self.ffts.fetch_add(1, Ordering::Relaxed);
+        CpuBackend.fft_in_place(domain, coeffs)
+    }
+
+    fn ifft_in_place(&self, domain: D<Fp>, evals: &mut Vec<Fp>) {
+        self.ffts.fetch_add(1, Ordering::Relaxed);
+        CpuBackend.ifft_in_place(domain, evals)
+    }
+}
+
+#[test]
+fn test_commitments_with_backend() {
+    let n = 64;
+    let domain = D::<Fp>::new(n).unwrap();
+    let rng = &mut o1_utils::tests::make_test_rng(None);
+
+    let srs = SRS::<VestaG>::create(n);
+    let backend = Arc::new(CountingBackend::default());
+    let srs_backend = SRS::<VestaG>::create(n).with_backend(backend.clone());
+
+    // chunked, full and partial polynomials
+    for size in [2 * n, n, n / 2] {
+        let poly = DensePolynomial::<Fp>::rand(size - 1, rng);
+        assert_eq!(
+            srs.commit_non_hiding(&poly, 2),
+            srs_backend.commit_non_hiding(&poly, 2)
+        );
+    }
+
+    let evals = Evaluations::from_vec_and_domain((0..n).map(|_| Fp::rand(rng)).collect(), domain);
+    assert_eq!(
+        srs.commit_evaluations_non_hiding(domain, &evals),
+        srs_backend.commit_evaluations_non_hiding(domain, &evals)
+    );
+    assert!(backend.msms.load(Ordering::Relaxed) > 0);
+
+    let fft = srs_backend.fft_backend();
+    assert_eq!(fft.interpolate(evals.clone()), evals.interpolate_by_ref());
+    assert_eq!(backend.ffts.load(Ordering::Relaxed), 1);
+}
@@ -0,0 +1,75 @@
+#!/usr/bin/env python3
+
+# Generates the round constants of the Poseidon2 permutation over the Pasta
+# fields, for a state of 3 elements and the S-box x^7, as a Rust module of
+# crate::pasta.
+#
+# Usage: ./poseidon2_params.py {p,q} > ../src/pasta/f{p,q}_poseidon2.rs
+
+import hashlib
+import sys
+
+_pasta = {
+    'p': 28948022309329048855892746252171976963363056481941560715954676764349967630337,
+    'q': 28948022309329048855892746252171976963363056481941647379679742748393362948097,
+}
+
+_width = 3
+_full_rounds = 8
+_partial_rounds = 48
+
+def random_value(order, prefix, i):
+  j = 0
+  while True:
+    x = int(hashlib.sha256("{}{}_{}".format(prefix, i, j).encode('utf-8')).hexdigest(), 16)
+    if x < order:
+      return x
+    j += 1
+
+def field_element(letter, x):
+  return 'F{}::from_str("{}").unwrap()'.format(letter, x)
+
+if len(sys.argv) != 2 or sys.argv[1] not in _pasta:
+  print('usage: {} {{p,q}}'.format(sys.argv[0]), file=sys.stderr)
+  sys.exit(1)
+
+letter = sys.argv[1]
+order = _pasta[letter]
+prefix = 'Poseidon2Pasta_' + letter
+
+external = [[random_value(order, prefix + 'ExternalRoundConstants', r * _width + i)
+             for i in range(_width)]
+            for r in range(_full_rounds)]
+internal = [random_value(order, prefix + 'InternalRoundConstants', r)
+            for r in range(_partial_rounds)]
+
+print('extern crate alloc;')
+print('use crate::poseidon2::Poseidon2Params;')
+print('use alloc::vec;')
+print('use core::str::FromStr;')
+print('use mina_curves::pasta::F{};'.format(letter))
+print('use once_cell::sync::Lazy;')
+print()
+print('/* Generated by ./scripts/poseidon2_params.py {} */'.format(letter))
+print()
+print('pub fn params() -> Poseidon2Params<F{}> {{'.format(letter))
+print('    Poseidon2Params {')
+print('        external_round_constants: vec![')
+for rc in external:
+  print('            [')
+  for x in rc:
+    print('                {},'.format(field_element(letter, x)))
+  print('            ],')
+print('        ],')
+print('        internal_round_constants: vec![')
+for x in internal:
+  print('            {},'.format(field_element(letter, x)))
+print('        ],')
+print('    }')
+print('}')
+print()
+print('/// the f{} Poseidon2 params'.format(letter))
+print('pub fn static_params() -> &\'static Poseidon2Params<F{}> {{'.format(letter))
+print('    static PARAMS: Lazy<Poseidon2Params<F{}>> = Lazy::new(params);'.format(letter))
+print('    &PARAMS')
+print('}')
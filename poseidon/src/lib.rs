@@ -30,6 +30,7 @@ pub mod dummy_values;
 pub mod pasta;
 pub mod permutation;
 pub mod poseidon;
+pub mod poseidon2;
 pub mod sponge;
 
 pub use sponge::FqSponge; // Commonly used so reexported for convenience
@@ -0,0 +1,327 @@
+extern crate alloc;
+use crate::poseidon2::Poseidon2Params;
+use alloc::vec;
+use core::str::FromStr;
+use mina_curves::pasta::Fp;
+use once_cell::sync::Lazy;
+
+/* Generated by ./scripts/poseidon2_params.py p */
+
+pub fn params() -> Poseidon2Params<Fp> {
+    Poseidon2Params {
+        external_round_constants: vec![
+            [
+                Fp::from_str(
+                    "21194542816427497049340524235251298842623164624658139694949815506469068445918",
+                )
+                .unwrap(),
+                Fp::from_str(
+                    "23788991158699161134464369813835126552186235647431196984709431131665918038913",
+                )
+                .unwrap(),
+                Fp::from_str(
+                    "17128059741500294987585333408757822812799079448994353123551352657252066652207",
+                )
+                .unwrap(),
+            ],
+            [
+                Fp::from_str(
+                    "15293109582160133457295783751458056339344922867375036021667920658764768671490",
+                )
+                .unwrap(),
+                Fp::from_str(
+                    "24868631180826182337091767206637462138555144752295932263832103635794799415985",
+                )
+                .unwrap(),
+                Fp::from_str(
+                    "11127366629965030771786218478103506040633703547543973271708592029550866734594",
+                )
+                .unwrap(),
+            ],
+            [
+                Fp::from_str(
+                    "26303161400429265860231333122459522023784917413250943980799218744626133007260",
+                )
+                .unwrap(),
+                Fp::from_str(
+                    "5291516983288528370358740603286787345316823814666637078393406217191285323711",
+                )
+                .unwrap(),
+                Fp::from_str(
+                    "168868025535795758811031126416925999983995016376014243751707910812217044169",
+                )
+                .unwrap(),
+            ],
+            [
+                Fp::from_str(
+                    "15950097307227916326793764219500605642698087687687213289131684142001192022153",
+                )
+                .unwrap(),
+                Fp::from_str(
+                    "6052154693021907193878543652317912560024516282596516382071855880391295140136",
+                )
+                .unwrap(),
+                Fp::from_str(
+                    "18292624315947619437449383797724149793101734542296015727804215439098692975923",
+                )
+                .unwrap(),
+            ],
+            [
+                Fp::from_str(
+                    "18850997604529866272048479017191388550693237656356411879381673816246000597267",
+                )
+                .unwrap(),
+                Fp::from_str(
+                    "13410613029304544184673710046975331561958820288659370787221817258168268053984",
+                )
+                .unwrap(),
+                Fp::from_str(
+                    "16994455896722397120009910226149920584194677416257182905287304849549724654492",
+                )
+                .unwrap(),
+            ],
+            [
+                Fp::from_str(
+                    "6178297254092774311984041129756255696617919417781200668632870780133270024777",
+                )
+                .unwrap(),
+                Fp::from_str(
+                    "13985410110357342670015578916206623308041250883750856893295925049162442442174",
+                )
+                .unwrap(),
+                Fp::from_str(
+                    "21062282216473624599676703838827341033871000357065643698528809986670007266472",
+                )
+                .unwrap(),
+            ],
+            [
+                Fp::from_str(
+                    "17059046458937217653183148666211864107277246433735156231210109489881400385979",
+                )
+                .unwrap(),
+                Fp::from_str(
+                    "763575634433596002303267530591002316303183006798993847757812110159913588631",
+                )
+                .unwrap(),
+                Fp::from_str(
+                    "21608658950415285530767655570056131726894776715626083464411386610072747065855",
+                )
+                .unwrap(),
+            ],
+            [
+                Fp::from_str(
+                    "15683361002215996013482831943883477353364091776045610342695792719393066406826",
+                )
+                .unwrap(),
+                Fp::from_str(
+                    "27785235814235631244236276534718344633714318109556980360721644121915139268255",
+                )
+                .unwrap(),
+                Fp::from_str(
+                    "27338345350864566115798247318078962623674888799520427870140599331829589566184",
+                )
+                .unwrap(),
+            ],
+        ],
+        internal_round_constants: vec![
+            Fp::from_str(
+                "19647990579799468605473400009696324465697642995779061084440504507043634713360",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "3298886327820349764281344740408735494641068431428378360417708316751124380421",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "20944750283991425793539875903380409407070643455832609742489076942721193987671",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "6108559399880640202456481683423328790326675767086681071894073487105563496906",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "716241176504730938592988542119067368252025310702957016504294411977578197319",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "27273232574687457988234891734586357068334017907643162547787872085647770088732",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "15198425394743105155482613223297949655923250463934115254805523186673436707140",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "28730933831865013255717710303060242312697768565136802893314250033127947250375",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "3487101866059930111216971314995728654018416418833685859884989783116898650987",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "26917330040607591166238934953185713149571678543727936065858188367400104418936",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "14074642997811685636518910880649611326687852107692896839987148195373622384717",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "17935995811303031194702806071902150450467813825751240149840223803193569357851",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "5658750901369908566543395503695312619020752575088010714808491103173302589598",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "12703490655805611530373780286581019493191205495242833180172901118163754535378",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "11105433058927672735516384266082370156441240201233547057915269758784085819800",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "5562769199575025965285044834962651802320454169266524894196000353296940330735",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "2528273447612680111655278854217419616679648361355340783617320594906796723559",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "28799912429336085307160907740321391561746135065516919764240589472668640959249",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "4673531939050903964232708034212689327617702798039309329944548610482714845935",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "6630593862352096533536361527843149877292160762694448278303496794487462315391",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "3257730396947102101841632830199534744478240287399053717291379757792656503839",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "6737259933119718884059041483308119180871887351317564398086740664447187362604",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "6344754500526885978844198918562652537454701944744085222686984647378177887028",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "11224008736263277451801936041027973458193354904350299370627556606955633547871",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "13828560187765187869511236760551491153540154913891956296331677572834741610636",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "5239328859673618484461952144910643125512374518480313678325727757414162773420",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "17638370951045310385186462410002766702443171344921178760628616256012045333801",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "11079093120856912005083277069075518564371289770111946126526788638889614874522",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "12382122328872488504797063741376210188841254237981299428433573085555478319927",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "3734499732905670431898617393444060221995800364046935550942842749409358876931",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "24353775282183332756283278121600908605970670936299733472995045512897256084965",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "9605369072429654769877710568608913187439056706066160975666919244332324770083",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "27605986697708136954832313752113471689665001899460237279144388317518012671295",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "4317930251460207852510673672085200546942289219633756578021611587860839417561",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "22223653634880764369595528624945470390717097961688659789542936824544922376842",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "22005148741486692129980100868112800974820353358133116562299922759992613993176",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "22861123905598608725692257342235186959414070849735197011748420106424349921351",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "22236018285785545535438630598231066377869934219088965819491546146566692372416",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "13804451683242038438158095779819777119540413721625836691568528332004884506140",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "21659521293713551386060302916011433987354751237590314219268589693516376150410",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "7300355949440484377420196873629166867649557961632882569544927585250908932971",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "25805663376103569660912696475769726170244137329552581511005961449514679708347",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "28657314697877826338020194321595645922304701170431877638354505210041186054022",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "9445882459150830971717780894228721842993081076231615873610769801151176502253",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "20650080971083467376277894813352486556324546197154592243718576533600620240197",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "27457054504275558619171652807761587191728577971188629847885214908756209042100",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "3914631943407072540801368221320423764020817548417622475752810132532329772413",
+            )
+            .unwrap(),
+            Fp::from_str(
+                "766926482763785792768991469951697857842652905581347634132481789720266849093",
+            )
+            .unwrap(),
+        ],
+    }
+}
+
+/// the fp Poseidon2 params
+pub fn static_params() -> &'static Poseidon2Params<Fp> {
+    static PARAMS: Lazy<Poseidon2Params<Fp>> = Lazy::new(params);
+    &PARAMS
+}
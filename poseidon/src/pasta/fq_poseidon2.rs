@@ -0,0 +1,327 @@
+extern crate alloc;
+use crate::poseidon2::Poseidon2Params;
+use alloc::vec;
+use core::str::FromStr;
+use mina_curves::pasta::Fq;
+use once_cell::sync::Lazy;
+
+/* Generated by ./scripts/poseidon2_params.py q */
+
+pub fn params() -> Poseidon2Params<Fq> {
+    Poseidon2Params {
+        external_round_constants: vec![
+            [
+                Fq::from_str(
+                    "27409345082932981784264986085682026819290024780636577698119424204769420416905",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "4189827127569070266632374073628147556900004742630745242879116188599227071781",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "25674381712715850522130322241709696764415451636937332234298120295929148612728",
+                )
+                .unwrap(),
+            ],
+            [
+                Fq::from_str(
+                    "20411592266119156343569605986612969497065106852590076731461390647262947659797",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "10775165477983696158668920088775951137644547861264571041984085424032201325658",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "15481333984846378481148722516659435914635946706813688867888702041508819452890",
+                )
+                .unwrap(),
+            ],
+            [
+                Fq::from_str(
+                    "22588633370394693582592507169843328243316708321392002937034461601871508039383",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "22754017727886087775120076045560424827189078643693866288745022333559104267736",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "818953464045314292733216344876602652457270785195050515255142385275188582391",
+                )
+                .unwrap(),
+            ],
+            [
+                Fq::from_str(
+                    "3858794746345092066231328812842744863144560798355908630447881018645528745964",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "1143210614112815237224265265167321328798260265474755567594805598338686214344",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "19080565709431322247790611173527005197259446160482592314565255046225213139032",
+                )
+                .unwrap(),
+            ],
+            [
+                Fq::from_str(
+                    "10713135780551577722153112472044068317503507378060694200369309438461112153081",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "5303284351440527740230571816804496493553430104824689005278045166191380358227",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "16336929500042973036789170616613961137156143253109683014972795452791852353435",
+                )
+                .unwrap(),
+            ],
+            [
+                Fq::from_str(
+                    "9087105781424189913760622993560888837321333667791200163790185487437266974151",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "22216635549521823547989893781726291486472555165370358177151983590707880856297",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "15139417689129975754388510907118402197757256926099204768446427840122296634754",
+                )
+                .unwrap(),
+            ],
+            [
+                Fq::from_str(
+                    "6755069518604596100551360048873661471096047139162879555396492550066153068047",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "16595182656599883302797713435763400665300523883867161845380553394648411032261",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "12683957965823811105028952458262063815321137194925327243821115678518294612925",
+                )
+                .unwrap(),
+            ],
+            [
+                Fq::from_str(
+                    "6086194702174597002109408207018958975667600027546116891725838605150869725340",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "8335354125589555784245236026156247344000553668796898562937500681940993136361",
+                )
+                .unwrap(),
+                Fq::from_str(
+                    "19579169263527695830950978593379335678493952429873125488661748729583822348993",
+                )
+                .unwrap(),
+            ],
+        ],
+        internal_round_constants: vec![
+            Fq::from_str(
+                "14066637714727211767359184428498912431568070926026651331660358302910210983494",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "28875769612375119076455102316281939495300918273488648789745184194810759597768",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "26587669558170747721758798510179581382906892574027186282876642236229701338446",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "122061329396823676525957745683339012851719381884248880303739881280930404251",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "3893517384383948615130428329021101648814373861892092269745444621662253510202",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "23947245503537796979780257014353887471590383526816826913564238612403158226612",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "8560670617379757064784936775313622061842633162251209066448312238643345910034",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "10097967557738906882021793800670796457608345991434769408428062394965977508863",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "23498274388370618892659225177873139669587760299727509331319151901065441407007",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "14512286579778101473485058708059103676365579442303782735499618252172260226839",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "22779713777642188006909695727514660049513091926511560807065688732202413854866",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "20109194453089406405400073525479456879083671822918038791470851042975987111823",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "11314563762290963670175868630723904551739606326481198187150136556051935625522",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "2787348843544994462058928111242422236229697687029296813795775302215431805317",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "8125952253057822161417766770283273344271104611204899178729126626483303529760",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "8620872200019295809106708366765056336380152274889922043361071093308882947898",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "27710999680807050711982065955150314914497692969888303845601097894486294908583",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "10884074914033182124577673703395012067527225439624079090627675424505017079817",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "6374606696987916350957777714159649374359056970993235095689643609816374090235",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "25932745839272430877607802686516316847714944024380470719176814721980892651051",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "21707387289574150034223787892277127943741017822845217435326087054343948133525",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "8570935096600132449558716766952004063491870101279886501589480472883539044686",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "3947595048007782879591249229240764976542940532158094068647908975374437751718",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "12241843316150870336859496968290080539680058802138802051111077876748555452268",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "20879532618644867587023818833229061726815803805827117921797747663939657204542",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "16503223242924115489108087496891941173249354761491781521754236599796601657668",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "166557503068099268100390531673547350541574965834747405513379592808022190259",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "23697765657343527894919945127950794281088508362917976664320028726766150283125",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "19175447863819636532804391179050240881454892967983401657280025783981151808591",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "12336409036870409159981429747530541450829666701658001398394462673825176232821",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "16674318891444058308053576459028633430460002788018342293438930836101002562179",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "3571750192317089689772682631343311299470399235570338963760870424839605491132",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "13357306189795525128632931176861114907797625522824842767184417775516428038929",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "20266274505459623095569955490536637978312923519080291935555458593663918187794",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "20926626653536788556803154200122600338713304651624656894033706382254976260722",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "26479733186409081863451466789145163942667293162692833716754216836267772532941",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "16683926482535927350255567147428213683321827076357576484425759142183150748561",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "16228495221888646577529205223684500131669921427915664246312202500348312764449",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "14657791556918803529515584287591600540824032410785950060261211347375216531032",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "1298712947960960986637470966936605798332275771973351054255889823753334806250",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "25625921821327247019264193222406636142816198256886890639628355074176667270688",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "11975779462363547326465136985567533722578541246472289953635459301860062596215",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "27529294793636248744024615212499478770807146760415941686489032627632579696232",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "12266186420378881274409231158754376377551991388486824560485652418616098134807",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "10906541738771064477971566160491284010652148582361368242886492409648270611933",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "17581650609915021838492451706840376345245230125437817613160749171515559134288",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "1970991218881852442330805348901799775877994802486017317598374837059605725537",
+            )
+            .unwrap(),
+            Fq::from_str(
+                "20401444714492780258832282043349837947388090286320491594109979070665505608502",
+            )
+            .unwrap(),
+        ],
+    }
+}
+
+/// the fq Poseidon2 params
+pub fn static_params() -> &'static Poseidon2Params<Fq> {
+    static PARAMS: Lazy<Poseidon2Params<Fq>> = Lazy::new(params);
+    &PARAMS
+}
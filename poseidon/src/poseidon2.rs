@@ -0,0 +1,109 @@
+//! The Poseidon2 permutation, for a state of [WIDTH] field elements and the
+//! S-box `x^7`.
+//!
+//! Compared to Poseidon, the linear layer of Poseidon2 is made of two cheap
+//! matrices with small integer entries, and most of its rounds are partial
+//! rounds, applying the S-box to the first element of the state only:
+//!
+//! - the external matrix `M_E = circ(2, 1, 1)` is applied to the input,
+//! - [HALF_FULL_ROUNDS] full rounds `s <- M_E (s + c)^7`,
+//! - [PARTIAL_ROUNDS] partial rounds `s[0] <- (s[0] + c)^7; s <- M_I s`,
+//!   where `M_I = J + diag(1, 1, 2)` and `J` is the matrix of ones,
+//! - [HALF_FULL_ROUNDS] full rounds again.
+//!
+//! The numbers of rounds are above the bounds given by the Poseidon2 paper
+//! (<https://eprint.iacr.org/2023/323>) for 128 bits of security with the
+//! S-box `x^7` over the Pasta fields, the interpolation attack requiring 47
+//! rounds.
+//!
+//! The round constants for the Pasta fields are given in
+//! [crate::pasta::fp_poseidon2] and [crate::pasta::fq_poseidon2].
+
+extern crate alloc;
+use alloc::vec::Vec;
+use ark_ff::Field;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+/// Number of field elements of the state
+pub const WIDTH: usize = 3;
+
+/// Number of full rounds, half of them being applied before the partial rounds
+pub const FULL_ROUNDS: usize = 8;
+
+/// Number of full rounds before (and after) the partial rounds
+pub const HALF_FULL_ROUNDS: usize = FULL_ROUNDS / 2;
+
+/// Number of partial rounds
+pub const PARTIAL_ROUNDS: usize = 48;
+
+/// Exponent of the S-box
+pub const SBOX: u64 = 7;
+
+/// Diagonal of the internal matrix, minus the matrix of ones
+pub const INTERNAL_DIAGONAL: [u64; WIDTH] = [1, 1, 2];
+
+/// The round constants of a Poseidon2 instance
+#[serde_as]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Poseidon2Params<F: Field> {
+    /// Round constants of the [FULL_ROUNDS] full rounds
+    #[serde_as(as = "Vec<[o1_utils::serialization::SerdeAs; WIDTH]>")]
+    pub external_round_constants: Vec<[F; WIDTH]>,
+    /// Round constants of the [PARTIAL_ROUNDS] partial rounds
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub internal_round_constants: Vec<F>,
+}
+
+/// Applies the S-box `x^7`
+pub fn sbox<F: Field>(x: F) -> F {
+    let square = x.square();
+    x * square * square.square()
+}
+
+/// Applies the external matrix `circ(2, 1, 1)` to `state`
+pub fn apply_external_matrix<F: Field>(state: &mut [F; WIDTH]) {
+    let sum: F = state.iter().sum();
+    state.iter_mut().for_each(|s| *s += sum);
+}
+
+/// Applies the internal matrix `J + diag(1, 1, 2)` to `state`
+pub fn apply_internal_matrix<F: Field>(state: &mut [F; WIDTH]) {
+    let sum: F = state.iter().sum();
+    state
+        .iter_mut()
+        .zip(INTERNAL_DIAGONAL)
+        .for_each(|(s, d)| *s = sum + *s * F::from(d));
+}
+
+/// Applies the full round of round constants `round_constants` to `state`
+pub fn full_round<F: Field>(state: &mut [F; WIDTH], round_constants: &[F; WIDTH]) {
+    state
+        .iter_mut()
+        .zip(round_constants)
+        .for_each(|(s, c)| *s = sbox(*s + c));
+    apply_external_matrix(state);
+}
+
+/// Applies the partial round of round constant `round_constant` to `state`
+pub fn partial_round<F: Field>(state: &mut [F; WIDTH], round_constant: F) {
+    state[0] = sbox(state[0] + round_constant);
+    apply_internal_matrix(state);
+}
+
+/// Applies the Poseidon2 permutation with the round constants of `params` to
+/// `state`.
+pub fn permutation<F: Field>(params: &Poseidon2Params<F>, state: &mut [F; WIDTH]) {
+    let (first_rounds, last_rounds) = params.external_round_constants.split_at(HALF_FULL_ROUNDS);
+
+    apply_external_matrix(state);
+    for round_constants in first_rounds {
+        full_round(state, round_constants);
+    }
+    for round_constant in &params.internal_round_constants {
+        partial_round(state, *round_constant);
+    }
+    for round_constants in last_rounds {
+        full_round(state, round_constants);
+    }
+}
@@ -1,10 +1,11 @@
 use ark_ec::AffineRepr;
-use ark_ff::{Field, UniformRand};
+use ark_ff::{Field, UniformRand, Zero};
 use mina_curves::pasta::{Fp, Fq, Pallas, PallasParameters, Vesta, VestaParameters};
 use mina_poseidon::{
     constants::{PlonkSpongeConstantsKimchi, PlonkSpongeConstantsLegacy},
-    pasta::{fp_kimchi, fp_legacy, fq_kimchi},
+    pasta::{fp_kimchi, fp_legacy, fp_poseidon2, fq_kimchi, fq_poseidon2},
     poseidon::{ArithmeticSponge as Poseidon, Sponge as _},
+    poseidon2,
     sponge::DefaultFqSponge,
     FqSponge as _,
 };
@@ -199,3 +200,55 @@ fn test_poseidon_challenge_multiple_times_without_absorbtion() {
         challenges.push(chal);
     }
 }
+
+#[test]
+fn test_poseidon2_matrices() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let state: [Fp; 3] = core::array::from_fn(|_| Fp::rand(&mut rng));
+    let apply = |matrix: [[u64; 3]; 3]| -> [Fp; 3] {
+        core::array::from_fn(|i| {
+            (0..3)
+                .map(|j| Fp::from(matrix[i][j]) * state[j])
+                .sum::<Fp>()
+        })
+    };
+
+    let mut external = state;
+    poseidon2::apply_external_matrix(&mut external);
+    assert_eq!(external, apply([[2, 1, 1], [1, 2, 1], [1, 1, 2]]));
+
+    let mut internal = state;
+    poseidon2::apply_internal_matrix(&mut internal);
+    assert_eq!(internal, apply([[2, 1, 1], [1, 2, 1], [1, 1, 3]]));
+}
+
+#[test]
+fn test_poseidon2_pasta_params() {
+    let fp_params = fp_poseidon2::static_params();
+    let fq_params = fq_poseidon2::static_params();
+    assert_eq!(
+        fp_params.external_round_constants.len(),
+        poseidon2::FULL_ROUNDS
+    );
+    assert_eq!(
+        fp_params.internal_round_constants.len(),
+        poseidon2::PARTIAL_ROUNDS
+    );
+    assert_eq!(
+        fq_params.external_round_constants.len(),
+        poseidon2::FULL_ROUNDS
+    );
+    assert_eq!(
+        fq_params.internal_round_constants.len(),
+        poseidon2::PARTIAL_ROUNDS
+    );
+
+    // The permutation is a bijection, the inputs differing in one element
+    // give different outputs
+    let mut state = [Fp::from(0u64); 3];
+    let mut other_state = [Fp::from(0u64), Fp::from(0u64), Fp::from(1u64)];
+    poseidon2::permutation(fp_params, &mut state);
+    poseidon2::permutation(fp_params, &mut other_state);
+    assert_ne!(state, other_state);
+    assert!(state.iter().all(|s| !s.is_zero()));
+}
@@ -1,6 +1,6 @@
-use std::{fs::File, path::Path};
+use std::path::Path;
 
-use poly_commitment::{commitment::CommitmentCurve, ipa::SRS, precomputed_srs::TestSRS};
+use poly_commitment::{commitment::CommitmentCurve, ipa::SRS};
 use time::macros::format_description;
 use tracing::debug;
 use tracing_subscriber::{
@@ -10,24 +10,9 @@ use tracing_subscriber::{
 
 pub fn get_srs_from_cache<G: CommitmentCurve>(cache: String) -> SRS<G> {
     debug!("Loading SRS from cache {}", cache);
-    let file_path = Path::new(&cache);
-    let file = File::open(file_path).expect("Error opening SRS cache file");
-    let srs: SRS<G> = {
-        // By convention, proof systems serializes a TestSRS with filename 'test_<CURVE_NAME>.srs'.
-        // The benefit of using this is you don't waste time verifying the SRS.
-        if file_path
-            .file_name()
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .starts_with("test_")
-        {
-            let test_srs: TestSRS<G> = rmp_serde::from_read(&file).unwrap();
-            From::from(test_srs)
-        } else {
-            rmp_serde::from_read(&file).unwrap()
-        }
-    };
+    let srs = srs_store::load(Path::new(&cache))
+        .and_then(|srs| srs_store::verify(&srs).map(|_| srs))
+        .unwrap_or_else(|e| panic!("Error loading the SRS cache file: {e}"));
     debug!("SRS loaded successfully from cache");
     srs
 }
@@ -9,6 +9,8 @@ pub mod read_proof;
 pub mod storage;
 pub mod storage_proof;
 pub mod utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 use mina_poseidon::{
     constants::PlonkSpongeConstantsKimchi,
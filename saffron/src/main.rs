@@ -11,6 +11,7 @@ use saffron::{
     storage_proof::{self, StorageProof},
     Curve, CurveSponge, ScalarField, Sponge,
 };
+use srs_store::SrsStore;
 use std::{
     fs::File,
     io::{Read, Write},
@@ -25,14 +26,17 @@ fn get_srs_and_domain(cache: Option<String>) -> (SRS<Curve>, Radix2EvaluationDom
             (srs, domain_fp)
         }
         None => {
+            let store = SrsStore::from_env();
             debug!(
-                "No SRS cache provided. Creating SRS from scratch with domain size {}",
-                saffron::SRS_SIZE
+                "No SRS cache provided. Getting SRS with domain size {} from {:?}",
+                saffron::SRS_SIZE,
+                store.dir()
             );
-            let domain_size = saffron::SRS_SIZE;
-            let srs = SRS::create(domain_size);
+            let srs = store
+                .get(saffron::SRS_SIZE)
+                .unwrap_or_else(|e| panic!("Error getting the SRS: {e}"));
             let domain_fp = Radix2EvaluationDomain::new(srs.size()).unwrap();
-            debug!("SRS created successfully");
+            debug!("SRS obtained successfully");
             (srs, domain_fp)
         }
     };
@@ -33,14 +33,17 @@ use ark_poly::{
     Radix2EvaluationDomain as R2D,
 };
 use kimchi::{circuits::domains::EvaluationDomains, curve::KimchiCurve, plonk_sponge::FrSponge};
+use o1_utils::proof::{Proof, ProofError, ProofFormat};
 use poly_commitment::{
     commitment::{combined_inner_product, BatchEvaluationProof, CommitmentCurve, Evaluation},
     ipa::{OpeningProof, SRS},
     utils::DensePolynomialOrEvaluations,
     PolyComm,
 };
-use rand::{CryptoRng, Rng, RngCore};
-use tracing::instrument;
+use rand::{rngs::OsRng, CryptoRng, Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use tracing::{debug_span, instrument};
 
 /// Indexes of the data to be read ; this will be stored onchain
 /// Note: indexes are represented with u16, matching indexes from 0 to 2¹⁶ - 1. If the SRS is made bigger, the integer type has to handle this
@@ -103,27 +106,35 @@ impl Answer {
     }
 }
 
-// #[serde_as]
-#[derive(Debug, Clone)]
-// TODO? serialize, deserialize
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReadProof {
     // Commitment to the answer
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub answer_comm: Curve,
     // Commitment of quotient polynomial T (aka t_comm)
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub quotient_comm: Curve,
 
     // Evaluation of data polynomial at the required challenge point
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub data_eval: ScalarField,
     // Evaluation of query polynomial at the required challenge point
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub query_eval: ScalarField,
     // Evaluation of answer polynomial at the required challenge point
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub answer_eval: ScalarField,
 
     // Polynomial commitment’s proof for the validity of returned evaluations
     pub opening_proof: OpeningProof<Curve>,
 }
 
-#[instrument(skip_all, level = "debug")]
+#[instrument(
+    skip_all,
+    level = "debug",
+    fields(prover = "saffron-read", domain_size = domain.d1.size, columns = 3)
+)]
 pub fn prove<RNG>(
     srs: &SRS<Curve>,
     domain: EvaluationDomains<ScalarField>,
@@ -143,6 +154,7 @@ where
 {
     let data = &data.data;
 
+    let phase = debug_span!("commit_witness").entered();
     let mut curve_sponge = CurveSponge::new(Curve::other_curve_sponge_params());
 
     let data_poly = evals_to_polynomial(data.to_vec(), domain.d1);
@@ -155,7 +167,9 @@ where
     };
 
     curve_sponge.absorb_g(&[data_comm.cm, *query_comm, answer_comm]);
+    drop(phase);
 
+    let phase = debug_span!("quotient").entered();
     // coefficient form, over d4? d2?
     // quotient_Poly has degree d1
     let quotient_poly: DensePolynomial<ScalarField> = {
@@ -186,7 +200,9 @@ where
     // num_chunks = 1 because our constraint is degree 2, which makes the quotient polynomial of degree d1
     let quotient_comm = commit_poly(srs, &quotient_poly);
     curve_sponge.absorb_g(&[quotient_comm]);
+    drop(phase);
 
+    let phase = debug_span!("evaluations").entered();
     // aka zeta
     let evaluation_point = curve_sponge.challenge();
 
@@ -225,6 +241,9 @@ where
         ]
     };
 
+    drop(phase);
+
+    let _phase = debug_span!("opening").entered();
     let opening_proof = srs.open(
         group_map,
         opening_proof_inputs.as_slice(),
@@ -354,6 +373,49 @@ pub fn verify_answer(srs: &SRS<Curve>, query: &Query, answer: &Answer, proof: &R
     answer_comm == proof.answer_comm
 }
 
+/// The statement of a read proof: the commitments to the data and to the
+/// query
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadStatement {
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    pub data_comm: Curve,
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    pub query_comm: Curve,
+}
+
+/// The data needed to verify read proofs
+pub struct ReadVerifier {
+    pub srs: SRS<Curve>,
+    pub domain: EvaluationDomains<ScalarField>,
+    pub group_map: <Curve as CommitmentCurve>::Map,
+}
+
+impl Proof for ReadProof {
+    const FORMAT: ProofFormat = ProofFormat::new("saffron-read", 1);
+
+    type Statement = ReadStatement;
+
+    type Verifier = ReadVerifier;
+
+    fn verify(&self, verifier: &ReadVerifier, statement: &ReadStatement) -> Result<(), ProofError> {
+        let valid = verify(
+            &verifier.srs,
+            verifier.domain,
+            &verifier.group_map,
+            &mut OsRng,
+            &statement.data_comm.into(),
+            &statement.query_comm,
+            self,
+        );
+        if valid {
+            Ok(())
+        } else {
+            Err(ProofError::Invalid("the read proof does not verify".into()))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -361,6 +423,7 @@ mod tests {
     use ark_ec::AffineRepr;
     use ark_ff::{One, UniformRand};
     use kimchi::{circuits::domains::EvaluationDomains, groupmap::GroupMap};
+    use o1_utils::proof::{EncodedProof, ProofRegistry};
     use poly_commitment::{commitment::CommitmentCurve, SRS as _};
 
     #[test]
@@ -482,5 +545,28 @@ mod tests {
         let res_5 = verify_answer(&srs, &query, &answer, &proof);
 
         assert!(!res_5, "Soundness: Wrong answer must NOT verify");
+
+        let mut registry = ProofRegistry::new();
+        registry.register::<ReadProof>(ReadVerifier {
+            srs: srs.clone(),
+            domain,
+            group_map,
+        });
+        let statement = ReadStatement {
+            data_comm: data_comm.cm,
+            query_comm,
+        };
+        let encoded = EncodedProof::new(&proof, &statement).unwrap();
+        assert_eq!(
+            registry.verify(&encoded),
+            Ok(()),
+            "Completeness: Encoded proof must verify"
+        );
+
+        let encoded_malformed = EncodedProof::new(&proof_malformed_1, &statement).unwrap();
+        assert!(
+            registry.verify(&encoded_malformed).is_err(),
+            "Soundness: Encoded malformed proof must NOT verify"
+        );
     }
 }
@@ -19,6 +19,7 @@ use ark_poly::{
     EvaluationDomain, Evaluations, Polynomial, Radix2EvaluationDomain as D, Radix2EvaluationDomain,
 };
 use kimchi::{curve::KimchiCurve, plonk_sponge::FrSponge};
+use o1_utils::proof::{Proof, ProofError, ProofFormat};
 use poly_commitment::{
     commitment::{BatchEvaluationProof, CommitmentCurve, Evaluation},
     ipa::{OpeningProof, SRS},
@@ -29,7 +30,7 @@ use rand::rngs::OsRng;
 use rayon::iter::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
-use tracing::instrument;
+use tracing::{debug_span, instrument};
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -39,7 +40,15 @@ pub struct StorageProof {
     pub opening_proof: OpeningProof<Curve>,
 }
 
-#[instrument(skip_all, level = "debug")]
+#[instrument(
+    skip_all,
+    level = "debug",
+    fields(
+        prover = "saffron-storage",
+        domain_size = SRS_SIZE,
+        columns = blob.commitments.len(),
+    )
+)]
 pub fn prove(
     srs: &SRS<Curve>,
     group_map: &<Curve as CommitmentCurve>::Map,
@@ -53,6 +62,7 @@ pub fn prove(
     let final_chunk = (blob.data.len() / SRS_SIZE) - 1;
     assert!(blob.data.len() % SRS_SIZE == 0);
 
+    let phase = debug_span!("commit_witness").entered();
     // ∑_{i=1} com_i^{challenge^i}
     let combined_data_commitment =
         utils::aggregate_commitments(challenge, blob.commitments.as_slice());
@@ -74,6 +84,9 @@ pub fn prove(
         initial
     };
 
+    drop(phase);
+
+    let phase = debug_span!("evaluations").entered();
     let mut curve_sponge = CurveSponge::new(Curve::other_curve_sponge_params());
     curve_sponge.absorb_g(&[combined_data_commitment]);
     let evaluation_point = curve_sponge.squeeze(2);
@@ -89,7 +102,9 @@ pub fn prove(
     // TODO: check and see if we need to also absorb the absorb the poly cm
     // see https://github.com/o1-labs/proof-systems/blob/feature/test-data-storage-commitments/data-storage/src/main.rs#L265-L269
     scalar_sponge.absorb(&combined_data_eval);
+    drop(phase);
 
+    let _phase = debug_span!("opening").entered();
     let opening_proof =
         srs.open(
             group_map,
@@ -173,6 +188,53 @@ pub fn verify(
     verify_wrt_combined_data_commitment(srs, group_map, combined_data_commitment, proof, rng)
 }
 
+/// The statement of a storage proof: the commitments to the data and the
+/// challenge used to combine them
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageStatement {
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub commitments: Vec<Curve>,
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    pub challenge: ScalarField,
+}
+
+/// The data needed to verify storage proofs
+pub struct StorageVerifier {
+    pub srs: SRS<Curve>,
+    pub group_map: <Curve as CommitmentCurve>::Map,
+}
+
+impl Proof for StorageProof {
+    const FORMAT: ProofFormat = ProofFormat::new("saffron-storage", 1);
+
+    type Statement = StorageStatement;
+
+    type Verifier = StorageVerifier;
+
+    fn verify(
+        &self,
+        verifier: &StorageVerifier,
+        statement: &StorageStatement,
+    ) -> Result<(), ProofError> {
+        let valid = verify(
+            &verifier.srs,
+            &verifier.group_map,
+            &statement.commitments,
+            statement.challenge,
+            self,
+            &mut OsRng,
+        );
+        if valid {
+            Ok(())
+        } else {
+            Err(ProofError::Invalid(
+                "the storage proof does not verify".into(),
+            ))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,95 @@
+//! JavaScript bindings to verify saffron storage proofs, for light clients and
+//! browser dashboards. Only the verifier is exposed.
+//!
+//! The SRS is given serialized with MessagePack, as stored by the SRS cache of
+//! the CLI, and the proofs as an [EncodedProof] serialized with MessagePack.
+//!
+//! ```js
+//! const verifier = new StorageProofVerifier(srsBytes);
+//! verifier.verify(proofBytes); // throws if the proof is rejected
+//! ```
+
+use crate::{
+    storage_proof::{StorageProof, StorageVerifier},
+    Curve,
+};
+use kimchi::groupmap::GroupMap;
+use o1_utils::proof::{EncodedProof, Proof};
+use poly_commitment::{commitment::CommitmentCurve, ipa::SRS};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub struct StorageProofVerifier(StorageVerifier);
+
+#[wasm_bindgen]
+impl StorageProofVerifier {
+    /// Create a verifier from an SRS serialized with MessagePack
+    #[wasm_bindgen(constructor)]
+    pub fn new(srs: &[u8]) -> Result<StorageProofVerifier, JsError> {
+        let srs: SRS<Curve> = rmp_serde::from_slice(srs)?;
+        Ok(StorageProofVerifier(StorageVerifier {
+            srs,
+            group_map: <Curve as CommitmentCurve>::Map::setup(),
+        }))
+    }
+
+    /// Verify a storage proof, encoded along with its statement.
+    /// An error describing why the proof is rejected is thrown otherwise.
+    pub fn verify(&self, encoded_proof: &[u8]) -> Result<(), JsError> {
+        let encoded: EncodedProof = rmp_serde::from_slice(encoded_proof)?;
+        let (proof, statement) = encoded.decode::<StorageProof>()?;
+        proof.verify(&self.0, &statement)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        blob::FieldBlob,
+        commitment::{combine_commitments, commit_to_field_elems},
+        encoding::encode_for_domain,
+        storage_proof::{self, StorageStatement},
+        CurveSponge, ScalarField,
+    };
+    use ark_ff::UniformRand;
+    use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+    use kimchi::curve::KimchiCurve;
+    use mina_poseidon::FqSponge;
+    use poly_commitment::SRS as _;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn test_verify_encoded_storage_proof() {
+        let mut rng = OsRng;
+        let srs = poly_commitment::precomputed_srs::get_srs_test::<Curve>();
+        let domain = Radix2EvaluationDomain::new(srs.size()).unwrap();
+        let group_map = <Curve as CommitmentCurve>::Map::setup();
+
+        let data = b"some data stored by a saffron node".to_vec();
+        let field_elems: Vec<_> = encode_for_domain(domain.size(), &data)
+            .into_iter()
+            .flatten()
+            .collect();
+        let commitments = commit_to_field_elems(&srs, &field_elems);
+        let mut sponge = CurveSponge::new(Curve::other_curve_sponge_params());
+        sponge.absorb_fr(&[ScalarField::rand(&mut rng)]);
+        let (_, challenge) = combine_commitments(&mut sponge, &commitments);
+
+        let blob = FieldBlob::from_bytes::<_>(&srs, domain, &data);
+        let proof = storage_proof::prove(&srs, &group_map, blob, challenge, &mut rng);
+        let statement = StorageStatement {
+            commitments,
+            challenge,
+        };
+        let encoded = EncodedProof::new(&proof, &statement).unwrap();
+
+        let Ok(verifier) = StorageProofVerifier::new(&rmp_serde::to_vec(&srs).unwrap()) else {
+            panic!("the SRS cannot be decoded");
+        };
+        assert!(verifier
+            .verify(&rmp_serde::to_vec(&encoded).unwrap())
+            .is_ok());
+    }
+}
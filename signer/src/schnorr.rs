@@ -24,6 +24,7 @@ use blake2::{
 };
 use core::ops::{Add, Neg};
 use mina_hasher::{self, DomainParameter, Hasher, ROInput};
+use o1_utils::ConstantTimeFieldHelpers;
 
 /// Schnorr signer context for the Mina signature algorithm
 ///
@@ -63,7 +64,7 @@ impl<H: 'static + Hashable> Signer<H> for Schnorr<H> {
         let r: CurvePoint = CurvePoint::generator()
             .mul_bigint(k.into_bigint())
             .into_affine();
-        let k: ScalarField = if r.y.into_bigint().is_even() { k } else { -k };
+        let k: ScalarField = ScalarField::ct_select(&k, &-k, r.y.into_bigint().is_even());
 
         let e: ScalarField = self.message_hash(&kp.public, r.x, input);
         let s: ScalarField = k + e * kp.secret.scalar();
@@ -4,7 +4,7 @@ extern crate alloc;
 use crate::ScalarField;
 use alloc::{string::String, vec, vec::Vec};
 use ark_ff::UniformRand;
-use o1_utils::FieldHelpers;
+use o1_utils::{ConstantTimeFieldHelpers, FieldHelpers};
 use rand::{self, CryptoRng, RngCore};
 use sha2::{Digest, Sha256};
 use thiserror::Error;
@@ -67,7 +67,7 @@ impl SecKey {
         sec_bytes.clone_from_slice(bytes);
         sec_bytes.reverse(); // mina scalars hex format is in big-endian order
         let secret =
-            ScalarField::from_bytes(&sec_bytes).map_err(|_| SecKeyError::SecretKeyBytes)?;
+            ScalarField::ct_from_bytes(&sec_bytes).map_err(|_| SecKeyError::SecretKeyBytes)?;
         Ok(SecKey(secret))
     }
 
@@ -0,0 +1,237 @@
+//! This module implements the Cairo builtins.
+//! A builtin owns a segment of the memory, starting at a base address, that is
+//! split into consecutive instances of a fixed number of cells. The first
+//! cells of an instance are inputs written by the program, the remaining ones
+//! are outputs that the runner deduces from the inputs when the program reads
+//! them. After the execution, every instance used by the program is validated
+//! against the builtin.
+//! Kimchi has sub-circuits for the range check and bitwise instances, but they
+//! are not wired to the memory of the execution: they only prove that the
+//! values given to them are valid instances.
+//!
+//! The following builtins are supported:
+//! - output: 1 input cell per value written to the output of the program
+//! - range check: 1 input cell that must fit in [RANGE_CHECK_BITS] bits
+//! - bitwise: 2 input cells `x` and `y`, and 3 output cells `x & y`, `x ^ y` and `x | y`
+//!
+//! The pedersen builtin is not supported: its hash is defined over the STARK
+//! curve, whose base field is not the field of the runner.
+
+use crate::memory::CairoMemory;
+use ark_ff::{Field, PrimeField};
+use o1_utils::{BigUintFieldHelpers, FieldHelpers};
+use thiserror::Error;
+
+/// Number of bits of the values accepted by the range check builtin
+pub const RANGE_CHECK_BITS: u64 = 128;
+
+/// Name of the output builtin
+pub const OUTPUT: &str = "output";
+
+/// Errors raised by the builtins
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BuiltinError {
+    /// An input does not fit in the number of bits accepted by the builtin
+    #[error("the input does not fit in {0} bits")]
+    OutOfRange(u64),
+    /// An instance is used but one of its input cells was never written
+    #[error("the input cell {0} of a used builtin instance was never written")]
+    MissingInput(u64),
+    /// An output cell contains a value that is not the one deduced from the inputs
+    #[error("the output cell {0} does not contain the deduced value")]
+    Inconsistent(u64),
+    /// The inputs of the instance starting at `addr` are rejected by the builtin
+    #[error("the builtin instance at {addr} is invalid: {reason}")]
+    InvalidInstance {
+        /// first address of the instance
+        addr: u64,
+        /// why the builtin rejected the inputs
+        reason: Box<BuiltinError>,
+    },
+}
+
+/// Result alias using [BuiltinError]
+pub type Result<T> = std::result::Result<T, BuiltinError>;
+
+/// The logic of a builtin
+pub trait Builtin<F> {
+    /// Name of the builtin, as used in the `%builtins` directive
+    fn name(&self) -> &'static str;
+
+    /// Number of memory cells of an instance
+    fn cells_per_instance(&self) -> usize;
+
+    /// Number of input cells of an instance, the first ones
+    fn n_input_cells(&self) -> usize;
+
+    /// Checks the inputs of an instance and computes its outputs
+    fn deduce(&self, inputs: &[F]) -> Result<Vec<F>>;
+}
+
+/// A memory segment owned by a builtin
+pub struct BuiltinSegment<F> {
+    /// first address of the segment
+    base: u64,
+    /// logic of the builtin
+    builtin: Box<dyn Builtin<F>>,
+}
+
+impl<F: Field> BuiltinSegment<F> {
+    /// Creates a segment starting at address `base`
+    pub fn new(base: u64, builtin: impl Builtin<F> + 'static) -> Self {
+        Self {
+            base,
+            builtin: Box::new(builtin),
+        }
+    }
+
+    /// Returns the first address of the segment
+    pub fn base(&self) -> u64 {
+        self.base
+    }
+
+    /// Returns the builtin owning the segment
+    pub fn builtin(&self) -> &dyn Builtin<F> {
+        self.builtin.as_ref()
+    }
+
+    /// Returns the first address of the instance containing `addr`, if `addr`
+    /// belongs to the segment
+    pub(crate) fn instance_of(&self, addr: u64) -> Option<u64> {
+        let offset = addr.checked_sub(self.base)?;
+        let cells = self.builtin.cells_per_instance() as u64;
+        Some(self.base + offset - offset % cells)
+    }
+
+    /// Returns the content of the cells of the instances used by the program,
+    /// that is, up to the first instance without any written cell
+    pub fn instances(&self, mem: &CairoMemory<F>) -> Vec<Vec<Option<F>>> {
+        let cells = self.builtin.cells_per_instance() as u64;
+        (0..)
+            .map(|i| {
+                let start = self.base + i * cells;
+                (start..start + cells).map(|addr| mem.get(addr)).collect()
+            })
+            .take_while(|instance: &Vec<Option<F>>| instance.iter().any(Option::is_some))
+            .collect()
+    }
+
+    /// Checks that every used instance has all of its inputs, that the builtin
+    /// accepts them, and that the written outputs are the deduced ones
+    pub fn validate(&self, mem: &CairoMemory<F>) -> Result<()> {
+        let cells = self.builtin.cells_per_instance();
+        let n_inputs = self.builtin.n_input_cells();
+        for (i, instance) in self.instances(mem).iter().enumerate() {
+            let start = self.base + (i * cells) as u64;
+            let inputs = instance[..n_inputs]
+                .iter()
+                .enumerate()
+                .map(|(j, cell)| cell.ok_or(BuiltinError::MissingInput(start + j as u64)))
+                .collect::<Result<Vec<F>>>()?;
+            let outputs =
+                self.builtin
+                    .deduce(&inputs)
+                    .map_err(|reason| BuiltinError::InvalidInstance {
+                        addr: start,
+                        reason: Box::new(reason),
+                    })?;
+            for (j, (cell, output)) in instance[n_inputs..].iter().zip(outputs).enumerate() {
+                if cell.is_some_and(|value| value != output) {
+                    return Err(BuiltinError::Inconsistent(start + (n_inputs + j) as u64));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The output builtin, whose segment holds the values output by the program.
+/// Its cells are part of the public memory.
+#[derive(Clone, Copy, Default)]
+pub struct Output;
+
+impl<F: Field> Builtin<F> for Output {
+    fn name(&self) -> &'static str {
+        OUTPUT
+    }
+
+    fn cells_per_instance(&self) -> usize {
+        1
+    }
+
+    fn n_input_cells(&self) -> usize {
+        1
+    }
+
+    fn deduce(&self, _inputs: &[F]) -> Result<Vec<F>> {
+        Ok(vec![])
+    }
+}
+
+/// The range check builtin, asserting that values fit in [RANGE_CHECK_BITS] bits
+#[derive(Clone, Copy, Default)]
+pub struct RangeCheck;
+
+impl<F: PrimeField> Builtin<F> for RangeCheck {
+    fn name(&self) -> &'static str {
+        "range_check"
+    }
+
+    fn cells_per_instance(&self) -> usize {
+        1
+    }
+
+    fn n_input_cells(&self) -> usize {
+        1
+    }
+
+    fn deduce(&self, inputs: &[F]) -> Result<Vec<F>> {
+        check_bits(inputs[0], RANGE_CHECK_BITS)?;
+        Ok(vec![])
+    }
+}
+
+/// The bitwise builtin, computing the AND, XOR and OR of two values of at most `bits` bits
+#[derive(Clone, Copy)]
+pub struct Bitwise {
+    /// number of bits of the inputs
+    bits: u64,
+}
+
+impl Bitwise {
+    /// Creates a bitwise builtin for inputs of at most `bits` bits
+    pub fn new(bits: u64) -> Self {
+        Self { bits }
+    }
+}
+
+impl<F: PrimeField> Builtin<F> for Bitwise {
+    fn name(&self) -> &'static str {
+        "bitwise"
+    }
+
+    fn cells_per_instance(&self) -> usize {
+        5
+    }
+
+    fn n_input_cells(&self) -> usize {
+        2
+    }
+
+    fn deduce(&self, inputs: &[F]) -> Result<Vec<F>> {
+        check_bits(inputs[0], self.bits)?;
+        check_bits(inputs[1], self.bits)?;
+        let (x, y) = (inputs[0].to_biguint(), inputs[1].to_biguint());
+        Ok([&x & &y, &x ^ &y, &x | &y]
+            .into_iter()
+            .map(|value| value.to_field().expect("fits in the field"))
+            .collect())
+    }
+}
+
+fn check_bits<F: PrimeField>(value: F, bits: u64) -> Result<()> {
+    if value.to_biguint().bits() > bits {
+        return Err(BuiltinError::OutOfRange(bits));
+    }
+    Ok(())
+}
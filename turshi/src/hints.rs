@@ -0,0 +1,130 @@
+//! This module implements the hint mechanism of the Cairo runner.
+//! Hints are host callbacks attached to a program counter, that are executed
+//! right before the instruction at that address. They can read the memory and
+//! the pointers, and write nondeterministic values to memory cells that the
+//! program then only constrains (e.g. the result of a square root that the
+//! program checks by squaring it). Cells written by hints are flagged in
+//! [CairoMemory] so that they can be told apart from the ones computed by the
+//! instructions.
+
+use crate::{
+    helper::CairoFieldHelpers,
+    memory::CairoMemory,
+    runner::{CairoState, Pointers},
+};
+use ark_ff::Field;
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// Errors raised while running hints
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum HintError {
+    /// A hint tried to write a value to a memory cell that already contains
+    /// a different one. Cairo memory is write-once.
+    #[error("hint tried to overwrite memory cell {0} with a different value")]
+    Overwrite(u64),
+    /// A hint failed for a reason of its own
+    #[error("hint failed: {0}")]
+    Custom(String),
+}
+
+/// Result alias using [HintError]
+pub type Result<T> = std::result::Result<T, HintError>;
+
+/// The view of the execution that is given to a hint
+pub struct HintContext<'a, F> {
+    /// memory of the execution
+    mem: &'a mut CairoMemory<F>,
+    /// pointers before executing the instruction the hint is attached to
+    ptrs: CairoState<F>,
+}
+
+impl<'a, F: Field> HintContext<'a, F> {
+    /// Creates a new hint context
+    pub fn new(mem: &'a mut CairoMemory<F>, ptrs: CairoState<F>) -> Self {
+        Self { mem, ptrs }
+    }
+
+    /// Read element in memory address
+    pub fn read(&mut self, addr: F) -> Option<F> {
+        self.mem.read(addr)
+    }
+
+    /// Writes a nondeterministic value in memory address
+    pub fn write(&mut self, addr: F, elem: F) -> Result<()> {
+        self.mem.write_hint(addr, elem)
+    }
+}
+
+impl<F: Field> Pointers<F> for HintContext<'_, F> {
+    fn pc(&self) -> F {
+        self.ptrs.pc()
+    }
+
+    fn ap(&self) -> F {
+        self.ptrs.ap()
+    }
+
+    fn fp(&self) -> F {
+        self.ptrs.fp()
+    }
+}
+
+/// A host callback run by the Cairo runner
+pub trait Hint<F> {
+    /// Runs the hint on the current state of the execution
+    fn execute(&self, ctx: &mut HintContext<F>) -> Result<()>;
+}
+
+impl<F, T> Hint<F> for T
+where
+    T: Fn(&mut HintContext<F>) -> Result<()>,
+{
+    fn execute(&self, ctx: &mut HintContext<F>) -> Result<()> {
+        self(ctx)
+    }
+}
+
+/// This struct stores the hints of a program, indexed by the program counter
+/// of the instruction they precede
+pub struct HintProcessor<F> {
+    hints: BTreeMap<u64, Vec<Box<dyn Hint<F>>>>,
+}
+
+impl<F> Default for HintProcessor<F> {
+    fn default() -> Self {
+        Self {
+            hints: BTreeMap::new(),
+        }
+    }
+}
+
+impl<F: Field> HintProcessor<F> {
+    /// Creates a processor without any hints
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attaches a hint to the instruction at address `pc`. Several hints
+    /// can be attached to the same instruction, they are run in the order in
+    /// which they were added.
+    pub fn add_hint(&mut self, pc: u64, hint: impl Hint<F> + 'static) {
+        self.hints.entry(pc).or_default().push(Box::new(hint));
+    }
+
+    /// Returns whether there are no hints
+    pub fn is_empty(&self) -> bool {
+        self.hints.is_empty()
+    }
+
+    /// Runs the hints attached to the current program counter, if any
+    pub fn execute(&self, mem: &mut CairoMemory<F>, ptrs: CairoState<F>) -> Result<()> {
+        if let Some(hints) = self.hints.get(&ptrs.pc().to_u64()) {
+            let mut ctx = HintContext::new(mem, ptrs);
+            for hint in hints {
+                hint.execute(&mut ctx)?;
+            }
+        }
+        Ok(())
+    }
+}
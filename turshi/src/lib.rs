@@ -6,13 +6,17 @@
 //! logic which is represented as steps of computation making up the full
 //! program.
 
+pub mod builtins;
 pub mod flags;
 pub mod helper;
+pub mod hints;
 pub mod memory;
 pub mod runner;
 pub mod word;
 
 pub use self::{
+    builtins::{Bitwise, Builtin, Output, RangeCheck},
+    hints::{HintContext, HintProcessor},
     memory::CairoMemory,
     runner::{CairoInstruction, CairoProgram, Pointers},
     word::{FlagBits, Offsets},
@@ -2,11 +2,17 @@
 //! compiled Cairo program that occupies the first few entries
 
 use std::{
+    collections::BTreeSet,
     fmt::{Display, Formatter, Result},
     ops::{Index, IndexMut},
 };
 
-use crate::{helper::*, word::CairoWord};
+use crate::{
+    builtins::{self, Builtin, BuiltinSegment},
+    helper::*,
+    hints::{self, HintError},
+    word::CairoWord,
+};
 use ark_ff::Field;
 use core::iter::repeat;
 
@@ -16,6 +22,10 @@ pub struct CairoMemory<F> {
     codelen: usize,
     /// full memory vector, None if non initialized
     data: Vec<Option<CairoWord<F>>>,
+    /// addresses of the cells written by hints
+    hinted: BTreeSet<u64>,
+    /// segments owned by builtins
+    builtins: Vec<BuiltinSegment<F>>,
 }
 
 impl<F: Field> Index<F> for CairoMemory<F> {
@@ -61,6 +71,8 @@ impl<F: Field> CairoMemory<F> {
         CairoMemory {
             codelen: aux.len() - 1,
             data: aux.into_iter().map(|i| Some(CairoWord::new(i))).collect(),
+            hinted: BTreeSet::new(),
+            builtins: Vec::new(),
         }
     }
 
@@ -94,9 +106,134 @@ impl<F: Field> CairoMemory<F> {
         self[addr] = Some(CairoWord::new(elem));
     }
 
-    /// Read element in memory address
+    /// Write a nondeterministic element in memory address, as done by hints.
+    /// Fails if the cell already contains a different value.
+    pub fn write_hint(&mut self, addr: F, elem: F) -> hints::Result<()> {
+        match self.read(addr) {
+            Some(prev) if prev != elem => Err(HintError::Overwrite(addr.to_u64())),
+            _ => {
+                self.write(addr, elem);
+                self.hinted.insert(addr.to_u64());
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns whether the content of the memory address was written by a hint
+    pub fn is_hinted(&self, addr: F) -> bool {
+        self.hinted.contains(&addr.to_u64())
+    }
+
+    /// Read element in memory address. If the cell belongs to a builtin
+    /// segment and was never written, its content is deduced by the builtin.
     pub fn read(&mut self, addr: F) -> Option<F> {
         self.resize(addr.to_u64()); // Resize if necessary
+        if self[addr].is_none() {
+            self.deduce(addr.to_u64());
+        }
         self[addr].map(|x| x.word())
     }
+
+    /// Read element in memory address without resizing nor deducing it
+    pub fn get(&self, addr: u64) -> Option<F> {
+        self.data
+            .get(addr as usize)
+            .copied()
+            .flatten()
+            .map(|x| x.word())
+    }
+
+    /// Gives the segment starting at address `base` to a builtin. The program
+    /// usually receives `base` as an argument of its main function.
+    pub fn add_builtin(&mut self, base: u64, builtin: impl Builtin<F> + 'static) {
+        self.builtins.push(BuiltinSegment::new(base, builtin));
+    }
+
+    /// Returns the segments owned by builtins
+    pub fn builtins(&self) -> &[BuiltinSegment<F>] {
+        &self.builtins
+    }
+
+    /// Returns the values written to the segment of the output builtin, if any
+    pub fn outputs(&self) -> Vec<F> {
+        self.output_segment()
+            .map(|segment| {
+                segment
+                    .instances(self)
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the words of the compiled program
+    pub fn code(&self) -> Vec<F> {
+        (1..=self.codelen as u64)
+            .map(|addr| self.get(addr).expect("code is set"))
+            .collect()
+    }
+
+    /// Returns the output segment as pairs of address and value
+    pub fn output_memory(&self) -> Vec<(u64, F)> {
+        self.output_segment()
+            .map(|segment| segment.base())
+            .into_iter()
+            .flat_map(|base| (base..).zip(self.outputs()))
+            .collect()
+    }
+
+    /// Returns the public memory as pairs of address and value: the
+    /// compiled program followed by the output segment. This is the part of
+    /// the memory that the verifier knows.
+    pub fn public_memory(&self) -> Vec<(u64, F)> {
+        let code = (1..).zip(self.code());
+        code.chain(self.output_memory()).collect()
+    }
+
+    fn output_segment(&self) -> Option<&BuiltinSegment<F>> {
+        self.builtins
+            .iter()
+            .find(|segment| segment.builtin().name() == builtins::OUTPUT)
+    }
+
+    /// Checks the instances of all the builtins used by the program
+    pub fn validate_builtins(&self) -> builtins::Result<()> {
+        self.builtins
+            .iter()
+            .try_for_each(|segment| segment.validate(self))
+    }
+
+    /// Fills the outputs of the builtin instance containing `addr`, if any,
+    /// when all its inputs are known. Invalid inputs are left to
+    /// [CairoMemory::validate_builtins].
+    fn deduce(&mut self, addr: u64) {
+        // the segment with the closest base below addr owns it
+        let Some(segment) = self
+            .builtins
+            .iter()
+            .filter(|segment| segment.base() <= addr)
+            .max_by_key(|segment| segment.base())
+        else {
+            return;
+        };
+        let builtin = segment.builtin();
+        let start = segment.instance_of(addr).expect("addr is in the segment");
+        let n_inputs = builtin.n_input_cells() as u64;
+        let Some(inputs) = (start..start + n_inputs)
+            .map(|a| self.get(a))
+            .collect::<Option<Vec<F>>>()
+        else {
+            return;
+        };
+        let Ok(outputs) = builtin.deduce(&inputs) else {
+            return;
+        };
+        for (a, output) in (start + n_inputs..).zip(outputs) {
+            if self.get(a).is_none() {
+                self.write(F::from(a), output);
+            }
+        }
+    }
 }
@@ -3,6 +3,7 @@
 
 use crate::{
     flags::*,
+    hints::{self, HintProcessor},
     memory::CairoMemory,
     word::{CairoWord, FlagBits, FlagSets, Offsets},
 };
@@ -504,6 +505,17 @@ pub struct CairoProgram<'a, F> {
 impl<'a, F: Field> CairoProgram<'a, F> {
     /// Creates a Cairo execution from the public information (memory and initial pointers)
     pub fn new(mem: &mut CairoMemory<F>, pc: u64) -> CairoProgram<F> {
+        CairoProgram::new_with_hints(mem, pc, &HintProcessor::new())
+            .expect("a program without hints cannot fail on hints")
+    }
+
+    /// Creates a Cairo execution from the public information (memory and initial pointers),
+    /// running the given hints before the instructions they are attached to
+    pub fn new_with_hints(
+        mem: &'a mut CairoMemory<F>,
+        pc: u64,
+        hints: &HintProcessor<F>,
+    ) -> hints::Result<CairoProgram<'a, F>> {
         let ap = mem.len();
         let mut prog = CairoProgram {
             steps: F::zero(),
@@ -512,8 +524,8 @@ impl<'a, F: Field> CairoProgram<'a, F> {
             fin: CairoState::new(F::zero(), F::zero(), F::zero()),
             trace: Vec::new(),
         };
-        prog.execute();
-        prog
+        prog.execute(hints)?;
+        Ok(prog)
     }
 
     /// Outputs the total number of steps of the execution carried out by the runner
@@ -531,6 +543,26 @@ impl<'a, F: Field> CairoProgram<'a, F> {
         self.fin
     }
 
+    /// Returns the values output by the program, see [CairoMemory::outputs]
+    pub fn outputs(&self) -> Vec<F> {
+        self.mem.outputs()
+    }
+
+    /// Returns the words of the program, see [CairoMemory::code]
+    pub fn code(&self) -> Vec<F> {
+        self.mem.code()
+    }
+
+    /// Returns the output segment of the execution, see [CairoMemory::output_memory]
+    pub fn output_memory(&self) -> Vec<(u64, F)> {
+        self.mem.output_memory()
+    }
+
+    /// Returns the public memory of the execution, see [CairoMemory::public_memory]
+    pub fn public_memory(&self) -> Vec<(u64, F)> {
+        self.mem.public_memory()
+    }
+
     /// Returns a reference to the set of instructions
     pub fn trace(&self) -> &Vec<CairoInstruction<F>> {
         &self.trace
@@ -538,7 +570,7 @@ impl<'a, F: Field> CairoProgram<'a, F> {
 
     /// This function simulates an execution of the Cairo program received as input.
     /// It generates the full memory stack and the execution trace
-    fn execute(&mut self) {
+    fn execute(&mut self, hints: &HintProcessor<F>) -> hints::Result<()> {
         // set finishing flag to false, as it just started
         let mut end = false;
         // saves local copy of the initial (claimed) pointers of the program
@@ -548,6 +580,8 @@ impl<'a, F: Field> CairoProgram<'a, F> {
         let mut n: u64 = 0;
         // keep executing steps until the end is reached
         while !end {
+            // run the hints of the instruction, before it reads its operands
+            hints.execute(self.mem, next)?;
             // create current step of computation
             let mut step = CairoStep::new(self.mem, next);
             // save current value of the pointers
@@ -572,5 +606,6 @@ impl<'a, F: Field> CairoProgram<'a, F> {
         }
         self.steps = F::from(n);
         self.fin = CairoState::new(curr.pc, curr.ap, curr.fp);
+        Ok(())
     }
 }
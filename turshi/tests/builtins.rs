@@ -0,0 +1,50 @@
+use ark_ff::Field;
+use mina_curves::pasta::Fp as F;
+use turshi::{
+    builtins::{BuiltinError, RANGE_CHECK_BITS},
+    Bitwise, CairoMemory, RangeCheck,
+};
+
+fn empty_memory() -> CairoMemory<F> {
+    CairoMemory::new(vec![F::from(0x208b7fff7fff7ffeu64)])
+}
+
+#[test]
+fn test_range_check_builtin() {
+    let mut mem = empty_memory();
+    mem.add_builtin(10, RangeCheck);
+    mem.write(F::from(10u32), F::from(u64::MAX));
+    mem.write(F::from(11u32), F::from(2u32).pow([RANGE_CHECK_BITS - 1]));
+    assert_eq!(mem.builtins()[0].instances(&mem).len(), 2);
+    assert_eq!(mem.validate_builtins(), Ok(()));
+
+    mem.write(F::from(12u32), F::from(2u32).pow([RANGE_CHECK_BITS]));
+    assert_eq!(
+        mem.validate_builtins(),
+        Err(BuiltinError::InvalidInstance {
+            addr: 12,
+            reason: Box::new(BuiltinError::OutOfRange(RANGE_CHECK_BITS)),
+        })
+    );
+}
+
+#[test]
+fn test_bitwise_builtin() {
+    let mut mem = empty_memory();
+    mem.add_builtin(10, Bitwise::new(64));
+    mem.write(F::from(10u32), F::from(0b1100u32));
+    mem.write(F::from(11u32), F::from(0b1010u32));
+    // outputs are deduced when read
+    assert_eq!(mem.read(F::from(13u32)), Some(F::from(0b0110u32)));
+    assert_eq!(mem.read(F::from(12u32)), Some(F::from(0b1000u32)));
+    assert_eq!(mem.read(F::from(14u32)), Some(F::from(0b1110u32)));
+    // inputs are never deduced
+    assert_eq!(mem.read(F::from(15u32)), None);
+    assert_eq!(mem.validate_builtins(), Ok(()));
+
+    mem.write(F::from(15u32), F::from(1u32));
+    assert_eq!(mem.validate_builtins(), Err(BuiltinError::MissingInput(16)));
+    mem.write(F::from(16u32), F::from(1u32));
+    mem.write(F::from(17u32), F::from(0u32));
+    assert_eq!(mem.validate_builtins(), Err(BuiltinError::Inconsistent(17)));
+}
@@ -0,0 +1,51 @@
+use mina_curves::pasta::Fp as F;
+use turshi::{
+    hints::{HintError, Result},
+    CairoMemory, CairoProgram, HintContext, HintProcessor, Pointers,
+};
+
+/// Builds the memory of the following program, where the content of `[ap]`
+/// is not computed by any instruction and must be provided by a hint
+///     [ap+1] = [ap] + [ap]; ap++
+///     ret
+fn hinted_memory() -> CairoMemory<F> {
+    let instrs = [0x4830800080008001, 0x208b7fff7fff7ffe]
+        .iter()
+        .map(|&i: &i64| F::from(i))
+        .collect();
+    let mut mem = CairoMemory::new(instrs);
+    mem.write(F::from(3u32), F::from(7u32));
+    mem.write(F::from(4u32), F::from(7u32));
+    mem
+}
+
+#[test]
+fn test_cairo_hint() {
+    let mut mem = hinted_memory();
+    let mut hints = HintProcessor::new();
+    hints.add_hint(1, |ctx: &mut HintContext<F>| -> Result<()> {
+        let ap = ctx.ap();
+        ctx.write(ap, F::from(21u32))
+    });
+    let prog = CairoProgram::new_with_hints(&mut mem, 1, &hints).unwrap();
+    assert_eq!(prog.fin().pc(), F::from(2u32));
+    assert_eq!(prog.mem.read(F::from(5u32)).unwrap(), F::from(21u32));
+    assert_eq!(prog.mem.read(F::from(6u32)).unwrap(), F::from(42u32));
+    assert!(prog.mem.is_hinted(F::from(5u32)));
+    assert!(!prog.mem.is_hinted(F::from(6u32)));
+}
+
+#[test]
+fn test_cairo_hint_overwrite() {
+    let mut mem = hinted_memory();
+    let mut hints = HintProcessor::new();
+    // The return address of the frame is already set
+    hints.add_hint(1, |ctx: &mut HintContext<F>| -> Result<()> {
+        let fp = ctx.fp();
+        ctx.write(fp - F::from(1u32), F::from(8u32))
+    });
+    assert_eq!(
+        CairoProgram::new_with_hints(&mut mem, 1, &hints).err(),
+        Some(HintError::Overwrite(4))
+    );
+}
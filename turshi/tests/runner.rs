@@ -1,7 +1,7 @@
 use mina_curves::pasta::Fp as F;
 use turshi::{
     runner::{CairoState, CairoStep},
-    CairoMemory, CairoProgram, Pointers,
+    CairoMemory, CairoProgram, Output, Pointers,
 };
 
 #[test]
@@ -95,6 +95,7 @@ fn test_cairo_output() {
     mem.write(F::from(21u32), F::from(41u32)); // beginning of outputs
     mem.write(F::from(22u32), F::from(44u32)); // end of outputs
     mem.write(F::from(23u32), F::from(44u32)); //end of program
+    mem.add_builtin(41, Output);
     let prog = CairoProgram::new(&mut mem, 5);
     assert_eq!(prog.fin().pc(), F::from(20u32));
     assert_eq!(prog.fin().ap(), F::from(41u32));
@@ -120,4 +121,16 @@ fn test_cairo_output() {
     assert_eq!(prog.mem.read(F::from(41u32)).unwrap(), F::from(10u32));
     assert_eq!(prog.mem.read(F::from(42u32)).unwrap(), F::from(20u32));
     assert_eq!(prog.mem.read(F::from(43u32)).unwrap(), F::from(410u32));
+
+    let outputs = vec![F::from(10u32), F::from(20u32), F::from(410u32)];
+    assert_eq!(prog.outputs(), outputs);
+    let public_memory = prog.public_memory();
+    assert_eq!(public_memory.len(), 20 + outputs.len());
+    assert_eq!(public_memory[0], (1, F::from(0x400380007ffc7ffdu64)));
+    assert_eq!(
+        public_memory[20..],
+        [(41, outputs[0]), (42, outputs[1]), (43, outputs[2])]
+    );
+    assert_eq!(prog.code().len(), 20);
+    assert_eq!(prog.output_memory(), public_memory[20..]);
 }
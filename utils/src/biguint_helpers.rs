@@ -9,7 +9,14 @@ pub trait BigUintHelpers<Rhs = Self> {
     fn bitlen(&self) -> usize;
 
     /// Creates a BigUint from an hexadecimal string in big endian
+    /// Panics if the string is not valid hexadecimal
     fn from_hex(s: &str) -> Self;
+
+    /// Creates a BigUint from an hexadecimal string in big endian, returning
+    /// `None` if the string is not valid hexadecimal
+    fn try_from_hex(s: &str) -> Option<Self>
+    where
+        Self: Sized;
 }
 
 impl BigUintHelpers for BigUint {
@@ -21,6 +28,9 @@ impl BigUintHelpers for BigUint {
         }
     }
     fn from_hex(s: &str) -> Self {
-        BigUint::parse_bytes(s.as_bytes(), 16).unwrap()
+        Self::try_from_hex(s).unwrap()
+    }
+    fn try_from_hex(s: &str) -> Option<Self> {
+        BigUint::parse_bytes(s.as_bytes(), 16)
     }
 }
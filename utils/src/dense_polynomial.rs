@@ -21,6 +21,13 @@ pub trait ExtendedDensePolynomial<F: Field> {
     /// `eval_polynomial(coeffs, x)` evaluates a polynomial given its coefficients `coeffs` and a point `x`.
     fn eval_polynomial(coeffs: &[F], x: F) -> F;
 
+    /// Evaluates each chunk of size `chunk_size` of the polynomial at `x`.
+    /// This is equivalent to `self.to_chunked_polynomial(num_chunks,
+    /// chunk_size).evaluate_chunks(x)`, but does not materialize the chunk
+    /// polynomials.
+    /// Implementors must ensure that the result contains exactly num_chunks.
+    fn eval_chunks(&self, num_chunks: usize, chunk_size: usize, x: F) -> Vec<F>;
+
     /// Convert a polynomial into chunks.
     /// Implementors must ensure that the result contains exactly num_chunks.
     fn to_chunked_polynomial(&self, num_chunks: usize, size: usize) -> ChunkedPolynomial<F>;
@@ -46,6 +53,25 @@ impl<F: Field> ExtendedDensePolynomial<F> for DensePolynomial<F> {
         DensePolynomial::from_coefficients_slice(coeffs).evaluate(&x)
     }
 
+    fn eval_chunks(&self, num_chunks: usize, chunk_size: usize, x: F) -> Vec<F> {
+        let mut res: Vec<F> = self
+            .coeffs
+            .par_chunks(chunk_size)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .rev()
+                    .fold(F::zero(), |acc, coeff| acc * x + coeff)
+            })
+            .collect();
+
+        // Ensuring that the number of chunks is the one requested, following
+        // trait documentation
+        assert!(res.len() <= num_chunks);
+        res.resize(num_chunks, F::zero());
+        res
+    }
+
     fn to_chunked_polynomial(&self, num_chunks: usize, chunk_size: usize) -> ChunkedPolynomial<F> {
         let mut chunk_polys: Vec<DensePolynomial<F>> = Vec::with_capacity(num_chunks);
         for chunk in self.coeffs.chunks(chunk_size) {
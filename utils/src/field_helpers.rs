@@ -18,6 +18,10 @@ pub enum FieldHelpersError {
     DecodeHex,
     #[error("failed to convert BigUint into field element")]
     FromBigToField,
+    #[error("value is not smaller than the field modulus")]
+    NotCanonical,
+    #[error("expected {expected} bytes, got {actual}")]
+    InvalidLength { expected: usize, actual: usize },
 }
 
 /// Result alias using [FieldHelpersError]
@@ -87,6 +91,46 @@ pub trait FieldHelpers<F> {
         Ok(F::from(big.clone()))
     }
 
+    /// Deserialize from BigUint, failing with
+    /// [FieldHelpersError::NotCanonical] if `big` is not smaller than the
+    /// modulus instead of reducing it.
+    fn from_biguint_checked(big: &BigUint) -> Result<F>
+    where
+        F: PrimeField,
+    {
+        if big >= &F::modulus_biguint() {
+            return Err(FieldHelpersError::NotCanonical);
+        }
+        F::from_biguint(big)
+    }
+
+    /// Deserialize from exactly [FieldHelpers::size_in_bytes] little-endian
+    /// bytes encoding a value smaller than the modulus. Use this for inputs
+    /// coming from untrusted sources.
+    fn from_bytes_checked(bytes: &[u8]) -> Result<F>
+    where
+        F: PrimeField,
+    {
+        let expected = F::size_in_bytes();
+        if bytes.len() != expected {
+            return Err(FieldHelpersError::InvalidLength {
+                expected,
+                actual: bytes.len(),
+            });
+        }
+        F::from_biguint_checked(&BigUint::from_bytes_le(bytes))
+    }
+
+    /// Deserialize from little-endian hex, with the same checks as
+    /// [FieldHelpers::from_bytes_checked]
+    fn from_hex_checked(hex: &str) -> Result<F>
+    where
+        F: PrimeField,
+    {
+        let bytes: Vec<u8> = hex::decode(hex).map_err(|_| FieldHelpersError::DecodeHex)?;
+        F::from_bytes_checked(&bytes)
+    }
+
     /// Serialize to bytes
     fn to_bytes(&self) -> Vec<u8>;
 
@@ -186,16 +230,120 @@ impl<F: Field> FieldHelpers<F> for F {
     }
 }
 
+/// Constant-time field element helpers
+///
+/// These mirror the conversions and comparisons of [FieldHelpers], but never
+/// branch on (or return early because of) the value of a secret operand. Only
+/// public properties, such as the length of a byte slice, or the final
+/// success of a deserialization, may influence control flow. Use them in
+/// signing and nonce derivation paths instead of their variable-time
+/// counterparts.
+pub trait ConstantTimeFieldHelpers<F> {
+    /// Deserialize from little-endian bytes, checking canonicity in constant
+    /// time.
+    fn ct_from_bytes(bytes: &[u8]) -> Result<F>;
+
+    /// Serialize to little-endian bytes of length
+    /// [FieldHelpers::size_in_bytes]
+    fn ct_to_bytes(&self) -> Vec<u8>;
+
+    /// Constant-time equality
+    fn ct_eq(&self, other: &F) -> bool;
+
+    /// Constant-time zero test
+    fn ct_is_zero(&self) -> bool;
+
+    /// Returns `a` if `choice` is set and `b` otherwise, without branching on
+    /// `choice`.
+    fn ct_select(a: &F, b: &F, choice: bool) -> F;
+}
+
+impl<F: PrimeField> ConstantTimeFieldHelpers<F> for F {
+    fn ct_from_bytes(bytes: &[u8]) -> Result<F> {
+        // The length of the input is public
+        if bytes.len() != F::size_in_bytes() {
+            return Err(FieldHelpersError::DeserializeBytes);
+        }
+        let modulus = F::MODULUS.to_bytes_le();
+        if !ct_lt_bytes_le(bytes, &modulus[..bytes.len()]) {
+            return Err(FieldHelpersError::DeserializeBytes);
+        }
+        // The input is known to be canonical, so the reduction is a no-op
+        Ok(F::from_le_bytes_mod_order(bytes))
+    }
+
+    fn ct_to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.into_bigint().to_bytes_le();
+        bytes.truncate(F::size_in_bytes());
+        bytes
+    }
+
+    fn ct_eq(&self, other: &F) -> bool {
+        let lhs = self.into_bigint();
+        let rhs = other.into_bigint();
+        let diff = lhs
+            .as_ref()
+            .iter()
+            .zip(rhs.as_ref())
+            .fold(0u64, |acc, (l, r)| acc | (l ^ r));
+        ct_is_zero_u64(diff)
+    }
+
+    fn ct_is_zero(&self) -> bool {
+        let diff = self
+            .into_bigint()
+            .as_ref()
+            .iter()
+            .fold(0u64, |acc, limb| acc | limb);
+        ct_is_zero_u64(diff)
+    }
+
+    fn ct_select(a: &F, b: &F, choice: bool) -> F {
+        // b + choice * (a - b), where the multiplication does not depend on
+        // the value of choice
+        let choice = F::from(std::hint::black_box(choice) as u64);
+        *b + (*a - b) * choice
+    }
+}
+
+/// Returns whether `x` is zero, without branching on `x`
+fn ct_is_zero_u64(x: u64) -> bool {
+    let x = std::hint::black_box(x);
+    // The top bit of `x | -x` is set if and only if `x` is non-zero
+    (((x | x.wrapping_neg()) >> 63) ^ 1) == 1
+}
+
+/// Returns whether `a < b` when both are interpreted as little-endian integers
+/// of the same length, without branching on their contents
+fn ct_lt_bytes_le(a: &[u8], b: &[u8]) -> bool {
+    assert_eq!(a.len(), b.len());
+    // Walk from the least to the most significant byte, so that the most
+    // significant differing byte decides the final borrow
+    let borrow = a.iter().zip(b).fold(0u16, |borrow, (x, y)| {
+        let diff = (*x as u16).wrapping_sub(*y as u16).wrapping_sub(borrow);
+        (diff >> 8) & 1
+    });
+    std::hint::black_box(borrow) == 1
+}
+
 /// Field element wrapper for [BigUint]
 pub trait BigUintFieldHelpers {
     /// Convert BigUint into PrimeField element
     fn to_field<F: PrimeField>(self) -> Result<F>;
+
+    /// Convert BigUint into PrimeField element, failing if it is not smaller
+    /// than the modulus
+    fn to_field_checked<F: PrimeField>(self) -> Result<F>;
 }
 
 impl BigUintFieldHelpers for BigUint {
     fn to_field<F: PrimeField>(self) -> Result<F> {
         F::from_biguint(&self)
     }
+
+    fn to_field_checked<F: PrimeField>(self) -> Result<F> {
+        F::from_biguint_checked(&self)
+    }
 }
 
 /// Converts an [i32] into a [Field]
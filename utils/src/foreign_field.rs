@@ -10,6 +10,22 @@ use std::{
     fmt::{Debug, Formatter},
     ops::{Index, IndexMut},
 };
+use thiserror::Error;
+
+/// Foreign element error
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ForeignElementError {
+    /// The value does not fit in the available limbs
+    #[error("value of {bits} bits does not fit in {limbs} limbs of {limb_bits} bits")]
+    TooLarge {
+        /// Bit length of the value
+        bits: u64,
+        /// Bit length of one limb
+        limb_bits: usize,
+        /// Number of limbs
+        limbs: usize,
+    },
+}
 
 /// Represents a foreign field element
 #[derive(Clone, PartialEq, Eq)]
@@ -38,24 +54,34 @@ impl<F: Field, const B: usize, const N: usize> ForeignElement<F, B, N> {
     /// Initializes a new foreign element from a big unsigned integer
     /// Panics if the BigUint is too large to fit in the `N` limbs
     pub fn from_biguint(big: BigUint) -> Self {
+        Self::try_from_biguint(big).expect("BigUint element is too large for N limbs")
+    }
+
+    /// Initializes a new foreign element from a big unsigned integer, failing
+    /// with [ForeignElementError::TooLarge] if it does not fit in `N` limbs of
+    /// `B` bits
+    pub fn try_from_biguint(big: BigUint) -> Result<Self, ForeignElementError> {
+        if big.bits() > (B * N) as u64 {
+            return Err(ForeignElementError::TooLarge {
+                bits: big.bits(),
+                limb_bits: B,
+                limbs: N,
+            });
+        }
         let vec = ForeignElement::<F, B, N>::big_to_vec(big);
 
         // create an array of N native elements containing the limbs
         // until the array is full in big endian, so most significant
         // limbs may be zero if the big number is smaller
-        if vec.len() > N {
-            panic!("BigUint element is too large for N limbs");
-        }
-
         let mut limbs = [F::zero(); N];
         for (i, term) in vec.iter().enumerate() {
             limbs[i] = *term;
         }
 
-        Self {
+        Ok(Self {
             limbs,
             len: limbs.len(),
-        }
+        })
     }
 
     /// Initializes a new foreign element from an absolute `BigUint` but the equivalent
@@ -74,6 +100,12 @@ impl<F: Field, const B: usize, const N: usize> ForeignElement<F, B, N> {
         Self::from_biguint(BigUint::from_bytes_be(bytes))
     }
 
+    /// Initializes a new foreign element from a set of bytes in big endian,
+    /// failing if the value does not fit in `N` limbs of `B` bits
+    pub fn try_from_be(bytes: &[u8]) -> Result<Self, ForeignElementError> {
+        Self::try_from_biguint(BigUint::from_bytes_be(bytes))
+    }
+
     /// Obtains the big integer representation of the foreign field element
     pub fn to_biguint(&self) -> BigUint {
         let mut bytes = vec![];
@@ -105,6 +105,12 @@ impl<T> LazyCache<T> {
     pub fn get(&self) -> &T {
         self.try_get().unwrap()
     }
+
+    /// Returns whether the value has already been computed, or was given at
+    /// creation with [LazyCache::preinit].
+    pub fn is_initialized(&self) -> bool {
+        self.once.is_completed()
+    }
 }
 
 // Wrapper to support cases where the init function might return an error that
@@ -197,9 +203,12 @@ mod test {
                 let b = 20;
                 a + b
             });
+            assert!(!lazy.is_initialized());
             assert_eq!(*lazy.get(), 30);
+            assert!(lazy.is_initialized());
             // Ensure the value is cached and can be accessed multiple times
             assert_eq!(*lazy.get(), 30);
+            assert!(cache.is_initialized());
         }
 
         // function called only once
@@ -14,6 +14,8 @@ pub mod foreign_field;
 pub mod hasher;
 pub mod lazy_cache;
 pub mod math;
+pub mod pointwise_evaluations;
+pub mod proof;
 pub mod serialization;
 
 pub use biguint_helpers::BigUintHelpers;
@@ -21,7 +23,9 @@ pub use bitwise_operations::BitwiseOps;
 pub use chunked_evaluations::ChunkedEvaluations;
 pub use dense_polynomial::ExtendedDensePolynomial;
 pub use evaluations::ExtendedEvaluations;
-pub use field_helpers::{BigUintFieldHelpers, FieldHelpers, RandomField, Two};
+pub use field_helpers::{
+    BigUintFieldHelpers, ConstantTimeFieldHelpers, FieldHelpers, RandomField, Two,
+};
 pub use foreign_field::ForeignElement;
 
 /// Utils only for testing
@@ -0,0 +1,130 @@
+//! Pointwise arithmetic on [Evaluations] over matching domains.
+//!
+//! The provers combine columns given in evaluation form, and move them between
+//! the base domain `d1` and its extensions `d2`, `d4`, `d8`. This module
+//! provides these operations, parallelised with rayon. Only the IVC prover
+//! uses it for now: kimchi, folding and kimchi_msm still have their own
+//! versions.
+//!
+//! All binary operations require both operands to be defined over the same
+//! domain and return [PointwiseError::DomainMismatch] otherwise. Use
+//! [resize] first to bring them on the same domain.
+
+use ark_ff::FftField;
+use ark_poly::{EvaluationDomain, Evaluations, Radix2EvaluationDomain as D};
+use rayon::prelude::*;
+use std::cmp::Ordering;
+use thiserror::Error;
+
+/// Errors raised by the pointwise operations
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PointwiseError {
+    /// The operands are not defined over the same domain
+    #[error("the operands are defined over domains of size {0} and {1}")]
+    DomainMismatch(u64, u64),
+}
+
+/// Result alias using [PointwiseError]
+pub type Result<T> = std::result::Result<T, PointwiseError>;
+
+fn check_domains<F: FftField>(
+    lhs: &Evaluations<F, D<F>>,
+    rhs: &Evaluations<F, D<F>>,
+) -> Result<D<F>> {
+    let (d_lhs, d_rhs) = (lhs.domain(), rhs.domain());
+    if d_lhs != d_rhs {
+        return Err(PointwiseError::DomainMismatch(d_lhs.size, d_rhs.size));
+    }
+    Ok(d_lhs)
+}
+
+fn zip_with<F: FftField>(
+    lhs: &Evaluations<F, D<F>>,
+    rhs: &Evaluations<F, D<F>>,
+    op: impl Fn(&F, &F) -> F + Sync,
+) -> Result<Evaluations<F, D<F>>> {
+    let domain = check_domains(lhs, rhs)?;
+    let evals = lhs
+        .evals
+        .par_iter()
+        .zip(rhs.evals.par_iter())
+        .map(|(l, r)| op(l, r))
+        .collect();
+    Ok(Evaluations::from_vec_and_domain(evals, domain))
+}
+
+/// Computes `lhs + rhs` pointwise
+pub fn add<F: FftField>(
+    lhs: &Evaluations<F, D<F>>,
+    rhs: &Evaluations<F, D<F>>,
+) -> Result<Evaluations<F, D<F>>> {
+    zip_with(lhs, rhs, |l, r| *l + r)
+}
+
+/// Computes `lhs - rhs` pointwise
+pub fn sub<F: FftField>(
+    lhs: &Evaluations<F, D<F>>,
+    rhs: &Evaluations<F, D<F>>,
+) -> Result<Evaluations<F, D<F>>> {
+    zip_with(lhs, rhs, |l, r| *l - r)
+}
+
+/// Computes `lhs * rhs` pointwise
+pub fn mul<F: FftField>(
+    lhs: &Evaluations<F, D<F>>,
+    rhs: &Evaluations<F, D<F>>,
+) -> Result<Evaluations<F, D<F>>> {
+    zip_with(lhs, rhs, |l, r| *l * r)
+}
+
+/// Multiplies each evaluation by `scalar`
+pub fn scale<F: FftField>(evals: &Evaluations<F, D<F>>, scalar: F) -> Evaluations<F, D<F>> {
+    let res = evals.evals.par_iter().map(|e| *e * scalar).collect();
+    Evaluations::from_vec_and_domain(res, evals.domain())
+}
+
+/// Computes `acc += scalar * evals` pointwise, in place
+pub fn add_scaled_assign<F: FftField>(
+    acc: &mut Evaluations<F, D<F>>,
+    scalar: F,
+    evals: &Evaluations<F, D<F>>,
+) -> Result<()> {
+    check_domains(acc, evals)?;
+    acc.evals
+        .par_iter_mut()
+        .zip(evals.evals.par_iter())
+        .for_each(|(a, e)| *a += scalar * e);
+    Ok(())
+}
+
+/// Computes `sum_i scalar_i * evals_i` pointwise. All the evaluations must be
+/// over `domain`.
+pub fn linear_combination<F: FftField>(
+    domain: D<F>,
+    terms: &[(F, &Evaluations<F, D<F>>)],
+) -> Result<Evaluations<F, D<F>>> {
+    let mut acc = Evaluations::from_vec_and_domain(vec![F::zero(); domain.size()], domain);
+    for (scalar, evals) in terms {
+        add_scaled_assign(&mut acc, *scalar, evals)?;
+    }
+    Ok(acc)
+}
+
+/// Moves evaluations to another domain.
+///
+/// When the target domain is larger, the evaluations are interpolated and
+/// evaluated over the target domain. When it is smaller, the target domain is
+/// a subgroup of the source domain (both have a power of two size) and the
+/// evaluations are simply subsampled, which avoids any FFT.
+pub fn resize<F: FftField>(evals: &Evaluations<F, D<F>>, target: D<F>) -> Evaluations<F, D<F>> {
+    let source = evals.domain();
+    match target.size.cmp(&source.size) {
+        Ordering::Equal => evals.clone(),
+        Ordering::Greater => evals.interpolate_by_ref().evaluate_over_domain(target),
+        Ordering::Less => {
+            let step = (source.size / target.size) as usize;
+            let res = evals.evals.par_iter().step_by(step).copied().collect();
+            Evaluations::from_vec_and_domain(res, target)
+        }
+    }
+}
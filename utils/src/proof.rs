@@ -0,0 +1,201 @@
+//! This module defines a common interface to the proofs of the different
+//! proof systems of the workspace, and a registry of their verifiers.
+//!
+//! Each proof type implements [Proof], which fixes its [ProofFormat], the
+//! statement it attests, and the data needed to verify it. A proof and its
+//! statement can then be encoded into an [EncodedProof] tagged with its
+//! format, and a [ProofRegistry] verifies encoded proofs of any registered
+//! format, so that a service can handle heterogeneous proofs uniformly.
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_with::{serde_as, Bytes};
+use std::{borrow::Cow, collections::HashMap, fmt};
+use thiserror::Error;
+
+/// Identifier of the encoding of the proofs of a proof system
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ProofFormat {
+    /// Name of the proof system and of its instantiation
+    pub name: Cow<'static, str>,
+    /// Version of the encoding, bumped on every incompatible change
+    pub version: u32,
+}
+
+impl ProofFormat {
+    /// Creates a format identifier
+    pub const fn new(name: &'static str, version: u32) -> Self {
+        ProofFormat {
+            name: Cow::Borrowed(name),
+            version,
+        }
+    }
+}
+
+impl fmt::Display for ProofFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/v{}", self.name, self.version)
+    }
+}
+
+/// Errors of the encoding, decoding and verification of proofs
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ProofError {
+    #[error("the proof could not be serialized: {0}")]
+    Serialization(String),
+
+    #[error("the proof could not be deserialized: {0}")]
+    Deserialization(String),
+
+    #[error("no verifier is registered for the proof format {0}")]
+    UnknownFormat(ProofFormat),
+
+    #[error("expected a proof of format {expected}, got {found}")]
+    FormatMismatch {
+        expected: ProofFormat,
+        found: ProofFormat,
+    },
+
+    #[error("the proof is invalid: {0}")]
+    Invalid(String),
+}
+
+/// A proof of a proof system of the workspace
+pub trait Proof: Serialize + DeserializeOwned {
+    /// The format of the encoded proofs
+    const FORMAT: ProofFormat;
+
+    /// The public statement attested by a proof
+    type Statement: Serialize + DeserializeOwned;
+
+    /// The data needed to verify a proof, like a verifier index or an SRS
+    type Verifier;
+
+    /// Verifies the proof against a statement
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the proof is not valid for the statement.
+    fn verify(
+        &self,
+        verifier: &Self::Verifier,
+        statement: &Self::Statement,
+    ) -> Result<(), ProofError>;
+
+    /// Serializes the proof
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the proof cannot be serialized.
+    fn to_bytes(&self) -> Result<Vec<u8>, ProofError> {
+        rmp_serde::to_vec(self).map_err(|e| ProofError::Serialization(e.to_string()))
+    }
+
+    /// Deserializes a proof
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the bytes are not a proof of this format.
+    fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        rmp_serde::from_slice(bytes).map_err(|e| ProofError::Deserialization(e.to_string()))
+    }
+}
+
+/// A serialized proof and statement, tagged with the format of the proof
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncodedProof {
+    pub format: ProofFormat,
+    #[serde_as(as = "Bytes")]
+    pub proof: Vec<u8>,
+    #[serde_as(as = "Bytes")]
+    pub statement: Vec<u8>,
+}
+
+impl EncodedProof {
+    /// Encodes a proof along with the statement it attests
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the proof or the statement cannot be serialized.
+    pub fn new<P: Proof>(proof: &P, statement: &P::Statement) -> Result<Self, ProofError> {
+        Ok(EncodedProof {
+            format: P::FORMAT,
+            proof: proof.to_bytes()?,
+            statement: rmp_serde::to_vec(statement)
+                .map_err(|e| ProofError::Serialization(e.to_string()))?,
+        })
+    }
+
+    /// Decodes the proof and the statement, checking that they are of format
+    /// `P::FORMAT`
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the format does not match, or if the bytes cannot be
+    /// deserialized.
+    pub fn decode<P: Proof>(&self) -> Result<(P, P::Statement), ProofError> {
+        if self.format != P::FORMAT {
+            return Err(ProofError::FormatMismatch {
+                expected: P::FORMAT,
+                found: self.format.clone(),
+            });
+        }
+        let proof = P::from_bytes(&self.proof)?;
+        let statement = rmp_serde::from_slice(&self.statement)
+            .map_err(|e| ProofError::Deserialization(e.to_string()))?;
+        Ok((proof, statement))
+    }
+}
+
+type VerifyFn = Box<dyn Fn(&EncodedProof) -> Result<(), ProofError> + Send + Sync>;
+
+/// A registry of the verifiers of proof formats
+#[derive(Default)]
+pub struct ProofRegistry {
+    verifiers: HashMap<ProofFormat, VerifyFn>,
+}
+
+impl ProofRegistry {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the verifier of the proofs of type `P`, replacing the
+    /// previous verifier of its format if any
+    pub fn register<P>(&mut self, verifier: P::Verifier)
+    where
+        P: Proof + 'static,
+        P::Verifier: Send + Sync + 'static,
+    {
+        let verify = move |encoded: &EncodedProof| {
+            let (proof, statement) = encoded.decode::<P>()?;
+            proof.verify(&verifier, &statement)
+        };
+        self.verifiers.insert(P::FORMAT, Box::new(verify));
+    }
+
+    /// Returns whether a verifier is registered for the format
+    pub fn contains(&self, format: &ProofFormat) -> bool {
+        self.verifiers.contains_key(format)
+    }
+
+    /// Returns the registered formats, in no particular order
+    pub fn formats(&self) -> impl Iterator<Item = &ProofFormat> {
+        self.verifiers.keys()
+    }
+
+    /// Verifies an encoded proof with the verifier of its format
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the format is not registered, or if the proof is not
+    /// valid for its statement.
+    pub fn verify(&self, encoded: &EncodedProof) -> Result<(), ProofError> {
+        let verify = self
+            .verifiers
+            .get(&encoded.format)
+            .ok_or_else(|| ProofError::UnknownFormat(encoded.format.clone()))?;
+        verify(encoded)
+    }
+}
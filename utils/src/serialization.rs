@@ -1,9 +1,10 @@
 //! This adds a few utility functions for serializing and deserializing
 //! [arkworks](http://arkworks.rs/) types that implement [CanonicalSerialize] and [CanonicalDeserialize].
 
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Write};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError, Write};
 use serde_with::Bytes;
 use std::io::BufReader;
+use thiserror::Error;
 
 //
 // Serialization with serde
@@ -129,6 +130,74 @@ where
     }
 }
 
+/// Errors returned when deserializing with [from_bytes_canonical]
+#[derive(Error, Debug)]
+pub enum CanonicalDeserializationError {
+    /// The bytes could not be deserialized, or failed validation (e.g. a
+    /// field element not smaller than the modulus)
+    #[error(transparent)]
+    Deserialization(#[from] SerializationError),
+    /// Bytes were left after deserializing the value
+    #[error("{0} trailing bytes after the encoded value")]
+    TrailingBytes(usize),
+    /// The value does not re-serialize to the same bytes
+    #[error("encoding is not canonical")]
+    NotCanonical,
+}
+
+/// Deserializes a compressed and validated value from `bytes`, rejecting
+/// inputs that have trailing bytes or that are not the canonical encoding of
+/// the value they decode to. Use this for inputs coming from untrusted
+/// sources.
+pub fn from_bytes_canonical<T>(bytes: &[u8]) -> Result<T, CanonicalDeserializationError>
+where
+    T: CanonicalSerialize + CanonicalDeserialize,
+{
+    let mut reader = bytes;
+    let value = T::deserialize_compressed(&mut reader)?;
+    if !reader.is_empty() {
+        return Err(CanonicalDeserializationError::TrailingBytes(reader.len()));
+    }
+    let mut reserialized = Vec::with_capacity(bytes.len());
+    value.serialize_compressed(&mut reserialized)?;
+    if reserialized != bytes {
+        return Err(CanonicalDeserializationError::NotCanonical);
+    }
+    Ok(value)
+}
+
+/// Same as `SerdeAs` but deserializing with [from_bytes_canonical].
+pub struct SerdeAsCanonical;
+
+impl<T> serde_with::SerializeAs<T> for SerdeAsCanonical
+where
+    T: CanonicalSerialize,
+{
+    fn serialize_as<S>(val: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        SerdeAs::serialize_as(val, serializer)
+    }
+}
+
+impl<'de, T> serde_with::DeserializeAs<'de, T> for SerdeAsCanonical
+where
+    T: CanonicalSerialize + CanonicalDeserialize,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let bytes: Vec<u8> = if deserializer.is_human_readable() {
+            hex::serde::deserialize(deserializer)?
+        } else {
+            Bytes::deserialize_as(deserializer)?
+        };
+        from_bytes_canonical(&bytes).map_err(serde::de::Error::custom)
+    }
+}
+
 /// A generic regression serialization test for serialization via
 /// `CanonicalSerialize` and `CanonicalDeserialize`.
 pub fn test_generic_serialization_regression_canonical<
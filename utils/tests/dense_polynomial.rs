@@ -1,4 +1,4 @@
-use ark_ff::One;
+use ark_ff::{One, UniformRand};
 use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
 use mina_curves::pasta::Fp;
 use o1_utils::ExtendedDensePolynomial;
@@ -19,3 +19,18 @@ fn test_chunk() {
         assert!(eval == three);
     }
 }
+
+#[test]
+fn test_eval_chunks() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let x = Fp::rand(&mut rng);
+    for (len, chunk_size, num_chunks) in [(0, 4, 1), (7, 4, 2), (8, 4, 2), (9, 4, 4), (16, 16, 1)] {
+        let coeffs: Vec<Fp> = (0..len).map(|_| Fp::rand(&mut rng)).collect();
+        let f = DensePolynomial::from_coefficients_vec(coeffs);
+        assert_eq!(
+            f.eval_chunks(num_chunks, chunk_size, x),
+            f.to_chunked_polynomial(num_chunks, chunk_size)
+                .evaluate_chunks(x)
+        );
+    }
+}
@@ -4,7 +4,7 @@ use mina_curves::pasta::Pallas as CurvePoint;
 use num_bigint::BigUint;
 use o1_utils::{
     field_helpers::{FieldHelpersError, Result},
-    BigUintFieldHelpers, FieldHelpers,
+    BigUintFieldHelpers, ConstantTimeFieldHelpers, FieldHelpers,
 };
 
 /// Base field element type
@@ -154,3 +154,85 @@ fn field_big() {
     assert_eq!(fe.to_biguint(), bi);
     assert_eq!(bi.to_field::<BaseField>().unwrap(), fe);
 }
+
+#[test]
+fn field_constant_time() {
+    let bytes = [
+        46, 174, 218, 228, 42, 116, 97, 213, 149, 45, 39, 185, 126, 202, 208, 104, 182, 152, 235,
+        185, 78, 138, 14, 76, 69, 56, 139, 182, 19, 222, 126, 8,
+    ];
+    let fe = BaseField::ct_from_bytes(&bytes).expect("failed to create field element from bytes");
+    assert_eq!(fe, BaseField::from_bytes(&bytes).unwrap());
+    assert_eq!(fe.ct_to_bytes(), fe.to_bytes());
+
+    // Wrong length
+    assert_eq!(
+        BaseField::ct_from_bytes(&bytes[..8]),
+        Err(FieldHelpersError::DeserializeBytes)
+    );
+
+    // The modulus itself and anything above it are not canonical
+    let modulus = BaseField::MODULUS.to_bytes_le();
+    assert_eq!(
+        BaseField::ct_from_bytes(&modulus),
+        Err(FieldHelpersError::DeserializeBytes)
+    );
+    assert_eq!(
+        BaseField::ct_from_bytes(&[0xff; 32]),
+        Err(FieldHelpersError::DeserializeBytes)
+    );
+    let mut below_modulus = modulus.clone();
+    below_modulus[0] -= 1;
+    assert_eq!(
+        BaseField::ct_from_bytes(&below_modulus),
+        Ok(-BaseField::one())
+    );
+
+    assert!(BaseField::from(0u32).ct_is_zero());
+    assert!(!fe.ct_is_zero());
+    assert!(fe.ct_eq(&fe));
+    assert!(!fe.ct_eq(&-fe));
+
+    let one = BaseField::one();
+    assert_eq!(BaseField::ct_select(&fe, &one, true), fe);
+    assert_eq!(BaseField::ct_select(&fe, &one, false), one);
+}
+
+#[test]
+fn field_checked_conversions() {
+    let modulus = BaseField::modulus_biguint();
+    assert_eq!(
+        BaseField::from_biguint_checked(&modulus),
+        Err(FieldHelpersError::NotCanonical)
+    );
+    assert_eq!(
+        (modulus.clone() + 1u32).to_field_checked::<BaseField>(),
+        Err(FieldHelpersError::NotCanonical)
+    );
+    assert_eq!(
+        BaseField::from_biguint_checked(&(modulus.clone() - 1u32)),
+        Ok(-BaseField::one())
+    );
+
+    assert_eq!(
+        BaseField::from_bytes_checked(&[0u8; 31]),
+        Err(FieldHelpersError::InvalidLength {
+            expected: 32,
+            actual: 31
+        })
+    );
+    assert_eq!(
+        BaseField::from_bytes_checked(&modulus.to_bytes_le()),
+        Err(FieldHelpersError::NotCanonical)
+    );
+    assert_eq!(
+        BaseField::from_hex_checked("zz"),
+        Err(FieldHelpersError::DecodeHex)
+    );
+
+    let field_hex = "f2eee8d8f6e5fb182c610cae6c5393fce69dc4d900e7b4923b074e54ad00fb36";
+    assert_eq!(
+        BaseField::from_hex_checked(field_hex),
+        BaseField::from_hex(field_hex)
+    );
+}
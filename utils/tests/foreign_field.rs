@@ -2,7 +2,7 @@ use ark_ec::AffineRepr;
 use ark_ff::One;
 use mina_curves::pasta::Pallas as CurvePoint;
 use num_bigint::BigUint;
-use o1_utils::{field_helpers::FieldHelpers, ForeignElement};
+use o1_utils::{field_helpers::FieldHelpers, foreign_field::ForeignElementError, ForeignElement};
 
 /// Base field element type
 pub type BaseField = <CurvePoint as AffineRepr>::BaseField;
@@ -71,3 +71,27 @@ fn test_from_biguint() {
         );
     }
 }
+
+#[test]
+fn test_try_from_biguint() {
+    let big = secp256k1_modulus();
+    assert_eq!(
+        ForeignElement::<BaseField, TEST_B_1, TEST_N_1>::try_from_biguint(big.clone()),
+        Ok(ForeignElement::<BaseField, TEST_B_1, TEST_N_1>::from_biguint(big.clone()))
+    );
+
+    let too_large = BigUint::from(1u32) << (TEST_B_1 * TEST_N_1);
+    assert_eq!(
+        ForeignElement::<BaseField, TEST_B_1, TEST_N_1>::try_from_biguint(too_large.clone()),
+        Err(ForeignElementError::TooLarge {
+            bits: (TEST_B_1 * TEST_N_1 + 1) as u64,
+            limb_bits: TEST_B_1,
+            limbs: TEST_N_1,
+        })
+    );
+    let too_large = BigUint::from(1u32) << (TEST_B_2 * TEST_N_2);
+    assert!(
+        ForeignElement::<BaseField, TEST_B_2, TEST_N_2>::try_from_be(&too_large.to_bytes_be())
+            .is_err()
+    );
+}
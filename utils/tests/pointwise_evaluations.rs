@@ -0,0 +1,57 @@
+use ark_ff::UniformRand;
+use ark_poly::{
+    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Evaluations,
+    Radix2EvaluationDomain as D,
+};
+use mina_curves::pasta::Fp;
+use o1_utils::pointwise_evaluations::{self, PointwiseError};
+
+fn random_evals(domain: D<Fp>) -> Evaluations<Fp, D<Fp>> {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let evals = (0..domain.size()).map(|_| Fp::rand(&mut rng)).collect();
+    Evaluations::from_vec_and_domain(evals, domain)
+}
+
+#[test]
+fn test_pointwise_operations() {
+    let d1 = D::<Fp>::new(16).unwrap();
+    let a = random_evals(d1);
+    let b = random_evals(d1);
+    let two = Fp::from(2u64);
+
+    assert_eq!(pointwise_evaluations::add(&a, &b).unwrap(), &a + &b);
+    assert_eq!(pointwise_evaluations::sub(&a, &b).unwrap(), &a - &b);
+    assert_eq!(pointwise_evaluations::mul(&a, &b).unwrap(), &a * &b);
+    assert_eq!(pointwise_evaluations::scale(&a, two), &a + &a);
+    assert_eq!(
+        pointwise_evaluations::linear_combination(d1, &[(two, &a), (Fp::from(1u64), &b)]).unwrap(),
+        &(&a + &a) + &b
+    );
+
+    let d4 = D::<Fp>::new(64).unwrap();
+    let c = random_evals(d4);
+    assert_eq!(
+        pointwise_evaluations::add(&a, &c),
+        Err(PointwiseError::DomainMismatch(16, 64))
+    );
+}
+
+#[test]
+fn test_resize() {
+    let mut rng = o1_utils::tests::make_test_rng(None);
+    let d1 = D::<Fp>::new(16).unwrap();
+    let d4 = D::<Fp>::new(64).unwrap();
+    let d8 = D::<Fp>::new(128).unwrap();
+
+    let poly = DensePolynomial::<Fp>::rand(15, &mut rng);
+    let evals_d1 = poly.evaluate_over_domain_by_ref(d1);
+    let evals_d4 = poly.evaluate_over_domain_by_ref(d4);
+    let evals_d8 = poly.evaluate_over_domain_by_ref(d8);
+
+    // Enlarging interpolates
+    assert_eq!(pointwise_evaluations::resize(&evals_d1, d8), evals_d8);
+    // Shrinking subsamples
+    assert_eq!(pointwise_evaluations::resize(&evals_d8, d4), evals_d4);
+    assert_eq!(pointwise_evaluations::resize(&evals_d8, d1), evals_d1);
+    assert_eq!(pointwise_evaluations::resize(&evals_d4, d4), evals_d4);
+}
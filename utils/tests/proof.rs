@@ -0,0 +1,113 @@
+use o1_utils::proof::{EncodedProof, Proof, ProofError, ProofFormat, ProofRegistry};
+use serde::{Deserialize, Serialize};
+
+/// A toy proof of knowledge of a square root modulo a public modulus
+#[derive(Serialize, Deserialize)]
+struct SquareRoot(u64);
+
+impl Proof for SquareRoot {
+    const FORMAT: ProofFormat = ProofFormat::new("square-root", 1);
+
+    type Statement = u64;
+
+    type Verifier = u64;
+
+    fn verify(&self, modulus: &u64, square: &u64) -> Result<(), ProofError> {
+        if (self.0 * self.0) % modulus == *square {
+            Ok(())
+        } else {
+            Err(ProofError::Invalid(format!(
+                "{} is not a square root",
+                self.0
+            )))
+        }
+    }
+}
+
+/// Another toy proof, of knowledge of the factors of a public number
+#[derive(Serialize, Deserialize)]
+struct Factors(u64, u64);
+
+impl Proof for Factors {
+    const FORMAT: ProofFormat = ProofFormat::new("factors", 1);
+
+    type Statement = u64;
+
+    type Verifier = ();
+
+    fn verify(&self, _: &(), product: &u64) -> Result<(), ProofError> {
+        if self.0 > 1 && self.1 > 1 && self.0 * self.1 == *product {
+            Ok(())
+        } else {
+            Err(ProofError::Invalid("wrong factors".into()))
+        }
+    }
+}
+
+#[test]
+fn test_proof_registry() {
+    let mut registry = ProofRegistry::new();
+    registry.register::<SquareRoot>(11);
+    registry.register::<Factors>(());
+    assert!(registry.contains(&SquareRoot::FORMAT));
+    assert_eq!(registry.formats().count(), 2);
+
+    let proofs = [
+        EncodedProof::new(&SquareRoot(4), &5).unwrap(),
+        EncodedProof::new(&Factors(3, 7), &21).unwrap(),
+    ];
+    for proof in &proofs {
+        assert_eq!(registry.verify(proof), Ok(()));
+    }
+
+    let wrong = EncodedProof::new(&Factors(1, 21), &21).unwrap();
+    assert_eq!(
+        registry.verify(&wrong),
+        Err(ProofError::Invalid("wrong factors".into()))
+    );
+}
+
+#[test]
+fn test_encoded_proof_formats() {
+    let encoded = EncodedProof::new(&SquareRoot(4), &5).unwrap();
+    let bytes = rmp_serde::to_vec(&encoded).unwrap();
+    let decoded: EncodedProof = rmp_serde::from_slice(&bytes).unwrap();
+    assert_eq!(decoded, encoded);
+    assert_eq!(decoded.format.to_string(), "square-root/v1");
+
+    assert_eq!(
+        decoded.decode::<Factors>().err(),
+        Some(ProofError::FormatMismatch {
+            expected: Factors::FORMAT,
+            found: SquareRoot::FORMAT,
+        })
+    );
+
+    let registry = ProofRegistry::new();
+    assert_eq!(
+        registry.verify(&decoded),
+        Err(ProofError::UnknownFormat(SquareRoot::FORMAT))
+    );
+}
+
+#[test]
+fn test_older_versions_are_rejected() {
+    let mut encoded = EncodedProof::new(&SquareRoot(4), &5).unwrap();
+    encoded.format.version -= 1;
+    assert_eq!(encoded.format.to_string(), "square-root/v0");
+
+    assert_eq!(
+        encoded.decode::<SquareRoot>().err(),
+        Some(ProofError::FormatMismatch {
+            expected: SquareRoot::FORMAT,
+            found: ProofFormat::new("square-root", 0),
+        })
+    );
+
+    let mut registry = ProofRegistry::new();
+    registry.register::<SquareRoot>(11);
+    assert_eq!(
+        registry.verify(&encoded),
+        Err(ProofError::UnknownFormat(ProofFormat::new("square-root", 0)))
+    );
+}
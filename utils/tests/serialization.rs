@@ -1,7 +1,8 @@
 use ark_ec::short_weierstrass::SWCurveConfig;
 use mina_curves::pasta::{Pallas, PallasParameters, Vesta, VestaParameters};
 use o1_utils::serialization::{
-    test_generic_serialization_regression_canonical, test_generic_serialization_regression_serde,
+    from_bytes_canonical, test_generic_serialization_regression_canonical,
+    test_generic_serialization_regression_serde, CanonicalDeserializationError,
 };
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
@@ -74,3 +75,27 @@ pub fn ser_regression_canonical_pasta() {
 
     test_generic_serialization_regression_serde(data_expected, buf_expected);
 }
+
+#[test]
+pub fn deserialize_canonical() {
+    use ark_serialize::CanonicalSerialize;
+    use mina_curves::pasta::Fp;
+
+    let fp = Fp::from(42u64);
+    let mut bytes = vec![];
+    fp.serialize_compressed(&mut bytes).unwrap();
+    assert_eq!(from_bytes_canonical::<Fp>(&bytes).unwrap(), fp);
+
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(matches!(
+        from_bytes_canonical::<Fp>(&trailing),
+        Err(CanonicalDeserializationError::TrailingBytes(1))
+    ));
+
+    // Above the modulus
+    assert!(matches!(
+        from_bytes_canonical::<Fp>(&[0xff; 32]),
+        Err(CanonicalDeserializationError::Deserialization(_))
+    ));
+}